@@ -0,0 +1,414 @@
+//! Group-by aggregation into a new sheet.
+//!
+//! `xlex aggregate` streams a sheet's rows into one accumulator per group,
+//! then writes a row per group to a new sheet of the same workbook: the
+//! group columns followed by each aggregate, under a bold header row, with
+//! counts as whole numbers, other results with thousands separators and
+//! dates kept as dates. The sheet's first row is its header.
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use xlex_core::aggregate::{AggFunc, Aggregation, GroupBy};
+use xlex_core::style::{Font, NumberFormat, Style};
+use xlex_core::{CellRef, CellValue, LazyWorkbook, Workbook, XlexError};
+
+use super::join::{header_column, header_row};
+use super::output::CommandOutput;
+use super::GlobalOptions;
+
+/// Arguments for the aggregate command.
+#[derive(Parser)]
+pub struct AggregateArgs {
+    /// Path to the xlsx file
+    pub file: std::path::PathBuf,
+    /// Sheet to aggregate
+    pub sheet: String,
+    /// Columns to group by, by header text or letter (comma-separated)
+    #[arg(long, value_delimiter = ',')]
+    pub group_by: Vec<String>,
+    /// Aggregates such as 'sum(D) as total, count() as n' (sum, count, avg, min, max)
+    #[arg(long)]
+    pub agg: String,
+    /// Sheet to write the results to
+    #[arg(long, default_value = "Summary")]
+    pub out_sheet: String,
+    /// Replace the output sheet if it exists
+    #[arg(long, short = 'F')]
+    pub force: bool,
+    /// Sum and average as exact decimals, so cents add up without drift
+    #[arg(long)]
+    pub decimal: bool,
+}
+
+/// An aggregate as written: function, column spec and output name.
+#[derive(Debug, PartialEq)]
+struct AggSpec {
+    func: AggFunc,
+    column: Option<String>,
+    name: String,
+}
+
+/// Parses a comma-separated list of `func(column) [as name]`.
+fn parse_aggs(text: &str) -> Result<Vec<AggSpec>> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+
+    parts
+        .into_iter()
+        .map(|part| {
+            let part = part.trim();
+            let invalid = || {
+                anyhow::anyhow!(
+                    "Invalid aggregate '{}': expected func(column) [as name]",
+                    part
+                )
+            };
+            let (func_name, rest) = part.split_once('(').ok_or_else(invalid)?;
+            let (column, rest) = rest.split_once(')').ok_or_else(invalid)?;
+            let func = AggFunc::from_name(func_name.trim()).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown aggregate '{}' (expected sum, count, avg, min or max)",
+                    func_name.trim()
+                )
+            })?;
+            let column = Some(column.trim().to_string()).filter(|c| !c.is_empty());
+            if column.is_none() && func != AggFunc::Count {
+                anyhow::bail!("{}() needs a column", func_name.trim());
+            }
+            let rest = rest.trim();
+            let name = if rest.is_empty() {
+                part.to_string()
+            } else {
+                match rest.split_once(char::is_whitespace) {
+                    Some((keyword, name)) if keyword.eq_ignore_ascii_case("as") => {
+                        name.trim().to_string()
+                    }
+                    _ => return Err(invalid()),
+                }
+            };
+            Ok(AggSpec { func, column, name })
+        })
+        .collect()
+}
+
+/// Run the aggregate command.
+pub fn run(args: &AggregateArgs, global: &GlobalOptions) -> Result<()> {
+    if args.out_sheet == args.sheet {
+        anyhow::bail!("The output sheet must differ from the sheet being aggregated");
+    }
+    let specs = parse_aggs(&args.agg)?;
+
+    let lazy = LazyWorkbook::open(&args.file)?;
+    if !lazy.has_sheet(&args.sheet) {
+        return Err(XlexError::SheetNotFound {
+            name: args.sheet.clone(),
+        }
+        .into());
+    }
+    if lazy.has_sheet(&args.out_sheet) && !args.force {
+        return Err(XlexError::SheetAlreadyExists {
+            name: args.out_sheet.clone(),
+        }
+        .into());
+    }
+    let header = header_row(&lazy, &args.sheet)?;
+    let column = |spec: &str| {
+        header_column(&header, spec.trim()).ok_or_else(|| {
+            anyhow::anyhow!("No column '{}' in the header row of {}", spec, args.sheet)
+        })
+    };
+    let group_cols = args
+        .group_by
+        .iter()
+        .map(|spec| column(spec))
+        .collect::<Result<Vec<_>>>()?;
+    let aggregations = specs
+        .iter()
+        .map(|spec| {
+            let col = spec.column.as_deref().map(column).transpose()?;
+            Ok(Aggregation::new(spec.func, col, spec.name.as_str()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut out_header: Vec<CellValue> = group_cols
+        .iter()
+        .zip(&args.group_by)
+        .map(|(&col, spec)| match header.get(col as usize - 1) {
+            Some(value) if !value.is_empty() => value.clone(),
+            _ => CellValue::string(spec.trim()),
+        })
+        .collect();
+    out_header.extend(aggregations.iter().map(|a| CellValue::string(&a.name)));
+
+    if global.dry_run {
+        outln!(
+            "Would aggregate {} by {} into {} ({} aggregates)",
+            args.sheet,
+            if args.group_by.is_empty() {
+                "nothing".to_string()
+            } else {
+                args.group_by.join(", ")
+            },
+            args.out_sheet,
+            aggregations.len()
+        );
+        return Ok(());
+    }
+
+    let funcs: Vec<AggFunc> = aggregations.iter().map(|a| a.func).collect();
+    let mut group_by = GroupBy::new(group_cols.clone(), aggregations).decimal(args.decimal);
+    let mut first = true;
+    lazy.pipeline(&args.sheet)
+        .filter(move |_| !std::mem::replace(&mut first, false))
+        .write_to(&mut group_by)?;
+    let rows_read = group_by.rows();
+    let rows = group_by.finish();
+    drop(lazy);
+
+    let mut workbook = Workbook::open(&args.file)?;
+    if workbook.get_sheet(&args.out_sheet).is_some() {
+        workbook.remove_sheet(&args.out_sheet)?;
+    }
+    workbook.add_sheet(&args.out_sheet)?;
+    let mut style = |number_format: Option<NumberFormat>, bold: bool| {
+        workbook.style_registry_mut().add(Style {
+            font: Font {
+                bold,
+                ..Font::default()
+            },
+            number_format: number_format.unwrap_or_else(NumberFormat::general),
+            ..Style::default()
+        })
+    };
+    let header_style = style(None, true);
+    let count_style = style(Some(NumberFormat::number(0)), false);
+    let amount_style = style(Some(NumberFormat::custom("#,##0.00")), false);
+    let date_style = style(Some(NumberFormat::date()), false);
+    let key_count = group_cols.len();
+    let groups = rows.len();
+    workbook.with_sheet_mut(&args.out_sheet, |target| {
+        for (c, value) in out_header.into_iter().enumerate() {
+            let cell_ref = CellRef::new(c as u32 + 1, 1);
+            target.set_cell(cell_ref.clone(), value);
+            target.set_cell_style(&cell_ref, Some(header_style));
+        }
+        for (r, row) in rows.into_iter().enumerate() {
+            for (c, value) in row.into_iter().enumerate() {
+                let style = match (&value, c.checked_sub(key_count).map(|i| funcs[i])) {
+                    (CellValue::DateTime(_), _) => Some(date_style),
+                    (CellValue::Number(_), Some(AggFunc::Count)) => Some(count_style),
+                    (CellValue::Number(_) | CellValue::Decimal(_), Some(_)) => Some(amount_style),
+                    _ => None,
+                };
+                if value.is_empty() {
+                    continue;
+                }
+                let cell_ref = CellRef::new(c as u32 + 1, r as u32 + 2);
+                target.set_cell(cell_ref.clone(), value);
+                if style.is_some() {
+                    target.set_cell_style(&cell_ref, style);
+                }
+            }
+        }
+    })?;
+    workbook.save()?;
+
+    CommandOutput::new("aggregate")
+        .file(&args.file)
+        .target("sheet", args.sheet.as_str())
+        .field("outSheet", args.out_sheet.as_str())
+        .field("rowsRead", rows_read)
+        .field("groups", groups)
+        .print(
+            global,
+            format!(
+                "Aggregated {} rows into {} groups in {}",
+                rows_read,
+                groups.to_string().green(),
+                args.out_sheet.cyan()
+            ),
+        )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::OutputFormat;
+    use tempfile::TempDir;
+
+    fn default_global() -> GlobalOptions {
+        GlobalOptions {
+            quiet: true,
+            verbose: false,
+            format: OutputFormat::Text,
+            no_color: true,
+            color: false,
+            json_errors: false,
+            dry_run: false,
+            output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
+        }
+    }
+
+    fn sales(dir: &TempDir) -> std::path::PathBuf {
+        let path = dir.path().join("sales.xlsx");
+        let mut wb = Workbook::with_sheets(&["Sheet1"]);
+        let rows: [[&str; 4]; 5] = [
+            ["region", "rep", "item", "amount"],
+            ["north", "ann", "x", "10"],
+            ["south", "bo", "y", "2.5"],
+            ["north", "ann", "z", "5"],
+            ["north", "cy", "x", "n/a"],
+        ];
+        for (r, row) in rows.iter().enumerate() {
+            for (c, value) in row.iter().enumerate() {
+                let value = value
+                    .parse()
+                    .map(CellValue::Number)
+                    .unwrap_or_else(|_| CellValue::string(*value));
+                let cell_ref = CellRef::new(c as u32 + 1, r as u32 + 1);
+                wb.set_cell("Sheet1", cell_ref, value).unwrap();
+            }
+        }
+        wb.save_as(&path).unwrap();
+        path
+    }
+
+    fn args(file: &std::path::Path, group_by: &[&str], agg: &str) -> AggregateArgs {
+        AggregateArgs {
+            file: file.to_path_buf(),
+            sheet: "Sheet1".to_string(),
+            group_by: group_by.iter().map(|c| c.to_string()).collect(),
+            agg: agg.to_string(),
+            out_sheet: "Summary".to_string(),
+            force: true,
+            decimal: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_aggs() {
+        let specs = parse_aggs("sum(D) as total, count() as n,avg( Unit Price )").unwrap();
+        assert_eq!(
+            specs,
+            vec![
+                AggSpec {
+                    func: AggFunc::Sum,
+                    column: Some("D".to_string()),
+                    name: "total".to_string()
+                },
+                AggSpec {
+                    func: AggFunc::Count,
+                    column: None,
+                    name: "n".to_string()
+                },
+                AggSpec {
+                    func: AggFunc::Avg,
+                    column: Some("Unit Price".to_string()),
+                    name: "avg( Unit Price )".to_string()
+                },
+            ]
+        );
+        assert!(parse_aggs("median(D)").is_err());
+        assert!(parse_aggs("sum()").is_err());
+        assert!(parse_aggs("sum(D) total").is_err());
+        assert!(parse_aggs("sum D").is_err());
+    }
+
+    #[test]
+    fn test_run_aggregate() {
+        let dir = TempDir::new().unwrap();
+        let file = sales(&dir);
+        let args = args(&file, &["A", "rep"], "sum(D) as total, count() as n");
+        run(&args, &default_global()).unwrap();
+
+        let wb = Workbook::open(&file).unwrap();
+        let sheet = wb.get_sheet("Summary").unwrap();
+        let text = |c, r| sheet.get_value(&CellRef::new(c, r)).to_display_string();
+        let header: Vec<_> = (1..=4).map(|c| text(c, 1)).collect();
+        assert_eq!(header, vec!["region", "rep", "total", "n"]);
+        let rows: Vec<Vec<_>> = (2..=4)
+            .map(|r| (1..=4).map(|c| text(c, r)).collect())
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                vec!["north", "ann", "15", "2"],
+                vec!["south", "bo", "2.5", "1"],
+                vec!["north", "cy", "0", "1"],
+            ]
+        );
+        let style = |c, r| {
+            let id = sheet
+                .get_cell(&CellRef::new(c, r))
+                .unwrap()
+                .style_id
+                .unwrap();
+            wb.style_registry().get(id).unwrap().clone()
+        };
+        assert!(style(1, 1).font.bold);
+        assert_eq!(style(3, 2).number_format.code.as_deref(), Some("#,##0.00"));
+        assert_eq!(style(4, 2).number_format.code.as_deref(), Some("0"));
+
+        // Rerunning replaces the summary
+        run(&args, &default_global()).unwrap();
+        let wb = Workbook::open(&file).unwrap();
+        assert_eq!(wb.sheet_names(), vec!["Sheet1", "Summary"]);
+    }
+
+    #[test]
+    fn test_run_aggregate_decimal() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("cents.xlsx");
+        let mut wb = Workbook::with_sheets(&["Sheet1"]);
+        wb.set_cell("Sheet1", CellRef::new(1, 1), CellValue::string("amount"))
+            .unwrap();
+        for (row, amount) in [(2, 0.1), (3, 0.2)] {
+            wb.set_cell("Sheet1", CellRef::new(1, row), CellValue::Number(amount))
+                .unwrap();
+        }
+        wb.save_as(&path).unwrap();
+        let total = |decimal| {
+            let mut args = args(&path, &[], "sum(A) as total");
+            args.decimal = decimal;
+            run(&args, &default_global()).unwrap();
+            let wb = Workbook::open(&path).unwrap();
+            let sheet = wb.get_sheet("Summary").unwrap();
+            sheet.get_value(&CellRef::new(1, 2))
+        };
+        assert_eq!(total(false), CellValue::Number(0.1 + 0.2));
+        assert_eq!(total(true), CellValue::Number(0.3));
+    }
+
+    #[test]
+    fn test_run_aggregate_errors() {
+        let dir = TempDir::new().unwrap();
+        let file = sales(&dir);
+        let err = run(&args(&file, &["Zone"], "count()"), &default_global()).unwrap_err();
+        assert!(err.to_string().contains("No column 'Zone'"));
+
+        run(&args(&file, &[], "count()"), &default_global()).unwrap();
+        let mut exists = args(&file, &[], "count()");
+        exists.force = false;
+        assert!(run(&exists, &default_global()).is_err());
+    }
+}
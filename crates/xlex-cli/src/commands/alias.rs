@@ -0,0 +1,256 @@
+//! Alias expansion.
+//!
+//! Aliases map a name to a command line, e.g. `ls = "sheet list"`. Before
+//! the command line is parsed, an alias in command position is replaced
+//! by its expansion. `$1`, `$2`, ... in the expansion take the matching
+//! argument and `$@` takes all of them; arguments not taken by a
+//! placeholder are appended. Placeholders must be whole words, so cell
+//! references like `$A$1` are left alone.
+
+use std::collections::BTreeMap;
+use std::ffi::{OsStr, OsString};
+
+use anyhow::Result;
+use clap::CommandFactory;
+
+use super::{get_alias_path, shell_words, Cli};
+
+/// Aliases every installation has. User aliases of the same name win.
+pub(super) const BUILTIN_ALIASES: &[(&str, &str)] = &[("ls", "sheet list"), ("cat", "cell get")];
+
+/// Alias name to expansion.
+pub(super) type Aliases = BTreeMap<String, String>;
+
+/// Global options that take a value (`--format`, `-f`, ...), so the word
+/// after them is not the command.
+fn value_options() -> Vec<String> {
+    Cli::command()
+        .get_arguments()
+        .filter(|a| !a.is_positional() && a.get_action().takes_values())
+        .flat_map(|a| {
+            let long = a.get_long().map(|l| format!("--{}", l));
+            let short = a.get_short().map(|s| format!("-{}", s));
+            long.into_iter().chain(short)
+        })
+        .collect()
+}
+
+/// Returns true if `name` is a command or command alias known to clap.
+/// Aliases cannot shadow these.
+pub(super) fn is_command(name: &str) -> bool {
+    Cli::command()
+        .get_subcommands()
+        .any(|c| c.get_name() == name || c.get_all_aliases().any(|a| a == name))
+}
+
+/// Loads the built-in and user aliases. An unreadable alias file is
+/// treated as empty, like `xlex alias list` does.
+pub(super) fn load() -> Aliases {
+    let mut aliases: Aliases = BUILTIN_ALIASES
+        .iter()
+        .map(|(name, command)| (name.to_string(), command.to_string()))
+        .collect();
+    let user: serde_yaml::Mapping = get_alias_path()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_yaml::from_str(&content).ok())
+        .unwrap_or_default();
+    for (name, command) in user {
+        if let (serde_yaml::Value::String(name), serde_yaml::Value::String(command)) =
+            (name, command)
+        {
+            aliases.insert(name, command);
+        }
+    }
+    aliases.retain(|name, _| !is_command(name));
+    aliases
+}
+
+/// Expands an alias in a full command line (`xlex <options> <command> ...`).
+/// The alias file is only read when the command is not a known one.
+pub(crate) fn expand_aliases(args: Vec<OsString>) -> Result<Vec<OsString>> {
+    let needs_expansion = command_index(&args, 1)
+        .and_then(|i| args[i].to_str())
+        .is_some_and(|name| !is_command(name));
+    if !needs_expansion {
+        return Ok(args);
+    }
+    expand(&args, 1, &load())
+}
+
+/// Position of the command word at or after `start`, skipping options.
+fn command_index<T: AsRef<OsStr>>(args: &[T], start: usize) -> Option<usize> {
+    let value_options = value_options();
+    let mut i = start;
+    while let Some(arg) = args.get(i) {
+        let arg = arg.as_ref().to_str()?;
+        if arg == "--" {
+            return (i + 1 < args.len()).then_some(i + 1);
+        }
+        if !arg.starts_with('-') || arg == "-" {
+            return Some(i);
+        }
+        // Short flags may be bundled (`-qf json`), the last one taking the value
+        let takes_value = value_options.iter().any(|o| o == arg)
+            || (!arg.starts_with("--")
+                && arg.chars().last().is_some_and(|last| {
+                    value_options
+                        .iter()
+                        .any(|o| o.len() == 2 && o.ends_with(last))
+                }));
+        i += if takes_value { 2 } else { 1 };
+    }
+    None
+}
+
+/// Repeatedly expands the alias in command position (found at or after
+/// `start`) until the command is not an alias. Fails if an alias refers
+/// back to itself or is given fewer arguments than its placeholders use.
+pub(super) fn expand<T>(args: &[T], start: usize, aliases: &Aliases) -> Result<Vec<T>>
+where
+    T: Clone + From<String> + AsRef<OsStr>,
+{
+    let mut args = args.to_vec();
+    let mut chain: Vec<String> = Vec::new();
+
+    while let Some(index) = command_index(&args, start) {
+        let Some(name) = args[index].as_ref().to_str().map(str::to_string) else {
+            break;
+        };
+        let Some(template) = aliases.get(&name) else {
+            break;
+        };
+        if chain.contains(&name) {
+            chain.push(name);
+            anyhow::bail!("Alias loop: {}", chain.join(" → "));
+        }
+
+        let rest = &args[index + 1..];
+        let mut used = vec![false; rest.len()];
+        let mut expanded: Vec<T> = Vec::new();
+        for word in shell_words(template) {
+            if word == "$@" {
+                expanded.extend(rest.iter().cloned());
+                used.iter_mut().for_each(|u| *u = true);
+                continue;
+            }
+            let position = word
+                .strip_prefix('$')
+                .and_then(|n| n.parse::<usize>().ok())
+                .filter(|n| *n > 0);
+            match position {
+                Some(n) => {
+                    let arg = rest.get(n - 1).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Alias '{}' needs at least {} argument(s): {}",
+                            name,
+                            n,
+                            template
+                        )
+                    })?;
+                    expanded.push(arg.clone());
+                    used[n - 1] = true;
+                }
+                None => expanded.push(T::from(word)),
+            }
+        }
+        expanded.extend(
+            rest.iter()
+                .zip(&used)
+                .filter(|(_, used)| !**used)
+                .map(|(arg, _)| arg.clone()),
+        );
+
+        args.truncate(index);
+        args.extend(expanded);
+        chain.push(name);
+    }
+
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aliases(entries: &[(&str, &str)]) -> Aliases {
+        entries
+            .iter()
+            .map(|(n, c)| (n.to_string(), c.to_string()))
+            .collect()
+    }
+
+    fn words(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_expand_placeholders() {
+        let aliases = aliases(&[
+            ("ls", "sheet list"),
+            ("hdr", "cell set $1 Sheet1 A1 $2"),
+            ("abs", "cell formula $1 Sheet1 B1 =$A$1*2"),
+            ("all", "range get $@"),
+        ]);
+        assert_eq!(
+            expand(&words("xlex -q ls a.xlsx"), 1, &aliases).unwrap(),
+            words("xlex -q sheet list a.xlsx")
+        );
+        assert_eq!(
+            expand(&words("xlex -f json hdr a.xlsx Title -v"), 1, &aliases).unwrap(),
+            words("xlex -f json cell set a.xlsx Sheet1 A1 Title -v")
+        );
+        assert_eq!(
+            expand(&words("xlex abs a.xlsx"), 1, &aliases).unwrap(),
+            words("xlex cell formula a.xlsx Sheet1 B1 =$A$1*2")
+        );
+        assert_eq!(
+            expand(&words("xlex all a.xlsx Sheet1 A1:B2"), 1, &aliases).unwrap(),
+            words("xlex range get a.xlsx Sheet1 A1:B2")
+        );
+        // Every global option taking a value is skipped with it
+        assert_eq!(
+            expand(&words("xlex --trace-output t.json ls a.xlsx"), 1, &aliases).unwrap(),
+            words("xlex --trace-output t.json sheet list a.xlsx")
+        );
+        assert_eq!(
+            expand(&words("xlex -qf json ls a.xlsx"), 1, &aliases).unwrap(),
+            words("xlex -qf json sheet list a.xlsx")
+        );
+        // Not an alias: left as is
+        assert_eq!(
+            expand(&words("xlex info a.xlsx"), 1, &aliases).unwrap(),
+            words("xlex info a.xlsx")
+        );
+        // Batch lines have no program name
+        assert_eq!(
+            expand(&words("hdr x.xlsx Total"), 0, &aliases).unwrap(),
+            words("cell set x.xlsx Sheet1 A1 Total")
+        );
+        assert!(expand(&words("xlex hdr a.xlsx"), 1, &aliases)
+            .unwrap_err()
+            .to_string()
+            .contains("needs at least 2"));
+    }
+
+    #[test]
+    fn test_expand_chained_and_loops() {
+        let aliases = aliases(&[("l", "ls"), ("ls", "sheet list"), ("a", "b $1"), ("b", "a")]);
+        assert_eq!(
+            expand(&words("xlex l a.xlsx"), 1, &aliases).unwrap(),
+            words("xlex sheet list a.xlsx")
+        );
+        let err = expand(&words("xlex a x"), 1, &aliases).unwrap_err();
+        assert_eq!(err.to_string(), "Alias loop: a → b → a");
+    }
+
+    #[test]
+    fn test_commands_are_not_aliases() {
+        assert!(is_command("info"));
+        assert!(is_command("sheet"));
+        assert!(!is_command("ls"));
+        let aliases = load();
+        assert_eq!(aliases.get("ls").map(String::as_str), Some("sheet list"));
+        assert!(!aliases.contains_key("info"));
+    }
+}
@@ -0,0 +1,386 @@
+//! Workbook audit operations.
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+
+use xlex_core::workbook::{HiddenContent, UnusedContent};
+use xlex_core::{CellRef, LazyWorkbook, PackageOrphan, Workbook};
+
+use super::{GlobalOptions, OutputFormat};
+
+/// Arguments for audit operations.
+#[derive(Parser)]
+pub struct AuditArgs {
+    #[command(subcommand)]
+    pub command: AuditCommand,
+}
+
+#[derive(Subcommand)]
+pub enum AuditCommand {
+    /// List unused names, styles, hidden sheets and orphaned parts
+    Unused {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Remove everything listed
+        #[arg(long)]
+        remove: bool,
+    },
+    /// List hidden sheets, rows and columns, invisible text and cells
+    /// covered by merges
+    Hidden {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+    },
+}
+
+/// Run audit operations.
+pub fn run(args: &AuditArgs, global: &GlobalOptions) -> Result<()> {
+    match &args.command {
+        AuditCommand::Unused { file, remove } => unused(file, *remove, global),
+        AuditCommand::Hidden { file } => hidden(file, global),
+    }
+}
+
+fn unused(file: &std::path::Path, remove: bool, global: &GlobalOptions) -> Result<()> {
+    let mut workbook = Workbook::open(file)?;
+    let unused = workbook.find_unused();
+    let orphans = LazyWorkbook::open(file)?.orphans()?;
+
+    if remove && global.dry_run {
+        outln!(
+            "Would remove {} defined name(s), {} style(s), {} hidden sheet(s) and {} orphaned part(s) or relationship(s) from {}",
+            unused.defined_names.len(),
+            unused.styles.len(),
+            unused.hidden_sheets.len(),
+            orphans.len(),
+            file.display()
+        );
+        return Ok(());
+    }
+
+    let sheet_names: Vec<String> = workbook
+        .sheet_names()
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let removed = remove && (!unused.is_empty() || !orphans.is_empty());
+    if removed {
+        // Saving rewrites the package from the model, dropping the orphans
+        workbook.remove_unused(&unused)?;
+        workbook.save()?;
+    }
+
+    if global.quiet {
+        return Ok(());
+    }
+    if global.format == OutputFormat::Json {
+        print_json(&unused, &orphans, &sheet_names, removed)?;
+    } else {
+        print_text(&unused, &orphans, &sheet_names, removed);
+    }
+
+    Ok(())
+}
+
+fn scope_name(local_sheet_id: Option<usize>, sheet_names: &[String]) -> Option<String> {
+    local_sheet_id.and_then(|i| sheet_names.get(i).cloned())
+}
+
+fn print_json(
+    unused: &UnusedContent,
+    orphans: &[PackageOrphan],
+    sheet_names: &[String],
+    removed: bool,
+) -> Result<()> {
+    let names: Vec<serde_json::Value> = unused
+        .defined_names
+        .iter()
+        .map(|d| {
+            serde_json::json!({
+                "name": d.name,
+                "reference": d.reference,
+                "scope": scope_name(d.local_sheet_id, sheet_names),
+            })
+        })
+        .collect();
+    let orphans: Vec<serde_json::Value> = orphans
+        .iter()
+        .map(|o| match o {
+            PackageOrphan::Relationship { source, id, target } => serde_json::json!({
+                "kind": "relationship",
+                "source": source,
+                "id": id,
+                "target": target,
+            }),
+            PackageOrphan::Part { name } => serde_json::json!({
+                "kind": "part",
+                "part": name,
+            }),
+        })
+        .collect();
+    let json = serde_json::json!({
+        "definedNames": names,
+        "styles": unused.styles,
+        "hiddenSheets": unused.hidden_sheets,
+        "orphans": orphans,
+        "removed": removed,
+    });
+    outln!("{}", serde_json::to_string_pretty(&json)?);
+    Ok(())
+}
+
+fn print_text(
+    unused: &UnusedContent,
+    orphans: &[PackageOrphan],
+    sheet_names: &[String],
+    removed: bool,
+) {
+    if unused.is_empty() && orphans.is_empty() {
+        outln!("{}", "No unused content found".green());
+        return;
+    }
+
+    if !unused.defined_names.is_empty() {
+        outln!("{}:", "Unused defined names".bold());
+        for d in &unused.defined_names {
+            let scope = scope_name(d.local_sheet_id, sheet_names)
+                .map(|s| format!(" (scope: {})", s))
+                .unwrap_or_default();
+            outln!("  {} = {}{}", d.name.cyan(), d.reference, scope);
+        }
+    }
+    if !unused.styles.is_empty() {
+        let ids: Vec<String> = unused.styles.iter().map(|id| id.to_string()).collect();
+        outln!(
+            "{}: {} ({})",
+            "Unused styles".bold(),
+            unused.styles.len(),
+            ids.join(", ")
+        );
+    }
+    if !unused.hidden_sheets.is_empty() {
+        outln!("{}:", "Unreferenced hidden sheets".bold());
+        for name in &unused.hidden_sheets {
+            outln!("  {}", name.cyan());
+        }
+    }
+    if !orphans.is_empty() {
+        outln!("{}:", "Orphaned package entries".bold());
+        for orphan in orphans {
+            match orphan {
+                PackageOrphan::Relationship { source, id, target } => {
+                    let source = if source.is_empty() { "package" } else { source };
+                    outln!("  {} {} -> {} (missing)", source, id, target.yellow());
+                }
+                PackageOrphan::Part { name } => {
+                    outln!("  {} (unreferenced part)", name.yellow());
+                }
+            }
+        }
+    }
+
+    if removed {
+        outln!("\n{}", "Removed all of the above".green());
+    } else {
+        outln!("\nRun with --remove to strip them");
+    }
+}
+
+fn hidden(file: &std::path::Path, global: &GlobalOptions) -> Result<()> {
+    let hidden = Workbook::open(file)?.find_hidden();
+
+    if global.quiet {
+        return Ok(());
+    }
+    if global.format == OutputFormat::Json {
+        let rows = |list: &[(String, u32)]| -> Vec<serde_json::Value> {
+            list.iter()
+                .map(|(sheet, row)| serde_json::json!({ "sheet": sheet, "row": row }))
+                .collect()
+        };
+        let columns = |list: &[(String, u32)]| -> Vec<serde_json::Value> {
+            list.iter()
+                .map(|(sheet, col)| {
+                    serde_json::json!({ "sheet": sheet, "column": CellRef::col_to_letters(*col) })
+                })
+                .collect()
+        };
+        let cells = |list: &[(String, CellRef)]| -> Vec<serde_json::Value> {
+            list.iter()
+                .map(|(sheet, cell)| serde_json::json!({ "sheet": sheet, "cell": cell.to_a1() }))
+                .collect()
+        };
+        let json = serde_json::json!({
+            "hiddenSheets": hidden.hidden_sheets,
+            "veryHiddenSheets": hidden.very_hidden_sheets,
+            "hiddenRows": rows(&hidden.hidden_rows),
+            "hiddenColumns": columns(&hidden.hidden_columns),
+            "zeroWidthColumns": columns(&hidden.zero_width_columns),
+            "invisibleText": cells(&hidden.invisible_text),
+            "coveredCells": cells(&hidden.covered_cells),
+        });
+        outln!("{}", serde_json::to_string_pretty(&json)?);
+    } else {
+        print_hidden_text(&hidden);
+    }
+
+    Ok(())
+}
+
+/// Groups `(sheet, n)` pairs by sheet, collapsing consecutive numbers into
+/// spans such as "4-9".
+fn spans_by_sheet(list: &[(String, u32)], label: impl Fn(u32) -> String) -> Vec<(String, String)> {
+    let mut grouped: Vec<(String, Vec<(u32, u32)>)> = Vec::new();
+    for (sheet, n) in list {
+        match grouped.last_mut() {
+            Some((last, spans)) if last == sheet => match spans.last_mut() {
+                Some((_, end)) if *end + 1 == *n => *end = *n,
+                _ => spans.push((*n, *n)),
+            },
+            _ => grouped.push((sheet.clone(), vec![(*n, *n)])),
+        }
+    }
+    grouped
+        .into_iter()
+        .map(|(sheet, spans)| {
+            let spans: Vec<String> = spans
+                .into_iter()
+                .map(|(start, end)| {
+                    if start == end {
+                        label(start)
+                    } else {
+                        format!("{}-{}", label(start), label(end))
+                    }
+                })
+                .collect();
+            (sheet, spans.join(", "))
+        })
+        .collect()
+}
+
+fn print_hidden_text(hidden: &HiddenContent) {
+    if hidden.is_empty() {
+        outln!("{}", "No hidden content found".green());
+        return;
+    }
+
+    for (title, sheets) in [
+        ("Hidden sheets", &hidden.hidden_sheets),
+        ("Very hidden sheets", &hidden.very_hidden_sheets),
+    ] {
+        if !sheets.is_empty() {
+            outln!("{}: {}", title.bold(), sheets.join(", ").cyan());
+        }
+    }
+    for (title, list, is_row) in [
+        ("Hidden rows", &hidden.hidden_rows, true),
+        ("Hidden columns", &hidden.hidden_columns, false),
+        ("Zero-width columns", &hidden.zero_width_columns, false),
+    ] {
+        if list.is_empty() {
+            continue;
+        }
+        outln!("{}:", title.bold());
+        let label = |n: u32| {
+            if is_row {
+                n.to_string()
+            } else {
+                CellRef::col_to_letters(n)
+            }
+        };
+        for (sheet, spans) in spans_by_sheet(list, label) {
+            outln!("  {}: {}", sheet.cyan(), spans);
+        }
+    }
+    for (title, list) in [
+        ("Text matching its background", &hidden.invisible_text),
+        ("Cells covered by merges", &hidden.covered_cells),
+    ] {
+        if list.is_empty() {
+            continue;
+        }
+        outln!("{}:", title.bold());
+        for (sheet, cell) in list {
+            outln!("  {}!{}", sheet, cell.to_a1().yellow());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use xlex_core::sheet::SheetVisibility;
+    use xlex_core::{CellValue, DefinedName};
+
+    fn default_global() -> GlobalOptions {
+        GlobalOptions {
+            quiet: true,
+            verbose: false,
+            format: OutputFormat::Text,
+            no_color: true,
+            color: false,
+            json_errors: false,
+            dry_run: false,
+            output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
+        }
+    }
+
+    #[test]
+    fn test_unused_list_and_remove() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("audit.xlsx");
+        let mut wb = Workbook::with_sheets(&["Report", "Old"]);
+        wb.set_cell("Report", CellRef::new(1, 1), CellValue::formula("Rate*2"))
+            .unwrap();
+        wb.set_defined_name(DefinedName::new("Rate", "Report!$B$1"));
+        wb.set_defined_name(DefinedName::new("Stale", "Report!$C$1"));
+        wb.set_sheet_visibility("Old", SheetVisibility::Hidden)
+            .unwrap();
+        wb.save_as(&file_path).unwrap();
+
+        let mut json = default_global();
+        json.format = OutputFormat::Json;
+        unused(&file_path, false, &json).unwrap();
+        assert_eq!(Workbook::open(&file_path).unwrap().sheet_count(), 2);
+
+        unused(&file_path, true, &default_global()).unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(wb.sheet_names(), vec!["Report"]);
+        assert!(wb.get_defined_name("Stale").is_none());
+        assert!(wb.get_defined_name("Rate").is_some());
+    }
+
+    #[test]
+    fn test_hidden() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("hidden.xlsx");
+        let mut wb = Workbook::with_sheets(&["Model", "Keys"]);
+        wb.set_sheet_visibility("Keys", SheetVisibility::VeryHidden)
+            .unwrap();
+        let sheet = wb.get_sheet_mut("Model").unwrap();
+        for row in [3, 4, 5, 9] {
+            sheet.set_row_hidden(row, true);
+        }
+        sheet.set_cell(CellRef::new(1, 1), CellValue::Number(1.0));
+        wb.save_as(&file_path).unwrap();
+
+        let mut json = default_global();
+        json.format = OutputFormat::Json;
+        hidden(&file_path, &json).unwrap();
+        hidden(&file_path, &default_global()).unwrap();
+
+        let found = Workbook::open(&file_path).unwrap().find_hidden();
+        assert_eq!(found.very_hidden_sheets, vec!["Keys"]);
+        assert_eq!(
+            spans_by_sheet(&found.hidden_rows, |n| n.to_string()),
+            vec![("Model".to_string(), "3-5, 9".to_string())]
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! Benchmarks of core operations on a given workbook.
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use xlex_core::{LazyWorkbook, Workbook};
+
+use super::{GlobalOptions, OutputFormat};
+
+/// Arguments for the bench command.
+#[derive(Parser)]
+pub struct BenchArgs {
+    /// Path to the xlsx file
+    pub file: std::path::PathBuf,
+    /// Operations to time, comma-separated
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_value = "open,stream,export-csv"
+    )]
+    pub ops: Vec<BenchOp>,
+    /// Runs of each operation
+    #[arg(long, default_value = "5", value_parser = clap::value_parser!(u32).range(1..))]
+    pub iterations: u32,
+}
+
+/// An operation timed by `xlex bench`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BenchOp {
+    /// Load the whole workbook into memory
+    Open,
+    /// Read workbook metadata only
+    LazyOpen,
+    /// Stream every row of every sheet
+    Stream,
+    /// Render every sheet as CSV in memory
+    ExportCsv,
+    /// Open the workbook and save a copy to a temporary file
+    Save,
+}
+
+impl BenchOp {
+    fn name(self) -> &'static str {
+        match self {
+            BenchOp::Open => "open",
+            BenchOp::LazyOpen => "lazy-open",
+            BenchOp::Stream => "stream",
+            BenchOp::ExportCsv => "export-csv",
+            BenchOp::Save => "save",
+        }
+    }
+
+    /// Runs the operation once.
+    fn run(self, file: &std::path::Path, scratch: &std::path::Path) -> Result<()> {
+        match self {
+            BenchOp::Open => {
+                Workbook::open(file)?;
+            }
+            BenchOp::LazyOpen => {
+                LazyWorkbook::open(file)?;
+            }
+            BenchOp::Stream => {
+                let workbook = LazyWorkbook::open(file)?;
+                for sheet in workbook.sheet_names() {
+                    std::hint::black_box(workbook.stream_rows(sheet)?);
+                }
+            }
+            BenchOp::ExportCsv => {
+                let workbook = Workbook::open(file)?;
+                let mut csv = String::new();
+                for name in workbook.sheet_names() {
+                    let Some(sheet) = workbook.get_sheet(name) else {
+                        continue;
+                    };
+                    let (max_col, max_row) = sheet.dimensions();
+                    for row in 1..=max_row {
+                        for col in 1..=max_col {
+                            if col > 1 {
+                                csv.push(',');
+                            }
+                            let value = sheet.get_value(&xlex_core::CellRef::new(col, row));
+                            csv.push_str(&value.to_display_string());
+                        }
+                        csv.push('\n');
+                    }
+                }
+                std::hint::black_box(csv);
+            }
+            BenchOp::Save => {
+                Workbook::open(file)?.save_as(scratch)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Timings of one operation.
+struct OpResult {
+    op: BenchOp,
+    runs: Vec<Duration>,
+    peak_rss: Option<u64>,
+}
+
+impl OpResult {
+    fn millis(duration: Duration) -> f64 {
+        duration.as_secs_f64() * 1000.0
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let mut sorted = self.runs.clone();
+        sorted.sort();
+        let total: Duration = sorted.iter().sum();
+        serde_json::json!({
+            "op": self.op.name(),
+            "iterations": sorted.len(),
+            "minMs": Self::millis(sorted[0]),
+            "medianMs": Self::millis(sorted[sorted.len() / 2]),
+            "meanMs": Self::millis(total) / sorted.len() as f64,
+            "maxMs": Self::millis(sorted[sorted.len() - 1]),
+            "peakRssBytes": self.peak_rss,
+        })
+    }
+}
+
+/// Peak resident set size of this process, where the platform reports it.
+fn peak_rss() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmHWM:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+/// Resets the peak RSS so each operation reports its own; best effort.
+fn reset_peak_rss() {
+    let _ = std::fs::write("/proc/self/clear_refs", "5");
+}
+
+/// Run the bench command.
+pub fn run(args: &BenchArgs, global: &GlobalOptions) -> Result<()> {
+    let size = std::fs::metadata(&args.file)?.len();
+    let scratch = std::env::temp_dir().join(format!("xlex-bench-{}.xlsx", std::process::id()));
+
+    let mut results = Vec::new();
+    for &op in &args.ops {
+        reset_peak_rss();
+        let mut runs = Vec::new();
+        for _ in 0..args.iterations {
+            let start = Instant::now();
+            let outcome = op.run(&args.file, &scratch);
+            runs.push(start.elapsed());
+            if let Err(e) = outcome {
+                let _ = std::fs::remove_file(&scratch);
+                return Err(e.context(format!("Benchmark '{}' failed", op.name())));
+            }
+        }
+        results.push(OpResult {
+            op,
+            runs,
+            peak_rss: peak_rss(),
+        });
+    }
+    let _ = std::fs::remove_file(&scratch);
+
+    if global.format == OutputFormat::Json {
+        let json = serde_json::json!({
+            "file": args.file.display().to_string(),
+            "sizeBytes": size,
+            "version": env!("CARGO_PKG_VERSION"),
+            "os": std::env::consts::OS,
+            "arch": std::env::consts::ARCH,
+            "ops": results.iter().map(OpResult::to_json).collect::<Vec<_>>(),
+        });
+        outln!("{}", serde_json::to_string_pretty(&json)?);
+    } else if !global.quiet {
+        outln!(
+            "{} ({}, {} iterations)",
+            args.file.display().to_string().bold(),
+            super::workbook::format_file_size(size),
+            args.iterations
+        );
+        outln!(
+            "  {:<12} {:>10} {:>10} {:>10} {:>10} {:>10}",
+            "op",
+            "min ms",
+            "median ms",
+            "mean ms",
+            "max ms",
+            "peak RSS"
+        );
+        for result in &results {
+            let json = result.to_json();
+            let ms = |key: &str| format!("{:.2}", json[key].as_f64().unwrap_or_default());
+            let rss = result
+                .peak_rss
+                .map(super::workbook::format_file_size)
+                .unwrap_or_else(|| "-".to_string());
+            outln!(
+                "  {:<12} {:>10} {:>10} {:>10} {:>10} {:>10}",
+                result.op.name().cyan(),
+                ms("minMs"),
+                ms("medianMs"),
+                ms("meanMs"),
+                ms("maxMs"),
+                rss
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_bench_all_ops() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("bench.xlsx");
+        let mut workbook = Workbook::new();
+        workbook
+            .set_cell(
+                "Sheet1",
+                xlex_core::CellRef::new(1, 1),
+                xlex_core::CellValue::Number(1.0),
+            )
+            .unwrap();
+        workbook.save_as(&file).unwrap();
+
+        let scratch = temp_dir.path().join("copy.xlsx");
+        for op in [
+            BenchOp::Open,
+            BenchOp::LazyOpen,
+            BenchOp::Stream,
+            BenchOp::ExportCsv,
+            BenchOp::Save,
+        ] {
+            op.run(&file, &scratch).unwrap();
+        }
+        assert!(scratch.exists());
+
+        let result = OpResult {
+            op: BenchOp::Stream,
+            runs: vec![Duration::from_millis(3), Duration::from_millis(1)],
+            peak_rss: Some(1024),
+        };
+        let json = result.to_json();
+        assert_eq!(json["op"], "stream");
+        assert_eq!(json["minMs"], 1.0);
+        assert_eq!(json["meanMs"], 2.0);
+        assert_eq!(json["peakRssBytes"], 1024);
+    }
+}
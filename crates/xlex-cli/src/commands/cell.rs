@@ -4,8 +4,9 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use colored::Colorize;
 
-use xlex_core::{CellRef, CellValue, Workbook};
+use xlex_core::{CellRef, CellValue, Range, Workbook};
 
+use super::output::CommandOutput;
 use super::{GlobalOptions, OutputFormat};
 
 /// Arguments for cell operations.
@@ -50,6 +51,12 @@ pub enum CellCommand {
         cell: String,
         /// Formula (without leading =)
         formula: String,
+        /// Enter as an array formula over this range, starting at the cell
+        #[arg(long, value_name = "RANGE")]
+        array: Option<String>,
+        /// Enter as a dynamic array formula that spills from the cell
+        #[arg(long)]
+        dynamic: bool,
     },
     /// Clear cell
     Clear {
@@ -69,15 +76,52 @@ pub enum CellCommand {
         /// Cell reference (e.g., A1, B2)
         cell: String,
     },
-    /// Batch cell operations from stdin
-    Batch {
-        /// Path to the xlsx file
-        file: std::path::PathBuf,
-    },
+    /// Batch cell updates from stdin or a file
+    Batch(BatchArgs),
     /// Cell comment operations
     Comment(CommentArgs),
     /// Cell hyperlink operations
     Link(LinkArgs),
+    /// Cell style inspection
+    Style(CellStyleArgs),
+    /// Checkbox cell operations
+    Checkbox(CheckboxArgs),
+    /// Dropdown list operations
+    Dropdown(DropdownArgs),
+}
+
+/// Arguments for batch cell updates.
+///
+/// Each input line is one update:
+///
+/// ```text
+/// Sheet1 A1 Hello          # sheet, cell, value (auto-typed)
+/// Sheet1 A2                # sheet and cell only: clear
+/// Sheet2!B3:number=42      # reference[:type]=value
+/// B4==SUM(B1:B3)           # formula (auto type, leading =)
+/// 'My Sheet'!C1:comment=Checked by QA
+/// C2:clear=
+/// ```
+///
+/// Types are `auto`, `string`, `number`, `boolean`, `formula`, `comment`
+/// and `clear`. With `--json` the input is an array of objects, or one
+/// object per line, such as `{"ref": "Sheet2!B3", "value": 42}`.
+#[derive(Parser)]
+pub struct BatchArgs {
+    /// Path to the xlsx file
+    pub file: std::path::PathBuf,
+    /// Read updates from this file instead of stdin
+    #[arg(long, short = 'i')]
+    pub input: Option<std::path::PathBuf>,
+    /// Input is JSON
+    #[arg(long)]
+    pub json: bool,
+    /// Sheet for references without one (default: the only sheet)
+    #[arg(long, short = 's')]
+    pub sheet: Option<String>,
+    /// Apply the valid updates and save even if some fail
+    #[arg(long)]
+    pub continue_on_error: bool,
 }
 
 /// Arguments for comment operations.
@@ -173,6 +217,100 @@ pub enum LinkCommand {
     },
 }
 
+/// Arguments for cell style operations.
+#[derive(Parser)]
+pub struct CellStyleArgs {
+    #[command(subcommand)]
+    pub command: CellStyleCommand,
+}
+
+#[derive(Subcommand)]
+pub enum CellStyleCommand {
+    /// Get the fully resolved style of a cell
+    Get {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name
+        sheet: String,
+        /// Cell reference
+        cell: String,
+    },
+}
+
+/// Arguments for checkbox operations.
+#[derive(Parser)]
+pub struct CheckboxArgs {
+    #[command(subcommand)]
+    pub command: CheckboxCommand,
+}
+
+#[derive(Subcommand)]
+pub enum CheckboxCommand {
+    /// Turn cells into checkboxes (unchecked unless --checked)
+    Set {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name
+        sheet: String,
+        /// Cell or range reference (e.g., B2 or B2:B20)
+        cell: String,
+        /// Tick the checkboxes
+        #[arg(long)]
+        checked: bool,
+    },
+    /// Remove the checkbox format, keeping the TRUE/FALSE values
+    Remove {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name
+        sheet: String,
+        /// Cell or range reference
+        cell: String,
+    },
+}
+
+/// Arguments for dropdown operations.
+#[derive(Parser)]
+pub struct DropdownArgs {
+    #[command(subcommand)]
+    pub command: DropdownCommand,
+}
+
+#[derive(Subcommand)]
+pub enum DropdownCommand {
+    /// Add a dropdown list to a range
+    Set {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name
+        sheet: String,
+        /// Cell or range reference (e.g., C2:C100)
+        range: String,
+        /// Comma-separated items (e.g., "Open,In progress,Done")
+        #[arg(long, value_delimiter = ',', required_unless_present = "source")]
+        items: Vec<String>,
+        /// Range holding the items (e.g., Lists!$A$1:$A$5)
+        #[arg(long, conflicts_with = "items")]
+        source: Option<String>,
+    },
+    /// List dropdowns in a sheet
+    List {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name
+        sheet: String,
+    },
+    /// Remove dropdowns overlapping a range
+    Remove {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name
+        sheet: String,
+        /// Cell or range reference
+        range: String,
+    },
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
 pub enum ValueType {
     #[default]
@@ -199,12 +337,31 @@ pub fn run(args: &CellArgs, global: &GlobalOptions) -> Result<()> {
             sheet,
             cell,
             formula,
-        } => set_formula(file, sheet, cell, formula, global),
+            array,
+            dynamic,
+        } => {
+            if array.is_some() || *dynamic {
+                set_array_formula(
+                    file,
+                    sheet,
+                    cell,
+                    formula,
+                    array.as_deref(),
+                    *dynamic,
+                    global,
+                )
+            } else {
+                set_formula(file, sheet, cell, formula, global)
+            }
+        }
         CellCommand::Clear { file, sheet, cell } => clear(file, sheet, cell, global),
         CellCommand::Type { file, sheet, cell } => get_type(file, sheet, cell, global),
-        CellCommand::Batch { file } => batch(file, global),
+        CellCommand::Batch(args) => batch(args, global),
         CellCommand::Comment(args) => run_comment(args, global),
         CellCommand::Link(args) => run_link(args, global),
+        CellCommand::Style(args) => run_style(args, global),
+        CellCommand::Checkbox(args) => run_checkbox(args, global),
+        CellCommand::Dropdown(args) => run_dropdown(args, global),
     }
 }
 
@@ -227,11 +384,12 @@ fn get(file: &std::path::Path, sheet: &str, cell: &str, global: &GlobalOptions)
                 }),
                 CellValue::Error(e) => serde_json::Value::String(e.to_string()),
                 CellValue::DateTime(d) => serde_json::json!(d),
+                CellValue::Decimal(_) => serde_json::Value::from(&value),
             },
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else {
-        println!("{}", value.to_display_string());
+        outln!("{}", value.to_display_string());
     }
 
     Ok(())
@@ -246,48 +404,30 @@ fn set(
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!("Would set {} in {} to '{}'", cell, sheet, value);
+        outln!("Would set {} in {} to '{}'", cell, sheet, value);
         return Ok(());
     }
 
+    let output = CommandOutput::new("set");
+
     let mut workbook = Workbook::open(file)?;
     let cell_ref = CellRef::parse(cell)?;
 
-    let cell_value = match value_type {
-        ValueType::Auto => parse_auto_value(value),
-        ValueType::String => CellValue::String(value.to_string()),
-        ValueType::Number => {
-            let n: f64 = value
-                .parse()
-                .map_err(|_| xlex_core::XlexError::InvalidCellValue {
-                    message: format!("Cannot parse '{}' as number", value),
-                })?;
-            CellValue::Number(n)
-        }
-        ValueType::Boolean => {
-            let b = value.eq_ignore_ascii_case("true")
-                || value == "1"
-                || value.eq_ignore_ascii_case("yes");
-            CellValue::Boolean(b)
-        }
-        ValueType::Formula => CellValue::formula(value),
-    };
+    let cell_value = typed_value(value, value_type)?;
 
     workbook.set_cell(sheet, cell_ref, cell_value)?;
     workbook.save()?;
 
-    if !global.quiet {
-        if global.format == OutputFormat::Json {
-            let json = serde_json::json!({
-                "action": "set",
-                "cell": cell,
-                "value": value,
-            });
-            println!("{}", serde_json::to_string_pretty(&json)?);
-        } else {
-            println!("Set {} to '{}'", cell.cyan(), value.green());
-        }
-    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("cell", cell)
+        .field("cell", cell)
+        .field("value", value)
+        .print(
+            global,
+            format!("Set {} to '{}'", cell.cyan(), value.green()),
+        )?;
 
     Ok(())
 }
@@ -303,58 +443,119 @@ fn set_formula(
     let formula = formula.strip_prefix('=').unwrap_or(formula);
 
     if global.dry_run {
-        println!(
+        outln!(
             "Would set formula in {} in {} to '={}'",
-            cell, sheet, formula
+            cell,
+            sheet,
+            formula
         );
         return Ok(());
     }
 
+    let output = CommandOutput::new("formula");
+
     let mut workbook = Workbook::open(file)?;
     let cell_ref = CellRef::parse(cell)?;
 
     workbook.set_cell(sheet, cell_ref, CellValue::formula(formula))?;
     workbook.save()?;
 
-    if !global.quiet {
-        if global.format == OutputFormat::Json {
-            let json = serde_json::json!({
-                "action": "formula",
-                "cell": cell,
-                "formula": formula,
-            });
-            println!("{}", serde_json::to_string_pretty(&json)?);
-        } else {
-            println!("Set formula at {} to '={}'", cell.cyan(), formula.green());
-        }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("cell", cell)
+        .field("cell", cell)
+        .field("formula", formula)
+        .print(
+            global,
+            format!("Set formula at {} to '={}'", cell.cyan(), formula.green()),
+        )?;
+
+    Ok(())
+}
+
+fn set_array_formula(
+    file: &std::path::Path,
+    sheet: &str,
+    cell: &str,
+    formula: &str,
+    array: Option<&str>,
+    dynamic: bool,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let formula = formula.strip_prefix('=').unwrap_or(formula);
+    let cell_ref = CellRef::parse(cell)?;
+    let range = match array {
+        Some(array) => Range::parse(array)?,
+        None => Range::new(cell_ref.clone(), cell_ref.clone()),
+    };
+    if range.start != cell_ref {
+        anyhow::bail!(
+            "Array range {} must start at the formula cell {}",
+            range.to_a1(),
+            cell
+        );
     }
+    let kind = if dynamic { "dynamic array" } else { "array" };
+
+    if global.dry_run {
+        outln!(
+            "Would set {} formula over {} in {} to '={}'",
+            kind,
+            range.to_a1(),
+            sheet,
+            formula
+        );
+        return Ok(());
+    }
+
+    let output = CommandOutput::new("formula");
+
+    let mut workbook = Workbook::open(file)?;
+    workbook.with_sheet_mut(sheet, |s| s.set_array_formula(&range, formula, dynamic))?;
+    workbook.save()?;
+
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("cell", cell)
+        .field("cell", cell)
+        .field("formula", formula)
+        .field("array", range.to_a1())
+        .field("dynamic", dynamic)
+        .print(
+            global,
+            format!(
+                "Set {} formula at {} to '={}'",
+                kind,
+                range.to_a1().cyan(),
+                formula.green()
+            ),
+        )?;
 
     Ok(())
 }
 
 fn clear(file: &std::path::Path, sheet: &str, cell: &str, global: &GlobalOptions) -> Result<()> {
     if global.dry_run {
-        println!("Would clear {} in {}", cell, sheet);
+        outln!("Would clear {} in {}", cell, sheet);
         return Ok(());
     }
 
+    let output = CommandOutput::new("clear");
+
     let mut workbook = Workbook::open(file)?;
     let cell_ref = CellRef::parse(cell)?;
 
     workbook.clear_cell(sheet, &cell_ref)?;
     workbook.save()?;
 
-    if !global.quiet {
-        if global.format == OutputFormat::Json {
-            let json = serde_json::json!({
-                "action": "clear",
-                "cell": cell,
-            });
-            println!("{}", serde_json::to_string_pretty(&json)?);
-        } else {
-            println!("Cleared {}", cell.cyan());
-        }
-    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("cell", cell)
+        .field("cell", cell)
+        .print(global, format!("Cleared {}", cell.cyan()))?;
 
     Ok(())
 }
@@ -369,116 +570,312 @@ fn get_type(file: &std::path::Path, sheet: &str, cell: &str, global: &GlobalOpti
             "cell": cell,
             "type": value.type_name(),
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else {
-        println!("{}", value.type_name());
+        outln!("{}", value.type_name());
+    }
+
+    Ok(())
+}
+
+/// Converts `value` to a cell value of the given type.
+fn typed_value(value: &str, value_type: ValueType) -> Result<CellValue> {
+    Ok(match value_type {
+        ValueType::Auto => parse_auto_value(value),
+        ValueType::String => CellValue::String(value.to_string()),
+        ValueType::Number => {
+            let n: f64 = value
+                .parse()
+                .map_err(|_| xlex_core::XlexError::InvalidCellValue {
+                    message: format!("Cannot parse '{}' as number", value),
+                })?;
+            CellValue::Number(n)
+        }
+        ValueType::Boolean => {
+            let b = value.eq_ignore_ascii_case("true")
+                || value == "1"
+                || value.eq_ignore_ascii_case("yes");
+            CellValue::Boolean(b)
+        }
+        ValueType::Formula => CellValue::formula(value.strip_prefix('=').unwrap_or(value)),
+    })
+}
+
+/// What a batch update does to its cell.
+#[derive(Debug, PartialEq)]
+enum BatchOp {
+    Set(CellValue),
+    Comment(String),
+    Clear,
+}
+
+/// One update read by `cell batch`.
+#[derive(Debug, PartialEq)]
+struct BatchUpdate {
+    sheet: Option<String>,
+    cell: CellRef,
+    op: BatchOp,
+}
+
+impl BatchUpdate {
+    /// Builds an update from a `[sheet!]cell`, a type name and a value.
+    fn typed(reference: &str, kind: &str, value: &str) -> Result<Self> {
+        let (sheet, cell) = CellRef::parse_qualified(reference)?;
+        let op = match kind.to_lowercase().as_str() {
+            "comment" => BatchOp::Comment(value.to_string()),
+            "clear" => BatchOp::Clear,
+            "auto" if value.is_empty() => BatchOp::Clear,
+            kind => {
+                let value_type = <ValueType as clap::ValueEnum>::from_str(kind, true)
+                    .map_err(|_| anyhow::anyhow!("Unknown type '{}'", kind))?;
+                BatchOp::Set(typed_value(value, value_type)?)
+            }
+        };
+        Ok(Self { sheet, cell, op })
     }
+}
 
+/// Parses a text batch line: `SHEET CELL [VALUE]` or
+/// `[SHEET!]CELL[:TYPE]=VALUE`. Returns nothing for blank and comment
+/// lines.
+fn parse_batch_line(line: &str) -> Result<Vec<BatchUpdate>> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(Vec::new());
+    }
+
+    // The key of `key=value` has no whitespace outside a quoted sheet name
+    let mut in_quotes = false;
+    let mut key_end = None;
+    for (i, c) in line.char_indices() {
+        match c {
+            '\'' => in_quotes = !in_quotes,
+            '=' if !in_quotes => {
+                key_end = Some(i);
+                break;
+            }
+            c if c.is_whitespace() && !in_quotes => break,
+            _ => {}
+        }
+    }
+    if let Some(end) = key_end {
+        let (key, value) = (&line[..end], &line[end + 1..]);
+        let (reference, kind) = match key.rsplit_once(':') {
+            Some((reference, kind)) if !kind.contains('!') && !kind.contains('\'') => {
+                (reference, kind)
+            }
+            _ => (key, "auto"),
+        };
+        return Ok(vec![BatchUpdate::typed(reference, kind, value)?]);
+    }
+
+    let parts: Vec<&str> = line.splitn(3, char::is_whitespace).collect();
+    let op = match parts.len() {
+        2 => BatchOp::Clear,
+        3 => BatchOp::Set(parse_auto_value(parts[2])),
+        _ => anyhow::bail!("Invalid format: expected 'SHEET CELL [VALUE]' or 'CELL=VALUE'"),
+    };
+    Ok(vec![BatchUpdate {
+        sheet: Some(parts[0].to_string()),
+        cell: CellRef::parse(parts[1])?,
+        op,
+    }])
+}
+
+/// Parses a JSON batch item: `{"ref": "Sheet1!B2", "value": 42}` or with
+/// `sheet` and `cell`, plus optional `type`, `formula` and `comment`. A
+/// `null` value clears the cell.
+fn parse_batch_json(item: &serde_json::Value) -> Result<Vec<BatchUpdate>> {
+    use serde_json::Value;
+
+    let object = item
+        .as_object()
+        .ok_or_else(|| anyhow::anyhow!("Expected an object"))?;
+    let text = |key: &str| object.get(key).and_then(Value::as_str);
+    let reference = match (text("ref"), text("sheet"), text("cell")) {
+        (Some(reference), _, _) => reference.to_string(),
+        (None, Some(sheet), Some(cell)) => {
+            format!("{}!{}", xlex_core::sheet::quote_sheet_name(sheet), cell)
+        }
+        (None, None, Some(cell)) => cell.to_string(),
+        _ => anyhow::bail!("Missing \"ref\" or \"cell\""),
+    };
+    let kind = text("type").unwrap_or("auto");
+
+    let mut updates = Vec::new();
+    if let Some(formula) = text("formula") {
+        updates.push(BatchUpdate::typed(&reference, "formula", formula)?);
+    }
+    match object.get("value") {
+        None => {}
+        Some(Value::Null) => updates.push(BatchUpdate::typed(&reference, "clear", "")?),
+        Some(Value::String(s)) if kind == "auto" => {
+            updates.push(BatchUpdate::typed(&reference, "string", s)?)
+        }
+        Some(Value::String(s)) => updates.push(BatchUpdate::typed(&reference, kind, s)?),
+        Some(Value::Number(n)) if kind == "auto" => {
+            let (sheet, cell) = CellRef::parse_qualified(&reference)?;
+            let n = n
+                .as_f64()
+                .ok_or_else(|| anyhow::anyhow!("Invalid number {}", n))?;
+            updates.push(BatchUpdate {
+                sheet,
+                cell,
+                op: BatchOp::Set(CellValue::Number(n)),
+            });
+        }
+        Some(value) => updates.push(BatchUpdate::typed(&reference, kind, &value.to_string())?),
+    }
+    if let Some(comment) = text("comment") {
+        updates.push(BatchUpdate::typed(&reference, "comment", comment)?);
+    }
+    if updates.is_empty() {
+        anyhow::bail!("Nothing to do: give \"value\", \"formula\" or \"comment\"");
+    }
+    Ok(updates)
+}
+
+/// Applies one update to the workbook.
+fn apply_batch_update(
+    workbook: &mut Workbook,
+    update: BatchUpdate,
+    default_sheet: Option<&str>,
+) -> Result<()> {
+    let sheet = match (&update.sheet, default_sheet) {
+        (Some(sheet), _) => sheet.as_str(),
+        (None, Some(sheet)) => sheet,
+        (None, None) => anyhow::bail!(
+            "No sheet for {}: write it as Sheet1!{} or pass --sheet",
+            update.cell,
+            update.cell
+        ),
+    };
+    match update.op {
+        BatchOp::Set(value) => workbook.set_cell(sheet, update.cell, value)?,
+        BatchOp::Clear => workbook.clear_cell(sheet, &update.cell)?,
+        BatchOp::Comment(text) => workbook
+            .get_sheet_mut(sheet)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: sheet.to_string(),
+            })?
+            .set_cell_comment(&update.cell, Some(text)),
+    }
     Ok(())
 }
 
-fn batch(file: &std::path::Path, global: &GlobalOptions) -> Result<()> {
-    use std::io::{self, BufRead};
+fn batch(args: &BatchArgs, global: &GlobalOptions) -> Result<()> {
+    use std::io::Read;
 
     if global.dry_run {
-        println!("Would process batch operations from stdin");
+        match &args.input {
+            Some(input) => outln!("Would process batch operations from {}", input.display()),
+            None => outln!("Would process batch operations from stdin"),
+        }
         return Ok(());
     }
 
-    let mut workbook = Workbook::open(file)?;
-    let stdin = io::stdin();
-    let mut line_num = 0;
-    let mut success_count = 0;
-    let mut error_count = 0;
+    let report = CommandOutput::new("batch");
+    let input = match &args.input {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+            input
+        }
+    };
 
-    for line in stdin.lock().lines() {
-        line_num += 1;
-        let line = line?;
-        let line = line.trim();
+    // (line or item number, source text, parsed updates)
+    let items: Vec<(usize, String, Result<Vec<BatchUpdate>>)> =
+        if args.json && input.trim_start().starts_with('[') {
+            let array: Vec<serde_json::Value> = serde_json::from_str(&input)?;
+            array
+                .iter()
+                .enumerate()
+                .map(|(i, item)| (i + 1, item.to_string(), parse_batch_json(item)))
+                .collect()
+        } else {
+            input
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !args.json || !line.trim().is_empty())
+                .map(|(i, line)| {
+                    let updates = if args.json {
+                        serde_json::from_str(line)
+                            .map_err(anyhow::Error::from)
+                            .and_then(|item| parse_batch_json(&item))
+                    } else {
+                        parse_batch_line(line)
+                    };
+                    (i + 1, line.trim().to_string(), updates)
+                })
+                .collect()
+        };
 
-        // Skip empty lines and comments
-        if line.is_empty() || line.starts_with('#') {
-            continue;
-        }
+    let mut workbook = Workbook::open(&args.file)?;
+    let default_sheet = match &args.sheet {
+        Some(sheet) => Some(sheet.clone()),
+        None => match workbook.sheet_names().as_slice() {
+            [only] => Some(only.to_string()),
+            _ => None,
+        },
+    };
 
-        // Parse line: SHEET CELL VALUE or SHEET CELL (for clear)
-        let parts: Vec<&str> = line.splitn(3, char::is_whitespace).collect();
-
-        match parts.len() {
-            2 => {
-                // Clear operation: SHEET CELL
-                let sheet = parts[0];
-                let cell = parts[1];
-                match CellRef::parse(cell) {
-                    Ok(cell_ref) => {
-                        if let Err(e) = workbook.clear_cell(sheet, &cell_ref) {
-                            if !global.quiet {
-                                eprintln!("Line {}: Error clearing {}: {}", line_num, cell, e);
-                            }
-                            error_count += 1;
-                        } else {
-                            success_count += 1;
-                        }
-                    }
-                    Err(e) => {
-                        if !global.quiet {
-                            eprintln!("Line {}: Invalid cell '{}': {}", line_num, cell, e);
-                        }
-                        error_count += 1;
-                    }
-                }
-            }
-            3 => {
-                // Set operation: SHEET CELL VALUE
-                let sheet = parts[0];
-                let cell = parts[1];
-                let value = parts[2];
-                match CellRef::parse(cell) {
-                    Ok(cell_ref) => {
-                        let cell_value = parse_auto_value(value);
-                        if let Err(e) = workbook.set_cell(sheet, cell_ref, cell_value) {
-                            if !global.quiet {
-                                eprintln!("Line {}: Error setting {}: {}", line_num, cell, e);
-                            }
-                            error_count += 1;
-                        } else {
-                            success_count += 1;
-                        }
-                    }
-                    Err(e) => {
-                        if !global.quiet {
-                            eprintln!("Line {}: Invalid cell '{}': {}", line_num, cell, e);
-                        }
-                        error_count += 1;
-                    }
-                }
+    let mut success_count = 0;
+    let mut errors: Vec<(usize, String, String)> = Vec::new();
+    for (line_num, text, updates) in items {
+        let result = updates.and_then(|updates| {
+            let applied = updates.len();
+            for update in updates {
+                apply_batch_update(&mut workbook, update, default_sheet.as_deref())?;
             }
-            _ => {
+            Ok(applied)
+        });
+        match result {
+            Ok(applied) => success_count += applied,
+            Err(e) => {
                 if !global.quiet {
-                    eprintln!("Line {}: Invalid format: '{}'", line_num, line);
+                    eprintln!("  {} Line {}: {} — {}", "✗".red(), line_num, text, e);
                 }
-                error_count += 1;
+                if !args.continue_on_error {
+                    anyhow::bail!(
+                        "Update failed at line {}: {} (nothing saved; use --continue-on-error to apply the rest)",
+                        line_num,
+                        e
+                    );
+                }
+                errors.push((line_num, text, e.to_string()));
             }
         }
     }
 
-    workbook.save()?;
-
-    if global.format == OutputFormat::Json {
-        let json = serde_json::json!({
-            "success": success_count,
-            "errors": error_count,
-            "total": success_count + error_count,
-        });
-        println!("{}", serde_json::to_string_pretty(&json)?);
-    } else if !global.quiet {
-        println!(
-            "Batch complete: {} succeeded, {} errors",
-            success_count.to_string().green(),
-            error_count.to_string().red()
-        );
+    if success_count > 0 {
+        workbook.save()?;
     }
 
+    let failures: Vec<serde_json::Value> = errors
+        .iter()
+        .map(|(line, input, error)| {
+            serde_json::json!({
+                "line": line,
+                "input": input,
+                "error": error,
+            })
+        })
+        .collect();
+    report
+        .file(&args.file)
+        .count("succeeded", success_count)
+        .count("failed", errors.len())
+        .field("errors", failures)
+        .print(
+            global,
+            format!(
+                "Batch complete: {} succeeded, {} errors",
+                success_count.to_string().green(),
+                errors.len().to_string().red()
+            ),
+        )?;
+
     Ok(())
 }
 
@@ -522,11 +919,11 @@ fn comment_get(
             "cell": cell,
             "comment": comment,
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else if let Some(text) = comment {
-        println!("{}", text);
+        outln!("{}", text);
     } else {
-        println!("(no comment)");
+        outln!("(no comment)");
     }
     Ok(())
 }
@@ -540,10 +937,12 @@ fn comment_set(
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!("Would set comment on {} to '{}'", cell, text);
+        outln!("Would set comment on {} to '{}'", cell, text);
         return Ok(());
     }
 
+    let output = CommandOutput::new("comment");
+
     let mut workbook = Workbook::open(file)?;
     let cell_ref = CellRef::parse(cell)?;
 
@@ -559,9 +958,15 @@ fn comment_set(
 
     workbook.save()?;
 
-    if !global.quiet {
-        println!("{} Set comment on {}", "✓".green(), cell.cyan());
-    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("cell", cell)
+        .field("text", text)
+        .print(
+            global,
+            format!("{} Set comment on {}", "✓".green(), cell.cyan()),
+        )?;
     Ok(())
 }
 
@@ -572,10 +977,12 @@ fn comment_remove(
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!("Would remove comment from {}", cell);
+        outln!("Would remove comment from {}", cell);
         return Ok(());
     }
 
+    let output = CommandOutput::new("remove-comment");
+
     let mut workbook = Workbook::open(file)?;
     let cell_ref = CellRef::parse(cell)?;
 
@@ -591,9 +998,14 @@ fn comment_remove(
 
     workbook.save()?;
 
-    if !global.quiet {
-        println!("{} Removed comment from {}", "✓".green(), cell.cyan());
-    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("cell", cell)
+        .print(
+            global,
+            format!("{} Removed comment from {}", "✓".green(), cell.cyan()),
+        )?;
     Ok(())
 }
 
@@ -619,16 +1031,16 @@ fn comment_list(file: &std::path::Path, sheet: &str, global: &GlobalOptions) ->
         .collect();
 
     if global.format == OutputFormat::Json {
-        println!(
+        outln!(
             "{}",
             serde_json::to_string_pretty(&serde_json::json!({ "comments": comments }))?
         );
     } else if comments.is_empty() {
-        println!("No comments found");
+        outln!("No comments found");
     } else {
-        println!("{} comment(s):", comments.len());
+        outln!("{} comment(s):", comments.len());
         for c in comments {
-            println!(
+            outln!(
                 "  {}: {}",
                 c["cell"].as_str().unwrap().cyan(),
                 c["comment"].as_str().unwrap()
@@ -638,117 +1050,476 @@ fn comment_list(file: &std::path::Path, sheet: &str, global: &GlobalOptions) ->
     Ok(())
 }
 
-fn run_link(args: &LinkArgs, global: &GlobalOptions) -> Result<()> {
+fn run_link(args: &LinkArgs, global: &GlobalOptions) -> Result<()> {
+    match &args.command {
+        LinkCommand::Get { file, sheet, cell } => link_get(file, sheet, cell, global),
+        LinkCommand::Set {
+            file,
+            sheet,
+            cell,
+            url,
+            text,
+        } => link_set(file, sheet, cell, url, text.as_deref(), global),
+        LinkCommand::Remove { file, sheet, cell } => link_remove(file, sheet, cell, global),
+    }
+}
+
+fn link_get(file: &std::path::Path, sheet: &str, cell: &str, global: &GlobalOptions) -> Result<()> {
+    let workbook = Workbook::open(file)?;
+    let cell_ref = CellRef::parse(cell)?;
+
+    let sheet_obj =
+        workbook
+            .get_sheet(sheet)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: sheet.to_string(),
+            })?;
+
+    let hyperlink = sheet_obj
+        .get_cell(&cell_ref)
+        .and_then(|c| c.hyperlink.clone());
+
+    if global.format == OutputFormat::Json {
+        let json = serde_json::json!({
+            "cell": cell,
+            "link": hyperlink,
+        });
+        outln!("{}", serde_json::to_string_pretty(&json)?);
+    } else if let Some(url) = hyperlink {
+        outln!("{}", url);
+    } else {
+        outln!("(no hyperlink)");
+    }
+    Ok(())
+}
+
+fn link_set(
+    file: &std::path::Path,
+    sheet: &str,
+    cell: &str,
+    url: &str,
+    _text: Option<&str>,
+    global: &GlobalOptions,
+) -> Result<()> {
+    if global.dry_run {
+        outln!("Would set hyperlink on {} to '{}'", cell, url);
+        return Ok(());
+    }
+
+    let output = CommandOutput::new("link");
+
+    let mut workbook = Workbook::open(file)?;
+    let cell_ref = CellRef::parse(cell)?;
+
+    {
+        let sheet_obj =
+            workbook
+                .get_sheet_mut(sheet)
+                .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                    name: sheet.to_string(),
+                })?;
+        sheet_obj.set_cell_hyperlink(&cell_ref, Some(url.to_string()));
+    }
+
+    workbook.save()?;
+
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("cell", cell)
+        .field("url", url)
+        .print(
+            global,
+            format!(
+                "{} Set hyperlink on {} to {}",
+                "✓".green(),
+                cell.cyan(),
+                url
+            ),
+        )?;
+    Ok(())
+}
+
+fn link_remove(
+    file: &std::path::Path,
+    sheet: &str,
+    cell: &str,
+    global: &GlobalOptions,
+) -> Result<()> {
+    if global.dry_run {
+        outln!("Would remove hyperlink from {}", cell);
+        return Ok(());
+    }
+
+    let output = CommandOutput::new("remove-link");
+
+    let mut workbook = Workbook::open(file)?;
+    let cell_ref = CellRef::parse(cell)?;
+
+    {
+        let sheet_obj =
+            workbook
+                .get_sheet_mut(sheet)
+                .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                    name: sheet.to_string(),
+                })?;
+        sheet_obj.set_cell_hyperlink(&cell_ref, None);
+    }
+
+    workbook.save()?;
+
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("cell", cell)
+        .print(
+            global,
+            format!("{} Removed hyperlink from {}", "✓".green(), cell.cyan()),
+        )?;
+    Ok(())
+}
+
+fn run_style(args: &CellStyleArgs, global: &GlobalOptions) -> Result<()> {
+    match &args.command {
+        CellStyleCommand::Get { file, sheet, cell } => style_get(file, sheet, cell, global),
+    }
+}
+
+fn style_get(
+    file: &std::path::Path,
+    sheet: &str,
+    cell: &str,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let workbook = Workbook::open(file)?;
+    let cell_ref = CellRef::parse(cell)?;
+
+    let style_id = workbook
+        .get_sheet(sheet)
+        .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+            name: sheet.to_string(),
+        })?
+        .get_cell(&cell_ref)
+        .and_then(|c| c.style_id);
+    let style = workbook.get_cell_style(sheet, &cell_ref)?;
+
+    if global.format == OutputFormat::Json {
+        let mut json = super::style::style_json(&style);
+        json["cell"] = serde_json::json!(cell_ref.to_a1());
+        json["styleId"] = serde_json::json!(style_id);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
+    } else {
+        let font = &style.font;
+        let mut font_attrs = vec![format!(
+            "{} {}pt",
+            font.name.as_deref().unwrap_or("Calibri"),
+            font.size.unwrap_or(11.0)
+        )];
+        for (set, name) in [
+            (font.bold, "bold"),
+            (font.italic, "italic"),
+            (font.underline, "underline"),
+            (font.strikethrough, "strikethrough"),
+        ] {
+            if set {
+                font_attrs.push(name.to_string());
+            }
+        }
+        if let Some(hex) = font.color.as_ref().and_then(|c| c.to_hex()) {
+            font_attrs.push(format!("#{}", hex));
+        }
+
+        let border = &style.border;
+        let borders: Vec<String> = [
+            ("left", &border.left),
+            ("right", &border.right),
+            ("top", &border.top),
+            ("bottom", &border.bottom),
+            ("diagonal", &border.diagonal),
+        ]
+        .iter()
+        .filter(|(_, side)| side.style != xlex_core::style::BorderStyle::None)
+        .map(|(name, side)| format!("{} {:?}", name, side.style))
+        .collect();
+
+        outln!("{}: {}", "Cell".bold(), cell_ref.to_a1());
+        match style_id {
+            Some(id) => outln!("{}: {}", "Style ID".bold(), id),
+            None => outln!("{}: (default)", "Style ID".bold()),
+        }
+        outln!("  Font: {}", font_attrs.join(", "));
+        let fill_color = style.fill.fg_color.as_ref().and_then(|c| c.to_hex());
+        if let Some(ref gradient) = style.fill.gradient {
+            let stops: Vec<String> = gradient
+                .stops
+                .iter()
+                .filter_map(|s| s.color.to_hex().map(|hex| format!("#{}", hex)))
+                .collect();
+            outln!(
+                "  Fill: {:?} gradient {}",
+                gradient.gradient_type,
+                stops.join(" → ")
+            );
+        } else {
+            match fill_color {
+                Some(hex) => outln!("  Fill: {:?} #{}", style.fill.pattern, hex),
+                None => outln!("  Fill: {:?}", style.fill.pattern),
+            }
+        }
+        if borders.is_empty() {
+            outln!("  Border: none");
+        } else {
+            outln!("  Border: {}", borders.join(", "));
+        }
+        outln!(
+            "  Number format: {}",
+            style.number_format.code.as_deref().unwrap_or("General")
+        );
+        outln!(
+            "  Alignment: {:?} / {:?}{}",
+            style.horizontal_alignment,
+            style.vertical_alignment,
+            if style.wrap_text { ", wrap" } else { "" }
+        );
+        outln!(
+            "  Protection: {}{}",
+            if style.protection.locked {
+                "locked"
+            } else {
+                "unlocked"
+            },
+            if style.protection.hidden {
+                ", formula hidden"
+            } else {
+                ""
+            }
+        );
+    }
+    Ok(())
+}
+
+fn run_checkbox(args: &CheckboxArgs, global: &GlobalOptions) -> Result<()> {
+    match &args.command {
+        CheckboxCommand::Set {
+            file,
+            sheet,
+            cell,
+            checked,
+        } => checkbox_update(file, sheet, cell, Some(*checked), global),
+        CheckboxCommand::Remove { file, sheet, cell } => {
+            checkbox_update(file, sheet, cell, None, global)
+        }
+    }
+}
+
+fn checkbox_update(
+    file: &std::path::Path,
+    sheet: &str,
+    cell: &str,
+    checked: Option<bool>,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let range = xlex_core::Range::parse(cell)?;
+
+    if global.dry_run {
+        match checked {
+            Some(checked) => outln!(
+                "Would set {} checkbox(es) at {}!{} to {} in {}",
+                range.cell_count(),
+                sheet,
+                cell,
+                checked,
+                file.display()
+            ),
+            None => outln!(
+                "Would remove checkboxes at {}!{} in {}",
+                sheet,
+                cell,
+                file.display()
+            ),
+        }
+        return Ok(());
+    }
+
+    let mut workbook = Workbook::open(file)?;
+    for cell_ref in range.cells() {
+        match checked {
+            Some(checked) => workbook.set_checkbox(sheet, &cell_ref, checked)?,
+            None => workbook.clear_checkbox(sheet, &cell_ref)?,
+        }
+    }
+    workbook.save()?;
+
+    if !global.quiet {
+        if global.format == OutputFormat::Json {
+            let json = serde_json::json!({
+                "sheet": sheet,
+                "range": cell,
+                "cells": range.cell_count(),
+                "checkbox": checked.is_some(),
+                "checked": checked,
+            });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else {
+            match checked {
+                Some(true) => outln!("Set checked checkbox(es) at {}", cell.green()),
+                Some(false) => outln!("Set unchecked checkbox(es) at {}", cell.green()),
+                None => outln!("Removed checkboxes at {}", cell.green()),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn run_dropdown(args: &DropdownArgs, global: &GlobalOptions) -> Result<()> {
     match &args.command {
-        LinkCommand::Get { file, sheet, cell } => link_get(file, sheet, cell, global),
-        LinkCommand::Set {
+        DropdownCommand::Set {
             file,
             sheet,
-            cell,
-            url,
-            text,
-        } => link_set(file, sheet, cell, url, text.as_deref(), global),
-        LinkCommand::Remove { file, sheet, cell } => link_remove(file, sheet, cell, global),
-    }
-}
-
-fn link_get(file: &std::path::Path, sheet: &str, cell: &str, global: &GlobalOptions) -> Result<()> {
-    let workbook = Workbook::open(file)?;
-    let cell_ref = CellRef::parse(cell)?;
-
-    let sheet_obj =
-        workbook
-            .get_sheet(sheet)
-            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
-                name: sheet.to_string(),
-            })?;
-
-    let hyperlink = sheet_obj
-        .get_cell(&cell_ref)
-        .and_then(|c| c.hyperlink.clone());
-
-    if global.format == OutputFormat::Json {
-        let json = serde_json::json!({
-            "cell": cell,
-            "link": hyperlink,
-        });
-        println!("{}", serde_json::to_string_pretty(&json)?);
-    } else if let Some(url) = hyperlink {
-        println!("{}", url);
-    } else {
-        println!("(no hyperlink)");
+            range,
+            items,
+            source,
+        } => dropdown_set(file, sheet, range, items, source.as_deref(), global),
+        DropdownCommand::List { file, sheet } => dropdown_list(file, sheet, global),
+        DropdownCommand::Remove { file, sheet, range } => {
+            dropdown_remove(file, sheet, range, global)
+        }
     }
-    Ok(())
 }
 
-fn link_set(
+fn dropdown_set(
     file: &std::path::Path,
     sheet: &str,
-    cell: &str,
-    url: &str,
-    _text: Option<&str>,
+    range: &str,
+    items: &[String],
+    source: Option<&str>,
     global: &GlobalOptions,
 ) -> Result<()> {
+    let target = xlex_core::Range::parse(range)?;
+    let dropdown = match source {
+        Some(source) => xlex_core::sheet::Dropdown::from_reference(target, source),
+        None => xlex_core::sheet::Dropdown::from_items(target, items)?,
+    };
+
     if global.dry_run {
-        println!("Would set hyperlink on {} to '{}'", cell, url);
+        outln!(
+            "Would add dropdown {} to {}!{} in {}",
+            dropdown.source,
+            sheet,
+            range,
+            file.display()
+        );
         return Ok(());
     }
 
     let mut workbook = Workbook::open(file)?;
-    let cell_ref = CellRef::parse(cell)?;
+    let sheet_obj =
+        workbook
+            .get_sheet_mut(sheet)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: sheet.to_string(),
+            })?;
+    let source = dropdown.source.clone();
+    sheet_obj.add_dropdown(dropdown);
+    workbook.save()?;
 
-    {
-        let sheet_obj =
-            workbook
-                .get_sheet_mut(sheet)
-                .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
-                    name: sheet.to_string(),
-                })?;
-        sheet_obj.set_cell_hyperlink(&cell_ref, Some(url.to_string()));
+    if !global.quiet {
+        if global.format == OutputFormat::Json {
+            let json = serde_json::json!({
+                "sheet": sheet,
+                "range": range,
+                "source": source,
+            });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else {
+            outln!("Added dropdown {} to {}", source, range.green());
+        }
     }
 
-    workbook.save()?;
+    Ok(())
+}
 
-    if !global.quiet {
-        println!(
-            "{} Set hyperlink on {} to {}",
-            "✓".green(),
-            cell.cyan(),
-            url
-        );
+fn dropdown_list(file: &std::path::Path, sheet: &str, global: &GlobalOptions) -> Result<()> {
+    let workbook = Workbook::open(file)?;
+    let sheet_obj =
+        workbook
+            .get_sheet(sheet)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: sheet.to_string(),
+            })?;
+    let dropdowns = sheet_obj.dropdowns();
+
+    if global.format == OutputFormat::Json {
+        let items: Vec<serde_json::Value> = dropdowns
+            .iter()
+            .map(|d| {
+                serde_json::json!({
+                    "range": d.range.to_a1(),
+                    "source": d.source,
+                    "items": d.items(),
+                })
+            })
+            .collect();
+        let json = serde_json::json!({
+            "sheet": sheet,
+            "dropdowns": items,
+        });
+        outln!("{}", serde_json::to_string_pretty(&json)?);
+    } else if dropdowns.is_empty() {
+        outln!("No dropdowns in sheet '{}'", sheet);
+    } else {
+        for dropdown in dropdowns {
+            outln!("{}: {}", dropdown.range.to_a1().cyan(), dropdown.source);
+        }
     }
+
     Ok(())
 }
 
-fn link_remove(
+fn dropdown_remove(
     file: &std::path::Path,
     sheet: &str,
-    cell: &str,
+    range: &str,
     global: &GlobalOptions,
 ) -> Result<()> {
+    let target = xlex_core::Range::parse(range)?;
+
     if global.dry_run {
-        println!("Would remove hyperlink from {}", cell);
+        outln!(
+            "Would remove dropdowns at {}!{} in {}",
+            sheet,
+            range,
+            file.display()
+        );
         return Ok(());
     }
 
     let mut workbook = Workbook::open(file)?;
-    let cell_ref = CellRef::parse(cell)?;
-
-    {
-        let sheet_obj =
-            workbook
-                .get_sheet_mut(sheet)
-                .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
-                    name: sheet.to_string(),
-                })?;
-        sheet_obj.set_cell_hyperlink(&cell_ref, None);
+    let sheet_obj =
+        workbook
+            .get_sheet_mut(sheet)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: sheet.to_string(),
+            })?;
+    let removed = sheet_obj.remove_dropdowns(&target);
+    if removed > 0 {
+        workbook.save()?;
     }
 
-    workbook.save()?;
-
     if !global.quiet {
-        println!("{} Removed hyperlink from {}", "✓".green(), cell.cyan());
+        if global.format == OutputFormat::Json {
+            let json = serde_json::json!({
+                "sheet": sheet,
+                "range": range,
+                "removed": removed,
+            });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else {
+            outln!("Removed {} dropdown(s) at {}", removed, range.green());
+        }
     }
+
     Ok(())
 }
 
@@ -791,6 +1562,11 @@ mod tests {
             json_errors: false,
             dry_run: false,
             output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
         }
     }
 
@@ -1454,6 +2230,8 @@ mod tests {
                 sheet: "Sheet1".to_string(),
                 cell: "A1".to_string(),
                 formula: "SUM(B1:B10)".to_string(),
+                array: None,
+                dynamic: false,
             },
         };
 
@@ -1461,6 +2239,53 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_set_array_formula() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "array_formula.xlsx");
+
+        set_array_formula(
+            &file_path,
+            "Sheet1",
+            "B1",
+            "=A1:A3*2",
+            Some("B1:B3"),
+            false,
+            &default_global(),
+        )
+        .unwrap();
+        set_array_formula(
+            &file_path,
+            "Sheet1",
+            "D1",
+            "SORT(A1:A3)",
+            None,
+            true,
+            &default_global(),
+        )
+        .unwrap();
+        assert!(set_array_formula(
+            &file_path,
+            "Sheet1",
+            "C1",
+            "A1",
+            Some("B1:B3"),
+            false,
+            &default_global(),
+        )
+        .is_err());
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let sheet = wb.get_sheet("Sheet1").unwrap();
+        let (anchor, array) = sheet.array_formula_at(&CellRef::new(2, 3)).unwrap();
+        assert_eq!(anchor, CellRef::new(2, 1));
+        assert!(!array.dynamic);
+        assert_eq!(sheet.get_value(&anchor), CellValue::formula("A1:A3*2"));
+        let (anchor, array) = sheet.array_formula_at(&CellRef::new(4, 1)).unwrap();
+        assert_eq!(array.range(&anchor).to_a1(), "D1");
+        assert!(array.dynamic);
+    }
+
     #[test]
     fn test_run_clear_command() {
         let temp_dir = TempDir::new().unwrap();
@@ -1650,6 +2475,80 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_style_get_default() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "style_get_default.xlsx");
+
+        let result = style_get(&file_path, "Sheet1", "B2", &default_global());
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_style_get_json() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "style_get_json.xlsx");
+
+        let mut wb = Workbook::open(&file_path).unwrap();
+        let style_id = wb.style_registry_mut().add(xlex_core::Style {
+            font: xlex_core::style::Font {
+                bold: true,
+                color: xlex_core::style::Color::from_hex("FF0000"),
+                ..Default::default()
+            },
+            number_format: xlex_core::style::NumberFormat::percentage(1),
+            ..Default::default()
+        });
+        wb.set_cell("Sheet1", CellRef::new(2, 2), CellValue::number(0.5))
+            .unwrap();
+        wb.get_sheet_mut("Sheet1")
+            .unwrap()
+            .set_cell_style(&CellRef::new(2, 2), Some(style_id));
+        wb.save().unwrap();
+
+        let mut global = default_global();
+        global.format = OutputFormat::Json;
+        assert!(style_get(&file_path, "Sheet1", "B2", &global).is_ok());
+        assert!(style_get(&file_path, "Sheet1", "B2", &default_global()).is_ok());
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let style = wb.get_cell_style("Sheet1", &CellRef::new(2, 2)).unwrap();
+        let json = crate::commands::style::style_json(&style);
+        assert_eq!(json["font"]["bold"], true);
+        assert_eq!(json["font"]["color"], "FF0000");
+        assert_eq!(json["numberFormat"]["code"], "0.0%");
+        assert_eq!(json["protection"]["locked"], true);
+        assert_eq!(json["protection"]["hidden"], false);
+    }
+
+    #[test]
+    fn test_style_get_sheet_not_found() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "style_get_missing.xlsx");
+
+        let result = style_get(&file_path, "Missing", "A1", &default_global());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_run_style_get_command() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "run_style_get.xlsx");
+
+        let args = CellArgs {
+            command: CellCommand::Style(CellStyleArgs {
+                command: CellStyleCommand::Get {
+                    file: file_path,
+                    sheet: "Sheet1".to_string(),
+                    cell: "A1".to_string(),
+                },
+            }),
+        };
+
+        let result = run(&args, &default_global());
+        assert!(result.is_ok());
+    }
+
     #[test]
     fn test_run_link_get_command() {
         let temp_dir = TempDir::new().unwrap();
@@ -2005,10 +2904,139 @@ mod tests {
         let mut global = default_global();
         global.dry_run = true;
 
-        let result = batch(&file_path, &global);
+        let args = BatchArgs {
+            file: file_path,
+            input: None,
+            json: false,
+            sheet: None,
+            continue_on_error: false,
+        };
+        let result = batch(&args, &global);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_parse_batch_line() {
+        let update = |sheet: Option<&str>, cell: &str, op| BatchUpdate {
+            sheet: sheet.map(str::to_string),
+            cell: CellRef::parse(cell).unwrap(),
+            op,
+        };
+        assert!(parse_batch_line("  # comment").unwrap().is_empty());
+        assert_eq!(
+            parse_batch_line("Sheet1 A1 Hello world").unwrap(),
+            [update(
+                Some("Sheet1"),
+                "A1",
+                BatchOp::Set(CellValue::String("Hello world".to_string()))
+            )]
+        );
+        assert_eq!(
+            parse_batch_line("Sheet1 A1 =SUM(B1:B2)").unwrap(),
+            [update(
+                Some("Sheet1"),
+                "A1",
+                BatchOp::Set(CellValue::formula("SUM(B1:B2)"))
+            )]
+        );
+        assert_eq!(
+            parse_batch_line("Sheet2!B3:number=42").unwrap(),
+            [update(
+                Some("Sheet2"),
+                "B3",
+                BatchOp::Set(CellValue::Number(42.0))
+            )]
+        );
+        assert_eq!(
+            parse_batch_line("B4==SUM(B1:B3)").unwrap(),
+            [update(
+                None,
+                "B4",
+                BatchOp::Set(CellValue::formula("SUM(B1:B3)"))
+            )]
+        );
+        assert_eq!(
+            parse_batch_line("'My Sheet'!C1:comment=a = b").unwrap(),
+            [update(
+                Some("My Sheet"),
+                "C1",
+                BatchOp::Comment("a = b".to_string())
+            )]
+        );
+        assert_eq!(
+            parse_batch_line("C2=").unwrap(),
+            [update(None, "C2", BatchOp::Clear)]
+        );
+        assert!(parse_batch_line("B1:number=abc").is_err());
+        assert!(parse_batch_line("B1:color=red").is_err());
+        assert!(parse_batch_line("Sheet1").is_err());
+    }
+
+    #[test]
+    fn test_batch_from_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "batch.xlsx");
+        let input = temp_dir.path().join("updates.txt");
+        std::fs::write(
+            &input,
+            "A1=Total\nSheet1!B1:number=42\nC1==B1*2\nC1:comment=Doubled\nSheet1 D1 yes\n",
+        )
+        .unwrap();
+
+        let mut args = BatchArgs {
+            file: file_path.clone(),
+            input: Some(input.clone()),
+            json: false,
+            sheet: None,
+            continue_on_error: false,
+        };
+        batch(&args, &default_global()).unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        let get = |cell: &str| {
+            wb.get_cell("Sheet1", &CellRef::parse(cell).unwrap())
+                .unwrap()
+        };
+        assert_eq!(get("A1"), CellValue::String("Total".to_string()));
+        assert_eq!(get("B1"), CellValue::Number(42.0));
+        assert!(matches!(get("C1"), CellValue::Formula { .. }));
+
+        // A bad line stops the batch before anything is saved
+        std::fs::write(&input, "A2=kept\nNope!A3=x\n").unwrap();
+        assert!(batch(&args, &default_global()).is_err());
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(
+            wb.get_cell("Sheet1", &CellRef::parse("A2").unwrap())
+                .unwrap(),
+            CellValue::Empty
+        );
+        args.continue_on_error = true;
+        batch(&args, &default_global()).unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(
+            wb.get_cell("Sheet1", &CellRef::parse("A2").unwrap())
+                .unwrap(),
+            CellValue::String("kept".to_string())
+        );
+
+        // JSON input
+        std::fs::write(
+            &input,
+            r#"[{"ref": "Sheet1!E1", "value": 7}, {"cell": "E2", "formula": "E1+1", "comment": "next"}, {"ref": "A1", "value": null}]"#,
+        )
+        .unwrap();
+        args.json = true;
+        args.continue_on_error = false;
+        batch(&args, &default_global()).unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        let get = |cell: &str| {
+            wb.get_cell("Sheet1", &CellRef::parse(cell).unwrap())
+                .unwrap()
+        };
+        assert_eq!(get("E1"), CellValue::Number(7.0));
+        assert!(matches!(get("E2"), CellValue::Formula { .. }));
+        assert_eq!(get("A1"), CellValue::Empty);
+    }
+
     #[test]
     fn test_get_display_text_string() {
         let temp_dir = TempDir::new().unwrap();
@@ -2124,4 +3152,70 @@ mod tests {
         let result = get_type(&file_path, "Sheet1", "A1", &global);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_run_checkbox_set_and_remove() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "checkbox.xlsx");
+
+        let args = CellArgs {
+            command: CellCommand::Checkbox(CheckboxArgs {
+                command: CheckboxCommand::Set {
+                    file: file_path.clone(),
+                    sheet: "Sheet1".to_string(),
+                    cell: "B2:B4".to_string(),
+                    checked: true,
+                },
+            }),
+        };
+        run(&args, &default_global()).unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let b3 = CellRef::parse("B3").unwrap();
+        assert_eq!(
+            wb.get_cell("Sheet1", &b3).unwrap(),
+            CellValue::Boolean(true)
+        );
+        assert!(wb.get_cell_style("Sheet1", &b3).unwrap().checkbox);
+
+        checkbox_update(&file_path, "Sheet1", "B3", None, &default_global()).unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        assert!(!wb.get_cell_style("Sheet1", &b3).unwrap().checkbox);
+        assert_eq!(
+            wb.get_cell("Sheet1", &b3).unwrap(),
+            CellValue::Boolean(true)
+        );
+    }
+
+    #[test]
+    fn test_run_dropdown_set_list_remove() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "dropdown.xlsx");
+
+        let args = CellArgs {
+            command: CellCommand::Dropdown(DropdownArgs {
+                command: DropdownCommand::Set {
+                    file: file_path.clone(),
+                    sheet: "Sheet1".to_string(),
+                    range: "C2:C20".to_string(),
+                    items: vec!["Open".to_string(), "Done".to_string()],
+                    source: None,
+                },
+            }),
+        };
+        run(&args, &default_global()).unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let dropdowns = wb.get_sheet("Sheet1").unwrap().dropdowns();
+        assert_eq!(dropdowns.len(), 1);
+        assert_eq!(dropdowns[0].items(), Some(vec!["Open", "Done"]));
+
+        let mut json = default_global();
+        json.format = OutputFormat::Json;
+        dropdown_list(&file_path, "Sheet1", &json).unwrap();
+
+        dropdown_remove(&file_path, "Sheet1", "C5", &default_global()).unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        assert!(wb.get_sheet("Sheet1").unwrap().dropdowns().is_empty());
+    }
 }
@@ -4,8 +4,10 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use colored::Colorize;
 
+use xlex_core::units::Length;
 use xlex_core::{CellRef, Workbook};
 
+use super::output::CommandOutput;
 use super::{GlobalOptions, OutputFormat};
 
 /// Arguments for column operations.
@@ -74,8 +76,9 @@ pub enum ColumnCommand {
         sheet: String,
         /// Column
         column: String,
-        /// Width in characters (omit to show current)
-        width: Option<f64>,
+        /// Width in column width units, or with a unit: 120px, 90pt, 12ch
+        /// (omit to show current)
+        width: Option<Length>,
     },
     /// Hide a column
     Hide {
@@ -95,6 +98,20 @@ pub enum ColumnCommand {
         /// Column
         column: String,
     },
+    /// Set, clear or show the default style of a whole column
+    Style {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name
+        sheet: String,
+        /// Column
+        column: String,
+        /// Style ID (omit to show current)
+        style_id: Option<u32>,
+        /// Remove the column's default style
+        #[arg(long, conflicts_with = "style_id")]
+        clear: bool,
+    },
     /// Get column header (first row value)
     Header {
         /// Path to the xlsx file
@@ -121,6 +138,12 @@ pub enum ColumnCommand {
         sheet: String,
         /// Column
         column: String,
+        /// Count distinct values
+        #[arg(long)]
+        distinct: bool,
+        /// List the N most frequent values
+        #[arg(long, value_name = "N")]
+        top: Option<usize>,
     },
 }
 
@@ -159,7 +182,13 @@ pub fn run(args: &ColumnArgs, global: &GlobalOptions) -> Result<()> {
             sheet,
             column,
             width: col_width,
-        } => width(file, sheet, column, *col_width, global),
+        } => width(
+            file,
+            sheet,
+            column,
+            col_width.map(Length::to_column_width),
+            global,
+        ),
         ColumnCommand::Hide {
             file,
             sheet,
@@ -170,6 +199,13 @@ pub fn run(args: &ColumnArgs, global: &GlobalOptions) -> Result<()> {
             sheet,
             column,
         } => unhide(file, sheet, column, global),
+        ColumnCommand::Style {
+            file,
+            sheet,
+            column,
+            style_id,
+            clear,
+        } => style(file, sheet, column, *style_id, *clear, global),
         ColumnCommand::Header {
             file,
             sheet,
@@ -184,7 +220,9 @@ pub fn run(args: &ColumnArgs, global: &GlobalOptions) -> Result<()> {
             file,
             sheet,
             column,
-        } => stats(file, sheet, column, global),
+            distinct,
+            top,
+        } => stats(file, sheet, column, *distinct, *top, global),
     }
 }
 
@@ -203,29 +241,23 @@ fn get(file: &std::path::Path, sheet: &str, column: &str, global: &GlobalOptions
                 name: sheet.to_string(),
             })?;
 
-    // Collect cells in this column
-    let mut col_cells: Vec<_> = sheet_obj
-        .cells()
-        .filter(|c| c.reference.col == col)
-        .collect();
-    col_cells.sort_by_key(|c| c.reference.row);
+    let col_values = sheet_obj.column_values(col, xlex_core::ColumnOptions::default());
 
     if global.format == OutputFormat::Json {
-        let values: Vec<_> = col_cells
-            .iter()
-            .map(|c| {
+        let values: Vec<_> = col_values
+            .map(|(row, value)| {
                 serde_json::json!({
-                    "row": c.reference.row,
-                    "ref": c.reference.to_a1(),
-                    "value": c.value.to_display_string(),
-                    "type": c.value.type_name(),
+                    "row": row,
+                    "ref": CellRef::new(col, row).to_a1(),
+                    "value": value.to_display_string(),
+                    "type": value.type_name(),
                 })
             })
             .collect();
-        println!("{}", serde_json::to_string_pretty(&values)?);
+        outln!("{}", serde_json::to_string_pretty(&values)?);
     } else {
-        for cell in col_cells {
-            println!("{}: {}", cell.reference.to_a1().cyan(), cell.value);
+        for (row, value) in col_values {
+            outln!("{}: {}", CellRef::new(col, row).to_a1().cyan(), value);
         }
     }
 
@@ -236,10 +268,12 @@ fn insert(file: &std::path::Path, sheet: &str, column: &str, global: &GlobalOpti
     let col = parse_column(column)?;
 
     if global.dry_run {
-        println!("Would insert column at {}", column);
+        outln!("Would insert column at {}", column);
         return Ok(());
     }
 
+    let output = CommandOutput::new("insert");
+
     let mut workbook = Workbook::open(file)?;
     let sheet_obj =
         workbook
@@ -252,9 +286,14 @@ fn insert(file: &std::path::Path, sheet: &str, column: &str, global: &GlobalOpti
     let _ = sheet_obj;
     workbook.save()?;
 
-    if !global.quiet {
-        println!("Inserted column at {}", column.to_uppercase().green());
-    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("column", column.to_uppercase())
+        .print(
+            global,
+            format!("Inserted column at {}", column.to_uppercase().green()),
+        )?;
 
     Ok(())
 }
@@ -263,10 +302,12 @@ fn delete(file: &std::path::Path, sheet: &str, column: &str, global: &GlobalOpti
     let col = parse_column(column)?;
 
     if global.dry_run {
-        println!("Would delete column {}", column);
+        outln!("Would delete column {}", column);
         return Ok(());
     }
 
+    let output = CommandOutput::new("delete");
+
     let mut workbook = Workbook::open(file)?;
     let sheet_obj =
         workbook
@@ -279,9 +320,14 @@ fn delete(file: &std::path::Path, sheet: &str, column: &str, global: &GlobalOpti
     let _ = sheet_obj;
     workbook.save()?;
 
-    if !global.quiet {
-        println!("Deleted column {}", column.to_uppercase().green());
-    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("column", column.to_uppercase())
+        .print(
+            global,
+            format!("Deleted column {}", column.to_uppercase().green()),
+        )?;
 
     Ok(())
 }
@@ -297,10 +343,12 @@ fn copy(
     let dest_col = parse_column(dest)?;
 
     if global.dry_run {
-        println!("Would copy column {} to column {}", source, dest);
+        outln!("Would copy column {} to column {}", source, dest);
         return Ok(());
     }
 
+    let output = CommandOutput::new("copy");
+
     let mut workbook = Workbook::open(file)?;
 
     // Get source column data
@@ -313,12 +361,12 @@ fn copy(
 
     // Collect all cells in the source column
     let source_cells: Vec<_> = sheet_obj
-        .cells()
-        .filter(|c| c.reference.col == source_col)
-        .map(|c| (c.reference.row, c.value.clone()))
+        .column_values(source_col, xlex_core::ColumnOptions::default())
+        .map(|(row, value)| (row, value.clone()))
         .collect();
 
     let source_width = sheet_obj.get_column_width(source_col);
+    let cells = source_cells.len();
     let _ = sheet_obj;
 
     // Insert a new column at destination
@@ -345,13 +393,20 @@ fn copy(
     let _ = sheet_obj;
     workbook.save()?;
 
-    if !global.quiet {
-        println!(
-            "Copied column {} to column {}",
-            source.to_uppercase().cyan(),
-            dest.to_uppercase().green()
-        );
-    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("source", source.to_uppercase())
+        .target("destination", dest.to_uppercase())
+        .count("cells", cells)
+        .print(
+            global,
+            format!(
+                "Copied column {} to column {}",
+                source.to_uppercase().cyan(),
+                dest.to_uppercase().green()
+            ),
+        )?;
 
     Ok(())
 }
@@ -367,10 +422,12 @@ fn move_column(
     let dest_col = parse_column(dest)?;
 
     if global.dry_run {
-        println!("Would move column {} to column {}", source, dest);
+        outln!("Would move column {} to column {}", source, dest);
         return Ok(());
     }
 
+    let output = CommandOutput::new("move");
+
     let mut workbook = Workbook::open(file)?;
 
     // Get source column data
@@ -383,12 +440,12 @@ fn move_column(
 
     // Collect all cells in the source column
     let source_cells: Vec<_> = sheet_obj
-        .cells()
-        .filter(|c| c.reference.col == source_col)
-        .map(|c| (c.reference.row, c.value.clone()))
+        .column_values(source_col, xlex_core::ColumnOptions::default())
+        .map(|(row, value)| (row, value.clone()))
         .collect();
 
     let source_width = sheet_obj.get_column_width(source_col);
+    let cells = source_cells.len();
     let _ = sheet_obj;
 
     let sheet_obj =
@@ -435,13 +492,20 @@ fn move_column(
     let _ = sheet_obj;
     workbook.save()?;
 
-    if !global.quiet {
-        println!(
-            "Moved column {} to column {}",
-            source.to_uppercase().cyan(),
-            dest.to_uppercase().green()
-        );
-    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("source", source.to_uppercase())
+        .target("destination", dest.to_uppercase())
+        .count("cells", cells)
+        .print(
+            global,
+            format!(
+                "Moved column {} to column {}",
+                source.to_uppercase().cyan(),
+                dest.to_uppercase().green()
+            ),
+        )?;
 
     Ok(())
 }
@@ -457,10 +521,11 @@ fn width(
 
     if let Some(w) = width {
         if global.dry_run {
-            println!("Would set column {} width to {}", column, w);
+            outln!("Would set column {} width to {}", column, w);
             return Ok(());
         }
 
+        let output = CommandOutput::new("width");
         let mut workbook = Workbook::open(file)?;
         let sheet_obj =
             workbook
@@ -473,9 +538,15 @@ fn width(
         let _ = sheet_obj;
         workbook.save()?;
 
-        if !global.quiet {
-            println!("Set column {} width to {}", column.to_uppercase().cyan(), w);
-        }
+        output
+            .file(file)
+            .target("sheet", sheet)
+            .target("column", column.to_uppercase())
+            .field("width", w)
+            .print(
+                global,
+                format!("Set column {} width to {}", column.to_uppercase().cyan(), w),
+            )?;
     } else {
         let workbook = Workbook::open(file)?;
         let sheet_obj =
@@ -485,10 +556,17 @@ fn width(
                     name: sheet.to_string(),
                 })?;
 
-        if let Some(w) = sheet_obj.get_column_width(col) {
-            println!("{}", w);
+        let width = sheet_obj.get_column_width(col);
+        if global.format == OutputFormat::Json {
+            let json = serde_json::json!({
+                "column": column.to_uppercase(),
+                "width": width,
+            });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else if let Some(w) = width {
+            outln!("{}", w);
         } else {
-            println!("default");
+            outln!("default");
         }
     }
 
@@ -497,10 +575,12 @@ fn width(
 
 fn hide(file: &std::path::Path, sheet: &str, column: &str, global: &GlobalOptions) -> Result<()> {
     if global.dry_run {
-        println!("Would hide column {}", column);
+        outln!("Would hide column {}", column);
         return Ok(());
     }
 
+    let output = CommandOutput::new("hide");
+
     let col = parse_column(column)?;
     let mut workbook = Workbook::open(file)?;
     let sheet_obj =
@@ -514,19 +594,95 @@ fn hide(file: &std::path::Path, sheet: &str, column: &str, global: &GlobalOption
     let _ = sheet_obj;
     workbook.save()?;
 
-    if !global.quiet {
-        println!("Hid column {}", column.to_uppercase().dimmed());
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("column", column.to_uppercase())
+        .print(
+            global,
+            format!("Hid column {}", column.to_uppercase().dimmed()),
+        )?;
+
+    Ok(())
+}
+
+fn style(
+    file: &std::path::Path,
+    sheet: &str,
+    column: &str,
+    style_id: Option<u32>,
+    clear: bool,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let col = parse_column(column)?;
+    let column = column.to_uppercase();
+
+    if style_id.is_none() && !clear {
+        let workbook = Workbook::open(file)?;
+        let sheet_obj =
+            workbook
+                .get_sheet(sheet)
+                .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                    name: sheet.to_string(),
+                })?;
+
+        let style_id = sheet_obj.get_column_style(col);
+        if global.format == OutputFormat::Json {
+            let json = serde_json::json!({
+                "column": column,
+                "styleId": style_id,
+            });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else if let Some(id) = style_id {
+            outln!("{}", id);
+        } else {
+            outln!("none");
+        }
+        return Ok(());
+    }
+
+    if global.dry_run {
+        match style_id {
+            Some(id) => outln!("Would set column {} style to {}", column, id),
+            None => outln!("Would clear column {} style", column),
+        }
+        return Ok(());
+    }
+
+    let output = CommandOutput::new("style");
+    let mut workbook = Workbook::open(file)?;
+    if let Some(id) = style_id {
+        super::style::ensure_style_exists(&workbook, id)?;
     }
+    workbook.with_sheet_mut(sheet, |sheet_obj| sheet_obj.set_column_style(col, style_id))?;
+    workbook.save()?;
+
+    let message = match style_id {
+        Some(id) => format!(
+            "Set column {} style to {}",
+            column.cyan(),
+            id.to_string().cyan()
+        ),
+        None => format!("Cleared column {} style", column.cyan()),
+    };
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("column", column.as_str())
+        .field("styleId", style_id)
+        .print(global, message)?;
 
     Ok(())
 }
 
 fn unhide(file: &std::path::Path, sheet: &str, column: &str, global: &GlobalOptions) -> Result<()> {
     if global.dry_run {
-        println!("Would unhide column {}", column);
+        outln!("Would unhide column {}", column);
         return Ok(());
     }
 
+    let output = CommandOutput::new("unhide");
+
     let col = parse_column(column)?;
     let mut workbook = Workbook::open(file)?;
     let sheet_obj =
@@ -540,9 +696,14 @@ fn unhide(file: &std::path::Path, sheet: &str, column: &str, global: &GlobalOpti
     let _ = sheet_obj;
     workbook.save()?;
 
-    if !global.quiet {
-        println!("Unhid column {}", column.to_uppercase().green());
-    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("column", column.to_uppercase())
+        .print(
+            global,
+            format!("Unhid column {}", column.to_uppercase().green()),
+        )?;
 
     Ok(())
 }
@@ -558,9 +719,9 @@ fn header(file: &std::path::Path, sheet: &str, column: &str, global: &GlobalOpti
             "column": column.to_uppercase(),
             "header": value.to_display_string(),
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else {
-        println!("{}", value.to_display_string());
+        outln!("{}", value.to_display_string());
     }
 
     Ok(())
@@ -595,78 +756,133 @@ fn find(file: &std::path::Path, sheet: &str, pattern: &str, global: &GlobalOptio
             "matches": col_letters,
             "count": matches.len(),
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else {
         for col in col_letters {
-            println!("{}", col);
+            outln!("{}", col);
         }
     }
 
     Ok(())
 }
 
-fn stats(file: &std::path::Path, sheet: &str, column: &str, global: &GlobalOptions) -> Result<()> {
-    let workbook = Workbook::open(file)?;
-    let col = parse_column(column)?;
-    let sheet_obj =
-        workbook
-            .get_sheet(sheet)
-            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
-                name: sheet.to_string(),
-            })?;
-
-    // Collect statistics
-    let mut count = 0;
-    let mut numeric_count = 0;
-    let mut sum = 0.0;
-    let mut min: Option<f64> = None;
-    let mut max: Option<f64> = None;
+/// Statistics gathered by `column stats`.
+#[derive(Debug, Default)]
+struct ColumnStats {
+    /// Non-empty cells
+    count: usize,
+    /// Rows of the used range with no value in the column
+    nulls: usize,
+    numeric_count: usize,
+    sum: f64,
+    min: Option<f64>,
+    max: Option<f64>,
+    /// Occurrences of each value, when distinct values are tracked
+    frequencies: Option<std::collections::HashMap<String, usize>>,
+}
 
-    for cell in sheet_obj.cells() {
-        if cell.reference.col != col {
-            continue;
+impl ColumnStats {
+    /// Gathers statistics from a streamed column.
+    fn collect(column: xlex_core::StreamColumn, track_values: bool) -> Self {
+        let mut stats = ColumnStats {
+            frequencies: track_values.then(std::collections::HashMap::new),
+            ..Default::default()
+        };
+        for (_, value) in column.values {
+            stats.count += 1;
+            if let xlex_core::CellValue::Number(n) = value {
+                stats.numeric_count += 1;
+                stats.sum += n;
+                stats.min = Some(stats.min.map_or(n, |m| m.min(n)));
+                stats.max = Some(stats.max.map_or(n, |m| m.max(n)));
+            }
+            if let Some(frequencies) = &mut stats.frequencies {
+                *frequencies.entry(value.to_display_string()).or_insert(0) += 1;
+            }
         }
-        count += 1;
+        stats.nulls = column.last_row as usize - stats.count;
+        stats
+    }
 
-        if let xlex_core::CellValue::Number(n) = cell.value {
-            numeric_count += 1;
-            sum += n;
-            min = Some(min.map_or(n, |m| m.min(n)));
-            max = Some(max.map_or(n, |m| m.max(n)));
-        }
+    /// The `n` most frequent values, ties broken by value.
+    fn top(&self, n: usize) -> Vec<(&str, usize)> {
+        let mut values: Vec<(&str, usize)> = self
+            .frequencies
+            .iter()
+            .flatten()
+            .map(|(value, count)| (value.as_str(), *count))
+            .collect();
+        values.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        values.truncate(n);
+        values
     }
+}
 
-    let avg = if numeric_count > 0 {
-        Some(sum / numeric_count as f64)
-    } else {
-        None
-    };
+fn stats(
+    file: &std::path::Path,
+    sheet: &str,
+    column: &str,
+    distinct: bool,
+    top: Option<usize>,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let col = parse_column(column)?;
+    let workbook = xlex_core::LazyWorkbook::open(file)?;
+    let stats = ColumnStats::collect(
+        workbook.stream_column(sheet, col)?,
+        distinct || top.is_some(),
+    );
+
+    let sum = (stats.numeric_count > 0).then_some(stats.sum);
+    let avg = sum.map(|sum| sum / stats.numeric_count as f64);
+    let distinct_count = stats.frequencies.as_ref().map(|f| f.len());
+    let top_values = top.map(|n| stats.top(n));
 
     if global.format == OutputFormat::Json {
-        let json = serde_json::json!({
+        let mut json = serde_json::json!({
             "column": column.to_uppercase(),
-            "count": count,
-            "numericCount": numeric_count,
-            "sum": if numeric_count > 0 { Some(sum) } else { None },
+            "count": stats.count,
+            "nulls": stats.nulls,
+            "numericCount": stats.numeric_count,
+            "sum": sum,
             "average": avg,
-            "min": min,
-            "max": max,
+            "min": stats.min,
+            "max": stats.max,
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        if let Some(distinct) = distinct_count {
+            json["distinct"] = distinct.into();
+        }
+        if let Some(values) = &top_values {
+            json["top"] = values
+                .iter()
+                .map(|(value, count)| serde_json::json!({"value": value, "count": count}))
+                .collect();
+        }
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else {
-        println!("{}: {}", "Column".bold(), column.to_uppercase());
-        println!("  {}: {}", "Count".cyan(), count);
-        println!("  {}: {}", "Numeric".cyan(), numeric_count);
-        if numeric_count > 0 {
-            println!("  {}: {}", "Sum".cyan(), sum);
+        outln!("{}: {}", "Column".bold(), column.to_uppercase());
+        outln!("  {}: {}", "Count".cyan(), stats.count);
+        outln!("  {}: {}", "Nulls".cyan(), stats.nulls);
+        outln!("  {}: {}", "Numeric".cyan(), stats.numeric_count);
+        if let Some(sum) = sum {
+            outln!("  {}: {}", "Sum".cyan(), sum);
             if let Some(a) = avg {
-                println!("  {}: {:.2}", "Average".cyan(), a);
+                outln!("  {}: {:.2}", "Average".cyan(), a);
+            }
+            if let Some(m) = stats.min {
+                outln!("  {}: {}", "Min".cyan(), m);
             }
-            if let Some(m) = min {
-                println!("  {}: {}", "Min".cyan(), m);
+            if let Some(m) = stats.max {
+                outln!("  {}: {}", "Max".cyan(), m);
             }
-            if let Some(m) = max {
-                println!("  {}: {}", "Max".cyan(), m);
+        }
+        if let Some(distinct) = distinct_count {
+            outln!("  {}: {}", "Distinct".cyan(), distinct);
+        }
+        if let Some(values) = &top_values {
+            outln!("  {}:", "Top values".cyan());
+            for (value, count) in values {
+                outln!("    {:>6}  {}", count, value);
             }
         }
     }
@@ -690,6 +906,11 @@ mod tests {
             json_errors: false,
             dry_run: false,
             output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
         }
     }
 
@@ -863,10 +1084,38 @@ mod tests {
         let file_path = create_test_workbook(&temp_dir, "stats.xlsx");
         setup_test_data(&file_path);
 
-        let result = stats(&file_path, "Sheet1", "A", &default_global());
+        let result = stats(&file_path, "Sheet1", "A", false, None, &default_global());
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_column_stats_distinct_and_top() {
+        use xlex_core::{CellValue, StreamColumn};
+
+        let column = StreamColumn {
+            values: vec![
+                (1, CellValue::String("red".to_string())),
+                (2, CellValue::Number(3.0)),
+                (3, CellValue::String("blue".to_string())),
+                (5, CellValue::String("red".to_string())),
+                (7, CellValue::Number(3.0)),
+                (8, CellValue::String("red".to_string())),
+            ],
+            last_row: 8,
+        };
+        let stats = ColumnStats::collect(column, true);
+        assert_eq!(stats.count, 6);
+        assert_eq!(stats.nulls, 2);
+        assert_eq!(stats.numeric_count, 2);
+        assert_eq!(stats.sum, 6.0);
+        assert_eq!(stats.frequencies.as_ref().map(|f| f.len()), Some(3));
+        assert_eq!(stats.top(2), vec![("red", 3), ("3", 2)]);
+
+        let untracked = ColumnStats::collect(StreamColumn::default(), false);
+        assert!(untracked.frequencies.is_none());
+        assert!(untracked.top(5).is_empty());
+    }
+
     #[test]
     fn test_stats_json() {
         let temp_dir = TempDir::new().unwrap();
@@ -876,7 +1125,7 @@ mod tests {
         let mut global = default_global();
         global.format = OutputFormat::Json;
 
-        let result = stats(&file_path, "Sheet1", "A", &global);
+        let result = stats(&file_path, "Sheet1", "A", false, None, &global);
         assert!(result.is_ok());
     }
 
@@ -1000,7 +1249,7 @@ mod tests {
                 file: file_path,
                 sheet: "Sheet1".to_string(),
                 column: "A".to_string(),
-                width: Some(20.0),
+                width: Some(Length::Native(20.0)),
             },
         };
 
@@ -1008,6 +1257,26 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_run_width_with_unit() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "run_width_unit.xlsx");
+
+        let args = ColumnArgs {
+            command: ColumnCommand::Width {
+                file: file_path.clone(),
+                sheet: "Sheet1".to_string(),
+                column: "B".to_string(),
+                width: Some("120px".parse().unwrap()),
+            },
+        };
+        run(&args, &default_global()).unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let width = wb.get_sheet("Sheet1").unwrap().get_column_width(2).unwrap();
+        assert_eq!(xlex_core::units::column_width_to_pixels(width), 120.0);
+    }
+
     #[test]
     fn test_run_hide_command() {
         let temp_dir = TempDir::new().unwrap();
@@ -1025,6 +1294,47 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_column_style() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "column_style.xlsx");
+        let mut wb = Workbook::open(&file_path).unwrap();
+        let mut bold = xlex_core::Style::default();
+        bold.font.bold = true;
+        let bold = wb.style_registry_mut().add(bold);
+        wb.save().unwrap();
+
+        let args = |style_id, clear| ColumnArgs {
+            command: ColumnCommand::Style {
+                file: file_path.clone(),
+                sheet: "Sheet1".to_string(),
+                column: "B".to_string(),
+                style_id,
+                clear,
+            },
+        };
+        run(&args(Some(bold), false), &default_global()).unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(
+            wb.get_sheet("Sheet1").unwrap().get_column_style(2),
+            Some(bold)
+        );
+        let style = |col, row| {
+            wb.get_cell_style("Sheet1", &xlex_core::CellRef::new(col, row))
+                .unwrap()
+        };
+        assert!(style(2, 40).font.bold);
+        assert!(!style(1, 40).font.bold);
+
+        // Unknown style IDs are rejected
+        assert!(run(&args(Some(99), false), &default_global()).is_err());
+
+        run(&args(None, true), &default_global()).unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(wb.get_sheet("Sheet1").unwrap().get_column_style(2), None);
+    }
+
     #[test]
     fn test_run_unhide_command() {
         let temp_dir = TempDir::new().unwrap();
@@ -1091,6 +1401,8 @@ mod tests {
                 file: file_path,
                 sheet: "Sheet1".to_string(),
                 column: "A".to_string(),
+                distinct: true,
+                top: Some(3),
             },
         };
 
@@ -1216,7 +1528,7 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let file_path = create_test_workbook(&temp_dir, "stats_empty.xlsx");
 
-        let result = stats(&file_path, "Sheet1", "A", &default_global());
+        let result = stats(&file_path, "Sheet1", "A", false, None, &default_global());
         assert!(result.is_ok());
     }
 
@@ -1332,7 +1644,7 @@ mod tests {
         let mut global = default_global();
         global.quiet = false;
 
-        let result = stats(&file_path, "Sheet1", "A", &global);
+        let result = stats(&file_path, "Sheet1", "A", false, None, &global);
         assert!(result.is_ok());
     }
 }
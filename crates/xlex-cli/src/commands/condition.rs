@@ -0,0 +1,129 @@
+//! Cell value conditions shared by commands that test cells.
+//!
+//! Accepted forms: `always`, `blank`, `nonblank`, `negative`, `positive`,
+//! `zero`, `contains <text>`, and a comparison (`<`, `<=`, `>`, `>=`, `=`,
+//! `==`, `!=`, `<>`) against a number or quoted text.
+
+use anyhow::Result;
+
+/// A test on a single cell value, as written after `when:` in style
+/// rules and transform filters.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Condition {
+    Always,
+    Blank,
+    NonBlank,
+    Compare(std::cmp::Ordering, bool, String),
+    Contains(String),
+}
+
+impl Condition {
+    pub(super) fn parse(when: Option<&str>) -> Result<Self> {
+        use std::cmp::Ordering;
+
+        let Some(when) = when.map(str::trim) else {
+            return Ok(Condition::Always);
+        };
+        let zero = || "0".to_string();
+        let condition = match when.to_lowercase().as_str() {
+            "" | "always" => Condition::Always,
+            "blank" => Condition::Blank,
+            "nonblank" | "not blank" => Condition::NonBlank,
+            "negative" => Condition::Compare(Ordering::Less, false, zero()),
+            "positive" => Condition::Compare(Ordering::Greater, false, zero()),
+            "zero" => Condition::Compare(Ordering::Equal, false, zero()),
+            lower => {
+                if lower.starts_with("contains ") {
+                    return Ok(Condition::Contains(when[9..].trim().to_lowercase()));
+                }
+                // (operator, ordering, negated)
+                let operators = [
+                    ("<=", Ordering::Greater, true),
+                    (">=", Ordering::Less, true),
+                    ("!=", Ordering::Equal, true),
+                    ("<>", Ordering::Equal, true),
+                    ("==", Ordering::Equal, false),
+                    ("<", Ordering::Less, false),
+                    (">", Ordering::Greater, false),
+                    ("=", Ordering::Equal, false),
+                ];
+                let (op, ordering, negated) = operators
+                    .iter()
+                    .find(|(op, _, _)| when.starts_with(op))
+                    .ok_or_else(|| anyhow::anyhow!("Unknown condition '{}'", when))?;
+                let operand = when[op.len()..].trim().trim_matches('"').to_string();
+                Condition::Compare(*ordering, *negated, operand)
+            }
+        };
+        Ok(condition)
+    }
+
+    pub(super) fn matches(&self, value: &xlex_core::CellValue) -> bool {
+        match self {
+            Condition::Always => true,
+            Condition::Blank => value.is_empty(),
+            Condition::NonBlank => !value.is_empty(),
+            Condition::Contains(needle) => {
+                value.to_display_string().to_lowercase().contains(needle)
+            }
+            Condition::Compare(ordering, negated, operand) => {
+                if value.is_empty() {
+                    return false;
+                }
+                let ord = match (condition_number(value), operand.parse::<f64>().ok()) {
+                    (Some(a), Some(b)) => a.partial_cmp(&b),
+                    _ => Some(value.to_display_string().as_str().cmp(operand.as_str())),
+                };
+                ord.is_some_and(|ord| (ord == *ordering) != *negated)
+            }
+        }
+    }
+}
+
+/// Returns the numeric value of a cell for comparisons.
+pub(super) fn condition_number(value: &xlex_core::CellValue) -> Option<f64> {
+    use xlex_core::CellValue;
+
+    match value {
+        CellValue::Number(n) | CellValue::DateTime(n) => Some(*n),
+        CellValue::Formula {
+            cached_result: Some(result),
+            ..
+        } => condition_number(result),
+        CellValue::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_condition_parse_and_match() {
+        use xlex_core::CellValue;
+
+        let negative = Condition::parse(Some("negative")).unwrap();
+        assert!(negative.matches(&CellValue::number(-1.0)));
+        assert!(!negative.matches(&CellValue::number(0.0)));
+        assert!(!negative.matches(&CellValue::Empty));
+
+        let big = Condition::parse(Some("> 1000")).unwrap();
+        assert!(big.matches(&CellValue::number(1500.0)));
+        assert!(!big.matches(&CellValue::number(1000.0)));
+
+        let at_most = Condition::parse(Some("<= 10")).unwrap();
+        assert!(at_most.matches(&CellValue::number(10.0)));
+        assert!(!at_most.matches(&CellValue::number(11.0)));
+
+        let not_done = Condition::parse(Some("!= \"done\"")).unwrap();
+        assert!(not_done.matches(&CellValue::string("open")));
+        assert!(!not_done.matches(&CellValue::string("done")));
+
+        let contains = Condition::parse(Some("contains Error")).unwrap();
+        assert!(contains.matches(&CellValue::string("fatal error here")));
+
+        assert_eq!(Condition::parse(None).unwrap(), Condition::Always);
+        assert!(Condition::parse(Some("sometimes")).is_err());
+    }
+}
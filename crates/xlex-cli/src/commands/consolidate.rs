@@ -0,0 +1,549 @@
+//! Consolidating many similar workbooks into one table.
+//!
+//! `xlex consolidate plan.yml` reads every workbook matching the plan's
+//! input patterns, maps each one's headers onto a canonical set of
+//! columns, and stacks their rows under one header in an xlsx or CSV
+//! output, with a column naming the file each row came from. Rows are
+//! streamed from input to output, and relative paths are taken from the
+//! plan file's directory.
+//!
+//! ```yaml
+//! inputs:
+//!   - submissions/*.xlsx            # * and ? in the file name
+//!   - { path: late/*.xlsx, sheet: Data }
+//! sheet: Sales                      # default: each workbook's first sheet
+//! columns:
+//!   - Region
+//!   - { name: Revenue, aliases: [Sales, Total Sales] }
+//! source_column: File               # default: Source; "" leaves it out
+//! output: all-regions.xlsx
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use serde::Deserialize;
+
+use xlex_core::pipeline::{Record, RowSink};
+use xlex_core::writer::StreamingSheetWriter;
+use xlex_core::{CellValue, LazyWorkbook, XlexError};
+
+use super::import::matching_files;
+use super::output::CommandOutput;
+use super::run::CsvSink;
+use super::GlobalOptions;
+
+/// Arguments for the consolidate command.
+#[derive(Parser)]
+pub struct ConsolidateArgs {
+    /// Plan file (YAML) listing inputs, columns and the output
+    pub plan: PathBuf,
+    /// Overwrite the output file if it exists
+    #[arg(long, short = 'F')]
+    pub force: bool,
+}
+
+/// A consolidation plan.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Plan {
+    inputs: Vec<InputSpec>,
+    /// Sheet to read from each workbook (default: the first)
+    sheet: Option<String>,
+    columns: Vec<ColumnSpec>,
+    /// Header of the column naming each row's file; empty to leave it out
+    #[serde(default = "default_source_column")]
+    source_column: String,
+    output: PathBuf,
+    /// Sheet name for an xlsx output (default: Consolidated)
+    output_sheet: Option<String>,
+}
+
+fn default_source_column() -> String {
+    "Source".to_string()
+}
+
+/// Workbooks to read: a path pattern, optionally with its own sheet.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum InputSpec {
+    Pattern(String),
+    Detailed(DetailedInput),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DetailedInput {
+    path: String,
+    sheet: Option<String>,
+}
+
+/// A canonical column: its name, and other headers that mean the same.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ColumnSpec {
+    Name(String),
+    Mapped(MappedColumn),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MappedColumn {
+    name: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+}
+
+impl ColumnSpec {
+    fn name(&self) -> &str {
+        match self {
+            Self::Name(name) | Self::Mapped(MappedColumn { name, .. }) => name,
+        }
+    }
+
+    /// Returns true if `header` names this column, ignoring case and
+    /// surrounding spaces.
+    fn matches(&self, header: &str) -> bool {
+        let header = header.trim();
+        let aliases = match self {
+            Self::Name(_) => &[][..],
+            Self::Mapped(column) => column.aliases.as_slice(),
+        };
+        std::iter::once(self.name())
+            .chain(aliases.iter().map(String::as_str))
+            .any(|name| name.trim().eq_ignore_ascii_case(header))
+    }
+}
+
+/// A workbook to consolidate.
+struct Input {
+    path: PathBuf,
+    /// The path as given in the plan, naming the rows' source
+    label: String,
+    sheet: Option<String>,
+}
+
+/// How one workbook's headers mapped onto the canonical columns.
+struct Mapping {
+    /// Canonical column index for each source column, by position
+    targets: Vec<Option<usize>>,
+    /// Canonical columns the workbook has no header for
+    missing: Vec<String>,
+    /// Headers matching no canonical column, whose values are dropped
+    unmapped: Vec<String>,
+}
+
+impl Plan {
+    fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let plan: Plan = serde_yaml::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Invalid plan {}: {}", path.display(), e))?;
+        if plan.columns.is_empty() {
+            anyhow::bail!("Plan {} defines no columns", path.display());
+        }
+        for (i, column) in plan.columns.iter().enumerate() {
+            if plan.columns[..i].iter().any(|c| c.name() == column.name()) {
+                anyhow::bail!("Column '{}' is defined twice", column.name());
+            }
+        }
+        Ok(plan)
+    }
+
+    /// Lists the workbooks the input patterns match, in pattern order and
+    /// by name within a pattern. Excel lock files (`~$...`) and the output
+    /// itself are skipped, and a workbook matched twice is read once.
+    fn inputs(&self, base: &Path, output: &Path) -> Result<Vec<Input>> {
+        let mut inputs: Vec<Input> = Vec::new();
+        for spec in &self.inputs {
+            let (pattern, sheet) = match spec {
+                InputSpec::Pattern(pattern) => (pattern.as_str(), None),
+                InputSpec::Detailed(input) => (input.path.as_str(), input.sheet.clone()),
+            };
+            let pattern_path = Path::new(pattern);
+            let dir = pattern_path.parent().unwrap_or(Path::new(""));
+            let name = pattern_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .ok_or_else(|| anyhow::anyhow!("Input '{}' has no file name", pattern))?;
+            if dir.to_string_lossy().contains(['*', '?']) {
+                anyhow::bail!(
+                    "Input '{}': wildcards are only supported in the file name",
+                    pattern
+                );
+            }
+            let full_dir = base.join(dir);
+            if !full_dir.is_dir() {
+                return Err(XlexError::FileNotFound { path: full_dir }.into());
+            }
+            for path in matching_files(&full_dir, name)? {
+                let file_name = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                if file_name.starts_with("~$")
+                    || same_file(&path, output)
+                    || inputs.iter().any(|input| same_file(&input.path, &path))
+                {
+                    continue;
+                }
+                inputs.push(Input {
+                    label: dir.join(&file_name).to_string_lossy().to_string(),
+                    path,
+                    sheet: sheet.clone(),
+                });
+            }
+        }
+        Ok(inputs)
+    }
+
+    /// Maps a header row onto the canonical columns; the first source
+    /// column a canonical column matches wins.
+    fn mapping(&self, header: &Record) -> Mapping {
+        let mut found = vec![false; self.columns.len()];
+        let mut targets = Vec::with_capacity(header.values.len());
+        let mut unmapped = Vec::new();
+        for value in &header.values {
+            let text = value.to_display_string();
+            let target = self
+                .columns
+                .iter()
+                .enumerate()
+                .position(|(i, column)| !found[i] && column.matches(&text));
+            match target {
+                Some(i) => found[i] = true,
+                None if !text.trim().is_empty() => unmapped.push(text),
+                None => {}
+            }
+            targets.push(target);
+        }
+        let missing = self
+            .columns
+            .iter()
+            .zip(&found)
+            .filter(|(_, found)| !**found)
+            .map(|(column, _)| column.name().to_string())
+            .collect();
+        Mapping {
+            targets,
+            missing,
+            unmapped,
+        }
+    }
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// What a formula cell shows, so stacked rows don't refer to cells of
+/// another workbook.
+fn shown(value: &CellValue) -> CellValue {
+    match value {
+        CellValue::Formula {
+            cached_result: Some(result),
+            ..
+        } => (**result).clone(),
+        CellValue::Formula { .. } => CellValue::Empty,
+        other => other.clone(),
+    }
+}
+
+/// The consolidated table being written.
+#[allow(clippy::large_enum_variant)]
+enum Output {
+    Csv(CsvSink),
+    Xlsx(StreamingSheetWriter),
+}
+
+impl RowSink for Output {
+    fn write_row(&mut self, values: &[CellValue]) -> xlex_core::XlexResult<()> {
+        match self {
+            Self::Csv(sink) => sink.write_row(values),
+            Self::Xlsx(writer) => writer.write_row(values),
+        }
+    }
+}
+
+impl Output {
+    fn finish(self) -> Result<()> {
+        match self {
+            Self::Csv(mut sink) => sink.writer.flush()?,
+            Self::Xlsx(writer) => writer.finish()?,
+        }
+        Ok(())
+    }
+}
+
+/// Run the consolidate command.
+pub fn run(args: &ConsolidateArgs, global: &GlobalOptions) -> Result<()> {
+    let plan = Plan::load(&args.plan)?;
+    let base = args.plan.parent().unwrap_or(Path::new(""));
+    let output = base.join(&plan.output);
+    let is_csv = match output
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase()
+        .as_str()
+    {
+        "xlsx" => false,
+        "csv" => true,
+        _ => anyhow::bail!(
+            "Can't tell the format of {}; write .xlsx or .csv",
+            plan.output.display()
+        ),
+    };
+    let inputs = plan.inputs(base, &output)?;
+    if inputs.is_empty() {
+        anyhow::bail!("No workbooks match the inputs of {}", args.plan.display());
+    }
+    if output.exists() && !args.force {
+        return Err(XlexError::FileExists { path: output }.into());
+    }
+
+    if global.dry_run {
+        for input in &inputs {
+            outln!(
+                "Would consolidate {} into {}",
+                input.label,
+                plan.output.display()
+            );
+        }
+        return Ok(());
+    }
+
+    let with_source = !plan.source_column.is_empty();
+    let mut header: Vec<CellValue> = Vec::new();
+    if with_source {
+        header.push(CellValue::string(plan.source_column.as_str()));
+    }
+    header.extend(plan.columns.iter().map(|c| CellValue::string(c.name())));
+
+    let mut sink = if is_csv {
+        Output::Csv(CsvSink::create(&output)?)
+    } else {
+        let sheet = plan.output_sheet.as_deref().unwrap_or("Consolidated");
+        Output::Xlsx(StreamingSheetWriter::create(&output, sheet)?)
+    };
+    sink.write_row(&header)?;
+
+    let offset = usize::from(with_source);
+    let mut total = 0u64;
+    let mut files = Vec::new();
+    let mut lines = Vec::new();
+    for input in &inputs {
+        let context = |e: anyhow::Error| anyhow::anyhow!("{}: {}", input.label, e);
+        let workbook = LazyWorkbook::open(&input.path).map_err(|e| context(e.into()))?;
+        let sheet = match input.sheet.as_ref().or(plan.sheet.as_ref()) {
+            Some(sheet) => sheet.clone(),
+            None => workbook
+                .sheet_names()
+                .first()
+                .cloned()
+                .ok_or_else(|| context(anyhow::anyhow!("no sheets")))?,
+        };
+        if !workbook.has_sheet(&sheet) {
+            return Err(context(XlexError::SheetNotFound { name: sheet }.into()));
+        }
+
+        let mut mapping: Option<Mapping> = None;
+        let mut rows = 0u64;
+        let source = CellValue::string(input.label.as_str());
+        workbook
+            .for_each_row(&sheet, |row| {
+                let record = Record::from_stream_row(row);
+                let Some(mapping) = &mapping else {
+                    mapping = Some(plan.mapping(&record));
+                    return Ok(());
+                };
+                let mut values = vec![CellValue::Empty; offset + plan.columns.len()];
+                let mut any = false;
+                for (value, target) in record.values.iter().zip(&mapping.targets) {
+                    if let Some(i) = target {
+                        values[offset + i] = shown(value);
+                        any |= !values[offset + i].is_empty();
+                    }
+                }
+                if !any {
+                    return Ok(());
+                }
+                if with_source {
+                    values[0] = source.clone();
+                }
+                rows += 1;
+                sink.write_row(&values)
+            })
+            .map_err(|e| context(e.into()))?;
+
+        let (missing, unmapped) = mapping.map(|m| (m.missing, m.unmapped)).unwrap_or_else(|| {
+            let all = plan.columns.iter().map(|c| c.name().to_string()).collect();
+            (all, Vec::new())
+        });
+        total += rows;
+        let mut line = format!("  {} ({}): {} rows", input.label.cyan(), sheet, rows);
+        if !missing.is_empty() {
+            line.push_str(&format!("; missing {}", missing.join(", ").yellow()));
+        }
+        if !unmapped.is_empty() {
+            line.push_str(&format!("; ignored {}", unmapped.join(", ").yellow()));
+        }
+        lines.push(line);
+        files.push(serde_json::json!({
+            "path": input.label,
+            "sheet": sheet,
+            "rows": rows,
+            "missing": missing,
+            "unmapped": unmapped,
+        }));
+    }
+    sink.finish()?;
+
+    CommandOutput::new("consolidate")
+        .file(&args.plan)
+        .target("output", plan.output.display().to_string())
+        .count("files", inputs.len())
+        .count("rows", total)
+        .field("files", serde_json::Value::Array(files))
+        .print(
+            global,
+            format!(
+                "{} Consolidated {} rows from {} workbooks into {}\n{}",
+                "✓".green(),
+                total.to_string().green(),
+                inputs.len(),
+                plan.output.display(),
+                lines.join("\n")
+            ),
+        )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::OutputFormat;
+    use tempfile::TempDir;
+    use xlex_core::{CellRef, Workbook};
+
+    fn default_global() -> GlobalOptions {
+        GlobalOptions {
+            quiet: true,
+            verbose: false,
+            format: OutputFormat::Text,
+            no_color: true,
+            color: false,
+            json_errors: false,
+            dry_run: false,
+            output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
+        }
+    }
+
+    fn submission(path: &Path, rows: &[&[CellValue]]) {
+        let mut wb = Workbook::with_sheets(&["Sales"]);
+        for (r, row) in (1..).zip(rows) {
+            for (c, value) in (1..).zip(row.iter()) {
+                wb.set_cell("Sales", CellRef::new(c, r), value.clone())
+                    .unwrap();
+            }
+        }
+        wb.save_as(path).unwrap();
+    }
+
+    const PLAN: &str = r#"
+inputs:
+  - regions/*.xlsx
+columns:
+  - Region
+  - { name: Revenue, aliases: [Sales, Total Sales] }
+output: all.xlsx
+"#;
+
+    #[test]
+    fn test_consolidate() {
+        let dir = TempDir::new().unwrap();
+        let regions = dir.path().join("regions");
+        std::fs::create_dir(&regions).unwrap();
+        let s = CellValue::string;
+        submission(
+            &regions.join("north.xlsx"),
+            &[
+                &[s("Region"), s("Revenue")],
+                &[s("N1"), CellValue::Number(10.0)],
+            ],
+        );
+        submission(
+            &regions.join("south.xlsx"),
+            &[
+                &[s("Notes"), s(" total sales "), s("REGION")],
+                &[s("late"), CellValue::Number(5.0), s("S1")],
+                &[],
+                &[s(""), CellValue::formula("2*3"), s("S2")],
+            ],
+        );
+        submission(&regions.join("~$north.xlsx"), &[&[s("Region")]]);
+        let plan = dir.path().join("plan.yml");
+        std::fs::write(&plan, PLAN).unwrap();
+        let args = ConsolidateArgs {
+            plan: plan.clone(),
+            force: false,
+        };
+        run(&args, &default_global()).unwrap();
+
+        let out = Workbook::open(dir.path().join("all.xlsx")).unwrap();
+        let sheet = out.get_sheet("Consolidated").unwrap();
+        let row = |r| -> Vec<String> {
+            (1..=3)
+                .map(|c| sheet.get_value(&CellRef::new(c, r)).to_display_string())
+                .collect()
+        };
+        assert_eq!(row(1), ["Source", "Region", "Revenue"]);
+        assert_eq!(row(2), ["regions/north.xlsx", "N1", "10"]);
+        assert_eq!(row(3), ["regions/south.xlsx", "S1", "5"]);
+        assert_eq!(row(4)[2], "");
+        assert_eq!(sheet.last_row(), Some(4));
+
+        // The output exists now
+        assert!(run(&args, &default_global()).is_err());
+
+        std::fs::write(
+            &plan,
+            PLAN.replace("all.xlsx", "all.csv") + "source_column: \"\"\n",
+        )
+        .unwrap();
+        run(&args, &default_global()).unwrap();
+        let csv = std::fs::read_to_string(dir.path().join("all.csv")).unwrap();
+        assert_eq!(csv, "Region,Revenue\nN1,10\nS1,5\nS2,\n");
+    }
+
+    #[test]
+    fn test_consolidate_plan_errors() {
+        let dir = TempDir::new().unwrap();
+        let plan = dir.path().join("plan.yml");
+        let error = |yaml: &str| {
+            std::fs::write(&plan, yaml).unwrap();
+            let args = ConsolidateArgs {
+                plan: plan.clone(),
+                force: false,
+            };
+            run(&args, &default_global()).unwrap_err().to_string()
+        };
+        assert!(error("inputs: [a/*.xlsx]\ncolumns: []\noutput: o.xlsx\n").contains("no columns"));
+        assert!(error("inputs: [a/*.xlsx]\ncolumns: [A, A]\noutput: o.xlsx\n").contains("twice"));
+        assert!(error("inputs: [a/*.xlsx]\ncolumns: [A]\noutput: o.txt\n").contains("format"));
+        assert!(error("inputs: ['*/x.xlsx']\ncolumns: [A]\noutput: o.csv\n").contains("file name"));
+        assert!(error("inputs: ['*.xlsx']\ncolumns: [A]\noutput: o.csv\n").contains("No workbooks"));
+        assert!(
+            error("inputs: []\ncolumns: [A]\noutput: o.csv\nextra: 1\n").contains("Invalid plan")
+        );
+    }
+}
@@ -0,0 +1,353 @@
+//! Running and window calculations written back as values.
+//!
+//! `xlex derive` streams the columns its calculations read, computes each
+//! `--new-column` down the rows, and writes the results into the sheet as
+//! plain values. Every calculation sees the sheet as it was before the
+//! command, so one may overwrite a column another reads.
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use xlex_core::window::{decimal_window, window, WindowFunc};
+use xlex_core::{CellRef, CellValue, LazyWorkbook, Workbook, XlexError};
+
+use super::join::{header_column, header_row};
+use super::output::CommandOutput;
+use super::GlobalOptions;
+
+/// Arguments for the derive command.
+#[derive(Parser)]
+pub struct DeriveArgs {
+    /// Path to the xlsx file
+    pub file: std::path::PathBuf,
+    /// Sheet name
+    pub sheet: String,
+    /// Column to fill, as TARGET=func(COLUMN) [asc|desc]; repeatable.
+    /// Functions: cumsum(c), movavg(c, n), rank(c), lag(c[, n]), lead(c[, n])
+    #[arg(long = "new-column", required = true)]
+    pub new_columns: Vec<String>,
+    /// Treat the first row as data rather than a header
+    #[arg(long)]
+    pub no_header: bool,
+    /// Compute running sums and moving averages as exact decimals
+    #[arg(long)]
+    pub decimal: bool,
+}
+
+/// A parsed `--new-column`.
+#[derive(Debug, PartialEq)]
+struct NewColumn {
+    target: String,
+    func: WindowFunc,
+    source: String,
+    /// The calculation as written, used as the header of a new column
+    text: String,
+}
+
+fn parse_new_column(spec: &str) -> Result<NewColumn> {
+    let invalid = || {
+        anyhow::anyhow!(
+            "Invalid --new-column '{}': expected TARGET=func(COLUMN)",
+            spec
+        )
+    };
+    let (target, text) = spec.split_once('=').ok_or_else(invalid)?;
+    let (target, text) = (target.trim(), text.trim());
+    let (name, rest) = text.split_once('(').ok_or_else(invalid)?;
+    let (args, order) = rest.split_once(')').ok_or_else(invalid)?;
+    let mut args = args.split(',').map(str::trim);
+    let source = args.next().filter(|s| !s.is_empty()).ok_or_else(invalid)?;
+    let count = args
+        .next()
+        .map(|n| {
+            n.parse::<usize>()
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or_else(|| anyhow::anyhow!("Invalid row count '{}' in '{}'", n, spec))
+        })
+        .transpose()?;
+    if args.next().is_some() || target.is_empty() {
+        return Err(invalid());
+    }
+    let descending = match order.trim().to_lowercase().as_str() {
+        "" | "asc" => false,
+        "desc" => true,
+        other => anyhow::bail!("Unexpected '{}' in '{}': expected asc or desc", other, spec),
+    };
+    let name = name.trim().to_lowercase();
+    if !order.trim().is_empty() && name != "rank" {
+        anyhow::bail!("Only rank() takes asc or desc, in '{}'", spec);
+    }
+    let func = match (name.as_str(), count) {
+        ("cumsum", None) => WindowFunc::CumSum,
+        ("movavg" | "moving_avg", Some(n)) => WindowFunc::MovingAvg(n),
+        ("movavg" | "moving_avg", None) => {
+            anyhow::bail!("movavg() needs a window size, as movavg({}, 3)", source)
+        }
+        ("rank", None) => WindowFunc::Rank { descending },
+        ("lag", n) => WindowFunc::Lag(n.unwrap_or(1)),
+        ("lead", n) => WindowFunc::Lead(n.unwrap_or(1)),
+        ("cumsum" | "rank", Some(_)) => return Err(invalid()),
+        _ => anyhow::bail!(
+            "Unknown function '{}' (expected cumsum, movavg, rank, lag or lead)",
+            name
+        ),
+    };
+    Ok(NewColumn {
+        target: target.to_string(),
+        func,
+        source: source.to_string(),
+        text: text.to_string(),
+    })
+}
+
+/// Run the derive command.
+pub fn run(args: &DeriveArgs, global: &GlobalOptions) -> Result<()> {
+    let new_columns = args
+        .new_columns
+        .iter()
+        .map(|spec| parse_new_column(spec))
+        .collect::<Result<Vec<_>>>()?;
+
+    let lazy = LazyWorkbook::open(&args.file)?;
+    if !lazy.has_sheet(&args.sheet) {
+        return Err(XlexError::SheetNotFound {
+            name: args.sheet.clone(),
+        }
+        .into());
+    }
+    let header = if args.no_header {
+        Vec::new()
+    } else {
+        header_row(&lazy, &args.sheet)?
+    };
+    let column = |spec: &str| {
+        header_column(&header, spec)
+            .ok_or_else(|| anyhow::anyhow!("No column '{}' in {}", spec, args.sheet))
+    };
+    let resolved = new_columns
+        .iter()
+        .map(|c| Ok((column(&c.target)?, column(&c.source)?)))
+        .collect::<Result<Vec<_>>>()?;
+
+    // Stream the source columns, after the header
+    let mut sources: Vec<u32> = resolved.iter().map(|&(_, source)| source).collect();
+    sources.sort_unstable();
+    sources.dedup();
+    let mut rows: Vec<u32> = Vec::new();
+    let mut values: Vec<Vec<CellValue>> = vec![Vec::new(); sources.len()];
+    let mut header_row_number = None;
+    lazy.for_each_row(&args.sheet, |row| {
+        if !args.no_header && header_row_number.is_none() {
+            header_row_number = Some(row.row_number);
+            return Ok(());
+        }
+        rows.push(row.row_number);
+        for (column, &col) in values.iter_mut().zip(&sources) {
+            let value = row
+                .cells
+                .iter()
+                .find(|(cell_ref, _)| cell_ref.col == col)
+                .map_or(CellValue::Empty, |(_, value)| value.clone());
+            column.push(value);
+        }
+        Ok(())
+    })?;
+    drop(lazy);
+
+    let targets: Vec<String> = resolved
+        .iter()
+        .map(|&(target, _)| CellRef::col_to_letters(target))
+        .collect();
+    if global.dry_run {
+        outln!(
+            "Would fill {} in {} rows of {}",
+            targets.join(", "),
+            rows.len(),
+            args.sheet
+        );
+        return Ok(());
+    }
+
+    let results: Vec<Vec<CellValue>> = new_columns
+        .iter()
+        .zip(&resolved)
+        .map(|(new_column, &(_, source))| {
+            let index = sources.binary_search(&source).unwrap_or_default();
+            if args.decimal {
+                decimal_window(new_column.func, &values[index])
+            } else {
+                window(new_column.func, &values[index])
+            }
+        })
+        .collect();
+
+    let mut workbook = Workbook::open(&args.file)?;
+    let header_row = header_row_number.unwrap_or(1);
+    workbook.with_sheet_mut(&args.sheet, |sheet| {
+        for ((new_column, &(target, _)), results) in new_columns.iter().zip(&resolved).zip(results)
+        {
+            if !args.no_header {
+                let cell_ref = CellRef::new(target, header_row);
+                if sheet.get_value(&cell_ref).is_empty() {
+                    sheet.set_cell(cell_ref, CellValue::string(&new_column.text));
+                }
+            }
+            for (&row, value) in rows.iter().zip(results) {
+                let cell_ref = CellRef::new(target, row);
+                match sheet.get_cell_mut(&cell_ref) {
+                    Some(cell) => cell.value = value,
+                    None if value.is_empty() => {}
+                    None => sheet.set_cell(cell_ref, value),
+                }
+            }
+        }
+    })?;
+    workbook.save()?;
+
+    CommandOutput::new("derive")
+        .file(&args.file)
+        .target("sheet", args.sheet.as_str())
+        .field("columns", targets.clone())
+        .field("rows", rows.len())
+        .print(
+            global,
+            format!(
+                "Filled {} in {} rows of {}",
+                targets.join(", ").green(),
+                rows.len(),
+                args.sheet.cyan()
+            ),
+        )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::OutputFormat;
+    use tempfile::TempDir;
+
+    fn default_global() -> GlobalOptions {
+        GlobalOptions {
+            quiet: true,
+            verbose: false,
+            format: OutputFormat::Text,
+            no_color: true,
+            color: false,
+            json_errors: false,
+            dry_run: false,
+            output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
+        }
+    }
+
+    fn sales(dir: &TempDir) -> std::path::PathBuf {
+        let path = dir.path().join("sales.xlsx");
+        let mut wb = Workbook::with_sheets(&["Sheet1"]);
+        wb.set_cell("Sheet1", CellRef::new(4, 1), CellValue::string("amount"))
+            .unwrap();
+        for (row, amount) in [(2, 10.0), (3, 30.0), (4, 20.0)] {
+            wb.set_cell("Sheet1", CellRef::new(4, row), CellValue::Number(amount))
+                .unwrap();
+        }
+        wb.save_as(&path).unwrap();
+        path
+    }
+
+    fn column(file: &std::path::Path, col: u32) -> Vec<String> {
+        let wb = Workbook::open(file).unwrap();
+        let sheet = wb.get_sheet("Sheet1").unwrap();
+        (1..=4)
+            .map(|r| sheet.get_value(&CellRef::new(col, r)).to_display_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_new_column() {
+        assert_eq!(
+            parse_new_column("F=rank(D) desc").unwrap(),
+            NewColumn {
+                target: "F".to_string(),
+                func: WindowFunc::Rank { descending: true },
+                source: "D".to_string(),
+                text: "rank(D) desc".to_string(),
+            }
+        );
+        assert_eq!(
+            parse_new_column("G = movavg(amount, 3)").unwrap().func,
+            WindowFunc::MovingAvg(3)
+        );
+        assert_eq!(
+            parse_new_column("H=lag(D)").unwrap().func,
+            WindowFunc::Lag(1)
+        );
+        for bad in [
+            "cumsum(D)",
+            "E=cumsum()",
+            "E=movavg(D)",
+            "E=movavg(D, 0)",
+            "E=cumsum(D) desc",
+            "E=median(D)",
+            "E=lead(D, 1, 2)",
+        ] {
+            assert!(parse_new_column(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_run_derive() {
+        let dir = TempDir::new().unwrap();
+        let file = sales(&dir);
+        let args = DeriveArgs {
+            file: file.clone(),
+            sheet: "Sheet1".to_string(),
+            new_columns: vec![
+                "E=cumsum(D)".to_string(),
+                "F=rank(amount) desc".to_string(),
+                "G=lag(D)".to_string(),
+                "D=movavg(D, 2)".to_string(),
+            ],
+            no_header: false,
+            decimal: false,
+        };
+        run(&args, &default_global()).unwrap();
+        assert_eq!(column(&file, 5), vec!["cumsum(D)", "10", "40", "60"]);
+        assert_eq!(column(&file, 6), vec!["rank(amount) desc", "3", "1", "2"]);
+        assert_eq!(column(&file, 7), vec!["lag(D)", "", "10", "30"]);
+        assert_eq!(column(&file, 4), vec!["amount", "10", "20", "25"]);
+
+        let missing = DeriveArgs {
+            new_columns: vec!["E=cumsum(total)".to_string()],
+            ..args
+        };
+        let err = run(&missing, &default_global()).unwrap_err();
+        assert!(err.to_string().contains("No column 'total'"));
+    }
+
+    #[test]
+    fn test_run_derive_decimal() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("cents.xlsx");
+        let mut wb = Workbook::with_sheets(&["Sheet1"]);
+        for (row, amount) in [(1, 0.1), (2, 0.2), (3, 0.3)] {
+            wb.set_cell("Sheet1", CellRef::new(1, row), CellValue::Number(amount))
+                .unwrap();
+        }
+        wb.save_as(&path).unwrap();
+        let args = DeriveArgs {
+            file: path.clone(),
+            sheet: "Sheet1".to_string(),
+            new_columns: vec!["B=cumsum(A)".to_string()],
+            no_header: true,
+            decimal: true,
+        };
+        run(&args, &default_global()).unwrap();
+        assert_eq!(column(&path, 2)[..3], ["0.1", "0.3", "0.6"]);
+    }
+}
@@ -0,0 +1,417 @@
+//! Workbook comparison.
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use xlex_core::compare::{
+    compare_rows, compare_sheets, compare_workbooks, ComparisonOptions, Difference, RowChange,
+    RowChangeKind,
+};
+use xlex_core::{CellRef, Sheet, Workbook};
+
+use super::{GlobalOptions, OutputFormat};
+
+/// Arguments for comparing two workbooks.
+#[derive(Parser)]
+pub struct DiffArgs {
+    /// First (old) xlsx file
+    pub left: std::path::PathBuf,
+    /// Second (new) xlsx file
+    pub right: std::path::PathBuf,
+    /// Compare only one sheet (present in both files)
+    #[arg(long, short = 's')]
+    pub sheet: Option<String>,
+    /// Compare displayed values only: formulas by their cached results,
+    /// formatting ignored
+    #[arg(long)]
+    pub values_only: bool,
+    /// Largest difference between numbers still counted as equal
+    #[arg(long, default_value = "0")]
+    pub tolerance: f64,
+    /// Pair rows by the value in this column instead of by row number
+    #[arg(long, value_name = "COLUMN")]
+    pub key_column: Option<String>,
+    /// Print the rows added, removed and changed as NDJSON, one object per
+    /// row with values named by the headers in row 1
+    #[arg(long, requires = "key_column")]
+    pub rows: bool,
+    /// Fail (exit code 12) when the workbooks differ
+    #[arg(long)]
+    pub exit_code: bool,
+}
+
+/// Run the diff command.
+pub fn run(args: &DiffArgs, global: &GlobalOptions) -> Result<()> {
+    let key_column = args
+        .key_column
+        .as_deref()
+        .map(|col| {
+            CellRef::col_from_letters_pub(&col.to_uppercase())
+                .ok_or_else(|| anyhow::anyhow!("Invalid column: {}", col))
+        })
+        .transpose()?;
+    let options = ComparisonOptions::new()
+        .values_only(args.values_only)
+        .tolerance(args.tolerance)
+        .key_column(key_column);
+
+    let left = Workbook::open(&args.left)?;
+    let right = Workbook::open(&args.right)?;
+    if let Some(sheet) = &args.sheet {
+        for workbook in [&left, &right] {
+            if workbook.get_sheet(sheet).is_none() {
+                return Err(xlex_core::XlexError::SheetNotFound {
+                    name: sheet.clone(),
+                }
+                .into());
+            }
+        }
+    }
+    if args.rows {
+        return run_rows(args, &left, &right, &options);
+    }
+    let differences = match &args.sheet {
+        Some(sheet) => compare_sheets(&left, sheet, &right, sheet, &options),
+        None => compare_workbooks(&left, &right, &options),
+    };
+
+    if global.format == OutputFormat::Json {
+        let json = serde_json::json!({
+            "left": args.left.display().to_string(),
+            "right": args.right.display().to_string(),
+            "identical": differences.is_empty(),
+            "differences": differences.iter().map(difference_json).collect::<Vec<_>>(),
+        });
+        outln!("{}", serde_json::to_string_pretty(&json)?);
+    } else if differences.is_empty() {
+        if !global.quiet {
+            outln!("{} No differences", "✓".green());
+        }
+    } else {
+        for difference in &differences {
+            outln!("{}", difference);
+        }
+        if !global.quiet {
+            outln!();
+            outln!("{} difference(s)", differences.len().to_string().yellow());
+        }
+    }
+
+    if args.exit_code && !differences.is_empty() {
+        return Err(xlex_core::XlexError::ValidationFailed {
+            message: format!(
+                "{} and {} differ in {} place(s)",
+                args.left.display(),
+                args.right.display(),
+                differences.len()
+            ),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Prints the row changes of each sheet, or of `--sheet`, as NDJSON.
+///
+/// Row 1 of each sheet names the columns and is not itself compared.
+/// Sheets in only one workbook have all their rows added or removed.
+fn run_rows(
+    args: &DiffArgs,
+    left: &Workbook,
+    right: &Workbook,
+    options: &ComparisonOptions,
+) -> Result<()> {
+    let sheets: Vec<&str> = match &args.sheet {
+        Some(sheet) => vec![sheet.as_str()],
+        None => {
+            let mut names = left.sheet_names();
+            for name in right.sheet_names() {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+            names
+        }
+    };
+
+    let mut count = 0;
+    for sheet in sheets {
+        let (left_sheet, right_sheet) = (left.get_sheet(sheet), right.get_sheet(sheet));
+        for change in compare_rows(left, sheet, right, sheet, options) {
+            if change.left_row == Some(1) || change.right_row == Some(1) {
+                continue;
+            }
+            outln!(
+                "{}",
+                serde_json::to_string(&row_change_json(sheet, &change, left_sheet, right_sheet))?
+            );
+            count += 1;
+        }
+    }
+
+    if args.exit_code && count > 0 {
+        return Err(xlex_core::XlexError::ValidationFailed {
+            message: format!(
+                "{} and {} differ in {} row(s)",
+                args.left.display(),
+                args.right.display(),
+                count
+            ),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// The name of a column: its header in row 1, or its letters.
+fn column_name(sheet: Option<&Sheet>, col: u32) -> String {
+    let header = sheet
+        .map(|s| s.get_value(&CellRef::new(col, 1)).to_display_string())
+        .unwrap_or_default();
+    if header.is_empty() {
+        CellRef::col_to_letters(col)
+    } else {
+        header
+    }
+}
+
+/// A row's values by column name, through the header's last column or
+/// the row's, whichever is further.
+fn row_values(sheet: Option<&Sheet>, row: u32) -> serde_json::Value {
+    let Some(sheet) = sheet else {
+        return serde_json::Value::Object(serde_json::Map::new());
+    };
+    let last_col = sheet
+        .last_col_in_row(1)
+        .max(sheet.last_col_in_row(row))
+        .unwrap_or(0);
+    let values = (1..=last_col)
+        .map(|col| {
+            let value = sheet.get_value(&CellRef::new(col, row));
+            (
+                column_name(Some(sheet), col),
+                serde_json::Value::from(value),
+            )
+        })
+        .collect();
+    serde_json::Value::Object(values)
+}
+
+fn row_change_json(
+    sheet: &str,
+    change: &RowChange,
+    left: Option<&Sheet>,
+    right: Option<&Sheet>,
+) -> serde_json::Value {
+    let mut json = serde_json::json!({
+        "op": change.kind.to_string(),
+        "sheet": sheet,
+        "key": change.key,
+    });
+    match change.kind {
+        RowChangeKind::Added => {
+            json["row"] = change.right_row.into();
+            json["values"] = row_values(right, change.right_row.unwrap_or_default());
+        }
+        RowChangeKind::Removed => {
+            json["row"] = change.left_row.into();
+            json["values"] = row_values(left, change.left_row.unwrap_or_default());
+        }
+        RowChangeKind::Changed => {
+            let (left_row, right_row) = (
+                change.left_row.unwrap_or_default(),
+                change.right_row.unwrap_or_default(),
+            );
+            json["oldRow"] = left_row.into();
+            json["row"] = right_row.into();
+            json["values"] = row_values(right, right_row);
+            let changes: serde_json::Map<String, serde_json::Value> = change
+                .columns
+                .iter()
+                .map(|&col| {
+                    let value = |sheet: Option<&Sheet>, row| {
+                        sheet.map_or(serde_json::Value::Null, |s| {
+                            s.get_value(&CellRef::new(col, row)).into()
+                        })
+                    };
+                    (
+                        column_name(right.or(left), col),
+                        serde_json::json!({
+                            "old": value(left, left_row),
+                            "new": value(right, right_row),
+                        }),
+                    )
+                })
+                .collect();
+            json["changes"] = serde_json::Value::Object(changes);
+        }
+    }
+    json
+}
+
+fn difference_json(difference: &Difference) -> serde_json::Value {
+    match difference {
+        Difference::Sheet { sheet, only_in } => serde_json::json!({
+            "kind": "sheet",
+            "sheet": sheet,
+            "onlyIn": only_in.to_string(),
+        }),
+        Difference::Row {
+            sheet,
+            key,
+            row,
+            only_in,
+        } => serde_json::json!({
+            "kind": "row",
+            "sheet": sheet,
+            "key": key,
+            "row": row,
+            "onlyIn": only_in.to_string(),
+        }),
+        Difference::Value {
+            sheet,
+            left,
+            right,
+            left_value,
+            right_value,
+        } => serde_json::json!({
+            "kind": "value",
+            "sheet": sheet,
+            "left": left.to_a1(),
+            "right": right.to_a1(),
+            "leftValue": left_value,
+            "rightValue": right_value,
+        }),
+        Difference::Style { sheet, left, right } => serde_json::json!({
+            "kind": "style",
+            "sheet": sheet,
+            "left": left.to_a1(),
+            "right": right.to_a1(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use xlex_core::CellValue;
+
+    fn default_global() -> GlobalOptions {
+        GlobalOptions {
+            quiet: true,
+            verbose: false,
+            format: OutputFormat::Text,
+            no_color: true,
+            color: false,
+            json_errors: false,
+            dry_run: false,
+            output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
+        }
+    }
+
+    #[test]
+    fn test_run_diff() {
+        let temp_dir = TempDir::new().unwrap();
+        let left = temp_dir.path().join("v1.xlsx");
+        let right = temp_dir.path().join("v2.xlsx");
+        for (path, total) in [(&left, 10.0), (&right, 10.0 + 1e-12)] {
+            let mut wb = Workbook::new();
+            wb.set_cell("Sheet1", CellRef::new(1, 1), CellValue::string("id"))
+                .unwrap();
+            wb.set_cell("Sheet1", CellRef::new(2, 1), CellValue::Number(total))
+                .unwrap();
+            wb.save_as(path).unwrap();
+        }
+
+        let mut args = DiffArgs {
+            left,
+            right,
+            sheet: None,
+            values_only: false,
+            tolerance: 0.0,
+            key_column: None,
+            rows: false,
+            exit_code: true,
+        };
+        let err = run(&args, &default_global()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<xlex_core::XlexError>()
+                .unwrap()
+                .exit_code(),
+            12
+        );
+
+        args.values_only = true;
+        args.tolerance = 1e-9;
+        args.key_column = Some("a".to_string());
+        run(&args, &default_global()).unwrap();
+
+        args.key_column = Some("1".to_string());
+        assert!(run(&args, &default_global()).is_err());
+        args.key_column = None;
+        args.sheet = Some("Missing".to_string());
+        assert!(run(&args, &default_global()).is_err());
+    }
+
+    #[test]
+    fn test_run_diff_rows() {
+        let temp_dir = TempDir::new().unwrap();
+        let left = temp_dir.path().join("v1.xlsx");
+        let right = temp_dir.path().join("v2.xlsx");
+        let rows: [&[(&str, f64)]; 2] = [&[("x", 1.0), ("gone", 2.0)], &[("x", 5.0), ("new", 3.0)]];
+        for (path, rows) in [(&left, rows[0]), (&right, rows[1])] {
+            let mut wb = Workbook::new();
+            wb.set_cell("Sheet1", CellRef::new(1, 1), CellValue::string("id"))
+                .unwrap();
+            wb.set_cell("Sheet1", CellRef::new(2, 1), CellValue::string("qty"))
+                .unwrap();
+            for (row, (id, qty)) in (2..).zip(rows) {
+                wb.set_cell("Sheet1", CellRef::new(1, row), CellValue::string(*id))
+                    .unwrap();
+                wb.set_cell("Sheet1", CellRef::new(2, row), CellValue::Number(*qty))
+                    .unwrap();
+            }
+            wb.save_as(path).unwrap();
+        }
+
+        let (old, new) = (
+            Workbook::open(&left).unwrap(),
+            Workbook::open(&right).unwrap(),
+        );
+        let options = ComparisonOptions::new().key_column(Some(1));
+        let (old_sheet, new_sheet) = (old.get_sheet("Sheet1"), new.get_sheet("Sheet1"));
+        let json: Vec<serde_json::Value> = compare_rows(&old, "Sheet1", &new, "Sheet1", &options)
+            .iter()
+            .map(|change| row_change_json("Sheet1", change, old_sheet, new_sheet))
+            .collect();
+        assert_eq!(json.len(), 3);
+        assert_eq!(json[0]["op"], "removed");
+        assert_eq!(json[0]["values"]["id"], "gone");
+        assert_eq!(json[1]["op"], "added");
+        assert_eq!(json[1]["values"]["qty"], 3.0);
+        assert_eq!(json[2]["op"], "changed");
+        assert_eq!(json[2]["key"], "x");
+        assert_eq!(json[2]["changes"]["qty"]["old"], 1.0);
+        assert_eq!(json[2]["changes"]["qty"]["new"], 5.0);
+
+        let args = DiffArgs {
+            left,
+            right,
+            sheet: Some("Sheet1".to_string()),
+            values_only: false,
+            tolerance: 0.0,
+            key_column: Some("A".to_string()),
+            rows: true,
+            exit_code: true,
+        };
+        let err = run(&args, &default_global()).unwrap_err();
+        assert!(err.to_string().contains("3 row(s)"));
+    }
+}
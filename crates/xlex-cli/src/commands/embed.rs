@@ -0,0 +1,280 @@
+//! Embedded object operations.
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+
+use xlex_core::embed::EmbeddedObject;
+use xlex_core::LazyWorkbook;
+
+use super::{GlobalOptions, OutputFormat};
+
+/// Arguments for embedded object operations.
+#[derive(Parser)]
+pub struct EmbedArgs {
+    #[command(subcommand)]
+    pub command: EmbedCommand,
+}
+
+#[derive(Subcommand)]
+pub enum EmbedCommand {
+    /// List embedded objects (PDFs, documents, workbooks)
+    List {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+    },
+    /// Extract embedded objects as their original files
+    Extract {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Object number to extract (see `embed list`; default: all)
+        #[arg(long)]
+        object: Option<usize>,
+        /// Directory to write the files to
+        #[arg(long, default_value = ".")]
+        dir: std::path::PathBuf,
+    },
+}
+
+/// Run embedded object operations.
+pub fn run(args: &EmbedArgs, global: &GlobalOptions) -> Result<()> {
+    match &args.command {
+        EmbedCommand::List { file } => list(file, global),
+        EmbedCommand::Extract { file, object, dir } => extract(file, *object, dir, global),
+    }
+}
+
+fn list(file: &std::path::Path, global: &GlobalOptions) -> Result<()> {
+    let workbook = LazyWorkbook::open(file)?;
+    let objects = workbook.embedded_objects()?;
+    print_objects(&objects, global)
+}
+
+/// Prints embedded objects, numbered from 1.
+pub(super) fn print_objects(objects: &[EmbeddedObject], global: &GlobalOptions) -> Result<()> {
+    if global.format == OutputFormat::Json {
+        let items: Vec<serde_json::Value> = objects
+            .iter()
+            .enumerate()
+            .map(|(i, o)| {
+                serde_json::json!({
+                    "object": i + 1,
+                    "part": o.part,
+                    "sheet": o.sheet,
+                    "progId": o.prog_id,
+                    "size": o.size,
+                })
+            })
+            .collect();
+        let json = serde_json::json!({ "objects": items });
+        outln!("{}", serde_json::to_string_pretty(&json)?);
+    } else if objects.is_empty() {
+        outln!("No embedded objects");
+    } else {
+        for (i, o) in objects.iter().enumerate() {
+            outln!(
+                "{} {} ({} bytes){}{}",
+                format!("{}.", i + 1).cyan(),
+                o.part_name(),
+                o.size,
+                o.prog_id
+                    .as_deref()
+                    .map(|p| format!(" {}", p))
+                    .unwrap_or_default(),
+                o.sheet
+                    .as_deref()
+                    .map(|s| format!(" on '{}'", s))
+                    .unwrap_or_default()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Returns a path in `dir` for `file_name` that doesn't overwrite an
+/// existing file, adding " (2)", " (3)", ... before the extension.
+fn unique_path(dir: &std::path::Path, file_name: &str) -> std::path::PathBuf {
+    let candidate = dir.join(file_name);
+    if !candidate.exists() {
+        return candidate;
+    }
+    let (stem, ext) = match file_name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{}", ext)),
+        _ => (file_name, String::new()),
+    };
+    (2..)
+        .map(|n| dir.join(format!("{} ({}){}", stem, n, ext)))
+        .find(|path| !path.exists())
+        .unwrap_or(candidate)
+}
+
+fn extract(
+    file: &std::path::Path,
+    object: Option<usize>,
+    dir: &std::path::Path,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let workbook = LazyWorkbook::open(file)?;
+    let objects = workbook.embedded_objects()?;
+    let selected: Vec<&EmbeddedObject> = match object {
+        Some(n) => vec![n
+            .checked_sub(1)
+            .and_then(|i| objects.get(i))
+            .ok_or_else(|| anyhow::anyhow!("Embedded object {} does not exist", n))?],
+        None => objects.iter().collect(),
+    };
+
+    let mut written = Vec::new();
+    for object in selected {
+        let extracted = workbook.extract_embedded_object(object)?;
+        if global.dry_run {
+            outln!(
+                "Would extract {} as {}",
+                object.part_name(),
+                dir.join(&extracted.file_name).display()
+            );
+            continue;
+        }
+        std::fs::create_dir_all(dir)?;
+        let path = unique_path(dir, &extracted.file_name);
+        std::fs::write(&path, &extracted.data)?;
+        written.push((object.part_name().to_string(), path, extracted.data.len()));
+    }
+
+    if !global.quiet && !global.dry_run {
+        if global.format == OutputFormat::Json {
+            let items: Vec<serde_json::Value> = written
+                .iter()
+                .map(|(part, path, size)| {
+                    serde_json::json!({
+                        "part": part,
+                        "path": path.display().to_string(),
+                        "size": size,
+                    })
+                })
+                .collect();
+            let json = serde_json::json!({ "extracted": items });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else if written.is_empty() {
+            outln!("No embedded objects in {}", file.display());
+        } else {
+            for (part, path, size) in &written {
+                outln!(
+                    "Extracted {} -> {} ({} bytes)",
+                    part,
+                    path.display().to_string().green(),
+                    size
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use tempfile::TempDir;
+    use xlex_core::Workbook;
+
+    fn default_global() -> GlobalOptions {
+        GlobalOptions {
+            quiet: true,
+            verbose: false,
+            format: OutputFormat::Text,
+            no_color: true,
+            color: false,
+            json_errors: false,
+            dry_run: false,
+            output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
+        }
+    }
+
+    /// Creates a workbook with an embedded Word document on Sheet1 and an
+    /// unreferenced PDF part.
+    fn create_embedding_workbook(dir: &TempDir, name: &str) -> std::path::PathBuf {
+        let plain = dir.path().join("plain.xlsx");
+        Workbook::new().save_as(&plain).unwrap();
+
+        let file_path = dir.path().join(name);
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&plain).unwrap()).unwrap();
+        let mut out = zip::ZipWriter::new(std::fs::File::create(&file_path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).unwrap();
+            let entry_name = entry.name().to_string();
+            let mut content = String::new();
+            entry.read_to_string(&mut content).unwrap();
+            let content = if entry_name == "xl/worksheets/sheet1.xml" {
+                content.replace(
+                    "</worksheet>",
+                    r#"<oleObjects><oleObject progId="Word.Document.12" shapeId="1025" r:id="rIdOle1"/></oleObjects></worksheet>"#,
+                )
+            } else {
+                content
+            };
+            out.start_file(entry_name, options).unwrap();
+            out.write_all(content.as_bytes()).unwrap();
+        }
+        out.start_file("xl/worksheets/_rels/sheet1.xml.rels", options)
+            .unwrap();
+        out.write_all(br#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rIdOle1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/package" Target="../embeddings/Microsoft_Word_Document.docx"/></Relationships>"#)
+            .unwrap();
+        out.start_file("xl/embeddings/Microsoft_Word_Document.docx", options)
+            .unwrap();
+        out.write_all(b"PK\x03\x04 word/document.xml").unwrap();
+        out.start_file("xl/embeddings/report.pdf", options).unwrap();
+        out.write_all(b"%PDF-1.7 report").unwrap();
+        out.finish().unwrap();
+        file_path
+    }
+
+    #[test]
+    fn test_list_embedded_objects() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_embedding_workbook(&temp_dir, "embed.xlsx");
+
+        let objects = LazyWorkbook::open(&file_path)
+            .unwrap()
+            .embedded_objects()
+            .unwrap();
+        assert_eq!(objects.len(), 2);
+        let docx = objects
+            .iter()
+            .find(|o| o.part_name() == "Microsoft_Word_Document.docx")
+            .unwrap();
+        assert_eq!(docx.sheet.as_deref(), Some("Sheet1"));
+        assert_eq!(docx.prog_id.as_deref(), Some("Word.Document.12"));
+        assert!(objects.iter().any(|o| o.sheet.is_none()));
+
+        let mut json = default_global();
+        json.format = OutputFormat::Json;
+        list(&file_path, &json).unwrap();
+    }
+
+    #[test]
+    fn test_extract_embedded_objects() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_embedding_workbook(&temp_dir, "extract.xlsx");
+        let out_dir = temp_dir.path().join("attachments");
+
+        extract(&file_path, None, &out_dir, &default_global()).unwrap();
+        assert_eq!(
+            std::fs::read(out_dir.join("report.pdf")).unwrap(),
+            b"%PDF-1.7 report"
+        );
+        assert!(out_dir.join("Microsoft_Word_Document.docx").exists());
+
+        // Extracting again keeps the earlier files
+        extract(&file_path, Some(2), &out_dir, &default_global()).unwrap();
+        assert_eq!(std::fs::read_dir(&out_dir).unwrap().count(), 3);
+        assert!(extract(&file_path, Some(3), &out_dir, &default_global()).is_err());
+    }
+}
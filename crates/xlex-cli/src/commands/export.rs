@@ -6,7 +6,8 @@ use colored::Colorize;
 
 use xlex_core::Workbook;
 
-use super::GlobalOptions;
+use super::output::CommandOutput;
+use super::{GlobalOptions, OutputFormat};
 use crate::progress::Progress;
 
 /// Arguments for export operations.
@@ -22,7 +23,8 @@ pub enum ExportCommand {
     Csv {
         /// Source xlsx file
         source: std::path::PathBuf,
-        /// Destination file (or - for stdout)
+        /// Destination file (default: - for stdout, which honours --output)
+        #[arg(default_value = "-")]
         dest: String,
         /// Sheet name (default: first sheet)
         #[arg(short, long)]
@@ -30,15 +32,32 @@ pub enum ExportCommand {
         /// Delimiter character
         #[arg(short, long, default_value = ",")]
         delimiter: char,
-        /// Export all sheets (creates multiple files with sheet name suffix)
+        /// Export all sheets (creates multiple files with sheet name suffix,
+        /// or one file per sheet when dest is a directory)
         #[arg(long)]
         all: bool,
+        /// With --all, skip sheets whose content is unchanged since the last run
+        #[arg(long, requires = "all")]
+        incremental: bool,
+        /// File recording sheet content hashes for --incremental
+        /// (default: .xlex-state.json)
+        #[arg(long, requires = "incremental")]
+        state: Option<std::path::PathBuf>,
+        /// Append only the rows after this row number to dest
+        #[arg(long, conflicts_with_all = ["all", "since_hash"])]
+        since_row: Option<u32>,
+        /// Append only the rows added since the last export recorded in this
+        /// state file, and record this one. Fails if the last exported row
+        /// has changed since
+        #[arg(long, value_name = "STATE", conflicts_with = "all")]
+        since_hash: Option<std::path::PathBuf>,
     },
     /// Export to JSON
     Json {
         /// Source xlsx file
         source: std::path::PathBuf,
-        /// Destination file (or - for stdout)
+        /// Destination file (default: - for stdout, which honours --output)
+        #[arg(default_value = "-")]
         dest: String,
         /// Sheet name (default: first sheet)
         #[arg(short, long)]
@@ -54,7 +73,8 @@ pub enum ExportCommand {
     Tsv {
         /// Source xlsx file
         source: std::path::PathBuf,
-        /// Destination file (or - for stdout)
+        /// Destination file (default: - for stdout, which honours --output)
+        #[arg(default_value = "-")]
         dest: String,
         /// Sheet name (default: first sheet)
         #[arg(short, long)]
@@ -62,12 +82,20 @@ pub enum ExportCommand {
         /// Export all sheets
         #[arg(long)]
         all: bool,
+        /// With --all, skip sheets whose content is unchanged since the last run
+        #[arg(long, requires = "all")]
+        incremental: bool,
+        /// File recording sheet content hashes for --incremental
+        /// (default: .xlex-state.json)
+        #[arg(long, requires = "incremental")]
+        state: Option<std::path::PathBuf>,
     },
     /// Export to YAML
     Yaml {
         /// Source xlsx file
         source: std::path::PathBuf,
-        /// Destination file (or - for stdout)
+        /// Destination file (default: - for stdout, which honours --output)
+        #[arg(default_value = "-")]
         dest: String,
         /// Sheet name (default: first sheet)
         #[arg(short, long)]
@@ -80,7 +108,8 @@ pub enum ExportCommand {
     Markdown {
         /// Source xlsx file
         source: std::path::PathBuf,
-        /// Destination file (or - for stdout)
+        /// Destination file (default: - for stdout, which honours --output)
+        #[arg(default_value = "-")]
         dest: String,
         /// Sheet name (default: first sheet)
         #[arg(short, long)]
@@ -93,7 +122,8 @@ pub enum ExportCommand {
     Ndjson {
         /// Source xlsx file
         source: std::path::PathBuf,
-        /// Destination file (or - for stdout)
+        /// Destination file (default: - for stdout, which honours --output)
+        #[arg(default_value = "-")]
         dest: String,
         /// Sheet name (default: first sheet)
         #[arg(short, long)]
@@ -105,15 +135,43 @@ pub enum ExportCommand {
         #[arg(long)]
         all: bool,
     },
+    /// Export every sheet to its own file, named from a template
+    All {
+        /// Source xlsx file
+        source: std::path::PathBuf,
+        /// File name template; {workbook}, {sheet} and {index} are filled
+        /// in and the extension picks the format (csv, tsv, json, ndjson,
+        /// yaml, md)
+        #[arg(long, default_value = "{workbook}_{sheet}.csv")]
+        out: String,
+        /// Use first row as keys (json, ndjson)
+        #[arg(long)]
+        header: bool,
+        /// What to do when a file already exists
+        #[arg(long, value_enum, default_value = "overwrite")]
+        if_exists: IfExists,
+    },
     /// Export workbook metadata
     Meta {
         /// Source xlsx file
         source: std::path::PathBuf,
-        /// Destination file (or - for stdout)
+        /// Destination file (default: - for stdout, which honours --output)
+        #[arg(default_value = "-")]
         dest: String,
     },
 }
 
+/// What `export all` does with files that already exist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum IfExists {
+    /// Replace the file
+    Overwrite,
+    /// Write next to it as name_2.ext, name_3.ext, ...
+    Suffix,
+    /// Fail before writing anything
+    Error,
+}
+
 /// Run export operations.
 pub fn run(args: &ExportArgs, global: &GlobalOptions) -> Result<()> {
     match &args.command {
@@ -123,9 +181,21 @@ pub fn run(args: &ExportArgs, global: &GlobalOptions) -> Result<()> {
             sheet,
             delimiter,
             all,
+            incremental,
+            state,
+            since_row,
+            since_hash,
         } => {
             if *all {
-                export_all_csv(source, dest, *delimiter, global)
+                let state = incremental.then(|| state_path(state.as_deref()));
+                export_all_csv(source, dest, *delimiter, state.as_deref(), global)
+            } else if since_row.is_some() || since_hash.is_some() {
+                let since = match (since_row, since_hash) {
+                    (Some(row), _) => AppendFrom::Row(*row),
+                    (None, Some(path)) => AppendFrom::State(path),
+                    (None, None) => unreachable!(),
+                };
+                append_csv(source, dest, sheet.as_deref(), *delimiter, since, global)
             } else {
                 export_csv(source, dest, sheet.as_deref(), *delimiter, global)
             }
@@ -148,9 +218,13 @@ pub fn run(args: &ExportArgs, global: &GlobalOptions) -> Result<()> {
             dest,
             sheet,
             all,
+            incremental,
+            state,
         } => {
             if *all {
-                export_all_csv(source, dest, '\t', global) // TSV is CSV with tab
+                // TSV is CSV with tab
+                let state = incremental.then(|| state_path(state.as_deref()));
+                export_all_csv(source, dest, '\t', state.as_deref(), global)
             } else {
                 export_tsv(source, dest, sheet.as_deref(), global)
             }
@@ -192,16 +266,195 @@ pub fn run(args: &ExportArgs, global: &GlobalOptions) -> Result<()> {
                 export_ndjson(source, dest, sheet.as_deref(), *header, global)
             }
         }
+        ExportCommand::All {
+            source,
+            out,
+            header,
+            if_exists,
+        } => export_all(source, out, *header, *if_exists, global),
         ExportCommand::Meta { source, dest } => export_meta(source, dest, global),
     }
 }
 
+/// A sheet name with the characters that cannot appear in file names
+/// replaced by `_`.
+fn file_name_part(sheet: &str) -> String {
+    sheet
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Fills in an `export all` file name template for one sheet.
+fn expand_out_template(template: &str, workbook: &str, sheet: &str, index: usize) -> String {
+    template
+        .replace("{workbook}", workbook)
+        .replace("{sheet}", &file_name_part(sheet))
+        .replace("{index}", &index.to_string())
+}
+
+/// `path` with `_n` added before its extension.
+fn numbered_path(path: &std::path::Path, n: usize) -> std::path::PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name = match path.extension() {
+        Some(ext) => format!("{}_{}.{}", stem, n, ext.to_string_lossy()),
+        None => format!("{}_{}", stem, n),
+    };
+    path.with_file_name(name)
+}
+
+/// Output paths for each sheet. Sheets whose names expand to the same path
+/// are numbered `_2`, `_3`, ...; existing files are handled per `if_exists`.
+fn export_all_paths(
+    template: &str,
+    workbook: &str,
+    sheet_names: &[String],
+    if_exists: IfExists,
+) -> Result<Vec<std::path::PathBuf>> {
+    let mut paths: Vec<std::path::PathBuf> = Vec::new();
+    for (index, sheet_name) in sheet_names.iter().enumerate() {
+        let base = std::path::PathBuf::from(expand_out_template(
+            template,
+            workbook,
+            sheet_name,
+            index + 1,
+        ));
+        let taken = |path: &std::path::Path| {
+            paths.iter().any(|p| p == path) || (if_exists == IfExists::Suffix && path.exists())
+        };
+        let mut path = base.clone();
+        let mut n = 2;
+        while taken(&path) {
+            path = numbered_path(&base, n);
+            n += 1;
+        }
+        if if_exists == IfExists::Error && path.exists() {
+            anyhow::bail!("{} already exists", path.display());
+        }
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+fn export_all(
+    source: &std::path::Path,
+    template: &str,
+    header: bool,
+    if_exists: IfExists,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let extension = std::path::Path::new(template)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if !matches!(
+        extension.as_str(),
+        "csv" | "tsv" | "json" | "ndjson" | "yaml" | "yml" | "md" | "markdown"
+    ) {
+        anyhow::bail!(
+            "Cannot tell the export format from '{}': use a .csv, .tsv, .json, .ndjson, .yaml or .md extension",
+            template
+        );
+    }
+
+    let report = CommandOutput::new("export");
+    let per_sheet = per_sheet_options(global);
+    let workbook = Workbook::open(source)?;
+    let sheet_names: Vec<String> = workbook
+        .sheet_names()
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let workbook_name = source
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let paths = export_all_paths(template, &workbook_name, &sheet_names, if_exists)?;
+
+    if global.dry_run {
+        for (sheet_name, path) in sheet_names.iter().zip(&paths) {
+            outln!("Would export sheet '{}' to {}", sheet_name, path.display());
+        }
+        return Ok(());
+    }
+
+    for (sheet_name, path) in sheet_names.iter().zip(&paths) {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let dest = path.to_string_lossy();
+        let sheet = Some(sheet_name.as_str());
+        match extension.as_str() {
+            "csv" => export_csv(source, &dest, sheet, ',', &per_sheet)?,
+            "tsv" => export_tsv(source, &dest, sheet, &per_sheet)?,
+            "json" => export_json(source, &dest, sheet, header, &per_sheet)?,
+            "ndjson" => export_ndjson(source, &dest, sheet, header, &per_sheet)?,
+            "yaml" | "yml" => export_yaml(source, &dest, sheet, &per_sheet)?,
+            _ => export_markdown(source, &dest, sheet, &per_sheet)?,
+        }
+    }
+
+    report
+        .file(source)
+        .target("template", template)
+        .field(
+            "files",
+            paths
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>(),
+        )
+        .count("sheets", sheet_names.len())
+        .print(
+            global,
+            format!("{} Exported {} sheets", "✓".green(), sheet_names.len()),
+        )?;
+
+    Ok(())
+}
+
+/// The `--state` file, defaulting to `.xlex-state.json`.
+fn state_path(state: Option<&std::path::Path>) -> std::path::PathBuf {
+    state
+        .map(std::path::Path::to_path_buf)
+        .unwrap_or_else(|| std::path::PathBuf::from(".xlex-state.json"))
+}
+
+/// Sheet content hashes recorded by `--incremental` exports, keyed by
+/// workbook path and then sheet name.
+type ExportState = std::collections::BTreeMap<String, std::collections::BTreeMap<String, String>>;
+
+fn read_export_state(path: &std::path::Path) -> Result<ExportState> {
+    if !path.exists() {
+        return Ok(ExportState::new());
+    }
+    let content = std::fs::read_to_string(path)?;
+    serde_json::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("Invalid state file {}: {}", path.display(), e))
+}
+
 fn export_all_csv(
     source: &std::path::Path,
     dest: &str,
     delimiter: char,
+    state: Option<&std::path::Path>,
     global: &GlobalOptions,
 ) -> Result<()> {
+    // One file per sheet has no single stream to go to
+    if dest == "-" {
+        anyhow::bail!(
+            "Exporting every sheet writes one file per sheet; give a directory (out/) or a file name to derive them from (out.{})",
+            if delimiter == '\t' { "tsv" } else { "csv" }
+        );
+    }
+    let report = CommandOutput::new("export");
+    let per_sheet = per_sheet_options(global);
     let workbook = Workbook::open(source)?;
     let sheet_names: Vec<String> = workbook
         .sheet_names()
@@ -209,42 +462,107 @@ fn export_all_csv(
         .map(|s| s.to_string())
         .collect();
 
-    // Create output directory based on dest name
+    // Into a directory as <sheet>.<ext>, or next to dest as <stem>_<sheet>.<ext>
     let base_path = std::path::Path::new(dest);
-    let parent = base_path.parent().unwrap_or(std::path::Path::new("."));
-    let stem = base_path
-        .file_stem()
-        .map(|s| s.to_string_lossy().to_string())
-        .unwrap_or_else(|| "export".to_string());
-    let ext = base_path
-        .extension()
-        .map(|s| s.to_string_lossy().to_string())
-        .unwrap_or_else(|| "csv".to_string());
+    let default_ext = if delimiter == '\t' { "tsv" } else { "csv" };
+    let into_dir = dest.ends_with('/') || dest.ends_with('\\') || base_path.is_dir();
+    let output_path = |sheet_name: &str| {
+        let sheet_part = file_name_part(sheet_name).replace(' ', "_");
+        if into_dir {
+            return base_path.join(format!("{}.{}", sheet_part, default_ext));
+        }
+        let parent = base_path.parent().unwrap_or(std::path::Path::new("."));
+        let stem = base_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "export".to_string());
+        let ext = base_path
+            .extension()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| default_ext.to_string());
+        parent.join(format!("{}_{}.{}", stem, sheet_part, ext))
+    };
+    if into_dir && !global.dry_run {
+        std::fs::create_dir_all(base_path)?;
+    }
+
+    let mut export_state = match state {
+        Some(path) => read_export_state(path)?,
+        None => ExportState::new(),
+    };
+    let workbook_key = source.display().to_string();
+    let previous = export_state.remove(&workbook_key).unwrap_or_default();
+    let mut hashes = std::collections::BTreeMap::new();
+    let mut skipped = Vec::new();
 
     for sheet_name in &sheet_names {
-        let output_path = parent.join(format!("{}_{}.{}", stem, sheet_name.replace(' ', "_"), ext));
+        let output_path = output_path(sheet_name);
+        if state.is_some() {
+            let hash = workbook
+                .get_sheet(sheet_name)
+                .map(|sheet| format!("{:016x}", sheet.content_hash()))
+                .unwrap_or_default();
+            let unchanged = previous.get(sheet_name) == Some(&hash) && output_path.exists();
+            hashes.insert(sheet_name.clone(), hash);
+            if unchanged {
+                skipped.push(sheet_name.clone());
+                continue;
+            }
+        }
         export_csv(
             source,
             &output_path.to_string_lossy(),
             Some(sheet_name),
             delimiter,
-            global,
+            &per_sheet,
         )?;
     }
 
-    if !global.quiet {
-        println!("{} Exported {} sheets", "✓".green(), sheet_names.len());
+    if let Some(path) = state.filter(|_| !global.dry_run) {
+        export_state.insert(workbook_key, hashes);
+        std::fs::write(path, serde_json::to_string_pretty(&export_state)?)?;
     }
 
+    let exported = sheet_names.len() - skipped.len();
+    let mut report = report
+        .file(source)
+        .target("destination", dest)
+        .count("sheets", exported);
+    if state.is_some() {
+        report = report
+            .count("skipped", skipped.len())
+            .field("skipped", skipped.clone());
+    }
+    let message = if skipped.is_empty() {
+        format!("{} Exported {} sheets", "✓".green(), exported)
+    } else {
+        format!(
+            "{} Exported {} sheets, {} unchanged",
+            "✓".green(),
+            exported,
+            skipped.len()
+        )
+    };
+    report.print(global, message)?;
+
     Ok(())
 }
 
+/// Options for the per-sheet exports of an all-sheets export. In JSON mode
+/// only the summary of the whole export is printed.
+fn per_sheet_options(global: &GlobalOptions) -> GlobalOptions {
+    let mut options = global.clone();
+    options.quiet |= global.format == OutputFormat::Json;
+    options
+}
+
 fn export_all_json(
     source: &std::path::Path,
     dest: &str,
     header: bool,
     global: &GlobalOptions,
 ) -> Result<()> {
+    let report = CommandOutput::new("export");
     let workbook = Workbook::open(source)?;
     let sheet_names: Vec<String> = workbook
         .sheet_names()
@@ -271,19 +589,28 @@ fn export_all_json(
     let json_str = serde_json::to_string_pretty(&serde_json::Value::Object(combined))?;
     write_output(dest, &json_str, global)?;
 
-    if !global.quiet && dest != "-" {
-        println!(
-            "{} Exported {} sheets to {}",
-            "✓".green(),
-            sheet_names.len(),
-            dest
-        );
+    if dest != "-" {
+        report
+            .file(source)
+            .target("destination", dest)
+            .count("sheets", sheet_names.len())
+            .print(
+                global,
+                format!(
+                    "{} Exported {} sheets to {}",
+                    "✓".green(),
+                    sheet_names.len(),
+                    dest
+                ),
+            )?;
     }
 
     Ok(())
 }
 
 fn export_all_yaml(source: &std::path::Path, dest: &str, global: &GlobalOptions) -> Result<()> {
+    let report = CommandOutput::new("export");
+    let per_sheet = per_sheet_options(global);
     let workbook = Workbook::open(source)?;
     let sheet_names: Vec<String> = workbook
         .sheet_names()
@@ -308,18 +635,25 @@ fn export_all_yaml(source: &std::path::Path, dest: &str, global: &GlobalOptions)
             source,
             &output_path.to_string_lossy(),
             Some(sheet_name),
-            global,
+            &per_sheet,
         )?;
     }
 
-    if !global.quiet {
-        println!("{} Exported {} sheets", "✓".green(), sheet_names.len());
-    }
+    report
+        .file(source)
+        .target("destination", dest)
+        .count("sheets", sheet_names.len())
+        .print(
+            global,
+            format!("{} Exported {} sheets", "✓".green(), sheet_names.len()),
+        )?;
 
     Ok(())
 }
 
 fn export_all_markdown(source: &std::path::Path, dest: &str, global: &GlobalOptions) -> Result<()> {
+    let report = CommandOutput::new("export");
+    let per_sheet = per_sheet_options(global);
     let workbook = Workbook::open(source)?;
     let sheet_names: Vec<String> = workbook
         .sheet_names()
@@ -344,13 +678,18 @@ fn export_all_markdown(source: &std::path::Path, dest: &str, global: &GlobalOpti
             source,
             &output_path.to_string_lossy(),
             Some(sheet_name),
-            global,
+            &per_sheet,
         )?;
     }
 
-    if !global.quiet {
-        println!("{} Exported {} sheets", "✓".green(), sheet_names.len());
-    }
+    report
+        .file(source)
+        .target("destination", dest)
+        .count("sheets", sheet_names.len())
+        .print(
+            global,
+            format!("{} Exported {} sheets", "✓".green(), sheet_names.len()),
+        )?;
 
     Ok(())
 }
@@ -361,6 +700,8 @@ fn export_all_ndjson(
     header: bool,
     global: &GlobalOptions,
 ) -> Result<()> {
+    let report = CommandOutput::new("export");
+    let per_sheet = per_sheet_options(global);
     let workbook = Workbook::open(source)?;
     let sheet_names: Vec<String> = workbook
         .sheet_names()
@@ -386,13 +727,18 @@ fn export_all_ndjson(
             &output_path.to_string_lossy(),
             Some(sheet_name),
             header,
-            global,
+            &per_sheet,
         )?;
     }
 
-    if !global.quiet {
-        println!("{} Exported {} sheets", "✓".green(), sheet_names.len());
-    }
+    report
+        .file(source)
+        .target("destination", dest)
+        .count("sheets", sheet_names.len())
+        .print(
+            global,
+            format!("{} Exported {} sheets", "✓".green(), sheet_names.len()),
+        )?;
 
     Ok(())
 }
@@ -403,8 +749,6 @@ fn export_sheet_to_json_value(
     max_row: u32,
     header: bool,
 ) -> serde_json::Value {
-    use xlex_core::CellValue;
-
     if header && max_row > 0 {
         // Export as array of objects
         let mut headers: Vec<String> = Vec::new();
@@ -420,13 +764,7 @@ fn export_sheet_to_json_value(
             for (idx, col) in (1..=max_col).enumerate() {
                 let cell_ref = xlex_core::CellRef::new(col, row);
                 let value = sheet.get_value(&cell_ref);
-                let json_value = match value {
-                    CellValue::Empty => serde_json::Value::Null,
-                    CellValue::String(s) => serde_json::Value::String(s),
-                    CellValue::Number(n) => serde_json::json!(n),
-                    CellValue::Boolean(b) => serde_json::Value::Bool(b),
-                    _ => serde_json::Value::String(value.to_display_string()),
-                };
+                let json_value = serde_json::Value::from(value);
                 if idx < headers.len() {
                     obj.insert(headers[idx].clone(), json_value);
                 }
@@ -442,13 +780,7 @@ fn export_sheet_to_json_value(
             for col in 1..=max_col {
                 let cell_ref = xlex_core::CellRef::new(col, row);
                 let value = sheet.get_value(&cell_ref);
-                let json_value = match value {
-                    CellValue::Empty => serde_json::Value::Null,
-                    CellValue::String(s) => serde_json::Value::String(s),
-                    CellValue::Number(n) => serde_json::json!(n),
-                    CellValue::Boolean(b) => serde_json::Value::Bool(b),
-                    _ => serde_json::Value::String(value.to_display_string()),
-                };
+                let json_value = serde_json::Value::from(value);
                 row_values.push(json_value);
             }
             rows.push(serde_json::Value::Array(row_values));
@@ -464,6 +796,7 @@ fn export_csv(
     delimiter: char,
     global: &GlobalOptions,
 ) -> Result<()> {
+    let report = CommandOutput::new("export");
     let workbook = Workbook::open(source)?;
     let sheet_name = sheet
         .or_else(|| workbook.sheet_names().first().copied())
@@ -480,15 +813,7 @@ fn export_csv(
     let (max_col, max_row) = sheet_obj.dimensions();
 
     // Create progress for large exports
-    let progress = if max_row > 100 {
-        Some(Progress::bar(
-            max_row as u64,
-            "Exporting to CSV...",
-            global.quiet,
-        ))
-    } else {
-        None
-    };
+    let progress = Progress::rows(max_row as usize, "Exporting to CSV...", global);
 
     let mut output = String::new();
     for row in 1..=max_row {
@@ -496,14 +821,7 @@ fn export_csv(
         for col in 1..=max_col {
             let cell_ref = xlex_core::CellRef::new(col, row);
             let value = sheet_obj.get_value(&cell_ref);
-            let str_value = value.to_display_string();
-            // Escape if contains delimiter or newline
-            if str_value.contains(delimiter) || str_value.contains('\n') || str_value.contains('"')
-            {
-                row_values.push(format!("\"{}\"", str_value.replace('"', "\"\"")));
-            } else {
-                row_values.push(str_value);
-            }
+            row_values.push(csv_field(value.to_display_string(), delimiter));
         }
         output.push_str(&row_values.join(&delimiter.to_string()));
         output.push('\n');
@@ -519,8 +837,239 @@ fn export_csv(
 
     write_output(dest, &output, global)?;
 
-    if !global.quiet && dest != "-" {
-        println!("Exported {} rows to {}", max_row.to_string().green(), dest);
+    if dest != "-" {
+        report
+            .file(source)
+            .target("destination", dest)
+            .count("rows", max_row)
+            .print(
+                global,
+                format!("Exported {} rows to {}", max_row.to_string().green(), dest),
+            )?;
+    }
+
+    Ok(())
+}
+
+/// Quotes a CSV field if it contains the delimiter, a newline or a quote.
+fn csv_field(value: String, delimiter: char) -> String {
+    if value.contains(delimiter) || value.contains('\n') || value.contains('"') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// Where an appending CSV export starts.
+enum AppendFrom<'a> {
+    /// After this row
+    Row(u32),
+    /// After the last row recorded in this state file
+    State(&'a std::path::Path),
+}
+
+/// Last rows appended by `--since-hash` exports, keyed by workbook path and
+/// then sheet name.
+type AppendState =
+    std::collections::BTreeMap<String, std::collections::BTreeMap<String, AppendMark>>;
+
+/// The last row an appending export wrote.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AppendMark {
+    /// Row number
+    last_row: u32,
+    /// Hash of the row's cells, which tells a sheet that was only appended
+    /// to from one whose earlier rows changed
+    row_hash: String,
+}
+
+fn read_append_state(path: &std::path::Path) -> Result<AppendState> {
+    if !path.exists() {
+        return Ok(AppendState::new());
+    }
+    let content = std::fs::read_to_string(path)?;
+    serde_json::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("Invalid state file {}: {}", path.display(), e))
+}
+
+/// Hashes a row's non-empty cells, ignoring the sheet's column count.
+fn row_hash(row: Option<&xlex_core::lazy::StreamRow>) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    for (cell, value) in row.map(|r| r.cells.as_slice()).unwrap_or_default() {
+        let text = value.to_display_string();
+        if !text.is_empty() {
+            hasher.update(format!("{}={}\n", cell.to_a1(), text).as_bytes());
+        }
+    }
+    let hex: String = hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("sha256:{}", hex)
+}
+
+/// Appends the rows of a sheet after a given row to `dest`, treating the
+/// sheet as an append-only log.
+///
+/// Only the new rows are parsed: the sheet's row index (from its index
+/// file, when there is one) locates the first of them, and each row is
+/// written as soon as it is read. With a state file, the last row of the
+/// previous export must be unchanged, so a sheet whose rows were removed
+/// or rewritten from there on is reported instead of appended to. The new
+/// state is written to a temporary file first and renamed over the state
+/// file once the rows are appended.
+fn append_csv(
+    source: &std::path::Path,
+    dest: &str,
+    sheet: Option<&str>,
+    delimiter: char,
+    from: AppendFrom,
+    global: &GlobalOptions,
+) -> Result<()> {
+    use std::io::Write;
+
+    let report = CommandOutput::new("export");
+    let workbook = xlex_core::LazyWorkbook::open(source)?;
+    let sheet_name = sheet
+        .or_else(|| workbook.sheet_names().first().map(String::as_str))
+        .ok_or_else(|| anyhow::anyhow!("No sheets in workbook"))?
+        .to_string();
+    let index = workbook.row_index(&sheet_name)?;
+    let (max_col, last_row) = index
+        .dimension
+        .as_ref()
+        .map_or((0, 0), |d| (d.end.col, index.last_row));
+    let row_range = |first: u32, last: u32| {
+        xlex_core::Range::new(
+            xlex_core::CellRef::new(1, first),
+            xlex_core::CellRef::new(max_col.max(1), last),
+        )
+    };
+
+    // The same workbook reached by another path shares its marks
+    let workbook_key = std::fs::canonicalize(source)?.display().to_string();
+    let mut state = match from {
+        AppendFrom::State(path) => Some((path, read_append_state(path)?)),
+        AppendFrom::Row(_) => None,
+    };
+    let after = match (&from, &state) {
+        (AppendFrom::Row(row), _) => *row,
+        (AppendFrom::State(path), Some((_, recorded))) => {
+            match recorded.get(&workbook_key).and_then(|s| s.get(&sheet_name)) {
+                None => 0,
+                Some(mark) => {
+                    let rows = workbook
+                        .stream_rows_in(&sheet_name, &row_range(mark.last_row, mark.last_row))?;
+                    if mark.last_row > last_row || row_hash(rows.first()) != mark.row_hash {
+                        return Err(xlex_core::XlexError::InvalidOperation {
+                            message: format!(
+                                "Row {} of sheet '{}' changed since the export recorded in {}; \
+                                 the sheet is not append-only, export it in full instead",
+                                mark.last_row,
+                                sheet_name,
+                                path.display()
+                            ),
+                        }
+                        .into());
+                    }
+                    mark.last_row
+                }
+            }
+        }
+        (AppendFrom::State(_), None) => unreachable!(),
+    };
+    let appended = last_row.saturating_sub(after);
+
+    if !global.dry_run && appended > 0 {
+        // Record the new mark before appending, then commit it by renaming
+        let pending_state = match state.as_mut() {
+            Some((path, recorded)) => {
+                let last = workbook.stream_rows_in(&sheet_name, &row_range(last_row, last_row))?;
+                recorded.entry(workbook_key).or_default().insert(
+                    sheet_name.clone(),
+                    AppendMark {
+                        last_row,
+                        row_hash: row_hash(last.first()),
+                    },
+                );
+                let mut temp = path.as_os_str().to_owned();
+                temp.push(".tmp");
+                let temp = std::path::PathBuf::from(temp);
+                std::fs::write(&temp, serde_json::to_string_pretty(recorded)?)?;
+                Some((temp, *path))
+            }
+            None => None,
+        };
+
+        let mut file = match dest {
+            "-" => None,
+            _ => Some(std::io::BufWriter::new(
+                std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(dest)?,
+            )),
+        };
+        let mut write_line = |line: &str| -> std::io::Result<()> {
+            match file.as_mut() {
+                Some(file) => writeln!(file, "{}", line),
+                None => {
+                    outln!("{}", line);
+                    Ok(())
+                }
+            }
+        };
+        // Rows without cells are missing from the sheet but still get a line
+        let blank = vec![""; max_col as usize].join(&delimiter.to_string());
+        let mut next_row = after + 1;
+        workbook.for_each_row_in(&sheet_name, &row_range(after + 1, last_row), |row| {
+            for _ in next_row..row.row_number {
+                write_line(&blank)?;
+            }
+            let mut values = vec![String::new(); max_col as usize];
+            for (cell, value) in &row.cells {
+                values[cell.col as usize - 1] = csv_field(value.to_display_string(), delimiter);
+            }
+            write_line(&values.join(&delimiter.to_string()))?;
+            next_row = row.row_number + 1;
+            Ok(())
+        })?;
+        for _ in next_row..=last_row {
+            write_line(&blank)?;
+        }
+        if let Some(file) = file.as_mut() {
+            file.flush()?;
+        }
+
+        if let Some((temp, path)) = pending_state {
+            std::fs::rename(temp, path)?;
+        }
+    }
+
+    if dest != "-" {
+        let message = if appended == 0 {
+            format!("No rows after row {} to append to {}", after, dest)
+        } else {
+            format!(
+                "Appended {} rows ({}-{}) to {}",
+                appended.to_string().green(),
+                after + 1,
+                last_row,
+                dest
+            )
+        };
+        report
+            .file(source)
+            .target("sheet", sheet_name.as_str())
+            .target("destination", dest)
+            .count("rows", appended)
+            .field("fromRow", after + 1)
+            .field("lastRow", last_row)
+            .print(global, message)?;
     }
 
     Ok(())
@@ -533,6 +1082,7 @@ fn export_json(
     has_header: bool,
     global: &GlobalOptions,
 ) -> Result<()> {
+    let report = CommandOutput::new("export");
     let workbook = Workbook::open(source)?;
     let sheet_name = sheet
         .or_else(|| workbook.sheet_names().first().copied())
@@ -547,6 +1097,7 @@ fn export_json(
 
     // Get dimensions
     let (max_col, max_row) = sheet_obj.dimensions();
+    let progress = Progress::rows(max_row as usize, "Exporting to JSON...", global);
 
     let data = if has_header && max_row > 0 {
         // Use first row as keys
@@ -563,9 +1114,12 @@ fn export_json(
             for (col_idx, header) in headers.iter().enumerate() {
                 let cell_ref = xlex_core::CellRef::new((col_idx + 1) as u32, row);
                 let value = sheet_obj.get_value(&cell_ref);
-                obj.insert(header.clone(), cell_to_json(&value));
+                obj.insert(header.clone(), value.into());
             }
             rows.push(serde_json::Value::Object(obj));
+            if let Some(ref pb) = progress {
+                pb.inc(1);
+            }
         }
         serde_json::Value::Array(rows)
     } else {
@@ -576,18 +1130,27 @@ fn export_json(
             for col in 1..=max_col {
                 let cell_ref = xlex_core::CellRef::new(col, row);
                 let value = sheet_obj.get_value(&cell_ref);
-                row_values.push(cell_to_json(&value));
+                row_values.push(value.into());
             }
             rows.push(serde_json::Value::Array(row_values));
+            if let Some(ref pb) = progress {
+                pb.inc(1);
+            }
         }
         serde_json::Value::Array(rows)
     };
+    if let Some(ref pb) = progress {
+        pb.finish_and_clear();
+    }
 
     let output = serde_json::to_string_pretty(&data)?;
     write_output(dest, &output, global)?;
 
-    if !global.quiet && dest != "-" {
-        println!("Exported JSON to {}", dest);
+    if dest != "-" {
+        report
+            .file(source)
+            .target("destination", dest)
+            .print(global, format!("Exported JSON to {}", dest))?;
     }
 
     Ok(())
@@ -608,6 +1171,7 @@ fn export_yaml(
     sheet: Option<&str>,
     global: &GlobalOptions,
 ) -> Result<()> {
+    let report = CommandOutput::new("export");
     let workbook = Workbook::open(source)?;
     let sheet_name = sheet
         .or_else(|| workbook.sheet_names().first().copied())
@@ -629,7 +1193,7 @@ fn export_yaml(
         for col in 1..=max_col {
             let cell_ref = xlex_core::CellRef::new(col, row);
             let value = sheet_obj.get_value(&cell_ref);
-            row_values.push(cell_to_json(&value));
+            row_values.push(value.into());
         }
         rows.push(row_values);
     }
@@ -637,8 +1201,11 @@ fn export_yaml(
     let output = serde_yaml::to_string(&rows)?;
     write_output(dest, &output, global)?;
 
-    if !global.quiet && dest != "-" {
-        println!("Exported YAML to {}", dest);
+    if dest != "-" {
+        report
+            .file(source)
+            .target("destination", dest)
+            .print(global, format!("Exported YAML to {}", dest))?;
     }
 
     Ok(())
@@ -650,6 +1217,7 @@ fn export_markdown(
     sheet: Option<&str>,
     global: &GlobalOptions,
 ) -> Result<()> {
+    let report = CommandOutput::new("export");
     let workbook = Workbook::open(source)?;
     let sheet_name = sheet
         .or_else(|| workbook.sheet_names().first().copied())
@@ -695,26 +1263,19 @@ fn export_markdown(
 
     write_output(dest, &output, global)?;
 
-    if !global.quiet && dest != "-" {
-        println!("Exported Markdown to {}", dest);
+    if dest != "-" {
+        report
+            .file(source)
+            .target("destination", dest)
+            .print(global, format!("Exported Markdown to {}", dest))?;
     }
 
     Ok(())
 }
 
-fn cell_to_json(value: &xlex_core::CellValue) -> serde_json::Value {
-    match value {
-        xlex_core::CellValue::Empty => serde_json::Value::Null,
-        xlex_core::CellValue::String(s) => serde_json::Value::String(s.clone()),
-        xlex_core::CellValue::Number(n) => serde_json::json!(*n),
-        xlex_core::CellValue::Boolean(b) => serde_json::Value::Bool(*b),
-        _ => serde_json::Value::String(value.to_display_string()),
-    }
-}
-
 fn write_output(dest: &str, content: &str, _global: &GlobalOptions) -> Result<()> {
     if dest == "-" {
-        print!("{}", content);
+        out!("{}", content);
     } else {
         std::fs::write(dest, content)?;
     }
@@ -728,6 +1289,7 @@ fn export_ndjson(
     has_header: bool,
     global: &GlobalOptions,
 ) -> Result<()> {
+    let report = CommandOutput::new("export");
     let workbook = Workbook::open(source)?;
     let sheet_name = sheet
         .or_else(|| workbook.sheet_names().first().copied())
@@ -742,6 +1304,7 @@ fn export_ndjson(
 
     // Get dimensions
     let (max_col, max_row) = sheet_obj.dimensions();
+    let progress = Progress::rows(max_row as usize, "Exporting to NDJSON...", global);
 
     let mut output = String::new();
 
@@ -759,10 +1322,13 @@ fn export_ndjson(
             for (col_idx, header) in headers.iter().enumerate() {
                 let cell_ref = xlex_core::CellRef::new((col_idx + 1) as u32, row);
                 let value = sheet_obj.get_value(&cell_ref);
-                obj.insert(header.clone(), cell_to_json(&value));
+                obj.insert(header.clone(), value.into());
             }
             output.push_str(&serde_json::to_string(&serde_json::Value::Object(obj))?);
             output.push('\n');
+            if let Some(ref pb) = progress {
+                pb.inc(1);
+            }
         }
     } else {
         // Array of arrays, one per line
@@ -771,29 +1337,43 @@ fn export_ndjson(
             for col in 1..=max_col {
                 let cell_ref = xlex_core::CellRef::new(col, row);
                 let value = sheet_obj.get_value(&cell_ref);
-                row_values.push(cell_to_json(&value));
+                row_values.push(value.into());
             }
             output.push_str(&serde_json::to_string(&serde_json::Value::Array(
                 row_values,
             ))?);
             output.push('\n');
+            if let Some(ref pb) = progress {
+                pb.inc(1);
+            }
         }
     }
+    if let Some(ref pb) = progress {
+        pb.finish_and_clear();
+    }
 
     write_output(dest, &output, global)?;
 
-    if !global.quiet && dest != "-" {
-        println!(
-            "Exported {} rows to NDJSON {}",
-            max_row.to_string().green(),
-            dest
-        );
+    if dest != "-" {
+        report
+            .file(source)
+            .target("destination", dest)
+            .count("rows", max_row)
+            .print(
+                global,
+                format!(
+                    "Exported {} rows to NDJSON {}",
+                    max_row.to_string().green(),
+                    dest
+                ),
+            )?;
     }
 
     Ok(())
 }
 
 fn export_meta(source: &std::path::Path, dest: &str, global: &GlobalOptions) -> Result<()> {
+    let report = CommandOutput::new("export");
     let workbook = Workbook::open(source)?;
 
     // Build metadata
@@ -832,8 +1412,11 @@ fn export_meta(source: &std::path::Path, dest: &str, global: &GlobalOptions) ->
     let output = serde_json::to_string_pretty(&meta)?;
     write_output(dest, &output, global)?;
 
-    if !global.quiet && dest != "-" {
-        println!("Exported metadata to {}", dest);
+    if dest != "-" {
+        report
+            .file(source)
+            .target("destination", dest)
+            .print(global, format!("Exported metadata to {}", dest))?;
     }
 
     Ok(())
@@ -855,6 +1438,11 @@ mod tests {
             json_errors: false,
             dry_run: false,
             output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
         }
     }
 
@@ -1075,18 +1663,21 @@ mod tests {
     }
 
     #[test]
-    fn test_cell_to_json() {
-        assert_eq!(cell_to_json(&CellValue::Empty), serde_json::Value::Null);
+    fn test_cell_value_to_json() {
         assert_eq!(
-            cell_to_json(&CellValue::String("test".to_string())),
+            serde_json::Value::from(&CellValue::Empty),
+            serde_json::Value::Null
+        );
+        assert_eq!(
+            serde_json::Value::from(&CellValue::String("test".to_string())),
             serde_json::Value::String("test".to_string())
         );
         assert_eq!(
-            cell_to_json(&CellValue::Number(42.0)),
+            serde_json::Value::from(&CellValue::Number(42.0)),
             serde_json::json!(42.0)
         );
         assert_eq!(
-            cell_to_json(&CellValue::Boolean(true)),
+            serde_json::Value::from(&CellValue::Boolean(true)),
             serde_json::Value::Bool(true)
         );
     }
@@ -1181,10 +1772,154 @@ mod tests {
         setup_test_data(&file_path);
 
         let dest = temp_dir.path().join("output_all.csv");
-        let result = export_all_csv(&file_path, &dest.to_string_lossy(), ',', &default_global());
+        let result = export_all_csv(
+            &file_path,
+            &dest.to_string_lossy(),
+            ',',
+            None,
+            &default_global(),
+        );
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_export_all_csv_to_stdout() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "test_all.xlsx");
+        setup_test_data(&file_path);
+
+        let err = export_all_csv(&file_path, "-", ',', None, &default_global()).unwrap_err();
+        assert!(err.to_string().contains("one file per sheet"));
+        assert!(!std::path::Path::new("-_Sheet1.csv").exists());
+    }
+
+    #[test]
+    fn test_export_all_csv_incremental() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "incremental.xlsx");
+        setup_test_data(&file_path);
+        let mut wb = Workbook::open(&file_path).unwrap();
+        wb.add_sheet("Other").unwrap();
+        wb.save().unwrap();
+
+        let out = temp_dir.path().join("out");
+        let state = temp_dir.path().join("state.json");
+        let dest = format!("{}/", out.display());
+        let export = || export_all_csv(&file_path, &dest, ',', Some(&state), &default_global());
+        export().unwrap();
+        assert!(out.join("Sheet1.csv").exists());
+        assert!(out.join("Other.csv").exists());
+        let recorded = read_export_state(&state).unwrap();
+        assert_eq!(recorded[&file_path.display().to_string()].len(), 2);
+
+        // Unchanged sheets are not rewritten
+        std::fs::write(out.join("Sheet1.csv"), "stale").unwrap();
+        std::fs::write(out.join("Other.csv"), "stale").unwrap();
+        let mut wb = Workbook::open(&file_path).unwrap();
+        wb.set_cell(
+            "Other",
+            xlex_core::CellRef::new(1, 1),
+            CellValue::Number(1.0),
+        )
+        .unwrap();
+        wb.save().unwrap();
+        export().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(out.join("Sheet1.csv")).unwrap(),
+            "stale"
+        );
+        assert_eq!(
+            std::fs::read_to_string(out.join("Other.csv")).unwrap(),
+            "1\n"
+        );
+
+        // A missing output is written again even when unchanged
+        std::fs::remove_file(out.join("Sheet1.csv")).unwrap();
+        export().unwrap();
+        assert!(std::fs::read_to_string(out.join("Sheet1.csv"))
+            .unwrap()
+            .contains("Alice"));
+    }
+
+    #[test]
+    fn test_append_csv() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "log.xlsx");
+        setup_test_data(&file_path);
+        let dest = temp_dir.path().join("log.csv");
+        let dest_str = dest.to_string_lossy().to_string();
+        let state = temp_dir.path().join("append.json");
+        let append = || {
+            append_csv(
+                &file_path,
+                &dest_str,
+                None,
+                ',',
+                AppendFrom::State(&state),
+                &default_global(),
+            )
+        };
+        let add_row = |row: u32, name: &str| {
+            let mut wb = Workbook::open(&file_path).unwrap();
+            wb.set_cell(
+                "Sheet1",
+                xlex_core::CellRef::new(1, row),
+                CellValue::string(name),
+            )
+            .unwrap();
+            wb.save().unwrap();
+        };
+
+        // The first export writes everything, later ones only new rows
+        append().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&dest).unwrap(),
+            "Name,Age\nAlice,30\nBob,25\n"
+        );
+        append().unwrap();
+        add_row(5, "Dan");
+        append().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&dest).unwrap(),
+            "Name,Age\nAlice,30\nBob,25\n,\nDan,\n"
+        );
+        let recorded = read_append_state(&state).unwrap();
+        let key = std::fs::canonicalize(&file_path).unwrap();
+        assert_eq!(recorded[&key.display().to_string()]["Sheet1"].last_row, 5);
+        assert!(!temp_dir.path().join("append.json.tmp").exists());
+
+        // The same workbook reached by another path picks up the same mark
+        append_csv(
+            &temp_dir.path().join(".").join("log.xlsx"),
+            &dest_str,
+            None,
+            ',',
+            AppendFrom::State(&state),
+            &default_global(),
+        )
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&dest).unwrap(),
+            "Name,Age\nAlice,30\nBob,25\n,\nDan,\n"
+        );
+
+        // Rewriting an exported row is an error, not a silent skip
+        add_row(5, "Eve");
+        assert!(append().unwrap_err().to_string().contains("Row 5"));
+
+        let since = temp_dir.path().join("since.csv");
+        append_csv(
+            &file_path,
+            &since.to_string_lossy(),
+            None,
+            ',',
+            AppendFrom::Row(3),
+            &default_global(),
+        )
+        .unwrap();
+        assert_eq!(std::fs::read_to_string(&since).unwrap(), ",\nEve,\n");
+    }
+
     #[test]
     fn test_export_all_json() {
         let temp_dir = TempDir::new().unwrap();
@@ -1253,6 +1988,10 @@ mod tests {
                 sheet: None,
                 delimiter: ',',
                 all: false,
+                incremental: false,
+                state: None,
+                since_row: None,
+                since_hash: None,
             },
         };
 
@@ -1294,6 +2033,8 @@ mod tests {
                 dest: dest.to_string_lossy().to_string(),
                 sheet: None,
                 all: false,
+                incremental: false,
+                state: None,
             },
         };
 
@@ -1394,6 +2135,10 @@ mod tests {
                 sheet: None,
                 delimiter: ',',
                 all: true,
+                incremental: false,
+                state: None,
+                since_row: None,
+                since_hash: None,
             },
         };
 
@@ -1414,6 +2159,8 @@ mod tests {
                 dest: dest.to_string_lossy().to_string(),
                 sheet: None,
                 all: true,
+                incremental: false,
+                state: None,
             },
         };
 
@@ -1427,7 +2174,7 @@ mod tests {
             formula: "SUM(A1:A10)".to_string(),
             cached_result: Some(Box::new(CellValue::Number(100.0))),
         };
-        let result = cell_to_json(&value);
+        let result = serde_json::Value::from(&value);
         // cell_to_json uses to_display_string() for formulas, which returns "=formula"
         assert_eq!(
             result,
@@ -1441,7 +2188,7 @@ mod tests {
             formula: "SUM(A1:A10)".to_string(),
             cached_result: None,
         };
-        let result = cell_to_json(&value);
+        let result = serde_json::Value::from(&value);
         // cell_to_json uses to_display_string() for formulas
         assert_eq!(
             result,
@@ -1654,7 +2401,7 @@ mod tests {
     fn test_cell_to_json_datetime() {
         // DateTime in xlex_core is stored as f64 (Excel serial date)
         let value = CellValue::DateTime(44945.4375); // 2024-01-15 10:30
-        let result = cell_to_json(&value);
+        let result = serde_json::Value::from(&value);
         // DateTime is serialized via to_display_string() which returns a string
         assert!(result.is_string());
     }
@@ -1663,8 +2410,67 @@ mod tests {
     fn test_cell_to_json_error() {
         use xlex_core::cell::CellError;
         let value = CellValue::Error(CellError::Value);
-        let result = cell_to_json(&value);
+        let result = serde_json::Value::from(&value);
         // Error is serialized as string
         assert!(result.is_string());
     }
+
+    #[test]
+    fn test_expand_out_template() {
+        assert_eq!(
+            expand_out_template("out/{workbook}_{index}_{sheet}.csv", "report", "Q1/Q2", 3),
+            "out/report_3_Q1_Q2.csv"
+        );
+    }
+
+    #[test]
+    fn test_export_all_paths_collisions() {
+        let temp_dir = TempDir::new().unwrap();
+        let template = format!("{}/{{workbook}}.csv", temp_dir.path().display());
+        let sheets = vec!["A".to_string(), "B".to_string()];
+        let file = |name: &str| temp_dir.path().join(name);
+
+        // Sheets expanding to the same name are numbered
+        let paths = export_all_paths(&template, "r", &sheets, IfExists::Overwrite).unwrap();
+        assert_eq!(paths, vec![file("r.csv"), file("r_2.csv")]);
+
+        std::fs::write(file("r.csv"), "").unwrap();
+        let paths = export_all_paths(&template, "r", &sheets, IfExists::Overwrite).unwrap();
+        assert_eq!(paths, vec![file("r.csv"), file("r_2.csv")]);
+        let paths = export_all_paths(&template, "r", &sheets, IfExists::Suffix).unwrap();
+        assert_eq!(paths, vec![file("r_2.csv"), file("r_3.csv")]);
+        assert!(export_all_paths(&template, "r", &sheets, IfExists::Error).is_err());
+    }
+
+    #[test]
+    fn test_run_export_all() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "report.xlsx");
+        setup_test_data(&file_path);
+        let mut wb = Workbook::open(&file_path).unwrap();
+        wb.add_sheet("Empty Sheet").unwrap();
+        wb.save().unwrap();
+
+        let args = |out: String| ExportArgs {
+            command: ExportCommand::All {
+                source: file_path.clone(),
+                out,
+                header: true,
+                if_exists: IfExists::Overwrite,
+            },
+        };
+        let dir = temp_dir.path().join("exports");
+        run(
+            &args(format!("{}/{{workbook}}_{{sheet}}.json", dir.display())),
+            &default_global(),
+        )
+        .unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.join("report_Sheet1.json")).unwrap())
+                .unwrap();
+        assert_eq!(json[0]["Name"], "Alice");
+        assert!(dir.join("report_Empty Sheet.json").exists());
+
+        assert!(run(&args(format!("{}/x.txt", dir.display())), &default_global()).is_err());
+    }
 }
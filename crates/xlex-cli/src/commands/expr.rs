@@ -0,0 +1,336 @@
+//! Row expressions for derived columns.
+//!
+//! An expression combines numbers, quoted text and the row's cells with
+//! `+`, `-`, `*`, `/`, `&` (join as text) and parentheses, for example
+//! `[Unit Price] * Qty` or `First & " " & Last`. Columns are bare words or
+//! bracketed header text, or column letters. As in Excel, blank cells count
+//! as zero, text that isn't a number gives `#VALUE!` and dividing by zero
+//! gives `#DIV/0!`.
+
+use anyhow::Result;
+use xlex_core::pipeline::Record;
+use xlex_core::{CellError, CellValue};
+
+use super::condition::condition_number;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Concat,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f64),
+    Text(String),
+    /// Index into [`Expression::columns`]
+    Column(usize),
+    Neg(Box<Expr>),
+    Binary(Op, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Text(String),
+    Column(String),
+    Op(Op),
+    Open,
+    Close,
+}
+
+/// A parsed expression and the columns it reads.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Expression {
+    expr: Expr,
+    /// Column names or letters, in order of first use
+    pub(super) columns: Vec<String>,
+}
+
+impl Expression {
+    pub(super) fn parse(text: &str) -> Result<Self> {
+        let tokens = tokenize(text)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+            columns: Vec::new(),
+        };
+        let expr = parser.concat()?;
+        if parser.pos < tokens.len() {
+            anyhow::bail!(
+                "Unexpected {:?} in expression '{}'",
+                tokens[parser.pos],
+                text
+            );
+        }
+        Ok(Self {
+            expr,
+            columns: parser.columns,
+        })
+    }
+
+    /// Evaluates the expression for `record`, where `cols[i]` is the
+    /// 1-based column of `columns[i]`.
+    pub(super) fn eval(&self, record: &Record, cols: &[u32]) -> CellValue {
+        eval(&self.expr, record, cols)
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            ' ' | '\t' => {
+                chars.next();
+            }
+            '+' | '-' | '*' | '/' | '&' => {
+                chars.next();
+                tokens.push(Token::Op(match c {
+                    '+' => Op::Add,
+                    '-' => Op::Sub,
+                    '*' => Op::Mul,
+                    '/' => Op::Div,
+                    _ => Op::Concat,
+                }));
+            }
+            '(' | ')' => {
+                chars.next();
+                tokens.push(if c == '(' { Token::Open } else { Token::Close });
+            }
+            '"' => {
+                chars.next();
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            s.push('"');
+                        }
+                        Some('"') => break,
+                        Some(ch) => s.push(ch),
+                        None => anyhow::bail!("Unclosed quote in expression '{}'", text),
+                    }
+                }
+                tokens.push(Token::Text(s));
+            }
+            '[' => {
+                chars.next();
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some(']') => break,
+                        Some(ch) => name.push(ch),
+                        None => anyhow::bail!("Unclosed '[' in expression '{}'", text),
+                    }
+                }
+                tokens.push(Token::Column(name));
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let mut number = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if !(ch.is_ascii_digit() || ch == '.') {
+                        break;
+                    }
+                    number.push(ch);
+                    chars.next();
+                }
+                let n = number
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Invalid number '{}' in expression", number))?;
+                tokens.push(Token::Number(n));
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut name = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if !(ch.is_alphanumeric() || ch == '_') {
+                        break;
+                    }
+                    name.push(ch);
+                    chars.next();
+                }
+                tokens.push(Token::Column(name));
+            }
+            _ => anyhow::bail!("Unexpected '{}' in expression '{}'", c, text),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    columns: Vec<String>,
+}
+
+impl Parser<'_> {
+    fn next_op(&mut self, ops: &[Op]) -> Option<Op> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Op(op)) if ops.contains(op) => {
+                self.pos += 1;
+                Some(*op)
+            }
+            _ => None,
+        }
+    }
+
+    /// `&` binds loosest, as in Excel.
+    fn concat(&mut self) -> Result<Expr> {
+        let mut left = self.sum()?;
+        while let Some(op) = self.next_op(&[Op::Concat]) {
+            left = Expr::Binary(op, Box::new(left), Box::new(self.sum()?));
+        }
+        Ok(left)
+    }
+
+    fn sum(&mut self) -> Result<Expr> {
+        let mut left = self.product()?;
+        while let Some(op) = self.next_op(&[Op::Add, Op::Sub]) {
+            left = Expr::Binary(op, Box::new(left), Box::new(self.product()?));
+        }
+        Ok(left)
+    }
+
+    fn product(&mut self) -> Result<Expr> {
+        let mut left = self.unary()?;
+        while let Some(op) = self.next_op(&[Op::Mul, Op::Div]) {
+            left = Expr::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        if self.next_op(&[Op::Sub]).is_some() {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.next_op(&[Op::Add]);
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Expr> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .ok_or_else(|| anyhow::anyhow!("Expression ends too early"))?;
+        self.pos += 1;
+        Ok(match token {
+            Token::Number(n) => Expr::Number(*n),
+            Token::Text(s) => Expr::Text(s.clone()),
+            Token::Column(name) => {
+                let index = match self.columns.iter().position(|c| c == name) {
+                    Some(index) => index,
+                    None => {
+                        self.columns.push(name.clone());
+                        self.columns.len() - 1
+                    }
+                };
+                Expr::Column(index)
+            }
+            Token::Open => {
+                let inner = self.concat()?;
+                if self.tokens.get(self.pos) != Some(&Token::Close) {
+                    anyhow::bail!("Missing ')' in expression");
+                }
+                self.pos += 1;
+                inner
+            }
+            other => anyhow::bail!("Unexpected {:?} in expression", other),
+        })
+    }
+}
+
+fn eval(expr: &Expr, record: &Record, cols: &[u32]) -> CellValue {
+    match expr {
+        Expr::Number(n) => CellValue::Number(*n),
+        Expr::Text(s) => CellValue::string(s.as_str()),
+        Expr::Column(index) => record.get(cols[*index]).clone(),
+        Expr::Neg(inner) => match number(&eval(inner, record, cols)) {
+            Ok(n) => CellValue::Number(-n),
+            Err(e) => CellValue::Error(e),
+        },
+        Expr::Binary(Op::Concat, left, right) => {
+            let (left, right) = (eval(left, record, cols), eval(right, record, cols));
+            if let Some(e) = left.error().or_else(|| right.error()) {
+                return CellValue::Error(e);
+            }
+            CellValue::String(left.to_display_string() + right.to_display_string().as_str())
+        }
+        Expr::Binary(op, left, right) => {
+            let operands = number(&eval(left, record, cols))
+                .and_then(|a| number(&eval(right, record, cols)).map(|b| (a, b)));
+            let (a, b) = match operands {
+                Ok(operands) => operands,
+                Err(e) => return CellValue::Error(e),
+            };
+            match op {
+                Op::Add => CellValue::Number(a + b),
+                Op::Sub => CellValue::Number(a - b),
+                Op::Mul => CellValue::Number(a * b),
+                Op::Div if b == 0.0 => CellValue::Error(CellError::DivZero),
+                _ => CellValue::Number(a / b),
+            }
+        }
+    }
+}
+
+/// Returns a value as a number for arithmetic.
+fn number(value: &CellValue) -> Result<f64, CellError> {
+    if let Some(e) = value.error() {
+        return Err(e);
+    }
+    match value {
+        CellValue::Empty => Ok(0.0),
+        CellValue::Boolean(b) => Ok(f64::from(u8::from(*b))),
+        other => condition_number(other).ok_or(CellError::Value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(values: Vec<CellValue>) -> Record {
+        Record { row: 2, values }
+    }
+
+    #[test]
+    fn test_expression_eval() {
+        let row = record(vec![
+            CellValue::Number(3.0),
+            CellValue::string("2.5"),
+            CellValue::string("Ann"),
+            CellValue::Empty,
+        ]);
+        let eval = |text: &str| {
+            let expr = Expression::parse(text).unwrap();
+            let cols: Vec<u32> = expr
+                .columns
+                .iter()
+                .map(|c| u32::from(c.as_bytes()[0] - b'A' + 1))
+                .collect();
+            expr.eval(&row, &cols)
+        };
+        assert_eq!(eval("A * B + 1"), CellValue::Number(8.5));
+        assert_eq!(eval("-(A - 1) * 2"), CellValue::Number(-4.0));
+        assert_eq!(eval("A + D"), CellValue::Number(3.0));
+        assert_eq!(eval("C & \" \" & A"), CellValue::string("Ann 3"));
+        assert_eq!(eval("[C] & \"\"\"\""), CellValue::string("Ann\""));
+        assert_eq!(eval("C * 2"), CellValue::Error(CellError::Value));
+        assert_eq!(eval("A / D"), CellValue::Error(CellError::DivZero));
+        assert_eq!(eval("(A / D) & C"), CellValue::Error(CellError::DivZero));
+    }
+
+    #[test]
+    fn test_expression_parse() {
+        let expr = Expression::parse("[Unit Price] * Qty + [Unit Price]").unwrap();
+        assert_eq!(expr.columns, vec!["Unit Price", "Qty"]);
+        assert!(Expression::parse("A +").is_err());
+        assert!(Expression::parse("(A").is_err());
+        assert!(Expression::parse("A B").is_err());
+        assert!(Expression::parse("\"open").is_err());
+        assert!(Expression::parse("A % 2").is_err());
+    }
+}
@@ -4,9 +4,12 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use colored::Colorize;
 
-use xlex_core::{CellRef, CellValue, Range, Workbook};
+use xlex_core::formula::{collect_ranges, complexity, FormulaComplexity};
+use xlex_core::sheet::quote_sheet_name;
+use xlex_core::{CellError, CellRef, CellValue, Range, Workbook};
 
-use super::{GlobalOptions, OutputFormat};
+use super::output::CommandOutput;
+use super::{FailOn, GlobalOptions, OutputFormat};
 
 /// Arguments for formula operations.
 #[derive(Parser)]
@@ -37,6 +40,21 @@ pub enum FormulaCommand {
         /// Formula (without leading =)
         formula: String,
     },
+    /// Write a formula into every cell of a range
+    ///
+    /// `{row}` and `{col}` are replaced by each cell's row number and column
+    /// letters; without them the formula is written as for the range's first
+    /// cell and its relative references are adjusted for the others.
+    Apply {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name
+        sheet: String,
+        /// Target range (e.g., D2:D10000)
+        range: String,
+        /// Formula (leading = optional), e.g. "=B{row}*C{row}"
+        formula: String,
+    },
     /// List all formulas in a sheet
     List {
         /// Path to the xlsx file
@@ -64,6 +82,9 @@ pub enum FormulaCommand {
     Validate {
         /// Formula to validate
         formula: String,
+        /// Findings that fail the command (exit code 12)
+        #[arg(long, value_enum, default_value = "errors")]
+        fail_on: FailOn,
     },
     /// Show formula statistics
     Stats {
@@ -107,6 +128,36 @@ pub enum FormulaCommand {
         /// Sheet name (check all if not specified)
         sheet: Option<String>,
     },
+    /// List error cells (#DIV/0!, #N/A, #REF!, ...) grouped by type and sheet
+    Errors {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name (all sheets if not specified)
+        sheet: Option<String>,
+    },
+    /// Rank cells by number of dependents or by formula complexity
+    Hotspots {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name (all sheets if not specified)
+        sheet: Option<String>,
+        /// Ranking (dependents, complexity)
+        #[arg(long, default_value = "dependents")]
+        by: HotspotRanking,
+        /// Number of entries to show
+        #[arg(long, default_value = "20")]
+        top: usize,
+    },
+}
+
+/// How `formula hotspots` ranks cells.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum HotspotRanking {
+    /// Cells and ranges referenced by the most formulas
+    #[default]
+    Dependents,
+    /// Formulas with the deepest nesting, then most volatile calls
+    Complexity,
 }
 
 #[derive(Parser)]
@@ -177,6 +228,12 @@ pub fn run(args: &FormulaArgs, global: &GlobalOptions) -> Result<()> {
             cell,
             formula,
         } => set(file, sheet, cell, formula, global),
+        FormulaCommand::Apply {
+            file,
+            sheet,
+            range,
+            formula,
+        } => apply(file, sheet, range, formula, global),
         FormulaCommand::List { file, sheet } => list(file, sheet, global),
         FormulaCommand::Eval {
             file,
@@ -184,7 +241,7 @@ pub fn run(args: &FormulaArgs, global: &GlobalOptions) -> Result<()> {
             formula,
         } => eval(file, sheet, formula, global),
         FormulaCommand::Check { file, sheet } => check(file, sheet.as_deref(), global),
-        FormulaCommand::Validate { formula } => validate(formula, global),
+        FormulaCommand::Validate { formula, fail_on } => validate(formula, *fail_on, global),
         FormulaCommand::Stats { file, sheet } => stats(file, sheet.as_deref(), global),
         FormulaCommand::Refs {
             file,
@@ -201,6 +258,13 @@ pub fn run(args: &FormulaArgs, global: &GlobalOptions) -> Result<()> {
         } => replace_formula(file, sheet, find, replace, global),
         FormulaCommand::Calc(calc_args) => run_calc(calc_args, global),
         FormulaCommand::Circular { file, sheet } => circular(file, sheet.as_deref(), global),
+        FormulaCommand::Errors { file, sheet } => errors(file, sheet.as_deref(), global),
+        FormulaCommand::Hotspots {
+            file,
+            sheet,
+            by,
+            top,
+        } => hotspots(file, sheet.as_deref(), *by, *top, global),
     }
 }
 
@@ -220,11 +284,11 @@ fn get(file: &std::path::Path, sheet: &str, cell: &str, global: &GlobalOptions)
             "formula": formula,
             "value": value.to_display_string(),
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else if let Some(f) = formula {
-        println!("={}", f);
+        outln!("={}", f);
     } else {
-        println!("(no formula)");
+        outln!("(no formula)");
     }
 
     Ok(())
@@ -238,10 +302,12 @@ fn set(
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!("Would set formula ={} in {}", formula, cell);
+        outln!("Would set formula ={} in {}", formula, cell);
         return Ok(());
     }
 
+    let output = CommandOutput::new("set");
+
     let mut workbook = Workbook::open(file)?;
     let cell_ref = CellRef::parse(cell)?;
 
@@ -250,13 +316,19 @@ fn set(
 
     workbook.save()?;
 
-    if !global.quiet {
-        println!(
-            "Set formula {} = {}",
-            cell.cyan(),
-            format!("={}", formula).green()
-        );
-    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("cell", cell)
+        .field("formula", formula)
+        .print(
+            global,
+            format!(
+                "Set formula {} = {}",
+                cell.cyan(),
+                format!("={}", formula).green()
+            ),
+        )?;
 
     Ok(())
 }
@@ -292,15 +364,15 @@ fn list(file: &std::path::Path, sheet: &str, global: &GlobalOptions) -> Result<(
                 })
             })
             .collect();
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else {
-        println!("{}: {} formulas", "Sheet".bold(), formulas.len());
+        outln!("{}: {} formulas", "Sheet".bold(), formulas.len());
         for (cell, formula, result) in &formulas {
             let result_str = result
                 .as_ref()
                 .map(|r| format!(" → {}", r.to_display_string()))
                 .unwrap_or_default();
-            println!(
+            outln!(
                 "  {}: ={}{}",
                 cell.to_a1().cyan(),
                 formula,
@@ -330,15 +402,15 @@ fn eval(
     // Try to parse as simple number expression
     if let Ok(n) = result.parse::<f64>() {
         if global.format == OutputFormat::Json {
-            println!("{}", serde_json::json!({ "formula": formula, "result": n }));
+            outln!("{}", serde_json::json!({ "formula": formula, "result": n }));
         } else {
-            println!("{}", n);
+            outln!("{}", n);
         }
         return Ok(());
     }
 
     // For complex formulas, we can't evaluate without Excel
-    println!("Formula evaluation requires Excel - result will be calculated when file is opened");
+    outln!("Formula evaluation requires Excel - result will be calculated when file is opened");
     Ok(())
 }
 
@@ -388,13 +460,13 @@ fn check(file: &std::path::Path, sheet: Option<&str>, global: &GlobalOptions) ->
             }).collect::<Vec<_>>(),
             "count": errors.len(),
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else if errors.is_empty() {
-        println!("{}: No formula errors found", "✓".green());
+        outln!("{}: No formula errors found", "✓".green());
     } else {
-        println!("{}: {} formula errors found", "✗".red(), errors.len());
+        outln!("{}: {} formula errors found", "✗".red(), errors.len());
         for (sheet_name, cell, error) in &errors {
-            println!("  {}!{}: {}", sheet_name, cell.cyan(), error.red());
+            outln!("  {}!{}: {}", sheet_name, cell.cyan(), error.red());
         }
     }
 
@@ -432,17 +504,16 @@ fn calc_sum(
             })?;
 
     let mut sum = 0.0;
-    for cell_ref in range_ref.cells() {
-        let value = sheet_obj.get_value(&cell_ref);
-        if let CellValue::Number(n) = value {
+    for (_, cell) in sheet_obj.iter_range(&range_ref) {
+        if let CellValue::Number(n) = cell.value {
             sum += n;
         }
     }
 
     if global.format == OutputFormat::Json {
-        println!("{}", serde_json::json!({ "sum": sum }));
+        outln!("{}", serde_json::json!({ "sum": sum }));
     } else {
-        println!("{}", sum);
+        outln!("{}", sum);
     }
 
     Ok(())
@@ -465,9 +536,8 @@ fn calc_avg(
 
     let mut sum = 0.0;
     let mut count = 0;
-    for cell_ref in range_ref.cells() {
-        let value = sheet_obj.get_value(&cell_ref);
-        if let CellValue::Number(n) = value {
+    for (_, cell) in sheet_obj.iter_range(&range_ref) {
+        if let CellValue::Number(n) = cell.value {
             sum += n;
             count += 1;
         }
@@ -476,9 +546,9 @@ fn calc_avg(
     let avg = if count > 0 { sum / count as f64 } else { 0.0 };
 
     if global.format == OutputFormat::Json {
-        println!("{}", serde_json::json!({ "average": avg, "count": count }));
+        outln!("{}", serde_json::json!({ "average": avg, "count": count }));
     } else {
-        println!("{}", avg);
+        outln!("{}", avg);
     }
 
     Ok(())
@@ -500,22 +570,16 @@ fn calc_count(
                 name: sheet.to_string(),
             })?;
 
-    let mut count = 0;
-    for cell_ref in range_ref.cells() {
-        let value = sheet_obj.get_value(&cell_ref);
-        if nonempty {
-            if !value.is_empty() {
-                count += 1;
-            }
-        } else {
-            count += 1;
-        }
-    }
+    let count = if nonempty {
+        sheet_obj.iter_range(&range_ref).count()
+    } else {
+        range_ref.cell_count() as usize
+    };
 
     if global.format == OutputFormat::Json {
-        println!("{}", serde_json::json!({ "count": count }));
+        outln!("{}", serde_json::json!({ "count": count }));
     } else {
-        println!("{}", count);
+        outln!("{}", count);
     }
 
     Ok(())
@@ -537,19 +601,18 @@ fn calc_min(
             })?;
 
     let mut min: Option<f64> = None;
-    for cell_ref in range_ref.cells() {
-        let value = sheet_obj.get_value(&cell_ref);
-        if let CellValue::Number(n) = value {
+    for (_, cell) in sheet_obj.iter_range(&range_ref) {
+        if let CellValue::Number(n) = cell.value {
             min = Some(min.map_or(n, |m| m.min(n)));
         }
     }
 
     if global.format == OutputFormat::Json {
-        println!("{}", serde_json::json!({ "min": min }));
+        outln!("{}", serde_json::json!({ "min": min }));
     } else {
         match min {
-            Some(m) => println!("{}", m),
-            None => println!("(no numeric values)"),
+            Some(m) => outln!("{}", m),
+            None => outln!("(no numeric values)"),
         }
     }
 
@@ -572,26 +635,25 @@ fn calc_max(
             })?;
 
     let mut max: Option<f64> = None;
-    for cell_ref in range_ref.cells() {
-        let value = sheet_obj.get_value(&cell_ref);
-        if let CellValue::Number(n) = value {
+    for (_, cell) in sheet_obj.iter_range(&range_ref) {
+        if let CellValue::Number(n) = cell.value {
             max = Some(max.map_or(n, |m| m.max(n)));
         }
     }
 
     if global.format == OutputFormat::Json {
-        println!("{}", serde_json::json!({ "max": max }));
+        outln!("{}", serde_json::json!({ "max": max }));
     } else {
         match max {
-            Some(m) => println!("{}", m),
-            None => println!("(no numeric values)"),
+            Some(m) => outln!("{}", m),
+            None => outln!("(no numeric values)"),
         }
     }
 
     Ok(())
 }
 
-fn validate(formula: &str, global: &GlobalOptions) -> Result<()> {
+fn validate(formula: &str, fail_on: FailOn, global: &GlobalOptions) -> Result<()> {
     // Basic formula validation - check for common patterns
     let formula_str = if formula.starts_with('=') {
         &formula[1..]
@@ -689,26 +751,22 @@ fn validate(formula: &str, global: &GlobalOptions) -> Result<()> {
             "errors": errors,
             "warnings": warnings,
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else {
         if is_valid {
-            println!("{}: Formula syntax is valid", "✓".green());
+            outln!("{}: Formula syntax is valid", "✓".green());
         } else {
-            println!("{}: Formula has syntax errors", "✗".red());
+            outln!("{}: Formula has syntax errors", "✗".red());
         }
         for err in &errors {
-            println!("  {}: {}", "Error".red(), err);
+            outln!("  {}: {}", "Error".red(), err);
         }
         for warn in &warnings {
-            println!("  {}: {}", "Warning".yellow(), warn);
+            outln!("  {}: {}", "Warning".yellow(), warn);
         }
     }
 
-    if is_valid {
-        Ok(())
-    } else {
-        anyhow::bail!("Formula validation failed")
-    }
+    fail_on.check(errors.len(), warnings.len())
 }
 
 fn stats(file: &std::path::Path, sheet: Option<&str>, global: &GlobalOptions) -> Result<()> {
@@ -764,7 +822,7 @@ fn stats(file: &std::path::Path, sheet: Option<&str>, global: &GlobalOptions) ->
 
     // Sort functions by count
     let mut sorted_funcs: Vec<_> = function_counts.into_iter().collect();
-    sorted_funcs.sort_by(|a, b| b.1.cmp(&a.1));
+    sorted_funcs.sort_by_key(|f| std::cmp::Reverse(f.1));
 
     if global.format == OutputFormat::Json {
         let json = serde_json::json!({
@@ -774,16 +832,118 @@ fn stats(file: &std::path::Path, sheet: Option<&str>, global: &GlobalOptions) ->
                 serde_json::json!({"name": name, "count": count})
             }).collect::<Vec<_>>(),
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else {
-        println!("{}: {}", "Total formulas".bold(), total_formulas);
-        println!("{}: {}", "Errors".bold(), total_errors);
-        println!("\n{}:", "Functions used".bold());
+        outln!("{}: {}", "Total formulas".bold(), total_formulas);
+        outln!("{}: {}", "Errors".bold(), total_errors);
+        outln!("\n{}:", "Functions used".bold());
         for (func, count) in sorted_funcs.iter().take(10) {
-            println!("  {}: {}", func.cyan(), count);
+            outln!("  {}: {}", func.cyan(), count);
         }
         if sorted_funcs.len() > 10 {
-            println!("  ... and {} more", sorted_funcs.len() - 10);
+            outln!("  ... and {} more", sorted_funcs.len() - 10);
+        }
+    }
+
+    Ok(())
+}
+
+/// Error cells of one type, by sheet in workbook order.
+struct ErrorGroup {
+    error: CellError,
+    sheets: Vec<(String, Vec<CellRef>)>,
+}
+
+impl ErrorGroup {
+    fn count(&self) -> usize {
+        self.sheets.iter().map(|(_, cells)| cells.len()).sum()
+    }
+}
+
+/// Groups the cells showing an error, directly or as a formula result.
+fn collect_errors(workbook: &Workbook, sheets: &[String]) -> Vec<ErrorGroup> {
+    let mut groups: Vec<ErrorGroup> = CellError::ALL
+        .into_iter()
+        .map(|error| ErrorGroup {
+            error,
+            sheets: Vec::new(),
+        })
+        .collect();
+    for sheet_name in sheets {
+        let Some(sheet) = workbook.get_sheet(sheet_name) else {
+            continue;
+        };
+        let mut cells: Vec<(CellError, CellRef)> = sheet
+            .cells()
+            .filter_map(|cell| cell.value.error().map(|e| (e, cell.reference.clone())))
+            .collect();
+        cells.sort_by_key(|(_, cell_ref)| (cell_ref.row, cell_ref.col));
+        for group in &mut groups {
+            let matching: Vec<CellRef> = cells
+                .iter()
+                .filter(|(error, _)| *error == group.error)
+                .map(|(_, cell_ref)| cell_ref.clone())
+                .collect();
+            if !matching.is_empty() {
+                group.sheets.push((sheet_name.clone(), matching));
+            }
+        }
+    }
+    groups.retain(|group| !group.sheets.is_empty());
+    groups
+}
+
+fn errors(file: &std::path::Path, sheet: Option<&str>, global: &GlobalOptions) -> Result<()> {
+    let workbook = Workbook::open(file)?;
+    let sheets: Vec<String> = match sheet {
+        Some(name) => {
+            if workbook.get_sheet(name).is_none() {
+                return Err(xlex_core::XlexError::SheetNotFound {
+                    name: name.to_string(),
+                }
+                .into());
+            }
+            vec![name.to_string()]
+        }
+        None => workbook
+            .sheet_names()
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+    };
+
+    let groups = collect_errors(&workbook, &sheets);
+    let total: usize = groups.iter().map(ErrorGroup::count).sum();
+
+    if global.format == OutputFormat::Json {
+        let json = serde_json::json!({
+            "total": total,
+            "errors": groups.iter().map(|group| serde_json::json!({
+                "error": group.error.as_str(),
+                "count": group.count(),
+                "sheets": group.sheets.iter().map(|(sheet, cells)| serde_json::json!({
+                    "sheet": sheet,
+                    "cells": cells.iter().map(CellRef::to_a1).collect::<Vec<_>>(),
+                })).collect::<Vec<_>>(),
+            })).collect::<Vec<_>>(),
+        });
+        outln!("{}", serde_json::to_string_pretty(&json)?);
+    } else if groups.is_empty() {
+        if !global.quiet {
+            outln!("{}", "No error cells found".green());
+        }
+    } else {
+        outln!("{}: {}", "Error cells".bold(), total);
+        for group in &groups {
+            outln!(
+                "\n{} ({})",
+                group.error.as_str().red().bold(),
+                group.count()
+            );
+            for (sheet, cells) in &group.sheets {
+                let cells: Vec<String> = cells.iter().map(CellRef::to_a1).collect();
+                outln!("  {}: {}", sheet.cyan(), cells.join(", "));
+            }
         }
     }
 
@@ -844,28 +1004,100 @@ fn refs(
             "precedents": precedents,
             "dependents": dependents,
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else {
         if !precedents.is_empty() {
-            println!("{} (cells referenced by {}):", "Precedents".bold(), cell);
+            outln!("{} (cells referenced by {}):", "Precedents".bold(), cell);
             for p in &precedents {
-                println!("  {}", p.cyan());
+                outln!("  {}", p.cyan());
             }
         }
         if !dependents.is_empty() {
-            println!("{} (cells that reference {}):", "Dependents".bold(), cell);
+            outln!("{} (cells that reference {}):", "Dependents".bold(), cell);
             for d in &dependents {
-                println!("  {}", d.cyan());
+                outln!("  {}", d.cyan());
             }
         }
         if precedents.is_empty() && dependents.is_empty() {
-            println!("No references found for {}", cell);
+            outln!("No references found for {}", cell);
         }
     }
 
     Ok(())
 }
 
+/// The formula `template` gives for `cell` in a range starting at `start`.
+fn applied_formula(template: &str, start: &CellRef, cell: &CellRef) -> String {
+    if template.contains("{row}") || template.contains("{col}") {
+        template
+            .replace("{row}", &cell.row.to_string())
+            .replace("{col}", &CellRef::col_to_letters(cell.col))
+    } else {
+        xlex_core::formula::shift_refs(
+            template,
+            cell.col as i64 - start.col as i64,
+            cell.row as i64 - start.row as i64,
+        )
+    }
+}
+
+fn apply(
+    file: &std::path::Path,
+    sheet: &str,
+    range: &str,
+    formula: &str,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let template = formula.strip_prefix('=').unwrap_or(formula);
+    let range_ref = Range::parse(range)?;
+
+    if global.dry_run {
+        outln!(
+            "Would apply '={}' to {} cells in {}",
+            applied_formula(template, &range_ref.start, &range_ref.start),
+            range_ref.cell_count(),
+            range
+        );
+        return Ok(());
+    }
+
+    let output = CommandOutput::new("apply");
+
+    let mut workbook = Workbook::open(file)?;
+    let count = workbook.with_sheet_mut(sheet, |sheet_obj| {
+        let mut count = 0;
+        for cell_ref in range_ref.cells() {
+            let style_id = sheet_obj.get_cell(&cell_ref).and_then(|c| c.style_id);
+            let value = CellValue::formula(applied_formula(template, &range_ref.start, &cell_ref));
+            sheet_obj.set_cell(cell_ref.clone(), value);
+            if style_id.is_some() {
+                sheet_obj.set_cell_style(&cell_ref, style_id);
+            }
+            count += 1;
+        }
+        count
+    })?;
+    workbook.save()?;
+
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("range", range)
+        .count("cells", count)
+        .field("formula", template)
+        .print(
+            global,
+            format!(
+                "Applied '={}' to {} cells in {}",
+                template.green(),
+                count.to_string().green(),
+                range.cyan()
+            ),
+        )?;
+
+    Ok(())
+}
+
 fn replace_formula(
     file: &std::path::Path,
     sheet: &str,
@@ -874,9 +1106,10 @@ fn replace_formula(
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!(
+        outln!(
             "Would replace '{}' with '{}' in formulas",
-            find, replace_with
+            find,
+            replace_with
         );
         return Ok(());
     }
@@ -923,9 +1156,9 @@ fn replace_formula(
             "replace": replace_with,
             "count": replaced_count,
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else if !global.quiet {
-        println!(
+        outln!(
             "Replaced '{}' with '{}' in {} formulas",
             find.cyan(),
             replace_with.green(),
@@ -1022,25 +1255,238 @@ fn circular(
                 })
             })
             .collect();
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else if unique_cycles.is_empty() {
         if !global.quiet {
-            println!("{}", "No circular references detected".green());
+            outln!("{}", "No circular references detected".green());
         }
     } else {
-        println!(
+        outln!(
             "{}: {} circular reference(s) detected",
             "Warning".yellow().bold(),
             unique_cycles.len()
         );
         for (sheet, cell, cycle) in &unique_cycles {
-            println!(
+            outln!(
                 "\n  {} in {}!{}:",
                 "Cycle".red().bold(),
                 sheet.cyan(),
                 cell.cyan()
             );
-            println!("    {}", cycle.join(" → "));
+            outln!("    {}", cycle.join(" → "));
+        }
+    }
+
+    Ok(())
+}
+
+/// A cell or range and the formula cells that reference it.
+struct DependentHotspot {
+    target: String,
+    dependents: Vec<String>,
+}
+
+/// A formula cell and how complex its formula is.
+struct ComplexityHotspot {
+    cell: String,
+    formula: String,
+    complexity: FormulaComplexity,
+}
+
+/// Returns the names of `sheet`, or of every sheet when not given.
+fn sheets_in_scope(workbook: &Workbook, sheet: Option<&str>) -> Result<Vec<String>> {
+    match sheet {
+        Some(name) => {
+            workbook
+                .get_sheet(name)
+                .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                    name: name.to_string(),
+                })?;
+            Ok(vec![name.to_string()])
+        }
+        None => Ok(workbook
+            .sheet_names()
+            .iter()
+            .map(|s| s.to_string())
+            .collect()),
+    }
+}
+
+/// Counts the formula cells referencing each cell or range, most
+/// referenced first.
+fn dependent_hotspots(workbook: &Workbook, sheets: &[String]) -> Vec<DependentHotspot> {
+    let mut dependents: std::collections::BTreeMap<String, Vec<String>> =
+        std::collections::BTreeMap::new();
+    for sheet_name in sheets {
+        let Some(sheet) = workbook.get_sheet(sheet_name) else {
+            continue;
+        };
+        let quoted = quote_sheet_name(sheet_name);
+        for cell in sheet.cells() {
+            let CellValue::Formula { formula, .. } = &cell.value else {
+                continue;
+            };
+            let dependent = format!("{}!{}", quoted, cell.reference.to_a1());
+            for (target_sheet, range) in collect_ranges(formula) {
+                let target_sheet = target_sheet
+                    .as_deref()
+                    .map(quote_sheet_name)
+                    .unwrap_or_else(|| quoted.clone());
+                let entry = dependents
+                    .entry(format!("{}!{}", target_sheet, range.to_a1()))
+                    .or_default();
+                if entry.last() != Some(&dependent) {
+                    entry.push(dependent.clone());
+                }
+            }
+        }
+    }
+
+    let mut hotspots: Vec<DependentHotspot> = dependents
+        .into_iter()
+        .map(|(target, dependents)| DependentHotspot { target, dependents })
+        .collect();
+    hotspots.sort_by_key(|h| std::cmp::Reverse(h.dependents.len()));
+    hotspots
+}
+
+/// Measures every formula, most complex first.
+fn complexity_hotspots(workbook: &Workbook, sheets: &[String]) -> Vec<ComplexityHotspot> {
+    let mut hotspots = Vec::new();
+    for sheet_name in sheets {
+        let Some(sheet) = workbook.get_sheet(sheet_name) else {
+            continue;
+        };
+        let quoted = quote_sheet_name(sheet_name);
+        let mut cells: Vec<_> = sheet
+            .cells()
+            .filter_map(|cell| match &cell.value {
+                CellValue::Formula { formula, .. } => Some((cell.reference.clone(), formula)),
+                _ => None,
+            })
+            .collect();
+        cells.sort_by_key(|(reference, _)| (reference.row, reference.col));
+        for (reference, formula) in cells {
+            hotspots.push(ComplexityHotspot {
+                cell: format!("{}!{}", quoted, reference.to_a1()),
+                formula: formula.clone(),
+                complexity: complexity(formula),
+            });
+        }
+    }
+    hotspots.sort_by_key(|h| {
+        std::cmp::Reverse((
+            h.complexity.nesting_depth,
+            h.complexity.volatile_functions.len(),
+            h.complexity.function_count,
+            h.complexity.reference_count,
+        ))
+    });
+    hotspots
+}
+
+/// Rank cells by dependents or formulas by complexity.
+fn hotspots(
+    file: &std::path::Path,
+    sheet: Option<&str>,
+    by: HotspotRanking,
+    top: usize,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let workbook = Workbook::open(file)?;
+    let sheets = sheets_in_scope(&workbook, sheet)?;
+
+    match by {
+        HotspotRanking::Dependents => {
+            let hotspots = dependent_hotspots(&workbook, &sheets);
+            if global.format == OutputFormat::Json {
+                let items: Vec<_> = hotspots
+                    .iter()
+                    .take(top)
+                    .map(|h| {
+                        serde_json::json!({
+                            "target": h.target,
+                            "dependentCount": h.dependents.len(),
+                            "dependents": h.dependents,
+                        })
+                    })
+                    .collect();
+                let json = serde_json::json!({
+                    "by": "dependents",
+                    "targets": hotspots.len(),
+                    "hotspots": items,
+                });
+                outln!("{}", serde_json::to_string_pretty(&json)?);
+            } else if hotspots.is_empty() {
+                outln!("No formula references found");
+            } else {
+                for (i, h) in hotspots.iter().take(top).enumerate() {
+                    outln!(
+                        "{:>3}. {}: {} dependent(s)",
+                        i + 1,
+                        h.target.cyan(),
+                        h.dependents.len()
+                    );
+                }
+            }
+        }
+        HotspotRanking::Complexity => {
+            let hotspots = complexity_hotspots(&workbook, &sheets);
+            let volatile_cells = hotspots
+                .iter()
+                .filter(|h| !h.complexity.volatile_functions.is_empty())
+                .count();
+            if global.format == OutputFormat::Json {
+                let items: Vec<_> = hotspots
+                    .iter()
+                    .take(top)
+                    .map(|h| {
+                        serde_json::json!({
+                            "cell": h.cell,
+                            "formula": h.formula,
+                            "nestingDepth": h.complexity.nesting_depth,
+                            "functionCount": h.complexity.function_count,
+                            "volatileFunctions": h.complexity.volatile_functions,
+                            "referenceCount": h.complexity.reference_count,
+                        })
+                    })
+                    .collect();
+                let json = serde_json::json!({
+                    "by": "complexity",
+                    "formulas": hotspots.len(),
+                    "volatileCells": volatile_cells,
+                    "hotspots": items,
+                });
+                outln!("{}", serde_json::to_string_pretty(&json)?);
+            } else if hotspots.is_empty() {
+                outln!("No formulas found");
+            } else {
+                for (i, h) in hotspots.iter().take(top).enumerate() {
+                    let volatile = if h.complexity.volatile_functions.is_empty() {
+                        String::new()
+                    } else {
+                        format!(
+                            ", volatile: {}",
+                            h.complexity.volatile_functions.join(", ").yellow()
+                        )
+                    };
+                    outln!(
+                        "{:>3}. {}: depth {}, {} function(s){}",
+                        i + 1,
+                        h.cell.cyan(),
+                        h.complexity.nesting_depth,
+                        h.complexity.function_count,
+                        volatile
+                    );
+                    outln!("       ={}", h.formula);
+                }
+                if volatile_cells > 0 {
+                    outln!(
+                        "\n{} formula(s) call volatile functions and recalculate on every change",
+                        volatile_cells
+                    );
+                }
+            }
         }
     }
 
@@ -1122,6 +1568,11 @@ mod tests {
             json_errors: false,
             dry_run: false,
             output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
         }
     }
 
@@ -1234,22 +1685,32 @@ mod tests {
 
     #[test]
     fn test_validate_valid_formula() {
-        let result = validate("SUM(A1:A10)", &default_global());
+        let result = validate("SUM(A1:A10)", FailOn::Errors, &default_global());
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_validate_with_equals() {
-        let result = validate("=SUM(A1:A10)", &default_global());
+        let result = validate("=SUM(A1:A10)", FailOn::Errors, &default_global());
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_validate_invalid_formula() {
-        let result = validate("SUM(A1:A10", &default_global());
+        let result = validate("SUM(A1:A10", FailOn::Errors, &default_global());
         assert!(result.is_err()); // Missing closing paren
     }
 
+    #[test]
+    fn test_validate_fail_on() {
+        // Unknown function is a warning
+        assert!(validate("FOO(A1)", FailOn::Errors, &default_global()).is_ok());
+        let err = validate("FOO(A1)", FailOn::Warnings, &default_global()).unwrap_err();
+        let err = err.downcast_ref::<xlex_core::XlexError>().unwrap();
+        assert_eq!(err.exit_code(), 12);
+        assert!(validate("SUM(A1:A10", FailOn::Never, &default_global()).is_ok());
+    }
+
     #[test]
     fn test_stats() {
         let temp_dir = TempDir::new().unwrap();
@@ -1290,6 +1751,17 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_calc_whole_columns() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "columns.xlsx");
+        setup_formula_data(&file_path);
+
+        // Whole-column ranges visit only the stored cells
+        assert!(calc_sum(&file_path, "Sheet1", "A:C", &default_global()).is_ok());
+        assert!(calc_count(&file_path, "Sheet1", "A:XFD", true, &default_global()).is_ok());
+    }
+
     #[test]
     fn test_calc_avg() {
         let temp_dir = TempDir::new().unwrap();
@@ -1349,6 +1821,168 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_applied_formula() {
+        let start = CellRef::new(4, 2);
+        assert_eq!(
+            applied_formula("B{row}*C{row}", &start, &CellRef::new(4, 7)),
+            "B7*C7"
+        );
+        assert_eq!(
+            applied_formula("SUM({col}1:{col}{row})", &start, &CellRef::new(28, 3)),
+            "SUM(AB1:AB3)"
+        );
+        assert_eq!(
+            applied_formula("B2*$A$1", &start, &CellRef::new(5, 4)),
+            "C4*$A$1"
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "apply.xlsx");
+        let mut wb = Workbook::open(&file_path).unwrap();
+        let mut bold = xlex_core::Style::default();
+        bold.font.bold = true;
+        let bold_id = wb.style_registry_mut().add(bold);
+        wb.get_sheet_mut("Sheet1")
+            .unwrap()
+            .set_cell_style(&CellRef::new(4, 3), Some(bold_id));
+        wb.save().unwrap();
+
+        apply(
+            &file_path,
+            "Sheet1",
+            "D2:D100",
+            "=B{row}*C{row}",
+            &default_global(),
+        )
+        .unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let sheet = wb.get_sheet("Sheet1").unwrap();
+        assert_eq!(
+            sheet.get_value(&CellRef::new(4, 100)),
+            CellValue::formula("B100*C100")
+        );
+        assert_eq!(
+            sheet.get_cell(&CellRef::new(4, 3)).unwrap().style_id,
+            Some(1)
+        );
+        assert!(apply(&file_path, "Missing", "A1", "1", &default_global()).is_err());
+    }
+
+    #[test]
+    fn test_collect_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "errors.xlsx");
+        let mut wb = Workbook::open(&file_path).unwrap();
+        wb.add_sheet("Other").unwrap();
+        wb.set("Sheet1!B2", CellError::DivZero).unwrap();
+        wb.set("Sheet1!A1", CellError::DivZero).unwrap();
+        wb.set("Sheet1!C1", CellError::Na).unwrap();
+        wb.set("Other!A1", CellError::DivZero).unwrap();
+        wb.set(
+            "Other!A2",
+            CellValue::Formula {
+                formula: "SEQUENCE(3)".to_string(),
+                cached_result: Some(Box::new(CellValue::Error(CellError::Spill))),
+            },
+        )
+        .unwrap();
+        wb.save().unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let groups = collect_errors(&wb, &["Sheet1".to_string(), "Other".to_string()]);
+        let summary: Vec<(&str, usize, Vec<String>)> = groups
+            .iter()
+            .map(|group| {
+                (
+                    group.error.as_str(),
+                    group.count(),
+                    group
+                        .sheets
+                        .iter()
+                        .map(|(sheet, cells)| {
+                            let cells: Vec<String> = cells.iter().map(CellRef::to_a1).collect();
+                            format!("{}:{}", sheet, cells.join(","))
+                        })
+                        .collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "#DIV/0!",
+                    3,
+                    vec!["Sheet1:A1,B2".to_string(), "Other:A1".to_string()]
+                ),
+                ("#N/A", 1, vec!["Sheet1:C1".to_string()]),
+                ("#SPILL!", 1, vec!["Other:A2".to_string()]),
+            ]
+        );
+
+        assert!(errors(&file_path, None, &default_global()).is_ok());
+        assert!(errors(&file_path, Some("Missing"), &default_global()).is_err());
+    }
+
+    #[test]
+    fn test_hotspots() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "hotspots.xlsx");
+        setup_formula_data(&file_path);
+        let mut wb = Workbook::open(&file_path).unwrap();
+        wb.add_sheet("Rates").unwrap();
+        for (row, formula) in [
+            (3, "A1*Rates!B1"),
+            (4, "A1+A1*Rates!$B$1"),
+            (5, "IF(ISBLANK(A1),0,SUM(OFFSET(A1,0,0,1,3)))+NOW()"),
+        ] {
+            wb.set_cell("Sheet1", CellRef::new(1, row), CellValue::formula(formula))
+                .unwrap();
+        }
+        wb.save().unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let sheets = sheets_in_scope(&wb, None).unwrap();
+        let by_dependents = dependent_hotspots(&wb, &sheets);
+        assert_eq!(by_dependents[0].target, "Sheet1!A1");
+        assert_eq!(by_dependents[0].dependents.len(), 3);
+        assert_eq!(by_dependents[1].target, "Rates!B1");
+        assert_eq!(by_dependents[1].dependents.len(), 2);
+
+        let by_complexity = complexity_hotspots(&wb, &sheets);
+        assert_eq!(by_complexity[0].cell, "Sheet1!A5");
+        assert_eq!(by_complexity[0].complexity.nesting_depth, 3);
+        assert_eq!(
+            by_complexity[0].complexity.volatile_functions,
+            vec!["OFFSET", "NOW"]
+        );
+
+        let mut json = default_global();
+        json.format = OutputFormat::Json;
+        hotspots(&file_path, None, HotspotRanking::Complexity, 5, &json).unwrap();
+        hotspots(
+            &file_path,
+            Some("Sheet1"),
+            HotspotRanking::Dependents,
+            5,
+            &json,
+        )
+        .unwrap();
+        assert!(hotspots(
+            &file_path,
+            Some("Nope"),
+            HotspotRanking::Dependents,
+            5,
+            &json
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_extract_cell_refs() {
         let refs = extract_cell_refs("SUM(A1:B10)");
@@ -1440,6 +2074,7 @@ mod tests {
         let args = FormulaArgs {
             command: FormulaCommand::Validate {
                 formula: "SUM(A1:A10)".to_string(),
+                fail_on: FailOn::Errors,
             },
         };
 
@@ -1613,7 +2248,7 @@ mod tests {
         let mut global = default_global();
         global.format = OutputFormat::Json;
 
-        let result = validate("SUM(A1:A10)", &global);
+        let result = validate("SUM(A1:A10)", FailOn::Errors, &global);
         assert!(result.is_ok());
     }
 
@@ -0,0 +1,701 @@
+//! Generated workbooks for benchmarks, demos and bug reports.
+//!
+//! `gen random` fills a table with random values of each type. `gen sample`
+//! builds a realistic-looking dataset from a YAML schema; its names, emails
+//! and other fakes come from the `fake` crate behind the `fake-data` feature
+//! (on by default).
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use serde::Deserialize;
+
+use xlex_core::style::{Font, NumberFormat, Style};
+use xlex_core::testing::{fill_random_table, SeededRng, ValueKind};
+use xlex_core::{CellRef, CellValue, Workbook};
+
+use super::output::CommandOutput;
+use super::GlobalOptions;
+
+/// Arguments for workbook generation.
+#[derive(Parser)]
+pub struct GenArgs {
+    #[command(subcommand)]
+    pub command: GenCommand,
+}
+
+#[derive(Subcommand)]
+pub enum GenCommand {
+    /// Generate a table of random values
+    Random {
+        /// Output xlsx file
+        output: std::path::PathBuf,
+        /// Data rows below the header row
+        #[arg(long, default_value = "1000", value_parser = clap::value_parser!(u32).range(1..=1_048_575))]
+        rows: u32,
+        /// Columns
+        #[arg(long, default_value = "10", value_parser = clap::value_parser!(u32).range(1..=16_384))]
+        cols: u32,
+        /// Value types of the columns, taken in turn (comma-separated)
+        #[arg(long, value_enum, value_delimiter = ',', default_value = "mixed")]
+        types: Vec<GenType>,
+        /// Seed for the random values; the same seed gives the same file
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Sheet name
+        #[arg(long, short = 's', default_value = "Sheet1")]
+        sheet: String,
+        /// Overwrite existing file
+        #[arg(long, short = 'F')]
+        force: bool,
+    },
+    /// Generate a sample dataset described by a YAML schema
+    Sample {
+        /// Output xlsx file
+        output: std::path::PathBuf,
+        /// Schema file listing the columns to generate
+        #[arg(long)]
+        schema: std::path::PathBuf,
+        /// Data rows below the header row
+        #[arg(long, default_value = "1000", value_parser = clap::value_parser!(u32).range(1..=1_048_575))]
+        rows: u32,
+        /// Seed for the generated values; the same seed gives the same file
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Sheet name (default: the schema's `sheet`, or Sheet1)
+        #[arg(long, short = 's')]
+        sheet: Option<String>,
+        /// Overwrite existing file
+        #[arg(long, short = 'F')]
+        force: bool,
+    },
+}
+
+/// A value type for generated columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum GenType {
+    /// Every type below
+    Mixed,
+    /// Integers, amounts and fractions
+    Numbers,
+    /// Words, including non-ASCII text
+    Text,
+    /// Dates and times, formatted as dates
+    Dates,
+    /// TRUE and FALSE
+    Booleans,
+    /// SUM formulas over the table
+    Formulas,
+    /// Error values such as #N/A
+    Errors,
+}
+
+impl GenType {
+    fn kinds(self) -> &'static [ValueKind] {
+        match self {
+            GenType::Mixed => &ValueKind::ALL,
+            GenType::Numbers => &[ValueKind::Number],
+            GenType::Text => &[ValueKind::Text],
+            GenType::Dates => &[ValueKind::Date],
+            GenType::Booleans => &[ValueKind::Boolean],
+            GenType::Formulas => &[ValueKind::Formula],
+            GenType::Errors => &[ValueKind::Error],
+        }
+    }
+}
+
+/// Run generation commands.
+pub fn run(args: &GenArgs, global: &GlobalOptions) -> Result<()> {
+    match &args.command {
+        GenCommand::Random {
+            output,
+            rows,
+            cols,
+            types,
+            seed,
+            sheet,
+            force,
+        } => random(output, *rows, *cols, types, *seed, sheet, *force, global),
+        GenCommand::Sample {
+            output,
+            schema,
+            rows,
+            seed,
+            sheet,
+            force,
+        } => sample(
+            output,
+            schema,
+            *rows,
+            *seed,
+            sheet.as_deref(),
+            *force,
+            global,
+        ),
+    }
+}
+
+/// Returns a seed that differs from run to run.
+fn fresh_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn random(
+    output: &std::path::Path,
+    rows: u32,
+    cols: u32,
+    types: &[GenType],
+    seed: Option<u64>,
+    sheet: &str,
+    force: bool,
+    global: &GlobalOptions,
+) -> Result<()> {
+    if output.exists() && !force {
+        return Err(xlex_core::XlexError::FileExists {
+            path: output.to_path_buf(),
+        }
+        .into());
+    }
+    let seed = seed.unwrap_or_else(fresh_seed);
+    let kinds: Vec<ValueKind> = types.iter().flat_map(|t| t.kinds()).copied().collect();
+
+    if global.dry_run {
+        outln!(
+            "Would generate {} with {} rows × {} columns of random values (seed {})",
+            output.display(),
+            rows,
+            cols,
+            seed
+        );
+        return Ok(());
+    }
+
+    let mut workbook = Workbook::with_sheets(&[sheet]);
+    let mut rng = SeededRng::new(seed);
+    fill_random_table(&mut workbook, sheet, &mut rng, rows, cols, &kinds)?;
+    workbook.save_as(output)?;
+
+    CommandOutput::new("generate")
+        .file(output)
+        .field("sheet", sheet)
+        .field("rows", rows)
+        .field("cols", cols)
+        .field("seed", seed)
+        .print(
+            global,
+            format!(
+                "Generated {} with {} rows × {} columns (seed {})",
+                output.display().to_string().cyan(),
+                rows,
+                cols,
+                seed.to_string().yellow()
+            ),
+        )?;
+    Ok(())
+}
+
+/// A `gen sample` schema file.
+///
+/// ```yaml
+/// sheet: Customers
+/// columns:
+///   - { name: ID, type: sequence }
+///   - { name: Customer, type: name }
+///   - { name: Email, type: email }
+///   - { name: Joined, type: date, from: 2020-01-01, to: 2025-12-31 }
+///   - { name: Balance, type: amount, min: 0, max: 5000 }
+///   - { name: Plan, type: choice, values: [Free, Pro, Team], blank: 0.1 }
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SampleSchema {
+    /// Sheet to write, unless `--sheet` is given
+    sheet: Option<String>,
+    columns: Vec<SampleColumn>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SampleColumn {
+    /// Header text
+    name: String,
+    #[serde(rename = "type")]
+    kind: SampleKind,
+    /// Smallest number, or the first value of a sequence
+    min: Option<f64>,
+    /// Largest number
+    max: Option<f64>,
+    /// First date (YYYY-MM-DD)
+    from: Option<String>,
+    /// Last date (YYYY-MM-DD)
+    to: Option<String>,
+    /// Values a `choice` column picks from
+    #[serde(default)]
+    values: Vec<String>,
+    /// Fraction of cells left blank, from 0 to 1
+    #[serde(default)]
+    blank: f64,
+}
+
+/// The type of a sample column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SampleKind {
+    /// 1, 2, 3, ... (or from `min`)
+    Sequence,
+    /// Whole numbers from `min` to `max` (default 0 to 1000)
+    Integer,
+    /// Amounts with two decimals from `min` to `max` (default 0 to 10000)
+    Amount,
+    /// Dates from `from` to `to` (default 2020-01-01 to 2025-12-31)
+    Date,
+    Boolean,
+    /// One of `values`
+    Choice,
+    Name,
+    FirstName,
+    LastName,
+    Email,
+    Phone,
+    Company,
+    City,
+    Country,
+    /// A short lorem ipsum sentence
+    Sentence,
+}
+
+/// A sample column checked and ready to generate values.
+enum SampleField {
+    Sequence(f64),
+    Integer(i64, i64),
+    Amount(f64, f64),
+    Date(chrono::NaiveDate, u64),
+    Boolean,
+    Choice(Vec<String>),
+    #[cfg_attr(not(feature = "fake-data"), allow(dead_code))]
+    Fake(SampleKind),
+}
+
+impl SampleColumn {
+    /// Checks the column's options and turns it into a field.
+    fn field(&self) -> Result<SampleField> {
+        let fail = |message: &str| anyhow::anyhow!("Column '{}': {}", self.name, message);
+        if !(0.0..=1.0).contains(&self.blank) {
+            return Err(fail("blank must be between 0 and 1"));
+        }
+        let range = |min: f64, max: f64| {
+            let (min, max) = (self.min.unwrap_or(min), self.max.unwrap_or(max));
+            if min > max {
+                Err(fail("min is greater than max"))
+            } else {
+                Ok((min, max))
+            }
+        };
+        let date = |text: &Option<String>, default: &str| {
+            let text = text.as_deref().unwrap_or(default);
+            chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .map_err(|_| fail(&format!("invalid date '{}' (expected YYYY-MM-DD)", text)))
+        };
+
+        Ok(match self.kind {
+            SampleKind::Sequence => SampleField::Sequence(self.min.unwrap_or(1.0)),
+            SampleKind::Integer => {
+                let (min, max) = range(0.0, 1000.0)?;
+                SampleField::Integer(min.ceil() as i64, max.floor() as i64)
+            }
+            SampleKind::Amount => {
+                let (min, max) = range(0.0, 10_000.0)?;
+                SampleField::Amount(min, max)
+            }
+            SampleKind::Date => {
+                let (from, to) = (
+                    date(&self.from, "2020-01-01")?,
+                    date(&self.to, "2025-12-31")?,
+                );
+                if from > to {
+                    return Err(fail("from is after to"));
+                }
+                SampleField::Date(from, (to - from).num_days() as u64)
+            }
+            SampleKind::Boolean => SampleField::Boolean,
+            SampleKind::Choice if self.values.is_empty() => {
+                return Err(fail("a choice column needs values"));
+            }
+            SampleKind::Choice => SampleField::Choice(self.values.clone()),
+            kind if cfg!(feature = "fake-data") => SampleField::Fake(kind),
+            _ => {
+                return Err(fail(
+                    "fake names, emails and the like need xlex built with the fake-data feature",
+                ))
+            }
+        })
+    }
+}
+
+impl SampleField {
+    /// Returns the value for the data row at `index` (0 for the first).
+    fn value(&self, rng: &mut SeededRng, index: u32) -> CellValue {
+        match self {
+            SampleField::Sequence(start) => CellValue::Number(start + index as f64),
+            SampleField::Integer(min, max) => {
+                let span = (max - min) as u64 + 1;
+                CellValue::Number((min + rng.below(span) as i64) as f64)
+            }
+            SampleField::Amount(min, max) => {
+                let amount = min + rng.next_f64() * (max - min);
+                CellValue::Number(((amount * 100.0).round() / 100.0).clamp(*min, *max))
+            }
+            SampleField::Date(from, days) => {
+                let offset = chrono::Duration::days(rng.below(days + 1) as i64);
+                CellValue::date(*from + offset)
+            }
+            SampleField::Boolean => CellValue::Boolean(rng.chance(0.5)),
+            SampleField::Choice(values) => CellValue::String(rng.pick(values).clone()),
+            SampleField::Fake(kind) => CellValue::String(fake_text(*kind, rng)),
+        }
+    }
+}
+
+/// Adapts [`SeededRng`] to the `rand` traits the `fake` crate expects.
+#[cfg(feature = "fake-data")]
+struct FakeRng<'a>(&'a mut SeededRng);
+
+#[cfg(feature = "fake-data")]
+impl fake::rand::RngCore for FakeRng<'_> {
+    fn next_u32(&mut self) -> u32 {
+        (self.0.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        for chunk in dst.chunks_mut(8) {
+            let bytes = self.0.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
+
+#[cfg(feature = "fake-data")]
+fn fake_text(kind: SampleKind, rng: &mut SeededRng) -> String {
+    use fake::faker::address::en::{CityName, CountryName};
+    use fake::faker::company::en::CompanyName;
+    use fake::faker::internet::en::SafeEmail;
+    use fake::faker::lorem::en::Sentence;
+    use fake::faker::name::en::{FirstName, LastName, Name};
+    use fake::faker::phone_number::en::PhoneNumber;
+    use fake::Fake;
+
+    let rng = &mut FakeRng(rng);
+    match kind {
+        SampleKind::Name => Name().fake_with_rng(rng),
+        SampleKind::FirstName => FirstName().fake_with_rng(rng),
+        SampleKind::LastName => LastName().fake_with_rng(rng),
+        SampleKind::Email => SafeEmail().fake_with_rng(rng),
+        SampleKind::Phone => PhoneNumber().fake_with_rng(rng),
+        SampleKind::Company => CompanyName().fake_with_rng(rng),
+        SampleKind::City => CityName().fake_with_rng(rng),
+        SampleKind::Country => CountryName().fake_with_rng(rng),
+        SampleKind::Sentence => Sentence(3..8).fake_with_rng(rng),
+        _ => String::new(),
+    }
+}
+
+/// Without the `fake` crate no field is [`SampleField::Fake`], so this is
+/// never reached.
+#[cfg(not(feature = "fake-data"))]
+fn fake_text(_kind: SampleKind, _rng: &mut SeededRng) -> String {
+    String::new()
+}
+
+fn sample(
+    output: &std::path::Path,
+    schema_path: &std::path::Path,
+    rows: u32,
+    seed: Option<u64>,
+    sheet: Option<&str>,
+    force: bool,
+    global: &GlobalOptions,
+) -> Result<()> {
+    if output.exists() && !force {
+        return Err(xlex_core::XlexError::FileExists {
+            path: output.to_path_buf(),
+        }
+        .into());
+    }
+    let content = std::fs::read_to_string(schema_path)?;
+    let schema: SampleSchema = serde_yaml::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("Invalid schema {}: {}", schema_path.display(), e))?;
+    if schema.columns.is_empty() {
+        anyhow::bail!("Schema {} has no columns", schema_path.display());
+    }
+    let fields = schema
+        .columns
+        .iter()
+        .map(SampleColumn::field)
+        .collect::<Result<Vec<_>>>()?;
+    let sheet = sheet.or(schema.sheet.as_deref()).unwrap_or("Sheet1");
+    let seed = seed.unwrap_or_else(fresh_seed);
+
+    if global.dry_run {
+        outln!(
+            "Would generate {} with {} rows of {} columns from {} (seed {})",
+            output.display(),
+            rows,
+            fields.len(),
+            schema_path.display(),
+            seed
+        );
+        return Ok(());
+    }
+
+    let mut workbook = Workbook::with_sheets(&[sheet]);
+    let header_style = workbook.style_registry_mut().add(Style {
+        font: Font {
+            bold: true,
+            ..Font::default()
+        },
+        ..Style::default()
+    });
+    let mut column_style = |kind| {
+        let number_format = match kind {
+            SampleKind::Date => NumberFormat::date(),
+            SampleKind::Amount => NumberFormat::custom("#,##0.00"),
+            _ => return None,
+        };
+        Some(workbook.style_registry_mut().add(Style {
+            number_format,
+            ..Style::default()
+        }))
+    };
+    let styles: Vec<Option<u32>> = schema
+        .columns
+        .iter()
+        .map(|c| column_style(c.kind))
+        .collect();
+
+    let mut rng = SeededRng::new(seed);
+    workbook.with_sheet_mut(sheet, |target| {
+        for (i, column) in schema.columns.iter().enumerate() {
+            let cell_ref = CellRef::new(i as u32 + 1, 1);
+            target.set_cell(cell_ref.clone(), CellValue::string(&column.name));
+            target.set_cell_style(&cell_ref, Some(header_style));
+        }
+        for index in 0..rows {
+            for (i, (column, field)) in schema.columns.iter().zip(&fields).enumerate() {
+                let value = field.value(&mut rng, index);
+                if column.blank > 0.0 && rng.chance(column.blank) {
+                    continue;
+                }
+                let cell_ref = CellRef::new(i as u32 + 1, index + 2);
+                target.set_cell(cell_ref.clone(), value);
+                if styles[i].is_some() {
+                    target.set_cell_style(&cell_ref, styles[i]);
+                }
+            }
+        }
+    })?;
+    workbook.save_as(output)?;
+
+    CommandOutput::new("generate")
+        .file(output)
+        .field("sheet", sheet)
+        .field("rows", rows)
+        .field("cols", fields.len())
+        .field("seed", seed)
+        .print(
+            global,
+            format!(
+                "Generated {} with {} rows of {} columns (seed {})",
+                output.display().to_string().cyan(),
+                rows,
+                fields.len(),
+                seed.to_string().yellow()
+            ),
+        )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::OutputFormat;
+    use tempfile::TempDir;
+
+    fn default_global() -> GlobalOptions {
+        GlobalOptions {
+            quiet: true,
+            verbose: false,
+            format: OutputFormat::Text,
+            no_color: true,
+            color: false,
+            json_errors: false,
+            dry_run: false,
+            output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
+        }
+    }
+
+    #[test]
+    fn test_run_gen_random() {
+        let temp_dir = TempDir::new().unwrap();
+        let first = temp_dir.path().join("first.xlsx");
+        let second = temp_dir.path().join("second.xlsx");
+        let gen = |output: &std::path::Path, force| GenArgs {
+            command: GenCommand::Random {
+                output: output.to_path_buf(),
+                rows: 20,
+                cols: 3,
+                types: vec![GenType::Numbers, GenType::Dates],
+                seed: Some(7),
+                sheet: "Data".to_string(),
+                force,
+            },
+        };
+
+        run(&gen(&first, false), &default_global()).unwrap();
+        run(&gen(&second, false), &default_global()).unwrap();
+        assert!(run(&gen(&first, false), &default_global()).is_err());
+        run(&gen(&first, true), &default_global()).unwrap();
+
+        let workbook = Workbook::open(&first).unwrap();
+        let sheet = workbook.get_sheet("Data").unwrap();
+        assert_eq!(sheet.dimensions(), (3, 21));
+        assert_eq!(
+            sheet.get_value(&CellRef::new(3, 1)),
+            CellValue::string("number_3")
+        );
+        let date = CellRef::new(2, 2);
+        assert!(matches!(sheet.get_value(&date), CellValue::Number(_)));
+        assert_eq!(
+            workbook
+                .get_cell_style("Data", &date)
+                .unwrap()
+                .number_format
+                .id,
+            Some(14)
+        );
+        xlex_core::assert_workbook_eq!(workbook, Workbook::open(&second).unwrap());
+    }
+
+    const SCHEMA: &str = "
+sheet: Customers
+columns:
+  - { name: ID, type: sequence, min: 100 }
+  - { name: Joined, type: date, from: 2024-01-01, to: 2024-01-31 }
+  - { name: Balance, type: amount, min: 5, max: 10 }
+  - { name: Seats, type: integer, min: 1, max: 3 }
+  - { name: Plan, type: choice, values: [Free, Pro], blank: 0.5 }
+  - { name: Active, type: boolean }
+";
+
+    #[test]
+    fn test_run_gen_sample() {
+        let temp_dir = TempDir::new().unwrap();
+        let schema = temp_dir.path().join("schema.yml");
+        std::fs::write(&schema, SCHEMA).unwrap();
+        let output = temp_dir.path().join("sample.xlsx");
+        let sample = |schema: &std::path::Path| GenArgs {
+            command: GenCommand::Sample {
+                output: output.clone(),
+                schema: schema.to_path_buf(),
+                rows: 40,
+                seed: Some(3),
+                sheet: None,
+                force: true,
+            },
+        };
+        run(&sample(&schema), &default_global()).unwrap();
+
+        let workbook = Workbook::open(&output).unwrap();
+        let sheet = workbook.get_sheet("Customers").unwrap();
+        assert_eq!(
+            sheet.get_value(&CellRef::new(2, 1)),
+            CellValue::string("Joined")
+        );
+        assert_eq!(
+            sheet.get_value(&CellRef::new(1, 41)),
+            CellValue::Number(139.0)
+        );
+        // 2024-01-01
+        let first_day = 45292.0;
+        let number = |value: CellValue| match value {
+            CellValue::Number(n) | CellValue::DateTime(n) => n,
+            other => panic!("not a number: {:?}", other),
+        };
+        let mut blanks = 0;
+        for row in 2..=41 {
+            let value = |col| sheet.get_value(&CellRef::new(col, row));
+            assert!((first_day..=first_day + 30.0).contains(&number(value(2))));
+            assert!((5.0..=10.0).contains(&number(value(3))));
+            assert!([1.0, 2.0, 3.0].contains(&number(value(4))));
+            match value(5) {
+                CellValue::Empty => blanks += 1,
+                plan => {
+                    assert!(plan == CellValue::string("Free") || plan == CellValue::string("Pro"))
+                }
+            }
+            assert!(matches!(value(6), CellValue::Boolean(_)));
+        }
+        assert!(blanks > 0 && blanks < 40);
+
+        for bad in [
+            "columns: []",
+            "columns: [{ name: A, type: choice }]",
+            "columns: [{ name: A, type: integer, min: 5, max: 1 }]",
+            "columns: [{ name: A, type: date, from: 2024-13-01 }]",
+            "columns: [{ name: A, type: amount, blank: 2 }]",
+            "columns: [{ name: A, type: unknown }]",
+        ] {
+            let path = temp_dir.path().join("bad.yml");
+            std::fs::write(&path, bad).unwrap();
+            assert!(run(&sample(&path), &default_global()).is_err(), "{}", bad);
+        }
+    }
+
+    #[cfg(feature = "fake-data")]
+    #[test]
+    fn test_run_gen_sample_fakes() {
+        let temp_dir = TempDir::new().unwrap();
+        let schema = temp_dir.path().join("schema.yml");
+        std::fs::write(
+            &schema,
+            "columns:\n  - { name: Customer, type: name }\n  - { name: Email, type: email }\n",
+        )
+        .unwrap();
+        let generate = |name: &str| {
+            let output = temp_dir.path().join(name);
+            let args = GenArgs {
+                command: GenCommand::Sample {
+                    output: output.clone(),
+                    schema: schema.clone(),
+                    rows: 10,
+                    seed: Some(9),
+                    sheet: Some("People".to_string()),
+                    force: false,
+                },
+            };
+            run(&args, &default_global()).unwrap();
+            Workbook::open(&output).unwrap()
+        };
+        let first = generate("first.xlsx");
+        let sheet = first.get_sheet("People").unwrap();
+        for row in 2..=11 {
+            let name = sheet.get_value(&CellRef::new(1, row)).to_display_string();
+            assert!(!name.is_empty());
+            let email = sheet.get_value(&CellRef::new(2, row)).to_display_string();
+            assert!(email.contains('@'), "{}", email);
+        }
+        xlex_core::assert_workbook_eq!(first, generate("second.xlsx"));
+    }
+}
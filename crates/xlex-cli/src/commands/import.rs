@@ -1,11 +1,15 @@
 //! Import operations.
 
+use std::collections::HashSet;
+
 use anyhow::Result;
 use clap::{Parser, Subcommand, ValueEnum};
 use colored::Colorize;
 
-use xlex_core::{CellRef, CellValue, Workbook};
+use xlex_core::style::{NumberFormat, Style};
+use xlex_core::{CellRef, CellValue, ImportOptions, Sheet, Workbook};
 
+use super::output::CommandOutput;
 use super::GlobalOptions;
 use crate::progress::Progress;
 
@@ -23,6 +27,15 @@ pub enum ImportFormat {
     Tsv,
 }
 
+/// Where `import dir` takes sheet names from.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum SheetNameFrom {
+    /// The file name without its extension
+    Filename,
+    /// Sheet1, Sheet2, ... in file name order
+    Index,
+}
+
 #[derive(Subcommand)]
 pub enum ImportCommand {
     /// Import CSV file
@@ -40,6 +53,16 @@ pub enum ImportCommand {
         /// Has header row
         #[arg(long)]
         header: bool,
+        /// Quote text starting with =, +, - or @ so it cannot act as a formula
+        #[arg(long)]
+        sanitize_formulas: bool,
+        /// Store numbers as exact decimals, written with every digit
+        #[arg(long)]
+        decimal: bool,
+        /// Keep identifiers as text: columns such as A,C, or auto for numbers
+        /// over 15 digits and zero-padded codes
+        #[arg(long, value_delimiter = ',', value_name = "COLS")]
+        preserve_ids: Vec<String>,
     },
     /// Import JSON file
     Json {
@@ -50,6 +73,16 @@ pub enum ImportCommand {
         /// Sheet name (default: Sheet1)
         #[arg(short, long)]
         sheet: Option<String>,
+        /// Quote text starting with =, +, - or @ so it cannot act as a formula
+        #[arg(long)]
+        sanitize_formulas: bool,
+        /// Store numbers as exact decimals, written with every digit
+        #[arg(long)]
+        decimal: bool,
+        /// Keep identifiers as text: columns such as A,C, or auto for numbers
+        /// over 15 digits and zero-padded codes
+        #[arg(long, value_delimiter = ',', value_name = "COLS")]
+        preserve_ids: Vec<String>,
     },
     /// Import TSV file
     Tsv {
@@ -60,6 +93,16 @@ pub enum ImportCommand {
         /// Sheet name (default: Sheet1)
         #[arg(short, long)]
         sheet: Option<String>,
+        /// Quote text starting with =, +, - or @ so it cannot act as a formula
+        #[arg(long)]
+        sanitize_formulas: bool,
+        /// Store numbers as exact decimals, written with every digit
+        #[arg(long)]
+        decimal: bool,
+        /// Keep identifiers as text: columns such as A,C, or auto for numbers
+        /// over 15 digits and zero-padded codes
+        #[arg(long, value_delimiter = ',', value_name = "COLS")]
+        preserve_ids: Vec<String>,
     },
     /// Import NDJSON file (newline-delimited JSON)
     Ndjson {
@@ -73,6 +116,39 @@ pub enum ImportCommand {
         /// Use first row keys as headers
         #[arg(long)]
         header: bool,
+        /// Quote text starting with =, +, - or @ so it cannot act as a formula
+        #[arg(long)]
+        sanitize_formulas: bool,
+        /// Store numbers as exact decimals, written with every digit
+        #[arg(long)]
+        decimal: bool,
+        /// Keep identifiers as text: columns such as A,C, or auto for numbers
+        /// over 15 digits and zero-padded codes
+        #[arg(long, value_delimiter = ',', value_name = "COLS")]
+        preserve_ids: Vec<String>,
+    },
+    /// Import every matching CSV/TSV file in a directory, one sheet per file
+    Dir {
+        /// Source directory
+        source: std::path::PathBuf,
+        /// Destination xlsx file
+        dest: std::path::PathBuf,
+        /// File name pattern (* and ? wildcards); .tsv files are tab-delimited
+        #[arg(long, default_value = "*.csv")]
+        pattern: String,
+        /// How to name the sheets
+        #[arg(long, value_enum, default_value = "filename")]
+        sheet_name_from: SheetNameFrom,
+        /// Quote text starting with =, +, - or @ so it cannot act as a formula
+        #[arg(long)]
+        sanitize_formulas: bool,
+        /// Store numbers as exact decimals, written with every digit
+        #[arg(long)]
+        decimal: bool,
+        /// Keep identifiers as text: columns such as A,C, or auto for numbers
+        /// over 15 digits and zero-padded codes
+        #[arg(long, value_delimiter = ',', value_name = "COLS")]
+        preserve_ids: Vec<String>,
     },
 }
 
@@ -85,23 +161,139 @@ pub fn run(args: &ImportArgs, global: &GlobalOptions) -> Result<()> {
             sheet,
             delimiter,
             header,
-        } => import_csv(source, dest, sheet.as_deref(), *delimiter, *header, global),
+            sanitize_formulas,
+            decimal,
+            preserve_ids,
+        } => import_csv(
+            source,
+            dest,
+            sheet.as_deref(),
+            *delimiter,
+            *header,
+            import_options(*sanitize_formulas, *decimal, preserve_ids)?,
+            global,
+        ),
         ImportCommand::Json {
             source,
             dest,
             sheet,
-        } => import_json(source, dest, sheet.as_deref(), global),
+            sanitize_formulas,
+            decimal,
+            preserve_ids,
+        } => import_json(
+            source,
+            dest,
+            sheet.as_deref(),
+            import_options(*sanitize_formulas, *decimal, preserve_ids)?,
+            global,
+        ),
         ImportCommand::Tsv {
             source,
             dest,
             sheet,
-        } => import_tsv(source, dest, sheet.as_deref(), global),
+            sanitize_formulas,
+            decimal,
+            preserve_ids,
+        } => import_tsv(
+            source,
+            dest,
+            sheet.as_deref(),
+            import_options(*sanitize_formulas, *decimal, preserve_ids)?,
+            global,
+        ),
         ImportCommand::Ndjson {
             source,
             dest,
             sheet,
             header,
-        } => import_ndjson(source, dest, sheet.as_deref(), *header, global),
+            sanitize_formulas,
+            decimal,
+            preserve_ids,
+        } => import_ndjson(
+            source,
+            dest,
+            sheet.as_deref(),
+            *header,
+            import_options(*sanitize_formulas, *decimal, preserve_ids)?,
+            global,
+        ),
+        ImportCommand::Dir {
+            source,
+            dest,
+            pattern,
+            sheet_name_from,
+            sanitize_formulas,
+            decimal,
+            preserve_ids,
+        } => import_dir(
+            source,
+            dest,
+            pattern,
+            *sheet_name_from,
+            import_options(*sanitize_formulas, *decimal, preserve_ids)?,
+            global,
+        ),
+    }
+}
+
+fn import_options(
+    sanitize_formulas: bool,
+    decimal: bool,
+    preserve_ids: &[String],
+) -> Result<ImportOptions> {
+    let mut detect_ids = false;
+    let mut text_columns = Vec::new();
+    for spec in preserve_ids {
+        let spec = spec.trim();
+        if spec.eq_ignore_ascii_case("auto") {
+            detect_ids = true;
+        } else {
+            text_columns.push(
+                CellRef::col_from_letters_pub(&spec.to_uppercase()).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Invalid --preserve-ids column '{}': expected letters or auto",
+                        spec
+                    )
+                })?,
+            );
+        }
+    }
+    Ok(ImportOptions::new()
+        .sanitize_formulas(sanitize_formulas)
+        .decimal(decimal)
+        .text_columns(text_columns)
+        .detect_ids(detect_ids))
+}
+
+/// The text number format's style, registered when identifiers may be
+/// kept as text.
+fn text_style(workbook: &mut Workbook, options: &ImportOptions) -> Option<u32> {
+    options.preserves_ids().then(|| {
+        workbook.style_registry_mut().add(Style {
+            number_format: NumberFormat::text(),
+            ..Style::default()
+        })
+    })
+}
+
+/// Writes a row of imported cells, giving identifiers kept as text the
+/// text number format so Excel leaves them alone when they are edited.
+fn write_row(
+    sheet: &mut Sheet,
+    options: &ImportOptions,
+    text_style: Option<u32>,
+    updates: impl IntoIterator<Item = (CellRef, CellValue)>,
+) {
+    let mut ids = Vec::new();
+    sheet.apply_updates(updates.into_iter().inspect(|(cell_ref, value)| {
+        if let (Some(_), CellValue::String(text)) = (text_style, value) {
+            if options.id_field(cell_ref.col, text).is_some() {
+                ids.push(cell_ref.clone());
+            }
+        }
+    }));
+    for cell_ref in &ids {
+        sheet.set_cell_style(cell_ref, text_style);
     }
 }
 
@@ -111,28 +303,23 @@ fn import_csv(
     sheet: Option<&str>,
     delimiter: char,
     _has_header: bool,
+    options: ImportOptions,
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!("Would import {} to {}", source.display(), dest.display());
+        outln!("Would import {} to {}", source.display(), dest.display());
         return Ok(());
     }
 
+    let output = CommandOutput::new("import");
+
     let sheet_name = sheet.unwrap_or("Sheet1");
     let content = std::fs::read_to_string(source)?;
     let lines: Vec<&str> = content.lines().collect();
     let total_lines = lines.len();
 
     // Create progress bar for large files
-    let progress = if total_lines > 100 {
-        Some(Progress::bar(
-            total_lines as u64,
-            "Importing CSV...",
-            global.quiet,
-        ))
-    } else {
-        None
-    };
+    let progress = Progress::rows(total_lines, "Importing CSV...", global);
 
     let mut workbook = if dest.exists() {
         Workbook::open(dest)?
@@ -144,21 +331,21 @@ fn import_csv(
     if workbook.get_sheet(sheet_name).is_none() {
         workbook.add_sheet(sheet_name)?;
     }
-
-    let mut row = 1u32;
-    for line in lines.iter() {
-        let mut col = 1u32;
-        for value in line.split(delimiter) {
-            let cell_ref = CellRef::new(col, row);
-            let cell_value = parse_value(value.trim());
-            workbook.set_cell(sheet_name, cell_ref, cell_value)?;
-            col += 1;
-        }
-        row += 1;
-        if let Some(ref pb) = progress {
-            pb.inc(1);
+    let text_style = text_style(&mut workbook, &options);
+
+    workbook.with_sheet_mut(sheet_name, |sheet_obj| {
+        for (row, line) in (1u32..).zip(lines.iter()) {
+            write_row(
+                sheet_obj,
+                &options,
+                text_style,
+                delimited_row(line, delimiter, row, &options),
+            );
+            if let Some(ref pb) = progress {
+                pb.inc(1);
+            }
         }
-    }
+    })?;
 
     if let Some(ref pb) = progress {
         pb.finish_and_clear();
@@ -166,13 +353,19 @@ fn import_csv(
 
     workbook.save_as(dest)?;
 
-    if !global.quiet {
-        println!(
-            "Imported {} rows to {}",
-            (row - 1).to_string().green(),
-            dest.display()
-        );
-    }
+    output
+        .file(dest)
+        .target("sheet", sheet_name)
+        .target("source", source.display().to_string())
+        .count("rows", total_lines)
+        .print(
+            global,
+            format!(
+                "Imported {} rows to {}",
+                total_lines.to_string().green(),
+                dest.display()
+            ),
+        )?;
 
     Ok(())
 }
@@ -181,13 +374,16 @@ fn import_json(
     source: &std::path::Path,
     dest: &std::path::Path,
     sheet: Option<&str>,
+    options: ImportOptions,
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!("Would import {} to {}", source.display(), dest.display());
+        outln!("Would import {} to {}", source.display(), dest.display());
         return Ok(());
     }
 
+    let output = CommandOutput::new("import");
+
     let sheet_name = sheet.unwrap_or("Sheet1");
     let content = std::fs::read_to_string(source)?;
     let data: serde_json::Value = serde_json::from_str(&content)?;
@@ -202,48 +398,72 @@ fn import_json(
     if workbook.get_sheet(sheet_name).is_none() {
         workbook.add_sheet(sheet_name)?;
     }
+    let text_style = text_style(&mut workbook, &options);
 
     match data {
         serde_json::Value::Array(arr) => {
             // Array of objects or arrays
-            if let Some(first) = arr.first() {
-                if first.is_object() {
+            let progress = Progress::rows(arr.len(), "Importing JSON...", global);
+            workbook.with_sheet_mut(sheet_name, |sheet_obj| {
+                let Some(first) = arr.first() else {
+                    return;
+                };
+                if let serde_json::Value::Object(obj) = first {
                     // Array of objects - use keys as headers
-                    if let serde_json::Value::Object(obj) = first {
-                        let keys: Vec<_> = obj.keys().collect();
-
-                        // Write headers
-                        for (col, key) in keys.iter().enumerate() {
-                            let cell_ref = CellRef::new((col + 1) as u32, 1);
-                            workbook.set_cell(
-                                sheet_name,
-                                cell_ref,
-                                CellValue::String((*key).clone()),
-                            )?;
-                        }
+                    let keys: Vec<_> = obj.keys().collect();
+
+                    // Write headers
+                    sheet_obj.apply_updates(keys.iter().enumerate().map(|(col, key)| {
+                        (
+                            CellRef::new((col + 1) as u32, 1),
+                            options.apply(CellValue::String((*key).clone())),
+                        )
+                    }));
 
-                        // Write data
-                        for (row, item) in arr.iter().enumerate() {
-                            if let serde_json::Value::Object(obj) = item {
-                                for (col, key) in keys.iter().enumerate() {
-                                    let cell_ref = CellRef::new((col + 1) as u32, (row + 2) as u32);
+                    // Write data
+                    for (row, item) in arr.iter().enumerate() {
+                        if let serde_json::Value::Object(obj) = item {
+                            write_row(
+                                sheet_obj,
+                                &options,
+                                text_style,
+                                keys.iter().enumerate().map(|(col, key)| {
                                     let value = obj.get(*key).unwrap_or(&serde_json::Value::Null);
-                                    workbook.set_cell(sheet_name, cell_ref, json_to_cell(value))?;
-                                }
-                            }
+                                    (
+                                        CellRef::new((col + 1) as u32, (row + 2) as u32),
+                                        json_cell(&options, (col + 1) as u32, value),
+                                    )
+                                }),
+                            );
+                        }
+                        if let Some(ref pb) = progress {
+                            pb.inc(1);
                         }
                     }
                 } else if first.is_array() {
                     // Array of arrays
                     for (row, item) in arr.iter().enumerate() {
                         if let serde_json::Value::Array(row_arr) = item {
-                            for (col, value) in row_arr.iter().enumerate() {
-                                let cell_ref = CellRef::new((col + 1) as u32, (row + 1) as u32);
-                                workbook.set_cell(sheet_name, cell_ref, json_to_cell(value))?;
-                            }
+                            write_row(
+                                sheet_obj,
+                                &options,
+                                text_style,
+                                row_arr.iter().enumerate().map(|(col, value)| {
+                                    (
+                                        CellRef::new((col + 1) as u32, (row + 1) as u32),
+                                        json_cell(&options, (col + 1) as u32, value),
+                                    )
+                                }),
+                            );
+                        }
+                        if let Some(ref pb) = progress {
+                            pb.inc(1);
                         }
                     }
                 }
+            })?;
+            if let Some(ref pb) = progress {
+                pb.finish_and_clear();
             }
         }
         _ => {
@@ -253,9 +473,11 @@ fn import_json(
 
     workbook.save_as(dest)?;
 
-    if !global.quiet {
-        println!("Imported JSON data to {}", dest.display());
-    }
+    output
+        .file(dest)
+        .target("sheet", sheet_name)
+        .target("source", source.display().to_string())
+        .print(global, format!("Imported JSON data to {}", dest.display()))?;
 
     Ok(())
 }
@@ -264,12 +486,194 @@ fn import_tsv(
     source: &std::path::Path,
     dest: &std::path::Path,
     sheet: Option<&str>,
+    options: ImportOptions,
     global: &GlobalOptions,
 ) -> Result<()> {
-    import_csv(source, dest, sheet, '\t', false, global)
+    import_csv(source, dest, sheet, '\t', false, options, global)
 }
 
-fn parse_value(s: &str) -> CellValue {
+/// Files in `source` whose name matches `pattern`, sorted by name.
+pub(super) fn matching_files(
+    source: &std::path::Path,
+    pattern: &str,
+) -> Result<Vec<std::path::PathBuf>> {
+    let regex = format!(
+        "^{}$",
+        regex_lite::escape(pattern)
+            .replace(r"\*", ".*")
+            .replace(r"\?", ".")
+    );
+    let regex = regex_lite::Regex::new(&regex)?;
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(source)? {
+        let path = entry?.path();
+        let matches = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| regex.is_match(name));
+        if matches && path.is_file() {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Turns a file name into a valid sheet name not in `taken`: forbidden
+/// characters become `_`, long names are cut to 31 characters and repeats
+/// get a ` (2)`, ` (3)`, ... suffix.
+fn unique_sheet_name(taken: &HashSet<String>, stem: &str) -> String {
+    let cleaned: String = stem
+        .chars()
+        .map(|c| {
+            if matches!(c, ':' | '\\' | '/' | '?' | '*' | '[' | ']') {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let cleaned = cleaned.trim_matches('\'');
+    let cleaned = if cleaned.is_empty() { "Sheet" } else { cleaned };
+    let truncate = |s: &str, max: usize| -> String { s.chars().take(max).collect() };
+
+    let mut name = truncate(cleaned, 31);
+    let mut n = 2;
+    while taken.contains(&name) {
+        let suffix = format!(" ({})", n);
+        name = format!("{}{}", truncate(cleaned, 31 - suffix.len()), suffix);
+        n += 1;
+    }
+    name
+}
+
+fn import_dir(
+    source: &std::path::Path,
+    dest: &std::path::Path,
+    pattern: &str,
+    sheet_name_from: SheetNameFrom,
+    options: ImportOptions,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let files = matching_files(source, pattern)?;
+    if files.is_empty() {
+        anyhow::bail!("No files matching '{}' in {}", pattern, source.display());
+    }
+
+    if global.dry_run {
+        for file in &files {
+            outln!("Would import {} to {}", file.display(), dest.display());
+        }
+        return Ok(());
+    }
+
+    let output = CommandOutput::new("import");
+
+    let existing = if dest.exists() {
+        Some(Workbook::open(dest)?)
+    } else {
+        None
+    };
+    let mut taken: HashSet<String> = existing
+        .iter()
+        .flat_map(|wb| wb.sheet_names())
+        .map(str::to_string)
+        .collect();
+    let sheet_names: Vec<String> = files
+        .iter()
+        .enumerate()
+        .map(|(index, file)| {
+            let stem = match sheet_name_from {
+                SheetNameFrom::Filename => file
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                SheetNameFrom::Index => format!("Sheet{}", index + 1),
+            };
+            let name = unique_sheet_name(&taken, &stem);
+            taken.insert(name.clone());
+            name
+        })
+        .collect();
+    let mut workbook = match existing {
+        Some(workbook) => workbook,
+        None => Workbook::with_sheets(&[sheet_names[0].as_str()]),
+    };
+
+    let text_style = text_style(&mut workbook, &options);
+    let mut imported = Vec::new();
+    let mut total_rows = 0;
+    for (file, sheet_name) in files.iter().zip(&sheet_names) {
+        let content = std::fs::read_to_string(file)?;
+        let delimiter = match file.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("tsv") => '\t',
+            _ => ',',
+        };
+        if workbook.get_sheet(sheet_name).is_none() {
+            workbook.add_sheet(sheet_name)?;
+        }
+
+        let rows = content.lines().count();
+        workbook.with_sheet_mut(sheet_name, |sheet| {
+            for (row, line) in (1u32..).zip(content.lines()) {
+                write_row(
+                    sheet,
+                    &options,
+                    text_style,
+                    delimited_row(line, delimiter, row, &options),
+                );
+            }
+        })?;
+        total_rows += rows;
+        imported.push(serde_json::json!({
+            "sheet": sheet_name,
+            "source": file.display().to_string(),
+            "rows": rows,
+        }));
+    }
+
+    workbook.save_as(dest)?;
+
+    output
+        .file(dest)
+        .target("source", source.display().to_string())
+        .field("sheets", imported)
+        .count("sheets", files.len())
+        .count("rows", total_rows)
+        .print(
+            global,
+            format!(
+                "Imported {} files ({} rows) to {}",
+                files.len().to_string().green(),
+                total_rows,
+                dest.display()
+            ),
+        )?;
+
+    Ok(())
+}
+
+/// Cells of one delimited line at `row`, with their types inferred.
+fn delimited_row<'a>(
+    line: &'a str,
+    delimiter: char,
+    row: u32,
+    options: &'a ImportOptions,
+) -> impl Iterator<Item = (CellRef, CellValue)> + 'a {
+    (1u32..)
+        .zip(line.split(delimiter))
+        .map(move |(col, value)| {
+            (
+                CellRef::new(col, row),
+                options
+                    .id_field(col, value.trim())
+                    .or_else(|| options.decimal_field(value.trim()))
+                    .unwrap_or_else(|| options.apply(parse_value(value.trim()))),
+            )
+        })
+}
+
+pub(super) fn parse_value(s: &str) -> CellValue {
     if s.is_empty() {
         return CellValue::Empty;
     }
@@ -289,14 +693,22 @@ fn parse_value(s: &str) -> CellValue {
     CellValue::String(s.to_string())
 }
 
-fn json_to_cell(value: &serde_json::Value) -> CellValue {
-    match value {
-        serde_json::Value::Null => CellValue::Empty,
-        serde_json::Value::Bool(b) => CellValue::Boolean(*b),
-        serde_json::Value::Number(n) => CellValue::Number(n.as_f64().unwrap_or(0.0)),
-        serde_json::Value::String(s) => CellValue::String(s.clone()),
-        _ => CellValue::String(value.to_string()),
-    }
+/// Converts a JSON value to a cell, storing nested arrays and objects as
+/// their JSON text.
+pub(super) fn json_to_cell(value: &serde_json::Value) -> CellValue {
+    CellValue::try_from(value).unwrap_or_else(|_| CellValue::String(value.to_string()))
+}
+
+/// Converts a JSON value in column `col` to a cell with the import options
+/// applied, keeping identifiers as text.
+fn json_cell(options: &ImportOptions, col: u32, value: &serde_json::Value) -> CellValue {
+    let text = match value {
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::String(s) => Some(s.clone()),
+        _ => None,
+    };
+    text.and_then(|text| options.id_field(col, &text))
+        .unwrap_or_else(|| options.apply(json_to_cell(value)))
 }
 
 fn import_ndjson(
@@ -304,13 +716,16 @@ fn import_ndjson(
     dest: &std::path::Path,
     sheet: Option<&str>,
     has_header: bool,
+    options: ImportOptions,
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!("Would import {} to {}", source.display(), dest.display());
+        outln!("Would import {} to {}", source.display(), dest.display());
         return Ok(());
     }
 
+    let output = CommandOutput::new("import");
+
     let sheet_name = sheet.unwrap_or("Sheet1");
     let content = std::fs::read_to_string(source)?;
     let lines: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
@@ -329,66 +744,112 @@ fn import_ndjson(
     if workbook.get_sheet(sheet_name).is_none() {
         workbook.add_sheet(sheet_name)?;
     }
+    let text_style = text_style(&mut workbook, &options);
 
     // Parse first line to determine format
     let first: serde_json::Value = serde_json::from_str(lines[0])?;
-
-    if first.is_object() && has_header {
-        // Object format - use keys as headers
-        if let serde_json::Value::Object(obj) = &first {
-            let keys: Vec<_> = obj.keys().cloned().collect();
-
-            // Write headers
-            for (col, key) in keys.iter().enumerate() {
-                let cell_ref = CellRef::new((col + 1) as u32, 1);
-                workbook.set_cell(sheet_name, cell_ref, CellValue::String(key.clone()))?;
+    let progress = Progress::rows(lines.len(), "Importing NDJSON...", global);
+
+    workbook.with_sheet_mut(sheet_name, |sheet_obj| -> Result<()> {
+        if first.is_object() && has_header {
+            // Object format - use keys as headers
+            if let serde_json::Value::Object(obj) = &first {
+                let keys: Vec<_> = obj.keys().cloned().collect();
+
+                // Write headers
+                sheet_obj.apply_updates(keys.iter().enumerate().map(|(col, key)| {
+                    (
+                        CellRef::new((col + 1) as u32, 1),
+                        options.apply(CellValue::String(key.clone())),
+                    )
+                }));
+
+                // Write data
+                for (row_idx, line) in lines.iter().enumerate() {
+                    let item: serde_json::Value = serde_json::from_str(line)?;
+                    if let serde_json::Value::Object(obj) = item {
+                        write_row(
+                            sheet_obj,
+                            &options,
+                            text_style,
+                            keys.iter().enumerate().map(|(col, key)| {
+                                let value = obj.get(key).unwrap_or(&serde_json::Value::Null);
+                                (
+                                    CellRef::new((col + 1) as u32, (row_idx + 2) as u32),
+                                    json_cell(&options, (col + 1) as u32, value),
+                                )
+                            }),
+                        );
+                    }
+                    if let Some(ref pb) = progress {
+                        pb.inc(1);
+                    }
+                }
             }
-
-            // Write data
+        } else if first.is_array() {
+            // Array format
             for (row_idx, line) in lines.iter().enumerate() {
                 let item: serde_json::Value = serde_json::from_str(line)?;
-                if let serde_json::Value::Object(obj) = item {
-                    for (col, key) in keys.iter().enumerate() {
-                        let cell_ref = CellRef::new((col + 1) as u32, (row_idx + 2) as u32);
-                        let value = obj.get(key).unwrap_or(&serde_json::Value::Null);
-                        workbook.set_cell(sheet_name, cell_ref, json_to_cell(value))?;
-                    }
+                if let serde_json::Value::Array(arr) = item {
+                    write_row(
+                        sheet_obj,
+                        &options,
+                        text_style,
+                        arr.iter().enumerate().map(|(col, value)| {
+                            (
+                                CellRef::new((col + 1) as u32, (row_idx + 1) as u32),
+                                json_cell(&options, (col + 1) as u32, value),
+                            )
+                        }),
+                    );
                 }
-            }
-        }
-    } else if first.is_array() {
-        // Array format
-        for (row_idx, line) in lines.iter().enumerate() {
-            let item: serde_json::Value = serde_json::from_str(line)?;
-            if let serde_json::Value::Array(arr) = item {
-                for (col, value) in arr.iter().enumerate() {
-                    let cell_ref = CellRef::new((col + 1) as u32, (row_idx + 1) as u32);
-                    workbook.set_cell(sheet_name, cell_ref, json_to_cell(value))?;
+                if let Some(ref pb) = progress {
+                    pb.inc(1);
                 }
             }
-        }
-    } else if first.is_object() {
-        // Object format without headers
-        for (row_idx, line) in lines.iter().enumerate() {
-            let item: serde_json::Value = serde_json::from_str(line)?;
-            if let serde_json::Value::Object(obj) = item {
-                for (col, (_key, value)) in obj.iter().enumerate() {
-                    let cell_ref = CellRef::new((col + 1) as u32, (row_idx + 1) as u32);
-                    workbook.set_cell(sheet_name, cell_ref, json_to_cell(value))?;
+        } else if first.is_object() {
+            // Object format without headers
+            for (row_idx, line) in lines.iter().enumerate() {
+                let item: serde_json::Value = serde_json::from_str(line)?;
+                if let serde_json::Value::Object(obj) = item {
+                    write_row(
+                        sheet_obj,
+                        &options,
+                        text_style,
+                        obj.iter().enumerate().map(|(col, (_key, value))| {
+                            (
+                                CellRef::new((col + 1) as u32, (row_idx + 1) as u32),
+                                json_cell(&options, (col + 1) as u32, value),
+                            )
+                        }),
+                    );
+                }
+                if let Some(ref pb) = progress {
+                    pb.inc(1);
                 }
             }
         }
+        Ok(())
+    })??;
+    if let Some(ref pb) = progress {
+        pb.finish_and_clear();
     }
 
     workbook.save_as(dest)?;
 
-    if !global.quiet {
-        println!(
-            "Imported {} rows to {}",
-            lines.len().to_string().green(),
-            dest.display()
-        );
-    }
+    output
+        .file(dest)
+        .target("sheet", sheet_name)
+        .target("source", source.display().to_string())
+        .count("rows", lines.len())
+        .print(
+            global,
+            format!(
+                "Imported {} rows to {}",
+                lines.len().to_string().green(),
+                dest.display()
+            ),
+        )?;
 
     Ok(())
 }
@@ -408,6 +869,11 @@ mod tests {
             json_errors: false,
             dry_run: false,
             output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
         }
     }
 
@@ -462,7 +928,15 @@ mod tests {
 
         std::fs::write(&csv_path, "Name,Age\nAlice,30\nBob,25").unwrap();
 
-        let result = import_csv(&csv_path, &xlsx_path, None, ',', false, &default_global());
+        let result = import_csv(
+            &csv_path,
+            &xlsx_path,
+            None,
+            ',',
+            false,
+            ImportOptions::default(),
+            &default_global(),
+        );
         assert!(result.is_ok());
         assert!(xlsx_path.exists());
 
@@ -483,7 +957,15 @@ mod tests {
         let mut global = default_global();
         global.dry_run = true;
 
-        let result = import_csv(&csv_path, &xlsx_path, None, ',', false, &global);
+        let result = import_csv(
+            &csv_path,
+            &xlsx_path,
+            None,
+            ',',
+            false,
+            ImportOptions::default(),
+            &global,
+        );
         assert!(result.is_ok());
         assert!(!xlsx_path.exists()); // Should not create file
     }
@@ -496,7 +978,13 @@ mod tests {
 
         std::fs::write(&tsv_path, "Name\tAge\nAlice\t30\nBob\t25").unwrap();
 
-        let result = import_tsv(&tsv_path, &xlsx_path, None, &default_global());
+        let result = import_tsv(
+            &tsv_path,
+            &xlsx_path,
+            None,
+            ImportOptions::default(),
+            &default_global(),
+        );
         assert!(result.is_ok());
         assert!(xlsx_path.exists());
     }
@@ -513,7 +1001,13 @@ mod tests {
         )
         .unwrap();
 
-        let result = import_json(&json_path, &xlsx_path, None, &default_global());
+        let result = import_json(
+            &json_path,
+            &xlsx_path,
+            None,
+            ImportOptions::default(),
+            &default_global(),
+        );
         assert!(result.is_ok());
         assert!(xlsx_path.exists());
     }
@@ -526,7 +1020,13 @@ mod tests {
 
         std::fs::write(&json_path, r#"[["Name", "Age"], ["Alice", 30]]"#).unwrap();
 
-        let result = import_json(&json_path, &xlsx_path, None, &default_global());
+        let result = import_json(
+            &json_path,
+            &xlsx_path,
+            None,
+            ImportOptions::default(),
+            &default_global(),
+        );
         assert!(result.is_ok());
     }
 
@@ -543,7 +1043,14 @@ mod tests {
         )
         .unwrap();
 
-        let result = import_ndjson(&ndjson_path, &xlsx_path, None, true, &default_global());
+        let result = import_ndjson(
+            &ndjson_path,
+            &xlsx_path,
+            None,
+            true,
+            ImportOptions::default(),
+            &default_global(),
+        );
         assert!(result.is_ok());
         assert!(xlsx_path.exists());
     }
@@ -561,7 +1068,14 @@ mod tests {
         )
         .unwrap();
 
-        let result = import_ndjson(&ndjson_path, &xlsx_path, None, false, &default_global());
+        let result = import_ndjson(
+            &ndjson_path,
+            &xlsx_path,
+            None,
+            false,
+            ImportOptions::default(),
+            &default_global(),
+        );
         assert!(result.is_ok());
     }
 
@@ -581,6 +1095,7 @@ mod tests {
             Some("Data"),
             ',',
             false,
+            ImportOptions::default(),
             &default_global(),
         );
         assert!(result.is_ok());
@@ -597,7 +1112,15 @@ mod tests {
 
         std::fs::write(&csv_path, "Name,Age\nAlice,30\nBob,25").unwrap();
 
-        let result = import_csv(&csv_path, &xlsx_path, None, ',', true, &default_global());
+        let result = import_csv(
+            &csv_path,
+            &xlsx_path,
+            None,
+            ',',
+            true,
+            ImportOptions::default(),
+            &default_global(),
+        );
         assert!(result.is_ok());
     }
 
@@ -609,7 +1132,15 @@ mod tests {
 
         std::fs::write(&csv_path, "Name;Age\nAlice;30").unwrap();
 
-        let result = import_csv(&csv_path, &xlsx_path, None, ';', false, &default_global());
+        let result = import_csv(
+            &csv_path,
+            &xlsx_path,
+            None,
+            ';',
+            false,
+            ImportOptions::default(),
+            &default_global(),
+        );
         assert!(result.is_ok());
     }
 
@@ -624,7 +1155,13 @@ mod tests {
         let mut global = default_global();
         global.dry_run = true;
 
-        let result = import_json(&json_path, &xlsx_path, None, &global);
+        let result = import_json(
+            &json_path,
+            &xlsx_path,
+            None,
+            ImportOptions::default(),
+            &global,
+        );
         assert!(result.is_ok());
         assert!(!xlsx_path.exists());
     }
@@ -640,7 +1177,14 @@ mod tests {
         let mut global = default_global();
         global.dry_run = true;
 
-        let result = import_ndjson(&ndjson_path, &xlsx_path, None, true, &global);
+        let result = import_ndjson(
+            &ndjson_path,
+            &xlsx_path,
+            None,
+            true,
+            ImportOptions::default(),
+            &global,
+        );
         assert!(result.is_ok());
         assert!(!xlsx_path.exists());
     }
@@ -653,7 +1197,13 @@ mod tests {
 
         std::fs::write(&tsv_path, "A\tB\n1\t2").unwrap();
 
-        let result = import_tsv(&tsv_path, &xlsx_path, Some("MySheet"), &default_global());
+        let result = import_tsv(
+            &tsv_path,
+            &xlsx_path,
+            Some("MySheet"),
+            ImportOptions::default(),
+            &default_global(),
+        );
         assert!(result.is_ok());
 
         let wb = Workbook::open(&xlsx_path).unwrap();
@@ -668,7 +1218,13 @@ mod tests {
 
         std::fs::write(&json_path, r#"[{"x": 1}]"#).unwrap();
 
-        let result = import_json(&json_path, &xlsx_path, Some("JsonData"), &default_global());
+        let result = import_json(
+            &json_path,
+            &xlsx_path,
+            Some("JsonData"),
+            ImportOptions::default(),
+            &default_global(),
+        );
         assert!(result.is_ok());
 
         let wb = Workbook::open(&xlsx_path).unwrap();
@@ -688,6 +1244,7 @@ mod tests {
             &xlsx_path,
             Some("NdjsonData"),
             true,
+            ImportOptions::default(),
             &default_global(),
         );
         assert!(result.is_ok());
@@ -711,6 +1268,9 @@ mod tests {
                 sheet: None,
                 delimiter: ',',
                 header: false,
+                sanitize_formulas: false,
+                decimal: false,
+                preserve_ids: Vec::new(),
             },
         };
 
@@ -731,6 +1291,9 @@ mod tests {
                 source: tsv_path,
                 dest: xlsx_path,
                 sheet: None,
+                sanitize_formulas: false,
+                decimal: false,
+                preserve_ids: Vec::new(),
             },
         };
 
@@ -751,6 +1314,9 @@ mod tests {
                 source: json_path,
                 dest: xlsx_path,
                 sheet: None,
+                sanitize_formulas: false,
+                decimal: false,
+                preserve_ids: Vec::new(),
             },
         };
 
@@ -772,6 +1338,9 @@ mod tests {
                 dest: xlsx_path,
                 sheet: None,
                 header: true,
+                sanitize_formulas: false,
+                decimal: false,
+                preserve_ids: Vec::new(),
             },
         };
 
@@ -801,4 +1370,211 @@ mod tests {
         assert_eq!(parse_value("no"), CellValue::Boolean(false));
         assert_eq!(parse_value("NO"), CellValue::Boolean(false));
     }
+
+    #[test]
+    fn test_unique_sheet_name() {
+        let taken: HashSet<String> = ["Sales".to_string(), "Sales (2)".to_string()].into();
+        assert_eq!(unique_sheet_name(&taken, "Sales"), "Sales (3)");
+        assert_eq!(unique_sheet_name(&taken, "a/b:c"), "a_b_c");
+        assert_eq!(unique_sheet_name(&taken, "'quoted'"), "quoted");
+        assert_eq!(unique_sheet_name(&taken, ""), "Sheet");
+        let long = "x".repeat(40);
+        assert_eq!(unique_sheet_name(&taken, &long), "x".repeat(31));
+        let taken: HashSet<String> = ["x".repeat(31)].into();
+        assert_eq!(
+            unique_sheet_name(&taken, &long),
+            format!("{} (2)", "x".repeat(27))
+        );
+    }
+
+    #[test]
+    fn test_run_import_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("data");
+        std::fs::create_dir(&source).unwrap();
+        std::fs::write(source.join("sales.csv"), "Region,Total\nNorth,10\n").unwrap();
+        std::fs::write(source.join("costs.tsv"), "Item\tCost\nRent\t5\n").unwrap();
+        std::fs::write(source.join("notes.txt"), "skip me").unwrap();
+        let dest = temp_dir.path().join("out.xlsx");
+
+        let args = |pattern: &str, sheet_name_from| ImportArgs {
+            command: ImportCommand::Dir {
+                source: source.clone(),
+                dest: dest.clone(),
+                pattern: pattern.to_string(),
+                sheet_name_from,
+                sanitize_formulas: false,
+                decimal: false,
+                preserve_ids: Vec::new(),
+            },
+        };
+        run(&args("*.?sv", SheetNameFrom::Filename), &default_global()).unwrap();
+
+        let wb = Workbook::open(&dest).unwrap();
+        assert_eq!(wb.sheet_names(), vec!["costs", "sales"]);
+        let sales = wb.get_sheet("sales").unwrap();
+        assert_eq!(
+            sales.get_value(&CellRef::new(1, 2)),
+            CellValue::String("North".to_string())
+        );
+        assert_eq!(
+            sales.get_value(&CellRef::new(2, 2)),
+            CellValue::Number(10.0)
+        );
+        let costs = wb.get_sheet("costs").unwrap();
+        assert_eq!(costs.get_value(&CellRef::new(2, 2)), CellValue::Number(5.0));
+
+        // Importing again into the same file keeps existing sheets
+        run(&args("*.csv", SheetNameFrom::Index), &default_global()).unwrap();
+        let wb = Workbook::open(&dest).unwrap();
+        assert_eq!(wb.sheet_names(), vec!["costs", "sales", "Sheet1"]);
+
+        assert!(run(&args("*.xml", SheetNameFrom::Filename), &default_global()).is_err());
+    }
+
+    #[test]
+    fn test_import_sanitize_formulas() {
+        let temp_dir = TempDir::new().unwrap();
+        let csv_path = temp_dir.path().join("untrusted.csv");
+        let json_path = temp_dir.path().join("untrusted.json");
+        let xlsx_path = temp_dir.path().join("untrusted.xlsx");
+        std::fs::write(&csv_path, "=1+1,-5,@SUM(A1),plain\n").unwrap();
+        std::fs::write(&json_path, r#"[{"+key": "=cmd", "n": -2}]"#).unwrap();
+        let options = ImportOptions::new().sanitize_formulas(true);
+
+        import_csv(
+            &csv_path,
+            &xlsx_path,
+            None,
+            ',',
+            false,
+            options.clone(),
+            &default_global(),
+        )
+        .unwrap();
+        let wb = Workbook::open(&xlsx_path).unwrap();
+        let sheet = wb.get_sheet("Sheet1").unwrap();
+        assert_eq!(
+            sheet.get_value(&CellRef::new(1, 1)),
+            CellValue::string("'=1+1")
+        );
+        assert_eq!(
+            sheet.get_value(&CellRef::new(2, 1)),
+            CellValue::Number(-5.0)
+        );
+        assert_eq!(
+            sheet.get_value(&CellRef::new(3, 1)),
+            CellValue::string("'@SUM(A1)")
+        );
+        assert_eq!(
+            sheet.get_value(&CellRef::new(4, 1)),
+            CellValue::string("plain")
+        );
+
+        import_json(
+            &json_path,
+            &xlsx_path,
+            Some("Json"),
+            options,
+            &default_global(),
+        )
+        .unwrap();
+        let wb = Workbook::open(&xlsx_path).unwrap();
+        let sheet = wb.get_sheet("Json").unwrap();
+        assert_eq!(
+            sheet.get_value(&CellRef::new(1, 1)),
+            CellValue::string("'+key")
+        );
+        assert_eq!(
+            sheet.get_value(&CellRef::new(1, 2)),
+            CellValue::string("'=cmd")
+        );
+        assert_eq!(
+            sheet.get_value(&CellRef::new(2, 2)),
+            CellValue::Number(-2.0)
+        );
+    }
+    #[test]
+    fn test_import_decimal() {
+        use std::io::Read;
+
+        let temp_dir = TempDir::new().unwrap();
+        let csv_path = temp_dir.path().join("amounts.csv");
+        let xlsx_path = temp_dir.path().join("amounts.xlsx");
+        std::fs::write(&csv_path, "12345678901234567.89,0.10,n/a\n").unwrap();
+
+        import_csv(
+            &csv_path,
+            &xlsx_path,
+            None,
+            ',',
+            false,
+            ImportOptions::new().decimal(true),
+            &default_global(),
+        )
+        .unwrap();
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&xlsx_path).unwrap()).unwrap();
+        let mut xml = String::new();
+        archive
+            .by_name("xl/worksheets/sheet1.xml")
+            .unwrap()
+            .read_to_string(&mut xml)
+            .unwrap();
+        assert!(xml.contains("<v>12345678901234567.89</v>"));
+        assert!(xml.contains("<v>0.10</v>"));
+
+        // Files store numbers as text, read back as floats
+        let wb = Workbook::open(&xlsx_path).unwrap();
+        let sheet = wb.get_sheet("Sheet1").unwrap();
+        assert_eq!(sheet.get_value(&CellRef::new(2, 1)), CellValue::Number(0.1));
+        assert_eq!(
+            sheet.get_value(&CellRef::new(3, 1)),
+            CellValue::string("n/a")
+        );
+    }
+
+    #[test]
+    fn test_import_preserve_ids() {
+        let temp_dir = TempDir::new().unwrap();
+        let csv_path = temp_dir.path().join("cards.csv");
+        let json_path = temp_dir.path().join("cards.json");
+        let xlsx_path = temp_dir.path().join("cards.xlsx");
+        std::fs::write(&csv_path, "4111111111111111,00123,42,7\n").unwrap();
+        std::fs::write(&json_path, r#"[[4111111111111111, "00123", 42]]"#).unwrap();
+
+        let options = import_options(false, false, &["auto".to_string(), "d".to_string()]).unwrap();
+        assert_eq!(options.text_columns, vec![4]);
+        assert!(import_options(false, false, &["1".to_string()]).is_err());
+
+        let check = |wb: &Workbook, ids: &[(u32, &str)], number: u32| {
+            let sheet = wb.get_sheet("Sheet1").unwrap();
+            for &(col, text) in ids {
+                let cell = sheet.get_cell(&CellRef::new(col, 1)).unwrap();
+                assert_eq!(cell.value, CellValue::string(text));
+                let style = wb.style_registry().get(cell.style_id.unwrap()).unwrap();
+                assert_eq!(style.number_format, NumberFormat::text());
+            }
+            let cell = sheet.get_cell(&CellRef::new(number, 1)).unwrap();
+            assert_eq!(cell.value, CellValue::Number(42.0));
+            assert_eq!(cell.style_id, None);
+        };
+
+        import_csv(
+            &csv_path,
+            &xlsx_path,
+            None,
+            ',',
+            false,
+            options.clone(),
+            &default_global(),
+        )
+        .unwrap();
+        let wb = Workbook::open(&xlsx_path).unwrap();
+        check(&wb, &[(1, "4111111111111111"), (2, "00123"), (4, "7")], 3);
+
+        std::fs::remove_file(&xlsx_path).unwrap();
+        import_json(&json_path, &xlsx_path, None, options, &default_global()).unwrap();
+        let wb = Workbook::open(&xlsx_path).unwrap();
+        check(&wb, &[(1, "4111111111111111"), (2, "00123")], 3);
+    }
 }
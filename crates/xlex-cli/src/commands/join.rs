@@ -0,0 +1,283 @@
+//! Joining two sheets on a key column.
+//!
+//! `xlex join` loads the right sheet into a hash table, spilling it to disk
+//! past `--spill-after` rows, then streams the left sheet past it into a
+//! new workbook. The first row of each sheet is its header; key columns are
+//! named by header text or column letter.
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use xlex_core::join::{JoinOptions, JoinTable, JoinType};
+use xlex_core::writer::StreamingSheetWriter;
+use xlex_core::{CellValue, LazyWorkbook, Record, XlexError};
+
+use super::output::CommandOutput;
+use super::transform::letter_column;
+use super::GlobalOptions;
+
+/// Arguments for the join command.
+#[derive(Parser)]
+pub struct JoinArgs {
+    /// Left xlsx file; every output row starts with one of its rows
+    pub left: std::path::PathBuf,
+    /// Sheet of the left file
+    pub left_sheet: String,
+    /// Right xlsx file, looked up by key
+    pub right: std::path::PathBuf,
+    /// Sheet of the right file
+    pub right_sheet: String,
+    /// Key columns as LEFT=RIGHT, or one name for both (header text or letter)
+    #[arg(long)]
+    pub on: String,
+    /// Which left rows to keep
+    #[arg(long = "type", value_enum, default_value = "inner")]
+    pub join_type: JoinTypeArg,
+    /// Output xlsx file
+    #[arg(long)]
+    pub out: std::path::PathBuf,
+    /// Sheet name in the output (default: the left sheet's)
+    #[arg(long)]
+    pub out_sheet: Option<String>,
+    /// Right rows held in memory before spilling to disk
+    #[arg(long, default_value = "1000000")]
+    pub spill_after: usize,
+    /// Overwrite existing file
+    #[arg(long, short = 'F')]
+    pub force: bool,
+}
+
+/// Join types for the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum JoinTypeArg {
+    /// Only left rows with a match
+    Inner,
+    /// Every left row, blank where nothing matches
+    Left,
+}
+
+impl From<JoinTypeArg> for JoinType {
+    fn from(arg: JoinTypeArg) -> Self {
+        match arg {
+            JoinTypeArg::Inner => JoinType::Inner,
+            JoinTypeArg::Left => JoinType::Left,
+        }
+    }
+}
+
+/// Reads the first row of a sheet, laid out by column.
+pub(super) fn header_row(workbook: &LazyWorkbook, sheet: &str) -> Result<Vec<CellValue>> {
+    Ok(workbook
+        .first_row(sheet)?
+        .map(|row| Record::from_stream_row(row).values)
+        .unwrap_or_default())
+}
+
+/// Finds the 1-based column `spec` names: its header text, or its letter.
+pub(super) fn header_column(header: &[CellValue], spec: &str) -> Option<u32> {
+    header
+        .iter()
+        .position(|v| v.to_display_string() == spec)
+        .map(|i| i as u32 + 1)
+        .or_else(|| letter_column(spec))
+}
+
+fn key_column(workbook: &LazyWorkbook, sheet: &str, spec: &str) -> Result<u32> {
+    header_column(&header_row(workbook, sheet)?, spec)
+        .ok_or_else(|| anyhow::anyhow!("No column '{}' in the header row of {}", spec, sheet))
+}
+
+/// Run the join command.
+pub fn run(args: &JoinArgs, global: &GlobalOptions) -> Result<()> {
+    if args.out.exists() && !args.force {
+        return Err(XlexError::FileExists {
+            path: args.out.clone(),
+        }
+        .into());
+    }
+    let (left_spec, right_spec) = args.on.split_once('=').unwrap_or((&args.on, &args.on));
+    let (left_spec, right_spec) = (left_spec.trim(), right_spec.trim());
+    if left_spec.is_empty() || right_spec.is_empty() {
+        anyhow::bail!("Invalid --on '{}': expected LEFT=RIGHT", args.on);
+    }
+
+    let left = LazyWorkbook::open(&args.left)?;
+    let right = LazyWorkbook::open(&args.right)?;
+    for (workbook, sheet) in [(&left, &args.left_sheet), (&right, &args.right_sheet)] {
+        if !workbook.has_sheet(sheet) {
+            return Err(XlexError::SheetNotFound {
+                name: sheet.clone(),
+            }
+            .into());
+        }
+    }
+    let left_key = key_column(&left, &args.left_sheet, left_spec)?;
+    let right_key = key_column(&right, &args.right_sheet, right_spec)?;
+    let out_sheet = args.out_sheet.as_deref().unwrap_or(&args.left_sheet);
+
+    if global.dry_run {
+        outln!(
+            "Would join {} and {} on {}={} into {}",
+            args.left_sheet,
+            args.right_sheet,
+            left_spec,
+            right_spec,
+            args.out.display()
+        );
+        return Ok(());
+    }
+
+    let options = JoinOptions {
+        join_type: args.join_type.into(),
+        spill_after: args.spill_after,
+        ..JoinOptions::default()
+    };
+    let mut table = JoinTable::new(right_key, options);
+    right.pipeline(&args.right_sheet).write_to(&mut table)?;
+
+    let mut writer = StreamingSheetWriter::create(&args.out, out_sheet)?;
+    let stats = table.join(left.pipeline(&args.left_sheet), left_key, &mut writer)?;
+    writer.finish()?;
+
+    CommandOutput::new("join")
+        .file(&args.left)
+        .target("sheet", args.left_sheet.as_str())
+        .field("right", args.right.display().to_string())
+        .field("rightSheet", args.right_sheet.as_str())
+        .field("out", args.out.display().to_string())
+        .field("leftRows", stats.left_rows)
+        .field("rightRows", stats.right_rows)
+        .field("rowsWritten", stats.rows_written)
+        .field("spilled", stats.spilled)
+        .print(
+            global,
+            format!(
+                "Joined {} rows of {} with {} into {}{}",
+                stats.rows_written.to_string().green(),
+                args.left_sheet.cyan(),
+                args.right_sheet.cyan(),
+                args.out.display().to_string().cyan(),
+                if stats.spilled {
+                    " (spilled to disk)"
+                } else {
+                    ""
+                }
+            ),
+        )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::OutputFormat;
+    use tempfile::TempDir;
+    use xlex_core::{CellRef, Workbook};
+
+    fn default_global() -> GlobalOptions {
+        GlobalOptions {
+            quiet: true,
+            verbose: false,
+            format: OutputFormat::Text,
+            no_color: true,
+            color: false,
+            json_errors: false,
+            dry_run: false,
+            output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
+        }
+    }
+
+    fn save(dir: &TempDir, name: &str, sheet: &str, rows: &[&[&str]]) -> std::path::PathBuf {
+        let path = dir.path().join(name);
+        let mut wb = Workbook::with_sheets(&[sheet]);
+        for (r, row) in rows.iter().enumerate() {
+            for (c, value) in row.iter().enumerate() {
+                let value = value
+                    .parse()
+                    .map(CellValue::Number)
+                    .unwrap_or_else(|_| CellValue::string(*value));
+                let cell_ref = CellRef::new(c as u32 + 1, r as u32 + 1);
+                wb.set_cell(sheet, cell_ref, value).unwrap();
+            }
+        }
+        wb.save_as(&path).unwrap();
+        path
+    }
+
+    fn join_args(dir: &TempDir, on: &str, join_type: JoinTypeArg, spill_after: usize) -> JoinArgs {
+        let left = save(
+            dir,
+            "orders.xlsx",
+            "Sheet1",
+            &[&["SKU", "Qty"], &["A1", "3"], &["Z9", "1"], &["B2", "2"]],
+        );
+        let right = save(
+            dir,
+            "prices.xlsx",
+            "Prices",
+            &[&["Price", "SKU"], &["4.5", "A1"], &["2", "B2"]],
+        );
+        JoinArgs {
+            left,
+            left_sheet: "Sheet1".to_string(),
+            right,
+            right_sheet: "Prices".to_string(),
+            on: on.to_string(),
+            join_type,
+            out: dir.path().join("merged.xlsx"),
+            out_sheet: None,
+            spill_after,
+            force: true,
+        }
+    }
+
+    fn column(path: &std::path::Path, col: u32) -> Vec<String> {
+        let wb = Workbook::open(path).unwrap();
+        let sheet = wb.get_sheet("Sheet1").unwrap();
+        let (_, rows) = sheet.dimensions();
+        (1..=rows)
+            .map(|r| sheet.get_value(&CellRef::new(col, r)).to_display_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_run_join() {
+        let dir = TempDir::new().unwrap();
+        for spill_after in [1_000_000, 1] {
+            let args = join_args(&dir, "A=SKU", JoinTypeArg::Left, spill_after);
+            run(&args, &default_global()).unwrap();
+            assert_eq!(column(&args.out, 1), vec!["SKU", "A1", "Z9", "B2"]);
+            assert_eq!(column(&args.out, 3), vec!["Price", "4.5", "", "2"]);
+
+            let args = join_args(&dir, "SKU", JoinTypeArg::Inner, spill_after);
+            run(&args, &default_global()).unwrap();
+            assert_eq!(column(&args.out, 1), vec!["SKU", "A1", "B2"]);
+        }
+    }
+
+    #[test]
+    fn test_run_join_errors() {
+        let dir = TempDir::new().unwrap();
+        let missing = join_args(&dir, "A=Code", JoinTypeArg::Inner, 10);
+        let err = run(&missing, &default_global()).unwrap_err();
+        assert!(err.to_string().contains("No column 'Code'"));
+
+        let bad = join_args(&dir, "A=", JoinTypeArg::Inner, 10);
+        assert!(run(&bad, &default_global()).is_err());
+
+        let mut exists = join_args(&dir, "SKU", JoinTypeArg::Inner, 10);
+        exists.force = false;
+        std::fs::write(&exists.out, b"").unwrap();
+        assert!(run(&exists, &default_global()).is_err());
+
+        let mut no_sheet = join_args(&dir, "SKU", JoinTypeArg::Inner, 10);
+        no_sheet.right_sheet = "Nope".to_string();
+        assert!(run(&no_sheet, &default_global()).is_err());
+    }
+}
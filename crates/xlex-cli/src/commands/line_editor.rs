@@ -0,0 +1,320 @@
+//! Line editing, history and completion for the interactive modes.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use anyhow::Result;
+use rustyline::completion::{Completer, FilenameCompleter, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::FileHistory;
+use rustyline::validate::Validator;
+use rustyline::{CompletionType, Config, Context, Editor, Helper};
+
+use xlex_core::{CellRef, LazyWorkbook};
+
+/// Commands understood by `xlex repl`.
+pub(super) const SESSION_COMMANDS: &[&str] = &[
+    "info", "sheets", "cell", "row", "search", "embed", "pii", "help", "exit",
+];
+
+/// Most cell references offered at once.
+const MAX_REF_CANDIDATES: usize = 100;
+
+/// Completes command words, and either file paths (`xlex interactive`) or
+/// sheet names and cell references of the loaded workbook (`xlex repl`).
+pub(super) struct ReplHelper<'a> {
+    /// Command names with their subcommand names
+    commands: Vec<(String, Vec<String>)>,
+    workbook: Option<&'a LazyWorkbook>,
+    files: FilenameCompleter,
+    /// Cell references per sheet, read on first use
+    refs: RefCell<HashMap<String, Vec<String>>>,
+}
+
+impl<'a> ReplHelper<'a> {
+    /// Completion for `xlex interactive`: commands, subcommands, then files.
+    pub(super) fn for_commands(commands: Vec<(String, Vec<String>)>) -> Self {
+        Self {
+            commands,
+            workbook: None,
+            files: FilenameCompleter::new(),
+            refs: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Completion for `xlex repl`: session commands, sheets, cell references.
+    pub(super) fn for_session(workbook: &'a LazyWorkbook) -> Self {
+        let commands = SESSION_COMMANDS
+            .iter()
+            .map(|c| {
+                let subcommands = if *c == "sheets" {
+                    vec!["list".to_string()]
+                } else {
+                    Vec::new()
+                };
+                (c.to_string(), subcommands)
+            })
+            .collect();
+        Self {
+            commands,
+            workbook: Some(workbook),
+            files: FilenameCompleter::new(),
+            refs: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Non-empty cells of a sheet as A1 references, in row order.
+    fn cell_refs(&self, workbook: &LazyWorkbook, sheet: &str) -> Vec<String> {
+        self.refs
+            .borrow_mut()
+            .entry(sheet.to_string())
+            .or_insert_with(|| {
+                workbook
+                    .stream_rows(sheet)
+                    .map(|rows| {
+                        rows.iter()
+                            .flat_map(|row| row.cells.iter().map(|(r, _)| r.to_a1()))
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            })
+            .clone()
+    }
+
+    /// Candidates for the word being typed, given the words before it.
+    /// Returns `None` where file names should be completed instead.
+    fn candidates(&self, before: &[&str], prefix: &str) -> Option<Vec<String>> {
+        let starts_with =
+            |candidate: &str| candidate.to_lowercase().starts_with(&prefix.to_lowercase());
+        let Some(command) = before.first() else {
+            return Some(
+                self.commands
+                    .iter()
+                    .map(|(name, _)| name.clone())
+                    .filter(|name| starts_with(name))
+                    .collect(),
+            );
+        };
+        let subcommands = self
+            .commands
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(command))
+            .map(|(_, subs)| subs.as_slice())
+            .unwrap_or_default();
+        if before.len() == 1 && !subcommands.is_empty() {
+            return Some(
+                subcommands
+                    .iter()
+                    .filter(|name| starts_with(name))
+                    .cloned()
+                    .collect(),
+            );
+        }
+
+        let workbook = self.workbook?;
+        let sheets = || -> Vec<String> {
+            workbook
+                .sheet_names()
+                .iter()
+                .filter(|name| starts_with(name))
+                .cloned()
+                .collect()
+        };
+        let candidates = match (command.to_lowercase().as_str(), before.len()) {
+            ("cell" | "row" | "pii", 1) | ("search" | "find", 2) => sheets(),
+            ("cell", 2) => {
+                let refs = self.cell_refs(workbook, before[1]);
+                let prefix = prefix.to_uppercase();
+                refs.into_iter()
+                    .filter(|r| r.starts_with(&prefix))
+                    .take(MAX_REF_CANDIDATES)
+                    .collect()
+            }
+            ("row", 2) => {
+                let mut rows: Vec<String> = self
+                    .cell_refs(workbook, before[1])
+                    .iter()
+                    .filter_map(|r| CellRef::parse(r).ok().map(|c| c.row.to_string()))
+                    .filter(|row| row.starts_with(prefix))
+                    .collect();
+                rows.dedup();
+                rows.truncate(MAX_REF_CANDIDATES);
+                rows
+            }
+            _ => Vec::new(),
+        };
+        Some(candidates)
+    }
+}
+
+impl Completer for ReplHelper<'_> {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let head = &line[..pos];
+        let start = head.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let before: Vec<&str> = head[..start].split_whitespace().collect();
+        match self.candidates(&before, &head[start..]) {
+            Some(candidates) => Ok((
+                start,
+                candidates
+                    .into_iter()
+                    .map(|c| Pair {
+                        display: c.clone(),
+                        replacement: c,
+                    })
+                    .collect(),
+            )),
+            None => self.files.complete(line, pos, ctx),
+        }
+    }
+}
+
+impl Hinter for ReplHelper<'_> {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper<'_> {}
+
+impl Validator for ReplHelper<'_> {}
+
+impl Helper for ReplHelper<'_> {}
+
+/// A prompt with line editing, Ctrl-R history search and completion,
+/// keeping history in the config directory between runs.
+pub(super) struct LineEditor<'a> {
+    editor: Editor<ReplHelper<'a>, FileHistory>,
+    history_path: Option<std::path::PathBuf>,
+}
+
+impl<'a> LineEditor<'a> {
+    /// Creates an editor keeping its history in `history_file` under the
+    /// config directory.
+    pub(super) fn new(helper: ReplHelper<'a>, history_file: &str) -> Result<Self> {
+        let config = Config::builder()
+            .auto_add_history(false)
+            .completion_type(CompletionType::List)
+            .max_history_size(1000)?
+            .build();
+        let mut editor = Editor::with_config(config)?;
+        editor.set_helper(Some(helper));
+
+        // History is a convenience: a missing config dir or unreadable
+        // file starts an empty one
+        let history_path = super::get_config_dir()
+            .ok()
+            .map(|dir| dir.join(history_file));
+        if let Some(path) = &history_path {
+            let _ = editor.load_history(path);
+        }
+        Ok(Self {
+            editor,
+            history_path,
+        })
+    }
+
+    /// Reads a trimmed line. Returns `None` at end of input (Ctrl-D);
+    /// Ctrl-C discards the current line.
+    pub(super) fn read_line(&mut self, prompt: &str) -> Result<Option<String>> {
+        match self.editor.readline(prompt) {
+            Ok(line) => {
+                let line = line.trim().to_string();
+                if !line.is_empty() {
+                    let _ = self.editor.add_history_entry(line.as_str());
+                }
+                Ok(Some(line))
+            }
+            Err(ReadlineError::Interrupted) => Ok(Some(String::new())),
+            Err(ReadlineError::Eof) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl Drop for LineEditor<'_> {
+    fn drop(&mut self) {
+        if let Some(path) = &self.history_path {
+            if let Some(dir) = path.parent() {
+                let _ = std::fs::create_dir_all(dir);
+            }
+            let _ = self.editor.save_history(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use xlex_core::{CellValue, Workbook};
+
+    #[test]
+    fn test_command_completion() {
+        let helper = ReplHelper::for_commands(vec![
+            (
+                "cell".to_string(),
+                vec!["get".to_string(), "set".to_string()],
+            ),
+            ("clone".to_string(), Vec::new()),
+            ("info".to_string(), Vec::new()),
+        ]);
+        assert_eq!(
+            helper.candidates(&[], "c"),
+            Some(vec!["cell".to_string(), "clone".to_string()])
+        );
+        assert_eq!(
+            helper.candidates(&["cell"], "s"),
+            Some(vec!["set".to_string()])
+        );
+        // File arguments fall back to file name completion
+        assert_eq!(helper.candidates(&["info"], "rep"), None);
+        assert_eq!(helper.candidates(&["cell", "get"], ""), None);
+    }
+
+    #[test]
+    fn test_session_completion() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("complete.xlsx");
+        let mut wb = Workbook::with_sheets(&["Sales", "Summary", "Costs"]);
+        for (col, row) in [(1, 1), (2, 1), (2, 12), (27, 3)] {
+            wb.set_cell("Sales", CellRef::new(col, row), CellValue::Number(1.0))
+                .unwrap();
+        }
+        wb.save_as(&file_path).unwrap();
+        let workbook = LazyWorkbook::open(&file_path).unwrap();
+        let helper = ReplHelper::for_session(&workbook);
+
+        assert_eq!(
+            helper.candidates(&[], "s"),
+            Some(vec!["sheets".to_string(), "search".to_string()])
+        );
+        assert_eq!(
+            helper.candidates(&["sheets"], ""),
+            Some(vec!["list".to_string()])
+        );
+        assert_eq!(
+            helper.candidates(&["cell"], "s"),
+            Some(vec!["Sales".to_string(), "Summary".to_string()])
+        );
+        assert_eq!(
+            helper.candidates(&["cell", "Sales"], "b"),
+            Some(vec!["B1".to_string(), "B12".to_string()])
+        );
+        assert_eq!(
+            helper.candidates(&["row", "Sales"], "1"),
+            Some(vec!["1".to_string(), "12".to_string()])
+        );
+        assert_eq!(
+            helper.candidates(&["search", "revenue"], "C"),
+            Some(vec!["Costs".to_string()])
+        );
+        assert_eq!(helper.candidates(&["info"], ""), Some(Vec::new()));
+    }
+}
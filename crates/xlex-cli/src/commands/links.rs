@@ -0,0 +1,417 @@
+//! External workbook link operations.
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+
+use xlex_core::{CellValue, Workbook};
+
+use super::{GlobalOptions, OutputFormat};
+
+/// Arguments for external link operations.
+#[derive(Parser)]
+pub struct LinksArgs {
+    #[command(subcommand)]
+    pub command: LinksCommand,
+}
+
+#[derive(Subcommand)]
+pub enum LinksCommand {
+    /// List links to other workbooks
+    List {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+    },
+    /// Repoint links at a new workbook path
+    Update {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Link number, as in [1] (see `links list`)
+        #[arg(long, required_unless_present = "from", conflicts_with = "from")]
+        link: Option<usize>,
+        /// Replace this path prefix in every link (e.g., \\oldserver\share)
+        #[arg(long)]
+        from: Option<String>,
+        /// New path, or the replacement prefix with --from
+        #[arg(long)]
+        to: String,
+    },
+    /// Replace linked formulas with their cached values and drop the links
+    Break {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Link number to break (default: all links)
+        #[arg(long)]
+        link: Option<usize>,
+    },
+}
+
+/// Run external link operations.
+pub fn run(args: &LinksArgs, global: &GlobalOptions) -> Result<()> {
+    match &args.command {
+        LinksCommand::List { file } => list(file, global),
+        LinksCommand::Update {
+            file,
+            link,
+            from,
+            to,
+        } => update(file, *link, from.as_deref(), to, global),
+        LinksCommand::Break { file, link } => break_links(file, *link, global),
+    }
+}
+
+/// Counts the formulas (cells and defined names) using each link.
+fn usage_counts(workbook: &Workbook) -> Vec<usize> {
+    let mut counts = vec![0; workbook.external_links().len()];
+    let mut tally = |formula: &str| {
+        for index in xlex_core::formula::external_links(formula) {
+            if let Some(count) = counts.get_mut(index.wrapping_sub(1)) {
+                *count += 1;
+            }
+        }
+    };
+    for sheet_name in workbook.sheet_names() {
+        if let Some(sheet) = workbook.get_sheet(sheet_name) {
+            for cell in sheet.cells() {
+                if let CellValue::Formula { formula, .. } = &cell.value {
+                    tally(formula);
+                }
+            }
+        }
+    }
+    for name in workbook.defined_names() {
+        tally(&name.reference);
+    }
+    counts
+}
+
+fn list(file: &std::path::Path, global: &GlobalOptions) -> Result<()> {
+    let workbook = Workbook::open(file)?;
+    let counts = usage_counts(&workbook);
+
+    if global.format == OutputFormat::Json {
+        let items: Vec<serde_json::Value> = workbook
+            .external_links()
+            .iter()
+            .zip(&counts)
+            .enumerate()
+            .map(|(i, (link, count))| {
+                serde_json::json!({
+                    "link": i + 1,
+                    "target": link.target,
+                    "sheets": link.sheet_names(),
+                    "formulas": count,
+                })
+            })
+            .collect();
+        let json = serde_json::json!({
+            "file": file.display().to_string(),
+            "links": items,
+        });
+        outln!("{}", serde_json::to_string_pretty(&json)?);
+    } else if workbook.external_links().is_empty() {
+        outln!("No external links in {}", file.display());
+    } else {
+        for (i, (link, count)) in workbook.external_links().iter().zip(&counts).enumerate() {
+            outln!(
+                "{} {} ({} formula(s); sheets: {})",
+                format!("[{}]", i + 1).cyan(),
+                link.target,
+                count,
+                link.sheet_names().join(", ")
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn update(
+    file: &std::path::Path,
+    link: Option<usize>,
+    from: Option<&str>,
+    to: &str,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let mut workbook = Workbook::open(file)?;
+
+    // Work out the new target of every affected link
+    let changes: Vec<(usize, String, String)> = match (link, from) {
+        (Some(index), _) => {
+            let old = index
+                .checked_sub(1)
+                .and_then(|i| workbook.external_links().get(i))
+                .map(|l| l.target.clone())
+                .ok_or_else(|| anyhow::anyhow!("External link [{}] does not exist", index))?;
+            vec![(index, old, to.to_string())]
+        }
+        (None, Some(prefix)) => workbook
+            .external_links()
+            .iter()
+            .enumerate()
+            .filter_map(|(i, l)| {
+                l.target
+                    .strip_prefix(prefix)
+                    .map(|rest| (i + 1, l.target.clone(), format!("{}{}", to, rest)))
+            })
+            .collect(),
+        (None, None) => anyhow::bail!("Specify --link or --from"),
+    };
+
+    if global.dry_run {
+        for (index, old, new) in &changes {
+            outln!("Would repoint [{}] {} -> {}", index, old, new);
+        }
+        return Ok(());
+    }
+
+    for (index, _, new) in &changes {
+        workbook.set_external_link_target(*index, new.as_str())?;
+    }
+    if !changes.is_empty() {
+        workbook.save()?;
+    }
+
+    if !global.quiet {
+        if global.format == OutputFormat::Json {
+            let items: Vec<serde_json::Value> = changes
+                .iter()
+                .map(|(index, old, new)| {
+                    serde_json::json!({
+                        "link": index,
+                        "from": old,
+                        "to": new,
+                    })
+                })
+                .collect();
+            let json = serde_json::json!({
+                "file": file.display().to_string(),
+                "updated": items,
+            });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else if changes.is_empty() {
+            outln!("No links matched");
+        } else {
+            for (index, old, new) in &changes {
+                outln!("Repointed [{}] {} -> {}", index, old, new.green());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn break_links(file: &std::path::Path, link: Option<usize>, global: &GlobalOptions) -> Result<()> {
+    let mut workbook = Workbook::open(file)?;
+    let count = workbook.external_links().len();
+    let indices: Vec<usize> = match link {
+        Some(index) if index == 0 || index > count => {
+            anyhow::bail!("External link [{}] does not exist", index)
+        }
+        Some(index) => vec![index],
+        // Highest first, so earlier numbers stay valid as links are removed
+        None => (1..=count).rev().collect(),
+    };
+
+    if global.dry_run {
+        for index in indices.iter().rev() {
+            outln!(
+                "Would break [{}] {}",
+                index,
+                workbook.external_links()[index - 1].target
+            );
+        }
+        return Ok(());
+    }
+
+    let mut broken = Vec::new();
+    for &index in &indices {
+        let target = workbook.external_links()[index - 1].target.clone();
+        let converted = workbook.break_external_link(index)?;
+        broken.push((index, target, converted));
+    }
+    broken.reverse();
+    if !broken.is_empty() {
+        workbook.save()?;
+    }
+
+    if !global.quiet {
+        if global.format == OutputFormat::Json {
+            let items: Vec<serde_json::Value> = broken
+                .iter()
+                .map(|(index, target, converted)| {
+                    serde_json::json!({
+                        "link": index,
+                        "target": target,
+                        "converted": converted,
+                    })
+                })
+                .collect();
+            let json = serde_json::json!({
+                "file": file.display().to_string(),
+                "broken": items,
+            });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else if broken.is_empty() {
+            outln!("No external links in {}", file.display());
+        } else {
+            for (index, target, converted) in &broken {
+                outln!(
+                    "Broke [{}] {} ({} formula(s) converted to values)",
+                    index,
+                    target,
+                    converted.to_string().green()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use tempfile::TempDir;
+    use xlex_core::CellRef;
+
+    fn default_global() -> GlobalOptions {
+        GlobalOptions {
+            quiet: true,
+            verbose: false,
+            format: OutputFormat::Text,
+            no_color: true,
+            color: false,
+            json_errors: false,
+            dry_run: false,
+            output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
+        }
+    }
+
+    /// Creates a workbook whose formulas use two linked workbooks.
+    fn create_linked_workbook(dir: &TempDir, name: &str) -> std::path::PathBuf {
+        let plain = dir.path().join("plain.xlsx");
+        let mut wb = Workbook::new();
+        let sheet = wb.get_sheet_mut("Sheet1").unwrap();
+        sheet.set_cell(CellRef::new(1, 1), CellValue::formula("[1]Budget!A1*2"));
+        sheet.set_cell(CellRef::new(1, 2), CellValue::formula("[2]Rates!B2"));
+        wb.save_as(&plain).unwrap();
+
+        let file_path = dir.path().join(name);
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&plain).unwrap()).unwrap();
+        let mut out = zip::ZipWriter::new(std::fs::File::create(&file_path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).unwrap();
+            let entry_name = entry.name().to_string();
+            let mut content = String::new();
+            entry.read_to_string(&mut content).unwrap();
+            let content = match entry_name.as_str() {
+                "xl/workbook.xml" => content.replace(
+                    "</sheets>",
+                    r#"</sheets><externalReferences><externalReference r:id="rIdX1"/><externalReference r:id="rIdX2"/></externalReferences>"#,
+                ),
+                "xl/_rels/workbook.xml.rels" => content.replace(
+                    "</Relationships>",
+                    r#"<Relationship Id="rIdX1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/externalLink" Target="externalLinks/externalLink1.xml"/><Relationship Id="rIdX2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/externalLink" Target="externalLinks/externalLink2.xml"/></Relationships>"#,
+                ),
+                _ => content,
+            };
+            out.start_file(entry_name, options).unwrap();
+            out.write_all(content.as_bytes()).unwrap();
+        }
+        for (i, (target, sheet)) in [
+            (r"\\oldserver\share\budget.xlsx", "Budget"),
+            (r"\\oldserver\share\rates.xlsx", "Rates"),
+        ]
+        .iter()
+        .enumerate()
+        {
+            out.start_file(
+                format!("xl/externalLinks/externalLink{}.xml", i + 1),
+                options,
+            )
+            .unwrap();
+            write!(
+                out,
+                r#"<externalLink xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><externalBook r:id="rId1"><sheetNames><sheetName val="{}"/></sheetNames></externalBook></externalLink>"#,
+                sheet
+            )
+            .unwrap();
+            out.start_file(
+                format!("xl/externalLinks/_rels/externalLink{}.xml.rels", i + 1),
+                options,
+            )
+            .unwrap();
+            write!(
+                out,
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/externalLinkPath" Target="{}" TargetMode="External"/></Relationships>"#,
+                target
+            )
+            .unwrap();
+        }
+        out.finish().unwrap();
+        file_path
+    }
+
+    #[test]
+    fn test_list_and_update_by_prefix() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_linked_workbook(&temp_dir, "links.xlsx");
+
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(usage_counts(&wb), vec![1, 1]);
+        let mut json = default_global();
+        json.format = OutputFormat::Json;
+        list(&file_path, &json).unwrap();
+
+        update(
+            &file_path,
+            None,
+            Some(r"\\oldserver\share"),
+            r"\\newserver\finance",
+            &default_global(),
+        )
+        .unwrap();
+        update(&file_path, Some(2), None, "rates.xlsx", &default_global()).unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let targets: Vec<&str> = wb
+            .external_links()
+            .iter()
+            .map(|l| l.target.as_str())
+            .collect();
+        assert_eq!(
+            targets,
+            vec![r"\\newserver\finance\budget.xlsx", "rates.xlsx"]
+        );
+        assert!(update(&file_path, Some(3), None, "x.xlsx", &default_global()).is_err());
+    }
+
+    #[test]
+    fn test_break_links() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_linked_workbook(&temp_dir, "break.xlsx");
+
+        break_links(&file_path, Some(1), &default_global()).unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(wb.external_links().len(), 1);
+        let sheet = wb.get_sheet("Sheet1").unwrap();
+        assert_eq!(sheet.get_value(&CellRef::new(1, 1)), CellValue::Empty);
+        assert_eq!(
+            sheet.get_value(&CellRef::new(1, 2)),
+            CellValue::formula("[1]Rates!B2")
+        );
+
+        break_links(&file_path, None, &default_global()).unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        assert!(wb.external_links().is_empty());
+        assert!(break_links(&file_path, Some(1), &default_global()).is_err());
+    }
+}
@@ -1,21 +1,54 @@
 //! CLI commands for xlex.
 
+mod aggregate;
+mod alias;
+mod audit;
+mod bench;
 mod cell;
 mod column;
+mod condition;
+mod consolidate;
+mod derive;
+mod diff;
+mod embed;
 mod export;
+mod expr;
 mod formula;
+mod generate;
 mod import;
+mod join;
+mod line_editor;
+mod links;
+mod name;
+mod output;
+mod plugin;
 mod range;
+mod reshape;
 mod row;
+mod run;
+mod scan;
+mod schema;
+mod script;
 mod search;
 pub mod session;
 mod sheet;
+mod sheet_args;
+mod sparkline;
 mod style;
 mod template;
+mod template_syntax;
+mod transform;
 mod update;
+mod view;
 mod workbook;
 
-use anyhow::Result;
+pub(crate) use alias::expand_aliases;
+pub(crate) use plugin::PluginExit;
+pub(crate) use sheet_args::resolve_sheet_args;
+
+use std::ffi::OsString;
+
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 
 const LONG_ABOUT: &str = r#"XLEX - A streaming Excel manipulation tool.
@@ -37,6 +70,7 @@ PERFORMANCE TIP:
 #[command(name = "xlex")]
 #[command(author, version, about, long_about = LONG_ABOUT)]
 #[command(propagate_version = true)]
+#[command(allow_external_subcommands = true)]
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
@@ -56,7 +90,7 @@ pub struct GlobalOptions {
     #[arg(long, short = 'v', global = true)]
     pub verbose: bool,
 
-    /// Output format (text, json, csv)
+    /// Output format (text, json, csv, ndjson, html-table)
     #[arg(long, short = 'f', global = true, default_value = "text")]
     pub format: OutputFormat,
 
@@ -77,8 +111,43 @@ pub struct GlobalOptions {
     pub dry_run: bool,
 
     /// Write output to file instead of stdout
-    #[arg(long, short = 'o', global = true)]
+    // Own id so it does not pick up subcommand arguments named `output`
+    #[arg(id = "output_file", long = "output", short = 'o', global = true)]
     pub output: Option<std::path::PathBuf>,
+
+    /// Append to the --output file instead of overwriting it
+    #[arg(long, global = true, requires = "output_file")]
+    pub append: bool,
+
+    /// Also print to stdout when writing to --output
+    #[arg(long, global = true, requires = "output_file")]
+    pub tee: bool,
+
+    /// Hide progress bars
+    #[arg(long, global = true)]
+    pub no_progress: bool,
+
+    /// Write a Chrome trace (chrome://tracing, Perfetto) of core operations
+    #[arg(long, global = true, value_name = "FILE")]
+    pub trace_output: Option<std::path::PathBuf>,
+
+    /// Match sheet names ignoring case, and take the closest name for a
+    /// missing sheet (asking first on a terminal)
+    #[arg(long, global = true)]
+    pub fuzzy_sheets: bool,
+}
+
+impl GlobalOptions {
+    /// Whether progress bars may be drawn: never with `--quiet`,
+    /// `--no-progress` or JSON output, and only when stdout is a terminal.
+    pub fn show_progress(&self) -> bool {
+        use std::io::IsTerminal;
+
+        !self.quiet
+            && !self.no_progress
+            && !matches!(self.format, OutputFormat::Json | OutputFormat::Ndjson)
+            && std::io::stdout().is_terminal()
+    }
 }
 
 /// Output format options.
@@ -89,6 +158,38 @@ pub enum OutputFormat {
     Json,
     Csv,
     Ndjson,
+    /// A standalone HTML table, for `range get` and `row get`
+    HtmlTable,
+}
+
+/// Findings that make a check command fail with exit code 12.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum FailOn {
+    /// Fail on warnings as well as errors
+    Warnings,
+    /// Fail on errors only
+    #[default]
+    Errors,
+    /// Report findings but always succeed
+    Never,
+}
+
+impl FailOn {
+    /// Returns a validation error if `errors` and `warnings` fail the check.
+    pub fn check(self, errors: usize, warnings: usize) -> Result<()> {
+        let fails = match self {
+            FailOn::Warnings => errors + warnings > 0,
+            FailOn::Errors => errors > 0,
+            FailOn::Never => false,
+        };
+        if fails {
+            return Err(xlex_core::XlexError::ValidationFailed {
+                message: format!("{} error(s), {} warning(s)", errors, warnings),
+            }
+            .into());
+        }
+        Ok(())
+    }
 }
 
 /// Available subcommands.
@@ -107,6 +208,18 @@ pub enum Commands {
     Props(workbook::PropsArgs),
     /// Display workbook statistics
     Stats(workbook::StatsArgs),
+    /// Shrink a workbook (unused styles, trailing blanks, recompression)
+    Optimize(workbook::OptimizeArgs),
+    /// Remove personal metadata before sharing a workbook
+    Scrub(workbook::ScrubArgs),
+    /// Write an index file so a large workbook reopens without scanning
+    Index(workbook::IndexArgs),
+    /// Show, stamp or verify a workbook's content hash
+    Hash(workbook::HashArgs),
+    /// Digitally sign a workbook with a PKCS#12 certificate
+    Sign(workbook::SignArgs),
+    /// Check a workbook's digital signatures
+    VerifySignature(workbook::VerifySignatureArgs),
 
     // Sheet operations
     /// Sheet operations (list, add, remove, rename, etc.)
@@ -128,14 +241,36 @@ pub enum Commands {
     /// Range operations (get, copy, move, merge, etc.)
     Range(range::RangeArgs),
 
+    // Named cell operations
+    /// Named cells and ranges, usable as @NAME (add, list, remove)
+    Name(name::NameArgs),
+
     // Style operations
     /// Style operations (list, get, apply, etc.)
     Style(style::StyleArgs),
 
+    // Sparkline operations
+    /// Sparkline operations (add, list, remove)
+    Sparkline(sparkline::SparklineArgs),
+
     // Formula operations
     /// Formula operations (get, set, list, check, etc.)
     Formula(formula::FormulaArgs),
 
+    // External link operations
+    /// External workbook link operations (list, update, break)
+    Links(links::LinksArgs),
+
+    // Embedded object operations
+    /// Embedded object operations (list, extract)
+    Embed(embed::EmbedArgs),
+
+    // Audit operations
+    /// Workbook audits (unused, hidden)
+    Audit(audit::AuditArgs),
+    /// Content scans (pii)
+    Scan(scan::ScanArgs),
+
     // Template operations
     /// Template operations (apply, list, validate, etc.)
     Template(template::TemplateArgs),
@@ -154,12 +289,45 @@ pub enum Commands {
     /// Search for text across all sheets (like Ctrl+F in Excel)
     Search(search::SearchArgs),
 
+    // Comparison
+    /// Compare two workbooks cell by cell
+    Diff(diff::DiffArgs),
+
+    // Generation
+    /// Generate workbooks of random data
+    Gen(generate::GenArgs),
+
+    // Transformation
+    /// Stream a sheet through declarative steps into a new workbook
+    Transform(transform::TransformArgs),
+
+    /// Join two sheets on a key column into a new workbook
+    Join(join::JoinArgs),
+
+    /// Reshape a range between wide and long layouts
+    Reshape(reshape::ReshapeArgs),
+
+    /// Group rows and write sums, counts and averages to a new sheet
+    Aggregate(aggregate::AggregateArgs),
+
+    /// Fill columns with running totals, moving averages, ranks or lag/lead values
+    Derive(derive::DeriveArgs),
+
+    /// Run a job file of sources, transforms and sinks
+    Run(run::RunArgs),
+
+    /// Stack many similar workbooks into one, mapping their headers to common columns
+    Consolidate(consolidate::ConsolidateArgs),
+
+    /// Run a sandboxed Rhai script against a workbook
+    Script(script::ScriptArgs),
+
     // Session management (open → operate → commit)
     /// Open a workbook for editing (creates a session)
     Open(OpenArgs),
 
     /// Save session changes back to the original file
-    Commit,
+    Commit(CommitArgs),
 
     /// Discard session changes and close
     Close,
@@ -180,6 +348,9 @@ pub enum Commands {
     /// Manage command aliases
     Alias(AliasArgs),
 
+    /// Time core operations on a workbook
+    Bench(bench::BenchArgs),
+
     /// Display version information
     Version,
 
@@ -190,6 +361,9 @@ pub enum Commands {
     #[command(name = "repl")]
     Session(SessionArgs),
 
+    /// Browse a workbook in an interactive terminal viewer
+    View(view::ViewArgs),
+
     /// Show examples for commands
     Examples(ExamplesArgs),
 
@@ -198,6 +372,10 @@ pub enum Commands {
 
     /// Update xlex to the latest version
     Update(update::UpdateArgs),
+
+    /// Run an `xlex-<name>` plugin from PATH
+    #[command(external_subcommand)]
+    External(Vec<OsString>),
 }
 
 /// Examples arguments.
@@ -214,7 +392,7 @@ pub struct ExamplesArgs {
 #[derive(Parser)]
 pub struct ManArgs {
     /// Output directory for man pages
-    #[arg(short, long, default_value = ".")]
+    #[arg(long, default_value = ".")]
     pub output_dir: std::path::PathBuf,
     /// Generate for all commands
     #[arg(long)]
@@ -245,6 +423,14 @@ pub struct OpenArgs {
     pub file: std::path::PathBuf,
 }
 
+/// Session commit arguments.
+#[derive(Parser)]
+pub struct CommitArgs {
+    /// Record the content hash and time in custom properties (see `xlex hash`)
+    #[arg(long)]
+    pub stamp_hash: bool,
+}
+
 /// Batch execution arguments.
 #[derive(Parser)]
 pub struct BatchArgs {
@@ -341,6 +527,20 @@ impl Cli {
             colored::control::set_override(true);
         }
 
+        // Route output to --output until the command finishes
+        let _redirect = match &self.global.output {
+            Some(path) => {
+                if !self.global.color {
+                    colored::control::set_override(false);
+                }
+                Some(
+                    crate::output::redirect(path, self.global.append, self.global.tee)
+                        .with_context(|| format!("Cannot open output file {}", path.display()))?,
+                )
+            }
+            None => None,
+        };
+
         match &self.command {
             // Workbook operations
             Commands::Info(args) => workbook::info(args, &self.global),
@@ -349,6 +549,12 @@ impl Cli {
             Commands::Create(args) => workbook::create(args, &self.global),
             Commands::Props(args) => workbook::props(args, &self.global),
             Commands::Stats(args) => workbook::stats(args, &self.global),
+            Commands::Optimize(args) => workbook::optimize(args, &self.global),
+            Commands::Scrub(args) => workbook::scrub(args, &self.global),
+            Commands::Index(args) => workbook::index(args, &self.global),
+            Commands::Hash(args) => workbook::hash(args, &self.global),
+            Commands::Sign(args) => workbook::sign(args, &self.global),
+            Commands::VerifySignature(args) => workbook::verify_signature(args, &self.global),
 
             // Sheet operations
             Commands::Sheet(args) => sheet::run(args, &self.global),
@@ -365,12 +571,28 @@ impl Cli {
             // Range operations
             Commands::Range(args) => range::run(args, &self.global),
 
+            // Named cell operations
+            Commands::Name(args) => name::run(args, &self.global),
+
             // Style operations
             Commands::Style(args) => style::run(args, &self.global),
 
+            // Sparkline operations
+            Commands::Sparkline(args) => sparkline::run(args, &self.global),
+
             // Formula operations
             Commands::Formula(args) => formula::run(args, &self.global),
 
+            // External link operations
+            Commands::Links(args) => links::run(args, &self.global),
+
+            // Embedded object operations
+            Commands::Embed(args) => embed::run(args, &self.global),
+
+            // Audit operations
+            Commands::Audit(args) => audit::run(args, &self.global),
+            Commands::Scan(args) => scan::run(args, &self.global),
+
             // Template operations
             Commands::Template(args) => template::run(args, &self.global),
 
@@ -382,9 +604,25 @@ impl Cli {
             // Search
             Commands::Search(args) => search::run(args, &self.global),
 
+            // Comparison
+            Commands::Diff(args) => diff::run(args, &self.global),
+
+            // Generation
+            Commands::Gen(args) => generate::run(args, &self.global),
+
+            // Transformation
+            Commands::Transform(args) => transform::run(args, &self.global),
+            Commands::Join(args) => join::run(args, &self.global),
+            Commands::Reshape(args) => reshape::run(args, &self.global),
+            Commands::Aggregate(args) => aggregate::run(args, &self.global),
+            Commands::Derive(args) => derive::run(args, &self.global),
+            Commands::Run(args) => run::run(args, &self.global),
+            Commands::Consolidate(args) => consolidate::run(args, &self.global),
+            Commands::Script(args) => script::run(args, &self.global),
+
             // Session management
             Commands::Open(args) => run_open(args, &self.global),
-            Commands::Commit => run_commit(&self.global),
+            Commands::Commit(args) => run_commit(args, &self.global),
             Commands::Close => run_close(&self.global),
             Commands::Status => run_status(&self.global),
 
@@ -393,12 +631,15 @@ impl Cli {
             Commands::Config(args) => run_config(args, &self.global),
             Commands::Batch(args) => run_batch(args, &self.global),
             Commands::Alias(args) => run_alias(args, &self.global),
+            Commands::Bench(args) => bench::run(args, &self.global),
             Commands::Version => run_version(&self.global),
             Commands::Interactive => run_interactive(&self.global),
             Commands::Session(args) => run_session(args, &self.global),
+            Commands::View(args) => view::run(args, &self.global),
             Commands::Examples(args) => run_examples(args, &self.global),
             Commands::Man(args) => run_man(args, &self.global),
             Commands::Update(args) => update::run(args, &self.global),
+            Commands::External(args) => plugin::run(args, &self.global),
         }
     }
 }
@@ -420,7 +661,7 @@ fn run_convert(args: &ConvertArgs, global: &GlobalOptions) -> Result<()> {
         .unwrap_or_default();
 
     if global.dry_run {
-        println!(
+        outln!(
             "Would convert {} ({}) to {} ({})",
             input.display(),
             input_ext,
@@ -441,6 +682,9 @@ fn run_convert(args: &ConvertArgs, global: &GlobalOptions) -> Result<()> {
                     sheet: None,
                     delimiter: ',',
                     header: false,
+                    sanitize_formulas: false,
+                    decimal: false,
+                    preserve_ids: Vec::new(),
                 },
             };
             import::run(&import_args, global)
@@ -452,6 +696,9 @@ fn run_convert(args: &ConvertArgs, global: &GlobalOptions) -> Result<()> {
                     source: input.clone(),
                     dest: output.clone(),
                     sheet: None,
+                    sanitize_formulas: false,
+                    decimal: false,
+                    preserve_ids: Vec::new(),
                 },
             };
             import::run(&import_args, global)
@@ -463,6 +710,9 @@ fn run_convert(args: &ConvertArgs, global: &GlobalOptions) -> Result<()> {
                     source: input.clone(),
                     dest: output.clone(),
                     sheet: None,
+                    sanitize_formulas: false,
+                    decimal: false,
+                    preserve_ids: Vec::new(),
                 },
             };
             import::run(&import_args, global)
@@ -475,6 +725,9 @@ fn run_convert(args: &ConvertArgs, global: &GlobalOptions) -> Result<()> {
                     dest: output.clone(),
                     sheet: None,
                     header: true,
+                    sanitize_formulas: false,
+                    decimal: false,
+                    preserve_ids: Vec::new(),
                 },
             };
             import::run(&import_args, global)
@@ -488,6 +741,10 @@ fn run_convert(args: &ConvertArgs, global: &GlobalOptions) -> Result<()> {
                     sheet: None,
                     delimiter: ',',
                     all: false,
+                    incremental: false,
+                    state: None,
+                    since_row: None,
+                    since_hash: None,
                 },
             };
             export::run(&export_args, global)
@@ -500,6 +757,8 @@ fn run_convert(args: &ConvertArgs, global: &GlobalOptions) -> Result<()> {
                     dest: output.to_string_lossy().to_string(),
                     sheet: None,
                     all: false,
+                    incremental: false,
+                    state: None,
                 },
             };
             export::run(&export_args, global)
@@ -556,12 +815,16 @@ fn run_convert(args: &ConvertArgs, global: &GlobalOptions) -> Result<()> {
         }
         // XLSX -> XLSX (copy)
         ("xlsx", "xlsx") => {
+            let report = output::CommandOutput::new("convert");
             let workbook = Workbook::open(input)?;
             workbook.save_as(output)?;
-            if !global.quiet {
-                println!("Copied {} to {}", input.display(), output.display());
-            }
-            Ok(())
+            report
+                .file(input)
+                .target("destination", output.display().to_string())
+                .print(
+                    global,
+                    format!("Copied {} to {}", input.display(), output.display()),
+                )
         }
         _ => anyhow::bail!(
             "Unsupported conversion: {} -> {}. Supported: csv, tsv, json, ndjson, yaml, md <-> xlsx",
@@ -573,7 +836,9 @@ fn run_convert(args: &ConvertArgs, global: &GlobalOptions) -> Result<()> {
 
 fn run_completion(args: &CompletionArgs) -> Result<()> {
     let mut cmd = Cli::command();
-    clap_complete::generate(args.shell, &mut cmd, "xlex", &mut std::io::stdout());
+    let mut script = Vec::new();
+    clap_complete::generate(args.shell, &mut cmd, "xlex", &mut script);
+    out!("{}", String::from_utf8_lossy(&script));
     Ok(())
 }
 
@@ -600,26 +865,26 @@ fn run_config(args: &ConfigArgs, global: &GlobalOptions) -> Result<()> {
                 } else {
                     "{}".to_string()
                 };
-                println!("{}", config);
+                outln!("{}", config);
             } else {
-                println!("{}: {}\n", "Config file".bold(), config_path.display());
+                outln!("{}: {}\n", "Config file".bold(), config_path.display());
 
                 if config_path.exists() {
                     let content = std::fs::read_to_string(&config_path)?;
-                    println!("{}", content);
+                    outln!("{}", content);
                 } else {
-                    println!(
+                    outln!(
                         "{}",
                         "(No config file found. Run 'xlex config init' to create one.)".dimmed()
                     );
                 }
 
                 if *effective {
-                    println!("\n{}:", "Effective values".bold());
-                    println!("  default_format: {}", global.format as u8);
-                    println!("  quiet: {}", global.quiet);
-                    println!("  verbose: {}", global.verbose);
-                    println!("  no_color: {}", global.no_color);
+                    outln!("\n{}:", "Effective values".bold());
+                    outln!("  default_format: {}", global.format as u8);
+                    outln!("  quiet: {}", global.quiet);
+                    outln!("  verbose: {}", global.verbose);
+                    outln!("  no_color: {}", global.no_color);
                 }
             }
         }
@@ -633,14 +898,14 @@ fn run_config(args: &ConfigArgs, global: &GlobalOptions) -> Result<()> {
             let yaml: serde_yaml::Value = serde_yaml::from_str(&content)?;
 
             if let Some(value) = yaml.get(key) {
-                println!("{}", serde_yaml::to_string(value)?.trim());
+                outln!("{}", serde_yaml::to_string(value)?.trim());
             } else {
                 anyhow::bail!("Key '{}' not found in config", key);
             }
         }
         ConfigCommand::Set { key, value } => {
             if global.dry_run {
-                println!("Would set {} = {}", key, value);
+                outln!("Would set {} = {}", key, value);
                 return Ok(());
             }
 
@@ -667,29 +932,33 @@ fn run_config(args: &ConfigArgs, global: &GlobalOptions) -> Result<()> {
             // Write back
             std::fs::write(&config_path, serde_yaml::to_string(&yaml)?)?;
 
-            if !global.quiet {
-                println!("{} Set {} = {}", "✓".green(), key.cyan(), value);
-            }
+            output::CommandOutput::new("set")
+                .target("key", key.as_str())
+                .field("value", value.as_str())
+                .print(
+                    global,
+                    format!("{} Set {} = {}", "✓".green(), key.cyan(), value),
+                )?;
         }
         ConfigCommand::Reset => {
             if global.dry_run {
-                println!("Would reset config to defaults");
+                outln!("Would reset config to defaults");
                 return Ok(());
             }
 
             let config_path = get_config_path()?;
             if config_path.exists() {
                 std::fs::remove_file(&config_path)?;
-                if !global.quiet {
-                    println!("{} Config reset to defaults", "✓".green());
-                }
+                output::CommandOutput::new("reset")
+                    .file(&config_path)
+                    .print(global, format!("{} Config reset to defaults", "✓".green()))?;
             } else {
-                println!("No config file to reset");
+                outln!("No config file to reset");
             }
         }
         ConfigCommand::Init => {
             if global.dry_run {
-                println!("Would create config file");
+                outln!("Would create config file");
                 return Ok(());
             }
 
@@ -716,7 +985,7 @@ verbose: false
 # Disable colored output
 no_color: false
 
-# Default sheet name for new workbooks
+# Sheet used when a command leaves out the sheet of a multi-sheet workbook
 default_sheet: Sheet1
 
 # CSV settings
@@ -730,13 +999,16 @@ csv_quote: '\"'
 
             std::fs::write(&config_path, default_config)?;
 
-            if !global.quiet {
-                println!(
-                    "{} Created config file: {}",
-                    "✓".green(),
-                    config_path.display()
-                );
-            }
+            output::CommandOutput::new("init")
+                .file(&config_path)
+                .print(
+                    global,
+                    format!(
+                        "{} Created config file: {}",
+                        "✓".green(),
+                        config_path.display()
+                    ),
+                )?;
         }
         ConfigCommand::Validate => {
             let config_path = get_config_path()?;
@@ -747,9 +1019,10 @@ csv_quote: '\"'
             let content = std::fs::read_to_string(&config_path)?;
             let _yaml: serde_yaml::Value = serde_yaml::from_str(&content)?;
 
-            if !global.quiet {
-                println!("{} Config file is valid", "✓".green());
-            }
+            output::CommandOutput::new("validate")
+                .file(&config_path)
+                .field("valid", true)
+                .print(global, format!("{} Config file is valid", "✓".green()))?;
         }
     }
 
@@ -768,37 +1041,45 @@ fn run_open(args: &OpenArgs, global: &GlobalOptions) -> Result<()> {
                 "original": state.original_path,
                 "working": state.working_path,
             });
-            println!("{}", serde_json::to_string_pretty(&json)?);
+            outln!("{}", serde_json::to_string_pretty(&json)?);
         } else {
-            println!(
+            outln!(
                 "{} {}",
                 "Opened".bold().green(),
                 state.original_path.display()
             );
-            println!(
+            outln!(
                 "Working copy: {}",
                 state.working_path.display().to_string().dimmed()
             );
-            println!();
-            println!(
+            outln!();
+            outln!(
                 "Run commands with  {}",
                 "xlex batch -c \"cell set Sheet1 A1 hello\"".cyan()
             );
-            println!(
+            outln!(
                 "Or pipe commands   {}",
                 "echo 'cell set Sheet1 A1 hello' | xlex batch".cyan()
             );
-            println!("Save changes with  {}", "xlex commit".cyan());
-            println!("Discard with       {}", "xlex close".cyan());
+            outln!("Save changes with  {}", "xlex commit".cyan());
+            outln!("Discard with       {}", "xlex close".cyan());
         }
     }
 
     Ok(())
 }
 
-fn run_commit(global: &GlobalOptions) -> Result<()> {
+fn run_commit(args: &CommitArgs, global: &GlobalOptions) -> Result<()> {
     use colored::Colorize;
 
+    let mut stamped = None;
+    if args.stamp_hash {
+        if let Some(state) = session::load() {
+            let mut workbook = xlex_core::Workbook::open(&state.working_path)?;
+            stamped = Some(xlex_core::stamp::stamp(&mut workbook));
+            workbook.save()?;
+        }
+    }
     let state = session::commit()?;
 
     if !global.quiet {
@@ -806,14 +1087,18 @@ fn run_commit(global: &GlobalOptions) -> Result<()> {
             let json = serde_json::json!({
                 "action": "commit",
                 "file": state.original_path,
+                "contentHash": stamped,
             });
-            println!("{}", serde_json::to_string_pretty(&json)?);
+            outln!("{}", serde_json::to_string_pretty(&json)?);
         } else {
-            println!(
+            outln!(
                 "{} Changes saved to {}",
                 "Committed".bold().green(),
                 state.original_path.display()
             );
+            if let Some(hash) = &stamped {
+                outln!("Stamped content hash {}", hash.cyan());
+            }
         }
     }
 
@@ -831,9 +1116,9 @@ fn run_close(global: &GlobalOptions) -> Result<()> {
                 "action": "close",
                 "file": state.original_path,
             });
-            println!("{}", serde_json::to_string_pretty(&json)?);
+            outln!("{}", serde_json::to_string_pretty(&json)?);
         } else {
-            println!(
+            outln!(
                 "{} Changes discarded for {}",
                 "Closed".bold().yellow(),
                 state.original_path.display()
@@ -856,24 +1141,24 @@ fn run_status(global: &GlobalOptions) -> Result<()> {
                     "working": state.working_path,
                     "opened_at": state.opened_at,
                 });
-                println!("{}", serde_json::to_string_pretty(&json)?);
+                outln!("{}", serde_json::to_string_pretty(&json)?);
             } else {
-                println!("{}", "Session active".bold().green());
-                println!("  File:       {}", state.original_path.display());
-                println!(
+                outln!("{}", "Session active".bold().green());
+                outln!("  File:       {}", state.original_path.display());
+                outln!(
                     "  Working:    {}",
                     state.working_path.display().to_string().dimmed()
                 );
-                println!("  Opened at:  {}", state.opened_at);
+                outln!("  Opened at:  {}", state.opened_at);
             }
         }
         None => {
             if global.format == OutputFormat::Json {
                 let json = serde_json::json!({ "active": false });
-                println!("{}", serde_json::to_string_pretty(&json)?);
+                outln!("{}", serde_json::to_string_pretty(&json)?);
             } else {
-                println!("{}", "No active session".dimmed());
-                println!("Run {} to start", "xlex open <file>".cyan());
+                outln!("{}", "No active session".dimmed());
+                outln!("Run {} to start", "xlex open <file>".cyan());
             }
         }
     }
@@ -916,6 +1201,8 @@ fn run_batch(args: &BatchArgs, global: &GlobalOptions) -> Result<()> {
     let mut workbook = Workbook::open(&file_path)
         .map_err(|e| anyhow::anyhow!("Failed to open '{}': {}", file_path.display(), e))?;
 
+    let report = output::CommandOutput::new("batch");
+    let aliases = alias::load();
     let mut errors: Vec<(usize, String, String)> = Vec::new();
     let mut success_count = 0;
     let mut modified = false;
@@ -929,15 +1216,15 @@ fn run_batch(args: &BatchArgs, global: &GlobalOptions) -> Result<()> {
         }
 
         if global.verbose {
-            println!("{} {}: {}", "[BATCH]".blue(), line_num + 1, line);
+            outln!("{} {}: {}", "[BATCH]".blue(), line_num + 1, line);
         }
 
-        match execute_batch_command(&mut workbook, line) {
+        match execute_batch_command(&mut workbook, line, &aliases) {
             Ok(msg) => {
                 success_count += 1;
                 modified = true;
-                if !global.quiet && !msg.is_empty() {
-                    println!("  {} {}", "✓".green(), msg);
+                if !global.quiet && global.format != OutputFormat::Json && !msg.is_empty() {
+                    outln!("  {} {}", "✓".green(), msg);
                 }
             }
             Err(e) => {
@@ -966,14 +1253,30 @@ fn run_batch(args: &BatchArgs, global: &GlobalOptions) -> Result<()> {
             .map_err(|e| anyhow::anyhow!("Failed to save: {}", e))?;
     }
 
-    if !global.quiet {
-        println!(
-            "\n{}: {} succeeded, {} failed",
-            "Batch complete".bold(),
-            success_count.to_string().green(),
-            errors.len().to_string().red()
-        );
-    }
+    let failures: Vec<serde_json::Value> = errors
+        .iter()
+        .map(|(line, command, error)| {
+            serde_json::json!({
+                "line": line,
+                "command": command,
+                "error": error,
+            })
+        })
+        .collect();
+    report
+        .file(&file_path)
+        .count("succeeded", success_count)
+        .count("failed", errors.len())
+        .field("errors", failures)
+        .print(
+            global,
+            format!(
+                "\n{}: {} succeeded, {} failed",
+                "Batch complete".bold(),
+                success_count.to_string().green(),
+                errors.len().to_string().red()
+            ),
+        )?;
 
     if !errors.is_empty() && !args.continue_on_error {
         anyhow::bail!("{} commands failed", errors.len());
@@ -997,12 +1300,28 @@ fn run_batch(args: &BatchArgs, global: &GlobalOptions) -> Result<()> {
 /// sheet remove <name>
 /// sheet rename <old> <new>
 /// ```
-fn execute_batch_command(workbook: &mut xlex_core::Workbook, line: &str) -> Result<String> {
-    let parts = shell_words(line);
+fn execute_batch_command(
+    workbook: &mut xlex_core::Workbook,
+    line: &str,
+    aliases: &alias::Aliases,
+) -> Result<String> {
+    let mut parts = alias::expand(&shell_words(line), 0, aliases)?;
     if parts.is_empty() {
         return Ok(String::new());
     }
 
+    // `cell <op> Sheet1!B2 ...` names the sheet and cell in one word
+    if parts[0].eq_ignore_ascii_case("cell") {
+        let split = parts
+            .get(2)
+            .and_then(|word| xlex_core::sheet::split_sheet_ref(word))
+            .map(|(sheet, cell)| (sheet, cell.to_string()));
+        if let Some((sheet, cell)) = split {
+            parts[2] = sheet;
+            parts.insert(3, cell);
+        }
+    }
+
     let cmd = parts[0].to_lowercase();
     let sub = parts.get(1).map(|s| s.to_lowercase()).unwrap_or_default();
 
@@ -1178,14 +1497,15 @@ fn run_alias(args: &AliasArgs, global: &GlobalOptions) -> Result<()> {
                     serde_yaml::Mapping::new()
                 };
 
-                println!("{}", serde_json::to_string_pretty(&aliases)?);
+                outln!("{}", serde_json::to_string_pretty(&aliases)?);
             } else {
-                println!("{}:\n", "Aliases".bold());
+                outln!("{}:\n", "Aliases".bold());
 
                 // Built-in aliases
-                println!("  {} (built-in)", "Built-in".dimmed());
-                println!("    {} → {}", "ls".cyan(), "sheet list");
-                println!("    {} → {}", "cat".cyan(), "cell get");
+                outln!("  {} (built-in)", "Built-in".dimmed());
+                for (name, command) in alias::BUILTIN_ALIASES {
+                    outln!("    {} → {}", name.cyan(), command);
+                }
 
                 // User aliases
                 if alias_path.exists() {
@@ -1194,23 +1514,29 @@ fn run_alias(args: &AliasArgs, global: &GlobalOptions) -> Result<()> {
                         serde_yaml::from_str(&content).unwrap_or_default();
 
                     if !aliases.is_empty() {
-                        println!("\n  {} (user-defined)", "Custom".dimmed());
+                        outln!("\n  {} (user-defined)", "Custom".dimmed());
                         for (name, cmd) in aliases {
                             if let (serde_yaml::Value::String(n), serde_yaml::Value::String(c)) =
                                 (name, cmd)
                             {
-                                println!("    {} → {}", n.cyan(), c);
+                                outln!("    {} → {}", n.cyan(), c);
                             }
                         }
                     }
                 } else {
-                    println!("\n  {}", "(No user-defined aliases)".dimmed());
+                    outln!("\n  {}", "(No user-defined aliases)".dimmed());
                 }
             }
         }
         AliasCommand::Add { name, command } => {
+            if name.is_empty() || name.starts_with('-') || name.contains(char::is_whitespace) {
+                anyhow::bail!("Invalid alias name '{}'", name);
+            }
+            if alias::is_command(name) {
+                anyhow::bail!("'{}' is a built-in command and cannot be aliased", name);
+            }
             if global.dry_run {
-                println!("Would add alias: {} → {}", name, command);
+                outln!("Would add alias: {} → {}", name, command);
                 return Ok(());
             }
 
@@ -1233,13 +1559,17 @@ fn run_alias(args: &AliasArgs, global: &GlobalOptions) -> Result<()> {
 
             std::fs::write(&alias_path, serde_yaml::to_string(&aliases)?)?;
 
-            if !global.quiet {
-                println!("{} Added alias: {} → {}", "✓".green(), name.cyan(), command);
-            }
+            output::CommandOutput::new("add")
+                .target("alias", name.as_str())
+                .field("command", command.as_str())
+                .print(
+                    global,
+                    format!("{} Added alias: {} → {}", "✓".green(), name.cyan(), command),
+                )?;
         }
         AliasCommand::Remove { name } => {
             if global.dry_run {
-                println!("Would remove alias: {}", name);
+                outln!("Would remove alias: {}", name);
                 return Ok(());
             }
 
@@ -1259,9 +1589,12 @@ fn run_alias(args: &AliasArgs, global: &GlobalOptions) -> Result<()> {
 
             std::fs::write(&alias_path, serde_yaml::to_string(&aliases)?)?;
 
-            if !global.quiet {
-                println!("{} Removed alias: {}", "✓".green(), name.cyan());
-            }
+            output::CommandOutput::new("remove")
+                .target("alias", name.as_str())
+                .print(
+                    global,
+                    format!("{} Removed alias: {}", "✓".green(), name.cyan()),
+                )?;
         }
     }
 
@@ -1275,40 +1608,45 @@ fn run_version(global: &GlobalOptions) -> Result<()> {
             "version": version,
             "name": "xlex",
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else {
-        println!("xlex {}", version);
+        outln!("xlex {}", version);
     }
     Ok(())
 }
 
 fn run_interactive(global: &GlobalOptions) -> Result<()> {
+    use clap::CommandFactory;
     use colored::Colorize;
-    use std::io::{BufRead, Write};
 
     if !global.quiet {
-        println!("{}", "XLEX Interactive Mode".bold().cyan());
-        println!(
+        outln!("{}", "XLEX Interactive Mode".bold().cyan());
+        outln!(
             "Type {} for help, {} to exit",
             "help".green(),
             "exit".green()
         );
-        println!();
+        outln!();
     }
 
-    let stdin = std::io::stdin();
-    let mut stdout = std::io::stdout();
-
-    loop {
-        print!("{} ", "xlex>".bold().green());
-        stdout.flush()?;
-
-        let mut line = String::new();
-        if stdin.lock().read_line(&mut line)? == 0 {
-            break; // EOF
-        }
-
-        let line = line.trim();
+    let mut commands: Vec<(String, Vec<String>)> = Cli::command()
+        .get_subcommands()
+        .map(|c| {
+            let subcommands = c
+                .get_subcommands()
+                .map(|s| s.get_name().to_string())
+                .collect();
+            (c.get_name().to_string(), subcommands)
+        })
+        .collect();
+    commands.push(("exit".to_string(), Vec::new()));
+    let mut editor =
+        line_editor::LineEditor::new(line_editor::ReplHelper::for_commands(commands), "history")?;
+    let prompt = format!("{} ", "xlex>".bold().green());
+    let aliases = alias::load();
+
+    while let Some(line) = editor.read_line(&prompt)? {
+        let line = line.as_str();
         if line.is_empty() {
             continue;
         }
@@ -1316,7 +1654,7 @@ fn run_interactive(global: &GlobalOptions) -> Result<()> {
         match line.to_lowercase().as_str() {
             "exit" | "quit" | "q" => {
                 if !global.quiet {
-                    println!("Goodbye!");
+                    outln!("Goodbye!");
                 }
                 break;
             }
@@ -1331,18 +1669,29 @@ fn run_interactive(global: &GlobalOptions) -> Result<()> {
                 }
 
                 // Build the command line with "xlex" prefix
-                let mut cmd_args = vec!["xlex"];
-                cmd_args.extend(args);
+                let mut cmd_args = vec!["xlex".to_string()];
+                cmd_args.extend(args.into_iter().map(str::to_string));
+                let runs = match alias::expand(&cmd_args, 1, &aliases)
+                    .and_then(|expanded| resolve_sheet_args(expanded, 1))
+                {
+                    Ok(runs) => runs,
+                    Err(e) => {
+                        eprintln!("{}: {}", "error".red(), e);
+                        continue;
+                    }
+                };
 
                 // Parse and run
-                match Cli::try_parse_from(&cmd_args) {
-                    Ok(cli) => {
-                        if let Err(e) = cli.run() {
-                            eprintln!("{}: {}", "error".red(), e);
+                for cmd_args in runs {
+                    match Cli::try_parse_from(&cmd_args) {
+                        Ok(cli) => {
+                            if let Err(e) = cli.run() {
+                                eprintln!("{}: {}", "error".red(), e);
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!("{}", e);
                         }
-                    }
-                    Err(e) => {
-                        eprintln!("{}", e);
                     }
                 }
             }
@@ -1355,32 +1704,36 @@ fn run_interactive(global: &GlobalOptions) -> Result<()> {
 fn print_interactive_help() {
     use colored::Colorize;
 
-    println!("{}", "Interactive Mode Commands:".bold());
-    println!("  {}       - Show this help", "help".cyan());
-    println!("  {}       - Exit interactive mode", "exit".cyan());
-    println!();
-    println!("{}", "XLEX Commands (use without 'xlex' prefix):".bold());
-    println!(
+    outln!("{}", "Interactive Mode Commands:".bold());
+    outln!("  {}       - Show this help", "help".cyan());
+    outln!("  {}       - Exit interactive mode", "exit".cyan());
+    outln!(
+        "  {}        - Complete commands and file names",
+        "Tab".cyan()
+    );
+    outln!("  {}     - Search command history", "Ctrl-R".cyan());
+    outln!();
+    outln!("{}", "XLEX Commands (use without 'xlex' prefix):".bold());
+    outln!(
         "  {}         - Show workbook information",
         "info <file>".cyan()
     );
-    println!("  {}       - List sheets", "sheet list <file>".cyan());
-    println!("  {} - Get cell value", "cell get <file> <cell>".cyan());
-    println!(
+    outln!("  {}       - List sheets", "sheet list <file>".cyan());
+    outln!("  {} - Get cell value", "cell get <file> <cell>".cyan());
+    outln!(
         "  {}   - Set cell value",
         "cell set <file> <cell> <value>".cyan()
     );
-    println!();
-    println!("{}", "Examples:".bold());
-    println!("  info test.xlsx");
-    println!("  sheet list test.xlsx");
-    println!("  cell get test.xlsx A1");
-    println!("  cell set test.xlsx A1 \"Hello World\"");
+    outln!();
+    outln!("{}", "Examples:".bold());
+    outln!("  info test.xlsx");
+    outln!("  sheet list test.xlsx");
+    outln!("  cell get test.xlsx A1");
+    outln!("  cell set test.xlsx A1 \"Hello World\"");
 }
 
 fn run_session(args: &SessionArgs, global: &GlobalOptions) -> Result<()> {
     use colored::Colorize;
-    use std::io::{BufRead, Write};
     use std::time::Instant;
     use xlex_core::LazyWorkbook;
 
@@ -1388,7 +1741,7 @@ fn run_session(args: &SessionArgs, global: &GlobalOptions) -> Result<()> {
 
     // Load workbook once using lazy loading
     if !global.quiet {
-        println!("{} {}...", "Loading".bold().cyan(), file_path.display());
+        outln!("{} {}...", "Loading".bold().cyan(), file_path.display());
     }
 
     let start = Instant::now();
@@ -1397,34 +1750,28 @@ fn run_session(args: &SessionArgs, global: &GlobalOptions) -> Result<()> {
     let load_time = start.elapsed();
 
     if !global.quiet {
-        println!(
+        outln!(
             "{} in {:.2}s",
             "Loaded".bold().green(),
             load_time.as_secs_f64()
         );
-        println!();
-        println!("{}", "Session Mode".bold().cyan());
-        println!(
+        outln!();
+        outln!("{}", "Session Mode".bold().cyan());
+        outln!(
             "Type {} for help, {} to exit",
             "help".green(),
             "exit".green()
         );
-        println!();
+        outln!();
     }
 
-    let stdin = std::io::stdin();
-    let mut stdout = std::io::stdout();
-
-    loop {
-        print!("{} ", "session>".bold().yellow());
-        stdout.flush()?;
-
-        let mut line = String::new();
-        if stdin.lock().read_line(&mut line)? == 0 {
-            break; // EOF
-        }
+    let mut editor = line_editor::LineEditor::new(
+        line_editor::ReplHelper::for_session(&workbook),
+        "session_history",
+    )?;
+    let prompt = format!("{} ", "session>".bold().yellow());
 
-        let line = line.trim();
+    while let Some(line) = editor.read_line(&prompt)? {
         if line.is_empty() {
             continue;
         }
@@ -1440,7 +1787,7 @@ fn run_session(args: &SessionArgs, global: &GlobalOptions) -> Result<()> {
         match cmd.as_str() {
             "exit" | "quit" | "q" => {
                 if !global.quiet {
-                    println!("Goodbye!");
+                    outln!("Goodbye!");
                 }
                 break;
             }
@@ -1459,9 +1806,14 @@ fn run_session(args: &SessionArgs, global: &GlobalOptions) -> Result<()> {
                 }
             }
             "cell" => {
-                if args.len() < 2 {
+                let qualified = args
+                    .first()
+                    .and_then(|word| xlex_core::sheet::split_sheet_ref(word));
+                if let (Some((sheet, cell)), 1) = (&qualified, args.len()) {
+                    run_session_cell(&workbook, sheet, cell, global);
+                } else if args.len() < 2 {
                     eprintln!("{}: usage: cell <sheet> <ref>", "error".red());
-                    eprintln!("Example: cell Sheet1 A1");
+                    eprintln!("Example: cell Sheet1 A1 (or cell Sheet1!A1)");
                 } else {
                     run_session_cell(&workbook, args[0], args[1], global);
                 }
@@ -1488,6 +1840,22 @@ fn run_session(args: &SessionArgs, global: &GlobalOptions) -> Result<()> {
                     run_session_search(&workbook, pattern, sheet_filter, global);
                 }
             }
+            "embed" | "embeds" => match workbook.embedded_objects() {
+                Ok(objects) => {
+                    if let Err(e) = embed::print_objects(&objects, global) {
+                        eprintln!("{}: {}", "error".red(), e);
+                    }
+                }
+                Err(e) => eprintln!("{}: {}", "error".red(), e),
+            },
+            "pii" => match scan::find_pii(&workbook, args.first().copied()) {
+                Ok(findings) => {
+                    if let Err(e) = scan::print_findings(&findings, global) {
+                        eprintln!("{}: {}", "error".red(), e);
+                    }
+                }
+                Err(e) => eprintln!("{}: {}", "error".red(), e),
+            },
             _ => {
                 eprintln!("{}: unknown command '{}'", "error".red(), cmd);
                 eprintln!("Type 'help' for available commands");
@@ -1501,28 +1869,35 @@ fn run_session(args: &SessionArgs, global: &GlobalOptions) -> Result<()> {
 fn print_session_help() {
     use colored::Colorize;
 
-    println!("{}", "Session Mode Commands:".bold());
-    println!("  {}       - Show this help", "help".cyan());
-    println!("  {}       - Exit session mode", "exit".cyan());
-    println!();
-    println!("{}", "Workbook Commands:".bold());
-    println!("  {}           - Show workbook information", "info".cyan());
-    println!("  {}         - List all sheets", "sheets".cyan());
-    println!("  {}  - Get cell value", "cell <sheet> <ref>".cyan());
-    println!("  {} - Get row values", "row <sheet> <number>".cyan());
-    println!(
+    outln!("{}", "Session Mode Commands:".bold());
+    outln!("  {}       - Show this help", "help".cyan());
+    outln!("  {}       - Exit session mode", "exit".cyan());
+    outln!(
+        "  {}        - Complete commands, sheets and cells",
+        "Tab".cyan()
+    );
+    outln!("  {}     - Search command history", "Ctrl-R".cyan());
+    outln!();
+    outln!("{}", "Workbook Commands:".bold());
+    outln!("  {}           - Show workbook information", "info".cyan());
+    outln!("  {}         - List all sheets", "sheets".cyan());
+    outln!("  {}  - Get cell value", "cell <sheet> <ref>".cyan());
+    outln!("  {} - Get row values", "row <sheet> <number>".cyan());
+    outln!(
         "  {} - Search across all sheets",
         "search <pattern> [sheet]".cyan()
     );
-    println!();
-    println!("{}", "Examples:".bold());
-    println!("  info");
-    println!("  sheets");
-    println!("  cell Sheet1 A1");
-    println!("  cell Sheet1 B2:D5");
-    println!("  row Sheet1 1");
-    println!("  search revenue");
-    println!("  search error Sheet1");
+    outln!("  {}          - List embedded objects", "embed".cyan());
+    outln!("  {}    - Find personal data", "pii [sheet]".cyan());
+    outln!();
+    outln!("{}", "Examples:".bold());
+    outln!("  info");
+    outln!("  sheets");
+    outln!("  cell Sheet1 A1");
+    outln!("  cell Sheet1 B2:D5");
+    outln!("  row Sheet1 1");
+    outln!("  search revenue");
+    outln!("  search error Sheet1");
 }
 
 fn run_session_info(workbook: &xlex_core::LazyWorkbook, global: &GlobalOptions) {
@@ -1535,10 +1910,10 @@ fn run_session_info(workbook: &xlex_core::LazyWorkbook, global: &GlobalOptions)
             "sheet_count": sheets.len(),
             "sheets": sheets,
         });
-        println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        outln!("{}", serde_json::to_string_pretty(&json).unwrap());
     } else {
-        println!("{}: {}", "Sheet count".bold(), sheets.len());
-        println!("{}: {}", "Sheets".bold(), sheets.join(", "));
+        outln!("{}: {}", "Sheet count".bold(), sheets.len());
+        outln!("{}: {}", "Sheets".bold(), sheets.join(", "));
     }
 }
 
@@ -1551,11 +1926,11 @@ fn run_session_sheets(workbook: &xlex_core::LazyWorkbook, global: &GlobalOptions
         let json = serde_json::json!({
             "sheets": sheets,
         });
-        println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        outln!("{}", serde_json::to_string_pretty(&json).unwrap());
     } else {
-        println!("{}", "Sheets:".bold());
+        outln!("{}", "Sheets:".bold());
         for (i, name) in sheets.iter().enumerate() {
-            println!("  {}. {}", i + 1, name.cyan());
+            outln!("  {}. {}", i + 1, name.cyan());
         }
     }
 }
@@ -1590,9 +1965,9 @@ fn run_session_cell(
                     "cell": cell_ref_str,
                     "value": value.to_string(),
                 });
-                println!("{}", serde_json::to_string_pretty(&json).unwrap());
+                outln!("{}", serde_json::to_string_pretty(&json).unwrap());
             } else {
-                println!("{}", value);
+                outln!("{}", value);
             }
         }
         Ok(None) => {
@@ -1602,9 +1977,9 @@ fn run_session_cell(
                     "cell": cell_ref_str,
                     "value": null,
                 });
-                println!("{}", serde_json::to_string_pretty(&json).unwrap());
+                outln!("{}", serde_json::to_string_pretty(&json).unwrap());
             } else {
-                println!("(empty)");
+                outln!("(empty)");
             }
         }
         Err(e) => {
@@ -1639,11 +2014,11 @@ fn run_session_row(
                             "row": row_num,
                             "cells": cells_map,
                         });
-                        println!("{}", serde_json::to_string_pretty(&json).unwrap());
+                        outln!("{}", serde_json::to_string_pretty(&json).unwrap());
                     } else {
                         let values: Vec<String> =
                             row.cells.iter().map(|(_, v)| v.to_string()).collect();
-                        println!("{}", values.join("\t"));
+                        outln!("{}", values.join("\t"));
                     }
                     break;
                 }
@@ -1721,15 +2096,15 @@ fn run_session_search(
             "count": matches.len(),
             "matches": matches,
         });
-        println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        outln!("{}", serde_json::to_string_pretty(&json).unwrap());
     } else if matches.is_empty() {
-        println!(
+        outln!(
             "{}: no matches found for \"{}\"",
             "search".yellow(),
             pattern
         );
     } else {
-        println!(
+        outln!(
             "Found {} match{} for \"{}\":\n",
             matches.len().to_string().green(),
             if matches.len() == 1 { "" } else { "es" },
@@ -1739,14 +2114,14 @@ fn run_session_search(
         for m in &matches {
             if m.sheet != current_sheet {
                 if !current_sheet.is_empty() {
-                    println!();
+                    outln!();
                 }
-                println!("  {} {}", "Sheet:".bold(), m.sheet.bold());
+                outln!("  {} {}", "Sheet:".bold(), m.sheet.bold());
                 current_sheet = &m.sheet;
             }
-            println!("    {} = {}", m.cell.yellow(), m.value.dimmed());
+            outln!("    {} = {}", m.cell.yellow(), m.value.dimmed());
         }
-        println!();
+        outln!();
     }
 }
 
@@ -1768,201 +2143,201 @@ fn run_examples(args: &ExamplesArgs, _global: &GlobalOptions) -> Result<()> {
 fn print_overview_help() {
     use colored::Colorize;
 
-    println!(
+    outln!(
         "{}",
         "XLEX - A streaming Excel manipulation tool".bold().cyan()
     );
-    println!();
-    println!("{}", "USAGE:".bold());
-    println!("    xlex <COMMAND> [OPTIONS]");
-    println!();
-    println!("{}", "COMMANDS:".bold());
-    println!("    {}      Show workbook information", "info".green());
-    println!("    {}     Create a new workbook", "create".green());
-    println!("    {}     Sheet operations", "sheet".green());
-    println!("    {}      Cell operations", "cell".green());
-    println!("    {}       Row operations", "row".green());
-    println!("    {}    Column operations", "column".green());
-    println!("    {}     Range operations", "range".green());
-    println!("    {}     Style operations", "style".green());
-    println!("    {}   Formula operations", "formula".green());
-    println!("    {}  Template operations", "template".green());
-    println!("    {}    Import from external format", "import".green());
-    println!("    {}    Export to external format", "export".green());
-    println!("    {}   Convert between formats", "convert".green());
-    println!();
-    println!("{}", "QUICK EXAMPLES:".bold());
-    println!("    xlex info workbook.xlsx");
-    println!("    xlex create new.xlsx");
-    println!("    xlex cell get workbook.xlsx A1");
-    println!("    xlex cell set workbook.xlsx A1 \"Hello\"");
-    println!("    xlex sheet list workbook.xlsx");
-    println!("    xlex export csv workbook.xlsx data.csv");
-    println!();
-    println!(
+    outln!();
+    outln!("{}", "USAGE:".bold());
+    outln!("    xlex <COMMAND> [OPTIONS]");
+    outln!();
+    outln!("{}", "COMMANDS:".bold());
+    outln!("    {}      Show workbook information", "info".green());
+    outln!("    {}     Create a new workbook", "create".green());
+    outln!("    {}     Sheet operations", "sheet".green());
+    outln!("    {}      Cell operations", "cell".green());
+    outln!("    {}       Row operations", "row".green());
+    outln!("    {}    Column operations", "column".green());
+    outln!("    {}     Range operations", "range".green());
+    outln!("    {}     Style operations", "style".green());
+    outln!("    {}   Formula operations", "formula".green());
+    outln!("    {}  Template operations", "template".green());
+    outln!("    {}    Import from external format", "import".green());
+    outln!("    {}    Export to external format", "export".green());
+    outln!("    {}   Convert between formats", "convert".green());
+    outln!();
+    outln!("{}", "QUICK EXAMPLES:".bold());
+    outln!("    xlex info workbook.xlsx");
+    outln!("    xlex create new.xlsx");
+    outln!("    xlex cell get workbook.xlsx A1");
+    outln!("    xlex cell set workbook.xlsx A1 \"Hello\"");
+    outln!("    xlex sheet list workbook.xlsx");
+    outln!("    xlex export csv workbook.xlsx data.csv");
+    outln!();
+    outln!(
         "Run {} for examples for a specific command",
         "xlex examples <command>".yellow()
     );
-    println!("Run {} for all examples", "xlex examples --all".yellow());
+    outln!("Run {} for all examples", "xlex examples --all".yellow());
 }
 
 fn print_all_examples() {
     use colored::Colorize;
 
-    println!("{}", "XLEX Command Examples".bold().cyan());
-    println!();
+    outln!("{}", "XLEX Command Examples".bold().cyan());
+    outln!();
 
     // Workbook examples
-    println!("{}", "WORKBOOK OPERATIONS:".bold());
-    println!("  # Show workbook information");
-    println!("  {} workbook.xlsx", "xlex info".green());
-    println!("  {} workbook.xlsx --format json", "xlex info".green());
-    println!();
-    println!("  # Create a new workbook");
-    println!("  {} new.xlsx", "xlex create".green());
-    println!(
+    outln!("{}", "WORKBOOK OPERATIONS:".bold());
+    outln!("  # Show workbook information");
+    outln!("  {} workbook.xlsx", "xlex info".green());
+    outln!("  {} workbook.xlsx --format json", "xlex info".green());
+    outln!();
+    outln!("  # Create a new workbook");
+    outln!("  {} new.xlsx", "xlex create".green());
+    outln!(
         "  {} new.xlsx --sheets Sales,Inventory,Summary",
         "xlex create".green()
     );
-    println!();
-    println!("  # Clone a workbook");
-    println!("  {} original.xlsx copy.xlsx", "xlex clone".green());
-    println!();
+    outln!();
+    outln!("  # Clone a workbook");
+    outln!("  {} original.xlsx copy.xlsx", "xlex clone".green());
+    outln!();
 
     // Sheet examples
-    println!("{}", "SHEET OPERATIONS:".bold());
-    println!("  # List sheets");
-    println!("  {} workbook.xlsx", "xlex sheet list".green());
-    println!();
-    println!("  # Add a new sheet");
-    println!("  {} workbook.xlsx NewSheet", "xlex sheet add".green());
-    println!();
-    println!("  # Rename a sheet");
-    println!(
+    outln!("{}", "SHEET OPERATIONS:".bold());
+    outln!("  # List sheets");
+    outln!("  {} workbook.xlsx", "xlex sheet list".green());
+    outln!();
+    outln!("  # Add a new sheet");
+    outln!("  {} workbook.xlsx NewSheet", "xlex sheet add".green());
+    outln!();
+    outln!("  # Rename a sheet");
+    outln!(
         "  {} workbook.xlsx OldName NewName",
         "xlex sheet rename".green()
     );
-    println!();
-    println!("  # Remove a sheet");
-    println!(
+    outln!();
+    outln!("  # Remove a sheet");
+    outln!(
         "  {} workbook.xlsx SheetToRemove",
         "xlex sheet remove".green()
     );
-    println!();
+    outln!();
 
     // Cell examples
-    println!("{}", "CELL OPERATIONS:".bold());
-    println!("  # Get a cell value");
-    println!("  {} workbook.xlsx A1", "xlex cell get".green());
-    println!("  {} workbook.xlsx B2 -s Sales", "xlex cell get".green());
-    println!();
-    println!("  # Set a cell value");
-    println!(
+    outln!("{}", "CELL OPERATIONS:".bold());
+    outln!("  # Get a cell value");
+    outln!("  {} workbook.xlsx A1", "xlex cell get".green());
+    outln!("  {} workbook.xlsx B2 -s Sales", "xlex cell get".green());
+    outln!();
+    outln!("  # Set a cell value");
+    outln!(
         "  {} workbook.xlsx A1 \"Hello World\"",
         "xlex cell set".green()
     );
-    println!(
+    outln!(
         "  {} workbook.xlsx B2 123.45 -s Sales",
         "xlex cell set".green()
     );
-    println!();
-    println!("  # Set a formula");
-    println!(
+    outln!();
+    outln!("  # Set a formula");
+    outln!(
         "  {} workbook.xlsx A5 \"=SUM(A1:A4)\"",
         "xlex cell formula".green()
     );
-    println!();
-    println!("  # Batch update cells");
-    println!(
+    outln!();
+    outln!("  # Batch update cells");
+    outln!(
         "  echo 'A1=Hello' | {} workbook.xlsx",
         "xlex cell batch".green()
     );
-    println!();
+    outln!();
 
     // Row/Column examples
-    println!("{}", "ROW & COLUMN OPERATIONS:".bold());
-    println!("  # Get a row");
-    println!("  {} workbook.xlsx 1", "xlex row get".green());
-    println!();
-    println!("  # Append a row");
-    println!(
+    outln!("{}", "ROW & COLUMN OPERATIONS:".bold());
+    outln!("  # Get a row");
+    outln!("  {} workbook.xlsx 1", "xlex row get".green());
+    outln!();
+    outln!("  # Append a row");
+    outln!(
         "  {} workbook.xlsx Value1,Value2,Value3",
         "xlex row append".green()
     );
-    println!();
-    println!("  # Get a column");
-    println!("  {} workbook.xlsx A", "xlex column get".green());
-    println!();
-    println!("  # Set column width");
-    println!("  {} workbook.xlsx A 20", "xlex column width".green());
-    println!();
+    outln!();
+    outln!("  # Get a column");
+    outln!("  {} workbook.xlsx A", "xlex column get".green());
+    outln!();
+    outln!("  # Set column width");
+    outln!("  {} workbook.xlsx A 20", "xlex column width".green());
+    outln!();
 
     // Range examples
-    println!("{}", "RANGE OPERATIONS:".bold());
-    println!("  # Get a range");
-    println!("  {} workbook.xlsx A1:C10", "xlex range get".green());
-    println!();
-    println!("  # Copy a range");
-    println!("  {} workbook.xlsx A1:C10 E1", "xlex range copy".green());
-    println!();
-    println!("  # Merge cells");
-    println!("  {} workbook.xlsx A1:D1", "xlex range merge".green());
-    println!();
+    outln!("{}", "RANGE OPERATIONS:".bold());
+    outln!("  # Get a range");
+    outln!("  {} workbook.xlsx A1:C10", "xlex range get".green());
+    outln!();
+    outln!("  # Copy a range");
+    outln!("  {} workbook.xlsx A1:C10 E1", "xlex range copy".green());
+    outln!();
+    outln!("  # Merge cells");
+    outln!("  {} workbook.xlsx A1:D1", "xlex range merge".green());
+    outln!();
 
     // Import/Export examples
-    println!("{}", "IMPORT/EXPORT:".bold());
-    println!("  # Export to CSV");
-    println!("  {} workbook.xlsx output.csv", "xlex export csv".green());
-    println!();
-    println!("  # Export to JSON");
-    println!("  {} workbook.xlsx output.json", "xlex export json".green());
-    println!();
-    println!("  # Import from CSV");
-    println!("  {} data.csv workbook.xlsx", "xlex import csv".green());
-    println!();
-    println!("  # Convert between formats");
-    println!("  {} data.csv output.xlsx", "xlex convert".green());
-    println!("  {} workbook.xlsx output.json", "xlex convert".green());
-    println!();
+    outln!("{}", "IMPORT/EXPORT:".bold());
+    outln!("  # Export to CSV");
+    outln!("  {} workbook.xlsx output.csv", "xlex export csv".green());
+    outln!();
+    outln!("  # Export to JSON");
+    outln!("  {} workbook.xlsx output.json", "xlex export json".green());
+    outln!();
+    outln!("  # Import from CSV");
+    outln!("  {} data.csv workbook.xlsx", "xlex import csv".green());
+    outln!();
+    outln!("  # Convert between formats");
+    outln!("  {} data.csv output.xlsx", "xlex convert".green());
+    outln!("  {} workbook.xlsx output.json", "xlex convert".green());
+    outln!();
 
     // Template examples
-    println!("{}", "TEMPLATE OPERATIONS:".bold());
-    println!("  # Create a template");
-    println!(
+    outln!("{}", "TEMPLATE OPERATIONS:".bold());
+    outln!("  # Create a template");
+    outln!(
         "  {} template.xlsx --type invoice",
         "xlex template init".green()
     );
-    println!();
-    println!("  # List placeholders");
-    println!("  {} template.xlsx", "xlex template list".green());
-    println!();
-    println!("  # Apply template with variables");
-    println!(
+    outln!();
+    outln!("  # List placeholders");
+    outln!("  {} template.xlsx", "xlex template list".green());
+    outln!();
+    outln!("  # Apply template with variables");
+    outln!(
         "  {} template.xlsx output.xlsx -v vars.json",
         "xlex template apply".green()
     );
-    println!(
+    outln!(
         "  {} template.xlsx output.xlsx -D name=John -D date=2024-01-01",
         "xlex template apply".green()
     );
-    println!();
-    println!("  # Batch template processing");
-    println!(
+    outln!();
+    outln!("  # Batch template processing");
+    outln!(
         "  {} template.xlsx output.xlsx -v data.json --per-record",
         "xlex template apply".green()
     );
-    println!();
+    outln!();
 
     // Formula examples
-    println!("{}", "FORMULA OPERATIONS:".bold());
-    println!("  # List all formulas");
-    println!("  {} workbook.xlsx", "xlex formula list".green());
-    println!();
-    println!("  # Validate formulas");
-    println!("  {} workbook.xlsx", "xlex formula validate".green());
-    println!();
-    println!("  # Get formula statistics");
-    println!("  {} workbook.xlsx", "xlex formula stats".green());
+    outln!("{}", "FORMULA OPERATIONS:".bold());
+    outln!("  # List all formulas");
+    outln!("  {} workbook.xlsx", "xlex formula list".green());
+    outln!();
+    outln!("  # Validate formulas");
+    outln!("  {} workbook.xlsx", "xlex formula validate".green());
+    outln!();
+    outln!("  # Get formula statistics");
+    outln!("  {} workbook.xlsx", "xlex formula stats".green());
 }
 
 fn print_command_help_with_examples(command: &str) {
@@ -1970,93 +2345,96 @@ fn print_command_help_with_examples(command: &str) {
 
     match command.to_lowercase().as_str() {
         "info" => {
-            println!("{}", "xlex info - Display workbook information".bold());
-            println!();
-            println!("{}", "USAGE:".bold());
-            println!("    xlex info <FILE> [OPTIONS]");
-            println!();
-            println!("{}", "EXAMPLES:".bold());
-            println!("    xlex info workbook.xlsx");
-            println!("    xlex info workbook.xlsx --format json");
-            println!("    xlex info workbook.xlsx -v");
+            outln!("{}", "xlex info - Display workbook information".bold());
+            outln!();
+            outln!("{}", "USAGE:".bold());
+            outln!("    xlex info <FILE> [OPTIONS]");
+            outln!();
+            outln!("{}", "EXAMPLES:".bold());
+            outln!("    xlex info workbook.xlsx");
+            outln!("    xlex info workbook.xlsx --format json");
+            outln!("    xlex info workbook.xlsx -v");
         }
         "create" => {
-            println!("{}", "xlex create - Create a new workbook".bold());
-            println!();
-            println!("{}", "USAGE:".bold());
-            println!("    xlex create <FILE> [OPTIONS]");
-            println!();
-            println!("{}", "OPTIONS:".bold());
-            println!("    --sheets <NAMES>    Comma-separated sheet names");
-            println!("    --force             Overwrite existing file");
-            println!();
-            println!("{}", "EXAMPLES:".bold());
-            println!("    xlex create new.xlsx");
-            println!("    xlex create report.xlsx --sheets Summary,Data,Charts");
-            println!("    xlex create backup.xlsx --force");
+            outln!("{}", "xlex create - Create a new workbook".bold());
+            outln!();
+            outln!("{}", "USAGE:".bold());
+            outln!("    xlex create <FILE> [OPTIONS]");
+            outln!();
+            outln!("{}", "OPTIONS:".bold());
+            outln!("    --sheets <NAMES>    Comma-separated sheet names");
+            outln!("    --force             Overwrite existing file");
+            outln!();
+            outln!("{}", "EXAMPLES:".bold());
+            outln!("    xlex create new.xlsx");
+            outln!("    xlex create report.xlsx --sheets Summary,Data,Charts");
+            outln!("    xlex create backup.xlsx --force");
         }
         "sheet" => {
-            println!("{}", "xlex sheet - Sheet operations".bold());
-            println!();
-            println!("{}", "SUBCOMMANDS:".bold());
-            println!("    list    List all sheets");
-            println!("    add     Add a new sheet");
-            println!("    remove  Remove a sheet");
-            println!("    rename  Rename a sheet");
-            println!("    copy    Copy a sheet");
-            println!("    move    Move a sheet");
-            println!("    info    Show sheet information");
-            println!();
-            println!("{}", "EXAMPLES:".bold());
-            println!("    xlex sheet list workbook.xlsx");
-            println!("    xlex sheet add workbook.xlsx NewSheet");
-            println!("    xlex sheet rename workbook.xlsx OldName NewName");
-            println!("    xlex sheet copy workbook.xlsx Sheet1 Sheet1_Copy");
-            println!("    xlex sheet move workbook.xlsx Sheet1 2");
+            outln!("{}", "xlex sheet - Sheet operations".bold());
+            outln!();
+            outln!("{}", "SUBCOMMANDS:".bold());
+            outln!("    list    List all sheets");
+            outln!("    add     Add a new sheet");
+            outln!("    remove  Remove a sheet");
+            outln!("    rename  Rename a sheet");
+            outln!("    copy    Copy a sheet");
+            outln!("    move    Move a sheet");
+            outln!("    info    Show sheet information");
+            outln!();
+            outln!("{}", "EXAMPLES:".bold());
+            outln!("    xlex sheet list workbook.xlsx");
+            outln!("    xlex sheet add workbook.xlsx NewSheet");
+            outln!("    xlex sheet rename workbook.xlsx OldName NewName");
+            outln!("    xlex sheet copy workbook.xlsx Sheet1 Sheet1_Copy");
+            outln!("    xlex sheet move workbook.xlsx Sheet1 2");
         }
         "cell" => {
-            println!("{}", "xlex cell - Cell operations".bold());
-            println!();
-            println!("{}", "SUBCOMMANDS:".bold());
-            println!("    get      Get cell value");
-            println!("    set      Set cell value");
-            println!("    formula  Set cell formula");
-            println!("    clear    Clear cell content");
-            println!("    batch    Batch update cells");
-            println!();
-            println!("{}", "EXAMPLES:".bold());
-            println!("    xlex cell get workbook.xlsx A1");
-            println!("    xlex cell get workbook.xlsx B2 -s Sales");
-            println!("    xlex cell set workbook.xlsx A1 \"Hello World\"");
-            println!("    xlex cell formula workbook.xlsx C10 \"=SUM(C1:C9)\"");
-            println!("    echo 'A1=Hello\\nB1=World' | xlex cell batch workbook.xlsx");
+            outln!("{}", "xlex cell - Cell operations".bold());
+            outln!();
+            outln!("{}", "SUBCOMMANDS:".bold());
+            outln!("    get      Get cell value");
+            outln!("    set      Set cell value");
+            outln!("    formula  Set cell formula");
+            outln!("    clear    Clear cell content");
+            outln!("    batch    Batch update cells");
+            outln!();
+            outln!("{}", "EXAMPLES:".bold());
+            outln!("    xlex cell get workbook.xlsx A1");
+            outln!("    xlex cell get workbook.xlsx B2 -s Sales");
+            outln!("    xlex cell set workbook.xlsx A1 \"Hello World\"");
+            outln!("    xlex cell formula workbook.xlsx C10 \"=SUM(C1:C9)\"");
+            outln!("    echo 'A1=Hello\\nB1=World' | xlex cell batch workbook.xlsx");
         }
         "template" => {
-            println!("{}", "xlex template - Template operations".bold());
-            println!();
-            println!("{}", "SUBCOMMANDS:".bold());
-            println!("    init      Create a new template");
-            println!("    list      List placeholders");
-            println!("    validate  Validate template");
-            println!("    apply     Apply template with variables");
-            println!("    preview   Preview template rendering");
-            println!();
-            println!("{}", "TEMPLATE FEATURES:".bold());
-            println!("    {{{{name}}}}                  Simple placeholder");
-            println!("    {{{{name|upper}}}}            Filter (upper, lower, currency, etc.)");
-            println!("    {{{{#if condition}}}}...{{{{/if}}}}  Conditional");
-            println!("    {{{{#row-repeat items}}}}    Row repetition");
-            println!();
-            println!("{}", "EXAMPLES:".bold());
-            println!("    xlex template init report.xlsx --type invoice");
-            println!("    xlex template list template.xlsx");
-            println!("    xlex template apply template.xlsx output.xlsx -v vars.json");
-            println!("    xlex template apply template.xlsx output.xlsx -D name=John");
-            println!("    xlex template apply template.xlsx output.xlsx --per-record -v data.json");
+            outln!("{}", "xlex template - Template operations".bold());
+            outln!();
+            outln!("{}", "SUBCOMMANDS:".bold());
+            outln!("    init      Create a new template");
+            outln!("    list      List placeholders");
+            outln!("    validate  Validate template");
+            outln!("    apply     Apply template with variables");
+            outln!("    preview   Preview template rendering");
+            outln!();
+            outln!("{}", "TEMPLATE FEATURES:".bold());
+            outln!("    {{{{name}}}}                  Simple placeholder");
+            outln!("    {{{{name|upper}}}}            Filter (upper, currency, spellout:en, format_date:d MMMM yyyy, ...)");
+            outln!("    {{{{#if condition}}}}...{{{{/if}}}}  Conditional");
+            outln!("    {{{{#if c}}}} ... {{{{/if}}}} in other cells  Keep or remove whole rows");
+            outln!("    {{{{#row-repeat items}}}}    Row repetition");
+            outln!("    {{{{sum items.amount}}}}      Aggregate (sum, count, avg, min, max)");
+            outln!("    __vars sheet              Defaults: name, value, type per row");
+            outln!();
+            outln!("{}", "EXAMPLES:".bold());
+            outln!("    xlex template init report.xlsx --type invoice");
+            outln!("    xlex template list template.xlsx");
+            outln!("    xlex template apply template.xlsx output.xlsx -v vars.json");
+            outln!("    xlex template apply template.xlsx output.xlsx -D name=John");
+            outln!("    xlex template apply template.xlsx output.xlsx --per-record -v data.json");
         }
         _ => {
-            println!("No detailed help available for '{}'", command);
-            println!("Run {} for general help", "xlex --help".yellow());
+            outln!("No detailed help available for '{}'", command);
+            outln!("Run {} for general help", "xlex --help".yellow());
         }
     }
 }
@@ -2065,7 +2443,7 @@ fn run_man(args: &ManArgs, global: &GlobalOptions) -> Result<()> {
     use std::io::Write;
 
     if global.dry_run {
-        println!("Would generate man pages in {}", args.output_dir.display());
+        outln!("Would generate man pages in {}", args.output_dir.display());
         return Ok(());
     }
 
@@ -2078,7 +2456,7 @@ fn run_man(args: &ManArgs, global: &GlobalOptions) -> Result<()> {
     file.write_all(man_content.as_bytes())?;
 
     if !global.quiet {
-        println!("Generated man page: {}", man_path.display());
+        outln!("Generated man page: {}", man_path.display());
     }
 
     Ok(())
@@ -2104,11 +2482,29 @@ and automation pipelines. It can handle files up to 200MB without memory exhaust
 Display workbook information
 .TP
 .B create \fIFILE\fR
-Create a new workbook
+Create a new workbook, optionally from a YAML schema (\-\-from\-schema)
 .TP
 .B clone \fISOURCE\fR \fIDEST\fR
 Clone a workbook
 .TP
+.B optimize \fIFILE\fR
+Shrink a workbook and report the size before and after
+.TP
+.B scrub \fIFILE\fR
+Remove personal metadata (authors, paths, printer settings, hidden names, revisions, custom properties)
+.TP
+.B index \fIFILE\fR
+Write FILE.xlexidx so streaming reads of a large workbook reopen without scanning
+.TP
+.B hash \fIFILE\fR
+Show the content hash; \-\-stamp records it in custom properties, \-\-verify checks it
+.TP
+.B sign \fIFILE\fR \-\-pfx \fICERT\fR
+Add a digital signature with the key and certificate in a PKCS#12 file
+.TP
+.B verify\-signature \fIFILE\fR
+Check the digital signatures; \-\-ca checks the signers against trusted CAs
+.TP
 .B sheet \fISUBCOMMAND\fR
 Sheet operations (list, add, remove, rename, copy, move)
 .TP
@@ -2122,14 +2518,32 @@ Row operations (get, append, insert, delete)
 Column operations (get, insert, delete, width)
 .TP
 .B range \fISUBCOMMAND\fR
-Range operations (get, copy, move, merge, sort, filter)
+Range operations (get, copy, move, merge, sort, filter, round, normalize-dates, clean)
+.TP
+.B name \fISUBCOMMAND\fR
+Named cells and ranges, usable as @NAME in place of a sheet and cell (add, list, remove)
 .TP
 .B style \fISUBCOMMAND\fR
 Style operations (list, get, apply)
 .TP
+.B sparkline \fISUBCOMMAND\fR
+Sparkline operations (add, list, remove)
+.TP
 .B formula \fISUBCOMMAND\fR
 Formula operations (list, validate, stats, refs)
 .TP
+.B links \fISUBCOMMAND\fR
+External workbook link operations (list, update, break)
+.TP
+.B embed \fISUBCOMMAND\fR
+Embedded object operations (list, extract)
+.TP
+.B audit \fISUBCOMMAND\fR
+Workbook audits (unused, hidden)
+.TP
+.B scan \fISUBCOMMAND\fR
+Content scans (pii)
+.TP
 .B template \fISUBCOMMAND\fR
 Template operations (init, list, validate, apply, preview)
 .TP
@@ -2141,6 +2555,45 @@ Export to external format (csv, json, ndjson)
 .TP
 .B convert \fIINPUT\fR \fIOUTPUT\fR
 Convert between formats
+.TP
+.B diff \fILEFT\fR \fIRIGHT\fR
+Compare two workbooks cell by cell (\-\-values\-only, \-\-tolerance, \-\-key\-column), or list changed rows as NDJSON (\-\-rows)
+.TP
+.B gen random \fIOUTPUT\fR
+Generate a table of random values (\-\-rows, \-\-cols, \-\-types, \-\-seed)
+.TP
+.B gen sample \fIOUTPUT\fR \-\-schema \fISCHEMA\fR
+Generate a sample dataset of fake names, emails, dates, amounts and choices described by a YAML schema
+.TP
+.B transform \fIFILE\fR \-\-script \fISCRIPT\fR \-\-out \fIOUTPUT\fR
+Stream a sheet through YAML steps (filter, select, drop, rename, fill, trim, derive) into a new workbook
+.TP
+.B join \fILEFT\fR \fISHEET\fR \fIRIGHT\fR \fISHEET\fR \-\-on \fILEFT=RIGHT\fR \-\-out \fIOUTPUT\fR
+Hash join two sheets on a key column (\-\-type inner|left), spilling the right side to disk past \-\-spill\-after rows
+.TP
+.B reshape unpivot \fIFILE\fR \fISHEET\fR \fIRANGE\fR \-\-id\-cols \fICOLS\fR
+Turn the other columns of a range into rows of \-\-var\-name and \-\-value\-name in a new sheet (alias: pivot\-longer)
+.TP
+.B reshape pivot\-wider \fIFILE\fR \fISHEET\fR \fIRANGE\fR \-\-id\-cols \fICOLS\fR \-\-names\-from \fICOL\fR \-\-values\-from \fICOL\fR
+Turn rows of names and values into columns, one row per id, in a new sheet
+.TP
+.B aggregate \fIFILE\fR \fISHEET\fR \-\-group\-by \fICOLS\fR \-\-agg \fIAGGREGATES\fR
+Group rows and write sum, count, avg, min and max per group to a new sheet (\-\-out\-sheet, default Summary)
+.TP
+.B derive \fIFILE\fR \fISHEET\fR \-\-new\-column \fITARGET=FUNC(COL)\fR
+Fill a column with cumsum, movavg, rank, lag or lead of another column, written as values (repeatable)
+.TP
+.B run \fIJOB\fR
+Run a YAML job: read xlsx/csv/json sources, transform them (filter, select, derive, join, ...) and write xlsx or csv sinks
+.TP
+.B consolidate \fIPLAN\fR
+Stack similar workbooks matched by file name patterns into one xlsx or CSV, mapping their headers to common columns with a column naming each row's file
+.TP
+.B script run \fIFILE\fR \fISCRIPT\fR
+Run a sandboxed Rhai script that reads and writes cells, walks rows and adds sheets, then save the workbook (\-\-arg NAME=VALUE, \-\-out)
+.TP
+.B view \fIFILE\fR
+Browse a workbook in an interactive terminal viewer (sheet tabs, paging, cell inspection, search)
 .SH GLOBAL OPTIONS
 .TP
 .B \-q, \-\-quiet
@@ -2150,7 +2603,7 @@ Suppress all output except errors
 Enable verbose output
 .TP
 .B \-f, \-\-format \fIFORMAT\fR
-Output format (text, json, csv, ndjson)
+Output format (text, json, csv, ndjson, html\-table)
 .TP
 .B \-\-no\-color
 Disable colored output
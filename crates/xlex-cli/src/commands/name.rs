@@ -0,0 +1,279 @@
+//! Named cell anchors.
+//!
+//! An anchor is a workbook-wide defined name pointing at a cell or range,
+//! such as `TotalCell` = `Sheet1!$F$42`. Commands then accept `@TotalCell`
+//! wherever they take a sheet and cell (see `sheet_args`), so scripts keep
+//! working when the report layout moves.
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+
+use xlex_core::sheet::quote_sheet_name;
+use xlex_core::{CellRef, DefinedName, Range, Workbook};
+
+use super::output::CommandOutput;
+use super::{GlobalOptions, OutputFormat};
+
+/// Arguments for anchor operations.
+#[derive(Parser)]
+pub struct NameArgs {
+    #[command(subcommand)]
+    pub command: NameCommand,
+}
+
+#[derive(Subcommand)]
+pub enum NameCommand {
+    /// Name a cell or range, for use as @NAME in other commands
+    Add {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Name (letters, digits, _ and ., not a cell reference)
+        name: String,
+        /// Sheet-qualified cell or range (e.g., Sheet1!F42)
+        reference: String,
+    },
+    /// List named cells and ranges
+    List {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+    },
+    /// Remove a name
+    Remove {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Name to remove
+        name: String,
+    },
+}
+
+/// Run anchor operations.
+pub fn run(args: &NameArgs, global: &GlobalOptions) -> Result<()> {
+    match &args.command {
+        NameCommand::Add {
+            file,
+            name,
+            reference,
+        } => add(file, name, reference, global),
+        NameCommand::List { file } => list(file, global),
+        NameCommand::Remove { file, name } => remove(file, name, global),
+    }
+}
+
+/// Checks that `name` can be used as a defined name.
+fn validate_name(name: &str) -> Result<()> {
+    let valid = name.starts_with(|c: char| c.is_alphabetic() || c == '_' || c == '\\')
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '.' || c == '\\')
+        && CellRef::parse(name).is_err()
+        && !name.eq_ignore_ascii_case("R")
+        && !name.eq_ignore_ascii_case("C");
+    if !valid {
+        anyhow::bail!(
+            "Invalid name '{}': use letters, digits, _ and ., starting with a letter, and not a cell reference",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Formats a range with absolute references (`$F$42`, `$B$2:$C$3`).
+fn absolute(range: &Range) -> String {
+    let cell = |c: &CellRef| format!("${}${}", CellRef::col_to_letters(c.col), c.row);
+    if range.is_single() {
+        cell(&range.start)
+    } else {
+        format!("{}:{}", cell(&range.start), cell(&range.end))
+    }
+}
+
+fn add(file: &std::path::Path, name: &str, reference: &str, global: &GlobalOptions) -> Result<()> {
+    validate_name(name)?;
+    let (sheet, range) = match Range::parse_qualified(&reference.replace('$', ""))? {
+        (Some(sheet), range) => (sheet, range),
+        (None, _) => anyhow::bail!(
+            "Reference '{}' needs a sheet, e.g. Sheet1!{}",
+            reference,
+            reference
+        ),
+    };
+
+    let mut workbook = Workbook::open(file)?;
+    if workbook.get_sheet(&sheet).is_none() {
+        return Err(xlex_core::XlexError::SheetNotFound { name: sheet }.into());
+    }
+    let target = format!("{}!{}", quote_sheet_name(&sheet), absolute(&range));
+
+    if global.dry_run {
+        outln!("Would name {} '{}'", target, name);
+        return Ok(());
+    }
+
+    workbook.set_defined_name(DefinedName::new(name, &target));
+    workbook.save()?;
+
+    CommandOutput::new("add")
+        .file(file)
+        .target("name", name)
+        .field("sheet", sheet.as_str())
+        .field("reference", range.to_a1())
+        .print(
+            global,
+            format!("Named {} '{}'", target.yellow(), name.cyan()),
+        )?;
+    Ok(())
+}
+
+fn list(file: &std::path::Path, global: &GlobalOptions) -> Result<()> {
+    let workbook = Workbook::open(file)?;
+    let anchors: Vec<(&DefinedName, Option<(String, Range)>)> = workbook
+        .defined_names()
+        .iter()
+        .filter(|d| !d.name.starts_with("_xlnm."))
+        .map(|d| (d, workbook.resolve_name(&d.name).ok()))
+        .collect();
+
+    if global.format == OutputFormat::Json {
+        let json: Vec<serde_json::Value> = anchors
+            .iter()
+            .map(|(d, target)| {
+                serde_json::json!({
+                    "name": d.name,
+                    "reference": d.reference,
+                    "sheet": target.as_ref().map(|(sheet, _)| sheet),
+                    "range": target.as_ref().map(|(_, range)| range.to_a1()),
+                })
+            })
+            .collect();
+        outln!("{}", serde_json::to_string_pretty(&json)?);
+    } else if anchors.is_empty() {
+        outln!("No names defined");
+    } else {
+        for (d, target) in &anchors {
+            match target {
+                Some((sheet, range)) => outln!(
+                    "@{} {} {}!{}",
+                    d.name.cyan(),
+                    "→".dimmed(),
+                    sheet,
+                    range.to_a1().yellow()
+                ),
+                None => outln!(
+                    "@{} {} {}",
+                    d.name.cyan(),
+                    "→".dimmed(),
+                    d.reference.dimmed()
+                ),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn remove(file: &std::path::Path, name: &str, global: &GlobalOptions) -> Result<()> {
+    let mut workbook = Workbook::open(file)?;
+    if workbook.get_defined_name(name).is_none() {
+        anyhow::bail!("No name '{}' in {}", name, file.display());
+    }
+    if global.dry_run {
+        outln!("Would remove name '{}'", name);
+        return Ok(());
+    }
+    workbook.remove_defined_name(name);
+    workbook.save()?;
+
+    CommandOutput::new("remove")
+        .file(file)
+        .target("name", name)
+        .print(global, format!("Removed name '{}'", name.cyan()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn default_global() -> GlobalOptions {
+        GlobalOptions {
+            quiet: true,
+            verbose: false,
+            format: OutputFormat::Text,
+            no_color: true,
+            color: false,
+            json_errors: false,
+            dry_run: false,
+            output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
+        }
+    }
+
+    #[test]
+    fn test_validate_name() {
+        for name in ["TotalCell", "_rate", "Q1.Total", "Ümsatz"] {
+            assert!(validate_name(name).is_ok(), "{}", name);
+        }
+        for name in ["F42", "1st", "Total Cell", "a-b", "", "R", "c"] {
+            assert!(validate_name(name).is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_run_name_add_list_remove() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("names.xlsx");
+        Workbook::with_sheets(&["Sheet1", "My Report"])
+            .save_as(&file_path)
+            .unwrap();
+        let name_args = |command| NameArgs { command };
+
+        run(
+            &name_args(NameCommand::Add {
+                file: file_path.clone(),
+                name: "TotalCell".to_string(),
+                reference: "'My Report'!F42".to_string(),
+            }),
+            &default_global(),
+        )
+        .unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(
+            wb.get_defined_name("TotalCell").unwrap().reference,
+            "'My Report'!$F$42"
+        );
+
+        for reference in ["F42", "Missing!A1"] {
+            let args = name_args(NameCommand::Add {
+                file: file_path.clone(),
+                name: "Other".to_string(),
+                reference: reference.to_string(),
+            });
+            assert!(run(&args, &default_global()).is_err());
+        }
+
+        run(
+            &name_args(NameCommand::List {
+                file: file_path.clone(),
+            }),
+            &default_global(),
+        )
+        .unwrap();
+        run(
+            &name_args(NameCommand::Remove {
+                file: file_path.clone(),
+                name: "TotalCell".to_string(),
+            }),
+            &default_global(),
+        )
+        .unwrap();
+        assert!(Workbook::open(&file_path)
+            .unwrap()
+            .defined_names()
+            .is_empty());
+    }
+}
@@ -0,0 +1,188 @@
+//! Structured command results.
+
+use std::time::Instant;
+
+use anyhow::Result;
+use serde_json::{Map, Value};
+
+use super::{GlobalOptions, OutputFormat};
+
+/// Result of a command that changes something.
+///
+/// Printed as a one-line message in text mode, and with `--format json` as
+///
+/// ```json
+/// {
+///   "action": "merge",
+///   "target": { "file": "report.xlsx", "sheet": "Sheet1", "range": "A1:D1" },
+///   "counts": { "cells": 4 },
+///   "durationMs": 3
+/// }
+/// ```
+///
+/// Keys added with [`CommandOutput::field`] sit next to `action`, which
+/// keeps the fields commands printed before this type existed.
+pub(super) struct CommandOutput {
+    action: &'static str,
+    target: Map<String, Value>,
+    counts: Map<String, Value>,
+    fields: Map<String, Value>,
+    started: Instant,
+}
+
+impl CommandOutput {
+    /// Starts timing a command.
+    pub(super) fn new(action: &'static str) -> Self {
+        Self {
+            action,
+            target: Map::new(),
+            counts: Map::new(),
+            fields: Map::new(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Adds the workbook file to the target.
+    pub(super) fn file(self, file: &std::path::Path) -> Self {
+        self.target("file", file.display().to_string())
+    }
+
+    /// Adds what the command acted on (`sheet`, `range`, `row`, ...).
+    pub(super) fn target(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.target.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Adds a count of things changed.
+    pub(super) fn count(mut self, key: &str, count: impl Into<Value>) -> Self {
+        self.counts.insert(key.to_string(), count.into());
+        self
+    }
+
+    /// Adds a top-level field.
+    pub(super) fn field(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.fields.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Builds the JSON form.
+    pub(super) fn to_json(&self) -> Value {
+        let mut json = Map::new();
+        json.insert("action".to_string(), self.action.into());
+        json.extend(self.fields.clone());
+        json.insert("target".to_string(), Value::Object(self.target.clone()));
+        if !self.counts.is_empty() {
+            json.insert("counts".to_string(), Value::Object(self.counts.clone()));
+        }
+        json.insert(
+            "durationMs".to_string(),
+            (self.started.elapsed().as_millis() as u64).into(),
+        );
+        Value::Object(json)
+    }
+
+    /// Prints the result as JSON or as `message`, unless `--quiet`.
+    pub(super) fn print(
+        &self,
+        global: &GlobalOptions,
+        message: impl std::fmt::Display,
+    ) -> Result<()> {
+        if global.quiet {
+            return Ok(());
+        }
+        if global.format == OutputFormat::Json {
+            outln!("{}", serde_json::to_string_pretty(&self.to_json())?);
+        } else {
+            outln!("{}", message);
+        }
+        Ok(())
+    }
+}
+
+/// Minimal styling scoped to the table's class.
+const HTML_TABLE_STYLE: &str = "<style>
+.xlex-table { border-collapse: collapse; font-family: sans-serif; font-size: 0.9em; }
+.xlex-table th, .xlex-table td { border: 1px solid #ccc; padding: 0.2em 0.5em; }
+.xlex-table th { background: #f3f3f3; font-weight: 600; }
+.xlex-table td { text-align: left; }
+.xlex-table td.num { text-align: right; }
+</style>";
+
+/// Builds a standalone HTML table for `--format html-table`: a scoped
+/// `<style>` block, `columns` as headings, and each row led by its number.
+/// Numbers are right-aligned.
+pub(super) fn html_table(columns: &[String], rows: &[(u32, Vec<String>)]) -> String {
+    let mut html = String::from(HTML_TABLE_STYLE);
+    html.push_str("\n<table class=\"xlex-table\">\n<thead>\n<tr><th></th>");
+    for column in columns {
+        html.push_str(&format!("<th>{}</th>", html_escape(column)));
+    }
+    html.push_str("</tr>\n</thead>\n<tbody>\n");
+    for (row, values) in rows {
+        html.push_str(&format!("<tr><th>{}</th>", row));
+        for value in values {
+            if !value.is_empty() && value.parse::<f64>().is_ok() {
+                html.push_str(&format!("<td class=\"num\">{}</td>", value));
+            } else {
+                html.push_str(&format!("<td>{}</td>", html_escape(value)));
+            }
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</tbody>\n</table>");
+    html
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_output_json() {
+        let output = CommandOutput::new("clear")
+            .file(std::path::Path::new("report.xlsx"))
+            .target("sheet", "Sheet1")
+            .target("range", "A1:B2")
+            .count("cells", 4)
+            .field("cell", "A1");
+        let json = output.to_json();
+        assert_eq!(json["action"], "clear");
+        assert_eq!(json["cell"], "A1");
+        assert_eq!(json["target"]["file"], "report.xlsx");
+        assert_eq!(json["target"]["range"], "A1:B2");
+        assert_eq!(json["counts"]["cells"], 4);
+        assert!(json["durationMs"].is_u64());
+
+        let json = CommandOutput::new("hide").to_json();
+        assert!(json.get("counts").is_none());
+        assert_eq!(json["target"], serde_json::json!({}));
+    }
+
+    #[test]
+    fn test_html_table() {
+        let html = html_table(
+            &["Name".to_string(), "B".to_string()],
+            &[
+                (
+                    2,
+                    vec!["<b>Tom & Jerry</b>".to_string(), "12.5".to_string()],
+                ),
+                (3, vec![String::new(), "n/a".to_string()]),
+            ],
+        );
+        assert!(html.starts_with("<style>"));
+        assert!(html.ends_with("</table>"));
+        assert!(html.contains("<tr><th></th><th>Name</th><th>B</th></tr>"));
+        assert!(html.contains(
+            "<tr><th>2</th><td>&lt;b&gt;Tom &amp; Jerry&lt;/b&gt;</td><td class=\"num\">12.5</td></tr>"
+        ));
+        assert!(html.contains("<tr><th>3</th><td></td><td>n/a</td></tr>"));
+    }
+}
@@ -0,0 +1,245 @@
+//! External subcommands.
+//!
+//! `xlex foo ...` runs an `xlex-foo` executable found on `PATH`, the way
+//! `git foo` runs `git-foo`, so teams can add commands without forking.
+//! Aliases and built-in commands come first. The plugin gets the arguments
+//! after its name, and the global options given before it as `XLEX_*`
+//! environment variables. Its exit status becomes xlex's, and with
+//! `--output` its standard output goes to the file like any command's.
+
+use std::ffi::{OsStr, OsString};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+use clap::{CommandFactory, ValueEnum};
+
+use super::{Cli, GlobalOptions};
+
+/// Prefix of plugin executables.
+const PREFIX: &str = "xlex-";
+
+/// A plugin exited unsuccessfully. The plugin has reported the error
+/// itself, so xlex only exits with the same code.
+#[derive(Debug)]
+pub(crate) struct PluginExit {
+    pub name: String,
+    pub code: u8,
+}
+
+impl std::fmt::Display for PluginExit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{} exited with status {}",
+            PREFIX, self.name, self.code
+        )
+    }
+}
+
+impl std::error::Error for PluginExit {}
+
+/// Finds the executable of plugin `name` in the directories of `path`.
+fn find(name: &str, path: Option<&OsStr>) -> Option<PathBuf> {
+    let file = format!("{}{}{}", PREFIX, name, std::env::consts::EXE_SUFFIX);
+    std::env::split_paths(path?)
+        .map(|dir| dir.join(&file))
+        .find(|candidate| is_executable(candidate))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// The environment a plugin runs with: the global options, and the
+/// version and path of xlex for calling it back.
+fn plugin_env(global: &GlobalOptions) -> Vec<(&'static str, String)> {
+    let flag = |on: bool| if on { "1" } else { "0" }.to_string();
+    let format = global
+        .format
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default();
+    let color = colored::control::SHOULD_COLORIZE.should_colorize();
+    let mut env = vec![
+        ("XLEX_FORMAT", format),
+        ("XLEX_QUIET", flag(global.quiet)),
+        ("XLEX_VERBOSE", flag(global.verbose)),
+        ("XLEX_DRY_RUN", flag(global.dry_run)),
+        ("XLEX_JSON_ERRORS", flag(global.json_errors)),
+        ("XLEX_COLOR", flag(color)),
+        ("XLEX_VERSION", env!("CARGO_PKG_VERSION").to_string()),
+    ];
+    if !color {
+        env.push(("NO_COLOR", "1".to_string()));
+    }
+    if let Ok(exe) = std::env::current_exe() {
+        env.push(("XLEX_BIN", exe.display().to_string()));
+    }
+    env
+}
+
+/// Runs the plugin named by `args[0]` with the rest of `args`.
+pub(super) fn run(args: &[OsString], global: &GlobalOptions) -> Result<()> {
+    let Some((name, rest)) = args.split_first() else {
+        return Ok(());
+    };
+    let name = name.to_string_lossy();
+    let Some(program) = find(&name, std::env::var_os("PATH").as_deref()) else {
+        let mut message = format!(
+            "unrecognized subcommand '{}' (no {}{} on PATH)",
+            name, PREFIX, name
+        );
+        if let Some(similar) = similar_command(&name) {
+            message.push_str(&format!(
+                "\n\n  tip: a similar subcommand exists: '{}'",
+                similar
+            ));
+        }
+        return Err(Cli::command()
+            .error(clap::error::ErrorKind::InvalidSubcommand, message)
+            .into());
+    };
+    spawn(&program, &name, rest, global)
+}
+
+/// The built-in command closest to a mistyped `name`, as clap would suggest.
+fn similar_command(name: &str) -> Option<String> {
+    Cli::command()
+        .get_subcommands()
+        .map(|c| (strsim::jaro(name, c.get_name()), c.get_name().to_string()))
+        .filter(|(score, _)| *score > 0.8)
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, command)| command)
+}
+
+/// Runs a plugin executable and waits for it.
+fn spawn(program: &Path, name: &str, args: &[OsString], global: &GlobalOptions) -> Result<()> {
+    let mut command = Command::new(program);
+    command.args(args).envs(plugin_env(global));
+    // With --output, copy the plugin's output there
+    if global.output.is_some() {
+        command.stdout(Stdio::piped());
+    }
+    let mut child = command
+        .spawn()
+        .with_context(|| format!("Cannot run {}", program.display()))?;
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).split(b'\n') {
+            let line =
+                line.with_context(|| format!("Cannot read output of {}", program.display()))?;
+            outln!("{}", String::from_utf8_lossy(&line));
+        }
+    }
+    let status = child.wait()?;
+    if status.success() {
+        return Ok(());
+    }
+    Err(PluginExit {
+        name: name.to_string(),
+        // Killed by a signal: a general error
+        code: status
+            .code()
+            .and_then(|code| u8::try_from(code).ok())
+            .unwrap_or(1),
+    }
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[cfg(unix)]
+    fn write_plugin(dir: &Path, name: &str, script: &str) {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join(format!("{}{}", PREFIX, name));
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_find_plugin() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let other = tempfile::TempDir::new().unwrap();
+        write_plugin(dir.path(), "hello", "true");
+        // Not executable
+        std::fs::write(other.path().join("xlex-data"), "").unwrap();
+
+        let path = std::env::join_paths([other.path(), dir.path()]).unwrap();
+        assert_eq!(
+            find("hello", Some(&path)),
+            Some(dir.path().join("xlex-hello"))
+        );
+        assert_eq!(find("data", Some(&path)), None);
+        assert_eq!(find("hello", None), None);
+    }
+
+    #[test]
+    fn test_plugin_env() {
+        let cli =
+            Cli::try_parse_from(["xlex", "-f", "json", "--dry-run", "info", "x.xlsx"]).unwrap();
+        let env = plugin_env(&cli.global);
+        let get = |key: &str| env.iter().find(|(k, _)| *k == key).map(|(_, v)| v.as_str());
+        assert_eq!(get("XLEX_FORMAT"), Some("json"));
+        assert_eq!(get("XLEX_DRY_RUN"), Some("1"));
+        assert_eq!(get("XLEX_QUIET"), Some("0"));
+        assert_eq!(get("XLEX_VERSION"), Some(env!("CARGO_PKG_VERSION")));
+    }
+
+    #[test]
+    fn test_external_subcommand_parses() {
+        let cli = Cli::try_parse_from(["xlex", "-q", "no-such-plugin-xyz", "a", "--flag"]).unwrap();
+        let super::super::Commands::External(args) = &cli.command else {
+            panic!("not an external subcommand");
+        };
+        assert_eq!(args, &["no-such-plugin-xyz", "a", "--flag"]);
+        assert!(cli.global.quiet);
+        let err = run(args, &cli.global).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("no xlex-no-such-plugin-xyz on PATH"));
+        assert_eq!(similar_command("infoo").as_deref(), Some("info"));
+        assert_eq!(similar_command("no-such-plugin-xyz"), None);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_spawn_passes_arguments_and_status() {
+        let dir = tempfile::TempDir::new().unwrap();
+        write_plugin(
+            dir.path(),
+            "save",
+            "echo \"$XLEX_FORMAT $XLEX_QUIET $2\" > \"$1\"; exit \"$3\"",
+        );
+        let program = dir.path().join("xlex-save");
+        let saved = dir.path().join("saved.txt");
+        let global = Cli::try_parse_from(["xlex", "-f", "csv", "-q", "version"])
+            .unwrap()
+            .global;
+        let args = |status: &str| -> Vec<OsString> {
+            vec![saved.clone().into(), "b c".into(), status.into()]
+        };
+
+        spawn(&program, "save", &args("0"), &global).unwrap();
+        assert_eq!(std::fs::read_to_string(&saved).unwrap(), "csv 1 b c\n");
+
+        let err = spawn(&program, "save", &args("3"), &global).unwrap_err();
+        let exit = err.downcast_ref::<PluginExit>().unwrap();
+        assert_eq!(exit.code, 3);
+        assert_eq!(exit.to_string(), "xlex-save exited with status 3");
+    }
+}
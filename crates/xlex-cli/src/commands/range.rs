@@ -4,9 +4,13 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use colored::Colorize;
 
-use xlex_core::{DefinedName, Range, Workbook};
+use xlex_core::rounding::RoundingMode;
+use xlex_core::style::NumberFormat;
+use xlex_core::text::{CleanOptions, TextCase};
+use xlex_core::{CellRef, CellValue, DefinedName, Range, Workbook};
 
-use super::{GlobalOptions, OutputFormat};
+use super::output::{html_table, CommandOutput};
+use super::{FailOn, GlobalOptions, OutputFormat};
 
 /// Arguments for range operations.
 #[derive(Parser)]
@@ -16,6 +20,7 @@ pub struct RangeArgs {
 }
 
 #[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)]
 pub enum RangeCommand {
     /// Get range data
     Get {
@@ -48,6 +53,18 @@ pub enum RangeCommand {
         /// Destination (top-left cell)
         dest: String,
     },
+    /// Transpose a range (swap rows and columns)
+    Transpose {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name
+        sheet: String,
+        /// Source range
+        range: String,
+        /// Destination top-left cell (defaults to the range's top-left, in place)
+        #[arg(long)]
+        to: Option<String>,
+    },
     /// Clear a range
     Clear {
         /// Path to the xlsx file
@@ -60,7 +77,7 @@ pub enum RangeCommand {
         #[arg(long)]
         values_only: bool,
     },
-    /// Fill a range with a pattern
+    /// Fill a range with a value, a series, or a formula copied down
     Fill {
         /// Path to the xlsx file
         file: std::path::PathBuf,
@@ -68,8 +85,74 @@ pub enum RangeCommand {
         sheet: String,
         /// Range
         range: String,
-        /// Value or pattern
-        value: String,
+        /// Value or pattern (with --fill-down-formula: formula for the first row)
+        #[arg(required_unless_present_any = ["series", "fill_down_formula"])]
+        value: Option<String>,
+        /// Fill a series instead of a constant (e.g., "1..100 step 2",
+        /// "2024-01-01..2024-12-01 step 1m")
+        #[arg(long, num_args = 1..=3, conflicts_with_all = ["value", "fill_down_formula"])]
+        series: Option<Vec<String>>,
+        /// Copy the formula in the first row of the range down each column,
+        /// adjusting relative references
+        #[arg(long)]
+        fill_down_formula: bool,
+    },
+    /// Round the numbers in a range to a number of decimal places
+    Round {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name
+        sheet: String,
+        /// Range
+        range: String,
+        /// Decimal places (negative rounds to tens, hundreds, ...)
+        #[arg(long, allow_hyphen_values = true)]
+        decimals: i32,
+        /// How to round
+        #[arg(long, value_enum, default_value = "half-up")]
+        mode: RoundMode,
+        /// Also give the rounded cells a number format with that many decimals
+        #[arg(long)]
+        set_format: bool,
+    },
+    /// Turn text that looks like dates into real dates
+    NormalizeDates {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name
+        sheet: String,
+        /// Range (e.g., "C:C" or "C2:C100")
+        range: String,
+        /// Comma-separated strftime formats to try in order
+        #[arg(long, value_delimiter = ',', default_value = "%Y-%m-%d,%Y/%m/%d")]
+        from_formats: Vec<String>,
+        /// What to write: Excel dates, or ISO 8601 text
+        #[arg(long, value_enum, default_value = "excel-date")]
+        to: DateTarget,
+        /// Number format for written Excel dates
+        #[arg(long, default_value = "yyyy-mm-dd")]
+        display_format: String,
+    },
+    /// Clean up the text in a range in one pass
+    Clean {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name
+        sheet: String,
+        /// Range (e.g., "A:D")
+        range: String,
+        /// Remove whitespace at both ends
+        #[arg(long)]
+        trim: bool,
+        /// Replace runs of spaces, tabs and non-breaking spaces with one space
+        #[arg(long)]
+        collapse_spaces: bool,
+        /// Remove control characters and zero-width characters
+        #[arg(long)]
+        strip_nonprintable: bool,
+        /// Change the case
+        #[arg(long, value_enum)]
+        case: Option<CaseArg>,
     },
     /// Merge cells in a range
     Merge {
@@ -89,6 +172,27 @@ pub enum RangeCommand {
         /// Range
         range: String,
     },
+    /// Lock cells, so they cannot be edited once the sheet is protected
+    Lock {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name
+        sheet: String,
+        /// Range (whole columns like B:D or rows like 2:5 are styled as such)
+        range: String,
+        /// Also hide the cells' formulas from the formula bar
+        #[arg(long)]
+        hide_formulas: bool,
+    },
+    /// Unlock cells, leaving them editable on a protected sheet
+    Unlock {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name
+        sheet: String,
+        /// Range (whole columns like B:D or rows like 2:5 are styled as such)
+        range: String,
+    },
     /// Apply styling to a range
     Style {
         /// Path to the xlsx file
@@ -118,6 +222,21 @@ pub enum RangeCommand {
         /// Background color (hex, e.g., FFFF00)
         #[arg(long)]
         bg_color: Option<String>,
+        /// Fill pattern (e.g., darkGrid, lightUp, gray125)
+        #[arg(long)]
+        pattern: Option<String>,
+        /// Pattern color for --pattern (hex, default 000000)
+        #[arg(long, requires = "pattern")]
+        pattern_color: Option<String>,
+        /// Gradient fill colors (comma-separated hex, e.g., FFFFFF,4472C4)
+        #[arg(long, value_delimiter = ',', conflicts_with_all = ["pattern", "bg_color"])]
+        gradient: Option<Vec<String>>,
+        /// Gradient angle in degrees
+        #[arg(long, requires = "gradient", default_value_t = 0.0)]
+        gradient_angle: f64,
+        /// Radiate the gradient from the cell center instead of along an angle
+        #[arg(long, requires = "gradient")]
+        gradient_path: bool,
         /// Horizontal alignment (left, center, right)
         #[arg(long)]
         align: Option<String>,
@@ -133,9 +252,18 @@ pub enum RangeCommand {
         /// Format as percentage
         #[arg(long)]
         percent: bool,
-        /// Format as currency
+        /// Format as currency (ISO code, e.g., EUR)
         #[arg(long)]
         currency: Option<String>,
+        /// Locale for currency and thousands formats (e.g., de-DE)
+        #[arg(long)]
+        locale: Option<String>,
+        /// Use accounting layout for --currency
+        #[arg(long, requires = "currency")]
+        accounting: bool,
+        /// Format with thousands separators
+        #[arg(long, conflicts_with = "currency")]
+        thousands: bool,
         /// Date format (e.g., YYYY-MM-DD)
         #[arg(long)]
         date_format: Option<String>,
@@ -169,6 +297,15 @@ pub enum RangeCommand {
         /// Remove all borders
         #[arg(long)]
         none: bool,
+        /// Named border layout
+        #[arg(long, value_enum, conflicts_with_all = ["all", "outline", "none"])]
+        preset: Option<BorderPreset>,
+        /// Diagonal line from bottom-left to top-right
+        #[arg(long)]
+        diagonal_up: bool,
+        /// Diagonal line from top-left to bottom-right
+        #[arg(long)]
+        diagonal_down: bool,
         /// Border style (thin, medium, thick, dashed, dotted, double)
         #[arg(long, default_value = "thin")]
         style: String,
@@ -203,6 +340,9 @@ pub enum RangeCommand {
         range: String,
         /// Validation rule (nonempty, numeric, unique)
         rule: String,
+        /// Findings that fail the command (exit code 12)
+        #[arg(long, value_enum, default_value = "errors")]
+        fail_on: FailOn,
     },
     /// Sort range
     Sort {
@@ -250,6 +390,12 @@ pub fn run(args: &RangeArgs, global: &GlobalOptions) -> Result<()> {
             source,
             dest,
         } => move_range(file, sheet, source, dest, global),
+        RangeCommand::Transpose {
+            file,
+            sheet,
+            range,
+            to,
+        } => transpose(file, sheet, range, to.as_deref(), global),
         RangeCommand::Clear {
             file,
             sheet,
@@ -261,9 +407,83 @@ pub fn run(args: &RangeArgs, global: &GlobalOptions) -> Result<()> {
             sheet,
             range,
             value,
-        } => fill(file, sheet, range, value, global),
+            series,
+            fill_down_formula,
+        } => {
+            if let Some(series) = series {
+                fill_series(file, sheet, range, &series.join(" "), global)
+            } else if *fill_down_formula {
+                fill_down(file, sheet, range, value.as_deref(), global)
+            } else {
+                fill(
+                    file,
+                    sheet,
+                    range,
+                    value.as_deref().unwrap_or_default(),
+                    global,
+                )
+            }
+        }
+        RangeCommand::Round {
+            file,
+            sheet,
+            range,
+            decimals,
+            mode,
+            set_format,
+        } => round(
+            file,
+            sheet,
+            range,
+            *decimals,
+            (*mode).into(),
+            *set_format,
+            global,
+        ),
+        RangeCommand::NormalizeDates {
+            file,
+            sheet,
+            range,
+            from_formats,
+            to,
+            display_format,
+        } => normalize_dates(
+            file,
+            sheet,
+            range,
+            from_formats,
+            *to,
+            display_format,
+            global,
+        ),
+        RangeCommand::Clean {
+            file,
+            sheet,
+            range,
+            trim,
+            collapse_spaces,
+            strip_nonprintable,
+            case,
+        } => {
+            let options = CleanOptions {
+                trim: *trim,
+                collapse_spaces: *collapse_spaces,
+                strip_nonprintable: *strip_nonprintable,
+                case: case.map(Into::into),
+            };
+            clean(file, sheet, range, &options, global)
+        }
         RangeCommand::Merge { file, sheet, range } => merge(file, sheet, range, global),
         RangeCommand::Unmerge { file, sheet, range } => unmerge(file, sheet, range, global),
+        RangeCommand::Lock {
+            file,
+            sheet,
+            range,
+            hide_formulas,
+        } => lock(file, sheet, range, true, *hide_formulas, global),
+        RangeCommand::Unlock { file, sheet, range } => {
+            lock(file, sheet, range, false, false, global)
+        }
         RangeCommand::Style {
             file,
             sheet,
@@ -275,12 +495,20 @@ pub fn run(args: &RangeArgs, global: &GlobalOptions) -> Result<()> {
             font_size,
             text_color,
             bg_color,
+            pattern,
+            pattern_color,
+            gradient,
+            gradient_angle,
+            gradient_path,
             align,
             valign,
             wrap,
             number_format,
             percent,
             currency,
+            locale,
+            accounting,
+            thousands,
             date_format,
         } => range_style(
             file,
@@ -294,12 +522,20 @@ pub fn run(args: &RangeArgs, global: &GlobalOptions) -> Result<()> {
                 font_size: *font_size,
                 color: text_color.clone(),
                 bg_color: bg_color.clone(),
+                pattern: pattern.clone(),
+                pattern_color: pattern_color.clone(),
+                gradient: gradient.clone(),
+                gradient_angle: *gradient_angle,
+                gradient_path: *gradient_path,
                 align: align.clone(),
                 valign: valign.clone(),
                 wrap: *wrap,
                 number_format: number_format.clone(),
                 percent: *percent,
                 currency: currency.clone(),
+                locale: locale.clone(),
+                accounting: *accounting,
+                thousands: *thousands,
                 date_format: date_format.clone(),
             },
             global,
@@ -315,6 +551,9 @@ pub fn run(args: &RangeArgs, global: &GlobalOptions) -> Result<()> {
             left,
             right,
             none,
+            preset,
+            diagonal_up,
+            diagonal_down,
             style,
             border_color,
         } => range_border(
@@ -329,6 +568,9 @@ pub fn run(args: &RangeArgs, global: &GlobalOptions) -> Result<()> {
                 left: *left,
                 right: *right,
                 none: *none,
+                preset: *preset,
+                diagonal_up: *diagonal_up,
+                diagonal_down: *diagonal_down,
                 style: style.clone(),
                 border_color: border_color.clone(),
             },
@@ -346,7 +588,8 @@ pub fn run(args: &RangeArgs, global: &GlobalOptions) -> Result<()> {
             sheet,
             range,
             rule,
-        } => validate(file, sheet, range, rule, global),
+            fail_on,
+        } => validate(file, sheet, range, rule, *fail_on, global),
         RangeCommand::Sort {
             file,
             sheet,
@@ -373,15 +616,32 @@ struct RangeStyleOpts {
     font_size: Option<f64>,
     color: Option<String>,
     bg_color: Option<String>,
+    pattern: Option<String>,
+    pattern_color: Option<String>,
+    gradient: Option<Vec<String>>,
+    gradient_angle: f64,
+    gradient_path: bool,
     align: Option<String>,
     valign: Option<String>,
     wrap: bool,
     number_format: Option<String>,
     percent: bool,
     currency: Option<String>,
+    locale: Option<String>,
+    accounting: bool,
+    thousands: bool,
     date_format: Option<String>,
 }
 
+/// Named border layouts for `range border --preset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BorderPreset {
+    /// Every edge of every cell
+    Grid,
+    /// A line under the first row
+    UnderlineHeader,
+}
+
 #[derive(Default)]
 struct RangeBorderOpts {
     all: bool,
@@ -391,6 +651,9 @@ struct RangeBorderOpts {
     left: bool,
     right: bool,
     none: bool,
+    preset: Option<BorderPreset>,
+    diagonal_up: bool,
+    diagonal_down: bool,
     style: String,
     border_color: Option<String>,
 }
@@ -403,15 +666,32 @@ fn range_style(
     global: &GlobalOptions,
 ) -> Result<()> {
     use xlex_core::{
-        style::{Color, FillPattern, HorizontalAlignment, NumberFormat, Style, VerticalAlignment},
+        style::{
+            Color, FillPattern, GradientFill, HorizontalAlignment, Locale, NumberFormat, Style,
+            VerticalAlignment,
+        },
         CellRef,
     };
 
+    let locale = match opts.locale {
+        Some(ref tag) => Locale::from_tag(tag).ok_or_else(|| {
+            let supported: Vec<_> = Locale::ALL.iter().map(|l| l.tag()).collect();
+            anyhow::anyhow!(
+                "Unsupported locale '{}' (supported: {})",
+                tag,
+                supported.join(", ")
+            )
+        })?,
+        None => Locale::default(),
+    };
+
     if global.dry_run {
-        println!("Would apply styles to range {}", range);
+        outln!("Would apply styles to range {}", range);
         return Ok(());
     }
 
+    let output = CommandOutput::new("style");
+
     let mut workbook = Workbook::open(file)?;
     let range_ref = Range::parse(range)?;
 
@@ -430,12 +710,44 @@ fn range_style(
     }
 
     // Fill settings
-    if let Some(ref bg_color_str) = opts.bg_color {
+    let parse_color =
+        |hex: &str| Color::from_hex(hex).ok_or_else(|| anyhow::anyhow!("Invalid color '{}'", hex));
+    if let Some(ref pattern) = opts.pattern {
+        style.fill.pattern = FillPattern::parse(pattern).ok_or_else(|| {
+            let names: Vec<_> = FillPattern::ALL.iter().map(|p| p.as_str()).collect();
+            anyhow::anyhow!(
+                "Unknown fill pattern '{}' (expected one of: {})",
+                pattern,
+                names.join(", ")
+            )
+        })?;
+        if style.fill.pattern == FillPattern::Solid {
+            style.fill.fg_color = opts.bg_color.as_deref().map(parse_color).transpose()?;
+        } else if style.fill.pattern != FillPattern::None {
+            let pattern_color = opts.pattern_color.as_deref().unwrap_or("000000");
+            style.fill.fg_color = Some(parse_color(pattern_color)?);
+            style.fill.bg_color = opts.bg_color.as_deref().map(parse_color).transpose()?;
+        }
+    } else if let Some(ref bg_color_str) = opts.bg_color {
         if let Some(color) = Color::from_hex(bg_color_str) {
             style.fill.pattern = FillPattern::Solid;
             style.fill.fg_color = Some(color);
         }
     }
+    if let Some(ref stops) = opts.gradient {
+        if stops.len() < 2 {
+            anyhow::bail!("--gradient needs at least two colors");
+        }
+        let colors = stops
+            .iter()
+            .map(|hex| parse_color(hex.trim()))
+            .collect::<Result<Vec<_>>>()?;
+        style.fill.gradient = Some(if opts.gradient_path {
+            GradientFill::path(colors)
+        } else {
+            GradientFill::linear(opts.gradient_angle, colors)
+        });
+    }
 
     // Alignment
     if let Some(ref align) = opts.align {
@@ -462,16 +774,22 @@ fn range_style(
     // Number format
     if opts.percent {
         style.number_format = NumberFormat::percentage(2);
-    } else if let Some(ref _currency) = opts.currency {
-        style.number_format = NumberFormat::custom("$#,##0.00");
+    } else if let Some(ref currency) = opts.currency {
+        style.number_format = if opts.accounting {
+            NumberFormat::accounting(currency, locale)
+        } else {
+            NumberFormat::currency(currency, locale)
+        };
+    } else if opts.thousands {
+        style.number_format = NumberFormat::thousands(2, locale);
     } else if let Some(ref _date_fmt) = opts.date_format {
         style.number_format = NumberFormat::date();
     } else if let Some(ref fmt) = opts.number_format {
         style.number_format = NumberFormat::custom(fmt.clone());
     }
 
-    // Register the style and get its ID
-    let style_id = workbook.style_registry_mut().add(style);
+    // Register the style (reusing an identical one) and get its ID
+    let style_id = workbook.style_registry_mut().clone_for(&style);
 
     {
         let sheet_obj =
@@ -492,9 +810,15 @@ fn range_style(
 
     workbook.save()?;
 
-    if !global.quiet {
-        println!("{} Applied styles to range {}", "✓".green(), range.cyan());
-    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("range", range)
+        .count("cells", range_ref.cell_count())
+        .print(
+            global,
+            format!("{} Applied styles to range {}", "✓".green(), range.cyan()),
+        )?;
     Ok(())
 }
 
@@ -511,10 +835,12 @@ fn range_border(
     };
 
     if global.dry_run {
-        println!("Would apply borders to range {}", range);
+        outln!("Would apply borders to range {}", range);
         return Ok(());
     }
 
+    let output = CommandOutput::new("border");
+
     let mut workbook = Workbook::open(file)?;
     let range_ref = Range::parse(range)?;
 
@@ -544,7 +870,8 @@ fn range_border(
             name: sheet.to_string(),
         })?;
 
-    // For each cell, create a style with the appropriate border and register it
+    // Border edges are layered over each cell's existing style, and cells that
+    // end up identical share one registry entry
     for row in range_ref.start.row..=range_ref.end.row {
         for col in range_ref.start.col..=range_ref.end.col {
             let cell_ref = CellRef::new(col, row);
@@ -558,8 +885,12 @@ fn range_border(
 
             if opts.none {
                 // Remove all borders - use default (no borders)
-            } else if opts.all {
+            } else if opts.all || opts.preset == Some(BorderPreset::Grid) {
                 cell_border = Border::all(border_style, border_color.clone());
+            } else if opts.preset == Some(BorderPreset::UnderlineHeader) {
+                if is_top_edge {
+                    cell_border.bottom = border_side.clone();
+                }
             } else if opts.outline {
                 if is_top_edge {
                     cell_border.top = border_side.clone();
@@ -589,12 +920,26 @@ fn range_border(
                 }
             }
 
-            // Create style with border
-            let mut style = Style::default();
-            style.border = cell_border;
+            if !opts.none && (opts.diagonal_up || opts.diagonal_down) {
+                cell_border.diagonal = border_side.clone();
+                cell_border.diagonal_up = opts.diagonal_up;
+                cell_border.diagonal_down = opts.diagonal_down;
+            }
 
-            // Register style and apply to cell
-            let style_id = workbook.style_registry_mut().add(style);
+            let current = workbook
+                .get_sheet(sheet)
+                .and_then(|s| s.get_cell(&cell_ref))
+                .and_then(|c| c.style_id);
+            let registry = workbook.style_registry_mut();
+            let mut style: Style = current
+                .and_then(|id| registry.get(id).cloned())
+                .unwrap_or_default();
+            if opts.none {
+                style.border = Border::default();
+            } else {
+                merge_border(&mut style.border, cell_border);
+            }
+            let style_id = registry.clone_for(&style);
 
             let sheet_obj = workbook.get_sheet_mut(sheet).ok_or_else(|| {
                 xlex_core::XlexError::SheetNotFound {
@@ -607,18 +952,47 @@ fn range_border(
 
     workbook.save()?;
 
-    if !global.quiet {
-        let action = if opts.none { "Removed" } else { "Applied" };
-        println!(
-            "{} {} borders on range {}",
-            "✓".green(),
-            action,
-            range.cyan()
-        );
-    }
+    let action = if opts.none { "Removed" } else { "Applied" };
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("range", range)
+        .count("cells", range_ref.cell_count())
+        .field("removed", opts.none)
+        .print(
+            global,
+            format!(
+                "{} {} borders on range {}",
+                "✓".green(),
+                action,
+                range.cyan()
+            ),
+        )?;
     Ok(())
 }
 
+/// Overlays the sides that are set in `edges` onto an existing border.
+fn merge_border(border: &mut xlex_core::style::Border, edges: xlex_core::style::Border) {
+    use xlex_core::style::BorderStyle;
+
+    let sides = [
+        (&mut border.left, edges.left),
+        (&mut border.right, edges.right),
+        (&mut border.top, edges.top),
+        (&mut border.bottom, edges.bottom),
+    ];
+    for (side, edge) in sides {
+        if edge.style != BorderStyle::None {
+            *side = edge;
+        }
+    }
+    if edges.diagonal_up || edges.diagonal_down {
+        border.diagonal = edges.diagonal;
+        border.diagonal_up = edges.diagonal_up;
+        border.diagonal_down = edges.diagonal_down;
+    }
+}
+
 fn get(file: &std::path::Path, sheet: &str, range: &str, global: &GlobalOptions) -> Result<()> {
     let workbook = Workbook::open(file)?;
     let range_ref = Range::parse(range)?;
@@ -629,22 +1003,14 @@ fn get(file: &std::path::Path, sheet: &str, range: &str, global: &GlobalOptions)
                 name: sheet.to_string(),
             })?;
 
-    let mut rows: Vec<Vec<serde_json::Value>> = Vec::new();
-
-    for row in range_ref.start.row..=range_ref.end.row {
-        let mut row_values: Vec<serde_json::Value> = Vec::new();
-        for col in range_ref.start.col..=range_ref.end.col {
-            let cell_ref = xlex_core::CellRef::new(col, row);
-            let value = sheet_obj.get_value(&cell_ref);
-            row_values.push(match value {
-                xlex_core::CellValue::Empty => serde_json::Value::Null,
-                xlex_core::CellValue::String(s) => serde_json::Value::String(s),
-                xlex_core::CellValue::Number(n) => serde_json::json!(n),
-                xlex_core::CellValue::Boolean(b) => serde_json::Value::Bool(b),
-                _ => serde_json::Value::String(value.to_display_string()),
-            });
-        }
-        rows.push(row_values);
+    let mut rows: Vec<Vec<serde_json::Value>> =
+        vec![
+            vec![serde_json::Value::Null; range_ref.width() as usize];
+            range_ref.height() as usize
+        ];
+    for (cell_ref, cell) in sheet_obj.iter_range(&range_ref) {
+        rows[(cell_ref.row - range_ref.start.row) as usize]
+            [(cell_ref.col - range_ref.start.col) as usize] = (&cell.value).into();
     }
 
     if global.format == OutputFormat::Json {
@@ -652,7 +1018,32 @@ fn get(file: &std::path::Path, sheet: &str, range: &str, global: &GlobalOptions)
             "range": range,
             "data": rows,
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
+    } else if global.format == OutputFormat::HtmlTable {
+        let columns: Vec<String> = (range_ref.start.col..=range_ref.end.col)
+            .map(CellRef::col_to_letters)
+            .collect();
+        let rows: Vec<(u32, Vec<String>)> = rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let values = row
+                    .iter()
+                    .map(|v| match v {
+                        serde_json::Value::String(s) => s.clone(),
+                        // 42 rather than 42.0
+                        serde_json::Value::Number(n) => n.as_f64().map_or_else(
+                            || n.to_string(),
+                            |n| CellValue::Number(n).to_display_string(),
+                        ),
+                        serde_json::Value::Null => String::new(),
+                        _ => v.to_string(),
+                    })
+                    .collect();
+                (range_ref.start.row + i as u32, values)
+            })
+            .collect();
+        outln!("{}", html_table(&columns, &rows));
     } else if global.format == OutputFormat::Csv {
         for row in rows {
             let values: Vec<String> = row
@@ -665,7 +1056,7 @@ fn get(file: &std::path::Path, sheet: &str, range: &str, global: &GlobalOptions)
                     _ => v.to_string(),
                 })
                 .collect();
-            println!("{}", values.join(","));
+            outln!("{}", values.join(","));
         }
     } else {
         for (i, row) in rows.iter().enumerate() {
@@ -680,7 +1071,7 @@ fn get(file: &std::path::Path, sheet: &str, range: &str, global: &GlobalOptions)
                     _ => v.to_string(),
                 })
                 .collect();
-            println!(
+            outln!(
                 "{}: {}",
                 format!("Row {}", row_num).cyan(),
                 values.join(" | ")
@@ -699,10 +1090,12 @@ fn copy(
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!("Would copy {} to {} in {}", source, dest, sheet);
+        outln!("Would copy {} to {} in {}", source, dest, sheet);
         return Ok(());
     }
 
+    let output = CommandOutput::new("copy");
+
     let mut workbook = Workbook::open(file)?;
     let source_range = Range::parse(source)?;
     let dest_cell = xlex_core::CellRef::parse(dest)?;
@@ -738,6 +1131,7 @@ fn copy(
                 name: sheet.to_string(),
             })?;
 
+    let copied = values.len();
     for (col_offset, row_offset, value) in values {
         let dest_ref =
             xlex_core::CellRef::new(dest_cell.col + col_offset, dest_cell.row + row_offset);
@@ -747,9 +1141,16 @@ fn copy(
     let _ = sheet_obj;
     workbook.save()?;
 
-    if !global.quiet {
-        println!("Copied {} to {}", source.cyan(), dest.green());
-    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("source", source)
+        .target("destination", dest)
+        .count("cells", copied)
+        .print(
+            global,
+            format!("Copied {} to {}", source.cyan(), dest.green()),
+        )?;
 
     Ok(())
 }
@@ -762,10 +1163,12 @@ fn move_range(
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!("Would move {} to {} in {}", source, dest, sheet);
+        outln!("Would move {} to {} in {}", source, dest, sheet);
         return Ok(());
     }
 
+    let output = CommandOutput::new("move");
+
     let mut workbook = Workbook::open(file)?;
     let source_range = Range::parse(source)?;
     let dest_cell = xlex_core::CellRef::parse(dest)?;
@@ -821,10 +1224,69 @@ fn move_range(
     let _ = sheet_obj;
     workbook.save()?;
 
-    if !global.quiet {
-        println!("Moved {} to {}", source.cyan(), dest.green());
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("source", source)
+        .target("destination", dest)
+        .print(
+            global,
+            format!("Moved {} to {}", source.cyan(), dest.green()),
+        )?;
+
+    Ok(())
+}
+
+fn transpose(
+    file: &std::path::Path,
+    sheet: &str,
+    range: &str,
+    to: Option<&str>,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let source_range = Range::parse(range)?;
+    let dest_cell = match to {
+        Some(to) => xlex_core::CellRef::parse(to)?,
+        None => source_range.start.clone(),
+    };
+
+    if global.dry_run {
+        outln!(
+            "Would transpose {} to {} in {}",
+            range,
+            dest_cell.to_a1(),
+            sheet
+        );
+        return Ok(());
     }
 
+    let output = CommandOutput::new("transpose");
+
+    let mut workbook = Workbook::open(file)?;
+    let sheet_obj =
+        workbook
+            .get_sheet_mut(sheet)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: sheet.to_string(),
+            })?;
+
+    let target = sheet_obj.transpose_range(&source_range, &dest_cell)?;
+    let _ = sheet_obj;
+    workbook.save()?;
+
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("source", source_range.to_a1())
+        .target("destination", target.to_a1())
+        .count("cells", source_range.cell_count())
+        .field("sheet", sheet)
+        .field("source", source_range.to_a1())
+        .print(
+            global,
+            format!("Transposed {} to {}", range.cyan(), target.to_a1().green()),
+        )?;
+
     Ok(())
 }
 
@@ -836,10 +1298,12 @@ fn clear(
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!("Would clear range {} in {}", range, sheet);
+        outln!("Would clear range {} in {}", range, sheet);
         return Ok(());
     }
 
+    let output = CommandOutput::new("clear");
+
     let mut workbook = Workbook::open(file)?;
     let range_ref = Range::parse(range)?;
 
@@ -849,9 +1313,12 @@ fn clear(
 
     workbook.save()?;
 
-    if !global.quiet {
-        println!("Cleared range {}", range.cyan());
-    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("range", range)
+        .count("cells", range_ref.cell_count())
+        .print(global, format!("Cleared range {}", range.cyan()))?;
 
     Ok(())
 }
@@ -864,65 +1331,317 @@ fn fill(
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!("Would fill range {} with '{}' in {}", range, value, sheet);
+        outln!("Would fill range {} with '{}' in {}", range, value, sheet);
         return Ok(());
     }
 
+    let output = CommandOutput::new("fill");
+
     let mut workbook = Workbook::open(file)?;
     let range_ref = Range::parse(range)?;
     let cell_value = super::cell::parse_auto_value(value);
 
-    for cell_ref in range_ref.cells() {
-        workbook.set_cell(sheet, cell_ref, cell_value.clone())?;
-    }
+    workbook.with_sheet_mut(sheet, |sheet_obj| {
+        sheet_obj.apply_updates(
+            range_ref
+                .cells()
+                .map(|cell_ref| (cell_ref, cell_value.clone())),
+        )
+    })?;
 
     workbook.save()?;
 
-    if !global.quiet {
-        let count = range_ref.cell_count();
-        println!(
-            "Filled {} cells in {}",
-            count.to_string().green(),
-            range.cyan()
-        );
-    }
+    let count = range_ref.cell_count();
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("range", range)
+        .count("cells", count)
+        .field("value", value)
+        .print(
+            global,
+            format!(
+                "Filled {} cells in {}",
+                count.to_string().green(),
+                range.cyan()
+            ),
+        )?;
 
     Ok(())
 }
 
-fn merge(file: &std::path::Path, sheet: &str, range: &str, global: &GlobalOptions) -> Result<()> {
+/// Step between consecutive values of a date series.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DateStep {
+    Days(i64),
+    Months(i64),
+}
+
+/// A parsed `--series` specification.
+#[derive(Debug, Clone, PartialEq)]
+enum Series {
+    Number {
+        start: f64,
+        end: Option<f64>,
+        step: f64,
+    },
+    Date {
+        start: chrono::NaiveDate,
+        end: Option<chrono::NaiveDate>,
+        step: DateStep,
+    },
+}
+
+/// Parses `START..END [step N]`. END may be omitted to fill the whole range.
+/// Date steps take a unit suffix: d (days), w (weeks), m (months), y (years).
+fn parse_series(spec: &str) -> Result<Series> {
+    let spec = spec.trim();
+    let (bounds, step) = match spec.split_once(" step ") {
+        Some((bounds, step)) => (bounds.trim(), Some(step.trim())),
+        None => (spec, None),
+    };
+    let (start, end) = bounds
+        .split_once("..")
+        .ok_or_else(|| anyhow::anyhow!("Invalid series '{}': expected START..END", spec))?;
+    let (start, end) = (start.trim(), end.trim());
+    let end = (!end.is_empty()).then_some(end);
+
+    if let Ok(start) = chrono::NaiveDate::parse_from_str(start, "%Y-%m-%d") {
+        let end = end
+            .map(|e| chrono::NaiveDate::parse_from_str(e, "%Y-%m-%d"))
+            .transpose()
+            .map_err(|_| anyhow::anyhow!("Invalid series end date in '{}'", spec))?;
+        let step = match step {
+            None => DateStep::Days(if end.is_some_and(|end| end < start) {
+                -1
+            } else {
+                1
+            }),
+            Some(step) => {
+                let (n, unit) = step.split_at(step.len().saturating_sub(1));
+                let (n, unit) = match unit.parse::<i64>() {
+                    // A bare number means days
+                    Ok(_) => (step, "d"),
+                    Err(_) => (n, unit),
+                };
+                let n: i64 = n
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Invalid series step '{}'", step))?;
+                match unit {
+                    "d" => DateStep::Days(n),
+                    "w" => DateStep::Days(n * 7),
+                    "m" => DateStep::Months(n),
+                    "y" => DateStep::Months(n * 12),
+                    _ => anyhow::bail!("Invalid series step unit '{}' (use d, w, m or y)", unit),
+                }
+            }
+        };
+        let (DateStep::Days(n) | DateStep::Months(n)) = step;
+        check_series_step(spec, n as f64, end.map(|end| end.cmp(&start)))?;
+        return Ok(Series::Date { start, end, step });
+    }
+
+    let start: f64 = start
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid series start '{}'", start))?;
+    let end = end
+        .map(|e| e.parse::<f64>())
+        .transpose()
+        .map_err(|_| anyhow::anyhow!("Invalid series end in '{}'", spec))?;
+    let step = match step {
+        Some(step) => step
+            .parse::<f64>()
+            .map_err(|_| anyhow::anyhow!("Invalid series step '{}'", step))?,
+        None => match end {
+            Some(end) if end < start => -1.0,
+            _ => 1.0,
+        },
+    };
+    check_series_step(spec, step, end.and_then(|end| end.partial_cmp(&start)))?;
+    Ok(Series::Number { start, end, step })
+}
+
+/// Fails if a series step is zero or points away from the end, which
+/// would never reach it.
+fn check_series_step(spec: &str, step: f64, direction: Option<std::cmp::Ordering>) -> Result<()> {
+    use std::cmp::Ordering;
+
+    let message = if step == 0.0 {
+        "step must not be zero"
+    } else {
+        match direction {
+            Some(Ordering::Greater) if step < 0.0 => "step must be positive to count up",
+            Some(Ordering::Less) if step > 0.0 => "step must be negative to count down",
+            _ => return Ok(()),
+        }
+    };
+    Err(xlex_core::XlexError::ValidationFailed {
+        message: format!("Invalid series '{}': {}", spec, message),
+    }
+    .into())
+}
+
+impl Series {
+    /// Returns the `index`-th value, or `None` once the end bound is passed.
+    fn nth(&self, index: u64) -> Option<xlex_core::CellValue> {
+        match self {
+            Series::Number { start, end, step } => {
+                let value = start + step * index as f64;
+                match end {
+                    Some(end) if (*step > 0.0 && value > *end) || (*step < 0.0 && value < *end) => {
+                        None
+                    }
+                    _ => Some(xlex_core::CellValue::Number(value)),
+                }
+            }
+            Series::Date { start, end, step } => {
+                let date = match step {
+                    DateStep::Days(n) => {
+                        start.checked_add_signed(chrono::Duration::days(n * index as i64))
+                    }
+                    DateStep::Months(n) => {
+                        let months = n * index as i64;
+                        if months >= 0 {
+                            start.checked_add_months(chrono::Months::new(months as u32))
+                        } else {
+                            start.checked_sub_months(chrono::Months::new(
+                                months.unsigned_abs() as u32
+                            ))
+                        }
+                    }
+                }?;
+                let forward = match step {
+                    DateStep::Days(n) | DateStep::Months(n) => *n > 0,
+                };
+                match end {
+                    Some(end) if (forward && date > *end) || (!forward && date < *end) => None,
+                    _ => Some(xlex_core::CellValue::date(date)),
+                }
+            }
+        }
+    }
+}
+
+fn fill_series(
+    file: &std::path::Path,
+    sheet: &str,
+    range: &str,
+    spec: &str,
+    global: &GlobalOptions,
+) -> Result<()> {
+    use xlex_core::style::NumberFormat;
+
+    let series = parse_series(spec)?;
+
     if global.dry_run {
-        println!("Would merge range {} in {}", range, sheet);
+        outln!(
+            "Would fill range {} with series '{}' in {}",
+            range,
+            spec,
+            sheet
+        );
         return Ok(());
     }
 
+    let output = CommandOutput::new("fill");
+
     let mut workbook = Workbook::open(file)?;
     let range_ref = Range::parse(range)?;
 
+    // Single rows fill across, everything else fills down each column
+    let cells: Vec<xlex_core::CellRef> = if range_ref.height() == 1 {
+        range_ref.cells().collect()
+    } else {
+        (range_ref.start.col..=range_ref.end.col)
+            .flat_map(|col| {
+                (range_ref.start.row..=range_ref.end.row)
+                    .map(move |row| xlex_core::CellRef::new(col, row))
+            })
+            .collect()
+    };
+    let per_line = if range_ref.height() == 1 {
+        range_ref.width()
+    } else {
+        range_ref.height()
+    } as usize;
+    let filled: Vec<(xlex_core::CellRef, xlex_core::CellValue)> = cells
+        .chunks(per_line)
+        .flat_map(|line| {
+            line.iter()
+                .enumerate()
+                .map_while(|(index, cell_ref)| Some((cell_ref.clone(), series.nth(index as u64)?)))
+        })
+        .collect();
+
+    // Cells keep their formatting; dates also need a date number format to
+    // display as dates, applied on top of it
+    let mut styles = Vec::with_capacity(filled.len());
+    for (cell_ref, _) in &filled {
+        let style_id = match series {
+            Series::Date { .. } => {
+                let mut style = workbook.get_cell_style(sheet, cell_ref)?;
+                style.number_format = NumberFormat::date();
+                Some(workbook.style_registry_mut().clone_for(&style))
+            }
+            Series::Number { .. } => workbook
+                .get_sheet(sheet)
+                .and_then(|s| s.get_cell(cell_ref))
+                .and_then(|c| c.style_id),
+        };
+        styles.push(style_id);
+    }
+
     let sheet_obj =
         workbook
             .get_sheet_mut(sheet)
             .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
                 name: sheet.to_string(),
             })?;
+    let count = filled.len();
+    for ((cell_ref, value), style_id) in filled.into_iter().zip(styles) {
+        sheet_obj.set_cell(cell_ref.clone(), value);
+        if style_id.is_some() {
+            sheet_obj.set_cell_style(&cell_ref, style_id);
+        }
+    }
 
-    sheet_obj.add_merged_range(range_ref);
-    let _ = sheet_obj;
     workbook.save()?;
 
-    if !global.quiet {
-        println!("Merged range {}", range.cyan());
-    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("range", range)
+        .count("cells", count)
+        .field("range", range)
+        .field("series", spec)
+        .field("cells", count)
+        .print(
+            global,
+            format!(
+                "Filled {} cells in {} with series {}",
+                count.to_string().green(),
+                range.cyan(),
+                spec
+            ),
+        )?;
 
     Ok(())
 }
 
-fn unmerge(file: &std::path::Path, sheet: &str, range: &str, global: &GlobalOptions) -> Result<()> {
+fn fill_down(
+    file: &std::path::Path,
+    sheet: &str,
+    range: &str,
+    formula: Option<&str>,
+    global: &GlobalOptions,
+) -> Result<()> {
     if global.dry_run {
-        println!("Would unmerge range {} in {}", range, sheet);
+        outln!("Would fill formulas down range {} in {}", range, sheet);
         return Ok(());
     }
 
+    let output = CommandOutput::new("fill-down-formula");
+
     let mut workbook = Workbook::open(file)?;
     let range_ref = Range::parse(range)?;
 
@@ -933,29 +1652,568 @@ fn unmerge(file: &std::path::Path, sheet: &str, range: &str, global: &GlobalOpti
                 name: sheet.to_string(),
             })?;
 
-    sheet_obj.remove_merged_range(&range_ref);
+    let mut count = 0;
+    for col in range_ref.start.col..=range_ref.end.col {
+        let top = xlex_core::CellRef::new(col, range_ref.start.row);
+        let base = match formula {
+            Some(f) => f.strip_prefix('=').unwrap_or(f).to_string(),
+            None => match sheet_obj.get_value(&top) {
+                xlex_core::CellValue::Formula { formula, .. } => formula,
+                _ => continue,
+            },
+        };
+        let style_id = sheet_obj.get_cell(&top).and_then(|c| c.style_id);
+
+        for row in range_ref.start.row..=range_ref.end.row {
+            let cell_ref = xlex_core::CellRef::new(col, row);
+            let offset = (row - range_ref.start.row) as i64;
+            let shifted = xlex_core::formula::shift_refs(&base, 0, offset);
+            sheet_obj.set_cell(cell_ref.clone(), xlex_core::CellValue::formula(shifted));
+            sheet_obj.set_cell_style(&cell_ref, style_id);
+            count += 1;
+        }
+    }
+
     let _ = sheet_obj;
     workbook.save()?;
 
-    if !global.quiet {
-        println!("Unmerged range {}", range.cyan());
-    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("range", range)
+        .count("cells", count)
+        .field("range", range)
+        .field("cells", count)
+        .print(
+            global,
+            format!(
+                "Filled {} formula cells down {}",
+                count.to_string().green(),
+                range.cyan()
+            ),
+        )?;
 
     Ok(())
 }
 
-fn name(
+/// Rounding modes for `range round`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum RoundMode {
+    /// Halves away from zero, as ROUND
+    HalfUp,
+    /// Halves to the even neighbour (banker's rounding)
+    HalfEven,
+    /// Toward zero, as ROUNDDOWN
+    Down,
+    /// Away from zero, as ROUNDUP
+    Up,
+}
+
+impl From<RoundMode> for RoundingMode {
+    fn from(mode: RoundMode) -> Self {
+        match mode {
+            RoundMode::HalfUp => RoundingMode::HalfUp,
+            RoundMode::HalfEven => RoundingMode::HalfEven,
+            RoundMode::Down => RoundingMode::Down,
+            RoundMode::Up => RoundingMode::Up,
+        }
+    }
+}
+
+fn round(
     file: &std::path::Path,
-    name: &str,
+    sheet: &str,
     range: &str,
-    sheet: Option<&str>,
+    decimals: i32,
+    mode: RoundingMode,
+    set_format: bool,
     global: &GlobalOptions,
 ) -> Result<()> {
+    let range_ref = Range::parse(range)?;
     if global.dry_run {
-        println!("Would define named range '{}' as {}", name, range);
+        outln!(
+            "Would round numbers in {} of {} to {} decimals",
+            range,
+            sheet,
+            decimals
+        );
         return Ok(());
     }
 
+    let mut workbook = Workbook::open(file)?;
+    let sheet_obj =
+        workbook
+            .get_sheet(sheet)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: sheet.to_string(),
+            })?;
+    // Numbers only: formulas, dates and text are left alone
+    let numbers: Vec<(CellRef, f64, Option<u32>)> = sheet_obj
+        .cells()
+        .filter(|cell| range_ref.contains(&cell.reference))
+        .filter_map(|cell| match cell.value {
+            CellValue::Number(n) => Some((cell.reference.clone(), n, cell.style_id)),
+            _ => None,
+        })
+        .collect();
+
+    // One style per existing style, with the number format swapped
+    let mut formatted: std::collections::HashMap<Option<u32>, u32> =
+        std::collections::HashMap::new();
+    if set_format {
+        let number_format = NumberFormat::number(decimals.clamp(0, 30) as u8);
+        for (_, _, style_id) in &numbers {
+            if formatted.contains_key(style_id) {
+                continue;
+            }
+            let mut style = style_id
+                .and_then(|id| workbook.style_registry().get(id).cloned())
+                .unwrap_or_default();
+            style.number_format = number_format.clone();
+            let id = workbook.style_registry_mut().clone_for(&style);
+            formatted.insert(*style_id, id);
+        }
+    }
+
+    let mut changed = 0;
+    let sheet_obj =
+        workbook
+            .get_sheet_mut(sheet)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: sheet.to_string(),
+            })?;
+    for (cell_ref, value, style_id) in &numbers {
+        let rounded = xlex_core::rounding::round(*value, decimals, mode);
+        if let Some(cell) = sheet_obj.get_cell_mut(cell_ref) {
+            if rounded != *value {
+                cell.value = CellValue::Number(rounded);
+                changed += 1;
+            }
+            if let Some(&id) = formatted.get(style_id) {
+                cell.style_id = Some(id);
+            }
+        }
+    }
+    workbook.save()?;
+
+    CommandOutput::new("round")
+        .file(file)
+        .target("sheet", sheet)
+        .target("range", range)
+        .count("cells", numbers.len())
+        .field("changed", changed)
+        .field("decimals", decimals)
+        .print(
+            global,
+            format!(
+                "Rounded {} of {} numbers in {} to {} decimals",
+                changed.to_string().green(),
+                numbers.len(),
+                range.cyan(),
+                decimals
+            ),
+        )?;
+    Ok(())
+}
+
+/// What `range normalize-dates` writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DateTarget {
+    /// Date serial numbers with a date format
+    ExcelDate,
+    /// Text as YYYY-MM-DD, or YYYY-MM-DDTHH:MM:SS with a time
+    IsoText,
+}
+
+/// Parses `text` with the first of `formats` that matches all of it. Formats
+/// with a time give a date-time; the others a date.
+fn parse_date_text(text: &str, formats: &[String]) -> Option<chrono::NaiveDateTime> {
+    formats.iter().find_map(|format| {
+        chrono::NaiveDateTime::parse_from_str(text, format)
+            .ok()
+            .or_else(|| {
+                chrono::NaiveDate::parse_from_str(text, format)
+                    .ok()
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+            })
+    })
+}
+
+fn normalize_dates(
+    file: &std::path::Path,
+    sheet: &str,
+    range: &str,
+    formats: &[String],
+    to: DateTarget,
+    display_format: &str,
+    global: &GlobalOptions,
+) -> Result<()> {
+    use chrono::Timelike;
+
+    let range_ref = Range::parse(range)?;
+    let formats: Vec<String> = formats
+        .iter()
+        .map(|f| f.trim().to_string())
+        .filter(|f| !f.is_empty())
+        .collect();
+    if formats.is_empty() {
+        anyhow::bail!("--from-formats needs at least one format");
+    }
+
+    let mut workbook = Workbook::open(file)?;
+    let sheet_obj =
+        workbook
+            .get_sheet(sheet)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: sheet.to_string(),
+            })?;
+    // Text cells only: numbers and real dates are already what Excel wants
+    let mut parsed: Vec<(CellRef, chrono::NaiveDateTime, Option<u32>)> = Vec::new();
+    let mut unparsed: Vec<(CellRef, String)> = Vec::new();
+    for cell in sheet_obj.cells() {
+        if !range_ref.contains(&cell.reference) {
+            continue;
+        }
+        let CellValue::String(text) = &cell.value else {
+            continue;
+        };
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        match parse_date_text(text, &formats) {
+            Some(date) => parsed.push((cell.reference.clone(), date, cell.style_id)),
+            None => unparsed.push((cell.reference.clone(), text.to_string())),
+        }
+    }
+    parsed.sort_by_key(|(cell_ref, _, _)| (cell_ref.row, cell_ref.col));
+    unparsed.sort_by_key(|(cell_ref, _)| (cell_ref.row, cell_ref.col));
+
+    if global.dry_run {
+        outln!(
+            "Would normalize {} dates in {} of {} ({} unparseable)",
+            parsed.len(),
+            range,
+            sheet,
+            unparsed.len()
+        );
+        return Ok(());
+    }
+
+    // One style per existing style, with the date format swapped in
+    let mut formatted: std::collections::HashMap<Option<u32>, u32> =
+        std::collections::HashMap::new();
+    if to == DateTarget::ExcelDate {
+        for (_, _, style_id) in &parsed {
+            if formatted.contains_key(style_id) {
+                continue;
+            }
+            let mut style = style_id
+                .and_then(|id| workbook.style_registry().get(id).cloned())
+                .unwrap_or_default();
+            style.number_format = NumberFormat::custom(display_format);
+            let id = workbook.style_registry_mut().clone_for(&style);
+            formatted.insert(*style_id, id);
+        }
+    }
+
+    let sheet_obj =
+        workbook
+            .get_sheet_mut(sheet)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: sheet.to_string(),
+            })?;
+    for (cell_ref, date, style_id) in &parsed {
+        let has_time = date.num_seconds_from_midnight() != 0;
+        let value = match (to, has_time) {
+            (DateTarget::ExcelDate, false) => CellValue::date(date.date()),
+            (DateTarget::ExcelDate, true) => CellValue::datetime(*date),
+            (DateTarget::IsoText, false) => CellValue::String(date.format("%Y-%m-%d").to_string()),
+            (DateTarget::IsoText, true) => {
+                CellValue::String(date.format("%Y-%m-%dT%H:%M:%S").to_string())
+            }
+        };
+        if let Some(cell) = sheet_obj.get_cell_mut(cell_ref) {
+            cell.value = value;
+            if let Some(&id) = formatted.get(style_id) {
+                cell.style_id = Some(id);
+            }
+        }
+    }
+    if !parsed.is_empty() {
+        workbook.save()?;
+    }
+
+    let failures: Vec<serde_json::Value> = unparsed
+        .iter()
+        .map(|(cell_ref, text)| {
+            serde_json::json!({
+                "cell": cell_ref.to_a1(),
+                "value": text,
+            })
+        })
+        .collect();
+    let mut message = format!(
+        "Normalized {} dates in {}",
+        parsed.len().to_string().green(),
+        range.cyan()
+    );
+    if !unparsed.is_empty() {
+        // Name the first few so they can be fixed by hand
+        const SHOWN: usize = 5;
+        let shown: Vec<String> = unparsed
+            .iter()
+            .take(SHOWN)
+            .map(|(cell_ref, text)| format!("{} ({:?})", cell_ref.to_a1(), text))
+            .collect();
+        message.push_str(&format!(
+            "; {} unparseable: {}{}",
+            unparsed.len().to_string().red(),
+            shown.join(", "),
+            if unparsed.len() > SHOWN { ", ..." } else { "" }
+        ));
+    }
+    CommandOutput::new("normalize_dates")
+        .file(file)
+        .target("sheet", sheet)
+        .target("range", range)
+        .count("normalized", parsed.len())
+        .count("unparseable", unparsed.len())
+        .field("unparsed", failures)
+        .print(global, message)?;
+    Ok(())
+}
+
+/// Cases for `range clean --case`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CaseArg {
+    Lower,
+    Upper,
+    /// First letter of each word upper case, as PROPER
+    Title,
+}
+
+impl From<CaseArg> for TextCase {
+    fn from(case: CaseArg) -> Self {
+        match case {
+            CaseArg::Lower => TextCase::Lower,
+            CaseArg::Upper => TextCase::Upper,
+            CaseArg::Title => TextCase::Title,
+        }
+    }
+}
+
+fn clean(
+    file: &std::path::Path,
+    sheet: &str,
+    range: &str,
+    options: &CleanOptions,
+    global: &GlobalOptions,
+) -> Result<()> {
+    if options.is_empty() {
+        anyhow::bail!(
+            "Nothing to do: pass --trim, --collapse-spaces, --strip-nonprintable or --case"
+        );
+    }
+    let range_ref = Range::parse(range)?;
+
+    let mut workbook = Workbook::open(file)?;
+    let sheet_obj =
+        workbook
+            .get_sheet_mut(sheet)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: sheet.to_string(),
+            })?;
+    // Text cells only; formulas keep their text
+    let texts: Vec<(CellRef, String)> = sheet_obj
+        .cells()
+        .filter(|cell| range_ref.contains(&cell.reference))
+        .filter_map(|cell| match &cell.value {
+            CellValue::String(text) => Some((cell.reference.clone(), text.clone())),
+            _ => None,
+        })
+        .collect();
+    let changes: Vec<(CellRef, String)> = texts
+        .iter()
+        .filter_map(|(cell_ref, text)| {
+            let cleaned = xlex_core::text::clean(text, options);
+            (cleaned != *text).then(|| (cell_ref.clone(), cleaned))
+        })
+        .collect();
+
+    if global.dry_run {
+        outln!(
+            "Would clean {} of {} text cells in {} of {}",
+            changes.len(),
+            texts.len(),
+            range,
+            sheet
+        );
+        return Ok(());
+    }
+
+    for (cell_ref, cleaned) in &changes {
+        if let Some(cell) = sheet_obj.get_cell_mut(cell_ref) {
+            cell.value = if cleaned.is_empty() {
+                CellValue::Empty
+            } else {
+                CellValue::String(cleaned.clone())
+            };
+        }
+    }
+    if !changes.is_empty() {
+        workbook.save()?;
+    }
+
+    CommandOutput::new("clean")
+        .file(file)
+        .target("sheet", sheet)
+        .target("range", range)
+        .count("cells", texts.len())
+        .field("changed", changes.len())
+        .print(
+            global,
+            format!(
+                "Cleaned {} of {} text cells in {}",
+                changes.len().to_string().green(),
+                texts.len(),
+                range.cyan()
+            ),
+        )?;
+    Ok(())
+}
+
+fn merge(file: &std::path::Path, sheet: &str, range: &str, global: &GlobalOptions) -> Result<()> {
+    if global.dry_run {
+        outln!("Would merge range {} in {}", range, sheet);
+        return Ok(());
+    }
+
+    let output = CommandOutput::new("merge");
+
+    let mut workbook = Workbook::open(file)?;
+    let range_ref = Range::parse(range)?;
+
+    let sheet_obj =
+        workbook
+            .get_sheet_mut(sheet)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: sheet.to_string(),
+            })?;
+
+    let cells = range_ref.cell_count();
+    sheet_obj.add_merged_range(range_ref);
+    let _ = sheet_obj;
+    workbook.save()?;
+
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("range", range)
+        .count("cells", cells)
+        .print(global, format!("Merged range {}", range.cyan()))?;
+
+    Ok(())
+}
+
+fn unmerge(file: &std::path::Path, sheet: &str, range: &str, global: &GlobalOptions) -> Result<()> {
+    if global.dry_run {
+        outln!("Would unmerge range {} in {}", range, sheet);
+        return Ok(());
+    }
+
+    let output = CommandOutput::new("unmerge");
+
+    let mut workbook = Workbook::open(file)?;
+    let range_ref = Range::parse(range)?;
+
+    let sheet_obj =
+        workbook
+            .get_sheet_mut(sheet)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: sheet.to_string(),
+            })?;
+
+    sheet_obj.remove_merged_range(&range_ref);
+    let _ = sheet_obj;
+    workbook.save()?;
+
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("range", range)
+        .count("cells", range_ref.cell_count())
+        .print(global, format!("Unmerged range {}", range.cyan()))?;
+
+    Ok(())
+}
+
+fn lock(
+    file: &std::path::Path,
+    sheet: &str,
+    range: &str,
+    locked: bool,
+    hide_formulas: bool,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let range_ref = Range::parse(range)?;
+    let action = if locked { "lock" } else { "unlock" };
+    if global.dry_run {
+        outln!("Would {} range {} in {}", action, range, sheet);
+        return Ok(());
+    }
+
+    let output = CommandOutput::new(action);
+
+    let mut workbook = Workbook::open(file)?;
+    let protection = xlex_core::style::Protection {
+        locked,
+        hidden: hide_formulas,
+    };
+    workbook.set_cell_protection(sheet, &range_ref, protection)?;
+    let protected = workbook
+        .get_sheet(sheet)
+        .is_some_and(|s| s.protection().is_some());
+    workbook.save()?;
+
+    let mut message = format!(
+        "{} range {}",
+        if locked { "Locked" } else { "Unlocked" },
+        range.cyan()
+    );
+    if !protected {
+        message.push_str(&format!(
+            " (takes effect once the sheet is protected: xlex sheet protect {} {})",
+            file.display(),
+            sheet
+        ));
+    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("range", range)
+        .count("cells", range_ref.cell_count())
+        .field("hiddenFormulas", hide_formulas)
+        .field("sheetProtected", protected)
+        .print(global, message)?;
+
+    Ok(())
+}
+
+fn name(
+    file: &std::path::Path,
+    name: &str,
+    range: &str,
+    sheet: Option<&str>,
+    global: &GlobalOptions,
+) -> Result<()> {
+    if global.dry_run {
+        outln!("Would define named range '{}' as {}", name, range);
+        return Ok(());
+    }
+
+    let output = CommandOutput::new("name");
+
     let mut workbook = Workbook::open(file)?;
 
     // Validate range syntax
@@ -986,23 +2244,21 @@ fn name(
     workbook.set_defined_name(defined_name);
     workbook.save()?;
 
-    if !global.quiet {
-        if global.format == OutputFormat::Json {
-            let json = serde_json::json!({
-                "name": name,
-                "range": range,
-                "sheet": sheet,
-            });
-            println!("{}", serde_json::to_string_pretty(&json)?);
-        } else {
-            println!(
+    output
+        .file(file)
+        .target("name", name)
+        .field("name", name)
+        .field("range", range)
+        .field("sheet", sheet)
+        .print(
+            global,
+            format!(
                 "{} Defined named range '{}' as {}",
                 "✓".green(),
                 name.cyan(),
                 range.yellow()
-            );
-        }
-    }
+            ),
+        )?;
 
     Ok(())
 }
@@ -1024,18 +2280,18 @@ fn names(file: &std::path::Path, global: &GlobalOptions) -> Result<()> {
                 })
             })
             .collect();
-        println!("{}", serde_json::to_string_pretty(&names)?);
+        outln!("{}", serde_json::to_string_pretty(&names)?);
     } else if defined_names.is_empty() {
-        println!("No named ranges defined");
+        outln!("No named ranges defined");
     } else {
-        println!("{} named range(s):", defined_names.len());
+        outln!("{} named range(s):", defined_names.len());
         for dn in defined_names {
             let scope = if let Some(idx) = dn.local_sheet_id {
                 format!(" (sheet {})", idx)
             } else {
                 " (global)".to_string()
             };
-            println!(
+            outln!(
                 "  {} {} {}{}",
                 dn.name.cyan(),
                 "→".dimmed(),
@@ -1053,6 +2309,7 @@ fn validate(
     sheet: &str,
     range: &str,
     rule: &str,
+    fail_on: FailOn,
     global: &GlobalOptions,
 ) -> Result<()> {
     let workbook = Workbook::open(file)?;
@@ -1096,19 +2353,19 @@ fn validate(
             "rule": rule,
             "errors": errors,
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else {
         if valid {
-            println!("{}: Validation passed", "✓".green());
+            outln!("{}: Validation passed", "✓".green());
         } else {
-            println!("{}: Validation failed", "✗".red());
+            outln!("{}: Validation failed", "✗".red());
             for err in &errors {
-                println!("  - {}", err);
+                outln!("  - {}", err);
             }
         }
     }
 
-    Ok(())
+    fail_on.check(errors.len(), 0)
 }
 
 fn sort(
@@ -1120,10 +2377,12 @@ fn sort(
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!("Would sort range {} in {}", range, sheet);
+        outln!("Would sort range {} in {}", range, sheet);
         return Ok(());
     }
 
+    let output = CommandOutput::new("sort");
+
     let mut workbook = Workbook::open(file)?;
     let range_ref = Range::parse(range)?;
 
@@ -1203,14 +2462,17 @@ fn sort(
     let _ = sheet_obj;
     workbook.save()?;
 
-    if !global.quiet {
-        let order = if descending {
-            "descending"
-        } else {
-            "ascending"
-        };
-        println!("Sorted range {} ({})", range.cyan(), order);
-    }
+    let order = if descending {
+        "descending"
+    } else {
+        "ascending"
+    };
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("range", range)
+        .field("order", order)
+        .print(global, format!("Sorted range {} ({})", range.cyan(), order))?;
 
     Ok(())
 }
@@ -1264,17 +2526,20 @@ fn filter(
             })?;
 
     // Find rows matching the filter
-    let mut matching_rows: Vec<u32> = Vec::new();
-
-    for row in range_ref.start.row..=range_ref.end.row {
-        let cell_ref = xlex_core::CellRef::new(filter_col, row);
-        let cell_value = sheet_obj.get_value(&cell_ref);
-        let cell_str = cell_value.to_display_string();
-
-        if cell_str.contains(value) {
-            matching_rows.push(row);
-        }
-    }
+    let matching_rows: Vec<u32> = if value.is_empty() {
+        (range_ref.start.row..=range_ref.end.row).collect()
+    } else {
+        // Only non-empty cells can contain a non-empty value
+        let column = Range::new(
+            xlex_core::CellRef::new(filter_col, range_ref.start.row),
+            xlex_core::CellRef::new(filter_col, range_ref.end.row),
+        );
+        sheet_obj
+            .iter_range(&column)
+            .filter(|(_, cell)| cell.value.to_display_string().contains(value))
+            .map(|(cell_ref, _)| cell_ref.row)
+            .collect()
+    };
 
     // Output matching rows
     if global.format == OutputFormat::Json {
@@ -1284,14 +2549,7 @@ fn filter(
             let mut row_values: Vec<serde_json::Value> = Vec::new();
             for col in range_ref.start.col..=range_ref.end.col {
                 let cell_ref = xlex_core::CellRef::new(col, *row);
-                let val = sheet_obj.get_value(&cell_ref);
-                row_values.push(match val {
-                    xlex_core::CellValue::Empty => serde_json::Value::Null,
-                    xlex_core::CellValue::String(s) => serde_json::Value::String(s),
-                    xlex_core::CellValue::Number(n) => serde_json::json!(n),
-                    xlex_core::CellValue::Boolean(b) => serde_json::Value::Bool(b),
-                    _ => serde_json::Value::String(val.to_display_string()),
-                });
+                row_values.push(sheet_obj.get_value(&cell_ref).into());
             }
             result_rows.push(row_values);
         }
@@ -1301,9 +2559,9 @@ fn filter(
             "matches": matching_rows.len(),
             "rows": result_rows,
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else {
-        println!(
+        outln!(
             "Found {} matching rows:",
             matching_rows.len().to_string().green()
         );
@@ -1314,7 +2572,7 @@ fn filter(
                 let val = sheet_obj.get_value(&cell_ref);
                 values.push(val.to_display_string());
             }
-            println!(
+            outln!(
                 "  {}: {}",
                 format!("Row {}", row).cyan(),
                 values.join(" | ")
@@ -1341,6 +2599,11 @@ mod tests {
             json_errors: false,
             dry_run: false,
             output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
         }
     }
 
@@ -1375,97 +2638,513 @@ mod tests {
     }
 
     #[test]
-    fn test_get_range_json() {
+    fn test_get_range_json() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "get_json.xlsx");
+        setup_test_data(&file_path);
+
+        let mut global = default_global();
+        global.format = OutputFormat::Json;
+
+        let result = get(&file_path, "Sheet1", "A1:C3", &global);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_get_range_csv() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "get_csv.xlsx");
+        setup_test_data(&file_path);
+
+        let mut global = default_global();
+        global.format = OutputFormat::Csv;
+
+        let result = get(&file_path, "Sheet1", "A1:C3", &global);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_copy_range() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "copy.xlsx");
+        setup_test_data(&file_path);
+
+        let result = copy(&file_path, "Sheet1", "A1:B2", "E1", &default_global());
+        assert!(result.is_ok());
+
+        // Verify copy
+        let wb = Workbook::open(&file_path).unwrap();
+        let cell_ref = xlex_core::CellRef::new(5, 1); // E1 = col 5, row 1 (1-indexed)
+        let value = wb.get_cell("Sheet1", &cell_ref).unwrap();
+        assert_eq!(value, CellValue::Number(10.0));
+    }
+
+    #[test]
+    fn test_move_range() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "move.xlsx");
+        setup_test_data(&file_path);
+
+        let result = move_range(&file_path, "Sheet1", "A1:B2", "E1", &default_global());
+        assert!(result.is_ok());
+
+        // Verify move - source should be empty
+        let wb = Workbook::open(&file_path).unwrap();
+        let source_cell = xlex_core::CellRef::new(1, 1); // A1 = col 1, row 1 (1-indexed)
+        let source_value = wb.get_cell("Sheet1", &source_cell).unwrap();
+        assert_eq!(source_value, CellValue::Empty);
+
+        // Destination should have the value
+        let dest_cell = xlex_core::CellRef::new(5, 1); // E1 = col 5, row 1 (1-indexed)
+        let dest_value = wb.get_cell("Sheet1", &dest_cell).unwrap();
+        assert_eq!(dest_value, CellValue::Number(10.0));
+    }
+
+    #[test]
+    fn test_transpose_range() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "transpose.xlsx");
+        setup_test_data(&file_path);
+
+        let result = transpose(&file_path, "Sheet1", "A1:C2", Some("A5"), &default_global());
+        assert!(result.is_ok());
+
+        // A5:B7 holds the transposed A1:C2
+        let wb = Workbook::open(&file_path).unwrap();
+        let value = wb
+            .get_cell("Sheet1", &xlex_core::CellRef::new(2, 7))
+            .unwrap();
+        assert_eq!(value, CellValue::Number(22.0));
+        let value = wb
+            .get_cell("Sheet1", &xlex_core::CellRef::new(1, 6))
+            .unwrap();
+        assert_eq!(value, CellValue::Number(11.0));
+    }
+
+    #[test]
+    fn test_transpose_range_in_place() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "transpose_in_place.xlsx");
+        setup_test_data(&file_path);
+
+        let result = transpose(&file_path, "Sheet1", "A1:C3", None, &default_global());
+        assert!(result.is_ok());
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let value = wb
+            .get_cell("Sheet1", &xlex_core::CellRef::new(1, 3))
+            .unwrap();
+        assert_eq!(value, CellValue::Number(12.0));
+    }
+
+    #[test]
+    fn test_transpose_json_output() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "transpose_json.xlsx");
+        setup_test_data(&file_path);
+
+        let mut global = default_global();
+        global.quiet = false;
+        global.format = OutputFormat::Json;
+
+        let result = transpose(&file_path, "Sheet1", "A1:B3", Some("E1"), &global);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_transpose_sheet_not_found() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "transpose_missing.xlsx");
+
+        let result = transpose(&file_path, "Missing", "A1:B2", None, &default_global());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_clear_range() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "clear.xlsx");
+        setup_test_data(&file_path);
+
+        let result = clear(&file_path, "Sheet1", "A1:B2", false, &default_global());
+        assert!(result.is_ok());
+
+        // Verify cleared
+        let wb = Workbook::open(&file_path).unwrap();
+        let cell_ref = xlex_core::CellRef::new(1, 1); // A1 = col 1, row 1 (1-indexed)
+        let value = wb.get_cell("Sheet1", &cell_ref).unwrap();
+        assert_eq!(value, CellValue::Empty);
+    }
+
+    #[test]
+    fn test_fill_range() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "fill.xlsx");
+
+        let result = fill(&file_path, "Sheet1", "A1:C3", "test", &default_global());
+        assert!(result.is_ok());
+
+        // Verify fill
+        let wb = Workbook::open(&file_path).unwrap();
+        let cell_ref = xlex_core::CellRef::new(1, 1); // A1 = col 1, row 1 (1-indexed)
+        let value = wb.get_cell("Sheet1", &cell_ref).unwrap();
+        assert_eq!(value, CellValue::String("test".to_string()));
+    }
+
+    #[test]
+    fn test_round_range() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "round.xlsx");
+        let mut wb = Workbook::open(&file_path).unwrap();
+        let values = [
+            CellValue::Number(0.1 + 0.2),
+            CellValue::Number(2.675),
+            CellValue::Number(0.125),
+            CellValue::string("1.234"),
+        ];
+        for (row, value) in values.into_iter().enumerate() {
+            wb.set_cell("Sheet1", CellRef::new(2, row as u32 + 1), value)
+                .unwrap();
+        }
+        wb.set_cell("Sheet1", CellRef::new(3, 1), CellValue::Number(9.999))
+            .unwrap();
+        wb.save().unwrap();
+
+        round(
+            &file_path,
+            "Sheet1",
+            "B1:B4",
+            2,
+            RoundingMode::HalfEven,
+            true,
+            &default_global(),
+        )
+        .unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        let value = |col, row| wb.get_cell("Sheet1", &CellRef::new(col, row)).unwrap();
+        assert_eq!(value(2, 1), CellValue::Number(0.3));
+        assert_eq!(value(2, 2), CellValue::Number(2.68));
+        assert_eq!(value(2, 3), CellValue::Number(0.12));
+        assert_eq!(value(2, 4), CellValue::string("1.234"));
+        assert_eq!(value(3, 1), CellValue::Number(9.999));
+        let sheet = wb.get_sheet("Sheet1").unwrap();
+        let style_id = sheet.get_cell(&CellRef::new(2, 1)).unwrap().style_id;
+        let style = wb.style_registry().get(style_id.unwrap()).unwrap();
+        assert_eq!(style.number_format.code.as_deref(), Some("0.00"));
+    }
+
+    #[test]
+    fn test_normalize_dates() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "dates.xlsx");
+        let mut wb = Workbook::open(&file_path).unwrap();
+        let values = [
+            CellValue::string("03/02/2024"),
+            CellValue::string(" 12-25-2023 "),
+            CellValue::string("tomorrow"),
+            CellValue::Number(45000.0),
+            CellValue::string("31/12/2024 18:30"),
+        ];
+        for (row, value) in values.into_iter().enumerate() {
+            wb.set_cell("Sheet1", CellRef::new(3, row as u32 + 1), value)
+                .unwrap();
+        }
+        wb.save().unwrap();
+
+        let formats = ["%d/%m/%Y", "%m-%d-%Y", "%d/%m/%Y %H:%M"].map(String::from);
+        normalize_dates(
+            &file_path,
+            "Sheet1",
+            "C:C",
+            &formats,
+            DateTarget::ExcelDate,
+            "dd mmm yyyy",
+            &default_global(),
+        )
+        .unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        let value = |row| wb.get_cell("Sheet1", &CellRef::new(3, row)).unwrap();
+        // Dates read back as their serial numbers
+        assert_eq!(value(1), CellValue::Number(45325.0));
+        assert_eq!(value(2), CellValue::Number(45285.0));
+        assert_eq!(value(3), CellValue::string("tomorrow"));
+        assert_eq!(value(4), CellValue::Number(45000.0));
+        let CellValue::Number(serial) = value(5) else {
+            panic!("expected a date-time, got {:?}", value(5));
+        };
+        assert!((serial - 45657.770833).abs() < 1e-6);
+        let sheet = wb.get_sheet("Sheet1").unwrap();
+        let style_id = sheet.get_cell(&CellRef::new(3, 1)).unwrap().style_id;
+        let style = wb.style_registry().get(style_id.unwrap()).unwrap();
+        assert_eq!(style.number_format.code.as_deref(), Some("dd mmm yyyy"));
+
+        let mut wb = Workbook::open(&file_path).unwrap();
+        wb.set_cell("Sheet1", CellRef::new(4, 1), CellValue::string("2024/7/4"))
+            .unwrap();
+        wb.save().unwrap();
+        normalize_dates(
+            &file_path,
+            "Sheet1",
+            "D1",
+            &["%Y/%m/%d".to_string()],
+            DateTarget::IsoText,
+            "yyyy-mm-dd",
+            &default_global(),
+        )
+        .unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(
+            wb.get_cell("Sheet1", &CellRef::new(4, 1)).unwrap(),
+            CellValue::string("2024-07-04")
+        );
+    }
+
+    #[test]
+    fn test_clean_range() {
         let temp_dir = TempDir::new().unwrap();
-        let file_path = create_test_workbook(&temp_dir, "get_json.xlsx");
-        setup_test_data(&file_path);
+        let file_path = create_test_workbook(&temp_dir, "clean.xlsx");
+        let mut wb = Workbook::open(&file_path).unwrap();
+        let values = [
+            CellValue::string("  ALICE   Smith\u{200b} "),
+            CellValue::string("bob"),
+            CellValue::string(" \u{a0} "),
+            CellValue::Number(1.0),
+        ];
+        for (row, value) in values.into_iter().enumerate() {
+            wb.set_cell("Sheet1", CellRef::new(1, row as u32 + 1), value)
+                .unwrap();
+        }
+        wb.set_cell("Sheet1", CellRef::new(5, 1), CellValue::string(" Outside "))
+            .unwrap();
+        wb.save().unwrap();
 
-        let mut global = default_global();
-        global.format = OutputFormat::Json;
+        let options = CleanOptions {
+            trim: true,
+            collapse_spaces: true,
+            strip_nonprintable: true,
+            case: Some(TextCase::Lower),
+        };
+        clean(&file_path, "Sheet1", "A:D", &options, &default_global()).unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        let value = |col, row| wb.get_cell("Sheet1", &CellRef::new(col, row)).unwrap();
+        assert_eq!(value(1, 1), CellValue::string("alice smith"));
+        assert_eq!(value(1, 2), CellValue::string("bob"));
+        assert_eq!(value(1, 3), CellValue::Empty);
+        assert_eq!(value(1, 4), CellValue::Number(1.0));
+        assert_eq!(value(5, 1), CellValue::string(" Outside "));
+
+        let err = clean(
+            &file_path,
+            "Sheet1",
+            "A:D",
+            &CleanOptions::default(),
+            &default_global(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Nothing to do"));
+    }
 
-        let result = get(&file_path, "Sheet1", "A1:C3", &global);
-        assert!(result.is_ok());
+    #[test]
+    fn test_parse_series_numbers() {
+        assert_eq!(
+            parse_series("1..100 step 2").unwrap(),
+            Series::Number {
+                start: 1.0,
+                end: Some(100.0),
+                step: 2.0
+            }
+        );
+        assert_eq!(
+            parse_series("10..1").unwrap(),
+            Series::Number {
+                start: 10.0,
+                end: Some(1.0),
+                step: -1.0
+            }
+        );
+        assert!(parse_series("1..10 step 0").is_err());
+        assert!(parse_series("abc").is_err());
+        let err = parse_series("10..1 step 3").unwrap_err();
+        assert!(err.to_string().contains("step must be negative"));
+        assert!(matches!(
+            err.downcast_ref::<xlex_core::XlexError>(),
+            Some(xlex_core::XlexError::ValidationFailed { .. })
+        ));
+        assert!(parse_series("1..10 step -1").is_err());
+        assert!(parse_series("10..1 step -3").is_ok());
+        assert!(parse_series("5..5 step -1").is_ok());
     }
 
     #[test]
-    fn test_get_range_csv() {
-        let temp_dir = TempDir::new().unwrap();
-        let file_path = create_test_workbook(&temp_dir, "get_csv.xlsx");
-        setup_test_data(&file_path);
+    fn test_parse_series_dates() {
+        let series = parse_series("2024-01-31..2024-12-01 step 1m").unwrap();
+        assert_eq!(
+            series.nth(1),
+            Some(CellValue::date(
+                chrono::NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
+            ))
+        );
+        assert_eq!(series.nth(11), None);
+        let weekly = parse_series("2024-01-01.. step 2w").unwrap();
+        assert_eq!(
+            weekly.nth(1),
+            Some(CellValue::date(
+                chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()
+            ))
+        );
+        assert!(parse_series("2024-01-01..2024-02-01 step 1q").is_err());
+        assert!(parse_series("2024-02-01..2024-01-01 step 1w").is_err());
+        assert!(parse_series("2024-01-01..2024-02-01 step 0d").is_err());
+        let backwards = parse_series("2024-01-03..2024-01-01").unwrap();
+        assert_eq!(
+            backwards.nth(2),
+            Some(CellValue::date(
+                chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
+            ))
+        );
+    }
 
-        let mut global = default_global();
-        global.format = OutputFormat::Csv;
+    #[test]
+    fn test_fill_series_numbers() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "fill_series.xlsx");
 
-        let result = get(&file_path, "Sheet1", "A1:C3", &global);
+        let result = fill_series(
+            &file_path,
+            "Sheet1",
+            "A1:A10",
+            "1..7 step 2",
+            &default_global(),
+        );
         assert!(result.is_ok());
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let value = |row| {
+            wb.get_cell("Sheet1", &xlex_core::CellRef::new(1, row))
+                .unwrap()
+        };
+        assert_eq!(value(1), CellValue::Number(1.0));
+        assert_eq!(value(4), CellValue::Number(7.0));
+        // Series ended before the range did
+        assert_eq!(value(5), CellValue::Empty);
     }
 
     #[test]
-    fn test_copy_range() {
+    fn test_fill_series_dates_across_row() {
         let temp_dir = TempDir::new().unwrap();
-        let file_path = create_test_workbook(&temp_dir, "copy.xlsx");
-        setup_test_data(&file_path);
+        let file_path = create_test_workbook(&temp_dir, "fill_series_dates.xlsx");
 
-        let result = copy(&file_path, "Sheet1", "A1:B2", "E1", &default_global());
+        let result = fill_series(
+            &file_path,
+            "Sheet1",
+            "A1:L1",
+            "2024-01-01..2024-12-01 step 1m",
+            &default_global(),
+        );
         assert!(result.is_ok());
 
-        // Verify copy
         let wb = Workbook::open(&file_path).unwrap();
-        let cell_ref = xlex_core::CellRef::new(5, 1); // E1 = col 5, row 1 (1-indexed)
-        let value = wb.get_cell("Sheet1", &cell_ref).unwrap();
-        assert_eq!(value, CellValue::Number(10.0));
+        let last = wb
+            .get_cell("Sheet1", &xlex_core::CellRef::new(12, 1))
+            .unwrap();
+        assert_eq!(last.to_display_string(), "45627");
+        let cell = wb
+            .get_sheet("Sheet1")
+            .unwrap()
+            .get_cell(&xlex_core::CellRef::new(12, 1))
+            .unwrap();
+        assert!(cell.style_id.is_some());
     }
 
     #[test]
-    fn test_move_range() {
+    fn test_fill_series_keeps_cell_styles() {
         let temp_dir = TempDir::new().unwrap();
-        let file_path = create_test_workbook(&temp_dir, "move.xlsx");
-        setup_test_data(&file_path);
+        let file_path = create_test_workbook(&temp_dir, "fill_series_styled.xlsx");
+        {
+            let mut wb = Workbook::open(&file_path).unwrap();
+            let mut style = xlex_core::Style::default();
+            style.font.bold = true;
+            style.protection.locked = false;
+            let id = wb.style_registry_mut().add(style);
+            let sheet = wb.get_sheet_mut("Sheet1").unwrap();
+            for row in 1..=3 {
+                for col in 1..=2 {
+                    let cell_ref = xlex_core::CellRef::new(col, row);
+                    sheet.set_cell(cell_ref.clone(), CellValue::Empty);
+                    sheet.set_cell_style(&cell_ref, Some(id));
+                }
+            }
+            wb.save().unwrap();
+        }
 
-        let result = move_range(&file_path, "Sheet1", "A1:B2", "E1", &default_global());
-        assert!(result.is_ok());
+        fill_series(
+            &file_path,
+            "Sheet1",
+            "A1:A3",
+            "2024-01-01..",
+            &default_global(),
+        )
+        .unwrap();
+        fill_series(&file_path, "Sheet1", "B1:B3", "1..", &default_global()).unwrap();
 
-        // Verify move - source should be empty
         let wb = Workbook::open(&file_path).unwrap();
-        let source_cell = xlex_core::CellRef::new(1, 1); // A1 = col 1, row 1 (1-indexed)
-        let source_value = wb.get_cell("Sheet1", &source_cell).unwrap();
-        assert_eq!(source_value, CellValue::Empty);
-
-        // Destination should have the value
-        let dest_cell = xlex_core::CellRef::new(5, 1); // E1 = col 5, row 1 (1-indexed)
-        let dest_value = wb.get_cell("Sheet1", &dest_cell).unwrap();
-        assert_eq!(dest_value, CellValue::Number(10.0));
+        for col in 1..=2 {
+            let style = wb
+                .get_cell_style("Sheet1", &xlex_core::CellRef::new(col, 3))
+                .unwrap();
+            assert!(style.font.bold);
+            assert!(!style.protection.locked);
+        }
+        let date = wb
+            .get_cell_style("Sheet1", &xlex_core::CellRef::new(1, 1))
+            .unwrap();
+        assert_eq!(date.number_format.id, Some(14));
     }
 
     #[test]
-    fn test_clear_range() {
+    fn test_fill_down_formula() {
         let temp_dir = TempDir::new().unwrap();
-        let file_path = create_test_workbook(&temp_dir, "clear.xlsx");
+        let file_path = create_test_workbook(&temp_dir, "fill_down.xlsx");
         setup_test_data(&file_path);
 
-        let result = clear(&file_path, "Sheet1", "A1:B2", false, &default_global());
+        let result = fill_down(
+            &file_path,
+            "Sheet1",
+            "D1:D3",
+            Some("=A1*$B$1"),
+            &default_global(),
+        );
         assert!(result.is_ok());
 
-        // Verify cleared
         let wb = Workbook::open(&file_path).unwrap();
-        let cell_ref = xlex_core::CellRef::new(1, 1); // A1 = col 1, row 1 (1-indexed)
-        let value = wb.get_cell("Sheet1", &cell_ref).unwrap();
-        assert_eq!(value, CellValue::Empty);
+        let value = wb
+            .get_cell("Sheet1", &xlex_core::CellRef::new(4, 3))
+            .unwrap();
+        assert_eq!(value, CellValue::formula("A3*$B$1"));
     }
 
     #[test]
-    fn test_fill_range() {
+    fn test_fill_down_existing_formula() {
         let temp_dir = TempDir::new().unwrap();
-        let file_path = create_test_workbook(&temp_dir, "fill.xlsx");
+        let file_path = create_test_workbook(&temp_dir, "fill_down_existing.xlsx");
+        setup_test_data(&file_path);
+        let mut wb = Workbook::open(&file_path).unwrap();
+        wb.set_cell(
+            "Sheet1",
+            xlex_core::CellRef::new(4, 1),
+            CellValue::formula("SUM(A1:C1)"),
+        )
+        .unwrap();
+        wb.save().unwrap();
 
-        let result = fill(&file_path, "Sheet1", "A1:C3", "test", &default_global());
+        let result = fill_down(&file_path, "Sheet1", "D1:D3", None, &default_global());
         assert!(result.is_ok());
 
-        // Verify fill
         let wb = Workbook::open(&file_path).unwrap();
-        let cell_ref = xlex_core::CellRef::new(1, 1); // A1 = col 1, row 1 (1-indexed)
-        let value = wb.get_cell("Sheet1", &cell_ref).unwrap();
-        assert_eq!(value, CellValue::String("test".to_string()));
+        let value = wb
+            .get_cell("Sheet1", &xlex_core::CellRef::new(4, 2))
+            .unwrap();
+        assert_eq!(value, CellValue::formula("SUM(A2:C2)"));
     }
 
     #[test]
@@ -1535,7 +3214,14 @@ mod tests {
         let file_path = create_test_workbook(&temp_dir, "validate.xlsx");
         setup_test_data(&file_path);
 
-        let result = validate(&file_path, "Sheet1", "A1:C3", "nonempty", &default_global());
+        let result = validate(
+            &file_path,
+            "Sheet1",
+            "A1:C3",
+            "nonempty",
+            FailOn::Errors,
+            &default_global(),
+        );
         assert!(result.is_ok());
     }
 
@@ -1545,7 +3231,14 @@ mod tests {
         let file_path = create_test_workbook(&temp_dir, "validate_num.xlsx");
         setup_test_data(&file_path);
 
-        let result = validate(&file_path, "Sheet1", "A1:C3", "numeric", &default_global());
+        let result = validate(
+            &file_path,
+            "Sheet1",
+            "A1:C3",
+            "numeric",
+            FailOn::Errors,
+            &default_global(),
+        );
         assert!(result.is_ok());
     }
 
@@ -1700,6 +3393,54 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_run_lock_commands() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "run_lock.xlsx");
+        setup_test_data(&file_path);
+
+        let args = RangeArgs {
+            command: RangeCommand::Unlock {
+                file: file_path.clone(),
+                sheet: "Sheet1".to_string(),
+                range: "A1:B2".to_string(),
+            },
+        };
+        run(&args, &default_global()).unwrap();
+        let args = RangeArgs {
+            command: RangeCommand::Lock {
+                file: file_path.clone(),
+                sheet: "Sheet1".to_string(),
+                range: "B2".to_string(),
+                hide_formulas: true,
+            },
+        };
+        run(&args, &default_global()).unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let protection = |cell: &str| {
+            wb.get_cell_style("Sheet1", &CellRef::parse(cell).unwrap())
+                .unwrap()
+                .protection
+        };
+        assert!(!protection("A1").locked);
+        assert!(protection("B2").locked && protection("B2").hidden);
+        assert!(protection("C3").locked && !protection("C3").hidden);
+        assert_eq!(
+            wb.get_cell("Sheet1", &CellRef::new(1, 1)).unwrap(),
+            CellValue::Number(10.0)
+        );
+
+        let args = RangeArgs {
+            command: RangeCommand::Unlock {
+                file: file_path,
+                sheet: "Missing".to_string(),
+                range: "A1".to_string(),
+            },
+        };
+        assert!(run(&args, &default_global()).is_err());
+    }
+
     #[test]
     fn test_run_move_command() {
         let temp_dir = TempDir::new().unwrap();
@@ -1748,7 +3489,9 @@ mod tests {
                 file: file_path,
                 sheet: "Sheet1".to_string(),
                 range: "A1:B2".to_string(),
-                value: "test".to_string(),
+                value: Some("test".to_string()),
+                series: None,
+                fill_down_formula: false,
             },
         };
 
@@ -1883,7 +3626,14 @@ mod tests {
         let mut global = default_global();
         global.format = OutputFormat::Json;
 
-        let result = validate(&file_path, "Sheet1", "A1:C3", "nonempty", &global);
+        let result = validate(
+            &file_path,
+            "Sheet1",
+            "A1:C3",
+            "nonempty",
+            FailOn::Errors,
+            &global,
+        );
         assert!(result.is_ok());
     }
 
@@ -1975,12 +3725,20 @@ mod tests {
                 font_size: Some(12.0),
                 text_color: Some("FF0000".to_string()),
                 bg_color: Some("FFFF00".to_string()),
+                pattern: None,
+                pattern_color: None,
+                gradient: None,
+                gradient_angle: 0.0,
+                gradient_path: false,
                 align: Some("center".to_string()),
                 valign: Some("middle".to_string()),
                 wrap: true,
                 number_format: None,
                 percent: false,
                 currency: None,
+                locale: None,
+                accounting: false,
+                thousands: false,
                 date_format: None,
             },
         };
@@ -2006,6 +3764,9 @@ mod tests {
                 left: false,
                 right: false,
                 none: false,
+                preset: None,
+                diagonal_up: false,
+                diagonal_down: false,
                 style: "thin".to_string(),
                 border_color: Some("000000".to_string()),
             },
@@ -2061,6 +3822,7 @@ mod tests {
                 sheet: "Sheet1".to_string(),
                 range: "A1:C3".to_string(),
                 rule: "nonempty".to_string(),
+                fail_on: FailOn::Errors,
             },
         };
 
@@ -2295,6 +4057,7 @@ mod tests {
             "Sheet1",
             "A1:C3",
             "unknown_rule",
+            FailOn::Errors,
             &default_global(),
         );
         assert!(result.is_err());
@@ -2402,6 +4165,7 @@ mod tests {
             "NonExistentSheet",
             "A1:C3",
             "nonempty",
+            FailOn::Errors,
             &default_global(),
         );
         assert!(result.is_err());
@@ -2666,6 +4430,99 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_range_style_currency_locale() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "style_currency.xlsx");
+
+        let opts = RangeStyleOpts {
+            currency: Some("EUR".to_string()),
+            locale: Some("de-DE".to_string()),
+            ..Default::default()
+        };
+        range_style(&file_path, "Sheet1", "A1", opts, &default_global()).unwrap();
+        let opts = RangeStyleOpts {
+            currency: Some("USD".to_string()),
+            accounting: true,
+            ..Default::default()
+        };
+        range_style(&file_path, "Sheet1", "B1", opts, &default_global()).unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let code = |cell: &str| {
+            wb.get_cell_style("Sheet1", &xlex_core::CellRef::parse(cell).unwrap())
+                .unwrap()
+                .number_format
+                .code
+        };
+        assert_eq!(code("A1").as_deref(), Some("#,##0.00 [$€-407]"));
+        assert!(code("B1").unwrap().starts_with("_([$$-409]* #,##0.00_)"));
+
+        let opts = RangeStyleOpts {
+            thousands: true,
+            locale: Some("xx-XX".to_string()),
+            ..Default::default()
+        };
+        assert!(range_style(&file_path, "Sheet1", "C1", opts, &default_global()).is_err());
+    }
+
+    #[test]
+    fn test_range_style_pattern_and_gradient() {
+        use xlex_core::style::{FillPattern, GradientType};
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "style_fills.xlsx");
+
+        let opts = RangeStyleOpts {
+            pattern: Some("dark-trellis".to_string()),
+            bg_color: Some("FFFF00".to_string()),
+            ..Default::default()
+        };
+        range_style(&file_path, "Sheet1", "A1", opts, &default_global()).unwrap();
+        let opts = RangeStyleOpts {
+            gradient: Some(vec!["FFFFFF".to_string(), "4472C4".to_string()]),
+            gradient_angle: 90.0,
+            ..Default::default()
+        };
+        range_style(&file_path, "Sheet1", "B1", opts, &default_global()).unwrap();
+        let opts = RangeStyleOpts {
+            gradient: Some(vec!["FF0000".to_string(), "0000FF".to_string()]),
+            gradient_path: true,
+            ..Default::default()
+        };
+        range_style(&file_path, "Sheet1", "C1", opts, &default_global()).unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let fill = |cell: &str| {
+            wb.get_cell_style("Sheet1", &xlex_core::CellRef::parse(cell).unwrap())
+                .unwrap()
+                .fill
+        };
+        let a1 = fill("A1");
+        assert_eq!(a1.pattern, FillPattern::DarkTrellis);
+        assert_eq!(
+            a1.fg_color.and_then(|c| c.to_hex()),
+            Some("000000".to_string())
+        );
+        assert_eq!(
+            a1.bg_color.and_then(|c| c.to_hex()),
+            Some("FFFF00".to_string())
+        );
+        let b1 = fill("B1").gradient.unwrap();
+        assert_eq!(b1.degree, 90.0);
+        assert_eq!(b1.stops.len(), 2);
+        assert_eq!(
+            fill("C1").gradient.unwrap().gradient_type,
+            GradientType::Path
+        );
+
+        let opts = RangeStyleOpts {
+            pattern: Some("plaid".to_string()),
+            ..Default::default()
+        };
+        assert!(range_style(&file_path, "Sheet1", "D1", opts, &default_global()).is_err());
+    }
+
     // Range border various styles
 
     #[test]
@@ -2688,6 +4545,80 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_range_border_presets_share_styles() {
+        use xlex_core::style::BorderStyle;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "border_presets.xlsx");
+        let styles_before = Workbook::open(&file_path).unwrap().style_registry().len();
+
+        let opts = RangeBorderOpts {
+            preset: Some(BorderPreset::Grid),
+            style: "thin".to_string(),
+            ..Default::default()
+        };
+        range_border(&file_path, "Sheet1", "A1:D50", opts, &default_global()).unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(wb.style_registry().len(), styles_before + 1);
+        let border = wb
+            .get_cell_style("Sheet1", &xlex_core::CellRef::parse("C20").unwrap())
+            .unwrap()
+            .border;
+        assert_eq!(border.left.style, BorderStyle::Thin);
+        assert_eq!(border.bottom.style, BorderStyle::Thin);
+
+        let opts = RangeBorderOpts {
+            preset: Some(BorderPreset::UnderlineHeader),
+            style: "double".to_string(),
+            ..Default::default()
+        };
+        range_border(&file_path, "Sheet1", "F1:H10", opts, &default_global()).unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        let style_of = |cell: &str| {
+            wb.get_cell_style("Sheet1", &xlex_core::CellRef::parse(cell).unwrap())
+                .unwrap()
+        };
+        assert_eq!(style_of("G1").border.bottom.style, BorderStyle::Double);
+        assert_eq!(style_of("G1").border.top.style, BorderStyle::None);
+        assert_eq!(style_of("G2").border.bottom.style, BorderStyle::None);
+    }
+
+    #[test]
+    fn test_range_border_diagonal_keeps_existing_style() {
+        use xlex_core::style::BorderStyle;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "border_diagonal.xlsx");
+        let opts = RangeStyleOpts {
+            bold: true,
+            ..Default::default()
+        };
+        range_style(&file_path, "Sheet1", "A1:B2", opts, &default_global()).unwrap();
+
+        let opts = RangeBorderOpts {
+            diagonal_down: true,
+            style: "medium".to_string(),
+            border_color: Some("FF0000".to_string()),
+            ..Default::default()
+        };
+        range_border(&file_path, "Sheet1", "A1:B2", opts, &default_global()).unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let style = wb
+            .get_cell_style("Sheet1", &xlex_core::CellRef::parse("B2").unwrap())
+            .unwrap();
+        assert!(style.font.bold);
+        assert!(style.border.diagonal_down);
+        assert!(!style.border.diagonal_up);
+        assert_eq!(style.border.diagonal.style, BorderStyle::Medium);
+        assert_eq!(
+            style.border.diagonal.color.and_then(|c| c.to_hex()),
+            Some("FF0000".to_string())
+        );
+    }
+
     #[test]
     fn test_range_border_outline() {
         let temp_dir = TempDir::new().unwrap();
@@ -2780,8 +4711,29 @@ mod tests {
         let file_path = create_test_workbook(&temp_dir, "validate_fail.xlsx");
 
         // Don't set up test data, so cells are empty
-        let result = validate(&file_path, "Sheet1", "A1:C3", "nonempty", &default_global());
-        assert!(result.is_ok()); // Should succeed but report validation failures
+        let err = validate(
+            &file_path,
+            "Sheet1",
+            "A1:C3",
+            "nonempty",
+            FailOn::Errors,
+            &default_global(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<xlex_core::XlexError>(),
+            Some(xlex_core::XlexError::ValidationFailed { .. })
+        ));
+        // Reported but not failed
+        let result = validate(
+            &file_path,
+            "Sheet1",
+            "A1:C3",
+            "nonempty",
+            FailOn::Never,
+            &default_global(),
+        );
+        assert!(result.is_ok());
     }
 
     #[test]
@@ -2801,8 +4753,15 @@ mod tests {
             wb.save().unwrap();
         }
 
-        let result = validate(&file_path, "Sheet1", "A1:A1", "numeric", &default_global());
-        assert!(result.is_ok()); // Should succeed but report validation failures
+        let result = validate(
+            &file_path,
+            "Sheet1",
+            "A1:A1",
+            "numeric",
+            FailOn::Errors,
+            &default_global(),
+        );
+        assert!(result.is_err());
     }
 
     // Get with text output verbose
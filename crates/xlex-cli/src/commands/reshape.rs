@@ -0,0 +1,393 @@
+//! Reshaping a range between wide and long layouts.
+//!
+//! `reshape unpivot` (also `pivot-longer`) turns a cross-tab such as a
+//! column per month into a row per id and month; `reshape pivot-wider`
+//! does the reverse. The first row of the range is its header, and the
+//! result goes to a new sheet of the same workbook.
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+
+use xlex_core::reshape::{pivot_wider, unpivot};
+use xlex_core::{CellRef, CellValue, Range, Workbook, XlexError};
+
+use super::output::CommandOutput;
+use super::transform::letter_column;
+use super::GlobalOptions;
+
+/// Arguments for reshaping.
+#[derive(Parser)]
+pub struct ReshapeArgs {
+    #[command(subcommand)]
+    pub command: ReshapeCommand,
+}
+
+#[derive(Subcommand)]
+pub enum ReshapeCommand {
+    /// Turn value columns into rows of name and value (wide to long)
+    #[command(alias = "pivot-longer")]
+    Unpivot {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name
+        sheet: String,
+        /// Range to reshape, header row first (e.g. A1:M100)
+        range: String,
+        /// Columns kept on every row, by header text or letter (comma-separated)
+        #[arg(long, value_delimiter = ',', required = true)]
+        id_cols: Vec<String>,
+        /// Header for the column holding the old column names
+        #[arg(long, default_value = "variable")]
+        var_name: String,
+        /// Header for the column holding the values
+        #[arg(long, default_value = "value")]
+        value_name: String,
+        /// Keep rows for blank cells
+        #[arg(long)]
+        keep_blanks: bool,
+        /// Sheet to write the result to
+        #[arg(long, default_value = "Unpivoted")]
+        out_sheet: String,
+        /// Replace the output sheet if it exists
+        #[arg(long, short = 'F')]
+        force: bool,
+    },
+    /// Turn rows of name and value into columns (long to wide)
+    PivotWider {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name
+        sheet: String,
+        /// Range to reshape, header row first
+        range: String,
+        /// Columns identifying an output row, by header text or letter (comma-separated)
+        #[arg(long, value_delimiter = ',', required = true)]
+        id_cols: Vec<String>,
+        /// Column whose values become the new column headers
+        #[arg(long)]
+        names_from: String,
+        /// Column whose values fill the new columns
+        #[arg(long)]
+        values_from: String,
+        /// Sheet to write the result to
+        #[arg(long, default_value = "Pivoted")]
+        out_sheet: String,
+        /// Replace the output sheet if it exists
+        #[arg(long, short = 'F')]
+        force: bool,
+    },
+}
+
+/// Run the reshape command.
+pub fn run(args: &ReshapeArgs, global: &GlobalOptions) -> Result<()> {
+    match &args.command {
+        ReshapeCommand::Unpivot {
+            file,
+            sheet,
+            range,
+            id_cols,
+            var_name,
+            value_name,
+            keep_blanks,
+            out_sheet,
+            force,
+        } => {
+            let target = Target::new(file, sheet, range, out_sheet, *force)?;
+            let ids = target.columns(id_cols)?;
+            target.write(
+                "unpivot",
+                |rows| Ok(unpivot(rows, &ids, var_name, value_name, *keep_blanks)),
+                global,
+            )
+        }
+        ReshapeCommand::PivotWider {
+            file,
+            sheet,
+            range,
+            id_cols,
+            names_from,
+            values_from,
+            out_sheet,
+            force,
+        } => {
+            let target = Target::new(file, sheet, range, out_sheet, *force)?;
+            let ids = target.columns(id_cols)?;
+            let names = target.column(names_from)?;
+            let values = target.column(values_from)?;
+            target.write(
+                "pivot-wider",
+                |rows| Ok(pivot_wider(rows, &ids, names, values)?),
+                global,
+            )
+        }
+    }
+}
+
+/// The range being reshaped and where the result goes.
+struct Target<'a> {
+    file: &'a std::path::Path,
+    sheet: &'a str,
+    range: Range,
+    out_sheet: &'a str,
+    force: bool,
+    workbook: Workbook,
+    /// The range's rows, skipping blank ones, header first
+    rows: Vec<Vec<CellValue>>,
+}
+
+impl<'a> Target<'a> {
+    fn new(
+        file: &'a std::path::Path,
+        sheet: &'a str,
+        range: &str,
+        out_sheet: &'a str,
+        force: bool,
+    ) -> Result<Self> {
+        if out_sheet == sheet {
+            anyhow::bail!("The output sheet must differ from the sheet being reshaped");
+        }
+        let range = Range::parse(range)?;
+        let workbook = Workbook::open(file)?;
+        let source = workbook
+            .get_sheet(sheet)
+            .ok_or_else(|| XlexError::SheetNotFound {
+                name: sheet.to_string(),
+            })?;
+        if workbook.get_sheet(out_sheet).is_some() && !force {
+            return Err(XlexError::SheetAlreadyExists {
+                name: out_sheet.to_string(),
+            }
+            .into());
+        }
+        let rows = (range.start.row..=range.end.row)
+            .map(|r| {
+                (range.start.col..=range.end.col)
+                    .map(|c| source.get_value(&CellRef::new(c, r)))
+                    .collect::<Vec<_>>()
+            })
+            .enumerate()
+            .filter(|(i, row)| *i == 0 || row.iter().any(|v| !v.is_empty()))
+            .map(|(_, row)| row)
+            .collect();
+        Ok(Self {
+            file,
+            sheet,
+            range,
+            out_sheet,
+            force,
+            workbook,
+            rows,
+        })
+    }
+
+    /// Finds a column by header text, or by letter within the range, as a
+    /// 0-based index into the rows.
+    fn column(&self, spec: &str) -> Result<usize> {
+        let header = self.rows.first().map(Vec::as_slice).unwrap_or_default();
+        header
+            .iter()
+            .position(|v| v.to_display_string() == spec)
+            .or_else(|| {
+                letter_column(spec)
+                    .filter(|c| (self.range.start.col..=self.range.end.col).contains(c))
+                    .map(|c| (c - self.range.start.col) as usize)
+            })
+            .ok_or_else(|| anyhow::anyhow!("No column '{}' in {}", spec, self.range.to_a1()))
+    }
+
+    fn columns(&self, specs: &[String]) -> Result<Vec<usize>> {
+        specs.iter().map(|spec| self.column(spec.trim())).collect()
+    }
+
+    /// Reshapes the rows with `reshape` and writes them to the output sheet.
+    fn write(
+        mut self,
+        action: &'static str,
+        reshape: impl FnOnce(&[Vec<CellValue>]) -> Result<Vec<Vec<CellValue>>>,
+        global: &GlobalOptions,
+    ) -> Result<()> {
+        let result = reshape(&self.rows)?;
+        let rows_written = result.len().saturating_sub(1);
+        let columns = result.iter().map(Vec::len).max().unwrap_or(0);
+
+        if global.dry_run {
+            outln!(
+                "Would {} {} of {} into {} ({} rows)",
+                action,
+                self.range.to_a1(),
+                self.sheet,
+                self.out_sheet,
+                rows_written
+            );
+            return Ok(());
+        }
+
+        if self.force && self.workbook.get_sheet(self.out_sheet).is_some() {
+            self.workbook.remove_sheet(self.out_sheet)?;
+        }
+        self.workbook.add_sheet(self.out_sheet)?;
+        for (r, row) in result.into_iter().enumerate() {
+            for (c, value) in row.into_iter().enumerate() {
+                if !value.is_empty() {
+                    let cell_ref = CellRef::new(c as u32 + 1, r as u32 + 1);
+                    self.workbook.set_cell(self.out_sheet, cell_ref, value)?;
+                }
+            }
+        }
+        self.workbook.save()?;
+
+        CommandOutput::new(action)
+            .file(self.file)
+            .target("sheet", self.sheet)
+            .target("range", self.range.to_a1())
+            .field("outSheet", self.out_sheet)
+            .field("rowsWritten", rows_written)
+            .field("columns", columns)
+            .print(
+                global,
+                format!(
+                    "Wrote {} rows of {} columns to {}",
+                    rows_written.to_string().green(),
+                    columns,
+                    self.out_sheet.cyan()
+                ),
+            )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::OutputFormat;
+    use tempfile::TempDir;
+
+    fn default_global() -> GlobalOptions {
+        GlobalOptions {
+            quiet: true,
+            verbose: false,
+            format: OutputFormat::Text,
+            no_color: true,
+            color: false,
+            json_errors: false,
+            dry_run: false,
+            output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
+        }
+    }
+
+    /// A cross-tab in B2:E4 with a blank row below it.
+    fn cross_tab(dir: &TempDir) -> std::path::PathBuf {
+        let path = dir.path().join("sales.xlsx");
+        let mut wb = Workbook::with_sheets(&["Sheet1"]);
+        let rows: [[&str; 4]; 3] = [
+            ["region", "rep", "jan", "feb"],
+            ["north", "ann", "5", ""],
+            ["south", "bo", "1", "2"],
+        ];
+        for (r, row) in rows.iter().enumerate() {
+            for (c, value) in row.iter().enumerate() {
+                let value = match value.parse() {
+                    Ok(n) => CellValue::Number(n),
+                    Err(_) if value.is_empty() => continue,
+                    Err(_) => CellValue::string(*value),
+                };
+                let cell_ref = CellRef::new(c as u32 + 2, r as u32 + 2);
+                wb.set_cell("Sheet1", cell_ref, value).unwrap();
+            }
+        }
+        wb.save_as(&path).unwrap();
+        path
+    }
+
+    fn unpivot_args(file: &std::path::Path, id_cols: &[&str]) -> ReshapeArgs {
+        ReshapeArgs {
+            command: ReshapeCommand::Unpivot {
+                file: file.to_path_buf(),
+                sheet: "Sheet1".to_string(),
+                range: "B2:E6".to_string(),
+                id_cols: id_cols.iter().map(|c| c.to_string()).collect(),
+                var_name: "month".to_string(),
+                value_name: "amount".to_string(),
+                keep_blanks: false,
+                out_sheet: "Long".to_string(),
+                force: false,
+            },
+        }
+    }
+
+    fn sheet_rows(file: &std::path::Path, sheet: &str) -> Vec<Vec<String>> {
+        let wb = Workbook::open(file).unwrap();
+        let sheet = wb.get_sheet(sheet).unwrap();
+        let (cols, rows) = sheet.dimensions();
+        (1..=rows)
+            .map(|r| {
+                (1..=cols)
+                    .map(|c| sheet.get_value(&CellRef::new(c, r)).to_display_string())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_unpivot_and_pivot_wider() {
+        let dir = TempDir::new().unwrap();
+        let file = cross_tab(&dir);
+        run(&unpivot_args(&file, &["B", "rep"]), &default_global()).unwrap();
+        assert_eq!(
+            sheet_rows(&file, "Long"),
+            vec![
+                vec!["region", "rep", "month", "amount"],
+                vec!["north", "ann", "jan", "5"],
+                vec!["south", "bo", "jan", "1"],
+                vec!["south", "bo", "feb", "2"],
+            ]
+        );
+
+        let wider = ReshapeArgs {
+            command: ReshapeCommand::PivotWider {
+                file: file.clone(),
+                sheet: "Long".to_string(),
+                range: "A1:D4".to_string(),
+                id_cols: vec!["region".to_string(), "rep".to_string()],
+                names_from: "month".to_string(),
+                values_from: "D".to_string(),
+                out_sheet: "Wide".to_string(),
+                force: false,
+            },
+        };
+        run(&wider, &default_global()).unwrap();
+        assert_eq!(
+            sheet_rows(&file, "Wide"),
+            vec![
+                vec!["region", "rep", "jan", "feb"],
+                vec!["north", "ann", "5", ""],
+                vec!["south", "bo", "1", "2"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reshape_errors() {
+        let dir = TempDir::new().unwrap();
+        let file = cross_tab(&dir);
+        let err = run(&unpivot_args(&file, &["A"]), &default_global()).unwrap_err();
+        assert!(err.to_string().contains("No column 'A'"));
+
+        run(&unpivot_args(&file, &["B"]), &default_global()).unwrap();
+        let err = run(&unpivot_args(&file, &["B"]), &default_global()).unwrap_err();
+        assert!(err.to_string().contains("Long"));
+
+        let mut replace = unpivot_args(&file, &["B", "C"]);
+        if let ReshapeCommand::Unpivot { force, .. } = &mut replace.command {
+            *force = true;
+        }
+        run(&replace, &default_global()).unwrap();
+        assert_eq!(sheet_rows(&file, "Long")[0].len(), 4);
+    }
+}
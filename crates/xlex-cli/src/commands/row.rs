@@ -4,8 +4,10 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use colored::Colorize;
 
-use xlex_core::Workbook;
+use xlex_core::units::Length;
+use xlex_core::{CellRef, CellValue, LazyWorkbook, Range, StreamRow, Workbook};
 
+use super::output::{html_table, CommandOutput};
 use super::{GlobalOptions, OutputFormat};
 
 /// Arguments for row operations.
@@ -18,14 +20,7 @@ pub struct RowArgs {
 #[derive(Subcommand)]
 pub enum RowCommand {
     /// Get row data
-    Get {
-        /// Path to the xlsx file
-        file: std::path::PathBuf,
-        /// Sheet name
-        sheet: String,
-        /// Row number (1-indexed)
-        row: u32,
-    },
+    Get(GetArgs),
     /// Append a row
     Append {
         /// Path to the xlsx file
@@ -83,8 +78,8 @@ pub enum RowCommand {
         sheet: String,
         /// Row number (1-indexed)
         row: u32,
-        /// Height in points (omit to show current)
-        height: Option<f64>,
+        /// Height in points, or with a unit: 30pt, 40px (omit to show current)
+        height: Option<Length>,
     },
     /// Hide a row
     Hide {
@@ -104,6 +99,20 @@ pub enum RowCommand {
         /// Row number (1-indexed)
         row: u32,
     },
+    /// Set, clear or show the default style of a whole row
+    Style {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name
+        sheet: String,
+        /// Row number (1-indexed)
+        row: u32,
+        /// Style ID (omit to show current)
+        style_id: Option<u32>,
+        /// Remove the row's default style
+        #[arg(long, conflicts_with = "style_id")]
+        clear: bool,
+    },
     /// Find rows matching criteria
     Find {
         /// Path to the xlsx file
@@ -118,10 +127,39 @@ pub enum RowCommand {
     },
 }
 
+/// Arguments for `row get`.
+#[derive(Parser)]
+pub struct GetArgs {
+    /// Path to the xlsx file
+    pub file: std::path::PathBuf,
+    /// Sheet name
+    pub sheet: String,
+    /// Row number or range (1-indexed), e.g. 5 or 5:25
+    pub rows: String,
+    /// Columns to include, e.g. A:D,G
+    #[arg(long, short = 'c')]
+    pub columns: Option<String>,
+    /// Prepend the header row (row 1)
+    #[arg(long)]
+    pub with_header: bool,
+    /// Shape of JSON rows
+    #[arg(long = "as", value_enum, default_value = "cells")]
+    pub shape: RowShape,
+}
+
+/// Shape of JSON rows returned by `row get`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum RowShape {
+    /// Cells with reference, value and type
+    Cells,
+    /// Objects keyed by header names
+    Objects,
+}
+
 /// Run row operations.
 pub fn run(args: &RowArgs, global: &GlobalOptions) -> Result<()> {
     match &args.command {
-        RowCommand::Get { file, sheet, row } => get(file, sheet, *row, global),
+        RowCommand::Get(args) => get(args, global),
         RowCommand::Append {
             file,
             sheet,
@@ -146,9 +184,22 @@ pub fn run(args: &RowArgs, global: &GlobalOptions) -> Result<()> {
             sheet,
             row,
             height: row_height,
-        } => height(file, sheet, *row, *row_height, global),
+        } => height(
+            file,
+            sheet,
+            *row,
+            row_height.map(Length::to_row_height).transpose()?,
+            global,
+        ),
         RowCommand::Hide { file, sheet, row } => hide(file, sheet, *row, global),
         RowCommand::Unhide { file, sheet, row } => unhide(file, sheet, *row, global),
+        RowCommand::Style {
+            file,
+            sheet,
+            row,
+            style_id,
+            clear,
+        } => style(file, sheet, *row, *style_id, *clear, global),
         RowCommand::Find {
             file,
             sheet,
@@ -158,44 +209,247 @@ pub fn run(args: &RowArgs, global: &GlobalOptions) -> Result<()> {
     }
 }
 
-fn get(file: &std::path::Path, sheet: &str, row: u32, global: &GlobalOptions) -> Result<()> {
-    let workbook = Workbook::open(file)?;
-    let sheet_obj =
-        workbook
-            .get_sheet(sheet)
-            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
-                name: sheet.to_string(),
-            })?;
+/// Parses a row number or range such as `5` or `5:25`.
+fn parse_row_span(spec: &str) -> Result<(u32, u32)> {
+    let parse = |s: &str| -> Result<u32> {
+        match s.trim().parse::<u32>() {
+            Ok(n) if n > 0 => Ok(n),
+            _ => anyhow::bail!("Invalid row number: '{}'", s),
+        }
+    };
+    let (first, last) = match spec.split_once(':') {
+        Some((first, last)) => (parse(first)?, parse(last)?),
+        None => {
+            let row = parse(spec)?;
+            (row, row)
+        }
+    };
+    if first > last {
+        anyhow::bail!("Invalid row range '{}': {} is after {}", spec, first, last);
+    }
+    Ok((first, last))
+}
 
-    // Collect cells in this row
-    let mut row_cells: Vec<_> = sheet_obj
-        .cells()
-        .filter(|c| c.reference.row == row)
+/// Parses a column list such as `A:D,G` into 1-based column numbers.
+fn parse_column_list(spec: &str) -> Result<Vec<u32>> {
+    let column = |s: &str| {
+        xlex_core::CellRef::col_from_letters_pub(&s.trim().to_uppercase())
+            .ok_or_else(|| anyhow::anyhow!("Invalid column: '{}'", s))
+    };
+    let mut columns = Vec::new();
+    for part in spec.split(',').filter(|p| !p.trim().is_empty()) {
+        match part.split_once(':') {
+            Some((first, last)) => {
+                let (first, last) = (column(first)?, column(last)?);
+                columns.extend(first.min(last)..=first.max(last));
+            }
+            None => columns.push(column(part)?),
+        }
+    }
+    if columns.is_empty() {
+        anyhow::bail!("No columns in '{}'", spec);
+    }
+    Ok(columns)
+}
+
+/// Rows picked by `row get`, with cells limited to the selected columns.
+struct RowSelection {
+    /// Column order for tabular output
+    columns: Vec<u32>,
+    /// Header row cells, when requested
+    header: Option<Vec<(u32, CellValue)>>,
+    /// Selected rows in order
+    rows: Vec<(u32, Vec<(u32, CellValue)>)>,
+}
+
+/// Streams the sheet and keeps the rows and columns `args` asks for.
+fn select_rows(args: &GetArgs) -> Result<RowSelection> {
+    let (first, last) = parse_row_span(&args.rows)?;
+    let wanted = args.columns.as_deref().map(parse_column_list).transpose()?;
+    let need_header = args.with_header || args.shape == RowShape::Objects;
+
+    let workbook = LazyWorkbook::open(&args.file)?;
+    let keep = |row: StreamRow| -> Vec<(u32, CellValue)> {
+        row.cells
+            .into_iter()
+            .filter(|(r, _)| wanted.as_ref().map_or(true, |cols| cols.contains(&r.col)))
+            .map(|(r, value)| (r.col, value))
+            .collect()
+    };
+
+    let header = if need_header {
+        let first_row = workbook.first_row(&args.sheet)?;
+        Some(
+            first_row
+                .filter(|row| row.row_number == 1)
+                .map_or_else(Vec::new, keep),
+        )
+    } else {
+        None
+    };
+    // Reads only the span, starting from the sheet's row index
+    let span = Range::new(CellRef::new(1, first), CellRef::new(CellRef::MAX_COL, last));
+    let mut rows: Vec<_> = workbook
+        .stream_rows_in(&args.sheet, &span)?
+        .into_iter()
+        .map(|row| (row.row_number, keep(row)))
         .collect();
-    row_cells.sort_by_key(|c| c.reference.col);
+    // The header row is shown once, ahead of the data
+    if args.shape == RowShape::Objects || args.with_header {
+        rows.retain(|(number, _)| *number != 1);
+    }
+
+    let columns = match wanted {
+        Some(cols) => {
+            let mut seen = Vec::new();
+            for col in cols {
+                if !seen.contains(&col) {
+                    seen.push(col);
+                }
+            }
+            seen
+        }
+        None => {
+            let mut cols: Vec<u32> = header
+                .iter()
+                .flatten()
+                .chain(rows.iter().flat_map(|(_, cells)| cells))
+                .map(|(col, _)| *col)
+                .collect();
+            cols.sort_unstable();
+            cols.dedup();
+            cols
+        }
+    };
 
-    if global.format == OutputFormat::Json {
-        let values: Vec<_> = row_cells
+    Ok(RowSelection {
+        columns,
+        header,
+        rows,
+    })
+}
+
+fn get(args: &GetArgs, global: &GlobalOptions) -> Result<()> {
+    let selection = select_rows(args)?;
+    let value_at = |cells: &[(u32, CellValue)], col: u32| {
+        cells
+            .iter()
+            .find(|(c, _)| *c == col)
+            .map(|(_, v)| v.clone())
+            .unwrap_or(CellValue::Empty)
+    };
+    let header_rows = selection
+        .header
+        .as_ref()
+        .filter(|_| args.with_header)
+        .map(|cells| (1, cells.clone()));
+
+    if global.format == OutputFormat::Json && args.shape == RowShape::Objects {
+        let header = selection.header.as_deref().unwrap_or_default();
+        let keys: Vec<String> = selection
+            .columns
             .iter()
-            .map(|c| {
-                serde_json::json!({
-                    "col": c.reference.col,
-                    "ref": c.reference.to_a1(),
-                    "value": c.value.to_display_string(),
-                    "type": c.value.type_name(),
+            .map(|&col| match value_at(header, col).to_display_string() {
+                name if name.is_empty() => xlex_core::CellRef::col_to_letters(col),
+                name => name,
+            })
+            .collect();
+        let objects: Vec<serde_json::Value> = selection
+            .rows
+            .iter()
+            .map(|(_, cells)| {
+                let object: serde_json::Map<String, serde_json::Value> = keys
+                    .iter()
+                    .zip(&selection.columns)
+                    .map(|(key, &col)| (key.clone(), value_at(cells, col).into()))
+                    .collect();
+                serde_json::Value::Object(object)
+            })
+            .collect();
+        outln!("{}", serde_json::to_string_pretty(&objects)?);
+    } else if global.format == OutputFormat::Json {
+        let cells_json = |row: u32, cells: &[(u32, CellValue)]| -> Vec<serde_json::Value> {
+            cells
+                .iter()
+                .map(|(col, value)| {
+                    serde_json::json!({
+                        "col": col,
+                        "ref": xlex_core::CellRef::new(*col, row).to_a1(),
+                        "value": value.to_display_string(),
+                        "type": value.type_name(),
+                    })
                 })
+                .collect()
+        };
+        let single = parse_row_span(&args.rows)?;
+        if single.0 == single.1 && header_rows.is_none() {
+            // A single row keeps the plain list of cells
+            let cells = selection
+                .rows
+                .first()
+                .map(|(row, cells)| cells_json(*row, cells))
+                .unwrap_or_default();
+            outln!("{}", serde_json::to_string_pretty(&cells)?);
+        } else {
+            let rows: Vec<serde_json::Value> = header_rows
+                .iter()
+                .chain(&selection.rows)
+                .map(|(row, cells)| serde_json::json!({"row": row, "cells": cells_json(*row, cells)}))
+                .collect();
+            outln!("{}", serde_json::to_string_pretty(&rows)?);
+        }
+    } else if global.format == OutputFormat::HtmlTable {
+        // The header row heads the columns instead of letters
+        let header = header_rows.as_ref().map(|(_, cells)| cells.as_slice());
+        let columns: Vec<String> = selection
+            .columns
+            .iter()
+            .map(|&col| {
+                let name = header
+                    .map(|cells| value_at(cells, col).to_display_string())
+                    .unwrap_or_default();
+                if name.is_empty() {
+                    xlex_core::CellRef::col_to_letters(col)
+                } else {
+                    name
+                }
             })
             .collect();
-        println!("{}", serde_json::to_string_pretty(&values)?);
-    } else if global.format == OutputFormat::Csv {
-        let values: Vec<_> = row_cells
+        let rows: Vec<(u32, Vec<String>)> = selection
+            .rows
             .iter()
-            .map(|c| c.value.to_display_string())
+            .map(|(row, cells)| {
+                let values = selection
+                    .columns
+                    .iter()
+                    .map(|&col| value_at(cells, col).to_display_string())
+                    .collect();
+                (*row, values)
+            })
             .collect();
-        println!("{}", values.join(","));
+        outln!("{}", html_table(&columns, &rows));
+    } else if global.format == OutputFormat::Csv {
+        for (_, cells) in header_rows.iter().chain(&selection.rows) {
+            let values: Vec<String> = selection
+                .columns
+                .iter()
+                .map(|&col| {
+                    let value = value_at(cells, col).to_display_string();
+                    if value.contains(',') || value.contains('\n') || value.contains('"') {
+                        format!("\"{}\"", value.replace('"', "\"\""))
+                    } else {
+                        value
+                    }
+                })
+                .collect();
+            outln!("{}", values.join(","));
+        }
     } else {
-        for cell in row_cells {
-            println!("{}: {}", cell.reference.to_a1().cyan(), cell.value);
+        for (row, cells) in header_rows.iter().chain(&selection.rows) {
+            for (col, value) in cells {
+                let reference = xlex_core::CellRef::new(*col, *row).to_a1();
+                outln!("{}: {}", reference.cyan(), value);
+            }
         }
     }
 
@@ -204,10 +458,12 @@ fn get(file: &std::path::Path, sheet: &str, row: u32, global: &GlobalOptions) ->
 
 fn append(file: &std::path::Path, sheet: &str, values: &str, global: &GlobalOptions) -> Result<()> {
     if global.dry_run {
-        println!("Would append row with values: {}", values);
+        outln!("Would append row with values: {}", values);
         return Ok(());
     }
 
+    let output = CommandOutput::new("append");
+
     let mut workbook = Workbook::open(file)?;
     let sheet_obj =
         workbook
@@ -236,19 +492,27 @@ fn append(file: &std::path::Path, sheet: &str, values: &str, global: &GlobalOpti
     let _ = sheet_obj;
     workbook.save()?;
 
-    if !global.quiet {
-        println!("Appended row {}", new_row.to_string().green());
-    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("row", new_row)
+        .count("cells", cell_values.len())
+        .print(
+            global,
+            format!("Appended row {}", new_row.to_string().green()),
+        )?;
 
     Ok(())
 }
 
 fn insert(file: &std::path::Path, sheet: &str, row: u32, global: &GlobalOptions) -> Result<()> {
     if global.dry_run {
-        println!("Would insert row at position {}", row);
+        outln!("Would insert row at position {}", row);
         return Ok(());
     }
 
+    let output = CommandOutput::new("insert");
+
     let mut workbook = Workbook::open(file)?;
     let sheet_obj =
         workbook
@@ -261,19 +525,26 @@ fn insert(file: &std::path::Path, sheet: &str, row: u32, global: &GlobalOptions)
     let _ = sheet_obj;
     workbook.save()?;
 
-    if !global.quiet {
-        println!("Inserted row at position {}", row.to_string().green());
-    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("row", row)
+        .print(
+            global,
+            format!("Inserted row at position {}", row.to_string().green()),
+        )?;
 
     Ok(())
 }
 
 fn delete(file: &std::path::Path, sheet: &str, row: u32, global: &GlobalOptions) -> Result<()> {
     if global.dry_run {
-        println!("Would delete row {}", row);
+        outln!("Would delete row {}", row);
         return Ok(());
     }
 
+    let output = CommandOutput::new("delete");
+
     let mut workbook = Workbook::open(file)?;
     let sheet_obj =
         workbook
@@ -286,9 +557,11 @@ fn delete(file: &std::path::Path, sheet: &str, row: u32, global: &GlobalOptions)
     let _ = sheet_obj;
     workbook.save()?;
 
-    if !global.quiet {
-        println!("Deleted row {}", row.to_string().green());
-    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("row", row)
+        .print(global, format!("Deleted row {}", row.to_string().green()))?;
 
     Ok(())
 }
@@ -301,10 +574,12 @@ fn copy(
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!("Would copy row {} to row {}", source, dest);
+        outln!("Would copy row {} to row {}", source, dest);
         return Ok(());
     }
 
+    let output = CommandOutput::new("copy");
+
     let mut workbook = Workbook::open(file)?;
 
     // Get source row data
@@ -323,6 +598,7 @@ fn copy(
         .collect();
 
     let source_height = sheet_obj.get_row_height(source);
+    let cells = source_cells.len();
     let _ = sheet_obj;
 
     // Insert a new row at destination
@@ -349,13 +625,20 @@ fn copy(
     let _ = sheet_obj;
     workbook.save()?;
 
-    if !global.quiet {
-        println!(
-            "Copied row {} to row {}",
-            source.to_string().cyan(),
-            dest.to_string().green()
-        );
-    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("source", source)
+        .target("destination", dest)
+        .count("cells", cells)
+        .print(
+            global,
+            format!(
+                "Copied row {} to row {}",
+                source.to_string().cyan(),
+                dest.to_string().green()
+            ),
+        )?;
 
     Ok(())
 }
@@ -368,10 +651,12 @@ fn move_row(
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!("Would move row {} to row {}", source, dest);
+        outln!("Would move row {} to row {}", source, dest);
         return Ok(());
     }
 
+    let output = CommandOutput::new("move");
+
     let mut workbook = Workbook::open(file)?;
 
     // Get source row data
@@ -390,6 +675,7 @@ fn move_row(
         .collect();
 
     let source_height = sheet_obj.get_row_height(source);
+    let cells = source_cells.len();
     let _ = sheet_obj;
 
     let sheet_obj =
@@ -436,13 +722,20 @@ fn move_row(
     let _ = sheet_obj;
     workbook.save()?;
 
-    if !global.quiet {
-        println!(
-            "Moved row {} to row {}",
-            source.to_string().cyan(),
-            dest.to_string().green()
-        );
-    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("source", source)
+        .target("destination", dest)
+        .count("cells", cells)
+        .print(
+            global,
+            format!(
+                "Moved row {} to row {}",
+                source.to_string().cyan(),
+                dest.to_string().green()
+            ),
+        )?;
 
     Ok(())
 }
@@ -456,10 +749,11 @@ fn height(
 ) -> Result<()> {
     if let Some(h) = height {
         if global.dry_run {
-            println!("Would set row {} height to {}", row, h);
+            outln!("Would set row {} height to {}", row, h);
             return Ok(());
         }
 
+        let output = CommandOutput::new("height");
         let mut workbook = Workbook::open(file)?;
         let sheet_obj =
             workbook
@@ -472,9 +766,15 @@ fn height(
         let _ = sheet_obj;
         workbook.save()?;
 
-        if !global.quiet {
-            println!("Set row {} height to {}", row.to_string().cyan(), h);
-        }
+        output
+            .file(file)
+            .target("sheet", sheet)
+            .target("row", row)
+            .field("height", h)
+            .print(
+                global,
+                format!("Set row {} height to {}", row.to_string().cyan(), h),
+            )?;
     } else {
         let workbook = Workbook::open(file)?;
         let sheet_obj =
@@ -484,10 +784,17 @@ fn height(
                     name: sheet.to_string(),
                 })?;
 
-        if let Some(h) = sheet_obj.get_row_height(row) {
-            println!("{}", h);
+        let height = sheet_obj.get_row_height(row);
+        if global.format == OutputFormat::Json {
+            let json = serde_json::json!({
+                "row": row,
+                "height": height,
+            });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else if let Some(h) = height {
+            outln!("{}", h);
         } else {
-            println!("default");
+            outln!("default");
         }
     }
 
@@ -496,10 +803,12 @@ fn height(
 
 fn hide(file: &std::path::Path, sheet: &str, row: u32, global: &GlobalOptions) -> Result<()> {
     if global.dry_run {
-        println!("Would hide row {}", row);
+        outln!("Would hide row {}", row);
         return Ok(());
     }
 
+    let output = CommandOutput::new("hide");
+
     let mut workbook = Workbook::open(file)?;
     let sheet_obj =
         workbook
@@ -512,19 +821,89 @@ fn hide(file: &std::path::Path, sheet: &str, row: u32, global: &GlobalOptions) -
     let _ = sheet_obj;
     workbook.save()?;
 
-    if !global.quiet {
-        println!("Hid row {}", row.to_string().dimmed());
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("row", row)
+        .print(global, format!("Hid row {}", row.to_string().dimmed()))?;
+
+    Ok(())
+}
+
+fn style(
+    file: &std::path::Path,
+    sheet: &str,
+    row: u32,
+    style_id: Option<u32>,
+    clear: bool,
+    global: &GlobalOptions,
+) -> Result<()> {
+    if style_id.is_none() && !clear {
+        let workbook = Workbook::open(file)?;
+        let sheet_obj =
+            workbook
+                .get_sheet(sheet)
+                .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                    name: sheet.to_string(),
+                })?;
+
+        let style_id = sheet_obj.get_row_style(row);
+        if global.format == OutputFormat::Json {
+            let json = serde_json::json!({
+                "row": row,
+                "styleId": style_id,
+            });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else if let Some(id) = style_id {
+            outln!("{}", id);
+        } else {
+            outln!("none");
+        }
+        return Ok(());
     }
 
+    if global.dry_run {
+        match style_id {
+            Some(id) => outln!("Would set row {} style to {}", row, id),
+            None => outln!("Would clear row {} style", row),
+        }
+        return Ok(());
+    }
+
+    let output = CommandOutput::new("style");
+    let mut workbook = Workbook::open(file)?;
+    if let Some(id) = style_id {
+        super::style::ensure_style_exists(&workbook, id)?;
+    }
+    workbook.with_sheet_mut(sheet, |sheet_obj| sheet_obj.set_row_style(row, style_id))?;
+    workbook.save()?;
+
+    let message = match style_id {
+        Some(id) => format!(
+            "Set row {} style to {}",
+            row.to_string().cyan(),
+            id.to_string().cyan()
+        ),
+        None => format!("Cleared row {} style", row.to_string().cyan()),
+    };
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("row", row)
+        .field("styleId", style_id)
+        .print(global, message)?;
+
     Ok(())
 }
 
 fn unhide(file: &std::path::Path, sheet: &str, row: u32, global: &GlobalOptions) -> Result<()> {
     if global.dry_run {
-        println!("Would unhide row {}", row);
+        outln!("Would unhide row {}", row);
         return Ok(());
     }
 
+    let output = CommandOutput::new("unhide");
+
     let mut workbook = Workbook::open(file)?;
     let sheet_obj =
         workbook
@@ -537,9 +916,11 @@ fn unhide(file: &std::path::Path, sheet: &str, row: u32, global: &GlobalOptions)
     let _ = sheet_obj;
     workbook.save()?;
 
-    if !global.quiet {
-        println!("Unhid row {}", row.to_string().green());
-    }
+    output
+        .file(file)
+        .target("sheet", sheet)
+        .target("row", row)
+        .print(global, format!("Unhid row {}", row.to_string().green()))?;
 
     Ok(())
 }
@@ -587,10 +968,10 @@ fn find(
             "matches": matches,
             "count": matches.len(),
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else {
         for row in matches {
-            println!("{}", row);
+            outln!("{}", row);
         }
     }
 
@@ -613,6 +994,11 @@ mod tests {
             json_errors: false,
             dry_run: false,
             output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
         }
     }
 
@@ -636,13 +1022,24 @@ mod tests {
         wb.save().unwrap();
     }
 
+    fn get_args(file: &std::path::Path, sheet: &str, rows: &str) -> GetArgs {
+        GetArgs {
+            file: file.to_path_buf(),
+            sheet: sheet.to_string(),
+            rows: rows.to_string(),
+            columns: None,
+            with_header: false,
+            shape: RowShape::Cells,
+        }
+    }
+
     #[test]
     fn test_get_row() {
         let temp_dir = TempDir::new().unwrap();
         let file_path = create_test_workbook(&temp_dir, "get.xlsx");
         setup_test_data(&file_path);
 
-        let result = get(&file_path, "Sheet1", 1, &default_global());
+        let result = get(&get_args(&file_path, "Sheet1", "1"), &default_global());
         assert!(result.is_ok());
     }
 
@@ -655,7 +1052,7 @@ mod tests {
         let mut global = default_global();
         global.format = OutputFormat::Json;
 
-        let result = get(&file_path, "Sheet1", 1, &global);
+        let result = get(&get_args(&file_path, "Sheet1", "1"), &global);
         assert!(result.is_ok());
     }
 
@@ -668,10 +1065,62 @@ mod tests {
         let mut global = default_global();
         global.format = OutputFormat::Csv;
 
-        let result = get(&file_path, "Sheet1", 1, &global);
+        let result = get(&get_args(&file_path, "Sheet1", "1"), &global);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_parse_row_span_and_columns() {
+        assert_eq!(parse_row_span("5").unwrap(), (5, 5));
+        assert_eq!(parse_row_span("5:25").unwrap(), (5, 25));
+        assert!(parse_row_span("0").is_err());
+        assert!(parse_row_span("9:3").is_err());
+        assert_eq!(parse_column_list("A:D,G").unwrap(), vec![1, 2, 3, 4, 7]);
+        assert_eq!(parse_column_list("c").unwrap(), vec![3]);
+        assert!(parse_column_list("A:1").is_err());
+    }
+
+    #[test]
+    fn test_select_rows() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "select.xlsx");
+        setup_test_data(&file_path);
+
+        let mut args = get_args(&file_path, "Sheet1", "2:3");
+        args.columns = Some("A,C".to_string());
+        args.with_header = true;
+        let selection = select_rows(&args).unwrap();
+        assert_eq!(selection.columns, vec![1, 3]);
+        assert_eq!(
+            selection.header,
+            Some(vec![
+                (1, CellValue::Number(11.0)),
+                (3, CellValue::Number(13.0))
+            ])
+        );
+        let rows: Vec<u32> = selection.rows.iter().map(|(row, _)| *row).collect();
+        assert_eq!(rows, vec![2, 3]);
+        assert_eq!(
+            selection.rows[1].1,
+            vec![(1, CellValue::Number(31.0)), (3, CellValue::Number(33.0))]
+        );
+
+        // The header row is not repeated as data
+        args.rows = "1:2".to_string();
+        args.shape = RowShape::Objects;
+        let selection = select_rows(&args).unwrap();
+        let rows: Vec<u32> = selection.rows.iter().map(|(row, _)| *row).collect();
+        assert_eq!(rows, vec![2]);
+
+        let mut global = default_global();
+        global.format = OutputFormat::Json;
+        assert!(get(&args, &global).is_ok());
+        global.format = OutputFormat::Csv;
+        assert!(get(&args, &global).is_ok());
+        global.format = OutputFormat::HtmlTable;
+        assert!(get(&args, &global).is_ok());
+    }
+
     #[test]
     fn test_append_row() {
         let temp_dir = TempDir::new().unwrap();
@@ -826,11 +1275,7 @@ mod tests {
         setup_test_data(&file_path);
 
         let args = RowArgs {
-            command: RowCommand::Get {
-                file: file_path,
-                sheet: "Sheet1".to_string(),
-                row: 1,
-            },
+            command: RowCommand::Get(get_args(&file_path, "Sheet1", "1")),
         };
 
         let result = run(&args, &default_global());
@@ -938,7 +1383,7 @@ mod tests {
                 file: file_path,
                 sheet: "Sheet1".to_string(),
                 row: 1,
-                height: Some(30.0),
+                height: Some(Length::Native(30.0)),
             },
         };
 
@@ -946,6 +1391,29 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_run_height_with_unit() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "run_height_unit.xlsx");
+
+        let height = |value: &str| RowArgs {
+            command: RowCommand::Height {
+                file: file_path.clone(),
+                sheet: "Sheet1".to_string(),
+                row: 2,
+                height: Some(value.parse().unwrap()),
+            },
+        };
+        run(&height("40px"), &default_global()).unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(
+            wb.get_sheet("Sheet1").unwrap().get_row_height(2),
+            Some(30.0)
+        );
+
+        assert!(run(&height("3ch"), &default_global()).is_err());
+    }
+
     #[test]
     fn test_run_hide_command() {
         let temp_dir = TempDir::new().unwrap();
@@ -1006,7 +1474,7 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let file_path = create_test_workbook(&temp_dir, "get_empty.xlsx");
 
-        let result = get(&file_path, "Sheet1", 1, &default_global());
+        let result = get(&get_args(&file_path, "Sheet1", "1"), &default_global());
         assert!(result.is_ok());
     }
 
@@ -1120,7 +1588,7 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let file_path = create_test_workbook(&temp_dir, "notfound.xlsx");
 
-        let result = get(&file_path, "NonExistent", 1, &default_global());
+        let result = get(&get_args(&file_path, "NonExistent", "1"), &default_global());
         assert!(result.is_err());
     }
 
@@ -1216,4 +1684,42 @@ mod tests {
         let result = unhide(&file_path, "Sheet1", 1, &global);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_row_style() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "row_style.xlsx");
+        let mut wb = Workbook::open(&file_path).unwrap();
+        let mut bold = xlex_core::Style::default();
+        bold.font.bold = true;
+        let bold = wb.style_registry_mut().add(bold);
+        wb.save().unwrap();
+
+        let args = |style_id, clear| RowArgs {
+            command: RowCommand::Style {
+                file: file_path.clone(),
+                sheet: "Sheet1".to_string(),
+                row: 3,
+                style_id,
+                clear,
+            },
+        };
+        run(&args(Some(bold), false), &default_global()).unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(wb.get_sheet("Sheet1").unwrap().get_row_style(3), Some(bold));
+        let style = |col, row| {
+            wb.get_cell_style("Sheet1", &xlex_core::CellRef::new(col, row))
+                .unwrap()
+        };
+        assert!(style(9, 3).font.bold);
+        assert!(!style(9, 4).font.bold);
+
+        // Unknown style IDs are rejected
+        assert!(run(&args(Some(99), false), &default_global()).is_err());
+
+        run(&args(None, true), &default_global()).unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(wb.get_sheet("Sheet1").unwrap().get_row_style(3), None);
+    }
 }
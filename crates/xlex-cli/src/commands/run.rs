@@ -0,0 +1,779 @@
+//! Declarative jobs: read sources, transform them, write sinks.
+//!
+//! `xlex run job.yml` reads named `sources` (xlsx sheets, CSV/TSV, JSON or
+//! NDJSON files), derives named tables from them with `transforms` (the
+//! steps of `xlex transform`, plus `join` against another table), and
+//! writes tables to `sinks` (xlsx sheets, CSV or Parquet). Every table has
+//! a header row. Rows are streamed from source to sink; only the tables a
+//! `join` reads, and tables written to Parquet, are held in memory. Relative paths are taken from the job file's
+//! directory, so a job runs the same from anywhere.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use serde::Deserialize;
+
+use xlex_core::pipeline::{Pipeline, Record, RowSink};
+use xlex_core::writer::StreamingSheetWriter;
+use xlex_core::{CellValue, LazyWorkbook, XlexError, XlexResult};
+
+use super::import::{json_to_cell, parse_value};
+use super::output::CommandOutput;
+use super::transform::{add_step, TransformStep};
+use super::GlobalOptions;
+
+/// Arguments for the run command.
+#[derive(Parser)]
+pub struct RunArgs {
+    /// Job file (YAML) listing sources, transforms and sinks
+    pub job: PathBuf,
+    /// Overwrite existing output files
+    #[arg(long, short = 'F')]
+    pub force: bool,
+}
+
+/// A job file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Job {
+    sources: BTreeMap<String, SourceSpec>,
+    #[serde(default)]
+    transforms: Vec<TransformSpec>,
+    sinks: Vec<SinkSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SourceSpec {
+    path: PathBuf,
+    /// Sheet of an xlsx source (default: the first)
+    sheet: Option<String>,
+    /// File format (default: from the extension)
+    format: Option<SourceFormat>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum SourceFormat {
+    Xlsx,
+    Csv,
+    Tsv,
+    Json,
+    Ndjson,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TransformSpec {
+    /// Name of the table this transform makes
+    name: String,
+    /// Source or earlier transform it reads
+    from: String,
+    #[serde(default, with = "serde_yaml::with::singleton_map_recursive")]
+    steps: Vec<TransformStep>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SinkSpec {
+    /// Table to write
+    from: String,
+    path: PathBuf,
+    /// Sheet name for xlsx (default: the table name)
+    sheet: Option<String>,
+    /// File format (default: from the extension)
+    format: Option<SinkFormat>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum SinkFormat {
+    Xlsx,
+    Csv,
+    Parquet,
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+impl SourceSpec {
+    fn format(&self) -> Result<SourceFormat> {
+        if let Some(format) = self.format {
+            return Ok(format);
+        }
+        Ok(match extension(&self.path).as_str() {
+            "xlsx" | "xlsm" => SourceFormat::Xlsx,
+            "csv" => SourceFormat::Csv,
+            "tsv" | "tab" => SourceFormat::Tsv,
+            "json" => SourceFormat::Json,
+            "ndjson" | "jsonl" => SourceFormat::Ndjson,
+            _ => anyhow::bail!(
+                "Can't tell the format of {}; set `format`",
+                self.path.display()
+            ),
+        })
+    }
+}
+
+impl SinkSpec {
+    fn format(&self) -> Result<SinkFormat> {
+        let format = match self.format {
+            Some(format) => format,
+            None => match extension(&self.path).as_str() {
+                "xlsx" => SinkFormat::Xlsx,
+                "csv" => SinkFormat::Csv,
+                "parquet" => SinkFormat::Parquet,
+                _ => anyhow::bail!(
+                    "Can't tell the format of {}; set `format`",
+                    self.path.display()
+                ),
+            },
+        };
+        if cfg!(not(feature = "parquet")) && format == SinkFormat::Parquet {
+            anyhow::bail!(
+                "Can't write {}: xlex was built without the `parquet` feature",
+                self.path.display()
+            );
+        }
+        Ok(format)
+    }
+}
+
+/// A job with its paths resolved and its xlsx sources opened.
+struct Runner<'j> {
+    job: &'j Job,
+    base: PathBuf,
+    workbooks: HashMap<&'j str, LazyWorkbook>,
+}
+
+impl<'j> Runner<'j> {
+    fn new(job: &'j Job, base: &Path) -> Result<Self> {
+        let mut tables: HashSet<&str> = job.sources.keys().map(String::as_str).collect();
+        for transform in &job.transforms {
+            if !tables.contains(transform.from.as_str()) {
+                anyhow::bail!(
+                    "Transform '{}' reads '{}', which is not a source or an earlier transform",
+                    transform.name,
+                    transform.from
+                );
+            }
+            if !tables.insert(transform.name.as_str()) {
+                anyhow::bail!("Table '{}' is defined twice", transform.name);
+            }
+        }
+        for sink in &job.sinks {
+            if !tables.contains(sink.from.as_str()) {
+                anyhow::bail!(
+                    "Sink {} reads unknown table '{}'",
+                    sink.path.display(),
+                    sink.from
+                );
+            }
+            sink.format()?;
+        }
+
+        let mut runner = Self {
+            job,
+            base: base.to_path_buf(),
+            workbooks: HashMap::new(),
+        };
+        for (name, source) in &job.sources {
+            if source.format()? == SourceFormat::Xlsx {
+                let workbook = LazyWorkbook::open(runner.path(&source.path))?;
+                runner.workbooks.insert(name, workbook);
+            }
+        }
+        Ok(runner)
+    }
+
+    fn path(&self, path: &Path) -> PathBuf {
+        self.base.join(path)
+    }
+
+    /// Builds the pipeline that produces table `name`.
+    fn pipeline(&self, name: &str) -> Result<Pipeline<'_>> {
+        if let Some(source) = self.job.sources.get(name) {
+            return self.source(name, source);
+        }
+        let index = self
+            .job
+            .transforms
+            .iter()
+            .position(|t| t.name == name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown table '{}'", name))?;
+        let transform = &self.job.transforms[index];
+        let mut pipeline = self.pipeline(&transform.from)?;
+        for step in &transform.steps {
+            pipeline = add_step(pipeline, step.clone(), true, &mut |with| {
+                let earlier = self.job.transforms[..index].iter().any(|t| t.name == with);
+                if !earlier && !self.job.sources.contains_key(with) {
+                    anyhow::bail!(
+                        "Transform '{}' joins '{}', which is not a source or an earlier transform",
+                        transform.name,
+                        with
+                    );
+                }
+                let mut rows: Vec<Vec<CellValue>> = Vec::new();
+                self.pipeline(with)?.write_to(&mut rows)?;
+                Ok(rows)
+            })?;
+        }
+        Ok(pipeline)
+    }
+
+    fn source(&self, name: &str, source: &SourceSpec) -> Result<Pipeline<'_>> {
+        let path = self.path(&source.path);
+        Ok(match source.format()? {
+            SourceFormat::Xlsx => {
+                let workbook = &self.workbooks[name];
+                let sheet = match &source.sheet {
+                    Some(sheet) => sheet.clone(),
+                    None => workbook
+                        .sheet_names()
+                        .first()
+                        .cloned()
+                        .ok_or_else(|| anyhow::anyhow!("{} has no sheets", path.display()))?,
+                };
+                if !workbook.has_sheet(&sheet) {
+                    return Err(XlexError::SheetNotFound { name: sheet }.into());
+                }
+                workbook.pipeline(&sheet)
+            }
+            format @ (SourceFormat::Csv | SourceFormat::Tsv) => {
+                let reader = csv::ReaderBuilder::new()
+                    .has_headers(false)
+                    .flexible(true)
+                    .delimiter(if format == SourceFormat::Tsv {
+                        b'\t'
+                    } else {
+                        b','
+                    })
+                    .from_path(&path)
+                    .map_err(|e| anyhow::anyhow!("Can't read {}: {}", path.display(), e))?;
+                let location = path.display().to_string();
+                Pipeline::from_records(reader.into_records().zip(1..).map(move |(line, row)| {
+                    let line = line.map_err(|e| XlexError::ParseError {
+                        message: e.to_string(),
+                        location: location.clone(),
+                    })?;
+                    Ok(Record {
+                        row,
+                        values: line.iter().map(|v| parse_value(v.trim())).collect(),
+                    })
+                }))
+            }
+            SourceFormat::Json => {
+                let content = std::fs::read_to_string(&path)?;
+                let data: serde_json::Value = serde_json::from_str(&content)
+                    .map_err(|e| anyhow::anyhow!("Invalid JSON in {}: {}", path.display(), e))?;
+                let serde_json::Value::Array(items) = data else {
+                    anyhow::bail!("{} must hold an array of objects or arrays", path.display());
+                };
+                Pipeline::from_records(json_rows(items.into_iter().map(Ok)))
+            }
+            SourceFormat::Ndjson => {
+                use std::io::BufRead;
+
+                let file = std::io::BufReader::new(std::fs::File::open(&path)?);
+                let location = path.display().to_string();
+                let items = file
+                    .lines()
+                    .filter(|line| !matches!(line, Ok(l) if l.trim().is_empty()))
+                    .map(move |line| {
+                        serde_json::from_str(&line?).map_err(|e| XlexError::ParseError {
+                            message: e.to_string(),
+                            location: location.clone(),
+                        })
+                    });
+                Pipeline::from_records(json_rows(items))
+            }
+        })
+    }
+}
+
+/// Turns JSON rows into records: arrays as they are, objects by the keys
+/// of the first object, which also make the header row.
+fn json_rows<'a>(
+    items: impl Iterator<Item = XlexResult<serde_json::Value>> + 'a,
+) -> impl Iterator<Item = XlexResult<Record>> + 'a {
+    let mut keys: Option<Vec<String>> = None;
+    items
+        .flat_map(move |item| {
+            let item = match item {
+                Ok(item) => item,
+                Err(e) => return vec![Err(e)],
+            };
+            let mut rows = Vec::new();
+            let values = match item {
+                serde_json::Value::Array(values) => values.iter().map(json_to_cell).collect(),
+                serde_json::Value::Object(object) => {
+                    let keys = keys.get_or_insert_with(|| {
+                        let header: Vec<String> = object.keys().cloned().collect();
+                        rows.push(Ok(header
+                            .iter()
+                            .map(|k| CellValue::string(k.as_str()))
+                            .collect()));
+                        header
+                    });
+                    keys.iter()
+                        .map(|k| object.get(k).map(json_to_cell).unwrap_or(CellValue::Empty))
+                        .collect()
+                }
+                other => vec![json_to_cell(&other)],
+            };
+            rows.push(Ok(values));
+            rows
+        })
+        .zip(1..)
+        .map(|(values, row)| values.map(|values| Record { row, values }))
+}
+
+/// Writes rows to a CSV file.
+pub(super) struct CsvSink {
+    pub(super) writer: csv::Writer<std::fs::File>,
+    location: String,
+}
+
+impl CsvSink {
+    /// Creates the CSV file at `path`.
+    pub(super) fn create(path: &Path) -> Result<Self> {
+        let writer = csv::Writer::from_path(path)
+            .map_err(|e| anyhow::anyhow!("Can't write {}: {}", path.display(), e))?;
+        Ok(Self {
+            writer,
+            location: path.display().to_string(),
+        })
+    }
+}
+
+impl RowSink for CsvSink {
+    fn write_row(&mut self, values: &[CellValue]) -> XlexResult<()> {
+        self.writer
+            .write_record(values.iter().map(CellValue::to_display_string))
+            .map_err(|e| XlexError::OperationFailed {
+                message: format!("Can't write {}: {}", self.location, e),
+            })
+    }
+}
+
+/// Writes rows to a Parquet file. Column types are only known once every
+/// row is in, so the rows are collected and written by [`ParquetSink::finish`].
+#[cfg(feature = "parquet")]
+pub(super) struct ParquetSink {
+    path: PathBuf,
+    header: Option<Vec<String>>,
+    rows: Vec<Vec<CellValue>>,
+}
+
+#[cfg(feature = "parquet")]
+impl ParquetSink {
+    pub(super) fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            header: None,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Writes the file. A column whose non-blank cells are all numbers
+    /// becomes a double column and one of only booleans a boolean column;
+    /// anything else is written as text. Blank cells are nulls.
+    pub(super) fn finish(self) -> Result<()> {
+        use std::sync::Arc;
+
+        use arrow_array::{ArrayRef, BooleanArray, Float64Array, RecordBatch, StringArray};
+        use arrow_schema::{DataType, Field, Schema};
+
+        let header = self.header.unwrap_or_default();
+        let mut fields = Vec::new();
+        let mut columns: Vec<ArrayRef> = Vec::new();
+        for (index, name) in header.iter().enumerate() {
+            let cells: Vec<&CellValue> = self
+                .rows
+                .iter()
+                .map(|row| row.get(index).unwrap_or(&CellValue::Empty))
+                .collect();
+            let filled = || cells.iter().filter(|v| !v.is_empty());
+            let (data_type, column): (DataType, ArrayRef) =
+                if filled().next().is_some() && filled().all(|v| v.as_number().is_some()) {
+                    let values = cells.iter().map(|v| v.as_number());
+                    (
+                        DataType::Float64,
+                        Arc::new(values.collect::<Float64Array>()),
+                    )
+                } else if filled().next().is_some()
+                    && filled().all(|v| matches!(v, CellValue::Boolean(_)))
+                {
+                    let values = cells.iter().map(|v| match v {
+                        CellValue::Boolean(b) => Some(*b),
+                        _ => None,
+                    });
+                    (
+                        DataType::Boolean,
+                        Arc::new(values.collect::<BooleanArray>()),
+                    )
+                } else {
+                    let values = cells
+                        .iter()
+                        .map(|v| (!v.is_empty()).then(|| v.to_display_string()));
+                    (DataType::Utf8, Arc::new(values.collect::<StringArray>()))
+                };
+            fields.push(Field::new(name, data_type, true));
+            columns.push(column);
+        }
+
+        let schema = Arc::new(Schema::new(fields));
+        let batch = RecordBatch::try_new(schema.clone(), columns)?;
+        let file = std::fs::File::create(&self.path)
+            .map_err(|e| anyhow::anyhow!("Can't write {}: {}", self.path.display(), e))?;
+        let mut writer = parquet::arrow::ArrowWriter::try_new(file, schema, None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "parquet")]
+impl RowSink for ParquetSink {
+    fn write_row(&mut self, values: &[CellValue]) -> XlexResult<()> {
+        let Some(header) = &self.header else {
+            // Blank or repeated names would make an invalid schema
+            let mut seen = HashSet::new();
+            let names = values
+                .iter()
+                .enumerate()
+                .map(|(index, v)| {
+                    let name = v.to_display_string();
+                    let name = if name.is_empty() {
+                        xlex_core::CellRef::col_to_letters(index as u32 + 1)
+                    } else {
+                        name
+                    };
+                    let mut unique = name.clone();
+                    let mut n = 1;
+                    while !seen.insert(unique.clone()) {
+                        n += 1;
+                        unique = format!("{}_{}", name, n);
+                    }
+                    unique
+                })
+                .collect();
+            self.header = Some(names);
+            return Ok(());
+        };
+        let mut row = values.to_vec();
+        row.resize(header.len(), CellValue::Empty);
+        self.rows.push(row);
+        Ok(())
+    }
+}
+
+/// Run the run command.
+pub fn run(args: &RunArgs, global: &GlobalOptions) -> Result<()> {
+    let content = std::fs::read_to_string(&args.job)?;
+    let job: Job = serde_yaml::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("Invalid job {}: {}", args.job.display(), e))?;
+    let base = args.job.parent().unwrap_or(Path::new(""));
+    let runner = Runner::new(&job, base)?;
+
+    let mut outputs: Vec<PathBuf> = Vec::new();
+    for sink in &job.sinks {
+        let path = runner.path(&sink.path);
+        if !outputs.contains(&path) {
+            if path.exists() && !args.force {
+                return Err(XlexError::FileExists { path }.into());
+            }
+            outputs.push(path);
+        }
+    }
+
+    if global.dry_run {
+        for sink in &job.sinks {
+            outln!("Would write {} to {}", sink.from, sink.path.display());
+        }
+        return Ok(());
+    }
+
+    let mut workbooks: HashMap<PathBuf, StreamingSheetWriter> = HashMap::new();
+    let mut results = Vec::new();
+    let mut lines = Vec::new();
+    for sink in &job.sinks {
+        let path = runner.path(&sink.path);
+        let pipeline = runner.pipeline(&sink.from)?;
+        let sheet = sink.sheet.as_deref().unwrap_or(&sink.from);
+        let stats = match sink.format()? {
+            SinkFormat::Csv => {
+                let mut csv = CsvSink::create(&path)?;
+                let stats = pipeline.write_to(&mut csv)?;
+                csv.writer.flush()?;
+                stats
+            }
+            #[cfg(feature = "parquet")]
+            SinkFormat::Parquet => {
+                let mut parquet = ParquetSink::new(&path);
+                let stats = pipeline.write_to(&mut parquet)?;
+                parquet.finish()?;
+                stats
+            }
+            _ => {
+                let writer = match workbooks.entry(path.clone()) {
+                    std::collections::hash_map::Entry::Occupied(entry) => {
+                        let writer = entry.into_mut();
+                        writer.next_sheet(sheet)?;
+                        writer
+                    }
+                    std::collections::hash_map::Entry::Vacant(entry) => {
+                        entry.insert(StreamingSheetWriter::create(&path, sheet)?)
+                    }
+                };
+                pipeline.write_to(writer)?
+            }
+        };
+        lines.push(format!(
+            "  {} → {}: {} rows",
+            sink.from.cyan(),
+            sink.path.display(),
+            stats.rows_written
+        ));
+        results.push(serde_json::json!({
+            "from": sink.from,
+            "path": sink.path.display().to_string(),
+            "rows": stats.rows_written,
+        }));
+    }
+    for writer in workbooks.into_values() {
+        writer.finish()?;
+    }
+
+    CommandOutput::new("run")
+        .file(&args.job)
+        .field("sinks", serde_json::Value::Array(results))
+        .print(
+            global,
+            format!(
+                "Ran {} with {} sinks\n{}",
+                args.job.display().to_string().cyan(),
+                job.sinks.len().to_string().green(),
+                lines.join("\n")
+            ),
+        )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::OutputFormat;
+    use tempfile::TempDir;
+    use xlex_core::{CellRef, Workbook};
+
+    fn default_global() -> GlobalOptions {
+        GlobalOptions {
+            quiet: true,
+            verbose: false,
+            format: OutputFormat::Text,
+            no_color: true,
+            color: false,
+            json_errors: false,
+            dry_run: false,
+            output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
+        }
+    }
+
+    const JOB: &str = r#"
+sources:
+  orders: { path: orders.xlsx, sheet: Orders }
+  prices: { path: prices.csv }
+  regions: { path: regions.ndjson }
+transforms:
+  - name: priced
+    from: orders
+    steps:
+      - join: { with: prices, on: SKU, type: left }
+      - derive: { column: Amount, expr: "Qty * Price" }
+      - filter: { column: Amount, when: "> 10" }
+  - name: by_region
+    from: priced
+    steps:
+      - join: { with: regions, on: Customer, right_on: name }
+      - select: [Customer, region, Amount]
+sinks:
+  - { from: priced, path: out/report.xlsx, sheet: Priced }
+  - { from: by_region, path: out/report.xlsx }
+  - { from: by_region, path: out/by_region.csv }
+"#;
+
+    fn write_job(dir: &TempDir, job: &str) -> PathBuf {
+        let mut wb = Workbook::with_sheets(&["Orders"]);
+        let orders = [
+            ["Customer", "SKU", "Qty"].map(CellValue::string).to_vec(),
+            vec![
+                CellValue::string("Ann"),
+                CellValue::string("A1"),
+                CellValue::Number(3.0),
+            ],
+            vec![
+                CellValue::string("Bo"),
+                CellValue::string("B2"),
+                CellValue::Number(1.0),
+            ],
+            vec![
+                CellValue::string("Cy"),
+                CellValue::string("Z9"),
+                CellValue::Number(5.0),
+            ],
+        ];
+        for (r, row) in orders.iter().enumerate() {
+            for (c, value) in row.iter().enumerate() {
+                let cell_ref = CellRef::new(c as u32 + 1, r as u32 + 1);
+                wb.set_cell("Orders", cell_ref, value.clone()).unwrap();
+            }
+        }
+        wb.save_as(dir.path().join("orders.xlsx")).unwrap();
+        std::fs::write(dir.path().join("prices.csv"), "SKU,Price\nA1,4.5\nB2,2\n").unwrap();
+        std::fs::write(
+            dir.path().join("regions.ndjson"),
+            "{\"name\": \"Ann\", \"region\": \"North\"}\n\n{\"name\": \"Cy\", \"region\": \"South\"}\n",
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("out")).unwrap();
+        let path = dir.path().join("job.yml");
+        std::fs::write(&path, job).unwrap();
+        path
+    }
+
+    fn run_job(job: &Path, force: bool) -> Result<()> {
+        let args = RunArgs {
+            job: job.to_path_buf(),
+            force,
+        };
+        run(&args, &default_global())
+    }
+
+    #[test]
+    fn test_run_job() {
+        let dir = TempDir::new().unwrap();
+        let job = write_job(&dir, JOB);
+        run_job(&job, false).unwrap();
+
+        let report = Workbook::open(dir.path().join("out/report.xlsx")).unwrap();
+        assert_eq!(report.sheet_names(), vec!["Priced", "by_region"]);
+        let priced = report.get_sheet("Priced").unwrap();
+        let value = |col, row| priced.get_value(&CellRef::new(col, row));
+        assert_eq!(value(4, 1), CellValue::string("Price"));
+        assert_eq!(value(5, 1), CellValue::string("Amount"));
+        assert_eq!(value(5, 2), CellValue::Number(13.5));
+        // Bo (2.0) is filtered out; Cy has no price, so Qty * blank is 0
+        assert_eq!(value(1, 3), CellValue::Empty);
+
+        let csv = std::fs::read_to_string(dir.path().join("out/by_region.csv")).unwrap();
+        assert_eq!(csv, "Customer,region,Amount\nAnn,North,13.5\n");
+
+        // Outputs exist now
+        assert!(run_job(&job, false).is_err());
+        run_job(&job, true).unwrap();
+    }
+
+    #[test]
+    fn test_run_job_left_join_sinks() {
+        let dir = TempDir::new().unwrap();
+        let job = write_job(
+            &dir,
+            r#"
+sources:
+  orders: { path: orders.xlsx, sheet: Orders }
+  prices: { path: prices.csv }
+transforms:
+  - name: priced
+    from: orders
+    steps:
+      - join: { with: prices, on: SKU, type: left }
+      - derive: { column: Note, expr: "Qty" }
+sinks:
+  - { from: priced, path: out/priced.csv }
+  - { from: priced, path: out/priced.parquet }
+"#,
+        );
+        run_job(&job, false).unwrap();
+
+        // Cy has no price; the row keeps the header's width
+        let csv = std::fs::read_to_string(dir.path().join("out/priced.csv")).unwrap();
+        assert_eq!(
+            csv,
+            "Customer,SKU,Qty,Price,Note\nAnn,A1,3,4.5,3\nBo,B2,1,2,1\nCy,Z9,5,,5\n"
+        );
+
+        #[cfg(feature = "parquet")]
+        {
+            use arrow_array::{Array, Float64Array, StringArray};
+            use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+            let file = std::fs::File::open(dir.path().join("out/priced.parquet")).unwrap();
+            let reader = ParquetRecordBatchReaderBuilder::try_new(file)
+                .unwrap()
+                .build()
+                .unwrap();
+            let batches: Vec<_> = reader.map(Result::unwrap).collect();
+            assert_eq!(batches.len(), 1);
+            let batch = &batches[0];
+            assert_eq!(batch.num_rows(), 3);
+            let schema = batch.schema();
+            let names: Vec<_> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+            assert_eq!(names, ["Customer", "SKU", "Qty", "Price", "Note"]);
+            let customers = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap();
+            assert_eq!(customers.value(2), "Cy");
+            let prices = batch
+                .column(3)
+                .as_any()
+                .downcast_ref::<Float64Array>()
+                .unwrap();
+            assert_eq!(prices.value(0), 4.5);
+            assert!(prices.is_null(2));
+        }
+    }
+
+    #[test]
+    fn test_run_job_errors() {
+        let dir = TempDir::new().unwrap();
+        let job = write_job(
+            &dir,
+            "sources: {}\ntransforms:\n  - { name: t, from: nowhere }\nsinks: []\n",
+        );
+        let err = run_job(&job, false).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("not a source or an earlier transform"));
+
+        std::fs::write(
+            &job,
+            "sources:\n  o: { path: orders.xlsx }\nsinks:\n  - { from: o, path: out.txt }\n",
+        )
+        .unwrap();
+        let err = run_job(&job, false).unwrap_err();
+        assert!(err.to_string().contains("set `format`"));
+
+        std::fs::write(
+            &job,
+            "sources:\n  o: { path: orders.xlsx }\nsinks:\n  - { from: o, path: out.csv }\nextra: 1\n",
+        )
+        .unwrap();
+        let err = run_job(&job, false).unwrap_err();
+        assert!(err.to_string().contains("Invalid job"));
+    }
+}
@@ -0,0 +1,285 @@
+//! Content scanning operations.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+
+use xlex_core::pii::{self, PiiKind};
+use xlex_core::{CellValue, LazyWorkbook, Workbook};
+
+use super::{GlobalOptions, OutputFormat};
+
+/// Arguments for scan operations.
+#[derive(Parser)]
+pub struct ScanArgs {
+    #[command(subcommand)]
+    pub command: ScanCommand,
+}
+
+#[derive(Subcommand)]
+pub enum ScanCommand {
+    /// Find emails, phone numbers, IBANs, card numbers and national IDs
+    Pii {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Only scan this sheet
+        #[arg(long)]
+        sheet: Option<String>,
+        /// Overwrite matches with masked values
+        #[arg(long)]
+        redact: bool,
+    },
+}
+
+/// Personal data found in a cell. Only the masked value is kept so reports
+/// never repeat the data they flag.
+pub(super) struct PiiFinding {
+    sheet: String,
+    cell: String,
+    kind: PiiKind,
+    masked: String,
+}
+
+/// Run scan operations.
+pub fn run(args: &ScanArgs, global: &GlobalOptions) -> Result<()> {
+    match &args.command {
+        ScanCommand::Pii {
+            file,
+            sheet,
+            redact,
+        } => scan_pii(file, sheet.as_deref(), *redact, global),
+    }
+}
+
+/// Streams the string cells of `sheet` (or every sheet) looking for
+/// personal data.
+pub(super) fn find_pii(workbook: &LazyWorkbook, sheet: Option<&str>) -> Result<Vec<PiiFinding>> {
+    let sheets: Vec<String> = match sheet {
+        Some(name) => vec![name.to_string()],
+        None => workbook.sheet_names().to_vec(),
+    };
+
+    let mut findings = Vec::new();
+    for sheet_name in &sheets {
+        for row in workbook.stream_rows(sheet_name)? {
+            for (cell_ref, value) in &row.cells {
+                let CellValue::String(text) = value else {
+                    continue;
+                };
+                for m in pii::find_pii(text) {
+                    findings.push(PiiFinding {
+                        sheet: sheet_name.clone(),
+                        cell: cell_ref.to_a1(),
+                        kind: m.kind,
+                        masked: m.masked(),
+                    });
+                }
+            }
+        }
+    }
+    Ok(findings)
+}
+
+/// Prints findings with per-kind counts.
+pub(super) fn print_findings(findings: &[PiiFinding], global: &GlobalOptions) -> Result<()> {
+    let mut counts: BTreeMap<PiiKind, usize> = BTreeMap::new();
+    for f in findings {
+        *counts.entry(f.kind).or_default() += 1;
+    }
+
+    if global.format == OutputFormat::Json {
+        let counts: serde_json::Map<String, serde_json::Value> = PiiKind::ALL
+            .iter()
+            .map(|k| (k.to_string(), counts.get(k).copied().unwrap_or(0).into()))
+            .collect();
+        let matches: Vec<serde_json::Value> = findings
+            .iter()
+            .map(|f| {
+                serde_json::json!({
+                    "sheet": f.sheet,
+                    "cell": f.cell,
+                    "kind": f.kind.to_string(),
+                    "match": f.masked,
+                })
+            })
+            .collect();
+        let json = serde_json::json!({
+            "total": findings.len(),
+            "counts": counts,
+            "matches": matches,
+        });
+        outln!("{}", serde_json::to_string_pretty(&json)?);
+    } else if findings.is_empty() {
+        outln!("{}", "No personal data found".green());
+    } else {
+        for f in findings {
+            outln!(
+                "{}!{} {} {}",
+                f.sheet,
+                f.cell.cyan(),
+                f.kind.to_string().yellow(),
+                f.masked
+            );
+        }
+        let summary: Vec<String> = counts
+            .iter()
+            .map(|(kind, count)| format!("{} {}", count, kind))
+            .collect();
+        outln!(
+            "\n{}: {} ({})",
+            "Total".bold(),
+            findings.len(),
+            summary.join(", ")
+        );
+    }
+    Ok(())
+}
+
+fn scan_pii(
+    file: &std::path::Path,
+    sheet: Option<&str>,
+    redact: bool,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let findings = find_pii(&LazyWorkbook::open(file)?, sheet)?;
+
+    if redact && global.dry_run {
+        outln!(
+            "Would redact {} match(es) in {}",
+            findings.len(),
+            file.display()
+        );
+        return Ok(());
+    }
+
+    if redact && !findings.is_empty() {
+        redact_workbook(file, sheet)?;
+    }
+
+    if global.quiet {
+        return Ok(());
+    }
+    print_findings(&findings, global)?;
+    if redact && !findings.is_empty() && global.format != OutputFormat::Json {
+        outln!("{}", "Redacted all of the above".green());
+    }
+
+    Ok(())
+}
+
+/// Replaces personal data in string cells with masked values, keeping each
+/// cell's style, comment and hyperlink.
+fn redact_workbook(file: &std::path::Path, sheet: Option<&str>) -> Result<()> {
+    let mut workbook = Workbook::open(file)?;
+    let sheets: Vec<String> = match sheet {
+        Some(name) => vec![name.to_string()],
+        None => workbook
+            .sheet_names()
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+    };
+
+    for sheet_name in &sheets {
+        let sheet = workbook.get_sheet_mut(sheet_name).ok_or_else(|| {
+            xlex_core::XlexError::SheetNotFound {
+                name: sheet_name.to_string(),
+            }
+        })?;
+        let redacted: Vec<_> = sheet
+            .cells()
+            .filter_map(|cell| match &cell.value {
+                CellValue::String(text) => {
+                    let (masked, count) = pii::redact(text);
+                    (count > 0).then(|| {
+                        let mut cell = cell.clone();
+                        cell.value = CellValue::String(masked);
+                        cell
+                    })
+                }
+                _ => None,
+            })
+            .collect();
+        for cell in redacted {
+            sheet.insert_cell(cell);
+        }
+    }
+
+    workbook.save()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use xlex_core::CellRef;
+
+    fn default_global() -> GlobalOptions {
+        GlobalOptions {
+            quiet: true,
+            verbose: false,
+            format: OutputFormat::Text,
+            no_color: true,
+            color: false,
+            json_errors: false,
+            dry_run: false,
+            output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
+        }
+    }
+
+    #[test]
+    fn test_scan_and_redact_pii() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("pii.xlsx");
+        let mut wb = Workbook::with_sheets(&["Contacts", "Notes"]);
+        wb.set_cell(
+            "Contacts",
+            CellRef::new(1, 1),
+            CellValue::string("ann@example.com"),
+        )
+        .unwrap();
+        wb.set_cell(
+            "Contacts",
+            CellRef::new(2, 1),
+            CellValue::string("Card 4111 1111 1111 1111"),
+        )
+        .unwrap();
+        wb.set_cell("Notes", CellRef::new(1, 1), CellValue::string("No data"))
+            .unwrap();
+        wb.set_cell("Notes", CellRef::new(1, 2), CellValue::Number(41.5))
+            .unwrap();
+        wb.save_as(&file_path).unwrap();
+
+        let lazy = LazyWorkbook::open(&file_path).unwrap();
+        let findings = find_pii(&lazy, None).unwrap();
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].cell, "A1");
+        assert_eq!(findings[1].kind, PiiKind::CreditCard);
+        assert!(find_pii(&lazy, Some("Notes")).unwrap().is_empty());
+
+        let mut json = default_global();
+        json.format = OutputFormat::Json;
+        scan_pii(&file_path, None, false, &json).unwrap();
+
+        scan_pii(&file_path, None, true, &default_global()).unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(
+            wb.get_cell("Contacts", &CellRef::new(1, 1)).unwrap(),
+            CellValue::string("a***@example.com")
+        );
+        assert_eq!(
+            wb.get_cell("Contacts", &CellRef::new(2, 1)).unwrap(),
+            CellValue::string("Card **** **** **** 1111")
+        );
+        let lazy = LazyWorkbook::open(&file_path).unwrap();
+        assert!(find_pii(&lazy, None).unwrap().is_empty());
+    }
+}
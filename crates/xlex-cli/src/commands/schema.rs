@@ -0,0 +1,287 @@
+//! Workbook schemas for `create --from-schema`.
+//!
+//! A schema describes the sheets of a data-entry workbook: their headers,
+//! what each column holds and how wide it is, frozen panes and the look
+//! of the header row.
+//!
+//! ```yaml
+//! sheets:
+//!   - name: Orders
+//!     freeze: header          # or the first scrolling cell, e.g. B2
+//!     table_style: medium     # light, medium, dark or none
+//!     header_style: { color: FFFFFF }
+//!     columns:
+//!       - { header: Order ID, type: text, width: 12 }
+//!       - { header: Date, type: date }
+//!       - { header: Status, values: [Open, Shipped, Closed] }
+//! ```
+
+use anyhow::Result;
+use serde::Deserialize;
+use xlex_core::builder::{SheetBuilder, WorkbookBuilder};
+use xlex_core::sheet::Dropdown;
+use xlex_core::style::{Border, BorderSide, BorderStyle, Color, FillPattern, NumberFormat};
+use xlex_core::{CellRef, Range, Style, Workbook};
+
+use super::style::RuleStyle;
+
+/// A workbook schema file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct Schema {
+    sheets: Vec<SheetSchema>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SheetSchema {
+    name: String,
+    #[serde(default)]
+    columns: Vec<ColumnSchema>,
+    /// `header`, `none`, or the top-left cell that scrolls (`B2` keeps row
+    /// 1 and column A in view)
+    freeze: Option<String>,
+    /// Header look with filter buttons: `light`, `medium`, `dark`, `none`
+    table_style: Option<String>,
+    /// Header style, layered over the table style
+    header_style: Option<RuleStyle>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ColumnSchema {
+    header: String,
+    /// `text`, `number`, `integer`, `decimal`, `currency`, `percent`,
+    /// `date` or `bool`
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    /// Width in characters
+    width: Option<f64>,
+    /// Allowed values, offered as a dropdown
+    values: Option<Vec<String>>,
+}
+
+impl Schema {
+    /// Reads a schema from a YAML file.
+    pub(super) fn load(path: &std::path::Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let schema: Schema = serde_yaml::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Invalid schema {}: {}", path.display(), e))?;
+        if schema.sheets.is_empty() {
+            anyhow::bail!("Schema {} defines no sheets", path.display());
+        }
+        Ok(schema)
+    }
+
+    /// Returns the sheet names, in order.
+    pub(super) fn sheet_names(&self) -> Vec<&str> {
+        self.sheets.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    /// Builds the empty workbook the schema describes.
+    pub(super) fn build(&self) -> Result<Workbook> {
+        let mut builder = WorkbookBuilder::new();
+        for sheet in &self.sheets {
+            let described = sheet.describe(SheetBuilder::default())?;
+            builder = builder.sheet(&sheet.name, |_| described);
+        }
+        Ok(builder.build()?)
+    }
+}
+
+impl SheetSchema {
+    fn describe(&self, mut sheet: SheetBuilder) -> Result<SheetBuilder> {
+        let context = |e: anyhow::Error| anyhow::anyhow!("Sheet '{}': {}", self.name, e);
+        sheet = sheet.headers(self.columns.iter().map(|c| c.header.clone()));
+
+        for (col, column) in (1u32..).zip(&self.columns) {
+            if let Some(width) = column.width {
+                sheet = sheet.column_width(col, width);
+            }
+            if let Some(format) = column.number_format().map_err(context)? {
+                let mut style = Style::default();
+                style.number_format = format;
+                sheet = sheet.column_style(col, style);
+            }
+            let is_bool = column
+                .kind
+                .as_deref()
+                .is_some_and(|kind| kind.eq_ignore_ascii_case("bool"));
+            let items = match &column.values {
+                Some(values) => Some(values.clone()),
+                None if is_bool => Some(vec!["TRUE".to_string(), "FALSE".to_string()]),
+                None => None,
+            };
+            if let Some(items) = items {
+                let below_header =
+                    Range::new(CellRef::new(col, 2), CellRef::new(col, CellRef::MAX_ROW));
+                let dropdown = Dropdown::from_items(below_header, &items)
+                    .map_err(|e| context(anyhow::anyhow!("column '{}': {}", column.header, e)))?;
+                sheet = sheet.dropdown(dropdown);
+            }
+        }
+
+        if let Some(freeze) = &self.freeze {
+            sheet = match freeze.to_lowercase().as_str() {
+                "header" => sheet.freeze_header(),
+                "none" => sheet,
+                _ => {
+                    let cell = CellRef::parse(freeze).map_err(|_| {
+                        context(anyhow::anyhow!(
+                            "freeze must be 'header', 'none' or a cell such as B2, not '{}'",
+                            freeze
+                        ))
+                    })?;
+                    sheet.freeze_panes(cell.row - 1, cell.col - 1)
+                }
+            };
+        }
+
+        let mut header = match self.table_style.as_deref().map(str::to_lowercase) {
+            None => None,
+            Some(name) if name == "none" => None,
+            Some(name) => {
+                sheet = sheet.autofilter();
+                Some(table_header_style(&name).map_err(context)?)
+            }
+        };
+        if let Some(overrides) = &self.header_style {
+            let style = header.get_or_insert_with(Style::default);
+            overrides.apply_to(style).map_err(context)?;
+        }
+        if let Some(style) = header {
+            sheet = sheet.header_style(style);
+        }
+        Ok(sheet)
+    }
+}
+
+impl ColumnSchema {
+    /// The number format for the column's type; `None` keeps General.
+    fn number_format(&self) -> Result<Option<NumberFormat>> {
+        let Some(kind) = &self.kind else {
+            return Ok(None);
+        };
+        let format = match kind.to_lowercase().as_str() {
+            "number" | "bool" => return Ok(None),
+            "text" => NumberFormat::text(),
+            "integer" => NumberFormat::number(0),
+            "decimal" => NumberFormat::custom("#,##0.00"),
+            "currency" => NumberFormat::custom("$#,##0.00"),
+            "percent" => NumberFormat::percentage(0),
+            "date" => NumberFormat::custom("yyyy-mm-dd"),
+            other => anyhow::bail!(
+                "column '{}' has unknown type '{}' (expected text, number, integer, decimal, \
+                 currency, percent, date or bool)",
+                self.header,
+                other
+            ),
+        };
+        Ok(Some(format))
+    }
+}
+
+/// The header style of a table style: a bold header row over a fill,
+/// darker from `light` to `dark`.
+fn table_header_style(name: &str) -> Result<Style> {
+    let (fill, text) = match name {
+        "light" => ("D9E1F2", None),
+        "medium" => ("4472C4", Some("FFFFFF")),
+        "dark" => ("203764", Some("FFFFFF")),
+        other => anyhow::bail!(
+            "unknown table style '{}' (expected light, medium, dark or none)",
+            other
+        ),
+    };
+    let mut style = Style::default();
+    style.font.bold = true;
+    style.font.color = text.and_then(Color::from_hex);
+    style.fill.pattern = FillPattern::Solid;
+    style.fill.fg_color = Color::from_hex(fill);
+    style.border = Border {
+        bottom: BorderSide {
+            style: BorderStyle::Thin,
+            color: None,
+        },
+        ..Border::default()
+    };
+    Ok(style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(yaml: &str) -> Result<Workbook> {
+        let schema: Schema = serde_yaml::from_str(yaml)?;
+        schema.build()
+    }
+
+    #[test]
+    fn test_schema_build() {
+        let wb = parse(
+            r#"
+sheets:
+  - name: Orders
+    freeze: B2
+    table_style: medium
+    header_style: { italic: true }
+    columns:
+      - { header: Code, type: text, width: 12 }
+      - { header: Paid, type: bool }
+      - { header: Status, values: [Open, Closed] }
+      - { header: Due, type: date }
+  - name: Notes
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(wb.sheet_names(), vec!["Orders", "Notes"]);
+        let sheet = wb.get_sheet("Orders").unwrap();
+        assert_eq!(sheet.get_value(&CellRef::new(3, 1)).to_string(), "Status");
+        assert_eq!(sheet.get_column_width(1), Some(12.0));
+        assert_eq!(sheet.freeze_panes(), Some((1, 1)));
+        assert_eq!(sheet.auto_filter().unwrap().to_a1(), "A1:D1");
+        assert_eq!(sheet.dropdowns().len(), 2);
+        assert_eq!(sheet.dropdowns()[0].items(), Some(vec!["TRUE", "FALSE"]));
+        assert_eq!(sheet.dropdowns()[1].range.to_a1(), "C2:C1048576");
+
+        let format = |col| {
+            let id = sheet.get_column_style(col)?;
+            Some(wb.style_registry().get(id)?.number_format.clone())
+        };
+        assert_eq!(format(1), Some(NumberFormat::text()));
+        assert_eq!(format(2), None);
+        assert_eq!(format(4), Some(NumberFormat::custom("yyyy-mm-dd")));
+
+        let header = wb.get_cell_style("Orders", &CellRef::new(1, 1)).unwrap();
+        assert!(header.font.bold && header.font.italic);
+        assert_eq!(header.fill.fg_color, Color::from_hex("4472C4"));
+    }
+
+    #[test]
+    fn test_schema_errors() {
+        let column = |yaml: &str| {
+            parse(&format!(
+                "sheets:\n  - name: S\n    columns:\n      - {}\n",
+                yaml
+            ))
+            .unwrap_err()
+            .to_string()
+        };
+        assert!(column("{ header: A, type: money }").contains("unknown type 'money'"));
+        assert!(column("{ header: A, values: ['a,b'] }").contains("column 'A'"));
+        assert!(column("{ header: A, colour: red }").contains("unknown field"));
+
+        let sheet = |yaml: &str| parse(&format!("sheets:\n  - name: S\n    {}\n", yaml));
+        assert!(sheet("freeze: top").is_err());
+        assert!(sheet("table_style: fancy").is_err());
+        assert!(sheet("table_style: none")
+            .unwrap()
+            .get_sheet("S")
+            .unwrap()
+            .auto_filter()
+            .is_none());
+        assert!(parse("sheets:\n  - name: S\n  - name: S\n").is_err());
+    }
+}
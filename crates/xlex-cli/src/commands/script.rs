@@ -0,0 +1,595 @@
+//! Scripted workbook edits.
+//!
+//! `xlex script run FILE SCRIPT` runs a [Rhai](https://rhai.rs) script
+//! against a workbook, for transformations too awkward to spell as flags.
+//! The script sees only the workbook: functions to read and write cells,
+//! walk rows and add sheets. It cannot import modules or touch files,
+//! processes or the network, runs under operation and size limits, and the
+//! workbook is saved only if the whole script succeeds.
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+use super::GlobalOptions;
+
+/// Arguments for script operations.
+#[derive(Parser)]
+pub struct ScriptArgs {
+    #[command(subcommand)]
+    pub command: ScriptCommand,
+}
+
+#[derive(Subcommand)]
+pub enum ScriptCommand {
+    /// Run a Rhai script against a workbook and save the result
+    Run(RunArgs),
+}
+
+/// Arguments for `script run`.
+#[derive(Parser)]
+pub struct RunArgs {
+    /// Path to the xlsx file
+    pub file: std::path::PathBuf,
+    /// Path to the Rhai script
+    pub script: std::path::PathBuf,
+    /// Value the script reads as ARGS.NAME, as NAME=VALUE; repeatable
+    #[arg(long = "arg", value_name = "NAME=VALUE")]
+    pub args: Vec<String>,
+    /// Write the result here instead of overwriting the file
+    #[arg(long)]
+    pub out: Option<std::path::PathBuf>,
+    /// Overwrite the output file if it exists
+    #[arg(long, short = 'F')]
+    pub force: bool,
+    /// Stop the script after this many operations
+    #[arg(long, default_value_t = 10_000_000)]
+    pub max_operations: u64,
+}
+
+/// Run script operations.
+pub fn run(args: &ScriptArgs, global: &GlobalOptions) -> Result<()> {
+    match &args.command {
+        ScriptCommand::Run(args) => run_script(args, global),
+    }
+}
+
+/// Splits `--arg NAME=VALUE` options.
+#[cfg_attr(not(feature = "scripting"), allow(dead_code))]
+fn parse_args(args: &[String]) -> Result<Vec<(String, String)>> {
+    args.iter()
+        .map(|arg| match arg.split_once('=') {
+            Some((name, value)) if !name.trim().is_empty() => {
+                Ok((name.trim().to_string(), value.to_string()))
+            }
+            _ => anyhow::bail!("Invalid --arg '{}': expected NAME=VALUE", arg),
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "scripting"))]
+fn run_script(_args: &RunArgs, _global: &GlobalOptions) -> Result<()> {
+    anyhow::bail!("script run needs xlex built with the scripting feature")
+}
+
+#[cfg(feature = "scripting")]
+fn run_script(args: &RunArgs, global: &GlobalOptions) -> Result<()> {
+    use colored::Colorize;
+    use xlex_core::Workbook;
+
+    use super::output::CommandOutput;
+
+    let dest = args.out.as_deref().unwrap_or(&args.file);
+    if args.out.is_some() && dest.exists() && !args.force {
+        return Err(xlex_core::XlexError::FileExists {
+            path: dest.to_path_buf(),
+        }
+        .into());
+    }
+    let vars = parse_args(&args.args)?;
+    let source = std::fs::read_to_string(&args.script)
+        .map_err(|e| anyhow::anyhow!("Cannot read {}: {}", args.script.display(), e))?;
+    let workbook = Workbook::open(&args.file)?;
+
+    let limits = engine::Limits {
+        max_operations: args.max_operations,
+        verbose: global.verbose,
+    };
+    let (workbook, changes) = engine::execute(workbook, &source, &vars, &limits)
+        .map_err(|e| anyhow::anyhow!("{}: {}", args.script.display(), e))?;
+
+    let summary = changes.summary();
+    if global.dry_run {
+        outln!("Would write {} to {}", summary, dest.display());
+        return Ok(());
+    }
+    if args.out.is_some() {
+        workbook.save_as(dest)?;
+    } else {
+        workbook.save()?;
+    }
+
+    CommandOutput::new("script")
+        .file(dest)
+        .target("script", args.script.display().to_string())
+        .count("cells", changes.cells)
+        .field("sheetsAdded", changes.sheets_added.clone())
+        .print(
+            global,
+            format!(
+                "Ran {}: {} in {}",
+                args.script.display().to_string().cyan(),
+                summary,
+                dest.display().to_string().green()
+            ),
+        )?;
+    Ok(())
+}
+
+/// The Rhai engine and the workbook API it exposes.
+#[cfg(feature = "scripting")]
+mod engine {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use rhai::{Array, Dynamic, Engine, EvalAltResult, Map, Scope, INT};
+    use xlex_core::{CellRef, CellValue, Sheet, Workbook, XlexError};
+
+    type Fallible<T> = Result<T, Box<EvalAltResult>>;
+
+    /// Resource limits for a script.
+    pub(super) struct Limits {
+        pub max_operations: u64,
+        /// Show `debug()` output
+        pub verbose: bool,
+    }
+
+    /// What a script changed.
+    #[derive(Debug, Default)]
+    pub(super) struct Changes {
+        /// Cell writes, including clears
+        pub cells: usize,
+        pub sheets_added: Vec<String>,
+    }
+
+    impl Changes {
+        /// "3 cells and 1 sheet (Summary)"
+        pub(super) fn summary(&self) -> String {
+            let plural =
+                |n: usize, word: &str| format!("{} {}{}", n, word, if n == 1 { "" } else { "s" });
+            let mut summary = plural(self.cells, "cell");
+            if !self.sheets_added.is_empty() {
+                summary.push_str(&format!(
+                    " and {} ({})",
+                    plural(self.sheets_added.len(), "sheet"),
+                    self.sheets_added.join(", ")
+                ));
+            }
+            summary
+        }
+    }
+
+    struct State {
+        workbook: Workbook,
+        changes: Changes,
+    }
+
+    type Shared = Rc<RefCell<State>>;
+
+    fn error(e: impl std::fmt::Display) -> Box<EvalAltResult> {
+        e.to_string().into()
+    }
+
+    fn sheet<'a>(workbook: &'a Workbook, name: &str) -> Fallible<&'a Sheet> {
+        workbook.get_sheet(name).ok_or_else(|| {
+            error(XlexError::SheetNotFound {
+                name: name.to_string(),
+            })
+        })
+    }
+
+    fn cell_ref(cell: &str) -> Fallible<CellRef> {
+        CellRef::parse(cell).map_err(error)
+    }
+
+    /// A 1-based row and column.
+    fn position(row: INT, col: INT) -> Fallible<CellRef> {
+        match (u32::try_from(row), u32::try_from(col)) {
+            (Ok(row), Ok(col)) if row > 0 && col > 0 => Ok(CellRef::new(col, row)),
+            _ => Err(error(format!("invalid row {} or column {}", row, col))),
+        }
+    }
+
+    /// A cell's value as the script sees it: whole numbers as integers, a
+    /// formula as its cached result, and an empty cell as `()`.
+    fn to_dynamic(value: &CellValue) -> Dynamic {
+        match value {
+            CellValue::Empty => Dynamic::UNIT,
+            CellValue::String(s) => s.clone().into(),
+            CellValue::Number(n) | CellValue::DateTime(n) => {
+                if n.fract() == 0.0 && n.abs() < 1e15 {
+                    (*n as INT).into()
+                } else {
+                    (*n).into()
+                }
+            }
+            CellValue::Boolean(b) => (*b).into(),
+            CellValue::Formula { cached_result, .. } => {
+                cached_result.as_deref().map_or(Dynamic::UNIT, to_dynamic)
+            }
+            CellValue::Error(e) => e.to_string().into(),
+            CellValue::Decimal(d) => to_dynamic(&CellValue::Number(d.to_f64())),
+        }
+    }
+
+    fn from_dynamic(value: Dynamic) -> Fallible<CellValue> {
+        if value.is_unit() {
+            Ok(CellValue::Empty)
+        } else if let Some(n) = value.clone().try_cast::<INT>() {
+            Ok(CellValue::Number(n as f64))
+        } else if let Some(n) = value.clone().try_cast::<f64>() {
+            Ok(CellValue::Number(n))
+        } else if let Some(b) = value.clone().try_cast::<bool>() {
+            Ok(CellValue::Boolean(b))
+        } else if value.is_string() || value.is_char() {
+            Ok(CellValue::String(value.to_string()))
+        } else {
+            Err(error(format!(
+                "cannot store a value of type {} in a cell",
+                value.type_name()
+            )))
+        }
+    }
+
+    fn write(state: &Shared, sheet: &str, cell_ref: CellRef, value: CellValue) -> Fallible<()> {
+        let mut state = state.borrow_mut();
+        state
+            .workbook
+            .with_sheet_mut(sheet, |s| {
+                if value.is_empty() {
+                    s.clear_cell(&cell_ref);
+                } else {
+                    s.set_cell(cell_ref, value);
+                }
+            })
+            .map_err(error)?;
+        state.changes.cells += 1;
+        Ok(())
+    }
+
+    fn read(state: &Shared, name: &str, cell_ref: &CellRef) -> Fallible<Dynamic> {
+        let state = state.borrow();
+        Ok(to_dynamic(
+            &sheet(&state.workbook, name)?.get_value(cell_ref),
+        ))
+    }
+
+    /// Registers the workbook functions.
+    fn register(engine: &mut Engine, state: &Shared) {
+        let s = state.clone();
+        engine.register_fn("sheets", move || -> Array {
+            s.borrow()
+                .workbook
+                .sheet_names()
+                .into_iter()
+                .map(|name| name.to_string().into())
+                .collect()
+        });
+        let s = state.clone();
+        engine.register_fn("has_sheet", move |name: &str| -> bool {
+            s.borrow().workbook.get_sheet(name).is_some()
+        });
+        let s = state.clone();
+        engine.register_fn("add_sheet", move |name: &str| -> Fallible<()> {
+            let mut state = s.borrow_mut();
+            state.workbook.add_sheet(name).map_err(error)?;
+            state.changes.sheets_added.push(name.to_string());
+            Ok(())
+        });
+
+        let s = state.clone();
+        engine.register_fn("get", move |sheet: &str, cell: &str| -> Fallible<Dynamic> {
+            read(&s, sheet, &cell_ref(cell)?)
+        });
+        let s = state.clone();
+        engine.register_fn(
+            "get",
+            move |sheet: &str, row: INT, col: INT| -> Fallible<Dynamic> {
+                read(&s, sheet, &position(row, col)?)
+            },
+        );
+        let s = state.clone();
+        engine.register_fn(
+            "set",
+            move |sheet: &str, cell: &str, value: Dynamic| -> Fallible<()> {
+                write(&s, sheet, cell_ref(cell)?, from_dynamic(value)?)
+            },
+        );
+        let s = state.clone();
+        engine.register_fn(
+            "set",
+            move |sheet: &str, row: INT, col: INT, value: Dynamic| -> Fallible<()> {
+                write(&s, sheet, position(row, col)?, from_dynamic(value)?)
+            },
+        );
+        let s = state.clone();
+        engine.register_fn("clear", move |sheet: &str, cell: &str| -> Fallible<()> {
+            write(&s, sheet, cell_ref(cell)?, CellValue::Empty)
+        });
+        let s = state.clone();
+        engine.register_fn(
+            "formula",
+            move |sheet_name: &str, cell: &str| -> Fallible<Dynamic> {
+                let state = s.borrow();
+                let value = sheet(&state.workbook, sheet_name)?.get_value(&cell_ref(cell)?);
+                Ok(match value {
+                    CellValue::Formula { formula, .. } => formula.into(),
+                    _ => Dynamic::UNIT,
+                })
+            },
+        );
+        let s = state.clone();
+        engine.register_fn(
+            "set_formula",
+            move |sheet: &str, cell: &str, formula: &str| -> Fallible<()> {
+                let formula = formula.strip_prefix('=').unwrap_or(formula);
+                write(&s, sheet, cell_ref(cell)?, CellValue::formula(formula))
+            },
+        );
+
+        let s = state.clone();
+        engine.register_fn("last_row", move |name: &str| -> Fallible<INT> {
+            let state = s.borrow();
+            Ok(INT::from(sheet(&state.workbook, name)?.dimensions().1))
+        });
+        let s = state.clone();
+        engine.register_fn("last_col", move |name: &str| -> Fallible<INT> {
+            let state = s.borrow();
+            Ok(INT::from(sheet(&state.workbook, name)?.dimensions().0))
+        });
+        let s = state.clone();
+        engine.register_fn("rows", move |name: &str| -> Fallible<Array> {
+            let state = s.borrow();
+            let sheet = sheet(&state.workbook, name)?;
+            let (cols, rows) = sheet.dimensions();
+            Ok((1..=rows)
+                .map(|row| {
+                    let values: Array = (1..=cols)
+                        .map(|col| to_dynamic(&sheet.get_value(&CellRef::new(col, row))))
+                        .collect();
+                    values.into()
+                })
+                .collect())
+        });
+    }
+
+    /// A sandboxed engine: no modules, no `eval`, bounded work and memory.
+    fn sandboxed_engine(limits: &Limits) -> Engine {
+        let mut engine = Engine::new();
+        engine
+            .set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new())
+            .set_max_operations(limits.max_operations)
+            .set_max_call_levels(64)
+            .set_max_expr_depths(64, 32)
+            .set_max_string_size(10 << 20)
+            .set_max_array_size(1_000_000)
+            .set_max_map_size(100_000)
+            .disable_symbol("eval");
+        // Keep standard output for the command's own report
+        engine.on_print(|text| eprintln!("{}", text));
+        let verbose = limits.verbose;
+        engine.on_debug(move |text, _, pos| {
+            if verbose {
+                eprintln!("[{}] {}", pos, text);
+            }
+        });
+        engine
+    }
+
+    /// Runs `source` against `workbook`, with `vars` as the `ARGS` map.
+    pub(super) fn execute(
+        workbook: Workbook,
+        source: &str,
+        vars: &[(String, String)],
+        limits: &Limits,
+    ) -> Result<(Workbook, Changes), Box<EvalAltResult>> {
+        let state = Rc::new(RefCell::new(State {
+            workbook,
+            changes: Changes::default(),
+        }));
+        let mut engine = sandboxed_engine(limits);
+        register(&mut engine, &state);
+
+        let args: Map = vars
+            .iter()
+            .map(|(name, value)| (name.as_str().into(), value.clone().into()))
+            .collect();
+        let mut scope = Scope::new();
+        scope.push_constant("ARGS", args);
+        engine.run_with_scope(&mut scope, source)?;
+
+        // The engine holds the other references to the state
+        drop(engine);
+        let state = Rc::try_unwrap(state)
+            .map_err(|_| error("script state is still in use"))?
+            .into_inner();
+        Ok((state.workbook, state.changes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            parse_args(&["rate=0.2".to_string(), "label=a=b".to_string()]).unwrap(),
+            vec![
+                ("rate".to_string(), "0.2".to_string()),
+                ("label".to_string(), "a=b".to_string())
+            ]
+        );
+        assert!(parse_args(&["rate".to_string()]).is_err());
+        assert!(parse_args(&["=1".to_string()]).is_err());
+    }
+
+    #[cfg(feature = "scripting")]
+    mod scripting {
+        use super::super::engine::{execute, Limits};
+        use super::super::*;
+        use crate::commands::OutputFormat;
+        use tempfile::TempDir;
+        use xlex_core::{CellRef, CellValue, Workbook};
+
+        fn default_global() -> GlobalOptions {
+            GlobalOptions {
+                quiet: true,
+                verbose: false,
+                format: OutputFormat::Text,
+                no_color: true,
+                color: false,
+                json_errors: false,
+                dry_run: false,
+                output: None,
+                append: false,
+                tee: false,
+                no_progress: false,
+                trace_output: None,
+                fuzzy_sheets: false,
+            }
+        }
+
+        fn limits() -> Limits {
+            Limits {
+                max_operations: 100_000,
+                verbose: false,
+            }
+        }
+
+        fn prices() -> Workbook {
+            let mut wb = Workbook::with_sheets(&["Prices"]);
+            for (row, (item, price)) in [("item", ""), ("pen", "2"), ("ink", "7.5")]
+                .into_iter()
+                .enumerate()
+            {
+                let row = row as u32 + 1;
+                wb.set_cell("Prices", CellRef::new(1, row), CellValue::string(item))
+                    .unwrap();
+                let price = match price.parse::<f64>() {
+                    Ok(n) => CellValue::Number(n),
+                    Err(_) => CellValue::string("price"),
+                };
+                wb.set_cell("Prices", CellRef::new(2, row), price).unwrap();
+            }
+            wb
+        }
+
+        fn value(wb: &Workbook, sheet: &str, cell: &str) -> CellValue {
+            wb.get_sheet(sheet)
+                .unwrap()
+                .get_value(&CellRef::parse(cell).unwrap())
+        }
+
+        #[test]
+        fn test_execute_reads_and_writes_cells() {
+            let script = r#"
+                let rate = parse_float(ARGS.rate);
+                set("Prices", "C1", "with tax");
+                let rows = rows("Prices");
+                for r in 1..rows.len() {
+                    set("Prices", r + 1, 3, rows[r][1] * (1.0 + rate));
+                }
+                add_sheet("Summary");
+                set("Summary", "A1", get("Prices", 2, 1) + " and " + get("Prices", "A3"));
+                set_formula("Summary", "B1", "=SUM(Prices!C2:C3)");
+                clear("Prices", "A1");
+            "#;
+            let vars = vec![("rate".to_string(), "0.5".to_string())];
+            let (wb, changes) = execute(prices(), script, &vars, &limits()).unwrap();
+
+            assert_eq!(value(&wb, "Prices", "C2"), CellValue::Number(3.0));
+            assert_eq!(value(&wb, "Prices", "C3"), CellValue::Number(11.25));
+            assert_eq!(value(&wb, "Prices", "A1"), CellValue::Empty);
+            assert_eq!(
+                value(&wb, "Summary", "A1"),
+                CellValue::string("pen and ink")
+            );
+            assert_eq!(
+                value(&wb, "Summary", "B1"),
+                CellValue::formula("SUM(Prices!C2:C3)")
+            );
+            assert_eq!(changes.cells, 6);
+            assert_eq!(changes.sheets_added, vec!["Summary"]);
+            assert_eq!(changes.summary(), "6 cells and 1 sheet (Summary)");
+        }
+
+        #[test]
+        fn test_execute_values() {
+            let script = r#"
+                if type_of(get("Prices", "B2")) != "i64" { throw "not an integer"; }
+                if get("Prices", "Z9") != () { throw "not empty"; }
+                if last_row("Prices") != 3 || last_col("Prices") != 2 { throw "size"; }
+                if !has_sheet("Prices") || sheets() != ["Prices"] { throw "sheets"; }
+                if formula("Prices", "A1") != () { throw "formula"; }
+            "#;
+            let (_, changes) = execute(prices(), script, &[], &limits()).unwrap();
+            assert_eq!(changes.summary(), "0 cells");
+        }
+
+        #[test]
+        fn test_execute_errors() {
+            let run = |script: &str| {
+                execute(prices(), script, &[], &limits())
+                    .unwrap_err()
+                    .to_string()
+            };
+            assert!(run(r#"get("Nope", "A1")"#).contains("Nope"));
+            assert!(run(r#"set("Prices", "A1", [1, 2])"#).contains("array"));
+            assert!(run(r#"set("Prices", 0, 1, 1)"#).contains("invalid row 0"));
+            assert!(run(r#"add_sheet("Prices")"#).contains("Prices"));
+            assert!(run("let x = ;").contains("line 1"));
+        }
+
+        #[test]
+        fn test_execute_is_sandboxed() {
+            let run = |script: &str| execute(prices(), script, &[], &limits()).is_err();
+            assert!(run(r#"import "other" as other;"#));
+            assert!(run(r#"eval("1 + 1")"#));
+            assert!(run("loop {}"));
+        }
+
+        #[test]
+        fn test_run_script() {
+            let dir = TempDir::new().unwrap();
+            let file = dir.path().join("prices.xlsx");
+            let wb = prices();
+            wb.save_as(&file).unwrap();
+            let script = dir.path().join("double.rhai");
+            std::fs::write(&script, r#"set("Prices", "B2", get("Prices", "B2") * 2);"#).unwrap();
+            let args = |out: Option<std::path::PathBuf>| RunArgs {
+                file: file.clone(),
+                script: script.clone(),
+                args: Vec::new(),
+                out,
+                force: false,
+                max_operations: 1000,
+            };
+
+            let mut global = default_global();
+            global.dry_run = true;
+            run_script(&args(None), &global).unwrap();
+            let wb = Workbook::open(&file).unwrap();
+            assert_eq!(value(&wb, "Prices", "B2"), CellValue::Number(2.0));
+
+            let out = dir.path().join("out.xlsx");
+            run_script(&args(Some(out.clone())), &default_global()).unwrap();
+            let wb = Workbook::open(&out).unwrap();
+            assert_eq!(value(&wb, "Prices", "B2"), CellValue::Number(4.0));
+            assert!(run_script(&args(Some(out)), &default_global()).is_err());
+
+            run_script(&args(None), &default_global()).unwrap();
+            let wb = Workbook::open(&file).unwrap();
+            assert_eq!(value(&wb, "Prices", "B2"), CellValue::Number(4.0));
+        }
+    }
+}
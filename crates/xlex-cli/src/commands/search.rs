@@ -141,7 +141,9 @@ pub fn run(args: &SearchArgs, global: &GlobalOptions) -> Result<()> {
         OutputFormat::Json => print_json(&matches, &args.pattern)?,
         OutputFormat::Csv => print_csv(&matches)?,
         OutputFormat::Ndjson => print_ndjson(&matches)?,
-        OutputFormat::Text => print_text(&matches, &args.pattern, global)?,
+        OutputFormat::Text | OutputFormat::HtmlTable => {
+            print_text(&matches, &args.pattern, global)?
+        }
     }
 
     Ok(())
@@ -150,7 +152,7 @@ pub fn run(args: &SearchArgs, global: &GlobalOptions) -> Result<()> {
 fn print_text(matches: &[SearchMatch], pattern: &str, global: &GlobalOptions) -> Result<()> {
     if matches.is_empty() {
         if !global.quiet {
-            println!(
+            outln!(
                 "{}: no matches found for \"{}\"",
                 "search".yellow(),
                 pattern
@@ -160,7 +162,7 @@ fn print_text(matches: &[SearchMatch], pattern: &str, global: &GlobalOptions) ->
     }
 
     if !global.quiet {
-        println!(
+        outln!(
             "Found {} match{} for \"{}\":\n",
             matches.len().to_string().green(),
             if matches.len() == 1 { "" } else { "es" },
@@ -172,16 +174,16 @@ fn print_text(matches: &[SearchMatch], pattern: &str, global: &GlobalOptions) ->
     for m in matches {
         if m.sheet != current_sheet {
             if !current_sheet.is_empty() {
-                println!();
+                outln!();
             }
-            println!("  {} {}", "Sheet:".bold(), m.sheet.bold());
+            outln!("  {} {}", "Sheet:".bold(), m.sheet.bold());
             current_sheet = &m.sheet;
         }
-        println!("    {} = {}", m.cell.yellow(), m.value.dimmed());
+        outln!("    {} = {}", m.cell.yellow(), m.value.dimmed());
     }
 
     if !global.quiet {
-        println!();
+        outln!();
     }
 
     Ok(())
@@ -193,12 +195,12 @@ fn print_json(matches: &[SearchMatch], pattern: &str) -> Result<()> {
         "count": matches.len(),
         "matches": matches,
     });
-    println!("{}", serde_json::to_string_pretty(&json)?);
+    outln!("{}", serde_json::to_string_pretty(&json)?);
     Ok(())
 }
 
 fn print_csv(matches: &[SearchMatch]) -> Result<()> {
-    println!("sheet,cell,row,col,value");
+    outln!("sheet,cell,row,col,value");
     for m in matches {
         // Escape value for CSV
         let escaped = if m.value.contains(',') || m.value.contains('"') || m.value.contains('\n') {
@@ -206,14 +208,14 @@ fn print_csv(matches: &[SearchMatch]) -> Result<()> {
         } else {
             m.value.clone()
         };
-        println!("{},{},{},{},{}", m.sheet, m.cell, m.row, m.col, escaped);
+        outln!("{},{},{},{},{}", m.sheet, m.cell, m.row, m.col, escaped);
     }
     Ok(())
 }
 
 fn print_ndjson(matches: &[SearchMatch]) -> Result<()> {
     for m in matches {
-        println!("{}", serde_json::to_string(m)?);
+        outln!("{}", serde_json::to_string(m)?);
     }
     Ok(())
 }
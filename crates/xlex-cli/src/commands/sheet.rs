@@ -4,8 +4,11 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use colored::Colorize;
 
-use xlex_core::Workbook;
+use xlex_core::protection::{SheetAction, SheetProtection};
+use xlex_core::sheet::quote_sheet_name;
+use xlex_core::{CellRef, CellValue, Workbook};
 
+use super::output::CommandOutput;
 use super::{GlobalOptions, OutputFormat};
 
 /// Arguments for sheet operations.
@@ -48,6 +51,14 @@ pub enum SheetCommand {
         /// New name for the sheet
         new_name: String,
     },
+    /// Rename sheets matching a sed-style regex substitution
+    RenameAll {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Substitution, e.g. 's/^FY23/FY24/' (flags: g = all matches, i = ignore case)
+        #[arg(long)]
+        pattern: String,
+    },
     /// Copy a sheet
     Copy {
         /// Path to the xlsx file
@@ -57,6 +68,20 @@ pub enum SheetCommand {
         /// Name for the new sheet
         dest: String,
     },
+    /// Copy a sheet from another workbook with its styles and layout
+    Import {
+        /// Path to the xlsx file to add the sheet to
+        file: std::path::PathBuf,
+        /// Workbook to copy the sheet from
+        #[arg(long)]
+        from: std::path::PathBuf,
+        /// Name of the sheet in the source workbook
+        #[arg(long)]
+        sheet: String,
+        /// Name for the new sheet (default: the source name)
+        #[arg(long = "as")]
+        new_name: Option<String>,
+    },
     /// Move a sheet to a different position
     Move {
         /// Path to the xlsx file
@@ -66,6 +91,14 @@ pub enum SheetCommand {
         /// New position (0-indexed)
         position: usize,
     },
+    /// Reorder sheets by name
+    Reorder {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// New order, comma-separated; * stands for all other sheets (e.g., Summary,Data,*)
+        #[arg(long, value_delimiter = ',', required = true)]
+        order: Vec<String>,
+    },
     /// Hide a sheet
     Hide {
         /// Path to the xlsx file
@@ -83,6 +116,30 @@ pub enum SheetCommand {
         /// Name of the sheet to unhide
         name: String,
     },
+    /// Protect a sheet so only unlocked cells can be edited
+    Protect {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Name of the sheet to protect
+        name: String,
+        /// Actions still allowed, comma-separated (sort, filter, format-cells,
+        /// format-columns, format-rows, insert-rows, insert-columns,
+        /// insert-hyperlinks, delete-rows, delete-columns, pivot-tables,
+        /// objects, scenarios)
+        #[arg(long, value_delimiter = ',')]
+        allow: Vec<String>,
+        /// Password Excel asks for before unprotecting (a legacy hash that
+        /// deters accidental edits, not a security measure)
+        #[arg(long)]
+        password: Option<String>,
+    },
+    /// Remove a sheet's protection
+    Unprotect {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Name of the sheet to unprotect
+        name: String,
+    },
     /// Show sheet information
     Info {
         /// Path to the xlsx file
@@ -90,6 +147,32 @@ pub enum SheetCommand {
         /// Name of the sheet
         name: String,
     },
+    /// Show the declared dimension and the actual used range of a sheet
+    Dimensions {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Name of the sheet
+        name: String,
+    },
+    /// Set, clear or show the sheet's default style for unstyled columns
+    StyleDefault {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Name of the sheet
+        name: String,
+        /// Style ID (omit to show current)
+        style_id: Option<u32>,
+        /// Remove the sheet's default style
+        #[arg(long, conflicts_with = "style_id")]
+        clear: bool,
+    },
+    /// Transpose a sheet (swap rows and columns)
+    Transpose {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Name of the sheet to transpose
+        name: String,
+    },
     /// Set or display active sheet
     Active {
         /// Path to the xlsx file
@@ -97,6 +180,49 @@ pub enum SheetCommand {
         /// Name of the sheet to set as active (omit to show current)
         name: Option<String>,
     },
+    /// Set or remove a sheet background picture
+    Background {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Name of the sheet
+        name: String,
+        /// Image file to tile behind the cells (png, jpeg, gif or bmp)
+        #[arg(required_unless_present = "remove")]
+        image: Option<std::path::PathBuf>,
+        /// Remove the current background picture
+        #[arg(long, conflicts_with = "image")]
+        remove: bool,
+    },
+    /// Set or display print titles and print area
+    Print {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Name of the sheet
+        name: String,
+        /// Rows repeated at the top of every page (e.g., 1 or 1:2)
+        #[arg(long)]
+        title_rows: Option<String>,
+        /// Columns repeated at the left of every page (e.g., A or A:B)
+        #[arg(long)]
+        title_cols: Option<String>,
+        /// Area to print (e.g., A1:H50)
+        #[arg(long)]
+        area: Option<String>,
+        /// Clear print titles and print area
+        #[arg(long, conflicts_with_all = ["title_rows", "title_cols", "area"])]
+        clear: bool,
+    },
+    /// Add an index sheet linking to every visible sheet
+    Toc {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Name of the index sheet, placed first
+        #[arg(long, default_value = "Index")]
+        name: String,
+        /// Add a column with each sheet's row count
+        #[arg(long)]
+        row_counts: bool,
+    },
 }
 
 /// Run sheet operations.
@@ -114,7 +240,15 @@ pub fn run(args: &SheetArgs, global: &GlobalOptions) -> Result<()> {
             old_name,
             new_name,
         } => rename(file, old_name, new_name, global),
+        SheetCommand::RenameAll { file, pattern } => rename_all(file, pattern, global),
         SheetCommand::Copy { file, source, dest } => copy(file, source, dest, global),
+        SheetCommand::Import {
+            file,
+            from,
+            sheet,
+            new_name,
+        } => import(file, from, sheet, new_name.as_deref(), global),
+        SheetCommand::Reorder { file, order } => reorder(file, order, global),
         SheetCommand::Move {
             file,
             name,
@@ -122,8 +256,50 @@ pub fn run(args: &SheetArgs, global: &GlobalOptions) -> Result<()> {
         } => move_sheet(file, name, *position, global),
         SheetCommand::Hide { file, name, very } => hide(file, name, *very, global),
         SheetCommand::Unhide { file, name } => unhide(file, name, global),
+        SheetCommand::Protect {
+            file,
+            name,
+            allow,
+            password,
+        } => protect(file, name, allow, password.as_deref(), global),
+        SheetCommand::Unprotect { file, name } => unprotect(file, name, global),
         SheetCommand::Info { file, name } => info(file, name, global),
+        SheetCommand::Dimensions { file, name } => dimensions(file, name, global),
+        SheetCommand::StyleDefault {
+            file,
+            name,
+            style_id,
+            clear,
+        } => style_default(file, name, *style_id, *clear, global),
+        SheetCommand::Transpose { file, name } => transpose(file, name, global),
         SheetCommand::Active { file, name } => active(file, name.as_deref(), global),
+        SheetCommand::Background {
+            file,
+            name,
+            image,
+            remove: _,
+        } => background(file, name, image.as_deref(), global),
+        SheetCommand::Print {
+            file,
+            name,
+            title_rows,
+            title_cols,
+            area,
+            clear,
+        } => print_setup(
+            file,
+            name,
+            title_rows.as_deref(),
+            title_cols.as_deref(),
+            area.as_deref(),
+            *clear,
+            global,
+        ),
+        SheetCommand::Toc {
+            file,
+            name,
+            row_counts,
+        } => toc(file, name, *row_counts, global),
     }
 }
 
@@ -145,7 +321,7 @@ fn list(file: &std::path::Path, global: &GlobalOptions) -> Result<()> {
                 })
             })
             .collect();
-        println!("{}", serde_json::to_string_pretty(&sheets)?);
+        outln!("{}", serde_json::to_string_pretty(&sheets)?);
     } else {
         for (i, name) in workbook.sheet_names().iter().enumerate() {
             let visibility = workbook.get_sheet_visibility(name).unwrap_or_default();
@@ -159,7 +335,7 @@ fn list(file: &std::path::Path, global: &GlobalOptions) -> Result<()> {
             } else {
                 String::new()
             };
-            println!("{}. {}{}{}", i + 1, name, active, vis);
+            outln!("{}. {}{}{}", i + 1, name, active, vis);
         }
     }
 
@@ -173,50 +349,42 @@ fn add(
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!("Would add sheet '{}' to {}", name, file.display());
+        outln!("Would add sheet '{}' to {}", name, file.display());
         return Ok(());
     }
 
+    let output = CommandOutput::new("add");
+
     let mut workbook = Workbook::open(file)?;
     workbook.add_sheet(name)?;
     workbook.save()?;
 
-    if !global.quiet {
-        if global.format == OutputFormat::Json {
-            let json = serde_json::json!({
-                "action": "add",
-                "sheet": name,
-            });
-            println!("{}", serde_json::to_string_pretty(&json)?);
-        } else {
-            println!("Added sheet '{}'", name.green());
-        }
-    }
+    output
+        .file(file)
+        .target("sheet", name)
+        .field("sheet", name)
+        .print(global, format!("Added sheet '{}'", name.green()))?;
 
     Ok(())
 }
 
 fn remove(file: &std::path::Path, name: &str, global: &GlobalOptions) -> Result<()> {
     if global.dry_run {
-        println!("Would remove sheet '{}' from {}", name, file.display());
+        outln!("Would remove sheet '{}' from {}", name, file.display());
         return Ok(());
     }
 
+    let output = CommandOutput::new("remove");
+
     let mut workbook = Workbook::open(file)?;
     workbook.remove_sheet(name)?;
     workbook.save()?;
 
-    if !global.quiet {
-        if global.format == OutputFormat::Json {
-            let json = serde_json::json!({
-                "action": "remove",
-                "sheet": name,
-            });
-            println!("{}", serde_json::to_string_pretty(&json)?);
-        } else {
-            println!("Removed sheet '{}'", name.red());
-        }
-    }
+    output
+        .file(file)
+        .target("sheet", name)
+        .field("sheet", name)
+        .print(global, format!("Removed sheet '{}'", name.red()))?;
 
     Ok(())
 }
@@ -228,7 +396,7 @@ fn rename(
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!(
+        outln!(
             "Would rename sheet '{}' to '{}' in {}",
             old_name,
             new_name,
@@ -237,20 +405,227 @@ fn rename(
         return Ok(());
     }
 
+    let output = CommandOutput::new("rename");
+
     let mut workbook = Workbook::open(file)?;
     workbook.rename_sheet(old_name, new_name)?;
     workbook.save()?;
 
+    output
+        .file(file)
+        .target("sheet", old_name)
+        .field("oldName", old_name)
+        .field("newName", new_name)
+        .print(
+            global,
+            format!("Renamed '{}' to '{}'", old_name.cyan(), new_name.green()),
+        )?;
+
+    Ok(())
+}
+
+/// A parsed sed-style substitution (`s/pattern/replacement/flags`).
+struct Substitution {
+    regex: regex_lite::Regex,
+    replacement: String,
+    global: bool,
+}
+
+impl Substitution {
+    fn parse(expr: &str) -> Result<Self> {
+        let invalid = || {
+            anyhow::anyhow!(
+                "Invalid pattern '{}', expected s/regex/replacement/[gi]",
+                expr
+            )
+        };
+        let mut chars = expr.chars();
+        if chars.next() != Some('s') {
+            return Err(invalid());
+        }
+        let delimiter = chars
+            .next()
+            .filter(|c| !c.is_alphanumeric() && *c != '\\')
+            .ok_or_else(invalid)?;
+
+        // Split on unescaped delimiters; `\/` stands for a literal delimiter
+        let mut parts = Vec::new();
+        let mut current = String::new();
+        let mut escaped = false;
+        for c in chars {
+            if escaped {
+                if c != delimiter {
+                    current.push('\\');
+                }
+                current.push(c);
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == delimiter {
+                parts.push(std::mem::take(&mut current));
+            } else {
+                current.push(c);
+            }
+        }
+        parts.push(current);
+        if parts.len() != 3 {
+            return Err(invalid());
+        }
+        let flags = &parts[2];
+        if let Some(flag) = flags.chars().find(|c| !matches!(c, 'g' | 'i')) {
+            anyhow::bail!("Unknown substitution flag '{}' (expected g or i)", flag);
+        }
+
+        let pattern = if flags.contains('i') {
+            format!("(?i){}", parts[0])
+        } else {
+            parts[0].clone()
+        };
+        let regex = regex_lite::Regex::new(&pattern)
+            .map_err(|e| anyhow::anyhow!("Invalid regex '{}': {}", parts[0], e))?;
+
+        // sed writes groups as \1; the regex crate as ${1}
+        let mut replacement = String::new();
+        let mut rest = parts[1].chars().peekable();
+        while let Some(c) = rest.next() {
+            match (c, rest.peek()) {
+                ('\\', Some(d)) if d.is_ascii_digit() => {
+                    replacement.push_str(&format!("${{{}}}", d));
+                    rest.next();
+                }
+                ('$', _) => replacement.push_str("$$"),
+                _ => replacement.push(c),
+            }
+        }
+
+        Ok(Self {
+            regex,
+            replacement,
+            global: flags.contains('g'),
+        })
+    }
+
+    fn apply(&self, text: &str) -> String {
+        if self.global {
+            self.regex.replace_all(text, self.replacement.as_str())
+        } else {
+            self.regex.replace(text, self.replacement.as_str())
+        }
+        .into_owned()
+    }
+}
+
+fn rename_all(file: &std::path::Path, pattern: &str, global: &GlobalOptions) -> Result<()> {
+    let substitution = Substitution::parse(pattern)?;
+    let mut workbook = Workbook::open(file)?;
+    let names: Vec<String> = workbook
+        .sheet_names()
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let renames: Vec<(String, String)> = names
+        .iter()
+        .map(|name| (name.clone(), substitution.apply(name)))
+        .filter(|(old, new)| old != new)
+        .collect();
+
+    // Every sheet must still have a distinct name afterwards
+    let mut final_names: Vec<&str> = names
+        .iter()
+        .map(|name| {
+            renames
+                .iter()
+                .find(|(old, _)| old == name)
+                .map(|(_, new)| new.as_str())
+                .unwrap_or(name)
+        })
+        .collect();
+    final_names.sort_unstable();
+    if let Some(pair) = final_names.windows(2).find(|w| w[0] == w[1]) {
+        anyhow::bail!("Renaming would create two sheets named '{}'", pair[0]);
+    }
+
+    if global.dry_run {
+        for (old, new) in &renames {
+            outln!("Would rename sheet '{}' to '{}'", old, new);
+        }
+        return Ok(());
+    }
+
+    // Apply renames whose target name is free; a cycle (A -> B, B -> A)
+    // goes through a temporary name
+    let mut pending = renames.clone();
+    let mut temp_count = 0;
+    while !pending.is_empty() {
+        let ready = pending
+            .iter()
+            .position(|(_, new)| workbook.get_sheet(new).is_none());
+        match ready {
+            Some(i) => {
+                let (old, new) = pending.remove(i);
+                workbook.rename_sheet(&old, &new)?;
+            }
+            None => {
+                temp_count += 1;
+                let temp = format!("__xlex_rename_{}", temp_count);
+                let (old, new) = pending.remove(0);
+                workbook.rename_sheet(&old, &temp)?;
+                pending.push((temp, new));
+            }
+        }
+    }
+    if !renames.is_empty() {
+        workbook.save()?;
+    }
+
+    if !global.quiet {
+        if global.format == OutputFormat::Json {
+            let items: Vec<serde_json::Value> = renames
+                .iter()
+                .map(|(old, new)| serde_json::json!({ "oldName": old, "newName": new }))
+                .collect();
+            let json = serde_json::json!({
+                "action": "rename-all",
+                "renamed": items,
+            });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else if renames.is_empty() {
+            outln!("No sheet names matched");
+        } else {
+            for (old, new) in &renames {
+                outln!("Renamed '{}' to '{}'", old.cyan(), new.green());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn reorder(file: &std::path::Path, order: &[String], global: &GlobalOptions) -> Result<()> {
+    if global.dry_run {
+        outln!(
+            "Would reorder sheets as {} in {}",
+            order.join(","),
+            file.display()
+        );
+        return Ok(());
+    }
+
+    let mut workbook = Workbook::open(file)?;
+    let order: Vec<&str> = order.iter().map(|s| s.trim()).collect();
+    workbook.reorder_sheets(&order)?;
+    workbook.save()?;
+
     if !global.quiet {
+        let names = workbook.sheet_names();
         if global.format == OutputFormat::Json {
             let json = serde_json::json!({
-                "action": "rename",
-                "oldName": old_name,
-                "newName": new_name,
+                "action": "reorder",
+                "sheets": names,
             });
-            println!("{}", serde_json::to_string_pretty(&json)?);
+            outln!("{}", serde_json::to_string_pretty(&json)?);
         } else {
-            println!("Renamed '{}' to '{}'", old_name.cyan(), new_name.green());
+            outln!("Sheet order: {}", names.join(", ").green());
         }
     }
 
@@ -259,7 +634,7 @@ fn rename(
 
 fn copy(file: &std::path::Path, source: &str, dest: &str, global: &GlobalOptions) -> Result<()> {
     if global.dry_run {
-        println!(
+        outln!(
             "Would copy sheet '{}' to '{}' in {}",
             source,
             dest,
@@ -268,23 +643,67 @@ fn copy(file: &std::path::Path, source: &str, dest: &str, global: &GlobalOptions
         return Ok(());
     }
 
-    // TODO: Implement proper sheet copying with cell data
+    let output = CommandOutput::new("copy");
+
     let mut workbook = Workbook::open(file)?;
+    workbook.duplicate_sheet(source, dest)?;
+    workbook.save()?;
 
-    // Check source exists
-    if workbook.get_sheet(source).is_none() {
-        return Err(xlex_core::XlexError::SheetNotFound {
-            name: source.to_string(),
-        }
-        .into());
+    output
+        .file(file)
+        .target("source", source)
+        .target("destination", dest)
+        .print(
+            global,
+            format!("Copied '{}' to '{}'", source.cyan(), dest.green()),
+        )?;
+
+    Ok(())
+}
+
+fn import(
+    file: &std::path::Path,
+    from: &std::path::Path,
+    sheet: &str,
+    new_name: Option<&str>,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let name = new_name.unwrap_or(sheet);
+    if global.dry_run {
+        outln!(
+            "Would import sheet '{}' from {} as '{}' into {}",
+            sheet,
+            from.display(),
+            name,
+            file.display()
+        );
+        return Ok(());
     }
 
-    // Add new sheet
-    workbook.add_sheet(dest)?;
+    let source = Workbook::open(from)?;
+    let mut workbook = Workbook::open(file)?;
+    let index = workbook.import_sheet(&source, sheet, new_name)?;
     workbook.save()?;
 
     if !global.quiet {
-        println!("Copied '{}' to '{}'", source.cyan(), dest.green());
+        if global.format == OutputFormat::Json {
+            let imported = workbook.get_sheet_by_index(index);
+            let json = serde_json::json!({
+                "from": from.display().to_string(),
+                "sheet": sheet,
+                "name": name,
+                "index": index,
+                "cells": imported.map(|s| s.cell_count()).unwrap_or(0),
+            });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else {
+            outln!(
+                "Imported '{}' from {} as '{}'",
+                sheet.cyan(),
+                from.display(),
+                name.green()
+            );
+        }
     }
 
     Ok(())
@@ -297,7 +716,7 @@ fn move_sheet(
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!(
+        outln!(
             "Would move sheet '{}' to position {} in {}",
             name,
             position,
@@ -306,23 +725,32 @@ fn move_sheet(
         return Ok(());
     }
 
+    let output = CommandOutput::new("move");
+
     let mut workbook = Workbook::open(file)?;
     workbook.move_sheet(name, position)?;
     workbook.save()?;
 
-    if !global.quiet {
-        println!("Moved sheet '{}' to position {}", name.cyan(), position);
-    }
+    output
+        .file(file)
+        .target("sheet", name)
+        .field("position", position)
+        .print(
+            global,
+            format!("Moved sheet '{}' to position {}", name.cyan(), position),
+        )?;
 
     Ok(())
 }
 
 fn hide(file: &std::path::Path, name: &str, very: bool, global: &GlobalOptions) -> Result<()> {
     if global.dry_run {
-        println!("Would hide sheet '{}' in {}", name, file.display());
+        outln!("Would hide sheet '{}' in {}", name, file.display());
         return Ok(());
     }
 
+    let output = CommandOutput::new("hide");
+
     let mut workbook = Workbook::open(file)?;
     let visibility = if very {
         xlex_core::sheet::SheetVisibility::VeryHidden
@@ -332,27 +760,114 @@ fn hide(file: &std::path::Path, name: &str, very: bool, global: &GlobalOptions)
     workbook.set_sheet_visibility(name, visibility)?;
     workbook.save()?;
 
-    if !global.quiet {
-        println!("Hid sheet '{}'", name.dimmed());
-    }
+    output
+        .file(file)
+        .target("sheet", name)
+        .field("veryHidden", very)
+        .print(global, format!("Hid sheet '{}'", name.dimmed()))?;
 
     Ok(())
 }
 
 fn unhide(file: &std::path::Path, name: &str, global: &GlobalOptions) -> Result<()> {
     if global.dry_run {
-        println!("Would unhide sheet '{}' in {}", name, file.display());
+        outln!("Would unhide sheet '{}' in {}", name, file.display());
         return Ok(());
     }
 
+    let output = CommandOutput::new("unhide");
+
     let mut workbook = Workbook::open(file)?;
     workbook.set_sheet_visibility(name, xlex_core::sheet::SheetVisibility::Visible)?;
     workbook.save()?;
 
-    if !global.quiet {
-        println!("Unhid sheet '{}'", name.green());
+    output
+        .file(file)
+        .target("sheet", name)
+        .print(global, format!("Unhid sheet '{}'", name.green()))?;
+
+    Ok(())
+}
+
+fn protect(
+    file: &std::path::Path,
+    name: &str,
+    allow: &[String],
+    password: Option<&str>,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let allowed = allow
+        .iter()
+        .map(|action| SheetAction::parse(action.trim()))
+        .collect::<Result<Vec<_>, _>>()?;
+    if password == Some("") {
+        anyhow::bail!("--password cannot be empty");
+    }
+    if global.dry_run {
+        outln!("Would protect sheet '{}' in {}", name, file.display());
+        return Ok(());
     }
 
+    let output = CommandOutput::new("protect");
+
+    let mut workbook = Workbook::open(file)?;
+    let mut protection = SheetProtection::new(allowed);
+    if let Some(password) = password {
+        protection = protection.with_password(password);
+    }
+    let allowed: Vec<&str> = protection.allowed.iter().map(|a| a.name()).collect();
+    let summary = if allowed.is_empty() {
+        String::new()
+    } else {
+        format!(", allowing {}", allowed.join(", "))
+    };
+    let sheet =
+        workbook
+            .get_sheet_mut(name)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: name.to_string(),
+            })?;
+    sheet.set_protection(Some(protection));
+    workbook.save()?;
+
+    output
+        .file(file)
+        .target("sheet", name)
+        .field("allow", allowed)
+        .field("password", password.is_some())
+        .print(
+            global,
+            format!("Protected sheet '{}'{}", name.green(), summary),
+        )?;
+
+    Ok(())
+}
+
+fn unprotect(file: &std::path::Path, name: &str, global: &GlobalOptions) -> Result<()> {
+    if global.dry_run {
+        outln!("Would unprotect sheet '{}' in {}", name, file.display());
+        return Ok(());
+    }
+
+    let output = CommandOutput::new("unprotect");
+
+    let mut workbook = Workbook::open(file)?;
+    let sheet =
+        workbook
+            .get_sheet_mut(name)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: name.to_string(),
+            })?;
+    let was_protected = sheet.protection().is_some();
+    sheet.set_protection(None);
+    workbook.save()?;
+
+    output
+        .file(file)
+        .target("sheet", name)
+        .field("wasProtected", was_protected)
+        .print(global, format!("Unprotected sheet '{}'", name.green()))?;
+
     Ok(())
 }
 
@@ -370,13 +885,17 @@ fn info(file: &std::path::Path, name: &str, global: &GlobalOptions) -> Result<()
             "cellCount": sheet.cell_count(),
             "visibility": if sheet.info.visibility.is_visible() { "visible" } else { "hidden" },
             "index": sheet.info.index,
+            "protection": sheet.protection().map(|p| serde_json::json!({
+                "allow": p.allowed.iter().map(|a| a.name()).collect::<Vec<_>>(),
+                "password": p.password.is_some(),
+            })),
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else {
-        println!("{}: {}", "Name".bold(), sheet.name());
-        println!("{}: {}", "Index".cyan(), sheet.info.index);
-        println!("{}: {}", "Cells".cyan(), sheet.cell_count());
-        println!(
+        outln!("{}: {}", "Name".bold(), sheet.name());
+        outln!("{}: {}", "Index".cyan(), sheet.info.index);
+        outln!("{}: {}", "Cells".cyan(), sheet.cell_count());
+        outln!(
             "{}: {}",
             "Visibility".cyan(),
             if sheet.info.visibility.is_visible() {
@@ -385,25 +904,140 @@ fn info(file: &std::path::Path, name: &str, global: &GlobalOptions) -> Result<()
                 "hidden"
             }
         );
+        let protection = match sheet.protection() {
+            None => "none".to_string(),
+            Some(p) if p.allowed.is_empty() => "protected".to_string(),
+            Some(p) => {
+                let allowed: Vec<&str> = p.allowed.iter().map(|a| a.name()).collect();
+                format!("protected, allows {}", allowed.join(", "))
+            }
+        };
+        outln!("{}: {}", "Protection".cyan(), protection);
+    }
+
+    Ok(())
+}
+
+/// Declared dimension and used range of a sheet, as reported by `sheet dimensions`.
+fn dimensions_json(sheet: &xlex_core::Sheet) -> serde_json::Value {
+    let declared = sheet.declared_dimension().map(|r| r.to_a1());
+    let used = sheet.used_range().map(|r| r.to_a1());
+    serde_json::json!({
+        "name": sheet.name(),
+        "declared": declared,
+        "used": used,
+        "lastRow": sheet.last_row(),
+        "lastColumn": sheet.used_range().map(|r| xlex_core::CellRef::col_to_letters(r.end.col)),
+        "matches": declared.is_none() || declared == used,
+    })
+}
+
+fn dimensions(file: &std::path::Path, name: &str, global: &GlobalOptions) -> Result<()> {
+    let workbook = Workbook::open(file)?;
+    let sheet = workbook
+        .get_sheet(name)
+        .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+            name: name.to_string(),
+        })?;
+    let json = dimensions_json(sheet);
+
+    if global.format == OutputFormat::Json {
+        outln!("{}", serde_json::to_string_pretty(&json)?);
+    } else if !global.quiet {
+        let show = |value: &serde_json::Value| value.as_str().unwrap_or("-").to_string();
+        outln!("{}: {}", "Declared".cyan(), show(&json["declared"]));
+        outln!("{}: {}", "Used".cyan(), show(&json["used"]));
+        if json["matches"] == false {
+            outln!(
+                "  {}: declared dimension differs from the used range",
+                "Warning".yellow()
+            );
+        }
     }
 
     Ok(())
 }
 
+fn style_default(
+    file: &std::path::Path,
+    name: &str,
+    style_id: Option<u32>,
+    clear: bool,
+    global: &GlobalOptions,
+) -> Result<()> {
+    if style_id.is_none() && !clear {
+        let workbook = Workbook::open(file)?;
+        let sheet =
+            workbook
+                .get_sheet(name)
+                .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                    name: name.to_string(),
+                })?;
+
+        let style_id = sheet.default_style();
+        if global.format == OutputFormat::Json {
+            let json = serde_json::json!({
+                "sheet": name,
+                "styleId": style_id,
+            });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else if let Some(id) = style_id {
+            outln!("{}", id);
+        } else {
+            outln!("none");
+        }
+        return Ok(());
+    }
+
+    if global.dry_run {
+        match style_id {
+            Some(id) => outln!("Would set default style of '{}' to {}", name, id),
+            None => outln!("Would clear default style of '{}'", name),
+        }
+        return Ok(());
+    }
+
+    let output = CommandOutput::new("style-default");
+    let mut workbook = Workbook::open(file)?;
+    if let Some(id) = style_id {
+        super::style::ensure_style_exists(&workbook, id)?;
+    }
+    workbook.with_sheet_mut(name, |sheet| sheet.set_default_style(style_id))?;
+    workbook.save()?;
+
+    let message = match style_id {
+        Some(id) => format!(
+            "Set default style of '{}' to {}",
+            name.green(),
+            id.to_string().cyan()
+        ),
+        None => format!("Cleared default style of '{}'", name.green()),
+    };
+    output
+        .file(file)
+        .target("sheet", name)
+        .field("styleId", style_id)
+        .print(global, message)?;
+
+    Ok(())
+}
+
 fn active(file: &std::path::Path, name: Option<&str>, global: &GlobalOptions) -> Result<()> {
     if let Some(name) = name {
         if global.dry_run {
-            println!("Would set active sheet to '{}' in {}", name, file.display());
+            outln!("Would set active sheet to '{}' in {}", name, file.display());
             return Ok(());
         }
 
+        let output = CommandOutput::new("activate");
         let mut workbook = Workbook::open(file)?;
         workbook.set_active_sheet_by_name(name)?;
         workbook.save()?;
 
-        if !global.quiet {
-            println!("Set active sheet to '{}'", name.green());
-        }
+        output
+            .file(file)
+            .target("sheet", name)
+            .print(global, format!("Set active sheet to '{}'", name.green()))?;
     } else {
         let workbook = Workbook::open(file)?;
         let active_index = workbook.active_sheet_index();
@@ -414,9 +1048,284 @@ fn active(file: &std::path::Path, name: Option<&str>, global: &GlobalOptions) ->
                 "name": active_name,
                 "index": active_index,
             });
-            println!("{}", serde_json::to_string_pretty(&json)?);
+            outln!("{}", serde_json::to_string_pretty(&json)?);
         } else {
-            println!("{}", active_name);
+            outln!("{}", active_name);
+        }
+    }
+
+    Ok(())
+}
+
+fn transpose(file: &std::path::Path, name: &str, global: &GlobalOptions) -> Result<()> {
+    if global.dry_run {
+        outln!("Would transpose sheet '{}' in {}", name, file.display());
+        return Ok(());
+    }
+
+    let output = CommandOutput::new("transpose");
+
+    let mut workbook = Workbook::open(file)?;
+    let sheet =
+        workbook
+            .get_sheet_mut(name)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: name.to_string(),
+            })?;
+    sheet.transpose()?;
+    let (cols, rows) = sheet.dimensions();
+    workbook.save()?;
+
+    output
+        .file(file)
+        .target("sheet", name)
+        .field("sheet", name)
+        .field("columns", cols)
+        .field("rows", rows)
+        .print(global, format!("Transposed sheet '{}'", name.green()))?;
+
+    Ok(())
+}
+
+fn background(
+    file: &std::path::Path,
+    name: &str,
+    image: Option<&std::path::Path>,
+    global: &GlobalOptions,
+) -> Result<()> {
+    if global.dry_run {
+        match image {
+            Some(image) => outln!(
+                "Would set background of sheet '{}' to {} in {}",
+                name,
+                image.display(),
+                file.display()
+            ),
+            None => outln!(
+                "Would remove background of sheet '{}' in {}",
+                name,
+                file.display()
+            ),
+        }
+        return Ok(());
+    }
+
+    let picture = image
+        .map(xlex_core::sheet::SheetImage::from_path)
+        .transpose()?;
+    let size = picture.as_ref().map(|p| p.data.len());
+
+    let mut workbook = Workbook::open(file)?;
+    let sheet =
+        workbook
+            .get_sheet_mut(name)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: name.to_string(),
+            })?;
+    sheet.set_background(picture);
+    workbook.save()?;
+
+    if !global.quiet {
+        if global.format == OutputFormat::Json {
+            let json = serde_json::json!({
+                "action": if image.is_some() { "set" } else { "remove" },
+                "sheet": name,
+                "image": image.map(|p| p.display().to_string()),
+                "bytes": size,
+            });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else if let Some(image) = image {
+            outln!(
+                "Set background of sheet '{}' to {}",
+                name.green(),
+                image.display()
+            );
+        } else {
+            outln!("Removed background of sheet '{}'", name.green());
+        }
+    }
+
+    Ok(())
+}
+
+fn toc(file: &std::path::Path, name: &str, row_counts: bool, global: &GlobalOptions) -> Result<()> {
+    if global.dry_run {
+        outln!("Would add index sheet '{}' to {}", name, file.display());
+        return Ok(());
+    }
+
+    let output = CommandOutput::new("toc");
+
+    let mut workbook = Workbook::open(file)?;
+    // Hidden sheets are left out: links to them do nothing in Excel
+    let entries: Vec<(String, u32)> = workbook
+        .sheet_names()
+        .into_iter()
+        .filter_map(|sheet_name| workbook.get_sheet(sheet_name))
+        .filter(|sheet| sheet.info.visibility.is_visible())
+        .map(|sheet| (sheet.name().to_string(), sheet.last_row().unwrap_or(0)))
+        .collect();
+
+    workbook.add_sheet(name)?;
+    workbook.move_sheet(name, 0)?;
+    let mut bold = xlex_core::Style::default();
+    bold.font.bold = true;
+    let bold = workbook.style_registry_mut().add(bold);
+
+    let width = entries
+        .iter()
+        .map(|(sheet_name, _)| sheet_name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(5) as f64
+        + 2.0;
+    workbook.with_sheet_mut(name, |sheet| {
+        let mut headers = vec!["Sheet"];
+        if row_counts {
+            headers.push("Rows");
+        }
+        for (col, header) in (1u32..).zip(headers) {
+            sheet.set_cell(CellRef::new(col, 1), CellValue::from(header));
+            sheet.set_cell_style(&CellRef::new(col, 1), Some(bold));
+        }
+        for (row, (sheet_name, rows)) in (2u32..).zip(&entries) {
+            let cell_ref = CellRef::new(1, row);
+            sheet.set_cell(cell_ref.clone(), CellValue::from(sheet_name.as_str()));
+            sheet.set_cell_hyperlink(
+                &cell_ref,
+                Some(format!("#{}!A1", quote_sheet_name(sheet_name))),
+            );
+            if row_counts {
+                sheet.set_cell(CellRef::new(2, row), CellValue::Number(*rows as f64));
+            }
+        }
+        sheet.set_column_width(1, width);
+        sheet.set_freeze_panes(1, 0);
+    })?;
+    workbook.save()?;
+
+    output
+        .file(file)
+        .target("sheet", name)
+        .field("sheet", name)
+        .field(
+            "sheets",
+            entries
+                .iter()
+                .map(|(sheet_name, rows)| {
+                    let mut entry = serde_json::json!({ "name": sheet_name });
+                    if row_counts {
+                        entry["rows"] = (*rows).into();
+                    }
+                    entry
+                })
+                .collect::<Vec<_>>(),
+        )
+        .count("sheets", entries.len())
+        .print(
+            global,
+            format!(
+                "Added index sheet '{}' linking {} sheets",
+                name.green(),
+                entries.len()
+            ),
+        )?;
+
+    Ok(())
+}
+
+/// Parses a row span such as `1` or `1:2`.
+fn parse_row_span(s: &str) -> Result<(u32, u32)> {
+    let (first, last) = s.split_once(':').unwrap_or((s, s));
+    let parse = |v: &str| v.trim().trim_start_matches('$').parse::<u32>().ok();
+    match (parse(first), parse(last)) {
+        (Some(first), Some(last)) if first > 0 && last > 0 => Ok((first, last)),
+        _ => anyhow::bail!("Invalid row span '{}', expected e.g. 1 or 1:2", s),
+    }
+}
+
+/// Parses a column span such as `A` or `A:B`.
+fn parse_column_span(s: &str) -> Result<(u32, u32)> {
+    let (first, last) = s.split_once(':').unwrap_or((s, s));
+    let parse =
+        |v: &str| xlex_core::CellRef::col_from_letters_pub(v.trim().trim_start_matches('$'));
+    match (parse(first), parse(last)) {
+        (Some(first), Some(last)) => Ok((first, last)),
+        _ => anyhow::bail!("Invalid column span '{}', expected e.g. A or A:B", s),
+    }
+}
+
+fn print_setup(
+    file: &std::path::Path,
+    name: &str,
+    title_rows: Option<&str>,
+    title_cols: Option<&str>,
+    area: Option<&str>,
+    clear: bool,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let rows = title_rows.map(parse_row_span).transpose()?;
+    let cols = title_cols.map(parse_column_span).transpose()?;
+    let area = area.map(xlex_core::Range::parse).transpose()?;
+    let update = clear || rows.is_some() || cols.is_some() || area.is_some();
+
+    if update && global.dry_run {
+        outln!(
+            "Would update print settings of sheet '{}' in {}",
+            name,
+            file.display()
+        );
+        return Ok(());
+    }
+
+    let mut workbook = Workbook::open(file)?;
+    let sheet =
+        workbook
+            .get_sheet_mut(name)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: name.to_string(),
+            })?;
+
+    if clear {
+        sheet.set_print_titles(None, None);
+        sheet.set_print_area(None);
+    } else if update {
+        let (current_rows, current_cols) = sheet.print_titles();
+        if rows.is_some() || cols.is_some() {
+            sheet.set_print_titles(rows.or(current_rows), cols.or(current_cols));
+        }
+        if area.is_some() {
+            sheet.set_print_area(area);
+        }
+    }
+    let titles = sheet.print_titles_reference();
+    let print_area = sheet.print_area_reference();
+    if update {
+        workbook.save()?;
+    }
+
+    if !global.quiet || !update {
+        if global.format == OutputFormat::Json {
+            let json = serde_json::json!({
+                "sheet": name,
+                "printTitles": titles,
+                "printArea": print_area,
+            });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else {
+            if update {
+                outln!("Updated print settings of sheet '{}'", name.green());
+            }
+            outln!(
+                "{}: {}",
+                "Print titles".cyan(),
+                titles.as_deref().unwrap_or("(none)")
+            );
+            outln!(
+                "{}: {}",
+                "Print area".cyan(),
+                print_area.as_deref().unwrap_or("(none)")
+            );
         }
     }
 
@@ -438,6 +1347,11 @@ mod tests {
             json_errors: false,
             dry_run: false,
             output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
         }
     }
 
@@ -542,13 +1456,125 @@ mod tests {
     fn test_copy_sheet() {
         let temp_dir = TempDir::new().unwrap();
         let file_path = create_test_workbook(&temp_dir, "copy.xlsx");
+        {
+            let mut wb = Workbook::open(&file_path).unwrap();
+            let sheet = wb.get_sheet_mut("Sheet1").unwrap();
+            sheet.set_cell(
+                xlex_core::CellRef::new(1, 1),
+                xlex_core::CellValue::String("Region".to_string()),
+            );
+            sheet.add_merged_range(xlex_core::Range::parse("A1:B1").unwrap());
+            sheet.set_column_width(1, 30.0);
+            wb.save().unwrap();
+        }
 
         let result = copy(&file_path, "Sheet1", "Sheet1_Copy", &default_global());
         assert!(result.is_ok());
 
         let wb = Workbook::open(&file_path).unwrap();
         assert!(wb.sheet_names().contains(&"Sheet1"));
-        assert!(wb.sheet_names().contains(&"Sheet1_Copy"));
+        let copy = wb.get_sheet("Sheet1_Copy").unwrap();
+        assert_eq!(
+            copy.get_value(&xlex_core::CellRef::new(1, 1)),
+            xlex_core::CellValue::String("Region".to_string())
+        );
+        assert_eq!(copy.merged_ranges().len(), 1);
+        assert_eq!(copy.get_column_width(1), Some(30.0));
+    }
+
+    #[test]
+    fn test_import_sheet() {
+        let temp_dir = TempDir::new().unwrap();
+        let target = create_test_workbook(&temp_dir, "import_target.xlsx");
+        let source = temp_dir.path().join("import_source.xlsx");
+        {
+            let mut wb = Workbook::with_sheets(&["Q3Data"]);
+            let mut bold = xlex_core::Style::default();
+            bold.font.bold = true;
+            let bold_id = wb.style_registry_mut().add(bold);
+            let sheet = wb.get_sheet_mut("Q3Data").unwrap();
+            sheet.set_cell(
+                xlex_core::CellRef::new(1, 1),
+                xlex_core::CellValue::String("Region".to_string()),
+            );
+            sheet.set_cell_style(&xlex_core::CellRef::new(1, 1), Some(bold_id));
+            sheet.add_merged_range(xlex_core::Range::parse("A1:B1").unwrap());
+            sheet.set_column_width(1, 30.0);
+            wb.save_as(&source).unwrap();
+        }
+
+        import(
+            &target,
+            &source,
+            "Q3Data",
+            Some("Q3 Data"),
+            &default_global(),
+        )
+        .unwrap();
+
+        let wb = Workbook::open(&target).unwrap();
+        assert_eq!(wb.sheet_names(), vec!["Sheet1", "Q3 Data"]);
+        let sheet = wb.get_sheet("Q3 Data").unwrap();
+        assert_eq!(sheet.merged_ranges().len(), 1);
+        assert_eq!(sheet.get_column_width(1), Some(30.0));
+        let style = wb
+            .get_cell_style("Q3 Data", &xlex_core::CellRef::new(1, 1))
+            .unwrap();
+        assert!(style.font.bold);
+
+        // The name is taken now
+        assert!(import(
+            &target,
+            &source,
+            "Q3Data",
+            Some("Q3 Data"),
+            &default_global()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_rename_all_and_reorder() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("rename_all.xlsx");
+        Workbook::with_sheets(&["FY23 Data", "Notes", "fy23 Summary"])
+            .save_as(&file_path)
+            .unwrap();
+
+        rename_all(&file_path, "s/^FY23/FY24/i", &default_global()).unwrap();
+        reorder(
+            &file_path,
+            &["FY24 Summary".to_string(), "*".to_string()],
+            &default_global(),
+        )
+        .unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(wb.sheet_names(), vec!["FY24 Summary", "FY24 Data", "Notes"]);
+
+        // Names that would collide are rejected before anything changes
+        assert!(rename_all(&file_path, "s/.*/Same/", &default_global()).is_err());
+
+        // Swapping two names goes through a temporary name
+        let swap_path = temp_dir.path().join("swap.xlsx");
+        Workbook::with_sheets(&["AB", "BA"])
+            .save_as(&swap_path)
+            .unwrap();
+        rename_all(&swap_path, "s/^(.)(.)$/\\2\\1/", &default_global()).unwrap();
+        let wb = Workbook::open(&swap_path).unwrap();
+        assert_eq!(wb.sheet_names(), vec!["BA", "AB"]);
+    }
+
+    #[test]
+    fn test_substitution_parse() {
+        let sub = Substitution::parse("s|a/b|c$d|g").unwrap();
+        assert_eq!(sub.apply("a/b a/b"), "c$d c$d");
+        let sub = Substitution::parse("s/x\\/y/z/").unwrap();
+        assert_eq!(sub.apply("x/y x/y"), "z x/y");
+        assert!(Substitution::parse("y/a/b/").is_err());
+        assert!(Substitution::parse("s/a/b").is_err());
+        assert!(Substitution::parse("s/a/b/q").is_err());
+        assert!(Substitution::parse("s/(/b/").is_err());
     }
 
     #[test]
@@ -589,6 +1615,70 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_protect_sheet() {
+        use xlex_core::protection::SheetAction;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "protect.xlsx");
+
+        let allow = vec!["sort".to_string(), "autofilter".to_string()];
+        protect(&file_path, "Sheet1", &allow, Some("pw"), &default_global()).unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        let protection = wb.get_sheet("Sheet1").unwrap().protection().unwrap();
+        assert!(protection.allows(SheetAction::Sort) && protection.allows(SheetAction::Filter));
+        assert!(!protection.allows(SheetAction::FormatCells));
+        assert!(protection.password.is_some());
+
+        assert!(protect(
+            &file_path,
+            "Sheet1",
+            &["paint".to_string()],
+            None,
+            &default_global()
+        )
+        .is_err());
+        assert!(protect(&file_path, "Sheet1", &[], Some(""), &default_global()).is_err());
+        assert!(protect(&file_path, "Missing", &[], None, &default_global()).is_err());
+
+        unprotect(&file_path, "Sheet1", &default_global()).unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        assert!(wb.get_sheet("Sheet1").unwrap().protection().is_none());
+    }
+
+    #[test]
+    fn test_transpose_sheet() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "transpose.xlsx");
+
+        let mut wb = Workbook::open(&file_path).unwrap();
+        wb.set_cell(
+            "Sheet1",
+            xlex_core::CellRef::new(2, 5),
+            xlex_core::CellValue::string("x"),
+        )
+        .unwrap();
+        wb.save().unwrap();
+
+        let result = transpose(&file_path, "Sheet1", &default_global());
+        assert!(result.is_ok());
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let value = wb
+            .get_cell("Sheet1", &xlex_core::CellRef::new(5, 2))
+            .unwrap();
+        assert_eq!(value, xlex_core::CellValue::string("x"));
+    }
+
+    #[test]
+    fn test_transpose_sheet_not_found() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "transpose_missing.xlsx");
+
+        let result = transpose(&file_path, "Missing", &default_global());
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_info_sheet() {
         let temp_dir = TempDir::new().unwrap();
@@ -789,6 +1879,37 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_dimensions() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("dimensions.xlsx");
+        let mut wb = Workbook::new();
+        for (col, row) in [(2, 2), (4, 5)] {
+            wb.set_cell(
+                "Sheet1",
+                xlex_core::CellRef::new(col, row),
+                xlex_core::CellValue::Number(1.0),
+            )
+            .unwrap();
+        }
+        wb.save_as(&file_path).unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let json = dimensions_json(wb.get_sheet("Sheet1").unwrap());
+        assert_eq!(json["used"], "B2:D5");
+        assert_eq!(json["lastRow"], 5);
+        assert_eq!(json["lastColumn"], "D");
+        assert_eq!(json["matches"], true);
+
+        let args = SheetArgs {
+            command: SheetCommand::Dimensions {
+                file: file_path,
+                name: "Sheet1".to_string(),
+            },
+        };
+        assert!(run(&args, &default_global()).is_ok());
+    }
+
     #[test]
     fn test_run_active_command() {
         let temp_dir = TempDir::new().unwrap();
@@ -1008,4 +2129,205 @@ mod tests {
         let result = info(&file_path, "NonExistent", &default_global());
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_run_background_set_and_remove() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "background.xlsx");
+        let image_path = temp_dir.path().join("draft-watermark.png");
+        std::fs::write(&image_path, b"\x89PNG\r\n\x1a\npixels").unwrap();
+
+        let args = SheetArgs {
+            command: SheetCommand::Background {
+                file: file_path.clone(),
+                name: "Sheet1".to_string(),
+                image: Some(image_path),
+                remove: false,
+            },
+        };
+        run(&args, &default_global()).unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let picture = wb.get_sheet("Sheet1").unwrap().background().unwrap();
+        assert_eq!(picture.extension, "png");
+        assert_eq!(picture.data, b"\x89PNG\r\n\x1a\npixels");
+
+        background(&file_path, "Sheet1", None, &default_global()).unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        assert!(wb.get_sheet("Sheet1").unwrap().background().is_none());
+    }
+
+    #[test]
+    fn test_background_rejects_unsupported_image() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "background_svg.xlsx");
+        let image_path = temp_dir.path().join("logo.svg");
+        std::fs::write(&image_path, b"<svg/>").unwrap();
+
+        let result = background(&file_path, "Sheet1", Some(&image_path), &default_global());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_print_spans() {
+        assert_eq!(parse_row_span("1").unwrap(), (1, 1));
+        assert_eq!(parse_row_span("$1:$3").unwrap(), (1, 3));
+        assert!(parse_row_span("0").is_err());
+        assert_eq!(parse_column_span("B").unwrap(), (2, 2));
+        assert_eq!(parse_column_span("A:C").unwrap(), (1, 3));
+        assert!(parse_column_span("1:2").is_err());
+    }
+
+    #[test]
+    fn test_run_print_titles_and_area() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "print.xlsx");
+
+        let args = SheetArgs {
+            command: SheetCommand::Print {
+                file: file_path.clone(),
+                name: "Sheet1".to_string(),
+                title_rows: Some("1".to_string()),
+                title_cols: None,
+                area: Some("A1:H50".to_string()),
+                clear: false,
+            },
+        };
+        run(&args, &default_global()).unwrap();
+
+        // Later flags keep the settings they don't mention
+        print_setup(
+            &file_path,
+            "Sheet1",
+            None,
+            Some("A"),
+            None,
+            false,
+            &default_global(),
+        )
+        .unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        let sheet = wb.get_sheet("Sheet1").unwrap();
+        assert_eq!(sheet.print_titles(), (Some((1, 1)), Some((1, 1))));
+        assert_eq!(sheet.print_area().unwrap().to_a1(), "A1:H50");
+
+        print_setup(
+            &file_path,
+            "Sheet1",
+            None,
+            None,
+            None,
+            true,
+            &default_global(),
+        )
+        .unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        let sheet = wb.get_sheet("Sheet1").unwrap();
+        assert_eq!(sheet.print_titles(), (None, None));
+        assert!(sheet.print_area().is_none());
+    }
+
+    #[test]
+    fn test_sheet_default_style() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "sheet_default_style.xlsx");
+        let mut wb = Workbook::open(&file_path).unwrap();
+        let mut bold = xlex_core::Style::default();
+        bold.font.bold = true;
+        let bold = wb.style_registry_mut().add(bold);
+        wb.save().unwrap();
+
+        let args = |style_id, clear| SheetArgs {
+            command: SheetCommand::StyleDefault {
+                file: file_path.clone(),
+                name: "Sheet1".to_string(),
+                style_id,
+                clear,
+            },
+        };
+        run(&args(Some(bold), false), &default_global()).unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(wb.get_sheet("Sheet1").unwrap().default_style(), Some(bold));
+        let style = |col, row| {
+            wb.get_cell_style("Sheet1", &xlex_core::CellRef::new(col, row))
+                .unwrap()
+        };
+        assert!(style(200, 7).font.bold);
+
+        // Unknown style IDs are rejected
+        assert!(run(&args(Some(99), false), &default_global()).is_err());
+
+        run(&args(None, true), &default_global()).unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(wb.get_sheet("Sheet1").unwrap().default_style(), None);
+    }
+
+    #[test]
+    fn test_run_toc() {
+        use std::io::Read;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "toc.xlsx");
+        let mut wb = Workbook::open(&file_path).unwrap();
+        wb.add_sheet("Q1 Sales").unwrap();
+        wb.add_sheet("Scratch").unwrap();
+        wb.set_sheet_visibility("Scratch", xlex_core::sheet::SheetVisibility::Hidden)
+            .unwrap();
+        let sheet = wb.get_sheet_mut("Q1 Sales").unwrap();
+        sheet.set_cell(CellRef::new(1, 12), CellValue::Number(1.0));
+        wb.save().unwrap();
+
+        let args = |name: &str| SheetArgs {
+            command: SheetCommand::Toc {
+                file: file_path.clone(),
+                name: name.to_string(),
+                row_counts: true,
+            },
+        };
+        run(&args("Index"), &default_global()).unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(
+            wb.sheet_names(),
+            vec!["Index", "Sheet1", "Q1 Sales", "Scratch"]
+        );
+        let index = wb.get_sheet("Index").unwrap();
+        assert_eq!(
+            index.get_value(&CellRef::new(1, 1)),
+            CellValue::from("Sheet")
+        );
+        assert_eq!(
+            index.get_value(&CellRef::new(1, 3)),
+            CellValue::from("Q1 Sales")
+        );
+        assert_eq!(
+            index.get_value(&CellRef::new(2, 3)),
+            CellValue::Number(12.0)
+        );
+        assert_eq!(index.get_value(&CellRef::new(1, 4)), CellValue::Empty);
+        assert!(
+            wb.get_cell_style("Index", &CellRef::new(2, 1))
+                .unwrap()
+                .font
+                .bold
+        );
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&file_path).unwrap()).unwrap();
+        let mut xml = String::new();
+        archive
+            .by_name("xl/worksheets/sheet1.xml")
+            .unwrap()
+            .read_to_string(&mut xml)
+            .unwrap();
+        assert!(xml.contains(r#"<hyperlink ref="A2" location="Sheet1!A1"/>"#));
+        assert!(xml.contains(r#"<hyperlink ref="A3" location="&apos;Q1 Sales&apos;!A1"/>"#));
+        assert!(archive
+            .by_name("xl/worksheets/_rels/sheet1.xml.rels")
+            .is_err());
+        drop(archive);
+
+        // The index sheet name must be free
+        assert!(run(&args("Index"), &default_global()).is_err());
+    }
 }
@@ -0,0 +1,587 @@
+//! Sheet arguments on the command line.
+//!
+//! Before the command line is parsed, the sheet given to a command is
+//! resolved:
+//!
+//! - `@Name` in place of a sheet, cell or range is replaced by what the
+//!   defined name points at (see `xlex name add`): `Sheet1!F42` in place of
+//!   the sheet, `F42` in place of the cell, checked against the given sheet.
+//! - `Sheet1!B2` or `'My Sheet'!A1:C3` in place of a sheet followed by a
+//!   cell or range is split into the two arguments.
+//! - A sheet left out is filled in when the workbook has a single sheet,
+//!   or with `default_sheet` from the config file when the workbook has it.
+//! - A number that is not a sheet name picks the sheet at that position
+//!   (1-based), for the sheet argument and `--sheet` alike.
+//! - `*` means every sheet: `--sheet '*'` turns into `--all` where the
+//!   command has it, otherwise the command runs once per sheet.
+//! - With `--fuzzy-sheets`, a sheet that does not exist is replaced by the
+//!   one differing only in case, or by the closest name after asking on a
+//!   terminal (with a note otherwise).
+
+use std::ffi::OsStr;
+use std::io::IsTerminal;
+
+use anyhow::Result;
+use clap::{Arg, ArgAction, Command, CommandFactory};
+use colored::Colorize;
+use xlex_core::diagnostic::closest_match;
+use xlex_core::sheet::{quote_sheet_name, split_sheet_ref};
+use xlex_core::{LazyWorkbook, Range, Workbook};
+
+use super::{get_config_path, Cli};
+
+/// Where the sheet of a command is given.
+enum SheetArg {
+    /// The `sheet` positional, at this index in `args` (or where it is
+    /// missing), and whether another positional follows it
+    Positional {
+        index: usize,
+        missing: bool,
+        has_next: bool,
+    },
+    /// The value of `--sheet`, at this index in `args`, after `prefix`
+    /// (`--sheet=` when given inline)
+    Option { index: usize, prefix: String },
+}
+
+/// What a command line says about its sheet and workbook.
+struct Scan<'a> {
+    command: &'a Command,
+    sheet: Option<SheetArg>,
+    file: Option<String>,
+    /// Index in `args` and ID of each positional given
+    positionals: Vec<(usize, String)>,
+    /// IDs of the options given, including global ones
+    options: Vec<String>,
+}
+
+/// Resolves the sheet argument of the command starting at `start` and
+/// returns the command lines to run: one, or one per sheet for `*`.
+pub(crate) fn resolve_sheet_args<T>(mut args: Vec<T>, start: usize) -> Result<Vec<Vec<T>>>
+where
+    T: Clone + From<String> + AsRef<OsStr>,
+{
+    let root = Cli::command();
+    if let Some(found) = scan(&root, &args, start) {
+        expand_anchors(&mut args, &found)?;
+    }
+    let Some(scan) = scan(&root, &args, start) else {
+        return Ok(vec![args]);
+    };
+    let mut sheet_names: Option<Vec<String>> = None;
+    let mut load_sheets = |file: &Option<String>| -> Option<Vec<String>> {
+        if sheet_names.is_none() {
+            sheet_names = file
+                .as_ref()
+                .and_then(|f| LazyWorkbook::open(f).ok())
+                .map(|wb| wb.sheet_names().to_vec());
+        }
+        sheet_names.clone()
+    };
+
+    let (index, prefix, option) = match scan.sheet {
+        None => return Ok(vec![args]),
+        Some(SheetArg::Positional {
+            index,
+            missing,
+            has_next,
+        }) => {
+            let word = args.get(index).and_then(|a| a.as_ref().to_str());
+            let qualified = word
+                .filter(|_| has_next)
+                .and_then(split_sheet_ref)
+                .filter(|(_, reference)| Range::parse(reference).is_ok())
+                .map(|(sheet, reference)| (sheet, reference.to_string()));
+            if let Some((sheet, reference)) = qualified {
+                args[index] = T::from(sheet);
+                args.insert(index + 1, T::from(reference));
+            } else if missing {
+                let sheets = load_sheets(&scan.file);
+                let given_sheet = word.is_some_and(|w| {
+                    sheets
+                        .as_ref()
+                        .is_some_and(|names| names.iter().any(|n| n == w))
+                });
+                if let (Some(sheets), false) = (sheets, given_sheet) {
+                    let sheet = default_sheet(&sheets, scan.file.as_deref().unwrap_or(""))?;
+                    args.insert(index.min(args.len()), T::from(sheet));
+                }
+            }
+            (index, String::new(), false)
+        }
+        Some(SheetArg::Option { index, prefix }) => (index, prefix, true),
+    };
+
+    let Some(value) = args
+        .get(index)
+        .and_then(|a| a.as_ref().to_str())
+        .and_then(|a| a.strip_prefix(prefix.as_str()))
+        .map(str::to_string)
+    else {
+        return Ok(vec![args]);
+    };
+
+    if value == "*" {
+        if option && has_long(scan.command, "all") {
+            // `--sheet '*'` is `--all`
+            let first = if prefix.is_empty() { index - 1 } else { index };
+            args.drain(first..=index);
+            args.push(T::from("--all".to_string()));
+            return Ok(vec![args]);
+        }
+        let Some(sheets) = load_sheets(&scan.file) else {
+            return Ok(vec![args]);
+        };
+        return Ok(sheets
+            .into_iter()
+            .map(|sheet| {
+                let mut run = args.clone();
+                run[index] = T::from(format!("{}{}", prefix, sheet));
+                run
+            })
+            .collect());
+    }
+
+    if !value.is_empty() && value.chars().all(|c| c.is_ascii_digit()) {
+        if let Some(sheets) = load_sheets(&scan.file) {
+            if !sheets.contains(&value) {
+                let position: usize = value.parse().unwrap_or(0);
+                if let Some(sheet) = position.checked_sub(1).and_then(|i| sheets.get(i)) {
+                    args[index] = T::from(format!("{}{}", prefix, sheet));
+                }
+            }
+        }
+    } else if scan.options.iter().any(|o| o == "fuzzy_sheets") {
+        if let Some(sheets) = load_sheets(&scan.file) {
+            let interactive = std::io::stdin().is_terminal() && std::io::stderr().is_terminal();
+            let quiet = scan.options.iter().any(|o| o == "quiet");
+            if let Some(sheet) = fuzzy_sheet(&value, &sheets, interactive, quiet) {
+                args[index] = T::from(format!("{}{}", prefix, sheet));
+            }
+        }
+    }
+
+    Ok(vec![args])
+}
+
+/// The sheet meant by `name` under `--fuzzy-sheets`, when no sheet has
+/// exactly that name: one differing only in case, or the closest name,
+/// which must be confirmed when `interactive`.
+fn fuzzy_sheet(name: &str, sheets: &[String], interactive: bool, quiet: bool) -> Option<String> {
+    if name.is_empty() || sheets.iter().any(|s| s == name) {
+        return None;
+    }
+    let closest = closest_match(name, sheets)?;
+    if closest.to_lowercase() == name.to_lowercase() {
+        return Some(closest.to_string());
+    }
+    if interactive {
+        eprint!("Sheet '{}' not found; use '{}'? [Y/n] ", name, closest);
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer).ok()?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "" | "y" | "yes") {
+            return None;
+        }
+    } else if !quiet {
+        eprintln!(
+            "{}: using sheet '{}' for '{}'",
+            "note".cyan().bold(),
+            closest,
+            name
+        );
+    }
+    Some(closest.to_string())
+}
+
+/// Replaces each `@Name` given for a sheet, cell or range with the place
+/// the defined name points at.
+fn expand_anchors<T>(args: &mut Vec<T>, scan: &Scan) -> Result<()>
+where
+    T: From<String> + AsRef<OsStr>,
+{
+    let anchors: Vec<(usize, bool, String)> = scan
+        .positionals
+        .iter()
+        .filter(|(_, id)| matches!(id.as_str(), "sheet" | "cell" | "range"))
+        .filter_map(|(index, id)| {
+            let name = args[*index].as_ref().to_str()?.strip_prefix('@')?;
+            (!name.is_empty()).then(|| (*index, id == "sheet", name.to_string()))
+        })
+        .collect();
+    let Some((_, _, first)) = anchors.first() else {
+        return Ok(());
+    };
+    let Some(file) = scan.file.as_deref() else {
+        anyhow::bail!("@{} needs a workbook to look the name up in", first);
+    };
+    let workbook = Workbook::open(file)?;
+
+    let mut given_sheet = match &scan.sheet {
+        Some(SheetArg::Positional {
+            index,
+            missing: false,
+            ..
+        }) => args.get(*index).and_then(|a| a.as_ref().to_str()),
+        Some(SheetArg::Option { index, prefix }) => args
+            .get(*index)
+            .and_then(|a| a.as_ref().to_str())
+            .and_then(|a| a.strip_prefix(prefix.as_str())),
+        _ => None,
+    }
+    .map(str::to_string);
+
+    for (index, in_sheet_place, name) in anchors {
+        let (sheet, range) = workbook
+            .resolve_name(&name)
+            .map_err(|_| anyhow::anyhow!("No cell or range named @{} in {}", name, file))?;
+        if in_sheet_place {
+            args[index] = T::from(format!("{}!{}", quote_sheet_name(&sheet), range.to_a1()));
+            continue;
+        }
+        match given_sheet.as_deref() {
+            Some(given) if given == "*" || given.chars().all(|c| c.is_ascii_digit()) => {}
+            Some(given) if given != sheet => anyhow::bail!(
+                "@{} is on sheet '{}', not '{}'; leave the sheet out to use it",
+                name,
+                sheet,
+                given
+            ),
+            Some(_) => {}
+            None if has_long(scan.command, "sheet") => {
+                args.push(T::from(format!("--sheet={}", sheet)));
+                given_sheet = Some(sheet);
+            }
+            None => {}
+        }
+        args[index] = T::from(range.to_a1());
+    }
+    Ok(())
+}
+
+/// The sheet to use when a command leaves it out.
+fn default_sheet(sheets: &[String], file: &str) -> Result<String> {
+    if let [only] = sheets {
+        return Ok(only.clone());
+    }
+    let configured = get_config_path()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_yaml::from_str::<serde_yaml::Value>(&content).ok())
+        .and_then(|config| config.get("default_sheet")?.as_str().map(str::to_string));
+    match configured {
+        Some(sheet) if sheets.contains(&sheet) => Ok(sheet),
+        _ => anyhow::bail!(
+            "{} has {} sheets ({}); name one, or set default_sheet with `xlex config set default_sheet <name>`",
+            file,
+            sheets.len(),
+            sheets.join(", ")
+        ),
+    }
+}
+
+/// Finds the (sub)command at or after `start` and where its sheet and
+/// workbook are given.
+fn scan<'a, T: AsRef<OsStr>>(root: &'a Command, args: &[T], start: usize) -> Option<Scan<'a>> {
+    let mut chain = vec![root];
+    let mut given: Vec<usize> = Vec::new();
+    let mut sheet_option = None;
+    let mut options = Vec::new();
+    let mut options_done = false;
+    let mut i = start;
+    while let Some(arg) = args.get(i) {
+        let arg = arg.as_ref().to_str()?;
+        let command = *chain.last()?;
+        i += 1;
+        if !options_done && arg == "--" {
+            options_done = true;
+            continue;
+        }
+        if !options_done && arg.starts_with('-') && arg != "-" {
+            let (option, inline) = find_option(&chain, arg);
+            let separate_value =
+                inline.is_none() && option.is_some_and(|o| o.get_action().takes_values());
+            options.extend(option.map(|o| o.get_id().to_string()));
+            if option.is_some_and(|o| o.get_id() == "sheet") {
+                sheet_option = Some(match inline {
+                    Some(prefix) => SheetArg::Option {
+                        index: i - 1,
+                        prefix,
+                    },
+                    None => SheetArg::Option {
+                        index: i,
+                        prefix: String::new(),
+                    },
+                });
+            }
+            if separate_value {
+                i += 1;
+            }
+            continue;
+        }
+        if given.is_empty() {
+            if let Some(sub) = command.find_subcommand(arg) {
+                chain.push(sub);
+                continue;
+            }
+        }
+        given.push(i - 1);
+    }
+
+    let command = *chain.last()?;
+    let positionals: Vec<&Arg> = command.get_positionals().collect();
+    let given_positionals = given
+        .iter()
+        .zip(&positionals)
+        .map(|(&i, a)| (i, a.get_id().to_string()))
+        .collect();
+    let file = positionals
+        .iter()
+        .position(|a| a.get_id() == "file" || a.get_id() == "source")
+        .and_then(|p| given.get(p))
+        .and_then(|&i| args[i].as_ref().to_str())
+        .map(str::to_string);
+
+    let sheet = match positionals.iter().position(|a| a.get_id() == "sheet") {
+        Some(p) if !positionals[..p].iter().any(|a| is_multiple(a)) => {
+            let required = positionals.iter().filter(|a| a.is_required_set()).count();
+            let missing = positionals[p].is_required_set() && given.len() + 1 == required;
+            Some(SheetArg::Positional {
+                index: given.get(p).copied().unwrap_or(args.len()),
+                missing,
+                has_next: p + 1 < positionals.len(),
+            })
+        }
+        _ => sheet_option,
+    };
+    Some(Scan {
+        command,
+        sheet,
+        file,
+        positionals: given_positionals,
+        options,
+    })
+}
+
+/// Finds the option named by `arg` in the command or, for global options,
+/// its parents. Also returns the `--name=` prefix of an inline value.
+fn find_option<'a>(chain: &[&'a Command], arg: &str) -> (Option<&'a Arg>, Option<String>) {
+    let mut candidates = chain
+        .iter()
+        .rev()
+        .enumerate()
+        .flat_map(|(depth, c)| c.get_arguments().map(move |a| (depth, a)))
+        .filter(|(depth, a)| *depth == 0 || a.is_global_set())
+        .map(|(_, a)| a);
+    if let Some(long) = arg.strip_prefix("--") {
+        let (name, inline) = match long.split_once('=') {
+            Some((name, _)) => (name, Some(format!("--{}=", name))),
+            None => (long, None),
+        };
+        return (candidates.find(|a| a.get_long() == Some(name)), inline);
+    }
+    let short = arg[1..].chars().next();
+    let option = candidates.find(|a| a.get_short().is_some() && a.get_short() == short);
+    let inline = (arg.len() > 2 && option.is_some_and(|o| o.get_action().takes_values()))
+        .then(|| arg[..2].to_string());
+    (option, inline)
+}
+
+fn is_multiple(arg: &Arg) -> bool {
+    matches!(arg.get_action(), ArgAction::Append)
+}
+
+fn has_long(command: &Command, long: &str) -> bool {
+    command.get_arguments().any(|a| a.get_long() == Some(long))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use xlex_core::Workbook;
+
+    fn resolve(line: &str) -> Vec<Vec<String>> {
+        resolve_sheet_args(words(line), 1).unwrap()
+    }
+
+    fn words(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_split_qualified_refs() {
+        assert_eq!(
+            resolve("xlex cell get a.xlsx Sheet1!B2"),
+            [words("xlex cell get a.xlsx Sheet1 B2")]
+        );
+        assert_eq!(
+            resolve("xlex -f json range copy a.xlsx Data!A1:B2 D1 --dry-run"),
+            [words(
+                "xlex -f json range copy a.xlsx Data A1:B2 D1 --dry-run"
+            )]
+        );
+        assert_eq!(
+            resolve("xlex cell set -o log.txt a.xlsx Sheet1!C3 42"),
+            [words("xlex cell set -o log.txt a.xlsx Sheet1 C3 42")]
+        );
+        let args = ["xlex", "cell", "get", "a.xlsx", "'My Sheet'!B2"].map(String::from);
+        assert_eq!(
+            resolve_sheet_args(args.to_vec(), 1).unwrap(),
+            [["xlex", "cell", "get", "a.xlsx", "My Sheet", "B2"].map(String::from)]
+        );
+        // Separate arguments, non-references and commands without a
+        // following reference are left alone
+        for line in [
+            "xlex cell get a.xlsx Sheet1 B2",
+            "xlex cell get a.xlsx Q!4 B2",
+            "xlex sheet remove a.xlsx Old!A1",
+            "xlex info a.xlsx",
+        ] {
+            assert_eq!(resolve(line), [words(line)]);
+        }
+    }
+
+    #[test]
+    fn test_default_index_and_wildcard_sheets() {
+        let temp_dir = TempDir::new().unwrap();
+        let single = temp_dir.path().join("single.xlsx");
+        Workbook::with_sheets(&["Data"]).save_as(&single).unwrap();
+        let multi = temp_dir.path().join("multi.xlsx");
+        Workbook::with_sheets(&["Sales", "Costs", "2024"])
+            .save_as(&multi)
+            .unwrap();
+        let single = single.to_str().unwrap();
+        let multi = multi.to_str().unwrap();
+
+        // Omitted sheet: the only one
+        assert_eq!(
+            resolve(&format!("xlex cell set {} B2 42", single)),
+            [words(&format!("xlex cell set {} Data B2 42", single))]
+        );
+        assert_eq!(
+            resolve(&format!("xlex row get {} 5", single)),
+            [words(&format!("xlex row get {} Data 5", single))]
+        );
+        // Given sheets are kept, even with a missing argument after them
+        assert_eq!(
+            resolve(&format!("xlex cell set {} Data B2", single)),
+            [words(&format!("xlex cell set {} Data B2", single))]
+        );
+        // Several sheets and no configured default is an error
+        let err = resolve_sheet_args(words(&format!("xlex cell get {} B2", multi)), 1);
+        assert!(err.unwrap_err().to_string().contains("Sales, Costs, 2024"));
+
+        // Indices, unless a sheet has that name
+        assert_eq!(
+            resolve(&format!("xlex cell get {} 2 A1", multi)),
+            [words(&format!("xlex cell get {} Costs A1", multi))]
+        );
+        assert_eq!(
+            resolve(&format!("xlex cell get {} 2024 A1", multi)),
+            [words(&format!("xlex cell get {} 2024 A1", multi))]
+        );
+        assert_eq!(
+            resolve(&format!("xlex export csv {} --sheet 2", multi)),
+            [words(&format!("xlex export csv {} --sheet Costs", multi))]
+        );
+        assert_eq!(
+            resolve(&format!("xlex export json {} -s1", multi)),
+            [words(&format!("xlex export json {} -sSales", multi))]
+        );
+
+        // Wildcards
+        assert_eq!(
+            resolve(&format!("xlex export csv {} out.csv --sheet *", multi)),
+            [words(&format!("xlex export csv {} out.csv --all", multi))]
+        );
+        assert_eq!(
+            resolve(&format!("xlex cell get {} * A1", multi)),
+            ["Sales", "Costs", "2024"].map(|s| words(&format!("xlex cell get {} {} A1", multi, s)))
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_sheets() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("fuzzy.xlsx");
+        Workbook::with_sheets(&["Sales", "Summary"])
+            .save_as(&path)
+            .unwrap();
+        let file = path.to_str().unwrap();
+
+        // Case differences, for the positional and --sheet
+        assert_eq!(
+            resolve(&format!("xlex --fuzzy-sheets cell get {} SALES A1", file)),
+            [words(&format!(
+                "xlex --fuzzy-sheets cell get {} Sales A1",
+                file
+            ))]
+        );
+        assert_eq!(
+            resolve(&format!(
+                "xlex export csv {} --sheet=summary --fuzzy-sheets",
+                file
+            )),
+            [words(&format!(
+                "xlex export csv {} --sheet=Summary --fuzzy-sheets",
+                file
+            ))]
+        );
+        // Only when asked for
+        let line = format!("xlex cell get {} sales A1", file);
+        assert_eq!(resolve(&line), [words(&line)]);
+
+        let sheets = ["Sales".to_string(), "Summary".to_string()];
+        assert_eq!(
+            fuzzy_sheet("Sumary", &sheets, false, true).as_deref(),
+            Some("Summary")
+        );
+        assert_eq!(fuzzy_sheet("Costs", &sheets, false, true), None);
+        assert_eq!(fuzzy_sheet("Sales", &sheets, false, true), None);
+    }
+
+    #[test]
+    fn test_anchor_names() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("anchors.xlsx");
+        let mut wb = Workbook::with_sheets(&["Data", "My Report"]);
+        wb.set_defined_name(xlex_core::DefinedName::new(
+            "TotalCell",
+            "'My Report'!$F$42",
+        ));
+        wb.set_defined_name(xlex_core::DefinedName::new("Block", "Data!$A$1:$B$3"));
+        wb.save_as(&path).unwrap();
+        let file = path.to_str().unwrap();
+
+        // In place of the sheet: both sheet and cell
+        assert_eq!(
+            resolve(&format!("xlex cell set {} @TotalCell 1234", file)),
+            [["xlex", "cell", "set", file, "My Report", "F42", "1234"].map(String::from)]
+        );
+
+        // In place of the cell, with the sheet given
+        assert_eq!(
+            resolve(&format!("xlex cell get {} Data @block", file)),
+            [words(&format!("xlex cell get {} Data A1:B3", file))]
+        );
+        assert_eq!(
+            resolve(&format!("xlex range get {} @Block", file)),
+            [words(&format!("xlex range get {} Data A1:B3", file))]
+        );
+
+        // A different sheet or an unknown name is an error
+        assert!(
+            resolve_sheet_args(words(&format!("xlex cell get {} Data @TotalCell", file)), 1)
+                .unwrap_err()
+                .to_string()
+                .contains("My Report")
+        );
+        assert!(resolve_sheet_args(words(&format!("xlex cell get {} @Nope", file)), 1).is_err());
+
+        // Values are left alone
+        assert_eq!(
+            resolve(&format!("xlex cell set {} Data A1 @TotalCell", file)),
+            [words(&format!("xlex cell set {} Data A1 @TotalCell", file))]
+        );
+    }
+}
@@ -0,0 +1,355 @@
+//! Sparkline operations.
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+
+use xlex_core::sparkline::{SparklineGroup, SparklineType};
+use xlex_core::style::Color;
+use xlex_core::{Range, Workbook};
+
+use super::{GlobalOptions, OutputFormat};
+
+/// Arguments for sparkline operations.
+#[derive(Parser)]
+pub struct SparklineArgs {
+    #[command(subcommand)]
+    pub command: SparklineCommand,
+}
+
+#[derive(Subcommand)]
+pub enum SparklineCommand {
+    /// Add sparklines drawing a data range into cells
+    Add {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name
+        sheet: String,
+        /// Data range (e.g., B2:M2, or B2:M10 for one sparkline per row)
+        #[arg(long)]
+        data: String,
+        /// Target cell or range (e.g., N2, or N2:N10 for one per data row)
+        #[arg(long)]
+        at: String,
+        /// Sparkline type (line, column, winloss)
+        #[arg(long = "type", default_value = "line")]
+        sparkline_type: String,
+        /// Series color (hex, e.g., 376092)
+        #[arg(long)]
+        color: Option<String>,
+        /// Color for negative points (hex, e.g., D00000)
+        #[arg(long)]
+        negative_color: Option<String>,
+        /// Show markers on every point
+        #[arg(long)]
+        markers: bool,
+        /// Highlight the highest and lowest points
+        #[arg(long)]
+        high_low: bool,
+    },
+    /// List sparklines on a sheet
+    List {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name
+        sheet: String,
+    },
+    /// Remove sparklines drawn in a cell or range
+    Remove {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// Sheet name
+        sheet: String,
+        /// Cell or range to clear (e.g., N2 or N2:N10)
+        #[arg(long)]
+        at: String,
+    },
+}
+
+/// Options for `sparkline add`.
+struct AddOptions<'a> {
+    data: &'a str,
+    at: &'a str,
+    sparkline_type: &'a str,
+    color: Option<&'a str>,
+    negative_color: Option<&'a str>,
+    markers: bool,
+    high_low: bool,
+}
+
+/// Run sparkline operations.
+pub fn run(args: &SparklineArgs, global: &GlobalOptions) -> Result<()> {
+    match &args.command {
+        SparklineCommand::Add {
+            file,
+            sheet,
+            data,
+            at,
+            sparkline_type,
+            color,
+            negative_color,
+            markers,
+            high_low,
+        } => add(
+            file,
+            sheet,
+            &AddOptions {
+                data,
+                at,
+                sparkline_type,
+                color: color.as_deref(),
+                negative_color: negative_color.as_deref(),
+                markers: *markers,
+                high_low: *high_low,
+            },
+            global,
+        ),
+        SparklineCommand::List { file, sheet } => list(file, sheet, global),
+        SparklineCommand::Remove { file, sheet, at } => remove(file, sheet, at, global),
+    }
+}
+
+fn parse_color(hex: &str) -> Result<Color> {
+    Color::from_hex(hex).ok_or_else(|| anyhow::anyhow!("Invalid color '{}'", hex))
+}
+
+fn add(
+    file: &std::path::Path,
+    sheet_name: &str,
+    opts: &AddOptions,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let data = Range::parse(opts.data)?;
+    let at = Range::parse(opts.at)?;
+    let mut group = SparklineGroup::new(SparklineType::parse(opts.sparkline_type)?);
+    if let Some(hex) = opts.color {
+        group.color = parse_color(hex)?;
+    }
+    if let Some(hex) = opts.negative_color {
+        group.negative_color = parse_color(hex)?;
+    }
+    group.markers = opts.markers;
+    group.high_low = opts.high_low;
+    group.add_range(sheet_name, &data, &at)?;
+
+    if global.dry_run {
+        outln!(
+            "Would add {} {} sparkline(s) at {} in {}",
+            group.sparklines.len(),
+            group.sparkline_type,
+            opts.at,
+            file.display()
+        );
+        return Ok(());
+    }
+
+    let mut workbook = Workbook::open(file)?;
+    let sheet =
+        workbook
+            .get_sheet_mut(sheet_name)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: sheet_name.to_string(),
+            })?;
+    let count = group.sparklines.len();
+    let sparkline_type = group.sparkline_type;
+    sheet.add_sparkline_group(group);
+    workbook.save()?;
+
+    if !global.quiet {
+        if global.format == OutputFormat::Json {
+            let json = serde_json::json!({
+                "sheet": sheet_name,
+                "type": sparkline_type.to_string(),
+                "data": opts.data,
+                "at": opts.at,
+                "sparklines": count,
+            });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else {
+            outln!(
+                "Added {} {} sparkline(s) at {}",
+                count,
+                sparkline_type,
+                opts.at.green()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn list(file: &std::path::Path, sheet_name: &str, global: &GlobalOptions) -> Result<()> {
+    let workbook = Workbook::open(file)?;
+    let sheet =
+        workbook
+            .get_sheet(sheet_name)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: sheet_name.to_string(),
+            })?;
+
+    let rows: Vec<(String, String, SparklineType)> = sheet
+        .sparkline_groups()
+        .iter()
+        .flat_map(|group| {
+            group
+                .sparklines
+                .iter()
+                .map(move |s| (s.location.to_a1(), s.data.clone(), group.sparkline_type))
+        })
+        .collect();
+
+    if global.format == OutputFormat::Json {
+        let items: Vec<serde_json::Value> = rows
+            .iter()
+            .map(|(at, data, sparkline_type)| {
+                serde_json::json!({
+                    "at": at,
+                    "data": data,
+                    "type": sparkline_type.to_string(),
+                })
+            })
+            .collect();
+        let json = serde_json::json!({
+            "sheet": sheet_name,
+            "sparklines": items,
+        });
+        outln!("{}", serde_json::to_string_pretty(&json)?);
+    } else if rows.is_empty() {
+        outln!("No sparklines in sheet '{}'", sheet_name);
+    } else {
+        for (at, data, sparkline_type) in &rows {
+            outln!("{} {} ({})", at.cyan(), data, sparkline_type);
+        }
+    }
+
+    Ok(())
+}
+
+fn remove(
+    file: &std::path::Path,
+    sheet_name: &str,
+    at: &str,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let range = Range::parse(at)?;
+
+    if global.dry_run {
+        outln!("Would remove sparklines at {} in {}", at, file.display());
+        return Ok(());
+    }
+
+    let mut workbook = Workbook::open(file)?;
+    let sheet =
+        workbook
+            .get_sheet_mut(sheet_name)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: sheet_name.to_string(),
+            })?;
+    let removed = range
+        .cells()
+        .filter(|cell| sheet.remove_sparkline(cell))
+        .count();
+    if removed > 0 {
+        workbook.save()?;
+    }
+
+    if !global.quiet {
+        if global.format == OutputFormat::Json {
+            let json = serde_json::json!({
+                "sheet": sheet_name,
+                "at": at,
+                "removed": removed,
+            });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else {
+            outln!("Removed {} sparkline(s) at {}", removed, at.green());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn default_global() -> GlobalOptions {
+        GlobalOptions {
+            quiet: true,
+            verbose: false,
+            format: OutputFormat::Text,
+            no_color: true,
+            color: false,
+            json_errors: false,
+            dry_run: false,
+            output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
+        }
+    }
+
+    fn create_test_workbook(dir: &TempDir, name: &str) -> std::path::PathBuf {
+        let file_path = dir.path().join(name);
+        let wb = Workbook::new();
+        wb.save_as(&file_path).unwrap();
+        file_path
+    }
+
+    fn add_args(file: std::path::PathBuf, data: &str, at: &str) -> SparklineArgs {
+        SparklineArgs {
+            command: SparklineCommand::Add {
+                file,
+                sheet: "Sheet1".to_string(),
+                data: data.to_string(),
+                at: at.to_string(),
+                sparkline_type: "column".to_string(),
+                color: Some("#00B050".to_string()),
+                negative_color: None,
+                markers: false,
+                high_low: true,
+            },
+        }
+    }
+
+    #[test]
+    fn test_run_add_list_remove() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "sparkline.xlsx");
+
+        run(
+            &add_args(file_path.clone(), "B2:M4", "N2:N4"),
+            &default_global(),
+        )
+        .unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let groups = wb.get_sheet("Sheet1").unwrap().sparkline_groups();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].sparkline_type, SparklineType::Column);
+        assert_eq!(groups[0].color, Color::Rgb(0x00B050));
+        assert_eq!(groups[0].sparklines.len(), 3);
+
+        let mut json = default_global();
+        json.format = OutputFormat::Json;
+        list(&file_path, "Sheet1", &json).unwrap();
+
+        remove(&file_path, "Sheet1", "N2:N3", &default_global()).unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        let groups = wb.get_sheet("Sheet1").unwrap().sparkline_groups();
+        assert_eq!(groups[0].sparklines.len(), 1);
+        assert_eq!(groups[0].sparklines[0].data, "Sheet1!B4:M4");
+    }
+
+    #[test]
+    fn test_add_rejects_mismatched_target() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "sparkline_bad.xlsx");
+
+        let result = run(&add_args(file_path, "B2:M4", "N2:N3"), &default_global());
+        assert!(result.is_err());
+    }
+}
@@ -4,8 +4,12 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use colored::Colorize;
 
+use xlex_core::sheet::split_sheet_ref;
 use xlex_core::{CellRef, Range, Workbook};
 
+use serde::Deserialize;
+
+use super::condition::Condition;
 use super::{GlobalOptions, OutputFormat};
 
 /// Arguments for style operations.
@@ -45,11 +49,20 @@ pub enum StyleCommand {
         /// Path to the xlsx file
         file: std::path::PathBuf,
         /// Sheet name
-        sheet: String,
+        #[arg(required_unless_present = "from")]
+        sheet: Option<String>,
         /// Source cell
-        source: String,
+        #[arg(required_unless_present = "from")]
+        source: Option<String>,
         /// Destination range
-        dest: String,
+        #[arg(required_unless_present = "from")]
+        dest: Option<String>,
+        /// Source cell with sheet (e.g. Sheet1!A1)
+        #[arg(long, requires = "to", conflicts_with_all = ["sheet", "source", "dest"])]
+        from: Option<String>,
+        /// Destination cell or range, optionally with sheet (e.g. Sheet1!B2:D50)
+        #[arg(long, requires = "from")]
+        to: Option<String>,
     },
     /// Clear style from a range
     Clear {
@@ -66,6 +79,8 @@ pub enum StyleCommand {
     Freeze(FreezeArgs),
     /// Style presets
     Preset(PresetArgs),
+    /// Static formatting from a YAML rules file
+    Rules(RulesArgs),
 }
 
 #[derive(Parser)]
@@ -161,6 +176,23 @@ pub enum PresetCommand {
 }
 
 /// Run style operations.
+#[derive(Parser)]
+pub struct RulesArgs {
+    #[command(subcommand)]
+    pub command: RulesCommand,
+}
+
+#[derive(Subcommand)]
+pub enum RulesCommand {
+    /// Apply formatting rules as static cell styles
+    Apply {
+        /// Path to the xlsx file
+        file: std::path::PathBuf,
+        /// YAML file with the rules
+        rules: std::path::PathBuf,
+    },
+}
+
 pub fn run(args: &StyleArgs, global: &GlobalOptions) -> Result<()> {
     match &args.command {
         StyleCommand::List { file } => list(file, global),
@@ -176,11 +208,33 @@ pub fn run(args: &StyleArgs, global: &GlobalOptions) -> Result<()> {
             sheet,
             source,
             dest,
-        } => copy(file, sheet, source, dest, global),
+            from,
+            to,
+        } => match (from, to) {
+            (Some(from), Some(to)) => {
+                let (source_sheet, source) = split_sheet_ref(from).ok_or_else(|| {
+                    anyhow::anyhow!("--from must include a sheet name (e.g. Sheet1!A1)")
+                })?;
+                let (dest_sheet, dest) = split_sheet_ref(to).unwrap_or((source_sheet.clone(), to));
+                copy(file, &source_sheet, source, &dest_sheet, dest, global)
+            }
+            _ => {
+                let sheet = sheet.as_deref().unwrap_or_default();
+                copy(
+                    file,
+                    sheet,
+                    source.as_deref().unwrap_or_default(),
+                    sheet,
+                    dest.as_deref().unwrap_or_default(),
+                    global,
+                )
+            }
+        },
         StyleCommand::Clear { file, sheet, range } => clear(file, sheet, range, global),
         StyleCommand::Condition(cond_args) => run_condition(cond_args, global),
         StyleCommand::Freeze(freeze_args) => run_freeze(freeze_args, global),
         StyleCommand::Preset(preset_args) => run_preset(preset_args, global),
+        StyleCommand::Rules(rules_args) => run_rules(rules_args, global),
     }
 }
 
@@ -197,10 +251,10 @@ fn run_condition(args: &ConditionArgs, global: &GlobalOptions) -> Result<()> {
     if args.list {
         if global.format == OutputFormat::Json {
             // Conditional formatting stored in sheet XML - return empty for now
-            println!("{}", serde_json::json!({"conditions": []}));
+            outln!("{}", serde_json::json!({"conditions": []}));
         } else {
-            println!("No conditional formatting rules found");
-            println!(
+            outln!("No conditional formatting rules found");
+            outln!(
                 "{}",
                 "(Conditional formatting requires full XML support)".yellow()
             );
@@ -211,16 +265,16 @@ fn run_condition(args: &ConditionArgs, global: &GlobalOptions) -> Result<()> {
     // Remove conditional formatting
     if args.remove {
         if global.dry_run {
-            println!("Would remove conditional formatting from {:?}", args.range);
+            outln!("Would remove conditional formatting from {:?}", args.range);
             return Ok(());
         }
         if !global.quiet {
-            println!(
+            outln!(
                 "{} Removed conditional formatting from {:?}",
                 "✓".green(),
                 args.range
             );
-            println!(
+            outln!(
                 "{}",
                 "(Note: Full support requires conditional formatting XML)".yellow()
             );
@@ -245,16 +299,17 @@ fn run_condition(args: &ConditionArgs, global: &GlobalOptions) -> Result<()> {
             } else {
                 "specified condition".to_string()
             };
-            println!(
+            outln!(
                 "Would add highlight rule for cells {} to {}",
-                condition, range
+                condition,
+                range
             );
         } else if args.color_scale {
-            println!("Would add color scale to {}", range);
+            outln!("Would add color scale to {}", range);
         } else if args.data_bars {
-            println!("Would add data bars to {}", range);
+            outln!("Would add data bars to {}", range);
         } else if args.icon_set.is_some() {
-            println!(
+            outln!(
                 "Would add icon set '{}' to {}",
                 args.icon_set.as_ref().unwrap(),
                 range
@@ -272,7 +327,7 @@ fn run_condition(args: &ConditionArgs, global: &GlobalOptions) -> Result<()> {
                 .map(|c| format!(" with bg #{}", c))
                 .unwrap_or_default();
             if let Some(gt) = args.gt {
-                println!(
+                outln!(
                     "{} Added highlight rule for cells > {}{} to {}",
                     "✓".green(),
                     gt,
@@ -280,7 +335,7 @@ fn run_condition(args: &ConditionArgs, global: &GlobalOptions) -> Result<()> {
                     range.cyan()
                 );
             } else if let Some(lt) = args.lt {
-                println!(
+                outln!(
                     "{} Added highlight rule for cells < {}{} to {}",
                     "✓".green(),
                     lt,
@@ -288,7 +343,7 @@ fn run_condition(args: &ConditionArgs, global: &GlobalOptions) -> Result<()> {
                     range.cyan()
                 );
             } else if let Some(eq) = args.eq {
-                println!(
+                outln!(
                     "{} Added highlight rule for cells = {}{} to {}",
                     "✓".green(),
                     eq,
@@ -307,7 +362,7 @@ fn run_condition(args: &ConditionArgs, global: &GlobalOptions) -> Result<()> {
                 .as_ref()
                 .map(|c| format!("#{}", c))
                 .unwrap_or_else(|| "#00FF00".to_string());
-            println!(
+            outln!(
                 "{} Added color scale ({} to {}) to {}",
                 "✓".green(),
                 min,
@@ -320,21 +375,21 @@ fn run_condition(args: &ConditionArgs, global: &GlobalOptions) -> Result<()> {
                 .as_ref()
                 .map(|c| format!("#{}", c))
                 .unwrap_or_else(|| "#4472C4".to_string());
-            println!(
+            outln!(
                 "{} Added data bars ({}) to {}",
                 "✓".green(),
                 color,
                 range.cyan()
             );
         } else if let Some(ref icon_set) = args.icon_set {
-            println!(
+            outln!(
                 "{} Added icon set '{}' to {}",
                 "✓".green(),
                 icon_set,
                 range.cyan()
             );
         }
-        println!(
+        outln!(
             "{}",
             "(Note: Full conditional formatting requires XML support)".yellow()
         );
@@ -356,7 +411,7 @@ fn run_freeze(args: &FreezeArgs, global: &GlobalOptions) -> Result<()> {
     // Get current freeze pane status (show)
     if !args.unfreeze && args.rows.is_none() && args.cols.is_none() && args.at.is_none() {
         if global.format == OutputFormat::Json {
-            println!(
+            outln!(
                 "{}",
                 serde_json::json!({
                     "sheet": args.sheet,
@@ -365,23 +420,25 @@ fn run_freeze(args: &FreezeArgs, global: &GlobalOptions) -> Result<()> {
                 })
             );
         } else {
-            println!("{}: No freeze panes set", args.sheet);
-            println!("{}", "(Use --rows, --cols, or --at to freeze)".dimmed());
+            outln!("{}: No freeze panes set", args.sheet);
+            outln!("{}", "(Use --rows, --cols, or --at to freeze)".dimmed());
         }
         return Ok(());
     }
 
     if global.dry_run {
         if args.unfreeze {
-            println!("Would unfreeze panes in {}", args.sheet);
+            outln!("Would unfreeze panes in {}", args.sheet);
         } else if let Some(ref cell) = args.at {
-            println!("Would freeze at {} in {}", cell, args.sheet);
+            outln!("Would freeze at {} in {}", cell, args.sheet);
         } else {
             let rows = args.rows.unwrap_or(0);
             let cols = args.cols.unwrap_or(0);
-            println!(
+            outln!(
                 "Would freeze {} rows and {} columns in {}",
-                rows, cols, args.sheet
+                rows,
+                cols,
+                args.sheet
             );
         }
         return Ok(());
@@ -390,9 +447,9 @@ fn run_freeze(args: &FreezeArgs, global: &GlobalOptions) -> Result<()> {
     // Stub implementation - freeze panes stored in sheet XML
     if !global.quiet {
         if args.unfreeze {
-            println!("{} Unfroze panes in {}", "✓".green(), args.sheet.cyan());
+            outln!("{} Unfroze panes in {}", "✓".green(), args.sheet.cyan());
         } else if let Some(ref cell) = args.at {
-            println!(
+            outln!(
                 "{} Froze panes at {} in {}",
                 "✓".green(),
                 cell.cyan(),
@@ -401,7 +458,7 @@ fn run_freeze(args: &FreezeArgs, global: &GlobalOptions) -> Result<()> {
         } else {
             let rows = args.rows.unwrap_or(0);
             let cols = args.cols.unwrap_or(0);
-            println!(
+            outln!(
                 "{} Froze {} rows and {} columns in {}",
                 "✓".green(),
                 rows,
@@ -409,7 +466,7 @@ fn run_freeze(args: &FreezeArgs, global: &GlobalOptions) -> Result<()> {
                 args.sheet.cyan()
             );
         }
-        println!(
+        outln!(
             "{}",
             "(Note: Full freeze pane support requires sheetViews XML)".yellow()
         );
@@ -440,9 +497,9 @@ fn list(file: &std::path::Path, global: &GlobalOptions) -> Result<()> {
                 })
             })
             .collect();
-        println!("{}", serde_json::to_string_pretty(&styles)?);
+        outln!("{}", serde_json::to_string_pretty(&styles)?);
     } else {
-        println!("{}: {}", "Styles".bold(), registry.len());
+        outln!("{}: {}", "Styles".bold(), registry.len());
         for (id, style) in registry.iter() {
             let mut attrs = Vec::new();
             if let Some(ref name) = style.font.name {
@@ -463,7 +520,7 @@ fn list(file: &std::path::Path, global: &GlobalOptions) -> Result<()> {
             } else {
                 attrs.join(", ")
             };
-            println!("  {}: {}", format!("#{}", id).cyan(), attrs_str);
+            outln!("  {}: {}", format!("#{}", id).cyan(), attrs_str);
         }
     }
 
@@ -504,29 +561,110 @@ fn get(file: &std::path::Path, id: u32, global: &GlobalOptions) -> Result<()> {
                 "wrapText": style.wrap_text,
             },
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else {
-        println!("{}: {}", "Style ID".bold(), id);
-        println!("\n{}:", "Font".cyan());
+        outln!("{}: {}", "Style ID".bold(), id);
+        outln!("\n{}:", "Font".cyan());
         if let Some(ref name) = style.font.name {
-            println!("  Name: {}", name);
+            outln!("  Name: {}", name);
         }
         if let Some(size) = style.font.size {
-            println!("  Size: {}", size);
+            outln!("  Size: {}", size);
         }
-        println!("  Bold: {}", style.font.bold);
-        println!("  Italic: {}", style.font.italic);
-        println!("  Underline: {}", style.font.underline);
+        outln!("  Bold: {}", style.font.bold);
+        outln!("  Italic: {}", style.font.italic);
+        outln!("  Underline: {}", style.font.underline);
 
-        println!("\n{}:", "Alignment".cyan());
-        println!("  Horizontal: {:?}", style.horizontal_alignment);
-        println!("  Vertical: {:?}", style.vertical_alignment);
-        println!("  Wrap Text: {}", style.wrap_text);
+        outln!("\n{}:", "Alignment".cyan());
+        outln!("  Horizontal: {:?}", style.horizontal_alignment);
+        outln!("  Vertical: {:?}", style.vertical_alignment);
+        outln!("  Wrap Text: {}", style.wrap_text);
     }
 
     Ok(())
 }
 
+/// Renders a color for JSON output: hex for RGB, otherwise the color kind.
+fn color_json(color: &Option<xlex_core::style::Color>) -> serde_json::Value {
+    use xlex_core::style::Color;
+    match color {
+        None => serde_json::Value::Null,
+        Some(Color::Rgb(_)) => serde_json::json!(color.as_ref().and_then(|c| c.to_hex())),
+        Some(Color::Theme(i)) => serde_json::json!({ "theme": i }),
+        Some(Color::Indexed(i)) => serde_json::json!({ "indexed": i }),
+        Some(Color::Auto) => serde_json::json!("auto"),
+    }
+}
+
+/// Renders a complete style as JSON, including every font, fill, border,
+/// number format and alignment property.
+pub(crate) fn style_json(style: &xlex_core::Style) -> serde_json::Value {
+    let side = |s: &xlex_core::style::BorderSide| {
+        serde_json::json!({
+            "style": format!("{:?}", s.style),
+            "color": color_json(&s.color),
+        })
+    };
+    serde_json::json!({
+        "font": {
+            "name": style.font.name,
+            "size": style.font.size,
+            "bold": style.font.bold,
+            "italic": style.font.italic,
+            "underline": style.font.underline,
+            "strikethrough": style.font.strikethrough,
+            "color": color_json(&style.font.color),
+        },
+        "fill": {
+            "pattern": format!("{:?}", style.fill.pattern),
+            "fgColor": color_json(&style.fill.fg_color),
+            "bgColor": color_json(&style.fill.bg_color),
+            "gradient": style.fill.gradient.as_ref().map(|g| serde_json::json!({
+                "type": format!("{:?}", g.gradient_type),
+                "degree": g.degree,
+                "stops": g.stops.iter().map(|s| serde_json::json!({
+                    "position": s.position,
+                    "color": color_json(&Some(s.color.clone())),
+                })).collect::<Vec<_>>(),
+            })),
+        },
+        "border": {
+            "left": side(&style.border.left),
+            "right": side(&style.border.right),
+            "top": side(&style.border.top),
+            "bottom": side(&style.border.bottom),
+            "diagonal": side(&style.border.diagonal),
+            "diagonalUp": style.border.diagonal_up,
+            "diagonalDown": style.border.diagonal_down,
+        },
+        "numberFormat": {
+            "id": style.number_format.id,
+            "code": style.number_format.code,
+        },
+        "alignment": {
+            "horizontal": format!("{:?}", style.horizontal_alignment),
+            "vertical": format!("{:?}", style.vertical_alignment),
+            "wrapText": style.wrap_text,
+            "textRotation": style.text_rotation,
+            "indent": style.indent,
+            "shrinkToFit": style.shrink_to_fit,
+        },
+        "protection": {
+            "locked": style.protection.locked,
+            "hidden": style.protection.hidden,
+        },
+        "checkbox": style.checkbox,
+    })
+}
+
+/// Fails unless `style_id` names a style in the workbook's registry.
+pub(super) fn ensure_style_exists(workbook: &Workbook, style_id: u32) -> Result<()> {
+    if workbook.style_registry().get(style_id).is_none() {
+        anyhow::bail!("Style ID {} not found", style_id);
+    }
+    Ok(())
+}
+
 fn apply(
     file: &std::path::Path,
     sheet: &str,
@@ -535,16 +673,12 @@ fn apply(
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!("Would apply style {} to {} in {}", style_id, range, sheet);
+        outln!("Would apply style {} to {} in {}", style_id, range, sheet);
         return Ok(());
     }
 
     let mut workbook = Workbook::open(file)?;
-
-    // Verify style exists
-    if workbook.style_registry().get(style_id).is_none() {
-        anyhow::bail!("Style ID {} not found", style_id);
-    }
+    ensure_style_exists(&workbook, style_id)?;
 
     // Parse range (can be cell or range)
     let cells: Vec<CellRef> = if range.contains(':') {
@@ -577,9 +711,9 @@ fn apply(
                 "styleId": style_id,
                 "cellsUpdated": cells.len(),
             });
-            println!("{}", serde_json::to_string_pretty(&json)?);
+            outln!("{}", serde_json::to_string_pretty(&json)?);
         } else {
-            println!(
+            outln!(
                 "{} Applied style {} to {} ({} cells)",
                 "✓".green(),
                 style_id.to_string().cyan(),
@@ -594,13 +728,29 @@ fn apply(
 
 fn copy(
     file: &std::path::Path,
-    sheet: &str,
+    source_sheet: &str,
     source: &str,
+    dest_sheet: &str,
     dest: &str,
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!("Would copy style from {} to {} in {}", source, dest, sheet);
+        if source_sheet == dest_sheet {
+            outln!(
+                "Would copy style from {} to {} in {}",
+                source,
+                dest,
+                source_sheet
+            );
+        } else {
+            outln!(
+                "Would copy style from {}!{} to {}!{}",
+                source_sheet,
+                source,
+                dest_sheet,
+                dest
+            );
+        }
         return Ok(());
     }
 
@@ -610,16 +760,24 @@ fn copy(
     let source_ref = CellRef::parse(source)?;
 
     // Get source style ID
-    let style_id = {
-        let sheet_obj =
-            workbook
-                .get_sheet(sheet)
-                .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
-                    name: sheet.to_string(),
-                })?;
+    let source_id = {
+        let sheet_obj = workbook.get_sheet(source_sheet).ok_or_else(|| {
+            xlex_core::XlexError::SheetNotFound {
+                name: source_sheet.to_string(),
+            }
+        })?;
         sheet_obj.get_cell(&source_ref).and_then(|c| c.style_id)
     };
 
+    // Share the source style's registry entry rather than adding a new one
+    let style_id = source_id.map(|id| {
+        let registry = workbook.style_registry_mut();
+        match registry.get(id).cloned() {
+            Some(style) => registry.clone_for(&style),
+            None => id,
+        }
+    });
+
     // Parse destination (can be cell or range)
     let dest_cells: Vec<CellRef> = if dest.contains(':') {
         let range = Range::parse(dest)?;
@@ -630,12 +788,11 @@ fn copy(
 
     // Apply style to destination cells
     {
-        let sheet_obj =
-            workbook
-                .get_sheet_mut(sheet)
-                .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
-                    name: sheet.to_string(),
-                })?;
+        let sheet_obj = workbook.get_sheet_mut(dest_sheet).ok_or_else(|| {
+            xlex_core::XlexError::SheetNotFound {
+                name: dest_sheet.to_string(),
+            }
+        })?;
 
         for cell_ref in &dest_cells {
             sheet_obj.set_cell_style(cell_ref, style_id);
@@ -648,17 +805,19 @@ fn copy(
         if global.format == OutputFormat::Json {
             let json = serde_json::json!({
                 "source": source,
+                "sourceSheet": source_sheet,
                 "destination": dest,
+                "destinationSheet": dest_sheet,
                 "styleId": style_id,
                 "cellsUpdated": dest_cells.len(),
             });
-            println!("{}", serde_json::to_string_pretty(&json)?);
+            outln!("{}", serde_json::to_string_pretty(&json)?);
         } else {
-            println!(
+            outln!(
                 "{} Copied style from {} to {} ({} cells)",
                 "✓".green(),
-                source.cyan(),
-                dest.cyan(),
+                format!("{}!{}", source_sheet, source).cyan(),
+                format!("{}!{}", dest_sheet, dest).cyan(),
                 dest_cells.len()
             );
         }
@@ -669,7 +828,7 @@ fn copy(
 
 fn clear(file: &std::path::Path, sheet: &str, range: &str, global: &GlobalOptions) -> Result<()> {
     if global.dry_run {
-        println!("Would clear style from {} in {}", range, sheet);
+        outln!("Would clear style from {} in {}", range, sheet);
         return Ok(());
     }
 
@@ -705,9 +864,9 @@ fn clear(file: &std::path::Path, sheet: &str, range: &str, global: &GlobalOption
                 "range": range,
                 "cellsCleared": cells.len(),
             });
-            println!("{}", serde_json::to_string_pretty(&json)?);
+            outln!("{}", serde_json::to_string_pretty(&json)?);
         } else {
-            println!(
+            outln!(
                 "{} Cleared style from {} ({} cells)",
                 "✓".green(),
                 range.cyan(),
@@ -742,11 +901,11 @@ fn run_preset(args: &PresetArgs, global: &GlobalOptions) -> Result<()> {
                         })
                     })
                     .collect();
-                println!("{}", serde_json::to_string_pretty(&json)?);
+                outln!("{}", serde_json::to_string_pretty(&json)?);
             } else {
-                println!("{}:", "Available Presets".bold());
+                outln!("{}:", "Available Presets".bold());
                 for (name, desc) in presets {
-                    println!("  {}: {}", name.cyan(), desc);
+                    outln!("  {}: {}", name.cyan(), desc);
                 }
             }
             Ok(())
@@ -758,7 +917,7 @@ fn run_preset(args: &PresetArgs, global: &GlobalOptions) -> Result<()> {
             preset,
         } => {
             if global.dry_run {
-                println!("Would apply preset '{}' to {} in {}", preset, range, sheet);
+                outln!("Would apply preset '{}' to {} in {}", preset, range, sheet);
                 return Ok(());
             }
 
@@ -768,6 +927,223 @@ fn run_preset(args: &PresetArgs, global: &GlobalOptions) -> Result<()> {
     }
 }
 
+/// A YAML rules file for `style rules apply`.
+///
+/// ```yaml
+/// sheet: Sheet1
+/// rules:
+///   - range: B2:B100
+///     when: negative
+///     style: { color: FF0000 }
+///   - range: B2:B100
+///     when: "> 1000"
+///     style: { bold: true }
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    /// Default sheet for ranges without a sheet prefix
+    sheet: Option<String>,
+    rules: Vec<StyleRule>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StyleRule {
+    /// Target range, optionally sheet-qualified (`Sheet1!B2:B100`)
+    range: String,
+    /// Condition the cell value must meet; every cell matches when omitted
+    when: Option<String>,
+    style: RuleStyle,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct RuleStyle {
+    bold: Option<bool>,
+    italic: Option<bool>,
+    underline: Option<bool>,
+    strikethrough: Option<bool>,
+    font: Option<String>,
+    font_size: Option<f64>,
+    /// Text color (hex)
+    color: Option<String>,
+    /// Background color (hex)
+    bg_color: Option<String>,
+    number_format: Option<String>,
+    align: Option<String>,
+    wrap: Option<bool>,
+}
+
+impl RuleStyle {
+    /// Layers the rule's settings over an existing style.
+    pub(super) fn apply_to(&self, style: &mut xlex_core::Style) -> Result<()> {
+        use xlex_core::style::{Color, FillPattern, HorizontalAlignment, NumberFormat};
+
+        let color = |hex: &str| {
+            Color::from_hex(hex).ok_or_else(|| anyhow::anyhow!("Invalid color '{}'", hex))
+        };
+        if let Some(bold) = self.bold {
+            style.font.bold = bold;
+        }
+        if let Some(italic) = self.italic {
+            style.font.italic = italic;
+        }
+        if let Some(underline) = self.underline {
+            style.font.underline = underline;
+        }
+        if let Some(strikethrough) = self.strikethrough {
+            style.font.strikethrough = strikethrough;
+        }
+        if let Some(ref font) = self.font {
+            style.font.name = Some(font.clone());
+        }
+        if let Some(size) = self.font_size {
+            style.font.size = Some(size);
+        }
+        if let Some(ref hex) = self.color {
+            style.font.color = Some(color(hex)?);
+        }
+        if let Some(ref hex) = self.bg_color {
+            style.fill.pattern = FillPattern::Solid;
+            style.fill.fg_color = Some(color(hex)?);
+        }
+        if let Some(ref code) = self.number_format {
+            style.number_format = NumberFormat::custom(code.clone());
+        }
+        if let Some(ref align) = self.align {
+            style.horizontal_alignment = match align.to_lowercase().as_str() {
+                "left" => HorizontalAlignment::Left,
+                "center" => HorizontalAlignment::Center,
+                "right" => HorizontalAlignment::Right,
+                "justify" => HorizontalAlignment::Justify,
+                "general" => HorizontalAlignment::General,
+                other => anyhow::bail!("Invalid alignment '{}'", other),
+            };
+        }
+        if let Some(wrap) = self.wrap {
+            style.wrap_text = wrap;
+        }
+        Ok(())
+    }
+}
+
+fn run_rules(args: &RulesArgs, global: &GlobalOptions) -> Result<()> {
+    match &args.command {
+        RulesCommand::Apply { file, rules } => apply_rules(file, rules, global),
+    }
+}
+
+fn apply_rules(
+    file: &std::path::Path,
+    rules_path: &std::path::Path,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let content = std::fs::read_to_string(rules_path)?;
+    let rules_file: RulesFile = serde_yaml::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("Invalid rules file {}: {}", rules_path.display(), e))?;
+
+    // Validate everything before touching the workbook
+    let mut rules = Vec::with_capacity(rules_file.rules.len());
+    for rule in &rules_file.rules {
+        let (sheet, range) = match split_sheet_ref(&rule.range) {
+            Some((sheet, range)) => (sheet, range),
+            None => (
+                rules_file.sheet.clone().ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Rule range '{}' has no sheet and the rules file sets no default sheet",
+                        rule.range
+                    )
+                })?,
+                rule.range.as_str(),
+            ),
+        };
+        let range = Range::parse(range)?;
+        let condition = Condition::parse(rule.when.as_deref())?;
+        rule.style.apply_to(&mut xlex_core::Style::default())?;
+        rules.push((sheet, range, condition, &rule.style));
+    }
+
+    if global.dry_run {
+        outln!(
+            "Would apply {} rules from {} to {}",
+            rules.len(),
+            rules_path.display(),
+            file.display()
+        );
+        return Ok(());
+    }
+
+    let mut workbook = Workbook::open(file)?;
+    let styles_before = workbook.style_registry().len();
+
+    // Later rules layer over earlier ones, so collect each cell's matches first
+    let mut matched: Vec<(String, CellRef, Vec<&RuleStyle>)> = Vec::new();
+    let mut index: std::collections::HashMap<(String, u32, u32), usize> =
+        std::collections::HashMap::new();
+    for (sheet, range, condition, style) in &rules {
+        let sheet_obj =
+            workbook
+                .get_sheet(sheet)
+                .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                    name: sheet.clone(),
+                })?;
+        for cell_ref in range.cells() {
+            if !condition.matches(&sheet_obj.get_value(&cell_ref)) {
+                continue;
+            }
+            let key = (sheet.clone(), cell_ref.col, cell_ref.row);
+            let slot = *index.entry(key).or_insert_with(|| {
+                matched.push((sheet.clone(), cell_ref.clone(), Vec::new()));
+                matched.len() - 1
+            });
+            matched[slot].2.push(*style);
+        }
+    }
+
+    for (sheet, cell_ref, styles) in &matched {
+        let current = workbook
+            .get_sheet(sheet)
+            .and_then(|s| s.get_cell(cell_ref))
+            .and_then(|c| c.style_id);
+        let registry = workbook.style_registry_mut();
+        let mut style = current
+            .and_then(|id| registry.get(id).cloned())
+            .unwrap_or_default();
+        for rule_style in styles {
+            rule_style.apply_to(&mut style)?;
+        }
+        let style_id = registry.clone_for(&style);
+        if let Some(sheet_obj) = workbook.get_sheet_mut(sheet) {
+            sheet_obj.set_cell_style(cell_ref, Some(style_id));
+        }
+    }
+
+    let styles_added = workbook.style_registry().len() - styles_before;
+    workbook.save()?;
+
+    if !global.quiet {
+        if global.format == OutputFormat::Json {
+            let json = serde_json::json!({
+                "rules": rules.len(),
+                "cellsStyled": matched.len(),
+                "stylesAdded": styles_added,
+            });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else {
+            outln!(
+                "{} Applied {} rules ({} cells styled, {} new styles)",
+                "✓".green(),
+                rules.len(),
+                matched.len(),
+                styles_added
+            );
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -783,6 +1159,11 @@ mod tests {
             json_errors: false,
             dry_run: false,
             output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
         }
     }
 
@@ -875,7 +1256,14 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let file_path = create_test_workbook(&temp_dir, "copy.xlsx");
 
-        let result = copy(&file_path, "Sheet1", "A1", "B1", &default_global());
+        let result = copy(
+            &file_path,
+            "Sheet1",
+            "A1",
+            "Sheet1",
+            "B1",
+            &default_global(),
+        );
         assert!(result.is_ok());
     }
 
@@ -884,10 +1272,129 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let file_path = create_test_workbook(&temp_dir, "copy_range.xlsx");
 
-        let result = copy(&file_path, "Sheet1", "A1", "B1:C3", &default_global());
+        let result = copy(
+            &file_path,
+            "Sheet1",
+            "A1",
+            "Sheet1",
+            "B1:C3",
+            &default_global(),
+        );
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_run_copy_from_to_shares_style_id() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("copy_from_to.xlsx");
+        let mut wb = Workbook::new();
+        wb.add_sheet("Sheet2").unwrap();
+        let mut bold = xlex_core::Style::default();
+        bold.font.bold = true;
+        let style_id = wb.style_registry_mut().add(bold);
+        let sheet1 = wb.get_sheet_mut("Sheet1").unwrap();
+        let a1 = CellRef::parse("A1").unwrap();
+        sheet1.set_cell(a1.clone(), xlex_core::CellValue::string("Header"));
+        sheet1.set_cell_style(&a1, Some(style_id));
+        wb.save_as(&file_path).unwrap();
+
+        let before = Workbook::open(&file_path).unwrap();
+        let styles_before = before.style_registry().len();
+        let source_id = before
+            .get_sheet("Sheet1")
+            .unwrap()
+            .get_cell(&CellRef::parse("A1").unwrap())
+            .and_then(|c| c.style_id);
+
+        let args = StyleArgs {
+            command: StyleCommand::Copy {
+                file: file_path.clone(),
+                sheet: None,
+                source: None,
+                dest: None,
+                from: Some("Sheet1!A1".to_string()),
+                to: Some("Sheet2!B2:D5".to_string()),
+            },
+        };
+        run(&args, &default_global()).unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        assert!(source_id.is_some());
+        assert_eq!(wb.style_registry().len(), styles_before);
+        let sheet2 = wb.get_sheet("Sheet2").unwrap();
+        for cell in ["B2", "D5"] {
+            let id = sheet2
+                .get_cell(&CellRef::parse(cell).unwrap())
+                .and_then(|c| c.style_id);
+            assert_eq!(id, source_id);
+        }
+        assert!(
+            wb.get_cell_style("Sheet2", &CellRef::parse("C3").unwrap())
+                .unwrap()
+                .font
+                .bold
+        );
+    }
+
+    #[test]
+    fn test_apply_rules() {
+        use xlex_core::CellValue;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("rules.xlsx");
+        let mut wb = Workbook::new();
+        for (row, value) in [(2, -5.0), (3, 20.0), (4, 1500.0), (5, -7.0)] {
+            wb.set_cell("Sheet1", CellRef::new(2, row), CellValue::number(value))
+                .unwrap();
+        }
+        wb.save_as(&file_path).unwrap();
+
+        let rules_path = temp_dir.path().join("rules.yml");
+        std::fs::write(
+            &rules_path,
+            "sheet: Sheet1\nrules:\n  - range: B2:B100\n    when: negative\n    style: { color: FF0000 }\n  - range: Sheet1!B2:B100\n    when: \"> 1000\"\n    style: { bold: true }\n",
+        )
+        .unwrap();
+
+        apply_rules(&file_path, &rules_path, &default_global()).unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let style_of = |cell: &str| {
+            wb.get_cell_style("Sheet1", &CellRef::parse(cell).unwrap())
+                .unwrap()
+        };
+        assert_eq!(
+            style_of("B2").font.color.and_then(|c| c.to_hex()),
+            Some("FF0000".to_string())
+        );
+        assert!(style_of("B4").font.bold);
+        assert!(!style_of("B3").font.bold);
+        assert!(style_of("B3").font.color.is_none());
+
+        // Both negative cells share one style entry
+        let sheet = wb.get_sheet("Sheet1").unwrap();
+        let id_of = |cell: &str| {
+            sheet
+                .get_cell(&CellRef::parse(cell).unwrap())
+                .and_then(|c| c.style_id)
+        };
+        assert_eq!(id_of("B2"), id_of("B5"));
+    }
+
+    #[test]
+    fn test_apply_rules_requires_sheet() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_workbook(&temp_dir, "rules_nosheet.xlsx");
+        let rules_path = temp_dir.path().join("rules.yml");
+        std::fs::write(
+            &rules_path,
+            "rules:\n  - range: A1:A5\n    style: { bold: true }\n",
+        )
+        .unwrap();
+
+        assert!(apply_rules(&file_path, &rules_path, &default_global()).is_err());
+    }
+
     #[test]
     fn test_clear_style() {
         let temp_dir = TempDir::new().unwrap();
@@ -1040,9 +1547,11 @@ mod tests {
         let args = StyleArgs {
             command: StyleCommand::Copy {
                 file: file_path,
-                sheet: "Sheet1".to_string(),
-                source: "A1".to_string(),
-                dest: "B1".to_string(),
+                sheet: Some("Sheet1".to_string()),
+                source: Some("A1".to_string()),
+                dest: Some("B1".to_string()),
+                from: None,
+                to: None,
             },
         };
 
@@ -1075,7 +1584,7 @@ mod tests {
         let mut global = default_global();
         global.dry_run = true;
 
-        let result = copy(&file_path, "Sheet1", "A1", "B1", &global);
+        let result = copy(&file_path, "Sheet1", "A1", "Sheet1", "B1", &global);
         assert!(result.is_ok());
     }
 
@@ -1897,7 +2406,7 @@ mod tests {
         let mut global = default_global();
         global.quiet = false;
 
-        let result = copy(&file_path, "Sheet1", "A1", "B1", &global);
+        let result = copy(&file_path, "Sheet1", "A1", "Sheet1", "B1", &global);
         assert!(result.is_ok());
     }
 
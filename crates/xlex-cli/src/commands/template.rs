@@ -4,9 +4,16 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use colored::Colorize;
 
+use xlex_core::aggregate::{AggFunc, Aggregation, GroupBy};
+use xlex_core::filters::Filters;
+use xlex_core::pipeline::RowSink;
+use xlex_core::rounding::{round, RoundingMode};
 use xlex_core::{CellRef, CellValue, Workbook};
 
-use super::{GlobalOptions, OutputFormat};
+use super::output::CommandOutput;
+use super::template_syntax::{self, Block, Node, SyntaxError, TokenKind, CELL_BREAK};
+use super::{FailOn, GlobalOptions, OutputFormat};
+use crate::progress::Progress;
 
 /// Arguments for template operations.
 #[derive(Parser)]
@@ -59,6 +66,11 @@ pub enum TemplateCommand {
         /// Generate JSON schema for required data
         #[arg(long)]
         schema: bool,
+        /// Findings that fail the command (exit code 12). Syntax errors and,
+        /// with --vars, missing values are errors; unused variables and
+        /// placeholders without --vars are warnings
+        #[arg(long, value_enum, default_value = "errors")]
+        fail_on: FailOn,
     },
     /// Create template from existing file
     Create {
@@ -111,7 +123,8 @@ pub fn run(args: &TemplateArgs, global: &GlobalOptions) -> Result<()> {
             template,
             vars,
             schema,
-        } => validate(template, vars.as_deref(), *schema, global),
+            fail_on,
+        } => validate(template, vars.as_deref(), *schema, *fail_on, global),
         TemplateCommand::Create {
             source,
             output,
@@ -135,7 +148,7 @@ fn apply(
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!(
+        outln!(
             "Would apply template {} to {}",
             template.display(),
             output.display()
@@ -162,7 +175,9 @@ fn apply_single(
     vars: &TemplateVars,
     global: &GlobalOptions,
 ) -> Result<()> {
+    let report = CommandOutput::new("apply");
     let mut workbook = Workbook::open(template)?;
+    let vars = &take_defaults(&mut workbook, vars)?;
 
     let sheet_names: Vec<String> = workbook
         .sheet_names()
@@ -170,43 +185,58 @@ fn apply_single(
         .map(|s| s.to_string())
         .collect();
 
+    let mut removed_rows = 0;
     for sheet_name in sheet_names {
-        // First, handle row-repeat markers
+        // First, drop the rows of false row-level conditionals
+        removed_rows += process_row_conditionals(&mut workbook, &sheet_name, vars)?;
+
+        // Then, handle row-repeat markers
         process_row_repeats(&mut workbook, &sheet_name, vars)?;
 
         // Then, process all cells for placeholders
-        let cells_to_update: Vec<(CellRef, String)> =
-            if let Some(sheet) = workbook.get_sheet(&sheet_name) {
-                sheet
-                    .cells()
-                    .filter_map(|cell| {
-                        if let CellValue::String(s) = &cell.value {
-                            let new_value = process_template_string(s, vars);
-                            if new_value != *s {
-                                return Some((cell.reference.clone(), new_value));
-                            }
-                        }
-                        None
-                    })
-                    .collect()
-            } else {
-                continue;
-            };
+        let cells_to_update: Vec<(CellRef, String)> = if let Some(sheet) =
+            workbook.get_sheet(&sheet_name)
+        {
+            sheet
+                .cells()
+                .filter_map(|cell| {
+                    let CellValue::String(s) = &cell.value else {
+                        return None;
+                    };
+                    match process_template_string(s, vars) {
+                        Ok(new_value) if new_value == *s => None,
+                        Ok(new_value) => Some(Ok((cell.reference.clone(), new_value))),
+                        Err(err) => Some(Err(syntax_error(&sheet_name, &cell.reference, s, &err))),
+                    }
+                })
+                .collect::<Result<_>>()?
+        } else {
+            continue;
+        };
 
         // Apply the updates
-        for (cell_ref, new_value) in cells_to_update {
-            workbook.set_cell(&sheet_name, cell_ref, CellValue::String(new_value))?;
-        }
+        workbook.with_sheet_mut(&sheet_name, |sheet| {
+            sheet.apply_updates(
+                cells_to_update
+                    .into_iter()
+                    .map(|(cell_ref, new_value)| (cell_ref, CellValue::String(new_value))),
+            )
+        })?;
     }
 
     workbook.save_as(output)?;
 
-    if !global.quiet {
-        println!(
-            "Applied template to {}",
-            output.display().to_string().green()
-        );
-    }
+    report
+        .file(output)
+        .target("template", template.display().to_string())
+        .count("removedRows", removed_rows)
+        .print(
+            global,
+            format!(
+                "Applied template to {}",
+                output.display().to_string().green()
+            ),
+        )?;
 
     Ok(())
 }
@@ -240,6 +270,7 @@ fn apply_per_record(
         .unwrap_or("output");
 
     let mut generated_files = Vec::new();
+    let progress = Progress::rows(records.len(), "Generating files...", global);
 
     for (index, record) in records.iter().enumerate() {
         // Create vars for this record
@@ -267,6 +298,12 @@ fn apply_per_record(
         )?;
 
         generated_files.push(output_path);
+        if let Some(ref pb) = progress {
+            pb.inc(1);
+        }
+    }
+    if let Some(ref pb) = progress {
+        pb.finish_and_clear();
     }
 
     if !global.quiet {
@@ -275,15 +312,15 @@ fn apply_per_record(
                 "generated": generated_files.iter().map(|p| p.display().to_string()).collect::<Vec<_>>(),
                 "count": generated_files.len(),
             });
-            println!("{}", serde_json::to_string_pretty(&json)?);
+            outln!("{}", serde_json::to_string_pretty(&json)?);
         } else {
-            println!(
+            outln!(
                 "{} Generated {} files from template",
                 "✓".green(),
                 generated_files.len().to_string().cyan()
             );
             for path in &generated_files {
-                println!("  - {}", path.display().to_string().yellow());
+                outln!("  - {}", path.display().to_string().yellow());
             }
         }
     }
@@ -293,7 +330,7 @@ fn apply_per_record(
 
 fn init(output: &std::path::Path, template_type: &str, global: &GlobalOptions) -> Result<()> {
     if global.dry_run {
-        println!(
+        outln!(
             "Would create {} template at {}",
             template_type,
             output.display()
@@ -491,19 +528,19 @@ fn init(output: &std::path::Path, template_type: &str, global: &GlobalOptions) -
                 "created": output.display().to_string(),
                 "type": template_type,
             });
-            println!("{}", serde_json::to_string_pretty(&json)?);
+            outln!("{}", serde_json::to_string_pretty(&json)?);
         } else {
-            println!(
+            outln!(
                 "{} Created {} template: {}",
                 "✓".green(),
                 template_type.cyan(),
                 output.display().to_string().yellow()
             );
-            println!(
+            outln!(
                 "\nUse {} to see placeholders",
                 "xlex template list <file>".dimmed()
             );
-            println!(
+            outln!(
                 "Use {} to apply variables",
                 "xlex template apply <template> <output> -D key=value".dimmed()
             );
@@ -515,6 +552,7 @@ fn init(output: &std::path::Path, template_type: &str, global: &GlobalOptions) -
 
 fn list(template: &std::path::Path, global: &GlobalOptions) -> Result<()> {
     let workbook = Workbook::open(template)?;
+    let defaults = template_defaults(&workbook)?;
 
     let mut placeholders: Vec<(String, String, String)> = Vec::new(); // (sheet, cell, placeholder)
 
@@ -549,18 +587,42 @@ fn list(template: &std::path::Path, global: &GlobalOptions) -> Result<()> {
                     "placeholder": p,
                 })
             }).collect::<Vec<_>>(),
+            "defaults": defaults.iter().map(|d| {
+                serde_json::json!({
+                    "name": d.name,
+                    "value": d.value,
+                    "type": d.kind,
+                })
+            }).collect::<Vec<_>>(),
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else {
-        println!("{}: {}", "Placeholders".bold(), unique.len());
+        outln!("{}: {}", "Placeholders".bold(), unique.len());
         for name in &unique {
-            println!("  {}", format!("{{{{{}}}}}", name).cyan());
+            outln!("  {}", format!("{{{{{}}}}}", name).cyan());
+        }
+
+        if !defaults.is_empty() {
+            outln!(
+                "\n{} ({}): {}",
+                "Defaults".bold(),
+                VARS_SHEET,
+                defaults.len()
+            );
+            for d in &defaults {
+                outln!(
+                    "  {} = {} {}",
+                    d.name.cyan(),
+                    value_to_string(&d.value),
+                    format!("({})", d.kind).dimmed()
+                );
+            }
         }
 
         if global.verbose {
-            println!("\n{}:", "Locations".bold());
+            outln!("\n{}:", "Locations".bold());
             for (sheet, cell, placeholder) in &placeholders {
-                println!(
+                outln!(
                     "  {}!{}: {}",
                     sheet,
                     cell.cyan(),
@@ -577,16 +639,21 @@ fn validate(
     template: &std::path::Path,
     vars_file: Option<&std::path::Path>,
     generate_schema: bool,
+    fail_on: FailOn,
     global: &GlobalOptions,
 ) -> Result<()> {
     let workbook = Workbook::open(template)?;
 
-    // Find all placeholders
+    // Find all placeholders, and any malformed tags
     let mut placeholders: Vec<String> = Vec::new();
+    let mut errors: Vec<String> = Vec::new();
     for sheet_name in workbook.sheet_names() {
         if let Some(sheet) = workbook.get_sheet(sheet_name) {
             for cell in sheet.cells() {
                 if let CellValue::String(s) = &cell.value {
+                    if let Err(err) = template_syntax::parse(s) {
+                        errors.push(syntax_error(sheet_name, &cell.reference, s, &err).to_string());
+                    }
                     for placeholder in find_placeholders(s) {
                         if !placeholders.contains(&placeholder) {
                             placeholders.push(placeholder);
@@ -617,7 +684,7 @@ fn validate(
             "required": placeholders,
         });
 
-        println!("{}", serde_json::to_string_pretty(&schema)?);
+        outln!("{}", serde_json::to_string_pretty(&schema)?);
         return Ok(());
     }
 
@@ -642,38 +709,65 @@ fn validate(
         }
     }
 
-    let missing: Vec<_> = placeholders.iter().filter(|p| !vars.contains(p)).collect();
+    // Variables with a default in the template aren't missing
+    let defaults = template_defaults(&workbook)?;
+    let missing: Vec<_> = placeholders
+        .iter()
+        .filter(|p| {
+            !vars.contains(p)
+                && !defaults
+                    .iter()
+                    .any(|d| d.name.split('.').next() == Some(p.as_str()))
+        })
+        .collect();
 
     let unused: Vec<_> = vars.iter().filter(|v| !placeholders.contains(v)).collect();
 
     if global.format == OutputFormat::Json {
         let json = serde_json::json!({
-            "valid": missing.is_empty(),
+            "valid": missing.is_empty() && errors.is_empty(),
             "placeholders": placeholders,
             "variables": vars,
             "missing": missing,
             "unused": unused,
+            "errors": errors,
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else {
+        errors.sort();
+        if !errors.is_empty() {
+            outln!("{}: Syntax errors:", "✗".red());
+            for e in &errors {
+                outln!("  - {}", e);
+            }
+        }
         if missing.is_empty() {
-            println!("{}: All placeholders have values", "✓".green());
+            outln!("{}: All placeholders have values", "✓".green());
         } else {
-            println!("{}: Missing values for:", "✗".red());
+            outln!("{}: Missing values for:", "✗".red());
             for m in &missing {
-                println!("  - {}", format!("{{{{{}}}}}", m).cyan());
+                outln!("  - {}", format!("{{{{{}}}}}", m).cyan());
             }
         }
 
         if !unused.is_empty() && global.verbose {
-            println!("\n{}: Unused variables:", "⚠".yellow());
+            outln!("\n{}: Unused variables:", "⚠".yellow());
             for u in &unused {
-                println!("  - {}", u);
+                outln!("  - {}", u);
             }
         }
     }
 
-    Ok(())
+    // Without a variable file every placeholder is missing, which only
+    // warns that the template needs data
+    let (missing_errors, missing_warnings) = match vars_file {
+        Some(_) => (missing.len(), 0),
+        None => (0, missing.len()),
+    };
+    fail_on.check(
+        errors.len() + missing_errors,
+        missing_warnings + unused.len(),
+    )
 }
 
 fn preview(
@@ -685,7 +779,8 @@ fn preview(
     // Load variables using the new template system
     let vars = load_template_vars(vars_file, defines)?;
 
-    let workbook = Workbook::open(template)?;
+    let mut workbook = Workbook::open(template)?;
+    let vars = take_defaults(&mut workbook, &vars)?;
 
     // Collect all replacements using the advanced template processor
     let mut replacements: Vec<serde_json::Value> = Vec::new();
@@ -694,7 +789,8 @@ fn preview(
         if let Some(sheet) = workbook.get_sheet(sheet_name) {
             for cell in sheet.cells() {
                 if let CellValue::String(s) = &cell.value {
-                    let new_value = process_template_string(s, &vars);
+                    let new_value = process_template_string(s, &vars)
+                        .map_err(|err| syntax_error(sheet_name, &cell.reference, s, &err))?;
                     if new_value != *s {
                         replacements.push(serde_json::json!({
                             "sheet": sheet_name,
@@ -713,16 +809,16 @@ fn preview(
             "template": template.display().to_string(),
             "replacements": replacements,
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else {
-        println!("{}: {}\n", "Template".bold(), template.display());
+        outln!("{}: {}\n", "Template".bold(), template.display());
 
         if replacements.is_empty() {
-            println!("{}", "No replacements would be made".dimmed());
+            outln!("{}", "No replacements would be made".dimmed());
         } else {
-            println!("{} ({}):", "Replacements".bold(), replacements.len());
+            outln!("{} ({}):", "Replacements".bold(), replacements.len());
             for r in &replacements {
-                println!(
+                outln!(
                     "  [{}] {}: {} → {}",
                     r["sheet"].as_str().unwrap(),
                     r["cell"].as_str().unwrap().cyan(),
@@ -743,7 +839,7 @@ fn create(
     global: &GlobalOptions,
 ) -> Result<()> {
     if global.dry_run {
-        println!(
+        outln!(
             "Would create template {} from {}",
             output.display(),
             source.display()
@@ -751,6 +847,7 @@ fn create(
         return Ok(());
     }
 
+    let report = CommandOutput::new("create");
     let mut workbook = Workbook::open(source)?;
 
     for placeholder in placeholders {
@@ -774,13 +871,18 @@ fn create(
 
     workbook.save_as(output)?;
 
-    if !global.quiet {
-        println!(
-            "Created template {} with {} placeholders",
-            output.display().to_string().green(),
-            placeholders.len()
-        );
-    }
+    report
+        .file(output)
+        .target("source", source.display().to_string())
+        .count("placeholders", placeholders.len())
+        .print(
+            global,
+            format!(
+                "Created template {} with {} placeholders",
+                output.display().to_string().green(),
+                placeholders.len()
+            ),
+        )?;
 
     Ok(())
 }
@@ -888,6 +990,26 @@ impl TemplateVars {
         }
     }
 
+    /// Fills in values these variables lack from `defaults`; objects in
+    /// both are merged key by key.
+    pub fn merge_defaults(&mut self, defaults: &serde_json::Value) {
+        fn fill(target: &mut serde_json::Value, defaults: &serde_json::Value) {
+            if let (serde_json::Value::Object(target), serde_json::Value::Object(defaults)) =
+                (target, defaults)
+            {
+                for (k, v) in defaults {
+                    match target.get_mut(k) {
+                        Some(existing) => fill(existing, v),
+                        None => {
+                            target.insert(k.clone(), v.clone());
+                        }
+                    }
+                }
+            }
+        }
+        fill(&mut self.data, defaults);
+    }
+
     pub fn merge_object(&mut self, obj: &serde_json::Value) {
         if let (serde_json::Value::Object(ref mut target), serde_json::Value::Object(source)) =
             (&mut self.data, obj)
@@ -899,6 +1021,131 @@ impl TemplateVars {
     }
 }
 
+/// Name of the sheet holding a template's default variables.
+const VARS_SHEET: &str = "__vars";
+
+/// A default variable from the template's `__vars` sheet.
+#[derive(Debug, Clone, PartialEq)]
+struct TemplateDefault {
+    name: String,
+    value: serde_json::Value,
+    /// `string`, `number`, `bool` or `json`
+    kind: &'static str,
+}
+
+/// Reads the defaults from the template's `__vars` sheet: one per row,
+/// with the name in column A, the value in B and optionally its type in C.
+/// Without a type, numbers and booleans keep theirs and anything else is
+/// a string. A first row reading `name` is a header.
+fn template_defaults(workbook: &Workbook) -> Result<Vec<TemplateDefault>> {
+    let Some(sheet) = workbook.get_sheet(VARS_SHEET) else {
+        return Ok(Vec::new());
+    };
+    let mut rows: std::collections::BTreeMap<u32, [Option<&CellValue>; 3]> =
+        std::collections::BTreeMap::new();
+    for cell in sheet.cells() {
+        if (1..=3).contains(&cell.reference.col) {
+            rows.entry(cell.reference.row).or_default()[cell.reference.col as usize - 1] =
+                Some(&cell.value);
+        }
+    }
+
+    let mut defaults = Vec::new();
+    for (row, [name, value, kind]) in rows {
+        let text = |cell: Option<&CellValue>| {
+            cell.map(|v| v.to_display_string().trim().to_string())
+                .unwrap_or_default()
+        };
+        let name = text(name);
+        if name.is_empty() || (row == 1 && name.eq_ignore_ascii_case("name")) {
+            continue;
+        }
+        let located = |col: &str, message: String| {
+            anyhow::anyhow!("{}!{}{}: {}", VARS_SHEET, col, row, message)
+        };
+        let raw = text(value);
+        let kind = match text(kind).to_lowercase().as_str() {
+            "" => match value {
+                Some(CellValue::Number(_)) => "number",
+                Some(CellValue::Boolean(_)) => "bool",
+                _ => "string",
+            },
+            "string" | "text" => "string",
+            "number" => "number",
+            "bool" | "boolean" => "bool",
+            "json" => "json",
+            other => {
+                return Err(located(
+                    "C",
+                    format!("unknown type '{}' (string, number, bool or json)", other),
+                ))
+            }
+        };
+        let value = match kind {
+            "number" => {
+                let n: f64 = raw
+                    .parse()
+                    .map_err(|_| located("B", format!("'{}' is not a number", raw)))?;
+                if n.fract() == 0.0 && n.abs() < 9e15 {
+                    serde_json::Value::from(n as i64)
+                } else {
+                    serde_json::Value::from(n)
+                }
+            }
+            "bool" => match raw.to_lowercase().as_str() {
+                "true" | "yes" | "1" => serde_json::Value::Bool(true),
+                "false" | "no" | "0" | "" => serde_json::Value::Bool(false),
+                _ => return Err(located("B", format!("'{}' is not true or false", raw))),
+            },
+            "json" => serde_json::from_str(&raw)
+                .map_err(|e| located("B", format!("invalid JSON: {}", e)))?,
+            _ => serde_json::Value::String(raw),
+        };
+        defaults.push(TemplateDefault { name, value, kind });
+    }
+    Ok(defaults)
+}
+
+/// Takes the defaults out of the template: removes its `__vars` sheet and
+/// returns `vars` with the defaults filled in under them. Dotted names
+/// such as `company.name` set nested values.
+fn take_defaults(workbook: &mut Workbook, vars: &TemplateVars) -> Result<TemplateVars> {
+    let defaults = template_defaults(workbook)?;
+    let mut vars = vars.clone();
+    if workbook.get_sheet(VARS_SHEET).is_some() {
+        fn insert(
+            map: &mut serde_json::Map<String, serde_json::Value>,
+            path: &str,
+            value: serde_json::Value,
+        ) {
+            match path.split_once('.') {
+                Some((head, rest)) => {
+                    let entry = map
+                        .entry(head)
+                        .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+                    if !entry.is_object() {
+                        *entry = serde_json::Value::Object(serde_json::Map::new());
+                    }
+                    if let serde_json::Value::Object(inner) = entry {
+                        insert(inner, rest, value);
+                    }
+                }
+                None => {
+                    map.insert(path.to_string(), value);
+                }
+            }
+        }
+        let mut nested = serde_json::Map::new();
+        for default in defaults {
+            insert(&mut nested, &default.name, default.value);
+        }
+        let nested = serde_json::Value::Object(nested);
+        vars.merge_defaults(&nested);
+        workbook.remove_sheet(VARS_SHEET)?;
+    }
+    Ok(vars)
+}
+
 /// Load template variables from file and command-line defines.
 fn load_template_vars(
     vars_file: Option<&std::path::Path>,
@@ -962,66 +1209,104 @@ fn yaml_to_json(yaml: &serde_yaml::Value) -> serde_json::Value {
     }
 }
 
-/// Process a template string with all advanced features.
-fn process_template_string(s: &str, vars: &TemplateVars) -> String {
-    let mut result = s.to_string();
-
-    // Process conditionals first: {{#if condition}}...{{/if}}
-    result = process_conditionals(&result, vars);
-
-    // Process simple placeholders with filters: {{name|filter}}
-    result = process_placeholders_with_filters(&result, vars);
-
-    result
+/// Process a template string with all advanced features: blocks, which
+/// may nest, then placeholders with filters.
+fn process_template_string(s: &str, vars: &TemplateVars) -> Result<String, SyntaxError> {
+    let nodes = template_syntax::parse(s)?;
+    let mut out = String::with_capacity(s.len());
+    render(&nodes, vars, &mut out);
+    Ok(out)
 }
 
-/// Process conditional blocks: {{#if condition}}content{{/if}} and {{#if condition}}content{{else}}other{{/if}}
-fn process_conditionals(s: &str, vars: &TemplateVars) -> String {
-    let mut result = s.to_string();
-
-    // Pattern for {{#if condition}}...{{/if}} (with optional {{else}})
-    let if_pattern = regex_lite::Regex::new(r"\{\{#if\s+([^}]+)\}\}([\s\S]*?)\{\{/if\}\}").unwrap();
-
-    while let Some(captures) = if_pattern.captures(&result) {
-        let full_match = captures.get(0).unwrap();
-        let condition = captures.get(1).unwrap().as_str().trim();
-        let content = captures.get(2).unwrap().as_str();
-
-        // Check for else clause
-        let (if_content, else_content) = if let Some(else_pos) = content.find("{{else}}") {
-            (&content[..else_pos], &content[else_pos + 8..])
-        } else {
-            (content, "")
-        };
-
-        // Evaluate condition
-        let is_true = evaluate_condition(condition, vars);
-
-        let replacement = if is_true {
-            if_content.to_string()
-        } else {
-            else_content.to_string()
-        };
+/// Describes a syntax error in a cell's text by its location.
+fn syntax_error(sheet: &str, cell: &CellRef, text: &str, err: &SyntaxError) -> anyhow::Error {
+    anyhow::anyhow!(
+        "{}!{}: {} (character {})",
+        sheet,
+        cell.to_a1(),
+        err,
+        text[..err.offset].chars().count() + 1
+    )
+}
 
-        result = result.replace(full_match.as_str(), &replacement);
+/// Renders parsed nodes. `{{#if}}` and `{{#unless}}` render one branch; a
+/// `{{#row-repeat}}` nested in a repeated row renders its body once per
+/// item, side by side.
+fn render(nodes: &[Node], vars: &TemplateVars, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Placeholder { raw, expr } => out.push_str(&render_placeholder(raw, expr, vars)),
+            Node::Block {
+                block: Block::RowRepeat,
+                arg,
+                body,
+                ..
+            } => {
+                let items = vars.get_array(arg).unwrap_or_default();
+                let mut items_out = String::new();
+                for (idx, item) in items.iter().enumerate() {
+                    render(
+                        body,
+                        &item_vars(vars, item, idx, items.len()),
+                        &mut items_out,
+                    );
+                }
+                out.extend(items_out.chars().filter(|&c| c != CELL_BREAK));
+                keep_cell_breaks(body, out);
+            }
+            Node::Block {
+                block,
+                arg,
+                body,
+                otherwise,
+            } => {
+                if evaluate_condition(arg, vars) != (*block == Block::Unless) {
+                    render(body, vars, out);
+                    keep_cell_breaks(otherwise, out);
+                } else {
+                    keep_cell_breaks(body, out);
+                    render(otherwise, vars, out);
+                }
+            }
+        }
     }
+}
 
-    // Also handle {{#unless condition}}...{{/unless}}
-    let unless_pattern =
-        regex_lite::Regex::new(r"\{\{#unless\s+([^}]+)\}\}([\s\S]*?)\{\{/unless\}\}").unwrap();
-
-    while let Some(captures) = unless_pattern.captures(&result) {
-        let full_match = captures.get(0).unwrap();
-        let condition = captures.get(1).unwrap().as_str().trim();
-        let content = captures.get(2).unwrap().as_str();
-
-        let is_true = evaluate_condition(condition, vars);
-        let replacement = if !is_true { content } else { "" };
-
-        result = result.replace(full_match.as_str(), replacement);
+/// Writes the cell breaks of nodes that aren't rendered, so a row's
+/// cells still line up.
+fn keep_cell_breaks(nodes: &[Node], out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.extend(text.chars().filter(|&c| c == CELL_BREAK)),
+            Node::Placeholder { .. } => {}
+            Node::Block {
+                body, otherwise, ..
+            } => {
+                keep_cell_breaks(body, out);
+                keep_cell_breaks(otherwise, out);
+            }
+        }
     }
+}
 
-    result
+/// Variables for one item of a repeated array: its fields over the outer
+/// variables, the item itself as `this`, and its position as `_index`
+/// (from 1), `_index0`, `_first` and `_last`.
+fn item_vars(
+    vars: &TemplateVars,
+    item: &serde_json::Value,
+    idx: usize,
+    len: usize,
+) -> TemplateVars {
+    let mut item_vars = vars.clone();
+    item_vars.merge_object(item);
+    item_vars.set("this", &value_to_string(item));
+    item_vars.set("_index", &(idx + 1).to_string());
+    item_vars.set("_index0", &idx.to_string());
+    item_vars.set("_first", &(idx == 0).to_string());
+    item_vars.set("_last", &(idx + 1 == len).to_string());
+    item_vars
 }
 
 /// Evaluate a condition expression.
@@ -1104,232 +1389,380 @@ fn resolve_number(expr: &str, vars: &TemplateVars) -> Option<f64> {
     value.parse().ok().or_else(|| expr.parse().ok())
 }
 
-/// Process placeholders with optional filters: {{name}}, {{name|upper}}, {{amount|currency}}
-fn process_placeholders_with_filters(s: &str, vars: &TemplateVars) -> String {
-    let placeholder_pattern = regex_lite::Regex::new(r"\{\{([^#/][^}]*)\}\}").unwrap();
+/// Renders a placeholder with optional filters: `{{name}}`,
+/// `{{name|upper}}`, `{{amount|currency}}`. An unknown name leaves the tag
+/// as written.
+fn render_placeholder(raw: &str, expr: &str, vars: &TemplateVars) -> String {
+    let mut parts = expr.split('|');
+    let var_name = parts.next().unwrap_or_default().trim();
 
-    let mut result = s.to_string();
+    // Get the value: an aggregate such as `sum items.amount`, or a variable
+    let mut value = aggregate_helper(var_name, vars)
+        .or_else(|| vars.get(var_name))
+        .unwrap_or_else(|| raw.to_string());
 
-    // Find all placeholders and process them
-    for captures in placeholder_pattern.captures_iter(s) {
-        let full_match = captures.get(0).unwrap().as_str();
-        let inner = captures.get(1).unwrap().as_str().trim();
+    // Apply filters
+    for filter in parts {
+        value = apply_filter(&value, filter.trim());
+    }
+    value
+}
 
-        // Parse variable name and filters
-        let parts: Vec<&str> = inner.split('|').collect();
-        let var_name = parts[0].trim();
-        let filters: Vec<&str> = parts[1..].iter().map(|s| s.trim()).collect();
+/// Splits an aggregate helper such as `sum items.amount` into its function
+/// and path.
+fn split_helper(expr: &str) -> Option<(AggFunc, &str)> {
+    let (name, path) = expr.split_once(char::is_whitespace)?;
+    let path = path.trim();
+    if path.is_empty() || path.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((AggFunc::from_name(name)?, path))
+}
 
-        // Get the value
-        let mut value = vars.get(var_name).unwrap_or_else(|| full_match.to_string());
+/// Evaluates an aggregate over an array: `sum items.amount`,
+/// `count items`, `avg items.score`, `min`/`max items.price`. The path is
+/// an array, then optionally a field of its items; numbers given as text
+/// count as numbers.
+fn aggregate_helper(expr: &str, vars: &TemplateVars) -> Option<String> {
+    let (func, path) = split_helper(expr)?;
+    // The longest prefix of the path that is an array; the rest is a field
+    let (items, field) = std::iter::once((path, None))
+        .chain(
+            path.match_indices('.')
+                .rev()
+                .map(|(i, _)| (&path[..i], Some(&path[i + 1..]))),
+        )
+        .find_map(|(array, field)| Some((vars.get_array(array)?, field)))?;
+
+    let column = field.map(|_| 1);
+    let mut group = GroupBy::new(Vec::new(), vec![Aggregation::new(func, column, "")]);
+    for item in items {
+        let value = match field {
+            Some(field) => TemplateVars::from_json(item).get(field).unwrap_or_default(),
+            None => value_to_string(&item),
+        };
+        let cell = match value.trim().parse::<f64>() {
+            Ok(n) => CellValue::Number(n),
+            Err(_) if value.is_empty() => CellValue::Empty,
+            Err(_) => CellValue::String(value),
+        };
+        group.write_row(&[cell]).ok()?;
+    }
+    let result = group.finish().into_iter().next()?.into_iter().next()?;
+    Some(match result {
+        // No float noise such as 0.30000000000000004
+        CellValue::Number(n) => round(n, 10, RoundingMode::HalfUp).to_string(),
+        other => other.to_display_string(),
+    })
+}
 
-        // Apply filters
-        for filter in filters {
-            value = apply_filter(&value, filter);
-        }
+/// Apply a filter to a value with the built-in filters.
+fn apply_filter(value: &str, filter: &str) -> String {
+    static FILTERS: std::sync::OnceLock<Filters> = std::sync::OnceLock::new();
+    FILTERS.get_or_init(Filters::default).apply(value, filter)
+}
 
-        result = result.replace(full_match, &value);
-    }
+/// A conditional marker that isn't paired within its own cell, and so
+/// applies to whole rows.
+#[derive(Debug)]
+enum RowMarker {
+    Open { unless: bool, condition: String },
+    Else,
+    Close { unless: bool },
+}
 
-    result
+/// Returns the rows of a sheet that hold a `{{#row-repeat}}`.
+fn repeat_rows(sheet: &xlex_core::Sheet) -> std::collections::BTreeSet<u32> {
+    sheet
+        .cells()
+        .filter(|cell| match &cell.value {
+            CellValue::String(s) => template_syntax::tokenize(s).is_ok_and(|tokens| {
+                tokens
+                    .iter()
+                    .any(|t| matches!(t.kind, TokenKind::Open(Block::RowRepeat, _)))
+            }),
+            _ => false,
+        })
+        .map(|cell| cell.reference.row)
+        .collect()
 }
 
-/// Apply a filter to a value.
-fn apply_filter(value: &str, filter: &str) -> String {
-    // Parse filter name and optional argument: filter or filter:arg
-    let (filter_name, filter_arg) = if let Some(pos) = filter.find(':') {
-        (&filter[..pos], Some(&filter[pos + 1..]))
-    } else {
-        (filter, None)
+/// Process row-level conditionals in a sheet: a `{{#if condition}}` that
+/// is closed by a `{{/if}}` in another cell, in the same row or further
+/// down, keeps or removes every row from the `{{#if}}` to the `{{/if}}`.
+/// An `{{else}}` row splits the rows into the two branches, and
+/// `{{#unless}}` works the same way. Rows that hold nothing but markers are
+/// removed as well, and the rows below move up. Returns the number of rows
+/// removed.
+fn process_row_conditionals(
+    workbook: &mut Workbook,
+    sheet_name: &str,
+    vars: &TemplateVars,
+) -> Result<u32> {
+    let Some(sheet) = workbook.get_sheet(sheet_name) else {
+        return Ok(0);
     };
-
-    match filter_name {
-        // String filters
-        "upper" | "uppercase" => value.to_uppercase(),
-        "lower" | "lowercase" => value.to_lowercase(),
-        "capitalize" | "title" => {
-            let mut chars = value.chars();
-            match chars.next() {
-                None => String::new(),
-                Some(c) => c.to_uppercase().collect::<String>() + chars.as_str(),
-            }
-        }
-        "trim" => value.trim().to_string(),
-        "default" => {
-            if value.is_empty() {
-                filter_arg.unwrap_or("").to_string()
-            } else {
-                value.to_string()
-            }
+    // Blocks in a repeated row are rendered per item instead
+    let repeat_rows = repeat_rows(sheet);
+    let mut markers: Vec<(CellRef, std::ops::Range<usize>, RowMarker)> = Vec::new();
+    for cell in sheet.cells() {
+        let CellValue::String(s) = &cell.value else {
+            continue;
+        };
+        if repeat_rows.contains(&cell.reference.row) {
+            continue;
         }
-        "truncate" => {
-            let len: usize = filter_arg.and_then(|a| a.parse().ok()).unwrap_or(50);
-            if value.chars().count() > len {
-                format!("{}...", value.chars().take(len).collect::<String>())
-            } else {
-                value.to_string()
-            }
+        let tokens = template_syntax::unpaired_markers(s)
+            .map_err(|err| syntax_error(sheet_name, &cell.reference, s, &err))?;
+        for token in tokens {
+            let marker = match token.kind {
+                TokenKind::Open(Block::If, condition) => RowMarker::Open {
+                    unless: false,
+                    condition: condition.to_string(),
+                },
+                TokenKind::Open(Block::Unless, condition) => RowMarker::Open {
+                    unless: true,
+                    condition: condition.to_string(),
+                },
+                TokenKind::Else => RowMarker::Else,
+                TokenKind::Close(Block::If) => RowMarker::Close { unless: false },
+                TokenKind::Close(Block::Unless) => RowMarker::Close { unless: true },
+                // Row repeats are handled by `process_row_repeats`
+                _ => continue,
+            };
+            markers.push((cell.reference.clone(), token.span, marker));
         }
-        "replace" => {
-            // replace:old:new
-            if let Some(arg) = filter_arg {
-                let parts: Vec<&str> = arg.splitn(2, ':').collect();
-                if parts.len() == 2 {
-                    value.replace(parts[0], parts[1])
-                } else {
-                    value.to_string()
-                }
-            } else {
-                value.to_string()
+    }
+    if markers.is_empty() {
+        return Ok(0);
+    }
+    markers.sort_by_key(|(cell_ref, span, _)| (cell_ref.row, cell_ref.col, span.start));
+
+    // Pair the markers into blocks and collect the rows of false branches
+    struct OpenBlock<'a> {
+        cell: &'a CellRef,
+        unless: bool,
+        keep: bool,
+        else_row: Option<u32>,
+    }
+    let mut stack: Vec<OpenBlock> = Vec::new();
+    let mut removed = std::collections::BTreeSet::new();
+    for (cell_ref, _, marker) in &markers {
+        match marker {
+            RowMarker::Open { unless, condition } => stack.push(OpenBlock {
+                cell: cell_ref,
+                unless: *unless,
+                keep: evaluate_condition(condition, vars) != *unless,
+                else_row: None,
+            }),
+            RowMarker::Else => match stack.last_mut() {
+                Some(block) if block.else_row.is_none() => block.else_row = Some(cell_ref.row),
+                _ => anyhow::bail!(
+                    "{{{{else}}}} in {}!{} has no {{{{#if}}}} to belong to",
+                    sheet_name,
+                    cell_ref.to_a1()
+                ),
+            },
+            RowMarker::Close { unless } => {
+                let block = match stack.pop() {
+                    Some(block) if block.unless == *unless => block,
+                    _ => anyhow::bail!(
+                        "{{{{/{}}}}} in {}!{} has no matching {{{{#{}}}}}",
+                        if *unless { "unless" } else { "if" },
+                        sheet_name,
+                        cell_ref.to_a1(),
+                        if *unless { "unless" } else { "if" }
+                    ),
+                };
+                let (open, close) = (block.cell.row, cell_ref.row);
+                let dropped = match (block.else_row, block.keep) {
+                    (None, true) => 0..0,
+                    (None, false) => open..close + 1,
+                    (Some(else_row), true) => else_row..close + 1,
+                    (Some(else_row), false) => open..else_row,
+                };
+                removed.extend(dropped);
             }
         }
+    }
+    if let Some(block) = stack.pop() {
+        anyhow::bail!(
+            "{{{{#{}}}}} in {}!{} is never closed",
+            if block.unless { "unless" } else { "if" },
+            sheet_name,
+            block.cell.to_a1()
+        );
+    }
 
-        // Number filters
-        "currency" => {
-            if let Ok(num) = value.parse::<f64>() {
-                let symbol = filter_arg.unwrap_or("$");
-                format!("{}{:.2}", symbol, num)
-            } else {
-                value.to_string()
-            }
-        }
-        "number" | "format_number" => {
-            if let Ok(num) = value.parse::<f64>() {
-                let decimals: usize = filter_arg.and_then(|a| a.parse().ok()).unwrap_or(2);
-                format!("{:.prec$}", num, prec = decimals)
-            } else {
-                value.to_string()
-            }
+    // Strip the markers from the rows that stay
+    let mut marker_rows = std::collections::BTreeSet::new();
+    let mut by_cell: std::collections::BTreeMap<(u32, u32), Vec<std::ops::Range<usize>>> =
+        std::collections::BTreeMap::new();
+    for (cell_ref, span, _) in markers {
+        if !removed.contains(&cell_ref.row) {
+            marker_rows.insert(cell_ref.row);
+            by_cell
+                .entry((cell_ref.row, cell_ref.col))
+                .or_default()
+                .push(span);
         }
-        "percent" => {
-            if let Ok(num) = value.parse::<f64>() {
-                format!("{:.1}%", num * 100.0)
-            } else {
-                value.to_string()
-            }
-        }
-        "abs" => {
-            if let Ok(num) = value.parse::<f64>() {
-                num.abs().to_string()
-            } else {
-                value.to_string()
+    }
+    workbook.with_sheet_mut(sheet_name, |sheet| {
+        for ((row, col), spans) in by_cell {
+            let Some(cell) = sheet.get_cell_mut(&CellRef::new(col, row)) else {
+                continue;
+            };
+            if let CellValue::String(s) = &cell.value {
+                let mut text = s.clone();
+                for span in spans.into_iter().rev() {
+                    text.replace_range(span, "");
+                }
+                cell.value = if text.trim().is_empty() {
+                    CellValue::Empty
+                } else {
+                    CellValue::String(text)
+                };
             }
         }
-        "round" => {
-            if let Ok(num) = value.parse::<f64>() {
-                let decimals: i32 = filter_arg.and_then(|a| a.parse().ok()).unwrap_or(0);
-                let factor = 10_f64.powi(decimals);
-                ((num * factor).round() / factor).to_string()
-            } else {
-                value.to_string()
+        // Rows left with only markers go too
+        for row in marker_rows {
+            if sheet
+                .cells()
+                .all(|cell| cell.reference.row != row || cell.value.is_empty())
+            {
+                removed.insert(row);
             }
         }
-
-        // Date filters
-        "date" => {
-            // Try to parse and format date
-            let format = filter_arg.unwrap_or("%Y-%m-%d");
-            if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
-                date.format(format).to_string()
-            } else if let Ok(datetime) = chrono::DateTime::parse_from_rfc3339(value) {
-                datetime.format(format).to_string()
-            } else {
-                value.to_string()
-            }
+        for &row in removed.iter().rev() {
+            sheet.delete_rows(row, 1);
         }
-        "now" => {
-            let format = filter_arg.unwrap_or("%Y-%m-%d");
-            chrono::Local::now().format(format).to_string()
+    })?;
+
+    // Formulas pointing at or below the removed rows follow them up, on this
+    // sheet and on any sheet referring to it
+    if !removed.is_empty() {
+        let names: Vec<String> = workbook
+            .sheet_names()
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        for name in names {
+            let local = name == sheet_name;
+            workbook.with_sheet_mut(&name, |sheet| {
+                for cell_ref in sheet.formula_cells() {
+                    if let Some(CellValue::Formula { formula, .. }) =
+                        sheet.get_cell_mut(&cell_ref).map(|cell| &mut cell.value)
+                    {
+                        *formula =
+                            xlex_core::formula::remove_rows(
+                                formula,
+                                &removed,
+                                |sheet| match sheet {
+                                    Some(sheet) => sheet.eq_ignore_ascii_case(sheet_name),
+                                    None => local,
+                                },
+                            );
+                    }
+                }
+            })?;
         }
-
-        // Unknown filter - return value unchanged
-        _ => value.to_string(),
     }
+    Ok(removed.len() as u32)
 }
 
-/// Process row-repeat markers in a sheet.
-/// Format: {{#row-repeat items}}...{{/row-repeat}}
+/// Process row-repeat markers in a sheet: a row with
+/// `{{#row-repeat items}}` is written once per item of the array, from that
+/// row down. The row's cells are rendered as one text, so a block opened
+/// in one cell may close in a later one, and further `{{#row-repeat}}`
+/// blocks in the row repeat inline.
 fn process_row_repeats(
     workbook: &mut Workbook,
     sheet_name: &str,
     vars: &TemplateVars,
 ) -> Result<()> {
-    // Find cells with row-repeat markers
-    let repeat_markers: Vec<(u32, String, String)> =
-        if let Some(sheet) = workbook.get_sheet(sheet_name) {
-            sheet
-                .cells()
-                .filter_map(|cell| {
-                    if let CellValue::String(s) = &cell.value {
-                        // Look for {{#row-repeat array_name}}
-                        if let Some(start) = s.find("{{#row-repeat") {
-                            if let Some(end) = s[start..].find("}}") {
-                                let marker = &s[start..start + end + 2];
-                                // Extract array name
-                                let inner = &marker[13..marker.len() - 2].trim();
-                                return Some((cell.reference.row, inner.to_string(), s.clone()));
-                            }
-                        }
-                    }
-                    None
-                })
-                .collect()
-        } else {
-            return Ok(());
-        };
-
-    // Process each row-repeat marker
-    for (row, array_name, _original) in repeat_markers.iter().rev() {
-        // Get the array data
-        if let Some(array) = vars.get_array(array_name) {
-            if array.is_empty() {
-                continue;
+    let Some(sheet) = workbook.get_sheet(sheet_name) else {
+        return Ok(());
+    };
+    let rows = repeat_rows(sheet);
+    let mut row_cells: std::collections::BTreeMap<u32, Vec<(u32, String)>> =
+        std::collections::BTreeMap::new();
+    for cell in sheet.cells() {
+        if let CellValue::String(s) = &cell.value {
+            if rows.contains(&cell.reference.row) {
+                row_cells
+                    .entry(cell.reference.row)
+                    .or_default()
+                    .push((cell.reference.col, s.clone()));
             }
+        }
+    }
 
-            // Get the template row cells
-            let template_cells: Vec<(u32, String)> =
-                if let Some(sheet) = workbook.get_sheet(sheet_name) {
-                    sheet
-                        .cells()
-                        .filter(|c| c.reference.row == *row)
-                        .filter_map(|c| {
-                            if let CellValue::String(s) = &c.value {
-                                Some((c.reference.col, s.clone()))
-                            } else {
-                                None
-                            }
-                        })
-                        .collect()
-                } else {
-                    continue;
-                };
-
-            // Generate rows for each array item
-            for (idx, item) in array.iter().enumerate() {
-                let target_row = *row + idx as u32;
-
-                // Create vars for this item
-                let mut item_vars = vars.clone();
-                item_vars.merge_object(item);
-                item_vars.set("_index", &(idx + 1).to_string());
-                item_vars.set("_index0", &idx.to_string());
-                item_vars.set("_first", &(idx == 0).to_string());
-                item_vars.set("_last", &(idx == array.len() - 1).to_string());
-
-                for (col, template) in &template_cells {
-                    // Remove the row-repeat markers from template
-                    let clean_template = template
-                        .replace(&format!("{{{{#row-repeat {}}}}}", array_name), "")
-                        .replace("{{/row-repeat}}", "");
-
-                    let processed = process_template_string(&clean_template, &item_vars);
+    for (row, mut cells) in row_cells.into_iter().rev() {
+        cells.sort_by_key(|(col, _)| *col);
+        let joined = cells
+            .iter()
+            .map(|(_, s)| s.as_str())
+            .collect::<Vec<_>>()
+            .join(&CELL_BREAK.to_string());
+        let located = |text: &str, err: SyntaxError| {
+            // The cell the error is in, and the offset within it
+            let before = &text[..err.offset];
+            let index = before.matches(CELL_BREAK).count().min(cells.len() - 1);
+            let start = before
+                .rfind(CELL_BREAK)
+                .map_or(0, |i| i + CELL_BREAK.len_utf8());
+            let (col, cell_text) = &cells[index];
+            let err = SyntaxError {
+                offset: (err.offset - start).min(cell_text.len()),
+                message: err.message,
+            };
+            syntax_error(sheet_name, &CellRef::new(*col, row), cell_text, &err)
+        };
+        let tokens = template_syntax::tokenize(&joined).map_err(|err| located(&joined, err))?;
+        template_syntax::parse(&joined).map_err(|err| located(&joined, err))?;
+
+        // The first `{{#row-repeat}}` drives the row: drop it and its closer
+        let Some(open) = tokens
+            .iter()
+            .position(|t| matches!(t.kind, TokenKind::Open(Block::RowRepeat, _)))
+        else {
+            continue;
+        };
+        let TokenKind::Open(_, array_name) = tokens[open].kind else {
+            continue;
+        };
+        let mut depth = 0;
+        let close = tokens[open + 1..].iter().position(|t| match t.kind {
+            TokenKind::Open(Block::RowRepeat, _) => {
+                depth += 1;
+                false
+            }
+            TokenKind::Close(Block::RowRepeat) if depth == 0 => true,
+            TokenKind::Close(Block::RowRepeat) => {
+                depth -= 1;
+                false
+            }
+            _ => false,
+        });
+        let mut template = joined.clone();
+        if let Some(close) = close {
+            template.replace_range(tokens[open + 1 + close].span.clone(), "");
+        }
+        template.replace_range(tokens[open].span.clone(), "");
+        let nodes = template_syntax::parse(&template).map_err(|err| located(&template, err))?;
 
-                    workbook.set_cell(
-                        sheet_name,
-                        CellRef::new(*col, target_row),
-                        CellValue::String(processed),
-                    )?;
-                }
+        let Some(items) = vars.get_array(array_name) else {
+            continue;
+        };
+        for (idx, item) in items.iter().enumerate() {
+            let mut out = String::new();
+            render(&nodes, &item_vars(vars, item, idx, items.len()), &mut out);
+            for ((col, _), value) in cells.iter().zip(out.split(CELL_BREAK)) {
+                workbook.set_cell(
+                    sheet_name,
+                    CellRef::new(*col, row + idx as u32),
+                    CellValue::String(value.to_string()),
+                )?;
             }
         }
     }
@@ -1340,12 +1773,18 @@ fn process_row_repeats(
 /// Find placeholders in a string (simple {{name}} format only).
 fn find_placeholders(s: &str) -> Vec<String> {
     let mut result = Vec::new();
-    let pattern = regex_lite::Regex::new(r"\{\{([^#/}][^}]*)\}\}").unwrap();
+    let tokens = template_syntax::tokenize(s).unwrap_or_default();
 
-    for captures in pattern.captures_iter(s) {
-        let inner = captures.get(1).unwrap().as_str().trim();
+    for token in tokens {
+        let TokenKind::Placeholder(inner) = token.kind else {
+            continue;
+        };
         // Extract just the variable name (without filters)
-        let var_name = inner.split('|').next().unwrap_or(inner).trim();
+        let mut var_name = inner.split('|').next().unwrap_or(inner).trim();
+        // An aggregate needs the array it reads
+        if let Some((_, path)) = split_helper(var_name) {
+            var_name = path.split('.').next().unwrap_or(path);
+        }
         if !result.contains(&var_name.to_string()) {
             result.push(var_name.to_string());
         }
@@ -1370,6 +1809,11 @@ mod tests {
             color: false,
             json_errors: false,
             output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
         }
     }
 
@@ -1521,6 +1965,256 @@ mod tests {
         assert_eq!(result, vec!["name"]);
     }
 
+    #[test]
+    fn test_find_placeholders_aggregates() {
+        let result = find_placeholders("{{sum items.amount|currency}} {{count items}} {{total}}");
+        assert_eq!(result, vec!["items", "total"]);
+    }
+
+    #[test]
+    fn test_aggregate_helpers() {
+        let vars = TemplateVars::from_json(serde_json::json!({
+            "items": [
+                {"amount": 0.1, "score": "8"},
+                {"amount": 0.2, "score": "n/a"},
+                {"amount": 12, "score": 10},
+            ],
+            "order": {"lines": [{"qty": 2}, {"qty": 3}]},
+            "tags": ["a", "b"],
+            "empty": [],
+        }));
+        let render = |s: &str| process_template_string(s, &vars).unwrap();
+        assert_eq!(render("{{sum items.amount}}"), "12.3");
+        assert_eq!(render("{{sum items.amount|currency}}"), "$12.30");
+        assert_eq!(render("{{count items}} {{count tags}}"), "3 2");
+        assert_eq!(render("{{count items.score}}"), "3");
+        assert_eq!(render("{{avg items.score}}"), "9");
+        assert_eq!(
+            render("{{min items.amount}}-{{max items.amount}}"),
+            "0.1-12"
+        );
+        assert_eq!(render("{{sum order.lines.qty}}"), "5");
+        assert_eq!(render("{{sum empty.x}} {{avg empty.x}}"), "0 #DIV/0!");
+        // Not an array, or not a helper: left alone
+        assert_eq!(render("{{sum missing.x}}"), "{{sum missing.x}}");
+        assert_eq!(render("{{median items.amount}}"), "{{median items.amount}}");
+    }
+
+    #[test]
+    fn test_apply_single_aggregates_in_repeated_rows() {
+        let dir = TempDir::new().unwrap();
+        let template = create_template_workbook(
+            &dir,
+            "orders.xlsx",
+            &[
+                ("A1", "{{#row-repeat orders}}{{id}}"),
+                ("B1", "{{sum lines.qty}}{{/row-repeat}}"),
+                ("A5", "Total"),
+                ("B5", "{{sum orders.total}}"),
+            ],
+        );
+        let output = dir.path().join("out.xlsx");
+        let vars = TemplateVars::from_json(serde_json::json!({
+            "orders": [
+                {"id": "A", "total": 10, "lines": [{"qty": 1}, {"qty": 2}]},
+                {"id": "B", "total": 5.5, "lines": [{"qty": 4}]},
+            ],
+        }));
+        apply_single(&template, &output, &vars, &default_global()).unwrap();
+        let wb = Workbook::open(&output).unwrap();
+        let value = |cell: &str| {
+            wb.get_cell("Sheet1", &CellRef::parse(cell).unwrap())
+                .unwrap()
+                .to_display_string()
+        };
+        assert_eq!(
+            [
+                value("A1"),
+                value("B1"),
+                value("A2"),
+                value("B2"),
+                value("B5")
+            ],
+            ["A", "3", "B", "4", "15.5"]
+        );
+    }
+
+    #[test]
+    fn test_nested_conditionals() {
+        let template = "{{#if a}}A{{#if b}}B{{else}}-{{/if}}{{/if}}{{#unless a}}none{{/unless}}";
+        for (a, b, expected) in [
+            (true, true, "AB"),
+            (true, false, "A-"),
+            (false, true, "none"),
+        ] {
+            let vars = TemplateVars::from_json(serde_json::json!({"a": a, "b": b}));
+            assert_eq!(process_template_string(template, &vars).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_apply_single_reports_syntax_errors_by_cell() {
+        let dir = TempDir::new().unwrap();
+        let template = create_template_workbook(
+            &dir,
+            "bad.xlsx",
+            &[("A1", "ok"), ("B2", "ab{{#if x}}{{/unless}}")],
+        );
+        let output = dir.path().join("out.xlsx");
+        let err =
+            apply_single(&template, &output, &TemplateVars::new(), &default_global()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Sheet1!B2: {{/unless}} closes {{#if x}} (character 12)"
+        );
+
+        let template = create_template_workbook(&dir, "unknown.xlsx", &[("C3", "{{#each items}}")]);
+        let err =
+            apply_single(&template, &output, &TemplateVars::new(), &default_global()).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Sheet1!C3: unknown block {{#each}}"));
+    }
+
+    #[test]
+    fn test_apply_single_blocks_in_repeated_rows() {
+        let dir = TempDir::new().unwrap();
+        let template = create_template_workbook(
+            &dir,
+            "orders.xlsx",
+            &[
+                ("A1", "{{#row-repeat orders}}{{id}}"),
+                ("B1", "{{#if paid}}paid"),
+                ("C1", "{{total}}{{/if}}"),
+                (
+                    "D1",
+                    "{{#row-repeat tags}}{{this}};{{/row-repeat}}{{/row-repeat}}",
+                ),
+            ],
+        );
+        let output = dir.path().join("out.xlsx");
+        let vars = TemplateVars::from_json(serde_json::json!({
+            "paid": true,
+            "orders": [
+                {"id": "A", "paid": true, "total": 10, "tags": ["x", "y"]},
+                {"id": "B", "paid": false, "total": 5, "tags": []},
+            ],
+        }));
+        apply_single(&template, &output, &vars, &default_global()).unwrap();
+        let wb = Workbook::open(&output).unwrap();
+        let row = |row: u32| -> Vec<String> {
+            (1..=4)
+                .map(|col| {
+                    wb.get_cell("Sheet1", &CellRef::new(col, row))
+                        .map(|v| v.to_display_string())
+                        .unwrap_or_default()
+                })
+                .collect()
+        };
+        assert_eq!(row(1), ["A", "paid", "10", "x;y;"]);
+        assert_eq!(row(2), ["B", "", "", ""]);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn fuzz_process_template_string(s in proptest::prelude::any::<String>()) {
+            let vars = TemplateVars::from_json(serde_json::json!({"a": true, "items": [1, 2]}));
+            let _ = process_template_string(&s, &vars);
+        }
+    }
+
+    /// A template with `cells` on Sheet1 and a hidden `__vars` sheet.
+    fn create_template_with_defaults(
+        dir: &TempDir,
+        cells: &[(&str, &str)],
+        defaults: &[[CellValue; 3]],
+    ) -> std::path::PathBuf {
+        let path = create_template_workbook(dir, "defaults.xlsx", cells);
+        let mut wb = Workbook::open(&path).unwrap();
+        wb.add_sheet(VARS_SHEET).unwrap();
+        wb.set_sheet_visibility(VARS_SHEET, xlex_core::sheet::SheetVisibility::Hidden)
+            .unwrap();
+        let header = ["name", "value", "type"].map(|h| CellValue::String(h.to_string()));
+        for (row, values) in std::iter::once(&header).chain(defaults).enumerate() {
+            for (col, value) in values.iter().enumerate() {
+                wb.set_cell(
+                    VARS_SHEET,
+                    CellRef::new(col as u32 + 1, row as u32 + 1),
+                    value.clone(),
+                )
+                .unwrap();
+            }
+        }
+        wb.save_as(&path).unwrap();
+        path
+    }
+
+    fn text(s: &str) -> CellValue {
+        CellValue::String(s.to_string())
+    }
+
+    #[test]
+    fn test_apply_single_uses_vars_sheet_defaults() {
+        let dir = TempDir::new().unwrap();
+        let template = create_template_with_defaults(
+            &dir,
+            &[(
+                "A1",
+                "{{company.name}} {{rate|percent}} {{title}} {{#if vip}}VIP{{/if}} {{tags.1}}",
+            )],
+            &[
+                [text("company.name"), text("Acme"), CellValue::Empty],
+                [text("rate"), CellValue::Number(0.2), CellValue::Empty],
+                [text("title"), text("Default"), text("string")],
+                [text("vip"), text("yes"), text("bool")],
+                [text("tags"), text(r#"["a", "b"]"#), text("json")],
+            ],
+        );
+        let output = dir.path().join("out.xlsx");
+        let vars = TemplateVars::from_json(serde_json::json!({
+            "title": "Custom",
+            "company": {"city": "Oslo"},
+        }));
+        apply_single(&template, &output, &vars, &default_global()).unwrap();
+
+        let wb = Workbook::open(&output).unwrap();
+        assert_eq!(
+            wb.get_cell("Sheet1", &CellRef::parse("A1").unwrap())
+                .unwrap(),
+            text("Acme 20.0% Custom VIP b")
+        );
+        assert_eq!(wb.sheet_names(), ["Sheet1"]);
+    }
+
+    #[test]
+    fn test_template_defaults_errors() {
+        let dir = TempDir::new().unwrap();
+        let bad = |row: [CellValue; 3]| {
+            let template = create_template_with_defaults(&dir, &[("A1", "{{x}}")], &[row]);
+            let wb = Workbook::open(&template).unwrap();
+            template_defaults(&wb).unwrap_err().to_string()
+        };
+        assert_eq!(
+            bad([text("x"), text("1"), text("date")]),
+            "__vars!C2: unknown type 'date' (string, number, bool or json)"
+        );
+        assert_eq!(
+            bad([text("x"), text("lots"), text("number")]),
+            "__vars!B2: 'lots' is not a number"
+        );
+        assert!(bad([text("x"), text("{"), text("json")]).starts_with("__vars!B2: invalid JSON"));
+    }
+
+    #[test]
+    fn test_merge_defaults() {
+        let mut vars = TemplateVars::from_json(serde_json::json!({"a": 1, "o": {"x": 1}}));
+        vars.merge_defaults(&serde_json::json!({"a": 2, "b": 3, "o": {"x": 2, "y": 2}}));
+        assert_eq!(vars.get("a").as_deref(), Some("1"));
+        assert_eq!(vars.get("b").as_deref(), Some("3"));
+        assert_eq!(vars.get("o.x").as_deref(), Some("1"));
+        assert_eq!(vars.get("o.y").as_deref(), Some("2"));
+    }
+
     #[test]
     fn test_find_placeholders_ignores_conditionals() {
         let result = find_placeholders("{{#if show}}content{{/if}}");
@@ -1655,7 +2349,7 @@ mod tests {
         let mut vars = TemplateVars::new();
         vars.set("name", "Alice");
 
-        let result = process_template_string("Hello {{name}}!", &vars);
+        let result = process_template_string("Hello {{name}}!", &vars).unwrap();
         assert_eq!(result, "Hello Alice!");
     }
 
@@ -1664,7 +2358,7 @@ mod tests {
         let mut vars = TemplateVars::new();
         vars.set("name", "alice");
 
-        let result = process_template_string("Hello {{name|upper}}!", &vars);
+        let result = process_template_string("Hello {{name|upper}}!", &vars).unwrap();
         assert_eq!(result, "Hello ALICE!");
     }
 
@@ -1676,7 +2370,7 @@ mod tests {
         });
         let vars = TemplateVars::from_json(json);
 
-        let result = process_template_string("{{#if show}}Hello {{name}}{{/if}}", &vars);
+        let result = process_template_string("{{#if show}}Hello {{name}}{{/if}}", &vars).unwrap();
         assert_eq!(result, "Hello Bob");
     }
 
@@ -1688,7 +2382,7 @@ mod tests {
         });
         let vars = TemplateVars::from_json(json);
 
-        let result = process_template_string("{{#if show}}Hello {{name}}{{/if}}", &vars);
+        let result = process_template_string("{{#if show}}Hello {{name}}{{/if}}", &vars).unwrap();
         assert_eq!(result, "");
     }
 
@@ -1700,7 +2394,8 @@ mod tests {
         let vars = TemplateVars::from_json(json);
 
         let result =
-            process_template_string("{{#if premium}}Premium User{{else}}Free User{{/if}}", &vars);
+            process_template_string("{{#if premium}}Premium User{{else}}Free User{{/if}}", &vars)
+                .unwrap();
         assert_eq!(result, "Free User");
     }
 
@@ -1711,7 +2406,7 @@ mod tests {
         });
         let vars = TemplateVars::from_json(json);
 
-        let result = process_template_string("{{#unless hide}}Visible{{/unless}}", &vars);
+        let result = process_template_string("{{#unless hide}}Visible{{/unless}}", &vars).unwrap();
         assert_eq!(result, "Visible");
     }
 
@@ -1885,7 +2580,7 @@ mod tests {
             &[("A1", "{{name}}"), ("A2", "{{email}}")],
         );
 
-        let result = validate(&template, None, true, &default_global());
+        let result = validate(&template, None, true, FailOn::Errors, &default_global());
         assert!(result.is_ok());
     }
 
@@ -1906,7 +2601,13 @@ mod tests {
         )
         .unwrap();
 
-        let result = validate(&template, Some(&vars_path), false, &default_global());
+        let result = validate(
+            &template,
+            Some(&vars_path),
+            false,
+            FailOn::Errors,
+            &default_global(),
+        );
         assert!(result.is_ok());
     }
 
@@ -1919,7 +2620,13 @@ mod tests {
         let vars_path = dir.path().join("vars.yaml");
         std::fs::write(&vars_path, "name: Alice\n").unwrap();
 
-        let result = validate(&template, Some(&vars_path), false, &default_global());
+        let result = validate(
+            &template,
+            Some(&vars_path),
+            false,
+            FailOn::Errors,
+            &default_global(),
+        );
         assert!(result.is_ok());
     }
 
@@ -2146,7 +2853,8 @@ mod tests {
         let json = serde_json::json!({"show": true});
         let vars = TemplateVars::from_json(json);
 
-        let result = process_conditionals("Before {{#if show}}SHOWN{{/if}} After", &vars);
+        let result =
+            process_template_string("Before {{#if show}}SHOWN{{/if}} After", &vars).unwrap();
         assert_eq!(result, "Before SHOWN After");
     }
 
@@ -2155,7 +2863,8 @@ mod tests {
         let json = serde_json::json!({"show": false});
         let vars = TemplateVars::from_json(json);
 
-        let result = process_conditionals("Before {{#if show}}SHOWN{{/if}} After", &vars);
+        let result =
+            process_template_string("Before {{#if show}}SHOWN{{/if}} After", &vars).unwrap();
         assert_eq!(result, "Before  After");
     }
 
@@ -2164,7 +2873,8 @@ mod tests {
         let json = serde_json::json!({"premium": false});
         let vars = TemplateVars::from_json(json);
 
-        let result = process_conditionals("{{#if premium}}Premium{{else}}Basic{{/if}}", &vars);
+        let result =
+            process_template_string("{{#if premium}}Premium{{else}}Basic{{/if}}", &vars).unwrap();
         assert_eq!(result, "Basic");
     }
 
@@ -2173,7 +2883,7 @@ mod tests {
         let json = serde_json::json!({"hide": false});
         let vars = TemplateVars::from_json(json);
 
-        let result = process_conditionals("{{#unless hide}}Visible{{/unless}}", &vars);
+        let result = process_template_string("{{#unless hide}}Visible{{/unless}}", &vars).unwrap();
         assert_eq!(result, "Visible");
     }
 
@@ -2182,7 +2892,7 @@ mod tests {
         let json = serde_json::json!({"hide": true});
         let vars = TemplateVars::from_json(json);
 
-        let result = process_conditionals("{{#unless hide}}Visible{{/unless}}", &vars);
+        let result = process_template_string("{{#unless hide}}Visible{{/unless}}", &vars).unwrap();
         assert_eq!(result, "");
     }
 
@@ -2254,6 +2964,7 @@ mod tests {
                 template,
                 vars: None,
                 schema: false,
+                fail_on: FailOn::Errors,
             },
         };
 
@@ -2320,6 +3031,122 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_apply_single_row_conditionals() {
+        let dir = TempDir::new().unwrap();
+        let template = create_template_workbook(
+            &dir,
+            "invoice.xlsx",
+            &[
+                ("A1", "Subtotal"),
+                ("B1", "{{subtotal}}"),
+                ("A2", "{{#if discount}}Discount"),
+                ("B2", "{{discount}}{{/if}}"),
+                ("A3", "{{#if notes}}"),
+                ("A4", "Notes"),
+                ("A5", "{{notes}}"),
+                ("A6", "{{else}}"),
+                ("A7", "No notes"),
+                ("A8", "{{/if}}"),
+                ("A9", "Total"),
+                ("B9", "{{total}}"),
+            ],
+        );
+        let output = dir.path().join("out.xlsx");
+        let cells = |vars: TemplateVars| -> Vec<String> {
+            apply_single(&template, &output, &vars, &default_global()).unwrap();
+            let wb = Workbook::open(&output).unwrap();
+            (1..=6)
+                .map(|row| {
+                    let value = |col| {
+                        wb.get_cell("Sheet1", &CellRef::new(col, row))
+                            .unwrap()
+                            .to_display_string()
+                    };
+                    format!("{}|{}", value(1), value(2))
+                })
+                .collect()
+        };
+
+        let mut vars = TemplateVars::from_json(serde_json::json!({
+            "subtotal": 100, "discount": 0, "notes": "", "total": 100
+        }));
+        assert_eq!(
+            cells(vars.clone()),
+            ["Subtotal|100", "No notes|", "Total|100", "|", "|", "|"]
+        );
+
+        vars.set("discount", "10");
+        vars.set("notes", "Thanks");
+        assert_eq!(
+            cells(vars),
+            [
+                "Subtotal|100",
+                "Discount|10",
+                "Notes|",
+                "Thanks|",
+                "Total|100",
+                "|"
+            ]
+        );
+
+        let unclosed = create_template_workbook(&dir, "bad.xlsx", &[("A1", "{{#if x}}")]);
+        let err =
+            apply_single(&unclosed, &output, &TemplateVars::new(), &default_global()).unwrap_err();
+        assert!(err.to_string().contains("never closed"));
+    }
+
+    #[test]
+    fn test_apply_single_row_conditionals_shift_formulas() {
+        let dir = TempDir::new().unwrap();
+        let template = dir.path().join("invoice.xlsx");
+        let mut wb = Workbook::new();
+        wb.add_sheet("Summary").unwrap();
+        for (cell_ref, value) in [
+            ("A1", "Subtotal"),
+            ("A2", "{{#if discount}}Discount"),
+            ("B2", "{{discount}}{{/if}}"),
+            ("A3", "Total"),
+        ] {
+            wb.set_cell(
+                "Sheet1",
+                CellRef::parse(cell_ref).unwrap(),
+                CellValue::string(value),
+            )
+            .unwrap();
+        }
+        wb.set_cell(
+            "Sheet1",
+            CellRef::parse("B1").unwrap(),
+            CellValue::Number(100.0),
+        )
+        .unwrap();
+        wb.set_cell(
+            "Sheet1",
+            CellRef::parse("B3").unwrap(),
+            CellValue::formula("SUM(B1:B2)"),
+        )
+        .unwrap();
+        wb.set_cell(
+            "Summary",
+            CellRef::parse("A1").unwrap(),
+            CellValue::formula("Sheet1!B3+B3"),
+        )
+        .unwrap();
+        wb.save_as(&template).unwrap();
+
+        let output = dir.path().join("out.xlsx");
+        let vars = TemplateVars::from_json(serde_json::json!({ "discount": 0 }));
+        apply_single(&template, &output, &vars, &default_global()).unwrap();
+        let wb = Workbook::open(&output).unwrap();
+        let formula = |sheet, cell| match wb.get_cell(sheet, &CellRef::parse(cell).unwrap()) {
+            Ok(CellValue::Formula { formula, .. }) => formula,
+            other => panic!("expected a formula, got {:?}", other),
+        };
+        assert_eq!(formula("Sheet1", "B2"), "SUM(B1:B1)");
+        assert_eq!(formula("Summary", "A1"), "Sheet1!B2+B3");
+    }
+
     #[test]
     fn test_apply_single_json_output() {
         let dir = TempDir::new().unwrap();
@@ -2356,9 +3183,40 @@ mod tests {
         let dir = TempDir::new().unwrap();
         let template = create_template_workbook(&dir, "template.xlsx", &[("A1", "{{name}}")]);
 
-        let result = validate(&template, None, false, &default_global());
+        let result = validate(&template, None, false, FailOn::Errors, &default_global());
         // Should show warnings about missing variables but not error
         assert!(result.is_ok());
+        let err =
+            validate(&template, None, false, FailOn::Warnings, &default_global()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<xlex_core::XlexError>(),
+            Some(xlex_core::XlexError::ValidationFailed { .. })
+        ));
+
+        // With a variable file, a missing value is an error
+        let vars_path = dir.path().join("vars.json");
+        std::fs::write(&vars_path, r#"{"other": 1}"#).unwrap();
+        let err = validate(
+            &template,
+            Some(&vars_path),
+            false,
+            FailOn::Errors,
+            &default_global(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("1 error(s), 1 warning(s)"));
+        assert!(validate(
+            &template,
+            Some(&vars_path),
+            false,
+            FailOn::Never,
+            &default_global()
+        )
+        .is_ok());
+
+        let broken =
+            create_template_workbook(&dir, "broken.xlsx", &[("A1", "{{#if x}}{{/unless}}")]);
+        assert!(validate(&broken, None, false, FailOn::Errors, &default_global()).is_err());
     }
 
     #[test]
@@ -2415,6 +3273,19 @@ mod tests {
         assert_eq!(result, "2024-01-15");
     }
 
+    #[test]
+    fn test_filter_spellout_and_format_date() {
+        assert_eq!(
+            apply_filter("1250.5", "spellout:en"),
+            "one thousand two hundred fifty point five"
+        );
+        assert_eq!(apply_filter("3", "ordinal"), "3rd");
+        assert_eq!(
+            apply_filter("2024-01-15", "format_date:fr:d MMMM yyyy"),
+            "15 janvier 2024"
+        );
+    }
+
     #[test]
     fn test_filter_now() {
         // Now filter returns current date
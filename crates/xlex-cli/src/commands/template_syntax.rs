@@ -0,0 +1,468 @@
+//! Tokenizer and parser for template cell text.
+//!
+//! Text is split into literal runs and `{{...}}` tags: placeholders
+//! (`{{name|filter}}`), block openers (`{{#if cond}}`, `{{#unless cond}}`,
+//! `{{#row-repeat items}}`), `{{else}}` and closers (`{{/if}}`). The scan is
+//! a single forward pass, so any input finishes in linear time.
+//!
+//! Blocks nest. A block left open in a cell, or a closer with no opener
+//! before it, belongs to a block spanning cells: [`parse`] keeps such
+//! markers as text and [`unpaired_markers`] returns them.
+
+use std::ops::Range;
+
+/// Separates the cells of a row joined into one text. Tags never span it.
+pub(super) const CELL_BREAK: char = '\u{1f}';
+
+/// A kind of block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Block {
+    If,
+    Unless,
+    RowRepeat,
+}
+
+impl Block {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "if" => Some(Self::If),
+            "unless" => Some(Self::Unless),
+            "row-repeat" => Some(Self::RowRepeat),
+            _ => None,
+        }
+    }
+
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            Self::If => "if",
+            Self::Unless => "unless",
+            Self::RowRepeat => "row-repeat",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum TokenKind<'a> {
+    Text,
+    /// The trimmed text between the braces
+    Placeholder(&'a str),
+    /// A block and its condition or array name
+    Open(Block, &'a str),
+    Else,
+    Close(Block),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Token<'a> {
+    /// Byte range in the text, braces included
+    pub span: Range<usize>,
+    pub kind: TokenKind<'a>,
+}
+
+/// A parsed piece of template text.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Node<'a> {
+    Text(&'a str),
+    Placeholder {
+        /// The whole tag, kept when the value can't be resolved
+        raw: &'a str,
+        expr: &'a str,
+    },
+    Block {
+        block: Block,
+        arg: &'a str,
+        body: Vec<Node<'a>>,
+        /// After `{{else}}`
+        otherwise: Vec<Node<'a>>,
+    },
+}
+
+/// A malformed tag or badly nested block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct SyntaxError {
+    /// Byte offset of the offending tag
+    pub offset: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for SyntaxError {}
+
+fn error(offset: usize, message: String) -> SyntaxError {
+    SyntaxError { offset, message }
+}
+
+/// Splits text into tokens. A `{{` without a matching `}}`, or with
+/// another `{{` or a cell break before it, is literal text, as is `{{}}`.
+pub(super) fn tokenize(s: &str) -> Result<Vec<Token<'_>>, SyntaxError> {
+    let mut tokens = Vec::new();
+    let mut text_start = 0;
+    let mut pos = 0;
+    while let Some(found) = s[pos..].find("{{") {
+        let open = pos + found;
+        let inner_start = open + 2;
+        let Some(len) = s[inner_start..].find("}}") else {
+            break;
+        };
+        let inner_end = inner_start + len;
+        let raw_inner = &s[inner_start..inner_end];
+        if raw_inner.contains("{{") || raw_inner.contains(CELL_BREAK) {
+            pos = open + 1;
+            continue;
+        }
+        let end = inner_end + 2;
+        let inner = raw_inner.trim();
+        if inner.is_empty() {
+            pos = end;
+            continue;
+        }
+
+        let kind = if let Some(rest) = inner.strip_prefix('#') {
+            let (name, arg) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            let block = Block::from_name(name)
+                .ok_or_else(|| error(open, format!("unknown block {{{{#{}}}}}", name)))?;
+            let arg = arg.trim();
+            if arg.is_empty() {
+                let needs = match block {
+                    Block::RowRepeat => "an array name",
+                    Block::If | Block::Unless => "a condition",
+                };
+                return Err(error(
+                    open,
+                    format!("{{{{#{}}}}} needs {}", block.name(), needs),
+                ));
+            }
+            TokenKind::Open(block, arg)
+        } else if let Some(name) = inner.strip_prefix('/') {
+            let name = name.trim();
+            TokenKind::Close(
+                Block::from_name(name)
+                    .ok_or_else(|| error(open, format!("unknown block {{{{/{}}}}}", name)))?,
+            )
+        } else if inner == "else" {
+            TokenKind::Else
+        } else {
+            TokenKind::Placeholder(inner)
+        };
+
+        if text_start < open {
+            tokens.push(Token {
+                span: text_start..open,
+                kind: TokenKind::Text,
+            });
+        }
+        tokens.push(Token {
+            span: open..end,
+            kind,
+        });
+        pos = end;
+        text_start = end;
+    }
+    if text_start < s.len() {
+        tokens.push(Token {
+            span: text_start..s.len(),
+            kind: TokenKind::Text,
+        });
+    }
+    Ok(tokens)
+}
+
+/// Pairs the block markers within the tokens and returns the indices of
+/// those left unpaired: closers with no opener before them, `{{else}}`
+/// outside any block, and openers never closed.
+fn unpaired(s: &str, tokens: &[Token]) -> Result<Vec<usize>, SyntaxError> {
+    // Open blocks: the opener's index and whether an else was seen
+    let mut open: Vec<(usize, bool)> = Vec::new();
+    let mut unpaired = Vec::new();
+    let opener = |i: usize| &s[tokens[i].span.clone()];
+    for (i, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::Else => match open.last_mut() {
+                None => unpaired.push(i),
+                Some((o, _)) if matches!(tokens[*o].kind, TokenKind::Open(Block::RowRepeat, _)) => {
+                    return Err(error(
+                        token.span.start,
+                        format!("{{{{else}}}} can't be used in {}", opener(*o)),
+                    ));
+                }
+                Some((o, true)) => {
+                    return Err(error(
+                        token.span.start,
+                        format!("a second {{{{else}}}} in {}", opener(*o)),
+                    ));
+                }
+                Some((_, seen)) => *seen = true,
+            },
+            TokenKind::Open(..) => open.push((i, false)),
+            TokenKind::Close(block) => match open.pop() {
+                None => unpaired.push(i),
+                Some((o, _)) => {
+                    if !matches!(tokens[o].kind, TokenKind::Open(b, _) if b == block) {
+                        return Err(error(
+                            token.span.start,
+                            format!("{{{{/{}}}}} closes {}", block.name(), opener(o)),
+                        ));
+                    }
+                }
+            },
+            TokenKind::Text | TokenKind::Placeholder(_) => {}
+        }
+    }
+    unpaired.extend(open.into_iter().map(|(o, _)| o));
+    unpaired.sort_unstable();
+    Ok(unpaired)
+}
+
+/// Returns the block markers not paired within the text, in order: those
+/// of blocks that span cells.
+pub(super) fn unpaired_markers(s: &str) -> Result<Vec<Token<'_>>, SyntaxError> {
+    let tokens = tokenize(s)?;
+    let unpaired = unpaired(s, &tokens)?;
+    Ok(unpaired.into_iter().map(|i| tokens[i].clone()).collect())
+}
+
+/// Parses text into nodes. Unpaired block markers are kept as text.
+pub(super) fn parse(s: &str) -> Result<Vec<Node<'_>>, SyntaxError> {
+    struct Frame<'a> {
+        block: Block,
+        arg: &'a str,
+        body: Vec<Node<'a>>,
+        otherwise: Option<Vec<Node<'a>>>,
+    }
+
+    let tokens = tokenize(s)?;
+    let unpaired = unpaired(s, &tokens)?;
+    let mut root = Vec::new();
+    let mut stack: Vec<Frame> = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let raw = &s[token.span.clone()];
+        let kind = if unpaired.binary_search(&i).is_ok() {
+            &TokenKind::Text
+        } else {
+            &token.kind
+        };
+        let node = match *kind {
+            TokenKind::Text => Node::Text(raw),
+            TokenKind::Placeholder(expr) => Node::Placeholder { raw, expr },
+            TokenKind::Open(block, arg) => {
+                stack.push(Frame {
+                    block,
+                    arg,
+                    body: Vec::new(),
+                    otherwise: None,
+                });
+                continue;
+            }
+            TokenKind::Else => {
+                if let Some(frame) = stack.last_mut() {
+                    frame.otherwise = Some(Vec::new());
+                }
+                continue;
+            }
+            TokenKind::Close(_) => match stack.pop() {
+                Some(frame) => Node::Block {
+                    block: frame.block,
+                    arg: frame.arg,
+                    body: frame.body,
+                    otherwise: frame.otherwise.unwrap_or_default(),
+                },
+                None => continue,
+            },
+        };
+        match stack.last_mut() {
+            Some(Frame {
+                otherwise: Some(nodes),
+                ..
+            })
+            | Some(Frame { body: nodes, .. }) => nodes.push(node),
+            None => root.push(node),
+        }
+    }
+    Ok(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn kinds(s: &str) -> Vec<TokenKind<'_>> {
+        tokenize(s).unwrap().into_iter().map(|t| t.kind).collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            kinds("Hi {{ name|upper }}{{#if a > 1}}x{{else}}{{/if}}"),
+            vec![
+                TokenKind::Text,
+                TokenKind::Placeholder("name|upper"),
+                TokenKind::Open(Block::If, "a > 1"),
+                TokenKind::Text,
+                TokenKind::Else,
+                TokenKind::Close(Block::If),
+            ]
+        );
+        // Not tags
+        for text in ["{{", "{{}}", "a }} b", "{{a", "{a}"] {
+            assert_eq!(kinds(text), vec![TokenKind::Text], "{}", text);
+        }
+        assert_eq!(
+            kinds("{{a{{b}}"),
+            vec![TokenKind::Text, TokenKind::Placeholder("b")]
+        );
+        assert_eq!(
+            kinds("{{a\u{1f}b}}"),
+            vec![TokenKind::Text],
+            "tags don't span cells"
+        );
+    }
+
+    #[test]
+    fn test_tokenize_errors() {
+        let err = |s: &str| tokenize(s).unwrap_err();
+        assert_eq!(err("ab{{#each items}}").message, "unknown block {{#each}}");
+        assert_eq!(err("ab{{#each items}}").offset, 2);
+        assert_eq!(err("{{#if }}").message, "{{#if}} needs a condition");
+        assert_eq!(
+            err("{{#row-repeat}}").message,
+            "{{#row-repeat}} needs an array name"
+        );
+        assert_eq!(err("{{/each}}").message, "unknown block {{/each}}");
+    }
+
+    #[test]
+    fn test_parse_nested() {
+        let nodes = parse("{{#if a}}A{{#unless b}}B{{else}}C{{/unless}}{{/if}}").unwrap();
+        let [Node::Block {
+            block: Block::If,
+            arg: "a",
+            body,
+            otherwise,
+        }] = nodes.as_slice()
+        else {
+            panic!("{:?}", nodes);
+        };
+        assert!(otherwise.is_empty());
+        assert_eq!(body[0], Node::Text("A"));
+        assert_eq!(
+            body[1],
+            Node::Block {
+                block: Block::Unless,
+                arg: "b",
+                body: vec![Node::Text("B")],
+                otherwise: vec![Node::Text("C")],
+            }
+        );
+    }
+
+    #[test]
+    fn test_unpaired_markers() {
+        let markers = unpaired_markers("{{/if}}x{{#if a}}y{{/if}}{{else}}{{#unless b}}").unwrap();
+        let kinds: Vec<_> = markers.into_iter().map(|t| t.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Close(Block::If),
+                TokenKind::Else,
+                TokenKind::Open(Block::Unless, "b"),
+            ]
+        );
+        // Unpaired markers parse as text
+        assert_eq!(
+            parse("{{#if a}}x").unwrap(),
+            vec![Node::Text("{{#if a}}"), Node::Text("x")]
+        );
+    }
+
+    #[test]
+    fn test_nesting_errors() {
+        let err = |s: &str| parse(s).unwrap_err();
+        assert_eq!(
+            err("{{#if a}}x{{/unless}}"),
+            error(10, "{{/unless}} closes {{#if a}}".to_string())
+        );
+        assert_eq!(
+            err("{{#if a}}{{else}}{{else}}{{/if}}").message,
+            "a second {{else}} in {{#if a}}"
+        );
+        assert_eq!(
+            err("{{#row-repeat items}}{{else}}").message,
+            "{{else}} can't be used in {{#row-repeat items}}"
+        );
+    }
+
+    /// Fragments that make up fuzzed template text.
+    fn fragment() -> impl Strategy<Value = String> {
+        prop_oneof![
+            Just("{{".to_string()),
+            Just("}}".to_string()),
+            Just("{".to_string()),
+            Just("}".to_string()),
+            Just("{{#if a}}".to_string()),
+            Just("{{#unless b > 1}}".to_string()),
+            Just("{{#row-repeat items}}".to_string()),
+            Just("{{else}}".to_string()),
+            Just("{{/if}}".to_string()),
+            Just("{{/unless}}".to_string()),
+            Just("{{/row-repeat}}".to_string()),
+            Just("{{name|upper}}".to_string()),
+            Just("{{#".to_string()),
+            Just("{{/".to_string()),
+            Just(CELL_BREAK.to_string()),
+            "[a-z #/|é€ ]{0,4}",
+        ]
+    }
+
+    fn template_text() -> impl Strategy<Value = String> {
+        prop::collection::vec(fragment(), 0..24).prop_map(|parts| parts.concat())
+    }
+
+    proptest! {
+        #[test]
+        fn fuzz_tokens_cover_text(s in template_text()) {
+            if let Ok(tokens) = tokenize(&s) {
+                let mut end = 0;
+                for token in &tokens {
+                    prop_assert_eq!(token.span.start, end);
+                    prop_assert!(token.span.end > token.span.start);
+                    end = token.span.end;
+                }
+                prop_assert_eq!(end, s.len());
+            }
+        }
+
+        #[test]
+        fn fuzz_parse_keeps_text(s in template_text()) {
+            // Whatever the nesting, parsing finishes, and errors point into the text
+            match parse(&s) {
+                Ok(_) => {}
+                Err(err) => prop_assert!(s.is_char_boundary(err.offset) && err.offset < s.len()),
+            }
+        }
+
+        #[test]
+        fn fuzz_plain_text(s in "[^{}]{0,40}") {
+            let nodes = parse(&s).unwrap();
+            let text: String = nodes
+                .iter()
+                .map(|node| match node {
+                    Node::Text(text) => *text,
+                    other => panic!("{:?}", other),
+                })
+                .collect();
+            prop_assert_eq!(text, s);
+        }
+
+        #[test]
+        fn fuzz_arbitrary_input(s in any::<String>()) {
+            let _ = parse(&s);
+            let _ = unpaired_markers(&s);
+        }
+    }
+}
@@ -0,0 +1,594 @@
+//! Declarative row transforms.
+//!
+//! `xlex transform` streams a sheet through the steps of a YAML script into
+//! a new single-sheet workbook, so memory use stays flat however large the
+//! sheet is. With `header: true` (the default) the first row names the
+//! columns: it passes every filter, and steps can refer to columns by
+//! header text as well as by letter.
+//!
+//! The steps are shared with `xlex run`, where `join` can also look up
+//! rows in another table of the job.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use serde::Deserialize;
+
+use xlex_core::join::{JoinOptions, JoinTable, JoinType};
+use xlex_core::pipeline::{Pipeline, Record, RowSink};
+use xlex_core::writer::StreamingSheetWriter;
+use xlex_core::{CellRef, CellValue, LazyWorkbook, XlexError, XlexResult};
+
+use super::condition::Condition;
+use super::expr::Expression;
+use super::output::CommandOutput;
+use super::GlobalOptions;
+
+/// Arguments for the transform command.
+#[derive(Parser)]
+pub struct TransformArgs {
+    /// Path to the xlsx file
+    pub file: std::path::PathBuf,
+    /// YAML script with the steps to apply
+    #[arg(long)]
+    pub script: std::path::PathBuf,
+    /// Output xlsx file
+    #[arg(long)]
+    pub out: std::path::PathBuf,
+    /// Sheet to read (default: the script's `sheet`, or the first sheet)
+    #[arg(long, short = 's')]
+    pub sheet: Option<String>,
+    /// Overwrite existing file
+    #[arg(long, short = 'F')]
+    pub force: bool,
+}
+
+/// A transform script.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TransformScript {
+    /// Sheet to read
+    sheet: Option<String>,
+    /// Sheet name in the output (default: the source sheet's)
+    out_sheet: Option<String>,
+    /// Whether the first row names the columns
+    #[serde(default = "default_header")]
+    header: bool,
+    #[serde(with = "serde_yaml::with::singleton_map_recursive")]
+    steps: Vec<TransformStep>,
+}
+
+fn default_header() -> bool {
+    true
+}
+
+/// One step of a transform script. Columns are header text or letters.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub(super) enum TransformStep {
+    /// Keep rows whose cell in `column` meets `when`
+    Filter { column: String, when: String },
+    /// Keep only these columns, in this order
+    Select(Vec<String>),
+    /// Remove these columns
+    Drop(Vec<String>),
+    /// Change header text, old to new
+    Rename(BTreeMap<String, String>),
+    /// Put `value` in blank cells of `column`
+    Fill {
+        column: String,
+        value: serde_json::Value,
+    },
+    /// Strip leading and trailing whitespace from text in these columns
+    Trim(Vec<String>),
+    /// Set `column`, added at the end if new, to an expression over the row
+    Derive { column: String, expr: String },
+    /// Append the columns of the rows in table `with` whose `right_on`
+    /// column (default: `on`) matches this row's `on` column
+    Join {
+        with: String,
+        on: String,
+        right_on: Option<String>,
+        #[serde(rename = "type", default)]
+        kind: JoinKind,
+    },
+}
+
+/// Which rows a join keeps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum JoinKind {
+    /// Only rows with a match
+    #[default]
+    Inner,
+    /// Every row, with blanks where nothing matches
+    Left,
+}
+
+/// Column references of a step. With a header row they are looked up in
+/// the header as it reaches the step, after any earlier select or drop.
+struct Columns {
+    specs: Vec<String>,
+    resolved: Option<Vec<u32>>,
+}
+
+impl Columns {
+    fn new(specs: Vec<String>, header: bool) -> Result<Self> {
+        let resolved = if header {
+            None
+        } else {
+            let cols = specs
+                .iter()
+                .map(|spec| {
+                    letter_column(spec).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Column '{}' is not a column letter; names need `header: true`",
+                            spec
+                        )
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Some(cols)
+        };
+        Ok(Self { specs, resolved })
+    }
+
+    /// Looks the columns up in `record` if no row has been seen yet, and
+    /// returns whether it was the header row.
+    fn resolve(&mut self, record: &Record) -> XlexResult<bool> {
+        if self.resolved.is_some() {
+            return Ok(false);
+        }
+        let cols = self
+            .specs
+            .iter()
+            .map(|spec| {
+                record
+                    .values
+                    .iter()
+                    .position(|v| v.to_display_string() == *spec)
+                    .map(|i| i as u32 + 1)
+                    .or_else(|| letter_column(spec))
+                    .ok_or_else(|| XlexError::InvalidOperation {
+                        message: format!("No column '{}' in the header row", spec),
+                    })
+            })
+            .collect::<XlexResult<Vec<_>>>()?;
+        self.resolved = Some(cols);
+        Ok(true)
+    }
+
+    fn get(&self) -> &[u32] {
+        self.resolved.as_deref().unwrap_or_default()
+    }
+}
+
+pub(super) fn letter_column(spec: &str) -> Option<u32> {
+    if spec.is_empty() || !spec.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    CellRef::col_from_letters_pub(&spec.to_uppercase())
+}
+
+/// Rows of the table a join step names, header row first.
+pub(super) type JoinTables<'t> = dyn FnMut(&str) -> Result<Vec<Vec<CellValue>>> + 't;
+
+/// Adds a script step to the pipeline. `tables` supplies the rows of the
+/// table a join reads.
+pub(super) fn add_step<'a>(
+    pipeline: Pipeline<'a>,
+    step: TransformStep,
+    header: bool,
+    tables: &mut JoinTables<'_>,
+) -> Result<Pipeline<'a>> {
+    let pipeline = match step {
+        TransformStep::Filter { column, when } => {
+            let condition = Condition::parse(Some(&when))?;
+            let mut columns = Columns::new(vec![column], header)?;
+            pipeline.try_filter_map(move |record| {
+                if columns.resolve(&record)? {
+                    return Ok(Some(record));
+                }
+                let keep = condition.matches(record.get(columns.get()[0]));
+                Ok(keep.then_some(record))
+            })
+        }
+        TransformStep::Select(specs) => {
+            let mut columns = Columns::new(specs, header)?;
+            pipeline.try_filter_map(move |record| {
+                columns.resolve(&record)?;
+                let values = columns
+                    .get()
+                    .iter()
+                    .map(|&col| record.get(col).clone())
+                    .collect();
+                Ok(Some(Record { values, ..record }))
+            })
+        }
+        TransformStep::Drop(specs) => {
+            let mut columns = Columns::new(specs, header)?;
+            pipeline.try_filter_map(move |mut record| {
+                columns.resolve(&record)?;
+                let mut index = 0;
+                record.values.retain(|_| {
+                    index += 1;
+                    !columns.get().contains(&index)
+                });
+                Ok(Some(record))
+            })
+        }
+        TransformStep::Rename(names) => {
+            if !header {
+                anyhow::bail!("rename needs `header: true`");
+            }
+            let (old, new): (Vec<String>, Vec<String>) = names.into_iter().unzip();
+            let mut columns = Columns::new(old, header)?;
+            pipeline.try_filter_map(move |mut record| {
+                if columns.resolve(&record)? {
+                    for (&col, name) in columns.get().iter().zip(&new) {
+                        record.set(col, CellValue::string(name.as_str()));
+                    }
+                }
+                Ok(Some(record))
+            })
+        }
+        TransformStep::Fill { column, value } => {
+            let value = CellValue::try_from(&value)
+                .map_err(|e| anyhow::anyhow!("Invalid fill value for '{}': {}", column, e))?;
+            let mut columns = Columns::new(vec![column], header)?;
+            pipeline.try_filter_map(move |mut record| {
+                if !columns.resolve(&record)? {
+                    let col = columns.get()[0];
+                    if record.get(col).is_empty() {
+                        record.set(col, value.clone());
+                    }
+                }
+                Ok(Some(record))
+            })
+        }
+        TransformStep::Trim(specs) => {
+            let mut columns = Columns::new(specs, header)?;
+            pipeline.try_filter_map(move |mut record| {
+                if !columns.resolve(&record)? {
+                    for &col in columns.get() {
+                        if let CellValue::String(text) = record.get(col) {
+                            let trimmed = text.trim();
+                            if trimmed.len() != text.len() {
+                                record.set(col, CellValue::string(trimmed));
+                            }
+                        }
+                    }
+                }
+                Ok(Some(record))
+            })
+        }
+        TransformStep::Derive { column, expr } => {
+            if !header && letter_column(&column).is_none() {
+                anyhow::bail!(
+                    "Column '{}' is not a column letter; names need `header: true`",
+                    column
+                );
+            }
+            let expression = Expression::parse(&expr)?;
+            let mut inputs = Columns::new(expression.columns.clone(), header)?;
+            let mut target = (!header).then(|| letter_column(&column)).flatten();
+            pipeline.try_filter_map(move |mut record| {
+                if inputs.resolve(&record)? {
+                    // A new column goes after the last header cell
+                    let col = match record
+                        .values
+                        .iter()
+                        .position(|v| v.to_display_string() == column)
+                    {
+                        Some(i) => i as u32 + 1,
+                        None => record.values.len() as u32 + 1,
+                    };
+                    record.set(col, CellValue::string(column.as_str()));
+                    target = Some(col);
+                    return Ok(Some(record));
+                }
+                let value = expression.eval(&record, inputs.get());
+                if let Some(col) = target {
+                    record.set(col, value);
+                }
+                Ok(Some(record))
+            })
+        }
+        TransformStep::Join {
+            with,
+            on,
+            right_on,
+            kind,
+        } => {
+            if !header {
+                anyhow::bail!("join needs `header: true`");
+            }
+            let mut rows = tables(&with)?.into_iter();
+            let right_header = rows.next().unwrap_or_default();
+            let right_on = right_on.unwrap_or_else(|| on.clone());
+            let key_index = right_header
+                .iter()
+                .position(|v| v.to_display_string() == right_on)
+                .or_else(|| letter_column(&right_on).map(|c| c as usize - 1))
+                .ok_or_else(|| anyhow::anyhow!("No column '{}' in table '{}'", right_on, with))?;
+            let options = JoinOptions {
+                join_type: match kind {
+                    JoinKind::Inner => JoinType::Inner,
+                    JoinKind::Left => JoinType::Left,
+                },
+                // The table is already in memory, and probing needs it there
+                spill_after: usize::MAX,
+                ..JoinOptions::default()
+            };
+            let mut table = JoinTable::new(key_index as u32 + 1, options);
+            table.write_row(&right_header)?;
+            for row in rows {
+                table.write_row(&row)?;
+            }
+            let right_header = table.right_header();
+            let mut columns = Columns::new(vec![on], header)?;
+            let mut width = 0;
+            pipeline.try_flat_map(move |mut record| {
+                if columns.resolve(&record)? {
+                    width = record.values.len();
+                    record.values.extend(right_header.iter().cloned());
+                    return Ok(vec![record]);
+                }
+                record
+                    .values
+                    .resize(width.max(record.values.len()), CellValue::Empty);
+                let key = record.get(columns.get()[0]).to_display_string();
+                let row = record.row;
+                Ok(table
+                    .probe(&key, record.values)?
+                    .into_iter()
+                    .map(|values| Record { row, values })
+                    .collect())
+            })
+        }
+    };
+    Ok(pipeline)
+}
+
+/// Run the transform command.
+pub fn run(args: &TransformArgs, global: &GlobalOptions) -> Result<()> {
+    if args.out.exists() && !args.force {
+        return Err(XlexError::FileExists {
+            path: args.out.clone(),
+        }
+        .into());
+    }
+    let content = std::fs::read_to_string(&args.script)?;
+    let script: TransformScript = serde_yaml::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("Invalid script {}: {}", args.script.display(), e))?;
+
+    let workbook = LazyWorkbook::open(&args.file)?;
+    let sheet = match args.sheet.as_deref().or(script.sheet.as_deref()) {
+        Some(sheet) => sheet.to_string(),
+        None => workbook
+            .sheet_names()
+            .first()
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Workbook has no sheets"))?,
+    };
+    if !workbook.has_sheet(&sheet) {
+        return Err(XlexError::SheetNotFound { name: sheet }.into());
+    }
+    let out_sheet = script.out_sheet.as_deref().unwrap_or(&sheet).to_string();
+    let step_count = script.steps.len();
+
+    let mut pipeline = workbook.pipeline(&sheet);
+    for step in script.steps {
+        pipeline = add_step(pipeline, step, script.header, &mut |_| {
+            anyhow::bail!("join reads another table; use it in a job run with `xlex run`")
+        })?;
+    }
+
+    if global.dry_run {
+        outln!(
+            "Would transform {} in {} with {} steps into {}",
+            sheet,
+            args.file.display(),
+            step_count,
+            args.out.display()
+        );
+        return Ok(());
+    }
+
+    let mut writer = StreamingSheetWriter::create(&args.out, &out_sheet)?;
+    let stats = pipeline.write_to(&mut writer)?;
+    writer.finish()?;
+
+    CommandOutput::new("transform")
+        .file(&args.file)
+        .target("sheet", sheet.as_str())
+        .field("out", args.out.display().to_string())
+        .field("steps", step_count)
+        .field("rowsRead", stats.rows_read)
+        .field("rowsWritten", stats.rows_written)
+        .print(
+            global,
+            format!(
+                "Wrote {} of {} rows from {} to {}",
+                stats.rows_written.to_string().green(),
+                stats.rows_read,
+                sheet.cyan(),
+                args.out.display().to_string().cyan()
+            ),
+        )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::OutputFormat;
+    use tempfile::TempDir;
+    use xlex_core::Workbook;
+
+    fn default_global() -> GlobalOptions {
+        GlobalOptions {
+            quiet: true,
+            verbose: false,
+            format: OutputFormat::Text,
+            no_color: true,
+            color: false,
+            json_errors: false,
+            dry_run: false,
+            output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
+        }
+    }
+
+    fn orders(dir: &TempDir) -> std::path::PathBuf {
+        let path = dir.path().join("orders.xlsx");
+        let mut wb = Workbook::with_sheets(&["Orders"]);
+        let rows: [[CellValue; 4]; 4] = [
+            ["id", "customer", "total", "notes"].map(CellValue::string),
+            [
+                CellValue::Number(1.0),
+                CellValue::string("  Ann "),
+                CellValue::Number(50.0),
+                CellValue::string("x"),
+            ],
+            [
+                CellValue::Number(2.0),
+                CellValue::string("Bo"),
+                CellValue::Number(150.0),
+                CellValue::Empty,
+            ],
+            [
+                CellValue::Number(3.0),
+                CellValue::Empty,
+                CellValue::Number(300.0),
+                CellValue::Empty,
+            ],
+        ];
+        for (r, row) in rows.iter().enumerate() {
+            for (c, value) in row.iter().enumerate() {
+                let cell_ref = CellRef::new(c as u32 + 1, r as u32 + 1);
+                wb.set_cell("Orders", cell_ref, value.clone()).unwrap();
+            }
+        }
+        wb.save_as(&path).unwrap();
+        path
+    }
+
+    fn transform(dir: &TempDir, script: &str) -> Result<Workbook> {
+        let file = orders(dir);
+        let script_path = dir.path().join("transform.yml");
+        std::fs::write(&script_path, script).unwrap();
+        let out = dir.path().join("out.xlsx");
+        let args = TransformArgs {
+            file,
+            script: script_path,
+            out: out.clone(),
+            sheet: None,
+            force: true,
+        };
+        run(&args, &default_global())?;
+        Ok(Workbook::open(&out).unwrap())
+    }
+
+    fn rows(workbook: &Workbook, sheet: &str) -> Vec<Vec<CellValue>> {
+        let sheet = workbook.get_sheet(sheet).unwrap();
+        let (cols, rows) = sheet.dimensions();
+        (1..=rows)
+            .map(|r| {
+                (1..=cols)
+                    .map(|c| sheet.get_value(&CellRef::new(c, r)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_run_transform() {
+        let dir = TempDir::new().unwrap();
+        let workbook = transform(
+            &dir,
+            r#"
+out_sheet: Big
+steps:
+  - filter: { column: total, when: "> 100" }
+  - drop: [notes]
+  - select: [customer, C, id]
+  - rename: { customer: name }
+  - fill: { column: name, value: unknown }
+  - trim: [A]
+  - derive: { column: double, expr: "total * 2" }
+"#,
+        )
+        .unwrap();
+        assert_eq!(workbook.sheet_names(), vec!["Big"]);
+        assert_eq!(
+            rows(&workbook, "Big"),
+            vec![
+                ["name", "total", "id", "double"]
+                    .map(CellValue::string)
+                    .to_vec(),
+                vec![
+                    CellValue::string("Bo"),
+                    CellValue::Number(150.0),
+                    CellValue::Number(2.0),
+                    CellValue::Number(300.0)
+                ],
+                vec![
+                    CellValue::string("unknown"),
+                    CellValue::Number(300.0),
+                    CellValue::Number(3.0),
+                    CellValue::Number(600.0)
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_transform_without_header() {
+        let dir = TempDir::new().unwrap();
+        let workbook = transform(
+            &dir,
+            "header: false\nsteps:\n  - filter: { column: B, when: nonblank }\n  - trim: [B]\n  - select: [B]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            rows(&workbook, "Orders"),
+            vec![
+                vec![CellValue::string("customer")],
+                vec![CellValue::string("Ann")],
+                vec![CellValue::string("Bo")],
+            ]
+        );
+
+        let dir = TempDir::new().unwrap();
+        let err = transform(&dir, "header: false\nsteps:\n  - select: [total]\n").unwrap_err();
+        assert!(err.to_string().contains("not a column letter"));
+    }
+
+    #[test]
+    fn test_run_transform_errors() {
+        let dir = TempDir::new().unwrap();
+        let err = transform(&dir, "steps:\n  - select: [missing]\n").unwrap_err();
+        assert!(err.to_string().contains("No column 'missing'"));
+        assert!(!dir.path().join("out.xlsx").exists());
+
+        let err = transform(&dir, "steps:\n  - sort: [A]\n").unwrap_err();
+        assert!(err.to_string().contains("Invalid script"));
+
+        let err =
+            transform(&dir, "steps:\n  - filter: { column: A, when: sometimes }\n").unwrap_err();
+        assert!(err.to_string().contains("Unknown condition"));
+
+        let err = transform(&dir, "steps:\n  - join: { with: prices, on: id }\n").unwrap_err();
+        assert!(err.to_string().contains("xlex run"));
+
+        let err = transform(&dir, "sheet: Nope\nsteps: []\n").unwrap_err();
+        assert!(err.to_string().contains("Nope"));
+    }
+}
@@ -66,8 +66,8 @@ pub fn run(args: &UpdateArgs, global: &GlobalOptions) -> Result<()> {
     let current = normalize(CURRENT_VERSION);
 
     if !global.quiet {
-        println!("Current version: v{current}");
-        println!("Checking for updates...");
+        outln!("Current version: v{current}");
+        outln!("Checking for updates...");
     }
 
     let target_tag = if let Some(ref v) = args.target {
@@ -83,31 +83,31 @@ pub fn run(args: &UpdateArgs, global: &GlobalOptions) -> Result<()> {
 
     if current == latest {
         if !global.quiet {
-            println!("Already up to date (v{current}).");
+            outln!("Already up to date (v{current}).");
         }
         return Ok(());
     }
 
     if !global.quiet {
-        println!("New version available: v{latest}");
+        outln!("New version available: v{latest}");
     }
 
     if args.check {
         // --check: just report, don't install
         if global.format == super::OutputFormat::Json {
-            println!(r#"{{"current":"v{current}","latest":"v{latest}","update_available":true}}"#);
+            outln!(r#"{{"current":"v{current}","latest":"v{latest}","update_available":true}}"#);
         }
         return Ok(());
     }
 
     if global.dry_run {
-        println!("Would update from v{current} to v{latest}");
+        outln!("Would update from v{current} to v{latest}");
         return Ok(());
     }
 
     // Run the install script with the target version
     if !global.quiet {
-        println!("Updating to v{latest}...");
+        outln!("Updating to v{latest}...");
     }
 
     let install_url = format!("https://raw.githubusercontent.com/{REPO}/main/install.sh");
@@ -125,7 +125,7 @@ pub fn run(args: &UpdateArgs, global: &GlobalOptions) -> Result<()> {
     }
 
     if !global.quiet {
-        println!("Successfully updated to v{latest}!");
+        outln!("Successfully updated to v{latest}!");
     }
 
     Ok(())
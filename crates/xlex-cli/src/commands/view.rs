@@ -0,0 +1,607 @@
+//! Interactive terminal viewer.
+
+use std::collections::HashMap;
+use std::io::{IsTerminal, Write};
+
+use anyhow::Result;
+use clap::Parser;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::{cursor, queue, terminal};
+
+use xlex_core::{CellRef, CellValue, LazyWorkbook, Style, StyleRegistry};
+
+use super::GlobalOptions;
+
+/// Width of the row number gutter.
+const GUTTER: usize = 7;
+/// Width of each column.
+const COLUMN_WIDTH: usize = 12;
+/// Lines used by tabs, column headers, inspector and status bar.
+const CHROME_LINES: u16 = 5;
+
+/// Arguments for the view command.
+#[derive(Parser)]
+pub struct ViewArgs {
+    /// Path to the xlsx file
+    pub file: std::path::PathBuf,
+    /// Sheet to open first
+    #[arg(long)]
+    pub sheet: Option<String>,
+}
+
+/// Open the interactive viewer.
+pub fn run(args: &ViewArgs, _global: &GlobalOptions) -> Result<()> {
+    if !std::io::stdout().is_terminal() {
+        anyhow::bail!("xlex view needs an interactive terminal");
+    }
+
+    let workbook = LazyWorkbook::open(&args.file)?;
+    let mut viewer = Viewer::new(&workbook)?;
+    if let Some(sheet) = &args.sheet {
+        let index = workbook
+            .sheet_names()
+            .iter()
+            .position(|s| s == sheet)
+            .ok_or_else(|| xlex_core::XlexError::SheetNotFound {
+                name: sheet.clone(),
+            })?;
+        viewer.select_sheet(index)?;
+    }
+
+    let _terminal = RawTerminal::enter()?;
+    let mut stdout = std::io::stdout();
+    loop {
+        let (width, height) = terminal::size()?;
+        viewer.render(&mut stdout, width, height)?;
+        stdout.flush()?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press
+                && viewer.handle_key(key, page_size(width, height))?
+            {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Puts the terminal in raw mode on the alternate screen and restores it
+/// when dropped, including on errors.
+struct RawTerminal;
+
+impl RawTerminal {
+    fn enter() -> Result<Self> {
+        terminal::enable_raw_mode()?;
+        let mut stdout = std::io::stdout();
+        queue!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+        stdout.flush()?;
+        Ok(Self)
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let mut stdout = std::io::stdout();
+        let _ = queue!(stdout, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = stdout.flush();
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Visible rows and columns of the grid for a terminal size.
+fn page_size(width: u16, height: u16) -> (u32, u32) {
+    let rows = height.saturating_sub(CHROME_LINES).max(1) as u32;
+    let cols = ((width as usize).saturating_sub(GUTTER) / COLUMN_WIDTH).max(1) as u32;
+    (rows, cols)
+}
+
+/// Pads or truncates `text` to exactly `width` characters.
+fn fit(text: &str, width: usize) -> String {
+    let text: String = text
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+    if text.chars().count() > width {
+        let mut cut: String = text.chars().take(width.saturating_sub(1)).collect();
+        cut.push('~');
+        cut
+    } else {
+        format!("{:<width$}", text, width = width)
+    }
+}
+
+/// Text shown in the grid: formulas show their cached result.
+fn display_value(value: &CellValue) -> String {
+    match value {
+        CellValue::Formula {
+            cached_result: Some(result),
+            ..
+        } => result.to_display_string(),
+        other => other.to_display_string(),
+    }
+}
+
+/// One-line summary of a style for the inspector.
+fn describe_style(id: u32, style: &Style) -> String {
+    let mut parts = Vec::new();
+    let font = &style.font;
+    let mut font_desc = vec![font
+        .name
+        .clone()
+        .unwrap_or_else(|| "default font".to_string())];
+    if let Some(size) = font.size {
+        font_desc.push(size.to_string());
+    }
+    for (on, name) in [
+        (font.bold, "bold"),
+        (font.italic, "italic"),
+        (font.underline, "underline"),
+        (font.strikethrough, "strikethrough"),
+    ] {
+        if on {
+            font_desc.push(name.to_string());
+        }
+    }
+    parts.push(font_desc.join(" "));
+    if let Some(hex) = font.color.as_ref().and_then(|c| c.to_hex()) {
+        parts.push(format!("color {}", hex));
+    }
+    if let Some(hex) = style.fill.fg_color.as_ref().and_then(|c| c.to_hex()) {
+        parts.push(format!("fill {}", hex));
+    }
+    if let Some(code) = &style.number_format.code {
+        parts.push(format!("format {}", code));
+    }
+    format!("style {}: {}", id, parts.join(", "))
+}
+
+/// Whether the bottom line is showing status text or a search prompt.
+enum Mode {
+    Normal,
+    Search(String),
+}
+
+/// Viewer state, kept apart from the terminal so it can be tested.
+struct Viewer<'a> {
+    workbook: &'a LazyWorkbook,
+    sheet: usize,
+    cells: HashMap<(u32, u32), CellValue>,
+    max_row: u32,
+    max_col: u32,
+    /// Selected cell as (row, column), 1-based
+    cursor: (u32, u32),
+    /// Top-left visible cell as (row, column)
+    origin: (u32, u32),
+    mode: Mode,
+    query: Option<String>,
+    status: String,
+    styles: Option<StyleRegistry>,
+}
+
+impl<'a> Viewer<'a> {
+    fn new(workbook: &'a LazyWorkbook) -> Result<Self> {
+        let mut viewer = Self {
+            workbook,
+            sheet: 0,
+            cells: HashMap::new(),
+            max_row: 1,
+            max_col: 1,
+            cursor: (1, 1),
+            origin: (1, 1),
+            mode: Mode::Normal,
+            query: None,
+            status: String::new(),
+            styles: None,
+        };
+        viewer.select_sheet(0)?;
+        Ok(viewer)
+    }
+
+    fn sheet_name(&self) -> &str {
+        &self.workbook.sheet_names()[self.sheet]
+    }
+
+    /// Streams the rows of the sheet at `index` and moves to its first cell.
+    fn select_sheet(&mut self, index: usize) -> Result<()> {
+        self.sheet = index;
+        self.cells.clear();
+        self.max_row = 1;
+        self.max_col = 1;
+        if let Some(name) = self.workbook.sheet_names().get(index).cloned() {
+            for row in self.workbook.stream_rows(&name)? {
+                for (cell_ref, value) in row.cells {
+                    self.max_row = self.max_row.max(cell_ref.row);
+                    self.max_col = self.max_col.max(cell_ref.col);
+                    self.cells.insert((cell_ref.row, cell_ref.col), value);
+                }
+            }
+        }
+        self.cursor = (1, 1);
+        self.origin = (1, 1);
+        self.status = format!(
+            "{} cells, {}",
+            self.cells.len(),
+            CellRef::new(self.max_col, self.max_row).to_a1()
+        );
+        Ok(())
+    }
+
+    fn value_at(&self, row: u32, col: u32) -> Option<&CellValue> {
+        self.cells.get(&(row, col))
+    }
+
+    /// Moves the cursor by a delta, clamped to the used area, and scrolls
+    /// so it stays visible.
+    fn move_cursor(&mut self, rows: i64, cols: i64, page: (u32, u32)) {
+        let clamp = |v: i64, max: u32| v.clamp(1, max.max(1) as i64) as u32;
+        self.cursor.0 = clamp(self.cursor.0 as i64 + rows, self.max_row);
+        self.cursor.1 = clamp(self.cursor.1 as i64 + cols, self.max_col);
+        self.scroll_to_cursor(page);
+    }
+
+    fn scroll_to_cursor(&mut self, (page_rows, page_cols): (u32, u32)) {
+        if self.cursor.0 < self.origin.0 {
+            self.origin.0 = self.cursor.0;
+        } else if self.cursor.0 >= self.origin.0 + page_rows {
+            self.origin.0 = self.cursor.0 + 1 - page_rows;
+        }
+        if self.cursor.1 < self.origin.1 {
+            self.origin.1 = self.cursor.1;
+        } else if self.cursor.1 >= self.origin.1 + page_cols {
+            self.origin.1 = self.cursor.1 + 1 - page_cols;
+        }
+    }
+
+    /// Cells matching the search query, in row-major order.
+    fn matches(&self) -> Vec<(u32, u32)> {
+        let Some(query) = &self.query else {
+            return Vec::new();
+        };
+        let query = query.to_lowercase();
+        let mut found: Vec<(u32, u32)> = self
+            .cells
+            .iter()
+            .filter(|(_, value)| {
+                let formula = match value {
+                    CellValue::Formula { formula, .. } => formula.to_lowercase(),
+                    _ => String::new(),
+                };
+                display_value(value).to_lowercase().contains(&query) || formula.contains(&query)
+            })
+            .map(|(pos, _)| *pos)
+            .collect();
+        found.sort_unstable();
+        found
+    }
+
+    /// Jumps to the next (or previous) match after the cursor, wrapping.
+    fn jump_to_match(&mut self, forward: bool, page: (u32, u32)) {
+        let matches = self.matches();
+        let target = if forward {
+            matches
+                .iter()
+                .find(|pos| **pos > self.cursor)
+                .or(matches.first())
+        } else {
+            matches
+                .iter()
+                .rev()
+                .find(|pos| **pos < self.cursor)
+                .or(matches.last())
+        };
+        match target {
+            Some(&pos) => {
+                self.cursor = pos;
+                self.scroll_to_cursor(page);
+                let index = matches.iter().position(|m| *m == pos).unwrap_or(0);
+                self.status = format!("match {} of {}", index + 1, matches.len());
+            }
+            None => {
+                self.status = format!(
+                    "'{}' not found in {}",
+                    self.query.as_deref().unwrap_or_default(),
+                    self.sheet_name()
+                );
+            }
+        }
+    }
+
+    /// Describes the selected cell: reference, type, value, formula, style.
+    fn inspect(&mut self) -> Result<String> {
+        let (row, col) = self.cursor;
+        let cell_ref = CellRef::new(col, row);
+        let value = self.value_at(row, col).cloned().unwrap_or(CellValue::Empty);
+        let details = self
+            .workbook
+            .read_cell_details(self.sheet_name(), &cell_ref)?
+            .unwrap_or_default();
+
+        let type_name = if details.formula.is_some() {
+            "formula"
+        } else {
+            value.type_name()
+        };
+        let mut parts = vec![format!("{} [{}]", cell_ref.to_a1(), type_name)];
+        if let Some(formula) = &details.formula {
+            parts.push(format!("={}", formula));
+            if !value.is_empty() {
+                parts.push(format!("-> {}", display_value(&value)));
+            }
+        } else if !value.is_empty() {
+            parts.push(display_value(&value));
+        }
+        if let Some(id) = details.style_id {
+            if self.styles.is_none() {
+                self.styles = Some(self.workbook.styles()?);
+            }
+            if let Some(style) = self.styles.as_ref().and_then(|s| s.get(id)) {
+                parts.push(describe_style(id, style));
+            }
+        }
+        Ok(parts.join("  "))
+    }
+
+    /// Handles a key press. Returns true when the viewer should close.
+    fn handle_key(&mut self, key: KeyEvent, page: (u32, u32)) -> Result<bool> {
+        if let Mode::Search(input) = &mut self.mode {
+            match key.code {
+                KeyCode::Enter => {
+                    let query = std::mem::take(input);
+                    self.mode = Mode::Normal;
+                    if !query.is_empty() {
+                        self.query = Some(query);
+                        self.jump_to_match(true, page);
+                    }
+                }
+                KeyCode::Esc => self.mode = Mode::Normal,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+            return Ok(false);
+        }
+
+        let sheet_count = self.workbook.sheet_count();
+        let (page_rows, page_cols) = (page.0 as i64, page.1 as i64);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(true),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(true),
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1, 0, page),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1, 0, page),
+            KeyCode::Left | KeyCode::Char('h') => self.move_cursor(0, -1, page),
+            KeyCode::Right | KeyCode::Char('l') => self.move_cursor(0, 1, page),
+            KeyCode::PageUp => self.move_cursor(-page_rows, 0, page),
+            KeyCode::PageDown | KeyCode::Char(' ') => self.move_cursor(page_rows, 0, page),
+            KeyCode::Char('H') => self.move_cursor(0, -page_cols, page),
+            KeyCode::Char('L') => self.move_cursor(0, page_cols, page),
+            KeyCode::Home => self.move_cursor(0, -(self.max_col as i64), page),
+            KeyCode::End => self.move_cursor(0, self.max_col as i64, page),
+            KeyCode::Char('g') => self.move_cursor(-(self.max_row as i64), 0, page),
+            KeyCode::Char('G') => self.move_cursor(self.max_row as i64, 0, page),
+            KeyCode::Tab | KeyCode::Char(']') => {
+                self.select_sheet((self.sheet + 1) % sheet_count)?;
+            }
+            KeyCode::BackTab | KeyCode::Char('[') => {
+                self.select_sheet((self.sheet + sheet_count - 1) % sheet_count)?;
+            }
+            KeyCode::Enter | KeyCode::Char('i') => self.status = self.inspect()?,
+            KeyCode::Char('/') => self.mode = Mode::Search(String::new()),
+            KeyCode::Char('n') => self.jump_to_match(true, page),
+            KeyCode::Char('N') => self.jump_to_match(false, page),
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    /// Draws the whole screen.
+    fn render(&self, out: &mut impl Write, width: u16, height: u16) -> Result<()> {
+        let width = width as usize;
+        let (page_rows, page_cols) = page_size(width as u16, height);
+        queue!(out, terminal::Clear(terminal::ClearType::All))?;
+
+        // Sheet tabs
+        queue!(out, cursor::MoveTo(0, 0))?;
+        let mut used = 0;
+        for (i, name) in self.workbook.sheet_names().iter().enumerate() {
+            let tab = format!(" {} ", name);
+            used += tab.chars().count();
+            if used > width {
+                break;
+            }
+            if i == self.sheet {
+                queue!(
+                    out,
+                    SetAttribute(Attribute::Reverse),
+                    Print(&tab),
+                    SetAttribute(Attribute::Reset)
+                )?;
+            } else {
+                queue!(out, Print(&tab))?;
+            }
+        }
+
+        // Column headers
+        let mut header = fit("", GUTTER);
+        for col in self.origin.1..self.origin.1 + page_cols {
+            header.push_str(&fit(&CellRef::col_to_letters(col), COLUMN_WIDTH));
+        }
+        queue!(
+            out,
+            cursor::MoveTo(0, 1),
+            SetAttribute(Attribute::Bold),
+            Print(fit(&header, width)),
+            SetAttribute(Attribute::Reset)
+        )?;
+
+        // Grid
+        let matches = self.matches();
+        for (line, row) in (self.origin.0..self.origin.0 + page_rows).enumerate() {
+            queue!(
+                out,
+                cursor::MoveTo(0, 2 + line as u16),
+                SetAttribute(Attribute::Bold),
+                Print(fit(&row.to_string(), GUTTER)),
+                SetAttribute(Attribute::Reset)
+            )?;
+            for col in self.origin.1..self.origin.1 + page_cols {
+                let text = self
+                    .value_at(row, col)
+                    .map(display_value)
+                    .unwrap_or_default();
+                let text = fit(&text, COLUMN_WIDTH - 1) + " ";
+                if (row, col) == self.cursor {
+                    queue!(
+                        out,
+                        SetAttribute(Attribute::Reverse),
+                        Print(text),
+                        SetAttribute(Attribute::Reset)
+                    )?;
+                } else if matches.binary_search(&(row, col)).is_ok() {
+                    queue!(
+                        out,
+                        SetAttribute(Attribute::Underlined),
+                        Print(text),
+                        SetAttribute(Attribute::Reset)
+                    )?;
+                } else {
+                    queue!(out, Print(text))?;
+                }
+            }
+        }
+
+        // Selected cell and status / search prompt
+        let (row, col) = self.cursor;
+        let selected = self
+            .value_at(row, col)
+            .map(|v| v.to_display_string())
+            .unwrap_or_default();
+        let selected = format!("{}: {}", CellRef::new(col, row).to_a1(), selected);
+        let bottom = match &self.mode {
+            Mode::Search(input) => format!("/{}", input),
+            Mode::Normal if self.status.is_empty() => {
+                "arrows/hjkl move  PgUp/PgDn page  Tab sheet  Enter inspect  / search  n/N next  q quit"
+                    .to_string()
+            }
+            Mode::Normal => self.status.clone(),
+        };
+        queue!(
+            out,
+            cursor::MoveTo(0, height.saturating_sub(2)),
+            Print(fit(&selected, width)),
+            cursor::MoveTo(0, height.saturating_sub(1)),
+            SetAttribute(Attribute::Reverse),
+            Print(fit(&bottom, width)),
+            SetAttribute(Attribute::Reset)
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use xlex_core::style::Font;
+    use xlex_core::Workbook;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn create_test_workbook(dir: &TempDir) -> std::path::PathBuf {
+        let file_path = dir.path().join("view.xlsx");
+        let mut wb = Workbook::with_sheets(&["Data", "Summary"]);
+        let bold = wb.style_registry_mut().add(Style {
+            font: Font {
+                bold: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        for row in 1..=50 {
+            wb.set_cell(
+                "Data",
+                CellRef::new(1, row),
+                CellValue::string(format!("Item {}", row)),
+            )
+            .unwrap();
+            wb.set_cell("Data", CellRef::new(2, row), CellValue::Number(row as f64))
+                .unwrap();
+        }
+        wb.get_sheet_mut("Data")
+            .unwrap()
+            .set_cell_style(&CellRef::new(1, 1), Some(bold));
+        wb.set_cell(
+            "Summary",
+            CellRef::new(1, 1),
+            CellValue::formula("SUM(Data!B:B)"),
+        )
+        .unwrap();
+        wb.save_as(&file_path).unwrap();
+        file_path
+    }
+
+    #[test]
+    fn test_navigation_and_sheets() {
+        let temp_dir = TempDir::new().unwrap();
+        let workbook = LazyWorkbook::open(create_test_workbook(&temp_dir)).unwrap();
+        let mut viewer = Viewer::new(&workbook).unwrap();
+        let page = (10, 4);
+
+        assert_eq!((viewer.max_row, viewer.max_col), (50, 2));
+        viewer.handle_key(key(KeyCode::PageDown), page).unwrap();
+        assert_eq!(viewer.cursor, (11, 1));
+        assert_eq!(viewer.origin, (2, 1));
+        viewer.handle_key(key(KeyCode::Char('G')), page).unwrap();
+        assert_eq!(viewer.cursor, (50, 1));
+        viewer.handle_key(key(KeyCode::Right), page).unwrap();
+        viewer.handle_key(key(KeyCode::Right), page).unwrap();
+        assert_eq!(viewer.cursor, (50, 2));
+
+        viewer.handle_key(key(KeyCode::Tab), page).unwrap();
+        assert_eq!(viewer.sheet_name(), "Summary");
+        assert_eq!(viewer.cursor, (1, 1));
+        viewer.handle_key(key(KeyCode::BackTab), page).unwrap();
+        assert_eq!(viewer.sheet_name(), "Data");
+        assert!(viewer.handle_key(key(KeyCode::Char('q')), page).unwrap());
+    }
+
+    #[test]
+    fn test_search_inspect_and_render() {
+        let temp_dir = TempDir::new().unwrap();
+        let workbook = LazyWorkbook::open(create_test_workbook(&temp_dir)).unwrap();
+        let mut viewer = Viewer::new(&workbook).unwrap();
+        let page = (10, 4);
+
+        viewer.handle_key(key(KeyCode::Char('/')), page).unwrap();
+        for c in "item 4".chars() {
+            viewer.handle_key(key(KeyCode::Char(c)), page).unwrap();
+        }
+        viewer.handle_key(key(KeyCode::Enter), page).unwrap();
+        assert_eq!(viewer.cursor, (4, 1));
+        viewer.handle_key(key(KeyCode::Char('n')), page).unwrap();
+        assert_eq!(viewer.cursor, (40, 1));
+        assert_eq!(viewer.status, "match 2 of 11");
+        viewer.handle_key(key(KeyCode::Char('N')), page).unwrap();
+        assert_eq!(viewer.cursor, (4, 1));
+
+        viewer.handle_key(key(KeyCode::Char('g')), page).unwrap();
+        viewer.handle_key(key(KeyCode::Enter), page).unwrap();
+        assert!(viewer.status.starts_with("A1 [string]  Item 1"));
+        assert!(viewer.status.contains("bold"));
+
+        viewer.handle_key(key(KeyCode::Tab), page).unwrap();
+        viewer.handle_key(key(KeyCode::Char('i')), page).unwrap();
+        assert_eq!(viewer.status, "A1 [formula]  =SUM(Data!B:B)");
+
+        let mut screen = Vec::new();
+        viewer.render(&mut screen, 60, 15).unwrap();
+        let screen = String::from_utf8_lossy(&screen);
+        assert!(screen.contains(" Summary "));
+        assert!(screen.contains("=SUM(Data!B:B)"));
+        assert!(screen.contains("A1: "));
+    }
+}
@@ -4,9 +4,11 @@ use anyhow::Result;
 use clap::Parser;
 use colored::Colorize;
 
-use xlex_core::Workbook;
+use xlex_core::{CellValue, Workbook, XlexError};
 
-use super::{GlobalOptions, OutputFormat};
+use super::output::CommandOutput;
+use super::{FailOn, GlobalOptions, OutputFormat};
+use crate::progress::Progress;
 
 /// Arguments for the info command.
 #[derive(Parser)]
@@ -19,7 +21,7 @@ pub struct InfoArgs {
 const SESSION_RECOMMEND_SIZE: u64 = 100 * 1024 * 1024;
 
 /// Format file size in human-readable format.
-fn format_file_size(bytes: u64) -> String {
+pub(super) fn format_file_size(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
     const GB: u64 = MB * 1024;
@@ -61,17 +63,17 @@ pub fn info(args: &InfoArgs, global: &GlobalOptions) -> Result<()> {
                 "lastModifiedBy": props.last_modified_by,
             }
         });
-        println!("{}", serde_json::to_string_pretty(&json)?);
+        outln!("{}", serde_json::to_string_pretty(&json)?);
     } else {
-        println!(
+        outln!(
             "{}: {} ({})",
             "File".bold(),
             args.file.display(),
             format_file_size(file_size)
         );
-        println!("{}: {}", "Sheets".bold(), workbook.sheet_count());
+        outln!("{}: {}", "Sheets".bold(), workbook.sheet_count());
 
-        println!("\n{}:", "Sheet Names".bold());
+        outln!("\n{}:", "Sheet Names".bold());
         for (i, name) in workbook.sheet_names().iter().enumerate() {
             let visibility = workbook.get_sheet_visibility(name).unwrap_or_default();
             let vis_str = if visibility.is_hidden() {
@@ -79,37 +81,37 @@ pub fn info(args: &InfoArgs, global: &GlobalOptions) -> Result<()> {
             } else {
                 String::new()
             };
-            println!("  {}. {}{}", i + 1, name, vis_str);
+            outln!("  {}. {}{}", i + 1, name, vis_str);
         }
 
         if props.title.is_some() || props.creator.is_some() || props.subject.is_some() {
-            println!("\n{}:", "Properties".bold());
+            outln!("\n{}:", "Properties".bold());
             if let Some(ref title) = props.title {
-                println!("  {}: {}", "Title".cyan(), title);
+                outln!("  {}: {}", "Title".cyan(), title);
             }
             if let Some(ref creator) = props.creator {
-                println!("  {}: {}", "Creator".cyan(), creator);
+                outln!("  {}: {}", "Creator".cyan(), creator);
             }
             if let Some(ref subject) = props.subject {
-                println!("  {}: {}", "Subject".cyan(), subject);
+                outln!("  {}: {}", "Subject".cyan(), subject);
             }
             if let Some(ref description) = props.description {
-                println!("  {}: {}", "Description".cyan(), description);
+                outln!("  {}: {}", "Description".cyan(), description);
             }
             if let Some(ref keywords) = props.keywords {
-                println!("  {}: {}", "Keywords".cyan(), keywords);
+                outln!("  {}: {}", "Keywords".cyan(), keywords);
             }
         }
 
         // Show session mode recommendation for large files
         if session_recommended && !global.quiet {
-            println!();
-            println!(
+            outln!();
+            outln!(
                 "{} This file is large ({}). For multiple operations, consider using session mode:",
                 "💡".yellow(),
                 format_file_size(file_size).yellow()
             );
-            println!("    {} xlex session {}", "$".dimmed(), args.file.display());
+            outln!("    {} xlex session {}", "$".dimmed(), args.file.display());
         }
     }
 
@@ -121,37 +123,133 @@ pub fn info(args: &InfoArgs, global: &GlobalOptions) -> Result<()> {
 pub struct ValidateArgs {
     /// Path to the xlsx file
     pub file: std::path::PathBuf,
+    /// Findings that fail the command (exit code 12)
+    #[arg(long, value_enum, default_value = "errors")]
+    pub fail_on: FailOn,
+    /// Report spec violations in worksheet XML (duplicate cells, bad
+    /// references, values that do not match their type) as errors
+    #[arg(long)]
+    pub strict: bool,
+    /// Read every part and check it against its CRC-32 checksum, reporting
+    /// damaged parts and, for sheets, the first damaged row
+    #[arg(long)]
+    pub checksums: bool,
 }
 
 /// Validate workbook structure.
+///
+/// A workbook that cannot be parsed is an error; cells holding an error
+/// value (`#REF!`, `#DIV/0!`, ...), directly or as a cached formula
+/// result, are warnings. With `--strict`, each spec violation the parser
+/// recovered from is an error located by part, XML path and byte offset.
+/// With `--checksums`, each part failing its checksum is an error. A
+/// missing or unreadable file fails regardless of `--fail-on`.
 pub fn validate(args: &ValidateArgs, global: &GlobalOptions) -> Result<()> {
-    // Try to open and parse the workbook
+    let mut errors: Vec<String> = Vec::new();
+    let mut warnings: Vec<String> = Vec::new();
+    let mut violations: Vec<xlex_core::Violation> = Vec::new();
+    let mut damaged: Vec<xlex_core::checksum::DamagedPart> = Vec::new();
+
+    if args.checksums {
+        let reader = xlex_core::WorkbookReader::open(&args.file)?;
+        // A file that is not a zip package at all is reported when opened below
+        damaged = xlex_core::checksum::verify_checksums(reader.as_bytes()).unwrap_or_default();
+        errors.extend(damaged.iter().map(|d| match d.damaged_from_row {
+            Some(row) => format!("{}: {}, damaged from row {}", d.part, d.problem, row),
+            None => format!("{}: {}", d.part, d.problem),
+        }));
+    }
+
     match Workbook::open(&args.file) {
-        Ok(_workbook) => {
-            if global.format == OutputFormat::Json {
-                let json = serde_json::json!({
-                    "valid": true,
-                    "file": args.file.display().to_string(),
-                });
-                println!("{}", serde_json::to_string_pretty(&json)?);
-            } else if !global.quiet {
-                println!("{}: {}", "✓".green(), args.file.display());
-                println!("  Workbook is valid");
+        Ok(workbook) => {
+            if args.strict {
+                violations = workbook.parse_violations().to_vec();
+                errors.extend(violations.iter().map(|v| v.to_string()));
             }
-            Ok(())
-        }
-        Err(e) => {
-            if global.format == OutputFormat::Json {
-                let json = serde_json::json!({
-                    "valid": false,
-                    "file": args.file.display().to_string(),
-                    "error": e.to_string(),
-                });
-                println!("{}", serde_json::to_string_pretty(&json)?);
+            for sheet_name in workbook.sheet_names() {
+                let Some(sheet) = workbook.get_sheet(sheet_name) else {
+                    continue;
+                };
+                for cell in sheet.cells() {
+                    let value = match &cell.value {
+                        CellValue::Formula {
+                            cached_result: Some(result),
+                            ..
+                        } => result.as_ref(),
+                        value => value,
+                    };
+                    if let CellValue::Error(e) = value {
+                        warnings.push(format!(
+                            "{}!{}: value is {}",
+                            sheet_name,
+                            cell.reference.to_a1(),
+                            e
+                        ));
+                    }
+                }
             }
-            Err(e.into())
+        }
+        Err(
+            e @ (XlexError::FileNotFound { .. }
+            | XlexError::PermissionDenied { .. }
+            | XlexError::IoError { .. }),
+        ) => return Err(e.into()),
+        Err(e) => errors.push(e.to_string()),
+    }
+
+    let valid = errors.is_empty();
+    if global.format == OutputFormat::Json {
+        let mut json = serde_json::json!({
+            "valid": valid,
+            "file": args.file.display().to_string(),
+            "errors": errors,
+            "warnings": warnings,
+        });
+        if let Some(error) = errors.first() {
+            json["error"] = error.clone().into();
+        }
+        if args.strict {
+            json["violations"] = violations
+                .iter()
+                .map(|v| {
+                    serde_json::json!({
+                        "part": v.part,
+                        "path": v.path,
+                        "offset": v.offset,
+                        "message": v.message,
+                    })
+                })
+                .collect();
+        }
+        if args.checksums {
+            json["damaged"] = damaged
+                .iter()
+                .map(|d| {
+                    serde_json::json!({
+                        "part": d.part,
+                        "problem": d.problem,
+                        "damaged_from_row": d.damaged_from_row,
+                    })
+                })
+                .collect();
+        }
+        outln!("{}", serde_json::to_string_pretty(&json)?);
+    } else if !global.quiet {
+        if valid {
+            outln!("{}: {}", "✓".green(), args.file.display());
+            outln!("  Workbook is valid");
+        } else {
+            outln!("{}: {}", "✗".red(), args.file.display());
+        }
+        for error in &errors {
+            outln!("  {}: {}", "Error".red(), error);
+        }
+        for warning in &warnings {
+            outln!("  {}: {}", "Warning".yellow(), warning);
         }
     }
+
+    args.fail_on.check(errors.len(), warnings.len())
 }
 
 /// Arguments for the clone command.
@@ -177,7 +275,7 @@ pub fn clone(args: &CloneArgs, global: &GlobalOptions) -> Result<()> {
     }
 
     if global.dry_run {
-        println!(
+        outln!(
             "Would copy {} to {}",
             args.source.display(),
             args.dest.display()
@@ -193,9 +291,9 @@ pub fn clone(args: &CloneArgs, global: &GlobalOptions) -> Result<()> {
                 "source": args.source.display().to_string(),
                 "destination": args.dest.display().to_string(),
             });
-            println!("{}", serde_json::to_string_pretty(&json)?);
+            outln!("{}", serde_json::to_string_pretty(&json)?);
         } else {
-            println!(
+            outln!(
                 "Cloned {} to {}",
                 args.source.display().to_string().cyan(),
                 args.dest.display().to_string().green()
@@ -217,6 +315,13 @@ pub struct CreateArgs {
     /// Create multiple sheets (comma-separated)
     #[arg(long)]
     pub sheets: Option<String>,
+    /// Lay out sheets, headers, column types and widths, frozen panes and
+    /// header styles from a YAML schema
+    #[arg(long, value_name = "SCHEMA", conflicts_with = "sheets")]
+    pub from_schema: Option<std::path::PathBuf>,
+    /// Record the content hash and time in custom properties (see `xlex hash`)
+    #[arg(long)]
+    pub stamp_hash: bool,
     /// Overwrite existing file
     #[arg(long, short = 'F')]
     pub force: bool,
@@ -232,15 +337,23 @@ pub fn create(args: &CreateArgs, global: &GlobalOptions) -> Result<()> {
         .into());
     }
 
+    let schema = args
+        .from_schema
+        .as_deref()
+        .map(super::schema::Schema::load)
+        .transpose()?;
+
     // Determine sheets to create
-    let sheet_names: Vec<&str> = if let Some(ref sheets) = args.sheets {
+    let sheet_names: Vec<&str> = if let Some(ref schema) = schema {
+        schema.sheet_names()
+    } else if let Some(ref sheets) = args.sheets {
         sheets.split(',').map(|s| s.trim()).collect()
     } else {
         vec![args.sheet.as_str()]
     };
 
     if global.dry_run {
-        println!(
+        outln!(
             "Would create {} with sheets: {:?}",
             args.file.display(),
             sheet_names
@@ -249,22 +362,34 @@ pub fn create(args: &CreateArgs, global: &GlobalOptions) -> Result<()> {
     }
 
     // Create workbook
-    let workbook = Workbook::with_sheets(&sheet_names);
+    let mut workbook = match &schema {
+        Some(schema) => schema.build()?,
+        None => Workbook::with_sheets(&sheet_names),
+    };
+    let stamped = args
+        .stamp_hash
+        .then(|| xlex_core::stamp::stamp(&mut workbook));
     workbook.save_as(&args.file)?;
 
     if !global.quiet {
         if global.format == OutputFormat::Json {
-            let json = serde_json::json!({
+            let mut json = serde_json::json!({
                 "file": args.file.display().to_string(),
                 "sheets": sheet_names,
             });
-            println!("{}", serde_json::to_string_pretty(&json)?);
+            if let Some(hash) = &stamped {
+                json["contentHash"] = hash.as_str().into();
+            }
+            outln!("{}", serde_json::to_string_pretty(&json)?);
         } else {
-            println!(
+            outln!(
                 "Created {} with {} sheet(s)",
                 args.file.display().to_string().green(),
                 sheet_names.len()
             );
+            if let Some(hash) = &stamped {
+                outln!("Stamped content hash {}", hash.cyan());
+            }
         }
     }
 
@@ -322,9 +447,9 @@ pub fn props(args: &PropsArgs, global: &GlobalOptions) -> Result<()> {
                         "property": prop_name,
                         "value": value,
                     });
-                    println!("{}", serde_json::to_string_pretty(&json)?);
+                    outln!("{}", serde_json::to_string_pretty(&json)?);
                 } else {
-                    println!("{}", value.unwrap_or_default());
+                    outln!("{}", value.unwrap_or_default());
                 }
             } else {
                 // Show all properties
@@ -338,28 +463,28 @@ pub fn props(args: &PropsArgs, global: &GlobalOptions) -> Result<()> {
                         "lastModifiedBy": props.last_modified_by,
                         "category": props.category,
                     });
-                    println!("{}", serde_json::to_string_pretty(&json)?);
+                    outln!("{}", serde_json::to_string_pretty(&json)?);
                 } else {
                     if let Some(ref v) = props.title {
-                        println!("{}: {}", "title".cyan(), v);
+                        outln!("{}: {}", "title".cyan(), v);
                     }
                     if let Some(ref v) = props.subject {
-                        println!("{}: {}", "subject".cyan(), v);
+                        outln!("{}: {}", "subject".cyan(), v);
                     }
                     if let Some(ref v) = props.creator {
-                        println!("{}: {}", "creator".cyan(), v);
+                        outln!("{}: {}", "creator".cyan(), v);
                     }
                     if let Some(ref v) = props.keywords {
-                        println!("{}: {}", "keywords".cyan(), v);
+                        outln!("{}: {}", "keywords".cyan(), v);
                     }
                     if let Some(ref v) = props.description {
-                        println!("{}: {}", "description".cyan(), v);
+                        outln!("{}: {}", "description".cyan(), v);
                     }
                     if let Some(ref v) = props.last_modified_by {
-                        println!("{}: {}", "lastModifiedBy".cyan(), v);
+                        outln!("{}: {}", "lastModifiedBy".cyan(), v);
                     }
                     if let Some(ref v) = props.category {
-                        println!("{}: {}", "category".cyan(), v);
+                        outln!("{}: {}", "category".cyan(), v);
                     }
                 }
             }
@@ -370,7 +495,7 @@ pub fn props(args: &PropsArgs, global: &GlobalOptions) -> Result<()> {
             value,
         } => {
             if global.dry_run {
-                println!(
+                outln!(
                     "Would set {} to '{}' in {}",
                     property,
                     value,
@@ -379,6 +504,7 @@ pub fn props(args: &PropsArgs, global: &GlobalOptions) -> Result<()> {
                 return Ok(());
             }
 
+            let output = CommandOutput::new("set-property");
             let mut workbook = Workbook::open(file)?;
             let props = workbook.properties_mut();
 
@@ -395,9 +521,14 @@ pub fn props(args: &PropsArgs, global: &GlobalOptions) -> Result<()> {
 
             workbook.save()?;
 
-            if !global.quiet {
-                println!("Set {} to '{}'", property.cyan(), value.green());
-            }
+            output
+                .file(file)
+                .target("property", property.as_str())
+                .field("value", value.as_str())
+                .print(
+                    global,
+                    format!("Set {} to '{}'", property.cyan(), value.green()),
+                )?;
         }
     }
 
@@ -417,19 +548,638 @@ pub fn stats(args: &StatsArgs, global: &GlobalOptions) -> Result<()> {
     let stats = workbook.stats();
 
     if global.format == OutputFormat::Json {
-        println!("{}", serde_json::to_string_pretty(&stats)?);
+        outln!("{}", serde_json::to_string_pretty(&stats)?);
     } else {
-        println!("{}:", "Workbook Statistics".bold());
-        println!("  {}: {}", "Sheets".cyan(), stats.sheet_count);
-        println!("  {}: {}", "Total Cells".cyan(), stats.total_cells);
-        println!("  {}: {}", "Formulas".cyan(), stats.formula_count);
-        println!("  {}: {}", "Styles".cyan(), stats.style_count);
-        println!("  {}: {}", "Shared Strings".cyan(), stats.string_count);
+        outln!("{}:", "Workbook Statistics".bold());
+        outln!("  {}: {}", "Sheets".cyan(), stats.sheet_count);
+        outln!("  {}: {}", "Total Cells".cyan(), stats.total_cells);
+        outln!("  {}: {}", "Formulas".cyan(), stats.formula_count);
+        outln!("  {}: {}", "Styles".cyan(), stats.style_count);
+        outln!("  {}: {}", "Shared Strings".cyan(), stats.string_count);
         if stats.file_size > 0 {
-            println!("  {}: {} bytes", "File Size".cyan(), stats.file_size);
+            outln!("  {}: {} bytes", "File Size".cyan(), stats.file_size);
+        }
+        outln!(
+            "  {}: ~{}",
+            "Estimated Memory".cyan(),
+            format_file_size(stats.estimated_memory)
+        );
+
+        outln!();
+        outln!("{}:", "Sheets".bold());
+        for sheet in &stats.sheets {
+            outln!(
+                "  {}: {} cells, {} formulas, {} unique strings, {} styles, ~{}",
+                sheet.name.cyan(),
+                sheet.cell_count,
+                sheet.formula_count,
+                sheet.unique_strings,
+                sheet.style_count,
+                format_file_size(sheet.estimated_memory)
+            );
+            if sheet.largest_row > 0 {
+                outln!(
+                    "    largest row: {} ({} cells)",
+                    sheet.largest_row,
+                    sheet.largest_row_cells
+                );
+            }
+        }
+
+        if !stats.parts.is_empty() {
+            outln!();
+            outln!("{}:", "Parts (uncompressed / compressed)".bold());
+            for part in &stats.parts {
+                let ratio = if part.compressed_size > 0 {
+                    format!(
+                        " ({:.1}x)",
+                        part.uncompressed_size as f64 / part.compressed_size as f64
+                    )
+                } else {
+                    String::new()
+                };
+                outln!(
+                    "  {}: {} / {}{}",
+                    part.name,
+                    format_file_size(part.uncompressed_size),
+                    format_file_size(part.compressed_size),
+                    ratio
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Arguments for the optimize command.
+#[derive(Parser)]
+pub struct OptimizeArgs {
+    /// Path to the xlsx file
+    pub file: std::path::PathBuf,
+    /// Write the optimized workbook here instead of overwriting the file
+    #[arg(long)]
+    pub out: Option<std::path::PathBuf>,
+    /// Overwrite the output file if it exists
+    #[arg(long, short = 'F')]
+    pub force: bool,
+}
+
+/// Shrink a workbook and report the size before and after.
+pub fn optimize(args: &OptimizeArgs, global: &GlobalOptions) -> Result<()> {
+    let dest = args.out.as_deref().unwrap_or(&args.file);
+    if args.out.is_some() && dest.exists() && !args.force {
+        return Err(xlex_core::XlexError::FileExists {
+            path: dest.to_path_buf(),
+        }
+        .into());
+    }
+
+    let size_before = std::fs::metadata(&args.file)?.len();
+    let progress = Progress::phases("Reading workbook...", global);
+    let mut workbook = Workbook::open(&args.file)?;
+    if let Some(ref pb) = progress {
+        pb.set_message("Removing unused styles and blank cells...");
+    }
+    let report = workbook.optimize();
+
+    if global.dry_run {
+        outln!(
+            "Would optimize {} into {} ({} unused or duplicate styles, {} blank cells)",
+            args.file.display(),
+            dest.display(),
+            report.styles_before - report.styles_after,
+            report.blank_cells_removed
+        );
+        if let Some(ref pb) = progress {
+            pb.finish_and_clear();
+        }
+        return Ok(());
+    }
+
+    if let Some(ref pb) = progress {
+        pb.set_message("Writing compressed workbook...");
+    }
+    workbook.save_as_compressed(dest, 9)?;
+    if let Some(ref pb) = progress {
+        pb.finish_and_clear();
+    }
+    let size_after = std::fs::metadata(dest)?.len();
+
+    if !global.quiet {
+        if global.format == OutputFormat::Json {
+            let json = serde_json::json!({
+                "file": args.file.display().to_string(),
+                "output": dest.display().to_string(),
+                "sizeBefore": size_before,
+                "sizeAfter": size_after,
+                "styles": { "before": report.styles_before, "after": report.styles_after },
+                "numberFormats": {
+                    "before": report.number_formats_before,
+                    "after": report.number_formats_after,
+                },
+                "sharedStrings": {
+                    "before": report.shared_strings_before,
+                    "after": report.shared_strings_after,
+                },
+                "blankCellsRemoved": report.blank_cells_removed,
+                "rowHeightsRemoved": report.row_heights_removed,
+                "columnWidthsRemoved": report.column_widths_removed,
+            });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else {
+            outln!(
+                "Optimized {} -> {}",
+                args.file.display().to_string().cyan(),
+                dest.display().to_string().green()
+            );
+            outln!(
+                "  {}: {} -> {}",
+                "Size".cyan(),
+                format_file_size(size_before),
+                format_file_size(size_after)
+            );
+            outln!(
+                "  {}: {} -> {}",
+                "Styles".cyan(),
+                report.styles_before,
+                report.styles_after
+            );
+            outln!(
+                "  {}: {} -> {}",
+                "Number Formats".cyan(),
+                report.number_formats_before,
+                report.number_formats_after
+            );
+            outln!(
+                "  {}: {} -> {}",
+                "Shared Strings".cyan(),
+                report.shared_strings_before,
+                report.shared_strings_after
+            );
+            outln!(
+                "  {}: {} cells, {} row heights, {} column widths",
+                "Trimmed".cyan(),
+                report.blank_cells_removed,
+                report.row_heights_removed,
+                report.column_widths_removed
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Arguments for the scrub command.
+#[derive(Parser)]
+pub struct ScrubArgs {
+    /// Path to the xlsx file
+    pub file: std::path::PathBuf,
+    /// Write the scrubbed workbook here instead of overwriting the file
+    #[arg(long)]
+    pub out: Option<std::path::PathBuf>,
+    /// Overwrite the output file if it exists
+    #[arg(long, short = 'F')]
+    pub force: bool,
+}
+
+/// Remove personal metadata and report everything removed.
+pub fn scrub(args: &ScrubArgs, global: &GlobalOptions) -> Result<()> {
+    let dest = args.out.as_deref().unwrap_or(&args.file);
+    if args.out.is_some() && dest.exists() && !args.force {
+        return Err(xlex_core::XlexError::FileExists {
+            path: dest.to_path_buf(),
+        }
+        .into());
+    }
+
+    let found = xlex_core::LazyWorkbook::open(&args.file)?.personal_metadata()?;
+
+    if global.dry_run {
+        outln!(
+            "Would remove {} metadata item(s) from {} into {}",
+            found.len(),
+            args.file.display(),
+            dest.display()
+        );
+        return Ok(());
+    }
+
+    let mut workbook = Workbook::open(&args.file)?;
+    workbook.remove_personal_info();
+    workbook.save_as(dest)?;
+
+    if !global.quiet {
+        if global.format == OutputFormat::Json {
+            let removed: Vec<serde_json::Value> = found
+                .iter()
+                .map(|m| {
+                    serde_json::json!({
+                        "kind": m.kind.to_string(),
+                        "part": m.part,
+                        "value": m.value,
+                    })
+                })
+                .collect();
+            let json = serde_json::json!({
+                "file": args.file.display().to_string(),
+                "output": dest.display().to_string(),
+                "removed": removed,
+            });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else {
+            outln!(
+                "Scrubbed {} -> {}",
+                args.file.display().to_string().cyan(),
+                dest.display().to_string().green()
+            );
+            if found.is_empty() {
+                outln!("  No personal metadata found");
+            }
+            for m in &found {
+                outln!("  {}: {} ({})", m.kind.to_string().cyan(), m.value, m.part);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Arguments for the index command.
+#[derive(Parser)]
+pub struct IndexArgs {
+    /// Path to the xlsx file
+    pub file: std::path::PathBuf,
+    /// Delete the index file instead of writing it
+    #[arg(long)]
+    pub remove: bool,
+}
+
+/// Write or remove the index file that lets a large workbook reopen
+/// without scanning its shared strings and sheets.
+pub fn index(args: &IndexArgs, global: &GlobalOptions) -> Result<()> {
+    let index_path = xlex_core::index_file::IndexFile::path_for(&args.file);
+
+    if args.remove {
+        let existed = index_path.exists();
+        if existed && !global.dry_run {
+            std::fs::remove_file(&index_path)?;
+        }
+        if !global.quiet {
+            if global.format == OutputFormat::Json {
+                let json = serde_json::json!({
+                    "file": args.file.display().to_string(),
+                    "index": index_path.display().to_string(),
+                    "removed": existed,
+                });
+                outln!("{}", serde_json::to_string_pretty(&json)?);
+            } else if !existed {
+                outln!("No index file for {}", args.file.display());
+            } else if global.dry_run {
+                outln!("Would remove {}", index_path.display());
+            } else {
+                outln!("Removed {}", index_path.display().to_string().cyan());
+            }
+        }
+        return Ok(());
+    }
+
+    let workbook = xlex_core::LazyWorkbook::open(&args.file)?;
+    if global.dry_run {
+        outln!(
+            "Would index {} into {}",
+            args.file.display(),
+            index_path.display()
+        );
+        return Ok(());
+    }
+    workbook.write_index(&args.file)?;
+
+    if !global.quiet {
+        let mut sheets = Vec::new();
+        for name in workbook.sheet_names() {
+            let rows = workbook.row_index(name)?;
+            let dimension = rows.dimension.as_ref().map(|range| range.to_a1());
+            sheets.push((name, rows.last_row, dimension));
+        }
+        if global.format == OutputFormat::Json {
+            let sheets: Vec<serde_json::Value> = sheets
+                .iter()
+                .map(|(name, last_row, dimension)| {
+                    serde_json::json!({
+                        "name": name,
+                        "last_row": last_row,
+                        "dimension": dimension,
+                    })
+                })
+                .collect();
+            let json = serde_json::json!({
+                "file": args.file.display().to_string(),
+                "index": index_path.display().to_string(),
+                "sheets": sheets,
+            });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else {
+            outln!(
+                "Indexed {} -> {}",
+                args.file.display().to_string().cyan(),
+                index_path.display().to_string().green()
+            );
+            for (name, last_row, dimension) in &sheets {
+                outln!(
+                    "  {}: {} rows{}",
+                    name.cyan(),
+                    last_row,
+                    dimension
+                        .as_ref()
+                        .map(|d| format!(", {}", d))
+                        .unwrap_or_default()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Arguments for the hash command.
+#[derive(Parser)]
+pub struct HashArgs {
+    /// Path to the xlsx file
+    pub file: std::path::PathBuf,
+    /// Record the hash and the current time in custom document properties
+    #[arg(long, conflicts_with = "verify")]
+    pub stamp: bool,
+    /// Check the content against the stamped hash (exit 12 if it differs
+    /// or the workbook has no stamp)
+    #[arg(long)]
+    pub verify: bool,
+}
+
+/// Show, stamp or verify a workbook's canonical content hash: the SHA-256
+/// of its sheet names and cell contents, ignoring formatting.
+pub fn hash(args: &HashArgs, global: &GlobalOptions) -> Result<()> {
+    use xlex_core::stamp::{self, StampCheck};
+
+    let mut workbook = Workbook::open(&args.file)?;
+    let file = args.file.display().to_string();
+
+    if args.verify {
+        let check = stamp::verify(&workbook);
+        let (status, stamped, actual, generated_at) = match &check {
+            StampCheck::Valid { hash, generated_at } => ("valid", Some(hash), hash, generated_at),
+            StampCheck::Mismatch {
+                expected,
+                actual,
+                generated_at,
+            } => ("mismatch", Some(expected), actual, generated_at),
+            StampCheck::Unstamped { actual } => ("unstamped", None, actual, &None),
+        };
+        if !global.quiet {
+            if global.format == OutputFormat::Json {
+                let json = serde_json::json!({
+                    "file": file,
+                    "status": status,
+                    "stamped": stamped,
+                    "actual": actual,
+                    "generatedAt": generated_at,
+                });
+                outln!("{}", serde_json::to_string_pretty(&json)?);
+            } else if status == "valid" {
+                outln!(
+                    "{} {} matches its stamp{}",
+                    "✓".green(),
+                    file.cyan(),
+                    generated_at
+                        .as_ref()
+                        .map(|at| format!(" from {}", at))
+                        .unwrap_or_default()
+                );
+            }
+        }
+        return match &check {
+            StampCheck::Valid { .. } => Ok(()),
+            StampCheck::Mismatch { expected, .. } => Err(XlexError::ValidationFailed {
+                message: format!(
+                    "{} changed since it was stamped: content hash {} does not match {}",
+                    file, actual, expected
+                ),
+            }
+            .into()),
+            StampCheck::Unstamped { .. } => Err(XlexError::ValidationFailed {
+                message: format!(
+                    "{} has no content hash stamp (add one with `xlex hash --stamp`)",
+                    file
+                ),
+            }
+            .into()),
+        };
+    }
+
+    if args.stamp {
+        if global.dry_run {
+            outln!("Would stamp the content hash of {}", file);
+            return Ok(());
+        }
+        let hash = stamp::stamp(&mut workbook);
+        workbook.save()?;
+        if !global.quiet {
+            if global.format == OutputFormat::Json {
+                let json =
+                    serde_json::json!({ "file": file, "contentHash": hash, "stamped": true });
+                outln!("{}", serde_json::to_string_pretty(&json)?);
+            } else {
+                outln!("Stamped {} with {}", file.cyan(), hash.green());
+            }
         }
+        return Ok(());
+    }
+
+    let hash = stamp::content_hash(&workbook);
+    if global.format == OutputFormat::Json {
+        let json = serde_json::json!({ "file": file, "contentHash": hash, "stamped": false });
+        outln!("{}", serde_json::to_string_pretty(&json)?);
+    } else {
+        outln!("{}", hash);
     }
+    Ok(())
+}
+
+/// Arguments for the sign command.
+#[derive(Parser)]
+pub struct SignArgs {
+    /// Path to the xlsx file
+    pub file: std::path::PathBuf,
+    /// PKCS#12 file (.p12/.pfx) holding the signing key and certificate
+    #[arg(long)]
+    pub pfx: std::path::PathBuf,
+    /// Password of the PKCS#12 file
+    #[arg(
+        long,
+        env = "XLEX_PFX_PASSWORD",
+        hide_env_values = true,
+        default_value = ""
+    )]
+    pub password: String,
+    /// Write the signed workbook here instead of overwriting the file
+    #[arg(long)]
+    pub out: Option<std::path::PathBuf>,
+    /// Overwrite the output file if it exists
+    #[arg(long, short = 'F')]
+    pub force: bool,
+}
+
+/// Arguments for the verify-signature command.
+#[derive(Parser)]
+pub struct VerifySignatureArgs {
+    /// Path to the xlsx file
+    pub file: std::path::PathBuf,
+    /// PEM file of trusted CA certificates the signers must chain to
+    #[arg(long)]
+    pub ca: Option<std::path::PathBuf>,
+}
+
+#[cfg(not(feature = "signing"))]
+pub fn sign(_args: &SignArgs, _global: &GlobalOptions) -> Result<()> {
+    anyhow::bail!("sign needs xlex built with the signing feature")
+}
+
+#[cfg(not(feature = "signing"))]
+pub fn verify_signature(_args: &VerifySignatureArgs, _global: &GlobalOptions) -> Result<()> {
+    anyhow::bail!("verify-signature needs xlex built with the signing feature")
+}
 
+/// Add a digital signature to a workbook. Every part is signed, so this
+/// should be the last change made to the file.
+#[cfg(feature = "signing")]
+pub fn sign(args: &SignArgs, global: &GlobalOptions) -> Result<()> {
+    use xlex_core::signature::{self, SigningIdentity};
+
+    let dest = args.out.as_deref().unwrap_or(&args.file);
+    if args.out.is_some() && dest.exists() && !args.force {
+        return Err(XlexError::FileExists {
+            path: dest.to_path_buf(),
+        }
+        .into());
+    }
+    let pfx = std::fs::read(&args.pfx)
+        .map_err(|e| anyhow::anyhow!("Cannot read {}: {}", args.pfx.display(), e))?;
+    let identity = SigningIdentity::from_pkcs12(&pfx, &args.password)?;
+    let data = std::fs::read(&args.file)?;
+    if global.dry_run {
+        outln!(
+            "Would sign {} as {}",
+            args.file.display(),
+            identity.subject()
+        );
+        return Ok(());
+    }
+    let signed = signature::sign(&data, &identity)?;
+    std::fs::write(dest, signed)?;
+
+    CommandOutput::new("sign")
+        .file(dest)
+        .field("signer", identity.subject())
+        .print(
+            global,
+            format!(
+                "Signed {} as {}",
+                dest.display().to_string().cyan(),
+                identity.subject().green()
+            ),
+        )?;
+    Ok(())
+}
+
+/// Check every digital signature in a workbook (exit 12 if there is none
+/// or any fails).
+#[cfg(feature = "signing")]
+pub fn verify_signature(args: &VerifySignatureArgs, global: &GlobalOptions) -> Result<()> {
+    use xlex_core::signature::{self, SignatureStatus};
+
+    let data = std::fs::read(&args.file)?;
+    let ca = match &args.ca {
+        Some(path) => Some(
+            std::fs::read(path)
+                .map_err(|e| anyhow::anyhow!("Cannot read {}: {}", path.display(), e))?,
+        ),
+        None => None,
+    };
+    let reports = signature::verify(&data, ca.as_deref())?;
+    let file = args.file.display().to_string();
+    let valid = !reports.is_empty()
+        && reports
+            .iter()
+            .all(|report| report.status == SignatureStatus::Valid);
+
+    if !global.quiet {
+        if global.format == OutputFormat::Json {
+            let signatures: Vec<_> = reports
+                .iter()
+                .map(|report| {
+                    serde_json::json!({
+                        "part": report.part,
+                        "status": if report.status == SignatureStatus::Valid { "valid" } else { "invalid" },
+                        "signer": report.signer,
+                        "issuer": report.issuer,
+                        "notBefore": report.not_before,
+                        "notAfter": report.not_after,
+                        "signingTime": report.signing_time,
+                        "trusted": report.trusted,
+                        "signedParts": report.signed_parts,
+                        "problems": report.problems,
+                    })
+                })
+                .collect();
+            let json = serde_json::json!({
+                "file": file,
+                "valid": valid,
+                "signatures": signatures,
+            });
+            outln!("{}", serde_json::to_string_pretty(&json)?);
+        } else {
+            for report in &reports {
+                let when = report
+                    .signing_time
+                    .as_ref()
+                    .map(|at| format!(" at {}", at))
+                    .unwrap_or_default();
+                if report.status == SignatureStatus::Valid {
+                    outln!(
+                        "{} {} signed by {}{}",
+                        "✓".green(),
+                        report.part,
+                        report.signer.cyan(),
+                        when
+                    );
+                } else {
+                    outln!(
+                        "{} {} signed by {}{}",
+                        "✗".red(),
+                        report.part,
+                        report.signer.cyan(),
+                        when
+                    );
+                    for problem in &report.problems {
+                        outln!("    {}", problem);
+                    }
+                }
+            }
+        }
+    }
+
+    if reports.is_empty() {
+        return Err(XlexError::ValidationFailed {
+            message: format!("{} is not signed", file),
+        }
+        .into());
+    }
+    if !valid {
+        return Err(XlexError::ValidationFailed {
+            message: format!("{} has an invalid signature", file),
+        }
+        .into());
+    }
     Ok(())
 }
 
@@ -448,6 +1198,11 @@ mod tests {
             json_errors: false,
             dry_run: false,
             output: None,
+            append: false,
+            tee: false,
+            no_progress: false,
+            trace_output: None,
+            fuzzy_sheets: false,
         }
     }
 
@@ -460,6 +1215,8 @@ mod tests {
             file: file_path.clone(),
             sheet: "Sheet1".to_string(),
             sheets: None,
+            from_schema: None,
+            stamp_hash: false,
             force: false,
         };
 
@@ -477,6 +1234,8 @@ mod tests {
             file: file_path.clone(),
             sheet: "Sheet1".to_string(),
             sheets: Some("Data,Summary,Config".to_string()),
+            from_schema: None,
+            stamp_hash: false,
             force: false,
         };
 
@@ -488,6 +1247,105 @@ mod tests {
         assert_eq!(wb.sheet_count(), 3);
     }
 
+    #[test]
+    fn test_create_from_schema() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("entry.xlsx");
+        let schema_path = temp_dir.path().join("schema.yml");
+        std::fs::write(
+            &schema_path,
+            "sheets:\n  - name: Orders\n    freeze: header\n    table_style: light\n    columns:\n      - { header: Code, type: text, width: 14 }\n      - { header: Paid, type: bool }\n  - name: Lists\n",
+        )
+        .unwrap();
+
+        let mut args = CreateArgs {
+            file: file_path.clone(),
+            sheet: "Sheet1".to_string(),
+            sheets: None,
+            from_schema: Some(schema_path.clone()),
+            stamp_hash: false,
+            force: false,
+        };
+        create(&args, &default_global()).unwrap();
+
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(wb.sheet_names(), vec!["Orders", "Lists"]);
+        let sheet = wb.get_sheet("Orders").unwrap();
+        assert_eq!(
+            sheet.get_value(&xlex_core::CellRef::new(2, 1)),
+            CellValue::String("Paid".to_string())
+        );
+        assert_eq!(sheet.get_column_width(1), Some(14.0));
+        assert_eq!(sheet.freeze_panes(), Some((1, 0)));
+        assert_eq!(sheet.dropdowns().len(), 1);
+        let style_id = sheet.get_column_style(1).unwrap();
+        assert_eq!(
+            wb.style_registry().get(style_id).unwrap().number_format,
+            xlex_core::style::NumberFormat::text()
+        );
+
+        std::fs::write(&schema_path, "sheets: []\n").unwrap();
+        args.force = true;
+        assert!(create(&args, &default_global()).is_err());
+    }
+
+    #[test]
+    fn test_hash_stamp_and_verify() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("stamped.xlsx");
+        let args = CreateArgs {
+            file: file_path.clone(),
+            sheet: "Data".to_string(),
+            sheets: None,
+            from_schema: None,
+            stamp_hash: true,
+            force: false,
+        };
+        create(&args, &default_global()).unwrap();
+
+        let verify = HashArgs {
+            file: file_path.clone(),
+            stamp: false,
+            verify: true,
+        };
+        hash(&verify, &default_global()).unwrap();
+
+        let mut wb = Workbook::open(&file_path).unwrap();
+        wb.set_cell("Data", xlex_core::CellRef::new(1, 1), CellValue::from("x"))
+            .unwrap();
+        wb.save().unwrap();
+        let err = hash(&verify, &default_global()).unwrap_err();
+        assert_eq!(err.downcast_ref::<XlexError>().unwrap().exit_code(), 12);
+        assert!(err.to_string().contains("changed since it was stamped"));
+
+        let stamp = HashArgs {
+            file: file_path.clone(),
+            stamp: true,
+            verify: false,
+        };
+        hash(&stamp, &default_global()).unwrap();
+        hash(&verify, &default_global()).unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(
+            wb.properties()
+                .custom_property(xlex_core::stamp::HASH_PROPERTY)
+                .map(str::to_string),
+            Some(xlex_core::stamp::content_hash(&wb))
+        );
+
+        let plain = temp_dir.path().join("plain.xlsx");
+        Workbook::new().save_as(&plain).unwrap();
+        let unstamped = HashArgs {
+            file: plain,
+            stamp: false,
+            verify: true,
+        };
+        assert!(hash(&unstamped, &default_global())
+            .unwrap_err()
+            .to_string()
+            .contains("no content hash stamp"));
+    }
+
     #[test]
     fn test_create_workbook_exists_no_force() {
         let temp_dir = TempDir::new().unwrap();
@@ -498,6 +1356,8 @@ mod tests {
             file: file_path.clone(),
             sheet: "Sheet1".to_string(),
             sheets: None,
+            from_schema: None,
+            stamp_hash: false,
             force: false,
         };
         create(&args, &default_global()).unwrap();
@@ -517,6 +1377,8 @@ mod tests {
             file: file_path.clone(),
             sheet: "Sheet1".to_string(),
             sheets: None,
+            from_schema: None,
+            stamp_hash: false,
             force: false,
         };
         create(&args, &default_global()).unwrap();
@@ -526,6 +1388,8 @@ mod tests {
             file: file_path.clone(),
             sheet: "NewSheet".to_string(),
             sheets: None,
+            from_schema: None,
+            stamp_hash: false,
             force: true,
         };
         let result = create(&args, &default_global());
@@ -544,6 +1408,8 @@ mod tests {
             file: file_path.clone(),
             sheet: "Sheet1".to_string(),
             sheets: None,
+            from_schema: None,
+            stamp_hash: false,
             force: false,
         };
 
@@ -562,7 +1428,12 @@ mod tests {
         let wb = Workbook::new();
         wb.save_as(&file_path).unwrap();
 
-        let args = ValidateArgs { file: file_path };
+        let args = ValidateArgs {
+            file: file_path,
+            fail_on: FailOn::Errors,
+            strict: false,
+            checksums: false,
+        };
 
         let result = validate(&args, &default_global());
         assert!(result.is_ok());
@@ -576,12 +1447,157 @@ mod tests {
         // Create an invalid file
         std::fs::write(&file_path, "not a valid xlsx").unwrap();
 
-        let args = ValidateArgs { file: file_path };
+        let args = ValidateArgs {
+            file: file_path,
+            fail_on: FailOn::Errors,
+            strict: false,
+            checksums: false,
+        };
 
         let result = validate(&args, &default_global());
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_validate_fail_on() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("errors.xlsx");
+        let mut wb = Workbook::new();
+        wb.set_cell(
+            "Sheet1",
+            xlex_core::CellRef::new(1, 1),
+            CellValue::Error(xlex_core::cell::CellError::DivZero),
+        )
+        .unwrap();
+        wb.save_as(&file_path).unwrap();
+
+        let mut args = ValidateArgs {
+            file: file_path,
+            fail_on: FailOn::Errors,
+            strict: false,
+            checksums: false,
+        };
+        assert!(validate(&args, &default_global()).is_ok());
+        args.fail_on = FailOn::Warnings;
+        let err = validate(&args, &default_global()).unwrap_err();
+        assert_eq!(err.downcast_ref::<XlexError>().unwrap().exit_code(), 12);
+
+        // Unparseable files fail unless --fail-on never; missing ones always
+        std::fs::write(&args.file, "not a valid xlsx").unwrap();
+        args.fail_on = FailOn::Never;
+        assert!(validate(&args, &default_global()).is_ok());
+        args.file = temp_dir.path().join("missing.xlsx");
+        let err = validate(&args, &default_global()).unwrap_err();
+        assert_eq!(err.downcast_ref::<XlexError>().unwrap().exit_code(), 3);
+    }
+
+    #[test]
+    fn test_validate_strict() {
+        use std::io::{Read, Write};
+
+        let temp_dir = TempDir::new().unwrap();
+        let plain = temp_dir.path().join("plain.xlsx");
+        let mut wb = Workbook::new();
+        wb.set_cell(
+            "Sheet1",
+            xlex_core::CellRef::new(1, 1),
+            CellValue::Number(1.0),
+        )
+        .unwrap();
+        wb.set_cell(
+            "Sheet1",
+            xlex_core::CellRef::new(2, 1),
+            CellValue::Number(2.0),
+        )
+        .unwrap();
+        wb.save_as(&plain).unwrap();
+
+        // Rewrite B1 as a second A1
+        let file_path = temp_dir.path().join("duplicate.xlsx");
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&plain).unwrap()).unwrap();
+        let mut out = zip::ZipWriter::new(std::fs::File::create(&file_path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).unwrap();
+            let entry_name = entry.name().to_string();
+            let mut content = String::new();
+            entry.read_to_string(&mut content).unwrap();
+            if entry_name == "xl/worksheets/sheet1.xml" {
+                content = content.replace(r#"r="B1""#, r#"r="A1""#);
+            }
+            out.start_file(entry_name, options).unwrap();
+            out.write_all(content.as_bytes()).unwrap();
+        }
+        out.finish().unwrap();
+
+        let mut args = ValidateArgs {
+            file: file_path,
+            fail_on: FailOn::Errors,
+            strict: false,
+            checksums: false,
+        };
+        assert!(validate(&args, &default_global()).is_ok());
+        args.strict = true;
+        let err = validate(&args, &default_global()).unwrap_err();
+        assert_eq!(err.downcast_ref::<XlexError>().unwrap().exit_code(), 12);
+        args.fail_on = FailOn::Never;
+        let mut global = default_global();
+        global.format = OutputFormat::Json;
+        assert!(validate(&args, &global).is_ok());
+    }
+
+    #[test]
+    fn test_validate_checksums() {
+        use std::io::{Read, Write};
+
+        let temp_dir = TempDir::new().unwrap();
+        let plain = temp_dir.path().join("plain.xlsx");
+        let mut wb = Workbook::new();
+        for row in 1..=3 {
+            wb.set_cell(
+                "Sheet1",
+                xlex_core::CellRef::new(1, row),
+                CellValue::Number(row.into()),
+            )
+            .unwrap();
+        }
+        wb.save_as(&plain).unwrap();
+
+        // Store parts uncompressed, then break row 2's XML in place
+        let file_path = temp_dir.path().join("damaged.xlsx");
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&plain).unwrap()).unwrap();
+        let mut out = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).unwrap();
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content).unwrap();
+            out.start_file(entry.name(), options).unwrap();
+            out.write_all(&content).unwrap();
+        }
+        let mut data = out.finish().unwrap().into_inner();
+        let at = data.windows(8).position(|w| w == b"<v>2</v>").unwrap();
+        data[at + 6] = b'x';
+        std::fs::write(&file_path, data).unwrap();
+
+        let mut args = ValidateArgs {
+            file: file_path,
+            fail_on: FailOn::Never,
+            strict: false,
+            checksums: true,
+        };
+        let mut global = default_global();
+        global.format = OutputFormat::Json;
+        assert!(validate(&args, &global).is_ok());
+        args.fail_on = FailOn::Errors;
+        let err = validate(&args, &default_global()).unwrap_err();
+        assert_eq!(err.downcast_ref::<XlexError>().unwrap().exit_code(), 12);
+
+        args.file = plain;
+        assert!(validate(&args, &default_global()).is_ok());
+    }
+
     #[test]
     fn test_validate_json_output() {
         let temp_dir = TempDir::new().unwrap();
@@ -593,7 +1609,12 @@ mod tests {
         let mut global = default_global();
         global.format = OutputFormat::Json;
 
-        let args = ValidateArgs { file: file_path };
+        let args = ValidateArgs {
+            file: file_path,
+            fail_on: FailOn::Errors,
+            strict: false,
+            checksums: false,
+        };
 
         let result = validate(&args, &global);
         assert!(result.is_ok());
@@ -741,6 +1762,8 @@ mod tests {
             file: file_path,
             sheet: "Sheet1".to_string(),
             sheets: None,
+            from_schema: None,
+            stamp_hash: false,
             force: false,
         };
 
@@ -987,7 +2010,12 @@ mod tests {
         let mut global = default_global();
         global.format = OutputFormat::Json;
 
-        let args = ValidateArgs { file: file_path };
+        let args = ValidateArgs {
+            file: file_path,
+            fail_on: FailOn::Errors,
+            strict: false,
+            checksums: false,
+        };
 
         let result = validate(&args, &global);
         assert!(result.is_err());
@@ -1050,7 +2078,12 @@ mod tests {
         let mut global = default_global();
         global.quiet = false;
 
-        let args = ValidateArgs { file: file_path };
+        let args = ValidateArgs {
+            file: file_path,
+            fail_on: FailOn::Errors,
+            strict: false,
+            checksums: false,
+        };
 
         let result = validate(&args, &global);
         assert!(result.is_ok());
@@ -1090,6 +2123,8 @@ mod tests {
             file: file_path,
             sheet: "TestSheet".to_string(),
             sheets: None,
+            from_schema: None,
+            stamp_hash: false,
             force: false,
         };
 
@@ -1232,6 +2267,117 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_optimize() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("bloated.xlsx");
+        let out = temp_dir.path().join("slim.xlsx");
+
+        let mut wb = Workbook::new();
+        wb.set_cell(
+            "Sheet1",
+            xlex_core::CellRef::new(1, 1),
+            xlex_core::CellValue::Number(1.0),
+        )
+        .unwrap();
+        wb.set_cell(
+            "Sheet1",
+            xlex_core::CellRef::new(1, 5000),
+            xlex_core::CellValue::Empty,
+        )
+        .unwrap();
+        wb.save_as(&file_path).unwrap();
+
+        let args = OptimizeArgs {
+            file: file_path.clone(),
+            out: Some(out.clone()),
+            force: false,
+        };
+        optimize(&args, &default_global()).unwrap();
+        let slim = Workbook::open(&out).unwrap();
+        assert_eq!(slim.get_sheet("Sheet1").unwrap().cell_count(), 1);
+
+        // Refuses to overwrite an existing output without --force
+        assert!(optimize(&args, &default_global()).is_err());
+        let mut json = default_global();
+        json.format = OutputFormat::Json;
+        optimize(
+            &OptimizeArgs {
+                force: true,
+                ..args
+            },
+            &json,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_scrub() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("draft.xlsx");
+        let out = temp_dir.path().join("public.xlsx");
+
+        let mut wb = Workbook::new();
+        wb.properties_mut().creator = Some("Ann".to_string());
+        let mut scratch = xlex_core::DefinedName::new("Scratch", "Sheet1!$A$1");
+        scratch.hidden = true;
+        wb.set_defined_name(scratch);
+        wb.save_as(&file_path).unwrap();
+
+        let args = ScrubArgs {
+            file: file_path.clone(),
+            out: Some(out.clone()),
+            force: false,
+        };
+        let mut json = default_global();
+        json.format = OutputFormat::Json;
+        scrub(&args, &json).unwrap();
+
+        let public = Workbook::open(&out).unwrap();
+        assert!(public.properties().creator.is_none());
+        assert!(public.get_defined_name("Scratch").is_none());
+        assert_eq!(
+            Workbook::open(&file_path).unwrap().properties().creator,
+            Some("Ann".to_string())
+        );
+        assert!(scrub(&args, &default_global()).is_err());
+    }
+
+    #[test]
+    fn test_index() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("large.xlsx");
+        let index_path = temp_dir.path().join("large.xlsx.xlexidx");
+        let mut wb = Workbook::new();
+        wb.set_cell(
+            "Sheet1",
+            xlex_core::CellRef::new(2, 3),
+            CellValue::Number(1.0),
+        )
+        .unwrap();
+        wb.save_as(&file_path).unwrap();
+
+        let mut args = IndexArgs {
+            file: file_path.clone(),
+            remove: false,
+        };
+        let mut dry = default_global();
+        dry.dry_run = true;
+        index(&args, &dry).unwrap();
+        assert!(!index_path.exists());
+
+        index(&args, &default_global()).unwrap();
+        let saved: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&index_path).unwrap()).unwrap();
+        assert_eq!(saved["sheets"]["Sheet1"]["last_row"], 3);
+        assert_eq!(saved["sheets"]["Sheet1"]["dimension"]["start"]["col"], 2);
+
+        args.remove = true;
+        index(&args, &default_global()).unwrap();
+        assert!(!index_path.exists());
+        index(&args, &default_global()).unwrap();
+    }
+
     #[test]
     fn test_stats_verbose() {
         let temp_dir = TempDir::new().unwrap();
@@ -1268,6 +2414,47 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[cfg(feature = "signing")]
+    #[test]
+    fn test_sign_and_verify_signature() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("signed.xlsx");
+        let pfx = temp_dir.path().join("cert.p12");
+        Workbook::new().save_as(&file_path).unwrap();
+        std::fs::write(
+            &pfx,
+            xlex_core::signature::self_signed_pkcs12("Auditor", "pw").unwrap(),
+        )
+        .unwrap();
+
+        let verify = VerifySignatureArgs {
+            file: file_path.clone(),
+            ca: None,
+        };
+        let err = verify_signature(&verify, &default_global()).unwrap_err();
+        assert!(err.to_string().contains("is not signed"));
+
+        let mut args = SignArgs {
+            file: file_path.clone(),
+            pfx,
+            password: "wrong".to_string(),
+            out: None,
+            force: false,
+        };
+        assert!(sign(&args, &default_global()).is_err());
+        args.password = "pw".to_string();
+        sign(&args, &default_global()).unwrap();
+        verify_signature(&verify, &default_global()).unwrap();
+
+        // Any later change breaks the signature
+        let mut wb = Workbook::open(&file_path).unwrap();
+        wb.set_cell("Sheet1", xlex_core::CellRef::new(1, 1), CellValue::from(1))
+            .unwrap();
+        wb.save().unwrap();
+        let err = verify_signature(&verify, &default_global()).unwrap_err();
+        assert_eq!(err.downcast_ref::<XlexError>().unwrap().exit_code(), 12);
+    }
+
     #[test]
     fn test_props_get_all_properties() {
         let temp_dir = TempDir::new().unwrap();
@@ -11,73 +11,162 @@
 #![allow(clippy::print_literal)]
 #![allow(clippy::disallowed_names)]
 
+#[macro_use]
+mod output;
 mod commands;
 pub mod progress;
 // mod config; // TODO: Implement configuration module
-// mod output; // TODO: Implement output formatting module
 
 use std::io::Write;
 use std::process::ExitCode;
 
+use anyhow::Context;
 use clap::Parser;
 use colored::Colorize;
 use commands::Cli;
+use xlex_core::diagnostic::Diagnostic;
 
 fn main() -> ExitCode {
-    let cli = Cli::parse();
-
-    match cli.run() {
-        Ok(()) => ExitCode::SUCCESS,
+    let runs = match commands::expand_aliases(std::env::args_os().collect())
+        .and_then(|args| commands::resolve_sheet_args(args, 1))
+    {
+        Ok(runs) => runs,
         Err(e) => {
-            // Get error details
-            let (exit_code, error_code, suggestion) =
-                if let Some(xlex_err) = e.downcast_ref::<xlex_core::XlexError>() {
-                    (
-                        xlex_err.exit_code(),
-                        Some(xlex_err.code().to_string()),
-                        xlex_err.recovery_suggestion(),
-                    )
-                } else {
-                    (1, None, None)
-                };
-
-            // Log error to file if XLEX_LOG_FILE is set
-            if let Ok(log_file) = std::env::var("XLEX_LOG_FILE") {
-                log_error_to_file(&log_file, &e, error_code.as_deref());
+            eprintln!("{}: {}", "error".red().bold(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Flushes the trace file when main returns
+    let mut trace = None;
+
+    // `--sheet '*'` runs the command once per sheet, stopping at the first error
+    for args in runs {
+        let cli = Cli::parse_from(args);
+        if let (None, Some(path)) = (&trace, &cli.global.trace_output) {
+            match start_trace(path) {
+                Ok(guard) => trace = Some(guard),
+                Err(e) => return report_error(&cli, e),
             }
+        }
+        if let Err(e) = cli.run() {
+            return report_error(&cli, e);
+        }
+    }
+    ExitCode::SUCCESS
+}
 
-            // Print error
-            if cli.global.json_errors {
-                let mut error_json = serde_json::json!({
-                    "error": true,
-                    "message": e.to_string(),
-                    "exit_code": exit_code,
-                });
-                if let Some(code) = &error_code {
-                    error_json["code"] = serde_json::Value::String(code.clone());
-                }
-                if let Some(hint) = suggestion {
-                    error_json["suggestion"] = serde_json::Value::String(hint.to_string());
-                }
-                eprintln!("{}", serde_json::to_string_pretty(&error_json).unwrap());
-            } else {
-                if let Some(code) = &error_code {
-                    eprintln!("{} [{}]: {}", "error".red().bold(), code.yellow(), e);
-                } else {
-                    eprintln!("{}: {}", "error".red().bold(), e);
-                }
+/// Records spans from xlex-core to `path` in Chrome trace format.
+fn start_trace(path: &std::path::Path) -> anyhow::Result<tracing_chrome::FlushGuard> {
+    use tracing_subscriber::prelude::*;
 
-                // Print recovery suggestion if available and not in quiet mode
-                if !cli.global.quiet {
-                    if let Some(hint) = suggestion {
-                        eprintln!("{}: {}", "hint".cyan().bold(), hint);
-                    }
+    let file = std::fs::File::create(path)
+        .with_context(|| format!("Cannot create trace file {}", path.display()))?;
+    let (layer, guard) = tracing_chrome::ChromeLayerBuilder::new()
+        .writer(file)
+        .include_args(true)
+        .build();
+    tracing_subscriber::registry().with(layer).try_init()?;
+    Ok(guard)
+}
+
+/// Prints a command error and returns its exit code.
+fn report_error(cli: &Cli, e: anyhow::Error) -> ExitCode {
+    // A plugin has reported its own error; an unknown command is a usage error
+    if let Some(exit) = e.downcast_ref::<commands::PluginExit>() {
+        return ExitCode::from(exit.code);
+    }
+    if let Some(usage) = e.downcast_ref::<clap::Error>() {
+        let _ = usage.print();
+        return ExitCode::from(2);
+    }
+
+    // Get error details
+    let xlex_err = e.downcast_ref::<xlex_core::XlexError>();
+    let (exit_code, error_code, suggestion) = if let Some(xlex_err) = xlex_err {
+        (
+            xlex_err.exit_code(),
+            Some(xlex_err.code().to_string()),
+            xlex_err.recovery_suggestion(),
+        )
+    } else {
+        (1, None, None)
+    };
+    let diagnostic = xlex_err.map(|err| match err.without_context() {
+        xlex_core::XlexError::SheetNotFound { .. } => {
+            Diagnostic::with_sheets(err, &command_line_sheets())
+        }
+        _ => Diagnostic::new(err),
+    });
+
+    // Log error to file if XLEX_LOG_FILE is set
+    if let Ok(log_file) = std::env::var("XLEX_LOG_FILE") {
+        log_error_to_file(&log_file, &e, error_code.as_deref());
+    }
+
+    // Print error
+    if cli.global.json_errors {
+        let mut error_json = serde_json::json!({
+            "error": true,
+            "message": e.to_string(),
+            "exit_code": exit_code,
+        });
+        if let Some(code) = &error_code {
+            error_json["code"] = serde_json::Value::String(code.clone());
+        }
+        if let Some(hint) = suggestion {
+            error_json["suggestion"] = serde_json::Value::String(hint.to_string());
+        }
+        if let Some(diagnostic) = &diagnostic {
+            error_json["suggestions"] = serde_json::json!(diagnostic.suggestions);
+            error_json["docs"] = serde_json::Value::String(diagnostic.docs_url.clone());
+        }
+        if let Some(context) = xlex_err.and_then(|e| e.context()) {
+            error_json["context"] = serde_json::json!({
+                "part": context.part,
+                "sheet": context.sheet,
+                "cell": context.cell,
+                "offset": context.offset,
+            });
+        }
+        eprintln!("{}", serde_json::to_string_pretty(&error_json).unwrap());
+    } else {
+        if let Some(code) = &error_code {
+            eprintln!("{} [{}]: {}", "error".red().bold(), code.yellow(), e);
+        } else {
+            eprintln!("{}: {}", "error".red().bold(), e);
+        }
+
+        // Print suggestions and the docs link if available and not in quiet mode
+        if !cli.global.quiet {
+            if let Some(diagnostic) = &diagnostic {
+                for hint in &diagnostic.suggestions {
+                    eprintln!("{}: {}", "hint".cyan().bold(), hint);
                 }
+                eprintln!("{}: {}", "docs".cyan().bold(), diagnostic.docs_url);
             }
-
-            ExitCode::from(exit_code as u8)
         }
     }
+
+    ExitCode::from(exit_code as u8)
+}
+
+/// Sheet names of the workbooks named on the command line, to suggest the
+/// closest one for a missing sheet.
+fn command_line_sheets() -> Vec<String> {
+    std::env::args_os()
+        .skip(1)
+        .map(std::path::PathBuf::from)
+        .filter(|path| {
+            let extension = path.extension().and_then(|e| e.to_str());
+            matches!(
+                extension.map(str::to_lowercase).as_deref(),
+                Some("xlsx" | "xlsm")
+            ) && path.is_file()
+        })
+        .filter_map(|path| xlex_core::LazyWorkbook::open(&path).ok())
+        .flat_map(|workbook| workbook.sheet_names().to_vec())
+        .collect()
 }
 
 /// Log error to a file specified by XLEX_LOG_FILE environment variable.
@@ -0,0 +1,164 @@
+//! Destination of command output.
+//!
+//! Commands print with [`outln!`] and [`out!`] instead of `println!` and
+//! `print!`, so the global `--output` option can send everything to a file
+//! (`--append` adds to it, `--tee` also keeps printing to stdout). Errors
+//! and progress bars stay on stderr.
+//!
+//! The destination belongs to the thread that set it, so commands run side
+//! by side (as unit tests are) don't write into each other's files.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+
+struct Sink {
+    file: Option<BufWriter<File>>,
+    tee: bool,
+}
+
+thread_local! {
+    static SINK: RefCell<Sink> = const {
+        RefCell::new(Sink {
+            file: None,
+            tee: false,
+        })
+    };
+}
+
+/// Output redirection that lasts until dropped, then restores the previous
+/// destination. Nested commands (interactive mode) without `--output` keep
+/// writing wherever the outer command does.
+///
+/// Redirections nest, and being tied to their thread they cannot be
+/// dropped elsewhere, so they are always undone in reverse order.
+pub struct Redirect {
+    previous: Option<Sink>,
+    _thread: PhantomData<*const ()>,
+}
+
+/// Sends output to `path`, truncating it unless `append`. With `tee` the
+/// output is also printed to stdout.
+pub fn redirect(path: &Path, append: bool, tee: bool) -> io::Result<Redirect> {
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)?;
+    let previous = SINK.with_borrow_mut(|sink| {
+        std::mem::replace(
+            sink,
+            Sink {
+                file: Some(BufWriter::new(file)),
+                tee,
+            },
+        )
+    });
+    Ok(Redirect {
+        previous: Some(previous),
+        _thread: PhantomData,
+    })
+}
+
+impl Drop for Redirect {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            let mut current = SINK.with_borrow_mut(|sink| std::mem::replace(sink, previous));
+            if let Some(file) = current.file.as_mut() {
+                let _ = file.flush();
+            }
+        }
+    }
+}
+
+/// Writes formatted output to the current destination. Used by [`outln!`]
+/// and [`out!`]; panics on write errors like `println!` does.
+pub fn write_fmt(args: fmt::Arguments<'_>) {
+    let result = SINK.with_borrow_mut(|sink| {
+        let tee = sink.tee;
+        match sink.file.as_mut() {
+            Some(file) => file.write_fmt(args).and_then(|()| {
+                if tee {
+                    io::stdout().write_fmt(args)
+                } else {
+                    Ok(())
+                }
+            }),
+            None => io::stdout().write_fmt(args),
+        }
+    });
+    if let Err(e) = result {
+        panic!("failed printing output: {}", e);
+    }
+}
+
+/// `println!` that honours `--output`.
+macro_rules! outln {
+    () => {
+        $crate::output::write_fmt(format_args!("\n"))
+    };
+    ($($arg:tt)*) => {
+        $crate::output::write_fmt(format_args!("{}\n", format_args!($($arg)*)))
+    };
+}
+
+/// `print!` that honours `--output`.
+macro_rules! out {
+    ($($arg:tt)*) => {
+        $crate::output::write_fmt(format_args!($($arg)*))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redirect_and_restore() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("out.txt");
+
+        {
+            let _redirect = redirect(&path, false, false).unwrap();
+            outln!("first {}", 1);
+            out!("partial");
+            outln!();
+        }
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "first 1\npartial\n"
+        );
+
+        {
+            let _redirect = redirect(&path, true, false).unwrap();
+            outln!("second");
+        }
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "first 1\npartial\nsecond\n"
+        );
+
+        {
+            let _redirect = redirect(&path, false, false).unwrap();
+            outln!("third");
+        }
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "third\n");
+        assert!(SINK.with_borrow(|sink| sink.file.is_none()));
+
+        // Output from other threads is not redirected
+        let redirected = redirect(&path, false, false).unwrap();
+        std::thread::spawn(|| {
+            assert!(SINK.with_borrow(|sink| sink.file.is_none()));
+            outln!("elsewhere");
+        })
+        .join()
+        .unwrap();
+        outln!("here");
+        drop(redirected);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "here\n");
+    }
+}
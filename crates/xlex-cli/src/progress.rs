@@ -6,6 +6,11 @@
 use indicatif::{ProgressBar, ProgressStyle};
 use std::time::Duration;
 
+use crate::commands::GlobalOptions;
+
+/// Operations over fewer rows than this finish too quickly to need a bar.
+const MIN_PROGRESS_ROWS: usize = 100;
+
 /// Progress indicator types.
 pub enum ProgressKind {
     /// Spinner for operations with unknown total
@@ -68,6 +73,23 @@ impl Progress {
         Self::new(ProgressKind::Bar { total }, message, quiet)
     }
 
+    /// Create a row-count progress bar for an operation over `total` rows.
+    ///
+    /// Returns `None` for small operations and when progress is suppressed
+    /// (see [`GlobalOptions::show_progress`]).
+    pub fn rows(total: usize, message: &str, global: &GlobalOptions) -> Option<Self> {
+        (total > MIN_PROGRESS_ROWS && global.show_progress())
+            .then(|| Self::bar(total as u64, message, false))
+    }
+
+    /// Create a spinner for a long operation with no row count, or `None`
+    /// when progress is suppressed.
+    pub fn phases(message: &str, global: &GlobalOptions) -> Option<Self> {
+        global
+            .show_progress()
+            .then(|| Self::spinner(message, false))
+    }
+
     /// Update the progress message.
     pub fn set_message(&self, message: &str) {
         self.bar.set_message(message.to_string());
@@ -183,6 +205,25 @@ mod tests {
         progress.finish_with_message("Done");
     }
 
+    #[test]
+    fn test_rows_suppressed() {
+        use clap::Parser;
+
+        let parse = |args: &[&str]| GlobalOptions::try_parse_from(args).unwrap();
+        let global = parse(&["xlex"]);
+        assert!(Progress::rows(10, "Small", &global).is_none());
+        for args in [
+            &["xlex", "--no-progress"][..],
+            &["xlex", "--quiet"],
+            &["xlex", "--format", "json"],
+        ] {
+            let global = parse(args);
+            assert!(!global.show_progress());
+            assert!(Progress::rows(10_000, "Large", &global).is_none());
+            assert!(Progress::phases("Working", &global).is_none());
+        }
+    }
+
     #[test]
     fn test_multi_progress() {
         let multi = MultiProgress::new(true);
@@ -554,6 +554,36 @@ mod range_operations {
         assert!(output.contains("A1") || output.contains("B1"));
     }
 
+    #[test]
+    fn test_range_get_html_table() {
+        let temp_dir = TempDir::new().unwrap();
+        let xlsx_path = temp_dir.path().join("test.xlsx");
+        let xlsx_str = xlsx_path.to_str().unwrap();
+
+        assert!(xlex_success(&["create", xlsx_str]));
+        assert!(xlex_success(&[
+            "cell", "set", xlsx_str, "Sheet1", "A1", "Q&A"
+        ]));
+        assert!(xlex_success(&[
+            "cell", "set", xlsx_str, "Sheet1", "B2", "42"
+        ]));
+
+        let output = xlex_stdout(&[
+            "range",
+            "get",
+            xlsx_str,
+            "Sheet1",
+            "A1:B2",
+            "-f",
+            "html-table",
+        ]);
+        assert!(output.starts_with("<style>"));
+        assert!(output.contains("<tr><th></th><th>A</th><th>B</th></tr>"));
+        assert!(output.contains("<tr><th>1</th><td>Q&amp;A</td><td></td></tr>"));
+        assert!(output.contains("<tr><th>2</th><td></td><td class=\"num\">42</td></tr>"));
+        assert!(output.trim_end().ends_with("</table>"));
+    }
+
     #[test]
     fn test_range_clear() {
         let temp_dir = TempDir::new().unwrap();
@@ -600,6 +630,147 @@ mod range_operations {
         let output = xlex_stdout(&["cell", "get", xlsx_str, "Sheet1", "C1"]);
         assert!(output.contains("Source"));
     }
+
+    #[test]
+    fn test_range_json_output() {
+        let temp_dir = TempDir::new().unwrap();
+        let xlsx_path = temp_dir.path().join("test.xlsx");
+        let xlsx_str = xlsx_path.to_str().unwrap();
+
+        assert!(xlex_success(&["create", xlsx_str]));
+        assert!(xlex_success(&[
+            "cell", "set", xlsx_str, "Sheet1", "A1", "Data"
+        ]));
+
+        let output = xlex_stdout(&[
+            "range", "copy", xlsx_str, "Sheet1", "A1", "C1", "-f", "json",
+        ]);
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(json["action"], "copy");
+        assert_eq!(json["target"]["destination"], "C1");
+        assert_eq!(json["counts"]["cells"], 1);
+        assert!(json["durationMs"].is_u64());
+
+        let output = xlex_stdout(&["range", "merge", xlsx_str, "Sheet1", "A2:B2", "-f", "json"]);
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(json["action"], "merge");
+        assert_eq!(json["target"]["range"], "A2:B2");
+
+        let output = xlex_stdout(&["range", "clear", xlsx_str, "Sheet1", "A1:C1", "-f", "json"]);
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(json["action"], "clear");
+        assert_eq!(json["target"]["sheet"], "Sheet1");
+        assert_eq!(json["counts"]["cells"], 3);
+    }
+
+    #[test]
+    fn test_sheet_qualified_references() {
+        let temp_dir = TempDir::new().unwrap();
+        let xlsx_path = temp_dir.path().join("test.xlsx");
+        let xlsx_str = xlsx_path.to_str().unwrap();
+
+        assert!(xlex_success(&[
+            "create",
+            xlsx_str,
+            "--sheets",
+            "Data,My Sheet"
+        ]));
+        assert!(xlex_success(&["cell", "set", xlsx_str, "Data!B2", "42"]));
+        assert!(xlex_success(&[
+            "cell",
+            "set",
+            xlsx_str,
+            "'My Sheet'!C3",
+            "hello"
+        ]));
+
+        assert_eq!(
+            xlex_stdout(&["cell", "get", xlsx_str, "Data", "B2"]).trim(),
+            "42"
+        );
+        assert_eq!(
+            xlex_stdout(&["cell", "get", xlsx_str, "My Sheet!C3"]).trim(),
+            "hello"
+        );
+        let output = xlex_stdout(&["range", "get", xlsx_str, "Data!A1:B2", "-f", "json"]);
+        assert!(output.contains("42"));
+    }
+
+    #[test]
+    fn test_trace_output() {
+        let temp_dir = TempDir::new().unwrap();
+        let xlsx_path = temp_dir.path().join("test.xlsx");
+        let xlsx_str = xlsx_path.to_str().unwrap();
+        let trace_path = temp_dir.path().join("trace.json");
+
+        assert!(xlex_success(&["create", xlsx_str]));
+        assert!(xlex_success(&[
+            "cell",
+            "set",
+            xlsx_str,
+            "Sheet1",
+            "A1",
+            "Data",
+            "--trace-output",
+            trace_path.to_str().unwrap(),
+        ]));
+
+        let events: Vec<serde_json::Value> =
+            serde_json::from_str(&std::fs::read_to_string(&trace_path).unwrap()).unwrap();
+        let names: Vec<&str> = events.iter().filter_map(|e| e["name"].as_str()).collect();
+        for span in [
+            "open",
+            "parse_sheet",
+            "load_shared_strings",
+            "save",
+            "write_sheet",
+        ] {
+            assert!(names.contains(&span), "missing span {}", span);
+        }
+    }
+
+    #[test]
+    fn test_output_file_append_and_tee() {
+        let temp_dir = TempDir::new().unwrap();
+        let xlsx_path = temp_dir.path().join("test.xlsx");
+        let xlsx_str = xlsx_path.to_str().unwrap();
+        let out_path = temp_dir.path().join("data.json");
+        let out_str = out_path.to_str().unwrap();
+
+        assert!(xlex_success(&["create", xlsx_str]));
+        assert!(xlex_success(&[
+            "cell", "set", xlsx_str, "Sheet1", "A1", "Data"
+        ]));
+
+        let stdout = xlex_stdout(&[
+            "range", "get", xlsx_str, "Sheet1", "A1:A1", "-f", "json", "-o", out_str,
+        ]);
+        assert!(stdout.is_empty());
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&out_path).unwrap()).unwrap();
+        assert!(json.to_string().contains("Data"));
+
+        let stdout = xlex_stdout(&[
+            "cell", "get", xlsx_str, "Sheet1", "A1", "-o", out_str, "--append", "--tee",
+        ]);
+        assert!(stdout.contains("Data"));
+        let content = std::fs::read_to_string(&out_path).unwrap();
+        assert!(content.ends_with(&stdout));
+        assert!(content.len() > stdout.len());
+
+        // Exports default to stdout, so -o picks them up
+        let csv_path = temp_dir.path().join("data.csv");
+        assert!(xlex_success(&[
+            "export",
+            "csv",
+            xlsx_str,
+            "-o",
+            csv_path.to_str().unwrap(),
+        ]));
+        assert!(std::fs::read_to_string(&csv_path).unwrap().contains("Data"));
+
+        assert!(!xlex_success(&["info", xlsx_str, "--append"]));
+    }
 }
 
 mod export_import_operations {
@@ -1213,6 +1384,62 @@ mod error_handling {
         assert!(stderr.contains("true"));
     }
 
+    #[test]
+    fn test_json_error_context() {
+        use std::io::{Read, Write};
+
+        let temp_dir = TempDir::new().unwrap();
+        let plain_path = temp_dir.path().join("plain.xlsx");
+        assert!(xlex_success(&["create", plain_path.to_str().unwrap()]));
+
+        // Break the end tag of sheetData in the first sheet
+        let xlsx_path = temp_dir.path().join("broken.xlsx");
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&plain_path).unwrap()).unwrap();
+        let mut out = zip::ZipWriter::new(std::fs::File::create(&xlsx_path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).unwrap();
+            let name = entry.name().to_string();
+            let mut content = String::new();
+            entry.read_to_string(&mut content).unwrap();
+            if name == "xl/worksheets/sheet1.xml" {
+                content = content.replace("<sheetData/>", "<sheetData></sheetDat>");
+                content = content.replace("</sheetData>", "</sheetDat>");
+            }
+            out.start_file(name, options).unwrap();
+            out.write_all(content.as_bytes()).unwrap();
+        }
+        out.finish().unwrap();
+
+        let output = xlex(&["--json-errors", "info", xlsx_path.to_str().unwrap()]);
+        assert!(!output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+        assert_eq!(json["code"], "XLEX_E013");
+        assert_eq!(json["context"]["part"], "xl/worksheets/sheet1.xml");
+        assert_eq!(json["context"]["sheet"], "Sheet1");
+        assert!(json["context"]["offset"].is_u64());
+    }
+
+    #[test]
+    fn test_json_error_suggests_sheet() {
+        let temp_dir = TempDir::new().unwrap();
+        let xlsx_path = temp_dir.path().join("test.xlsx");
+        let xlsx_str = xlsx_path.to_str().unwrap();
+        assert!(xlex_success(&["create", xlsx_str]));
+        assert!(xlex_success(&["sheet", "add", xlsx_str, "Summary"]));
+
+        let output = xlex(&["--json-errors", "cell", "get", xlsx_str, "sumary", "A1"]);
+        assert!(!output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+        assert_eq!(json["code"], "XLEX_E030");
+        assert_eq!(json["suggestions"][0], "Did you mean 'Summary'?");
+        assert!(json["docs"].as_str().unwrap().ends_with("#xlex_e030"));
+
+        let output = xlex(&["cell", "get", xlsx_str, "SHEET1", "A1"]);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Did you mean 'Sheet1'?"));
+    }
+
     #[test]
     fn test_quiet_mode_error() {
         let output = xlex(&["-q", "info", "/nonexistent/file.xlsx"]);
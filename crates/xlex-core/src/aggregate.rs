@@ -0,0 +1,354 @@
+//! Group-by aggregation over streamed rows.
+//!
+//! A [`GroupBy`] is a [`RowSink`]: rows are folded into one accumulator per
+//! group as they arrive, so memory grows with the number of groups rather
+//! than the number of rows. Groups are keyed by the displayed text of their
+//! key cells and come out in the order they first appear.
+//!
+//! ```
+//! use xlex_core::aggregate::{AggFunc, Aggregation, GroupBy};
+//! use xlex_core::{CellValue, RowSink};
+//!
+//! let mut by_region = GroupBy::new(
+//!     vec![1],
+//!     vec![
+//!         Aggregation::new(AggFunc::Sum, Some(2), "total"),
+//!         Aggregation::new(AggFunc::Count, None, "n"),
+//!     ],
+//! );
+//! for (region, amount) in [("north", 5.0), ("south", 1.0), ("north", 2.0)] {
+//!     by_region.write_row(&[CellValue::string(region), CellValue::Number(amount)])?;
+//! }
+//! let rows = by_region.finish();
+//! assert_eq!(
+//!     rows[0],
+//!     vec![CellValue::string("north"), CellValue::Number(7.0), CellValue::Number(2.0)]
+//! );
+//! # Ok::<(), xlex_core::XlexError>(())
+//! ```
+
+use std::collections::HashMap;
+
+use crate::cell::{CellError, CellValue};
+use crate::decimal::{cell_decimal, Decimal};
+use crate::error::XlexResult;
+use crate::pipeline::RowSink;
+
+/// An aggregate function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggFunc {
+    /// Sum of the numbers
+    Sum,
+    /// Rows, or non-blank cells when given a column
+    Count,
+    /// Mean of the numbers (`#DIV/0!` when there are none)
+    Avg,
+    /// Smallest number (0 when there are none)
+    Min,
+    /// Largest number (0 when there are none)
+    Max,
+}
+
+impl AggFunc {
+    /// Looks a function up by name: `sum`, `count`, `avg` (or `average`,
+    /// `mean`), `min` or `max`, in any case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "sum" => Some(Self::Sum),
+            "count" => Some(Self::Count),
+            "avg" | "average" | "mean" => Some(Self::Avg),
+            "min" => Some(Self::Min),
+            "max" => Some(Self::Max),
+            _ => None,
+        }
+    }
+}
+
+/// One output column: a function of a column, under a name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Aggregation {
+    pub func: AggFunc,
+    /// 1-based column read, if any (`count()` reads none)
+    pub column: Option<u32>,
+    /// Header of the output column
+    pub name: String,
+}
+
+impl Aggregation {
+    pub fn new(func: AggFunc, column: Option<u32>, name: impl Into<String>) -> Self {
+        Self {
+            func,
+            column,
+            name: name.into(),
+        }
+    }
+}
+
+/// Running state of one aggregation in one group.
+#[derive(Debug, Clone, Default)]
+struct Accumulator {
+    sum: f64,
+    /// The sum as an exact decimal, in decimal mode
+    exact: Decimal,
+    /// A number didn't fit a decimal: fall back to `sum`
+    inexact: bool,
+    count: u64,
+    /// Smallest and largest number seen, and whether each was a date
+    min: Option<(f64, bool)>,
+    max: Option<(f64, bool)>,
+}
+
+impl Accumulator {
+    fn add(&mut self, func: AggFunc, value: Option<&CellValue>, decimal: bool) {
+        let Some(value) = value else {
+            // count() counts rows
+            self.count += 1;
+            return;
+        };
+        if func == AggFunc::Count {
+            if !value.is_empty() {
+                self.count += 1;
+            }
+            return;
+        }
+        let Some((n, is_date)) = number(value) else {
+            return;
+        };
+        self.sum += n;
+        self.count += 1;
+        if decimal && !self.inexact {
+            match cell_decimal(value).and_then(|d| self.exact.checked_add(d)) {
+                Some(exact) => self.exact = exact,
+                None => self.inexact = true,
+            }
+        }
+        if !matches!(self.min, Some((min, _)) if min <= n) {
+            self.min = Some((n, is_date));
+        }
+        if !matches!(self.max, Some((max, _)) if max >= n) {
+            self.max = Some((n, is_date));
+        }
+    }
+
+    fn result(&self, func: AggFunc, decimal: bool) -> CellValue {
+        let extreme = |value: Option<(f64, bool)>| match value {
+            Some((n, true)) => CellValue::DateTime(n),
+            Some((n, false)) => CellValue::Number(n),
+            None => CellValue::Number(0.0),
+        };
+        let exact = decimal && !self.inexact;
+        match func {
+            AggFunc::Sum if exact => CellValue::Decimal(self.exact),
+            AggFunc::Sum => CellValue::Number(self.sum),
+            AggFunc::Count => CellValue::Number(self.count as f64),
+            AggFunc::Avg if self.count == 0 => CellValue::Error(CellError::DivZero),
+            AggFunc::Avg => match self.exact.checked_div_int(i128::from(self.count)) {
+                Some(mean) if exact => CellValue::Decimal(mean),
+                _ => CellValue::Number(self.sum / self.count as f64),
+            },
+            AggFunc::Min => extreme(self.min),
+            AggFunc::Max => extreme(self.max),
+        }
+    }
+}
+
+/// Returns the number in a cell, looking through cached formula results,
+/// and whether it is a date. Text is ignored, as by Excel's `SUM`.
+fn number(value: &CellValue) -> Option<(f64, bool)> {
+    match value {
+        CellValue::Number(n) => Some((*n, false)),
+        CellValue::DateTime(n) => Some((*n, true)),
+        CellValue::Decimal(d) => Some((d.to_f64(), false)),
+        CellValue::Formula {
+            cached_result: Some(result),
+            ..
+        } => number(result),
+        _ => None,
+    }
+}
+
+/// Accumulates rows into groups.
+#[derive(Debug, Clone)]
+pub struct GroupBy {
+    group_cols: Vec<u32>,
+    aggregations: Vec<Aggregation>,
+    groups: Vec<(Vec<CellValue>, Vec<Accumulator>)>,
+    index: HashMap<Vec<String>, usize>,
+    rows: u64,
+    decimal: bool,
+}
+
+impl GroupBy {
+    /// Groups by the 1-based `group_cols`; with none, every row is in one
+    /// group.
+    pub fn new(group_cols: Vec<u32>, aggregations: Vec<Aggregation>) -> Self {
+        Self {
+            group_cols,
+            aggregations,
+            groups: Vec::new(),
+            index: HashMap::new(),
+            rows: 0,
+            decimal: false,
+        }
+    }
+
+    /// Sums and averages as exact decimals, giving [`CellValue::Decimal`]
+    /// results, so `0.1 + 0.2` totals `0.3`. A sum that outgrows a decimal
+    /// falls back to a float.
+    pub fn decimal(mut self, decimal: bool) -> Self {
+        self.decimal = decimal;
+        self
+    }
+
+    /// Returns the number of rows added.
+    pub fn rows(&self) -> u64 {
+        self.rows
+    }
+
+    /// Returns the number of groups so far.
+    pub fn group_count(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Returns a row per group: its key cells, then each aggregation.
+    pub fn finish(mut self) -> Vec<Vec<CellValue>> {
+        if self.groups.is_empty() && self.group_cols.is_empty() {
+            // A total over no rows is still a total
+            let empty = vec![Accumulator::default(); self.aggregations.len()];
+            self.groups.push((Vec::new(), empty));
+        }
+        let (aggregations, decimal) = (self.aggregations, self.decimal);
+        self.groups
+            .into_iter()
+            .map(|(mut row, accumulators)| {
+                row.extend(
+                    aggregations
+                        .iter()
+                        .zip(&accumulators)
+                        .map(|(agg, acc)| acc.result(agg.func, decimal)),
+                );
+                row
+            })
+            .collect()
+    }
+}
+
+impl RowSink for GroupBy {
+    fn write_row(&mut self, values: &[CellValue]) -> XlexResult<()> {
+        const EMPTY: &CellValue = &CellValue::Empty;
+        let cell = |col: u32| {
+            col.checked_sub(1)
+                .and_then(|i| values.get(i as usize))
+                .unwrap_or(EMPTY)
+        };
+        self.rows += 1;
+        let key: Vec<String> = self
+            .group_cols
+            .iter()
+            .map(|&c| cell(c).to_display_string())
+            .collect();
+        let group = match self.index.get(&key) {
+            Some(&group) => group,
+            None => {
+                let cells = self.group_cols.iter().map(|&c| cell(c).clone()).collect();
+                let accumulators = vec![Accumulator::default(); self.aggregations.len()];
+                self.groups.push((cells, accumulators));
+                self.index.insert(key, self.groups.len() - 1);
+                self.groups.len() - 1
+            }
+        };
+        let accumulators = &mut self.groups[group].1;
+        for (agg, acc) in self.aggregations.iter().zip(accumulators) {
+            acc.add(agg.func, agg.column.map(cell), self.decimal);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_by() {
+        let mut group_by = GroupBy::new(
+            vec![1],
+            vec![
+                Aggregation::new(AggFunc::Sum, Some(2), "total"),
+                Aggregation::new(AggFunc::Count, None, "n"),
+                Aggregation::new(AggFunc::Count, Some(2), "amounts"),
+                Aggregation::new(AggFunc::Avg, Some(2), "mean"),
+                Aggregation::new(AggFunc::Min, Some(3), "first"),
+                Aggregation::new(AggFunc::Max, Some(2), "top"),
+            ],
+        );
+        let rows = [
+            ("a", CellValue::Number(2.0), CellValue::DateTime(45000.0)),
+            ("b", CellValue::string("n/a"), CellValue::Empty),
+            ("a", CellValue::Number(4.0), CellValue::DateTime(44000.0)),
+            ("a", CellValue::Empty, CellValue::Empty),
+        ];
+        for (key, amount, date) in rows {
+            group_by
+                .write_row(&[CellValue::string(key), amount, date])
+                .unwrap();
+        }
+        assert_eq!(group_by.rows(), 4);
+        assert_eq!(group_by.group_count(), 2);
+        let out = group_by.finish();
+        assert_eq!(
+            out[0],
+            vec![
+                CellValue::string("a"),
+                CellValue::Number(6.0),
+                CellValue::Number(3.0),
+                CellValue::Number(2.0),
+                CellValue::Number(3.0),
+                CellValue::DateTime(44000.0),
+                CellValue::Number(4.0),
+            ]
+        );
+        assert_eq!(out[1][1], CellValue::Number(0.0));
+        assert_eq!(out[1][3], CellValue::Number(1.0));
+        assert_eq!(out[1][4], CellValue::Error(CellError::DivZero));
+    }
+
+    #[test]
+    fn test_group_by_decimal() {
+        let aggregations = || {
+            vec![
+                Aggregation::new(AggFunc::Sum, Some(1), "total"),
+                Aggregation::new(AggFunc::Avg, Some(1), "mean"),
+                Aggregation::new(AggFunc::Max, Some(1), "top"),
+            ]
+        };
+        let mut floats = GroupBy::new(vec![], aggregations());
+        let mut exact = GroupBy::new(vec![], aggregations()).decimal(true);
+        for amount in [0.1, 0.2, 0.3] {
+            floats.write_row(&[CellValue::Number(amount)]).unwrap();
+            exact.write_row(&[CellValue::Number(amount)]).unwrap();
+        }
+        exact
+            .write_row(&[CellValue::Decimal("0.40".parse().unwrap())])
+            .unwrap();
+        assert_eq!(floats.finish()[0][0], CellValue::Number(0.6000000000000001));
+        let row = exact.finish().remove(0);
+        assert_eq!(row[0].to_display_string(), "1.00");
+        assert_eq!(row[1].to_display_string(), "0.25");
+        assert_eq!(row[2], CellValue::Number(0.4));
+
+        let mut huge = GroupBy::new(vec![], aggregations()).decimal(true);
+        for _ in 0..2 {
+            huge.write_row(&[CellValue::Number(1e38)]).unwrap();
+        }
+        assert_eq!(huge.finish()[0][0], CellValue::Number(2e38));
+    }
+
+    #[test]
+    fn test_group_by_without_groups() {
+        let total = GroupBy::new(vec![], vec![Aggregation::new(AggFunc::Count, None, "n")]);
+        assert_eq!(total.finish(), vec![vec![CellValue::Number(0.0)]]);
+        assert_eq!(AggFunc::from_name("AVERAGE"), Some(AggFunc::Avg));
+        assert_eq!(AggFunc::from_name("median"), None);
+    }
+}
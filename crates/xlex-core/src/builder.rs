@@ -0,0 +1,317 @@
+//! Fluent workbook construction.
+//!
+//! [`WorkbookBuilder`] assembles a workbook sheet by sheet from headers and
+//! rows, for programs generating reports rather than editing existing files.
+//!
+//! ```
+//! use xlex_core::builder::WorkbookBuilder;
+//! use xlex_core::CellValue;
+//!
+//! let sales = [("North", 1200.0), ("South", 950.0)];
+//! let workbook = WorkbookBuilder::new()
+//!     .sheet("Data", |s| {
+//!         s.headers(["Region", "Sales"])
+//!             .rows(sales.map(|(region, total)| [CellValue::from(region), total.into()]))
+//!             .freeze_header()
+//!             .autofilter()
+//!     })
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(workbook.sheet_names(), vec!["Data"]);
+//! ```
+
+use crate::cell::{CellRef, CellValue};
+use crate::error::XlexResult;
+use crate::range::Range;
+use crate::sheet::Dropdown;
+use crate::style::Style;
+use crate::workbook::Workbook;
+
+/// Builds a workbook from sheet descriptions.
+#[derive(Debug, Default)]
+pub struct WorkbookBuilder {
+    sheets: Vec<(String, SheetBuilder)>,
+}
+
+impl WorkbookBuilder {
+    /// Creates a builder with no sheets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a sheet named `name`, described by `build`.
+    pub fn sheet(
+        mut self,
+        name: impl Into<String>,
+        build: impl FnOnce(SheetBuilder) -> SheetBuilder,
+    ) -> Self {
+        self.sheets
+            .push((name.into(), build(SheetBuilder::default())));
+        self
+    }
+
+    /// Builds the workbook. Without any sheet it holds an empty `Sheet1`.
+    ///
+    /// Fails if a sheet name is invalid or used twice.
+    pub fn build(self) -> XlexResult<Workbook> {
+        let mut workbook = Workbook::new();
+        for (index, (name, sheet)) in self.sheets.into_iter().enumerate() {
+            if index == 0 {
+                workbook.rename_sheet("Sheet1", &name)?;
+            } else {
+                workbook.add_sheet(&name)?;
+            }
+            sheet.apply(&mut workbook, &name)?;
+        }
+        Ok(workbook)
+    }
+}
+
+/// Describes the contents of one sheet for [`WorkbookBuilder::sheet`].
+#[derive(Debug, Default)]
+pub struct SheetBuilder {
+    headers: Vec<String>,
+    header_style: Option<Style>,
+    rows: Vec<Vec<CellValue>>,
+    column_widths: Vec<(u32, f64)>,
+    column_styles: Vec<(u32, Style)>,
+    dropdowns: Vec<Dropdown>,
+    freeze: Option<(u32, u32)>,
+    autofilter: bool,
+}
+
+impl SheetBuilder {
+    /// Sets the header row, written as row 1 above the data rows.
+    pub fn headers<I, S>(mut self, headers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.headers = headers.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the style of the header cells.
+    pub fn header_style(mut self, style: Style) -> Self {
+        self.header_style = Some(style);
+        self
+    }
+
+    /// Appends one data row.
+    pub fn row<R, V>(mut self, row: R) -> Self
+    where
+        R: IntoIterator<Item = V>,
+        V: Into<CellValue>,
+    {
+        self.rows.push(row.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Appends data rows.
+    pub fn rows<I, R, V>(mut self, rows: I) -> Self
+    where
+        I: IntoIterator<Item = R>,
+        R: IntoIterator<Item = V>,
+        V: Into<CellValue>,
+    {
+        self.rows.extend(
+            rows.into_iter()
+                .map(|row| row.into_iter().map(Into::into).collect()),
+        );
+        self
+    }
+
+    /// Sets the width of a column (1-based), in characters.
+    pub fn column_width(mut self, col: u32, width: f64) -> Self {
+        self.column_widths.push((col, width));
+        self
+    }
+
+    /// Sets the default style of a column (1-based), which cells typed
+    /// into the column later take, such as a number format.
+    pub fn column_style(mut self, col: u32, style: Style) -> Self {
+        self.column_styles.push((col, style));
+        self
+    }
+
+    /// Offers a dropdown list in a range of the sheet.
+    pub fn dropdown(mut self, dropdown: Dropdown) -> Self {
+        self.dropdowns.push(dropdown);
+        self
+    }
+
+    /// Keeps the header row in view while scrolling.
+    pub fn freeze_header(self) -> Self {
+        self.freeze_panes(1, 0)
+    }
+
+    /// Keeps the top `rows` rows and left `cols` columns in view while
+    /// scrolling.
+    pub fn freeze_panes(mut self, rows: u32, cols: u32) -> Self {
+        self.freeze = Some((rows, cols));
+        self
+    }
+
+    /// Adds filter buttons to the header row, covering the data below it.
+    pub fn autofilter(mut self) -> Self {
+        self.autofilter = true;
+        self
+    }
+
+    /// Writes the described contents into the named sheet.
+    fn apply(self, workbook: &mut Workbook, name: &str) -> XlexResult<()> {
+        let header_style = self
+            .header_style
+            .map(|style| workbook.style_registry_mut().add(style));
+        let column_styles: Vec<(u32, u32)> = self
+            .column_styles
+            .into_iter()
+            .map(|(col, style)| (col, workbook.style_registry_mut().add(style)))
+            .collect();
+        let first_row = if self.headers.is_empty() { 1 } else { 2 };
+        let width = self
+            .rows
+            .iter()
+            .map(Vec::len)
+            .chain([self.headers.len()])
+            .max()
+            .unwrap_or(0) as u32;
+        let last_row = first_row + self.rows.len() as u32 - 1;
+
+        workbook.with_sheet_mut(name, |sheet| {
+            sheet.apply_updates(
+                (1u32..)
+                    .zip(&self.headers)
+                    .map(|(col, header)| (CellRef::new(col, 1), CellValue::String(header.clone()))),
+            );
+            if let Some(style_id) = header_style {
+                for col in 1..=self.headers.len() as u32 {
+                    sheet.set_cell_style(&CellRef::new(col, 1), Some(style_id));
+                }
+            }
+            for (row, values) in (first_row..).zip(self.rows) {
+                sheet.apply_updates(
+                    (1u32..)
+                        .zip(values)
+                        .filter(|(_, value)| !matches!(value, CellValue::Empty))
+                        .map(|(col, value)| (CellRef::new(col, row), value)),
+                );
+            }
+            for (col, width) in self.column_widths {
+                sheet.set_column_width(col, width);
+            }
+            for (col, style_id) in column_styles {
+                sheet.set_column_style(col, Some(style_id));
+            }
+            for dropdown in self.dropdowns {
+                sheet.add_dropdown(dropdown);
+            }
+            if let Some((rows, cols)) = self.freeze {
+                sheet.set_freeze_panes(rows, cols);
+            }
+            if self.autofilter && width > 0 {
+                sheet.set_auto_filter(Some(Range::new(
+                    CellRef::new(1, 1),
+                    CellRef::new(width, last_row.max(1)),
+                )));
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_sheets() {
+        let mut bold = Style::default();
+        bold.font.bold = true;
+        let wb = WorkbookBuilder::new()
+            .sheet("Data", |s| {
+                s.headers(["Region", "Sales"])
+                    .header_style(bold)
+                    .row([
+                        CellValue::String("North".to_string()),
+                        CellValue::Number(1.0),
+                    ])
+                    .rows(vec![
+                        vec![CellValue::String("South".to_string())],
+                        vec![
+                            CellValue::Empty,
+                            CellValue::Number(3.0),
+                            CellValue::Number(4.0),
+                        ],
+                    ])
+                    .column_width(1, 20.0)
+                    .freeze_header()
+                    .autofilter()
+            })
+            .sheet("Notes", |s| s)
+            .build()
+            .unwrap();
+
+        assert_eq!(wb.sheet_names(), vec!["Data", "Notes"]);
+        let sheet = wb.get_sheet("Data").unwrap();
+        assert_eq!(
+            sheet.get_value(&CellRef::new(2, 1)),
+            CellValue::String("Sales".to_string())
+        );
+        assert_eq!(sheet.get_value(&CellRef::new(2, 4)), CellValue::Number(3.0));
+        assert!(sheet.get_cell(&CellRef::new(1, 4)).is_none());
+        assert!(
+            wb.get_cell_style("Data", &CellRef::new(2, 1))
+                .unwrap()
+                .font
+                .bold
+        );
+        assert!(
+            !wb.get_cell_style("Data", &CellRef::new(2, 2))
+                .unwrap()
+                .font
+                .bold
+        );
+        assert_eq!(sheet.get_column_width(1), Some(20.0));
+        assert_eq!(sheet.freeze_panes(), Some((1, 0)));
+        assert_eq!(sheet.auto_filter().unwrap().to_a1(), "A1:C4");
+    }
+
+    #[test]
+    fn test_build_columns() {
+        let mut text = Style::default();
+        text.number_format = crate::style::NumberFormat::text();
+        let status = Range::parse("B2:B100").unwrap();
+        let wb = WorkbookBuilder::new()
+            .sheet("Entry", |s| {
+                s.headers(["Code", "Status"])
+                    .column_style(1, text)
+                    .dropdown(Dropdown::from_items(status, &["Open", "Closed"]).unwrap())
+                    .freeze_panes(1, 1)
+            })
+            .build()
+            .unwrap();
+
+        let sheet = wb.get_sheet("Entry").unwrap();
+        let style_id = sheet.get_column_style(1).unwrap();
+        assert_eq!(
+            wb.style_registry().get(style_id).unwrap().number_format,
+            crate::style::NumberFormat::text()
+        );
+        assert!(sheet.get_column_style(2).is_none());
+        assert_eq!(sheet.dropdowns()[0].items(), Some(vec!["Open", "Closed"]));
+        assert_eq!(sheet.freeze_panes(), Some((1, 1)));
+    }
+
+    #[test]
+    fn test_build_errors_and_defaults() {
+        let wb = WorkbookBuilder::new().build().unwrap();
+        assert_eq!(wb.sheet_names(), vec!["Sheet1"]);
+
+        assert!(WorkbookBuilder::new()
+            .sheet("Data", |s| s)
+            .sheet("Data", |s| s)
+            .build()
+            .is_err());
+        assert!(WorkbookBuilder::new().sheet("a/b", |s| s).build().is_err());
+    }
+}
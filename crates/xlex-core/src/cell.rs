@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
 
+use crate::decimal::Decimal;
 use crate::error::{XlexError, XlexResult};
 
 /// Converts an Excel serial number to a human-readable date string.
@@ -52,6 +53,23 @@ fn excel_serial_to_date_string(serial: f64) -> String {
     }
 }
 
+/// Converts a calendar date to an Excel serial number.
+///
+/// Inverse of [`excel_serial_to_date_string`]: dates from 1900-03-01 on are
+/// counted from 1899-12-30 so that they line up with Excel's phantom
+/// 1900-02-29.
+fn date_to_excel_serial(date: chrono::NaiveDate) -> f64 {
+    let leap_bug = chrono::NaiveDate::from_ymd_opt(1900, 3, 1);
+    let epoch = if leap_bug.is_some_and(|d| date >= d) {
+        chrono::NaiveDate::from_ymd_opt(1899, 12, 30)
+    } else {
+        chrono::NaiveDate::from_ymd_opt(1899, 12, 31)
+    };
+    epoch
+        .map(|e| date.signed_duration_since(e).num_days() as f64)
+        .unwrap_or_default()
+}
+
 /// A reference to a cell in A1 notation.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct CellRef {
@@ -121,6 +139,15 @@ impl CellRef {
         Ok(Self { col, row })
     }
 
+    /// Parses a reference with an optional sheet: `B2`, `Sheet1!B2` or
+    /// `'My Sheet'!B2`.
+    pub fn parse_qualified(s: &str) -> XlexResult<(Option<String>, Self)> {
+        match crate::sheet::split_sheet_ref(s) {
+            Some((sheet, reference)) => Ok((Some(sheet), Self::parse(reference)?)),
+            None => Ok((None, Self::parse(s)?)),
+        }
+    }
+
     /// Converts a column letter sequence to a number (A=1, B=2, ..., Z=26, AA=27).
     fn col_from_letters(s: &str) -> Option<u32> {
         let mut result: u32 = 0;
@@ -197,6 +224,9 @@ pub enum CellValue {
     Error(CellError),
     /// Date/time value (stored as Excel serial number)
     DateTime(f64),
+    /// Exact decimal number, written to the file with every digit. Files
+    /// store numbers as text, so it reads back as a [`CellValue::Number`].
+    Decimal(Decimal),
 }
 
 impl CellValue {
@@ -215,6 +245,21 @@ impl CellValue {
         Self::Boolean(b)
     }
 
+    /// Creates a new date value from a calendar date.
+    pub fn date(date: chrono::NaiveDate) -> Self {
+        Self::DateTime(date_to_excel_serial(date))
+    }
+
+    /// Creates a new date/time value; the time of day is the fractional
+    /// part of the serial number.
+    pub fn datetime(datetime: chrono::NaiveDateTime) -> Self {
+        use chrono::Timelike;
+
+        let seconds =
+            datetime.num_seconds_from_midnight() as f64 + f64::from(datetime.nanosecond()) / 1e9;
+        Self::DateTime(date_to_excel_serial(datetime.date()) + seconds / 86_400.0)
+    }
+
     /// Creates a new formula value.
     pub fn formula(f: impl Into<String>) -> Self {
         Self::Formula {
@@ -238,6 +283,20 @@ impl CellValue {
             Self::Formula { .. } => "formula",
             Self::Error(_) => "error",
             Self::DateTime(_) => "datetime",
+            Self::Decimal(_) => "number",
+        }
+    }
+
+    /// The error this value shows, either directly or as a formula's cached
+    /// result.
+    pub fn error(&self) -> Option<CellError> {
+        match self {
+            Self::Error(e) => Some(*e),
+            Self::Formula {
+                cached_result: Some(cached),
+                ..
+            } => cached.error(),
+            _ => None,
         }
     }
 
@@ -258,6 +317,17 @@ impl CellValue {
             Self::Formula { formula, .. } => format!("={}", formula),
             Self::Error(e) => e.to_string(),
             Self::DateTime(serial) => excel_serial_to_date_string(*serial),
+            Self::Decimal(d) => d.to_string(),
+        }
+    }
+
+    /// The number in a number, date or decimal cell. Formulas and text give
+    /// `None`.
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Self::Number(n) | Self::DateTime(n) => Some(*n),
+            Self::Decimal(d) => Some(d.to_f64()),
+            _ => None,
         }
     }
 }
@@ -268,6 +338,136 @@ impl fmt::Display for CellValue {
     }
 }
 
+macro_rules! number_from {
+    ($($t:ty),*) => {
+        $(impl From<$t> for CellValue {
+            fn from(n: $t) -> Self {
+                Self::Number(n as f64)
+            }
+        })*
+    };
+}
+
+number_from!(f64, f32, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl From<bool> for CellValue {
+    fn from(b: bool) -> Self {
+        Self::Boolean(b)
+    }
+}
+
+impl From<&str> for CellValue {
+    fn from(s: &str) -> Self {
+        Self::String(s.to_string())
+    }
+}
+
+impl From<String> for CellValue {
+    fn from(s: String) -> Self {
+        Self::String(s)
+    }
+}
+
+impl From<Decimal> for CellValue {
+    fn from(d: Decimal) -> Self {
+        Self::Decimal(d)
+    }
+}
+
+impl From<CellError> for CellValue {
+    fn from(e: CellError) -> Self {
+        Self::Error(e)
+    }
+}
+
+impl From<chrono::NaiveDate> for CellValue {
+    fn from(date: chrono::NaiveDate) -> Self {
+        Self::date(date)
+    }
+}
+
+impl From<chrono::NaiveDateTime> for CellValue {
+    fn from(datetime: chrono::NaiveDateTime) -> Self {
+        Self::datetime(datetime)
+    }
+}
+
+/// `None` becomes an empty cell.
+impl<T: Into<CellValue>> From<Option<T>> for CellValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Empty, Into::into)
+    }
+}
+
+/// Converts a cell value to JSON for export and machine-readable output.
+///
+/// Empty cells become `null`, and strings, numbers and booleans map to their
+/// JSON counterparts (non-finite numbers become `null`). Dates are written as
+/// strings (`2024-01-15`, or `2024-01-15 18:00:00` with a time part)
+/// rather than serial numbers; formulas and errors use their display text.
+impl From<&CellValue> for serde_json::Value {
+    fn from(value: &CellValue) -> Self {
+        match value {
+            CellValue::Empty => Self::Null,
+            CellValue::String(s) => Self::String(s.clone()),
+            CellValue::Number(n) => {
+                serde_json::Number::from_f64(*n).map_or(Self::Null, Self::Number)
+            }
+            CellValue::Boolean(b) => Self::Bool(*b),
+            CellValue::Decimal(d) => {
+                serde_json::Number::from_f64(d.to_f64()).map_or(Self::Null, Self::Number)
+            }
+            _ => Self::String(value.to_display_string()),
+        }
+    }
+}
+
+impl From<CellValue> for serde_json::Value {
+    fn from(value: CellValue) -> Self {
+        match value {
+            CellValue::String(s) => Self::String(s),
+            value => Self::from(&value),
+        }
+    }
+}
+
+/// Converts a JSON scalar to a cell value.
+///
+/// `null` becomes an empty cell. Strings are kept as text, including ISO
+/// dates: JSON has no date type, so callers that want date cells parse the
+/// string and use [`CellValue::date`]. Arrays and objects are rejected.
+impl TryFrom<serde_json::Value> for CellValue {
+    type Error = XlexError;
+
+    fn try_from(value: serde_json::Value) -> XlexResult<Self> {
+        match value {
+            serde_json::Value::Null => Ok(Self::Empty),
+            serde_json::Value::Bool(b) => Ok(Self::Boolean(b)),
+            serde_json::Value::Number(n) => {
+                n.as_f64()
+                    .map(Self::Number)
+                    .ok_or_else(|| XlexError::InvalidCellValue {
+                        message: format!("number {} is out of range", n),
+                    })
+            }
+            serde_json::Value::String(s) => Ok(Self::String(s)),
+            value @ (serde_json::Value::Array(_) | serde_json::Value::Object(_)) => {
+                Err(XlexError::InvalidCellValue {
+                    message: format!("cannot store JSON {} in a cell", value),
+                })
+            }
+        }
+    }
+}
+
+impl TryFrom<&serde_json::Value> for CellValue {
+    type Error = XlexError;
+
+    fn try_from(value: &serde_json::Value) -> XlexResult<Self> {
+        Self::try_from(value.clone())
+    }
+}
+
 /// Excel cell error types.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CellError {
@@ -287,28 +487,52 @@ pub enum CellError {
     Na,
     /// #GETTING_DATA - Data retrieval in progress
     GettingData,
+    /// #SPILL! - Dynamic array result blocked or out of bounds
+    Spill,
+    /// #CALC! - Calculation engine cannot produce a result (e.g., empty array)
+    Calc,
+    /// #FIELD! - Missing field in a linked data type
+    Field,
+    /// #BLOCKED! - Access to a required resource is blocked
+    Blocked,
+    /// #CONNECT! - External data connection failed
+    Connect,
+    /// #BUSY! - External resource is still computing
+    Busy,
+    /// #UNKNOWN! - Data type not supported by this version of Excel
+    Unknown,
 }
 
 impl CellError {
+    /// Every error type, in the order Excel's `ERROR.TYPE` numbers them.
+    pub const ALL: [Self; 15] = [
+        Self::Null,
+        Self::DivZero,
+        Self::Value,
+        Self::Ref,
+        Self::Name,
+        Self::Num,
+        Self::Na,
+        Self::GettingData,
+        Self::Spill,
+        Self::Connect,
+        Self::Blocked,
+        Self::Unknown,
+        Self::Field,
+        Self::Calc,
+        Self::Busy,
+    ];
+
     /// Parses an error string (e.g., "#VALUE!").
     pub fn parse(s: &str) -> Option<Self> {
-        match s.to_uppercase().as_str() {
-            "#NULL!" => Some(Self::Null),
-            "#DIV/0!" => Some(Self::DivZero),
-            "#VALUE!" => Some(Self::Value),
-            "#REF!" => Some(Self::Ref),
-            "#NAME?" => Some(Self::Name),
-            "#NUM!" => Some(Self::Num),
-            "#N/A" => Some(Self::Na),
-            "#GETTING_DATA" => Some(Self::GettingData),
-            _ => None,
-        }
+        Self::ALL
+            .into_iter()
+            .find(|error| error.as_str().eq_ignore_ascii_case(s))
     }
-}
 
-impl fmt::Display for CellError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let s = match self {
+    /// The error as Excel displays it (e.g., "#VALUE!").
+    pub fn as_str(&self) -> &'static str {
+        match self {
             Self::Null => "#NULL!",
             Self::DivZero => "#DIV/0!",
             Self::Value => "#VALUE!",
@@ -317,8 +541,58 @@ impl fmt::Display for CellError {
             Self::Num => "#NUM!",
             Self::Na => "#N/A",
             Self::GettingData => "#GETTING_DATA",
-        };
-        write!(f, "{}", s)
+            Self::Spill => "#SPILL!",
+            Self::Calc => "#CALC!",
+            Self::Field => "#FIELD!",
+            Self::Blocked => "#BLOCKED!",
+            Self::Connect => "#CONNECT!",
+            Self::Busy => "#BUSY!",
+            Self::Unknown => "#UNKNOWN!",
+        }
+    }
+}
+
+impl fmt::Display for CellError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Array formula anchored at a cell, whose result fills a block of cells
+/// starting at the anchor.
+///
+/// The block is kept as a size rather than a range so it moves with the
+/// anchor when rows and columns are inserted or deleted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArrayFormula {
+    /// Rows covered by the result, including the anchor row
+    pub rows: u32,
+    /// Columns covered by the result, including the anchor column
+    pub cols: u32,
+    /// Dynamic array formula that spills its result (FILTER, SORT, ...)
+    /// rather than a legacy Ctrl+Shift+Enter array formula
+    pub dynamic: bool,
+}
+
+impl ArrayFormula {
+    /// Creates an array formula covering `range`, anchored at its top-left cell.
+    pub fn covering(range: &crate::range::Range, dynamic: bool) -> Self {
+        Self {
+            rows: range.height(),
+            cols: range.width(),
+            dynamic,
+        }
+    }
+
+    /// Cells covered by the result when anchored at `anchor`.
+    pub fn range(&self, anchor: &CellRef) -> crate::range::Range {
+        crate::range::Range::new(
+            anchor.clone(),
+            CellRef::new(
+                (anchor.col + self.cols - 1).min(CellRef::MAX_COL),
+                (anchor.row + self.rows - 1).min(CellRef::MAX_ROW),
+            ),
+        )
     }
 }
 
@@ -335,6 +609,8 @@ pub struct Cell {
     pub comment: Option<String>,
     /// Hyperlink URL, if any
     pub hyperlink: Option<String>,
+    /// Array formula anchored here, when the value is an array formula
+    pub array: Option<ArrayFormula>,
 }
 
 impl Cell {
@@ -346,6 +622,7 @@ impl Cell {
             style_id: None,
             comment: None,
             hyperlink: None,
+            array: None,
         }
     }
 
@@ -371,6 +648,12 @@ impl Cell {
         self.hyperlink = Some(url.into());
         self
     }
+
+    /// Marks the cell as the anchor of an array formula.
+    pub fn with_array(mut self, array: ArrayFormula) -> Self {
+        self.array = Some(array);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -418,6 +701,25 @@ mod tests {
         assert!(CellRef::parse("ABC").is_err()); // Only letters
     }
 
+    #[test]
+    fn test_cell_ref_parse_qualified() {
+        assert_eq!(
+            CellRef::parse_qualified("B2").unwrap(),
+            (None, CellRef::new(2, 2))
+        );
+        assert_eq!(
+            CellRef::parse_qualified("Sheet1!B2").unwrap(),
+            (Some("Sheet1".to_string()), CellRef::new(2, 2))
+        );
+        assert_eq!(
+            CellRef::parse_qualified("'Bob''s Sheet'!c3").unwrap(),
+            (Some("Bob's Sheet".to_string()), CellRef::new(3, 3))
+        );
+        assert!(CellRef::parse_qualified("Sheet1!").is_err());
+        assert!(CellRef::parse_qualified("!B2").is_err());
+        assert!(CellRef::parse_qualified("'Open!B2").is_err());
+    }
+
     #[test]
     fn test_cell_ref_to_a1() {
         assert_eq!(CellRef::new(1, 1).to_a1(), "A1");
@@ -538,6 +840,115 @@ mod tests {
         assert!(!CellValue::boolean(false).is_empty());
     }
 
+    #[test]
+    fn test_cell_value_date() {
+        let date = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(CellValue::date(date(1900, 1, 1)), CellValue::DateTime(1.0));
+        assert_eq!(CellValue::date(date(1900, 3, 1)), CellValue::DateTime(61.0));
+        assert_eq!(
+            CellValue::date(date(2024, 1, 15)),
+            CellValue::DateTime(45306.0)
+        );
+        assert_eq!(
+            CellValue::date(date(2024, 1, 15)).to_display_string(),
+            "2024-01-15"
+        );
+    }
+
+    #[test]
+    fn test_cell_value_from() {
+        assert_eq!(CellValue::from(42), CellValue::Number(42.0));
+        assert_eq!(CellValue::from(2.5f32), CellValue::Number(2.5));
+        assert_eq!(CellValue::from(7usize), CellValue::Number(7.0));
+        assert_eq!(CellValue::from(true), CellValue::Boolean(true));
+        assert_eq!(CellValue::from("a"), CellValue::String("a".to_string()));
+        assert_eq!(
+            CellValue::from("b".to_string()),
+            CellValue::String("b".to_string())
+        );
+        assert_eq!(
+            CellValue::from(CellError::DivZero),
+            CellValue::Error(CellError::DivZero)
+        );
+        assert_eq!(CellValue::from(None::<f64>), CellValue::Empty);
+        assert_eq!(
+            CellValue::from(Some("x")),
+            CellValue::String("x".to_string())
+        );
+
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        assert_eq!(CellValue::from(date), CellValue::DateTime(45306.0));
+        let noon = date.and_hms_opt(18, 0, 0).unwrap();
+        assert_eq!(CellValue::from(noon), CellValue::DateTime(45306.75));
+    }
+
+    #[test]
+    fn test_cell_error_parse_all() {
+        for error in CellError::ALL {
+            assert_eq!(CellError::parse(error.as_str()), Some(error));
+            assert_eq!(error.to_string(), error.as_str());
+        }
+        assert_eq!(CellError::parse("#spill!"), Some(CellError::Spill));
+        assert_eq!(CellError::parse("#CALC!"), Some(CellError::Calc));
+        assert_eq!(CellError::parse("#OOPS!"), None);
+    }
+
+    #[test]
+    fn test_cell_value_error() {
+        assert_eq!(CellValue::Error(CellError::Na).error(), Some(CellError::Na));
+        let formula = CellValue::Formula {
+            formula: "1/0".to_string(),
+            cached_result: Some(Box::new(CellValue::Error(CellError::DivZero))),
+        };
+        assert_eq!(formula.error(), Some(CellError::DivZero));
+        assert_eq!(CellValue::formula("A1").error(), None);
+        assert_eq!(CellValue::Number(1.0).error(), None);
+    }
+
+    #[test]
+    fn test_cell_value_to_json() {
+        use serde_json::{json, Value};
+
+        assert_eq!(Value::from(CellValue::Empty), Value::Null);
+        assert_eq!(Value::from(CellValue::from("a")), json!("a"));
+        assert_eq!(Value::from(&CellValue::Number(1.5)), json!(1.5));
+        assert_eq!(Value::from(CellValue::Number(f64::NAN)), Value::Null);
+        assert_eq!(Value::from(CellValue::Boolean(true)), json!(true));
+        assert_eq!(
+            Value::from(CellValue::DateTime(45306.0)),
+            json!("2024-01-15")
+        );
+        assert_eq!(
+            Value::from(CellValue::DateTime(45306.75)),
+            json!("2024-01-15 18:00:00")
+        );
+        assert_eq!(
+            Value::from(CellValue::Error(CellError::DivZero)),
+            json!("#DIV/0!")
+        );
+    }
+
+    #[test]
+    fn test_cell_value_from_json() {
+        use serde_json::json;
+
+        assert_eq!(CellValue::try_from(json!(null)).unwrap(), CellValue::Empty);
+        assert_eq!(
+            CellValue::try_from(json!(false)).unwrap(),
+            CellValue::Boolean(false)
+        );
+        assert_eq!(
+            CellValue::try_from(&json!(3)).unwrap(),
+            CellValue::Number(3.0)
+        );
+        assert_eq!(
+            CellValue::try_from(json!("2024-01-15")).unwrap(),
+            CellValue::String("2024-01-15".to_string())
+        );
+        assert!(CellValue::try_from(json!([1, 2])).is_err());
+        assert!(CellValue::try_from(json!({"a": 1})).is_err());
+    }
+
     #[test]
     fn test_cell_value_constructors() {
         let s = CellValue::string("test");
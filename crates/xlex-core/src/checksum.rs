@@ -0,0 +1,226 @@
+//! Detecting parts of a workbook package damaged in storage.
+//!
+//! Every part of an xlsx file is stored with the CRC-32 checksum of its
+//! content. Reads that run to the end of a part check it, so a damaged
+//! part fails to read with [`XlexError::InvalidZipStructure`] instead of
+//! yielding wrong data. [`verify_checksums`] checks every part up front
+//! and, for worksheets, finds the first row that cannot be read back.
+//!
+//! ```no_run
+//! let data = std::fs::read("report.xlsx").unwrap();
+//! for damaged in xlex_core::checksum::verify_checksums(&data).unwrap() {
+//!     println!("{}: {}", damaged.part, damaged.problem);
+//! }
+//! ```
+
+use std::io::{self, Cursor, Read};
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use zip::ZipArchive;
+
+use crate::error::XlexResult;
+
+/// A part whose content does not match its checksum or cannot be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DamagedPart {
+    /// Part name (e.g. `xl/worksheets/sheet1.xml`)
+    pub part: String,
+    /// What is wrong with it
+    pub problem: String,
+    /// For a worksheet, the first row that cannot be read back intact;
+    /// rows before it are undamaged. `None` when the damage leaves the
+    /// XML well-formed, so it cannot be placed.
+    pub damaged_from_row: Option<u32>,
+}
+
+/// Returns true if `error` is a read failing its part's CRC-32 check.
+pub(crate) fn is_checksum_error(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::InvalidData && error.to_string() == "Invalid checksum"
+}
+
+/// Reads every part of the package in `data`, returning those that fail
+/// their checksum, cannot be decompressed or cannot be opened. An
+/// undamaged package gives an empty list.
+pub fn verify_checksums(data: &[u8]) -> XlexResult<Vec<DamagedPart>> {
+    let mut archive = ZipArchive::new(Cursor::new(data))?;
+    let mut damaged = Vec::new();
+    for i in 0..archive.len() {
+        let part = archive.name_for_index(i).unwrap_or_default().to_string();
+        let mut file = match archive.by_index(i) {
+            Ok(file) => file,
+            Err(e) => {
+                damaged.push(DamagedPart {
+                    part,
+                    problem: format!("cannot be opened: {}", e),
+                    damaged_from_row: None,
+                });
+                continue;
+            }
+        };
+        let mut content = Vec::new();
+        let Err(e) = file.read_to_end(&mut content) else {
+            continue;
+        };
+        let complete = is_checksum_error(&e);
+        let problem = if complete {
+            "CRC-32 checksum mismatch".to_string()
+        } else {
+            format!("cannot be decompressed: {}", e)
+        };
+        let damaged_from_row = if part.starts_with("xl/worksheets/") {
+            first_unreadable_row(&content, complete)
+        } else {
+            None
+        };
+        damaged.push(DamagedPart {
+            part,
+            problem,
+            damaged_from_row,
+        });
+    }
+    Ok(damaged)
+}
+
+/// Finds the first row of worksheet XML that does not parse, or that is
+/// cut off when `complete` is false because decompression stopped early.
+fn first_unreadable_row(xml: &[u8], complete: bool) -> Option<u32> {
+    let mut reader = Reader::from_reader(xml);
+    let mut buf = Vec::new();
+    let mut open_row = None;
+    let mut last_row = 0;
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) if e.name().as_ref() == b"row" => {
+                let row = e
+                    .try_get_attribute("r")
+                    .ok()
+                    .flatten()
+                    .and_then(|a| String::from_utf8_lossy(&a.value).parse().ok());
+                open_row = Some(row.unwrap_or(last_row + 1));
+            }
+            Ok(Event::Empty(e)) if e.name().as_ref() == b"row" => {
+                let row = e
+                    .try_get_attribute("r")
+                    .ok()
+                    .flatten()
+                    .and_then(|a| String::from_utf8_lossy(&a.value).parse().ok());
+                last_row = row.unwrap_or(last_row + 1);
+            }
+            Ok(Event::End(e)) if e.name().as_ref() == b"row" => {
+                last_row = open_row.take().unwrap_or(last_row + 1);
+            }
+            Ok(Event::Eof) if complete => return None,
+            Ok(Event::Eof) | Err(_) => return Some(open_row.unwrap_or(last_row + 1)),
+            _ => {}
+        }
+        buf.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CellRef, CellValue, LazyWorkbook, Workbook, XlexError};
+    use std::io::Write;
+
+    /// A workbook with five rows in Sheet1, repackaged without compression
+    /// so its sheet XML can be damaged in place.
+    fn stored_workbook() -> Vec<u8> {
+        let mut wb = Workbook::new();
+        for row in 1..=5 {
+            wb.set_cell(
+                "Sheet1",
+                CellRef::new(1, row),
+                CellValue::Number(row.into()),
+            )
+            .unwrap();
+        }
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("stored.xlsx");
+        wb.save_as(&path).unwrap();
+
+        let mut source = ZipArchive::new(Cursor::new(std::fs::read(&path).unwrap())).unwrap();
+        let mut out = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        for i in 0..source.len() {
+            let mut file = source.by_index(i).unwrap();
+            let mut content = Vec::new();
+            file.read_to_end(&mut content).unwrap();
+            out.start_file(file.name(), options).unwrap();
+            out.write_all(&content).unwrap();
+        }
+        out.finish().unwrap().into_inner()
+    }
+
+    fn replace(data: &mut [u8], from: &[u8], to: &[u8]) {
+        let at = data.windows(from.len()).position(|w| w == from).unwrap();
+        data[at..at + to.len()].copy_from_slice(to);
+    }
+
+    #[test]
+    fn test_verify_checksums() {
+        let intact = stored_workbook();
+        assert!(verify_checksums(&intact).unwrap().is_empty());
+        assert!(verify_checksums(b"not a zip").is_err());
+
+        // Breaks the XML inside row 4
+        let mut broken = intact.clone();
+        replace(&mut broken, b"<v>4</v>", b"<v>4</x>");
+        let damaged = verify_checksums(&broken).unwrap();
+        assert_eq!(damaged.len(), 1);
+        assert_eq!(damaged[0].part, "xl/worksheets/sheet1.xml");
+        assert_eq!(damaged[0].problem, "CRC-32 checksum mismatch");
+        assert_eq!(damaged[0].damaged_from_row, Some(4));
+
+        // Changes a value but leaves the XML well-formed
+        let mut altered = intact;
+        replace(&mut altered, b"<v>4</v>", b"<v>9</v>");
+        let damaged = verify_checksums(&altered).unwrap();
+        assert_eq!(damaged[0].damaged_from_row, None);
+    }
+
+    #[test]
+    fn test_first_unreadable_row() {
+        let xml = br#"<sheetData><row r="1"><c r="A1"/></row><row r="2"><c r="A2"/>"#;
+        assert_eq!(first_unreadable_row(xml, false), Some(2));
+        assert_eq!(first_unreadable_row(&xml[..39], false), Some(2));
+        assert_eq!(first_unreadable_row(b"<sheetData></sheetData>", true), None);
+    }
+
+    #[test]
+    fn test_streaming_read_checks_crc() {
+        let mut altered = stored_workbook();
+        replace(&mut altered, b"<v>4</v>", b"<v>9</v>");
+        let lazy = LazyWorkbook::from_bytes(altered).unwrap();
+        let error = lazy.stream_rows("Sheet1").unwrap_err();
+        assert!(matches!(
+            error.without_context(),
+            XlexError::InvalidZipStructure { .. }
+        ));
+        assert!(error.to_string().contains("xl/worksheets/sheet1.xml"));
+        assert!(lazy.stream_column("Sheet1", 1).is_err());
+
+        // The row-offset path used by `export csv`
+        let range = crate::Range::parse("A1:A5").unwrap();
+        let error = lazy.stream_rows_in("Sheet1", &range).unwrap_err();
+        assert!(error.to_string().contains("xl/worksheets/sheet1.xml"));
+    }
+
+    #[test]
+    fn test_open_rejects_damaged_sheet() {
+        let mut altered = stored_workbook();
+        replace(&mut altered, b"<v>4</v>", b"<v>9</v>");
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("damaged.xlsx");
+        std::fs::write(&path, altered).unwrap();
+
+        let error = Workbook::open(&path).unwrap_err();
+        assert!(matches!(
+            error.without_context(),
+            XlexError::InvalidZipStructure { .. }
+        ));
+        assert!(error.to_string().contains("xl/worksheets/sheet1.xml"));
+    }
+}
@@ -0,0 +1,635 @@
+//! Cell-level comparison of two workbooks.
+//!
+//! [`compare_workbooks`] lists what differs between two versions of a
+//! workbook: sheets or rows present in only one, cells whose values differ,
+//! and cells formatted differently. [`ComparisonOptions`] tunes it for
+//! regression tests of generated reports, where styling changes and float
+//! jitter are noise and rows may move:
+//!
+//! ```
+//! use xlex_core::compare::{compare_workbooks, ComparisonOptions};
+//! use xlex_core::{CellRef, CellValue, Workbook};
+//!
+//! let mut old = Workbook::new();
+//! old.set_cell("Sheet1", CellRef::new(1, 1), CellValue::Number(0.1 + 0.2)).unwrap();
+//! let mut new = Workbook::new();
+//! new.set_cell("Sheet1", CellRef::new(1, 1), CellValue::Number(0.3)).unwrap();
+//!
+//! assert_eq!(compare_workbooks(&old, &new, &ComparisonOptions::new()).len(), 1);
+//! let options = ComparisonOptions::new().values_only(true).tolerance(1e-9);
+//! assert!(compare_workbooks(&old, &new, &options).is_empty());
+//! ```
+//!
+//! [`compare_rows`] summarizes the same comparison row by row, as the rows
+//! added, removed and changed, for feeding edits to a hand-maintained
+//! sheet into another system.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+use crate::cell::{Cell, CellRef, CellValue};
+use crate::sheet::Sheet;
+use crate::workbook::Workbook;
+
+/// What [`compare_workbooks`] looks at.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ComparisonOptions {
+    /// Compare only what cells show: formulas by their cached results, and
+    /// formatting ignored
+    pub values_only: bool,
+    /// Largest difference between two numbers still counted as equal
+    pub tolerance: f64,
+    /// Column (1-based) whose values pair up rows, instead of row numbers
+    pub key_column: Option<u32>,
+}
+
+impl ComparisonOptions {
+    /// Creates the default options: exact comparison of values, formulas
+    /// and formatting, row by row.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether only displayed values are compared.
+    pub fn values_only(mut self, values_only: bool) -> Self {
+        self.values_only = values_only;
+        self
+    }
+
+    /// Sets the largest difference between numbers counted as equal.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance.abs();
+        self
+    }
+
+    /// Pairs rows by the value in this column (1-based) instead of by
+    /// row number.
+    pub fn key_column(mut self, col: Option<u32>) -> Self {
+        self.key_column = col;
+        self
+    }
+}
+
+/// Which of the two compared workbooks something is in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// The first workbook
+    Left,
+    /// The second workbook
+    Right,
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Left => write!(f, "left"),
+            Self::Right => write!(f, "right"),
+        }
+    }
+}
+
+/// One difference between two workbooks.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Difference {
+    /// A sheet present in only one workbook
+    Sheet {
+        /// Sheet name
+        sheet: String,
+        /// The workbook holding it
+        only_in: Side,
+    },
+    /// A row whose key is present in only one sheet (with a key column)
+    Row {
+        /// Sheet name
+        sheet: String,
+        /// The row's key
+        key: String,
+        /// Row number in the sheet holding it
+        row: u32,
+        /// The workbook holding it
+        only_in: Side,
+    },
+    /// A cell whose value or formula differs
+    Value {
+        /// Sheet name
+        sheet: String,
+        /// Cell in the left workbook
+        left: CellRef,
+        /// Cell in the right workbook (another row with a key column)
+        right: CellRef,
+        /// Value in the left workbook
+        left_value: CellValue,
+        /// Value in the right workbook
+        right_value: CellValue,
+    },
+    /// A cell formatted differently (unless comparing values only)
+    Style {
+        /// Sheet name
+        sheet: String,
+        /// Cell in the left workbook
+        left: CellRef,
+        /// Cell in the right workbook
+        right: CellRef,
+    },
+}
+
+impl Difference {
+    /// Name of the sheet the difference is on.
+    pub fn sheet(&self) -> &str {
+        match self {
+            Self::Sheet { sheet, .. }
+            | Self::Row { sheet, .. }
+            | Self::Value { sheet, .. }
+            | Self::Style { sheet, .. } => sheet,
+        }
+    }
+}
+
+/// How a row differs between two versions of a sheet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowChangeKind {
+    /// Only in the right sheet
+    Added,
+    /// Only in the left sheet
+    Removed,
+    /// In both, with different values
+    Changed,
+}
+
+impl fmt::Display for RowChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Added => write!(f, "added"),
+            Self::Removed => write!(f, "removed"),
+            Self::Changed => write!(f, "changed"),
+        }
+    }
+}
+
+/// A row added, removed or changed between two versions of a sheet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowChange {
+    /// How the row changed
+    pub kind: RowChangeKind,
+    /// The row's key, or its left row number without a key column
+    pub key: String,
+    /// Row number in the left sheet (`None` for added rows)
+    pub left_row: Option<u32>,
+    /// Row number in the right sheet (`None` for removed rows)
+    pub right_row: Option<u32>,
+    /// Columns whose values differ, for changed rows
+    pub columns: Vec<u32>,
+}
+
+/// Formats a cell pair as `B3`, or `B3 → B5` when rows were paired by key.
+fn cell_pair(left: &CellRef, right: &CellRef) -> String {
+    if left == right {
+        left.to_a1()
+    } else {
+        format!("{} → {}", left.to_a1(), right.to_a1())
+    }
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sheet { sheet, only_in } => write!(f, "sheet '{}' only in {}", sheet, only_in),
+            Self::Row {
+                sheet,
+                key,
+                row,
+                only_in,
+            } => write!(
+                f,
+                "{}: row {} (key {:?}) only in {}",
+                sheet, row, key, only_in
+            ),
+            Self::Value {
+                sheet,
+                left,
+                right,
+                left_value,
+                right_value,
+            } => write!(
+                f,
+                "{}!{}: {} ≠ {}",
+                sheet,
+                cell_pair(left, right),
+                describe(left_value),
+                describe(right_value)
+            ),
+            Self::Style { sheet, left, right } => {
+                write!(
+                    f,
+                    "{}!{}: formatting differs",
+                    sheet,
+                    cell_pair(left, right)
+                )
+            }
+        }
+    }
+}
+
+/// Describes a value for a difference report, quoting text so that `"1"`
+/// and `1` tell apart.
+pub(crate) fn describe(value: &CellValue) -> String {
+    match value {
+        CellValue::Empty => "(empty)".to_string(),
+        CellValue::String(s) => format!("{:?}", s),
+        CellValue::Formula {
+            formula,
+            cached_result: Some(result),
+        } => format!("={} [{}]", formula, describe(result)),
+        other => other.to_display_string(),
+    }
+}
+
+/// Lists the differences between two workbooks, sheet by sheet in the
+/// order of the left workbook, then sheets only in the right one.
+pub fn compare_workbooks(
+    left: &Workbook,
+    right: &Workbook,
+    options: &ComparisonOptions,
+) -> Vec<Difference> {
+    let mut differences = Vec::new();
+    for name in left.sheet_names() {
+        if right.get_sheet(name).is_some() {
+            differences.extend(compare_sheets(left, name, right, name, options));
+        } else {
+            differences.push(Difference::Sheet {
+                sheet: name.to_string(),
+                only_in: Side::Left,
+            });
+        }
+    }
+    for name in right.sheet_names() {
+        if left.get_sheet(name).is_none() {
+            differences.push(Difference::Sheet {
+                sheet: name.to_string(),
+                only_in: Side::Right,
+            });
+        }
+    }
+    differences
+}
+
+/// Lists the differences between a sheet of one workbook and a sheet of
+/// another, reported under the left sheet's name. Missing sheets compare
+/// as empty.
+pub fn compare_sheets(
+    left: &Workbook,
+    left_sheet: &str,
+    right: &Workbook,
+    right_sheet: &str,
+    options: &ComparisonOptions,
+) -> Vec<Difference> {
+    let left_rows = left.get_sheet(left_sheet).map(rows).unwrap_or_default();
+    let right_rows = right.get_sheet(right_sheet).map(rows).unwrap_or_default();
+
+    let mut differences = Vec::new();
+    let (pairs, unpaired) = pair_rows(&left_rows, &right_rows, options.key_column);
+    for (only_in, row, key) in unpaired {
+        differences.push(Difference::Row {
+            sheet: left_sheet.to_string(),
+            key,
+            row,
+            only_in,
+        });
+    }
+
+    let no_cells = BTreeMap::new();
+    for (left_row, right_row) in pairs {
+        let left_cells = left_rows.get(&left_row).unwrap_or(&no_cells);
+        let right_cells = right_rows.get(&right_row).unwrap_or(&no_cells);
+        let cols: BTreeSet<u32> = left_cells
+            .keys()
+            .chain(right_cells.keys())
+            .copied()
+            .collect();
+        for col in cols {
+            let left_ref = CellRef::new(col, left_row);
+            let right_ref = CellRef::new(col, right_row);
+            let left_value = left_cells.get(&col).map_or(&CellValue::Empty, |c| &c.value);
+            let right_value = right_cells
+                .get(&col)
+                .map_or(&CellValue::Empty, |c| &c.value);
+            if !values_equal(left_value, right_value, options) {
+                differences.push(Difference::Value {
+                    sheet: left_sheet.to_string(),
+                    left: left_ref.clone(),
+                    right: right_ref.clone(),
+                    left_value: left_value.clone(),
+                    right_value: right_value.clone(),
+                });
+            }
+            if !options.values_only
+                && left.get_cell_style(left_sheet, &left_ref).ok()
+                    != right.get_cell_style(right_sheet, &right_ref).ok()
+            {
+                differences.push(Difference::Style {
+                    sheet: left_sheet.to_string(),
+                    left: left_ref,
+                    right: right_ref,
+                });
+            }
+        }
+    }
+    differences
+}
+
+/// Lists the rows added, removed and changed between a sheet of one
+/// workbook and a sheet of another.
+///
+/// Rows are paired as by [`compare_sheets`], which needs a key column to
+/// tell added and removed rows from changed ones. Only values count:
+/// formatting changes are ignored. Removed and added rows come first,
+/// then changed rows in left row order.
+pub fn compare_rows(
+    left: &Workbook,
+    left_sheet: &str,
+    right: &Workbook,
+    right_sheet: &str,
+    options: &ComparisonOptions,
+) -> Vec<RowChange> {
+    let mut changes = Vec::new();
+    let mut changed: BTreeMap<(u32, u32), Vec<u32>> = BTreeMap::new();
+    for difference in compare_sheets(left, left_sheet, right, right_sheet, options) {
+        match difference {
+            Difference::Row {
+                key, row, only_in, ..
+            } => changes.push(RowChange {
+                kind: match only_in {
+                    Side::Left => RowChangeKind::Removed,
+                    Side::Right => RowChangeKind::Added,
+                },
+                key,
+                left_row: (only_in == Side::Left).then_some(row),
+                right_row: (only_in == Side::Right).then_some(row),
+                columns: Vec::new(),
+            }),
+            Difference::Value { left, right, .. } => {
+                changed
+                    .entry((left.row, right.row))
+                    .or_default()
+                    .push(left.col);
+            }
+            Difference::Sheet { .. } | Difference::Style { .. } => {}
+        }
+    }
+
+    let sheet = left.get_sheet(left_sheet);
+    for ((left_row, right_row), columns) in changed {
+        let key = match (options.key_column, sheet) {
+            (Some(col), Some(sheet)) => {
+                let value = sheet.get_value(&CellRef::new(col, left_row));
+                displayed(&value).to_display_string()
+            }
+            _ => left_row.to_string(),
+        };
+        changes.push(RowChange {
+            kind: RowChangeKind::Changed,
+            key,
+            left_row: Some(left_row),
+            right_row: Some(right_row),
+            columns,
+        });
+    }
+    changes
+}
+
+/// Cells of a sheet by row, then column.
+type Rows<'a> = BTreeMap<u32, BTreeMap<u32, &'a Cell>>;
+
+fn rows(sheet: &Sheet) -> Rows<'_> {
+    let mut rows: Rows = BTreeMap::new();
+    for cell in sheet.cells() {
+        rows.entry(cell.reference.row)
+            .or_default()
+            .insert(cell.reference.col, cell);
+    }
+    rows
+}
+
+/// Pairs the rows of two sheets: by row number, or by the key column's
+/// value with repeated keys paired in order. Also returns the rows left
+/// unpaired, with their keys.
+#[allow(clippy::type_complexity)]
+fn pair_rows(
+    left: &Rows,
+    right: &Rows,
+    key_column: Option<u32>,
+) -> (Vec<(u32, u32)>, Vec<(Side, u32, String)>) {
+    let Some(col) = key_column else {
+        let all: BTreeSet<u32> = left.keys().chain(right.keys()).copied().collect();
+        return (all.into_iter().map(|row| (row, row)).collect(), Vec::new());
+    };
+    let key = |cells: &BTreeMap<u32, &Cell>| {
+        cells
+            .get(&col)
+            .map(|cell| displayed(&cell.value).to_display_string())
+            .unwrap_or_default()
+    };
+
+    let mut right_by_key: HashMap<String, Vec<u32>> = HashMap::new();
+    for (&row, cells) in right.iter().rev() {
+        right_by_key.entry(key(cells)).or_default().push(row);
+    }
+    let mut pairs = Vec::new();
+    let mut unpaired = Vec::new();
+    for (&row, cells) in left {
+        let key = key(cells);
+        match right_by_key.get_mut(&key).and_then(Vec::pop) {
+            Some(right_row) => pairs.push((row, right_row)),
+            None => unpaired.push((Side::Left, row, key)),
+        }
+    }
+    let mut right_only: Vec<(Side, u32, String)> = right_by_key
+        .into_iter()
+        .flat_map(|(key, rows)| {
+            rows.into_iter()
+                .map(move |row| (Side::Right, row, key.clone()))
+        })
+        .collect();
+    right_only.sort_by_key(|(_, row, _)| *row);
+    unpaired.extend(right_only);
+    (pairs, unpaired)
+}
+
+/// What a cell shows: a formula's cached result, else the value itself.
+fn displayed(value: &CellValue) -> &CellValue {
+    match value {
+        CellValue::Formula {
+            cached_result: Some(result),
+            ..
+        } => result,
+        CellValue::Formula { .. } => &CellValue::Empty,
+        other => other,
+    }
+}
+
+fn values_equal(left: &CellValue, right: &CellValue, options: &ComparisonOptions) -> bool {
+    let (left, right) = if options.values_only {
+        (displayed(left), displayed(right))
+    } else {
+        (left, right)
+    };
+    match (left, right) {
+        (
+            CellValue::Number(a) | CellValue::DateTime(a),
+            CellValue::Number(b) | CellValue::DateTime(b),
+        ) => a == b || (a - b).abs() <= options.tolerance,
+        (CellValue::Decimal(_), _) | (_, CellValue::Decimal(_)) => {
+            match (left.as_number(), right.as_number()) {
+                (Some(a), Some(b)) => a == b || (a - b).abs() <= options.tolerance,
+                _ => false,
+            }
+        }
+        (
+            CellValue::Formula {
+                formula: a,
+                cached_result: a_result,
+            },
+            CellValue::Formula {
+                formula: b,
+                cached_result: b_result,
+            },
+        ) => {
+            a == b
+                && match (a_result, b_result) {
+                    (Some(a), Some(b)) => values_equal(a, b, options),
+                    _ => true,
+                }
+        }
+        _ => left == right,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::Style;
+
+    fn workbook(cells: &[(&str, CellValue)]) -> Workbook {
+        let mut wb = Workbook::new();
+        for (reference, value) in cells {
+            wb.set_cell("Sheet1", CellRef::parse(reference).unwrap(), value.clone())
+                .unwrap();
+        }
+        wb
+    }
+
+    fn cached(formula: &str, result: f64) -> CellValue {
+        CellValue::Formula {
+            formula: formula.to_string(),
+            cached_result: Some(Box::new(CellValue::Number(result))),
+        }
+    }
+
+    #[test]
+    fn test_compare_values_and_tolerance() {
+        let left = workbook(&[
+            ("A1", CellValue::string("Total")),
+            ("B1", CellValue::Number(1.0)),
+            ("C1", cached("SUM(B1)", 1.0)),
+        ]);
+        let right = workbook(&[
+            ("A1", CellValue::string("Total")),
+            ("B1", CellValue::Number(1.0 + 1e-12)),
+            ("C1", CellValue::Number(1.0)),
+            ("D1", CellValue::string("new")),
+        ]);
+
+        let diffs = compare_workbooks(&left, &right, &ComparisonOptions::new());
+        let cells: Vec<String> = diffs
+            .iter()
+            .map(|d| match d {
+                Difference::Value { left, .. } => left.to_a1(),
+                other => panic!("unexpected {}", other),
+            })
+            .collect();
+        assert_eq!(cells, ["B1", "C1", "D1"]);
+        assert_eq!(diffs[2].to_string(), "Sheet1!D1: (empty) ≠ \"new\"");
+
+        let options = ComparisonOptions::new().values_only(true).tolerance(1e-9);
+        let diffs = compare_workbooks(&left, &right, &options);
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].to_string(), "Sheet1!D1: (empty) ≠ \"new\"");
+    }
+
+    #[test]
+    fn test_compare_styles_and_sheets() {
+        let mut left = workbook(&[("A1", CellValue::Number(1.0))]);
+        let mut right = workbook(&[("A1", CellValue::Number(1.0))]);
+        let mut bold = Style::default();
+        bold.font.bold = true;
+        let id = right.style_registry_mut().add(bold);
+        right
+            .get_sheet_mut("Sheet1")
+            .unwrap()
+            .set_cell_style(&CellRef::new(1, 1), Some(id));
+        left.add_sheet("Old").unwrap();
+        right.add_sheet("New").unwrap();
+
+        let diffs = compare_workbooks(&left, &right, &ComparisonOptions::new());
+        let text: Vec<String> = diffs.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            text,
+            [
+                "Sheet1!A1: formatting differs",
+                "sheet 'Old' only in left",
+                "sheet 'New' only in right"
+            ]
+        );
+        let options = ComparisonOptions::new().values_only(true);
+        assert_eq!(compare_workbooks(&left, &right, &options).len(), 2);
+    }
+
+    #[test]
+    fn test_compare_by_key_column() {
+        let left = workbook(&[
+            ("A1", CellValue::string("id")),
+            ("A2", CellValue::string("x")),
+            ("B2", CellValue::Number(1.0)),
+            ("A3", CellValue::string("y")),
+            ("B3", CellValue::Number(2.0)),
+            ("A4", CellValue::string("gone")),
+        ]);
+        let right = workbook(&[
+            ("A1", CellValue::string("id")),
+            ("A2", CellValue::string("new")),
+            ("A3", CellValue::string("y")),
+            ("B3", CellValue::Number(2.0)),
+            ("A4", CellValue::string("x")),
+            ("B4", CellValue::Number(5.0)),
+        ]);
+
+        let options = ComparisonOptions::new().key_column(Some(1));
+        let text: Vec<String> = compare_workbooks(&left, &right, &options)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            text,
+            [
+                "Sheet1: row 4 (key \"gone\") only in left",
+                "Sheet1: row 2 (key \"new\") only in right",
+                "Sheet1!B2 → B4: 1 ≠ 5",
+            ]
+        );
+
+        let changes = compare_rows(&left, "Sheet1", &right, "Sheet1", &options);
+        let summary: Vec<(RowChangeKind, &str, Option<u32>, Option<u32>)> = changes
+            .iter()
+            .map(|c| (c.kind, c.key.as_str(), c.left_row, c.right_row))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (RowChangeKind::Removed, "gone", Some(4), None),
+                (RowChangeKind::Added, "new", None, Some(2)),
+                (RowChangeKind::Changed, "x", Some(2), Some(4)),
+            ]
+        );
+        assert_eq!(changes[2].columns, [2]);
+    }
+}
@@ -0,0 +1,392 @@
+//! Fixed-point decimal numbers.
+//!
+//! Sums of binary floats drift: `0.1 + 0.2` is `0.30000000000000004`, and
+//! a column of amounts adds up to a total a cent off. A [`Decimal`] stores
+//! a number as an integer count of `10^-scale` units, so sums, differences
+//! and products of decimal amounts are exact. Cells hold one as
+//! [`CellValue::Decimal`](crate::CellValue::Decimal), which is written to
+//! the file with every digit.
+//!
+//! ```
+//! use xlex_core::decimal::Decimal;
+//!
+//! let a = Decimal::from_f64(0.1).unwrap();
+//! let b: Decimal = "0.2".parse().unwrap();
+//! assert_eq!(a.checked_add(b).unwrap().to_string(), "0.3");
+//! assert_eq!(Decimal::parse("12.50").unwrap().to_string(), "12.50");
+//! assert_eq!(Decimal::parse("1").unwrap().checked_div(Decimal::parse("3").unwrap())
+//!     .unwrap().to_string(), "0.333333333333333");
+//! ```
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::cell::CellValue;
+use crate::error::XlexError;
+
+/// Most decimal places a [`Decimal`] keeps.
+pub const MAX_SCALE: u32 = 28;
+
+/// Decimal places kept by a quotient, at least.
+pub const DIV_SCALE: u32 = 15;
+
+/// A decimal number: `units / 10^scale`.
+///
+/// The scale is kept as given, so `12.50` prints as `12.50`; equality and
+/// ordering compare values, so `12.50 == 12.5`.
+#[derive(Debug, Clone, Copy)]
+pub struct Decimal {
+    units: i128,
+    scale: u32,
+}
+
+fn pow10(exp: u32) -> Option<i128> {
+    10i128.checked_pow(exp)
+}
+
+impl Decimal {
+    /// Zero, with no decimal places.
+    pub const ZERO: Decimal = Decimal { units: 0, scale: 0 };
+
+    /// Creates `units / 10^scale`; `None` if `scale` exceeds [`MAX_SCALE`].
+    pub fn new(units: i128, scale: u32) -> Option<Self> {
+        (scale <= MAX_SCALE).then_some(Self { units, scale })
+    }
+
+    /// The integer count of `10^-scale` units.
+    pub fn units(&self) -> i128 {
+        self.units
+    }
+
+    /// The number of decimal places.
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Parses decimal text such as `-1234.50`, `+.5` or `1.5e3`. Returns
+    /// `None` for anything else, or past 38 digits.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (mantissa, exponent) = match text.find(['e', 'E']) {
+            Some(i) => (&text[..i], text[i + 1..].parse::<i32>().ok()?),
+            None => (text, 0),
+        };
+        let (negative, digits) = match mantissa.as_bytes().first()? {
+            b'-' => (true, &mantissa[1..]),
+            b'+' => (false, &mantissa[1..]),
+            _ => (false, mantissa),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        if whole.is_empty() && fraction.is_empty()
+            || !whole
+                .bytes()
+                .chain(fraction.bytes())
+                .all(|b| b.is_ascii_digit())
+        {
+            return None;
+        }
+        let mut units: i128 = 0;
+        for b in whole.bytes().chain(fraction.bytes()) {
+            units = units.checked_mul(10)?.checked_add(i128::from(b - b'0'))?;
+        }
+        if negative {
+            units = -units;
+        }
+        let scale = i64::try_from(fraction.len()).ok()? - i64::from(exponent);
+        if scale < 0 {
+            let shift = u32::try_from(-scale).ok()?;
+            return Self::new(units.checked_mul(pow10(shift)?)?, 0);
+        }
+        let scale = u32::try_from(scale).ok()?;
+        if scale > MAX_SCALE {
+            // Drop digits beyond the precision kept
+            return Self { units, scale }.round(MAX_SCALE);
+        }
+        Self::new(units, scale)
+    }
+
+    /// The decimal a float was written as: `0.1` gives exactly `0.1`, the
+    /// shortest text that reads back as the same float. `None` for
+    /// infinities and NaN.
+    pub fn from_f64(value: f64) -> Option<Self> {
+        if !value.is_finite() {
+            return None;
+        }
+        Self::parse(&value.to_string())
+    }
+
+    /// The nearest float.
+    pub fn to_f64(&self) -> f64 {
+        // Through text, so 0.1 gives the float written 0.1
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    /// The value with `scale` decimal places, rounding halves to even when
+    /// places are dropped. `None` on overflow.
+    pub fn round(self, scale: u32) -> Option<Self> {
+        if scale >= self.scale {
+            let units = self.units.checked_mul(pow10(scale - self.scale)?)?;
+            return Self::new(units, scale);
+        }
+        let divisor = pow10(self.scale - scale)?;
+        let (quotient, remainder) = (self.units / divisor, self.units % divisor);
+        let twice = remainder.unsigned_abs() * 2;
+        let step = divisor.unsigned_abs();
+        let away = twice > step || twice == step && quotient % 2 != 0;
+        let units = if away {
+            quotient + self.units.signum()
+        } else {
+            quotient
+        };
+        Self::new(units, scale)
+    }
+
+    /// Drops trailing zero decimal places: `12.50` becomes `12.5`.
+    pub fn normalize(self) -> Self {
+        let mut result = self;
+        while result.scale > 0 && result.units % 10 == 0 {
+            result.units /= 10;
+            result.scale -= 1;
+        }
+        result
+    }
+
+    /// Both values at the larger of their scales.
+    fn aligned(self, other: Self) -> Option<(i128, i128, u32)> {
+        let scale = self.scale.max(other.scale);
+        Some((self.round(scale)?.units, other.round(scale)?.units, scale))
+    }
+
+    /// `self + other`, exactly; `None` on overflow.
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let (a, b, scale) = self.aligned(other)?;
+        Self::new(a.checked_add(b)?, scale)
+    }
+
+    /// `self - other`, exactly; `None` on overflow.
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        let (a, b, scale) = self.aligned(other)?;
+        Self::new(a.checked_sub(b)?, scale)
+    }
+
+    /// `self * other`, exactly up to [`MAX_SCALE`] places; `None` on
+    /// overflow.
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        let units = self.units.checked_mul(other.units)?;
+        let product = Self {
+            units,
+            scale: self.scale + other.scale,
+        };
+        if product.scale > MAX_SCALE {
+            product.round(MAX_SCALE)
+        } else {
+            Some(product)
+        }
+    }
+
+    /// `self / other` to at least [`DIV_SCALE`] places, rounding halves to
+    /// even, with trailing zeros dropped. `None` when dividing by zero or
+    /// on overflow.
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        if other.units == 0 {
+            return None;
+        }
+        let scale = self.scale.max(other.scale).max(DIV_SCALE);
+        let shift = scale + other.scale - self.scale;
+        let numerator = self.units.checked_mul(pow10(shift)?)?;
+        let (quotient, remainder) = (numerator / other.units, numerator % other.units);
+        let twice = remainder.unsigned_abs() * 2;
+        let divisor = other.units.unsigned_abs();
+        let away = twice > divisor || twice == divisor && quotient % 2 != 0;
+        let units = match (away, (numerator < 0) != (other.units < 0)) {
+            (false, _) => quotient,
+            (true, false) => quotient + 1,
+            (true, true) => quotient - 1,
+        };
+        let rounded = Self::new(units, scale)?;
+        Some(rounded.normalize())
+    }
+
+    /// `self / n`, as [`Decimal::checked_div`].
+    pub fn checked_div_int(self, n: i128) -> Option<Self> {
+        self.checked_div(Self { units: n, scale: 0 })
+    }
+}
+
+/// The number in a cell as a decimal, looking through cached formula
+/// results: a decimal cell as is, and a number or date as the text it would
+/// be written as. Text and blanks give `None`.
+pub fn cell_decimal(value: &CellValue) -> Option<Decimal> {
+    match value {
+        CellValue::Decimal(d) => Some(*d),
+        CellValue::Formula {
+            cached_result: Some(result),
+            ..
+        } => cell_decimal(result),
+        value => value.as_number().and_then(Decimal::from_f64),
+    }
+}
+
+impl Default for Decimal {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.aligned(*other) {
+            Some((a, b, _)) => a.cmp(&b),
+            // Too large to align exactly: far enough apart for floats
+            None => self.to_f64().total_cmp(&other.to_f64()),
+        }
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.units.unsigned_abs().to_string();
+        let sign = if self.units < 0 { "-" } else { "" };
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{}{}.{}", sign, whole, fraction)
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = XlexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| XlexError::InvalidCellValue {
+            message: format!("'{}' is not a decimal number", s),
+        })
+    }
+}
+
+/// Serialized as its text, so no digit is lost.
+impl Serialize for Decimal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Decimal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(text: &str) -> Decimal {
+        Decimal::parse(text).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        for text in ["0", "12.50", "-0.05", "123456789012345678901234.5678"] {
+            assert_eq!(d(text).to_string(), text);
+        }
+        assert_eq!(d("+.5").to_string(), "0.5");
+        assert_eq!(d("1.5e3").to_string(), "1500");
+        assert_eq!(d("15E-3").to_string(), "0.015");
+        assert_eq!(d("-7.").to_string(), "-7");
+        for text in ["", "-", ".", "1.2.3", "1e", "abc", "NaN", "inf", "1,5"] {
+            assert_eq!(Decimal::parse(text), None, "{}", text);
+        }
+        assert_eq!(Decimal::parse(&"9".repeat(40)), None);
+        assert_eq!(d("0.12345678901234567890123456789").scale(), MAX_SCALE);
+    }
+
+    #[test]
+    fn test_from_f64() {
+        assert_eq!(Decimal::from_f64(0.1).unwrap().to_string(), "0.1");
+        assert_eq!(Decimal::from_f64(-2.5).unwrap().to_string(), "-2.5");
+        assert_eq!(
+            Decimal::from_f64(1e20).unwrap().to_string(),
+            "100000000000000000000"
+        );
+        assert_eq!(Decimal::from_f64(f64::NAN), None);
+        assert_eq!(d("0.1").to_f64(), 0.1);
+    }
+
+    #[test]
+    fn test_arithmetic_is_exact() {
+        let cents = ["0.1", "0.2", "19.99", "-5.01"].map(d);
+        let sum = cents
+            .iter()
+            .try_fold(Decimal::ZERO, |sum, &n| sum.checked_add(n))
+            .unwrap();
+        assert_eq!(sum.to_string(), "15.28");
+        assert_eq!(d("0.3").checked_sub(d("0.1")).unwrap().to_string(), "0.2");
+        assert_eq!(
+            d("1.5").checked_mul(d("-0.25")).unwrap().to_string(),
+            "-0.375"
+        );
+        assert_eq!(d("10").checked_div_int(4).unwrap().to_string(), "2.5");
+        assert_eq!(
+            d("2").checked_div(d("3")).unwrap().to_string(),
+            "0.666666666666667"
+        );
+        assert_eq!(d("1").checked_div(Decimal::ZERO), None);
+        let max = Decimal::new(i128::MAX, 0).unwrap();
+        assert_eq!(max.checked_add(d("1")), None);
+    }
+
+    #[test]
+    fn test_round_half_even() {
+        assert_eq!(d("2.675").round(2).unwrap().to_string(), "2.68");
+        assert_eq!(d("0.125").round(2).unwrap().to_string(), "0.12");
+        assert_eq!(d("-0.135").round(2).unwrap().to_string(), "-0.14");
+        assert_eq!(d("1.5").round(3).unwrap().to_string(), "1.500");
+        assert_eq!(d("12.500").normalize().to_string(), "12.5");
+        assert_eq!(d("1").checked_div(d("8")).unwrap().to_string(), "0.125");
+        // Halves at the last kept place go to even
+        let tiny = d("0.000000000000001");
+        assert_eq!(tiny.checked_div_int(2).unwrap().to_string(), "0");
+        assert_eq!(tiny.checked_div_int(-2).unwrap().to_string(), "0");
+        let three = d("0.000000000000003");
+        assert_eq!(
+            three.checked_div_int(2).unwrap().to_string(),
+            "0.000000000000002"
+        );
+        assert_eq!(
+            three.checked_div_int(-2).unwrap().to_string(),
+            "-0.000000000000002"
+        );
+    }
+
+    #[test]
+    fn test_compare_and_serde() {
+        assert_eq!(d("12.50"), d("12.5"));
+        assert!(d("-1") < d("0.001"));
+        assert_eq!([d("3"), d("1.5"), d("-2")].iter().max(), Some(&d("3")));
+        let json = serde_json::to_string(&d("0.10")).unwrap();
+        assert_eq!(json, "\"0.10\"");
+        let back: Decimal = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.to_string(), "0.10");
+    }
+}
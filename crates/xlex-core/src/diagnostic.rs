@@ -0,0 +1,149 @@
+//! Structured diagnostics for errors.
+//!
+//! A [`Diagnostic`] gathers what a user or agent needs to act on an
+//! [`XlexError`]: its stable [`ErrorCode`], a link to the code's
+//! documentation and suggestions, most specific first. Suggestions can use
+//! what the caller knows about the workbook: a missing sheet is matched
+//! against the workbook's sheet names with [`closest_match`].
+//!
+//! ```
+//! use xlex_core::diagnostic::Diagnostic;
+//! use xlex_core::XlexError;
+//!
+//! let error = XlexError::SheetNotFound {
+//!     name: "sheet1".to_string(),
+//! };
+//! let diagnostic = Diagnostic::with_sheets(&error, &["Sheet1", "Totals"]);
+//! assert_eq!(diagnostic.code.as_str(), "XLEX_E030");
+//! assert_eq!(diagnostic.suggestions[0], "Did you mean 'Sheet1'?");
+//! ```
+
+use crate::error::{ErrorCode, XlexError};
+
+/// An error's code, documentation and suggestions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Stable error code
+    pub code: ErrorCode,
+    /// Link to the code's documentation
+    pub docs_url: String,
+    /// What to try, most specific first
+    pub suggestions: Vec<String>,
+}
+
+impl Diagnostic {
+    /// Diagnoses `error` from the error alone.
+    pub fn new(error: &XlexError) -> Self {
+        Self::with_sheets::<&str>(error, &[])
+    }
+
+    /// Diagnoses `error` raised on a workbook with `sheets`, suggesting the
+    /// closest sheet name when a sheet is missing.
+    pub fn with_sheets<S: AsRef<str>>(error: &XlexError, sheets: &[S]) -> Self {
+        let code = error.code();
+        let mut suggestions = Vec::new();
+        if let XlexError::SheetNotFound { name } = error.without_context() {
+            if let Some(closest) = closest_match(name, sheets) {
+                suggestions.push(format!("Did you mean '{}'?", closest));
+            } else if !sheets.is_empty() {
+                let names: Vec<&str> = sheets.iter().map(AsRef::as_ref).collect();
+                suggestions.push(format!("Available sheets: {}", names.join(", ")));
+            }
+        }
+        suggestions.extend(error.recovery_suggestion().map(str::to_string));
+        Self {
+            code,
+            docs_url: code.docs_url(),
+            suggestions,
+        }
+    }
+}
+
+/// The candidate closest to `name`: one differing only in case, or else
+/// the one fewest edits away, allowing one edit per three characters.
+/// Ties go to the earlier candidate.
+pub fn closest_match<'a, S: AsRef<str>>(name: &str, candidates: &'a [S]) -> Option<&'a str> {
+    let name = name.to_lowercase();
+    let limit = (name.chars().count() / 3).max(1);
+    let mut best: Option<(usize, &str)> = None;
+    for candidate in candidates {
+        let candidate = candidate.as_ref();
+        let distance = edit_distance(&name, &candidate.to_lowercase());
+        if distance <= limit && best.map_or(true, |(d, _)| distance < d) {
+            best = Some((distance, candidate));
+        }
+    }
+    best.map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance between `a` and `b`, in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorContext;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("sheet1", "sheet1"), 0);
+        assert_eq!(edit_distance("données", "donnees"), 1);
+    }
+
+    #[test]
+    fn test_closest_match() {
+        let sheets = ["Sheet1", "Sales 2024", "Summary"];
+        assert_eq!(closest_match("sheet1", &sheets), Some("Sheet1"));
+        assert_eq!(closest_match("Sales2024", &sheets), Some("Sales 2024"));
+        assert_eq!(closest_match("Sumary", &sheets), Some("Summary"));
+        assert_eq!(closest_match("Data", &sheets), None);
+        assert_eq!(closest_match::<&str>("Sheet1", &[]), None);
+    }
+
+    #[test]
+    fn test_diagnostic() {
+        let error = XlexError::SheetNotFound {
+            name: "Sumary".to_string(),
+        };
+        let diagnostic = Diagnostic::with_sheets(&error, &["Data", "Summary"]);
+        assert_eq!(diagnostic.code, ErrorCode::SheetNotFound);
+        assert_eq!(
+            diagnostic.docs_url,
+            "https://yen0304.github.io/xlex/reference/error-codes/#xlex_e030"
+        );
+        assert_eq!(
+            diagnostic.suggestions,
+            vec![
+                "Did you mean 'Summary'?".to_string(),
+                error.recovery_suggestion().unwrap().to_string(),
+            ]
+        );
+
+        let unrelated = Diagnostic::with_sheets(&error, &["Data"]);
+        assert_eq!(unrelated.suggestions[0], "Available sheets: Data");
+
+        let contextual = error.with_context(ErrorContext::default().part("xl/workbook.xml"));
+        assert_eq!(
+            Diagnostic::with_sheets(&contextual, &["Summary"]).suggestions[0],
+            "Did you mean 'Summary'?"
+        );
+
+        let plain = Diagnostic::new(&XlexError::CannotDeleteLastSheet);
+        assert_eq!(plain.code.as_str(), "XLEX_E034");
+        assert_eq!(plain.suggestions.len(), 1);
+    }
+}
@@ -0,0 +1,425 @@
+//! Embedded object types.
+//!
+//! Objects embedded in a workbook (PDFs, documents, other workbooks) are
+//! stored as parts under `xl/embeddings/`. Office documents are usually
+//! stored as plain packages; anything else is wrapped in an OLE compound
+//! file (`oleObject*.bin`), which [`unwrap_object`] opens to recover the
+//! original file.
+
+/// An object embedded in a workbook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedObject {
+    /// Part path inside the package (e.g., "xl/embeddings/oleObject1.bin")
+    pub part: String,
+    /// Sheet the object is placed on, if any
+    pub sheet: Option<String>,
+    /// OLE program ID (e.g., "Acrobat.Document.DC", "Package")
+    pub prog_id: Option<String>,
+    /// Size of the stored part in bytes
+    pub size: u64,
+}
+
+impl EmbeddedObject {
+    /// Returns the file name of the part (e.g., "oleObject1.bin").
+    pub fn part_name(&self) -> &str {
+        self.part.rsplit('/').next().unwrap_or(&self.part)
+    }
+}
+
+/// The file recovered from an embedded object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedFile {
+    /// File name, from the OLE package when it records one
+    pub file_name: String,
+    /// File contents
+    pub data: Vec<u8>,
+}
+
+/// Recovers the original file from an embedded part.
+///
+/// OLE packages (`\x01Ole10Native`) keep the attached file and its name;
+/// `CONTENTS` and `Package` streams hold the raw document (e.g., a PDF or
+/// an xlsx). Parts that are not OLE compound files are returned as is.
+pub fn unwrap_object(part_name: &str, data: Vec<u8>) -> EmbeddedFile {
+    let stem = part_name
+        .rsplit_once('.')
+        .map(|(stem, _)| stem)
+        .unwrap_or(part_name);
+
+    if let Some(cfb) = CompoundFile::parse(&data) {
+        if let Some((name, contents)) = cfb
+            .stream("\u{1}Ole10Native")
+            .and_then(|s| parse_ole10_native(&s))
+        {
+            let file_name = sanitize_file_name(&name)
+                .unwrap_or_else(|| format!("{}.{}", stem, sniff_extension(&contents)));
+            return EmbeddedFile {
+                file_name,
+                data: contents,
+            };
+        }
+        if let Some(contents) = cfb.stream("CONTENTS").or_else(|| cfb.stream("Package")) {
+            return EmbeddedFile {
+                file_name: format!("{}.{}", stem, sniff_extension(&contents)),
+                data: contents,
+            };
+        }
+    }
+
+    EmbeddedFile {
+        file_name: part_name.to_string(),
+        data,
+    }
+}
+
+/// Guesses a file extension from the leading bytes of a file.
+fn sniff_extension(data: &[u8]) -> &'static str {
+    if data.starts_with(b"%PDF") {
+        "pdf"
+    } else if data.starts_with(b"PK\x03\x04") {
+        // Office packages name their main folder early in the archive
+        let head = &data[..data.len().min(64 * 1024)];
+        let contains = |needle: &[u8]| head.windows(needle.len()).any(|w| w == needle);
+        if contains(b"word/") {
+            "docx"
+        } else if contains(b"xl/") {
+            "xlsx"
+        } else if contains(b"ppt/") {
+            "pptx"
+        } else {
+            "zip"
+        }
+    } else if data.starts_with(&CFB_SIGNATURE) {
+        "bin"
+    } else if data.starts_with(b"\x89PNG") {
+        "png"
+    } else if data.starts_with(b"\xFF\xD8\xFF") {
+        "jpg"
+    } else {
+        "bin"
+    }
+}
+
+/// Keeps only the final path component of a stored file name, so that
+/// extraction cannot write outside the target directory.
+fn sanitize_file_name(name: &str) -> Option<String> {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or(name);
+    let cleaned: String = base.chars().filter(|c| !c.is_control()).collect();
+    let cleaned = cleaned.trim();
+    (!cleaned.is_empty() && cleaned != "." && cleaned != "..").then(|| cleaned.to_string())
+}
+
+/// Parses an `Ole10Native` stream into the stored file name and contents.
+fn parse_ole10_native(stream: &[u8]) -> Option<(String, Vec<u8>)> {
+    fn c_string(data: &[u8], pos: &mut usize) -> Option<String> {
+        let len = data.get(*pos..)?.iter().position(|&b| b == 0)?;
+        let s = String::from_utf8_lossy(&data[*pos..*pos + len]).to_string();
+        *pos += len + 1;
+        Some(s)
+    }
+
+    // Total size (4) and a type marker (2)
+    let mut pos = 6;
+    let label = c_string(stream, &mut pos)?;
+    let source_path = c_string(stream, &mut pos)?;
+    // Two reserved values
+    pos += 8;
+    let _temp_path = c_string(stream, &mut pos)?;
+    let size = read_u32(stream, pos)? as usize;
+    pos += 4;
+    let contents = stream.get(pos..pos.checked_add(size)?)?.to_vec();
+
+    let name = if label.is_empty() { source_path } else { label };
+    Some((name, contents))
+}
+
+const CFB_SIGNATURE: [u8; 8] = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+/// Sector IDs at or above this value mark the end of a chain.
+const MAX_REG_SECT: u32 = 0xFFFF_FFFA;
+
+fn read_u16(data: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(pos..pos + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(pos..pos + 4)?.try_into().ok()?))
+}
+
+/// A directory entry of a compound file.
+struct DirEntry {
+    name: String,
+    is_stream: bool,
+    start: u32,
+    size: u64,
+}
+
+/// A minimal read-only OLE compound file (CFB) reader.
+struct CompoundFile<'a> {
+    data: &'a [u8],
+    sector_size: usize,
+    mini_sector_size: usize,
+    mini_cutoff: u64,
+    fat: Vec<u32>,
+    mini_fat: Vec<u32>,
+    mini_stream: Vec<u8>,
+    entries: Vec<DirEntry>,
+}
+
+impl<'a> CompoundFile<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        if data.len() < 512 || data[..8] != CFB_SIGNATURE {
+            return None;
+        }
+        let sector_shift = read_u16(data, 0x1E)?;
+        let mini_shift = read_u16(data, 0x20)?;
+        if !(7..=16).contains(&sector_shift) || mini_shift > sector_shift {
+            return None;
+        }
+        let mut cfb = Self {
+            data,
+            sector_size: 1 << sector_shift,
+            mini_sector_size: 1 << mini_shift,
+            mini_cutoff: u64::from(read_u32(data, 0x38)?),
+            fat: Vec::new(),
+            mini_fat: Vec::new(),
+            mini_stream: Vec::new(),
+            entries: Vec::new(),
+        };
+
+        // The FAT sectors are listed in the header, then in DIFAT sectors
+        let fat_count = read_u32(data, 0x2C)? as usize;
+        let mut fat_sectors: Vec<u32> = (0..109)
+            .filter_map(|i| read_u32(data, 0x4C + i * 4))
+            .filter(|&s| s < MAX_REG_SECT)
+            .collect();
+        let mut difat = read_u32(data, 0x44)?;
+        let per_difat = cfb.sector_size / 4 - 1;
+        // Bounded by the sector count so a cyclic chain can't loop forever
+        for _ in 0..data.len() / cfb.sector_size {
+            if difat >= MAX_REG_SECT || fat_sectors.len() >= fat_count {
+                break;
+            }
+            let sector = cfb.sector(difat)?;
+            fat_sectors.extend(
+                (0..per_difat)
+                    .filter_map(|i| read_u32(sector, i * 4))
+                    .filter(|&s| s < MAX_REG_SECT),
+            );
+            difat = read_u32(sector, per_difat * 4)?;
+        }
+        fat_sectors.truncate(fat_count);
+        for sector in fat_sectors {
+            let sector = cfb.sector(sector)?;
+            cfb.fat
+                .extend((0..sector.len() / 4).filter_map(|i| read_u32(sector, i * 4)));
+        }
+
+        let directory = cfb.read_chain(read_u32(data, 0x30)?)?;
+        for raw in directory.chunks_exact(128) {
+            let name_len = (read_u16(raw, 64)? as usize).clamp(2, 64) - 2;
+            let name: Vec<u16> = (0..name_len / 2)
+                .filter_map(|i| read_u16(raw, i * 2))
+                .collect();
+            let mut size = u64::from(read_u32(raw, 120)?);
+            if cfb.sector_size > 512 {
+                size |= u64::from(read_u32(raw, 124)?) << 32;
+            }
+            cfb.entries.push(DirEntry {
+                name: String::from_utf16_lossy(&name),
+                is_stream: raw[66] == 2,
+                start: read_u32(raw, 116)?,
+                size,
+            });
+        }
+
+        // Small streams live in the mini stream, owned by the root entry
+        let root = cfb.entries.first()?;
+        let (root_start, root_size) = (root.start, root.size);
+        let mut mini_stream = cfb.read_chain(root_start).unwrap_or_default();
+        mini_stream.truncate(usize::try_from(root_size).ok()?);
+        cfb.mini_stream = mini_stream;
+        let mini_fat = cfb.read_chain(read_u32(data, 0x3C)?).unwrap_or_default();
+        cfb.mini_fat = (0..mini_fat.len() / 4)
+            .filter_map(|i| read_u32(&mini_fat, i * 4))
+            .collect();
+
+        Some(cfb)
+    }
+
+    fn sector(&self, id: u32) -> Option<&'a [u8]> {
+        let start = (id as usize + 1).checked_mul(self.sector_size)?;
+        self.data.get(start..start + self.sector_size)
+    }
+
+    /// Reads a chain of regular sectors starting at `start`.
+    fn read_chain(&self, start: u32) -> Option<Vec<u8>> {
+        let mut out = Vec::new();
+        let mut id = start;
+        // A chain can't be longer than the FAT; this also stops cycles
+        for _ in 0..=self.fat.len() {
+            if id >= MAX_REG_SECT {
+                return Some(out);
+            }
+            out.extend_from_slice(self.sector(id)?);
+            id = *self.fat.get(id as usize)?;
+        }
+        None
+    }
+
+    /// Reads a chain of mini sectors starting at `start`.
+    fn read_mini_chain(&self, start: u32) -> Option<Vec<u8>> {
+        let mut out = Vec::new();
+        let mut id = start;
+        for _ in 0..=self.mini_fat.len() {
+            if id >= MAX_REG_SECT {
+                return Some(out);
+            }
+            let offset = id as usize * self.mini_sector_size;
+            out.extend_from_slice(
+                self.mini_stream
+                    .get(offset..offset + self.mini_sector_size)?,
+            );
+            id = *self.mini_fat.get(id as usize)?;
+        }
+        None
+    }
+
+    /// Returns the contents of a stream by name (case-insensitive).
+    fn stream(&self, name: &str) -> Option<Vec<u8>> {
+        let entry = self
+            .entries
+            .iter()
+            .find(|e| e.is_stream && e.name.eq_ignore_ascii_case(name))?;
+        let mut data = if entry.size < self.mini_cutoff {
+            self.read_mini_chain(entry.start)?
+        } else {
+            self.read_chain(entry.start)?
+        };
+        data.truncate(usize::try_from(entry.size).ok()?);
+        Some(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a compound file with 512-byte sectors holding the given
+    /// streams. Streams are stored in regular sectors (the mini stream
+    /// cutoff is set to 0).
+    fn build_compound_file(streams: &[(&str, &[u8])]) -> Vec<u8> {
+        const SECTOR: usize = 512;
+        let sectors_for = |len: usize| len.div_ceil(SECTOR).max(1);
+        let dir_sectors = sectors_for((streams.len() + 1) * 128);
+        let data_sectors: usize = streams.iter().map(|(_, d)| sectors_for(d.len())).sum();
+        // Sector 0 is the FAT; it must describe every sector
+        assert!(1 + dir_sectors + data_sectors <= SECTOR / 4);
+
+        let mut fat = vec![0xFFFF_FFFFu32; SECTOR / 4];
+        fat[0] = 0xFFFF_FFFD;
+        let mut next = 1u32;
+        let mut chain = |count: usize, fat: &mut Vec<u32>| {
+            let start = next;
+            for i in 0..count as u32 {
+                fat[(start + i) as usize] = if i + 1 == count as u32 {
+                    0xFFFF_FFFE
+                } else {
+                    start + i + 1
+                };
+            }
+            next += count as u32;
+            start
+        };
+        let dir_start = chain(dir_sectors, &mut fat);
+        let starts: Vec<u32> = streams
+            .iter()
+            .map(|(_, d)| chain(sectors_for(d.len()), &mut fat))
+            .collect();
+
+        let mut header = vec![0u8; SECTOR];
+        header[..8].copy_from_slice(&CFB_SIGNATURE);
+        header[0x1A] = 0x3E;
+        header[0x1C..0x20].copy_from_slice(&[0x03, 0x00, 0xFE, 0xFF]);
+        header[0x1E..0x20].copy_from_slice(&9u16.to_le_bytes());
+        header[0x20..0x22].copy_from_slice(&6u16.to_le_bytes());
+        header[0x2C..0x30].copy_from_slice(&1u32.to_le_bytes());
+        header[0x30..0x34].copy_from_slice(&dir_start.to_le_bytes());
+        header[0x38..0x3C].copy_from_slice(&0u32.to_le_bytes());
+        header[0x3C..0x40].copy_from_slice(&0xFFFF_FFFEu32.to_le_bytes());
+        header[0x44..0x48].copy_from_slice(&0xFFFF_FFFEu32.to_le_bytes());
+        header[0x4C..0x50].copy_from_slice(&0u32.to_le_bytes());
+        for i in 1..109 {
+            header[0x4C + i * 4..0x50 + i * 4].copy_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+        }
+
+        let entry = |name: &str, kind: u8, start: u32, size: usize| {
+            let mut raw = vec![0u8; 128];
+            let utf16: Vec<u16> = name.encode_utf16().chain([0]).collect();
+            for (i, unit) in utf16.iter().enumerate() {
+                raw[i * 2..i * 2 + 2].copy_from_slice(&unit.to_le_bytes());
+            }
+            raw[64..66].copy_from_slice(&((utf16.len() * 2) as u16).to_le_bytes());
+            raw[66] = kind;
+            raw[116..120].copy_from_slice(&start.to_le_bytes());
+            raw[120..124].copy_from_slice(&(size as u32).to_le_bytes());
+            raw
+        };
+        let mut directory = entry("Root Entry", 5, 0xFFFF_FFFE, 0);
+        for ((name, data), start) in streams.iter().zip(&starts) {
+            directory.extend(entry(name, 2, *start, data.len()));
+        }
+        directory.resize(dir_sectors * SECTOR, 0);
+
+        let mut out = header;
+        out.extend(fat.iter().flat_map(|v| v.to_le_bytes()));
+        out.extend(directory);
+        for (_, data) in streams {
+            let mut padded = data.to_vec();
+            padded.resize(sectors_for(data.len()) * SECTOR, 0);
+            out.extend(padded);
+        }
+        out
+    }
+
+    /// Builds an `Ole10Native` stream for a packaged file.
+    fn build_ole10_native(name: &str, contents: &[u8]) -> Vec<u8> {
+        let mut body = vec![0x02, 0x00];
+        body.extend(name.as_bytes());
+        body.push(0);
+        body.extend(format!("C:\\Users\\me\\{}", name).as_bytes());
+        body.push(0);
+        body.extend([0u8; 8]);
+        body.extend(b"C:\\Temp\\x");
+        body.push(0);
+        body.extend((contents.len() as u32).to_le_bytes());
+        body.extend(contents);
+        let mut stream = (body.len() as u32).to_le_bytes().to_vec();
+        stream.extend(body);
+        stream
+    }
+
+    #[test]
+    fn test_unwrap_ole_package() {
+        let pdf = b"%PDF-1.7\nattachment".repeat(40);
+        let native = build_ole10_native("..\\..\\invoice 42.pdf", &pdf);
+        let cfb = build_compound_file(&[("\u{1}Ole10Native", &native)]);
+
+        let file = unwrap_object("oleObject1.bin", cfb);
+        assert_eq!(file.file_name, "invoice 42.pdf");
+        assert_eq!(file.data, pdf);
+    }
+
+    #[test]
+    fn test_unwrap_contents_stream_and_plain_part() {
+        let pdf = b"%PDF-1.4 acrobat".to_vec();
+        let cfb = build_compound_file(&[("CONTENTS", &pdf)]);
+        let file = unwrap_object("oleObject2.bin", cfb);
+        assert_eq!(file.file_name, "oleObject2.pdf");
+        assert_eq!(file.data, pdf);
+
+        let docx = b"PK\x03\x04....word/document.xml".to_vec();
+        let file = unwrap_object("Microsoft_Word_Document.docx", docx.clone());
+        assert_eq!(file.file_name, "Microsoft_Word_Document.docx");
+        assert_eq!(file.data, docx);
+    }
+}
@@ -53,6 +53,7 @@ pub enum ErrorCode {
     OperationFailed = 60,
     InvalidOperation = 61,
     UnsupportedOperation = 62,
+    ValidationFailed = 63,
 
     // Template errors (E070-E079)
     TemplateParseError = 70,
@@ -73,6 +74,14 @@ impl ErrorCode {
     pub fn as_str(&self) -> String {
         format!("XLEX_E{:03}", *self as u32)
     }
+
+    /// Returns the link to this code's entry in the error code reference.
+    pub fn docs_url(&self) -> String {
+        format!(
+            "https://yen0304.github.io/xlex/reference/error-codes/#{}",
+            self.as_str().to_lowercase()
+        )
+    }
 }
 
 impl std::fmt::Display for ErrorCode {
@@ -81,6 +90,78 @@ impl std::fmt::Display for ErrorCode {
     }
 }
 
+/// Where in a workbook an error occurred.
+///
+/// Every field is optional; only what is known at the failure site is set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// Package part (e.g., `xl/worksheets/sheet3.xml`)
+    pub part: Option<String>,
+    /// Sheet name
+    pub sheet: Option<String>,
+    /// Cell reference in A1 notation
+    pub cell: Option<String>,
+    /// Byte offset within the part
+    pub offset: Option<u64>,
+}
+
+impl ErrorContext {
+    /// Sets the package part.
+    pub fn part(mut self, part: impl Into<String>) -> Self {
+        self.part = Some(part.into());
+        self
+    }
+
+    /// Sets the sheet name.
+    pub fn sheet(mut self, sheet: impl Into<String>) -> Self {
+        self.sheet = Some(sheet.into());
+        self
+    }
+
+    /// Sets the cell reference.
+    pub fn cell(mut self, cell: impl Into<String>) -> Self {
+        self.cell = Some(cell.into());
+        self
+    }
+
+    /// Sets the byte offset.
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Fills fields not set here from `other`.
+    fn merge(&mut self, other: ErrorContext) {
+        self.part = self.part.take().or(other.part);
+        self.sheet = self.sheet.take().or(other.sheet);
+        self.cell = self.cell.take().or(other.cell);
+        self.offset = self.offset.or(other.offset);
+    }
+}
+
+impl std::fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        match (&self.sheet, &self.cell) {
+            (Some(sheet), Some(cell)) => parts.push(format!(
+                "{}!{}",
+                crate::sheet::quote_sheet_name(sheet),
+                cell
+            )),
+            (Some(sheet), None) => parts.push(format!("sheet {}", sheet)),
+            (None, Some(cell)) => parts.push(format!("cell {}", cell)),
+            (None, None) => {}
+        }
+        if let Some(part) = &self.part {
+            parts.push(format!("in {}", part));
+        }
+        if let Some(offset) = self.offset {
+            parts.push(format!("at byte {}", offset));
+        }
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
 /// Main error type for xlex operations.
 #[derive(Error, Debug)]
 pub enum XlexError {
@@ -194,6 +275,9 @@ pub enum XlexError {
     )]
     UnsupportedOperation { message: String },
 
+    #[error("{}: Validation failed: {message}", ErrorCode::ValidationFailed)]
+    ValidationFailed { message: String },
+
     // Template errors
     #[error("{}: Template parse error: {message}", ErrorCode::TemplateParseError)]
     TemplateParseError { message: String },
@@ -217,12 +301,57 @@ pub enum XlexError {
 
     #[error("{}: Not implemented: {feature}", ErrorCode::NotImplemented)]
     NotImplemented { feature: String },
+
+    /// Another error with where in the workbook it occurred.
+    #[error("{source} ({context})")]
+    WithContext {
+        context: ErrorContext,
+        source: Box<XlexError>,
+    },
 }
 
 impl XlexError {
+    /// Attaches where the error occurred, filling in fields an inner
+    /// context has not already set.
+    pub fn with_context(self, context: ErrorContext) -> Self {
+        match self {
+            XlexError::WithContext {
+                context: mut inner,
+                source,
+            } => {
+                inner.merge(context);
+                XlexError::WithContext {
+                    context: inner,
+                    source,
+                }
+            }
+            source => XlexError::WithContext {
+                context,
+                source: Box::new(source),
+            },
+        }
+    }
+
+    /// Returns where the error occurred, if known.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            XlexError::WithContext { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Returns the error without its context.
+    pub fn without_context(&self) -> &XlexError {
+        match self {
+            XlexError::WithContext { source, .. } => source,
+            other => other,
+        }
+    }
+
     /// Returns the error code for this error.
     pub fn code(&self) -> ErrorCode {
         match self {
+            XlexError::WithContext { source, .. } => source.code(),
             XlexError::FileNotFound { .. } => ErrorCode::FileNotFound,
             XlexError::FileExists { .. } => ErrorCode::FileExists,
             XlexError::PermissionDenied { .. } => ErrorCode::PermissionDenied,
@@ -250,6 +379,7 @@ impl XlexError {
             XlexError::OperationFailed { .. } => ErrorCode::OperationFailed,
             XlexError::InvalidOperation { .. } => ErrorCode::InvalidOperation,
             XlexError::UnsupportedOperation { .. } => ErrorCode::UnsupportedOperation,
+            XlexError::ValidationFailed { .. } => ErrorCode::ValidationFailed,
             XlexError::TemplateParseError { .. } => ErrorCode::TemplateParseError,
             XlexError::TemplateRenderError { .. } => ErrorCode::TemplateRenderError,
             XlexError::InvalidTemplateData { .. } => ErrorCode::InvalidTemplateData,
@@ -299,6 +429,8 @@ impl XlexError {
             | ErrorCode::InvalidOperation
             | ErrorCode::UnsupportedOperation => 1,
 
+            ErrorCode::ValidationFailed => 12,
+
             ErrorCode::TemplateParseError
             | ErrorCode::TemplateRenderError
             | ErrorCode::InvalidTemplateData => 9,
@@ -315,6 +447,7 @@ impl XlexError {
     /// Provides actionable advice to help users resolve common issues.
     pub fn recovery_suggestion(&self) -> Option<&'static str> {
         match self {
+            XlexError::WithContext { source, .. } => source.recovery_suggestion(),
             XlexError::FileNotFound { .. } => Some(
                 "Check if the file path is correct. Use `ls` to verify the file exists.",
             ),
@@ -405,6 +538,9 @@ impl XlexError {
             XlexError::UnsupportedOperation { .. } => Some(
                 "This operation is not yet supported. Check the documentation for alternatives.",
             ),
+            XlexError::ValidationFailed { .. } => Some(
+                "Fix the reported problems, or choose how strict the check is with --fail-on (warnings, errors, never).",
+            ),
             XlexError::InternalError { .. } => Some(
                 "Please report this issue at https://github.com/xlex/xlex/issues with the full error message.",
             ),
@@ -416,8 +552,18 @@ impl XlexError {
     }
 }
 
+/// The error for a part whose content fails its CRC-32 check.
+fn checksum_mismatch() -> XlexError {
+    XlexError::InvalidZipStructure {
+        message: "CRC-32 checksum mismatch, the part is damaged".to_string(),
+    }
+}
+
 impl From<std::io::Error> for XlexError {
     fn from(err: std::io::Error) -> Self {
+        if crate::checksum::is_checksum_error(&err) {
+            return checksum_mismatch();
+        }
         XlexError::IoError {
             message: err.to_string(),
             source: Some(err),
@@ -435,6 +581,11 @@ impl From<zip::result::ZipError> for XlexError {
 
 impl From<quick_xml::Error> for XlexError {
     fn from(err: quick_xml::Error) -> Self {
+        if let quick_xml::Error::Io(e) = &err {
+            if crate::checksum::is_checksum_error(e) {
+                return checksum_mismatch();
+            }
+        }
         XlexError::InvalidXml {
             message: err.to_string(),
         }
@@ -674,6 +825,13 @@ mod tests {
             .code(),
             ErrorCode::UnsupportedOperation
         );
+        assert_eq!(
+            XlexError::ValidationFailed {
+                message: "test".to_string()
+            }
+            .code(),
+            ErrorCode::ValidationFailed
+        );
 
         // Template errors
         assert_eq!(
@@ -1004,6 +1162,13 @@ mod tests {
             .exit_code(),
             11
         );
+        assert_eq!(
+            XlexError::ValidationFailed {
+                message: "test".to_string()
+            }
+            .exit_code(),
+            12
+        );
         assert_eq!(
             XlexError::OperationFailed {
                 message: "test".to_string()
@@ -1026,4 +1191,36 @@ mod tests {
             1
         );
     }
+
+    #[test]
+    fn test_with_context() {
+        let err = XlexError::InvalidXml {
+            message: "bad".to_string(),
+        }
+        .with_context(
+            ErrorContext::default()
+                .part("xl/worksheets/sheet3.xml")
+                .offset(42),
+        )
+        .with_context(
+            ErrorContext::default()
+                .sheet("My Data")
+                .cell("BQ1042")
+                .offset(7),
+        );
+
+        assert_eq!(err.code(), ErrorCode::InvalidXml);
+        assert_eq!(err.exit_code(), 5);
+        assert!(err.recovery_suggestion().is_some());
+        // Inner fields win; missing ones are filled from the outer context
+        assert_eq!(err.context().unwrap().offset, Some(42));
+        assert_eq!(
+            err.to_string(),
+            "XLEX_E013: Invalid XML: bad ('My Data'!BQ1042 in xl/worksheets/sheet3.xml at byte 42)"
+        );
+        assert!(matches!(
+            err.without_context(),
+            XlexError::InvalidXml { .. }
+        ));
+    }
 }
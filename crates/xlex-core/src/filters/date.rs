@@ -0,0 +1,442 @@
+//! Dates formatted with ICU patterns.
+//!
+//! Patterns use the letters of ICU's `SimpleDateFormat`: `yyyy`, `MM`,
+//! `MMMM`, `d`, `EEEE`, `HH`, `mm` and so on, with literal text in single
+//! quotes. Month and weekday names come from the locale.
+
+use chrono::{Datelike, NaiveDateTime, Timelike};
+
+use crate::style::Locale;
+
+/// Parses an ISO 8601 date or date-time (`2024-03-05`,
+/// `2024-03-05T14:07:00`, `2024-03-05 14:07`), with or without an offset.
+pub fn parse_date(text: &str) -> Option<NaiveDateTime> {
+    let text = text.trim();
+    if let Ok(datetime) = chrono::DateTime::parse_from_rfc3339(text) {
+        return Some(datetime.naive_local());
+    }
+    for format in [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M",
+    ] {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(text, format) {
+            return Some(datetime);
+        }
+    }
+    chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+}
+
+/// Month and weekday names, Monday first.
+struct Names {
+    months: [&'static str; 12],
+    short_months: [&'static str; 12],
+    days: [&'static str; 7],
+    short_days: [&'static str; 7],
+    am_pm: [&'static str; 2],
+}
+
+const EN: Names = Names {
+    months: [
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ],
+    short_months: [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ],
+    days: [
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+        "Thursday",
+        "Friday",
+        "Saturday",
+        "Sunday",
+    ],
+    short_days: ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+    am_pm: ["AM", "PM"],
+};
+
+const DE: Names = Names {
+    months: [
+        "Januar",
+        "Februar",
+        "März",
+        "April",
+        "Mai",
+        "Juni",
+        "Juli",
+        "August",
+        "September",
+        "Oktober",
+        "November",
+        "Dezember",
+    ],
+    short_months: [
+        "Jan.", "Feb.", "März", "Apr.", "Mai", "Juni", "Juli", "Aug.", "Sept.", "Okt.", "Nov.",
+        "Dez.",
+    ],
+    days: [
+        "Montag",
+        "Dienstag",
+        "Mittwoch",
+        "Donnerstag",
+        "Freitag",
+        "Samstag",
+        "Sonntag",
+    ],
+    short_days: ["Mo.", "Di.", "Mi.", "Do.", "Fr.", "Sa.", "So."],
+    am_pm: ["AM", "PM"],
+};
+
+const FR: Names = Names {
+    months: [
+        "janvier",
+        "février",
+        "mars",
+        "avril",
+        "mai",
+        "juin",
+        "juillet",
+        "août",
+        "septembre",
+        "octobre",
+        "novembre",
+        "décembre",
+    ],
+    short_months: [
+        "janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.", "nov.",
+        "déc.",
+    ],
+    days: [
+        "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche",
+    ],
+    short_days: ["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."],
+    am_pm: ["AM", "PM"],
+};
+
+const ES: Names = Names {
+    months: [
+        "enero",
+        "febrero",
+        "marzo",
+        "abril",
+        "mayo",
+        "junio",
+        "julio",
+        "agosto",
+        "septiembre",
+        "octubre",
+        "noviembre",
+        "diciembre",
+    ],
+    short_months: [
+        "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sept", "oct", "nov", "dic",
+    ],
+    days: [
+        "lunes",
+        "martes",
+        "miércoles",
+        "jueves",
+        "viernes",
+        "sábado",
+        "domingo",
+    ],
+    short_days: ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+    am_pm: ["a. m.", "p. m."],
+};
+
+const IT: Names = Names {
+    months: [
+        "gennaio",
+        "febbraio",
+        "marzo",
+        "aprile",
+        "maggio",
+        "giugno",
+        "luglio",
+        "agosto",
+        "settembre",
+        "ottobre",
+        "novembre",
+        "dicembre",
+    ],
+    short_months: [
+        "gen", "feb", "mar", "apr", "mag", "giu", "lug", "ago", "set", "ott", "nov", "dic",
+    ],
+    days: [
+        "lunedì",
+        "martedì",
+        "mercoledì",
+        "giovedì",
+        "venerdì",
+        "sabato",
+        "domenica",
+    ],
+    short_days: ["lun", "mar", "mer", "gio", "ven", "sab", "dom"],
+    am_pm: ["AM", "PM"],
+};
+
+const NL: Names = Names {
+    months: [
+        "januari",
+        "februari",
+        "maart",
+        "april",
+        "mei",
+        "juni",
+        "juli",
+        "augustus",
+        "september",
+        "oktober",
+        "november",
+        "december",
+    ],
+    short_months: [
+        "jan", "feb", "mrt", "apr", "mei", "jun", "jul", "aug", "sep", "okt", "nov", "dec",
+    ],
+    days: [
+        "maandag",
+        "dinsdag",
+        "woensdag",
+        "donderdag",
+        "vrijdag",
+        "zaterdag",
+        "zondag",
+    ],
+    short_days: ["ma", "di", "wo", "do", "vr", "za", "zo"],
+    am_pm: ["a.m.", "p.m."],
+};
+
+const PT: Names = Names {
+    months: [
+        "janeiro",
+        "fevereiro",
+        "março",
+        "abril",
+        "maio",
+        "junho",
+        "julho",
+        "agosto",
+        "setembro",
+        "outubro",
+        "novembro",
+        "dezembro",
+    ],
+    short_months: [
+        "jan", "fev", "mar", "abr", "mai", "jun", "jul", "ago", "set", "out", "nov", "dez",
+    ],
+    days: [
+        "segunda-feira",
+        "terça-feira",
+        "quarta-feira",
+        "quinta-feira",
+        "sexta-feira",
+        "sábado",
+        "domingo",
+    ],
+    short_days: ["seg", "ter", "qua", "qui", "sex", "sáb", "dom"],
+    am_pm: ["AM", "PM"],
+};
+
+const CJK_MONTHS: [&str; 12] = [
+    "1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月",
+];
+
+const ZH_TW: Names = Names {
+    months: CJK_MONTHS,
+    short_months: CJK_MONTHS,
+    days: [
+        "星期一",
+        "星期二",
+        "星期三",
+        "星期四",
+        "星期五",
+        "星期六",
+        "星期日",
+    ],
+    short_days: ["週一", "週二", "週三", "週四", "週五", "週六", "週日"],
+    am_pm: ["上午", "下午"],
+};
+
+const ZH_CN: Names = Names {
+    months: CJK_MONTHS,
+    short_months: CJK_MONTHS,
+    days: [
+        "星期一",
+        "星期二",
+        "星期三",
+        "星期四",
+        "星期五",
+        "星期六",
+        "星期日",
+    ],
+    short_days: ["周一", "周二", "周三", "周四", "周五", "周六", "周日"],
+    am_pm: ["上午", "下午"],
+};
+
+const JA: Names = Names {
+    months: CJK_MONTHS,
+    short_months: CJK_MONTHS,
+    days: [
+        "月曜日",
+        "火曜日",
+        "水曜日",
+        "木曜日",
+        "金曜日",
+        "土曜日",
+        "日曜日",
+    ],
+    short_days: ["月", "火", "水", "木", "金", "土", "日"],
+    am_pm: ["午前", "午後"],
+};
+
+const KO: Names = Names {
+    months: [
+        "1월", "2월", "3월", "4월", "5월", "6월", "7월", "8월", "9월", "10월", "11월", "12월",
+    ],
+    short_months: [
+        "1월", "2월", "3월", "4월", "5월", "6월", "7월", "8월", "9월", "10월", "11월", "12월",
+    ],
+    days: [
+        "월요일",
+        "화요일",
+        "수요일",
+        "목요일",
+        "금요일",
+        "토요일",
+        "일요일",
+    ],
+    short_days: ["월", "화", "수", "목", "금", "토", "일"],
+    am_pm: ["오전", "오후"],
+};
+
+fn names(locale: Locale) -> &'static Names {
+    match locale {
+        Locale::EnUs | Locale::EnGb => &EN,
+        Locale::De | Locale::DeCh => &DE,
+        Locale::Fr => &FR,
+        Locale::Es => &ES,
+        Locale::It => &IT,
+        Locale::Nl => &NL,
+        Locale::PtBr => &PT,
+        Locale::ZhTw => &ZH_TW,
+        Locale::ZhCn => &ZH_CN,
+        Locale::Ja => &JA,
+        Locale::Ko => &KO,
+    }
+}
+
+/// Formats `datetime` with an ICU pattern such as `d MMMM yyyy` or
+/// `EEE, dd.MM.yy HH:mm`. Letters without a meaning here are copied.
+pub fn format_date(datetime: NaiveDateTime, pattern: &str, locale: Locale) -> String {
+    let names = names(locale);
+    let mut out = String::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\'' {
+            // '' is a quote; otherwise quoted text up to the next quote
+            if chars.peek() == Some(&'\'') {
+                chars.next();
+                out.push('\'');
+                continue;
+            }
+            while let Some(c) = chars.next() {
+                match c {
+                    '\'' if chars.peek() == Some(&'\'') => {
+                        chars.next();
+                        out.push('\'');
+                    }
+                    '\'' => break,
+                    _ => out.push(c),
+                }
+            }
+            continue;
+        }
+        if !c.is_ascii_alphabetic() {
+            out.push(c);
+            continue;
+        }
+        let mut count = 1;
+        while chars.peek() == Some(&c) {
+            chars.next();
+            count += 1;
+        }
+        let number = |value: u32| format!("{:0width$}", value, width = count.min(2));
+        let month = datetime.month0() as usize;
+        let weekday = datetime.weekday().num_days_from_monday() as usize;
+        match c {
+            'y' | 'u' if count == 2 => {
+                out.push_str(&format!("{:02}", datetime.year().rem_euclid(100)))
+            }
+            'y' | 'u' => out.push_str(&format!("{:0width$}", datetime.year(), width = count)),
+            'M' | 'L' if count >= 4 => out.push_str(names.months[month]),
+            'M' | 'L' if count == 3 => out.push_str(names.short_months[month]),
+            'M' | 'L' => out.push_str(&number(datetime.month())),
+            'd' => out.push_str(&number(datetime.day())),
+            'D' => out.push_str(&format!("{:0width$}", datetime.ordinal(), width = count)),
+            'E' | 'c' | 'e' if count >= 4 => out.push_str(names.days[weekday]),
+            'E' | 'c' | 'e' => out.push_str(names.short_days[weekday]),
+            'H' => out.push_str(&number(datetime.hour())),
+            'h' => out.push_str(&number(datetime.hour12().1)),
+            'm' => out.push_str(&number(datetime.minute())),
+            's' => out.push_str(&number(datetime.second())),
+            'a' => out.push_str(names.am_pm[usize::from(datetime.hour12().0)]),
+            _ => (0..count).for_each(|_| out.push(c)),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> NaiveDateTime {
+        parse_date(text).unwrap()
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(
+            date("2024-03-05").to_string(),
+            "2024-03-05 00:00:00".to_string()
+        );
+        assert_eq!(date("2024-03-05 14:07").minute(), 7);
+        assert_eq!(date("2024-03-05T14:07:09+08:00").hour(), 14);
+        assert_eq!(parse_date("05/03/2024"), None);
+    }
+
+    #[test]
+    fn test_format_date() {
+        let d = date("2024-03-05T14:07:09");
+        let cases = [
+            ("yyyy-MM-dd", Locale::EnUs, "2024-03-05"),
+            ("d MMM yy", Locale::EnGb, "5 Mar 24"),
+            ("EEEE d MMMM", Locale::Fr, "mardi 5 mars"),
+            ("EEE, dd.MM.yyyy", Locale::De, "Di., 05.03.2024"),
+            ("d 'de' MMMM 'de' y", Locale::Es, "5 de marzo de 2024"),
+            ("yyyy年M月d日 EEEE", Locale::ZhTw, "2024年3月5日 星期二"),
+            ("h:mm a", Locale::EnUs, "2:07 PM"),
+            ("ah時", Locale::Ja, "午後2時"),
+            ("'o''clock' HH 'q'", Locale::EnUs, "o'clock 14 q"),
+            ("D", Locale::EnUs, "65"),
+        ];
+        for (pattern, locale, expected) in cases {
+            assert_eq!(format_date(d, pattern, locale), expected, "{}", pattern);
+        }
+    }
+}
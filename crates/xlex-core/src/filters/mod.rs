@@ -0,0 +1,268 @@
+//! Value filters for template placeholders.
+//!
+//! A filter turns a value's text into new text, optionally taking an
+//! argument: `{{name|upper}}`, `{{total|currency:€}}`. [`Filters`] maps
+//! filter names to functions; [`Filters::default`] holds the built-in ones
+//! and more can be added with [`Filters::register`].
+//!
+//! ```
+//! use xlex_core::filters::Filters;
+//!
+//! let mut filters = Filters::default();
+//! assert_eq!(filters.apply("1234", "spellout:en"), "one thousand two hundred thirty-four");
+//! assert_eq!(filters.apply("2024-03-05", "format_date:d MMMM yyyy"), "5 March 2024");
+//!
+//! filters.register("reverse", |value, _| value.chars().rev().collect());
+//! assert_eq!(filters.apply("abc", "reverse"), "cba");
+//! ```
+
+mod date;
+mod spellout;
+
+use std::collections::HashMap;
+
+use crate::style::Locale;
+
+pub use date::{format_date, parse_date};
+pub use spellout::{ordinal, spellout};
+
+/// A filter: the value's text and the argument after `:`, if any.
+pub type Filter = Box<dyn Fn(&str, Option<&str>) -> String + Send + Sync>;
+
+/// Filters by name.
+pub struct Filters {
+    filters: HashMap<String, Filter>,
+}
+
+impl std::fmt::Debug for Filters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Filters")
+            .field("names", &self.names())
+            .finish()
+    }
+}
+
+impl Default for Filters {
+    /// The built-in filters.
+    fn default() -> Self {
+        let mut filters = Self::empty();
+        register_builtins(&mut filters);
+        filters
+    }
+}
+
+impl Filters {
+    /// Creates a registry with no filters.
+    pub fn empty() -> Self {
+        Self {
+            filters: HashMap::new(),
+        }
+    }
+
+    /// Adds a filter, replacing any of the same name.
+    pub fn register<F>(&mut self, name: impl Into<String>, filter: F) -> &mut Self
+    where
+        F: Fn(&str, Option<&str>) -> String + Send + Sync + 'static,
+    {
+        self.filters.insert(name.into(), Box::new(filter));
+        self
+    }
+
+    /// Returns true if a filter of this name exists.
+    pub fn contains(&self, name: &str) -> bool {
+        self.filters.contains_key(name)
+    }
+
+    /// Returns the filter names, sorted.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.filters.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Applies a filter written as `name` or `name:argument`. Unknown
+    /// filters leave the value unchanged.
+    pub fn apply(&self, value: &str, filter: &str) -> String {
+        let (name, arg) = match filter.split_once(':') {
+            Some((name, arg)) => (name, Some(arg)),
+            None => (filter, None),
+        };
+        match self.filters.get(name) {
+            Some(filter) => filter(value, arg),
+            None => value.to_string(),
+        }
+    }
+}
+
+/// Applies `f` to the value as a number, leaving other text unchanged.
+fn numeric(value: &str, f: impl FnOnce(f64) -> String) -> String {
+    value.parse::<f64>().map_or_else(|_| value.to_string(), f)
+}
+
+/// Splits an optional leading locale tag off an argument: `de:d. MMMM`
+/// gives German and `d. MMMM`; `d MMMM` gives the default locale.
+fn locale_prefix(arg: &str) -> (Locale, &str) {
+    match arg.split_once(':') {
+        Some((tag, rest)) => match Locale::from_tag(tag) {
+            Some(locale) => (locale, rest),
+            None => (Locale::default(), arg),
+        },
+        None => (Locale::default(), arg),
+    }
+}
+
+fn register_builtins(filters: &mut Filters) {
+    // String filters
+    let upper = |value: &str, _: Option<&str>| value.to_uppercase();
+    let lower = |value: &str, _: Option<&str>| value.to_lowercase();
+    let capitalize = |value: &str, _: Option<&str>| {
+        let mut chars = value.chars();
+        match chars.next() {
+            None => String::new(),
+            Some(c) => c.to_uppercase().collect::<String>() + chars.as_str(),
+        }
+    };
+    filters
+        .register("upper", upper)
+        .register("uppercase", upper)
+        .register("lower", lower)
+        .register("lowercase", lower)
+        .register("capitalize", capitalize)
+        .register("title", capitalize)
+        .register("trim", |value, _| value.trim().to_string())
+        .register("default", |value, arg| {
+            if value.is_empty() {
+                arg.unwrap_or("").to_string()
+            } else {
+                value.to_string()
+            }
+        })
+        .register("truncate", |value, arg| {
+            let len: usize = arg.and_then(|a| a.parse().ok()).unwrap_or(50);
+            if value.chars().count() > len {
+                format!("{}...", value.chars().take(len).collect::<String>())
+            } else {
+                value.to_string()
+            }
+        })
+        .register("replace", |value, arg| {
+            // replace:old:new
+            match arg.and_then(|arg| arg.split_once(':')) {
+                Some((old, new)) => value.replace(old, new),
+                None => value.to_string(),
+            }
+        });
+
+    // Number filters
+    let number = |value: &str, arg: Option<&str>| {
+        numeric(value, |num| {
+            let decimals: usize = arg.and_then(|a| a.parse().ok()).unwrap_or(2);
+            format!("{:.prec$}", num, prec = decimals)
+        })
+    };
+    filters
+        .register("currency", |value, arg| {
+            numeric(value, |num| format!("{}{:.2}", arg.unwrap_or("$"), num))
+        })
+        .register("number", number)
+        .register("format_number", number)
+        .register("percent", |value, _| {
+            numeric(value, |num| format!("{:.1}%", num * 100.0))
+        })
+        .register("abs", |value, _| {
+            numeric(value, |num| num.abs().to_string())
+        })
+        .register("round", |value, arg| {
+            numeric(value, |num| {
+                let decimals: i32 = arg.and_then(|a| a.parse().ok()).unwrap_or(0);
+                let factor = 10_f64.powi(decimals);
+                ((num * factor).round() / factor).to_string()
+            })
+        })
+        .register("spellout", |value, arg| {
+            let locale = arg.map_or(Some(Locale::default()), Locale::from_tag);
+            locale
+                .and_then(|locale| spellout(value, locale))
+                .unwrap_or_else(|| value.to_string())
+        })
+        .register("ordinal", |value, arg| {
+            let locale = arg.map_or(Some(Locale::default()), Locale::from_tag);
+            locale
+                .and_then(|locale| ordinal(value, locale))
+                .unwrap_or_else(|| value.to_string())
+        });
+
+    // Date filters
+    filters
+        .register("date", |value, arg| {
+            // strftime format
+            let format = arg.unwrap_or("%Y-%m-%d");
+            if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+                date.format(format).to_string()
+            } else if let Ok(datetime) = chrono::DateTime::parse_from_rfc3339(value) {
+                datetime.format(format).to_string()
+            } else {
+                value.to_string()
+            }
+        })
+        .register("format_date", |value, arg| {
+            // ICU pattern, optionally after a locale: format_date:de:d. MMMM yyyy
+            let (locale, pattern) = locale_prefix(arg.unwrap_or("yyyy-MM-dd"));
+            match parse_date(value) {
+                Some(datetime) => format_date(datetime, pattern, locale),
+                None => value.to_string(),
+            }
+        })
+        .register("now", |_, arg| {
+            let format = arg.unwrap_or("%Y-%m-%d");
+            chrono::Local::now().format(format).to_string()
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_filters() {
+        let filters = Filters::default();
+        assert_eq!(filters.apply("hello", "upper"), "HELLO");
+        assert_eq!(filters.apply("hello world", "title"), "Hello world");
+        assert_eq!(filters.apply("a-b", "replace:-:+"), "a+b");
+        assert_eq!(filters.apply("a-b", "replace"), "a-b");
+        assert_eq!(filters.apply("2.5", "currency:€"), "€2.50");
+        assert_eq!(filters.apply("n/a", "percent"), "n/a");
+        assert_eq!(filters.apply("21", "ordinal"), "21st");
+        assert_eq!(filters.apply("42", "spellout:de"), "zweiundvierzig");
+        assert_eq!(filters.apply("42", "spellout:xx"), "42");
+        assert_eq!(filters.apply("x", "no_such_filter"), "x");
+        assert!(filters.contains("format_date"));
+        assert!(!Filters::empty().contains("upper"));
+    }
+
+    #[test]
+    fn test_format_date_filter() {
+        let filters = Filters::default();
+        assert_eq!(
+            filters.apply("2024-03-05", "format_date:de:EEEE, d. MMMM yyyy"),
+            "Dienstag, 5. März 2024"
+        );
+        assert_eq!(
+            filters.apply("2024-03-05T14:07:00", "format_date:HH:mm"),
+            "14:07"
+        );
+        assert_eq!(filters.apply("2024-03-05", "format_date"), "2024-03-05");
+        assert_eq!(filters.apply("soon", "format_date:yyyy"), "soon");
+    }
+
+    #[test]
+    fn test_register_replaces() {
+        let mut filters = Filters::default();
+        filters.register("upper", |value, arg| {
+            format!("{}{}", value, arg.unwrap_or("!"))
+        });
+        assert_eq!(filters.apply("hi", "upper"), "hi!");
+        assert_eq!(filters.apply("hi", "upper:?"), "hi?");
+        assert!(filters.names().windows(2).all(|w| w[0] < w[1]));
+    }
+}
@@ -0,0 +1,645 @@
+//! Numbers in words, and ordinals.
+//!
+//! Spelling follows ICU's `spellout-numbering` rules: whole numbers in
+//! words, then the digits after the decimal point one by one.
+
+use crate::style::Locale;
+
+/// A number split out of its text, keeping the decimals as written so
+/// `0.10` doesn't become `0.1000000000000000055`.
+struct Parts<'a> {
+    negative: bool,
+    whole: u64,
+    decimals: &'a str,
+}
+
+fn parts(text: &str) -> Option<Parts<'_>> {
+    let text = text.trim();
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let (whole, decimals) = text.split_once('.').unwrap_or((text, ""));
+    if whole.is_empty() || !whole.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if !decimals.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some(Parts {
+        negative,
+        whole: whole.parse().ok()?,
+        decimals,
+    })
+}
+
+/// Words for a language.
+struct Speller {
+    whole: fn(u64) -> String,
+    minus: &'static str,
+    point: &'static str,
+    /// Separator between words; none in Chinese and Japanese
+    space: &'static str,
+}
+
+fn speller(locale: Locale) -> Option<Speller> {
+    let speller = |whole, minus, point, space| Speller {
+        whole,
+        minus,
+        point,
+        space,
+    };
+    match locale {
+        Locale::EnUs | Locale::EnGb => Some(speller(english, "minus", "point", " ")),
+        Locale::De | Locale::DeCh => Some(speller(german, "minus", "Komma", " ")),
+        Locale::Fr => Some(speller(french, "moins", "virgule", " ")),
+        Locale::Es => Some(speller(spanish, "menos", "coma", " ")),
+        Locale::ZhTw => Some(speller(|n| cjk(n, &ZH_TW), "負", "點", "")),
+        Locale::ZhCn => Some(speller(|n| cjk(n, &ZH_CN), "负", "点", "")),
+        Locale::Ja => Some(speller(|n| cjk(n, &JA), "マイナス", "点", "")),
+        Locale::It | Locale::Nl | Locale::PtBr | Locale::Ko => None,
+    }
+}
+
+/// Spells out a number written as text, as `1234.5` in English gives
+/// "one thousand two hundred thirty-four point five". Returns `None` for
+/// text that isn't a plain decimal number and for languages without rules
+/// (Italian, Dutch, Portuguese and Korean).
+pub fn spellout(text: &str, locale: Locale) -> Option<String> {
+    let parts = parts(text)?;
+    let speller = speller(locale)?;
+    let mut words = Vec::new();
+    if parts.negative && (parts.whole > 0 || parts.decimals.bytes().any(|b| b != b'0')) {
+        words.push(speller.minus.to_string());
+    }
+    words.push((speller.whole)(parts.whole));
+    if !parts.decimals.is_empty() {
+        words.push(speller.point.to_string());
+        for digit in parts.decimals.bytes() {
+            words.push((speller.whole)(u64::from(digit - b'0')));
+        }
+    }
+    Some(words.join(speller.space))
+}
+
+/// Writes a whole number as an ordinal: `21st` in English, `21.` in
+/// German, `21e` in French, `21.º` in Spanish, `第21` in Chinese and
+/// Japanese. Returns `None` for other text and other languages.
+pub fn ordinal(text: &str, locale: Locale) -> Option<String> {
+    let text = text.trim();
+    let n: u64 = text.parse().ok()?;
+    match locale {
+        Locale::EnUs | Locale::EnGb => {
+            let suffix = match (n % 10, n % 100) {
+                (_, 11..=13) => "th",
+                (1, _) => "st",
+                (2, _) => "nd",
+                (3, _) => "rd",
+                _ => "th",
+            };
+            Some(format!("{}{}", n, suffix))
+        }
+        Locale::De | Locale::DeCh => Some(format!("{}.", n)),
+        Locale::Fr if n == 1 => Some("1er".to_string()),
+        Locale::Fr => Some(format!("{}e", n)),
+        Locale::Es => Some(format!("{}.º", n)),
+        Locale::ZhTw | Locale::ZhCn | Locale::Ja => Some(format!("第{}", n)),
+        Locale::It | Locale::Nl | Locale::PtBr | Locale::Ko => None,
+    }
+}
+
+const EN_ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+const EN_TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+fn english_below_1000(n: u64) -> String {
+    let mut words = Vec::new();
+    if n >= 100 {
+        words.push(format!("{} hundred", EN_ONES[(n / 100) as usize]));
+    }
+    let rest = (n % 100) as usize;
+    match rest {
+        0 if n > 0 => {}
+        0..=19 => words.push(EN_ONES[rest].to_string()),
+        _ if rest % 10 == 0 => words.push(EN_TENS[rest / 10].to_string()),
+        _ => words.push(format!("{}-{}", EN_TENS[rest / 10], EN_ONES[rest % 10])),
+    }
+    words.join(" ")
+}
+
+fn english(n: u64) -> String {
+    const SCALES: [(u64, &str); 6] = [
+        (1_000_000_000_000_000_000, "quintillion"),
+        (1_000_000_000_000_000, "quadrillion"),
+        (1_000_000_000_000, "trillion"),
+        (1_000_000_000, "billion"),
+        (1_000_000, "million"),
+        (1_000, "thousand"),
+    ];
+    if n == 0 {
+        return EN_ONES[0].to_string();
+    }
+    let mut words = Vec::new();
+    let mut rest = n;
+    for (scale, name) in SCALES {
+        if rest >= scale {
+            words.push(format!("{} {}", english_below_1000(rest / scale), name));
+            rest %= scale;
+        }
+    }
+    if rest > 0 {
+        words.push(english_below_1000(rest));
+    }
+    words.join(" ")
+}
+
+const DE_ONES: [&str; 20] = [
+    "null",
+    "eins",
+    "zwei",
+    "drei",
+    "vier",
+    "fünf",
+    "sechs",
+    "sieben",
+    "acht",
+    "neun",
+    "zehn",
+    "elf",
+    "zwölf",
+    "dreizehn",
+    "vierzehn",
+    "fünfzehn",
+    "sechzehn",
+    "siebzehn",
+    "achtzehn",
+    "neunzehn",
+];
+const DE_TENS: [&str; 10] = [
+    "", "", "zwanzig", "dreißig", "vierzig", "fünfzig", "sechzig", "siebzig", "achtzig", "neunzig",
+];
+
+/// German below 1000 as one word. `final_one` spells a trailing 1 as
+/// "eins", as at the end of a number, rather than the "ein" of
+/// "eintausend".
+fn german_below_1000(n: u64, final_one: bool) -> String {
+    let unit = |d: u64| if d == 1 { "ein" } else { DE_ONES[d as usize] };
+    let mut word = String::new();
+    if n >= 100 {
+        word.push_str(unit(n / 100));
+        word.push_str("hundert");
+    }
+    let rest = n % 100;
+    match rest {
+        0 => {}
+        1 if !final_one => word.push_str("ein"),
+        1..=19 => word.push_str(DE_ONES[rest as usize]),
+        _ if rest % 10 == 0 => word.push_str(DE_TENS[(rest / 10) as usize]),
+        _ => {
+            word.push_str(unit(rest % 10));
+            word.push_str("und");
+            word.push_str(DE_TENS[(rest / 10) as usize]);
+        }
+    }
+    word
+}
+
+fn german(n: u64) -> String {
+    const SCALES: [(u64, &str, &str); 5] = [
+        (1_000_000_000_000_000_000, "Trillion", "Trillionen"),
+        (1_000_000_000_000_000, "Billiarde", "Billiarden"),
+        (1_000_000_000_000, "Billion", "Billionen"),
+        (1_000_000_000, "Milliarde", "Milliarden"),
+        (1_000_000, "Million", "Millionen"),
+    ];
+    if n == 0 {
+        return DE_ONES[0].to_string();
+    }
+    let mut words = Vec::new();
+    let mut rest = n;
+    for (scale, one, many) in SCALES {
+        let count = rest / scale;
+        // Scale words are feminine nouns, written apart
+        match count {
+            0 => {}
+            1 => words.push(format!("eine {}", one)),
+            _ => words.push(format!("{} {}", german_below_1000(count, true), many)),
+        }
+        rest %= scale;
+    }
+    // Below a million, one word
+    let mut word = String::new();
+    if rest >= 1000 {
+        word.push_str(&german_below_1000(rest / 1000, false));
+        word.push_str("tausend");
+    }
+    if rest % 1000 > 0 {
+        word.push_str(&german_below_1000(rest % 1000, true));
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words.join(" ")
+}
+
+const FR_ONES: [&str; 17] = [
+    "zéro", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf", "dix", "onze",
+    "douze", "treize", "quatorze", "quinze", "seize",
+];
+const FR_TENS: [&str; 7] = [
+    "",
+    "",
+    "vingt",
+    "trente",
+    "quarante",
+    "cinquante",
+    "soixante",
+];
+
+/// French below 100. `plural` keeps the "s" of a final "quatre-vingts".
+fn french_below_100(n: u64, plural: bool) -> String {
+    let n = n as usize;
+    match n {
+        0..=16 => FR_ONES[n].to_string(),
+        17..=19 => format!("dix-{}", FR_ONES[n - 10]),
+        20..=69 if n % 10 == 0 => FR_TENS[n / 10].to_string(),
+        20..=69 if n % 10 == 1 => format!("{}-et-un", FR_TENS[n / 10]),
+        20..=69 => format!("{}-{}", FR_TENS[n / 10], FR_ONES[n % 10]),
+        71 => "soixante-et-onze".to_string(),
+        70..=79 => format!("soixante-{}", french_below_100(n as u64 - 60, plural)),
+        80 if plural => "quatre-vingts".to_string(),
+        80 => "quatre-vingt".to_string(),
+        _ => format!("quatre-vingt-{}", french_below_100(n as u64 - 80, plural)),
+    }
+}
+
+/// French below 1000. `plural` is false before "mille", which leaves
+/// "cent" and "vingt" without their plural "s".
+fn french_below_1000(n: u64, plural: bool) -> String {
+    let (hundreds, rest) = (n / 100, n % 100);
+    let mut words = Vec::new();
+    match hundreds {
+        0 => {}
+        1 => words.push("cent".to_string()),
+        _ if rest == 0 && plural => words.push(format!("{} cents", FR_ONES[hundreds as usize])),
+        _ => words.push(format!("{} cent", FR_ONES[hundreds as usize])),
+    }
+    if rest > 0 || hundreds == 0 {
+        words.push(french_below_100(rest, plural));
+    }
+    words.join(" ")
+}
+
+fn french(n: u64) -> String {
+    const SCALES: [(u64, &str); 5] = [
+        (1_000_000_000_000_000_000, "trillion"),
+        (1_000_000_000_000_000, "billiard"),
+        (1_000_000_000_000, "billion"),
+        (1_000_000_000, "milliard"),
+        (1_000_000, "million"),
+    ];
+    let mut words = Vec::new();
+    let mut rest = n;
+    for (scale, name) in SCALES {
+        let count = rest / scale;
+        match count {
+            0 => {}
+            1 => words.push(format!("un {}", name)),
+            _ => words.push(format!("{} {}s", french_below_1000(count, true), name)),
+        }
+        rest %= scale;
+    }
+    match rest / 1000 {
+        0 => {}
+        1 => words.push("mille".to_string()),
+        thousands => words.push(format!("{} mille", french_below_1000(thousands, false))),
+    }
+    if rest % 1000 > 0 || words.is_empty() {
+        words.push(french_below_1000(rest % 1000, true));
+    }
+    words.join(" ")
+}
+
+const ES_ONES: [&str; 30] = [
+    "cero",
+    "uno",
+    "dos",
+    "tres",
+    "cuatro",
+    "cinco",
+    "seis",
+    "siete",
+    "ocho",
+    "nueve",
+    "diez",
+    "once",
+    "doce",
+    "trece",
+    "catorce",
+    "quince",
+    "dieciséis",
+    "diecisiete",
+    "dieciocho",
+    "diecinueve",
+    "veinte",
+    "veintiuno",
+    "veintidós",
+    "veintitrés",
+    "veinticuatro",
+    "veinticinco",
+    "veintiséis",
+    "veintisiete",
+    "veintiocho",
+    "veintinueve",
+];
+const ES_TENS: [&str; 10] = [
+    "",
+    "",
+    "",
+    "treinta",
+    "cuarenta",
+    "cincuenta",
+    "sesenta",
+    "setenta",
+    "ochenta",
+    "noventa",
+];
+const ES_HUNDREDS: [&str; 10] = [
+    "",
+    "ciento",
+    "doscientos",
+    "trescientos",
+    "cuatrocientos",
+    "quinientos",
+    "seiscientos",
+    "setecientos",
+    "ochocientos",
+    "novecientos",
+];
+
+/// Spanish below 1000. `short_one` shortens a final "uno" to "un" before
+/// a noun such as "mil" or "millones".
+fn spanish_below_1000(n: u64, short_one: bool) -> String {
+    if n == 100 {
+        return "cien".to_string();
+    }
+    let (hundreds, rest) = ((n / 100) as usize, (n % 100) as usize);
+    let mut words = Vec::new();
+    if hundreds > 0 {
+        words.push(ES_HUNDREDS[hundreds].to_string());
+    }
+    if rest > 0 || hundreds == 0 {
+        words.push(match rest {
+            0..=29 => ES_ONES[rest].to_string(),
+            _ if rest % 10 == 0 => ES_TENS[rest / 10].to_string(),
+            _ => format!("{} y {}", ES_TENS[rest / 10], ES_ONES[rest % 10]),
+        });
+    }
+    let words = words.join(" ");
+    if !short_one {
+        words
+    } else if let Some(stem) = words.strip_suffix("veintiuno") {
+        format!("{}veintiún", stem)
+    } else if let Some(stem) = words.strip_suffix("uno") {
+        format!("{}un", stem)
+    } else {
+        words
+    }
+}
+
+/// Spanish below a million.
+fn spanish_below_million(n: u64, short_one: bool) -> String {
+    let mut words = Vec::new();
+    match n / 1000 {
+        0 => {}
+        1 => words.push("mil".to_string()),
+        thousands => words.push(format!("{} mil", spanish_below_1000(thousands, true))),
+    }
+    if n % 1000 > 0 || n == 0 {
+        words.push(spanish_below_1000(n % 1000, short_one));
+    }
+    words.join(" ")
+}
+
+fn spanish(n: u64) -> String {
+    // Long scale: a billón is a million millions
+    const SCALES: [(u64, &str, &str); 3] = [
+        (1_000_000_000_000_000_000, "trillón", "trillones"),
+        (1_000_000_000_000, "billón", "billones"),
+        (1_000_000, "millón", "millones"),
+    ];
+    let mut words = Vec::new();
+    let mut rest = n;
+    for (scale, one, many) in SCALES {
+        let count = rest / scale;
+        match count {
+            0 => {}
+            1 => words.push(format!("un {}", one)),
+            _ => words.push(format!("{} {}", spanish_below_million(count, true), many)),
+        }
+        rest %= scale;
+    }
+    if rest > 0 || words.is_empty() {
+        words.push(spanish_below_million(rest, false));
+    }
+    words.join(" ")
+}
+
+/// Numerals for Chinese or Japanese.
+struct Cjk {
+    digits: [&'static str; 10],
+    /// Units of ten thousand, 10^4, 10^8, ...
+    myriads: [&'static str; 5],
+    /// Chinese writes a zero for a gap, as 一千零一; Japanese doesn't, and
+    /// drops the 一 before 十, 百 and 千
+    gap_zero: bool,
+}
+
+const ZH_TW: Cjk = Cjk {
+    digits: ["零", "一", "二", "三", "四", "五", "六", "七", "八", "九"],
+    myriads: ["", "萬", "億", "兆", "京"],
+    gap_zero: true,
+};
+const ZH_CN: Cjk = Cjk {
+    digits: ["零", "一", "二", "三", "四", "五", "六", "七", "八", "九"],
+    myriads: ["", "万", "亿", "兆", "京"],
+    gap_zero: true,
+};
+const JA: Cjk = Cjk {
+    digits: ["〇", "一", "二", "三", "四", "五", "六", "七", "八", "九"],
+    myriads: ["", "万", "億", "兆", "京"],
+    gap_zero: false,
+};
+
+fn cjk(n: u64, numerals: &Cjk) -> String {
+    if n == 0 {
+        return numerals.digits[0].to_string();
+    }
+    let mut groups = Vec::new();
+    let mut rest = n;
+    while rest > 0 {
+        groups.push(rest % 10_000);
+        rest /= 10_000;
+    }
+    let mut out = String::new();
+    let mut zero_pending = false;
+    for (myriad, &group) in groups.iter().enumerate().rev() {
+        if group == 0 {
+            zero_pending |= !out.is_empty();
+            continue;
+        }
+        zero_pending |= !out.is_empty() && group < 1000;
+        let mut started = false;
+        for (power, unit) in [(1000, "千"), (100, "百"), (10, "十"), (1, "")] {
+            let digit = (group / power % 10) as usize;
+            if digit == 0 {
+                zero_pending |= started;
+                continue;
+            }
+            if zero_pending && numerals.gap_zero {
+                out.push_str(numerals.digits[0]);
+            }
+            zero_pending = false;
+            // 十 alone for a leading ten in Chinese; 十, 百, 千 in Japanese
+            let bare_one =
+                digit == 1 && power > 1 && (!numerals.gap_zero || (power == 10 && out.is_empty()));
+            if !bare_one {
+                out.push_str(numerals.digits[digit]);
+            }
+            out.push_str(unit);
+            started = true;
+        }
+        out.push_str(numerals.myriads[myriad]);
+        // Trailing zeros of a group are covered by the next group's size
+        zero_pending = false;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spell(n: &str, locale: Locale) -> String {
+        spellout(n, locale).unwrap()
+    }
+
+    #[test]
+    fn test_spellout_english() {
+        let cases = [
+            ("0", "zero"),
+            ("13", "thirteen"),
+            ("40", "forty"),
+            ("105", "one hundred five"),
+            ("1000000", "one million"),
+            ("2021042", "two million twenty-one thousand forty-two"),
+            ("-3.05", "minus three point zero five"),
+            ("-0", "zero"),
+        ];
+        for (n, words) in cases {
+            assert_eq!(spell(n, Locale::EnUs), words, "{}", n);
+        }
+        assert_eq!(spellout("1e5", Locale::EnUs), None);
+        assert_eq!(spellout("12", Locale::It), None);
+    }
+
+    #[test]
+    fn test_spellout_european() {
+        let german = [
+            ("1", "eins"),
+            ("21", "einundzwanzig"),
+            ("101", "einhunderteins"),
+            ("1234", "eintausendzweihundertvierunddreißig"),
+            ("2000001", "zwei Millionen eins"),
+            ("1000000000", "eine Milliarde"),
+        ];
+        for (n, words) in german {
+            assert_eq!(spell(n, Locale::De), words, "{}", n);
+        }
+        let french = [
+            ("21", "vingt-et-un"),
+            ("71", "soixante-et-onze"),
+            ("80", "quatre-vingts"),
+            ("99", "quatre-vingt-dix-neuf"),
+            ("200", "deux cents"),
+            ("201", "deux cent un"),
+            ("80000", "quatre-vingt mille"),
+            ("1001", "mille un"),
+            ("2000000", "deux millions"),
+        ];
+        for (n, words) in french {
+            assert_eq!(spell(n, Locale::Fr), words, "{}", n);
+        }
+        let spanish = [
+            ("1", "uno"),
+            ("100", "cien"),
+            ("115", "ciento quince"),
+            ("21000", "veintiún mil"),
+            ("31", "treinta y uno"),
+            ("1000000", "un millón"),
+            ("2500000", "dos millones quinientos mil"),
+            ("1000000000", "mil millones"),
+        ];
+        for (n, words) in spanish {
+            assert_eq!(spell(n, Locale::Es), words, "{}", n);
+        }
+    }
+
+    #[test]
+    fn test_spellout_cjk() {
+        let cases = [
+            ("10", "十", "十", "十"),
+            ("110", "一百一十", "一百一十", "百十"),
+            ("1010", "一千零一十", "一千零一十", "千十"),
+            ("100001", "十萬零一", "十万零一", "十万一"),
+            ("10001000", "一千萬一千", "一千万一千", "千万千"),
+            ("100000000", "一億", "一亿", "一億"),
+            ("3.5", "三點五", "三点五", "三点五"),
+        ];
+        for (n, tw, cn, ja) in cases {
+            assert_eq!(spell(n, Locale::ZhTw), tw, "{}", n);
+            assert_eq!(spell(n, Locale::ZhCn), cn, "{}", n);
+            assert_eq!(spell(n, Locale::Ja), ja, "{}", n);
+        }
+        assert_eq!(spell("0", Locale::Ja), "〇");
+    }
+
+    #[test]
+    fn test_ordinal() {
+        let english: Vec<String> = ["1", "2", "3", "4", "11", "12", "13", "21", "102", "111"]
+            .iter()
+            .map(|n| ordinal(n, Locale::EnGb).unwrap())
+            .collect();
+        assert_eq!(
+            english,
+            ["1st", "2nd", "3rd", "4th", "11th", "12th", "13th", "21st", "102nd", "111th"]
+        );
+        assert_eq!(ordinal("3", Locale::De).as_deref(), Some("3."));
+        assert_eq!(ordinal("1", Locale::Fr).as_deref(), Some("1er"));
+        assert_eq!(ordinal("2", Locale::Fr).as_deref(), Some("2e"));
+        assert_eq!(ordinal("5", Locale::ZhTw).as_deref(), Some("第5"));
+        assert_eq!(ordinal("2.5", Locale::EnUs), None);
+    }
+}
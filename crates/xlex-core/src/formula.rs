@@ -0,0 +1,1195 @@
+//! Formula reference utilities.
+//!
+//! Formulas are kept as plain strings in [`CellValue::Formula`](crate::CellValue).
+//! The helpers here tokenize just enough of a formula to find A1-style cell
+//! references so they can be rewritten when cells are moved, copied or
+//! transposed. String literals, function names and defined names are left
+//! untouched.
+
+use std::collections::BTreeSet;
+
+use crate::cell::CellRef;
+use crate::range::Range;
+
+/// Functions Excel recalculates on every change, whatever their inputs.
+pub const VOLATILE_FUNCTIONS: &[&str] = &[
+    "NOW",
+    "TODAY",
+    "RAND",
+    "RANDBETWEEN",
+    "RANDARRAY",
+    "OFFSET",
+    "INDIRECT",
+    "CELL",
+    "INFO",
+];
+
+/// Functions newer than the file format, with the prefix Excel expects in
+/// stored formulas. Without it Excel shows `#NAME?`.
+const FUTURE_FUNCTIONS: &[(&str, &str)] = &[
+    ("FILTER", "_xlfn._xlws."),
+    ("SORT", "_xlfn._xlws."),
+    ("SORTBY", "_xlfn."),
+    ("UNIQUE", "_xlfn."),
+    ("SEQUENCE", "_xlfn."),
+    ("RANDARRAY", "_xlfn."),
+    ("XLOOKUP", "_xlfn."),
+    ("XMATCH", "_xlfn."),
+    ("LET", "_xlfn."),
+    ("TEXTSPLIT", "_xlfn."),
+    ("TEXTBEFORE", "_xlfn."),
+    ("TEXTAFTER", "_xlfn."),
+    ("VSTACK", "_xlfn."),
+    ("HSTACK", "_xlfn."),
+    ("TAKE", "_xlfn."),
+    ("DROP", "_xlfn."),
+    ("CHOOSECOLS", "_xlfn."),
+    ("CHOOSEROWS", "_xlfn."),
+    ("TOCOL", "_xlfn."),
+    ("TOROW", "_xlfn."),
+    ("WRAPCOLS", "_xlfn."),
+    ("WRAPROWS", "_xlfn."),
+    ("EXPAND", "_xlfn."),
+];
+
+/// A cell reference found inside a formula.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormulaRef {
+    /// Column number (1-indexed)
+    pub col: u32,
+    /// Row number (1-indexed)
+    pub row: u32,
+    /// Whether the column is absolute (`$A1`)
+    pub col_absolute: bool,
+    /// Whether the row is absolute (`A$1`)
+    pub row_absolute: bool,
+    /// Sheet qualifier (`Sheet1!A1`), without quotes
+    pub sheet: Option<String>,
+}
+
+impl FormulaRef {
+    /// Returns the reference in A1 notation, keeping `$` markers but
+    /// without the sheet qualifier.
+    pub fn to_a1(&self) -> String {
+        format!(
+            "{}{}{}{}",
+            if self.col_absolute { "$" } else { "" },
+            CellRef::col_to_letters(self.col),
+            if self.row_absolute { "$" } else { "" },
+            self.row
+        )
+    }
+
+    /// Returns the reference as a plain cell reference.
+    pub fn cell_ref(&self) -> CellRef {
+        CellRef::new(self.col, self.row)
+    }
+}
+
+/// Whether a [`LineRef`] spans whole rows or whole columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineAxis {
+    /// Whole rows (`1:5`)
+    Rows,
+    /// Whole columns (`A:C`)
+    Columns,
+}
+
+/// A whole-row (`1:5`) or whole-column (`A:C`) reference found inside a
+/// formula.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineRef {
+    /// Rows or columns
+    pub axis: LineAxis,
+    /// First row or column number (1-indexed), as written
+    pub start: u32,
+    /// Last row or column number (1-indexed), as written
+    pub end: u32,
+    /// Whether the first endpoint is absolute (`$1`, `$A`)
+    pub start_absolute: bool,
+    /// Whether the last endpoint is absolute
+    pub end_absolute: bool,
+    /// Sheet qualifier (`Sheet1!1:5`), without quotes
+    pub sheet: Option<String>,
+}
+
+impl LineRef {
+    /// Returns the reference in A1 notation (`$1:5`, `A:$C`), without the
+    /// sheet qualifier.
+    pub fn to_a1(&self) -> String {
+        let endpoint = |n: u32, absolute: bool| {
+            let n = match self.axis {
+                LineAxis::Rows => n.to_string(),
+                LineAxis::Columns => CellRef::col_to_letters(n),
+            };
+            format!("{}{}", if absolute { "$" } else { "" }, n)
+        };
+        format!(
+            "{}:{}",
+            endpoint(self.start, self.start_absolute),
+            endpoint(self.end, self.end_absolute)
+        )
+    }
+}
+
+/// A replacement for a reference produced by a [`map_refs`] callback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RefEdit<R = FormulaRef> {
+    /// Keep the reference as written.
+    Keep,
+    /// Replace the reference.
+    Replace(R),
+    /// The reference no longer points at a valid cell (`#REF!`).
+    Invalid,
+}
+
+/// Rewrites every A1-style cell reference in a formula.
+///
+/// The callback receives each reference and decides whether it is kept,
+/// replaced or invalidated. Range endpoints (`A1:B2`) are visited
+/// separately; the second endpoint inherits the sheet qualifier of the first.
+pub fn map_refs<F>(formula: &str, mut f: F) -> String
+where
+    F: FnMut(&FormulaRef) -> RefEdit,
+{
+    walk_refs(formula, |r, _| f(r))
+}
+
+/// Rewrites every whole-row (`1:5`) and whole-column (`A:C`) reference in
+/// a formula, the way [`map_refs`] does cell references. A replacement is a
+/// [`RefEdit`] carrying a [`LineRef`].
+pub fn map_line_refs<G>(formula: &str, g: G) -> String
+where
+    G: FnMut(&LineRef) -> RefEdit<LineRef>,
+{
+    walk(formula, |_, _| RefEdit::Keep, g)
+}
+
+/// Like [`map_refs`], but also tells the callback whether the reference is
+/// the second endpoint of a range.
+fn walk_refs<F>(formula: &str, f: F) -> String
+where
+    F: FnMut(&FormulaRef, bool) -> RefEdit,
+{
+    walk(formula, f, |_| RefEdit::Keep)
+}
+
+/// Rewrites cell references through `f` (see [`walk_refs`]) and whole-row
+/// or whole-column references through `g`.
+fn walk<F, G>(formula: &str, mut f: F, mut g: G) -> String
+where
+    F: FnMut(&FormulaRef, bool) -> RefEdit,
+    G: FnMut(&LineRef) -> RefEdit<LineRef>,
+{
+    let chars: Vec<char> = formula.chars().collect();
+    let mut out = String::with_capacity(formula.len());
+    let mut pending_sheet: Option<String> = None;
+    let mut range_sheet: Option<Option<String>> = None;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == '"' {
+            // String literal, "" is an escaped quote
+            let start = i;
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == '"' {
+                    if chars.get(i + 1) == Some(&'"') {
+                        i += 2;
+                        continue;
+                    }
+                    break;
+                }
+                i += 1;
+            }
+            i = (i + 1).min(chars.len());
+            out.extend(&chars[start..i]);
+            pending_sheet = None;
+            range_sheet = None;
+            continue;
+        }
+
+        if c == '\'' {
+            // Quoted sheet name, '' is an escaped quote
+            let start = i;
+            let mut name = String::new();
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == '\'' {
+                    if chars.get(i + 1) == Some(&'\'') {
+                        name.push('\'');
+                        i += 2;
+                        continue;
+                    }
+                    break;
+                }
+                name.push(chars[i]);
+                i += 1;
+            }
+            i = (i + 1).min(chars.len());
+            out.extend(&chars[start..i]);
+            if chars.get(i) == Some(&'!') {
+                out.push('!');
+                i += 1;
+                pending_sheet = Some(name);
+            } else {
+                pending_sheet = None;
+            }
+            range_sheet = None;
+            continue;
+        }
+
+        if is_word_char(c) {
+            let start = i;
+            while i < chars.len() && is_word_char(chars[i]) {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+
+            if chars.get(i) == Some(&'!') {
+                out.push_str(&word);
+                out.push('!');
+                i += 1;
+                pending_sheet = Some(word);
+                range_sheet = None;
+                continue;
+            }
+
+            // `1:5` or `A:C`, both endpoints of the same kind
+            let line = parse_line(&word).filter(|_| chars.get(i) == Some(&':'));
+            if let Some((axis, start_n, start_absolute)) = line {
+                let mut end = i + 1;
+                while end < chars.len() && is_word_char(chars[end]) {
+                    end += 1;
+                }
+                let end_word: String = chars[i + 1..end].iter().collect();
+                if let Some((_, end_n, end_absolute)) =
+                    parse_line(&end_word).filter(|(end_axis, _, _)| *end_axis == axis)
+                {
+                    let reference = LineRef {
+                        axis,
+                        start: start_n,
+                        end: end_n,
+                        start_absolute,
+                        end_absolute,
+                        sheet: pending_sheet.take(),
+                    };
+                    match g(&reference) {
+                        RefEdit::Keep => out.extend(&chars[start..end]),
+                        RefEdit::Replace(new_ref) => out.push_str(&new_ref.to_a1()),
+                        RefEdit::Invalid => out.push_str("#REF!"),
+                    }
+                    i = end;
+                    range_sheet = None;
+                    continue;
+                }
+            }
+
+            let is_call = chars.get(i) == Some(&'(');
+            match parse_ref(&word).filter(|_| !is_call) {
+                Some((col, row, col_absolute, row_absolute)) => {
+                    let range_end = range_sheet.is_some();
+                    let sheet = match range_sheet.take() {
+                        Some(sheet) => sheet,
+                        None => pending_sheet.take(),
+                    };
+                    let reference = FormulaRef {
+                        col,
+                        row,
+                        col_absolute,
+                        row_absolute,
+                        sheet: sheet.clone(),
+                    };
+                    match f(&reference, range_end) {
+                        RefEdit::Keep => out.push_str(&word),
+                        RefEdit::Replace(new_ref) => out.push_str(&new_ref.to_a1()),
+                        // A range with both endpoints gone is a single #REF!
+                        RefEdit::Invalid if range_end && out.ends_with("#REF!:") => {
+                            out.pop();
+                        }
+                        RefEdit::Invalid => out.push_str("#REF!"),
+                    }
+                    if chars.get(i) == Some(&':') {
+                        range_sheet = Some(sheet);
+                    }
+                }
+                None => {
+                    out.push_str(&word);
+                    range_sheet = None;
+                }
+            }
+            pending_sheet = None;
+            continue;
+        }
+
+        if c != ':' {
+            range_sheet = None;
+        }
+        pending_sheet = None;
+        out.push(c);
+        i += 1;
+    }
+
+    out
+}
+
+/// Collects every cell reference in a formula.
+pub fn collect_refs(formula: &str) -> Vec<FormulaRef> {
+    let mut refs = Vec::new();
+    map_refs(formula, |r| {
+        refs.push(r.clone());
+        RefEdit::Keep
+    });
+    refs
+}
+
+/// Collects every cell or range referenced by a formula, with its sheet
+/// qualifier (`Data!A1:B10` gives `(Some("Data"), A1:B10)`).
+pub fn collect_ranges(formula: &str) -> Vec<(Option<String>, Range)> {
+    let mut ranges: Vec<(Option<String>, Range)> = Vec::new();
+    walk_refs(formula, |r, range_end| {
+        match ranges.last_mut() {
+            Some((_, range)) if range_end => range.end = r.cell_ref(),
+            _ => ranges.push((r.sheet.clone(), Range::single(r.cell_ref()))),
+        }
+        RefEdit::Keep
+    });
+    ranges
+}
+
+/// Structural complexity of a formula.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FormulaComplexity {
+    /// Deepest nesting of function calls (`IF(ISBLANK(A1),..)` is 2)
+    pub nesting_depth: usize,
+    /// Number of function calls
+    pub function_count: usize,
+    /// Volatile functions called, uppercased and deduplicated
+    pub volatile_functions: Vec<String>,
+    /// Number of cells and ranges referenced
+    pub reference_count: usize,
+}
+
+/// Measures how complex a formula is to read and to recalculate.
+pub fn complexity(formula: &str) -> FormulaComplexity {
+    let chars: Vec<char> = formula.chars().collect();
+    let mut result = FormulaComplexity {
+        reference_count: collect_ranges(formula).len(),
+        ..Default::default()
+    };
+    // One entry per open parenthesis: whether it belongs to a function call
+    let mut stack: Vec<bool> = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '"' || c == '\'' {
+            // String literal or quoted sheet name; doubled quotes escape
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == c {
+                    if chars.get(i + 1) == Some(&c) {
+                        i += 2;
+                        continue;
+                    }
+                    break;
+                }
+                i += 1;
+            }
+            i += 1;
+            continue;
+        }
+
+        if is_word_char(c) {
+            let start = i;
+            while i < chars.len() && is_word_char(chars[i]) {
+                i += 1;
+            }
+            if chars.get(i) == Some(&'(') {
+                let word: String = chars[start..i].iter().collect();
+                let name = word.to_ascii_uppercase();
+                let name = name.strip_prefix("_XLFN.").unwrap_or(&name);
+                result.function_count += 1;
+                if VOLATILE_FUNCTIONS.contains(&name)
+                    && !result.volatile_functions.iter().any(|v| v == name)
+                {
+                    result.volatile_functions.push(name.to_string());
+                }
+                stack.push(true);
+                let depth = stack.iter().filter(|is_call| **is_call).count();
+                result.nesting_depth = result.nesting_depth.max(depth);
+                i += 1;
+            }
+            continue;
+        }
+
+        match c {
+            '(' => stack.push(false),
+            ')' => {
+                stack.pop();
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    result
+}
+
+/// Adds the `_xlfn.` prefix Excel stores before dynamic array and other
+/// newer functions (`FILTER(` becomes `_xlfn._xlws.FILTER(`).
+///
+/// Calls that already carry a prefix are left alone.
+pub fn add_function_prefixes(formula: &str) -> String {
+    map_calls(formula, |name| {
+        let upper = name.to_ascii_uppercase();
+        FUTURE_FUNCTIONS
+            .iter()
+            .find(|(function, _)| *function == upper)
+            .map(|(_, prefix)| format!("{}{}", prefix, name))
+    })
+}
+
+/// Removes the prefixes added by [`add_function_prefixes`], giving the
+/// formula as Excel displays it.
+pub fn strip_function_prefixes(formula: &str) -> String {
+    map_calls(formula, |name| {
+        let upper = name.to_ascii_uppercase();
+        FUTURE_FUNCTIONS.iter().find_map(|(function, prefix)| {
+            (upper.len() == prefix.len() + function.len()
+                && upper.starts_with(&prefix.to_ascii_uppercase())
+                && upper.ends_with(function))
+            .then(|| name[prefix.len()..].to_string())
+        })
+    })
+}
+
+/// Rewrites the names of function calls outside string literals and quoted
+/// sheet names. The callback returns the new name, or `None` to keep it.
+fn map_calls<F>(formula: &str, mut f: F) -> String
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let chars: Vec<char> = formula.chars().collect();
+    let mut result = String::with_capacity(formula.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '"' || c == '\'' {
+            let start = i;
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == c {
+                    if chars.get(i + 1) == Some(&c) {
+                        i += 2;
+                        continue;
+                    }
+                    break;
+                }
+                i += 1;
+            }
+            i = (i + 1).min(chars.len());
+            result.extend(&chars[start..i]);
+            continue;
+        }
+
+        if is_word_char(c) {
+            let start = i;
+            while i < chars.len() && is_word_char(chars[i]) {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            match f(&word).filter(|_| chars.get(i) == Some(&'(')) {
+                Some(name) => result.push_str(&name),
+                None => result.push_str(&word),
+            }
+            continue;
+        }
+
+        result.push(c);
+        i += 1;
+    }
+
+    result
+}
+
+/// Shifts the relative parts of every reference by the given offsets, the
+/// way Excel adjusts a formula that is copied to another cell.
+///
+/// Whole-row references (`1:5`) move with the row offset and whole-column
+/// references (`A:C`) with the column offset. References that would move
+/// off the grid become `#REF!`.
+pub fn shift_refs(formula: &str, col_delta: i64, row_delta: i64) -> String {
+    let shift_line = |r: &LineRef| {
+        let (delta, max) = match r.axis {
+            LineAxis::Rows => (row_delta, CellRef::MAX_ROW),
+            LineAxis::Columns => (col_delta, CellRef::MAX_COL),
+        };
+        let endpoint = |n: u32, absolute: bool| {
+            if absolute {
+                Some(n)
+            } else {
+                offset(n, delta, max)
+            }
+        };
+        match (
+            endpoint(r.start, r.start_absolute),
+            endpoint(r.end, r.end_absolute),
+        ) {
+            (Some(start), Some(end)) => RefEdit::Replace(LineRef {
+                start,
+                end,
+                ..r.clone()
+            }),
+            _ => RefEdit::Invalid,
+        }
+    };
+    let shift_cell = |r: &FormulaRef, _| {
+        let col = if r.col_absolute {
+            Some(r.col)
+        } else {
+            offset(r.col, col_delta, CellRef::MAX_COL)
+        };
+        let row = if r.row_absolute {
+            Some(r.row)
+        } else {
+            offset(r.row, row_delta, CellRef::MAX_ROW)
+        };
+        match (col, row) {
+            (Some(col), Some(row)) => RefEdit::Replace(FormulaRef {
+                col,
+                row,
+                ..r.clone()
+            }),
+            _ => RefEdit::Invalid,
+        }
+    };
+    walk(formula, shift_cell, shift_line)
+}
+
+/// Rewrites references after `removed` rows are deleted from a sheet, the
+/// way Excel does: references below a removed row move up, references to a
+/// removed cell become `#REF!`, and ranges, whole-row ones (`1:5`)
+/// included, shrink around the removed rows.
+///
+/// `on_sheet` receives each reference's sheet qualifier and tells whether it
+/// points at the sheet the rows were removed from.
+pub fn remove_rows<P>(formula: &str, removed: &BTreeSet<u32>, on_sheet: P) -> String
+where
+    P: Fn(Option<&str>) -> bool,
+{
+    let ranges = collect_ranges(formula);
+    let mut index = 0;
+    // Where `row` of the rows `top..=bottom` ends up, or None when all of
+    // them are removed. Only the bottom endpoint of a range moves up past
+    // its own row.
+    let moved = |row: u32, top: u32, bottom: u32| {
+        if removed.range(top..=bottom).count() as u32 == bottom - top + 1 {
+            return None;
+        }
+        let below = removed.range(..row).count() as u32;
+        if removed.contains(&row) && row == bottom {
+            Some(row - below - 1)
+        } else {
+            Some(row - below)
+        }
+    };
+
+    let cell = |r: &FormulaRef, range_end: bool| {
+        if !range_end {
+            index += 1;
+        }
+        if !on_sheet(r.sheet.as_deref()) {
+            return RefEdit::Keep;
+        }
+        let Some((_, range)) = ranges.get(index - 1) else {
+            return RefEdit::Keep;
+        };
+        let (top, bottom) = (
+            range.start.row.min(range.end.row),
+            range.start.row.max(range.end.row),
+        );
+        match moved(r.row, top, bottom) {
+            None => RefEdit::Invalid,
+            Some(row) if row == r.row => RefEdit::Keep,
+            Some(row) => RefEdit::Replace(FormulaRef { row, ..r.clone() }),
+        }
+    };
+    let line = |r: &LineRef| {
+        if r.axis != LineAxis::Rows || !on_sheet(r.sheet.as_deref()) {
+            return RefEdit::Keep;
+        }
+        let (top, bottom) = (r.start.min(r.end), r.start.max(r.end));
+        match (moved(r.start, top, bottom), moved(r.end, top, bottom)) {
+            (Some(start), Some(end)) if (start, end) == (r.start, r.end) => RefEdit::Keep,
+            (Some(start), Some(end)) => RefEdit::Replace(LineRef {
+                start,
+                end,
+                ..r.clone()
+            }),
+            _ => RefEdit::Invalid,
+        }
+    };
+    walk(formula, cell, line)
+}
+
+/// Rewrites sheet qualifiers naming `old` (`Old!A1`, `'Old Data'!A1:B2`)
+/// to name `new`, quoting it when needed.
+///
+/// Sheet names are compared case-insensitively, as Excel does. String
+/// literals and external references (`[1]Old!A1`) are left untouched.
+pub fn rename_sheet_refs(formula: &str, old: &str, new: &str) -> String {
+    let chars: Vec<char> = formula.chars().collect();
+    let mut out = String::with_capacity(formula.len());
+    let renamed = crate::sheet::quote_sheet_name(new);
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+
+        if c == '"' || c == '\'' {
+            // String literal or quoted sheet name; doubled quotes escape
+            let mut name = String::new();
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == c {
+                    if chars.get(i + 1) == Some(&c) {
+                        name.push(c);
+                        i += 2;
+                        continue;
+                    }
+                    break;
+                }
+                name.push(chars[i]);
+                i += 1;
+            }
+            i = (i + 1).min(chars.len());
+            if c == '\'' && chars.get(i) == Some(&'!') && name.eq_ignore_ascii_case(old) {
+                out.push_str(&renamed);
+            } else {
+                out.extend(&chars[start..i]);
+            }
+            continue;
+        }
+
+        if is_word_char(c) {
+            while i < chars.len() && is_word_char(chars[i]) {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            let external = start > 0 && chars[start - 1] == ']';
+            if chars.get(i) == Some(&'!') && !external && word.eq_ignore_ascii_case(old) {
+                out.push_str(&renamed);
+            } else {
+                out.push_str(&word);
+            }
+            continue;
+        }
+
+        out.push(c);
+        i += 1;
+    }
+
+    out
+}
+
+/// Collects the sheet names a formula qualifies references with
+/// (`Data!A1`, `'Q1 Sales'!B:B`, `Rates!TaxRate`), without quotes.
+///
+/// External references (`[1]Data!A1`) name another workbook's sheets and
+/// are skipped.
+pub fn sheet_qualifiers(formula: &str) -> Vec<String> {
+    let chars: Vec<char> = formula.chars().collect();
+    let mut sheets: Vec<String> = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+
+        if c == '"' || c == '\'' {
+            let mut name = String::new();
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == c {
+                    if chars.get(i + 1) == Some(&c) {
+                        name.push(c);
+                        i += 2;
+                        continue;
+                    }
+                    break;
+                }
+                name.push(chars[i]);
+                i += 1;
+            }
+            i = (i + 1).min(chars.len());
+            if c == '\'' && chars.get(i) == Some(&'!') && !name.starts_with('[') {
+                sheets.push(name);
+            }
+            continue;
+        }
+
+        if is_word_char(c) {
+            while i < chars.len() && is_word_char(chars[i]) {
+                i += 1;
+            }
+            let external = start > 0 && chars[start - 1] == ']';
+            if chars.get(i) == Some(&'!') && !external {
+                sheets.push(chars[start..i].iter().collect());
+            }
+            continue;
+        }
+
+        i += 1;
+    }
+
+    sheets
+}
+
+/// Collects the identifiers in a formula that may be defined names: words
+/// that are not cell references, function names, sheet qualifiers,
+/// numbers, booleans or structured reference columns.
+pub fn collect_names(formula: &str) -> Vec<String> {
+    let chars: Vec<char> = formula.chars().collect();
+    let mut names: Vec<String> = Vec::new();
+    let mut brackets = 0usize;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == '"' || c == '\'' {
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == c {
+                    if chars.get(i + 1) == Some(&c) {
+                        i += 2;
+                        continue;
+                    }
+                    break;
+                }
+                i += 1;
+            }
+            i += 1;
+            continue;
+        }
+
+        if is_word_char(c) {
+            let start = i;
+            while i < chars.len() && is_word_char(chars[i]) {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            let qualifier_or_call = matches!(chars.get(i), Some('!') | Some('('));
+            let literal = word.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+                || word.eq_ignore_ascii_case("TRUE")
+                || word.eq_ignore_ascii_case("FALSE");
+            if brackets == 0
+                && !qualifier_or_call
+                && !literal
+                && !word.contains('$')
+                && parse_ref(&word).is_none()
+            {
+                names.push(word);
+            }
+            continue;
+        }
+
+        match c {
+            '[' => brackets += 1,
+            ']' => brackets = brackets.saturating_sub(1),
+            _ => {}
+        }
+        i += 1;
+    }
+
+    names
+}
+
+/// Rewrites the external workbook index of every external reference
+/// (`[1]Sheet1!A1`, `'[2]My Sheet'!B2`, `[1]!Name`).
+///
+/// The callback receives the 1-based link index and returns the new index.
+/// Structured table references such as `Table1[[#This Row],[2019]]` are
+/// left untouched.
+pub fn map_external_links<F>(formula: &str, mut f: F) -> String
+where
+    F: FnMut(usize) -> usize,
+{
+    let chars: Vec<char> = formula.chars().collect();
+    let mut out = String::with_capacity(formula.len());
+    let mut in_string = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '"' {
+            in_string = !in_string;
+        } else if !in_string && c == '[' {
+            if let Some((index, end)) = external_index_at(&chars, i) {
+                out.push_str(&format!("[{}]", f(index)));
+                i = end;
+                continue;
+            }
+        }
+        out.push(c);
+        i += 1;
+    }
+
+    out
+}
+
+/// Returns the external workbook indices referenced by a formula.
+pub fn external_links(formula: &str) -> Vec<usize> {
+    let mut links = Vec::new();
+    map_external_links(formula, |index| {
+        if !links.contains(&index) {
+            links.push(index);
+        }
+        index
+    });
+    links
+}
+
+/// Parses `[n]` at `start` if it begins an external reference, returning
+/// the index and the position after the closing bracket.
+fn external_index_at(chars: &[char], start: usize) -> Option<(usize, usize)> {
+    let prev = start.checked_sub(1).map(|p| chars[p]);
+    if prev.is_some_and(|p| is_word_char(p) || p == '[' || p == ']') {
+        return None;
+    }
+    let digits_end = (start + 1..chars.len()).find(|&j| !chars[j].is_ascii_digit())?;
+    if digits_end == start + 1 || chars[digits_end] != ']' {
+        return None;
+    }
+    let index: usize = chars[start + 1..digits_end]
+        .iter()
+        .collect::<String>()
+        .parse()
+        .ok()?;
+
+    // An external reference continues with a sheet name (or nothing) and `!`
+    let mut k = digits_end + 1;
+    if prev == Some('\'') {
+        while k < chars.len() && chars[k] != '\'' {
+            k += 1;
+        }
+        k += 1;
+    } else {
+        while k < chars.len() && (is_word_char(chars[k]) || chars[k] == '.') {
+            k += 1;
+        }
+    }
+    (chars.get(k) == Some(&'!')).then_some((index, digits_end + 1))
+}
+
+fn offset(value: u32, delta: i64, max: u32) -> Option<u32> {
+    let shifted = value as i64 + delta;
+    if shifted >= 1 && shifted <= max as i64 {
+        Some(shifted as u32)
+    } else {
+        None
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$' || c == '.'
+}
+
+/// Parses one endpoint of a whole-row (`$5`) or whole-column (`$C`)
+/// reference into (axis, number, absolute).
+fn parse_line(word: &str) -> Option<(LineAxis, u32, bool)> {
+    let (absolute, rest) = match word.strip_prefix('$') {
+        Some(rest) => (true, rest),
+        None => (false, word),
+    };
+    if !rest.is_empty() && rest.bytes().all(|b| b.is_ascii_digit()) {
+        let row: u32 = rest.parse().ok()?;
+        return (1..=CellRef::MAX_ROW)
+            .contains(&row)
+            .then_some((LineAxis::Rows, row, absolute));
+    }
+    if (1..=3).contains(&rest.len()) && rest.bytes().all(|b| b.is_ascii_alphabetic()) {
+        let col = CellRef::col_from_letters_pub(&rest.to_ascii_uppercase())?;
+        return (1..=CellRef::MAX_COL)
+            .contains(&col)
+            .then_some((LineAxis::Columns, col, absolute));
+    }
+    None
+}
+
+/// Parses `$A$1`-style tokens into (col, row, col_absolute, row_absolute).
+fn parse_ref(word: &str) -> Option<(u32, u32, bool, bool)> {
+    let bytes = word.as_bytes();
+    let mut i = 0;
+
+    let col_absolute = bytes.first() == Some(&b'$');
+    if col_absolute {
+        i += 1;
+    }
+    let col_start = i;
+    while i < bytes.len() && bytes[i].is_ascii_alphabetic() {
+        i += 1;
+    }
+    let letters = &word[col_start..i];
+    if letters.is_empty() || letters.len() > 3 {
+        return None;
+    }
+
+    let row_absolute = bytes.get(i) == Some(&b'$');
+    if row_absolute {
+        i += 1;
+    }
+    let digits = &word[i..];
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let col = CellRef::col_from_letters_pub(&letters.to_ascii_uppercase())?;
+    let row: u32 = digits.parse().ok()?;
+    if col == 0 || col > CellRef::MAX_COL || row == 0 || row > CellRef::MAX_ROW {
+        return None;
+    }
+
+    Some((col, row, col_absolute, row_absolute))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rename_sheet_refs() {
+        assert_eq!(
+            rename_sheet_refs("SUM(q3data!A1:B2)+Other!A1", "Q3Data", "Q3 Data"),
+            "SUM('Q3 Data'!A1:B2)+Other!A1"
+        );
+        assert_eq!(
+            rename_sheet_refs("'Q3 Data'!$A$1&\"Q3 Data!\"", "Q3 Data", "Summary"),
+            "Summary!$A$1&\"Q3 Data!\""
+        );
+        assert_eq!(
+            rename_sheet_refs("[1]Q3Data!A1+Q3Data!A1", "Q3Data", "It's"),
+            "[1]Q3Data!A1+'It''s'!A1"
+        );
+    }
+
+    #[test]
+    fn test_external_links() {
+        assert_eq!(
+            external_links("[1]Sheet1!A1+'[2]My Sheet'!B2*[1]!Rate"),
+            vec![1, 2]
+        );
+        assert!(external_links("Table1[[#This Row],[2019]]+\"[1]x!\"").is_empty());
+        assert!(external_links("SUM(Data[2019])").is_empty());
+        assert_eq!(
+            map_external_links("[3]Sheet1!A1+'[2]Q 1'!B2", |i| i - 1),
+            "[2]Sheet1!A1+'[1]Q 1'!B2"
+        );
+    }
+
+    #[test]
+    fn test_collect_refs_simple() {
+        let refs = collect_refs("A1+B2*$C$3");
+        assert_eq!(refs.len(), 3);
+        assert_eq!((refs[0].col, refs[0].row), (1, 1));
+        assert_eq!((refs[1].col, refs[1].row), (2, 2));
+        assert!(refs[2].col_absolute && refs[2].row_absolute);
+    }
+
+    #[test]
+    fn test_collect_refs_skips_strings_and_functions() {
+        let refs = collect_refs(r#"IF(LOG10(A1)>1,"B2 ""C3""",D4)"#);
+        let cells: Vec<String> = refs.iter().map(|r| r.to_a1()).collect();
+        assert_eq!(cells, vec!["A1", "D4"]);
+    }
+
+    #[test]
+    fn test_collect_refs_sheet_qualified() {
+        let refs = collect_refs("SUM('My Sheet'!A1:B2)+Data!C3+D4");
+        assert_eq!(refs[0].sheet.as_deref(), Some("My Sheet"));
+        assert_eq!(refs[1].sheet.as_deref(), Some("My Sheet"));
+        assert_eq!(refs[2].sheet.as_deref(), Some("Data"));
+        assert_eq!(refs[3].sheet, None);
+    }
+
+    #[test]
+    fn test_sheet_qualifiers() {
+        assert_eq!(
+            sheet_qualifiers("SUM('Q1 Sales'!B:B)+Rates!TaxRate+[1]Data!A1+\"X!\""),
+            vec!["Q1 Sales", "Rates"]
+        );
+        assert!(sheet_qualifiers("'[2]Other'!A1+A1").is_empty());
+    }
+
+    #[test]
+    fn test_collect_names() {
+        assert_eq!(
+            collect_names("SUM(Sales)*TaxRate+A1+$B$2+1.5E3+TRUE+Data!Total+T[Qty]+\"Name\""),
+            vec!["Sales", "TaxRate", "Total", "T"]
+        );
+    }
+
+    #[test]
+    fn test_collect_ranges() {
+        let ranges = collect_ranges("SUM('My Sheet'!A1:B2)+Data!C3*$D$4:E5");
+        let found: Vec<(Option<&str>, String)> = ranges
+            .iter()
+            .map(|(sheet, range)| (sheet.as_deref(), range.to_a1()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (Some("My Sheet"), "A1:B2".to_string()),
+                (Some("Data"), "C3".to_string()),
+                (None, "D4:E5".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_complexity() {
+        let c = complexity(r#"IF(ISBLANK(A1),"(x)",SUM(OFFSET(B1,0,0,(C1+1),1))+NOW()+now())"#);
+        assert_eq!(c.nesting_depth, 3);
+        assert_eq!(c.function_count, 6);
+        assert_eq!(c.volatile_functions, vec!["OFFSET", "NOW"]);
+        assert_eq!(c.reference_count, 3);
+
+        assert_eq!(
+            complexity("A1+1"),
+            FormulaComplexity {
+                reference_count: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            complexity("_xlfn.RANDARRAY(3)").volatile_functions,
+            vec!["RANDARRAY"]
+        );
+    }
+
+    #[test]
+    fn test_add_function_prefixes() {
+        assert_eq!(
+            add_function_prefixes(r#"SORT(FILTER(A1:B9,B1:B9>0,"FILTER(x)"))"#),
+            r#"_xlfn._xlws.SORT(_xlfn._xlws.FILTER(A1:B9,B1:B9>0,"FILTER(x)"))"#
+        );
+        assert_eq!(
+            add_function_prefixes("unique('Sort Data'!A:A)+SUM(1)"),
+            "_xlfn.unique('Sort Data'!A:A)+SUM(1)"
+        );
+        assert_eq!(
+            add_function_prefixes("_xlfn.SEQUENCE(3)+Sort"),
+            "_xlfn.SEQUENCE(3)+Sort"
+        );
+    }
+
+    #[test]
+    fn test_strip_function_prefixes() {
+        assert_eq!(
+            strip_function_prefixes(r#"_xlfn._xlws.SORT(_xlfn.UNIQUE(A1:A9),"_xlfn.UNIQUE(")"#),
+            r#"SORT(UNIQUE(A1:A9),"_xlfn.UNIQUE(")"#
+        );
+        // Prefixes of functions outside the table are kept
+        assert_eq!(
+            strip_function_prefixes("_xlfn.CONCAT(A1,B1)+_xlfn.SEQUENCE"),
+            "_xlfn.CONCAT(A1,B1)+_xlfn.SEQUENCE"
+        );
+        let formula = "LET(x,FILTER(A:A,B:B),SORT(x))";
+        assert_eq!(
+            strip_function_prefixes(&add_function_prefixes(formula)),
+            formula
+        );
+    }
+
+    #[test]
+    fn test_shift_refs_relative() {
+        assert_eq!(shift_refs("A1+B2", 1, 2), "B3+C4");
+    }
+
+    #[test]
+    fn test_shift_refs_absolute() {
+        assert_eq!(shift_refs("$A1+A$1+$A$1", 1, 1), "$A2+B$1+$A$1");
+    }
+
+    #[test]
+    fn test_shift_refs_line_refs() {
+        assert_eq!(shift_refs("SUM(1:1)+A1", 0, 1), "SUM(2:2)+A2");
+        assert_eq!(shift_refs("SUM(A:B)+SUM($C:D)", 2, 5), "SUM(C:D)+SUM($C:F)");
+        assert_eq!(shift_refs("Sheet1!$1:3*2", 1, 1), "Sheet1!$1:4*2");
+        assert_eq!(shift_refs("SUM(1:1)", 0, -1), "SUM(#REF!)");
+        // Not references: times in strings, function names
+        assert_eq!(shift_refs("\"1:30\"&LOG10(2)", 1, 1), "\"1:30\"&LOG10(2)");
+    }
+
+    #[test]
+    fn test_map_line_refs() {
+        let mut seen = Vec::new();
+        map_line_refs("SUM('My Data'!A:C)+COUNT(3:$5)+A1:B2", |r| {
+            seen.push(r.clone());
+            RefEdit::Keep
+        });
+        assert_eq!(
+            seen,
+            [
+                LineRef {
+                    axis: LineAxis::Columns,
+                    start: 1,
+                    end: 3,
+                    start_absolute: false,
+                    end_absolute: false,
+                    sheet: Some("My Data".to_string()),
+                },
+                LineRef {
+                    axis: LineAxis::Rows,
+                    start: 3,
+                    end: 5,
+                    start_absolute: false,
+                    end_absolute: true,
+                    sheet: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shift_refs_off_grid() {
+        assert_eq!(shift_refs("A1*2", 0, -1), "#REF!*2");
+    }
+
+    #[test]
+    fn test_remove_rows() {
+        let removed: BTreeSet<u32> = [2, 4].into_iter().collect();
+        let local = |sheet: Option<&str>| sheet.is_none();
+        assert_eq!(remove_rows("SUM(B1:B5)", &removed, local), "SUM(B1:B3)");
+        assert_eq!(remove_rows("B5*$B$3", &removed, local), "B3*$B$2");
+        assert_eq!(remove_rows("B2+B1", &removed, local), "#REF!+B1");
+        assert_eq!(remove_rows("SUM(B2:B4)", &removed, local), "SUM(B2:B2)");
+        assert_eq!(remove_rows("SUM(B4:B4)", &removed, local), "SUM(#REF!)");
+        assert_eq!(remove_rows("Other!B5+B5", &removed, local), "Other!B5+B3");
+        // Whole rows
+        assert_eq!(remove_rows("SUM(1:5)+B3", &removed, local), "SUM(1:3)+B2");
+        assert_eq!(remove_rows("SUM($4:$4)*2", &removed, local), "SUM(#REF!)*2");
+        assert_eq!(remove_rows("SUM(A:A)", &removed, local), "SUM(A:A)");
+        let one: BTreeSet<u32> = [2].into_iter().collect();
+        assert_eq!(remove_rows("SUM(1:5)+B3", &one, local), "SUM(1:4)+B2");
+    }
+
+    #[test]
+    fn test_map_refs_keeps_untouched_text() {
+        let formula = "SUM(Sheet1!A1:A10)/COUNT(x_1)";
+        assert_eq!(map_refs(formula, |_| RefEdit::Keep), formula);
+    }
+
+    #[test]
+    fn test_parse_ref_rejects_names() {
+        assert!(parse_ref("ABCD1").is_none());
+        assert!(parse_ref("A0").is_none());
+        assert!(parse_ref("1A").is_none());
+        assert!(parse_ref("TRUE").is_none());
+    }
+}
@@ -0,0 +1,207 @@
+//! Options for turning imported data into cell values.
+//!
+//! Text read from CSV or JSON files is untrusted: a value such as
+//! `=HYPERLINK("http://evil.example", "Click")` becomes a live formula once
+//! the sheet is exported to CSV again and opened in a spreadsheet app.
+//! [`ImportOptions::sanitize_formulas`] neutralizes such values by prefixing
+//! them with a quote, the usual defence against CSV injection.
+//! [`ImportOptions::decimal`] keeps numbers as exact decimals instead of
+//! floats, and [`ImportOptions::text_columns`] and
+//! [`ImportOptions::detect_ids`] keep identifiers such as card numbers and
+//! zero-padded codes as text, which Excel would otherwise round to 15
+//! digits or strip of their leading zeros.
+
+use crate::cell::CellValue;
+use crate::decimal::Decimal;
+
+/// First characters that make spreadsheet apps treat text as a formula.
+const FORMULA_TRIGGERS: [char; 4] = ['=', '+', '-', '@'];
+
+/// How imported values are stored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportOptions {
+    /// Prefix text starting with `=`, `+`, `-` or `@` with `'`
+    pub sanitize_formulas: bool,
+    /// Store numbers as [`CellValue::Decimal`]
+    pub decimal: bool,
+    /// Columns (1-based) whose values are stored as text
+    pub text_columns: Vec<u32>,
+    /// Store values that [`looks_like_id`] as text in every column
+    pub detect_ids: bool,
+}
+
+impl ImportOptions {
+    /// Creates the default options: values are stored as read.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether text that looks like a formula is quoted.
+    pub fn sanitize_formulas(mut self, sanitize: bool) -> Self {
+        self.sanitize_formulas = sanitize;
+        self
+    }
+
+    /// Sets whether numbers are stored as exact decimals, written with
+    /// every digit.
+    pub fn decimal(mut self, decimal: bool) -> Self {
+        self.decimal = decimal;
+        self
+    }
+
+    /// Sets the columns (1-based) whose values are stored as text.
+    pub fn text_columns(mut self, columns: Vec<u32>) -> Self {
+        self.text_columns = columns;
+        self
+    }
+
+    /// Sets whether values that look like identifiers are stored as text.
+    pub fn detect_ids(mut self, detect: bool) -> Self {
+        self.detect_ids = detect;
+        self
+    }
+
+    /// Whether any values may be kept as text identifiers, which should
+    /// then get the text number format.
+    pub fn preserves_ids(&self) -> bool {
+        self.detect_ids || !self.text_columns.is_empty()
+    }
+
+    /// Reads a field in `col` as text, exactly as written, when the column
+    /// is one of [`text_columns`](Self::text_columns) or the field
+    /// [`looks_like_id`] and identifiers are detected.
+    pub fn id_field(&self, col: u32, text: &str) -> Option<CellValue> {
+        let keep = !text.is_empty()
+            && (self.text_columns.contains(&col) || (self.detect_ids && looks_like_id(text)));
+        keep.then(|| self.apply(CellValue::string(text)))
+    }
+
+    /// Reads a field of delimited text as an exact decimal, when numbers
+    /// are stored as decimals. Parsing the text itself keeps digits a float
+    /// would lose, such as those of `12345678901234567.89`.
+    pub fn decimal_field(&self, text: &str) -> Option<CellValue> {
+        self.decimal
+            .then(|| Decimal::parse(text))
+            .flatten()
+            .map(CellValue::Decimal)
+    }
+
+    /// Applies the options to an imported value. Text that looks like a
+    /// formula is quoted, and numbers become decimals; numbers such as `-5`
+    /// have already been read as numbers.
+    pub fn apply(&self, value: CellValue) -> CellValue {
+        match value {
+            CellValue::String(text) if self.sanitize_formulas => {
+                CellValue::String(sanitize_formula_text(text))
+            }
+            CellValue::Number(n) if self.decimal => {
+                Decimal::from_f64(n).map_or(value, CellValue::Decimal)
+            }
+            value => value,
+        }
+    }
+}
+
+/// Whether `text` is an identifier that would not survive being read as a
+/// number: digits only, and either more than Excel's 15 significant digits
+/// or a code with leading zeros such as `007`.
+pub fn looks_like_id(text: &str) -> bool {
+    !text.is_empty()
+        && text.bytes().all(|b| b.is_ascii_digit())
+        && (text.len() > 15 || (text.len() > 1 && text.starts_with('0')))
+}
+
+/// Prefixes `text` with `'` when it starts with a formula trigger
+/// (`=`, `+`, `-`, `@`).
+pub fn sanitize_formula_text(text: String) -> String {
+    if text.starts_with(FORMULA_TRIGGERS) {
+        format!("'{}", text)
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_formula_text() {
+        assert_eq!(sanitize_formula_text("=1+1".to_string()), "'=1+1");
+        assert_eq!(sanitize_formula_text("+cmd".to_string()), "'+cmd");
+        assert_eq!(sanitize_formula_text("-2+3".to_string()), "'-2+3");
+        assert_eq!(sanitize_formula_text("@SUM(A1)".to_string()), "'@SUM(A1)");
+        assert_eq!(sanitize_formula_text("a=b".to_string()), "a=b");
+        assert_eq!(sanitize_formula_text(String::new()), "");
+    }
+
+    #[test]
+    fn test_import_options_apply() {
+        let options = ImportOptions::new().sanitize_formulas(true);
+        assert_eq!(
+            options.apply(CellValue::string("=cmd|' /C calc'!A0")),
+            CellValue::string("'=cmd|' /C calc'!A0")
+        );
+        assert_eq!(
+            options.apply(CellValue::Number(-5.0)),
+            CellValue::Number(-5.0)
+        );
+        assert_eq!(
+            ImportOptions::new().apply(CellValue::string("=1+1")),
+            CellValue::string("=1+1")
+        );
+    }
+
+    #[test]
+    fn test_import_options_decimal() {
+        let options = ImportOptions::new().decimal(true);
+        assert_eq!(
+            options.apply(CellValue::Number(0.1)).to_display_string(),
+            "0.1"
+        );
+        assert!(matches!(
+            options.apply(CellValue::Number(0.1)),
+            CellValue::Decimal(_)
+        ));
+        assert_eq!(
+            options
+                .decimal_field("12345678901234567.89")
+                .map(|v| v.to_display_string())
+                .as_deref(),
+            Some("12345678901234567.89")
+        );
+        assert_eq!(options.decimal_field("abc"), None);
+        assert_eq!(ImportOptions::new().decimal_field("1.5"), None);
+    }
+
+    #[test]
+    fn test_looks_like_id() {
+        assert!(looks_like_id("4111111111111111"));
+        assert!(looks_like_id("00123"));
+        assert!(!looks_like_id("123456789012345"));
+        assert!(!looks_like_id("0"));
+        assert!(!looks_like_id("0.5"));
+        assert!(!looks_like_id("-0123"));
+        assert!(!looks_like_id(""));
+    }
+
+    #[test]
+    fn test_import_options_id_field() {
+        let options = ImportOptions::new().detect_ids(true);
+        assert!(options.preserves_ids());
+        assert_eq!(
+            options.id_field(1, "4111111111111111"),
+            Some(CellValue::string("4111111111111111"))
+        );
+        assert_eq!(options.id_field(1, "42"), None);
+
+        let options = ImportOptions::new()
+            .text_columns(vec![2])
+            .sanitize_formulas(true);
+        assert_eq!(options.id_field(2, "42"), Some(CellValue::string("42")));
+        assert_eq!(options.id_field(2, "=1"), Some(CellValue::string("'=1")));
+        assert_eq!(options.id_field(2, ""), None);
+        assert_eq!(options.id_field(1, "00123"), None);
+        assert!(!ImportOptions::new().preserves_ids());
+    }
+}
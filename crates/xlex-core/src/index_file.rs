@@ -0,0 +1,177 @@
+//! Index files that let large workbooks reopen without scanning.
+//!
+//! An index file sits next to a workbook as `<file>.xlexidx` and records
+//! what [`LazyWorkbook`](crate::LazyWorkbook) otherwise works out by
+//! scanning the package on every open: where each shared string starts,
+//! and each sheet's [`RowIndex`] with its extent. It is tied to the
+//! workbook by a [`fingerprint`] and ignored once the workbook changes.
+//!
+//! Index files are opt-in: [`LazyWorkbook::open_indexed`] writes one when
+//! it is missing or stale, and [`LazyWorkbook::open`] uses a valid one
+//! it finds.
+//!
+//! ```no_run
+//! use xlex_core::LazyWorkbook;
+//!
+//! // Scans the workbook and writes large.xlsx.xlexidx
+//! let first = LazyWorkbook::open_indexed("large.xlsx")?;
+//! // Reads the index instead of scanning
+//! let later = LazyWorkbook::open("large.xlsx")?;
+//! # Ok::<(), xlex_core::XlexError>(())
+//! ```
+//!
+//! [`LazyWorkbook::open_indexed`]: crate::LazyWorkbook::open_indexed
+//! [`LazyWorkbook::open`]: crate::LazyWorkbook::open
+
+use std::collections::BTreeMap;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::error::{XlexError, XlexResult};
+use crate::lazy::RowIndex;
+
+/// Extension appended to a workbook's file name to name its index file.
+pub const EXTENSION: &str = "xlexidx";
+
+/// Format version written into index files; others are ignored.
+const VERSION: u32 = 1;
+
+/// What a workbook's index file records.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexFile {
+    /// Format version
+    pub version: u32,
+    /// [`fingerprint`] of the workbook the index was built from
+    pub fingerprint: u64,
+    /// Byte offset and length of each `<si>` element in the shared strings
+    /// part, in index order
+    pub shared_strings: Vec<(u64, u32)>,
+    /// Row index and extent of each sheet, by sheet name
+    pub sheets: BTreeMap<String, RowIndex>,
+}
+
+impl IndexFile {
+    /// Creates an index for the workbook with `fingerprint`.
+    pub fn new(
+        fingerprint: u64,
+        shared_strings: Vec<(u64, u32)>,
+        sheets: BTreeMap<String, RowIndex>,
+    ) -> Self {
+        Self {
+            version: VERSION,
+            fingerprint,
+            shared_strings,
+            sheets,
+        }
+    }
+
+    /// Returns where the index file of the workbook at `workbook` goes:
+    /// `report.xlsx` is indexed in `report.xlsx.xlexidx`.
+    pub fn path_for(workbook: &Path) -> PathBuf {
+        let mut name = workbook.as_os_str().to_owned();
+        name.push(".");
+        name.push(EXTENSION);
+        PathBuf::from(name)
+    }
+
+    /// Reads the index file at `path` if it is there, readable, and was
+    /// built from a workbook with `fingerprint`.
+    pub fn load(path: &Path, fingerprint: u64) -> Option<Self> {
+        let text = std::fs::read(path).ok()?;
+        let index: Self = serde_json::from_slice(&text).ok()?;
+        (index.version == VERSION && index.fingerprint == fingerprint).then_some(index)
+    }
+
+    /// Writes the index to `path`.
+    pub fn save(&self, path: &Path) -> XlexResult<()> {
+        let json = serde_json::to_vec(self).map_err(|e| XlexError::OperationFailed {
+            message: e.to_string(),
+        })?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+/// Fingerprints a workbook's bytes for matching it to its index file.
+///
+/// The FNV-1a hash covers the file's length and the name, CRC-32 and
+/// sizes the zip directory records for every part, so any change to a
+/// part's content changes it, while computing it reads only the directory
+/// rather than hashing the whole file.
+pub fn fingerprint(data: &[u8]) -> XlexResult<u64> {
+    let mut archive = ZipArchive::new(Cursor::new(data))?;
+    let mut hash = Fnv1a::default();
+    hash.write(&(data.len() as u64).to_le_bytes());
+    for i in 0..archive.len() {
+        let file = archive.by_index_raw(i)?;
+        hash.write(file.name().as_bytes());
+        hash.write(&file.crc32().to_le_bytes());
+        hash.write(&file.size().to_le_bytes());
+        hash.write(&file.compressed_size().to_le_bytes());
+    }
+    Ok(hash.0)
+}
+
+/// 64-bit FNV-1a, which unlike the standard library's hasher is stable
+/// across Rust releases.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CellRef, CellValue, Workbook};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_fnv1a() {
+        let mut hash = Fnv1a::default();
+        hash.write(b"a");
+        assert_eq!(hash.0, 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn test_index_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("large.xlsx");
+        let mut wb = Workbook::with_sheets(&["Data"]);
+        wb.set_cell("Data", CellRef::new(1, 1), CellValue::string("a"))
+            .unwrap();
+        wb.save_as(&path).unwrap();
+
+        let data = std::fs::read(&path).unwrap();
+        let print = fingerprint(&data).unwrap();
+        assert_eq!(print, fingerprint(&data).unwrap());
+        assert!(fingerprint(b"not a zip").is_err());
+
+        let index_path = IndexFile::path_for(&path);
+        assert_eq!(index_path, dir.path().join("large.xlsx.xlexidx"));
+        assert!(IndexFile::load(&index_path, print).is_none());
+
+        let index = IndexFile::new(print, vec![(0, 10)], BTreeMap::new());
+        index.save(&index_path).unwrap();
+        assert_eq!(IndexFile::load(&index_path, print), Some(index));
+        assert!(IndexFile::load(&index_path, print ^ 1).is_none());
+
+        wb.set_cell("Data", CellRef::new(1, 1), CellValue::string("b"))
+            .unwrap();
+        wb.save_as(&path).unwrap();
+        assert_ne!(fingerprint(&std::fs::read(&path).unwrap()).unwrap(), print);
+    }
+}
@@ -0,0 +1,605 @@
+//! Hash joins of two row streams.
+//!
+//! A [`JoinTable`] collects the right-hand rows, keyed by one column, as a
+//! [`RowSink`]; [`JoinTable::join`] then streams the left-hand rows past
+//! it. Right rows are kept in memory up to [`JoinOptions::spill_after`].
+//! Beyond that they are split by key into partition files on disk, the left
+//! rows are split the same way, and each pair of partitions is joined in
+//! turn, so memory use is bounded by the largest partition. Either way the
+//! output keeps the order of the left rows.
+//!
+//! The first row of each side is its header. The output header is the left
+//! header followed by the right header without its key column, and each
+//! joined row likewise gets the right row's cells other than the key. Left
+//! rows without a match in a left join get blanks for those cells. Keys
+//! match on their displayed text; blank keys never match.
+//!
+//! ```no_run
+//! use xlex_core::join::{JoinOptions, JoinTable, JoinType};
+//! use xlex_core::writer::StreamingSheetWriter;
+//! use xlex_core::LazyWorkbook;
+//!
+//! let orders = LazyWorkbook::open("orders.xlsx")?;
+//! let prices = LazyWorkbook::open("prices.xlsx")?;
+//!
+//! // Column B of Prices holds the SKU
+//! let options = JoinOptions {
+//!     join_type: JoinType::Left,
+//!     ..JoinOptions::default()
+//! };
+//! let mut table = JoinTable::new(2, options);
+//! prices.pipeline("Prices").write_to(&mut table)?;
+//!
+//! let mut out = StreamingSheetWriter::create("merged.xlsx", "Orders")?;
+//! table.join(orders.pipeline("Orders"), 1, &mut out)?;
+//! out.finish()?;
+//! # Ok::<(), xlex_core::XlexError>(())
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BinaryHeap, HashMap};
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, BufWriter, Lines, Write};
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::cell::CellValue;
+use crate::error::{XlexError, XlexResult};
+use crate::pipeline::{Pipeline, RowSink};
+
+/// Which left rows a join keeps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JoinType {
+    /// Only rows with a matching right row
+    #[default]
+    Inner,
+    /// Every row, with blanks where no right row matches
+    Left,
+}
+
+/// Settings for a [`JoinTable`].
+#[derive(Debug, Clone)]
+pub struct JoinOptions {
+    pub join_type: JoinType,
+    /// Right rows kept in memory before spilling to disk
+    pub spill_after: usize,
+    /// Number of partition files per side when spilling
+    pub partitions: usize,
+    /// Directory for partition files (default: the system temp directory)
+    pub spill_dir: Option<PathBuf>,
+}
+
+impl Default for JoinOptions {
+    fn default() -> Self {
+        Self {
+            join_type: JoinType::Inner,
+            spill_after: 1_000_000,
+            partitions: 32,
+            spill_dir: None,
+        }
+    }
+}
+
+/// Row counts from a join, not counting header rows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JoinStats {
+    /// Left rows read
+    pub left_rows: u64,
+    /// Right rows read
+    pub right_rows: u64,
+    /// Joined rows written
+    pub rows_written: u64,
+    /// Whether the right rows outgrew memory and were joined on disk
+    pub spilled: bool,
+}
+
+/// The right-hand side of a hash join, keyed by one column.
+pub struct JoinTable {
+    key_index: usize,
+    options: JoinOptions,
+    header: Option<Vec<CellValue>>,
+    rows: HashMap<String, Vec<Vec<CellValue>>>,
+    in_memory: usize,
+    right_rows: u64,
+    spill: Option<Spill>,
+}
+
+impl JoinTable {
+    /// Starts an empty table keyed by 1-based column `key_col`.
+    pub fn new(key_col: u32, options: JoinOptions) -> Self {
+        Self {
+            key_index: key_col.saturating_sub(1) as usize,
+            options,
+            header: None,
+            rows: HashMap::new(),
+            in_memory: 0,
+            right_rows: 0,
+            spill: None,
+        }
+    }
+
+    /// Returns whether the rows added so far have spilled to disk.
+    pub fn is_spilled(&self) -> bool {
+        self.spill.is_some()
+    }
+
+    /// Returns the right header without its key column, as it appears after
+    /// the left header in the output.
+    pub fn right_header(&self) -> Vec<CellValue> {
+        self.header
+            .as_ref()
+            .map(|header| self.without_key(header))
+            .unwrap_or_default()
+    }
+
+    /// Joins a single left row against the table, for callers that see
+    /// left rows one at a time. `values` should already be padded to the
+    /// left header's width. Only in-memory tables can be probed this way.
+    pub fn probe(&self, key: &str, values: Vec<CellValue>) -> XlexResult<Vec<Vec<CellValue>>> {
+        if self.spill.is_some() {
+            return Err(XlexError::OperationFailed {
+                message: "cannot probe a join table that spilled to disk".to_string(),
+            });
+        }
+        let right_width = self.right_header().len();
+        Ok(matches(
+            &self.rows,
+            key,
+            values,
+            self.options.join_type,
+            right_width,
+        ))
+    }
+
+    /// Returns the cells of a right row other than the key, padded to the
+    /// header's width.
+    fn without_key(&self, values: &[CellValue]) -> Vec<CellValue> {
+        let width = self.header.as_ref().map_or(0, Vec::len).max(values.len());
+        let mut row = values.to_vec();
+        row.resize(width.max(self.key_index + 1), CellValue::Empty);
+        row.remove(self.key_index);
+        row
+    }
+
+    fn start_spill(&mut self) -> XlexResult<()> {
+        let mut spill = Spill::create(&self.options)?;
+        for (key, rows) in self.rows.drain() {
+            for row in rows {
+                spill.right.write(&key, &(&key, &row))?;
+            }
+        }
+        self.in_memory = 0;
+        self.spill = Some(spill);
+        Ok(())
+    }
+
+    /// Streams `left` past the table and writes the joined rows to `sink`,
+    /// matching the left rows' 1-based column `left_key_col`.
+    pub fn join(
+        mut self,
+        left: Pipeline<'_>,
+        left_key_col: u32,
+        sink: &mut impl RowSink,
+    ) -> XlexResult<JoinStats> {
+        let left_key = left_key_col.saturating_sub(1) as usize;
+        let right_header = self.right_header();
+        let right_width = right_header.len();
+        let join_type = self.options.join_type;
+        let mut stats = JoinStats {
+            right_rows: self.right_rows,
+            spilled: self.spill.is_some(),
+            ..JoinStats::default()
+        };
+
+        let Some(spill) = self.spill.take() else {
+            let mut probe = Probe {
+                rows: &self.rows,
+                left_key,
+                join_type,
+                right_header,
+                left_width: None,
+                sink,
+                written: 0,
+            };
+            let read = left.write_to(&mut probe)?;
+            stats.left_rows = read.rows_written.saturating_sub(1);
+            stats.rows_written = probe.written;
+            return Ok(stats);
+        };
+
+        // Split the left rows like the right ones, numbered to restore
+        // their order afterwards
+        let mut split = SplitLeft {
+            left_key,
+            header: None,
+            seq: 0,
+            partitions: Partitions::create(&spill.dir.0, "left", self.options.partitions)?,
+        };
+        left.write_to(&mut split)?;
+        stats.left_rows = split.seq;
+        let left_header = split.header.take().unwrap_or_default();
+        let left_width = left_header.len();
+        let mut header = left_header;
+        header.extend(right_header);
+        sink.write_row(&header)?;
+
+        let left_paths = split.partitions.finish()?;
+        let right_paths = spill.right.finish()?;
+        let mut joined = Partitions::create(&spill.dir.0, "joined", self.options.partitions)?;
+        for (index, (left_path, right_path)) in left_paths.iter().zip(&right_paths).enumerate() {
+            let mut rows: HashMap<String, Vec<Vec<CellValue>>> = HashMap::new();
+            for line in read_lines::<(String, Vec<CellValue>)>(right_path)? {
+                let (key, row) = line?;
+                rows.entry(key).or_default().push(row);
+            }
+            for line in read_lines::<(u64, String, Vec<CellValue>)>(left_path)? {
+                let (seq, key, mut values) = line?;
+                values.resize(left_width.max(values.len()), CellValue::Empty);
+                for row in matches(&rows, &key, values, join_type, right_width) {
+                    joined.write_to(index, &(seq, row))?;
+                }
+            }
+        }
+
+        // Merge the joined partitions back into left-row order
+        let mut readers = Vec::new();
+        let mut heap = BinaryHeap::new();
+        for (index, path) in joined.finish()?.iter().enumerate() {
+            let mut lines = read_lines::<(u64, Vec<CellValue>)>(path)?;
+            if let Some(line) = lines.next() {
+                let (seq, row) = line?;
+                heap.push(Reverse(Pending { seq, index, row }));
+            }
+            readers.push(lines);
+        }
+        while let Some(Reverse(Pending { index, row, .. })) = heap.pop() {
+            sink.write_row(&row)?;
+            stats.rows_written += 1;
+            if let Some(line) = readers[index].next() {
+                let (seq, row) = line?;
+                heap.push(Reverse(Pending { seq, index, row }));
+            }
+        }
+        spill.dir.remove();
+        Ok(stats)
+    }
+}
+
+impl RowSink for JoinTable {
+    fn write_row(&mut self, values: &[CellValue]) -> XlexResult<()> {
+        if self.header.is_none() {
+            self.header = Some(values.to_vec());
+            return Ok(());
+        }
+        self.right_rows += 1;
+        let key = key_of(values, self.key_index);
+        if key.is_empty() {
+            return Ok(());
+        }
+        let row = self.without_key(values);
+        match self.spill.as_mut() {
+            Some(spill) => spill.right.write(&key, &(&key, &row)),
+            None => {
+                self.rows.entry(key).or_default().push(row);
+                self.in_memory += 1;
+                if self.in_memory > self.options.spill_after {
+                    self.start_spill()?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn key_of(values: &[CellValue], index: usize) -> String {
+    values
+        .get(index)
+        .map(CellValue::to_display_string)
+        .unwrap_or_default()
+}
+
+/// Joins one left row (already padded) with its matches. An unmatched row
+/// kept by a left join gets `right_width` blanks, so every row has the
+/// header's width.
+fn matches(
+    rows: &HashMap<String, Vec<Vec<CellValue>>>,
+    key: &str,
+    mut values: Vec<CellValue>,
+    join_type: JoinType,
+    right_width: usize,
+) -> Vec<Vec<CellValue>> {
+    let found = if key.is_empty() { None } else { rows.get(key) };
+    match found {
+        Some(found) => found
+            .iter()
+            .map(|right| {
+                let mut row = values.clone();
+                row.extend(right.iter().cloned());
+                row
+            })
+            .collect(),
+        None if join_type == JoinType::Left => {
+            values.resize(values.len() + right_width, CellValue::Empty);
+            vec![values]
+        }
+        None => Vec::new(),
+    }
+}
+
+/// Joins left rows against the in-memory table as they arrive.
+struct Probe<'t, S> {
+    rows: &'t HashMap<String, Vec<Vec<CellValue>>>,
+    left_key: usize,
+    join_type: JoinType,
+    right_header: Vec<CellValue>,
+    left_width: Option<usize>,
+    sink: &'t mut S,
+    written: u64,
+}
+
+impl<S: RowSink> RowSink for Probe<'_, S> {
+    fn write_row(&mut self, values: &[CellValue]) -> XlexResult<()> {
+        let Some(width) = self.left_width else {
+            self.left_width = Some(values.len());
+            let mut header = values.to_vec();
+            header.extend(self.right_header.iter().cloned());
+            return self.sink.write_row(&header);
+        };
+        let key = key_of(values, self.left_key);
+        let mut padded = values.to_vec();
+        padded.resize(width.max(values.len()), CellValue::Empty);
+        let right_width = self.right_header.len();
+        for row in matches(self.rows, &key, padded, self.join_type, right_width) {
+            self.sink.write_row(&row)?;
+            self.written += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Splits numbered left rows into partitions by key.
+struct SplitLeft {
+    left_key: usize,
+    header: Option<Vec<CellValue>>,
+    seq: u64,
+    partitions: Partitions,
+}
+
+impl RowSink for SplitLeft {
+    fn write_row(&mut self, values: &[CellValue]) -> XlexResult<()> {
+        if self.header.is_none() {
+            self.header = Some(values.to_vec());
+            return Ok(());
+        }
+        self.seq += 1;
+        let key = key_of(values, self.left_key);
+        self.partitions.write(&key, &(self.seq, &key, values))
+    }
+}
+
+/// A joined row waiting to be merged, ordered by left row.
+struct Pending {
+    seq: u64,
+    index: usize,
+    row: Vec<CellValue>,
+}
+
+impl PartialEq for Pending {
+    fn eq(&self, other: &Self) -> bool {
+        (self.seq, self.index) == (other.seq, other.index)
+    }
+}
+
+impl Eq for Pending {}
+
+impl PartialOrd for Pending {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pending {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.seq, self.index).cmp(&(other.seq, other.index))
+    }
+}
+
+/// The spill directory and the right rows written to it.
+struct Spill {
+    dir: SpillDir,
+    right: Partitions,
+}
+
+impl Spill {
+    fn create(options: &JoinOptions) -> XlexResult<Self> {
+        let parent = options.spill_dir.clone().unwrap_or_else(std::env::temp_dir);
+        let dir = parent.join(format!("xlex-join-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir)?;
+        let dir = SpillDir(dir);
+        let right = Partitions::create(&dir.0, "right", options.partitions)?;
+        Ok(Self { dir, right })
+    }
+}
+
+/// Removes the spill directory when the join ends, however it ends.
+struct SpillDir(PathBuf);
+
+impl SpillDir {
+    fn remove(&self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+impl Drop for SpillDir {
+    fn drop(&mut self) {
+        self.remove();
+    }
+}
+
+/// Partition files of JSON lines, one per hash bucket.
+struct Partitions {
+    paths: Vec<PathBuf>,
+    writers: Vec<BufWriter<File>>,
+}
+
+impl Partitions {
+    fn create(dir: &Path, name: &str, count: usize) -> XlexResult<Self> {
+        let mut paths = Vec::new();
+        let mut writers = Vec::new();
+        for index in 0..count.max(1) {
+            let path = dir.join(format!("{}-{}.jsonl", name, index));
+            writers.push(BufWriter::new(File::create(&path)?));
+            paths.push(path);
+        }
+        Ok(Self { paths, writers })
+    }
+
+    /// Appends `line` to the partition of `key`.
+    fn write(&mut self, key: &str, line: &impl Serialize) -> XlexResult<()> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        let index = (hasher.finish() % self.writers.len() as u64) as usize;
+        self.write_to(index, line)
+    }
+
+    fn write_to(&mut self, index: usize, line: &impl Serialize) -> XlexResult<()> {
+        let writer = &mut self.writers[index];
+        serde_json::to_writer(&mut *writer, line).map_err(spill_error)?;
+        writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// Flushes the files and returns their paths.
+    fn finish(self) -> XlexResult<Vec<PathBuf>> {
+        for mut writer in self.writers {
+            writer.flush()?;
+        }
+        Ok(self.paths)
+    }
+}
+
+fn spill_error(e: serde_json::Error) -> XlexError {
+    XlexError::OperationFailed {
+        message: format!("join spill file: {}", e),
+    }
+}
+
+fn read_lines<T: DeserializeOwned>(path: &Path) -> XlexResult<impl Iterator<Item = XlexResult<T>>> {
+    let lines: Lines<BufReader<File>> = BufReader::new(File::open(path)?).lines();
+    Ok(lines.map(|line| serde_json::from_str(&line?).map_err(spill_error)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::Record;
+
+    fn records(rows: Vec<Vec<CellValue>>) -> Pipeline<'static> {
+        Pipeline::from_records(
+            rows.into_iter()
+                .zip(1..)
+                .map(|(values, row)| Ok(Record { row, values })),
+        )
+    }
+
+    fn text(values: &[&str]) -> Vec<CellValue> {
+        values
+            .iter()
+            .map(|v| match v.parse() {
+                Ok(n) => CellValue::Number(n),
+                Err(_) if v.is_empty() => CellValue::Empty,
+                Err(_) => CellValue::string(*v),
+            })
+            .collect()
+    }
+
+    fn run(join_type: JoinType, spill_after: usize) -> (Vec<Vec<CellValue>>, JoinStats) {
+        let dir = tempfile::TempDir::new().unwrap();
+        let options = JoinOptions {
+            join_type,
+            spill_after,
+            partitions: 4,
+            spill_dir: Some(dir.path().to_path_buf()),
+        };
+        let mut table = JoinTable::new(2, options);
+        let right = vec![
+            text(&["price", "sku", "name"]),
+            text(&["4.5", "A1", "apple"]),
+            text(&["2", "B2", "bean"]),
+            text(&["3", "A1", "apricot"]),
+            text(&["9", "", "nothing"]),
+            text(&["7", "C3"]),
+        ];
+        records(right).write_to(&mut table).unwrap();
+        let left = records(vec![
+            text(&["order", "sku"]),
+            text(&["1", "A1"]),
+            text(&["2", "Z9"]),
+            text(&["3", "C3"]),
+            text(&["4"]),
+            text(&["5", "B2"]),
+        ]);
+        let mut out = Vec::new();
+        let stats = table.join(left, 2, &mut out).unwrap();
+        // Spill files are gone
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+        (out, stats)
+    }
+
+    #[test]
+    fn test_join_in_memory_and_spilled() {
+        for spill_after in [100, 1] {
+            let (rows, stats) = run(JoinType::Inner, spill_after);
+            assert_eq!(stats.spilled, spill_after == 1);
+            assert_eq!(stats.left_rows, 5);
+            assert_eq!(stats.right_rows, 5);
+            assert_eq!(stats.rows_written, 4);
+            assert_eq!(
+                rows,
+                vec![
+                    text(&["order", "sku", "price", "name"]),
+                    text(&["1", "A1", "4.5", "apple"]),
+                    text(&["1", "A1", "3", "apricot"]),
+                    text(&["3", "C3", "7", ""]),
+                    text(&["5", "B2", "2", "bean"]),
+                ]
+            );
+
+            let (rows, stats) = run(JoinType::Left, spill_after);
+            assert_eq!(stats.rows_written, 6);
+            let orders: Vec<_> = rows[1..].iter().map(|r| r[0].clone()).collect();
+            assert_eq!(orders, text(&["1", "1", "2", "3", "4", "5"]));
+            assert_eq!(rows[3], text(&["2", "Z9", "", ""]));
+            assert_eq!(rows[5], text(&["4", "", "", ""]));
+            assert!(rows.iter().all(|row| row.len() == 4));
+        }
+    }
+
+    #[test]
+    fn test_probe() {
+        let options = JoinOptions {
+            join_type: JoinType::Left,
+            ..JoinOptions::default()
+        };
+        let mut table = JoinTable::new(1, options);
+        for row in [
+            text(&["sku", "price", "name"]),
+            text(&["A1", "4.5", "apple"]),
+        ] {
+            table.write_row(&row).unwrap();
+        }
+        assert_eq!(table.right_header(), text(&["price", "name"]));
+        assert_eq!(
+            table.probe("A1", text(&["1", "A1"])).unwrap(),
+            vec![text(&["1", "A1", "4.5", "apple"])]
+        );
+        assert_eq!(
+            table.probe("Z9", text(&["2", "Z9"])).unwrap(),
+            vec![text(&["2", "Z9", "", ""])]
+        );
+    }
+}
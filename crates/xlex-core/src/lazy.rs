@@ -4,19 +4,26 @@
 //! until data is actually needed, enabling efficient handling of large files.
 
 use std::collections::HashMap;
-use std::io::{BufReader, Cursor, Read, Seek};
+use std::io::{self, BufReader, Cursor, Read, Seek};
+use std::ops::ControlFlow;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 
 use quick_xml::events::Event;
 use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
 use zip::ZipArchive;
 
 use crate::cell::{CellError, CellRef, CellValue};
-use crate::error::{XlexError, XlexResult};
-use crate::parser::{validate_xlsx_structure, LazySharedStrings};
+use crate::embed::{EmbeddedFile, EmbeddedObject};
+use crate::error::{ErrorContext, XlexError, XlexResult};
+use crate::index_file::{fingerprint, IndexFile};
+use crate::parser::{resolve_part_path, validate_xlsx_structure, LazySharedStrings, StylesParser};
+use crate::pool::ReaderPool;
+use crate::range::Range;
 use crate::reader::WorkbookReader;
 use crate::sheet::SheetInfo;
+use crate::style::StyleRegistry;
 
 /// A lazy workbook that only parses sheets on demand.
 ///
@@ -46,6 +53,100 @@ pub struct LazyWorkbook {
     sheet_names: Vec<String>,
     /// Shared strings (lazy loaded, wrapped in Mutex for interior mutability)
     shared_strings: Arc<Mutex<LazySharedStrings>>,
+    /// Row offset indexes built so far, by sheet name, shared with forks
+    row_indexes: Arc<Mutex<HashMap<String, Arc<RowIndex>>>>,
+}
+
+/// Rows between [`RowIndex`] entries.
+const ROW_INDEX_STRIDE: usize = 256;
+
+/// A dangling entry in the package's relationship graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageOrphan {
+    /// A relationship whose target part is missing
+    Relationship {
+        /// Part owning the relationship (empty for the package root)
+        source: String,
+        /// Relationship ID
+        id: String,
+        /// Missing target part
+        target: String,
+    },
+    /// A part no relationship points to
+    Part {
+        /// Part path
+        name: String,
+    },
+}
+
+/// Kind of personal or identifying metadata stored in a package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataKind {
+    /// Document author (`dc:creator`)
+    Author,
+    /// Last person to save the file
+    LastModifiedBy,
+    /// Manager (extended properties)
+    Manager,
+    /// Company (extended properties)
+    Company,
+    /// Absolute path the file was last saved to
+    AbsolutePath,
+    /// Printer settings part (holds the printer name and driver)
+    PrinterSettings,
+    /// Comment author
+    CommentAuthor,
+    /// Hidden defined name
+    HiddenName,
+    /// Revision number, document ID or shared-workbook revision log
+    Revision,
+    /// Custom document property
+    CustomProperty,
+}
+
+impl MetadataKind {
+    /// Returns the kind's name as used in reports (`author`, `hiddenName`, ...).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Author => "author",
+            Self::LastModifiedBy => "lastModifiedBy",
+            Self::Manager => "manager",
+            Self::Company => "company",
+            Self::AbsolutePath => "absolutePath",
+            Self::PrinterSettings => "printerSettings",
+            Self::CommentAuthor => "commentAuthor",
+            Self::HiddenName => "hiddenName",
+            Self::Revision => "revision",
+            Self::CustomProperty => "customProperty",
+        }
+    }
+}
+
+impl std::fmt::Display for MetadataKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Personal metadata found in a package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersonalMetadata {
+    /// Kind of metadata
+    pub kind: MetadataKind,
+    /// Part it was found in
+    pub part: String,
+    /// The value (a name, path, part name or property name)
+    pub value: String,
+}
+
+/// Formula and style of a cell, read by
+/// [`LazyWorkbook::read_cell_details`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CellDetails {
+    /// Style ID (`None` for the default style)
+    pub style_id: Option<u32>,
+    /// Formula text without the leading `=`
+    pub formula: Option<String>,
 }
 
 /// A row from a sheet being streamed.
@@ -57,12 +158,62 @@ pub struct StreamRow {
     pub cells: Vec<(CellRef, CellValue)>,
 }
 
+/// One column of a sheet being streamed.
+#[derive(Debug, Clone, Default)]
+pub struct StreamColumn {
+    /// Non-empty values with their 1-based row numbers, top to bottom
+    pub values: Vec<(u32, CellValue)>,
+    /// Last row number in the sheet data, whether or not it reaches the column
+    pub last_row: u32,
+}
+
+/// Where rows start in a sheet's XML, so a window of rows can be read
+/// without parsing the rows before it, and the sheet's extent.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RowIndex {
+    /// Row number and byte offset in the uncompressed sheet XML of the
+    /// first row and every 256th row after it, in order
+    pub entries: Vec<(u32, u64)>,
+    /// Number of the last row in the sheet data, 0 for an empty sheet
+    pub last_row: u32,
+    /// Smallest range holding every cell in the sheet data, `None` for an
+    /// empty sheet
+    pub dimension: Option<Range>,
+}
+
+impl RowIndex {
+    /// Returns the offset of the last indexed row at or before `row`, or 0
+    /// to start from the top.
+    pub fn offset_before(&self, row: u32) -> u64 {
+        let after = self.entries.partition_point(|&(r, _)| r <= row);
+        after.checked_sub(1).map_or(0, |i| self.entries[i].1)
+    }
+}
+
 impl LazyWorkbook {
     /// Opens a workbook lazily from a file path.
     ///
     /// This is very fast as it only reads metadata, not sheet contents.
+    /// A valid [index file](crate::index_file) next to the workbook is
+    /// used in place of scanning shared strings and sheets.
     pub fn open(path: impl AsRef<Path>) -> XlexResult<Self> {
+        Self::open_with_index(path.as_ref()).map(|(workbook, _)| workbook)
+    }
+
+    /// Opens a workbook like [`open`](Self::open), writing its index file
+    /// when it is missing or stale so that later opens skip the scans.
+    pub fn open_indexed(path: impl AsRef<Path>) -> XlexResult<Self> {
         let path = path.as_ref();
+        let (workbook, indexed) = Self::open_with_index(path)?;
+        if !indexed {
+            workbook.write_index(path)?;
+        }
+        Ok(workbook)
+    }
+
+    /// Opens a workbook, reporting whether a valid index file was used.
+    fn open_with_index(path: &Path) -> XlexResult<(Self, bool)> {
+        let _span = tracing::info_span!("lazy_open", path = %path.display()).entered();
 
         // Check extension
         if path.extension().and_then(|e| e.to_str()) != Some("xlsx") {
@@ -72,11 +223,46 @@ impl LazyWorkbook {
         }
 
         let reader = WorkbookReader::open(path)?;
-        Self::from_bytes(reader.as_bytes().to_vec())
+        let data = reader.as_bytes().to_vec();
+        let index_path = IndexFile::path_for(path);
+        let index = if index_path.exists() {
+            let _span = tracing::info_span!("load_index_file").entered();
+            IndexFile::load(&index_path, fingerprint(&data)?)
+        } else {
+            None
+        };
+        let indexed = index.is_some();
+        Ok((Self::from_bytes_with_index(data, index)?, indexed))
+    }
+
+    /// Builds the row index of every sheet and writes them, with the
+    /// shared strings index, to the index file of the workbook at `path`,
+    /// which must hold this workbook's data. Returns the index file's path.
+    pub fn write_index(&self, path: impl AsRef<Path>) -> XlexResult<std::path::PathBuf> {
+        let sheets = self
+            .sheet_names
+            .iter()
+            .map(|name| Ok((name.clone(), RowIndex::clone(&*self.row_index(name)?))))
+            .collect::<XlexResult<_>>()?;
+        let shared_strings = self
+            .shared_strings
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .offsets();
+        let index = IndexFile::new(fingerprint(&self.data)?, shared_strings, sheets);
+        let index_path = IndexFile::path_for(path.as_ref());
+        index.save(&index_path)?;
+        Ok(index_path)
     }
 
     /// Creates a lazy workbook from raw bytes.
     pub fn from_bytes(data: Vec<u8>) -> XlexResult<Self> {
+        Self::from_bytes_with_index(data, None)
+    }
+
+    /// Creates a lazy workbook from raw bytes, taking string and row
+    /// positions from `index` instead of scanning for them.
+    fn from_bytes_with_index(data: Vec<u8>, index: Option<IndexFile>) -> XlexResult<Self> {
         let data = Arc::new(data);
 
         // Quick validation
@@ -88,17 +274,23 @@ impl LazyWorkbook {
 
         // Parse shared strings lazily
         let shared_strings = {
+            let _span = tracing::info_span!("load_shared_strings").entered();
             let cursor = Cursor::new(data.as_ref());
             let mut archive = ZipArchive::new(cursor)?;
 
             let result = if let Ok(mut file) = archive.by_name("xl/sharedStrings.xml") {
                 let mut ss_data = Vec::new();
-                file.read_to_end(&mut ss_data)
-                    .map_err(|e| XlexError::IoError {
-                        message: e.to_string(),
-                        source: Some(e),
-                    })?;
-                Arc::new(Mutex::new(LazySharedStrings::from_bytes_default(ss_data)?))
+                file.read_to_end(&mut ss_data).map_err(|e| {
+                    XlexError::from(e)
+                        .with_context(ErrorContext::default().part("xl/sharedStrings.xml"))
+                })?;
+                let strings = match &index {
+                    Some(index) => {
+                        LazySharedStrings::from_bytes_with_offsets(ss_data, &index.shared_strings)
+                    }
+                    None => LazySharedStrings::from_bytes_default(ss_data)?,
+                };
+                Arc::new(Mutex::new(strings))
             } else {
                 Arc::new(Mutex::new(LazySharedStrings::default()))
             };
@@ -117,9 +309,43 @@ impl LazyWorkbook {
             sheets,
             sheet_names,
             shared_strings,
+            row_indexes: Arc::new(Mutex::new(
+                index
+                    .map(|index| {
+                        index
+                            .sheets
+                            .into_iter()
+                            .map(|(name, rows)| (name, Arc::new(rows)))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            )),
         })
     }
 
+    /// Returns `n` read handles over this workbook's data for use from
+    /// several threads; see [`ReaderPool`].
+    pub fn reader_pool(&self, n: usize) -> ReaderPool {
+        ReaderPool::new(self, n)
+    }
+
+    /// Another handle over the same data, with its own shared strings
+    /// cache and lock.
+    pub(crate) fn fork(&self) -> Self {
+        let shared_strings = self
+            .shared_strings
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .fork();
+        Self {
+            data: Arc::clone(&self.data),
+            sheets: self.sheets.clone(),
+            sheet_names: self.sheet_names.clone(),
+            shared_strings: Arc::new(Mutex::new(shared_strings)),
+            row_indexes: Arc::clone(&self.row_indexes),
+        }
+    }
+
     /// Returns the list of sheet names.
     pub fn sheet_names(&self) -> &[String] {
         &self.sheet_names
@@ -140,6 +366,7 @@ impl LazyWorkbook {
     /// This is the primary method for reading sheet data in streaming fashion.
     /// Each row is parsed on demand from the underlying XML.
     pub fn stream_rows(&self, sheet_name: &str) -> XlexResult<Vec<StreamRow>> {
+        let _span = tracing::info_span!("stream_rows", sheet = sheet_name).entered();
         let (_index, _info, zip_path) =
             self.sheets
                 .get(sheet_name)
@@ -152,6 +379,186 @@ impl LazyWorkbook {
         let file = archive.by_name(zip_path)?;
 
         self.parse_rows_from_sheet(BufReader::new(file))
+            .map_err(|e| e.with_context(ErrorContext::default().part(zip_path).sheet(sheet_name)))
+    }
+
+    /// Streams the rows of a sheet through `f` one at a time.
+    ///
+    /// Unlike [`stream_rows`](Self::stream_rows), rows are never collected,
+    /// so memory use doesn't grow with the sheet. An error from `f` stops
+    /// the scan and is returned.
+    pub fn for_each_row(
+        &self,
+        sheet_name: &str,
+        mut f: impl FnMut(StreamRow) -> XlexResult<()>,
+    ) -> XlexResult<()> {
+        let _span = tracing::info_span!("for_each_row", sheet = sheet_name).entered();
+        let (_index, _info, zip_path) =
+            self.sheets
+                .get(sheet_name)
+                .ok_or_else(|| XlexError::SheetNotFound {
+                    name: sheet_name.to_string(),
+                })?;
+
+        let cursor = Cursor::new(self.data.as_ref().as_slice());
+        let mut archive = ZipArchive::new(cursor)?;
+        let file = archive.by_name(zip_path)?;
+
+        // Errors from `f` are returned as they are, without the location in
+        // this sheet that parse errors get
+        let mut visit_error = None;
+        let parsed = self.visit_rows_from_sheet(BufReader::new(file), |row| match f(row) {
+            Ok(()) => ControlFlow::Continue(()),
+            Err(e) => {
+                visit_error = Some(e);
+                ControlFlow::Break(())
+            }
+        });
+        match visit_error {
+            Some(e) => Err(e),
+            None => parsed.map_err(|e| {
+                e.with_context(ErrorContext::default().part(zip_path).sheet(sheet_name))
+            }),
+        }
+    }
+
+    /// Reads the rows of a sheet that fall in `range`, keeping only the
+    /// cells in its columns.
+    ///
+    /// Parsing starts from the nearest indexed row at or before the
+    /// range's first row and stops after its last, so a window deep in a
+    /// large sheet costs about as much as one near the top. The sheet's
+    /// [`RowIndex`] is built on the first call and reused after that by
+    /// this handle and its [`ReaderPool`] siblings.
+    pub fn stream_rows_in(&self, sheet_name: &str, range: &Range) -> XlexResult<Vec<StreamRow>> {
+        let mut rows = Vec::new();
+        self.for_each_row_in(sheet_name, range, |row| {
+            rows.push(row);
+            Ok(())
+        })?;
+        Ok(rows)
+    }
+
+    /// Streams the rows of a sheet that fall in `range` through `f`, like
+    /// [`stream_rows_in`](Self::stream_rows_in) without collecting them.
+    /// An error from `f` stops the scan and is returned.
+    pub fn for_each_row_in(
+        &self,
+        sheet_name: &str,
+        range: &Range,
+        mut f: impl FnMut(StreamRow) -> XlexResult<()>,
+    ) -> XlexResult<()> {
+        let _span = tracing::info_span!("for_each_row_in", sheet = sheet_name).entered();
+        let index = self.row_index(sheet_name)?;
+        let zip_path = &self.sheets[sheet_name].2;
+
+        let cursor = Cursor::new(self.data.as_ref().as_slice());
+        let mut archive = ZipArchive::new(cursor)?;
+        let mut file = archive.by_name(zip_path)?;
+        let offset = index.offset_before(range.start.row);
+        io::copy(&mut file.by_ref().take(offset), &mut io::sink()).map_err(|e| {
+            XlexError::IoError {
+                message: e.to_string(),
+                source: Some(e),
+            }
+        })?;
+
+        let mut visit_error = None;
+        let parsed = self.visit_rows_at(BufReader::new(file), offset, |mut row| {
+            if row.row_number > range.end.row {
+                return ControlFlow::Break(());
+            }
+            if row.row_number >= range.start.row {
+                row.cells
+                    .retain(|(cell, _)| (range.start.col..=range.end.col).contains(&cell.col));
+                if let Err(e) = f(row) {
+                    visit_error = Some(e);
+                    return ControlFlow::Break(());
+                }
+            }
+            ControlFlow::Continue(())
+        });
+        match visit_error {
+            Some(e) => Err(e),
+            None => parsed.map_err(|e| {
+                e.with_context(ErrorContext::default().part(zip_path).sheet(sheet_name))
+            }),
+        }
+    }
+
+    /// Returns the row offset index of a sheet, scanning the sheet to
+    /// build it the first time.
+    pub fn row_index(&self, sheet_name: &str) -> XlexResult<Arc<RowIndex>> {
+        let (_index, _info, zip_path) =
+            self.sheets
+                .get(sheet_name)
+                .ok_or_else(|| XlexError::SheetNotFound {
+                    name: sheet_name.to_string(),
+                })?;
+        if let Some(index) = self.lock_row_indexes().get(sheet_name) {
+            return Ok(Arc::clone(index));
+        }
+
+        let _span = tracing::info_span!("build_row_index", sheet = sheet_name).entered();
+        let cursor = Cursor::new(self.data.as_ref().as_slice());
+        let mut archive = ZipArchive::new(cursor)?;
+        let file = archive.by_name(zip_path)?;
+        let index = Arc::new(build_row_index(BufReader::new(file)).map_err(|e| {
+            e.with_context(ErrorContext::default().part(zip_path).sheet(sheet_name))
+        })?);
+        self.lock_row_indexes()
+            .insert(sheet_name.to_string(), Arc::clone(&index));
+        Ok(index)
+    }
+
+    fn lock_row_indexes(&self) -> std::sync::MutexGuard<'_, HashMap<String, Arc<RowIndex>>> {
+        self.row_indexes
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Reads the first row of a sheet, such as its header, without parsing
+    /// the rest. Returns `None` for an empty sheet.
+    pub fn first_row(&self, sheet_name: &str) -> XlexResult<Option<StreamRow>> {
+        let (_index, _info, zip_path) =
+            self.sheets
+                .get(sheet_name)
+                .ok_or_else(|| XlexError::SheetNotFound {
+                    name: sheet_name.to_string(),
+                })?;
+
+        let cursor = Cursor::new(self.data.as_ref().as_slice());
+        let mut archive = ZipArchive::new(cursor)?;
+        let file = archive.by_name(zip_path)?;
+
+        let mut first = None;
+        self.visit_rows_from_sheet(BufReader::new(file), |row| {
+            first = Some(row);
+            ControlFlow::Break(())
+        })
+        .map_err(|e| e.with_context(ErrorContext::default().part(zip_path).sheet(sheet_name)))?;
+        Ok(first)
+    }
+
+    /// Streams one column of a sheet.
+    ///
+    /// Cells of other columns are skipped without converting their values,
+    /// so full rows are never materialized.
+    pub fn stream_column(&self, sheet_name: &str, col: u32) -> XlexResult<StreamColumn> {
+        let _span = tracing::info_span!("stream_column", sheet = sheet_name, col).entered();
+        let (_index, _info, zip_path) =
+            self.sheets
+                .get(sheet_name)
+                .ok_or_else(|| XlexError::SheetNotFound {
+                    name: sheet_name.to_string(),
+                })?;
+
+        let cursor = Cursor::new(self.data.as_ref().as_slice());
+        let mut archive = ZipArchive::new(cursor)?;
+        let file = archive.by_name(zip_path)?;
+
+        self.parse_column_from_sheet(BufReader::new(file), col)
+            .map_err(|e| e.with_context(ErrorContext::default().part(zip_path).sheet(sheet_name)))
     }
 
     /// Reads a single cell value without loading the entire sheet.
@@ -170,6 +577,315 @@ impl LazyWorkbook {
         self.find_cell_in_sheet(BufReader::new(file), cell_ref)
     }
 
+    /// Reads the formula text and style ID of a single cell without loading
+    /// the entire sheet. Returns `None` if the cell isn't in the sheet.
+    ///
+    /// [`stream_rows`](Self::stream_rows) and [`read_cell`](Self::read_cell)
+    /// only return cached values; this is how to see the formula behind one.
+    pub fn read_cell_details(
+        &self,
+        sheet_name: &str,
+        cell_ref: &CellRef,
+    ) -> XlexResult<Option<CellDetails>> {
+        let (_index, _info, zip_path) =
+            self.sheets
+                .get(sheet_name)
+                .ok_or_else(|| XlexError::SheetNotFound {
+                    name: sheet_name.to_string(),
+                })?;
+
+        let cursor = Cursor::new(self.data.as_ref().as_slice());
+        let mut archive = ZipArchive::new(cursor)?;
+        let file = archive.by_name(zip_path)?;
+
+        let target = cell_ref.to_a1();
+        let mut reader = Reader::from_reader(BufReader::new(file));
+        let mut buf = Vec::new();
+        let mut details: Option<CellDetails> = None;
+        let mut in_formula = false;
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.name().as_ref() == b"c" => {
+                    if details.is_some() {
+                        // The target was a self-closing cell
+                        return Ok(details);
+                    }
+                    let mut is_target = false;
+                    let mut style_id = None;
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"r" => is_target = attr.value.as_ref() == target.as_bytes(),
+                            b"s" => style_id = String::from_utf8_lossy(&attr.value).parse().ok(),
+                            _ => {}
+                        }
+                    }
+                    if is_target {
+                        details = Some(CellDetails {
+                            style_id: style_id.filter(|id| *id != 0),
+                            formula: None,
+                        });
+                    }
+                }
+                Ok(Event::Start(e)) if details.is_some() && e.name().as_ref() == b"f" => {
+                    in_formula = true;
+                }
+                Ok(Event::Text(e)) if in_formula => {
+                    if let Some(details) = details.as_mut() {
+                        details.formula = Some(e.unescape().unwrap_or_default().to_string());
+                    }
+                }
+                Ok(Event::End(e)) => match e.name().as_ref() {
+                    b"f" => in_formula = false,
+                    b"c" | b"row" if details.is_some() => return Ok(details),
+                    _ => {}
+                },
+                Ok(Event::Eof) => return Ok(details),
+                Err(e) => return Err(XlexError::from(e)),
+                _ => {}
+            }
+            buf.clear();
+        }
+    }
+
+    /// Parses the workbook's styles. Style IDs match those returned by
+    /// [`read_cell_details`](Self::read_cell_details).
+    pub fn styles(&self) -> XlexResult<StyleRegistry> {
+        let cursor = Cursor::new(self.data.as_ref().as_slice());
+        let mut archive = ZipArchive::new(cursor)?;
+        let styles = match archive.by_name("xl/styles.xml") {
+            Ok(file) => StylesParser::new().parse(BufReader::new(file))?,
+            Err(_) => StyleRegistry::new(),
+        };
+        Ok(styles)
+    }
+
+    /// Lists the objects embedded in the workbook (parts under
+    /// `xl/embeddings/`), with the sheet and OLE program ID of each object
+    /// placed on a sheet.
+    pub fn embedded_objects(&self) -> XlexResult<Vec<EmbeddedObject>> {
+        let cursor = Cursor::new(self.data.as_ref().as_slice());
+        let mut archive = ZipArchive::new(cursor)?;
+
+        let mut objects = Vec::new();
+        for i in 0..archive.len() {
+            let file = archive.by_index(i)?;
+            if file.name().starts_with("xl/embeddings/") && !file.is_dir() {
+                objects.push(EmbeddedObject {
+                    part: file.name().to_string(),
+                    sheet: None,
+                    prog_id: None,
+                    size: file.size(),
+                });
+            }
+        }
+        if objects.is_empty() {
+            return Ok(objects);
+        }
+
+        for sheet_name in &self.sheet_names {
+            let Some((_, _, zip_path)) = self.sheets.get(sheet_name) else {
+                continue;
+            };
+            let (dir, file_name) = zip_path.rsplit_once('/').unwrap_or(("", zip_path));
+            let rels = Self::parse_part_relationships(
+                &mut archive,
+                &format!("{}/_rels/{}.rels", dir, file_name),
+            );
+            let embedded: HashMap<String, String> = rels
+                .into_iter()
+                .map(|(id, target)| (id, resolve_part_path(dir, &target)))
+                .filter(|(_, part)| objects.iter().any(|o| &o.part == part))
+                .collect();
+            if embedded.is_empty() {
+                continue;
+            }
+
+            // Sheets name each object's program in <oleObject progId r:id>
+            let mut prog_ids: HashMap<String, String> = HashMap::new();
+            if let Ok(file) = archive.by_name(zip_path) {
+                let mut reader = Reader::from_reader(BufReader::new(file));
+                let mut buf = Vec::new();
+                loop {
+                    match reader.read_event_into(&mut buf) {
+                        Ok(Event::Empty(e)) | Ok(Event::Start(e))
+                            if e.local_name().as_ref() == b"oleObject" =>
+                        {
+                            let mut prog_id = String::new();
+                            let mut rel_id = String::new();
+                            for attr in e.attributes().flatten() {
+                                match attr.key.as_ref() {
+                                    b"progId" => {
+                                        prog_id = String::from_utf8_lossy(&attr.value).to_string()
+                                    }
+                                    b"r:id" => {
+                                        rel_id = String::from_utf8_lossy(&attr.value).to_string()
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            prog_ids.insert(rel_id, prog_id);
+                        }
+                        Ok(Event::Eof) | Err(_) => break,
+                        _ => {}
+                    }
+                    buf.clear();
+                }
+            }
+
+            for (rel_id, part) in embedded {
+                if let Some(object) = objects.iter_mut().find(|o| o.part == part) {
+                    object.sheet.get_or_insert_with(|| sheet_name.clone());
+                    if let Some(prog_id) = prog_ids.remove(&rel_id).filter(|p| !p.is_empty()) {
+                        object.prog_id = Some(prog_id);
+                    }
+                }
+            }
+        }
+
+        Ok(objects)
+    }
+
+    /// Reads an embedded object, unwrapping OLE packages to recover the
+    /// original file (see [`crate::embed::unwrap_object`]).
+    pub fn extract_embedded_object(&self, object: &EmbeddedObject) -> XlexResult<EmbeddedFile> {
+        let cursor = Cursor::new(self.data.as_ref().as_slice());
+        let mut archive = ZipArchive::new(cursor)?;
+        let mut file = archive.by_name(&object.part)?;
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)
+            .map_err(|e| XlexError::IoError {
+                message: e.to_string(),
+                source: Some(e),
+            })?;
+        Ok(crate::embed::unwrap_object(object.part_name(), data))
+    }
+
+    /// Finds relationships pointing at parts missing from the package, and
+    /// parts no relationship points to.
+    ///
+    /// Saving the workbook with [`Workbook`](crate::Workbook) rewrites the
+    /// package from its model, which drops both.
+    pub fn orphans(&self) -> XlexResult<Vec<PackageOrphan>> {
+        let cursor = Cursor::new(self.data.as_ref().as_slice());
+        let mut archive = ZipArchive::new(cursor)?;
+        let names: Vec<String> = archive
+            .file_names()
+            .filter(|name| !name.ends_with('/'))
+            .map(str::to_string)
+            .collect();
+
+        let mut rels_parts: Vec<&String> = names.iter().filter(|n| n.ends_with(".rels")).collect();
+        rels_parts.sort();
+
+        let mut orphans = Vec::new();
+        let mut referenced: std::collections::HashSet<String> = std::collections::HashSet::new();
+        for rels_path in rels_parts {
+            // "xl/worksheets/_rels/sheet1.xml.rels" belongs to
+            // "xl/worksheets/sheet1.xml"; "_rels/.rels" to the package
+            let (rels_dir, rels_file) = rels_path.rsplit_once('/').unwrap_or(("", rels_path));
+            let dir = rels_dir.strip_suffix("_rels").unwrap_or(rels_dir);
+            let dir = dir.trim_end_matches('/');
+            let source = rels_file.strip_suffix(".rels").unwrap_or(rels_file);
+            let source = if dir.is_empty() {
+                source.to_string()
+            } else {
+                format!("{}/{}", dir, source)
+            };
+
+            let mut relationships: Vec<(String, String)> =
+                Self::parse_part_relationships(&mut archive, rels_path)
+                    .into_iter()
+                    .collect();
+            relationships.sort();
+            for (id, target) in relationships {
+                let part = resolve_part_path(dir, &target);
+                if names.contains(&part) {
+                    referenced.insert(part);
+                } else {
+                    orphans.push(PackageOrphan::Relationship {
+                        source: source.clone(),
+                        id,
+                        target: part,
+                    });
+                }
+            }
+        }
+
+        let mut parts: Vec<&String> = names
+            .iter()
+            .filter(|n| {
+                !n.ends_with(".rels") && *n != "[Content_Types].xml" && !referenced.contains(*n)
+            })
+            .collect();
+        parts.sort();
+        orphans.extend(
+            parts
+                .into_iter()
+                .map(|name| PackageOrphan::Part { name: name.clone() }),
+        );
+        Ok(orphans)
+    }
+
+    /// Lists personal and identifying metadata in the package: authors,
+    /// manager and company, the absolute save path, printer settings,
+    /// comment authors, hidden defined names, revision data and custom
+    /// properties.
+    ///
+    /// Saving the workbook with [`Workbook`](crate::Workbook) after
+    /// [`Workbook::remove_personal_info`](crate::Workbook::remove_personal_info)
+    /// drops all of it.
+    pub fn personal_metadata(&self) -> XlexResult<Vec<PersonalMetadata>> {
+        let cursor = Cursor::new(self.data.as_ref().as_slice());
+        let mut archive = ZipArchive::new(cursor)?;
+        let mut names: Vec<String> = archive
+            .file_names()
+            .filter(|name| !name.ends_with('/') && !name.contains("/_rels/"))
+            .map(str::to_string)
+            .collect();
+        names.sort();
+
+        let mut found = Vec::new();
+        for name in names {
+            let whole_part = if name.starts_with("xl/printerSettings/") {
+                Some(MetadataKind::PrinterSettings)
+            } else if name.starts_with("xl/revisions/") {
+                Some(MetadataKind::Revision)
+            } else {
+                None
+            };
+            if let Some(kind) = whole_part {
+                found.push(PersonalMetadata {
+                    kind,
+                    value: name.clone(),
+                    part: name,
+                });
+                continue;
+            }
+
+            let scanned = matches!(
+                name.as_str(),
+                "docProps/core.xml"
+                    | "docProps/app.xml"
+                    | "docProps/custom.xml"
+                    | "xl/workbook.xml"
+            ) || name.starts_with("xl/comments")
+                || name.starts_with("xl/persons/");
+            if !scanned {
+                continue;
+            }
+            let mut xml = String::new();
+            archive.by_name(&name)?.read_to_string(&mut xml)?;
+            for (kind, value) in scan_metadata_xml(&xml) {
+                found.push(PersonalMetadata {
+                    kind,
+                    part: name.clone(),
+                    value,
+                });
+            }
+        }
+        Ok(found)
+    }
+
     /// Parses only the sheet metadata (names, paths) without loading content.
     #[allow(clippy::type_complexity)]
     fn parse_sheet_metadata<R: Read + Seek>(
@@ -198,7 +914,7 @@ impl LazyWorkbook {
                     for attr in e.attributes().flatten() {
                         match attr.key.as_ref() {
                             b"name" => {
-                                name = String::from_utf8_lossy(&attr.value).to_string();
+                                name = attr.unescape_value().unwrap_or_default().to_string();
                             }
                             b"sheetId" => {
                                 sheet_id =
@@ -223,11 +939,7 @@ impl LazyWorkbook {
                     index += 1;
                 }
                 Ok(Event::Eof) => break,
-                Err(e) => {
-                    return Err(XlexError::InvalidXml {
-                        message: e.to_string(),
-                    })
-                }
+                Err(e) => return Err(XlexError::from(e)),
                 _ => {}
             }
             buf.clear();
@@ -240,9 +952,21 @@ impl LazyWorkbook {
     fn parse_relationships<R: Read + Seek>(
         archive: &mut ZipArchive<R>,
     ) -> XlexResult<HashMap<String, String>> {
+        Ok(Self::parse_part_relationships(
+            archive,
+            "xl/_rels/workbook.xml.rels",
+        ))
+    }
+
+    /// Parses a relationships part into an id -> target map. Targets
+    /// outside the package (`TargetMode="External"`) are skipped.
+    fn parse_part_relationships<R: Read + Seek>(
+        archive: &mut ZipArchive<R>,
+        rels_path: &str,
+    ) -> HashMap<String, String> {
         let mut relationships = HashMap::new();
 
-        if let Ok(file) = archive.by_name("xl/_rels/workbook.xml.rels") {
+        if let Ok(file) = archive.by_name(rels_path) {
             let mut reader = Reader::from_reader(BufReader::new(file));
             reader.config_mut().trim_text(true);
 
@@ -252,6 +976,7 @@ impl LazyWorkbook {
                     Ok(Event::Empty(e)) if e.name().as_ref() == b"Relationship" => {
                         let mut id = String::new();
                         let mut target = String::new();
+                        let mut external = false;
 
                         for attr in e.attributes().flatten() {
                             match attr.key.as_ref() {
@@ -261,11 +986,12 @@ impl LazyWorkbook {
                                 b"Target" => {
                                     target = String::from_utf8_lossy(&attr.value).to_string();
                                 }
+                                b"TargetMode" => external = attr.value.as_ref() == b"External",
                                 _ => {}
                             }
                         }
 
-                        if !id.is_empty() && !target.is_empty() {
+                        if !id.is_empty() && !target.is_empty() && !external {
                             relationships.insert(id, target);
                         }
                     }
@@ -277,7 +1003,7 @@ impl LazyWorkbook {
             }
         }
 
-        Ok(relationships)
+        relationships
     }
 
     /// Finds a specific cell in a sheet without loading the entire sheet.
@@ -341,11 +1067,7 @@ impl LazyWorkbook {
                     _ => {}
                 },
                 Ok(Event::Eof) => break,
-                Err(e) => {
-                    return Err(XlexError::InvalidXml {
-                        message: e.to_string(),
-                    })
-                }
+                Err(e) => return Err(XlexError::from(e)),
                 _ => {}
             }
             buf.clear();
@@ -399,12 +1121,118 @@ impl LazyWorkbook {
         }
     }
 
+    /// Parses the values of column `col` from a sheet reader.
+    fn parse_column_from_sheet<R: Read>(&self, reader: R, col: u32) -> XlexResult<StreamColumn> {
+        let mut xml_reader = Reader::from_reader(BufReader::new(reader));
+        xml_reader.config_mut().trim_text(true);
+
+        let mut column = StreamColumn::default();
+        let mut buf = Vec::new();
+        let mut cell_ref: Option<CellRef> = None;
+        let mut cell_type = String::new();
+        let mut in_value = false;
+        let mut value_text = String::new();
+
+        loop {
+            match xml_reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.name().as_ref() == b"row" => {
+                    for attr in e.attributes().flatten() {
+                        if attr.key.as_ref() == b"r" {
+                            if let Ok(row) = String::from_utf8_lossy(&attr.value).parse::<u32>() {
+                                column.last_row = column.last_row.max(row);
+                            }
+                        }
+                    }
+                }
+                Ok(Event::Start(e)) if e.name().as_ref() == b"c" => {
+                    cell_ref = None;
+                    cell_type.clear();
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"r" => {
+                                cell_ref = String::from_utf8_lossy(&attr.value)
+                                    .parse::<CellRef>()
+                                    .ok()
+                                    .filter(|r| r.col == col);
+                            }
+                            b"t" => {
+                                cell_type = String::from_utf8_lossy(&attr.value).to_string();
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Ok(Event::Start(e)) if e.name().as_ref() == b"v" && cell_ref.is_some() => {
+                    in_value = true;
+                    value_text.clear();
+                }
+                Ok(Event::Text(e)) if in_value => {
+                    value_text = e.unescape().unwrap_or_default().to_string();
+                }
+                Ok(Event::End(e)) => match e.name().as_ref() {
+                    b"v" => in_value = false,
+                    b"c" => {
+                        if let Some(ref_) = cell_ref.take() {
+                            let value = self
+                                .convert_cell_value(&cell_type, &value_text)
+                                .unwrap_or(CellValue::Empty);
+                            if !value.is_empty() {
+                                column.values.push((ref_.row, value));
+                            }
+                        }
+                        value_text.clear();
+                    }
+                    _ => {}
+                },
+                Ok(Event::Eof) => break,
+                Err(e) => {
+                    let mut context = ErrorContext::default().offset(xml_reader.error_position());
+                    if let Some(ref_) = &cell_ref {
+                        context = context.cell(ref_.to_a1());
+                    }
+                    return Err(XlexError::from(e).with_context(context));
+                }
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        Ok(column)
+    }
+
     /// Parses all rows from a sheet reader.
     fn parse_rows_from_sheet<R: Read>(&self, reader: R) -> XlexResult<Vec<StreamRow>> {
+        let mut rows = Vec::new();
+        self.visit_rows_from_sheet(reader, |row| {
+            rows.push(row);
+            ControlFlow::Continue(())
+        })?;
+        Ok(rows)
+    }
+
+    /// Parses the rows of sheet XML, handing each to `visit` as soon as it
+    /// is complete, until `visit` breaks. Only parse errors are returned.
+    fn visit_rows_from_sheet<R: Read>(
+        &self,
+        reader: R,
+        visit: impl FnMut(StreamRow) -> ControlFlow<()>,
+    ) -> XlexResult<()> {
+        self.visit_rows_at(reader, 0, visit)
+    }
+
+    /// [`visit_rows_from_sheet`](Self::visit_rows_from_sheet) for sheet XML
+    /// read from `offset` onwards, where the elements enclosing the rows
+    /// are closed without having been opened.
+    fn visit_rows_at<R: Read>(
+        &self,
+        reader: R,
+        offset: u64,
+        mut visit: impl FnMut(StreamRow) -> ControlFlow<()>,
+    ) -> XlexResult<()> {
         let mut xml_reader = Reader::from_reader(BufReader::new(reader));
         xml_reader.config_mut().trim_text(true);
+        xml_reader.config_mut().allow_unmatched_ends = offset > 0;
 
-        let mut rows = Vec::new();
         let mut buf = Vec::new();
         let mut current_row: Option<u32> = None;
         let mut current_cells: Vec<(CellRef, CellValue)> = Vec::new();
@@ -468,10 +1296,13 @@ impl LazyWorkbook {
                     }
                     b"row" => {
                         if let Some(row_num) = current_row.take() {
-                            rows.push(StreamRow {
+                            let flow = visit(StreamRow {
                                 row_number: row_num,
                                 cells: std::mem::take(&mut current_cells),
                             });
+                            if flow.is_break() {
+                                return Ok(());
+                            }
                         }
                     }
                     _ => {}
@@ -489,19 +1320,135 @@ impl LazyWorkbook {
                 }
                 Ok(Event::Eof) => break,
                 Err(e) => {
-                    return Err(XlexError::InvalidXml {
-                        message: e.to_string(),
-                    })
+                    let mut context =
+                        ErrorContext::default().offset(offset + xml_reader.error_position());
+                    if let Some(ref_) = &cell_ref {
+                        context = context.cell(ref_.to_a1());
+                    }
+                    return Err(XlexError::from(e).with_context(context));
                 }
                 _ => {}
             }
             buf.clear();
         }
 
-        Ok(rows)
+        Ok(())
     }
 }
 
+/// Scans sheet XML for where its rows start, recording every
+/// [`ROW_INDEX_STRIDE`]th row, and for its extent.
+fn build_row_index<R: Read>(reader: R) -> XlexResult<RowIndex> {
+    let mut xml_reader = Reader::from_reader(BufReader::new(reader));
+    let mut buf = Vec::new();
+    let mut index = RowIndex::default();
+    let mut seen = 0usize;
+    loop {
+        let position = xml_reader.buffer_position();
+        match xml_reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e) | Event::Empty(e)) if e.name().as_ref() == b"row" => {
+                let row = e
+                    .try_get_attribute("r")
+                    .ok()
+                    .flatten()
+                    .and_then(|a| String::from_utf8_lossy(&a.value).parse().ok());
+                if let Some(row) = row {
+                    if seen % ROW_INDEX_STRIDE == 0 {
+                        index.entries.push((row, position));
+                    }
+                    seen += 1;
+                    index.last_row = row;
+                }
+            }
+            Ok(Event::Start(e) | Event::Empty(e)) if e.name().as_ref() == b"c" => {
+                let cell = e
+                    .try_get_attribute("r")
+                    .ok()
+                    .flatten()
+                    .and_then(|a| String::from_utf8_lossy(&a.value).parse::<CellRef>().ok());
+                if let Some(cell) = cell {
+                    index.dimension = Some(match index.dimension.take() {
+                        Some(range) => Range::new(
+                            CellRef::new(
+                                range.start.col.min(cell.col),
+                                range.start.row.min(cell.row),
+                            ),
+                            CellRef::new(range.end.col.max(cell.col), range.end.row.max(cell.row)),
+                        ),
+                        None => Range::single(cell),
+                    });
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(XlexError::from(e)
+                    .with_context(ErrorContext::default().offset(xml_reader.error_position())))
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(index)
+}
+
+/// Finds personal metadata in a document properties, workbook, comments
+/// or persons part.
+fn scan_metadata_xml(xml: &str) -> Vec<(MetadataKind, String)> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let attr = |e: &quick_xml::events::BytesStart, key: &[u8]| {
+        e.attributes()
+            .flatten()
+            .find(|a| a.key.local_name().as_ref() == key)
+            .map(|a| String::from_utf8_lossy(&a.value).to_string())
+    };
+
+    let mut found = Vec::new();
+    let mut text_kind: Option<MetadataKind> = None;
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                text_kind = match e.local_name().as_ref() {
+                    b"creator" => Some(MetadataKind::Author),
+                    b"lastModifiedBy" => Some(MetadataKind::LastModifiedBy),
+                    b"revision" => Some(MetadataKind::Revision),
+                    b"Manager" => Some(MetadataKind::Manager),
+                    b"Company" => Some(MetadataKind::Company),
+                    b"author" => Some(MetadataKind::CommentAuthor),
+                    _ => None,
+                };
+                let value = match e.local_name().as_ref() {
+                    b"absPath" => attr(&e, b"url").map(|v| (MetadataKind::AbsolutePath, v)),
+                    b"revisionPtr" => attr(&e, b"documentId").map(|v| (MetadataKind::Revision, v)),
+                    b"person" => attr(&e, b"displayName").map(|v| (MetadataKind::CommentAuthor, v)),
+                    b"property" => attr(&e, b"name").map(|v| (MetadataKind::CustomProperty, v)),
+                    b"definedName" => attr(&e, b"name")
+                        .filter(|name| {
+                            !name.starts_with("_xlnm.")
+                                && matches!(attr(&e, b"hidden").as_deref(), Some("1" | "true"))
+                        })
+                        .map(|v| (MetadataKind::HiddenName, v)),
+                    _ => None,
+                };
+                found.extend(value);
+            }
+            Ok(Event::Text(e)) => {
+                if let Some(kind) = text_kind.take() {
+                    let text = e.unescape().unwrap_or_default().to_string();
+                    if !text.is_empty() {
+                        found.push((kind, text));
+                    }
+                }
+            }
+            Ok(Event::End(_)) => text_kind = None,
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+    found
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -522,6 +1469,175 @@ mod tests {
         assert!(format!("{:?}", row).contains("row_number: 1"));
     }
 
+    #[test]
+    fn test_stream_column() {
+        let path = std::env::temp_dir().join("xlex_test_stream_column.xlsx");
+        let mut workbook = crate::Workbook::new();
+        for (col, row, value) in [
+            (1, 1, CellValue::string("id")),
+            (2, 1, CellValue::string("name")),
+            (2, 2, CellValue::string("ann")),
+            (1, 4, CellValue::Number(4.0)),
+            (2, 5, CellValue::string("bob")),
+        ] {
+            workbook
+                .set_cell("Sheet1", CellRef::new(col, row), value)
+                .unwrap();
+        }
+        workbook.save_as(&path).unwrap();
+        let lazy = LazyWorkbook::open(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let column = lazy.stream_column("Sheet1", 2).unwrap();
+        assert_eq!(
+            column.values,
+            vec![
+                (1, CellValue::string("name")),
+                (2, CellValue::string("ann")),
+                (5, CellValue::string("bob")),
+            ]
+        );
+        assert_eq!(column.last_row, 5);
+        assert!(lazy.stream_column("Missing", 1).is_err());
+    }
+
+    #[test]
+    fn test_first_row() {
+        let path = std::env::temp_dir().join("xlex_test_first_row.xlsx");
+        let mut workbook = crate::Workbook::with_sheets(&["Data", "Empty"]);
+        for row in 1..=3 {
+            workbook
+                .set_cell("Data", CellRef::new(2, row), CellValue::Number(row.into()))
+                .unwrap();
+        }
+        workbook.save_as(&path).unwrap();
+        let lazy = LazyWorkbook::open(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let first = lazy.first_row("Data").unwrap().unwrap();
+        assert_eq!(first.row_number, 1);
+        assert_eq!(
+            first.cells,
+            vec![(CellRef::new(2, 1), CellValue::Number(1.0))]
+        );
+        assert!(lazy.first_row("Empty").unwrap().is_none());
+        assert!(lazy.first_row("Missing").is_err());
+    }
+
+    #[test]
+    fn test_stream_rows_in() {
+        let path = std::env::temp_dir().join("xlex_test_stream_rows_in.xlsx");
+        let mut workbook = crate::Workbook::with_sheets(&["Data"]);
+        for row in 1..=1000 {
+            for col in 1..=3 {
+                let value = CellValue::string(format!("{}{}", col, row));
+                workbook
+                    .set_cell("Data", CellRef::new(col, row), value)
+                    .unwrap();
+            }
+        }
+        workbook.save_as(&path).unwrap();
+        let lazy = LazyWorkbook::open(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let index = lazy.row_index("Data").unwrap();
+        assert_eq!(index.entries.len(), 4);
+        assert_eq!(index.entries[1].0, 257);
+        assert_eq!(index.offset_before(1), index.entries[0].1);
+        assert_eq!(index.offset_before(600), index.entries[2].1);
+        assert!(Arc::ptr_eq(&index, &lazy.row_index("Data").unwrap()));
+        assert_eq!(index.last_row, 1000);
+        assert_eq!(index.dimension, Some("A1:C1000".parse().unwrap()));
+
+        let range: Range = "B700:C702".parse().unwrap();
+        let rows = lazy.stream_rows_in("Data", &range).unwrap();
+        assert_eq!(
+            rows.iter().map(|r| r.row_number).collect::<Vec<_>>(),
+            [700, 701, 702]
+        );
+        assert_eq!(
+            rows[0].cells,
+            vec![
+                (CellRef::new(2, 700), CellValue::string("2700")),
+                (CellRef::new(3, 700), CellValue::string("3700")),
+            ]
+        );
+
+        let tail = lazy
+            .stream_rows_in("Data", &"A999:A5000".parse().unwrap())
+            .unwrap();
+        assert_eq!(tail.len(), 2);
+        let head = lazy
+            .stream_rows_in("Data", &"A1:A2".parse().unwrap())
+            .unwrap();
+        assert_eq!(head[0].cells[0].1, CellValue::string("11"));
+        assert!(lazy
+            .stream_rows_in("Data", &"A2000:A2001".parse().unwrap())
+            .unwrap()
+            .is_empty());
+        assert!(lazy.stream_rows_in("Missing", &range).is_err());
+        assert!(lazy
+            .reader_pool(1)
+            .get()
+            .row_indexes
+            .lock()
+            .unwrap()
+            .contains_key("Data"));
+    }
+
+    #[test]
+    fn test_open_indexed() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("indexed.xlsx");
+        let mut workbook = crate::Workbook::with_sheets(&["Data", "Notes"]);
+        for row in 1..=600 {
+            let value = CellValue::string(format!("row {}", row));
+            workbook
+                .set_cell("Data", CellRef::new(2, row), value)
+                .unwrap();
+        }
+        workbook.save_as(&path).unwrap();
+        let index_path = IndexFile::path_for(&path);
+
+        let plain = LazyWorkbook::open(&path).unwrap();
+        assert!(plain.lock_row_indexes().is_empty());
+        assert!(!index_path.exists());
+
+        let built = LazyWorkbook::open_indexed(&path).unwrap();
+        assert!(index_path.exists());
+        let reopened = LazyWorkbook::open(&path).unwrap();
+        assert_eq!(reopened.lock_row_indexes().len(), 2);
+        assert_eq!(
+            reopened.row_index("Data").unwrap(),
+            built.row_index("Data").unwrap()
+        );
+        let rows = reopened
+            .stream_rows_in("Data", &"B580:B580".parse().unwrap())
+            .unwrap();
+        assert_eq!(rows[0].cells[0].1, CellValue::string("row 580"));
+        assert_eq!(
+            reopened.read_cell("Data", &CellRef::new(2, 3)).unwrap(),
+            Some(CellValue::string("row 3"))
+        );
+
+        workbook
+            .set_cell("Data", CellRef::new(2, 700), CellValue::string("new"))
+            .unwrap();
+        workbook.save_as(&path).unwrap();
+        let changed = LazyWorkbook::open(&path).unwrap();
+        assert!(changed.lock_row_indexes().is_empty());
+        assert_eq!(changed.row_index("Data").unwrap().last_row, 700);
+        LazyWorkbook::open_indexed(&path).unwrap();
+        assert_eq!(
+            LazyWorkbook::open(&path)
+                .unwrap()
+                .row_index("Data")
+                .unwrap()
+                .last_row,
+            700
+        );
+    }
+
     #[test]
     fn test_convert_cell_value_empty() {
         let wb = LazyWorkbook {
@@ -529,6 +1645,7 @@ mod tests {
             sheets: HashMap::new(),
             sheet_names: vec![],
             shared_strings: Arc::new(Mutex::new(LazySharedStrings::default())),
+            row_indexes: Arc::default(),
         };
 
         let value = wb.convert_cell_value("", "").unwrap();
@@ -542,6 +1659,7 @@ mod tests {
             sheets: HashMap::new(),
             sheet_names: vec![],
             shared_strings: Arc::new(Mutex::new(LazySharedStrings::default())),
+            row_indexes: Arc::default(),
         };
 
         let value = wb.convert_cell_value("b", "1").unwrap();
@@ -558,6 +1676,7 @@ mod tests {
             sheets: HashMap::new(),
             sheet_names: vec![],
             shared_strings: Arc::new(Mutex::new(LazySharedStrings::default())),
+            row_indexes: Arc::default(),
         };
 
         let value = wb.convert_cell_value("n", "42.5").unwrap();
@@ -571,9 +1690,187 @@ mod tests {
             sheets: HashMap::new(),
             sheet_names: vec![],
             shared_strings: Arc::new(Mutex::new(LazySharedStrings::default())),
+            row_indexes: Arc::default(),
         };
 
         let value = wb.convert_cell_value("e", "#DIV/0!").unwrap();
         assert!(matches!(value, CellValue::Error(CellError::DivZero)));
     }
+
+    #[test]
+    fn test_orphans() {
+        use std::io::Write;
+
+        let path = std::env::temp_dir().join("xlex_test_orphans.xlsx");
+        crate::Workbook::new().save_as(&path).unwrap();
+        let plain = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert!(LazyWorkbook::from_bytes(plain.clone())
+            .unwrap()
+            .orphans()
+            .unwrap()
+            .is_empty());
+
+        let mut archive = ZipArchive::new(Cursor::new(plain)).unwrap();
+        let mut out = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).unwrap();
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content).unwrap();
+            out.start_file(entry.name(), options).unwrap();
+            out.write_all(&content).unwrap();
+        }
+        out.start_file("xl/worksheets/_rels/sheet1.xml.rels", options)
+            .unwrap();
+        out.write_all(br#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/drawing" Target="../drawings/drawing1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="https://example.com" TargetMode="External"/></Relationships>"#)
+            .unwrap();
+        out.start_file("xl/media/image9.png", options).unwrap();
+        out.write_all(b"\x89PNG").unwrap();
+        let data = out.finish().unwrap().into_inner();
+
+        let orphans = LazyWorkbook::from_bytes(data).unwrap().orphans().unwrap();
+        assert_eq!(
+            orphans,
+            vec![
+                PackageOrphan::Relationship {
+                    source: "xl/worksheets/sheet1.xml".to_string(),
+                    id: "rId1".to_string(),
+                    target: "xl/drawings/drawing1.xml".to_string(),
+                },
+                PackageOrphan::Part {
+                    name: "xl/media/image9.png".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_personal_metadata() {
+        use std::io::Write;
+
+        let path = std::env::temp_dir().join("xlex_test_personal_metadata.xlsx");
+        let mut wb = crate::Workbook::new();
+        wb.properties_mut().creator = Some("Ann".to_string());
+        wb.properties_mut().last_modified_by = Some("Bob".to_string());
+        let mut secret = crate::DefinedName::new("Secret", "Sheet1!$A$1");
+        secret.hidden = true;
+        wb.set_defined_name(secret);
+        wb.save_as(&path).unwrap();
+
+        let mut archive = ZipArchive::new(Cursor::new(std::fs::read(&path).unwrap())).unwrap();
+        let mut out = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).unwrap();
+            let mut content = String::new();
+            entry.read_to_string(&mut content).unwrap();
+            if entry.name() == "xl/workbook.xml" {
+                content = content.replacen(
+                    "<sheets>",
+                    r#"<x15ac:absPath xmlns:x15ac="http://schemas.microsoft.com/office/spreadsheetml/2010/11/ac" url="C:\Users\ann\Desktop\"/><sheets>"#,
+                    1,
+                );
+            }
+            out.start_file(entry.name(), options).unwrap();
+            out.write_all(content.as_bytes()).unwrap();
+        }
+        out.start_file("docProps/custom.xml", options).unwrap();
+        out.write_all(br#"<Properties xmlns="http://schemas.openxmlformats.org/officeDocument/2006/custom-properties" xmlns:vt="http://schemas.openxmlformats.org/officeDocument/2006/docPropsVTypes"><property fmtid="{D5CDD505-2E9C-101B-9397-08002B2CF9AE}" pid="2" name="Client"><vt:lpwstr>Acme</vt:lpwstr></property></Properties>"#)
+            .unwrap();
+        out.start_file("xl/printerSettings/printerSettings1.bin", options)
+            .unwrap();
+        out.write_all(b"\\\\print01\\HR-Laser").unwrap();
+        out.finish().unwrap();
+
+        let found: Vec<(MetadataKind, String)> = LazyWorkbook::open(&path)
+            .unwrap()
+            .personal_metadata()
+            .unwrap()
+            .into_iter()
+            .map(|m| (m.kind, m.value))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (MetadataKind::Author, "Ann".to_string()),
+                (MetadataKind::LastModifiedBy, "Bob".to_string()),
+                (MetadataKind::CustomProperty, "Client".to_string()),
+                (
+                    MetadataKind::PrinterSettings,
+                    "xl/printerSettings/printerSettings1.bin".to_string()
+                ),
+                (
+                    MetadataKind::AbsolutePath,
+                    r"C:\Users\ann\Desktop\".to_string()
+                ),
+                (MetadataKind::HiddenName, "Secret".to_string()),
+            ]
+        );
+
+        let mut wb = crate::Workbook::open(&path).unwrap();
+        wb.remove_personal_info();
+        wb.save().unwrap();
+        let remaining = LazyWorkbook::open(&path)
+            .unwrap()
+            .personal_metadata()
+            .unwrap();
+        std::fs::remove_file(&path).ok();
+        assert!(remaining.is_empty(), "{:?}", remaining);
+    }
+
+    #[test]
+    fn test_read_cell_details() {
+        use crate::style::{Font, Style};
+
+        let path = std::env::temp_dir().join("xlex_test_lazy_cell_style.xlsx");
+        let mut wb = crate::Workbook::new();
+        let bold = wb.style_registry_mut().add(Style {
+            font: Font {
+                bold: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        wb.set_cell("Sheet1", CellRef::new(1, 1), CellValue::Number(1.0))
+            .unwrap();
+        wb.set_cell("Sheet1", CellRef::new(2, 1), CellValue::Number(2.0))
+            .unwrap();
+        wb.set_cell("Sheet1", CellRef::new(3, 1), CellValue::formula("A1+B1"))
+            .unwrap();
+        wb.get_sheet_mut("Sheet1")
+            .unwrap()
+            .set_cell_style(&CellRef::new(2, 1), Some(bold));
+        wb.save_as(&path).unwrap();
+
+        let lazy = LazyWorkbook::open(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        let plain = lazy
+            .read_cell_details("Sheet1", &CellRef::new(1, 1))
+            .unwrap()
+            .unwrap();
+        assert_eq!(plain, CellDetails::default());
+        let id = lazy
+            .read_cell_details("Sheet1", &CellRef::new(2, 1))
+            .unwrap()
+            .unwrap()
+            .style_id
+            .unwrap();
+        assert!(lazy.styles().unwrap().get(id).unwrap().font.bold);
+        assert_eq!(
+            lazy.read_cell_details("Sheet1", &CellRef::new(3, 1))
+                .unwrap()
+                .unwrap()
+                .formula
+                .as_deref(),
+            Some("A1+B1")
+        );
+        assert!(lazy
+            .read_cell_details("Sheet1", &CellRef::new(9, 9))
+            .unwrap()
+            .is_none());
+        assert!(lazy
+            .read_cell_details("Missing", &CellRef::new(1, 1))
+            .is_err());
+    }
 }
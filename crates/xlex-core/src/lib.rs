@@ -44,26 +44,60 @@
     clippy::expect_used
 )]
 
+pub mod aggregate;
+pub mod builder;
 pub mod cell;
+pub mod checksum;
+pub mod compare;
+pub mod decimal;
+pub mod diagnostic;
+pub mod embed;
 pub mod error;
+pub mod filters;
+pub mod formula;
+pub mod import;
+pub mod index_file;
+pub mod join;
 pub mod lazy;
 pub mod parser;
+pub mod pii;
+pub mod pipeline;
+pub mod pool;
+pub mod protection;
 pub mod range;
 pub mod reader;
+pub mod reshape;
+pub mod rounding;
 pub mod sheet;
+#[cfg(feature = "signing")]
+pub mod signature;
+pub mod sparkline;
+pub mod stamp;
 pub mod style;
+pub mod testing;
+pub mod text;
+pub mod units;
+pub mod window;
 pub mod workbook;
 pub mod writer;
 
 // Re-exports
-pub use cell::{Cell, CellRef, CellValue};
-pub use error::{XlexError, XlexResult};
-pub use lazy::{LazyWorkbook, StreamRow};
+pub use builder::{SheetBuilder, WorkbookBuilder};
+pub use cell::{Cell, CellError, CellRef, CellValue};
+pub use error::{ErrorContext, XlexError, XlexResult};
+pub use import::ImportOptions;
+pub use lazy::{
+    CellDetails, LazyWorkbook, MetadataKind, PackageOrphan, PersonalMetadata, RowIndex,
+    StreamColumn, StreamRow,
+};
+pub use parser::{ParseMode, Violation};
+pub use pipeline::{Pipeline, PipelineStats, Record, RowSink};
+pub use pool::ReaderPool;
 pub use range::Range;
 pub use reader::WorkbookReader;
-pub use sheet::Sheet;
+pub use sheet::{ColumnOptions, Sheet};
 pub use style::{Style, StyleRegistry};
-pub use workbook::{DefinedName, Workbook};
+pub use workbook::{CustomProperty, DefinedName, ExternalLink, Workbook};
 
 /// Library version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -48,8 +48,8 @@ impl SharedStringsData {
 pub struct LazySharedStrings {
     /// Data source
     data: SharedStringsData,
-    /// Index of string positions
-    index: Vec<StringIndex>,
+    /// Index of string positions, shared with forks
+    index: Arc<Vec<StringIndex>>,
     /// LRU cache for recently accessed strings
     cache: LruCache<u32, String>,
     /// Total count of strings
@@ -68,7 +68,7 @@ impl LazySharedStrings {
 
         Ok(Self {
             data: SharedStringsData::InMemory(data),
-            index,
+            index: Arc::new(index),
             cache: LruCache::new(cache_size),
             count,
         })
@@ -79,6 +79,40 @@ impl LazySharedStrings {
         Self::from_bytes(data, DEFAULT_CACHE_SIZE)
     }
 
+    /// Creates a parser with the default cache size over `data` whose
+    /// `<si>` elements are already known to be at `offsets`, as
+    /// `(byte_offset, length)` pairs, skipping the scan.
+    pub(crate) fn from_bytes_with_offsets(data: Vec<u8>, offsets: &[(u64, u32)]) -> Self {
+        let index: Vec<StringIndex> = offsets
+            .iter()
+            .map(|&(offset, length)| StringIndex { offset, length })
+            .collect();
+        Self {
+            data: SharedStringsData::InMemory(Arc::new(data)),
+            count: index.len() as u32,
+            index: Arc::new(index),
+            cache: LruCache::new(NonZeroUsize::new(DEFAULT_CACHE_SIZE).unwrap()),
+        }
+    }
+
+    /// Returns where each `<si>` element is, as `(byte_offset, length)`
+    /// pairs in index order.
+    pub(crate) fn offsets(&self) -> Vec<(u64, u32)> {
+        self.index.iter().map(|e| (e.offset, e.length)).collect()
+    }
+
+    /// Creates another parser over the same data and index, with its own
+    /// empty cache of the same size, for use on another thread.
+    pub fn fork(&self) -> Self {
+        let SharedStringsData::InMemory(data) = &self.data;
+        Self {
+            data: SharedStringsData::InMemory(Arc::clone(data)),
+            index: Arc::clone(&self.index),
+            cache: LruCache::new(self.cache.cap()),
+            count: self.count,
+        }
+    }
+
     /// Creates from a reader by reading all data into memory.
     pub fn from_reader<R: Read>(mut reader: R, cache_size: usize) -> XlexResult<Self> {
         let mut data = Vec::new();
@@ -182,11 +216,9 @@ impl LazySharedStrings {
                     b"t" => in_t = false,
                     _ => {}
                 },
-                Ok(Event::Text(e)) => {
-                    if in_t {
-                        if let Ok(text) = e.unescape() {
-                            result.push_str(&text);
-                        }
+                Ok(Event::Text(e)) if in_t => {
+                    if let Ok(text) = e.unescape() {
+                        result.push_str(&text);
                     }
                 }
                 Ok(Event::Eof) => break,
@@ -234,7 +266,7 @@ impl Default for LazySharedStrings {
     fn default() -> Self {
         Self {
             data: SharedStringsData::InMemory(Arc::new(Vec::new())),
-            index: Vec::new(),
+            index: Arc::default(),
             cache: LruCache::new(NonZeroUsize::new(1).unwrap()),
             count: 0,
         }
@@ -3,11 +3,13 @@
 mod lazy_shared_strings;
 mod shared_strings;
 mod styles;
+mod violation;
 mod workbook;
 
 pub use lazy_shared_strings::LazySharedStrings;
 pub use shared_strings::SharedStringsParser;
 pub use styles::StylesParser;
+pub use violation::{ParseMode, Violation};
 pub use workbook::WorkbookParser;
 
 use crate::error::{XlexError, XlexResult};
@@ -29,6 +31,26 @@ pub fn validate_xlsx_structure<R: std::io::Read + std::io::Seek>(
     Ok(())
 }
 
+/// Resolves a relationship target against the directory of the part that
+/// owns the relationship (e.g., `xl/worksheets` + `../media/image1.png`).
+pub(crate) fn resolve_part_path(dir: &str, target: &str) -> String {
+    let mut parts: Vec<&str> = if target.starts_with('/') {
+        Vec::new()
+    } else {
+        dir.split('/').filter(|s| !s.is_empty()).collect()
+    };
+    for segment in target.trim_start_matches('/').split('/') {
+        match segment {
+            ".." => {
+                parts.pop();
+            }
+            "." | "" => {}
+            other => parts.push(other),
+        }
+    }
+    parts.join("/")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -73,13 +73,11 @@ impl SharedStringsParser {
                     }
                     _ => {}
                 },
-                Ok(Event::Text(e)) => {
-                    if in_t {
-                        let text = e.unescape().map_err(|e| XlexError::InvalidXml {
-                            message: e.to_string(),
-                        })?;
-                        current_string.push_str(&text);
-                    }
+                Ok(Event::Text(e)) if in_t => {
+                    let text = e.unescape().map_err(|e| XlexError::InvalidXml {
+                        message: e.to_string(),
+                    })?;
+                    current_string.push_str(&text);
                 }
                 Ok(Event::Eof) => break,
                 Err(e) => {
@@ -7,12 +7,13 @@ use quick_xml::Reader;
 
 use crate::error::{XlexError, XlexResult};
 use crate::style::{
-    Border, BorderSide, BorderStyle, Color, Fill, FillPattern, Font, HorizontalAlignment,
-    NumberFormat, Style, StyleRegistry, VerticalAlignment,
+    Border, BorderSide, BorderStyle, Color, Fill, FillPattern, Font, GradientFill, GradientStop,
+    GradientType, HorizontalAlignment, NumberFormat, Protection, Style, StyleRegistry,
+    VerticalAlignment,
 };
 
 /// Tuple type for cellXfs entry data during parsing.
-/// (fontId, fillId, borderId, numFmtId, hAlign, vAlign, wrapText)
+/// (fontId, fillId, borderId, numFmtId, hAlign, vAlign, wrapText, checkbox, protection)
 type CellXfEntry = (
     usize,
     usize,
@@ -21,6 +22,8 @@ type CellXfEntry = (
     Option<HorizontalAlignment>,
     Option<VerticalAlignment>,
     bool,
+    bool,
+    Protection,
 );
 
 /// Parser for styles.xml.
@@ -49,6 +52,7 @@ impl StylesParser {
         // Current parsing state
         let mut current_font: Option<Font> = None;
         let mut current_fill: Option<Fill> = None;
+        let mut current_stop: Option<f64> = None; // gradient stop position
         let mut current_border: Option<Border> = None;
         let mut current_border_side: Option<(String, BorderSide)> = None; // (side_name, side)
 
@@ -58,197 +62,295 @@ impl StylesParser {
 
         loop {
             match xml_reader.read_event_into(&mut buf) {
-                Ok(Event::Start(e)) | Ok(Event::Empty(e)) => match e.name().as_ref() {
-                    b"font" => {
-                        current_font = Some(Font::default());
-                    }
-                    b"name" if current_font.is_some() => {
-                        if let Some(ref mut font) = current_font {
-                            for attr in e.attributes().flatten() {
-                                if attr.key.as_ref() == b"val" {
-                                    font.name =
-                                        Some(String::from_utf8_lossy(&attr.value).to_string());
+                Ok(ref event @ (Event::Start(ref e) | Event::Empty(ref e))) => {
+                    let is_empty = matches!(event, Event::Empty(_));
+                    match e.name().as_ref() {
+                        b"font" => {
+                            current_font = Some(Font::default());
+                        }
+                        b"name" if current_font.is_some() => {
+                            if let Some(ref mut font) = current_font {
+                                for attr in e.attributes().flatten() {
+                                    if attr.key.as_ref() == b"val" {
+                                        font.name =
+                                            Some(String::from_utf8_lossy(&attr.value).to_string());
+                                    }
                                 }
                             }
                         }
-                    }
-                    b"sz" if current_font.is_some() => {
-                        if let Some(ref mut font) = current_font {
-                            for attr in e.attributes().flatten() {
-                                if attr.key.as_ref() == b"val" {
-                                    font.size = String::from_utf8_lossy(&attr.value).parse().ok();
+                        b"sz" if current_font.is_some() => {
+                            if let Some(ref mut font) = current_font {
+                                for attr in e.attributes().flatten() {
+                                    if attr.key.as_ref() == b"val" {
+                                        font.size =
+                                            String::from_utf8_lossy(&attr.value).parse().ok();
+                                    }
                                 }
                             }
                         }
-                    }
-                    b"b" if current_font.is_some() => {
-                        if let Some(ref mut font) = current_font {
-                            font.bold = true;
+                        b"b" if current_font.is_some() => {
+                            if let Some(ref mut font) = current_font {
+                                font.bold = true;
+                            }
                         }
-                    }
-                    b"i" if current_font.is_some() => {
-                        if let Some(ref mut font) = current_font {
-                            font.italic = true;
+                        b"i" if current_font.is_some() => {
+                            if let Some(ref mut font) = current_font {
+                                font.italic = true;
+                            }
                         }
-                    }
-                    b"u" if current_font.is_some() => {
-                        if let Some(ref mut font) = current_font {
-                            font.underline = true;
+                        b"u" if current_font.is_some() => {
+                            if let Some(ref mut font) = current_font {
+                                font.underline = true;
+                            }
                         }
-                    }
-                    b"strike" if current_font.is_some() => {
-                        if let Some(ref mut font) = current_font {
-                            font.strikethrough = true;
+                        b"strike" if current_font.is_some() => {
+                            if let Some(ref mut font) = current_font {
+                                font.strikethrough = true;
+                            }
                         }
-                    }
-                    b"fill" => {
-                        current_fill = Some(Fill::default());
-                    }
-                    b"patternFill" if current_fill.is_some() => {
-                        if let Some(ref mut fill) = current_fill {
-                            for attr in e.attributes().flatten() {
-                                if attr.key.as_ref() == b"patternType" {
-                                    fill.pattern =
-                                        parse_fill_pattern(&String::from_utf8_lossy(&attr.value));
+                        b"fill" => {
+                            current_fill = Some(Fill::default());
+                        }
+                        b"patternFill" if current_fill.is_some() => {
+                            if let Some(ref mut fill) = current_fill {
+                                for attr in e.attributes().flatten() {
+                                    if attr.key.as_ref() == b"patternType" {
+                                        fill.pattern = parse_fill_pattern(
+                                            &String::from_utf8_lossy(&attr.value),
+                                        );
+                                    }
                                 }
                             }
                         }
-                    }
-                    b"border" => {
-                        current_border = Some(Border::default());
-                    }
-                    b"numFmt" => {
-                        let mut id: Option<u32> = None;
-                        let mut code: Option<String> = None;
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"numFmtId" => {
-                                    id = String::from_utf8_lossy(&attr.value).parse().ok();
-                                }
-                                b"formatCode" => {
-                                    code = Some(String::from_utf8_lossy(&attr.value).to_string());
+                        b"gradientFill" if current_fill.is_some() => {
+                            let mut gradient = GradientFill::default();
+                            for attr in e.attributes().flatten() {
+                                let val = String::from_utf8_lossy(&attr.value);
+                                match attr.key.as_ref() {
+                                    b"type" if val == "path" => {
+                                        gradient.gradient_type = GradientType::Path
+                                    }
+                                    b"degree" => gradient.degree = val.parse().unwrap_or(0.0),
+                                    b"left" => gradient.left = val.parse().unwrap_or(0.0),
+                                    b"right" => gradient.right = val.parse().unwrap_or(0.0),
+                                    b"top" => gradient.top = val.parse().unwrap_or(0.0),
+                                    b"bottom" => gradient.bottom = val.parse().unwrap_or(0.0),
+                                    _ => {}
                                 }
-                                _ => {}
+                            }
+                            if let Some(ref mut fill) = current_fill {
+                                fill.gradient = Some(gradient);
                             }
                         }
-                        if let (Some(id), Some(code)) = (id, code) {
-                            num_fmts.insert(id, code);
+                        b"stop" if current_fill.is_some() => {
+                            current_stop = e
+                                .attributes()
+                                .flatten()
+                                .find(|a| a.key.as_ref() == b"position")
+                                .and_then(|a| String::from_utf8_lossy(&a.value).parse().ok())
+                                .or(Some(0.0));
                         }
-                    }
-                    b"cellXfs" => {
-                        in_cell_xfs = true;
-                    }
-                    b"xf" if in_cell_xfs => {
-                        let mut font_id: usize = 0;
-                        let mut fill_id: usize = 0;
-                        let mut border_id: usize = 0;
-                        let mut num_fmt_id: u32 = 0;
-
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"fontId" => {
-                                    font_id =
-                                        String::from_utf8_lossy(&attr.value).parse().unwrap_or(0);
-                                }
-                                b"fillId" => {
-                                    fill_id =
-                                        String::from_utf8_lossy(&attr.value).parse().unwrap_or(0);
+                        b"color" if current_stop.is_some() => {
+                            let color = e
+                                .attributes()
+                                .flatten()
+                                .find(|a| a.key.as_ref() == b"rgb")
+                                .and_then(|a| Color::from_hex(&String::from_utf8_lossy(&a.value)));
+                            if let (Some(color), Some(position), Some(gradient)) = (
+                                color,
+                                current_stop,
+                                current_fill.as_mut().and_then(|f| f.gradient.as_mut()),
+                            ) {
+                                gradient.stops.push(GradientStop { position, color });
+                            }
+                        }
+                        b"border" => {
+                            let mut border = Border::default();
+                            for attr in e.attributes().flatten() {
+                                let on = matches!(attr.value.as_ref(), b"1" | b"true");
+                                match attr.key.as_ref() {
+                                    b"diagonalUp" => border.diagonal_up = on,
+                                    b"diagonalDown" => border.diagonal_down = on,
+                                    _ => {}
                                 }
-                                b"borderId" => {
-                                    border_id =
-                                        String::from_utf8_lossy(&attr.value).parse().unwrap_or(0);
+                            }
+                            current_border = Some(border);
+                        }
+                        b"numFmt" => {
+                            let mut id: Option<u32> = None;
+                            let mut code: Option<String> = None;
+                            for attr in e.attributes().flatten() {
+                                match attr.key.as_ref() {
+                                    b"numFmtId" => {
+                                        id = String::from_utf8_lossy(&attr.value).parse().ok();
+                                    }
+                                    b"formatCode" => {
+                                        code =
+                                            Some(String::from_utf8_lossy(&attr.value).to_string());
+                                    }
+                                    _ => {}
                                 }
-                                b"numFmtId" => {
-                                    num_fmt_id =
-                                        String::from_utf8_lossy(&attr.value).parse().unwrap_or(0);
+                            }
+                            if let (Some(id), Some(code)) = (id, code) {
+                                num_fmts.insert(id, code);
+                            }
+                        }
+                        b"cellXfs" => {
+                            in_cell_xfs = true;
+                        }
+                        b"xf" if in_cell_xfs => {
+                            let mut font_id: usize = 0;
+                            let mut fill_id: usize = 0;
+                            let mut border_id: usize = 0;
+                            let mut num_fmt_id: u32 = 0;
+
+                            for attr in e.attributes().flatten() {
+                                match attr.key.as_ref() {
+                                    b"fontId" => {
+                                        font_id = String::from_utf8_lossy(&attr.value)
+                                            .parse()
+                                            .unwrap_or(0);
+                                    }
+                                    b"fillId" => {
+                                        fill_id = String::from_utf8_lossy(&attr.value)
+                                            .parse()
+                                            .unwrap_or(0);
+                                    }
+                                    b"borderId" => {
+                                        border_id = String::from_utf8_lossy(&attr.value)
+                                            .parse()
+                                            .unwrap_or(0);
+                                    }
+                                    b"numFmtId" => {
+                                        num_fmt_id = String::from_utf8_lossy(&attr.value)
+                                            .parse()
+                                            .unwrap_or(0);
+                                    }
+                                    _ => {}
                                 }
-                                _ => {}
                             }
+                            cell_xfs.push((
+                                font_id,
+                                fill_id,
+                                border_id,
+                                num_fmt_id,
+                                None,
+                                None,
+                                false,
+                                false,
+                                Protection::default(),
+                            ));
                         }
-                        cell_xfs.push((font_id, fill_id, border_id, num_fmt_id, None, None, false));
-                    }
-                    b"alignment" if in_cell_xfs && !cell_xfs.is_empty() => {
-                        let last = cell_xfs.last_mut().unwrap();
-                        for attr in e.attributes().flatten() {
-                            match attr.key.as_ref() {
-                                b"horizontal" => {
-                                    let val = String::from_utf8_lossy(&attr.value);
-                                    last.4 = Some(match val.as_ref() {
-                                        "left" => HorizontalAlignment::Left,
-                                        "center" => HorizontalAlignment::Center,
-                                        "right" => HorizontalAlignment::Right,
-                                        "justify" => HorizontalAlignment::Justify,
-                                        _ => HorizontalAlignment::General,
-                                    });
+                        // Checkbox cell format, stored as an xf feature property bag complement
+                        _ if in_cell_xfs && e.local_name().as_ref() == b"xfComplement" => {
+                            if let Some(last) = cell_xfs.last_mut() {
+                                last.7 = true;
+                            }
+                        }
+                        b"protection" if in_cell_xfs && !cell_xfs.is_empty() => {
+                            let last = cell_xfs.last_mut().unwrap();
+                            for attr in e.attributes().flatten() {
+                                let on = matches!(attr.value.as_ref(), b"1" | b"true");
+                                match attr.key.as_ref() {
+                                    b"locked" => last.8.locked = on,
+                                    b"hidden" => last.8.hidden = on,
+                                    _ => {}
                                 }
-                                b"vertical" => {
-                                    let val = String::from_utf8_lossy(&attr.value);
-                                    last.5 = Some(match val.as_ref() {
-                                        "top" => VerticalAlignment::Top,
-                                        "center" => VerticalAlignment::Center,
-                                        "bottom" => VerticalAlignment::Bottom,
-                                        _ => VerticalAlignment::Center,
-                                    });
+                            }
+                        }
+                        b"alignment" if in_cell_xfs && !cell_xfs.is_empty() => {
+                            let last = cell_xfs.last_mut().unwrap();
+                            for attr in e.attributes().flatten() {
+                                match attr.key.as_ref() {
+                                    b"horizontal" => {
+                                        let val = String::from_utf8_lossy(&attr.value);
+                                        last.4 = Some(match val.as_ref() {
+                                            "left" => HorizontalAlignment::Left,
+                                            "center" => HorizontalAlignment::Center,
+                                            "right" => HorizontalAlignment::Right,
+                                            "justify" => HorizontalAlignment::Justify,
+                                            _ => HorizontalAlignment::General,
+                                        });
+                                    }
+                                    b"vertical" => {
+                                        let val = String::from_utf8_lossy(&attr.value);
+                                        last.5 = Some(match val.as_ref() {
+                                            "top" => VerticalAlignment::Top,
+                                            "center" => VerticalAlignment::Center,
+                                            "bottom" => VerticalAlignment::Bottom,
+                                            _ => VerticalAlignment::Center,
+                                        });
+                                    }
+                                    b"wrapText" => {
+                                        last.6 = String::from_utf8_lossy(&attr.value) == "1";
+                                    }
+                                    _ => {}
                                 }
-                                b"wrapText" => {
-                                    last.6 = String::from_utf8_lossy(&attr.value) == "1";
+                            }
+                        }
+                        b"color" if current_font.is_some() => {
+                            if let Some(ref mut font) = current_font {
+                                for attr in e.attributes().flatten() {
+                                    if attr.key.as_ref() == b"rgb" {
+                                        let val = String::from_utf8_lossy(&attr.value);
+                                        font.color = Color::from_hex(&val);
+                                    }
                                 }
-                                _ => {}
                             }
                         }
-                    }
-                    b"color" if current_font.is_some() => {
-                        if let Some(ref mut font) = current_font {
-                            for attr in e.attributes().flatten() {
-                                if attr.key.as_ref() == b"rgb" {
-                                    let val = String::from_utf8_lossy(&attr.value);
-                                    font.color = Color::from_hex(&val);
+                        b"fgColor" if current_fill.is_some() => {
+                            if let Some(ref mut fill) = current_fill {
+                                for attr in e.attributes().flatten() {
+                                    if attr.key.as_ref() == b"rgb" {
+                                        let val = String::from_utf8_lossy(&attr.value);
+                                        fill.fg_color = Color::from_hex(&val);
+                                    }
                                 }
                             }
                         }
-                    }
-                    b"fgColor" if current_fill.is_some() => {
-                        if let Some(ref mut fill) = current_fill {
-                            for attr in e.attributes().flatten() {
-                                if attr.key.as_ref() == b"rgb" {
-                                    let val = String::from_utf8_lossy(&attr.value);
-                                    fill.fg_color = Color::from_hex(&val);
+                        b"bgColor" if current_fill.is_some() => {
+                            if let Some(ref mut fill) = current_fill {
+                                for attr in e.attributes().flatten() {
+                                    if attr.key.as_ref() == b"rgb" {
+                                        let val = String::from_utf8_lossy(&attr.value);
+                                        fill.bg_color = Color::from_hex(&val);
+                                    }
                                 }
                             }
                         }
-                    }
-                    b"bgColor" if current_fill.is_some() => {
-                        if let Some(ref mut fill) = current_fill {
+                        b"left" | b"right" | b"top" | b"bottom" | b"diagonal"
+                            if current_border.is_some() =>
+                        {
+                            let side_name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                            let mut side = BorderSide::default();
                             for attr in e.attributes().flatten() {
-                                if attr.key.as_ref() == b"rgb" {
-                                    let val = String::from_utf8_lossy(&attr.value);
-                                    fill.bg_color = Color::from_hex(&val);
+                                if attr.key.as_ref() == b"style" {
+                                    side.style =
+                                        parse_border_style(&String::from_utf8_lossy(&attr.value));
+                                }
+                            }
+                            if is_empty {
+                                // Self-closing side, no color element follows
+                                if let Some(ref mut border) = current_border {
+                                    set_border_side(border, &side_name, side);
                                 }
+                            } else {
+                                current_border_side = Some((side_name, side));
                             }
                         }
-                    }
-                    b"left" | b"right" | b"top" | b"bottom" if current_border.is_some() => {
-                        let side_name = String::from_utf8_lossy(e.name().as_ref()).to_string();
-                        let mut side = BorderSide::default();
-                        for attr in e.attributes().flatten() {
-                            if attr.key.as_ref() == b"style" {
-                                let val = String::from_utf8_lossy(&attr.value);
-                                side.style = match val.as_ref() {
-                                    "thin" => BorderStyle::Thin,
-                                    "medium" => BorderStyle::Medium,
-                                    "thick" => BorderStyle::Thick,
-                                    "dashed" => BorderStyle::Dashed,
-                                    "dotted" => BorderStyle::Dotted,
-                                    "double" => BorderStyle::Double,
-                                    "hair" => BorderStyle::Hair,
-                                    _ => BorderStyle::None,
-                                };
+                        b"color" if current_border_side.is_some() => {
+                            if let Some((_, ref mut side)) = current_border_side {
+                                for attr in e.attributes().flatten() {
+                                    if attr.key.as_ref() == b"rgb" {
+                                        side.color =
+                                            Color::from_hex(&String::from_utf8_lossy(&attr.value));
+                                    }
+                                }
                             }
                         }
-                        current_border_side = Some((side_name, side));
+                        _ => {}
                     }
-                    _ => {}
-                },
+                }
                 Ok(Event::End(e)) => match e.name().as_ref() {
                     b"font" => {
                         if let Some(font) = current_font.take() {
@@ -260,22 +362,19 @@ impl StylesParser {
                             fills.push(fill);
                         }
                     }
+                    b"stop" => {
+                        current_stop = None;
+                    }
                     b"border" => {
                         if let Some(border) = current_border.take() {
                             borders.push(border);
                         }
                     }
-                    b"left" | b"right" | b"top" | b"bottom" => {
+                    b"left" | b"right" | b"top" | b"bottom" | b"diagonal" => {
                         if let (Some(ref mut border), Some((side_name, side))) =
                             (&mut current_border, current_border_side.take())
                         {
-                            match side_name.as_str() {
-                                "left" => border.left = side,
-                                "right" => border.right = side,
-                                "top" => border.top = side,
-                                "bottom" => border.bottom = side,
-                                _ => {}
-                            }
+                            set_border_side(border, &side_name, side);
                         }
                     }
                     b"cellXfs" => {
@@ -309,8 +408,20 @@ impl StylesParser {
         }
 
         // Build Style objects from cellXfs entries
-        for (idx, (font_id, fill_id, border_id, num_fmt_id, h_align, v_align, wrap_text)) in
-            cell_xfs.into_iter().enumerate()
+        for (
+            idx,
+            (
+                font_id,
+                fill_id,
+                border_id,
+                num_fmt_id,
+                h_align,
+                v_align,
+                wrap_text,
+                checkbox,
+                protection,
+            ),
+        ) in cell_xfs.into_iter().enumerate()
         {
             let font = fonts.get(font_id).cloned().unwrap_or_default();
             let fill = fills.get(fill_id).cloned().unwrap_or_default();
@@ -335,6 +446,8 @@ impl StylesParser {
                 text_rotation: None,
                 indent: None,
                 shrink_to_fit: false,
+                checkbox,
+                protection,
             };
 
             // Add style with the cellXfs index as the ID (starts from 0)
@@ -352,31 +465,20 @@ impl Default for StylesParser {
 }
 
 fn parse_fill_pattern(s: &str) -> FillPattern {
-    match s {
-        "none" => FillPattern::None,
-        "solid" => FillPattern::Solid,
-        "mediumGray" => FillPattern::MediumGray,
-        "darkGray" => FillPattern::DarkGray,
-        "lightGray" => FillPattern::LightGray,
-        "darkHorizontal" => FillPattern::DarkHorizontal,
-        "darkVertical" => FillPattern::DarkVertical,
-        "darkDown" => FillPattern::DarkDown,
-        "darkUp" => FillPattern::DarkUp,
-        "darkGrid" => FillPattern::DarkGrid,
-        "darkTrellis" => FillPattern::DarkTrellis,
-        "lightHorizontal" => FillPattern::LightHorizontal,
-        "lightVertical" => FillPattern::LightVertical,
-        "lightDown" => FillPattern::LightDown,
-        "lightUp" => FillPattern::LightUp,
-        "lightGrid" => FillPattern::LightGrid,
-        "lightTrellis" => FillPattern::LightTrellis,
-        "gray125" => FillPattern::Gray125,
-        "gray0625" => FillPattern::Gray0625,
-        _ => FillPattern::None,
+    FillPattern::parse(s).unwrap_or(FillPattern::None)
+}
+
+fn set_border_side(border: &mut Border, name: &str, side: BorderSide) {
+    match name {
+        "left" => border.left = side,
+        "right" => border.right = side,
+        "top" => border.top = side,
+        "bottom" => border.bottom = side,
+        "diagonal" => border.diagonal = side,
+        _ => {}
     }
 }
 
-#[allow(dead_code)]
 fn parse_border_style(s: &str) -> BorderStyle {
     match s {
         "none" => BorderStyle::None,
@@ -542,6 +644,62 @@ mod tests {
         assert_eq!(fills[3].pattern, FillPattern::MediumGray);
     }
 
+    #[test]
+    fn test_parse_gradient_fill() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+            <fills count="3">
+                <fill><gradientFill degree="90"><stop position="0"><color rgb="FFFFFFFF"/></stop><stop position="1"><color rgb="FF4472C4"/></stop></gradientFill></fill>
+                <fill><gradientFill type="path" left="0.5" right="0.5" top="0.5" bottom="0.5"><stop position="0"><color rgb="FFFF0000"/></stop></gradientFill></fill>
+                <fill><patternFill patternType="darkTrellis"><fgColor rgb="FF000000"/></patternFill></fill>
+            </fills>
+        </styleSheet>"#;
+
+        let parser = StylesParser::new();
+        let registry = parser.parse(Cursor::new(xml)).unwrap();
+
+        let fills = registry.fills();
+        let linear = fills[0].gradient.as_ref().unwrap();
+        assert_eq!(linear.gradient_type, GradientType::Linear);
+        assert_eq!(linear.degree, 90.0);
+        assert_eq!(linear.stops.len(), 2);
+        assert_eq!(linear.stops[1].position, 1.0);
+        assert_eq!(linear.stops[1].color.to_hex(), Some("4472C4".to_string()));
+
+        let path = fills[1].gradient.as_ref().unwrap();
+        assert_eq!(path.gradient_type, GradientType::Path);
+        assert_eq!(path.top, 0.5);
+
+        assert!(fills[2].gradient.is_none());
+        assert_eq!(fills[2].pattern, FillPattern::DarkTrellis);
+    }
+
+    #[test]
+    fn test_parse_border_sides_and_diagonal() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+            <borders count="1">
+                <border diagonalUp="1"><left style="thin"/><right/><top style="dashDot"><color rgb="FFFF0000"/></top><bottom style="medium"/><diagonal style="hair"><color rgb="FF0000FF"/></diagonal></border>
+            </borders>
+        </styleSheet>"#;
+
+        let parser = StylesParser::new();
+        let registry = parser.parse(Cursor::new(xml)).unwrap();
+
+        let border = &registry.borders()[0];
+        assert_eq!(border.left.style, BorderStyle::Thin);
+        assert_eq!(border.right.style, BorderStyle::None);
+        assert_eq!(border.top.style, BorderStyle::DashDot);
+        assert_eq!(
+            border.top.color.as_ref().and_then(|c| c.to_hex()),
+            Some("FF0000".to_string())
+        );
+        assert_eq!(border.bottom.style, BorderStyle::Medium);
+        assert_eq!(border.diagonal.style, BorderStyle::Hair);
+        assert!(border.diagonal_up);
+        assert!(!border.diagonal_down);
+    }
+
     #[test]
     fn test_parse_multiple_borders() {
         let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -0,0 +1,61 @@
+//! Parser strictness and spec violations found while parsing.
+
+use std::fmt;
+
+use crate::cell::CellRef;
+use crate::error::{ErrorContext, XlexError};
+
+/// How the parser treats spec violations in worksheet XML.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Recover from violations (drop or coerce the offending value) and
+    /// record them on the workbook
+    #[default]
+    Lenient,
+    /// Fail on the first violation
+    Strict,
+}
+
+/// A spec violation found while parsing, with its location in the package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Package part containing the violation (e.g., `xl/worksheets/sheet1.xml`)
+    pub part: String,
+    /// XML path to the offending element (e.g., `sheetData/row[@r="3"]/c[@r="B3"]`)
+    pub path: String,
+    /// Byte offset of the offending element within the part
+    pub offset: u64,
+    /// Cell the violation concerns, when its reference is valid
+    pub cell: Option<CellRef>,
+    /// What is wrong
+    pub message: String,
+}
+
+impl Violation {
+    /// Location of the violation as `part: path (byte N)`.
+    pub fn location(&self) -> String {
+        format!("{}: {} (byte {})", self.part, self.path, self.offset)
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location(), self.message)
+    }
+}
+
+impl From<Violation> for XlexError {
+    fn from(violation: Violation) -> Self {
+        let mut context = ErrorContext::default()
+            .part(violation.part)
+            .offset(violation.offset);
+        if let Some(cell) = violation.cell {
+            context = context.cell(cell.to_a1());
+        }
+        XlexError::ParseError {
+            message: violation.message,
+            location: violation.path,
+        }
+        .with_context(context)
+    }
+}
@@ -4,28 +4,145 @@ use std::collections::HashMap;
 use std::io::{BufRead, BufReader, Read, Seek};
 use std::path::PathBuf;
 
-use quick_xml::events::Event;
+use quick_xml::events::{BytesStart, Event};
 use quick_xml::Reader;
 use zip::ZipArchive;
 
-use crate::cell::{Cell, CellError, CellRef, CellValue};
-use crate::error::{XlexError, XlexResult};
-use crate::parser::{validate_xlsx_structure, LazySharedStrings, StylesParser};
-use crate::sheet::{Sheet, SheetInfo, SheetVisibility};
-use crate::style::StyleRegistry;
-use crate::workbook::{DefinedName, DocumentProperties, Workbook};
+use crate::cell::{ArrayFormula, Cell, CellError, CellRef, CellValue};
+use crate::error::{ErrorContext, XlexError, XlexResult};
+use crate::parser::{
+    validate_xlsx_structure, LazySharedStrings, ParseMode, StylesParser, Violation,
+};
+use crate::protection::{ProtectionPassword, SheetAction, SheetProtection};
+use crate::sheet::{Dropdown, Sheet, SheetImage, SheetInfo, SheetVisibility};
+use crate::sparkline::{Sparkline, SparklineGroup, SparklineType};
+use crate::style::{Color, StyleRegistry};
+use crate::workbook::{CustomProperty, DefinedName, DocumentProperties, ExternalLink, Workbook};
+
+/// A sheet parsed from its part, with its background relationship id and the
+/// violations recovered from.
+type SheetParts = (Sheet, Option<String>, Vec<Violation>);
+
+/// A parsed sheet with its index, name and part path.
+type ParsedSheet = (usize, String, String, XlexResult<SheetParts>);
+
+/// Incremental reader for `x14:sparklineGroup` elements in a worksheet.
+#[derive(Default)]
+struct SparklineReader {
+    groups: Vec<SparklineGroup>,
+    current: Option<SparklineGroup>,
+    data: String,
+    location: String,
+    in_formula: bool,
+    in_sqref: bool,
+}
+
+impl SparklineReader {
+    fn is_active(&self) -> bool {
+        self.current.is_some()
+    }
+
+    fn start(&mut self, e: &BytesStart) {
+        let color = || {
+            e.attributes().flatten().find_map(|a| {
+                let value = String::from_utf8_lossy(&a.value);
+                match a.key.as_ref() {
+                    b"rgb" => Color::from_hex(&value),
+                    b"theme" => value.parse().ok().map(Color::Theme),
+                    b"indexed" => value.parse().ok().map(Color::Indexed),
+                    _ => None,
+                }
+            })
+        };
+        match e.local_name().as_ref() {
+            b"sparklineGroup" => {
+                let mut group = SparklineGroup::new(SparklineType::Line);
+                for attr in e.attributes().flatten() {
+                    let value = String::from_utf8_lossy(&attr.value);
+                    match attr.key.as_ref() {
+                        b"type" => {
+                            group.sparkline_type = SparklineType::parse(&value).unwrap_or_default()
+                        }
+                        b"markers" => group.markers = value == "1" || value == "true",
+                        b"high" => group.high_low = value == "1" || value == "true",
+                        _ => {}
+                    }
+                }
+                self.current = Some(group);
+            }
+            b"colorSeries" => {
+                if let (Some(group), Some(color)) = (self.current.as_mut(), color()) {
+                    group.color = color;
+                }
+            }
+            b"colorNegative" => {
+                if let (Some(group), Some(color)) = (self.current.as_mut(), color()) {
+                    group.negative_color = color;
+                }
+            }
+            b"f" => {
+                self.in_formula = true;
+                self.data.clear();
+            }
+            b"sqref" => {
+                self.in_sqref = true;
+                self.location.clear();
+            }
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.in_formula {
+            self.data.push_str(text);
+        } else if self.in_sqref {
+            self.location.push_str(text);
+        }
+    }
+
+    fn end(&mut self, local_name: &[u8]) {
+        match local_name {
+            b"f" => self.in_formula = false,
+            b"sqref" => self.in_sqref = false,
+            b"sparkline" => {
+                if let (Some(group), Ok(location)) =
+                    (self.current.as_mut(), CellRef::parse(self.location.trim()))
+                {
+                    group.sparklines.push(Sparkline {
+                        data: self.data.trim().to_string(),
+                        location,
+                    });
+                }
+            }
+            b"sparklineGroup" => {
+                if let Some(group) = self.current.take() {
+                    self.groups.push(group);
+                }
+            }
+            _ => {}
+        }
+    }
+}
 
 /// Parser for xlsx workbooks.
 pub struct WorkbookParser {
     /// Styles parser
     styles_parser: StylesParser,
+    /// How spec violations are treated
+    mode: ParseMode,
 }
 
 impl WorkbookParser {
     /// Creates a new workbook parser.
     pub fn new() -> Self {
+        Self::with_mode(ParseMode::Lenient)
+    }
+
+    /// Creates a workbook parser with the given strictness.
+    pub fn with_mode(mode: ParseMode) -> Self {
         Self {
             styles_parser: StylesParser::new(),
+            mode,
         }
     }
 
@@ -35,28 +152,35 @@ impl WorkbookParser {
         archive: &mut ZipArchive<R>,
         path: Option<PathBuf>,
     ) -> XlexResult<Workbook> {
+        let _span = tracing::info_span!("parse_workbook").entered();
+
         // Validate structure
         validate_xlsx_structure(archive)?;
 
         // Parse shared strings lazily if present
+        let ss_span = tracing::info_span!("load_shared_strings").entered();
         let mut lazy_strings = if let Ok(mut file) = archive.by_name("xl/sharedStrings.xml") {
             let mut data = Vec::new();
-            file.read_to_end(&mut data)
-                .map_err(|e| XlexError::IoError {
-                    message: e.to_string(),
-                    source: Some(e),
-                })?;
-            LazySharedStrings::from_bytes_default(data)?
+            file.read_to_end(&mut data).map_err(|e| {
+                XlexError::from(e)
+                    .with_context(ErrorContext::default().part("xl/sharedStrings.xml"))
+            })?;
+            LazySharedStrings::from_bytes_default(data)
+                .map_err(|e| e.with_context(ErrorContext::default().part("xl/sharedStrings.xml")))?
         } else {
             LazySharedStrings::default()
         };
 
         // Convert to Vec for backward compatibility (will be optimized later)
         let shared_strings = lazy_strings.to_vec();
+        drop(ss_span);
 
         // Parse styles if present
         let style_registry = if let Ok(file) = archive.by_name("xl/styles.xml") {
-            self.styles_parser.parse(BufReader::new(file))?
+            let _span = tracing::info_span!("parse_styles").entered();
+            self.styles_parser
+                .parse(BufReader::new(file))
+                .map_err(|e| e.with_context(ErrorContext::default().part("xl/styles.xml")))?
         } else {
             StyleRegistry::new()
         };
@@ -65,13 +189,15 @@ impl WorkbookParser {
         let properties = self.parse_properties(archive)?;
 
         // Parse workbook.xml to get sheet info and defined names
-        let (sheet_infos, defined_names) = self.parse_workbook_xml_full(archive)?;
+        let (sheet_infos, mut defined_names, external_rel_ids) = self
+            .parse_workbook_xml_full(archive)
+            .map_err(|e| e.with_context(ErrorContext::default().part("xl/workbook.xml")))?;
 
         // Parse relationships to get sheet file paths
         let relationships = self.parse_relationships(archive)?;
 
         // Collect sheet data (path + XML bytes) for parallel parsing
-        let sheet_data: Vec<(usize, SheetInfo, Vec<u8>)> = sheet_infos
+        let sheet_data: Vec<(usize, SheetInfo, String, Vec<u8>)> = sheet_infos
             .into_iter()
             .enumerate()
             .map(|(index, info)| {
@@ -81,64 +207,107 @@ impl WorkbookParser {
                     .unwrap_or_else(|| format!("xl/worksheets/sheet{}.xml", index + 1));
 
                 if let Ok(mut file) = archive.by_name(&sheet_path) {
+                    let _span =
+                        tracing::info_span!("read_zip_entry", entry = %sheet_path).entered();
                     let mut data = Vec::new();
-                    if file.read_to_end(&mut data).is_ok() {
-                        return (index, info, data);
-                    }
+                    // A part that fails to decompress or its CRC check is
+                    // damaged, not empty; reading on would save the blank
+                    // sheet over the real data.
+                    file.read_to_end(&mut data).map_err(|e| {
+                        XlexError::InvalidZipStructure {
+                            message: format!("failed to read {}: {}", sheet_path, e),
+                        }
+                        .with_context(ErrorContext::default().part(&sheet_path))
+                    })?;
+                    return Ok((index, info, sheet_path, data));
                 }
                 // Return empty data for missing sheets
-                (index, info, Vec::new())
+                Ok((index, info, sheet_path, Vec::new()))
             })
-            .collect();
+            .collect::<XlexResult<_>>()?;
 
         // Parse sheets (parallel when feature enabled, sequential otherwise)
         #[cfg(feature = "parallel")]
-        let parsed_sheets: Vec<(usize, String, XlexResult<Sheet>)> = {
+        let parsed_sheets: Vec<ParsedSheet> = {
             use rayon::prelude::*;
             sheet_data
                 .into_par_iter()
-                .map(|(index, info, data)| {
+                .map(|(index, info, sheet_path, data)| {
                     let name = info.name.clone();
                     let sheet = if data.is_empty() {
-                        Ok(Sheet::new(info))
+                        Ok((Sheet::new(info), None, Vec::new()))
                     } else {
-                        self.parse_sheet(std::io::Cursor::new(data), info, &shared_strings)
+                        self.parse_sheet(
+                            std::io::Cursor::new(data),
+                            info,
+                            &sheet_path,
+                            &shared_strings,
+                        )
                     };
-                    (index, name, sheet)
+                    (index, name, sheet_path, sheet)
                 })
                 .collect()
         };
 
         #[cfg(not(feature = "parallel"))]
-        let parsed_sheets: Vec<(usize, String, XlexResult<Sheet>)> = sheet_data
+        let parsed_sheets: Vec<ParsedSheet> = sheet_data
             .into_iter()
-            .map(|(index, info, data)| {
+            .map(|(index, info, sheet_path, data)| {
                 let name = info.name.clone();
                 let sheet = if data.is_empty() {
-                    Ok(Sheet::new(info))
+                    Ok((Sheet::new(info), None, Vec::new()))
                 } else {
-                    self.parse_sheet(std::io::Cursor::new(data), info, &shared_strings)
+                    self.parse_sheet(
+                        std::io::Cursor::new(data),
+                        info,
+                        &sheet_path,
+                        &shared_strings,
+                    )
                 };
-                (index, name, sheet)
+                (index, name, sheet_path, sheet)
             })
             .collect();
 
         // Build sheets vector and map, maintaining order
         let mut sheets = Vec::with_capacity(parsed_sheets.len());
         let mut sheet_map = HashMap::new();
+        let mut violations = Vec::new();
 
         // Sort by index to maintain original order
         let mut sorted_sheets = parsed_sheets;
-        sorted_sheets.sort_by_key(|(idx, _, _)| *idx);
-
-        for (index, name, sheet_result) in sorted_sheets {
-            let sheet = sheet_result?;
+        sorted_sheets.sort_by_key(|(idx, _, _, _)| *idx);
+
+        for (index, name, sheet_path, sheet_result) in sorted_sheets {
+            let (mut sheet, picture_rel, sheet_violations) =
+                sheet_result.map_err(|e| e.with_context(ErrorContext::default().sheet(&name)))?;
+            violations.extend(sheet_violations);
+            if let Some(rel_id) = picture_rel {
+                let background = self.load_sheet_image(archive, &sheet_path, &rel_id);
+                sheet.set_background(background);
+            }
             sheet_map.insert(name, index);
             sheets.push(sheet);
         }
 
+        // Built-in print names are kept on their sheet so they follow renames and moves
+        defined_names.retain(
+            |dn| match dn.local_sheet_id.and_then(|i| sheets.get_mut(i)) {
+                Some(sheet) => !sheet.apply_print_name(&dn.name, &dn.reference),
+                None => true,
+            },
+        );
+
+        // External workbook links, in the order formulas number them ([1], [2], ...)
+        let external_links: Vec<ExternalLink> = external_rel_ids
+            .iter()
+            .filter_map(|rel_id| relationships.get(rel_id))
+            .map(|target| self.load_external_link(archive, target))
+            .collect();
+
+        let calc_chain = self.load_calc_chain(archive, &sheets);
+
         // Construct workbook using the internal constructor
-        Ok(Workbook::__from_parts(
+        let mut workbook = Workbook::__from_parts(
             path,
             properties,
             sheets,
@@ -148,7 +317,11 @@ impl WorkbookParser {
             defined_names,
             0,
             false,
-        ))
+        );
+        workbook.set_parsed_external_links(external_links);
+        workbook.set_parsed_calc_chain(calc_chain);
+        workbook.set_parse_violations(violations);
+        Ok(workbook)
     }
 
     /// Parses document properties from core.xml and app.xml.
@@ -200,6 +373,50 @@ impl WorkbookParser {
             }
         }
 
+        // Parse custom.xml for custom properties
+        if let Ok(file) = archive.by_name("docProps/custom.xml") {
+            let mut reader = Reader::from_reader(BufReader::new(file));
+            let mut buf = Vec::new();
+            let mut current: Option<CustomProperty> = None;
+
+            loop {
+                match reader.read_event_into(&mut buf) {
+                    Ok(Event::Start(e)) if e.local_name().as_ref() == b"property" => {
+                        let name = e
+                            .try_get_attribute("name")
+                            .ok()
+                            .flatten()
+                            .and_then(|a| a.unescape_value().ok())
+                            .unwrap_or_default()
+                            .to_string();
+                        current = Some(CustomProperty {
+                            name,
+                            value: String::new(),
+                            value_type: String::new(),
+                        });
+                    }
+                    Ok(Event::Start(e)) => {
+                        if let Some(property) = current.as_mut() {
+                            property.value_type =
+                                String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                        }
+                    }
+                    Ok(Event::Text(e)) => {
+                        if let Some(property) = current.as_mut() {
+                            property.value.push_str(&e.unescape().unwrap_or_default());
+                        }
+                    }
+                    Ok(Event::End(e)) if e.local_name().as_ref() == b"property" => {
+                        props.custom.extend(current.take());
+                    }
+                    Ok(Event::Eof) => break,
+                    Err(_) => break,
+                    _ => {}
+                }
+                buf.clear();
+            }
+        }
+
         Ok(props)
     }
 
@@ -207,13 +424,14 @@ impl WorkbookParser {
     fn parse_workbook_xml_full<R: Read + Seek>(
         &self,
         archive: &mut ZipArchive<R>,
-    ) -> XlexResult<(Vec<SheetInfo>, Vec<DefinedName>)> {
+    ) -> XlexResult<(Vec<SheetInfo>, Vec<DefinedName>, Vec<String>)> {
         let file = archive.by_name("xl/workbook.xml")?;
         let mut reader = Reader::from_reader(BufReader::new(file));
         reader.config_mut().trim_text(true);
 
         let mut sheets = Vec::new();
         let mut defined_names = Vec::new();
+        let mut external_rel_ids = Vec::new();
         let mut buf = Vec::new();
         let mut in_defined_name = false;
         let mut current_defined_name: Option<DefinedName> = None;
@@ -229,7 +447,7 @@ impl WorkbookParser {
                     for attr in e.attributes().flatten() {
                         match attr.key.as_ref() {
                             b"name" => {
-                                name = String::from_utf8_lossy(&attr.value).to_string();
+                                name = attr.unescape_value().unwrap_or_default().to_string();
                             }
                             b"sheetId" => {
                                 sheet_id =
@@ -256,6 +474,17 @@ impl WorkbookParser {
                         sheets.push(info);
                     }
                 }
+                Ok(Event::Empty(e)) | Ok(Event::Start(e))
+                    if e.name().as_ref() == b"externalReference" =>
+                {
+                    if let Some(attr) = e
+                        .attributes()
+                        .flatten()
+                        .find(|a| a.key.local_name().as_ref() == b"id")
+                    {
+                        external_rel_ids.push(String::from_utf8_lossy(&attr.value).to_string());
+                    }
+                }
                 Ok(Event::Start(e)) if e.name().as_ref() == b"definedName" => {
                     in_defined_name = true;
                     let mut name = String::new();
@@ -320,17 +549,158 @@ impl WorkbookParser {
             });
         }
 
-        Ok((sheets, defined_names))
+        Ok((sheets, defined_names, external_rel_ids))
     }
 
     /// Parses relationships from xl/_rels/workbook.xml.rels.
     fn parse_relationships<R: Read + Seek>(
         &self,
         archive: &mut ZipArchive<R>,
+    ) -> XlexResult<HashMap<String, String>> {
+        self.parse_rels_part(archive, "xl/_rels/workbook.xml.rels")
+    }
+
+    /// Loads the image a sheet relationship points at, resolving the target
+    /// relative to the sheet part. Unreadable or unsupported images are skipped.
+    fn load_sheet_image<R: Read + Seek>(
+        &self,
+        archive: &mut ZipArchive<R>,
+        sheet_path: &str,
+        rel_id: &str,
+    ) -> Option<SheetImage> {
+        let (dir, file_name) = sheet_path.rsplit_once('/')?;
+        let rels_path = format!("{}/_rels/{}.rels", dir, file_name);
+        let target = self
+            .parse_rels_part(archive, &rels_path)
+            .ok()?
+            .remove(rel_id)?;
+
+        let image_path = super::resolve_part_path(dir, &target);
+
+        let mut data = Vec::new();
+        archive
+            .by_name(&image_path)
+            .ok()?
+            .read_to_end(&mut data)
+            .ok()?;
+        let extension = image_path.rsplit_once('.').map(|(_, e)| e)?;
+        SheetImage::new(data, extension).ok()
+    }
+
+    /// Reads `xl/calcChain.xml` as `(sheet, cell)` pairs in calculation order.
+    ///
+    /// Each `<c>` names its sheet by `sheetId` in `i`, which carries over
+    /// from the previous entry when omitted. Entries for unknown sheets or
+    /// invalid references are skipped; the chain is rebuilt on save anyway.
+    fn load_calc_chain<R: Read + Seek>(
+        &self,
+        archive: &mut ZipArchive<R>,
+        sheets: &[Sheet],
+    ) -> Vec<(String, CellRef)> {
+        let mut data = Vec::new();
+        match archive.by_name("xl/calcChain.xml") {
+            Ok(mut file) => {
+                if file.read_to_end(&mut data).is_err() {
+                    return Vec::new();
+                }
+            }
+            Err(_) => return Vec::new(),
+        }
+
+        let names: HashMap<u32, &str> = sheets
+            .iter()
+            .map(|sheet| (sheet.info.sheet_id, sheet.name()))
+            .collect();
+        let mut chain = Vec::new();
+        let mut sheet_id = 0;
+        let mut reader = Reader::from_reader(data.as_slice());
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.local_name().as_ref() == b"c" => {
+                    let mut cell = None;
+                    for attr in e.attributes().flatten() {
+                        match attr.key.local_name().as_ref() {
+                            b"r" => {
+                                cell = CellRef::parse(&String::from_utf8_lossy(&attr.value)).ok()
+                            }
+                            b"i" => {
+                                sheet_id = String::from_utf8_lossy(&attr.value).parse().unwrap_or(0)
+                            }
+                            _ => {}
+                        }
+                    }
+                    if let (Some(cell), Some(name)) = (cell, names.get(&sheet_id)) {
+                        chain.push((name.to_string(), cell));
+                    }
+                }
+                Ok(Event::Eof) | Err(_) => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+        chain
+    }
+
+    /// Loads an externalLink part and the path of the workbook it points at.
+    fn load_external_link<R: Read + Seek>(
+        &self,
+        archive: &mut ZipArchive<R>,
+        target: &str,
+    ) -> ExternalLink {
+        let part_path = match target.strip_prefix('/') {
+            Some(absolute) => absolute.to_string(),
+            None => format!("xl/{}", target),
+        };
+        let mut part = Vec::new();
+        if let Ok(mut file) = archive.by_name(&part_path) {
+            let _ = file.read_to_end(&mut part);
+        }
+
+        // The externalBook element names the relationship holding the path
+        let mut book_rel_id = String::new();
+        let mut reader = Reader::from_reader(part.as_slice());
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e))
+                    if e.local_name().as_ref() == b"externalBook" =>
+                {
+                    if let Some(attr) = e
+                        .attributes()
+                        .flatten()
+                        .find(|a| a.key.local_name().as_ref() == b"id")
+                    {
+                        book_rel_id = String::from_utf8_lossy(&attr.value).to_string();
+                    }
+                    break;
+                }
+                Ok(Event::Eof) | Err(_) => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        let (dir, file_name) = part_path.rsplit_once('/').unwrap_or(("", &part_path));
+        let rels_path = format!("{}/_rels/{}.rels", dir, file_name);
+        let book_target = self
+            .parse_rels_part(archive, &rels_path)
+            .ok()
+            .and_then(|mut rels| rels.remove(&book_rel_id))
+            .unwrap_or_default();
+
+        ExternalLink::from_part(book_target, part, book_rel_id)
+    }
+
+    /// Parses a relationships part into an id -> target map.
+    fn parse_rels_part<R: Read + Seek>(
+        &self,
+        archive: &mut ZipArchive<R>,
+        rels_path: &str,
     ) -> XlexResult<HashMap<String, String>> {
         let mut relationships = HashMap::new();
 
-        let file = match archive.by_name("xl/_rels/workbook.xml.rels") {
+        let file = match archive.by_name(rels_path) {
             Ok(f) => f,
             Err(_) => return Ok(relationships),
         };
@@ -374,14 +744,21 @@ impl WorkbookParser {
         Ok(relationships)
     }
 
-    /// Parses a worksheet XML file.
+    /// Parses a worksheet XML file stored at `part`.
     fn parse_sheet<R: Read + BufRead>(
         &self,
         reader: R,
         info: SheetInfo,
+        part: &str,
         shared_strings: &[String],
-    ) -> XlexResult<Sheet> {
+    ) -> XlexResult<SheetParts> {
+        let _span = tracing::info_span!("parse_sheet", sheet = %info.name).entered();
         let mut sheet = Sheet::new(info);
+        let mut picture_rel: Option<String> = None;
+        let mut sparklines = SparklineReader::default();
+        let mut list_validation: Option<Vec<crate::range::Range>> = None;
+        let mut validation_formula = String::new();
+        let mut in_validation_formula = false;
         let mut xml_reader = Reader::from_reader(reader);
         xml_reader.config_mut().trim_text(true);
 
@@ -389,15 +766,79 @@ impl WorkbookParser {
         let mut current_cell_ref: Option<CellRef> = None;
         let mut current_cell_type: Option<String> = None;
         let mut current_cell_style: Option<u32> = None;
+        let mut current_cell_metadata = false;
+        let mut current_formula_kind = FormulaKind::Normal;
+        // Shared formula groups by `si` index: first cell and its formula
+        let mut shared_formulas: HashMap<u32, (CellRef, String)> = HashMap::new();
         let mut current_value = String::new();
         let mut current_formula = String::new();
         let mut current_inline_string = String::new();
         let mut in_value = false;
         let mut in_formula = false;
         let mut in_inline_string = false;
+        let mut violations = Vec::new();
+        let mut current_row: Option<u32> = None;
+        let mut current_row_attr = String::new();
+        let mut current_cell_attr = String::new();
+        let mut cell_offset = 0;
 
         loop {
-            match xml_reader.read_event_into(&mut buf) {
+            let event = xml_reader.read_event_into(&mut buf);
+            // Offset of the element just read: its tag spans `<`..`>` or `<`..`/>`
+            let offset = match &event {
+                Ok(Event::Start(e)) => xml_reader.buffer_position() - e.len() as u64 - 2,
+                Ok(Event::Empty(e)) => xml_reader.buffer_position() - e.len() as u64 - 3,
+                _ => xml_reader.buffer_position(),
+            };
+            let violation = |path: String, message: String| Violation {
+                part: part.to_string(),
+                path,
+                offset,
+                message,
+                cell: None,
+            };
+            match event {
+                Ok(Event::Start(e))
+                    if sparklines.is_active() || e.local_name().as_ref() == b"sparklineGroup" =>
+                {
+                    sparklines.start(&e);
+                }
+                Ok(Event::Empty(e)) if sparklines.is_active() => sparklines.start(&e),
+                Ok(Event::Text(e)) if sparklines.is_active() => {
+                    sparklines.text(&e.unescape().unwrap_or_default());
+                }
+                Ok(Event::End(e)) if sparklines.is_active() => {
+                    sparklines.end(e.local_name().as_ref());
+                }
+                Ok(Event::Empty(e)) if e.name().as_ref() == b"c" => {
+                    // Self-closing cell: no value, but may carry a style
+                    let mut cell_ref = None;
+                    let mut style_id = None;
+                    for attr in e.attributes().flatten() {
+                        match attr.key.as_ref() {
+                            b"r" => {
+                                let ref_str = String::from_utf8_lossy(&attr.value);
+                                cell_ref = self.check_cell_ref(
+                                    &ref_str,
+                                    current_row,
+                                    &sheet,
+                                    &mut violations,
+                                    |message| {
+                                        violation(cell_path(&current_row_attr, &ref_str), message)
+                                    },
+                                )?;
+                            }
+                            b"s" => style_id = String::from_utf8_lossy(&attr.value).parse().ok(),
+                            _ => {}
+                        }
+                    }
+                    if let (Some(cell_ref), Some(style_id)) = (cell_ref, style_id) {
+                        sheet.insert_cell(Cell::empty(cell_ref).with_style(style_id));
+                    }
+                }
+                Ok(Event::Empty(e)) if e.name().as_ref() == b"f" => {
+                    current_formula_kind = formula_kind(&e);
+                }
                 Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
                     match e.name().as_ref() {
                         b"c" => {
@@ -406,7 +847,20 @@ impl WorkbookParser {
                                 match attr.key.as_ref() {
                                     b"r" => {
                                         let ref_str = String::from_utf8_lossy(&attr.value);
-                                        current_cell_ref = CellRef::parse(&ref_str).ok();
+                                        current_cell_ref = self.check_cell_ref(
+                                            &ref_str,
+                                            current_row,
+                                            &sheet,
+                                            &mut violations,
+                                            |message| {
+                                                violation(
+                                                    cell_path(&current_row_attr, &ref_str),
+                                                    message,
+                                                )
+                                            },
+                                        )?;
+                                        current_cell_attr = ref_str.into_owned();
+                                        cell_offset = offset;
                                     }
                                     b"t" => {
                                         current_cell_type =
@@ -416,6 +870,7 @@ impl WorkbookParser {
                                         current_cell_style =
                                             String::from_utf8_lossy(&attr.value).parse().ok();
                                     }
+                                    b"cm" => current_cell_metadata = true,
                                     _ => {}
                                 }
                             }
@@ -427,6 +882,7 @@ impl WorkbookParser {
                         b"f" => {
                             in_formula = true;
                             current_formula.clear();
+                            current_formula_kind = formula_kind(&e);
                         }
                         b"t" => {
                             // Text element inside <is> for inline strings
@@ -434,18 +890,223 @@ impl WorkbookParser {
                             current_inline_string.clear();
                         }
                         b"row" => {
-                            // Could parse row attributes (height, hidden) here
+                            let mut row = None;
+                            let mut height = None;
+                            let mut custom_height = false;
+                            let mut hidden = false;
+                            let mut style = None;
+                            let mut custom_format = false;
+                            for attr in e.attributes().flatten() {
+                                let value = String::from_utf8_lossy(&attr.value);
+                                match attr.key.as_ref() {
+                                    b"r" => {
+                                        let path =
+                                            format!("worksheet/sheetData/row[@r=\"{}\"]", value);
+                                        row = match value.parse::<u32>() {
+                                            Ok(r) if (1..=MAX_ROWS).contains(&r) => {
+                                                if let Some(previous) =
+                                                    current_row.filter(|&previous| r <= previous)
+                                                {
+                                                    self.report(
+                                                        &mut violations,
+                                                        violation(
+                                                            path,
+                                                            format!(
+                                                                "Row {} is out of order (after row {})",
+                                                                r, previous
+                                                            ),
+                                                        ),
+                                                    )?;
+                                                }
+                                                Some(r)
+                                            }
+                                            _ => {
+                                                self.report(
+                                                    &mut violations,
+                                                    violation(
+                                                        path,
+                                                        format!("Invalid row number '{}'", value),
+                                                    ),
+                                                )?;
+                                                None
+                                            }
+                                        };
+                                        current_row = row;
+                                        current_row_attr = value.into_owned();
+                                    }
+                                    b"ht" => height = value.parse::<f64>().ok(),
+                                    b"customHeight" => {
+                                        custom_height = value == "1" || value == "true"
+                                    }
+                                    b"hidden" => hidden = value == "1" || value == "true",
+                                    b"s" => style = value.parse::<u32>().ok(),
+                                    b"customFormat" => {
+                                        custom_format = value == "1" || value == "true"
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            if let Some(row) = row {
+                                // Row styles only apply with customFormat set
+                                if let Some(style) = style.filter(|_| custom_format) {
+                                    sheet.set_row_style(row, Some(style));
+                                }
+                                if let Some(height) = height.filter(|_| custom_height) {
+                                    sheet.set_row_height(row, height);
+                                }
+                                if hidden {
+                                    sheet.set_row_hidden(row, true);
+                                }
+                            }
                         }
                         b"col" => {
-                            // Could parse column attributes (width, hidden) here
+                            let mut min = None;
+                            let mut max = None;
+                            let mut width = None;
+                            let mut hidden = false;
+                            let mut style = None;
+                            for attr in e.attributes().flatten() {
+                                let value = String::from_utf8_lossy(&attr.value);
+                                match attr.key.as_ref() {
+                                    b"min" => min = value.parse::<u32>().ok(),
+                                    b"max" => max = value.parse::<u32>().ok(),
+                                    b"width" => width = value.parse::<f64>().ok(),
+                                    b"hidden" => hidden = value == "1" || value == "true",
+                                    b"style" => style = value.parse::<u32>().ok(),
+                                    _ => {}
+                                }
+                            }
+                            // Columns are stored one by one, so skip spans
+                            // covering the rest of the sheet (e.g., 5..=16384);
+                            // a style on such a span becomes the sheet default
+                            if let (Some(min), Some(max)) = (min, max) {
+                                if min >= 1 && max >= min && max - min < MAX_COLUMN_SPAN {
+                                    for col in min..=max {
+                                        if let Some(width) = width {
+                                            sheet.set_column_width(col, width);
+                                        }
+                                        if hidden {
+                                            sheet.set_column_hidden(col, true);
+                                        }
+                                        if style.is_some() {
+                                            sheet.set_column_style(col, style);
+                                        }
+                                    }
+                                } else if min >= 1 && max >= min && sheet.default_style().is_none()
+                                {
+                                    sheet.set_default_style(style);
+                                }
+                            }
+                        }
+                        b"dataValidation" => {
+                            let mut is_list = false;
+                            let mut ranges = Vec::new();
+                            for attr in e.attributes().flatten() {
+                                let value = String::from_utf8_lossy(&attr.value);
+                                match attr.key.as_ref() {
+                                    b"type" => is_list = value == "list",
+                                    b"sqref" => {
+                                        ranges = Vec::new();
+                                        for r in value.split_whitespace() {
+                                            match crate::range::Range::parse(r) {
+                                                Ok(range) => ranges.push(range),
+                                                Err(_) => self.report(
+                                                    &mut violations,
+                                                    violation(
+                                                        format!(
+                                                            "worksheet/dataValidations/dataValidation[@sqref=\"{}\"]",
+                                                            value
+                                                        ),
+                                                        format!("Invalid range '{}'", r),
+                                                    ),
+                                                )?,
+                                            }
+                                        }
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            // Only list validations (dropdowns) are modelled
+                            list_validation = is_list.then_some(ranges);
+                            validation_formula.clear();
+                        }
+                        b"formula1" if list_validation.is_some() => {
+                            in_validation_formula = true;
+                        }
+                        b"picture" => {
+                            for attr in e.attributes().flatten() {
+                                if attr.key.local_name().as_ref() == b"id" {
+                                    picture_rel =
+                                        Some(String::from_utf8_lossy(&attr.value).to_string());
+                                }
+                            }
+                        }
+                        b"dimension" => {
+                            for attr in e.attributes().flatten() {
+                                if attr.key.as_ref() == b"ref" {
+                                    let ref_str = String::from_utf8_lossy(&attr.value);
+                                    match crate::range::Range::parse(&ref_str) {
+                                        Ok(range) => sheet.set_declared_dimension(Some(range)),
+                                        Err(_) => self.report(
+                                            &mut violations,
+                                            violation(
+                                                format!(
+                                                    "worksheet/dimension[@ref=\"{}\"]",
+                                                    ref_str
+                                                ),
+                                                format!("Invalid range '{}'", ref_str),
+                                            ),
+                                        )?,
+                                    }
+                                }
+                            }
+                        }
+                        b"pane" => {
+                            let mut frozen = false;
+                            let (mut rows, mut cols) = (0, 0);
+                            for attr in e.attributes().flatten() {
+                                let value = String::from_utf8_lossy(&attr.value);
+                                match attr.key.as_ref() {
+                                    b"state" => frozen = value.starts_with("frozen"),
+                                    b"xSplit" => cols = value.parse::<f64>().unwrap_or(0.0) as u32,
+                                    b"ySplit" => rows = value.parse::<f64>().unwrap_or(0.0) as u32,
+                                    _ => {}
+                                }
+                            }
+                            // Unfrozen splits are measured in twips, not cells
+                            if frozen {
+                                sheet.set_freeze_panes(rows, cols);
+                            }
+                        }
+                        b"sheetProtection" => {
+                            sheet.set_protection(parse_sheet_protection(&e));
+                        }
+                        b"autoFilter" => {
+                            for attr in e.attributes().flatten() {
+                                if attr.key.as_ref() == b"ref" {
+                                    let ref_str = String::from_utf8_lossy(&attr.value);
+                                    sheet
+                                        .set_auto_filter(crate::range::Range::parse(&ref_str).ok());
+                                }
+                            }
                         }
                         b"mergeCell" => {
                             // Parse merged cell range
                             for attr in e.attributes().flatten() {
                                 if attr.key.as_ref() == b"ref" {
                                     let ref_str = String::from_utf8_lossy(&attr.value);
-                                    if let Ok(range) = crate::range::Range::parse(&ref_str) {
-                                        sheet.add_merged_range(range);
+                                    match crate::range::Range::parse(&ref_str) {
+                                        Ok(range) => sheet.add_merged_range(range),
+                                        Err(_) => self.report(
+                                            &mut violations,
+                                            violation(
+                                                format!(
+                                                    "worksheet/mergeCells/mergeCell[@ref=\"{}\"]",
+                                                    ref_str
+                                                ),
+                                                format!("Invalid range '{}'", ref_str),
+                                            ),
+                                        )?,
                                     }
                                 }
                             }
@@ -460,6 +1121,8 @@ impl WorkbookParser {
                         current_formula.push_str(&e.unescape().unwrap_or_default());
                     } else if in_inline_string {
                         current_inline_string.push_str(&e.unescape().unwrap_or_default());
+                    } else if in_validation_formula {
+                        validation_formula.push_str(&e.unescape().unwrap_or_default());
                     }
                 }
                 Ok(Event::End(e)) => {
@@ -473,6 +1136,42 @@ impl WorkbookParser {
                                 } else {
                                     &current_value
                                 };
+                                if let Some(message) = value_violation(
+                                    value_str,
+                                    current_cell_type.as_deref(),
+                                    shared_strings,
+                                ) {
+                                    self.report(
+                                        &mut violations,
+                                        Violation {
+                                            part: part.to_string(),
+                                            path: cell_path(&current_row_attr, &current_cell_attr),
+                                            offset: cell_offset,
+                                            message: format!("{} in {}", message, cell_ref),
+                                            cell: Some(cell_ref.clone()),
+                                        },
+                                    )?;
+                                }
+                                // Shared formulas store their text on the group's
+                                // first cell only; the others take it shifted by
+                                // their offset from that cell
+                                if let FormulaKind::Shared(index) = current_formula_kind {
+                                    if current_formula.is_empty() {
+                                        if let Some((first, formula)) = shared_formulas.get(&index)
+                                        {
+                                            current_formula = crate::formula::shift_refs(
+                                                formula,
+                                                cell_ref.col as i64 - first.col as i64,
+                                                cell_ref.row as i64 - first.row as i64,
+                                            );
+                                        }
+                                    } else {
+                                        shared_formulas.insert(
+                                            index,
+                                            (cell_ref.clone(), current_formula.clone()),
+                                        );
+                                    }
+                                }
                                 let value = self.parse_cell_value(
                                     value_str,
                                     &current_formula,
@@ -484,6 +1183,16 @@ impl WorkbookParser {
                                 if let Some(style_id) = current_cell_style {
                                     cell = cell.with_style(style_id);
                                 }
+                                if let FormulaKind::Array(range) = &current_formula_kind {
+                                    if !current_formula.is_empty() {
+                                        // Cell metadata on an array formula marks
+                                        // a dynamic (spilling) array
+                                        cell = cell.with_array(ArrayFormula::covering(
+                                            range,
+                                            current_cell_metadata,
+                                        ));
+                                    }
+                                }
 
                                 sheet.insert_cell(cell);
                             }
@@ -492,6 +1201,8 @@ impl WorkbookParser {
                             current_cell_ref = None;
                             current_cell_type = None;
                             current_cell_style = None;
+                            current_cell_metadata = false;
+                            current_formula_kind = FormulaKind::Normal;
                             current_value.clear();
                             current_formula.clear();
                             current_inline_string.clear();
@@ -505,21 +1216,87 @@ impl WorkbookParser {
                         b"t" => {
                             in_inline_string = false;
                         }
+                        b"formula1" => {
+                            in_validation_formula = false;
+                        }
+                        b"dataValidation" => {
+                            for range in list_validation.take().unwrap_or_default() {
+                                sheet.add_dropdown(Dropdown {
+                                    range,
+                                    source: validation_formula.trim().to_string(),
+                                });
+                            }
+                        }
                         _ => {}
                     }
                 }
                 Ok(Event::Eof) => break,
                 Err(e) => {
+                    let mut context = ErrorContext::default()
+                        .part(part)
+                        .offset(xml_reader.error_position());
+                    if let Some(cell_ref) = &current_cell_ref {
+                        context = context.cell(cell_ref.to_a1());
+                    }
                     return Err(XlexError::InvalidXml {
                         message: format!("Error parsing sheet: {}", e),
-                    });
+                    }
+                    .with_context(context));
                 }
                 _ => {}
             }
             buf.clear();
         }
 
-        Ok(sheet)
+        for group in sparklines.groups {
+            sheet.add_sparkline_group(group);
+        }
+
+        Ok((sheet, picture_rel, violations))
+    }
+
+    /// Records a violation, or fails with it in strict mode.
+    fn report(&self, violations: &mut Vec<Violation>, violation: Violation) -> XlexResult<()> {
+        match self.mode {
+            ParseMode::Strict => Err(violation.into()),
+            ParseMode::Lenient => {
+                violations.push(violation);
+                Ok(())
+            }
+        }
+    }
+
+    /// Parses a cell's `r` attribute, reporting references that are invalid,
+    /// outside the enclosing row or already used in the sheet.
+    fn check_cell_ref(
+        &self,
+        ref_str: &str,
+        row: Option<u32>,
+        sheet: &Sheet,
+        violations: &mut Vec<Violation>,
+        violation: impl Fn(String) -> Violation,
+    ) -> XlexResult<Option<CellRef>> {
+        let Ok(cell_ref) = CellRef::parse(ref_str) else {
+            self.report(
+                violations,
+                violation(format!("Invalid cell reference '{}'", ref_str)),
+            )?;
+            return Ok(None);
+        };
+        let located = |message: String| Violation {
+            cell: Some(cell_ref.clone()),
+            ..violation(message)
+        };
+        if let Some(row) = row.filter(|&row| row != cell_ref.row) {
+            self.report(
+                violations,
+                located(format!("Cell {} is outside its row {}", cell_ref, row)),
+            )?;
+        }
+        if sheet.get_cell(&cell_ref).is_some() {
+            self.report(violations, located(format!("Duplicate cell {}", cell_ref)))?;
+        }
+        Ok(Some(cell_ref))
     }
 
     /// Parses a cell value based on its type and content.
@@ -530,12 +1307,19 @@ impl WorkbookParser {
         cell_type: Option<&str>,
         shared_strings: &[String],
     ) -> CellValue {
-        // If there's a formula, return formula value
+        // If there's a formula, return formula value with its typed cached result
         if !formula.is_empty() {
             return CellValue::Formula {
-                formula: formula.to_string(),
+                // Newer functions are stored with an `_xlfn.` prefix that
+                // Excel hides; the writer adds it back
+                formula: crate::formula::strip_function_prefixes(formula),
                 cached_result: if !value.is_empty() {
-                    Some(Box::new(CellValue::String(value.to_string())))
+                    Some(Box::new(self.parse_cell_value(
+                        value,
+                        "",
+                        cell_type,
+                        shared_strings,
+                    )))
                 } else {
                     None
                 },
@@ -581,6 +1365,136 @@ impl WorkbookParser {
     }
 }
 
+/// Highest row number in a worksheet.
+const MAX_ROWS: u32 = 1_048_576;
+
+/// How an `<f>` element's formula relates to other cells.
+#[derive(Debug, Clone, PartialEq)]
+enum FormulaKind {
+    /// A formula of its own
+    Normal,
+    /// An array formula (`t="array"`) whose result covers a range
+    Array(crate::range::Range),
+    /// A member of the shared formula group (`t="shared"`) with this `si` index
+    Shared(u32),
+}
+
+/// Reads the `t`, `ref` and `si` attributes of an `<f>` element.
+fn formula_kind(e: &BytesStart) -> FormulaKind {
+    let mut kind = Vec::new();
+    let mut reference = None;
+    let mut shared_index = None;
+    for attr in e.attributes().flatten() {
+        match attr.key.as_ref() {
+            b"t" => kind = attr.value.to_vec(),
+            b"ref" => {
+                reference = crate::range::Range::parse(&String::from_utf8_lossy(&attr.value)).ok()
+            }
+            b"si" => shared_index = String::from_utf8_lossy(&attr.value).parse().ok(),
+            _ => {}
+        }
+    }
+    match (kind.as_slice(), reference, shared_index) {
+        (b"array", Some(range), _) => FormulaKind::Array(range),
+        (b"shared", _, Some(index)) => FormulaKind::Shared(index),
+        _ => FormulaKind::Normal,
+    }
+}
+
+/// Reads a `<sheetProtection>` element; `sheet="0"` or a missing `sheet`
+/// attribute leaves the sheet unprotected.
+fn parse_sheet_protection(e: &BytesStart) -> Option<SheetProtection> {
+    let mut protected = false;
+    let mut protection = SheetProtection::new(
+        SheetAction::ALL
+            .into_iter()
+            .filter(|action| !action.blocked_by_default()),
+    );
+    let (mut algorithm, mut hash, mut salt, mut spin_count) = (None, None, None, 0);
+    for attr in e.attributes().flatten() {
+        let value = String::from_utf8_lossy(&attr.value).to_string();
+        let on = value == "1" || value == "true";
+        match attr.key.as_ref() {
+            b"sheet" => protected = on,
+            b"password" => {
+                protection.password = u16::from_str_radix(&value, 16)
+                    .ok()
+                    .map(ProtectionPassword::Legacy);
+            }
+            b"algorithmName" => algorithm = Some(value),
+            b"hashValue" => hash = Some(value),
+            b"saltValue" => salt = Some(value),
+            b"spinCount" => spin_count = value.parse().unwrap_or(0),
+            key => {
+                if let Some(action) = SheetAction::ALL
+                    .into_iter()
+                    .find(|action| action.attribute().as_bytes() == key)
+                {
+                    if on {
+                        protection.allowed.remove(&action);
+                    } else {
+                        protection.allowed.insert(action);
+                    }
+                }
+            }
+        }
+    }
+    if let (Some(algorithm), Some(hash)) = (algorithm, hash) {
+        protection.password = Some(ProtectionPassword::Hashed {
+            algorithm,
+            hash,
+            salt: salt.unwrap_or_default(),
+            spin_count,
+        });
+    }
+    protected.then_some(protection)
+}
+
+/// XML path of a cell element within a worksheet.
+fn cell_path(row: &str, cell: &str) -> String {
+    if row.is_empty() {
+        format!("worksheet/sheetData/row/c[@r=\"{}\"]", cell)
+    } else {
+        format!("worksheet/sheetData/row[@r=\"{}\"]/c[@r=\"{}\"]", row, cell)
+    }
+}
+
+/// Describes why a cell's value does not fit its `t` type, if it does not.
+fn value_violation(
+    value: &str,
+    cell_type: Option<&str>,
+    shared_strings: &[String],
+) -> Option<String> {
+    if value.is_empty() {
+        return None;
+    }
+    match cell_type {
+        Some("s") => match value.parse::<usize>() {
+            Ok(index) if index < shared_strings.len() => None,
+            Ok(index) => Some(format!(
+                "Shared string index {} out of range ({} strings)",
+                index,
+                shared_strings.len()
+            )),
+            Err(_) => Some(format!("Invalid shared string index '{}'", value)),
+        },
+        Some("b") => (!matches!(value, "0" | "1" | "true" | "false"))
+            .then(|| format!("Invalid boolean '{}'", value)),
+        Some("e") => CellError::parse(value)
+            .is_none()
+            .then(|| format!("Unknown error value '{}'", value)),
+        Some("str") | Some("inlineStr") | Some("d") => None,
+        None | Some("n") => value
+            .parse::<f64>()
+            .is_err()
+            .then(|| format!("Invalid number '{}'", value)),
+        Some(other) => Some(format!("Unknown cell type '{}'", other)),
+    }
+}
+
+/// Widest `<col>` span expanded into per-column widths.
+const MAX_COLUMN_SPAN: u32 = 1024;
+
 impl Default for WorkbookParser {
     fn default() -> Self {
         Self::new()
@@ -737,7 +1651,7 @@ mod tests {
                 cached_result,
             } => {
                 assert_eq!(formula, "A1+B1");
-                assert!(cached_result.is_some());
+                assert_eq!(cached_result.as_deref(), Some(&CellValue::Number(42.0)));
             }
             _ => panic!("Expected formula"),
         }
@@ -804,8 +1718,156 @@ mod tests {
     #[test]
     fn test_parse_cell_value_error_unknown() {
         let parser = WorkbookParser::new();
-        let value = parser.parse_cell_value("#UNKNOWN!", "", Some("e"), &[]);
+        let value = parser.parse_cell_value("#BOGUS!", "", Some("e"), &[]);
         // Unknown error should return Empty based on parse implementation
         assert_eq!(value, CellValue::Empty);
     }
+
+    #[test]
+    fn test_parse_cell_value_error_dynamic_array() {
+        let parser = WorkbookParser::new();
+        let value = parser.parse_cell_value("#SPILL!", "", Some("e"), &[]);
+        assert_eq!(value, CellValue::Error(CellError::Spill));
+        let value = parser.parse_cell_value("#CALC!", "FILTER(A1:A3,B1:B3)", Some("e"), &[]);
+        assert_eq!(value.error(), Some(CellError::Calc));
+    }
+
+    #[test]
+    fn test_parse_sheet_shared_and_array_formulas() {
+        let xml = r#"<worksheet><sheetData><row r="2"><c r="C2"><f t="shared" ref="C2:D4" si="0">A2*$B$1+_xlfn.XLOOKUP(A2,$E$1:$E$9,F1:F9)</f><v>1</v></c><c r="D2"><f t="shared" si="0"/><v>2</v></c><c r="F2" cm="1"><f t="array" ref="F2:F5">_xlfn._xlws.SORT(A2:A5)</f><v>3</v></c></row><row r="4"><c r="D4"><f t="shared" si="0"/><v>4</v></c><c r="G4"><f t="array" ref="G4">MAX(A1:A3*B1:B3)</f></c></row></sheetData></worksheet>"#;
+        let (sheet, _, violations) = WorkbookParser::new()
+            .parse_sheet(
+                std::io::Cursor::new(xml.as_bytes()),
+                SheetInfo::new("Sheet1", 1, "rId1", 0),
+                "xl/worksheets/sheet1.xml",
+                &[],
+            )
+            .unwrap();
+        assert!(violations.is_empty());
+
+        let formula = |col, row| match sheet.get_value(&CellRef::new(col, row)) {
+            CellValue::Formula { formula, .. } => formula,
+            other => panic!("Expected formula, got {:?}", other),
+        };
+        assert_eq!(formula(3, 2), "A2*$B$1+XLOOKUP(A2,$E$1:$E$9,F1:F9)");
+        assert_eq!(formula(4, 2), "B2*$B$1+XLOOKUP(B2,$E$1:$E$9,G1:G9)");
+        assert_eq!(formula(4, 4), "B4*$B$1+XLOOKUP(B4,$E$1:$E$9,G3:G11)");
+        assert!(sheet.get_cell(&CellRef::new(3, 2)).unwrap().array.is_none());
+
+        let (anchor, array) = sheet.array_formula_at(&CellRef::new(6, 5)).unwrap();
+        assert_eq!(anchor, CellRef::new(6, 2));
+        assert!(array.dynamic);
+        assert_eq!(formula(6, 2), "SORT(A2:A5)");
+        let (_, array) = sheet.array_formula_at(&CellRef::new(7, 4)).unwrap();
+        assert!(!array.dynamic);
+    }
+
+    #[test]
+    fn test_parse_sheet_shared_formulas_with_line_refs() {
+        // SUM(1:1) filled down and SUM(A:A) filled right
+        let xml = r#"<worksheet><sheetData><row r="1"><c r="A1"><f t="shared" ref="A1:A3" si="0">SUM(1:1)+$2:2</f></c><c r="B1"><f t="shared" ref="B1:C1" si="1">SUM(A:A)*Data!$A:B</f></c><c r="C1"><f t="shared" si="1"/></c></row><row r="2"><c r="A2"><f t="shared" si="0"/></c></row><row r="3"><c r="A3"><f t="shared" si="0"/></c></row></sheetData></worksheet>"#;
+        let (sheet, _, violations) = WorkbookParser::new()
+            .parse_sheet(
+                std::io::Cursor::new(xml.as_bytes()),
+                SheetInfo::new("Sheet1", 1, "rId1", 0),
+                "xl/worksheets/sheet1.xml",
+                &[],
+            )
+            .unwrap();
+        assert!(violations.is_empty());
+
+        let formula = |col, row| match sheet.get_value(&CellRef::new(col, row)) {
+            CellValue::Formula { formula, .. } => formula,
+            other => panic!("Expected formula, got {:?}", other),
+        };
+        assert_eq!(formula(1, 2), "SUM(2:2)+$2:3");
+        assert_eq!(formula(1, 3), "SUM(3:3)+$2:4");
+        assert_eq!(formula(3, 1), "SUM(B:B)*Data!$A:C");
+    }
+
+    const VIOLATING_SHEET: &str = r#"<worksheet><sheetData><row r="1"><c r="A1"><v>1</v></c><c r="A1"><v>2</v></c><c r="ZZZZ9"><v>3</v></c><c r="B1" t="s"><v>7</v></c></row></sheetData></worksheet>"#;
+
+    fn parse_violating_sheet(mode: ParseMode) -> XlexResult<SheetParts> {
+        WorkbookParser::with_mode(mode).parse_sheet(
+            std::io::Cursor::new(VIOLATING_SHEET.as_bytes()),
+            SheetInfo::new("Sheet1", 1, "rId1", 0),
+            "xl/worksheets/sheet1.xml",
+            &[],
+        )
+    }
+
+    #[test]
+    fn test_parse_sheet_lenient_records_violations() {
+        let (sheet, _, violations) = parse_violating_sheet(ParseMode::Lenient).unwrap();
+        // Recovered: the last duplicate wins, the bad reference is dropped
+        assert_eq!(sheet.get_value(&CellRef::new(1, 1)), CellValue::Number(2.0));
+        let messages: Vec<&str> = violations.iter().map(|v| v.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Duplicate cell A1",
+                "Invalid cell reference 'ZZZZ9'",
+                "Shared string index 7 out of range (0 strings) in B1",
+            ]
+        );
+        let duplicate = &violations[0];
+        assert_eq!(duplicate.part, "xl/worksheets/sheet1.xml");
+        assert_eq!(
+            duplicate.path,
+            r#"worksheet/sheetData/row[@r="1"]/c[@r="A1"]"#
+        );
+        let offset = duplicate.offset as usize;
+        assert!(VIOLATING_SHEET[offset..].starts_with(r#"<c r="A1"><v>2"#));
+    }
+
+    #[test]
+    fn test_parse_sheet_strict_fails_on_first_violation() {
+        let err = parse_violating_sheet(ParseMode::Strict).unwrap_err();
+        assert_eq!(
+            err.context(),
+            Some(
+                &ErrorContext::default()
+                    .part("xl/worksheets/sheet1.xml")
+                    .cell("A1")
+                    .offset(55)
+            )
+        );
+        match err.without_context() {
+            XlexError::ParseError { message, location } => {
+                assert_eq!(message, "Duplicate cell A1");
+                assert_eq!(location, r#"worksheet/sheetData/row[@r="1"]/c[@r="A1"]"#);
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_sheet_declared_dimension() {
+        let xml = r#"<worksheet><dimension ref="A1:D10"/><sheetData><row r="2"><c r="B2"><v>1</v></c></row></sheetData></worksheet>"#;
+        let (sheet, _, _) = WorkbookParser::new()
+            .parse_sheet(
+                std::io::Cursor::new(xml.as_bytes()),
+                SheetInfo::new("Sheet1", 1, "rId1", 0),
+                "xl/worksheets/sheet1.xml",
+                &[],
+            )
+            .unwrap();
+        assert_eq!(sheet.declared_dimension().unwrap().to_a1(), "A1:D10");
+        assert_eq!(sheet.used_range().unwrap().to_a1(), "B2");
+    }
+
+    #[test]
+    fn test_value_violation() {
+        assert_eq!(value_violation("1.5", None, &[]), None);
+        assert_eq!(
+            value_violation("abc", Some("n"), &[]),
+            Some("Invalid number 'abc'".to_string())
+        );
+        assert_eq!(
+            value_violation("2", Some("b"), &[]),
+            Some("Invalid boolean '2'".to_string())
+        );
+        assert_eq!(value_violation("0", Some("s"), &["a".to_string()]), None);
+        assert!(value_violation("#BAD", Some("e"), &[]).is_some());
+    }
 }
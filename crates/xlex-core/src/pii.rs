@@ -0,0 +1,301 @@
+//! Personal data detection.
+//!
+//! Finds email addresses, phone numbers, IBANs, payment card numbers and
+//! national ID numbers in text so they can be reported or masked before a
+//! workbook is shared. Patterns with a check digit (IBAN mod-97, card Luhn,
+//! Taiwan ID) are only reported when the check passes.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Kind of personal data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PiiKind {
+    /// Email address
+    Email,
+    /// International bank account number
+    Iban,
+    /// Payment card number
+    CreditCard,
+    /// National ID number (US SSN, UK NINO, Taiwan ID)
+    NationalId,
+    /// Phone number
+    Phone,
+}
+
+impl PiiKind {
+    /// All kinds, in the order they are matched.
+    pub const ALL: [PiiKind; 5] = [
+        Self::Email,
+        Self::Iban,
+        Self::CreditCard,
+        Self::NationalId,
+        Self::Phone,
+    ];
+
+    /// Returns the kind's name as used in reports (`email`, `creditCard`, ...).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Email => "email",
+            Self::Iban => "iban",
+            Self::CreditCard => "creditCard",
+            Self::NationalId => "nationalId",
+            Self::Phone => "phone",
+        }
+    }
+}
+
+impl fmt::Display for PiiKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Personal data found in a piece of text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiiMatch {
+    /// Kind of data
+    pub kind: PiiKind,
+    /// Byte offset of the match
+    pub start: usize,
+    /// Byte offset just past the match
+    pub end: usize,
+    /// Matched text
+    pub text: String,
+}
+
+impl PiiMatch {
+    /// Returns the matched text masked (see [`mask`]).
+    pub fn masked(&self) -> String {
+        mask(self.kind, &self.text)
+    }
+}
+
+fn patterns() -> &'static [(PiiKind, Regex)] {
+    static PATTERNS: OnceLock<Vec<(PiiKind, Regex)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            (
+                PiiKind::Email,
+                r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}",
+            ),
+            (PiiKind::Iban, r"\b[A-Z]{2}\d{2}(?: ?[A-Z0-9]){11,30}\b"),
+            (PiiKind::CreditCard, r"\b\d(?:[ -]?\d){12,18}\b"),
+            (
+                PiiKind::NationalId,
+                r"\b\d{3}-\d{2}-\d{4}\b|\b[A-CEGHJ-PR-TW-Z]{2} ?\d{2} ?\d{2} ?\d{2} ?[A-D]\b|\b[A-Z][12]\d{8}\b",
+            ),
+            (
+                PiiKind::Phone,
+                r"(?:\+\d{1,3}[ .-]?)?(?:\(\d{1,4}\)[ .-]?)?\d{2,4}(?:[ .-]\d{2,4}){1,4}\b",
+            ),
+        ]
+        .into_iter()
+        .map(|(kind, pattern)| (kind, Regex::new(pattern).expect("valid PII pattern")))
+        .collect()
+    })
+}
+
+/// Finds personal data in `text`, in order of position.
+///
+/// When matches overlap, the earlier kind in [`PiiKind::ALL`] wins, so the
+/// digits of an IBAN are not also reported as a phone number.
+pub fn find_pii(text: &str) -> Vec<PiiMatch> {
+    let mut matches: Vec<PiiMatch> = Vec::new();
+    for (kind, regex) in patterns() {
+        for m in regex.find_iter(text) {
+            let overlaps = matches
+                .iter()
+                .any(|other| m.start() < other.end && other.start < m.end());
+            if !overlaps && is_valid(*kind, m.as_str()) {
+                matches.push(PiiMatch {
+                    kind: *kind,
+                    start: m.start(),
+                    end: m.end(),
+                    text: m.as_str().to_string(),
+                });
+            }
+        }
+    }
+    matches.sort_by_key(|m| m.start);
+    matches
+}
+
+/// Replaces all personal data in `text` with masked values. Returns the
+/// new text and the number of matches replaced.
+pub fn redact(text: &str) -> (String, usize) {
+    let matches = find_pii(text);
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for m in &matches {
+        out.push_str(&text[last..m.start]);
+        out.push_str(&m.masked());
+        last = m.end;
+    }
+    out.push_str(&text[last..]);
+    (out, matches.len())
+}
+
+/// Masks a value, keeping its shape.
+///
+/// Emails keep their first character and domain (`j***@example.com`);
+/// other kinds keep separators and their last four letters or digits
+/// (`****-****-****-1111`).
+pub fn mask(kind: PiiKind, text: &str) -> String {
+    if kind == PiiKind::Email {
+        if let Some((local, domain)) = text.split_once('@') {
+            let first: String = local.chars().take(1).collect();
+            return format!("{}***@{}", first, domain);
+        }
+    }
+    let total = text.chars().filter(|c| c.is_ascii_alphanumeric()).count();
+    let mut seen = 0;
+    text.chars()
+        .map(|c| {
+            if !c.is_ascii_alphanumeric() {
+                return c;
+            }
+            seen += 1;
+            if seen + 4 > total {
+                c
+            } else {
+                '*'
+            }
+        })
+        .collect()
+}
+
+fn digits(text: &str) -> Vec<u32> {
+    text.chars().filter_map(|c| c.to_digit(10)).collect()
+}
+
+fn is_valid(kind: PiiKind, text: &str) -> bool {
+    match kind {
+        PiiKind::Email => true,
+        PiiKind::Iban => iban_checksum(text),
+        PiiKind::CreditCard => {
+            let digits = digits(text);
+            (13..=19).contains(&digits.len()) && luhn(&digits)
+        }
+        PiiKind::NationalId => national_id(text),
+        PiiKind::Phone => {
+            let count = digits(text).len();
+            (9..=15).contains(&count)
+        }
+    }
+}
+
+/// Luhn check used by payment card numbers.
+fn luhn(digits: &[u32]) -> bool {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            if i % 2 == 1 {
+                let doubled = d * 2;
+                if doubled > 9 {
+                    doubled - 9
+                } else {
+                    doubled
+                }
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+/// ISO 13616 mod-97 check.
+fn iban_checksum(text: &str) -> bool {
+    let compact: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if !(15..=34).contains(&compact.len()) {
+        return false;
+    }
+    let rearranged = compact[4..].iter().chain(&compact[..4]);
+    let mut remainder: u64 = 0;
+    for c in rearranged {
+        let value = match c.to_digit(36) {
+            Some(v) => v as u64,
+            None => return false,
+        };
+        let width = if value >= 10 { 100 } else { 10 };
+        remainder = (remainder * width + value) % 97;
+    }
+    remainder == 1
+}
+
+fn national_id(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    if bytes.len() == 11 && bytes[3] == b'-' {
+        // US SSN: area 000, 666 and 900-999 are never issued
+        let area: u32 = text[..3].parse().unwrap_or(0);
+        return area != 0 && area != 666 && area < 900 && &text[4..6] != "00";
+    }
+    if bytes.len() == 10 && bytes[1].is_ascii_digit() {
+        // Taiwan ID: letter maps to a two-digit code, weighted checksum
+        const CODES: &[u8; 26] = b"\x0a\x0b\x0c\x0d\x0e\x0f\x10\x11\x22\x12\x13\x14\x15\x16\x23\x17\x18\x19\x1a\x1b\x1c\x1d\x20\x1e\x1f\x21";
+        let code = CODES[(bytes[0] - b'A') as usize] as u32;
+        let digits = digits(&text[1..]);
+        let weighted: u32 = digits[..8]
+            .iter()
+            .enumerate()
+            .map(|(i, d)| d * (8 - i as u32))
+            .sum();
+        let sum = code / 10 + (code % 10) * 9 + weighted + digits[8];
+        return sum % 10 == 0;
+    }
+    // UK NINO: the pattern already excludes invalid prefix letters
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(text: &str) -> Vec<PiiKind> {
+        find_pii(text).iter().map(|m| m.kind).collect()
+    }
+
+    #[test]
+    fn test_find_email_and_phone() {
+        let found = find_pii("Contact jane.doe@example.co.uk or +1 (555) 123-4567");
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].kind, PiiKind::Email);
+        assert_eq!(found[0].text, "jane.doe@example.co.uk");
+        assert_eq!(found[1].kind, PiiKind::Phone);
+        assert_eq!(found[1].text, "+1 (555) 123-4567");
+    }
+
+    #[test]
+    fn test_check_digits() {
+        assert_eq!(kinds("Card 4111 1111 1111 1111"), vec![PiiKind::CreditCard]);
+        assert!(!kinds("Card 4111 1111 1111 1112").contains(&PiiKind::CreditCard));
+        assert_eq!(kinds("GB82 WEST 1234 5698 7654 32"), vec![PiiKind::Iban]);
+        assert!(!kinds("GB00 WEST 1234 5698 7654 32").contains(&PiiKind::Iban));
+        assert_eq!(kinds("A123456789"), vec![PiiKind::NationalId]);
+        assert!(kinds("A123456788").is_empty());
+        assert_eq!(kinds("SSN 123-45-6789"), vec![PiiKind::NationalId]);
+        assert!(!kinds("SSN 666-45-6789").contains(&PiiKind::NationalId));
+        assert_eq!(kinds("NINO AB 12 34 56 C"), vec![PiiKind::NationalId]);
+    }
+
+    #[test]
+    fn test_ignores_dates_and_amounts() {
+        assert!(find_pii("2024-01-15").is_empty());
+        assert!(find_pii("Total 1,234,567.89 on 15.01.2024").is_empty());
+        assert!(find_pii("Invoice 12345").is_empty());
+    }
+
+    #[test]
+    fn test_redact() {
+        let (text, count) = redact("Mail bob@corp.com, card 4111-1111-1111-1111.");
+        assert_eq!(count, 2);
+        assert_eq!(text, "Mail b***@corp.com, card ****-****-****-1111.");
+    }
+}
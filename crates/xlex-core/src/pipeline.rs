@@ -0,0 +1,347 @@
+//! Row pipelines: read a sheet, filter and transform its rows, and write
+//! the result, one row at a time.
+//!
+//! A [`Pipeline`] streams rows from a [`LazyWorkbook`] through its steps
+//! into a [`RowSink`] such as a [`StreamingSheetWriter`], so neither the
+//! source sheet nor the result is ever held in memory.
+//!
+//! ```no_run
+//! use xlex_core::writer::StreamingSheetWriter;
+//! use xlex_core::LazyWorkbook;
+//!
+//! let workbook = LazyWorkbook::open("orders.xlsx")?;
+//! let mut out = StreamingSheetWriter::create("big-orders.xlsx", "Orders")?;
+//! let stats = workbook
+//!     .pipeline("Orders")
+//!     // Keep the header and orders over 100 in column C
+//!     .filter(|record| record.row == 1 || record.number(3).is_some_and(|n| n > 100.0))
+//!     .map(|mut record| {
+//!         record.values.truncate(3);
+//!         record
+//!     })
+//!     .write_to(&mut out)?;
+//! out.finish()?;
+//! println!("kept {} of {} rows", stats.rows_written, stats.rows_read);
+//! # Ok::<(), xlex_core::XlexError>(())
+//! ```
+
+use crate::cell::CellValue;
+use crate::error::XlexResult;
+use crate::lazy::{LazyWorkbook, StreamRow};
+use crate::writer::StreamingSheetWriter;
+
+/// A row flowing through a [`Pipeline`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    /// Row number in the source sheet
+    pub row: u32,
+    /// Values from column A onwards, up to the last cell present
+    pub values: Vec<CellValue>,
+}
+
+impl Record {
+    /// Lays out the cells of a streamed row by column.
+    pub fn from_stream_row(row: StreamRow) -> Self {
+        let width = row.cells.iter().map(|(r, _)| r.col).max().unwrap_or(0);
+        let mut values = vec![CellValue::Empty; width as usize];
+        for (cell_ref, value) in row.cells {
+            values[cell_ref.col as usize - 1] = value;
+        }
+        Self {
+            row: row.row_number,
+            values,
+        }
+    }
+
+    /// Returns the value in 1-based column `col`, empty past the end.
+    pub fn get(&self, col: u32) -> &CellValue {
+        const EMPTY: &CellValue = &CellValue::Empty;
+        col.checked_sub(1)
+            .and_then(|i| self.values.get(i as usize))
+            .unwrap_or(EMPTY)
+    }
+
+    /// Returns the numeric value in 1-based column `col`, looking through
+    /// dates and cached formula results.
+    pub fn number(&self, col: u32) -> Option<f64> {
+        match self.get(col) {
+            CellValue::Formula {
+                cached_result: Some(result),
+                ..
+            } => result.as_number(),
+            value => value.as_number(),
+        }
+    }
+
+    /// Sets the value in 1-based column `col`, widening the row if needed.
+    pub fn set(&mut self, col: u32, value: CellValue) {
+        let Some(index) = col.checked_sub(1).map(|i| i as usize) else {
+            return;
+        };
+        if index >= self.values.len() {
+            self.values.resize(index + 1, CellValue::Empty);
+        }
+        self.values[index] = value;
+    }
+}
+
+/// Destination for the rows coming out of a [`Pipeline`].
+pub trait RowSink {
+    /// Appends a row, with `values[0]` in column A.
+    fn write_row(&mut self, values: &[CellValue]) -> XlexResult<()>;
+}
+
+impl RowSink for StreamingSheetWriter {
+    fn write_row(&mut self, values: &[CellValue]) -> XlexResult<()> {
+        StreamingSheetWriter::write_row(self, values)
+    }
+}
+
+/// Collects rows in memory, for small results and tests.
+impl RowSink for Vec<Vec<CellValue>> {
+    fn write_row(&mut self, values: &[CellValue]) -> XlexResult<()> {
+        self.push(values.to_vec());
+        Ok(())
+    }
+}
+
+/// Row counts from running a [`Pipeline`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PipelineStats {
+    /// Rows read from the source sheet
+    pub rows_read: u64,
+    /// Rows written to the sink
+    pub rows_written: u64,
+}
+
+enum Step<'a> {
+    FilterMap(Box<dyn FnMut(Record) -> XlexResult<Option<Record>> + 'a>),
+    FlatMap(Box<dyn FnMut(Record) -> XlexResult<Vec<Record>> + 'a>),
+}
+
+type Records<'a> = Box<dyn Iterator<Item = XlexResult<Record>> + 'a>;
+
+enum Source<'a> {
+    Sheet(&'a LazyWorkbook, String),
+    Records(Records<'a>),
+}
+
+/// Steps applied to each row of a sheet, in order, as it is streamed.
+///
+/// Created with [`LazyWorkbook::pipeline`], or with
+/// [`from_records`](Self::from_records) for rows from elsewhere; nothing is
+/// read until [`write_to`](Self::write_to) runs it.
+pub struct Pipeline<'a> {
+    source: Source<'a>,
+    steps: Vec<Step<'a>>,
+}
+
+impl<'a> Pipeline<'a> {
+    /// Starts a pipeline over rows read by an iterator, such as the lines
+    /// of a CSV file.
+    pub fn from_records(records: impl Iterator<Item = XlexResult<Record>> + 'a) -> Self {
+        Self {
+            source: Source::Records(Box::new(records)),
+            steps: Vec::new(),
+        }
+    }
+
+    /// Keeps only the rows for which `predicate` returns true.
+    pub fn filter(self, mut predicate: impl FnMut(&Record) -> bool + 'a) -> Self {
+        self.try_filter_map(move |record| Ok(predicate(&record).then_some(record)))
+    }
+
+    /// Replaces each row with the result of `transform`.
+    pub fn map(self, mut transform: impl FnMut(Record) -> Record + 'a) -> Self {
+        self.try_filter_map(move |record| Ok(Some(transform(record))))
+    }
+
+    /// Replaces each row with the result of `step`, dropping it on `None`.
+    /// An error stops the pipeline and is returned from
+    /// [`write_to`](Self::write_to).
+    pub fn try_filter_map(
+        mut self,
+        step: impl FnMut(Record) -> XlexResult<Option<Record>> + 'a,
+    ) -> Self {
+        self.steps.push(Step::FilterMap(Box::new(step)));
+        self
+    }
+
+    /// Replaces each row with the rows returned by `step`, which may be
+    /// none or several, as when joining or unpivoting.
+    pub fn try_flat_map(
+        mut self,
+        step: impl FnMut(Record) -> XlexResult<Vec<Record>> + 'a,
+    ) -> Self {
+        self.steps.push(Step::FlatMap(Box::new(step)));
+        self
+    }
+
+    /// Streams the rows through the steps into `sink`.
+    pub fn write_to(self, sink: &mut impl RowSink) -> XlexResult<PipelineStats> {
+        let Pipeline { source, mut steps } = self;
+        let mut stats = PipelineStats::default();
+        let mut push = |record: Record| -> XlexResult<()> {
+            stats.rows_read += 1;
+            stats.rows_written += run_steps(&mut steps, record, sink)?;
+            Ok(())
+        };
+        match source {
+            Source::Sheet(workbook, sheet) => {
+                workbook.for_each_row(&sheet, |row| push(Record::from_stream_row(row)))?;
+            }
+            Source::Records(records) => {
+                for record in records {
+                    push(record?)?;
+                }
+            }
+        }
+        Ok(stats)
+    }
+}
+
+/// Passes `record` through `steps` into `sink`, returning the number of
+/// rows written.
+fn run_steps(steps: &mut [Step<'_>], record: Record, sink: &mut impl RowSink) -> XlexResult<u64> {
+    let Some((step, rest)) = steps.split_first_mut() else {
+        sink.write_row(&record.values)?;
+        return Ok(1);
+    };
+    match step {
+        Step::FilterMap(step) => match step(record)? {
+            Some(record) => run_steps(rest, record, sink),
+            None => Ok(0),
+        },
+        Step::FlatMap(step) => {
+            let mut written = 0;
+            for record in step(record)? {
+                written += run_steps(rest, record, sink)?;
+            }
+            Ok(written)
+        }
+    }
+}
+
+impl LazyWorkbook {
+    /// Starts a [`Pipeline`] over the rows of a sheet.
+    pub fn pipeline(&self, sheet_name: &str) -> Pipeline<'_> {
+        Pipeline {
+            source: Source::Sheet(self, sheet_name.to_string()),
+            steps: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::CellRef;
+    use crate::workbook::Workbook;
+
+    fn orders() -> (tempfile::TempDir, LazyWorkbook) {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("orders.xlsx");
+        let mut wb = Workbook::with_sheets(&["Orders"]);
+        let rows = [("id", "total"), ("a", "50"), ("b", "150"), ("c", "300")];
+        for (i, (id, total)) in rows.iter().enumerate() {
+            let row = i as u32 + 1;
+            wb.set_cell("Orders", CellRef::new(1, row), CellValue::string(*id))
+                .unwrap();
+            let total = total
+                .parse()
+                .map(CellValue::Number)
+                .unwrap_or_else(|_| CellValue::string(*total));
+            wb.set_cell("Orders", CellRef::new(3, row), total).unwrap();
+        }
+        wb.save_as(&path).unwrap();
+        let lazy = LazyWorkbook::open(&path).unwrap();
+        (dir, lazy)
+    }
+
+    #[test]
+    fn test_record_get_set() {
+        let mut record = Record::from_stream_row(StreamRow {
+            row_number: 4,
+            cells: vec![(CellRef::new(3, 4), CellValue::Number(1.0))],
+        });
+        assert_eq!(record.values.len(), 3);
+        assert_eq!(record.get(1), &CellValue::Empty);
+        assert_eq!(record.get(3), &CellValue::Number(1.0));
+        assert_eq!(record.get(0), &CellValue::Empty);
+        record.set(5, CellValue::Boolean(true));
+        assert_eq!(record.values.len(), 5);
+        assert_eq!(record.get(5), &CellValue::Boolean(true));
+    }
+
+    #[test]
+    fn test_pipeline_filter_map() {
+        let (_dir, workbook) = orders();
+        let mut rows: Vec<Vec<CellValue>> = Vec::new();
+        let stats = workbook
+            .pipeline("Orders")
+            .filter(|r| r.row == 1 || r.number(3).is_some_and(|n| n > 100.0))
+            .map(|mut r| {
+                r.values.remove(1);
+                r
+            })
+            .write_to(&mut rows)
+            .unwrap();
+        assert_eq!(
+            stats,
+            PipelineStats {
+                rows_read: 4,
+                rows_written: 3
+            }
+        );
+        assert_eq!(
+            rows,
+            vec![
+                vec![CellValue::string("id"), CellValue::string("total")],
+                vec![CellValue::string("b"), CellValue::Number(150.0)],
+                vec![CellValue::string("c"), CellValue::Number(300.0)],
+            ]
+        );
+        assert!(workbook.pipeline("Missing").write_to(&mut rows).is_err());
+
+        let failed = workbook
+            .pipeline("Orders")
+            .try_filter_map(|r| match r.row {
+                3 => Err(crate::XlexError::OperationFailed {
+                    message: "bad row".to_string(),
+                }),
+                _ => Ok(Some(r)),
+            })
+            .write_to(&mut Vec::new());
+        assert!(failed.unwrap_err().to_string().contains("bad row"));
+    }
+
+    #[test]
+    fn test_pipeline_from_records_flat_map() {
+        let records = (1..=3).map(|row| {
+            Ok(Record {
+                row,
+                values: vec![CellValue::Number(f64::from(row))],
+            })
+        });
+        let mut rows: Vec<Vec<CellValue>> = Vec::new();
+        let stats = Pipeline::from_records(records)
+            .try_flat_map(|r| Ok(vec![r.clone(); r.row as usize - 1]))
+            .map(|mut r| {
+                r.set(2, CellValue::string("x"));
+                r
+            })
+            .write_to(&mut rows)
+            .unwrap();
+        assert_eq!(stats.rows_read, 3);
+        assert_eq!(stats.rows_written, 3);
+        assert_eq!(
+            rows.iter().map(|r| r[0].clone()).collect::<Vec<_>>(),
+            vec![
+                CellValue::Number(2.0),
+                CellValue::Number(3.0),
+                CellValue::Number(3.0)
+            ]
+        );
+        assert!(rows.iter().all(|r| r[1] == CellValue::string("x")));
+    }
+}
@@ -0,0 +1,113 @@
+//! Read handles for serving one workbook from several threads.
+//!
+//! A [`LazyWorkbook`] can be shared between threads, but every shared
+//! string lookup takes the same lock. A [`ReaderPool`] holds several
+//! handles over the same file bytes and shared strings index, each with
+//! its own zip reader per read, string cache and lock, so parallel range
+//! reads only contend when they pick the same handle.
+//!
+//! ```no_run
+//! use xlex_core::{CellRef, Workbook};
+//!
+//! let workbook = Workbook::open("report.xlsx").unwrap();
+//! let pool = workbook.reader_pool(4).unwrap();
+//! std::thread::scope(|scope| {
+//!     for row in 1..=8 {
+//!         let pool = &pool;
+//!         scope.spawn(move || pool.get().read_cell("Sheet1", &CellRef::new(1, row)));
+//!     }
+//! });
+//! ```
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::lazy::LazyWorkbook;
+
+/// A fixed set of read handles over one workbook, handed out in turn.
+pub struct ReaderPool {
+    readers: Vec<LazyWorkbook>,
+    next: AtomicUsize,
+}
+
+impl ReaderPool {
+    /// Creates `n` handles (at least one) over `workbook`'s data.
+    pub(crate) fn new(workbook: &LazyWorkbook, n: usize) -> Self {
+        Self {
+            readers: (0..n.max(1)).map(|_| workbook.fork()).collect(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the next handle, in round-robin order.
+    pub fn get(&self) -> &LazyWorkbook {
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.readers.len();
+        &self.readers[index]
+    }
+
+    /// Returns the handle at `index`, for callers that pin a handle to
+    /// each worker thread.
+    pub fn handle(&self, index: usize) -> Option<&LazyWorkbook> {
+        self.readers.get(index)
+    }
+
+    /// Returns the number of handles.
+    pub fn len(&self) -> usize {
+        self.readers.len()
+    }
+
+    /// Returns `true` if the pool has no handles, which never happens.
+    pub fn is_empty(&self) -> bool {
+        self.readers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CellRef, CellValue, Workbook};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_reader_pool() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("pool.xlsx");
+        let mut wb = Workbook::with_sheets(&["Data"]);
+        for row in 1..=50 {
+            wb.set_cell(
+                "Data",
+                CellRef::new(1, row),
+                CellValue::string(format!("row {}", row)),
+            )
+            .unwrap();
+        }
+        wb.save_as(&path).unwrap();
+
+        let wb = Workbook::open(&path).unwrap();
+        let pool = wb.reader_pool(3).unwrap();
+        assert_eq!(pool.len(), 3);
+        assert!(!pool.is_empty());
+        assert!(pool.handle(2).is_some() && pool.handle(3).is_none());
+
+        let read: Vec<Vec<String>> = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..6)
+                .map(|_| {
+                    let pool = &pool;
+                    scope.spawn(move || {
+                        (1..=50)
+                            .map(|row| {
+                                let value = pool.get().read_cell("Data", &CellRef::new(1, row));
+                                value.unwrap().unwrap().to_display_string()
+                            })
+                            .collect()
+                    })
+                })
+                .collect();
+            workers.into_iter().map(|w| w.join().unwrap()).collect()
+        });
+        let expected: Vec<String> = (1..=50).map(|row| format!("row {}", row)).collect();
+        assert!(read.iter().all(|values| *values == expected));
+
+        assert_eq!(LazyWorkbook::open(&path).unwrap().reader_pool(0).len(), 1);
+        assert!(Workbook::new().reader_pool(2).is_err());
+    }
+}
@@ -0,0 +1,236 @@
+//! Sheet protection types.
+//!
+//! A protected sheet keeps every locked cell read-only; cells are locked
+//! unless their style's [`Protection`](crate::style::Protection) says
+//! otherwise. The sheet's `<sheetProtection>` element lists what users may
+//! still do, such as sorting or filtering, and may carry a password hash
+//! that Excel asks for before unprotecting.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::error::{XlexError, XlexResult};
+
+/// Something users may be allowed to do on a protected sheet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SheetAction {
+    /// Change cell formatting
+    FormatCells,
+    /// Resize or hide columns
+    FormatColumns,
+    /// Resize or hide rows
+    FormatRows,
+    /// Insert columns
+    InsertColumns,
+    /// Insert rows
+    InsertRows,
+    /// Insert hyperlinks
+    InsertHyperlinks,
+    /// Delete columns
+    DeleteColumns,
+    /// Delete rows
+    DeleteRows,
+    /// Sort
+    Sort,
+    /// Use filter buttons
+    Filter,
+    /// Use pivot tables
+    PivotTables,
+    /// Edit drawings, charts and comments
+    Objects,
+    /// Edit scenarios
+    Scenarios,
+}
+
+impl SheetAction {
+    /// Every action, in `<sheetProtection>` attribute order.
+    pub const ALL: [SheetAction; 13] = [
+        Self::Objects,
+        Self::Scenarios,
+        Self::FormatCells,
+        Self::FormatColumns,
+        Self::FormatRows,
+        Self::InsertColumns,
+        Self::InsertRows,
+        Self::InsertHyperlinks,
+        Self::DeleteColumns,
+        Self::DeleteRows,
+        Self::Sort,
+        Self::Filter,
+        Self::PivotTables,
+    ];
+
+    /// Returns the name used on the command line (e.g., `format-cells`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::FormatCells => "format-cells",
+            Self::FormatColumns => "format-columns",
+            Self::FormatRows => "format-rows",
+            Self::InsertColumns => "insert-columns",
+            Self::InsertRows => "insert-rows",
+            Self::InsertHyperlinks => "insert-hyperlinks",
+            Self::DeleteColumns => "delete-columns",
+            Self::DeleteRows => "delete-rows",
+            Self::Sort => "sort",
+            Self::Filter => "filter",
+            Self::PivotTables => "pivot-tables",
+            Self::Objects => "objects",
+            Self::Scenarios => "scenarios",
+        }
+    }
+
+    /// Parses an action name, ignoring case and `-`/`_` (`autofilter` is
+    /// accepted for `filter`).
+    pub fn parse(s: &str) -> XlexResult<Self> {
+        let key = s.to_ascii_lowercase().replace(['-', '_'], "");
+        Self::ALL
+            .into_iter()
+            .find(|action| {
+                action.name().replace('-', "") == key
+                    || (*action == Self::Filter && key == "autofilter")
+            })
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::ALL.iter().map(|a| a.name()).collect();
+                XlexError::InvalidOperation {
+                    message: format!(
+                        "Unknown sheet action '{}', expected one of: {}",
+                        s,
+                        names.join(", ")
+                    ),
+                }
+            })
+    }
+
+    /// Returns the `<sheetProtection>` attribute controlling the action.
+    pub(crate) fn attribute(&self) -> &'static str {
+        match self {
+            Self::FormatCells => "formatCells",
+            Self::FormatColumns => "formatColumns",
+            Self::FormatRows => "formatRows",
+            Self::InsertColumns => "insertColumns",
+            Self::InsertRows => "insertRows",
+            Self::InsertHyperlinks => "insertHyperlinks",
+            Self::DeleteColumns => "deleteColumns",
+            Self::DeleteRows => "deleteRows",
+            Self::Sort => "sort",
+            Self::Filter => "autoFilter",
+            Self::PivotTables => "pivotTables",
+            Self::Objects => "objects",
+            Self::Scenarios => "scenarios",
+        }
+    }
+
+    /// Returns true if the action is blocked when its attribute is absent.
+    /// Objects and scenarios default to editable, everything else to
+    /// blocked.
+    pub(crate) fn blocked_by_default(&self) -> bool {
+        !matches!(self, Self::Objects | Self::Scenarios)
+    }
+}
+
+impl fmt::Display for SheetAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Password a protected sheet asks for before it can be unprotected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProtectionPassword {
+    /// 16-bit hash in the `password` attribute, as written by
+    /// [`SheetProtection::with_password`]
+    Legacy(u16),
+    /// Salted hash written by recent Excel versions, kept as read
+    Hashed {
+        /// Hash algorithm (e.g., `SHA-512`)
+        algorithm: String,
+        /// Base64 hash value
+        hash: String,
+        /// Base64 salt
+        salt: String,
+        /// Hashing iterations
+        spin_count: u32,
+    },
+}
+
+/// Protection of a sheet against edits.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SheetProtection {
+    /// Actions users may still take; everything else is blocked
+    pub allowed: BTreeSet<SheetAction>,
+    /// Password required to unprotect, if any
+    pub password: Option<ProtectionPassword>,
+}
+
+impl SheetProtection {
+    /// Creates protection that blocks everything except `allowed`.
+    pub fn new(allowed: impl IntoIterator<Item = SheetAction>) -> Self {
+        Self {
+            allowed: allowed.into_iter().collect(),
+            password: None,
+        }
+    }
+
+    /// Requires `password` to unprotect the sheet.
+    ///
+    /// The password is stored as Excel's legacy 16-bit hash, which keeps
+    /// the sheet from being unprotected by accident but is easily reversed:
+    /// it is not a security measure.
+    pub fn with_password(mut self, password: &str) -> Self {
+        self.password = Some(ProtectionPassword::Legacy(legacy_password_hash(password)));
+        self
+    }
+
+    /// Returns true if users may take `action` on the protected sheet.
+    pub fn allows(&self, action: SheetAction) -> bool {
+        self.allowed.contains(&action)
+    }
+}
+
+/// Computes Excel's legacy 16-bit password hash (ECMA-376 Part 4, 14.7.1).
+pub fn legacy_password_hash(password: &str) -> u16 {
+    // Each character contributes its low byte
+    let bytes: Vec<u16> = password.chars().map(|c| (c as u32 & 0xFF) as u16).collect();
+    let rotate = |hash: u16| ((hash >> 14) & 0x01) | ((hash << 1) & 0x7FFF);
+    let mut hash = 0u16;
+    for &byte in bytes.iter().rev() {
+        hash = rotate(hash) ^ byte;
+    }
+    rotate(hash) ^ (bytes.len() as u16) ^ 0xCE4B
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_password_hash() {
+        assert_eq!(legacy_password_hash("password"), 0x83AF);
+        assert_eq!(legacy_password_hash(""), 0xCE4B);
+        let protection = SheetProtection::default().with_password("password");
+        assert_eq!(
+            protection.password,
+            Some(ProtectionPassword::Legacy(0x83AF))
+        );
+    }
+
+    #[test]
+    fn test_sheet_action_parse() {
+        assert_eq!(SheetAction::parse("sort").unwrap(), SheetAction::Sort);
+        assert_eq!(
+            SheetAction::parse("AutoFilter").unwrap(),
+            SheetAction::Filter
+        );
+        assert_eq!(
+            SheetAction::parse("format_cells").unwrap(),
+            SheetAction::FormatCells
+        );
+        let error = SheetAction::parse("paint").unwrap_err().to_string();
+        assert!(error.contains("pivot-tables"));
+
+        let protection = SheetProtection::new([SheetAction::Sort, SheetAction::Sort]);
+        assert!(protection.allows(SheetAction::Sort));
+        assert!(!protection.allows(SheetAction::Filter));
+        assert_eq!(protection.allowed.len(), 1);
+    }
+}
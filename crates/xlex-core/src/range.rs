@@ -30,6 +30,15 @@ impl Range {
         }
     }
 
+    /// Parses a range with an optional sheet: `A1:B10`, `Sheet1!A1:B10`
+    /// or `'My Sheet'!A:A`.
+    pub fn parse_qualified(s: &str) -> XlexResult<(Option<String>, Self)> {
+        match crate::sheet::split_sheet_ref(s) {
+            Some((sheet, range)) => Ok((Some(sheet), Self::parse(range)?)),
+            None => Ok((None, Self::parse(s)?)),
+        }
+    }
+
     /// Parses a range string (e.g., "A1:B10", "A1", "A:A", "1:1").
     pub fn parse(s: &str) -> XlexResult<Self> {
         let s = s.trim();
@@ -141,6 +150,19 @@ impl Range {
             && cell.row <= self.end.row
     }
 
+    /// Returns true if this range shares at least one cell with `other`.
+    pub fn intersects(&self, other: &Range) -> bool {
+        self.start.col <= other.end.col
+            && other.start.col <= self.end.col
+            && self.start.row <= other.end.row
+            && other.start.row <= self.end.row
+    }
+
+    /// Returns true if every cell of `other` lies inside this range.
+    pub fn contains_range(&self, other: &Range) -> bool {
+        self.contains(&other.start) && self.contains(&other.end)
+    }
+
     /// Returns an iterator over all cell references in this range.
     pub fn cells(&self) -> RangeCellIterator {
         RangeCellIterator {
@@ -274,6 +296,17 @@ mod tests {
         assert!(Range::parse("0:1").is_err()); // Row 0 is invalid
     }
 
+    #[test]
+    fn test_range_parse_qualified() {
+        let (sheet, range) = Range::parse_qualified("'My Sheet'!A1:B10").unwrap();
+        assert_eq!(sheet.as_deref(), Some("My Sheet"));
+        assert_eq!(range, Range::parse("A1:B10").unwrap());
+        let (sheet, range) = Range::parse_qualified("Data!A:A").unwrap();
+        assert_eq!(sheet.as_deref(), Some("Data"));
+        assert_eq!(range.end.row, CellRef::MAX_ROW);
+        assert_eq!(Range::parse_qualified("C3").unwrap().0, None);
+    }
+
     #[test]
     fn test_range_dimensions() {
         let range = Range::parse("A1:C5").unwrap();
@@ -449,4 +482,13 @@ mod tests {
         // Row 0 is invalid
         assert!(Range::parse("0:10").is_err());
     }
+
+    #[test]
+    fn test_range_intersects() {
+        let a = Range::parse("A1:C3").unwrap();
+        assert!(a.intersects(&Range::parse("C3:D4").unwrap()));
+        assert!(!a.intersects(&Range::parse("D1:E3").unwrap()));
+        assert!(a.contains_range(&Range::parse("B2:C3").unwrap()));
+        assert!(!a.contains_range(&Range::parse("B2:D3").unwrap()));
+    }
 }
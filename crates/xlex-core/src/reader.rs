@@ -47,6 +47,7 @@ impl WorkbookReader {
     /// Opens a file with a custom mmap threshold.
     pub fn open_with_threshold(path: impl AsRef<Path>, threshold: u64) -> XlexResult<Self> {
         let path = path.as_ref();
+        let _span = tracing::info_span!("read_file", path = %path.display()).entered();
 
         // Check file exists
         if !path.exists() {
@@ -0,0 +1,196 @@
+//! Reshaping tables between wide and long layouts.
+//!
+//! Cross-tab exports put one measure in many columns, such as a column per
+//! month. [`unpivot`] turns such a table long, with a row per id and
+//! column; [`pivot_wider`] turns a long table back into one row per id.
+//! Tables are rows of values with the header row first, and columns are
+//! 0-based indexes into them.
+//!
+//! ```
+//! use xlex_core::reshape::{pivot_wider, unpivot};
+//! use xlex_core::CellValue;
+//!
+//! let wide = vec![
+//!     vec![CellValue::string("region"), CellValue::string("jan"), CellValue::string("feb")],
+//!     vec![CellValue::string("north"), CellValue::Number(5.0), CellValue::Number(7.0)],
+//! ];
+//! let long = unpivot(&wide, &[0], "month", "amount", false);
+//! assert_eq!(long.len(), 3);
+//! assert_eq!(long[2], vec![
+//!     CellValue::string("north"),
+//!     CellValue::string("feb"),
+//!     CellValue::Number(7.0),
+//! ]);
+//! assert_eq!(pivot_wider(&long, &[0], 1, 2)?, wide);
+//! # Ok::<(), xlex_core::XlexError>(())
+//! ```
+
+use std::collections::HashMap;
+
+use crate::cell::CellValue;
+use crate::error::{XlexError, XlexResult};
+
+fn cell(row: &[CellValue], index: usize) -> CellValue {
+    row.get(index).cloned().unwrap_or(CellValue::Empty)
+}
+
+/// Turns every column other than `id_cols` into rows of the id values, the
+/// column's header under `var_name` and its value under `value_name`.
+/// Blank values are dropped unless `keep_blanks` is set.
+pub fn unpivot(
+    rows: &[Vec<CellValue>],
+    id_cols: &[usize],
+    var_name: &str,
+    value_name: &str,
+    keep_blanks: bool,
+) -> Vec<Vec<CellValue>> {
+    let Some((header, data)) = rows.split_first() else {
+        return Vec::new();
+    };
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let value_cols: Vec<usize> = (0..width).filter(|c| !id_cols.contains(c)).collect();
+
+    let mut out_header: Vec<CellValue> = id_cols.iter().map(|&c| cell(header, c)).collect();
+    out_header.push(CellValue::string(var_name));
+    out_header.push(CellValue::string(value_name));
+    let mut out = vec![out_header];
+    for row in data {
+        let ids: Vec<CellValue> = id_cols.iter().map(|&c| cell(row, c)).collect();
+        for &col in &value_cols {
+            let value = cell(row, col);
+            if value.is_empty() && !keep_blanks {
+                continue;
+            }
+            let mut long = ids.clone();
+            long.push(cell(header, col));
+            long.push(value);
+            out.push(long);
+        }
+    }
+    out
+}
+
+/// Turns a long table into one row per distinct combination of `id_cols`,
+/// with a column for each distinct value of `names_col` holding the
+/// matching `values_col`. Rows and new columns keep the order in which
+/// they first appear. Two rows with the same ids and name are an error,
+/// since only one value fits in the cell.
+pub fn pivot_wider(
+    rows: &[Vec<CellValue>],
+    id_cols: &[usize],
+    names_col: usize,
+    values_col: usize,
+) -> XlexResult<Vec<Vec<CellValue>>> {
+    let Some((header, data)) = rows.split_first() else {
+        return Ok(Vec::new());
+    };
+    let key = |row: &[CellValue], cols: &[usize]| -> Vec<String> {
+        cols.iter()
+            .map(|&c| cell(row, c).to_display_string())
+            .collect()
+    };
+
+    let mut names: Vec<CellValue> = Vec::new();
+    let mut name_index: HashMap<String, usize> = HashMap::new();
+    let mut groups: Vec<(Vec<CellValue>, Vec<CellValue>)> = Vec::new();
+    let mut group_index: HashMap<Vec<String>, usize> = HashMap::new();
+    for row in data {
+        let name = cell(row, names_col);
+        let name_key = name.to_display_string();
+        let column = *name_index.entry(name_key.clone()).or_insert_with(|| {
+            names.push(name);
+            names.len() - 1
+        });
+        let group = *group_index.entry(key(row, id_cols)).or_insert_with(|| {
+            let ids = id_cols.iter().map(|&c| cell(row, c)).collect();
+            groups.push((ids, Vec::new()));
+            groups.len() - 1
+        });
+        let values = &mut groups[group].1;
+        if values.len() <= column {
+            values.resize(column + 1, CellValue::Empty);
+        } else if !values[column].is_empty() {
+            return Err(XlexError::InvalidOperation {
+                message: format!(
+                    "More than one value for {} '{}' with {}; aggregate the rows first",
+                    cell(header, names_col).to_display_string(),
+                    name_key,
+                    key(row, id_cols).join(", ")
+                ),
+            });
+        }
+        values[column] = cell(row, values_col);
+    }
+
+    let mut out_header: Vec<CellValue> = id_cols.iter().map(|&c| cell(header, c)).collect();
+    out_header.extend(names);
+    let mut out = vec![out_header];
+    for (mut ids, values) in groups {
+        ids.extend(values);
+        out.push(ids);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(rows: &[&[&str]]) -> Vec<Vec<CellValue>> {
+        rows.iter()
+            .map(|row| {
+                row.iter()
+                    .map(|v| match v.parse() {
+                        Ok(n) => CellValue::Number(n),
+                        Err(_) if v.is_empty() => CellValue::Empty,
+                        Err(_) => CellValue::string(*v),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_unpivot() {
+        let wide = table(&[
+            &["region", "rep", "jan", "feb"],
+            &["north", "ann", "5", ""],
+            &["south", "bo", "1", "2"],
+        ]);
+        let long = unpivot(&wide, &[0, 1], "month", "amount", false);
+        assert_eq!(
+            long,
+            table(&[
+                &["region", "rep", "month", "amount"],
+                &["north", "ann", "jan", "5"],
+                &["south", "bo", "jan", "1"],
+                &["south", "bo", "feb", "2"],
+            ])
+        );
+        assert_eq!(unpivot(&wide, &[0, 1], "m", "v", true).len(), 5);
+        assert!(unpivot(&[], &[0], "m", "v", false).is_empty());
+    }
+
+    #[test]
+    fn test_pivot_wider() {
+        let long = table(&[
+            &["region", "month", "amount"],
+            &["north", "jan", "5"],
+            &["south", "feb", "2"],
+            &["north", "feb", "7"],
+        ]);
+        assert_eq!(
+            pivot_wider(&long, &[0], 1, 2).unwrap(),
+            table(&[
+                &["region", "jan", "feb"],
+                &["north", "5", "7"],
+                &["south", "", "2"],
+            ])
+        );
+
+        let mut duplicated = long.clone();
+        duplicated.push(duplicated[1].clone());
+        let err = pivot_wider(&duplicated, &[0], 1, 2).unwrap_err();
+        assert!(err.to_string().contains("month 'jan'"));
+    }
+}
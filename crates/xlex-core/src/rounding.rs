@@ -0,0 +1,109 @@
+//! Rounding numbers to a number of decimal places.
+//!
+//! Values are first taken to 15 significant digits, as Excel does, so float
+//! noise such as `2.675` being stored as `2.67499999...` doesn't decide the
+//! result.
+//!
+//! ```
+//! use xlex_core::rounding::{round, RoundingMode};
+//!
+//! assert_eq!(round(2.675, 2, RoundingMode::HalfUp), 2.68);
+//! assert_eq!(round(0.125, 2, RoundingMode::HalfEven), 0.12);
+//! assert_eq!(round(1234.5, -2, RoundingMode::HalfUp), 1200.0);
+//! assert_eq!(round(0.1 + 0.2, 2, RoundingMode::Down), 0.3);
+//! ```
+
+/// How a value halfway between two results, or any value for `Down` and
+/// `Up`, is rounded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Halves away from zero, as Excel's `ROUND`
+    #[default]
+    HalfUp,
+    /// Halves to the even neighbour (banker's rounding)
+    HalfEven,
+    /// Toward zero, as `ROUNDDOWN`
+    Down,
+    /// Away from zero, as `ROUNDUP`
+    Up,
+}
+
+/// Keeps 15 significant digits, dropping binary noise below them.
+fn significant(value: f64) -> f64 {
+    format!("{:.14e}", value).parse().unwrap_or(value)
+}
+
+/// Rounds `value` to `decimals` places; negative `decimals` round to tens,
+/// hundreds and so on.
+pub fn round(value: f64, decimals: i32, mode: RoundingMode) -> f64 {
+    if !value.is_finite() {
+        return value;
+    }
+    let factor = 10f64.powi(decimals.abs());
+    let scaled = if decimals >= 0 {
+        significant(value) * factor
+    } else {
+        significant(value) / factor
+    };
+    if !scaled.is_finite() {
+        return value;
+    }
+    let scaled = significant(scaled);
+    let rounded = match mode {
+        RoundingMode::HalfUp => scaled.round(),
+        RoundingMode::HalfEven if (scaled - scaled.trunc()).abs() == 0.5 => {
+            2.0 * (scaled / 2.0).round()
+        }
+        RoundingMode::HalfEven => scaled.round(),
+        RoundingMode::Down => scaled.trunc(),
+        RoundingMode::Up if scaled.fract() == 0.0 => scaled,
+        RoundingMode::Up => scaled.trunc() + scaled.signum(),
+    };
+    let result = if decimals >= 0 {
+        rounded / factor
+    } else {
+        rounded * factor
+    };
+    // No negative zero
+    result + 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_modes() {
+        let cases = [
+            (2.5, 0, [3.0, 2.0, 2.0, 3.0]),
+            (-2.5, 0, [-3.0, -2.0, -2.0, -3.0]),
+            (3.5, 0, [4.0, 4.0, 3.0, 4.0]),
+            (1.005, 2, [1.01, 1.0, 1.0, 1.01]),
+            (1.0049, 2, [1.0, 1.0, 1.0, 1.01]),
+            (-0.0001, 2, [0.0, 0.0, 0.0, -0.01]),
+            (15.0, -1, [20.0, 20.0, 10.0, 20.0]),
+            (4.0, 1, [4.0, 4.0, 4.0, 4.0]),
+        ];
+        let modes = [
+            RoundingMode::HalfUp,
+            RoundingMode::HalfEven,
+            RoundingMode::Down,
+            RoundingMode::Up,
+        ];
+        for (value, decimals, expected) in cases {
+            for (mode, expected) in modes.iter().zip(expected) {
+                assert_eq!(
+                    round(value, decimals, *mode),
+                    expected,
+                    "{} to {} places, {:?}",
+                    value,
+                    decimals,
+                    mode
+                );
+            }
+        }
+        assert!(round(f64::NAN, 2, RoundingMode::HalfUp).is_nan());
+        assert_eq!(round(1e308, 10, RoundingMode::HalfUp), 1e308);
+        assert!(round(-0.001, 1, RoundingMode::HalfUp).is_sign_positive());
+    }
+}
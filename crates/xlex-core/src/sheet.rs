@@ -4,7 +4,9 @@ use std::fmt;
 
 use serde::{Deserialize, Serialize};
 
-use crate::cell::{Cell, CellRef, CellValue};
+use crate::cell::{ArrayFormula, Cell, CellRef, CellValue};
+use crate::error::{XlexError, XlexResult};
+use crate::style::StyleRegistry;
 
 /// Sheet visibility state.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
@@ -73,6 +75,186 @@ impl SheetInfo {
     }
 }
 
+/// An image attached to a sheet, such as a background picture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SheetImage {
+    /// Raw image bytes
+    pub data: Vec<u8>,
+    /// Normalized file extension (png, jpeg, gif or bmp)
+    pub extension: String,
+}
+
+impl SheetImage {
+    /// Creates an image from raw bytes and a file extension.
+    pub fn new(data: Vec<u8>, extension: &str) -> XlexResult<Self> {
+        let extension = match extension.to_ascii_lowercase().as_str() {
+            "png" => "png",
+            "jpg" | "jpeg" => "jpeg",
+            "gif" => "gif",
+            "bmp" => "bmp",
+            other => {
+                return Err(XlexError::InvalidOperation {
+                    message: format!(
+                        "Unsupported image format '{}', expected png, jpeg, gif or bmp",
+                        other
+                    ),
+                })
+            }
+        };
+        Ok(Self {
+            data,
+            extension: extension.to_string(),
+        })
+    }
+
+    /// Reads an image from a file, using its extension to detect the format.
+    pub fn from_path(path: impl AsRef<std::path::Path>) -> XlexResult<Self> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default();
+        let data = std::fs::read(path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => XlexError::FileNotFound {
+                path: path.to_path_buf(),
+            },
+            _ => XlexError::IoError {
+                message: e.to_string(),
+                source: Some(e),
+            },
+        })?;
+        Self::new(data, extension)
+    }
+
+    /// Returns the MIME content type for the image.
+    pub fn content_type(&self) -> &'static str {
+        match self.extension.as_str() {
+            "jpeg" => "image/jpeg",
+            "gif" => "image/gif",
+            "bmp" => "image/bmp",
+            _ => "image/png",
+        }
+    }
+}
+
+/// A dropdown list offered in a range of cells (a list data validation).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dropdown {
+    /// Cells showing the dropdown
+    pub range: crate::range::Range,
+    /// List source: a quoted item list (`"Open,Closed"`) or a reference
+    /// (`Lists!$A$1:$A$5`)
+    pub source: String,
+}
+
+impl Dropdown {
+    /// Creates a dropdown offering fixed items.
+    ///
+    /// Excel stores the items as one comma-separated string, so items may
+    /// not contain commas or quotes and the list is limited to 255 characters.
+    pub fn from_items(range: crate::range::Range, items: &[impl AsRef<str>]) -> XlexResult<Self> {
+        let items: Vec<&str> = items.iter().map(|i| i.as_ref().trim()).collect();
+        if items.is_empty() || items.iter().any(|i| i.contains([',', '"'])) {
+            return Err(XlexError::InvalidOperation {
+                message: "Dropdown items must be non-empty and cannot contain commas or quotes"
+                    .to_string(),
+            });
+        }
+        let list = items.join(",");
+        if list.chars().count() > 255 {
+            return Err(XlexError::InvalidOperation {
+                message: "Dropdown item list exceeds 255 characters, use a source range instead"
+                    .to_string(),
+            });
+        }
+        Ok(Self {
+            range,
+            source: format!("\"{}\"", list),
+        })
+    }
+
+    /// Creates a dropdown offering the values of a range (e.g., `Lists!$A$1:$A$5`).
+    pub fn from_reference(range: crate::range::Range, reference: impl Into<String>) -> Self {
+        Self {
+            range,
+            source: reference.into().trim_start_matches('=').to_string(),
+        }
+    }
+
+    /// Returns the fixed items, or `None` if the dropdown uses a reference.
+    pub fn items(&self) -> Option<Vec<&str>> {
+        self.source
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .map(|list| list.split(',').collect())
+    }
+}
+
+/// Quotes a sheet name for use in a formula reference when required.
+pub fn quote_sheet_name(name: &str) -> String {
+    let plain = name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !name.starts_with(|c: char| c.is_ascii_digit());
+    if plain {
+        name.to_string()
+    } else {
+        format!("'{}'", name.replace('\'', "''"))
+    }
+}
+
+/// Splits a sheet-qualified reference such as `Sheet1!B2` or
+/// `'My Sheet'!B2:C4` into the unquoted sheet name and the reference.
+/// Returns `None` when there is no sheet part.
+pub fn split_sheet_ref(s: &str) -> Option<(String, &str)> {
+    let s = s.trim();
+    if let Some(rest) = s.strip_prefix('\'') {
+        // Quoted name, with '' standing for a quote
+        let mut name = String::new();
+        let mut chars = rest.char_indices();
+        while let Some((i, c)) = chars.next() {
+            if c != '\'' {
+                name.push(c);
+            } else if rest[i + 1..].starts_with('\'') {
+                name.push('\'');
+                chars.next();
+            } else {
+                let reference = rest[i + 1..].strip_prefix('!')?;
+                return (!name.is_empty()).then_some((name, reference));
+            }
+        }
+        return None;
+    }
+    let (name, reference) = s.rsplit_once('!')?;
+    (!name.is_empty()).then(|| (name.to_string(), reference))
+}
+
+/// Which rows [`Sheet::column_values`] reads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ColumnOptions {
+    /// First row to read (default: 1)
+    pub first_row: Option<u32>,
+    /// Last row to read (default: the last row holding a cell)
+    pub last_row: Option<u32>,
+    /// Yield `Empty` for rows without a value instead of skipping them
+    pub include_empty: bool,
+}
+
+/// Value yielded for rows without one when empty rows are included.
+static EMPTY_VALUE: CellValue = CellValue::Empty;
+
+/// Font size, in points, that column widths are measured in.
+const DEFAULT_FONT_SIZE: f64 = 11.0;
+
+/// Height, in points, of a row holding one line of default-size text.
+const DEFAULT_LINE_HEIGHT: f64 = 15.0;
+
+/// Estimates how many lines `text` takes when wrapped at `chars_per_line`.
+fn wrapped_line_count(text: &str, chars_per_line: f64) -> usize {
+    let chars_per_line = chars_per_line.floor().max(1.0) as usize;
+    text.split('\n')
+        .map(|line| line.chars().count().div_ceil(chars_per_line).max(1))
+        .sum()
+}
+
 /// A worksheet containing cells.
 #[derive(Debug, Clone)]
 pub struct Sheet {
@@ -88,10 +270,37 @@ pub struct Sheet {
     hidden_rows: std::collections::HashSet<u32>,
     /// Hidden columns
     hidden_columns: std::collections::HashSet<u32>,
+    /// Default style of whole rows (row -> style ID)
+    row_styles: std::collections::HashMap<u32, u32>,
+    /// Default style of whole columns (col -> style ID)
+    column_styles: std::collections::HashMap<u32, u32>,
+    /// Default style of every column without its own style
+    default_style: Option<u32>,
     /// Merged cell ranges
     merged_ranges: Vec<crate::range::Range>,
-    /// Used range (cached, may be None if not computed)
-    used_range: Option<crate::range::Range>,
+    /// First and last stored column of each row, built on first use and
+    /// dropped whenever cells are added, removed or moved
+    extents: std::sync::OnceLock<std::collections::BTreeMap<u32, (u32, u32)>>,
+    /// Range declared by the worksheet's `<dimension>` element, when read from a file
+    declared_dimension: Option<crate::range::Range>,
+    /// Background picture tiled behind the cells
+    background: Option<SheetImage>,
+    /// Rows repeated at the top of every printed page (first, last)
+    print_title_rows: Option<(u32, u32)>,
+    /// Columns repeated at the left of every printed page (first, last)
+    print_title_columns: Option<(u32, u32)>,
+    /// Area printed instead of the used range
+    print_area: Option<crate::range::Range>,
+    /// Rows and columns kept in view while scrolling (rows, columns)
+    freeze_panes: Option<(u32, u32)>,
+    /// Range with filter buttons on its header row
+    auto_filter: Option<crate::range::Range>,
+    /// Sparkline groups drawn into cells
+    sparkline_groups: Vec<crate::sparkline::SparklineGroup>,
+    /// Dropdown lists on cell ranges
+    dropdowns: Vec<Dropdown>,
+    /// Protection against edits, if the sheet is protected
+    protection: Option<crate::protection::SheetProtection>,
 }
 
 impl Sheet {
@@ -104,8 +313,21 @@ impl Sheet {
             column_widths: std::collections::HashMap::new(),
             hidden_rows: std::collections::HashSet::new(),
             hidden_columns: std::collections::HashSet::new(),
+            row_styles: std::collections::HashMap::new(),
+            column_styles: std::collections::HashMap::new(),
+            default_style: None,
             merged_ranges: Vec::new(),
-            used_range: None,
+            extents: std::sync::OnceLock::new(),
+            declared_dimension: None,
+            background: None,
+            print_title_rows: None,
+            print_title_columns: None,
+            print_area: None,
+            freeze_panes: None,
+            auto_filter: None,
+            sparkline_groups: Vec::new(),
+            dropdowns: Vec::new(),
+            protection: None,
         }
     }
 
@@ -119,6 +341,22 @@ impl Sheet {
         self.info.name = name.into();
     }
 
+    /// Returns a full copy of the sheet named `name`.
+    ///
+    /// Cells with their styles, comments and hyperlinks, merged ranges, row
+    /// heights, column widths, hidden rows and columns, row and column
+    /// styles, dropdowns, sparklines, print settings, freeze panes, the
+    /// autofilter and the background picture are all copied. References to this sheet's own name are
+    /// renamed to point at the copy. The copy keeps this sheet's ID and
+    /// relationship ID; use [`crate::Workbook::duplicate_sheet`] to add it
+    /// to a workbook with its own.
+    pub fn duplicate_as(&self, name: &str) -> Sheet {
+        let mut sheet = self.clone();
+        sheet.set_name(name);
+        sheet.rename_sheet_references(&self.info.name, name);
+        sheet
+    }
+
     /// Gets a cell by reference.
     pub fn get_cell(&self, cell_ref: &CellRef) -> Option<&Cell> {
         self.cells.get(&(cell_ref.col, cell_ref.row))
@@ -140,14 +378,37 @@ impl Sheet {
     pub fn set_cell(&mut self, cell_ref: CellRef, value: CellValue) {
         let cell = Cell::new(cell_ref.clone(), value);
         self.cells.insert((cell_ref.col, cell_ref.row), cell);
-        self.used_range = None; // Invalidate cache
+        self.invalidate_extents();
+    }
+
+    /// Sets many cell values at once, returning how many were written.
+    ///
+    /// Each update behaves like [`Sheet::set_cell`], but the cell map is
+    /// grown once up front and the cached extents are invalidated once for
+    /// the whole batch.
+    pub fn apply_updates<I>(&mut self, updates: I) -> usize
+    where
+        I: IntoIterator<Item = (CellRef, CellValue)>,
+    {
+        let updates = updates.into_iter();
+        self.cells.reserve(updates.size_hint().0);
+        let mut count = 0;
+        for (cell_ref, value) in updates {
+            let key = (cell_ref.col, cell_ref.row);
+            self.cells.insert(key, Cell::new(cell_ref, value));
+            count += 1;
+        }
+        if count > 0 {
+            self.invalidate_extents();
+        }
+        count
     }
 
     /// Inserts a complete cell object, preserving all its properties (style_id, comment, hyperlink).
     pub fn insert_cell(&mut self, cell: Cell) {
         self.cells
             .insert((cell.reference.col, cell.reference.row), cell);
-        self.used_range = None; // Invalidate cache
+        self.invalidate_extents();
     }
 
     /// Sets a cell's style ID.
@@ -159,9 +420,53 @@ impl Sheet {
             let mut cell = Cell::empty(cell_ref.clone());
             cell.style_id = Some(id);
             self.cells.insert((cell_ref.col, cell_ref.row), cell);
+            self.invalidate_extents();
         }
     }
 
+    /// Sets an array formula over `range`, anchored at its top-left cell.
+    ///
+    /// The anchor keeps its style; other cells in the range keep their
+    /// values until the formula is recalculated.
+    pub fn set_array_formula(
+        &mut self,
+        range: &crate::range::Range,
+        formula: impl Into<String>,
+        dynamic: bool,
+    ) {
+        let anchor = range.start.clone();
+        let style_id = self.get_cell(&anchor).and_then(|cell| cell.style_id);
+        let mut cell = Cell::new(anchor, CellValue::formula(formula))
+            .with_array(ArrayFormula::covering(range, dynamic));
+        cell.style_id = style_id;
+        self.insert_cell(cell);
+    }
+
+    /// Finds the array formula whose result covers a cell, returning its
+    /// anchor and shape.
+    pub fn array_formula_at(&self, cell_ref: &CellRef) -> Option<(CellRef, ArrayFormula)> {
+        self.cells.iter().find_map(|(&(col, row), cell)| {
+            let array = cell.array?;
+            let anchor = CellRef::new(col, row);
+            array
+                .range(&anchor)
+                .contains(cell_ref)
+                .then_some((anchor, array))
+        })
+    }
+
+    /// References of the cells holding a formula, in row-major order.
+    pub fn formula_cells(&self) -> Vec<CellRef> {
+        let mut refs: Vec<CellRef> = self
+            .cells
+            .iter()
+            .filter(|(_, cell)| matches!(cell.value, CellValue::Formula { .. }))
+            .map(|(&(col, row), _)| CellRef::new(col, row))
+            .collect();
+        refs.sort_by_key(|r| (r.row, r.col));
+        refs
+    }
+
     /// Sets a cell's comment.
     pub fn set_cell_comment(&mut self, cell_ref: &CellRef, comment: Option<String>) {
         if let Some(cell) = self.cells.get_mut(&(cell_ref.col, cell_ref.row)) {
@@ -170,6 +475,7 @@ impl Sheet {
             let mut cell = Cell::empty(cell_ref.clone());
             cell.comment = Some(text);
             self.cells.insert((cell_ref.col, cell_ref.row), cell);
+            self.invalidate_extents();
         }
     }
 
@@ -181,13 +487,14 @@ impl Sheet {
             let mut cell = Cell::empty(cell_ref.clone());
             cell.hyperlink = Some(url);
             self.cells.insert((cell_ref.col, cell_ref.row), cell);
+            self.invalidate_extents();
         }
     }
 
     /// Clears a cell.
     pub fn clear_cell(&mut self, cell_ref: &CellRef) {
         self.cells.remove(&(cell_ref.col, cell_ref.row));
-        self.used_range = None;
+        self.invalidate_extents();
     }
 
     /// Returns an iterator over all cells.
@@ -195,6 +502,70 @@ impl Sheet {
         self.cells.values()
     }
 
+    /// Iterates the non-empty cells within `range` in row-major order.
+    ///
+    /// Only rows holding cells are visited, and within them only the span
+    /// between their first and last cell, so sparse or whole-column ranges
+    /// cost about as much as the cells they contain.
+    pub fn iter_range<'a>(
+        &'a self,
+        range: &crate::range::Range,
+    ) -> impl Iterator<Item = (CellRef, &'a Cell)> + 'a {
+        let (first_row, last_row) = (
+            range.start.row.min(range.end.row),
+            range.start.row.max(range.end.row),
+        );
+        let (first_col, last_col) = (
+            range.start.col.min(range.end.col),
+            range.start.col.max(range.end.col),
+        );
+        self.extents()
+            .range(first_row..=last_row)
+            .flat_map(move |(&row, &(first, last))| {
+                (first.max(first_col)..=last.min(last_col)).map(move |col| (col, row))
+            })
+            .filter_map(move |key| {
+                self.cells
+                    .get(&key)
+                    .filter(|cell| !cell.value.is_empty())
+                    .map(|cell| (CellRef::new(key.0, key.1), cell))
+            })
+    }
+
+    /// Iterates the values of column `col` top to bottom with their row numbers.
+    ///
+    /// Reads the column directly instead of going through rows, skipping
+    /// rows whose cells do not reach it.
+    pub fn column_values(
+        &self,
+        col: u32,
+        options: ColumnOptions,
+    ) -> Box<dyn Iterator<Item = (u32, &CellValue)> + '_> {
+        let first_row = options.first_row.unwrap_or(1).max(1);
+        let last_row = options.last_row.or_else(|| self.last_row()).unwrap_or(0);
+        if first_row > last_row {
+            return Box::new(std::iter::empty());
+        }
+        let value = move |row: u32| {
+            self.cells
+                .get(&(col, row))
+                .map(|cell| &cell.value)
+                .filter(|value| !value.is_empty())
+        };
+        if options.include_empty {
+            Box::new(
+                (first_row..=last_row).map(move |row| (row, value(row).unwrap_or(&EMPTY_VALUE))),
+            )
+        } else {
+            Box::new(
+                self.extents()
+                    .range(first_row..=last_row)
+                    .filter(move |(_, &(first, last))| (first..=last).contains(&col))
+                    .filter_map(move |(&row, _)| value(row).map(|value| (row, value))),
+            )
+        }
+    }
+
     /// Returns the number of non-empty cells.
     pub fn cell_count(&self) -> usize {
         self.cells.len()
@@ -205,22 +576,90 @@ impl Sheet {
         self.cells.is_empty()
     }
 
+    /// Hash of the cell contents: positions, values, formulas and their
+    /// cached results, ignoring formatting, comments and links.
+    ///
+    /// The hash is FNV-1a over a canonical encoding of the cells in
+    /// row-major order, so it is stable across runs and versions and can be
+    /// stored to detect changes later.
+    pub fn content_hash(&self) -> u64 {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        self.write_canonical(|bytes| {
+            for &byte in bytes {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        });
+        hash
+    }
+
+    /// Feeds `write` the canonical encoding of the cell contents hashed by
+    /// [`Sheet::content_hash`]: one `row,col,value` line per non-empty
+    /// cell in row-major order, with the value as JSON.
+    pub(crate) fn write_canonical(&self, mut write: impl FnMut(&[u8])) {
+        let mut cells: Vec<(&(u32, u32), &Cell)> = self
+            .cells
+            .iter()
+            .filter(|(_, cell)| !matches!(cell.value, CellValue::Empty))
+            .collect();
+        cells.sort_by_key(|&(&(col, row), _)| (row, col));
+
+        for (&(col, row), cell) in cells {
+            let value = serde_json::to_string(&cell.value).unwrap_or_default();
+            write(format!("{},{},{}\n", row, col, value).as_bytes());
+        }
+    }
+
     /// Returns the dimensions of the sheet as (max_col, max_row).
     /// Returns (0, 0) if the sheet is empty.
     pub fn dimensions(&self) -> (u32, u32) {
-        if self.cells.is_empty() {
-            return (0, 0);
-        }
+        let extents = self.extents();
+        let max_col = extents.values().map(|&(_, last)| last).max().unwrap_or(0);
+        (max_col, self.last_row().unwrap_or(0))
+    }
 
-        let mut max_col = 0;
-        let mut max_row = 0;
+    /// Returns the last row holding a cell, if any.
+    pub fn last_row(&self) -> Option<u32> {
+        self.extents().keys().next_back().copied()
+    }
 
-        for (col, row) in self.cells.keys() {
-            max_col = max_col.max(*col);
-            max_row = max_row.max(*row);
-        }
+    /// Returns the last column holding a cell in `row`, if any.
+    pub fn last_col_in_row(&self, row: u32) -> Option<u32> {
+        self.extents().get(&row).map(|&(_, last)| last)
+    }
+
+    /// Returns the range declared by the worksheet's `<dimension>` element.
+    ///
+    /// Writers often leave it stale, so it may disagree with [`Sheet::used_range`].
+    pub fn declared_dimension(&self) -> Option<&crate::range::Range> {
+        self.declared_dimension.as_ref()
+    }
+
+    /// Records the range declared by the worksheet's `<dimension>` element.
+    pub(crate) fn set_declared_dimension(&mut self, range: Option<crate::range::Range>) {
+        self.declared_dimension = range;
+    }
+
+    /// Per-row column extents of the stored cells.
+    fn extents(&self) -> &std::collections::BTreeMap<u32, (u32, u32)> {
+        self.extents.get_or_init(|| {
+            let mut extents = std::collections::BTreeMap::new();
+            for &(col, row) in self.cells.keys() {
+                extents
+                    .entry(row)
+                    .and_modify(|(first, last): &mut (u32, u32)| {
+                        *first = (*first).min(col);
+                        *last = (*last).max(col);
+                    })
+                    .or_insert((col, col));
+            }
+            extents
+        })
+    }
 
-        (max_col, max_row)
+    /// Drops the cached extents after cells were added, removed or moved.
+    fn invalidate_extents(&mut self) {
+        self.extents.take();
     }
 
     /// Gets the row height.
@@ -243,6 +682,39 @@ impl Sheet {
         self.column_widths.insert(col, width);
     }
 
+    /// Writes text that wraps within its column instead of spilling over.
+    ///
+    /// Turns on wrapping in the cell's style, widens the column to fit the
+    /// longest line up to `max_width` characters, and raises the row so every
+    /// wrapped line is visible. Wider columns and taller rows are kept.
+    pub fn write_wrapped(
+        &mut self,
+        styles: &mut StyleRegistry,
+        cell_ref: &CellRef,
+        text: impl Into<String>,
+        max_width: f64,
+    ) {
+        let text = text.into();
+        let mut style = styles.resolve(self.effective_style_id(cell_ref));
+        style.wrap_text = true;
+        let font_size = style.font.size.unwrap_or(DEFAULT_FONT_SIZE);
+        let style_id = styles.clone_for(&style);
+
+        let longest = text.lines().map(|line| line.chars().count()).max();
+        let width = (longest.unwrap_or(0) as f64 + 1.0)
+            .min(max_width)
+            .max(self.get_column_width(cell_ref.col).unwrap_or(1.0));
+        let lines = wrapped_line_count(&text, width * DEFAULT_FONT_SIZE / font_size);
+        let height = lines as f64 * font_size * DEFAULT_LINE_HEIGHT / DEFAULT_FONT_SIZE;
+
+        self.set_cell(cell_ref.clone(), CellValue::String(text));
+        self.set_cell_style(cell_ref, Some(style_id));
+        self.set_column_width(cell_ref.col, width);
+        if self.get_row_height(cell_ref.row).unwrap_or(0.0) < height {
+            self.set_row_height(cell_ref.row, height);
+        }
+    }
+
     /// Checks if a row is hidden.
     pub fn is_row_hidden(&self, row: u32) -> bool {
         self.hidden_rows.contains(&row)
@@ -271,6 +743,132 @@ impl Sheet {
         }
     }
 
+    /// Gets the default style of a row.
+    pub fn get_row_style(&self, row: u32) -> Option<u32> {
+        self.row_styles.get(&row).copied()
+    }
+
+    /// Sets or clears the default style of a row.
+    pub fn set_row_style(&mut self, row: u32, style_id: Option<u32>) {
+        match style_id {
+            Some(id) => self.row_styles.insert(row, id),
+            None => self.row_styles.remove(&row),
+        };
+    }
+
+    /// Gets the default style of a column.
+    pub fn get_column_style(&self, col: u32) -> Option<u32> {
+        self.column_styles.get(&col).copied()
+    }
+
+    /// Sets or clears the default style of a column.
+    pub fn set_column_style(&mut self, col: u32, style_id: Option<u32>) {
+        match style_id {
+            Some(id) => self.column_styles.insert(col, id),
+            None => self.column_styles.remove(&col),
+        };
+    }
+
+    /// Gets the sheet's default style, used by columns without their own.
+    pub fn default_style(&self) -> Option<u32> {
+        self.default_style
+    }
+
+    /// Sets or clears the sheet's default style.
+    pub fn set_default_style(&mut self, style_id: Option<u32>) {
+        self.default_style = style_id;
+    }
+
+    /// Returns the style a cell is displayed with: its own style, else its
+    /// row's, else its column's, else the sheet default.
+    pub fn effective_style_id(&self, cell_ref: &CellRef) -> Option<u32> {
+        self.get_cell(cell_ref)
+            .and_then(|cell| cell.style_id)
+            .or_else(|| self.get_row_style(cell_ref.row))
+            .or_else(|| self.get_column_style(cell_ref.col))
+            .or(self.default_style)
+    }
+
+    /// Returns every style ID referenced by the sheet's cells, rows,
+    /// columns and default style, with repeats.
+    pub fn style_ids(&self) -> impl Iterator<Item = u32> + '_ {
+        self.cells
+            .values()
+            .filter_map(|cell| cell.style_id)
+            .chain(self.row_styles.values().copied())
+            .chain(self.column_styles.values().copied())
+            .chain(self.default_style)
+    }
+
+    /// Removes blank cells, row heights and column widths past the last
+    /// row and column holding anything visible.
+    ///
+    /// A cell is blank when it has no value, comment or hyperlink and its
+    /// style draws no fill or border and keeps the default protection, so
+    /// cells unlocked for input on a protected sheet stay. Hidden rows and
+    /// columns are kept.
+    /// Returns the number of (cells, row heights, column widths) removed.
+    pub fn trim_trailing_blanks(
+        &mut self,
+        registry: &crate::style::StyleRegistry,
+    ) -> (usize, usize, usize) {
+        let is_blank = |cell: &Cell| {
+            matches!(cell.value, CellValue::Empty)
+                && cell.comment.is_none()
+                && cell.hyperlink.is_none()
+                && cell
+                    .style_id
+                    .and_then(|id| registry.get(id))
+                    .map_or(true, |s| {
+                        s.fill == crate::style::Fill::default()
+                            && s.border == crate::style::Border::default()
+                            && s.protection == crate::style::Protection::default()
+                    })
+        };
+        let (max_col, max_row) = self
+            .cells
+            .values()
+            .filter(|cell| !is_blank(cell))
+            .fold((0, 0), |(col, row), cell| {
+                (col.max(cell.reference.col), row.max(cell.reference.row))
+            });
+
+        let before = self.cells.len();
+        self.cells
+            .retain(|&(col, row), cell| (col <= max_col && row <= max_row) || !is_blank(cell));
+        let cells = before - self.cells.len();
+
+        let hidden_rows = &self.hidden_rows;
+        let before = self.row_heights.len();
+        self.row_heights
+            .retain(|row, _| *row <= max_row || hidden_rows.contains(row));
+        let rows = before - self.row_heights.len();
+
+        let hidden_columns = &self.hidden_columns;
+        let before = self.column_widths.len();
+        self.column_widths
+            .retain(|col, _| *col <= max_col || hidden_columns.contains(col));
+        let columns = before - self.column_widths.len();
+
+        if cells > 0 {
+            self.invalidate_extents();
+        }
+        (cells, rows, columns)
+    }
+
+    /// Maps every cell's style ID through `remap`; IDs missing from it are
+    /// cleared.
+    pub(crate) fn remap_styles(&mut self, remap: &std::collections::HashMap<u32, u32>) {
+        for cell in self.cells.values_mut() {
+            cell.style_id = cell.style_id.and_then(|id| remap.get(&id).copied());
+        }
+        self.row_styles
+            .retain(|_, id| remap.get(id).map(|new| *id = *new).is_some());
+        self.column_styles
+            .retain(|_, id| remap.get(id).map(|new| *id = *new).is_some());
+        self.default_style = self.default_style.and_then(|id| remap.get(&id).copied());
+    }
+
     /// Gets merged ranges.
     pub fn merged_ranges(&self) -> &[crate::range::Range] {
         &self.merged_ranges
@@ -296,6 +894,16 @@ impl Sheet {
         &self.hidden_columns
     }
 
+    /// Returns all row default styles (row -> style ID).
+    pub fn row_styles(&self) -> &std::collections::HashMap<u32, u32> {
+        &self.row_styles
+    }
+
+    /// Returns all column default styles (col -> style ID).
+    pub fn column_styles(&self) -> &std::collections::HashMap<u32, u32> {
+        &self.column_styles
+    }
+
     /// Adds a merged range.
     pub fn add_merged_range(&mut self, range: crate::range::Range) {
         self.merged_ranges.push(range);
@@ -306,39 +914,293 @@ impl Sheet {
         self.merged_ranges.retain(|r| r != range);
     }
 
-    /// Calculates and returns the used range.
-    pub fn calculate_used_range(&mut self) -> Option<crate::range::Range> {
-        if self.cells.is_empty() {
-            self.used_range = None;
-            return None;
+    /// Returns the background picture, if any.
+    pub fn background(&self) -> Option<&SheetImage> {
+        self.background.as_ref()
+    }
+
+    /// Sets or clears the background picture.
+    pub fn set_background(&mut self, image: Option<SheetImage>) {
+        self.background = image;
+    }
+
+    /// Returns the rows and columns repeated on every printed page.
+    pub fn print_titles(&self) -> (Option<(u32, u32)>, Option<(u32, u32)>) {
+        (self.print_title_rows, self.print_title_columns)
+    }
+
+    /// Sets the rows (e.g. `Some((1, 1))` for the header row) and columns
+    /// repeated on every printed page. Pass `None` for both to clear them.
+    ///
+    /// These are written as the sheet-scoped `_xlnm.Print_Titles` defined name.
+    pub fn set_print_titles(&mut self, rows: Option<(u32, u32)>, cols: Option<(u32, u32)>) {
+        let ordered = |(a, b): (u32, u32)| (a.min(b), a.max(b));
+        self.print_title_rows = rows.map(ordered);
+        self.print_title_columns = cols.map(ordered);
+    }
+
+    /// Returns the print area, if any.
+    pub fn print_area(&self) -> Option<&crate::range::Range> {
+        self.print_area.as_ref()
+    }
+
+    /// Sets or clears the print area, written as the sheet-scoped
+    /// `_xlnm.Print_Area` defined name.
+    pub fn set_print_area(&mut self, area: Option<crate::range::Range>) {
+        self.print_area = area;
+    }
+
+    /// Returns the `_xlnm.Print_Titles` formula for this sheet, if set.
+    pub fn print_titles_reference(&self) -> Option<String> {
+        let prefix = quote_sheet_name(self.name());
+        let mut parts = Vec::new();
+        if let Some((first, last)) = self.print_title_columns {
+            parts.push(format!(
+                "{}!${}:${}",
+                prefix,
+                CellRef::col_to_letters(first),
+                CellRef::col_to_letters(last)
+            ));
+        }
+        if let Some((first, last)) = self.print_title_rows {
+            parts.push(format!("{}!${}:${}", prefix, first, last));
         }
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(","))
+        }
+    }
+
+    /// Returns the `_xlnm.Print_Area` formula for this sheet, if set.
+    pub fn print_area_reference(&self) -> Option<String> {
+        self.print_area.as_ref().map(|area| {
+            format!(
+                "{}!${}${}:${}${}",
+                quote_sheet_name(self.name()),
+                CellRef::col_to_letters(area.start.col),
+                area.start.row,
+                CellRef::col_to_letters(area.end.col),
+                area.end.row
+            )
+        })
+    }
+
+    /// Returns the frozen panes as the number of (rows, columns) kept in view.
+    pub fn freeze_panes(&self) -> Option<(u32, u32)> {
+        self.freeze_panes
+    }
+
+    /// Keeps the top `rows` rows and left `cols` columns in view while
+    /// scrolling. Freezing nothing (`0, 0`) removes the panes.
+    pub fn set_freeze_panes(&mut self, rows: u32, cols: u32) {
+        self.freeze_panes = (rows > 0 || cols > 0).then_some((rows, cols));
+    }
+
+    /// Returns the range with filter buttons, if set.
+    pub fn auto_filter(&self) -> Option<&crate::range::Range> {
+        self.auto_filter.as_ref()
+    }
 
-        let mut min_col = u32::MAX;
-        let mut max_col = 0;
-        let mut min_row = u32::MAX;
-        let mut max_row = 0;
+    /// Sets or clears the filtered range. Its first row holds the filter
+    /// buttons.
+    pub fn set_auto_filter(&mut self, range: Option<crate::range::Range>) {
+        self.auto_filter = range;
+    }
+
+    /// Returns the `_xlnm._FilterDatabase` formula for this sheet, if an
+    /// autofilter is set.
+    pub fn auto_filter_reference(&self) -> Option<String> {
+        self.auto_filter.as_ref().map(|range| {
+            format!(
+                "{}!${}${}:${}${}",
+                quote_sheet_name(self.name()),
+                CellRef::col_to_letters(range.start.col),
+                range.start.row,
+                CellRef::col_to_letters(range.end.col),
+                range.end.row
+            )
+        })
+    }
+
+    /// Returns the sparkline groups on this sheet.
+    pub fn sparkline_groups(&self) -> &[crate::sparkline::SparklineGroup] {
+        &self.sparkline_groups
+    }
+
+    /// Adds a sparkline group. Existing sparklines drawn in the same cells
+    /// are replaced.
+    pub fn add_sparkline_group(&mut self, group: crate::sparkline::SparklineGroup) {
+        for sparkline in &group.sparklines {
+            self.remove_sparkline(&sparkline.location);
+        }
+        if !group.sparklines.is_empty() {
+            self.sparkline_groups.push(group);
+        }
+    }
 
-        for (col, row) in self.cells.keys() {
-            min_col = min_col.min(*col);
-            max_col = max_col.max(*col);
-            min_row = min_row.min(*row);
-            max_row = max_row.max(*row);
+    /// Removes the sparkline drawn in a cell. Returns true if one was removed.
+    pub fn remove_sparkline(&mut self, location: &CellRef) -> bool {
+        let mut removed = false;
+        for group in &mut self.sparkline_groups {
+            let before = group.sparklines.len();
+            group.sparklines.retain(|s| &s.location != location);
+            removed |= group.sparklines.len() < before;
         }
+        self.sparkline_groups.retain(|g| !g.sparklines.is_empty());
+        removed
+    }
 
-        let range = crate::range::Range::new(
-            CellRef::new(min_col, min_row),
-            CellRef::new(max_col, max_row),
-        );
-        self.used_range = Some(range.clone());
-        Some(range)
+    /// Returns the dropdown lists on this sheet.
+    pub fn dropdowns(&self) -> &[Dropdown] {
+        &self.dropdowns
     }
 
-    /// Returns the cached used range, or calculates it if not cached.
-    pub fn used_range(&mut self) -> Option<crate::range::Range> {
-        if self.used_range.is_none() {
-            self.calculate_used_range();
+    /// Adds a dropdown, replacing any dropdowns overlapping its range.
+    pub fn add_dropdown(&mut self, dropdown: Dropdown) {
+        self.remove_dropdowns(&dropdown.range);
+        self.dropdowns.push(dropdown);
+    }
+
+    /// Removes dropdowns overlapping a range. Returns how many were removed.
+    pub fn remove_dropdowns(&mut self, range: &crate::range::Range) -> usize {
+        let before = self.dropdowns.len();
+        self.dropdowns.retain(|d| !d.range.intersects(range));
+        before - self.dropdowns.len()
+    }
+
+    /// Returns the sheet's protection, if it is protected.
+    pub fn protection(&self) -> Option<&crate::protection::SheetProtection> {
+        self.protection.as_ref()
+    }
+
+    /// Protects the sheet, or removes its protection with `None`. Only
+    /// locked cells are protected; see [`Workbook::set_cell_protection`].
+    ///
+    /// [`Workbook::set_cell_protection`]: crate::Workbook::set_cell_protection
+    pub fn set_protection(&mut self, protection: Option<crate::protection::SheetProtection>) {
+        self.protection = protection;
+    }
+
+    /// Returns every formula-like text on the sheet: cell formulas,
+    /// dropdown sources and sparkline data ranges.
+    pub(crate) fn formula_texts(&self) -> impl Iterator<Item = &str> {
+        let formulas = self.cells.values().filter_map(|cell| match &cell.value {
+            CellValue::Formula { formula, .. } => Some(formula.as_str()),
+            _ => None,
+        });
+        let dropdowns = self.dropdowns.iter().map(|d| d.source.as_str());
+        let sparklines = self
+            .sparkline_groups
+            .iter()
+            .flat_map(|g| g.sparklines.iter().map(|s| s.data.as_str()));
+        formulas.chain(dropdowns).chain(sparklines)
+    }
+
+    /// Rewrites references to the sheet named `old` in formulas, sparkline
+    /// data and dropdown sources so they name `new`.
+    pub(crate) fn rename_sheet_references(&mut self, old: &str, new: &str) {
+        use crate::formula::rename_sheet_refs;
+
+        for cell in self.cells.values_mut() {
+            if let CellValue::Formula { formula, .. } = &mut cell.value {
+                *formula = rename_sheet_refs(formula, old, new);
+            }
+        }
+        for group in &mut self.sparkline_groups {
+            for sparkline in &mut group.sparklines {
+                sparkline.data = rename_sheet_refs(&sparkline.data, old, new);
+            }
+        }
+        for dropdown in &mut self.dropdowns {
+            dropdown.source = rename_sheet_refs(&dropdown.source, old, new);
+        }
+    }
+
+    /// Applies a built-in print defined name read from a workbook.
+    /// Returns false if the name or reference is not understood.
+    pub(crate) fn apply_print_name(&mut self, name: &str, reference: &str) -> bool {
+        let mut parts = Vec::new();
+        let mut current = String::new();
+        let mut in_quotes = false;
+        for c in reference.chars() {
+            match c {
+                '\'' => {
+                    in_quotes = !in_quotes;
+                    current.push(c);
+                }
+                ',' if !in_quotes => parts.push(std::mem::take(&mut current)),
+                _ => current.push(c),
+            }
+        }
+        parts.push(current);
+        let parts: Vec<String> = parts
+            .iter()
+            .map(|p| {
+                let local = p.rsplit_once('!').map(|(_, r)| r).unwrap_or(p);
+                local.replace('$', "")
+            })
+            .collect();
+
+        if name.eq_ignore_ascii_case(crate::workbook::DefinedName::PRINT_AREA) {
+            if parts.len() != 1 {
+                return false;
+            }
+            match crate::range::Range::parse(&parts[0]) {
+                Ok(area) => {
+                    self.print_area = Some(area);
+                    true
+                }
+                Err(_) => false,
+            }
+        } else if name.eq_ignore_ascii_case(crate::workbook::DefinedName::PRINT_TITLES) {
+            let mut rows = None;
+            let mut cols = None;
+            for part in &parts {
+                let Some((first, last)) = part.split_once(':') else {
+                    return false;
+                };
+                if let (Ok(first), Ok(last)) = (first.parse::<u32>(), last.parse::<u32>()) {
+                    rows = Some((first, last));
+                } else if let (Some(first), Some(last)) = (
+                    CellRef::col_from_letters_pub(first),
+                    CellRef::col_from_letters_pub(last),
+                ) {
+                    cols = Some((first, last));
+                } else {
+                    return false;
+                }
+            }
+            self.set_print_titles(rows, cols);
+            true
+        } else {
+            false
         }
-        self.used_range.clone()
+    }
+
+    /// Recalculates and returns the used range.
+    pub fn calculate_used_range(&mut self) -> Option<crate::range::Range> {
+        self.invalidate_extents();
+        self.used_range()
+    }
+
+    /// Returns the smallest range covering every stored cell, including
+    /// cells that only carry a style, comment or hyperlink.
+    ///
+    /// Computed on first use and kept accurate across edits.
+    pub fn used_range(&self) -> Option<crate::range::Range> {
+        let extents = self.extents();
+        let (&first_row, _) = extents.first_key_value()?;
+        let (&last_row, _) = extents.last_key_value()?;
+        let (first_col, last_col) = extents
+            .values()
+            .fold((u32::MAX, 0), |(first, last), &(f, l)| {
+                (first.min(f), last.max(l))
+            });
+        Some(crate::range::Range::new(
+            CellRef::new(first_col, first_row),
+            CellRef::new(last_col, last_row),
+        ))
     }
 
     /// Inserts a row at the specified position, shifting all rows below down by one.
@@ -365,7 +1227,8 @@ impl Sheet {
         }
 
         // Insert at new positions (shifted down)
-        for ((col, r), cell) in cells_to_shift {
+        for ((col, r), mut cell) in cells_to_shift {
+            cell.reference.row = r + count;
             self.cells.insert((col, r + count), cell);
         }
 
@@ -385,6 +1248,22 @@ impl Sheet {
             self.row_heights.insert(r + count, h);
         }
 
+        // Shift row styles
+        let styles_to_shift: Vec<_> = self
+            .row_styles
+            .iter()
+            .filter(|(r, _)| **r >= row)
+            .map(|(r, s)| (*r, *s))
+            .collect();
+
+        for (r, _) in &styles_to_shift {
+            self.row_styles.remove(r);
+        }
+
+        for (r, s) in styles_to_shift {
+            self.row_styles.insert(r + count, s);
+        }
+
         // Shift hidden rows
         let hidden_to_shift: Vec<_> = self
             .hidden_rows
@@ -412,7 +1291,7 @@ impl Sheet {
         }
 
         // Invalidate used range cache
-        self.used_range = None;
+        self.invalidate_extents();
     }
 
     /// Deletes rows starting at the specified position, shifting all rows below up.
@@ -444,7 +1323,8 @@ impl Sheet {
         }
 
         // Insert at new positions (shifted up)
-        for ((col, r), cell) in cells_to_shift {
+        for ((col, r), mut cell) in cells_to_shift {
+            cell.reference.row = r - count;
             self.cells.insert((col, r - count), cell);
         }
 
@@ -468,6 +1348,26 @@ impl Sheet {
             self.row_heights.insert(r - count, h);
         }
 
+        // Remove deleted row styles and shift remaining
+        for r in row..=end_row {
+            self.row_styles.remove(&r);
+        }
+
+        let styles_to_shift: Vec<_> = self
+            .row_styles
+            .iter()
+            .filter(|(r, _)| **r > end_row)
+            .map(|(r, s)| (*r, *s))
+            .collect();
+
+        for (r, _) in &styles_to_shift {
+            self.row_styles.remove(r);
+        }
+
+        for (r, s) in styles_to_shift {
+            self.row_styles.insert(r - count, s);
+        }
+
         // Remove deleted hidden rows and shift remaining
         for r in row..=end_row {
             self.hidden_rows.remove(&r);
@@ -512,7 +1412,7 @@ impl Sheet {
         });
 
         // Invalidate used range cache
-        self.used_range = None;
+        self.invalidate_extents();
     }
 
     /// Inserts columns at the specified position, shifting all columns to the right.
@@ -539,7 +1439,8 @@ impl Sheet {
         }
 
         // Insert at new positions (shifted right)
-        for ((c, r), cell) in cells_to_shift {
+        for ((c, r), mut cell) in cells_to_shift {
+            cell.reference.col = c + count;
             self.cells.insert((c + count, r), cell);
         }
 
@@ -559,6 +1460,22 @@ impl Sheet {
             self.column_widths.insert(c + count, w);
         }
 
+        // Shift column styles
+        let styles_to_shift: Vec<_> = self
+            .column_styles
+            .iter()
+            .filter(|(c, _)| **c >= col)
+            .map(|(c, s)| (*c, *s))
+            .collect();
+
+        for (c, _) in &styles_to_shift {
+            self.column_styles.remove(c);
+        }
+
+        for (c, s) in styles_to_shift {
+            self.column_styles.insert(c + count, s);
+        }
+
         // Shift hidden columns
         let hidden_to_shift: Vec<_> = self
             .hidden_columns
@@ -586,7 +1503,7 @@ impl Sheet {
         }
 
         // Invalidate used range cache
-        self.used_range = None;
+        self.invalidate_extents();
     }
 
     /// Deletes columns starting at the specified position, shifting all columns to the left.
@@ -618,7 +1535,8 @@ impl Sheet {
         }
 
         // Insert at new positions (shifted left)
-        for ((c, r), cell) in cells_to_shift {
+        for ((c, r), mut cell) in cells_to_shift {
+            cell.reference.col = c - count;
             self.cells.insert((c - count, r), cell);
         }
 
@@ -642,6 +1560,26 @@ impl Sheet {
             self.column_widths.insert(c - count, w);
         }
 
+        // Remove deleted column styles and shift remaining
+        for c in col..=end_col {
+            self.column_styles.remove(&c);
+        }
+
+        let styles_to_shift: Vec<_> = self
+            .column_styles
+            .iter()
+            .filter(|(c, _)| **c > end_col)
+            .map(|(c, s)| (*c, *s))
+            .collect();
+
+        for (c, _) in &styles_to_shift {
+            self.column_styles.remove(c);
+        }
+
+        for (c, s) in styles_to_shift {
+            self.column_styles.insert(c - count, s);
+        }
+
         // Remove deleted hidden columns and shift remaining
         for c in col..=end_col {
             self.hidden_columns.remove(&c);
@@ -686,7 +1624,159 @@ impl Sheet {
         });
 
         // Invalidate used range cache
-        self.used_range = None;
+        self.invalidate_extents();
+    }
+
+    /// Writes a transposed copy of `source` with its top-left corner at `dest`.
+    ///
+    /// Values, styles and merged regions are swapped row-for-column. Formula
+    /// references that point inside `source` are rewritten to the transposed
+    /// location; other references are left untouched. The destination area is
+    /// overwritten. If it overlaps the source (e.g. an in-place transpose), the
+    /// source cells are cleared first.
+    ///
+    /// Returns the range that was written.
+    pub fn transpose_range(
+        &mut self,
+        source: &crate::range::Range,
+        dest: &CellRef,
+    ) -> XlexResult<crate::range::Range> {
+        let end_col = dest.col as u64 + source.height() as u64 - 1;
+        let end_row = dest.row as u64 + source.width() as u64 - 1;
+        if end_col > CellRef::MAX_COL as u64 || end_row > CellRef::MAX_ROW as u64 {
+            return Err(XlexError::ReferenceOutOfBounds {
+                reference: format!("{} transposed to {}", source, dest),
+            });
+        }
+        let target =
+            crate::range::Range::new(dest.clone(), CellRef::new(end_col as u32, end_row as u32));
+
+        let map = |col: u32, row: u32| {
+            CellRef::new(
+                dest.col + (row - source.start.row),
+                dest.row + (col - source.start.col),
+            )
+        };
+
+        let sheet_name = self.info.name.clone();
+        let moved: Vec<Cell> = self
+            .cells
+            .values()
+            .filter(|c| source.contains(&c.reference))
+            .map(|c| {
+                let mut cell = c.clone();
+                cell.reference = map(c.reference.col, c.reference.row);
+                if let CellValue::Formula { formula, .. } = &mut cell.value {
+                    *formula = crate::formula::map_refs(formula, |r| {
+                        let local = r.sheet.as_deref().map_or(true, |s| s == sheet_name);
+                        if !local || !source.contains(&r.cell_ref()) {
+                            return crate::formula::RefEdit::Keep;
+                        }
+                        let new_ref = map(r.col, r.row);
+                        crate::formula::RefEdit::Replace(crate::formula::FormulaRef {
+                            col: new_ref.col,
+                            row: new_ref.row,
+                            col_absolute: r.row_absolute,
+                            row_absolute: r.col_absolute,
+                            sheet: r.sheet.clone(),
+                        })
+                    });
+                }
+                cell
+            })
+            .collect();
+
+        let moved_merges: Vec<crate::range::Range> = self
+            .merged_ranges
+            .iter()
+            .filter(|m| source.contains_range(m))
+            .map(|m| {
+                crate::range::Range::new(map(m.start.col, m.start.row), map(m.end.col, m.end.row))
+            })
+            .collect();
+
+        let overlaps = source.intersects(&target);
+        self.cells.retain(|_, c| {
+            !(target.contains(&c.reference) || (overlaps && source.contains(&c.reference)))
+        });
+        self.merged_ranges
+            .retain(|m| !(m.intersects(&target) || (overlaps && source.intersects(m))));
+
+        for cell in moved {
+            self.insert_cell(cell);
+        }
+        self.merged_ranges.extend(moved_merges);
+        self.invalidate_extents();
+
+        Ok(target)
+    }
+
+    /// Transposes the whole sheet so that row N becomes column N.
+    ///
+    /// Cells, merged regions, hidden rows/columns and custom row heights and
+    /// column widths are swapped. Formula references to this sheet are
+    /// rewritten; references from other sheets are not.
+    pub fn transpose(&mut self) -> XlexResult<()> {
+        let (max_col, max_row) = self.dimensions();
+        let max_merge_row = self.merged_ranges.iter().map(|m| m.end.row).max();
+        if max_row.max(max_merge_row.unwrap_or(0)) > CellRef::MAX_COL {
+            return Err(XlexError::ReferenceOutOfBounds {
+                reference: format!(
+                    "{}{} cannot be transposed past column {}",
+                    CellRef::col_to_letters(max_col.max(1)),
+                    max_row,
+                    CellRef::col_to_letters(CellRef::MAX_COL)
+                ),
+            });
+        }
+
+        let sheet_name = self.info.name.clone();
+        let cells = std::mem::take(&mut self.cells);
+        for (_, mut cell) in cells {
+            cell.reference = CellRef::new(cell.reference.row, cell.reference.col);
+            if let CellValue::Formula { formula, .. } = &mut cell.value {
+                *formula = crate::formula::map_refs(formula, |r| {
+                    let local = r.sheet.as_deref().map_or(true, |s| s == sheet_name);
+                    if !local || r.col > CellRef::MAX_ROW || r.row > CellRef::MAX_COL {
+                        return crate::formula::RefEdit::Keep;
+                    }
+                    crate::formula::RefEdit::Replace(crate::formula::FormulaRef {
+                        col: r.row,
+                        row: r.col,
+                        col_absolute: r.row_absolute,
+                        row_absolute: r.col_absolute,
+                        sheet: r.sheet.clone(),
+                    })
+                });
+            }
+            self.cells
+                .insert((cell.reference.col, cell.reference.row), cell);
+        }
+
+        for range in &mut self.merged_ranges {
+            *range = crate::range::Range::new(
+                CellRef::new(range.start.row, range.start.col),
+                CellRef::new(range.end.row, range.end.col),
+            );
+        }
+
+        // Row heights are in points and column widths in characters; convert
+        // through pixels (7px per character plus 5px padding, 96 DPI).
+        let heights = std::mem::take(&mut self.row_heights);
+        let widths = std::mem::take(&mut self.column_widths);
+        self.column_widths = heights
+            .into_iter()
+            .map(|(row, pt)| (row, ((pt * 4.0 / 3.0 - 5.0) / 7.0).max(0.0)))
+            .collect();
+        self.row_heights = widths
+            .into_iter()
+            .map(|(col, chars)| (col, (chars * 7.0 + 5.0) * 0.75))
+            .collect();
+        std::mem::swap(&mut self.hidden_rows, &mut self.hidden_columns);
+        std::mem::swap(&mut self.row_styles, &mut self.column_styles);
+
+        self.invalidate_extents();
+        Ok(())
     }
 }
 
@@ -699,6 +1789,147 @@ mod tests {
         Sheet::new(info)
     }
 
+    #[test]
+    fn test_apply_updates() {
+        let mut sheet = make_sheet();
+        sheet.set_cell(CellRef::new(1, 1), CellValue::String("old".to_string()));
+        assert_eq!(sheet.used_range().unwrap().to_a1(), "A1");
+
+        let written = sheet.apply_updates(vec![
+            (CellRef::new(1, 1), CellValue::Number(1.0)),
+            (CellRef::new(3, 4), CellValue::Boolean(true)),
+        ]);
+        assert_eq!(written, 2);
+        assert_eq!(sheet.get_value(&CellRef::new(1, 1)), CellValue::Number(1.0));
+        assert_eq!(
+            sheet.get_value(&CellRef::new(3, 4)),
+            CellValue::Boolean(true)
+        );
+        assert_eq!(sheet.used_range().unwrap().to_a1(), "A1:C4");
+        assert_eq!(sheet.apply_updates(std::iter::empty()), 0);
+    }
+
+    #[test]
+    fn test_row_and_column_styles_follow_inserts_and_deletes() {
+        let mut sheet = make_sheet();
+        sheet.set_row_style(3, Some(1));
+        sheet.set_column_style(2, Some(2));
+        sheet.set_default_style(Some(3));
+        sheet.set_cell_style(&CellRef::new(2, 3), Some(4));
+
+        assert_eq!(sheet.effective_style_id(&CellRef::new(2, 3)), Some(4));
+        assert_eq!(sheet.effective_style_id(&CellRef::new(5, 3)), Some(1));
+        assert_eq!(sheet.effective_style_id(&CellRef::new(2, 9)), Some(2));
+        assert_eq!(sheet.effective_style_id(&CellRef::new(5, 9)), Some(3));
+
+        sheet.insert_rows(1, 2);
+        sheet.insert_columns(1, 1);
+        assert_eq!(sheet.get_row_style(5), Some(1));
+        assert_eq!(sheet.get_column_style(3), Some(2));
+        assert_eq!(sheet.get_row_style(3), None);
+
+        sheet.delete_rows(5, 1);
+        sheet.delete_columns(1, 2);
+        assert!(sheet.row_styles().is_empty());
+        assert_eq!(sheet.get_column_style(1), Some(2));
+
+        let mut ids: Vec<u32> = sheet.style_ids().collect();
+        ids.sort_unstable();
+        assert_eq!(ids, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_print_titles_and_area_references() {
+        let mut sheet = Sheet::new(SheetInfo::new("Q1 Report", 1, "rId1", 0));
+        assert!(sheet.print_titles_reference().is_none());
+
+        sheet.set_print_titles(Some((2, 1)), Some((1, 2)));
+        assert_eq!(sheet.print_titles(), (Some((1, 2)), Some((1, 2))));
+        assert_eq!(
+            sheet.print_titles_reference().unwrap(),
+            "'Q1 Report'!$A:$B,'Q1 Report'!$1:$2"
+        );
+
+        sheet.set_print_area(Some(crate::range::Range::parse("A1:H50").unwrap()));
+        assert_eq!(
+            sheet.print_area_reference().unwrap(),
+            "'Q1 Report'!$A$1:$H$50"
+        );
+
+        let mut parsed = make_sheet();
+        assert!(parsed.apply_print_name("_xlnm.Print_Titles", "'Q1, Report'!$1:$1"));
+        assert_eq!(parsed.print_titles(), (Some((1, 1)), None));
+        assert!(parsed.apply_print_name("_xlnm.Print_Area", "Test!$B$2:$D$9"));
+        assert_eq!(parsed.print_area().unwrap().to_a1(), "B2:D9");
+        assert!(!parsed.apply_print_name("_xlnm.Print_Area", "Test!$A$1,Test!$C$3"));
+        assert!(!parsed.apply_print_name("MyRange", "Test!$A$1"));
+    }
+
+    #[test]
+    fn test_array_formulas() {
+        use crate::range::Range;
+
+        let mut sheet = Sheet::new(SheetInfo::new("Test", 1, "rId1", 0));
+        sheet.set_cell_style(&CellRef::new(2, 2), Some(4));
+        sheet.set_array_formula(&Range::parse("B2:C4").unwrap(), "A1:B3*2", false);
+
+        let anchor = sheet.get_cell(&CellRef::new(2, 2)).unwrap();
+        assert_eq!(anchor.value, CellValue::formula("A1:B3*2"));
+        assert_eq!(anchor.style_id, Some(4));
+        let (at, array) = sheet.array_formula_at(&CellRef::new(3, 4)).unwrap();
+        assert_eq!(at, CellRef::new(2, 2));
+        assert_eq!(array.range(&at).to_a1(), "B2:C4");
+        assert!(!array.dynamic);
+        assert!(sheet.array_formula_at(&CellRef::new(4, 4)).is_none());
+
+        // The array moves with its anchor
+        sheet.insert_rows(1, 2);
+        let (at, array) = sheet.array_formula_at(&CellRef::new(2, 6)).unwrap();
+        assert_eq!(array.range(&at).to_a1(), "B4:C6");
+
+        // Overwriting the anchor drops the array
+        sheet.set_cell(at, CellValue::Number(1.0));
+        assert!(sheet.array_formula_at(&CellRef::new(2, 6)).is_none());
+    }
+
+    #[test]
+    fn test_dropdowns() {
+        use crate::range::Range;
+
+        let mut sheet = make_sheet();
+        let status =
+            Dropdown::from_items(Range::parse("B2:B20").unwrap(), &["Open", " Done "]).unwrap();
+        assert_eq!(status.source, "\"Open,Done\"");
+        sheet.add_dropdown(status);
+        assert!(Dropdown::from_items(Range::parse("A1").unwrap(), &["a,b"]).is_err());
+
+        // Overlapping dropdowns are replaced
+        sheet.add_dropdown(Dropdown::from_reference(
+            Range::parse("B10:B30").unwrap(),
+            "=Lists!$A$1:$A$3",
+        ));
+        assert_eq!(sheet.dropdowns().len(), 1);
+        assert_eq!(sheet.dropdowns()[0].source, "Lists!$A$1:$A$3");
+        assert!(sheet.dropdowns()[0].items().is_none());
+
+        assert_eq!(sheet.remove_dropdowns(&Range::parse("B15").unwrap()), 1);
+        assert!(sheet.dropdowns().is_empty());
+    }
+
+    #[test]
+    fn test_sheet_image_formats() {
+        let image = SheetImage::new(vec![1], "JPG").unwrap();
+        assert_eq!(image.extension, "jpeg");
+        assert_eq!(image.content_type(), "image/jpeg");
+        assert!(SheetImage::new(vec![1], "svg").is_err());
+
+        let mut sheet = make_sheet();
+        sheet.set_background(Some(image.clone()));
+        assert_eq!(sheet.background(), Some(&image));
+        sheet.set_background(None);
+        assert!(sheet.background().is_none());
+    }
+
     #[test]
     fn test_sheet_basic() {
         let sheet = make_sheet();
@@ -845,6 +2076,93 @@ mod tests {
         assert_eq!(range.end, CellRef::new(5, 10));
     }
 
+    #[test]
+    fn test_sheet_extents_follow_edits() {
+        let mut sheet = make_sheet();
+        assert_eq!(sheet.last_row(), None);
+        assert_eq!(sheet.last_col_in_row(1), None);
+
+        sheet.set_cell(CellRef::new(2, 3), CellValue::string("A"));
+        sheet.set_cell(CellRef::new(5, 3), CellValue::string("B"));
+        assert_eq!(sheet.last_row(), Some(3));
+        assert_eq!(sheet.last_col_in_row(3), Some(5));
+
+        // A style on an empty cell extends the used range
+        sheet.set_cell_style(&CellRef::new(7, 4), Some(1));
+        assert_eq!(sheet.used_range().unwrap().to_a1(), "B3:G4");
+
+        sheet.insert_rows(1, 2);
+        assert_eq!(sheet.last_row(), Some(6));
+        assert_eq!(sheet.last_col_in_row(5), Some(5));
+        assert_eq!(sheet.last_col_in_row(3), None);
+
+        sheet.clear_cell(&CellRef::new(7, 6));
+        assert_eq!(sheet.used_range().unwrap().to_a1(), "B5:E5");
+        assert_eq!(sheet.dimensions(), (5, 5));
+    }
+
+    #[test]
+    fn test_sheet_iter_range() {
+        let mut sheet = make_sheet();
+        sheet.set_cell(CellRef::new(3, 2), CellValue::Number(2.0));
+        sheet.set_cell(CellRef::new(1, 2), CellValue::Number(1.0));
+        sheet.set_cell(CellRef::new(2, 900_000), CellValue::Number(3.0));
+        sheet.set_cell(CellRef::new(5, 2), CellValue::Number(9.0));
+        sheet.set_cell_style(&CellRef::new(2, 2), Some(1));
+
+        // Whole columns A:C; the styled empty cell and E2 are skipped
+        let range = crate::range::Range::parse("A:C").unwrap();
+        let cells: Vec<(String, f64)> = sheet
+            .iter_range(&range)
+            .map(|(r, c)| (r.to_a1(), c.value.to_display_string().parse().unwrap()))
+            .collect();
+        assert_eq!(
+            cells,
+            vec![
+                ("A2".to_string(), 1.0),
+                ("C2".to_string(), 2.0),
+                ("B900000".to_string(), 3.0),
+            ]
+        );
+        let range = crate::range::Range::parse("D3:F10").unwrap();
+        assert_eq!(sheet.iter_range(&range).count(), 0);
+    }
+
+    #[test]
+    fn test_sheet_column_values() {
+        let mut sheet = make_sheet();
+        sheet.set_cell(CellRef::new(2, 1), CellValue::string("head"));
+        sheet.set_cell(CellRef::new(2, 3), CellValue::Number(3.0));
+        sheet.set_cell(CellRef::new(1, 4), CellValue::Number(9.0));
+        sheet.set_cell(CellRef::new(2, 5), CellValue::Number(5.0));
+
+        let rows: Vec<u32> = sheet
+            .column_values(2, ColumnOptions::default())
+            .map(|(row, _)| row)
+            .collect();
+        assert_eq!(rows, vec![1, 3, 5]);
+
+        let options = ColumnOptions {
+            first_row: Some(2),
+            include_empty: true,
+            ..Default::default()
+        };
+        let values: Vec<(u32, CellValue)> = sheet
+            .column_values(2, options)
+            .map(|(row, value)| (row, value.clone()))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                (2, CellValue::Empty),
+                (3, CellValue::Number(3.0)),
+                (4, CellValue::Empty),
+                (5, CellValue::Number(5.0)),
+            ]
+        );
+        assert_eq!(sheet.column_values(3, ColumnOptions::default()).count(), 0);
+    }
+
     #[test]
     fn test_sheet_insert_rows() {
         let mut sheet = make_sheet();
@@ -907,6 +2225,10 @@ mod tests {
             CellValue::string("A4")
         );
         assert_eq!(sheet.cell_count(), 3);
+        // Moved cells know where they are, for the writer
+        let mut rows: Vec<u32> = sheet.cells().map(|cell| cell.reference.row).collect();
+        rows.sort_unstable();
+        assert_eq!(rows, vec![1, 2, 3]);
 
         // Verify row height shifted
         assert_eq!(sheet.get_row_height(3), Some(30.0));
@@ -1167,6 +2489,32 @@ mod tests {
         assert_eq!(cell.hyperlink, None);
     }
 
+    #[test]
+    fn test_sheet_content_hash() {
+        let mut sheet = make_sheet();
+        let empty = sheet.content_hash();
+        // Formatting-only cells do not count as content
+        sheet.set_cell_hyperlink(&CellRef::new(3, 3), Some("https://example.com".to_string()));
+        assert_eq!(sheet.content_hash(), empty);
+
+        sheet.set_cell(CellRef::new(1, 1), CellValue::Number(1.0));
+        sheet.set_cell(CellRef::new(2, 1), CellValue::string("a"));
+        let hash = sheet.content_hash();
+        assert_ne!(hash, empty);
+
+        // Insertion order does not matter, values and positions do
+        let mut other = make_sheet();
+        other.set_cell(CellRef::new(2, 1), CellValue::string("a"));
+        other.set_cell(CellRef::new(1, 1), CellValue::Number(1.0));
+        assert_eq!(other.content_hash(), hash);
+        other.set_cell(CellRef::new(1, 1), CellValue::string("1"));
+        assert_ne!(other.content_hash(), hash);
+        other.set_cell(CellRef::new(1, 1), CellValue::Number(1.0));
+        other.set_cell(CellRef::new(1, 2), CellValue::string("a"));
+        other.clear_cell(&CellRef::new(2, 1));
+        assert_ne!(other.content_hash(), hash);
+    }
+
     #[test]
     fn test_sheet_merged_ranges() {
         let mut sheet = make_sheet();
@@ -1272,4 +2620,113 @@ mod tests {
         let ranges = sheet.merged_ranges();
         assert_eq!(ranges.len(), 1);
     }
+
+    #[test]
+    fn test_sheet_transpose_range() {
+        let mut sheet = make_sheet();
+        sheet.set_cell(CellRef::new(1, 1), CellValue::number(1.0));
+        sheet.set_cell(CellRef::new(2, 1), CellValue::number(2.0));
+        sheet.set_cell(CellRef::new(3, 1), CellValue::number(3.0));
+        sheet.set_cell(CellRef::new(1, 2), CellValue::formula("A1+C1"));
+        sheet.set_cell_style(&CellRef::new(2, 1), Some(4));
+        sheet.add_merged_range(crate::range::Range::parse("B2:C2").unwrap());
+
+        let source = crate::range::Range::parse("A1:C2").unwrap();
+        let target = sheet.transpose_range(&source, &CellRef::new(1, 5)).unwrap();
+
+        assert_eq!(target.to_a1(), "A5:B7");
+        assert_eq!(sheet.get_value(&CellRef::new(1, 6)), CellValue::number(2.0));
+        assert_eq!(sheet.get_value(&CellRef::new(1, 7)), CellValue::number(3.0));
+        assert_eq!(
+            sheet.get_cell(&CellRef::new(1, 6)).unwrap().style_id,
+            Some(4)
+        );
+        match sheet.get_value(&CellRef::new(2, 5)) {
+            CellValue::Formula { formula, .. } => assert_eq!(formula, "A5+A7"),
+            other => panic!("expected formula, got {:?}", other),
+        }
+        assert!(sheet
+            .merged_ranges()
+            .contains(&crate::range::Range::parse("B6:B7").unwrap()));
+        // Source is kept when the destination does not overlap it
+        assert_eq!(sheet.get_value(&CellRef::new(3, 1)), CellValue::number(3.0));
+    }
+
+    #[test]
+    fn test_sheet_transpose_range_in_place() {
+        let mut sheet = make_sheet();
+        sheet.set_cell(CellRef::new(1, 1), CellValue::string("a"));
+        sheet.set_cell(CellRef::new(2, 1), CellValue::string("b"));
+
+        let source = crate::range::Range::parse("A1:B1").unwrap();
+        sheet.transpose_range(&source, &CellRef::new(1, 1)).unwrap();
+
+        assert_eq!(sheet.get_value(&CellRef::new(1, 2)), CellValue::string("b"));
+        assert!(sheet.get_cell(&CellRef::new(2, 1)).is_none());
+    }
+
+    #[test]
+    fn test_sheet_transpose_range_out_of_bounds() {
+        let mut sheet = make_sheet();
+        let source = crate::range::Range::parse("A1:A3").unwrap();
+        let dest = CellRef::new(CellRef::MAX_COL, 1);
+        assert!(sheet.transpose_range(&source, &dest).is_err());
+    }
+
+    #[test]
+    fn test_sheet_transpose() {
+        let mut sheet = make_sheet();
+        sheet.set_cell(CellRef::new(1, 3), CellValue::string("x"));
+        sheet.set_cell(CellRef::new(2, 1), CellValue::formula("$A3*2"));
+        sheet.set_row_hidden(3, true);
+        sheet.add_merged_range(crate::range::Range::parse("A1:A2").unwrap());
+
+        sheet.transpose().unwrap();
+
+        assert_eq!(sheet.get_value(&CellRef::new(3, 1)), CellValue::string("x"));
+        match sheet.get_value(&CellRef::new(1, 2)) {
+            CellValue::Formula { formula, .. } => assert_eq!(formula, "C$1*2"),
+            other => panic!("expected formula, got {:?}", other),
+        }
+        assert!(sheet.is_column_hidden(3));
+        assert!(!sheet.is_row_hidden(3));
+        assert_eq!(
+            sheet.merged_ranges()[0],
+            crate::range::Range::parse("A1:B1").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_sheet_write_wrapped() {
+        let mut styles = StyleRegistry::new();
+        let mut sheet = Sheet::new(SheetInfo::new("Notes", 1, "rId1", 0));
+        let cell = CellRef::new(2, 3);
+        sheet.write_wrapped(&mut styles, &cell, "a".repeat(50) + "\nshort", 20.0);
+
+        assert_eq!(
+            sheet.get_value(&cell),
+            CellValue::string("a".repeat(50) + "\nshort")
+        );
+        assert!(styles.resolve(sheet.effective_style_id(&cell)).wrap_text);
+        assert_eq!(sheet.get_column_width(2), Some(20.0));
+        // 50 characters over 20 per line take 3 lines, plus the short line.
+        assert_eq!(sheet.get_row_height(3), Some(60.0));
+
+        sheet.set_row_height(4, 100.0);
+        sheet.write_wrapped(&mut styles, &CellRef::new(2, 4), "brief", 20.0);
+        assert_eq!(sheet.get_row_height(4), Some(100.0));
+        assert_eq!(sheet.get_column_width(2), Some(20.0));
+
+        sheet.write_wrapped(&mut styles, &CellRef::new(3, 1), "x".repeat(30), 40.0);
+        assert_eq!(sheet.get_column_width(3), Some(31.0));
+        assert_eq!(sheet.get_row_height(1), Some(15.0));
+    }
+
+    #[test]
+    fn test_wrapped_line_count() {
+        assert_eq!(wrapped_line_count("", 10.0), 1);
+        assert_eq!(wrapped_line_count("abcdefghij", 10.0), 1);
+        assert_eq!(wrapped_line_count("abcdefghijk", 10.0), 2);
+        assert_eq!(wrapped_line_count("a\n\nb", 0.5), 3);
+    }
 }
@@ -0,0 +1,1370 @@
+//! Digital signatures of workbook packages (feature `signing`).
+//!
+//! Signatures follow the Open Packaging Conventions: an XML signature part
+//! under `_xmlsignatures/`, reached from the package through a signature
+//! origin part. Its signed info covers a manifest with the SHA-256 of every
+//! other part and, as in XAdES, signed properties holding the signing time
+//! and a digest of the signer's certificate. Any change to a signed part,
+//! and any part added after signing, shows up when verifying.
+//!
+//! [`sign`] adds a signature with a key and certificate from a PKCS#12
+//! (`.p12`/`.pfx`) file; signing again adds another signature. [`verify`]
+//! checks every signature and, optionally, the signer's certificate chain
+//! against trusted CA certificates. Signatures made by other tools verify
+//! the same way as long as they only use the relationship transform and
+//! canonical XML, as Office does.
+//!
+//! ```no_run
+//! use xlex_core::signature::{self, SigningIdentity};
+//!
+//! let identity = SigningIdentity::from_pkcs12(&std::fs::read("cert.p12")?, "secret")?;
+//! let signed = signature::sign(&std::fs::read("report.xlsx")?, &identity)?;
+//! std::fs::write("report.xlsx", &signed)?;
+//!
+//! for report in signature::verify(&signed, None)? {
+//!     println!("{}: {:?}", report.signer, report.status);
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{Cursor, Read, Write};
+
+use chrono::{SecondsFormat, Utc};
+use openssl::asn1::Asn1Time;
+use openssl::base64;
+use openssl::bn::BigNum;
+use openssl::ecdsa::EcdsaSig;
+use openssl::error::ErrorStack;
+use openssl::hash::{hash, MessageDigest};
+use openssl::pkcs12::Pkcs12;
+use openssl::pkey::{Id, PKey, Private, Public};
+use openssl::sign::{Signer, Verifier};
+use openssl::stack::Stack;
+use openssl::x509::store::X509StoreBuilder;
+use openssl::x509::{X509NameRef, X509StoreContext, X509};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use zip::ZipArchive;
+
+use crate::error::{XlexError, XlexResult};
+
+const DSIG_NS: &str = "http://www.w3.org/2000/09/xmldsig#";
+const XADES_NS: &str = "http://uri.etsi.org/01903/v1.3.2#";
+const RELS_NS: &str = "http://schemas.openxmlformats.org/package/2006/relationships";
+const C14N: &str = "http://www.w3.org/TR/2001/REC-xml-c14n-20010315";
+const SHA256: &str = "http://www.w3.org/2001/04/xmlenc#sha256";
+const RELATIONSHIP_TRANSFORM: &str =
+    "http://schemas.openxmlformats.org/package/2006/RelationshipTransform";
+const ORIGIN_REL: &str =
+    "http://schemas.openxmlformats.org/package/2006/relationships/digital-signature/origin";
+const SIGNATURE_REL: &str =
+    "http://schemas.openxmlformats.org/package/2006/relationships/digital-signature/signature";
+const ORIGIN_CONTENT_TYPE: &str = "application/vnd.openxmlformats-package.digital-signature-origin";
+const SIGNATURE_CONTENT_TYPE: &str =
+    "application/vnd.openxmlformats-package.digital-signature-xmlsignature+xml";
+const ORIGIN_PART: &str = "_xmlsignatures/origin.sigs";
+const CONTENT_TYPES_PART: &str = "[Content_Types].xml";
+
+/// A private key and the certificate that goes with it.
+pub struct SigningIdentity {
+    key: PKey<Private>,
+    certificate: X509,
+}
+
+impl SigningIdentity {
+    /// Reads the key and certificate from a PKCS#12 (`.p12`/`.pfx`) file.
+    pub fn from_pkcs12(der: &[u8], password: &str) -> XlexResult<Self> {
+        let parsed = Pkcs12::from_der(der)
+            .and_then(|p12| p12.parse2(password))
+            .map_err(|e| crypto_error("Cannot read the PKCS#12 file (wrong password?)", e))?;
+        match (parsed.pkey, parsed.cert) {
+            (Some(key), Some(certificate)) => Ok(Self { key, certificate }),
+            _ => Err(XlexError::InvalidOperation {
+                message: "The PKCS#12 file must hold a private key and its certificate".to_string(),
+            }),
+        }
+    }
+
+    /// Returns the subject of the certificate.
+    pub fn subject(&self) -> String {
+        name_to_string(self.certificate.subject_name())
+    }
+}
+
+/// Whether a signature holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// The signature and every digest match, and every part is signed
+    Valid,
+    /// Something was altered or cannot be checked; see the problems
+    Invalid,
+}
+
+/// The result of checking one signature.
+#[derive(Debug, Clone)]
+pub struct SignatureReport {
+    /// Signature part (e.g. `_xmlsignatures/sig1.xml`)
+    pub part: String,
+    pub status: SignatureStatus,
+    /// Subject of the signer's certificate
+    pub signer: String,
+    /// Issuer of the signer's certificate
+    pub issuer: String,
+    /// Certificate validity period
+    pub not_before: String,
+    pub not_after: String,
+    /// Signing time claimed in the signed properties, if any
+    pub signing_time: Option<String>,
+    /// Whether the certificate chains to a trusted CA; `None` when no CA
+    /// certificates were given
+    pub trusted: Option<bool>,
+    /// Parts covered by the signature
+    pub signed_parts: Vec<String>,
+    /// What failed; empty for a valid signature
+    pub problems: Vec<String>,
+}
+
+fn crypto_error(context: &str, error: ErrorStack) -> XlexError {
+    XlexError::OperationFailed {
+        message: format!("{}: {}", context, error),
+    }
+}
+
+fn name_to_string(name: &X509NameRef) -> String {
+    name.entries()
+        .map(|entry| {
+            let key = entry.object().nid().short_name().unwrap_or("?");
+            let value = entry.data().to_string().unwrap_or_default();
+            format!("{}={}", key, value)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The parts of a package, in archive order.
+fn read_parts(data: &[u8]) -> XlexResult<Vec<(String, Vec<u8>)>> {
+    let mut archive = ZipArchive::new(Cursor::new(data))?;
+    let mut parts = Vec::new();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        if file.is_dir() {
+            continue;
+        }
+        let mut content = Vec::new();
+        file.read_to_end(&mut content)?;
+        parts.push((file.name().to_string(), content));
+    }
+    Ok(parts)
+}
+
+fn part<'p>(parts: &'p [(String, Vec<u8>)], name: &str) -> Option<&'p [u8]> {
+    parts
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, content)| content.as_slice())
+}
+
+/// Content types by part name, from `[Content_Types].xml`.
+fn content_types(parts: &[(String, Vec<u8>)]) -> XlexResult<HashMap<String, String>> {
+    let xml = part(parts, CONTENT_TYPES_PART).ok_or_else(|| XlexError::MissingRequiredEntry {
+        entry: CONTENT_TYPES_PART.to_string(),
+    })?;
+    let mut defaults = HashMap::new();
+    let mut overrides = HashMap::new();
+    let mut reader = Reader::from_reader(xml);
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) | Event::Empty(e) => {
+                let attr = |name: &[u8]| attribute(&e, name);
+                match e.local_name().as_ref() {
+                    b"Default" => {
+                        if let (Some(ext), Some(ct)) = (attr(b"Extension"), attr(b"ContentType")) {
+                            defaults.insert(ext.to_lowercase(), ct);
+                        }
+                    }
+                    b"Override" => {
+                        if let (Some(name), Some(ct)) = (attr(b"PartName"), attr(b"ContentType")) {
+                            overrides.insert(name.trim_start_matches('/').to_string(), ct);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(parts
+        .iter()
+        .filter_map(|(name, _)| {
+            let ct = overrides.get(name).cloned().or_else(|| {
+                let ext = name.rsplit_once('.')?.1.to_lowercase();
+                defaults.get(&ext).cloned()
+            })?;
+            Some((name.clone(), ct))
+        })
+        .collect())
+}
+
+fn attribute(e: &BytesStart, name: &[u8]) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|a| a.key.as_ref() == name)
+        .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+}
+
+/// A relationship: id, type, target.
+type Relationship = (String, String, String);
+
+fn relationships(xml: &[u8]) -> XlexResult<Vec<Relationship>> {
+    let mut rels = Vec::new();
+    let mut reader = Reader::from_reader(xml);
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"Relationship" => {
+                rels.push((
+                    attribute(&e, b"Id").unwrap_or_default(),
+                    attribute(&e, b"Type").unwrap_or_default(),
+                    attribute(&e, b"Target").unwrap_or_default(),
+                ));
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(rels)
+}
+
+/// Resolves a relationship target against the directory of its source.
+fn resolve(dir: &str, target: &str) -> String {
+    let mut segments: Vec<&str> = if target.starts_with('/') {
+        Vec::new()
+    } else {
+        dir.split('/').filter(|s| !s.is_empty()).collect()
+    };
+    for segment in target.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            s => segments.push(s),
+        }
+    }
+    segments.join("/")
+}
+
+/// The signature parts of a package, found through the signature origin.
+fn signature_parts(parts: &[(String, Vec<u8>)]) -> XlexResult<(Option<String>, Vec<String>)> {
+    let Some(root_rels) = part(parts, "_rels/.rels") else {
+        return Ok((None, Vec::new()));
+    };
+    let Some(origin) = relationships(root_rels)?
+        .into_iter()
+        .find(|(_, kind, _)| kind == ORIGIN_REL)
+        .map(|(_, _, target)| resolve("", &target))
+    else {
+        return Ok((None, Vec::new()));
+    };
+    let (dir, name) = origin.rsplit_once('/').unwrap_or(("", &origin));
+    let origin_rels = resolve(dir, &format!("_rels/{}.rels", name));
+    let signatures = match part(parts, &origin_rels) {
+        Some(xml) => relationships(xml)?
+            .into_iter()
+            .filter(|(_, kind, _)| kind == SIGNATURE_REL)
+            .map(|(_, _, target)| resolve(dir, &target))
+            .collect(),
+        None => Vec::new(),
+    };
+    Ok((Some(origin), signatures))
+}
+
+fn digest_algorithm(uri: &str) -> Option<MessageDigest> {
+    match uri {
+        "http://www.w3.org/2000/09/xmldsig#sha1" => Some(MessageDigest::sha1()),
+        SHA256 => Some(MessageDigest::sha256()),
+        "http://www.w3.org/2001/04/xmldsig-more#sha384" => Some(MessageDigest::sha384()),
+        "http://www.w3.org/2001/04/xmlenc#sha512" => Some(MessageDigest::sha512()),
+        _ => None,
+    }
+}
+
+/// Signature algorithm: the digest and whether the key is ECDSA.
+fn signature_algorithm(uri: &str) -> Option<(MessageDigest, bool)> {
+    let (digest, ecdsa) = match uri.rsplit_once('#')?.1 {
+        "rsa-sha1" => (MessageDigest::sha1(), false),
+        "rsa-sha256" => (MessageDigest::sha256(), false),
+        "rsa-sha384" => (MessageDigest::sha384(), false),
+        "rsa-sha512" => (MessageDigest::sha512(), false),
+        "ecdsa-sha256" => (MessageDigest::sha256(), true),
+        "ecdsa-sha384" => (MessageDigest::sha384(), true),
+        "ecdsa-sha512" => (MessageDigest::sha512(), true),
+        _ => return None,
+    };
+    Some((digest, ecdsa))
+}
+
+fn digest_base64(data: &[u8], algorithm: MessageDigest) -> XlexResult<String> {
+    let digest = hash(algorithm, data).map_err(|e| crypto_error("Digest failed", e))?;
+    Ok(base64::encode_block(&digest))
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\r', "&#xD;")
+}
+
+fn escape_attr(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('"', "&quot;")
+        .replace('\t', "&#x9;")
+        .replace('\n', "&#xA;")
+        .replace('\r', "&#xD;")
+}
+
+/// Namespace declarations in scope, by prefix (`""` for the default).
+type Namespaces = BTreeMap<String, String>;
+
+/// Canonicalizes an element (Canonical XML 1.0, without comments).
+/// `inherited` holds the namespaces declared by its ancestors, which the
+/// canonical form repeats on the element itself.
+fn canonicalize(fragment: &[u8], inherited: &Namespaces) -> XlexResult<Vec<u8>> {
+    let mut out = String::new();
+    let mut reader = Reader::from_reader(fragment);
+    // Namespaces in scope and those already written, per open element
+    let mut scopes: Vec<(Namespaces, Namespaces)> = vec![(inherited.clone(), Namespaces::new())];
+    let mut names: Vec<String> = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        let event = reader.read_event_into(&mut buf)?;
+        match &event {
+            Event::Start(e) | Event::Empty(e) => {
+                let (parent_scope, parent_written) = scopes.last().cloned().unwrap_or_default();
+                let mut scope = parent_scope;
+                let mut attrs = Vec::new();
+                for attr in e.attributes() {
+                    let attr = attr.map_err(|e| XlexError::InvalidXml {
+                        message: e.to_string(),
+                    })?;
+                    let key = String::from_utf8_lossy(attr.key.as_ref()).to_string();
+                    let value = attr
+                        .unescape_value()
+                        .map_err(|e| XlexError::InvalidXml {
+                            message: e.to_string(),
+                        })?
+                        .into_owned();
+                    if key == "xmlns" {
+                        scope.insert(String::new(), value);
+                    } else if let Some(prefix) = key.strip_prefix("xmlns:") {
+                        scope.insert(prefix.to_string(), value);
+                    } else {
+                        attrs.push((key, value));
+                    }
+                }
+                // Declarations whose value differs from what the nearest
+                // written ancestor declared
+                let mut written = parent_written;
+                let mut declarations = Vec::new();
+                for (prefix, uri) in &scope {
+                    if written.get(prefix) != Some(uri) && !(prefix.is_empty() && uri.is_empty()) {
+                        declarations.push((prefix.clone(), uri.clone()));
+                        written.insert(prefix.clone(), uri.clone());
+                    }
+                }
+                let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                out.push('<');
+                out.push_str(&name);
+                for (prefix, uri) in &declarations {
+                    if prefix.is_empty() {
+                        out.push_str(&format!(" xmlns=\"{}\"", escape_attr(uri)));
+                    } else {
+                        out.push_str(&format!(" xmlns:{}=\"{}\"", prefix, escape_attr(uri)));
+                    }
+                }
+                // Unqualified attributes first, then by namespace and name
+                let sort_key = |key: &str| match key.split_once(':') {
+                    Some((prefix, local)) => (
+                        scope.get(prefix).cloned().unwrap_or_default(),
+                        local.to_string(),
+                    ),
+                    None => (String::new(), key.to_string()),
+                };
+                attrs.sort_by_key(|(key, _)| sort_key(key));
+                for (key, value) in &attrs {
+                    out.push_str(&format!(" {}=\"{}\"", key, escape_attr(value)));
+                }
+                out.push('>');
+                if matches!(event, Event::Empty(_)) {
+                    out.push_str(&format!("</{}>", name));
+                } else {
+                    scopes.push((scope, written));
+                    names.push(name);
+                }
+            }
+            Event::End(_) => {
+                scopes.pop();
+                if let Some(name) = names.pop() {
+                    out.push_str(&format!("</{}>", name));
+                }
+            }
+            Event::Text(e) => {
+                let text = e.unescape().map_err(|e| XlexError::InvalidXml {
+                    message: e.to_string(),
+                })?;
+                out.push_str(&escape_text(&text));
+            }
+            Event::CData(e) => out.push_str(&escape_text(&String::from_utf8_lossy(e))),
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(out.into_bytes())
+}
+
+/// Finds the first element matching `matches`, returning its bytes and the
+/// namespaces its ancestors declare.
+fn find_element(
+    xml: &[u8],
+    matches: impl Fn(&BytesStart) -> bool,
+) -> XlexResult<Option<(&[u8], Namespaces)>> {
+    let mut reader = Reader::from_reader(xml);
+    let mut scopes: Vec<Namespaces> = vec![Namespaces::new()];
+    loop {
+        let start = reader.buffer_position() as usize;
+        match reader.read_event()? {
+            Event::Start(e) if matches(&e) => {
+                let inherited = scopes.last().cloned().unwrap_or_default();
+                let end = reader.read_to_end(e.name())?.end as usize;
+                let close = xml[end..]
+                    .iter()
+                    .position(|&b| b == b'>')
+                    .map_or(xml.len(), |p| end + p + 1);
+                return Ok(Some((&xml[start..close], inherited)));
+            }
+            Event::Empty(e) if matches(&e) => {
+                let end = reader.buffer_position() as usize;
+                return Ok(Some((
+                    &xml[start..end],
+                    scopes.last().cloned().unwrap_or_default(),
+                )));
+            }
+            Event::Start(e) => {
+                let mut scope = scopes.last().cloned().unwrap_or_default();
+                for attr in e.attributes().flatten() {
+                    let key = attr.key.as_ref();
+                    let value = String::from_utf8_lossy(&attr.value).to_string();
+                    if key == b"xmlns" {
+                        scope.insert(String::new(), value);
+                    } else if let Some(prefix) = key.strip_prefix(b"xmlns:") {
+                        scope.insert(String::from_utf8_lossy(prefix).to_string(), value);
+                    }
+                }
+                scopes.push(scope);
+            }
+            Event::End(_) => {
+                scopes.pop();
+            }
+            Event::Eof => return Ok(None),
+            _ => {}
+        }
+    }
+}
+
+/// Canonical bytes of the element matching `matches`.
+fn canonical_element(
+    xml: &[u8],
+    matches: impl Fn(&BytesStart) -> bool,
+) -> XlexResult<Option<Vec<u8>>> {
+    match find_element(xml, matches)? {
+        Some((fragment, inherited)) => Ok(Some(canonicalize(fragment, &inherited)?)),
+        None => Ok(None),
+    }
+}
+
+/// Text of the first element named `local` inside `xml`.
+fn element_text(xml: &[u8], local: &[u8]) -> XlexResult<Option<String>> {
+    let mut reader = Reader::from_reader(xml);
+    let mut inside = false;
+    let mut text = String::new();
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) if e.local_name().as_ref() == local => inside = true,
+            Event::Text(e) if inside => {
+                text.push_str(&e.unescape().map_err(|e| XlexError::InvalidXml {
+                    message: e.to_string(),
+                })?)
+            }
+            Event::End(e) if inside && e.local_name().as_ref() == local => return Ok(Some(text)),
+            Event::Eof => return Ok(None),
+            _ => {}
+        }
+    }
+}
+
+/// A reference from signed info or a manifest.
+#[derive(Debug, Default)]
+struct Reference {
+    uri: String,
+    transforms: Vec<Transform>,
+    digest_method: String,
+    digest_value: String,
+}
+
+#[derive(Debug, Default)]
+struct Transform {
+    algorithm: String,
+    source_ids: Vec<String>,
+    source_types: Vec<String>,
+}
+
+/// The references in `xml`, in document order.
+fn references(xml: &[u8]) -> XlexResult<Vec<Reference>> {
+    let mut refs: Vec<Reference> = Vec::new();
+    let mut reader = Reader::from_reader(xml);
+    let mut in_digest = false;
+    loop {
+        let event = reader.read_event()?;
+        match &event {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                b"Reference" => refs.push(Reference {
+                    uri: attribute(e, b"URI").unwrap_or_default(),
+                    ..Reference::default()
+                }),
+                b"Transform" => {
+                    if let Some(r) = refs.last_mut() {
+                        r.transforms.push(Transform {
+                            algorithm: attribute(e, b"Algorithm").unwrap_or_default(),
+                            ..Transform::default()
+                        });
+                    }
+                }
+                b"RelationshipReference" => {
+                    if let Some(t) = refs.last_mut().and_then(|r| r.transforms.last_mut()) {
+                        t.source_ids.extend(attribute(e, b"SourceId"));
+                    }
+                }
+                b"RelationshipsGroupReference" => {
+                    if let Some(t) = refs.last_mut().and_then(|r| r.transforms.last_mut()) {
+                        t.source_types.extend(attribute(e, b"SourceType"));
+                    }
+                }
+                b"DigestMethod" => {
+                    if let Some(r) = refs.last_mut() {
+                        r.digest_method = attribute(e, b"Algorithm").unwrap_or_default();
+                    }
+                }
+                b"DigestValue" => in_digest = matches!(event, Event::Start(_)),
+                _ => {}
+            },
+            Event::Text(e) if in_digest => {
+                if let Some(r) = refs.last_mut() {
+                    let text = e.unescape().map_err(|e| XlexError::InvalidXml {
+                        message: e.to_string(),
+                    })?;
+                    r.digest_value.push_str(text.trim());
+                }
+            }
+            Event::End(e) if e.local_name().as_ref() == b"DigestValue" => in_digest = false,
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(refs)
+}
+
+/// Applies the OPC relationship transform: keeps the relationships named
+/// by id or type, sorted by id, with `TargetMode` made explicit, in
+/// canonical form.
+fn relationship_transform(xml: &[u8], transform: &Transform) -> XlexResult<Vec<u8>> {
+    let mut selected = Vec::new();
+    let mut reader = Reader::from_reader(xml);
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"Relationship" => {
+                let id = attribute(&e, b"Id").unwrap_or_default();
+                let kind = attribute(&e, b"Type").unwrap_or_default();
+                if transform.source_ids.contains(&id) || transform.source_types.contains(&kind) {
+                    selected.push((
+                        id,
+                        attribute(&e, b"Target").unwrap_or_default(),
+                        attribute(&e, b"TargetMode").unwrap_or_else(|| "Internal".to_string()),
+                        kind,
+                    ));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    selected.sort();
+    let mut out = format!("<Relationships xmlns=\"{}\">", RELS_NS);
+    for (id, target, mode, kind) in selected {
+        out.push_str(&format!(
+            "<Relationship Id=\"{}\" Target=\"{}\" TargetMode=\"{}\" Type=\"{}\"></Relationship>",
+            escape_attr(&id),
+            escape_attr(&target),
+            escape_attr(&mode),
+            escape_attr(&kind)
+        ));
+    }
+    out.push_str("</Relationships>");
+    Ok(out.into_bytes())
+}
+
+/// Checks a manifest reference to a package part; returns the part name.
+fn check_part_reference(
+    reference: &Reference,
+    parts: &[(String, Vec<u8>)],
+    types: &HashMap<String, String>,
+    problems: &mut Vec<String>,
+) -> XlexResult<Option<String>> {
+    let (path, query) = reference
+        .uri
+        .split_once('?')
+        .unwrap_or((reference.uri.as_str(), ""));
+    let name = path.trim_start_matches('/').to_string();
+    let Some(content) = part(parts, &name) else {
+        problems.push(format!("signed part {} is missing", name));
+        return Ok(None);
+    };
+    if let Some(expected) = query.strip_prefix("ContentType=") {
+        if types.get(&name).map(String::as_str) != Some(expected) {
+            problems.push(format!("content type of {} changed", name));
+        }
+    }
+    let Some(algorithm) = digest_algorithm(&reference.digest_method) else {
+        problems.push(format!(
+            "unsupported digest {} for {}",
+            reference.digest_method, name
+        ));
+        return Ok(Some(name));
+    };
+    let mut data = content.to_vec();
+    for transform in &reference.transforms {
+        match transform.algorithm.as_str() {
+            RELATIONSHIP_TRANSFORM => data = relationship_transform(&data, transform)?,
+            a if a.starts_with("http://www.w3.org/TR/2001/REC-xml-c14n")
+                || a.starts_with("http://www.w3.org/2001/10/xml-exc-c14n") =>
+            {
+                // The relationship transform already yields canonical XML
+            }
+            other => {
+                problems.push(format!("unsupported transform {} for {}", other, name));
+                return Ok(Some(name));
+            }
+        }
+    }
+    if digest_base64(&data, algorithm)? != reference.digest_value {
+        problems.push(format!("{} was modified", name));
+    }
+    Ok(Some(name))
+}
+
+/// Converts an XML-DSig ECDSA value (r and s concatenated) to DER.
+fn ecdsa_raw_to_der(raw: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+    let (r, s) = raw.split_at(raw.len() / 2);
+    EcdsaSig::from_private_components(BigNum::from_slice(r)?, BigNum::from_slice(s)?)?.to_der()
+}
+
+/// Converts a DER ECDSA signature to r and s concatenated, each padded to
+/// the size of the curve order.
+fn ecdsa_der_to_raw(der: &[u8], key: &PKey<Private>) -> Result<Vec<u8>, ErrorStack> {
+    let sig = EcdsaSig::from_der(der)?;
+    let ec = key.ec_key()?;
+    let mut ctx = openssl::bn::BigNumContext::new()?;
+    let mut order = BigNum::new()?;
+    ec.group().order(&mut order, &mut ctx)?;
+    let size = order.num_bytes();
+    let mut raw = sig.r().to_vec_padded(size)?;
+    raw.extend(sig.s().to_vec_padded(size)?);
+    Ok(raw)
+}
+
+fn verify_signature_value(
+    key: &PKey<Public>,
+    method: &str,
+    signed_info: &[u8],
+    value: &[u8],
+) -> Result<bool, String> {
+    let (digest, ecdsa) =
+        signature_algorithm(method).ok_or_else(|| format!("unsupported signature {}", method))?;
+    let check = || -> Result<bool, ErrorStack> {
+        let value = if ecdsa {
+            ecdsa_raw_to_der(value)?
+        } else {
+            value.to_vec()
+        };
+        let mut verifier = Verifier::new(digest, key)?;
+        verifier.update(signed_info)?;
+        verifier.verify(&value)
+    };
+    check().or(Ok(false))
+}
+
+/// Checks every signature in a package. `trusted_pem` holds CA
+/// certificates the signers' certificates must chain to; without it the
+/// chain is not checked and [`SignatureReport::trusted`] is `None`.
+pub fn verify(data: &[u8], trusted_pem: Option<&[u8]>) -> XlexResult<Vec<SignatureReport>> {
+    let parts = read_parts(data)?;
+    let types = content_types(&parts)?;
+    let (origin, signatures) = signature_parts(&parts)?;
+    let trusted = match trusted_pem {
+        Some(pem) => Some(
+            X509::stack_from_pem(pem)
+                .map_err(|e| crypto_error("Cannot read the CA certificates", e))?,
+        ),
+        None => None,
+    };
+
+    // Parts that belong to the signatures themselves are never signed
+    let mut unsignable: BTreeSet<String> = signatures.iter().cloned().collect();
+    unsignable.insert(CONTENT_TYPES_PART.to_string());
+    if let Some(origin) = &origin {
+        unsignable.insert(origin.clone());
+        let (dir, name) = origin.rsplit_once('/').unwrap_or(("", origin));
+        unsignable.insert(resolve(dir, &format!("_rels/{}.rels", name)));
+    }
+
+    let mut reports = Vec::new();
+    for signature_part in signatures {
+        let mut problems = Vec::new();
+        let Some(xml) = part(&parts, &signature_part) else {
+            problems.push("signature part is missing".to_string());
+            reports.push(SignatureReport {
+                part: signature_part,
+                status: SignatureStatus::Invalid,
+                signer: String::new(),
+                issuer: String::new(),
+                not_before: String::new(),
+                not_after: String::new(),
+                signing_time: None,
+                trusted: None,
+                signed_parts: Vec::new(),
+                problems,
+            });
+            continue;
+        };
+
+        let certificate = element_text(xml, b"X509Certificate")?
+            .and_then(|text| {
+                let text: String = text.split_whitespace().collect();
+                base64::decode_block(&text).ok()
+            })
+            .and_then(|der| X509::from_der(&der).ok())
+            .ok_or_else(|| XlexError::InvalidXml {
+                message: format!("{} has no readable X509Certificate", signature_part),
+            })?;
+
+        // The signature value over the canonical signed info
+        let signed_info = canonical_element(xml, |e| e.local_name().as_ref() == b"SignedInfo")?
+            .ok_or_else(|| XlexError::InvalidXml {
+                message: format!("{} has no SignedInfo", signature_part),
+            })?;
+        let method = find_element(&signed_info, |e| {
+            e.local_name().as_ref() == b"SignatureMethod"
+        })?
+        .and_then(|(fragment, _)| {
+            let mut reader = Reader::from_reader(fragment);
+            match reader.read_event() {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) => attribute(&e, b"Algorithm"),
+                _ => None,
+            }
+        })
+        .unwrap_or_default();
+        let value = element_text(xml, b"SignatureValue")?
+            .map(|text| text.split_whitespace().collect::<String>())
+            .and_then(|text| base64::decode_block(&text).ok())
+            .unwrap_or_default();
+        let key = certificate
+            .public_key()
+            .map_err(|e| crypto_error("Cannot read the signer's public key", e))?;
+        match verify_signature_value(&key, &method, &signed_info, &value) {
+            Ok(true) => {}
+            Ok(false) => problems.push("signature value does not match".to_string()),
+            Err(problem) => problems.push(problem),
+        }
+
+        // Same-document references (package object, signed properties)
+        let mut signed_parts = Vec::new();
+        for reference in references(&signed_info)? {
+            let Some(id) = reference.uri.strip_prefix('#') else {
+                problems.push(format!("unsupported reference {}", reference.uri));
+                continue;
+            };
+            let Some(element) =
+                canonical_element(xml, |e| attribute(e, b"Id").as_deref() == Some(id))?
+            else {
+                problems.push(format!("referenced element {} is missing", id));
+                continue;
+            };
+            match digest_algorithm(&reference.digest_method) {
+                Some(algorithm)
+                    if digest_base64(&element, algorithm)? == reference.digest_value => {}
+                Some(_) => problems.push(format!("element {} was modified", id)),
+                None => problems.push(format!("unsupported digest {}", reference.digest_method)),
+            }
+            // A manifest lists the package parts
+            if find_element(&element, |e| e.local_name().as_ref() == b"Manifest")?.is_some() {
+                for part_ref in references(&element)? {
+                    if let Some(name) =
+                        check_part_reference(&part_ref, &parts, &types, &mut problems)?
+                    {
+                        signed_parts.push(name);
+                    }
+                }
+            }
+        }
+        if signed_parts.is_empty() {
+            problems.push("no package parts are signed".to_string());
+        }
+        for (name, _) in &parts {
+            if !unsignable.contains(name) && !signed_parts.contains(name) {
+                problems.push(format!("{} is not covered by the signature", name));
+            }
+        }
+
+        // The certificate named in the signed properties
+        if let Some((cert_digest, _)) =
+            find_element(xml, |e| e.local_name().as_ref() == b"CertDigest")?
+        {
+            let cert_ref = references(
+                format!(
+                    "<Reference>{}</Reference>",
+                    String::from_utf8_lossy(cert_digest)
+                )
+                .as_bytes(),
+            )?;
+            if let Some(cert_ref) = cert_ref.first() {
+                let der = certificate
+                    .to_der()
+                    .map_err(|e| crypto_error("Cannot encode the certificate", e))?;
+                match digest_algorithm(&cert_ref.digest_method) {
+                    Some(algorithm) if digest_base64(&der, algorithm)? == cert_ref.digest_value => {
+                    }
+                    _ => problems.push(
+                        "signing certificate does not match the signed properties".to_string(),
+                    ),
+                }
+            }
+        }
+
+        let trusted = match &trusted {
+            Some(cas) => Some(chains_to(&certificate, cas)?),
+            None => None,
+        };
+        match trusted {
+            Some(false) => {
+                problems.push("certificate does not chain to a trusted CA".to_string());
+            }
+            Some(true) => {}
+            // The chain check covers the validity period; without CAs it
+            // is checked here
+            None => problems.extend(validity_problem(&certificate)?),
+        }
+
+        reports.push(SignatureReport {
+            part: signature_part,
+            status: if problems.is_empty() {
+                SignatureStatus::Valid
+            } else {
+                SignatureStatus::Invalid
+            },
+            signer: name_to_string(certificate.subject_name()),
+            issuer: name_to_string(certificate.issuer_name()),
+            not_before: certificate.not_before().to_string(),
+            not_after: certificate.not_after().to_string(),
+            signing_time: element_text(xml, b"SigningTime")?,
+            trusted,
+            signed_parts,
+            problems,
+        });
+    }
+    Ok(reports)
+}
+
+/// Why `certificate` is not valid now, if it is not.
+fn validity_problem(certificate: &X509) -> XlexResult<Option<String>> {
+    let now = Asn1Time::from_unix(Utc::now().timestamp())
+        .map_err(|e| crypto_error("Cannot read the clock", e))?;
+    let compare = |time: &openssl::asn1::Asn1TimeRef| {
+        time.compare(&now)
+            .map_err(|e| crypto_error("Cannot read the certificate validity", e))
+    };
+    Ok(if compare(certificate.not_before())? == Ordering::Greater {
+        Some(format!(
+            "certificate is not valid before {}",
+            certificate.not_before()
+        ))
+    } else if compare(certificate.not_after())? == Ordering::Less {
+        Some(format!(
+            "certificate expired on {}",
+            certificate.not_after()
+        ))
+    } else {
+        None
+    })
+}
+
+fn chains_to(certificate: &X509, cas: &[X509]) -> XlexResult<bool> {
+    let check = || -> Result<bool, ErrorStack> {
+        let mut store = X509StoreBuilder::new()?;
+        for ca in cas {
+            store.add_cert(ca.clone())?;
+        }
+        let store = store.build();
+        let chain = Stack::new()?;
+        let mut context = X509StoreContext::new()?;
+        context.init(&store, certificate, &chain, |c| c.verify_cert())
+    };
+    check().map_err(|e| crypto_error("Certificate check failed", e))
+}
+
+/// Adds a signature by `identity` to a package and returns the new package.
+/// Every part present is signed, so the package should be final.
+pub fn sign(data: &[u8], identity: &SigningIdentity) -> XlexResult<Vec<u8>> {
+    let mut parts = read_parts(data)?;
+    let (origin, existing) = signature_parts(&parts)?;
+    let crypto = |e: ErrorStack| crypto_error("Signing failed", e);
+
+    // Package plumbing first, since the root relationships get signed
+    let origin = match origin {
+        Some(origin) => origin,
+        None => {
+            let rels =
+                part(&parts, "_rels/.rels").ok_or_else(|| XlexError::MissingRequiredEntry {
+                    entry: "_rels/.rels".to_string(),
+                })?;
+            let ids: Vec<String> = relationships(rels)?.into_iter().map(|r| r.0).collect();
+            let id = (1..)
+                .map(|n| format!("rIdSig{}", n))
+                .find(|id| !ids.contains(id))
+                .unwrap_or_default();
+            let rels = insert_before(
+                rels,
+                "</Relationships>",
+                &format!(
+                    "<Relationship Id=\"{}\" Type=\"{}\" Target=\"{}\"/>",
+                    id, ORIGIN_REL, ORIGIN_PART
+                ),
+            )?;
+            replace_part(&mut parts, "_rels/.rels", rels);
+            replace_part(&mut parts, ORIGIN_PART, Vec::new());
+            let types = part(&parts, CONTENT_TYPES_PART)
+                .unwrap_or_default()
+                .to_vec();
+            let types = insert_before(
+                &types,
+                "</Types>",
+                &format!(
+                    "<Default Extension=\"sigs\" ContentType=\"{}\"/>",
+                    ORIGIN_CONTENT_TYPE
+                ),
+            )?;
+            replace_part(&mut parts, CONTENT_TYPES_PART, types);
+            ORIGIN_PART.to_string()
+        }
+    };
+    let (dir, name) = origin.rsplit_once('/').unwrap_or(("", &origin));
+    let origin_rels_part = resolve(dir, &format!("_rels/{}.rels", name));
+    let signature_number = (1..)
+        .find(|n| {
+            let candidate = resolve(dir, &format!("sig{}.xml", n));
+            !existing.contains(&candidate) && part(&parts, &candidate).is_none()
+        })
+        .unwrap_or(1);
+    let signature_part = resolve(dir, &format!("sig{}.xml", signature_number));
+    let origin_rels = match part(&parts, &origin_rels_part) {
+        Some(rels) => rels.to_vec(),
+        None => format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<Relationships xmlns=\"{}\"></Relationships>",
+            RELS_NS
+        )
+        .into_bytes(),
+    };
+    let origin_rels = insert_before(
+        &origin_rels,
+        "</Relationships>",
+        &format!(
+            "<Relationship Id=\"rId{}\" Type=\"{}\" Target=\"sig{}.xml\"/>",
+            signature_number, SIGNATURE_REL, signature_number
+        ),
+    )?;
+    replace_part(&mut parts, &origin_rels_part, origin_rels);
+    let types = part(&parts, CONTENT_TYPES_PART)
+        .unwrap_or_default()
+        .to_vec();
+    let types = insert_before(
+        &types,
+        "</Types>",
+        &format!(
+            "<Override PartName=\"/{}\" ContentType=\"{}\"/>",
+            signature_part, SIGNATURE_CONTENT_TYPE
+        ),
+    )?;
+    replace_part(&mut parts, CONTENT_TYPES_PART, types);
+
+    // The manifest of every other part
+    let content_types = content_types(&parts)?;
+    let skip: BTreeSet<&str> = [
+        CONTENT_TYPES_PART,
+        origin.as_str(),
+        origin_rels_part.as_str(),
+    ]
+    .into_iter()
+    .chain(existing.iter().map(String::as_str))
+    .collect();
+    let mut manifest = String::new();
+    for (name, content) in &parts {
+        if skip.contains(name.as_str()) {
+            continue;
+        }
+        let content_type = content_types.get(name).cloned().unwrap_or_default();
+        manifest.push_str(&format!(
+            "<Reference URI=\"/{}?ContentType={}\"><DigestMethod Algorithm=\"{}\"></DigestMethod><DigestValue>{}</DigestValue></Reference>",
+            escape_attr(name),
+            escape_attr(&content_type),
+            SHA256,
+            digest_base64(content, MessageDigest::sha256())?
+        ));
+    }
+    let package_object = format!(
+        "<Object Id=\"idPackageObject\"><Manifest>{}</Manifest></Object>",
+        manifest
+    );
+
+    // Signed properties: signing time and the signer's certificate
+    let certificate = &identity.certificate;
+    let cert_der = certificate.to_der().map_err(crypto)?;
+    let serial = certificate
+        .serial_number()
+        .to_bn()
+        .and_then(|bn| bn.to_dec_str().map(|s| s.to_string()))
+        .map_err(crypto)?;
+    let signing_time = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+    let signed_properties = format!(
+        "<xd:SignedProperties Id=\"idSignedProperties\"><xd:SignedSignatureProperties><xd:SigningTime>{}</xd:SigningTime><xd:SigningCertificate><xd:Cert><xd:CertDigest><DigestMethod Algorithm=\"{}\"></DigestMethod><DigestValue>{}</DigestValue></xd:CertDigest><xd:IssuerSerial><X509IssuerName>{}</X509IssuerName><X509SerialNumber>{}</X509SerialNumber></xd:IssuerSerial></xd:Cert></xd:SigningCertificate></xd:SignedSignatureProperties></xd:SignedProperties>",
+        signing_time,
+        SHA256,
+        digest_base64(&cert_der, MessageDigest::sha256())?,
+        escape_text(&name_to_string(certificate.issuer_name())),
+        serial
+    );
+    let qualifying = format!(
+        "<Object><xd:QualifyingProperties xmlns:xd=\"{}\" Target=\"#idPackageSignature\">{}</xd:QualifyingProperties></Object>",
+        XADES_NS, signed_properties
+    );
+
+    let dsig: Namespaces = [(String::new(), DSIG_NS.to_string())].into();
+    let mut xades = dsig.clone();
+    xades.insert("xd".to_string(), XADES_NS.to_string());
+    let object_digest = digest_base64(
+        &canonicalize(package_object.as_bytes(), &dsig)?,
+        MessageDigest::sha256(),
+    )?;
+    let properties_digest = digest_base64(
+        &canonicalize(signed_properties.as_bytes(), &xades)?,
+        MessageDigest::sha256(),
+    )?;
+
+    let (method, ecdsa) = match identity.key.id() {
+        Id::RSA => ("http://www.w3.org/2001/04/xmldsig-more#rsa-sha256", false),
+        Id::EC => ("http://www.w3.org/2001/04/xmldsig-more#ecdsa-sha256", true),
+        _ => {
+            return Err(XlexError::InvalidOperation {
+                message: "Only RSA and EC keys can sign".to_string(),
+            })
+        }
+    };
+    let signed_info = format!(
+        "<SignedInfo><CanonicalizationMethod Algorithm=\"{c14n}\"></CanonicalizationMethod><SignatureMethod Algorithm=\"{method}\"></SignatureMethod><Reference Type=\"http://www.w3.org/2000/09/xmldsig#Object\" URI=\"#idPackageObject\"><DigestMethod Algorithm=\"{sha}\"></DigestMethod><DigestValue>{object_digest}</DigestValue></Reference><Reference Type=\"http://uri.etsi.org/01903#SignedProperties\" URI=\"#idSignedProperties\"><DigestMethod Algorithm=\"{sha}\"></DigestMethod><DigestValue>{properties_digest}</DigestValue></Reference></SignedInfo>",
+        c14n = C14N,
+        sha = SHA256,
+    );
+    let mut signer = Signer::new(MessageDigest::sha256(), &identity.key).map_err(crypto)?;
+    signer
+        .update(&canonicalize(signed_info.as_bytes(), &dsig)?)
+        .map_err(crypto)?;
+    let mut value = signer.sign_to_vec().map_err(crypto)?;
+    if ecdsa {
+        value = ecdsa_der_to_raw(&value, &identity.key).map_err(crypto)?;
+    }
+
+    let signature = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<Signature xmlns=\"{}\" Id=\"idPackageSignature\">{}<SignatureValue>{}</SignatureValue><KeyInfo><X509Data><X509Certificate>{}</X509Certificate></X509Data></KeyInfo>{}{}</Signature>",
+        DSIG_NS,
+        signed_info,
+        base64::encode_block(&value),
+        base64::encode_block(&cert_der),
+        package_object,
+        qualifying
+    );
+    replace_part(&mut parts, &signature_part, signature.into_bytes());
+    write_parts(&parts)
+}
+
+/// Makes a PKCS#12 file with a new RSA key and a self-signed certificate
+/// for `common_name`, valid for a year. Meant for tests and for trying out
+/// signing; signatures that others rely on need a certificate from a CA.
+#[cfg(any(test, feature = "test-util"))]
+pub fn self_signed_pkcs12(common_name: &str, password: &str) -> XlexResult<Vec<u8>> {
+    let key = openssl::rsa::Rsa::generate(2048)
+        .and_then(PKey::from_rsa)
+        .map_err(|e| crypto_error("Cannot generate a key", e))?;
+    self_signed(&key, common_name, password)
+}
+
+#[cfg(any(test, feature = "test-util"))]
+fn self_signed(key: &PKey<Private>, common_name: &str, password: &str) -> XlexResult<Vec<u8>> {
+    self_signed_for(key, common_name, password, 0..365)
+}
+
+/// A self-signed certificate valid for `days`, counted from now.
+#[cfg(any(test, feature = "test-util"))]
+fn self_signed_for(
+    key: &PKey<Private>,
+    common_name: &str,
+    password: &str,
+    days: std::ops::Range<i64>,
+) -> XlexResult<Vec<u8>> {
+    use openssl::x509::X509NameBuilder;
+
+    let build = || -> Result<Vec<u8>, ErrorStack> {
+        let mut name = X509NameBuilder::new()?;
+        name.append_entry_by_text("CN", common_name)?;
+        let name = name.build();
+        let mut cert = X509::builder()?;
+        cert.set_version(2)?;
+        cert.set_subject_name(&name)?;
+        cert.set_issuer_name(&name)?;
+        cert.set_pubkey(key)?;
+        let now = Utc::now().timestamp();
+        let not_before = Asn1Time::from_unix(now + days.start * 86_400)?;
+        let not_after = Asn1Time::from_unix(now + days.end * 86_400)?;
+        cert.set_not_before(&not_before)?;
+        cert.set_not_after(&not_after)?;
+        cert.sign(key, MessageDigest::sha256())?;
+        let cert = cert.build();
+        Pkcs12::builder()
+            .name(common_name)
+            .pkey(key)
+            .cert(&cert)
+            .build2(password)?
+            .to_der()
+    };
+    build().map_err(|e| crypto_error("Cannot make the certificate", e))
+}
+
+fn insert_before(xml: &[u8], closing: &str, insert: &str) -> XlexResult<Vec<u8>> {
+    let text = String::from_utf8_lossy(xml);
+    let at = text.rfind(closing).ok_or_else(|| XlexError::InvalidXml {
+        message: format!("missing {}", closing),
+    })?;
+    Ok(format!("{}{}{}", &text[..at], insert, &text[at..]).into_bytes())
+}
+
+fn replace_part(parts: &mut Vec<(String, Vec<u8>)>, name: &str, content: Vec<u8>) {
+    match parts.iter_mut().find(|(n, _)| n == name) {
+        Some(entry) => entry.1 = content,
+        None => parts.push((name.to_string(), content)),
+    }
+}
+
+fn write_parts(parts: &[(String, Vec<u8>)]) -> XlexResult<Vec<u8>> {
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    for (name, content) in parts {
+        zip.start_file(name.as_str(), options)?;
+        zip.write_all(content)?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CellRef, CellValue, Workbook};
+    use openssl::ec::{EcGroup, EcKey};
+    use openssl::nid::Nid;
+
+    fn identity(ec: bool) -> SigningIdentity {
+        let p12 = if ec {
+            let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+            let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+            self_signed(&key, "Report Signer", "secret").unwrap()
+        } else {
+            self_signed_pkcs12("Report Signer", "secret").unwrap()
+        };
+        assert!(SigningIdentity::from_pkcs12(&p12, "wrong").is_err());
+        SigningIdentity::from_pkcs12(&p12, "secret").unwrap()
+    }
+
+    fn workbook_bytes() -> Vec<u8> {
+        let mut wb = Workbook::new();
+        wb.set_cell("Sheet1", CellRef::new(1, 1), CellValue::Number(42.0))
+            .unwrap();
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("book.xlsx");
+        wb.save_as(&path).unwrap();
+        std::fs::read(&path).unwrap()
+    }
+
+    fn rewrite(data: &[u8], name: &str, edit: impl Fn(&mut Vec<u8>)) -> Vec<u8> {
+        let mut parts = read_parts(data).unwrap();
+        let entry = parts.iter_mut().find(|(n, _)| n == name).unwrap();
+        edit(&mut entry.1);
+        write_parts(&parts).unwrap()
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        for ec in [false, true] {
+            let identity = identity(ec);
+            let signed = sign(&workbook_bytes(), &identity).unwrap();
+            let reports = verify(&signed, None).unwrap();
+            assert_eq!(reports.len(), 1);
+            let report = &reports[0];
+            assert_eq!(
+                report.status,
+                SignatureStatus::Valid,
+                "{:?}",
+                report.problems
+            );
+            assert_eq!(report.part, "_xmlsignatures/sig1.xml");
+            assert_eq!(report.signer, "CN=Report Signer");
+            assert!(report.signing_time.is_some());
+            assert!(report
+                .signed_parts
+                .contains(&"xl/worksheets/sheet1.xml".to_string()));
+            assert_eq!(report.trusted, None);
+
+            // The workbook still opens
+            let dir = tempfile::TempDir::new().unwrap();
+            let path = dir.path().join("signed.xlsx");
+            std::fs::write(&path, &signed).unwrap();
+            let wb = Workbook::open(&path).unwrap();
+            assert_eq!(
+                wb.get_sheet("Sheet1")
+                    .unwrap()
+                    .get_value(&CellRef::new(1, 1)),
+                CellValue::Number(42.0)
+            );
+
+            // Self-signed: trusted only when it is its own CA
+            let pem = identity.certificate.to_pem().unwrap();
+            assert_eq!(verify(&signed, Some(&pem)).unwrap()[0].trusted, Some(true));
+
+            // A second signature keeps the first valid
+            let twice = sign(&signed, &identity).unwrap();
+            let reports = verify(&twice, None).unwrap();
+            assert_eq!(reports.len(), 2);
+            assert!(reports.iter().all(|r| r.status == SignatureStatus::Valid));
+        }
+    }
+
+    #[test]
+    fn test_verify_detects_changes() {
+        let identity = identity(false);
+        let signed = sign(&workbook_bytes(), &identity).unwrap();
+        assert!(verify(&workbook_bytes(), None).unwrap().is_empty());
+
+        let altered = rewrite(&signed, "xl/worksheets/sheet1.xml", |xml| {
+            let text = String::from_utf8(xml.clone()).unwrap();
+            *xml = text.replace("<v>42</v>", "<v>43</v>").into_bytes();
+        });
+        let report = &verify(&altered, None).unwrap()[0];
+        assert_eq!(report.status, SignatureStatus::Invalid);
+        assert_eq!(
+            report.problems,
+            vec!["xl/worksheets/sheet1.xml was modified"]
+        );
+
+        // A part added after signing is not covered
+        let mut parts = read_parts(&signed).unwrap();
+        parts.push(("xl/extra.xml".to_string(), b"<x/>".to_vec()));
+        let added = write_parts(&parts).unwrap();
+        let report = &verify(&added, None).unwrap()[0];
+        assert_eq!(
+            report.problems,
+            vec!["xl/extra.xml is not covered by the signature"]
+        );
+
+        // Tampering with the manifest breaks the signature value
+        let forged = rewrite(&signed, "_xmlsignatures/sig1.xml", |xml| {
+            let text = String::from_utf8(xml.clone()).unwrap();
+            *xml = text
+                .replace(
+                    "idPackageObject\"><Manifest>",
+                    "idPackageObject\"><Manifest> ",
+                )
+                .into_bytes();
+        });
+        let report = &verify(&forged, None).unwrap()[0];
+        assert_eq!(
+            report.problems,
+            vec!["element idPackageObject was modified"]
+        );
+    }
+
+    #[test]
+    fn test_verify_checks_validity_period() {
+        let key = PKey::from_rsa(openssl::rsa::Rsa::generate(2048).unwrap()).unwrap();
+        for (days, problem) in [
+            (-30..-1, "certificate expired on "),
+            (1..30, "certificate is not valid before "),
+        ] {
+            let p12 = self_signed_for(&key, "Report Signer", "secret", days).unwrap();
+            let identity = SigningIdentity::from_pkcs12(&p12, "secret").unwrap();
+            let signed = sign(&workbook_bytes(), &identity).unwrap();
+            let report = &verify(&signed, None).unwrap()[0];
+            assert_eq!(report.status, SignatureStatus::Invalid);
+            assert_eq!(report.problems.len(), 1);
+            assert!(
+                report.problems[0].starts_with(problem),
+                "{:?}",
+                report.problems
+            );
+        }
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        let inherited: Namespaces = [(String::new(), DSIG_NS.to_string())].into();
+        let xml =
+            br#"<SignedInfo b="2" a="x&amp;y"><Empty/><T>a &gt; b&#xD;</T><!-- c --></SignedInfo>"#;
+        assert_eq!(
+            String::from_utf8(canonicalize(xml, &inherited).unwrap()).unwrap(),
+            format!(
+                "<SignedInfo xmlns=\"{}\" a=\"x&amp;y\" b=\"2\"><Empty></Empty><T>a &gt; b&#xD;</T></SignedInfo>",
+                DSIG_NS
+            )
+        );
+
+        let rels = br#"<Relationships xmlns="x"><Relationship Type="t2" Target="b" Id="rId2"/><Relationship Id="rId1" Type="t1" Target="a" TargetMode="External"/></Relationships>"#;
+        let transform = Transform {
+            algorithm: RELATIONSHIP_TRANSFORM.to_string(),
+            source_ids: vec!["rId2".to_string()],
+            source_types: vec!["t1".to_string()],
+        };
+        assert_eq!(
+            String::from_utf8(relationship_transform(rels, &transform).unwrap()).unwrap(),
+            format!(
+                "<Relationships xmlns=\"{}\"><Relationship Id=\"rId1\" Target=\"a\" TargetMode=\"External\" Type=\"t1\"></Relationship><Relationship Id=\"rId2\" Target=\"b\" TargetMode=\"Internal\" Type=\"t2\"></Relationship></Relationships>",
+                RELS_NS
+            )
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! Sparkline types.
+//!
+//! Sparklines are stored in the worksheet's `x14` extension list as groups
+//! sharing a chart type and colors, each with one or more sparklines that
+//! map a data range to the cell they are drawn in.
+
+use std::fmt;
+
+use crate::cell::CellRef;
+use crate::error::{XlexError, XlexResult};
+use crate::range::Range;
+use crate::style::Color;
+
+/// Chart type of a sparkline group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SparklineType {
+    /// Line chart (default)
+    #[default]
+    Line,
+    /// Column chart
+    Column,
+    /// Win/loss chart (positive vs negative)
+    WinLoss,
+}
+
+impl SparklineType {
+    /// Returns the `type` attribute value used in the sheet XML.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Line => "line",
+            Self::Column => "column",
+            Self::WinLoss => "stacked",
+        }
+    }
+
+    /// Parses a sparkline type (`line`, `column`, `winloss` or `stacked`).
+    pub fn parse(s: &str) -> XlexResult<Self> {
+        match s.to_ascii_lowercase().replace(['-', '_'], "").as_str() {
+            "line" => Ok(Self::Line),
+            "column" => Ok(Self::Column),
+            "winloss" | "stacked" => Ok(Self::WinLoss),
+            _ => Err(XlexError::InvalidOperation {
+                message: format!(
+                    "Unknown sparkline type '{}', expected line, column or winloss",
+                    s
+                ),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for SparklineType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WinLoss => write!(f, "winloss"),
+            other => write!(f, "{}", other.as_str()),
+        }
+    }
+}
+
+/// A single sparkline: a data range drawn into one cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sparkline {
+    /// Data reference including the sheet (e.g., "Sheet1!B2:M2")
+    pub data: String,
+    /// Cell the sparkline is drawn in
+    pub location: CellRef,
+}
+
+impl Sparkline {
+    /// Creates a sparkline over `data` on `sheet`, drawn at `location`.
+    pub fn new(sheet: &str, data: &Range, location: CellRef) -> Self {
+        Self {
+            data: format!("{}!{}", crate::sheet::quote_sheet_name(sheet), data.to_a1()),
+            location,
+        }
+    }
+}
+
+/// A group of sparklines sharing type and formatting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparklineGroup {
+    /// Chart type
+    pub sparkline_type: SparklineType,
+    /// Series color
+    pub color: Color,
+    /// Color for negative, high and low points
+    pub negative_color: Color,
+    /// Show markers on every point (line sparklines only)
+    pub markers: bool,
+    /// Highlight the highest and lowest points
+    pub high_low: bool,
+    /// Sparklines in the group
+    pub sparklines: Vec<Sparkline>,
+}
+
+impl SparklineGroup {
+    /// Default series color used by Excel.
+    pub const DEFAULT_COLOR: Color = Color::Rgb(0x376092);
+    /// Default negative point color used by Excel.
+    pub const DEFAULT_NEGATIVE_COLOR: Color = Color::Rgb(0xD00000);
+
+    /// Creates an empty group of the given type with Excel's default colors.
+    pub fn new(sparkline_type: SparklineType) -> Self {
+        Self {
+            sparkline_type,
+            color: Self::DEFAULT_COLOR,
+            negative_color: Self::DEFAULT_NEGATIVE_COLOR,
+            markers: false,
+            high_low: false,
+            sparklines: Vec::new(),
+        }
+    }
+
+    /// Adds sparklines for `data` drawn into `location`.
+    ///
+    /// A single target cell takes the whole data range. A target row or
+    /// column gets one sparkline per data row or column, like Excel's
+    /// "Create Sparklines" dialog.
+    pub fn add_range(&mut self, sheet: &str, data: &Range, location: &Range) -> XlexResult<()> {
+        if location.is_single() {
+            self.sparklines
+                .push(Sparkline::new(sheet, data, location.start.clone()));
+            return Ok(());
+        }
+
+        let targets: Vec<CellRef> = location.cells().collect();
+        let slices: Vec<Range> = if location.width() == 1 && data.height() == location.height() {
+            (data.start.row..=data.end.row)
+                .map(|row| {
+                    Range::new(
+                        CellRef::new(data.start.col, row),
+                        CellRef::new(data.end.col, row),
+                    )
+                })
+                .collect()
+        } else if location.height() == 1 && data.width() == location.width() {
+            (data.start.col..=data.end.col)
+                .map(|col| {
+                    Range::new(
+                        CellRef::new(col, data.start.row),
+                        CellRef::new(col, data.end.row),
+                    )
+                })
+                .collect()
+        } else {
+            return Err(XlexError::InvalidRange {
+                range: format!(
+                    "{} does not match data range {} (one target cell per data row or column)",
+                    location.to_a1(),
+                    data.to_a1()
+                ),
+            });
+        };
+
+        for (target, slice) in targets.into_iter().zip(slices) {
+            self.sparklines.push(Sparkline::new(sheet, &slice, target));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparkline_type_parse() {
+        assert_eq!(SparklineType::parse("line").unwrap(), SparklineType::Line);
+        assert_eq!(
+            SparklineType::parse("Win-Loss").unwrap(),
+            SparklineType::WinLoss
+        );
+        assert_eq!(SparklineType::WinLoss.as_str(), "stacked");
+        assert!(SparklineType::parse("pie").is_err());
+    }
+
+    #[test]
+    fn test_add_range_per_row() {
+        let mut group = SparklineGroup::new(SparklineType::Column);
+        let data = Range::parse("B2:M4").unwrap();
+        group
+            .add_range("Q1 Sales", &data, &Range::parse("N2:N4").unwrap())
+            .unwrap();
+        assert_eq!(group.sparklines.len(), 3);
+        assert_eq!(group.sparklines[1].data, "'Q1 Sales'!B3:M3");
+        assert_eq!(group.sparklines[1].location.to_a1(), "N3");
+
+        let mut single = SparklineGroup::new(SparklineType::Line);
+        single
+            .add_range("Sheet1", &data, &Range::parse("N2").unwrap())
+            .unwrap();
+        assert_eq!(single.sparklines[0].data, "Sheet1!B2:M4");
+
+        assert!(group
+            .add_range("Sheet1", &data, &Range::parse("N2:N3").unwrap())
+            .is_err());
+    }
+}
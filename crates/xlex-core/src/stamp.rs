@@ -0,0 +1,155 @@
+//! Content hash stamps for verifying a workbook was not altered.
+//!
+//! A stamp records the SHA-256 of a workbook's canonical content, with the
+//! time it was generated, in two custom document properties. The content
+//! covers every sheet's name and cell values and formulas, in sheet order,
+//! but not formatting or document properties, so adding the stamp does
+//! not change the hash and restyling a stamped file keeps it valid.
+//!
+//! ```no_run
+//! use xlex_core::stamp::{self, StampCheck};
+//! use xlex_core::Workbook;
+//!
+//! let mut workbook = Workbook::open("report.xlsx")?;
+//! stamp::stamp(&mut workbook);
+//! workbook.save()?;
+//!
+//! let workbook = Workbook::open("report.xlsx")?;
+//! assert!(matches!(stamp::verify(&workbook), StampCheck::Valid { .. }));
+//! # Ok::<(), xlex_core::XlexError>(())
+//! ```
+
+use chrono::{SecondsFormat, Utc};
+use sha2::{Digest, Sha256};
+
+use crate::workbook::Workbook;
+
+/// Custom property holding the content hash (`sha256:<hex>`).
+pub const HASH_PROPERTY: &str = "XlexContentHash";
+
+/// Custom property holding when the stamp was made (RFC 3339, UTC).
+pub const GENERATED_AT_PROPERTY: &str = "XlexGeneratedAt";
+
+/// Prefix naming the hash algorithm in [`HASH_PROPERTY`].
+const ALGORITHM_PREFIX: &str = "sha256:";
+
+/// Outcome of checking a workbook against its stamp.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StampCheck {
+    /// The workbook has no stamp
+    Unstamped {
+        /// The workbook's current content hash
+        actual: String,
+    },
+    /// The content matches the stamped hash
+    Valid {
+        /// The stamped (and current) content hash
+        hash: String,
+        /// When the stamp was made, if recorded
+        generated_at: Option<String>,
+    },
+    /// The content changed since it was stamped
+    Mismatch {
+        /// The stamped content hash
+        expected: String,
+        /// The workbook's current content hash
+        actual: String,
+        /// When the stamp was made, if recorded
+        generated_at: Option<String>,
+    },
+}
+
+/// Computes the workbook's canonical content hash as `sha256:<hex>`.
+pub fn content_hash(workbook: &Workbook) -> String {
+    let mut hasher = Sha256::new();
+    for name in workbook.sheet_names() {
+        hasher.update(format!("sheet:{}\n", name).as_bytes());
+        if let Some(sheet) = workbook.get_sheet(name) {
+            sheet.write_canonical(|bytes| hasher.update(bytes));
+        }
+    }
+    let digest = hasher.finalize();
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}{}", ALGORITHM_PREFIX, hex)
+}
+
+/// Writes the content hash and the current time into the workbook's
+/// custom properties. Returns the hash.
+pub fn stamp(workbook: &mut Workbook) -> String {
+    let hash = content_hash(workbook);
+    let now = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+    let properties = workbook.properties_mut();
+    properties.set_custom_property(HASH_PROPERTY, hash.clone());
+    properties.set_custom_property(GENERATED_AT_PROPERTY, now);
+    hash
+}
+
+/// Checks the workbook's content against its stamp.
+pub fn verify(workbook: &Workbook) -> StampCheck {
+    let actual = content_hash(workbook);
+    let properties = workbook.properties();
+    let generated_at = properties
+        .custom_property(GENERATED_AT_PROPERTY)
+        .map(str::to_string);
+    match properties.custom_property(HASH_PROPERTY) {
+        None => StampCheck::Unstamped { actual },
+        Some(expected) if expected.eq_ignore_ascii_case(&actual) => StampCheck::Valid {
+            hash: actual,
+            generated_at,
+        },
+        Some(expected) => StampCheck::Mismatch {
+            expected: expected.to_string(),
+            actual,
+            generated_at,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CellRef, CellValue};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_stamp_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("stamped.xlsx");
+        let mut wb = Workbook::with_sheets(&["Data", "Summary"]);
+        wb.set_cell("Data", CellRef::new(1, 1), CellValue::string("Total"))
+            .unwrap();
+        wb.set_cell("Data", CellRef::new(2, 1), CellValue::Number(12.5))
+            .unwrap();
+        wb.set_cell("Summary", CellRef::new(1, 1), CellValue::formula("Data!B1"))
+            .unwrap();
+        assert!(matches!(verify(&wb), StampCheck::Unstamped { .. }));
+
+        let hash = stamp(&mut wb);
+        assert!(hash.starts_with("sha256:") && hash.len() == 7 + 64);
+        wb.properties_mut().set_custom_property("Client", "Acme");
+        wb.save_as(&path).unwrap();
+
+        let mut wb = Workbook::open(&path).unwrap();
+        assert_eq!(wb.properties().custom_property("Client"), Some("Acme"));
+        match verify(&wb) {
+            StampCheck::Valid {
+                hash: stamped,
+                generated_at,
+            } => {
+                assert_eq!(stamped, hash);
+                assert!(generated_at.is_some());
+            }
+            other => panic!("expected a valid stamp, got {:?}", other),
+        }
+
+        wb.set_cell("Data", CellRef::new(2, 1), CellValue::Number(13.0))
+            .unwrap();
+        assert!(matches!(verify(&wb), StampCheck::Mismatch { .. }));
+
+        // Sheet names are part of the content
+        let mut renamed = Workbook::with_sheets(&["A"]);
+        let before = content_hash(&renamed);
+        renamed.rename_sheet("A", "B").unwrap();
+        assert_ne!(content_hash(&renamed), before);
+    }
+}
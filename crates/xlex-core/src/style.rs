@@ -48,6 +48,28 @@ pub enum BorderStyle {
     SlantDashDot,
 }
 
+impl BorderStyle {
+    /// Returns the `style` attribute value used in styles.xml.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BorderStyle::None => "none",
+            BorderStyle::Thin => "thin",
+            BorderStyle::Medium => "medium",
+            BorderStyle::Thick => "thick",
+            BorderStyle::Dashed => "dashed",
+            BorderStyle::Dotted => "dotted",
+            BorderStyle::Double => "double",
+            BorderStyle::Hair => "hair",
+            BorderStyle::MediumDashed => "mediumDashed",
+            BorderStyle::DashDot => "dashDot",
+            BorderStyle::MediumDashDot => "mediumDashDot",
+            BorderStyle::DashDotDot => "dashDotDot",
+            BorderStyle::MediumDashDotDot => "mediumDashDotDot",
+            BorderStyle::SlantDashDot => "slantDashDot",
+        }
+    }
+}
+
 /// A color value.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum Color {
@@ -108,6 +130,19 @@ impl Color {
             _ => None,
         }
     }
+
+    /// Resolves the color to RGB where that doesn't depend on the theme
+    /// palette: theme colors 0 and 1 are the default light and dark
+    /// backgrounds, indexed 8 and 9 are black and white.
+    fn resolve(&self, auto: u32) -> Option<u32> {
+        match self {
+            Self::Rgb(val) => Some(*val),
+            Self::Auto | Self::Indexed(64) => Some(auto),
+            Self::Theme(0) | Self::Indexed(9) => Some(0xFFFFFF),
+            Self::Theme(1) | Self::Indexed(8) => Some(0x000000),
+            _ => None,
+        }
+    }
 }
 
 /// Font style.
@@ -154,6 +189,140 @@ pub enum FillPattern {
     Gray0625,
 }
 
+impl FillPattern {
+    /// All pattern types.
+    pub const ALL: [FillPattern; 19] = [
+        FillPattern::None,
+        FillPattern::Solid,
+        FillPattern::MediumGray,
+        FillPattern::DarkGray,
+        FillPattern::LightGray,
+        FillPattern::DarkHorizontal,
+        FillPattern::DarkVertical,
+        FillPattern::DarkDown,
+        FillPattern::DarkUp,
+        FillPattern::DarkGrid,
+        FillPattern::DarkTrellis,
+        FillPattern::LightHorizontal,
+        FillPattern::LightVertical,
+        FillPattern::LightDown,
+        FillPattern::LightUp,
+        FillPattern::LightGrid,
+        FillPattern::LightTrellis,
+        FillPattern::Gray125,
+        FillPattern::Gray0625,
+    ];
+
+    /// Returns the `patternType` name used in styles.xml.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FillPattern::None => "none",
+            FillPattern::Solid => "solid",
+            FillPattern::MediumGray => "mediumGray",
+            FillPattern::DarkGray => "darkGray",
+            FillPattern::LightGray => "lightGray",
+            FillPattern::DarkHorizontal => "darkHorizontal",
+            FillPattern::DarkVertical => "darkVertical",
+            FillPattern::DarkDown => "darkDown",
+            FillPattern::DarkUp => "darkUp",
+            FillPattern::DarkGrid => "darkGrid",
+            FillPattern::DarkTrellis => "darkTrellis",
+            FillPattern::LightHorizontal => "lightHorizontal",
+            FillPattern::LightVertical => "lightVertical",
+            FillPattern::LightDown => "lightDown",
+            FillPattern::LightUp => "lightUp",
+            FillPattern::LightGrid => "lightGrid",
+            FillPattern::LightTrellis => "lightTrellis",
+            FillPattern::Gray125 => "gray125",
+            FillPattern::Gray0625 => "gray0625",
+        }
+    }
+
+    /// Parses a pattern name, ignoring case, `-` and `_` (`darkGrid`,
+    /// `dark-grid`).
+    pub fn parse(s: &str) -> Option<Self> {
+        let normalized: String = s
+            .chars()
+            .filter(|c| *c != '-' && *c != '_')
+            .collect::<String>()
+            .to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|p| p.as_str().to_lowercase() == normalized)
+    }
+}
+
+/// Gradient fill type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GradientType {
+    /// Colors change along a line at [`GradientFill::degree`]
+    #[default]
+    Linear,
+    /// Colors radiate from a center box towards the cell edges
+    Path,
+}
+
+/// A color stop in a gradient fill.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GradientStop {
+    /// Position along the gradient (0.0 to 1.0)
+    pub position: f64,
+    /// Stop color
+    pub color: Color,
+}
+
+/// Gradient fill.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct GradientFill {
+    /// Gradient type
+    pub gradient_type: GradientType,
+    /// Angle in degrees (linear gradients)
+    pub degree: f64,
+    /// Inner box edges as fractions of the cell (path gradients)
+    pub left: f64,
+    pub right: f64,
+    pub top: f64,
+    pub bottom: f64,
+    /// Color stops, ordered by position
+    pub stops: Vec<GradientStop>,
+}
+
+impl GradientFill {
+    /// Linear gradient with colors spread evenly along the given angle.
+    pub fn linear(degree: f64, colors: Vec<Color>) -> Self {
+        Self {
+            degree,
+            stops: Self::even_stops(colors),
+            ..Default::default()
+        }
+    }
+
+    /// Path gradient radiating from the center of the cell.
+    pub fn path(colors: Vec<Color>) -> Self {
+        Self {
+            gradient_type: GradientType::Path,
+            left: 0.5,
+            right: 0.5,
+            top: 0.5,
+            bottom: 0.5,
+            stops: Self::even_stops(colors),
+            ..Default::default()
+        }
+    }
+
+    fn even_stops(colors: Vec<Color>) -> Vec<GradientStop> {
+        let last = colors.len().saturating_sub(1).max(1) as f64;
+        colors
+            .into_iter()
+            .enumerate()
+            .map(|(i, color)| GradientStop {
+                position: i as f64 / last,
+                color,
+            })
+            .collect()
+    }
+}
+
 /// Fill style.
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct Fill {
@@ -163,6 +332,8 @@ pub struct Fill {
     pub fg_color: Option<Color>,
     /// Background color
     pub bg_color: Option<Color>,
+    /// Gradient, used instead of the pattern when set
+    pub gradient: Option<GradientFill>,
 }
 
 /// Border definition for one side.
@@ -187,6 +358,17 @@ pub struct Border {
 }
 
 impl Border {
+    /// Creates a diagonal-only border running up (bottom-left to top-right),
+    /// down (top-left to bottom-right) or both.
+    pub fn diagonal(style: BorderStyle, color: Option<Color>, up: bool, down: bool) -> Self {
+        Self {
+            diagonal: BorderSide { style, color },
+            diagonal_up: up,
+            diagonal_down: down,
+            ..Default::default()
+        }
+    }
+
     /// Creates a border with all sides set to the same style.
     pub fn all(style: BorderStyle, color: Option<Color>) -> Self {
         let side = BorderSide {
@@ -203,6 +385,171 @@ impl Border {
     }
 }
 
+/// Locale used by locale-aware number formats.
+///
+/// Excel format codes always use `,` and `.` as the grouping and decimal
+/// placeholders; the locale is carried as an LCID tag (`[$€-407]`) that
+/// controls currency symbol placement and how the code is rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Locale {
+    /// English (United States)
+    #[default]
+    EnUs,
+    /// English (United Kingdom)
+    EnGb,
+    /// German (Germany)
+    De,
+    /// German (Switzerland)
+    DeCh,
+    /// French (France)
+    Fr,
+    /// Spanish (Spain)
+    Es,
+    /// Italian (Italy)
+    It,
+    /// Dutch (Netherlands)
+    Nl,
+    /// Portuguese (Brazil)
+    PtBr,
+    /// Japanese
+    Ja,
+    /// Chinese (Simplified)
+    ZhCn,
+    /// Chinese (Traditional, Taiwan)
+    ZhTw,
+    /// Korean
+    Ko,
+}
+
+impl Locale {
+    /// All supported locales.
+    pub const ALL: [Locale; 13] = [
+        Locale::EnUs,
+        Locale::EnGb,
+        Locale::De,
+        Locale::DeCh,
+        Locale::Fr,
+        Locale::Es,
+        Locale::It,
+        Locale::Nl,
+        Locale::PtBr,
+        Locale::Ja,
+        Locale::ZhCn,
+        Locale::ZhTw,
+        Locale::Ko,
+    ];
+
+    /// Parses a language tag such as `de-DE` or `de` (case-insensitive).
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let tag = tag.trim().replace('_', "-").to_lowercase();
+        Some(match tag.as_str() {
+            "en" | "en-us" => Locale::EnUs,
+            "en-gb" => Locale::EnGb,
+            "de" | "de-de" | "de-at" => Locale::De,
+            "de-ch" => Locale::DeCh,
+            "fr" | "fr-fr" => Locale::Fr,
+            "es" | "es-es" => Locale::Es,
+            "it" | "it-it" => Locale::It,
+            "nl" | "nl-nl" => Locale::Nl,
+            "pt-br" => Locale::PtBr,
+            "ja" | "ja-jp" => Locale::Ja,
+            "zh" | "zh-cn" => Locale::ZhCn,
+            "zh-tw" => Locale::ZhTw,
+            "ko" | "ko-kr" => Locale::Ko,
+            _ => return None,
+        })
+    }
+
+    /// Returns the language tag (`de-DE`).
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Locale::EnUs => "en-US",
+            Locale::EnGb => "en-GB",
+            Locale::De => "de-DE",
+            Locale::DeCh => "de-CH",
+            Locale::Fr => "fr-FR",
+            Locale::Es => "es-ES",
+            Locale::It => "it-IT",
+            Locale::Nl => "nl-NL",
+            Locale::PtBr => "pt-BR",
+            Locale::Ja => "ja-JP",
+            Locale::ZhCn => "zh-CN",
+            Locale::ZhTw => "zh-TW",
+            Locale::Ko => "ko-KR",
+        }
+    }
+
+    /// Returns the Windows locale ID used in format code tags.
+    pub fn lcid(&self) -> u32 {
+        match self {
+            Locale::EnUs => 0x409,
+            Locale::EnGb => 0x809,
+            Locale::De => 0x407,
+            Locale::DeCh => 0x807,
+            Locale::Fr => 0x40C,
+            Locale::Es => 0xC0A,
+            Locale::It => 0x410,
+            Locale::Nl => 0x413,
+            Locale::PtBr => 0x416,
+            Locale::Ja => 0x411,
+            Locale::ZhCn => 0x804,
+            Locale::ZhTw => 0x404,
+            Locale::Ko => 0x412,
+        }
+    }
+
+    /// Whether the currency symbol follows the amount (`1.234,56 €`).
+    fn symbol_after(&self) -> bool {
+        matches!(self, Locale::De | Locale::Fr | Locale::Es | Locale::It)
+    }
+
+    /// Whether a space separates the currency symbol from the amount.
+    fn symbol_spaced(&self) -> bool {
+        matches!(
+            self,
+            Locale::De
+                | Locale::DeCh
+                | Locale::Fr
+                | Locale::Es
+                | Locale::It
+                | Locale::Nl
+                | Locale::PtBr
+        )
+    }
+}
+
+/// Returns the display symbol for an ISO 4217 currency code.
+fn currency_symbol(code: &str) -> String {
+    match code {
+        "USD" => "$",
+        "EUR" => "€",
+        "GBP" => "£",
+        "JPY" | "CNY" => "¥",
+        "TWD" => "NT$",
+        "KRW" => "₩",
+        "INR" => "₹",
+        "BRL" => "R$",
+        other => other,
+    }
+    .to_string()
+}
+
+/// Returns the number of minor-unit digits shown for a currency.
+fn currency_decimals(code: &str) -> u8 {
+    match code {
+        "JPY" | "KRW" => 0,
+        _ => 2,
+    }
+}
+
+fn grouped_number(decimal_places: u8) -> String {
+    if decimal_places == 0 {
+        "#,##0".to_string()
+    } else {
+        format!("#,##0.{}", "0".repeat(decimal_places as usize))
+    }
+}
+
 /// Number format.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct NumberFormat {
@@ -255,6 +602,15 @@ impl NumberFormat {
         }
     }
 
+    /// Text format (@): the value is shown as typed and Excel does not
+    /// turn an edited value into a number
+    pub fn text() -> Self {
+        Self {
+            id: Some(49),
+            code: None,
+        }
+    }
+
     /// Custom format
     pub fn custom(code: impl Into<String>) -> Self {
         Self {
@@ -262,6 +618,96 @@ impl NumberFormat {
             code: Some(code.into()),
         }
     }
+
+    /// Number with thousands separators (`#,##0.00`) rendered for a locale.
+    pub fn thousands(decimal_places: u8, locale: Locale) -> Self {
+        Self::custom(format!(
+            "[$-{:X}]{}",
+            locale.lcid(),
+            grouped_number(decimal_places)
+        ))
+    }
+
+    /// Currency format for an ISO 4217 code (`EUR`) in a locale, e.g.
+    /// `#,##0.00 [$€-407]` for euros in German.
+    pub fn currency(currency: &str, locale: Locale) -> Self {
+        let currency = currency.trim().to_uppercase();
+        let symbol = format!("[${}-{:X}]", currency_symbol(&currency), locale.lcid());
+        let number = grouped_number(currency_decimals(&currency));
+        let space = if locale.symbol_spaced() { " " } else { "" };
+        let code = if locale.symbol_after() {
+            format!("{}{}{}", number, space, symbol)
+        } else {
+            format!("{}{}{}", symbol, space, number)
+        };
+        Self::custom(code)
+    }
+
+    /// Accounting format: aligned currency symbol, negatives in parentheses
+    /// (or with a leading minus where the symbol follows the amount) and
+    /// zero shown as a dash.
+    pub fn accounting(currency: &str, locale: Locale) -> Self {
+        let currency = currency.trim().to_uppercase();
+        let symbol = format!("[${}-{:X}]", currency_symbol(&currency), locale.lcid());
+        let decimals = currency_decimals(&currency);
+        let number = grouped_number(decimals);
+        let dash = if decimals == 0 {
+            "\"-\"".to_string()
+        } else {
+            format!("\"-\"{}", "?".repeat(decimals as usize))
+        };
+        let code = if locale.symbol_after() {
+            format!(
+                "_-* {n} {s}_-;-* {n} {s}_-;_-* {d} {s}_-;_-@_-",
+                n = number,
+                s = symbol,
+                d = dash
+            )
+        } else {
+            format!(
+                "_({s}* {n}_);_({s}* ({n});_({s}* {d}_);_(@_)",
+                n = number,
+                s = symbol,
+                d = dash
+            )
+        };
+        Self::custom(code)
+    }
+
+    /// Returns the format code of a built-in number format ID.
+    pub fn builtin_code(id: u32) -> Option<&'static str> {
+        Some(match id {
+            0 => "General",
+            1 => "0",
+            2 => "0.00",
+            3 => "#,##0",
+            4 => "#,##0.00",
+            9 => "0%",
+            10 => "0.00%",
+            11 => "0.00E+00",
+            12 => "# ?/?",
+            13 => "# ??/??",
+            14 => "mm-dd-yy",
+            15 => "d-mmm-yy",
+            16 => "d-mmm",
+            17 => "mmm-yy",
+            18 => "h:mm AM/PM",
+            19 => "h:mm:ss AM/PM",
+            20 => "h:mm",
+            21 => "h:mm:ss",
+            22 => "m/d/yy h:mm",
+            37 => "#,##0 ;(#,##0)",
+            38 => "#,##0 ;[Red](#,##0)",
+            39 => "#,##0.00;(#,##0.00)",
+            40 => "#,##0.00;[Red](#,##0.00)",
+            45 => "mm:ss",
+            46 => "[h]:mm:ss",
+            47 => "mmss.0",
+            48 => "##0.0E+0",
+            49 => "@",
+            _ => return None,
+        })
+    }
 }
 
 impl Default for NumberFormat {
@@ -270,6 +716,24 @@ impl Default for NumberFormat {
     }
 }
 
+/// Cell protection, enforced only while the sheet is protected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Protection {
+    /// The cell cannot be edited (on by default, as in Excel)
+    pub locked: bool,
+    /// The cell's formula is hidden from the formula bar
+    pub hidden: bool,
+}
+
+impl Default for Protection {
+    fn default() -> Self {
+        Self {
+            locked: true,
+            hidden: false,
+        }
+    }
+}
+
 /// Complete cell style.
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct Style {
@@ -293,6 +757,36 @@ pub struct Style {
     pub indent: Option<u32>,
     /// Shrink to fit
     pub shrink_to_fit: bool,
+    /// Show the cell value as a checkbox (Excel checkbox cell format)
+    #[serde(default)]
+    pub checkbox: bool,
+    /// Locked and hidden flags applied when the sheet is protected
+    #[serde(default)]
+    pub protection: Protection,
+}
+
+impl Style {
+    /// Returns true if the font color is the same as the cell background,
+    /// which makes the text invisible (white on white, for instance).
+    /// Patterned and gradient fills are never reported.
+    pub fn text_matches_background(&self) -> bool {
+        let background = match self.fill.pattern {
+            _ if self.fill.gradient.is_some() => None,
+            FillPattern::None => Some(0xFFFFFF),
+            FillPattern::Solid => self
+                .fill
+                .fg_color
+                .as_ref()
+                .map_or(Some(0xFFFFFF), |c| c.resolve(0xFFFFFF)),
+            _ => None,
+        };
+        let text = self
+            .font
+            .color
+            .as_ref()
+            .map_or(Some(0x000000), |c| c.resolve(0x000000));
+        background.is_some() && background == text
+    }
 }
 
 /// Registry of styles in a workbook.
@@ -331,6 +825,24 @@ impl StyleRegistry {
         id
     }
 
+    /// Returns a style ID for the given style, reusing an existing entry when
+    /// an identical style is already registered.
+    ///
+    /// Use this instead of [`add`](Self::add) when applying one style to many
+    /// cells so that they all share a single ID.
+    pub fn clone_for(&mut self, style: &Style) -> u32 {
+        let existing = self
+            .styles
+            .iter()
+            .filter(|(_, s)| *s == style)
+            .map(|(id, _)| *id)
+            .min();
+        match existing {
+            Some(id) => id,
+            None => self.add(style.clone()),
+        }
+    }
+
     /// Adds a style with a specific ID (used when loading from file).
     pub fn add_with_id(&mut self, id: u32, style: Style) {
         self.styles.insert(id, style);
@@ -401,6 +913,69 @@ impl StyleRegistry {
     pub fn add_number_format(&mut self, id: u32, code: impl Into<String>) {
         self.number_formats.insert(id, code.into());
     }
+
+    /// Keeps only the styles in `used`, merging identical ones, and drops
+    /// the fonts, fills, borders and custom number formats no remaining
+    /// style refers to.
+    ///
+    /// Kept styles are renumbered from 0 in their original order. Returns
+    /// the new ID for every kept old ID.
+    pub fn compact(&mut self, used: &std::collections::HashSet<u32>) -> HashMap<u32, u32> {
+        let mut ids: Vec<u32> = used
+            .iter()
+            .copied()
+            .filter(|id| self.styles.contains_key(id))
+            .collect();
+        ids.sort_unstable();
+
+        // Style holds floats, so identical styles are matched by their
+        // debug representation
+        let mut kept: Vec<Style> = Vec::new();
+        let mut by_repr: HashMap<String, u32> = HashMap::new();
+        let mut remap = HashMap::new();
+        for id in ids {
+            let style = &self.styles[&id];
+            let new_id = *by_repr.entry(format!("{:?}", style)).or_insert_with(|| {
+                kept.push(style.clone());
+                kept.len() as u32 - 1
+            });
+            remap.insert(id, new_id);
+        }
+
+        self.number_formats
+            .retain(|id, _| kept.iter().any(|s| s.number_format.id == Some(*id)));
+        self.fonts.retain(|f| kept.iter().any(|s| &s.font == f));
+        self.fills.retain(|f| kept.iter().any(|s| &s.fill == f));
+        self.borders.retain(|b| kept.iter().any(|s| &s.border == b));
+        self.next_id = kept.len() as u32;
+        self.styles = kept
+            .into_iter()
+            .enumerate()
+            .map(|(id, style)| (id as u32, style))
+            .collect();
+        remap
+    }
+
+    /// Resolves a cell's style ID into a complete style.
+    ///
+    /// Cells without a style, or with an ID missing from the registry, get
+    /// the default style. The number format code is filled in from the
+    /// workbook's custom formats or the built-in table when only an ID is set.
+    pub fn resolve(&self, style_id: Option<u32>) -> Style {
+        let mut style = style_id
+            .and_then(|id| self.get(id))
+            .cloned()
+            .unwrap_or_default();
+        if style.number_format.code.is_none() {
+            let id = style.number_format.id.unwrap_or(0);
+            style.number_format.id = Some(id);
+            style.number_format.code = self
+                .get_number_format(id)
+                .map(str::to_string)
+                .or_else(|| NumberFormat::builtin_code(id).map(str::to_string));
+        }
+        style
+    }
 }
 
 #[cfg(test)]
@@ -547,6 +1122,30 @@ mod tests {
         assert_eq!(registry.fonts()[0].name, Some("Arial".to_string()));
     }
 
+    #[test]
+    fn test_fill_pattern_names() {
+        for pattern in FillPattern::ALL {
+            assert_eq!(FillPattern::parse(pattern.as_str()), Some(pattern));
+        }
+        assert_eq!(FillPattern::parse("dark-grid"), Some(FillPattern::DarkGrid));
+        assert_eq!(FillPattern::parse("LIGHT_UP"), Some(FillPattern::LightUp));
+        assert_eq!(FillPattern::parse("plaid"), None);
+    }
+
+    #[test]
+    fn test_gradient_fill_stops() {
+        let gradient =
+            GradientFill::linear(90.0, vec![Color::rgb(255, 255, 255), Color::rgb(0, 0, 0)]);
+        assert_eq!(gradient.gradient_type, GradientType::Linear);
+        let positions: Vec<f64> = gradient.stops.iter().map(|s| s.position).collect();
+        assert_eq!(positions, vec![0.0, 1.0]);
+
+        let path = GradientFill::path(vec![Color::rgb(255, 0, 0); 3]);
+        assert_eq!(path.gradient_type, GradientType::Path);
+        assert_eq!(path.stops[1].position, 0.5);
+        assert_eq!(path.left, 0.5);
+    }
+
     #[test]
     fn test_style_registry_fills() {
         let mut registry = StyleRegistry::new();
@@ -557,6 +1156,7 @@ mod tests {
             pattern: FillPattern::Solid,
             fg_color: Some(Color::rgb(255, 0, 0)),
             bg_color: None,
+            gradient: None,
         };
 
         let idx = registry.add_fill(fill);
@@ -616,6 +1216,45 @@ mod tests {
         assert_eq!(fmt2.code, Some("0.00%".to_string()));
     }
 
+    #[test]
+    fn test_locale_from_tag() {
+        assert_eq!(Locale::from_tag("de-DE"), Some(Locale::De));
+        assert_eq!(Locale::from_tag("zh_tw"), Some(Locale::ZhTw));
+        assert_eq!(Locale::from_tag("xx-YY"), None);
+        for locale in Locale::ALL {
+            assert_eq!(Locale::from_tag(locale.tag()), Some(locale));
+        }
+    }
+
+    #[test]
+    fn test_number_format_currency() {
+        let eur = NumberFormat::currency("EUR", Locale::De);
+        assert_eq!(eur.code.as_deref(), Some("#,##0.00 [$€-407]"));
+
+        let usd = NumberFormat::currency("usd", Locale::EnUs);
+        assert_eq!(usd.code.as_deref(), Some("[$$-409]#,##0.00"));
+
+        let jpy = NumberFormat::currency("JPY", Locale::Ja);
+        assert_eq!(jpy.code.as_deref(), Some("[$¥-411]#,##0"));
+
+        let chf = NumberFormat::currency("CHF", Locale::DeCh);
+        assert_eq!(chf.code.as_deref(), Some("[$CHF-807] #,##0.00"));
+    }
+
+    #[test]
+    fn test_number_format_accounting_and_thousands() {
+        let usd = NumberFormat::accounting("USD", Locale::EnUs);
+        assert_eq!(
+            usd.code.as_deref(),
+            Some(r#"_([$$-409]* #,##0.00_);_([$$-409]* (#,##0.00);_([$$-409]* "-"??_);_(@_)"#)
+        );
+        let eur = NumberFormat::accounting("EUR", Locale::Fr);
+        assert!(eur.code.unwrap().starts_with("_-* #,##0.00 [$€-40C]_-;"));
+
+        let de = NumberFormat::thousands(0, Locale::De);
+        assert_eq!(de.code.as_deref(), Some("[$-407]#,##0"));
+    }
+
     #[test]
     fn test_number_format_date() {
         let fmt = NumberFormat::date();
@@ -693,4 +1332,62 @@ mod tests {
         assert_eq!(side.style, BorderStyle::None);
         assert!(side.color.is_none());
     }
+
+    #[test]
+    fn test_number_format_builtin_code() {
+        assert_eq!(NumberFormat::builtin_code(0), Some("General"));
+        assert_eq!(NumberFormat::builtin_code(14), Some("mm-dd-yy"));
+        assert_eq!(NumberFormat::builtin_code(164), None);
+    }
+
+    #[test]
+    fn test_style_registry_clone_for() {
+        let mut registry = StyleRegistry::new();
+        let mut bold = Style::default();
+        bold.font.bold = true;
+        let id = registry.add(bold.clone());
+        assert_eq!(registry.clone_for(&bold), id);
+        assert_eq!(registry.len(), 1);
+
+        let mut italic = Style::default();
+        italic.font.italic = true;
+        let other = registry.clone_for(&italic);
+        assert_ne!(other, id);
+        assert_eq!(registry.clone_for(&italic), other);
+        assert_eq!(registry.len(), 2);
+    }
+
+    #[test]
+    fn test_style_registry_resolve() {
+        let mut registry = StyleRegistry::new();
+        registry.add_number_format(164, "0.000");
+        let bold = registry.add(Style {
+            font: Font {
+                bold: true,
+                ..Default::default()
+            },
+            number_format: NumberFormat {
+                id: Some(164),
+                code: None,
+            },
+            ..Default::default()
+        });
+        let date = registry.add(Style {
+            number_format: NumberFormat::date(),
+            ..Default::default()
+        });
+
+        let resolved = registry.resolve(Some(bold));
+        assert!(resolved.font.bold);
+        assert_eq!(resolved.number_format.code.as_deref(), Some("0.000"));
+        assert_eq!(
+            registry.resolve(Some(date)).number_format.code.as_deref(),
+            Some("mm-dd-yy")
+        );
+
+        let default = registry.resolve(None);
+        assert!(!default.font.bold);
+        assert_eq!(default.number_format.code.as_deref(), Some("General"));
+        assert_eq!(registry.resolve(Some(999)), default);
+    }
 }
@@ -0,0 +1,629 @@
+//! Helpers for testing code that generates workbooks.
+//!
+//! [`assert_workbook_eq!`](crate::assert_workbook_eq) compares two workbooks
+//! with [`ComparisonOptions`] and panics with a cell-level report of what
+//! differs. For golden files, [`assert_golden`] compares against a saved
+//! `.xlsx` and [`assert_snapshot`] against a text [`snapshot`]; both write
+//! the file when it is missing, or when `XLEX_UPDATE_SNAPSHOTS` is set, so a
+//! changed report is accepted by re-running the tests with it.
+//!
+//! ```
+//! use xlex_core::compare::ComparisonOptions;
+//! use xlex_core::{assert_workbook_eq, CellRef, CellValue, Workbook};
+//!
+//! let mut expected = Workbook::new();
+//! expected.set_cell("Sheet1", CellRef::new(1, 1), CellValue::Number(0.3)).unwrap();
+//! let mut actual = Workbook::new();
+//! actual.set_cell("Sheet1", CellRef::new(1, 1), CellValue::Number(0.1 + 0.2)).unwrap();
+//!
+//! assert_workbook_eq!(actual, expected, ComparisonOptions::new().tolerance(1e-9));
+//! ```
+//!
+//! [`arbitrary_workbook`] builds a random but valid workbook from a seed,
+//! for fuzzing and round-trip tests, and [`fill_random_table`] writes a
+//! table of random typed columns for benchmarks and demos. Both draw from
+//! [`SeededRng`], so a seed is enough to reproduce a workbook exactly.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::cell::{CellError, CellRef, CellValue};
+use crate::compare::{compare_workbooks, describe, ComparisonOptions, Difference};
+use crate::error::XlexResult;
+use crate::style::{Font, NumberFormat, Style};
+use crate::workbook::Workbook;
+
+/// Environment variable that makes golden and snapshot files be rewritten.
+pub const UPDATE_ENV: &str = "XLEX_UPDATE_SNAPSHOTS";
+
+/// Asserts that two workbooks are equal under the given
+/// [`ComparisonOptions`](crate::compare::ComparisonOptions) (exact
+/// comparison when left out), panicking with the differences otherwise.
+#[macro_export]
+macro_rules! assert_workbook_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_workbook_eq!($left, $right, $crate::compare::ComparisonOptions::new())
+    };
+    ($left:expr, $right:expr, $options:expr $(,)?) => {
+        if let Err(report) = $crate::testing::check_workbooks_eq(&$left, &$right, &$options) {
+            panic!("workbooks differ\n{}", report);
+        }
+    };
+}
+
+/// Compares two workbooks, returning the report of
+/// [`format_differences`] when they differ.
+pub fn check_workbooks_eq(
+    left: &Workbook,
+    right: &Workbook,
+    options: &ComparisonOptions,
+) -> Result<(), String> {
+    let differences = compare_workbooks(left, right, options);
+    if differences.is_empty() {
+        Ok(())
+    } else {
+        Err(format_differences(&differences))
+    }
+}
+
+/// Formats differences as a count followed by one indented line each.
+pub fn format_differences(differences: &[Difference]) -> String {
+    let mut report = format!("{} difference(s):\n", differences.len());
+    for difference in differences {
+        report.push_str(&format!("  {}\n", difference));
+    }
+    report
+}
+
+/// Renders the cells of every sheet as text, one `A1 = value` line per
+/// cell in row-major order under a `[Sheet]` header. Text is quoted and
+/// formulas show their cached result, as in `C1 = =SUM(A1:B1) [3]`.
+pub fn snapshot(workbook: &Workbook) -> String {
+    let mut text = String::new();
+    for name in workbook.sheet_names() {
+        text.push_str(&format!("[{}]\n", name));
+        let Some(sheet) = workbook.get_sheet(name) else {
+            continue;
+        };
+        let mut cells: Vec<_> = sheet.cells().filter(|c| !c.value.is_empty()).collect();
+        cells.sort_by_key(|c| (c.reference.row, c.reference.col));
+        for cell in cells {
+            text.push_str(&format!(
+                "{} = {}\n",
+                cell.reference.to_a1(),
+                describe(&cell.value)
+            ));
+        }
+    }
+    text
+}
+
+fn update_requested() -> bool {
+    std::env::var_os(UPDATE_ENV).is_some_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Asserts that `workbook` matches the golden `.xlsx` at `path` under
+/// `options`, panicking with the differences otherwise. Writes the golden
+/// file instead when it is missing or `XLEX_UPDATE_SNAPSHOTS` is set.
+pub fn assert_golden(workbook: &Workbook, path: impl AsRef<Path>, options: &ComparisonOptions) {
+    let path = path.as_ref();
+    if update_requested() || !path.exists() {
+        if let Err(e) = workbook.save_as(path) {
+            panic!("cannot write golden file {}: {}", path.display(), e);
+        }
+        return;
+    }
+    let golden = match Workbook::open(path) {
+        Ok(golden) => golden,
+        Err(e) => panic!("cannot read golden file {}: {}", path.display(), e),
+    };
+    if let Err(report) = check_workbooks_eq(&golden, workbook, options) {
+        panic!(
+            "workbook differs from golden file {} (left: golden, right: actual; set {}=1 to update)\n{}",
+            path.display(),
+            UPDATE_ENV,
+            report
+        );
+    }
+}
+
+/// Asserts that the [`snapshot`] of `workbook` matches the text file at
+/// `path`, panicking with the lines removed (`-`) and added (`+`)
+/// otherwise. Writes the file instead when it is missing or
+/// `XLEX_UPDATE_SNAPSHOTS` is set.
+pub fn assert_snapshot(workbook: &Workbook, path: impl AsRef<Path>) {
+    let path = path.as_ref();
+    let actual = snapshot(workbook);
+    if update_requested() || !path.exists() {
+        if let Err(e) = std::fs::write(path, &actual) {
+            panic!("cannot write snapshot {}: {}", path.display(), e);
+        }
+        return;
+    }
+    let expected = match std::fs::read_to_string(path) {
+        Ok(expected) => expected,
+        Err(e) => panic!("cannot read snapshot {}: {}", path.display(), e),
+    };
+    if let Some(changes) = snapshot_changes(&expected, &actual) {
+        panic!(
+            "snapshot {} differs (set {}=1 to update)\n{}",
+            path.display(),
+            UPDATE_ENV,
+            changes
+        );
+    }
+}
+
+/// Lists the cell lines only in `expected` (`-`) and only in `actual`
+/// (`+`), each under its sheet header, or `None` when the snapshots match.
+fn snapshot_changes(expected: &str, actual: &str) -> Option<String> {
+    if expected.lines().eq(actual.lines()) {
+        return None;
+    }
+    let sectioned = |text: &str| -> Vec<(String, String)> {
+        let mut section = String::new();
+        let mut lines = Vec::new();
+        for line in text.lines() {
+            if line.starts_with('[') {
+                section = line.to_string();
+            }
+            lines.push((section.clone(), line.to_string()));
+        }
+        lines
+    };
+    let expected = sectioned(expected);
+    let actual = sectioned(actual);
+    let expected_set: HashSet<&(String, String)> = expected.iter().collect();
+    let actual_set: HashSet<&(String, String)> = actual.iter().collect();
+    let mut changes: Vec<(&String, String)> = expected
+        .iter()
+        .filter(|line| !actual_set.contains(line))
+        .map(|(section, line)| (section, format!("- {}", line)))
+        .collect();
+    changes.extend(
+        actual
+            .iter()
+            .filter(|line| !expected_set.contains(line))
+            .map(|(section, line)| (section, format!("+ {}", line))),
+    );
+    // Sheets in the order they first appear, removals before additions
+    let mut order: Vec<&String> = Vec::new();
+    for (section, _) in expected.iter().chain(&actual) {
+        if !order.contains(&section) {
+            order.push(section);
+        }
+    }
+    changes.sort_by_key(|(section, _)| order.iter().position(|s| s == section));
+
+    let mut report = String::new();
+    let mut current = None;
+    for (section, line) in changes {
+        if current != Some(section) {
+            report.push_str(&format!("{}\n", section));
+            current = Some(section);
+        }
+        report.push_str(&format!("  {}\n", line));
+    }
+    Some(report)
+}
+
+/// A seeded pseudo-random number generator (SplitMix64).
+///
+/// The sequence depends only on the seed, on every platform, so anything
+/// generated from it can be reproduced by passing the same seed again.
+#[derive(Debug, Clone)]
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    /// Creates a generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Returns the next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `0..bound`. `bound` must not be zero.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    /// Returns a number in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns true with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+
+    /// Returns a random element of a non-empty slice.
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+}
+
+/// A kind of value written by the random generators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    /// Integers, amounts with two decimals, and fractions
+    Number,
+    /// Words, including non-ASCII text and XML special characters
+    Text,
+    /// `TRUE` or `FALSE`
+    Boolean,
+    /// Dates and times from 2000 to 2030, formatted as dates
+    Date,
+    /// `SUM` formulas over cells of the same sheet, without cached results
+    Formula,
+    /// Error values such as `#N/A`
+    Error,
+}
+
+impl ValueKind {
+    /// Every kind, in declaration order.
+    pub const ALL: [ValueKind; 6] = [
+        Self::Number,
+        Self::Text,
+        Self::Boolean,
+        Self::Date,
+        Self::Formula,
+        Self::Error,
+    ];
+
+    /// Returns the lowercase name of the kind.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Number => "number",
+            Self::Text => "text",
+            Self::Boolean => "boolean",
+            Self::Date => "date",
+            Self::Formula => "formula",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// Words for random text, chosen to exercise escaping and encoding.
+const WORDS: &[&str] = &[
+    "alpha",
+    "beta",
+    "gamma",
+    "total",
+    "Ünïcödé",
+    "日本語",
+    "🙂",
+    "a & b",
+    "<tag>",
+    "\"quoted\"",
+    "it's",
+    "007",
+    "1e5",
+    "TRUE",
+    "=not a formula",
+    "line\nbreak",
+    "tab\tstop",
+];
+
+/// Sheet names for [`arbitrary_workbook`], including ones that need quoting.
+const SHEET_NAMES: &[&str] = &[
+    "Sheet1",
+    "Data",
+    "Q1 Summary",
+    "Übersicht",
+    "数据",
+    "O'Brien & Co",
+    "2024",
+];
+
+const ERRORS: &[CellError] = &[
+    CellError::Null,
+    CellError::DivZero,
+    CellError::Value,
+    CellError::Ref,
+    CellError::Name,
+    CellError::Num,
+    CellError::Na,
+];
+
+/// Returns a random value of `kind`. Formulas refer to cells within
+/// `rows` × `cols`.
+fn random_value(rng: &mut SeededRng, kind: ValueKind, rows: u32, cols: u32) -> CellValue {
+    match kind {
+        ValueKind::Number => CellValue::Number(match rng.below(4) {
+            0 => rng.below(2_000_001) as f64 - 1_000_000.0,
+            1 => rng.below(10_000_000) as f64 / 100.0,
+            2 => rng.next_f64(),
+            _ => *rng.pick(&[0.0, 0.1 + 0.2, 1e15, -1e-9, 123_456_789.125]),
+        }),
+        ValueKind::Text => {
+            let words: Vec<&str> = (0..=rng.below(3)).map(|_| *rng.pick(WORDS)).collect();
+            CellValue::String(words.join(" "))
+        }
+        ValueKind::Boolean => CellValue::Boolean(rng.chance(0.5)),
+        ValueKind::Date => {
+            // 2000-01-01 onwards, at whole minutes
+            let day = 36526 + rng.below(11_000);
+            let minute = rng.below(1440);
+            CellValue::DateTime(day as f64 + minute as f64 / 1440.0)
+        }
+        ValueKind::Formula => {
+            let corner = |rng: &mut SeededRng| {
+                CellRef::new(
+                    1 + rng.below(cols.max(1) as u64) as u32,
+                    1 + rng.below(rows.max(1) as u64) as u32,
+                )
+            };
+            let (a, b) = (corner(rng), corner(rng));
+            let start = CellRef::new(a.col.min(b.col), a.row.min(b.row));
+            let end = CellRef::new(a.col.max(b.col), a.row.max(b.row));
+            CellValue::Formula {
+                formula: format!("SUM({}:{})", start.to_a1(), end.to_a1()),
+                cached_result: None,
+            }
+        }
+        ValueKind::Error => CellValue::Error(*rng.pick(ERRORS)),
+    }
+}
+
+/// Adds the date style used for generated dates and returns its id. The
+/// default font is spelled out, as it is when the style is read back.
+fn date_style(workbook: &mut Workbook) -> u32 {
+    let style = Style {
+        font: Font {
+            name: Some("Calibri".to_string()),
+            size: Some(11.0),
+            ..Font::default()
+        },
+        number_format: NumberFormat::date(),
+        ..Style::default()
+    };
+    workbook.style_registry_mut().add(style)
+}
+
+/// Generates a random workbook from `seed`, with one to three sheets of at
+/// most `size` rows and `size` (up to 50) columns each.
+///
+/// Cells mix every [`ValueKind`] with gaps, and sheets get random column
+/// widths and row heights. The same seed and size always give the same
+/// workbook, which survives a save and reopen unchanged.
+pub fn arbitrary_workbook(seed: u64, size: usize) -> Workbook {
+    let mut rng = SeededRng::new(seed);
+    let size = size.max(1) as u64;
+
+    let mut names = SHEET_NAMES.to_vec();
+    let count = 1 + rng.below(3) as usize;
+    let sheets: Vec<&str> = (0..count)
+        .map(|_| names.remove(rng.below(names.len() as u64) as usize))
+        .collect();
+    let mut workbook = Workbook::with_sheets(&sheets);
+    let date_style = date_style(&mut workbook);
+
+    for name in sheets {
+        let rows = 1 + rng.below(size) as u32;
+        let cols = 1 + rng.below(size.min(50)) as u32;
+        workbook
+            .with_sheet_mut(name, |sheet| {
+                for row in 1..=rows {
+                    for col in 1..=cols {
+                        if rng.chance(0.2) {
+                            continue;
+                        }
+                        let kind = *rng.pick(&ValueKind::ALL);
+                        let cell_ref = CellRef::new(col, row);
+                        sheet.set_cell(cell_ref.clone(), random_value(&mut rng, kind, rows, cols));
+                        if kind == ValueKind::Date {
+                            sheet.set_cell_style(&cell_ref, Some(date_style));
+                        }
+                    }
+                }
+                for col in 1..=cols {
+                    if rng.chance(0.3) {
+                        sheet.set_column_width(col, (4 + rng.below(40)) as f64);
+                    }
+                }
+                for row in 1..=rows {
+                    if rng.chance(0.1) {
+                        sheet.set_row_height(row, (10 + rng.below(40)) as f64);
+                    }
+                }
+            })
+            .expect("generated sheet exists");
+    }
+    workbook
+}
+
+/// Writes a table of random values to `sheet_name`: a header row naming
+/// each column after its kind (`number_1`, `text_2`, ...) and `rows` rows
+/// below it. Columns take the kinds in `kinds` in turn.
+pub fn fill_random_table(
+    workbook: &mut Workbook,
+    sheet_name: &str,
+    rng: &mut SeededRng,
+    rows: u32,
+    cols: u32,
+    kinds: &[ValueKind],
+) -> XlexResult<()> {
+    let kinds = if kinds.is_empty() {
+        &ValueKind::ALL[..]
+    } else {
+        kinds
+    };
+    let column_kinds: Vec<ValueKind> = (0..cols as usize).map(|i| kinds[i % kinds.len()]).collect();
+    let has_dates = column_kinds.contains(&ValueKind::Date);
+    let date_style = has_dates.then(|| date_style(workbook));
+
+    workbook.with_sheet_mut(sheet_name, |sheet| {
+        sheet.apply_updates(column_kinds.iter().enumerate().map(|(i, kind)| {
+            (
+                CellRef::new(i as u32 + 1, 1),
+                CellValue::String(format!("{}_{}", kind.name(), i + 1)),
+            )
+        }));
+        for row in 2..=rows + 1 {
+            let values: Vec<(CellRef, CellValue)> = column_kinds
+                .iter()
+                .enumerate()
+                .map(|(i, &kind)| {
+                    let value = random_value(rng, kind, rows + 1, cols);
+                    (CellRef::new(i as u32 + 1, row), value)
+                })
+                .collect();
+            sheet.apply_updates(values);
+        }
+        if let Some(style) = date_style {
+            for (i, _) in column_kinds
+                .iter()
+                .enumerate()
+                .filter(|(_, &kind)| kind == ValueKind::Date)
+            {
+                for row in 2..=rows + 1 {
+                    sheet.set_cell_style(&CellRef::new(i as u32 + 1, row), Some(style));
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(total: f64) -> Workbook {
+        let mut wb = Workbook::new();
+        wb.set_cell("Sheet1", CellRef::new(1, 1), CellValue::string("Total"))
+            .unwrap();
+        wb.set_cell("Sheet1", CellRef::new(2, 1), CellValue::Number(total))
+            .unwrap();
+        wb.set_cell(
+            "Sheet1",
+            CellRef::new(3, 1),
+            CellValue::Formula {
+                formula: "B1*2".to_string(),
+                cached_result: Some(Box::new(CellValue::Number(total * 2.0))),
+            },
+        )
+        .unwrap();
+        wb
+    }
+
+    #[test]
+    fn test_check_workbooks_eq() {
+        assert!(check_workbooks_eq(&report(1.0), &report(1.0), &ComparisonOptions::new()).is_ok());
+        let err =
+            check_workbooks_eq(&report(1.0), &report(2.0), &ComparisonOptions::new()).unwrap_err();
+        assert_eq!(
+            err,
+            "2 difference(s):\n  Sheet1!B1: 1 ≠ 2\n  Sheet1!C1: =B1*2 [2] ≠ =B1*2 [4]\n"
+        );
+        assert_workbook_eq!(
+            report(1.0),
+            report(1.0 + 1e-12),
+            ComparisonOptions::new().tolerance(1e-9)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Sheet1!B1: 1 ≠ 2")]
+    fn test_assert_workbook_eq_panics() {
+        assert_workbook_eq!(report(1.0), report(2.0));
+    }
+
+    #[test]
+    fn test_snapshot_and_changes() {
+        let text = snapshot(&report(1.0));
+        assert_eq!(text, "[Sheet1]\nA1 = \"Total\"\nB1 = 1\nC1 = =B1*2 [2]\n");
+        assert_eq!(snapshot_changes(&text, &text), None);
+        assert_eq!(
+            snapshot_changes(&text, &snapshot(&report(2.0))).unwrap(),
+            "[Sheet1]\n  - B1 = 1\n  - C1 = =B1*2 [2]\n  + B1 = 2\n  + C1 = =B1*2 [4]\n"
+        );
+    }
+
+    #[test]
+    fn test_golden_and_snapshot_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let golden = dir.path().join("report.xlsx");
+        let text = dir.path().join("report.txt");
+
+        // First run writes the files, later runs compare against them
+        assert_golden(&report(1.0), &golden, &ComparisonOptions::new());
+        assert_snapshot(&report(1.0), &text);
+        assert!(golden.exists() && text.exists());
+        assert_golden(&report(1.0), &golden, &ComparisonOptions::new());
+        assert_snapshot(&report(1.0), &text);
+
+        let golden_mismatch = std::panic::catch_unwind(|| {
+            assert_golden(&report(2.0), &golden, &ComparisonOptions::new())
+        });
+        assert!(golden_mismatch.is_err());
+        let snapshot_mismatch = std::panic::catch_unwind(|| assert_snapshot(&report(2.0), &text));
+        assert!(snapshot_mismatch.is_err());
+    }
+
+    #[test]
+    fn test_seeded_rng_is_deterministic() {
+        let mut a = SeededRng::new(42);
+        let mut b = SeededRng::new(42);
+        let first: Vec<u64> = (0..5).map(|_| a.next_u64()).collect();
+        assert_eq!(first, (0..5).map(|_| b.next_u64()).collect::<Vec<_>>());
+        assert_ne!(SeededRng::new(43).next_u64(), first[0]);
+        for _ in 0..1000 {
+            assert!(a.below(7) < 7);
+            assert!((0.0..1.0).contains(&a.next_f64()));
+        }
+    }
+
+    #[test]
+    fn test_arbitrary_workbook_round_trips() {
+        let dir = tempfile::TempDir::new().unwrap();
+        for seed in 0..8 {
+            let workbook = arbitrary_workbook(seed, 20);
+            assert_eq!(snapshot(&workbook), snapshot(&arbitrary_workbook(seed, 20)));
+
+            let path = dir.path().join(format!("random-{}.xlsx", seed));
+            workbook.save_as(&path).unwrap();
+            let reopened = Workbook::open(&path).unwrap();
+            assert_workbook_eq!(workbook, reopened);
+        }
+        assert_ne!(
+            snapshot(&arbitrary_workbook(1, 20)),
+            snapshot(&arbitrary_workbook(2, 20))
+        );
+    }
+
+    #[test]
+    fn test_fill_random_table() {
+        let mut workbook = Workbook::new();
+        let mut rng = SeededRng::new(7);
+        fill_random_table(
+            &mut workbook,
+            "Sheet1",
+            &mut rng,
+            50,
+            4,
+            &[ValueKind::Number, ValueKind::Date],
+        )
+        .unwrap();
+        let sheet = workbook.get_sheet("Sheet1").unwrap();
+        assert_eq!(sheet.dimensions(), (4, 51));
+        assert_eq!(
+            sheet.get_value(&CellRef::new(4, 1)),
+            CellValue::string("date_4")
+        );
+        assert!(matches!(
+            sheet.get_value(&CellRef::new(2, 51)),
+            CellValue::DateTime(_)
+        ));
+        assert!(fill_random_table(&mut workbook, "Missing", &mut rng, 1, 1, &[]).is_err());
+    }
+}
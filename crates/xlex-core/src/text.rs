@@ -0,0 +1,144 @@
+//! Text cleanup for cell values.
+//!
+//! [`clean`] applies the steps of a [`CleanOptions`] in a fixed order:
+//! non-printable characters are stripped, runs of spaces collapsed, the ends
+//! trimmed, and the case changed last.
+//!
+//! ```
+//! use xlex_core::text::{clean, CleanOptions, TextCase};
+//!
+//! let options = CleanOptions {
+//!     trim: true,
+//!     collapse_spaces: true,
+//!     strip_nonprintable: true,
+//!     case: Some(TextCase::Title),
+//! };
+//! assert_eq!(clean("  ada\u{200b}   LOVELACE\t", &options), "Ada Lovelace");
+//! ```
+
+/// A case to convert text to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextCase {
+    Lower,
+    Upper,
+    /// First letter of each word upper case, the rest lower, as `PROPER`
+    Title,
+}
+
+/// Which cleanup steps to apply.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CleanOptions {
+    /// Remove whitespace at both ends
+    pub trim: bool,
+    /// Replace runs of spaces, tabs and non-breaking spaces with one space;
+    /// line breaks are kept
+    pub collapse_spaces: bool,
+    /// Remove control characters other than line breaks and tabs, and
+    /// invisible format characters such as zero-width spaces
+    pub strip_nonprintable: bool,
+    pub case: Option<TextCase>,
+}
+
+impl CleanOptions {
+    /// Returns true if no step is enabled.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn is_nonprintable(c: char) -> bool {
+    (c.is_control() && !matches!(c, '\n' | '\r' | '\t'))
+        || matches!(
+            c,
+            '\u{00ad}' | '\u{200b}'..='\u{200f}' | '\u{2060}'..='\u{2064}' | '\u{feff}'
+        )
+}
+
+fn title_case(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut at_word_start = true;
+    for c in text.chars() {
+        if at_word_start {
+            out.extend(c.to_uppercase());
+        } else {
+            out.extend(c.to_lowercase());
+        }
+        at_word_start = !c.is_alphanumeric();
+    }
+    out
+}
+
+/// Cleans `text` with the enabled steps.
+pub fn clean(text: &str, options: &CleanOptions) -> String {
+    let mut text = if options.strip_nonprintable {
+        text.chars().filter(|&c| !is_nonprintable(c)).collect()
+    } else {
+        text.to_string()
+    };
+    if options.collapse_spaces {
+        let mut collapsed = String::with_capacity(text.len());
+        let mut in_space = false;
+        for c in text.chars() {
+            let space = c.is_whitespace() && !matches!(c, '\n' | '\r');
+            if !(space && in_space) {
+                collapsed.push(if space { ' ' } else { c });
+            }
+            in_space = space;
+        }
+        text = collapsed;
+    }
+    if options.trim {
+        text = text.trim().to_string();
+    }
+    match options.case {
+        Some(TextCase::Lower) => text.to_lowercase(),
+        Some(TextCase::Upper) => text.to_uppercase(),
+        Some(TextCase::Title) => title_case(&text),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_steps() {
+        let text = " \u{feff}Hello,\u{7}  \u{a0}wORLD \n  o'brien-smith ";
+        let only = |options: CleanOptions| clean(text, &options);
+        assert_eq!(
+            only(CleanOptions {
+                strip_nonprintable: true,
+                ..Default::default()
+            }),
+            " Hello,  \u{a0}wORLD \n  o'brien-smith "
+        );
+        assert_eq!(
+            only(CleanOptions {
+                collapse_spaces: true,
+                ..Default::default()
+            }),
+            " \u{feff}Hello,\u{7} wORLD \n o'brien-smith "
+        );
+        assert_eq!(
+            only(CleanOptions {
+                trim: true,
+                strip_nonprintable: true,
+                collapse_spaces: true,
+                case: Some(TextCase::Title),
+            }),
+            "Hello, World \n O'Brien-Smith"
+        );
+        assert_eq!(
+            clean(
+                "straße",
+                &CleanOptions {
+                    case: Some(TextCase::Upper),
+                    ..Default::default()
+                }
+            ),
+            "STRASSE"
+        );
+        assert!(CleanOptions::default().is_empty());
+    }
+}
@@ -0,0 +1,182 @@
+//! Conversions between the units used for column widths and row heights.
+//!
+//! Column widths are stored in width units: the number of characters of the
+//! default font's widest digit that fit, plus 5 pixels of cell padding. Row
+//! heights are stored in points. The conversions assume the default 11pt
+//! Calibri font (7-pixel digits) at 96 DPI, as Excel does on screen.
+//!
+//! ```
+//! use xlex_core::units::{self, Length};
+//!
+//! assert_eq!(units::column_width_to_pixels(9.140625), 64.0);
+//! assert_eq!(units::column_width_to_characters(9.140625), 8.43);
+//! assert_eq!("20pt".parse::<Length>().unwrap().to_row_height().unwrap(), 20.0);
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{XlexError, XlexResult};
+
+/// Width, in pixels, of the widest digit of the default font.
+pub const MAX_DIGIT_WIDTH: f64 = 7.0;
+
+/// Pixels per point at 96 DPI.
+const PIXELS_PER_POINT: f64 = 96.0 / 72.0;
+
+/// Converts points to pixels.
+pub fn points_to_pixels(points: f64) -> f64 {
+    points * PIXELS_PER_POINT
+}
+
+/// Converts pixels to points.
+pub fn pixels_to_points(pixels: f64) -> f64 {
+    pixels / PIXELS_PER_POINT
+}
+
+/// Converts a column width to the whole pixels it is drawn with.
+pub fn column_width_to_pixels(width: f64) -> f64 {
+    ((256.0 * width + (128.0 / MAX_DIGIT_WIDTH).trunc()) / 256.0 * MAX_DIGIT_WIDTH).trunc()
+}
+
+/// Converts pixels to the column width drawn with that many pixels.
+pub fn pixels_to_column_width(pixels: f64) -> f64 {
+    (pixels / MAX_DIGIT_WIDTH * 256.0).trunc() / 256.0
+}
+
+/// Converts a number of characters to the column width that fits them,
+/// as Excel does for the width typed into its "Column Width" dialog.
+pub fn characters_to_column_width(characters: f64) -> f64 {
+    ((characters * MAX_DIGIT_WIDTH + 5.0) / MAX_DIGIT_WIDTH * 256.0).trunc() / 256.0
+}
+
+/// Converts a column width to the characters shown in Excel's
+/// "Column Width" dialog, rounded to hundredths.
+pub fn column_width_to_characters(width: f64) -> f64 {
+    let pixels = column_width_to_pixels(width);
+    ((pixels - 5.0) / MAX_DIGIT_WIDTH * 100.0 + 0.5)
+        .trunc()
+        .max(0.0)
+        / 100.0
+}
+
+/// A width or height with an optional unit, as typed on the command line
+/// (`120px`, `30pt`, `12ch`, or a bare number).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Length {
+    /// A bare number in the native unit: width units for columns, points
+    /// for rows
+    Native(f64),
+    /// Pixels (`px`)
+    Pixels(f64),
+    /// Points (`pt`)
+    Points(f64),
+    /// Characters of the default font (`ch`), for column widths only
+    Characters(f64),
+}
+
+impl Length {
+    /// Returns the length as a column width.
+    pub fn to_column_width(self) -> f64 {
+        match self {
+            Self::Native(width) => width,
+            Self::Pixels(pixels) => pixels_to_column_width(pixels),
+            Self::Points(points) => pixels_to_column_width(points_to_pixels(points)),
+            Self::Characters(characters) => characters_to_column_width(characters),
+        }
+    }
+
+    /// Returns the length as a row height in points.
+    ///
+    /// Fails for characters, which have no height.
+    pub fn to_row_height(self) -> XlexResult<f64> {
+        match self {
+            Self::Native(points) | Self::Points(points) => Ok(points),
+            Self::Pixels(pixels) => Ok(pixels_to_points(pixels)),
+            Self::Characters(_) => Err(XlexError::InvalidStyle {
+                message: format!("row height cannot be given in characters: {}", self),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Native(value) => write!(f, "{}", value),
+            Self::Pixels(value) => write!(f, "{}px", value),
+            Self::Points(value) => write!(f, "{}pt", value),
+            Self::Characters(value) => write!(f, "{}ch", value),
+        }
+    }
+}
+
+impl FromStr for Length {
+    type Err = XlexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text = s.trim().to_ascii_lowercase();
+        let units: [(&str, fn(f64) -> Length); 3] = [
+            ("px", Length::Pixels),
+            ("pt", Length::Points),
+            ("ch", Length::Characters),
+        ];
+        let (number, unit) = units
+            .iter()
+            .find_map(|&(suffix, unit)| Some((text.strip_suffix(suffix)?, unit)))
+            .unwrap_or((&text, Length::Native));
+        match number.trim().parse::<f64>() {
+            Ok(value) if value.is_finite() && value >= 0.0 => Ok(unit(value)),
+            _ => Err(XlexError::InvalidStyle {
+                message: format!(
+                    "invalid length '{}' (expected a number, optionally followed by px, pt or ch)",
+                    s
+                ),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_width_conversions() {
+        // Excel's default column: 8.43 characters, 64 pixels.
+        assert_eq!(characters_to_column_width(8.43), 9.140625);
+        assert_eq!(column_width_to_pixels(9.140625), 64.0);
+        assert_eq!(pixels_to_column_width(64.0), 9.140625);
+        assert_eq!(column_width_to_characters(9.140625), 8.43);
+        assert_eq!(column_width_to_pixels(pixels_to_column_width(120.0)), 120.0);
+        assert_eq!(column_width_to_characters(0.0), 0.0);
+    }
+
+    #[test]
+    fn test_point_pixel_conversions() {
+        assert_eq!(points_to_pixels(15.0), 20.0);
+        assert_eq!(pixels_to_points(20.0), 15.0);
+    }
+
+    #[test]
+    fn test_length_parse_and_convert() {
+        assert_eq!("12".parse::<Length>().unwrap(), Length::Native(12.0));
+        assert_eq!("120px".parse::<Length>().unwrap(), Length::Pixels(120.0));
+        assert_eq!(" 30 PT ".parse::<Length>().unwrap(), Length::Points(30.0));
+        assert_eq!(
+            "8.43ch".parse::<Length>().unwrap(),
+            Length::Characters(8.43)
+        );
+        assert!("wide".parse::<Length>().is_err());
+        assert!("-3px".parse::<Length>().is_err());
+        assert!("px".parse::<Length>().is_err());
+
+        assert_eq!(Length::Native(12.0).to_column_width(), 12.0);
+        assert_eq!(Length::Pixels(64.0).to_column_width(), 9.140625);
+        assert_eq!(Length::Points(48.0).to_column_width(), 9.140625);
+        assert_eq!(Length::Characters(8.43).to_column_width(), 9.140625);
+        assert_eq!(Length::Points(30.0).to_row_height().unwrap(), 30.0);
+        assert_eq!(Length::Pixels(40.0).to_row_height().unwrap(), 30.0);
+        assert!(Length::Characters(2.0).to_row_height().is_err());
+    }
+}
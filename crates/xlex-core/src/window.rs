@@ -0,0 +1,236 @@
+//! Running and window calculations down a column.
+//!
+//! [`window`] computes a [`WindowFunc`] over a column's values in row
+//! order, giving one result per row: running sums, moving averages, ranks,
+//! and values from earlier or later rows. Numbers are read through dates
+//! and cached formula results; text and blanks count as nothing.
+//!
+//! ```
+//! use xlex_core::window::{window, WindowFunc};
+//! use xlex_core::CellValue;
+//!
+//! let values = [3.0, 1.0, 2.0].map(CellValue::Number);
+//! assert_eq!(
+//!     window(WindowFunc::CumSum, &values),
+//!     [3.0, 4.0, 6.0].map(CellValue::Number).to_vec()
+//! );
+//! assert_eq!(
+//!     window(WindowFunc::Rank { descending: true }, &values),
+//!     [1.0, 3.0, 2.0].map(CellValue::Number).to_vec()
+//! );
+//! ```
+
+use crate::cell::CellValue;
+use crate::decimal::{cell_decimal, Decimal};
+
+/// A calculation over a column in row order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowFunc {
+    /// Sum of the numbers so far
+    CumSum,
+    /// Mean of the numbers in this row and the `n - 1` rows before it
+    MovingAvg(usize),
+    /// Position of the number when sorted, with ties sharing the lowest
+    /// rank, as Excel's `RANK.EQ`; blank for non-numbers
+    Rank { descending: bool },
+    /// Value `n` rows earlier, blank before the first
+    Lag(usize),
+    /// Value `n` rows later, blank past the last
+    Lead(usize),
+}
+
+fn number(value: &CellValue) -> Option<f64> {
+    match value {
+        CellValue::Formula {
+            cached_result: Some(result),
+            ..
+        } => number(result),
+        value => value.as_number(),
+    }
+}
+
+/// Computes `func` for each of `values`, in order.
+pub fn window(func: WindowFunc, values: &[CellValue]) -> Vec<CellValue> {
+    let numbers: Vec<Option<f64>> = values.iter().map(number).collect();
+    match func {
+        WindowFunc::CumSum => {
+            let mut sum = 0.0;
+            numbers
+                .iter()
+                .map(|n| {
+                    sum += n.unwrap_or(0.0);
+                    CellValue::Number(sum)
+                })
+                .collect()
+        }
+        WindowFunc::MovingAvg(size) => {
+            let size = size.max(1);
+            (0..numbers.len())
+                .map(|i| {
+                    let frame = &numbers[(i + 1).saturating_sub(size)..=i];
+                    let present: Vec<f64> = frame.iter().flatten().copied().collect();
+                    if present.is_empty() {
+                        CellValue::Empty
+                    } else {
+                        CellValue::Number(present.iter().sum::<f64>() / present.len() as f64)
+                    }
+                })
+                .collect()
+        }
+        WindowFunc::Rank { descending } => {
+            let mut sorted: Vec<f64> = numbers.iter().flatten().copied().collect();
+            sorted.sort_by(|a, b| {
+                let order = a.total_cmp(b);
+                if descending {
+                    order.reverse()
+                } else {
+                    order
+                }
+            });
+            numbers
+                .iter()
+                .map(|n| match n {
+                    Some(n) => {
+                        let before =
+                            sorted.partition_point(|s| if descending { s > n } else { s < n });
+                        CellValue::Number(before as f64 + 1.0)
+                    }
+                    None => CellValue::Empty,
+                })
+                .collect()
+        }
+        WindowFunc::Lag(offset) => (0..values.len())
+            .map(|i| {
+                i.checked_sub(offset)
+                    .map_or(CellValue::Empty, |j| values[j].clone())
+            })
+            .collect(),
+        WindowFunc::Lead(offset) => (0..values.len())
+            .map(|i| values.get(i + offset).cloned().unwrap_or(CellValue::Empty))
+            .collect(),
+    }
+}
+
+/// [`window`] with running sums and moving averages as exact decimals,
+/// giving [`CellValue::Decimal`] results; other functions are unchanged. A
+/// sum that outgrows a decimal falls back to [`window`].
+pub fn decimal_window(func: WindowFunc, values: &[CellValue]) -> Vec<CellValue> {
+    let numbers: Vec<Option<Decimal>> = values.iter().map(cell_decimal).collect();
+    let exact = match func {
+        WindowFunc::CumSum => {
+            let mut sum = Decimal::ZERO;
+            numbers
+                .iter()
+                .map(|n| {
+                    sum = sum.checked_add(n.unwrap_or_default())?;
+                    Some(CellValue::Decimal(sum))
+                })
+                .collect()
+        }
+        WindowFunc::MovingAvg(size) => {
+            let size = size.max(1);
+            (0..numbers.len())
+                .map(|i| {
+                    let frame = &numbers[(i + 1).saturating_sub(size)..=i];
+                    let present: Vec<Decimal> = frame.iter().flatten().copied().collect();
+                    if present.is_empty() {
+                        return Some(CellValue::Empty);
+                    }
+                    let sum = present
+                        .iter()
+                        .try_fold(Decimal::ZERO, |sum, &n| sum.checked_add(n))?;
+                    sum.checked_div_int(present.len() as i128)
+                        .map(CellValue::Decimal)
+                })
+                .collect()
+        }
+        _ => None,
+    };
+    exact.unwrap_or_else(|| window(func, values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column() -> Vec<CellValue> {
+        vec![
+            CellValue::Number(10.0),
+            CellValue::string("n/a"),
+            CellValue::Number(30.0),
+            CellValue::Number(10.0),
+            CellValue::Empty,
+        ]
+    }
+
+    fn numbers(values: &[Option<f64>]) -> Vec<CellValue> {
+        values
+            .iter()
+            .map(|n| n.map_or(CellValue::Empty, CellValue::Number))
+            .collect()
+    }
+
+    #[test]
+    fn test_window_running() {
+        assert_eq!(
+            window(WindowFunc::CumSum, &column()),
+            numbers(&[Some(10.0), Some(10.0), Some(40.0), Some(50.0), Some(50.0)])
+        );
+        assert_eq!(
+            window(WindowFunc::MovingAvg(2), &column()),
+            numbers(&[Some(10.0), Some(10.0), Some(30.0), Some(20.0), Some(10.0)])
+        );
+        assert_eq!(
+            window(WindowFunc::MovingAvg(1), &[CellValue::Empty]),
+            vec![CellValue::Empty]
+        );
+    }
+
+    #[test]
+    fn test_decimal_window() {
+        let cents = [0.1, 0.2, 0.3].map(CellValue::Number);
+        let text = |values: Vec<CellValue>| -> Vec<String> {
+            values.iter().map(CellValue::to_display_string).collect()
+        };
+        assert_eq!(
+            text(window(WindowFunc::CumSum, &cents))[1],
+            "0.30000000000000004"
+        );
+        assert_eq!(
+            text(decimal_window(WindowFunc::CumSum, &cents)),
+            ["0.1", "0.3", "0.6"]
+        );
+        assert_eq!(
+            text(decimal_window(WindowFunc::MovingAvg(2), &cents)),
+            ["0.1", "0.15", "0.25"]
+        );
+        assert_eq!(
+            decimal_window(WindowFunc::Lag(1), &cents),
+            window(WindowFunc::Lag(1), &cents)
+        );
+        let huge = [1e38, 1e38].map(CellValue::Number);
+        assert_eq!(
+            decimal_window(WindowFunc::CumSum, &huge),
+            window(WindowFunc::CumSum, &huge)
+        );
+    }
+
+    #[test]
+    fn test_window_rank_and_offsets() {
+        assert_eq!(
+            window(WindowFunc::Rank { descending: false }, &column()),
+            numbers(&[Some(1.0), None, Some(3.0), Some(1.0), None])
+        );
+        assert_eq!(
+            window(WindowFunc::Rank { descending: true }, &column()),
+            numbers(&[Some(2.0), None, Some(1.0), Some(2.0), None])
+        );
+        let values = column();
+        let lag = window(WindowFunc::Lag(2), &values);
+        assert_eq!(lag[..2], [CellValue::Empty, CellValue::Empty]);
+        assert_eq!(lag[2..], values[..3]);
+        let lead = window(WindowFunc::Lead(1), &values);
+        assert_eq!(lead[..4], values[1..]);
+        assert_eq!(lead[4], CellValue::Empty);
+    }
+}
@@ -1,6 +1,6 @@
 //! Workbook type and operations.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::{Read, Seek};
 use std::path::{Path, PathBuf};
 
@@ -8,11 +8,14 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use zip::ZipArchive;
 
-use crate::cell::{CellRef, CellValue};
+use crate::cell::{Cell, CellRef, CellValue};
 use crate::error::{XlexError, XlexResult};
-use crate::parser::WorkbookParser;
+use crate::lazy::LazyWorkbook;
+use crate::parser::{ParseMode, Violation, WorkbookParser};
+use crate::pool::ReaderPool;
+use crate::range::Range;
 use crate::sheet::{Sheet, SheetInfo, SheetVisibility};
-use crate::style::StyleRegistry;
+use crate::style::{Protection, Style, StyleRegistry};
 
 /// Document properties.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -27,6 +30,44 @@ pub struct DocumentProperties {
     pub modified: Option<DateTime<Utc>>,
     pub category: Option<String>,
     pub content_status: Option<String>,
+    /// Custom properties (`docProps/custom.xml`), in file order
+    #[serde(default)]
+    pub custom: Vec<CustomProperty>,
+}
+
+impl DocumentProperties {
+    /// Returns the value of a custom property.
+    pub fn custom_property(&self, name: &str) -> Option<&str> {
+        self.custom
+            .iter()
+            .find(|p| p.name == name)
+            .map(|p| p.value.as_str())
+    }
+
+    /// Sets a text custom property, replacing any property with that name.
+    pub fn set_custom_property(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        let property = CustomProperty {
+            name: name.into(),
+            value: value.into(),
+            value_type: "lpwstr".to_string(),
+        };
+        match self.custom.iter_mut().find(|p| p.name == property.name) {
+            Some(existing) => *existing = property,
+            None => self.custom.push(property),
+        }
+    }
+}
+
+/// A custom document property.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomProperty {
+    /// Property name
+    pub name: String,
+    /// Value as written in the file
+    pub value: String,
+    /// Variant type the value is stored as (`lpwstr`, `i4`, `r8`, `bool`,
+    /// `filetime`, ...)
+    pub value_type: String,
 }
 
 /// Workbook statistics.
@@ -44,6 +85,120 @@ pub struct WorkbookStats {
     pub string_count: usize,
     /// File size in bytes
     pub file_size: u64,
+    /// Approximate memory held by the parsed workbook, in bytes
+    pub estimated_memory: u64,
+    /// Per-sheet breakdown
+    pub sheets: Vec<SheetStats>,
+    /// Package parts with their sizes (empty if the workbook has no file)
+    pub parts: Vec<PartSize>,
+}
+
+/// Content nothing in a workbook refers to, found by
+/// [`Workbook::find_unused`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UnusedContent {
+    /// Defined names no formula, other name, dropdown or sparkline uses
+    pub defined_names: Vec<DefinedName>,
+    /// Style IDs no cell uses (the default style 0 is never listed)
+    pub styles: Vec<u32>,
+    /// Hidden sheets no other sheet or used defined name refers to
+    pub hidden_sheets: Vec<String>,
+}
+
+impl UnusedContent {
+    /// Returns true if nothing unused was found.
+    pub fn is_empty(&self) -> bool {
+        self.defined_names.is_empty() && self.styles.is_empty() && self.hidden_sheets.is_empty()
+    }
+}
+
+/// Content a reader of the workbook can't see, found by
+/// [`Workbook::find_hidden`]. Rows, columns and cells are listed with the
+/// name of their sheet.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HiddenContent {
+    /// Hidden sheets (can be unhidden from Excel)
+    pub hidden_sheets: Vec<String>,
+    /// Very hidden sheets (only visible from VBA or by editing the file)
+    pub very_hidden_sheets: Vec<String>,
+    /// Hidden rows
+    pub hidden_rows: Vec<(String, u32)>,
+    /// Hidden columns
+    pub hidden_columns: Vec<(String, u32)>,
+    /// Columns that aren't hidden but have a width of zero
+    pub zero_width_columns: Vec<(String, u32)>,
+    /// Non-empty cells whose font color matches their background
+    pub invisible_text: Vec<(String, CellRef)>,
+    /// Non-empty cells covered by a merged range they don't anchor
+    pub covered_cells: Vec<(String, CellRef)>,
+}
+
+impl HiddenContent {
+    /// Returns true if nothing hidden was found.
+    pub fn is_empty(&self) -> bool {
+        self.hidden_sheets.is_empty()
+            && self.very_hidden_sheets.is_empty()
+            && self.hidden_rows.is_empty()
+            && self.hidden_columns.is_empty()
+            && self.zero_width_columns.is_empty()
+            && self.invisible_text.is_empty()
+            && self.covered_cells.is_empty()
+    }
+}
+
+/// What [`Workbook::optimize`] removed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OptimizeReport {
+    /// Styles before optimizing
+    pub styles_before: usize,
+    /// Styles after dropping unused and merging identical ones
+    pub styles_after: usize,
+    /// Custom number formats before optimizing
+    pub number_formats_before: usize,
+    /// Custom number formats still used by a style
+    pub number_formats_after: usize,
+    /// Shared strings before optimizing
+    pub shared_strings_before: usize,
+    /// Shared strings still used by a cell
+    pub shared_strings_after: usize,
+    /// Blank cells removed past the end of the content
+    pub blank_cells_removed: usize,
+    /// Row heights removed past the last row with content
+    pub row_heights_removed: usize,
+    /// Column widths removed past the last column with content
+    pub column_widths_removed: usize,
+}
+
+/// Statistics for a single sheet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SheetStats {
+    /// Sheet name
+    pub name: String,
+    /// Number of stored cells
+    pub cell_count: usize,
+    /// Number of formula cells
+    pub formula_count: usize,
+    /// Number of distinct string values
+    pub unique_strings: usize,
+    /// Number of distinct cell styles used
+    pub style_count: usize,
+    /// Row with the most stored cells (0 for an empty sheet)
+    pub largest_row: u32,
+    /// Number of cells in the largest row
+    pub largest_row_cells: usize,
+    /// Approximate memory held by the sheet's cells, in bytes
+    pub estimated_memory: u64,
+}
+
+/// Compressed and uncompressed size of a part in the xlsx package.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartSize {
+    /// Part path (e.g., "xl/worksheets/sheet1.xml")
+    pub name: String,
+    /// Size inside the zip file
+    pub compressed_size: u64,
+    /// Size once extracted, which is what XML-based tools have to parse
+    pub uncompressed_size: u64,
 }
 
 /// A named range definition.
@@ -62,6 +217,13 @@ pub struct DefinedName {
 }
 
 impl DefinedName {
+    /// Built-in name for rows/columns repeated on every printed page.
+    pub const PRINT_TITLES: &'static str = "_xlnm.Print_Titles";
+    /// Built-in name for the printed area of a sheet.
+    pub const PRINT_AREA: &'static str = "_xlnm.Print_Area";
+    /// Built-in hidden name for the autofiltered range of a sheet.
+    pub const FILTER_DATABASE: &'static str = "_xlnm._FilterDatabase";
+
     /// Creates a new defined name with global scope.
     pub fn new(name: impl Into<String>, reference: impl Into<String>) -> Self {
         Self {
@@ -89,6 +251,123 @@ impl DefinedName {
     }
 }
 
+/// A link to another workbook, stored as an `externalLink` part.
+///
+/// The part keeps the linked sheet names and the cached values Excel shows
+/// until the link is refreshed; xlex preserves it verbatim.
+#[derive(Debug, Clone)]
+pub struct ExternalLink {
+    /// Path or URL of the linked workbook (empty for DDE/OLE links)
+    pub target: String,
+    /// Raw externalLink part
+    part: Vec<u8>,
+    /// Relationship ID the part uses to refer to `target`
+    target_rel_id: String,
+}
+
+impl ExternalLink {
+    pub(crate) fn from_part(target: String, part: Vec<u8>, target_rel_id: String) -> Self {
+        Self {
+            target,
+            part,
+            target_rel_id,
+        }
+    }
+
+    /// Returns the raw externalLink part.
+    pub(crate) fn part(&self) -> &[u8] {
+        &self.part
+    }
+
+    /// Returns the relationship ID of the target, if the link has one.
+    pub(crate) fn target_rel_id(&self) -> Option<&str> {
+        (!self.target_rel_id.is_empty()).then_some(self.target_rel_id.as_str())
+    }
+
+    /// Returns the names of the linked workbook's sheets.
+    pub fn sheet_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut reader = quick_xml::Reader::from_reader(self.part.as_slice());
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(quick_xml::events::Event::Empty(e) | quick_xml::events::Event::Start(e))
+                    if e.local_name().as_ref() == b"sheetName" =>
+                {
+                    if let Some(attr) = e.attributes().flatten().find(|a| a.key.as_ref() == b"val")
+                    {
+                        names.push(String::from_utf8_lossy(&attr.value).to_string());
+                    }
+                }
+                Ok(quick_xml::events::Event::Eof) | Err(_) => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+        names
+    }
+}
+
+/// Computes the statistics of one sheet.
+fn sheet_stats(sheet: &Sheet) -> SheetStats {
+    // A stored cell costs its map entry plus the text it owns
+    let entry_size = (std::mem::size_of::<(u32, u32)>() + std::mem::size_of::<Cell>() + 1) as u64;
+    let text_len = |value: &CellValue| match value {
+        CellValue::String(s) => s.len() as u64,
+        _ => 0,
+    };
+
+    let mut formula_count = 0;
+    let mut strings: std::collections::HashSet<&str> = std::collections::HashSet::new();
+    let mut styles: std::collections::HashSet<u32> = std::collections::HashSet::new();
+    let mut row_sizes: HashMap<u32, usize> = HashMap::new();
+    let mut estimated_memory = 0;
+
+    for cell in sheet.cells() {
+        estimated_memory += entry_size
+            + cell.comment.as_ref().map_or(0, |c| c.len() as u64)
+            + cell.hyperlink.as_ref().map_or(0, |h| h.len() as u64);
+        match &cell.value {
+            CellValue::String(s) => {
+                strings.insert(s);
+                estimated_memory += s.len() as u64;
+            }
+            CellValue::Formula {
+                formula,
+                cached_result,
+            } => {
+                formula_count += 1;
+                estimated_memory += formula.len() as u64
+                    + cached_result
+                        .as_deref()
+                        .map_or(0, |c| std::mem::size_of::<CellValue>() as u64 + text_len(c));
+            }
+            _ => {}
+        }
+        if let Some(style_id) = cell.style_id {
+            styles.insert(style_id);
+        }
+        *row_sizes.entry(cell.reference.row).or_default() += 1;
+    }
+
+    // Ties go to the first row
+    let (largest_row, largest_row_cells) = row_sizes
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+        .unwrap_or_default();
+
+    SheetStats {
+        name: sheet.name().to_string(),
+        cell_count: sheet.cell_count(),
+        formula_count,
+        unique_strings: strings.len(),
+        style_count: styles.len(),
+        largest_row,
+        largest_row_cells,
+        estimated_memory,
+    }
+}
+
 /// An Excel workbook.
 #[derive(Debug)]
 pub struct Workbook {
@@ -108,6 +387,12 @@ pub struct Workbook {
     shared_strings_map: HashMap<String, usize>,
     /// Defined names (named ranges)
     defined_names: Vec<DefinedName>,
+    /// Links to other workbooks, numbered `[1]`, `[2]`, ... in formulas
+    external_links: Vec<ExternalLink>,
+    /// Spec violations recovered from while parsing
+    parse_violations: Vec<Violation>,
+    /// Formula cells in calculation order, as read from `xl/calcChain.xml`
+    calc_chain: Vec<(String, CellRef)>,
     /// Active sheet index
     active_sheet: usize,
     /// Modified flag
@@ -119,7 +404,17 @@ impl Workbook {
     ///
     /// Automatically uses memory mapping for large files (>10MB) for better performance.
     pub fn open(path: impl AsRef<Path>) -> XlexResult<Self> {
+        Self::open_with_mode(path, ParseMode::Lenient)
+    }
+
+    /// Opens an existing workbook, treating spec violations according to `mode`.
+    ///
+    /// In [`ParseMode::Strict`] the first violation fails with a
+    /// [`XlexError::ParseError`] locating it; in [`ParseMode::Lenient`] they
+    /// are recovered from and listed by [`Workbook::parse_violations`].
+    pub fn open_with_mode(path: impl AsRef<Path>, mode: ParseMode) -> XlexResult<Self> {
         let path = path.as_ref();
+        let _span = tracing::info_span!("open", path = %path.display()).entered();
 
         // Check extension
         if path.extension().and_then(|e| e.to_str()) != Some("xlsx") {
@@ -131,13 +426,22 @@ impl Workbook {
         // Use WorkbookReader for automatic mmap handling
         let wb_reader = crate::reader::WorkbookReader::open(path)?;
         let cursor = std::io::Cursor::new(wb_reader.as_bytes());
-        Self::from_reader(cursor, Some(path.to_path_buf()))
+        Self::from_reader_with_mode(cursor, Some(path.to_path_buf()), mode)
     }
 
     /// Creates a workbook from a reader.
     pub fn from_reader<R: Read + Seek>(reader: R, path: Option<PathBuf>) -> XlexResult<Self> {
+        Self::from_reader_with_mode(reader, path, ParseMode::Lenient)
+    }
+
+    /// Creates a workbook from a reader, treating spec violations according to `mode`.
+    pub fn from_reader_with_mode<R: Read + Seek>(
+        reader: R,
+        path: Option<PathBuf>,
+        mode: ParseMode,
+    ) -> XlexResult<Self> {
         let mut archive = ZipArchive::new(reader)?;
-        let parser = WorkbookParser::new();
+        let parser = WorkbookParser::with_mode(mode);
         parser.parse(&mut archive, path)
     }
 
@@ -152,6 +456,9 @@ impl Workbook {
             shared_strings: Vec::new(),
             shared_strings_map: HashMap::new(),
             defined_names: Vec::new(),
+            external_links: Vec::new(),
+            parse_violations: Vec::new(),
+            calc_chain: Vec::new(),
             active_sheet: 0,
             modified: true,
         };
@@ -172,6 +479,9 @@ impl Workbook {
             shared_strings: Vec::new(),
             shared_strings_map: HashMap::new(),
             defined_names: Vec::new(),
+            external_links: Vec::new(),
+            parse_violations: Vec::new(),
+            calc_chain: Vec::new(),
             active_sheet: 0,
             modified: true,
         };
@@ -208,6 +518,19 @@ impl Workbook {
         self.path.as_deref()
     }
 
+    /// Returns `n` read handles over the workbook's file, as last saved,
+    /// for serving reads from several threads; see [`ReaderPool`].
+    /// Changes not yet saved are not seen.
+    pub fn reader_pool(&self, n: usize) -> XlexResult<ReaderPool> {
+        let path = self
+            .path
+            .as_deref()
+            .ok_or_else(|| XlexError::InvalidOperation {
+                message: "A reader pool needs a workbook opened from a file".to_string(),
+            })?;
+        Ok(LazyWorkbook::open(path)?.reader_pool(n))
+    }
+
     /// Returns the document properties.
     pub fn properties(&self) -> &DocumentProperties {
         &self.properties
@@ -234,6 +557,16 @@ impl Workbook {
         self.sheet_map.get(name).map(|&idx| &self.sheets[idx])
     }
 
+    /// Gets a sheet by name, ignoring case when no sheet has exactly that
+    /// name. Excel keeps sheet names unique regardless of case, so at most
+    /// one sheet matches.
+    pub fn get_sheet_ci(&self, name: &str) -> Option<&Sheet> {
+        self.get_sheet(name).or_else(|| {
+            let name = name.to_lowercase();
+            self.sheets.iter().find(|s| s.name().to_lowercase() == name)
+        })
+    }
+
     /// Gets a mutable sheet by name.
     pub fn get_sheet_mut(&mut self, name: &str) -> Option<&mut Sheet> {
         if let Some(&idx) = self.sheet_map.get(name) {
@@ -288,8 +621,17 @@ impl Workbook {
                 name: name.to_string(),
             })?;
 
-        // Remove from sheets
+        // Remove from sheets, with the names scoped to it
         self.sheets.remove(index);
+        self.defined_names
+            .retain(|d| d.local_sheet_id != Some(index));
+        for defined_name in &mut self.defined_names {
+            if let Some(local) = defined_name.local_sheet_id.as_mut() {
+                if *local > index {
+                    *local -= 1;
+                }
+            }
+        }
 
         // Rebuild sheet map
         self.sheet_map.clear();
@@ -329,11 +671,78 @@ impl Workbook {
             return Ok(());
         }
 
-        // Remove sheet from current position
-        let sheet = self.sheets.remove(current_index);
+        let mut order: Vec<usize> = (0..self.sheets.len()).collect();
+        order.remove(current_index);
+        order.insert(new_position, current_index);
+        self.apply_sheet_order(&order);
+        Ok(())
+    }
+
+    /// Reorders sheets by name.
+    ///
+    /// `*` stands for every sheet not listed, in its current order; without
+    /// it, unlisted sheets keep their order after the listed ones. Sheet
+    /// relationship IDs travel with their sheets, and sheet-scoped defined
+    /// names and the active sheet follow the new positions.
+    pub fn reorder_sheets(&mut self, order: &[&str]) -> XlexResult<()> {
+        let mut listed: Vec<Option<usize>> = Vec::with_capacity(order.len());
+        for name in order {
+            if *name == "*" {
+                if listed.contains(&None) {
+                    return Err(XlexError::InvalidOperation {
+                        message: "'*' can appear only once in a sheet order".to_string(),
+                    });
+                }
+                listed.push(None);
+                continue;
+            }
+            let index = *self
+                .sheet_map
+                .get(*name)
+                .ok_or_else(|| XlexError::SheetNotFound {
+                    name: name.to_string(),
+                })?;
+            if listed.contains(&Some(index)) {
+                return Err(XlexError::InvalidOperation {
+                    message: format!("Sheet '{}' is listed more than once", name),
+                });
+            }
+            listed.push(Some(index));
+        }
+        if !listed.contains(&None) {
+            listed.push(None);
+        }
+
+        let rest: Vec<usize> = (0..self.sheets.len())
+            .filter(|i| !listed.contains(&Some(*i)))
+            .collect();
+        let new_order: Vec<usize> = listed
+            .into_iter()
+            .flat_map(|entry| match entry {
+                Some(index) => vec![index],
+                None => rest.clone(),
+            })
+            .collect();
+
+        if new_order.iter().enumerate().any(|(i, &old)| i != old) {
+            self.apply_sheet_order(&new_order);
+        }
+        Ok(())
+    }
+
+    /// Puts the sheets in `order` (old indices, in their new order) and
+    /// updates everything that refers to sheets by index.
+    fn apply_sheet_order(&mut self, order: &[usize]) {
+        let mut new_index = vec![0; order.len()];
+        for (new, &old) in order.iter().enumerate() {
+            new_index[old] = new;
+        }
 
-        // Insert at new position
-        self.sheets.insert(new_position, sheet);
+        let mut old_sheets: Vec<Option<Sheet>> = self.sheets.drain(..).map(Some).collect();
+        self.sheets = order
+            .iter()
+            .filter_map(|&old| old_sheets[old].take())
+            .collect();
 
         // Rebuild sheet map with updated indices
         self.sheet_map.clear();
@@ -342,17 +751,19 @@ impl Workbook {
             self.sheet_map.insert(sheet.name().to_string(), i);
         }
 
-        // Adjust active sheet if needed
-        if self.active_sheet == current_index {
-            self.active_sheet = new_position;
-        } else if current_index < self.active_sheet && new_position >= self.active_sheet {
-            self.active_sheet -= 1;
-        } else if current_index > self.active_sheet && new_position <= self.active_sheet {
-            self.active_sheet += 1;
+        for dn in &mut self.defined_names {
+            if let Some(scope) = dn.local_sheet_id.as_mut() {
+                if let Some(&index) = new_index.get(*scope) {
+                    *scope = index;
+                }
+            }
         }
+        self.active_sheet = new_index
+            .get(self.active_sheet)
+            .copied()
+            .unwrap_or_default();
 
         self.modified = true;
-        Ok(())
     }
 
     /// Renames a sheet.
@@ -379,13 +790,157 @@ impl Workbook {
         self.sheet_map.remove(old_name);
         self.sheet_map.insert(new_name.to_string(), index);
         self.sheets[index].set_name(new_name);
+        for (sheet, _) in &mut self.calc_chain {
+            if sheet == old_name {
+                *sheet = new_name.to_string();
+            }
+        }
         self.modified = true;
 
         Ok(())
     }
 
+    /// Duplicates a sheet within the workbook, appending the copy as
+    /// `new_name`. Returns the index of the new sheet.
+    ///
+    /// The copy is made with [`Sheet::duplicate_as`], so it shares the
+    /// source's style IDs and keeps everything stored on the sheet; it gets
+    /// its own sheet ID and part, and the writer creates its relationships
+    /// (background picture, comments, hyperlinks) on save. Sheet-scoped
+    /// defined names are copied with the new scope.
+    pub fn duplicate_sheet(&mut self, sheet_name: &str, new_name: &str) -> XlexResult<usize> {
+        let source_index =
+            *self
+                .sheet_map
+                .get(sheet_name)
+                .ok_or_else(|| XlexError::SheetNotFound {
+                    name: sheet_name.to_string(),
+                })?;
+        Self::validate_sheet_name(new_name)?;
+        if self.sheet_map.contains_key(new_name) {
+            return Err(XlexError::SheetAlreadyExists {
+                name: new_name.to_string(),
+            });
+        }
+
+        let index = self.add_sheet_internal(new_name);
+        let info = self.sheets[index].info.clone();
+        let mut sheet = self.sheets[source_index].duplicate_as(new_name);
+        sheet.info = SheetInfo {
+            visibility: sheet.info.visibility,
+            ..info
+        };
+        self.sheets[index] = sheet;
+
+        let scoped: Vec<DefinedName> = self
+            .defined_names
+            .iter()
+            .filter(|dn| dn.local_sheet_id == Some(source_index))
+            .map(|dn| DefinedName {
+                reference: crate::formula::rename_sheet_refs(&dn.reference, sheet_name, new_name),
+                local_sheet_id: Some(index),
+                ..dn.clone()
+            })
+            .collect();
+        self.defined_names.extend(scoped);
+
+        Ok(index)
+    }
+
+    /// Copies a sheet from another workbook, appending it as `new_name`
+    /// (or its original name). Returns the index of the new sheet.
+    ///
+    /// Everything stored on the sheet comes along: values, formulas,
+    /// comments, hyperlinks, merged cells, row heights, column widths,
+    /// hidden rows and columns, sparklines, dropdowns, print settings and
+    /// the background picture. Cell styles are re-registered in this
+    /// workbook, reusing identical styles, and sheet-scoped defined names
+    /// are copied with the new scope. References to the sheet's own name
+    /// follow the rename; references to other sheets are kept as written.
+    pub fn import_sheet(
+        &mut self,
+        source: &Workbook,
+        sheet_name: &str,
+        new_name: Option<&str>,
+    ) -> XlexResult<usize> {
+        let source_index =
+            *source
+                .sheet_map
+                .get(sheet_name)
+                .ok_or_else(|| XlexError::SheetNotFound {
+                    name: sheet_name.to_string(),
+                })?;
+        let name = new_name.unwrap_or(sheet_name);
+        Self::validate_sheet_name(name)?;
+        if self.sheet_map.contains_key(name) {
+            return Err(XlexError::SheetAlreadyExists {
+                name: name.to_string(),
+            });
+        }
+
+        let index = self.add_sheet_internal(name);
+        let info = self.sheets[index].info.clone();
+        let mut sheet = source.sheets[source_index].clone();
+        sheet.info = SheetInfo {
+            visibility: sheet.info.visibility,
+            ..info
+        };
+        if name != sheet_name {
+            sheet.rename_sheet_references(sheet_name, name);
+        }
+
+        // Style IDs index the source registry; map them onto ours
+        let mut style_map: HashMap<u32, Option<u32>> = HashMap::new();
+        let mut map_style = |source_id: u32| {
+            *style_map.entry(source_id).or_insert_with(|| {
+                source
+                    .style_registry
+                    .get(source_id)
+                    .map(|style| self.style_registry.clone_for(style))
+            })
+        };
+        let styled: Vec<Cell> = sheet
+            .cells()
+            .filter(|cell| cell.style_id.is_some())
+            .cloned()
+            .collect();
+        for mut cell in styled {
+            cell.style_id = map_style(cell.style_id.unwrap_or_default());
+            sheet.insert_cell(cell);
+        }
+        let row_styles: Vec<(u32, u32)> =
+            sheet.row_styles().iter().map(|(r, s)| (*r, *s)).collect();
+        for (row, style_id) in row_styles {
+            sheet.set_row_style(row, map_style(style_id));
+        }
+        let column_styles: Vec<(u32, u32)> = sheet
+            .column_styles()
+            .iter()
+            .map(|(c, s)| (*c, *s))
+            .collect();
+        for (col, style_id) in column_styles {
+            sheet.set_column_style(col, map_style(style_id));
+        }
+        sheet.set_default_style(sheet.default_style().and_then(&mut map_style));
+        self.sheets[index] = sheet;
+
+        let scoped: Vec<DefinedName> = source
+            .defined_names
+            .iter()
+            .filter(|dn| dn.local_sheet_id == Some(source_index))
+            .map(|dn| DefinedName {
+                reference: crate::formula::rename_sheet_refs(&dn.reference, sheet_name, name),
+                local_sheet_id: Some(index),
+                ..dn.clone()
+            })
+            .collect();
+        self.defined_names.extend(scoped);
+
+        Ok(index)
+    }
+
     /// Validates a sheet name.
-    fn validate_sheet_name(name: &str) -> XlexResult<()> {
+    pub(crate) fn validate_sheet_name(name: &str) -> XlexResult<()> {
         if name.is_empty() {
             return Err(XlexError::InvalidSheetName {
                 name: name.to_string(),
@@ -483,6 +1038,186 @@ impl Workbook {
         Ok(sheet.get_value(cell_ref))
     }
 
+    /// Sets a cell by address, such as `Sheet1!B2`, `'My Data'!B2` or `B2`
+    /// (on the active sheet).
+    ///
+    /// Accepts anything convertible to a [`CellValue`]: numbers, strings,
+    /// booleans, `chrono` dates and `Option`s of those (`None` empties the
+    /// cell).
+    pub fn set(&mut self, address: &str, value: impl Into<CellValue>) -> XlexResult<()> {
+        let (sheet_name, cell_ref) = self.resolve_address(address)?;
+        self.set_cell(&sheet_name, cell_ref, value.into())
+    }
+
+    /// Gets a cell's value by address, such as `Sheet1!B2` or `B2` (on the
+    /// active sheet).
+    pub fn get(&self, address: &str) -> XlexResult<CellValue> {
+        let (sheet_name, cell_ref) = self.resolve_address(address)?;
+        self.get_cell(&sheet_name, &cell_ref)
+    }
+
+    /// Splits a cell address into its sheet (the active sheet if unnamed)
+    /// and cell. `$` anchors are ignored.
+    fn resolve_address(&self, address: &str) -> XlexResult<(String, CellRef)> {
+        let (sheet_name, cell) = match crate::sheet::split_sheet_ref(address) {
+            Some((sheet_name, cell)) => (sheet_name, cell),
+            None => {
+                let sheet =
+                    self.sheets
+                        .get(self.active_sheet)
+                        .ok_or_else(|| XlexError::SheetNotFound {
+                            name: address.to_string(),
+                        })?;
+                (sheet.name().to_string(), address)
+            }
+        };
+        Ok((sheet_name, CellRef::parse(&cell.replace('$', ""))?))
+    }
+
+    /// Gets the fully resolved style of a cell.
+    ///
+    /// Unstyled cells take their row's, column's or sheet's default style,
+    /// falling back to the default style.
+    pub fn get_cell_style(&self, sheet_name: &str, cell_ref: &CellRef) -> XlexResult<Style> {
+        let sheet = self
+            .get_sheet(sheet_name)
+            .ok_or_else(|| XlexError::SheetNotFound {
+                name: sheet_name.to_string(),
+            })?;
+        Ok(self
+            .style_registry
+            .resolve(sheet.effective_style_id(cell_ref)))
+    }
+
+    /// Turns a cell into a checkbox and sets its checked state.
+    ///
+    /// The value becomes TRUE/FALSE and the cell keeps its other formatting.
+    pub fn set_checkbox(
+        &mut self,
+        sheet_name: &str,
+        cell_ref: &CellRef,
+        checked: bool,
+    ) -> XlexResult<()> {
+        self.update_checkbox(sheet_name, cell_ref, Some(checked))
+    }
+
+    /// Removes the checkbox format from a cell, leaving its value in place.
+    pub fn clear_checkbox(&mut self, sheet_name: &str, cell_ref: &CellRef) -> XlexResult<()> {
+        self.update_checkbox(sheet_name, cell_ref, None)
+    }
+
+    /// Sets the locked and hidden flags of every cell in a range, keeping
+    /// their other formatting. The flags take effect once the sheet is
+    /// protected with [`Sheet::set_protection`].
+    ///
+    /// A range of whole columns (`B:D`) or whole rows (`2:5`) sets the
+    /// column or row style, plus the style of cells already stored there,
+    /// instead of styling every cell.
+    pub fn set_cell_protection(
+        &mut self,
+        sheet_name: &str,
+        range: &Range,
+        protection: Protection,
+    ) -> XlexResult<()> {
+        let sheet = self
+            .sheet_map
+            .get(sheet_name)
+            .and_then(|&index| self.sheets.get_mut(index))
+            .ok_or_else(|| XlexError::SheetNotFound {
+                name: sheet_name.to_string(),
+            })?;
+        let registry = &mut self.style_registry;
+        let mut restyle = |style_id: Option<u32>| {
+            let mut style: Style = style_id
+                .and_then(|id| registry.get(id).cloned())
+                .unwrap_or_default();
+            style.protection = protection;
+            registry.clone_for(&style)
+        };
+
+        let whole_columns = range.start.row == 1 && range.end.row == CellRef::MAX_ROW;
+        let whole_rows = range.start.col == 1 && range.end.col == CellRef::MAX_COL;
+        let cells: Vec<CellRef> = if whole_columns || whole_rows {
+            if whole_columns {
+                for col in range.start.col..=range.end.col {
+                    let current = sheet.get_column_style(col).or(sheet.default_style());
+                    sheet.set_column_style(col, Some(restyle(current)));
+                }
+            } else {
+                for row in range.start.row..=range.end.row {
+                    let current = sheet.get_row_style(row);
+                    sheet.set_row_style(row, Some(restyle(current)));
+                }
+            }
+            sheet
+                .cells()
+                .filter(|cell| cell.style_id.is_some() && range.contains(&cell.reference))
+                .map(|cell| cell.reference.clone())
+                .collect()
+        } else {
+            range.cells().collect()
+        };
+        for cell_ref in cells {
+            let current = sheet.effective_style_id(&cell_ref);
+            sheet.set_cell_style(&cell_ref, Some(restyle(current)));
+        }
+        self.modified = true;
+        Ok(())
+    }
+
+    /// Writes text that wraps within its column, sizing the column and row
+    /// so it is not clipped. See [`Sheet::write_wrapped`].
+    pub fn write_wrapped(
+        &mut self,
+        sheet_name: &str,
+        cell_ref: &CellRef,
+        text: impl Into<String>,
+        max_width: f64,
+    ) -> XlexResult<()> {
+        let sheet = self
+            .sheet_map
+            .get(sheet_name)
+            .and_then(|&index| self.sheets.get_mut(index))
+            .ok_or_else(|| XlexError::SheetNotFound {
+                name: sheet_name.to_string(),
+            })?;
+        sheet.write_wrapped(&mut self.style_registry, cell_ref, text, max_width);
+        self.modified = true;
+        Ok(())
+    }
+
+    fn update_checkbox(
+        &mut self,
+        sheet_name: &str,
+        cell_ref: &CellRef,
+        checked: Option<bool>,
+    ) -> XlexResult<()> {
+        let sheet = self
+            .sheet_map
+            .get(sheet_name)
+            .and_then(|&index| self.sheets.get_mut(index))
+            .ok_or_else(|| XlexError::SheetNotFound {
+                name: sheet_name.to_string(),
+            })?;
+        let mut cell = sheet
+            .get_cell(cell_ref)
+            .cloned()
+            .unwrap_or_else(|| Cell::empty(cell_ref.clone()));
+
+        let mut style = cell
+            .style_id
+            .and_then(|id| self.style_registry.get(id).cloned())
+            .unwrap_or_default();
+        style.checkbox = checked.is_some();
+        cell.style_id = Some(self.style_registry.clone_for(&style));
+        if let Some(checked) = checked {
+            cell.value = CellValue::Boolean(checked);
+        }
+        sheet.insert_cell(cell);
+        self.modified = true;
+        Ok(())
+    }
+
     /// Sets a cell value.
     pub fn set_cell(
         &mut self,
@@ -499,6 +1234,24 @@ impl Workbook {
         Ok(())
     }
 
+    /// Runs `f` against the named sheet, marking the workbook modified.
+    ///
+    /// Bulk edits should go through this (typically with
+    /// [`Sheet::apply_updates`]) rather than calling [`Workbook::set_cell`]
+    /// per cell, which repeats the sheet lookup each time.
+    pub fn with_sheet_mut<R>(
+        &mut self,
+        sheet_name: &str,
+        f: impl FnOnce(&mut Sheet) -> R,
+    ) -> XlexResult<R> {
+        let sheet = self
+            .get_sheet_mut(sheet_name)
+            .ok_or_else(|| XlexError::SheetNotFound {
+                name: sheet_name.to_string(),
+            })?;
+        Ok(f(sheet))
+    }
+
     /// Clears a cell.
     pub fn clear_cell(&mut self, sheet_name: &str, cell_ref: &CellRef) -> XlexResult<()> {
         let sheet = self
@@ -547,18 +1300,41 @@ impl Workbook {
     }
 
     /// Calculates workbook statistics.
+    ///
+    /// The memory figures estimate xlex's own cell storage; other tools
+    /// differ, but scale with the uncompressed part sizes listed in
+    /// [`WorkbookStats::parts`].
     pub fn stats(&self) -> WorkbookStats {
-        let mut total_cells = 0;
-        let mut formula_count = 0;
-
-        for sheet in &self.sheets {
-            total_cells += sheet.cell_count();
-            for cell in sheet.cells() {
-                if matches!(cell.value, CellValue::Formula { .. }) {
-                    formula_count += 1;
-                }
-            }
-        }
+        let sheets: Vec<SheetStats> = self.sheets.iter().map(sheet_stats).collect();
+        let total_cells = sheets.iter().map(|s| s.cell_count).sum();
+        let formula_count = sheets.iter().map(|s| s.formula_count).sum();
+        let strings_memory: u64 = self.shared_strings.iter().map(|s| s.len() as u64).sum();
+        let estimated_memory =
+            sheets.iter().map(|s| s.estimated_memory).sum::<u64>() + strings_memory;
+
+        let mut parts: Vec<PartSize> = self
+            .path
+            .as_ref()
+            .and_then(|p| std::fs::File::open(p).ok())
+            .and_then(|f| ZipArchive::new(f).ok())
+            .map(|mut archive| {
+                (0..archive.len())
+                    .filter_map(|i| {
+                        let file = archive.by_index_raw(i).ok()?;
+                        (!file.is_dir()).then(|| PartSize {
+                            name: file.name().to_string(),
+                            compressed_size: file.compressed_size(),
+                            uncompressed_size: file.size(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        parts.sort_by(|a, b| {
+            b.uncompressed_size
+                .cmp(&a.uncompressed_size)
+                .then_with(|| a.name.cmp(&b.name))
+        });
 
         WorkbookStats {
             sheet_count: self.sheets.len(),
@@ -572,6 +1348,9 @@ impl Workbook {
                 .and_then(|p| p.metadata().ok())
                 .map(|m| m.len())
                 .unwrap_or(0),
+            estimated_memory,
+            sheets,
+            parts,
         }
     }
 
@@ -602,18 +1381,269 @@ impl Workbook {
         writer.write(self, path)
     }
 
-    /// Returns true if the workbook has been modified.
-    pub fn is_modified(&self) -> bool {
-        self.modified
+    /// Saves the workbook to a new path, compressing parts at `level`
+    /// (0 = fastest, 9 = smallest).
+    pub fn save_as_compressed(&self, path: impl AsRef<Path>, level: u32) -> XlexResult<()> {
+        let path = path.as_ref();
+        if path.extension().and_then(|e| e.to_str()) != Some("xlsx") {
+            return Err(XlexError::InvalidExtension {
+                path: path.to_path_buf(),
+            });
+        }
+        crate::writer::WorkbookWriter::with_compression_level(level).write(self, path)
     }
 
-    /// Returns all defined names (named ranges).
-    pub fn defined_names(&self) -> &[DefinedName] {
-        &self.defined_names
-    }
+    /// Finds defined names, styles and hidden sheets nothing refers to.
+    ///
+    /// References are found by name in formulas, defined names, dropdown
+    /// sources and sparkline data. Built-in names such as print areas are
+    /// always considered used. References built at run time (`INDIRECT`)
+    /// cannot be seen.
+    pub fn find_unused(&self) -> UnusedContent {
+        use crate::formula::{collect_names, sheet_qualifiers};
+        use std::collections::HashSet;
+
+        let mut used_names: HashSet<String> = HashSet::new();
+        for text in self
+            .sheets
+            .iter()
+            .flat_map(|sheet| sheet.formula_texts())
+            .chain(self.defined_names.iter().map(|d| d.reference.as_str()))
+        {
+            used_names.extend(collect_names(text).iter().map(|n| n.to_lowercase()));
+        }
+        let defined_names: Vec<DefinedName> = self
+            .defined_names
+            .iter()
+            .filter(|d| {
+                !d.name.starts_with("_xlnm.") && !used_names.contains(&d.name.to_lowercase())
+            })
+            .cloned()
+            .collect();
 
-    /// Gets a defined name by name.
-    pub fn get_defined_name(&self, name: &str) -> Option<&DefinedName> {
+        let used_styles: HashSet<u32> = self
+            .sheets
+            .iter()
+            .flat_map(|sheet| sheet.style_ids())
+            .collect();
+        let mut styles: Vec<u32> = self
+            .style_registry
+            .iter()
+            .map(|(id, _)| id)
+            .filter(|id| *id != 0 && !used_styles.contains(id))
+            .collect();
+        styles.sort_unstable();
+
+        let name_refs: Vec<&str> = self
+            .defined_names
+            .iter()
+            .filter(|d| {
+                !d.name.starts_with("_xlnm.")
+                    && !defined_names
+                        .iter()
+                        .any(|u| u.name == d.name && u.local_sheet_id == d.local_sheet_id)
+            })
+            .map(|d| d.reference.as_str())
+            .collect();
+        let hidden_sheets = self
+            .sheets
+            .iter()
+            .filter(|sheet| sheet.info.visibility.is_hidden())
+            .filter(|hidden| {
+                let refers = |text: &str| {
+                    sheet_qualifiers(text)
+                        .iter()
+                        .any(|s| s.eq_ignore_ascii_case(hidden.name()))
+                };
+                let from_sheets = self
+                    .sheets
+                    .iter()
+                    .filter(|sheet| sheet.name() != hidden.name())
+                    .flat_map(|sheet| sheet.formula_texts())
+                    .any(refers);
+                !from_sheets && !name_refs.iter().any(|text| refers(text))
+            })
+            .map(|sheet| sheet.name().to_string())
+            .collect();
+
+        UnusedContent {
+            defined_names,
+            styles,
+            hidden_sheets,
+        }
+    }
+
+    /// Removes content found by [`find_unused`](Self::find_unused).
+    ///
+    /// Remaining styles are renumbered; cells keep their formatting.
+    pub fn remove_unused(&mut self, unused: &UnusedContent) -> XlexResult<()> {
+        self.defined_names.retain(|d| {
+            !unused
+                .defined_names
+                .iter()
+                .any(|u| u.name == d.name && u.local_sheet_id == d.local_sheet_id)
+        });
+        for name in &unused.hidden_sheets {
+            self.remove_sheet(name)?;
+        }
+
+        let keep: std::collections::HashSet<u32> = self
+            .style_registry
+            .iter()
+            .map(|(id, _)| id)
+            .filter(|id| !unused.styles.contains(id))
+            .collect();
+        let remap = self.style_registry.compact(&keep);
+        for sheet in &mut self.sheets {
+            sheet.remap_styles(&remap);
+        }
+
+        self.modified = true;
+        Ok(())
+    }
+
+    /// Finds sheets, rows, columns and cell values a reader can't see.
+    pub fn find_hidden(&self) -> HiddenContent {
+        let mut hidden = HiddenContent::default();
+        for sheet in &self.sheets {
+            let name = sheet.name().to_string();
+            match sheet.info.visibility {
+                SheetVisibility::Hidden => hidden.hidden_sheets.push(name.clone()),
+                SheetVisibility::VeryHidden => hidden.very_hidden_sheets.push(name.clone()),
+                SheetVisibility::Visible => {}
+            }
+
+            let mut rows: Vec<u32> = sheet.hidden_rows().iter().copied().collect();
+            rows.sort_unstable();
+            hidden
+                .hidden_rows
+                .extend(rows.into_iter().map(|row| (name.clone(), row)));
+            let mut columns: Vec<u32> = sheet.hidden_columns().iter().copied().collect();
+            columns.sort_unstable();
+            hidden
+                .hidden_columns
+                .extend(columns.into_iter().map(|col| (name.clone(), col)));
+            let mut zero_width: Vec<u32> = sheet
+                .column_widths()
+                .iter()
+                .filter(|(col, width)| **width <= 0.0 && !sheet.is_column_hidden(**col))
+                .map(|(col, _)| *col)
+                .collect();
+            zero_width.sort_unstable();
+            hidden
+                .zero_width_columns
+                .extend(zero_width.into_iter().map(|col| (name.clone(), col)));
+
+            let mut invisible = Vec::new();
+            let mut covered = Vec::new();
+            for cell in sheet.cells().filter(|c| !c.value.is_empty()) {
+                let reference = &cell.reference;
+                if cell
+                    .style_id
+                    .and_then(|id| self.style_registry.get(id))
+                    .is_some_and(|style| style.text_matches_background())
+                {
+                    invisible.push(reference.clone());
+                }
+                if sheet.merged_ranges().iter().any(|range| {
+                    range.contains(reference)
+                        && (range.start.col, range.start.row) != (reference.col, reference.row)
+                }) {
+                    covered.push(reference.clone());
+                }
+            }
+            invisible.sort_by_key(|c| (c.row, c.col));
+            covered.sort_by_key(|c| (c.row, c.col));
+            hidden
+                .invisible_text
+                .extend(invisible.into_iter().map(|c| (name.clone(), c)));
+            hidden
+                .covered_cells
+                .extend(covered.into_iter().map(|c| (name.clone(), c)));
+        }
+        hidden
+    }
+
+    /// Clears the author and last-modified-by properties and custom
+    /// properties, and removes hidden defined names (built-in `_xlnm.`
+    /// names are kept).
+    ///
+    /// Other personal metadata (company, absolute save path, printer
+    /// settings, revision data, comment authors) is not part of the model
+    /// and is dropped when the workbook is saved.
+    pub fn remove_personal_info(&mut self) {
+        self.properties.creator = None;
+        self.properties.last_modified_by = None;
+        self.properties.custom.clear();
+        self.defined_names
+            .retain(|d| !d.hidden || d.name.starts_with("_xlnm."));
+        self.modified = true;
+    }
+
+    /// Shrinks the workbook without changing how it looks.
+    ///
+    /// Drops styles no cell uses and merges identical ones, removes number
+    /// formats no style uses, trims blank cells and row/column sizes past
+    /// each sheet's content (see [`Sheet::trim_trailing_blanks`]) and
+    /// rebuilds the shared string table from the strings still in use.
+    pub fn optimize(&mut self) -> OptimizeReport {
+        let mut report = OptimizeReport {
+            styles_before: self.style_registry.len(),
+            number_formats_before: self.style_registry.number_formats().len(),
+            shared_strings_before: self.shared_strings.len(),
+            ..Default::default()
+        };
+
+        for sheet in &mut self.sheets {
+            let (cells, rows, columns) = sheet.trim_trailing_blanks(&self.style_registry);
+            report.blank_cells_removed += cells;
+            report.row_heights_removed += rows;
+            report.column_widths_removed += columns;
+        }
+
+        let used: std::collections::HashSet<u32> = self
+            .sheets
+            .iter()
+            .flat_map(|sheet| sheet.style_ids())
+            .collect();
+        let remap = self.style_registry.compact(&used);
+        for sheet in &mut self.sheets {
+            sheet.remap_styles(&remap);
+        }
+
+        self.shared_strings.clear();
+        self.shared_strings_map.clear();
+        for sheet in &self.sheets {
+            for cell in sheet.cells() {
+                if let CellValue::String(s) = &cell.value {
+                    if !self.shared_strings_map.contains_key(s) {
+                        self.shared_strings_map
+                            .insert(s.clone(), self.shared_strings.len());
+                        self.shared_strings.push(s.clone());
+                    }
+                }
+            }
+        }
+
+        report.styles_after = self.style_registry.len();
+        report.number_formats_after = self.style_registry.number_formats().len();
+        report.shared_strings_after = self.shared_strings.len();
+        self.modified = true;
+        report
+    }
+
+    /// Returns true if the workbook has been modified.
+    pub fn is_modified(&self) -> bool {
+        self.modified
+    }
+
+    /// Returns all defined names (named ranges).
+    pub fn defined_names(&self) -> &[DefinedName] {
+        &self.defined_names
+    }
+
+    /// Gets a defined name by name.
+    pub fn get_defined_name(&self, name: &str) -> Option<&DefinedName> {
         self.defined_names.iter().find(|d| d.name == name)
     }
 
@@ -627,6 +1657,35 @@ impl Workbook {
         self.defined_names.push(defined_name);
     }
 
+    /// Resolves a defined name to the sheet and range it refers to, such as
+    /// `Sheet1` and `F42` for `TotalCell` = `Sheet1!$F$42`.
+    ///
+    /// A workbook-wide name wins over sheet-scoped ones. Fails if there is
+    /// no such name or it refers to anything but one range on one sheet.
+    pub fn resolve_name(&self, name: &str) -> XlexResult<(String, crate::range::Range)> {
+        let defined_name = self
+            .defined_names
+            .iter()
+            .filter(|d| d.name.eq_ignore_ascii_case(name))
+            .min_by_key(|d| d.local_sheet_id.is_some())
+            .ok_or_else(|| XlexError::InvalidReference {
+                reference: name.to_string(),
+            })?;
+        let invalid = || XlexError::InvalidReference {
+            reference: format!("{} ({})", name, defined_name.reference),
+        };
+        let reference = defined_name
+            .reference
+            .trim_start_matches('=')
+            .replace('$', "");
+        let (sheet, range) = crate::sheet::split_sheet_ref(&reference).ok_or_else(invalid)?;
+        let range = crate::range::Range::parse(range).map_err(|_| invalid())?;
+        if self.get_sheet(&sheet).is_none() {
+            return Err(invalid());
+        }
+        Ok((sheet, range))
+    }
+
     /// Removes a defined name.
     pub fn remove_defined_name(&mut self, name: &str) -> bool {
         let before_len = self.defined_names.len();
@@ -638,6 +1697,88 @@ impl Workbook {
         removed
     }
 
+    /// Returns the links to other workbooks. Formulas refer to the first
+    /// link as `[1]`.
+    pub fn external_links(&self) -> &[ExternalLink] {
+        &self.external_links
+    }
+
+    /// Points an external link (1-based, as in `[1]`) at a new workbook path.
+    pub fn set_external_link_target(
+        &mut self,
+        index: usize,
+        target: impl Into<String>,
+    ) -> XlexResult<()> {
+        let link = index
+            .checked_sub(1)
+            .and_then(|i| self.external_links.get_mut(i))
+            .ok_or_else(|| XlexError::InvalidOperation {
+                message: format!("External link [{}] does not exist", index),
+            })?;
+        link.target = target.into();
+        self.modified = true;
+        Ok(())
+    }
+
+    /// Breaks an external link (1-based): formulas using it are replaced by
+    /// their cached values, defined names using it are removed, and later
+    /// links are renumbered. Returns the number of cells converted.
+    pub fn break_external_link(&mut self, index: usize) -> XlexResult<usize> {
+        if index == 0 || index > self.external_links.len() {
+            return Err(XlexError::InvalidOperation {
+                message: format!("External link [{}] does not exist", index),
+            });
+        }
+        let renumber = |i: usize| if i > index { i - 1 } else { i };
+
+        let mut converted = 0;
+        for sheet in &mut self.sheets {
+            let updates: Vec<Cell> = sheet
+                .cells()
+                .filter_map(|cell| {
+                    let CellValue::Formula {
+                        formula,
+                        cached_result,
+                    } = &cell.value
+                    else {
+                        return None;
+                    };
+                    let links = crate::formula::external_links(formula);
+                    if links.is_empty() {
+                        return None;
+                    }
+                    let mut cell = cell.clone();
+                    cell.value = if links.contains(&index) {
+                        converted += 1;
+                        cached_result
+                            .as_deref()
+                            .cloned()
+                            .unwrap_or(CellValue::Empty)
+                    } else {
+                        CellValue::Formula {
+                            formula: crate::formula::map_external_links(formula, renumber),
+                            cached_result: cached_result.clone(),
+                        }
+                    };
+                    Some(cell)
+                })
+                .collect();
+            for cell in updates {
+                sheet.insert_cell(cell);
+            }
+        }
+
+        self.defined_names
+            .retain(|dn| !crate::formula::external_links(&dn.reference).contains(&index));
+        for dn in &mut self.defined_names {
+            dn.reference = crate::formula::map_external_links(&dn.reference, renumber);
+        }
+
+        self.external_links.remove(index - 1);
+        self.modified = true;
+        Ok(converted)
+    }
+
     /// Internal constructor for the parser.
     /// This is hidden from the public API and should only be used by the parser module.
     #[doc(hidden)]
@@ -669,10 +1810,81 @@ impl Workbook {
             shared_strings,
             shared_strings_map,
             defined_names,
+            external_links: Vec::new(),
+            parse_violations: Vec::new(),
+            calc_chain: Vec::new(),
             active_sheet,
             modified,
         }
     }
+
+    /// Attaches the external links read by the parser.
+    pub(crate) fn set_parsed_external_links(&mut self, links: Vec<ExternalLink>) {
+        self.external_links = links;
+    }
+
+    /// Spec violations the lenient parser recovered from, in document order.
+    pub fn parse_violations(&self) -> &[Violation] {
+        &self.parse_violations
+    }
+
+    /// Attaches the violations recorded by the parser.
+    pub(crate) fn set_parse_violations(&mut self, violations: Vec<Violation>) {
+        self.parse_violations = violations;
+    }
+
+    /// Formula cells in calculation order, as `(sheet, cell)` pairs.
+    ///
+    /// This is the chain read from the file (or set by
+    /// [`Workbook::rebuild_calc_chain`]); it goes stale as formulas are
+    /// added or removed until rebuilt. Saving always writes a rebuilt chain.
+    pub fn calc_chain(&self) -> &[(String, CellRef)] {
+        &self.calc_chain
+    }
+
+    /// Brings the calculation chain in line with the formula cells.
+    ///
+    /// Entries whose cell no longer holds a formula are dropped, and new
+    /// formula cells are appended sheet by sheet in row-major order, so
+    /// Excel keeps its calculation order for existing formulas.
+    pub fn rebuild_calc_chain(&mut self) {
+        self.calc_chain = self.rebuilt_calc_chain();
+    }
+
+    /// The calculation chain [`Workbook::rebuild_calc_chain`] would store.
+    pub(crate) fn rebuilt_calc_chain(&self) -> Vec<(String, CellRef)> {
+        let mut remaining: HashMap<&str, HashSet<CellRef>> = self
+            .sheets
+            .iter()
+            .map(|sheet| (sheet.name(), sheet.formula_cells().into_iter().collect()))
+            .collect();
+        let mut chain: Vec<(String, CellRef)> = self
+            .calc_chain
+            .iter()
+            .filter(|(sheet, cell)| {
+                remaining
+                    .get_mut(sheet.as_str())
+                    .is_some_and(|cells| cells.remove(cell))
+            })
+            .cloned()
+            .collect();
+        for sheet in &self.sheets {
+            let cells = &remaining[sheet.name()];
+            chain.extend(
+                sheet
+                    .formula_cells()
+                    .into_iter()
+                    .filter(|cell| cells.contains(cell))
+                    .map(|cell| (sheet.name().to_string(), cell)),
+            );
+        }
+        chain
+    }
+
+    /// Attaches the calculation chain read by the parser.
+    pub(crate) fn set_parsed_calc_chain(&mut self, chain: Vec<(String, CellRef)>) {
+        self.calc_chain = chain;
+    }
 }
 
 impl Default for Workbook {
@@ -716,6 +1928,60 @@ mod tests {
         assert_eq!(wb.sheet_names(), vec!["Sheet1"]);
     }
 
+    #[test]
+    fn test_get_sheet_ci() {
+        let wb = Workbook::with_sheets(&["Sheet1", "Q3 Sales"]);
+        assert_eq!(wb.get_sheet_ci("sheet1").unwrap().name(), "Sheet1");
+        assert_eq!(wb.get_sheet_ci("Q3 SALES").unwrap().name(), "Q3 Sales");
+        assert!(wb.get_sheet_ci("Sheet2").is_none());
+        assert!(wb.get_sheet("sheet1").is_none());
+    }
+
+    #[test]
+    fn test_set_and_get_by_address() {
+        let mut wb = Workbook::with_sheets(&["Data", "My Data"]);
+        wb.set("Data!B2", 42).unwrap();
+        wb.set("'My Data'!$C$3", "North").unwrap();
+        wb.set("A1", true).unwrap();
+        wb.set("Data!D4", Some(1.5)).unwrap();
+        wb.set("Data!D4", None::<f64>).unwrap();
+
+        assert_eq!(wb.get("Data!B2").unwrap(), CellValue::Number(42.0));
+        assert_eq!(
+            wb.get("'My Data'!C3").unwrap(),
+            CellValue::String("North".to_string())
+        );
+        // Unqualified addresses use the active sheet
+        assert_eq!(wb.get("Data!A1").unwrap(), CellValue::Boolean(true));
+        assert_eq!(wb.get("Data!D4").unwrap(), CellValue::Empty);
+
+        assert!(matches!(
+            wb.set("Missing!A1", 1),
+            Err(XlexError::SheetNotFound { .. })
+        ));
+        assert!(wb.get("Data!nope").is_err());
+    }
+
+    #[test]
+    fn test_with_sheet_mut() {
+        let mut wb = Workbook::with_sheets(&["Data"]);
+        let written = wb
+            .with_sheet_mut("Data", |sheet| {
+                sheet.apply_updates(
+                    (1..=3).map(|row| (CellRef::new(1, row), CellValue::Number(row as f64))),
+                )
+            })
+            .unwrap();
+        assert_eq!(written, 3);
+        assert_eq!(
+            wb.get_cell("Data", &CellRef::new(1, 3)).unwrap(),
+            CellValue::Number(3.0)
+        );
+
+        let err = wb.with_sheet_mut("Missing", |_| ()).unwrap_err();
+        assert!(matches!(err, XlexError::SheetNotFound { .. }));
+    }
+
     #[test]
     fn test_add_sheet() {
         let mut wb = Workbook::new();
@@ -882,6 +2148,26 @@ mod tests {
         assert!(value.is_empty());
     }
 
+    #[test]
+    fn test_get_cell_style() {
+        let mut wb = Workbook::new();
+        let cell_ref = CellRef::new(2, 2);
+        let style_id = wb.style_registry_mut().add(Style {
+            wrap_text: true,
+            ..Default::default()
+        });
+        wb.set_cell("Sheet1", cell_ref.clone(), CellValue::number(1.0))
+            .unwrap();
+        wb.get_sheet_mut("Sheet1")
+            .unwrap()
+            .set_cell_style(&cell_ref, Some(style_id));
+
+        assert!(wb.get_cell_style("Sheet1", &cell_ref).unwrap().wrap_text);
+        let unstyled = wb.get_cell_style("Sheet1", &CellRef::new(1, 1)).unwrap();
+        assert!(!unstyled.wrap_text);
+        assert!(wb.get_cell_style("Missing", &cell_ref).is_err());
+    }
+
     #[test]
     fn test_cell_operations_nonexistent_sheet() {
         let mut wb = Workbook::new();
@@ -911,6 +2197,52 @@ mod tests {
         assert_eq!(stats.formula_count, 1);
     }
 
+    #[test]
+    fn test_workbook_stats_breakdown() {
+        let temp_dir = std::env::temp_dir();
+        let file_path = temp_dir.join("test_workbook_stats_breakdown.xlsx");
+
+        let mut wb = Workbook::with_sheets(&["Data", "Empty"]);
+        for col in 1..=3 {
+            wb.set_cell("Data", CellRef::new(col, 2), CellValue::string("East"))
+                .unwrap();
+        }
+        wb.set_cell("Data", CellRef::new(1, 1), CellValue::string("West"))
+            .unwrap();
+        wb.set_cell(
+            "Data",
+            CellRef::new(4, 2),
+            CellValue::formula("COUNTA(A2:C2)"),
+        )
+        .unwrap();
+        wb.save_as(&file_path).unwrap();
+
+        let stats = Workbook::open(&file_path).unwrap().stats();
+        let data = &stats.sheets[0];
+        assert_eq!(data.name, "Data");
+        assert_eq!(data.cell_count, 5);
+        assert_eq!(data.formula_count, 1);
+        assert_eq!(data.unique_strings, 2);
+        assert_eq!((data.largest_row, data.largest_row_cells), (2, 4));
+        assert!(data.estimated_memory > 0);
+        assert_eq!(stats.sheets[1].largest_row, 0);
+        assert!(stats.estimated_memory >= data.estimated_memory);
+
+        let sheet_part = stats
+            .parts
+            .iter()
+            .find(|p| p.name == "xl/worksheets/sheet1.xml")
+            .unwrap();
+        assert!(sheet_part.uncompressed_size > 0 && sheet_part.compressed_size > 0);
+        // Largest parts come first
+        assert!(stats
+            .parts
+            .windows(2)
+            .all(|w| w[0].uncompressed_size >= w[1].uncompressed_size));
+
+        std::fs::remove_file(file_path).ok();
+    }
+
     #[test]
     fn test_active_sheet() {
         let mut wb = Workbook::with_sheets(&["A", "B", "C"]);
@@ -1042,6 +2374,30 @@ mod tests {
         assert!(!name.hidden);
     }
 
+    #[test]
+    fn test_resolve_name() {
+        let mut wb = Workbook::with_sheets(&["Sheet1", "My Report"]);
+        wb.set_defined_name(DefinedName::new("TotalCell", "'My Report'!$F$42"));
+        wb.set_defined_name(DefinedName::with_sheet_scope("Area", "Sheet1!A1", 0));
+        wb.set_defined_name(DefinedName::new("Area", "Sheet1!$B$2:$C$3"));
+        wb.set_defined_name(DefinedName::new("Rate", "0.2"));
+        wb.set_defined_name(DefinedName::new("Gone", "Missing!$A$1"));
+
+        let (sheet, range) = wb.resolve_name("totalcell").unwrap();
+        assert_eq!(
+            (sheet.as_str(), range.to_a1().as_str()),
+            ("My Report", "F42")
+        );
+        let (sheet, range) = wb.resolve_name("Area").unwrap();
+        assert_eq!(
+            (sheet.as_str(), range.to_a1().as_str()),
+            ("Sheet1", "B2:C3")
+        );
+        assert!(wb.resolve_name("Rate").is_err());
+        assert!(wb.resolve_name("Gone").is_err());
+        assert!(wb.resolve_name("Nope").is_err());
+    }
+
     #[test]
     fn test_properties() {
         let mut wb = Workbook::new();
@@ -1169,6 +2525,1125 @@ mod tests {
         std::fs::remove_file(file_path).ok();
     }
 
+    #[test]
+    fn test_styled_empty_cell_roundtrip() {
+        let temp_dir = std::env::temp_dir();
+        let file_path = temp_dir.join("test_styled_empty_roundtrip.xlsx");
+
+        {
+            let mut wb = Workbook::new();
+            let mut bold = Style::default();
+            bold.font.bold = true;
+            let style_id = wb.style_registry_mut().add(bold);
+            wb.get_sheet_mut("Sheet1")
+                .unwrap()
+                .set_cell_style(&CellRef::new(2, 2), Some(style_id));
+            wb.save_as(&file_path).unwrap();
+        }
+
+        // Styles survive repeated saves without adding registry entries
+        let styles = Workbook::open(&file_path).unwrap().style_registry().len();
+        Workbook::open(&file_path).unwrap().save().unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(wb.style_registry().len(), styles);
+        let style = wb.get_cell_style("Sheet1", &CellRef::new(2, 2)).unwrap();
+        assert!(style.font.bold);
+        assert_eq!(
+            wb.get_cell("Sheet1", &CellRef::new(2, 2)).unwrap(),
+            CellValue::Empty
+        );
+
+        std::fs::remove_file(file_path).ok();
+    }
+
+    #[test]
+    fn test_row_column_and_default_style_roundtrip() {
+        let temp_dir = std::env::temp_dir();
+        let file_path = temp_dir.join("test_row_column_style_roundtrip.xlsx");
+
+        {
+            let mut wb = Workbook::new();
+            let mut bold = Style::default();
+            bold.font.bold = true;
+            let bold = wb.style_registry_mut().add(bold);
+            let mut italic = Style::default();
+            italic.font.italic = true;
+            let italic = wb.style_registry_mut().add(italic);
+            let mut underline = Style::default();
+            underline.font.underline = true;
+            let underline = wb.style_registry_mut().add(underline);
+
+            let sheet = wb.get_sheet_mut("Sheet1").unwrap();
+            sheet.set_row_style(2, Some(bold));
+            sheet.set_column_style(3, Some(italic));
+            sheet.set_default_style(Some(underline));
+            sheet.set_column_width(5, 20.0);
+            sheet.set_cell(CellRef::new(1, 2), CellValue::Number(1.0));
+            sheet.set_cell(CellRef::new(3, 7), CellValue::Number(2.0));
+            sheet.set_cell(CellRef::new(4, 7), CellValue::Number(3.0));
+            wb.save_as(&file_path).unwrap();
+        }
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let style = |col, row| {
+            wb.get_cell_style("Sheet1", &CellRef::new(col, row))
+                .unwrap()
+        };
+        // Row styles win over column styles, which win over the default
+        assert!(style(3, 2).font.bold);
+        assert!(style(3, 7).font.italic);
+        assert!(style(1, 1).font.underline);
+        assert!(style(5, 1).font.underline);
+        assert!(style(500, 1).font.underline);
+        assert!(!style(3, 7).font.bold);
+
+        let sheet = wb.get_sheet("Sheet1").unwrap();
+        assert_eq!(sheet.cell_count(), 3);
+        assert_eq!(sheet.get_column_width(5), Some(20.0));
+        // Style-only columns keep the default width
+        assert_eq!(sheet.get_column_width(3), None);
+
+        // Cells without a style of their own carry the one they sit in, so
+        // readers that ignore row and column styles still show it
+        assert!(style(1, 2).font.bold);
+        let cell_style = |col, row| {
+            let cell = sheet.get_cell(&CellRef::new(col, row)).unwrap();
+            wb.style_registry()
+                .get(cell.style_id.unwrap())
+                .unwrap()
+                .clone()
+        };
+        assert!(cell_style(1, 2).font.bold);
+        assert!(cell_style(3, 7).font.italic);
+        assert!(cell_style(4, 7).font.underline);
+
+        std::fs::remove_file(file_path).ok();
+    }
+
+    #[test]
+    fn test_freeze_panes_and_auto_filter_roundtrip() {
+        let temp_dir = std::env::temp_dir();
+        let file_path = temp_dir.join("test_freeze_filter_roundtrip.xlsx");
+
+        {
+            let mut wb = Workbook::with_sheets(&["My Data", "Plain"]);
+            let sheet = wb.get_sheet_mut("My Data").unwrap();
+            sheet.set_freeze_panes(1, 2);
+            sheet.set_auto_filter(Some(crate::range::Range::parse("A1:D20").unwrap()));
+            wb.save_as(&file_path).unwrap();
+        }
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let sheet = wb.get_sheet("My Data").unwrap();
+        assert_eq!(sheet.freeze_panes(), Some((1, 2)));
+        assert_eq!(sheet.auto_filter().unwrap().to_a1(), "A1:D20");
+        let plain = wb.get_sheet("Plain").unwrap();
+        assert_eq!(plain.freeze_panes(), None);
+        assert!(plain.auto_filter().is_none());
+        let filter = wb
+            .defined_names()
+            .iter()
+            .find(|dn| dn.name == DefinedName::FILTER_DATABASE)
+            .unwrap();
+        assert_eq!(filter.reference, "'My Data'!$A$1:$D$20");
+        assert!(filter.hidden);
+
+        std::fs::remove_file(file_path).ok();
+    }
+
+    #[test]
+    fn test_error_values_roundtrip() {
+        use crate::cell::CellError;
+
+        let temp_dir = std::env::temp_dir();
+        let file_path = temp_dir.join("test_error_values_roundtrip.xlsx");
+
+        {
+            let mut wb = Workbook::new();
+            let sheet = wb.get_sheet_mut("Sheet1").unwrap();
+            for (row, error) in (1..).zip(CellError::ALL) {
+                sheet.set_cell(CellRef::new(1, row), CellValue::Error(error));
+            }
+            sheet.set_cell(
+                CellRef::new(2, 1),
+                CellValue::Formula {
+                    formula: "SEQUENCE(3)".to_string(),
+                    cached_result: Some(Box::new(CellValue::Error(CellError::Spill))),
+                },
+            );
+            wb.save_as(&file_path).unwrap();
+        }
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let sheet = wb.get_sheet("Sheet1").unwrap();
+        for (row, error) in (1..).zip(CellError::ALL) {
+            assert_eq!(
+                sheet.get_value(&CellRef::new(1, row)),
+                CellValue::Error(error)
+            );
+        }
+        assert_eq!(
+            sheet.get_value(&CellRef::new(2, 1)).error(),
+            Some(CellError::Spill)
+        );
+
+        std::fs::remove_file(file_path).ok();
+    }
+
+    #[test]
+    fn test_print_titles_roundtrip() {
+        let temp_dir = std::env::temp_dir();
+        let file_path = temp_dir.join("test_print_titles_roundtrip.xlsx");
+
+        {
+            let mut wb = Workbook::with_sheets(&["Cover", "Data"]);
+            wb.get_sheet_mut("Data")
+                .unwrap()
+                .set_print_titles(Some((1, 1)), None);
+            wb.set_defined_name(DefinedName::new("Totals", "Data!$B$2"));
+            wb.save_as(&file_path).unwrap();
+        }
+
+        let mut wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(
+            wb.get_sheet("Data").unwrap().print_titles(),
+            (Some((1, 1)), None)
+        );
+        // Print names live on the sheet, user names stay on the workbook
+        assert_eq!(wb.defined_names().len(), 1);
+
+        // Moving the sheet re-scopes the built-in name on the next save
+        wb.move_sheet("Data", 0).unwrap();
+        wb.save().unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(
+            wb.get_sheet("Data").unwrap().print_titles(),
+            (Some((1, 1)), None)
+        );
+        assert_eq!(wb.get_sheet("Cover").unwrap().print_titles(), (None, None));
+
+        std::fs::remove_file(file_path).ok();
+    }
+
+    #[test]
+    fn test_sparkline_roundtrip() {
+        use crate::sparkline::{SparklineGroup, SparklineType};
+
+        let temp_dir = std::env::temp_dir();
+        let file_path = temp_dir.join("test_sparkline_roundtrip.xlsx");
+
+        {
+            let mut wb = Workbook::new();
+            let mut group = SparklineGroup::new(SparklineType::Column);
+            group.color = crate::style::Color::Rgb(0x00B050);
+            group.high_low = true;
+            group
+                .add_range(
+                    "Sheet1",
+                    &crate::range::Range::parse("B2:M3").unwrap(),
+                    &crate::range::Range::parse("N2:N3").unwrap(),
+                )
+                .unwrap();
+            let sheet = wb.get_sheet_mut("Sheet1").unwrap();
+            sheet.set_cell(CellRef::new(2, 2), CellValue::Number(1.0));
+            sheet.add_sparkline_group(group);
+            wb.save_as(&file_path).unwrap();
+        }
+
+        // Sparklines survive a second save through the parser
+        Workbook::open(&file_path).unwrap().save().unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        let sheet = wb.get_sheet("Sheet1").unwrap();
+        let groups = sheet.sparkline_groups();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].sparkline_type, SparklineType::Column);
+        assert_eq!(groups[0].color, crate::style::Color::Rgb(0x00B050));
+        assert!(groups[0].high_low);
+        assert_eq!(groups[0].sparklines.len(), 2);
+        assert_eq!(groups[0].sparklines[1].data, "Sheet1!B3:M3");
+        assert_eq!(groups[0].sparklines[1].location, CellRef::new(14, 3));
+        // The cell formula parser is not confused by the xm:f elements
+        assert_eq!(
+            wb.get_cell("Sheet1", &CellRef::new(2, 2)).unwrap(),
+            CellValue::Number(1.0)
+        );
+
+        std::fs::remove_file(file_path).ok();
+    }
+
+    #[test]
+    fn test_checkbox_and_dropdown_roundtrip() {
+        use crate::range::Range;
+        use crate::sheet::Dropdown;
+
+        let temp_dir = std::env::temp_dir();
+        let file_path = temp_dir.join("test_checkbox_dropdown_roundtrip.xlsx");
+
+        {
+            let mut wb = Workbook::new();
+            let mut bold = Style::default();
+            bold.font.bold = true;
+            let bold_id = wb.style_registry_mut().add(bold);
+            let sheet = wb.get_sheet_mut("Sheet1").unwrap();
+            sheet.set_cell_style(&CellRef::new(2, 2), Some(bold_id));
+            wb.set_checkbox("Sheet1", &CellRef::new(2, 2), true)
+                .unwrap();
+            wb.set_checkbox("Sheet1", &CellRef::new(2, 3), false)
+                .unwrap();
+
+            let sheet = wb.get_sheet_mut("Sheet1").unwrap();
+            sheet.add_dropdown(
+                Dropdown::from_items(Range::parse("C2:C10").unwrap(), &["Open", "Closed"]).unwrap(),
+            );
+            sheet.add_dropdown(Dropdown::from_reference(
+                Range::parse("D2:D10").unwrap(),
+                "Lists!$A$1:$A$5",
+            ));
+            wb.save_as(&file_path).unwrap();
+        }
+
+        Workbook::open(&file_path).unwrap().save().unwrap();
+        let mut wb = Workbook::open(&file_path).unwrap();
+        let style = wb.get_cell_style("Sheet1", &CellRef::new(2, 2)).unwrap();
+        assert!(style.checkbox);
+        assert!(style.font.bold);
+        assert_eq!(
+            wb.get_cell("Sheet1", &CellRef::new(2, 2)).unwrap(),
+            CellValue::Boolean(true)
+        );
+        assert_eq!(
+            wb.get_cell("Sheet1", &CellRef::new(2, 3)).unwrap(),
+            CellValue::Boolean(false)
+        );
+
+        let dropdowns = wb.get_sheet("Sheet1").unwrap().dropdowns();
+        assert_eq!(dropdowns.len(), 2);
+        assert_eq!(dropdowns[0].items(), Some(vec!["Open", "Closed"]));
+        assert_eq!(dropdowns[1].source, "Lists!$A$1:$A$5");
+
+        // Clearing keeps the value but drops the checkbox format
+        wb.clear_checkbox("Sheet1", &CellRef::new(2, 2)).unwrap();
+        let style = wb.get_cell_style("Sheet1", &CellRef::new(2, 2)).unwrap();
+        assert!(!style.checkbox);
+        assert!(style.font.bold);
+
+        std::fs::remove_file(file_path).ok();
+    }
+
+    #[test]
+    fn test_protection_roundtrip() {
+        use crate::protection::{ProtectionPassword, SheetAction, SheetProtection};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let file_path = dir.path().join("protected.xlsx");
+        let unlocked = Protection {
+            locked: false,
+            hidden: false,
+        };
+        {
+            let mut wb = Workbook::new();
+            let mut bold = Style::default();
+            bold.font.bold = true;
+            let bold_id = wb.style_registry_mut().add(bold);
+            let sheet = wb.get_sheet_mut("Sheet1").unwrap();
+            sheet.set_cell_style(&CellRef::new(2, 2), Some(bold_id));
+            wb.set_cell_protection("Sheet1", &Range::parse("B2:C3").unwrap(), unlocked)
+                .unwrap();
+            wb.set_cell_protection("Sheet1", &Range::parse("E:E").unwrap(), unlocked)
+                .unwrap();
+            let hidden = Protection {
+                locked: true,
+                hidden: true,
+            };
+            wb.set_cell_protection("Sheet1", &Range::parse("A1").unwrap(), hidden)
+                .unwrap();
+            let protection = SheetProtection::new([SheetAction::Sort, SheetAction::Filter])
+                .with_password("secret");
+            wb.get_sheet_mut("Sheet1")
+                .unwrap()
+                .set_protection(Some(protection));
+            assert!(wb
+                .set_cell_protection("Nope", &Range::parse("A1").unwrap(), unlocked)
+                .is_err());
+            wb.save_as(&file_path).unwrap();
+        }
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let style = |cell: &str| {
+            wb.get_cell_style("Sheet1", &CellRef::parse(cell).unwrap())
+                .unwrap()
+        };
+        assert_eq!(style("B2").protection, unlocked);
+        assert!(style("B2").font.bold);
+        assert_eq!(style("C3").protection, unlocked);
+        assert_eq!(style("E100000").protection, unlocked);
+        assert!(style("A1").protection.hidden);
+        assert_eq!(style("D4").protection, Protection::default());
+
+        let protection = wb.get_sheet("Sheet1").unwrap().protection().unwrap();
+        assert_eq!(
+            protection.allowed.iter().copied().collect::<Vec<_>>(),
+            vec![SheetAction::Sort, SheetAction::Filter]
+        );
+        assert!(matches!(
+            protection.password,
+            Some(ProtectionPassword::Legacy(_))
+        ));
+    }
+
+    #[test]
+    fn test_array_formula_roundtrip() {
+        use crate::range::Range;
+        use std::io::Read;
+
+        let temp_dir = std::env::temp_dir();
+        let file_path = temp_dir.join("test_array_formula_roundtrip.xlsx");
+
+        {
+            let mut wb = Workbook::new();
+            let sheet = wb.get_sheet_mut("Sheet1").unwrap();
+            sheet.set_array_formula(&Range::parse("C1:C3").unwrap(), "A1:A3*B1:B3", false);
+            sheet.set_array_formula(
+                &Range::parse("E1:E4").unwrap(),
+                "SORT(FILTER(A1:A9,B1:B9>0))",
+                true,
+            );
+            wb.save_as(&file_path).unwrap();
+        }
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&file_path).unwrap()).unwrap();
+        let mut xml = String::new();
+        archive
+            .by_name("xl/worksheets/sheet1.xml")
+            .unwrap()
+            .read_to_string(&mut xml)
+            .unwrap();
+        assert!(xml.contains(r#"<c r="C1"><f t="array" ref="C1:C3">A1:A3*B1:B3</f></c>"#));
+        assert!(xml.contains(
+            r#"<c r="E1" cm="1"><f t="array" ref="E1:E4">_xlfn._xlws.SORT(_xlfn._xlws.FILTER(A1:A9,B1:B9&gt;0))</f></c>"#
+        ));
+        assert!(archive.by_name("xl/metadata.xml").is_ok());
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let sheet = wb.get_sheet("Sheet1").unwrap();
+        let (anchor, array) = sheet.array_formula_at(&CellRef::new(3, 2)).unwrap();
+        assert_eq!(array.range(&anchor).to_a1(), "C1:C3");
+        assert!(!array.dynamic);
+        let (anchor, array) = sheet.array_formula_at(&CellRef::new(5, 4)).unwrap();
+        assert_eq!(array.range(&anchor).to_a1(), "E1:E4");
+        assert!(array.dynamic);
+        assert_eq!(
+            sheet.get_value(&anchor),
+            CellValue::formula("SORT(FILTER(A1:A9,B1:B9>0))")
+        );
+
+        std::fs::remove_file(file_path).ok();
+    }
+
+    #[test]
+    fn test_shared_formula_roundtrip() {
+        use std::io::Read;
+
+        let temp_dir = std::env::temp_dir();
+        let file_path = temp_dir.join("test_shared_formula_roundtrip.xlsx");
+
+        {
+            let mut wb = Workbook::new();
+            let sheet = wb.get_sheet_mut("Sheet1").unwrap();
+            for row in 1..=1000 {
+                sheet.set_cell(
+                    CellRef::new(3, row),
+                    CellValue::formula(format!("A{}*B{}+$D$1", row, row)),
+                );
+            }
+            // Breaks the run: rows 501 and 502 differ from the pattern
+            sheet.set_cell(CellRef::new(3, 501), CellValue::formula("A1"));
+            sheet.set_cell(CellRef::new(3, 502), CellValue::formula("A2"));
+            sheet.set_cell(CellRef::new(4, 1), CellValue::formula("SUM(C1:C1000)"));
+            wb.save_as(&file_path).unwrap();
+        }
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&file_path).unwrap()).unwrap();
+        let mut xml = String::new();
+        archive
+            .by_name("xl/worksheets/sheet1.xml")
+            .unwrap()
+            .read_to_string(&mut xml)
+            .unwrap();
+        assert!(xml.contains(r#"<f t="shared" ref="C1:C500" si="0">A1*B1+$D$1</f>"#));
+        assert!(xml.contains(r#"<f t="shared" ref="C501:C502" si="1">A1</f>"#));
+        assert!(xml.contains(r#"<f t="shared" ref="C503:C1000" si="2">A503*B503+$D$1</f>"#));
+        assert!(xml.contains("<f>SUM(C1:C1000)</f>"));
+        assert_eq!(xml.matches("+$D$1").count(), 2);
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let sheet = wb.get_sheet("Sheet1").unwrap();
+        assert_eq!(
+            sheet.get_value(&CellRef::new(3, 1000)),
+            CellValue::formula("A1000*B1000+$D$1")
+        );
+        assert_eq!(
+            sheet.get_value(&CellRef::new(3, 502)),
+            CellValue::formula("A2")
+        );
+        assert_eq!(
+            sheet.get_value(&CellRef::new(3, 250)),
+            CellValue::formula("A250*B250+$D$1")
+        );
+
+        std::fs::remove_file(file_path).ok();
+    }
+
+    #[test]
+    fn test_calc_chain_roundtrip_and_rebuild() {
+        use std::io::Read;
+
+        let temp_dir = std::env::temp_dir();
+        let file_path = temp_dir.join("test_calc_chain_roundtrip.xlsx");
+
+        {
+            let mut wb = Workbook::new();
+            wb.add_sheet("Totals").unwrap();
+            let sheet = wb.get_sheet_mut("Sheet1").unwrap();
+            sheet.set_cell(CellRef::new(2, 2), CellValue::formula("A2*2"));
+            sheet.set_cell(CellRef::new(2, 1), CellValue::formula("A1*2"));
+            wb.get_sheet_mut("Totals")
+                .unwrap()
+                .set_cell(CellRef::new(1, 1), CellValue::formula("SUM(Sheet1!B:B)"));
+            wb.save_as(&file_path).unwrap();
+        }
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&file_path).unwrap()).unwrap();
+        let mut xml = String::new();
+        archive
+            .by_name("xl/calcChain.xml")
+            .unwrap()
+            .read_to_string(&mut xml)
+            .unwrap();
+        assert!(xml.contains(r#"<c r="B1" i="1"/><c r="B2"/><c r="A1" i="2"/>"#));
+        drop(archive);
+
+        let mut wb = Workbook::open(&file_path).unwrap();
+        let entry = |sheet: &str, a1: &str| (sheet.to_string(), CellRef::parse(a1).unwrap());
+        assert_eq!(
+            wb.calc_chain(),
+            [
+                entry("Sheet1", "B1"),
+                entry("Sheet1", "B2"),
+                entry("Totals", "A1")
+            ]
+        );
+
+        // Removed formulas drop out, new ones are appended after the kept order
+        let sheet = wb.get_sheet_mut("Sheet1").unwrap();
+        sheet.set_cell(CellRef::new(2, 1), CellValue::Number(2.0));
+        sheet.set_cell(CellRef::new(1, 3), CellValue::formula("B2+1"));
+        wb.rename_sheet("Totals", "Summary").unwrap();
+        wb.rebuild_calc_chain();
+        assert_eq!(
+            wb.calc_chain(),
+            [
+                entry("Sheet1", "B2"),
+                entry("Summary", "A1"),
+                entry("Sheet1", "A3")
+            ]
+        );
+
+        // Without formulas the part is left out
+        let sheet = wb.get_sheet_mut("Sheet1").unwrap();
+        sheet.clear_cell(&CellRef::new(2, 2));
+        sheet.clear_cell(&CellRef::new(1, 3));
+        wb.get_sheet_mut("Summary")
+            .unwrap()
+            .clear_cell(&CellRef::new(1, 1));
+        wb.save_as(&file_path).unwrap();
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&file_path).unwrap()).unwrap();
+        assert!(archive.by_name("xl/calcChain.xml").is_err());
+        let mut rels = String::new();
+        archive
+            .by_name("xl/_rels/workbook.xml.rels")
+            .unwrap()
+            .read_to_string(&mut rels)
+            .unwrap();
+        assert!(!rels.contains("calcChain"));
+
+        std::fs::remove_file(file_path).ok();
+    }
+
+    #[test]
+    fn test_external_link_roundtrip_and_break() {
+        let temp_dir = std::env::temp_dir();
+        let file_path = temp_dir.join("test_external_link_roundtrip.xlsx");
+        let part = |name: &str| {
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<externalLink xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><externalBook r:id="rId1"><sheetNames><sheetName val="{}"/></sheetNames></externalBook></externalLink>"#,
+                name
+            )
+            .into_bytes()
+        };
+
+        {
+            let mut wb = Workbook::new();
+            wb.set_parsed_external_links(vec![
+                ExternalLink::from_part(
+                    r"\\oldserver\share\budget.xlsx".to_string(),
+                    part("Budget"),
+                    "rId1".to_string(),
+                ),
+                ExternalLink::from_part(
+                    "rates.xlsx".to_string(),
+                    part("Rates"),
+                    "rId1".to_string(),
+                ),
+            ]);
+            let sheet = wb.get_sheet_mut("Sheet1").unwrap();
+            let mut linked = Cell::new(CellRef::new(1, 1), CellValue::formula("[1]Budget!A1*2"));
+            if let CellValue::Formula { cached_result, .. } = &mut linked.value {
+                *cached_result = Some(Box::new(CellValue::Number(84.0)));
+            }
+            sheet.insert_cell(linked);
+            sheet.set_cell(CellRef::new(1, 2), CellValue::formula("[2]Rates!B2+1"));
+            wb.save_as(&file_path).unwrap();
+        }
+
+        let mut wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(wb.external_links().len(), 2);
+        assert_eq!(
+            wb.external_links()[0].target,
+            r"\\oldserver\share\budget.xlsx"
+        );
+        assert_eq!(wb.external_links()[1].sheet_names(), vec!["Rates"]);
+
+        wb.set_external_link_target(1, r"\\newserver\share\budget.xlsx")
+            .unwrap();
+        assert!(wb.set_external_link_target(3, "x.xlsx").is_err());
+        wb.save().unwrap();
+        let mut wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(
+            wb.external_links()[0].target,
+            r"\\newserver\share\budget.xlsx"
+        );
+
+        assert_eq!(wb.break_external_link(1).unwrap(), 1);
+        wb.save().unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(wb.external_links().len(), 1);
+        assert_eq!(wb.external_links()[0].target, "rates.xlsx");
+        let sheet = wb.get_sheet("Sheet1").unwrap();
+        assert_eq!(
+            sheet.get_value(&CellRef::new(1, 1)),
+            CellValue::Number(84.0)
+        );
+        assert_eq!(
+            sheet.get_value(&CellRef::new(1, 2)),
+            CellValue::formula("[1]Rates!B2+1")
+        );
+
+        std::fs::remove_file(file_path).ok();
+    }
+
+    #[test]
+    fn test_import_sheet_from_other_workbook() {
+        use crate::range::Range;
+        use crate::sparkline::{SparklineGroup, SparklineType};
+
+        let temp_dir = std::env::temp_dir();
+        let source_path = temp_dir.join("test_import_sheet_source.xlsx");
+        let target_path = temp_dir.join("test_import_sheet_target.xlsx");
+
+        {
+            let mut source = Workbook::with_sheets(&["Notes", "Q3Data"]);
+            let mut italic = Style::default();
+            italic.font.italic = true;
+            let mut bold = Style::default();
+            bold.font.bold = true;
+            let _italic_id = source.style_registry_mut().add(italic);
+            let bold_id = source.style_registry_mut().add(bold.clone());
+            source.defined_names.push(DefinedName::with_sheet_scope(
+                "Totals",
+                "Q3Data!$B$1:$B$3",
+                1,
+            ));
+
+            let sheet = source.get_sheet_mut("Q3Data").unwrap();
+            sheet.set_cell(CellRef::new(1, 1), CellValue::String("Region".to_string()));
+            sheet.set_cell_style(&CellRef::new(1, 1), Some(bold_id));
+            sheet.set_cell(CellRef::new(2, 4), CellValue::formula("SUM(Q3Data!B1:B3)"));
+            sheet.add_merged_range(Range::parse("A1:C1").unwrap());
+            sheet.set_column_width(1, 24.0);
+            let mut group = SparklineGroup::new(SparklineType::Line);
+            group
+                .add_range(
+                    "Q3Data",
+                    &Range::parse("B1:B3").unwrap(),
+                    &Range::parse("C4").unwrap(),
+                )
+                .unwrap();
+            sheet.add_sparkline_group(group);
+            source.save_as(&source_path).unwrap();
+
+            let mut target = Workbook::new();
+            let target_bold = target.style_registry_mut().add(bold);
+            target
+                .get_sheet_mut("Sheet1")
+                .unwrap()
+                .set_cell_style(&CellRef::new(1, 1), Some(target_bold));
+            target.save_as(&target_path).unwrap();
+        }
+
+        let source = Workbook::open(&source_path).unwrap();
+        let mut target = Workbook::open(&target_path).unwrap();
+        let index = target
+            .import_sheet(&source, "Q3Data", Some("Q3 Data"))
+            .unwrap();
+        assert_eq!(index, 1);
+        assert!(target.import_sheet(&source, "Missing", None).is_err());
+        assert!(target
+            .import_sheet(&source, "Q3Data", Some("Sheet1"))
+            .is_err());
+        target.save().unwrap();
+
+        let target = Workbook::open(&target_path).unwrap();
+        let sheet = target.get_sheet("Q3 Data").unwrap();
+        assert_eq!(sheet.merged_ranges(), &[Range::parse("A1:C1").unwrap()]);
+        assert_eq!(sheet.get_column_width(1), Some(24.0));
+        assert_eq!(
+            sheet.get_value(&CellRef::new(2, 4)),
+            CellValue::formula("SUM('Q3 Data'!B1:B3)")
+        );
+        assert_eq!(
+            sheet.sparkline_groups()[0].sparklines[0].data,
+            "'Q3 Data'!B1:B3"
+        );
+        let style = target
+            .get_cell_style("Q3 Data", &CellRef::new(1, 1))
+            .unwrap();
+        assert!(style.font.bold && !style.font.italic);
+        // The bold style already existed in the target and is reused
+        assert_eq!(
+            sheet.get_cell(&CellRef::new(1, 1)).unwrap().style_id,
+            target
+                .get_sheet("Sheet1")
+                .unwrap()
+                .get_cell(&CellRef::new(1, 1))
+                .unwrap()
+                .style_id
+        );
+        let totals = target
+            .defined_names()
+            .iter()
+            .find(|dn| dn.name == "Totals")
+            .unwrap();
+        assert_eq!(totals.local_sheet_id, Some(1));
+        assert_eq!(totals.reference, "'Q3 Data'!$B$1:$B$3");
+
+        std::fs::remove_file(source_path).ok();
+        std::fs::remove_file(target_path).ok();
+    }
+
+    #[test]
+    fn test_duplicate_sheet() {
+        use crate::range::Range;
+
+        let temp_dir = std::env::temp_dir();
+        let file_path = temp_dir.join("test_duplicate_sheet.xlsx");
+
+        {
+            let mut wb = Workbook::with_sheets(&["Data", "Notes"]);
+            let mut bold = Style::default();
+            bold.font.bold = true;
+            let bold = wb.style_registry_mut().add(bold);
+            wb.defined_names
+                .push(DefinedName::with_sheet_scope("Totals", "Data!$B$1:$B$3", 0));
+
+            let sheet = wb.get_sheet_mut("Data").unwrap();
+            sheet.set_cell(CellRef::new(1, 1), CellValue::String("Region".to_string()));
+            sheet.set_cell_style(&CellRef::new(1, 1), Some(bold));
+            sheet.set_cell_comment(&CellRef::new(1, 1), Some("Check".to_string()));
+            sheet.set_cell(CellRef::new(2, 4), CellValue::formula("SUM(Data!B1:B3)"));
+            sheet.set_cell(CellRef::new(3, 4), CellValue::formula("Notes!A1"));
+            sheet.add_merged_range(Range::parse("A1:C1").unwrap());
+            sheet.set_column_width(1, 24.0);
+            sheet.set_row_style(2, Some(bold));
+
+            let index = wb.duplicate_sheet("Data", "Data (2)").unwrap();
+            assert_eq!(index, 2);
+            assert!(wb.duplicate_sheet("Missing", "Copy").is_err());
+            assert!(wb.duplicate_sheet("Data", "Notes").is_err());
+            let copy = wb.get_sheet("Data (2)").unwrap();
+            assert_eq!(
+                copy.get_cell(&CellRef::new(1, 1))
+                    .unwrap()
+                    .comment
+                    .as_deref(),
+                Some("Check")
+            );
+            wb.save_as(&file_path).unwrap();
+        }
+
+        let wb = Workbook::open(&file_path).unwrap();
+        assert_eq!(wb.sheet_names(), vec!["Data", "Notes", "Data (2)"]);
+        let copy = wb.get_sheet("Data (2)").unwrap();
+        assert_eq!(copy.merged_ranges(), &[Range::parse("A1:C1").unwrap()]);
+        assert_eq!(copy.get_column_width(1), Some(24.0));
+        assert_eq!(
+            copy.get_value(&CellRef::new(2, 4)),
+            CellValue::formula("SUM('Data (2)'!B1:B3)")
+        );
+        assert_eq!(
+            copy.get_value(&CellRef::new(3, 4)),
+            CellValue::formula("Notes!A1")
+        );
+        assert!(
+            wb.get_cell_style("Data (2)", &CellRef::new(1, 1))
+                .unwrap()
+                .font
+                .bold
+        );
+        assert!(
+            wb.get_cell_style("Data (2)", &CellRef::new(9, 2))
+                .unwrap()
+                .font
+                .bold
+        );
+
+        // The source is untouched
+        assert_eq!(
+            wb.get_sheet("Data").unwrap().get_value(&CellRef::new(2, 4)),
+            CellValue::formula("SUM(Data!B1:B3)")
+        );
+        let scopes: Vec<_> = wb
+            .defined_names()
+            .iter()
+            .filter(|dn| dn.name == "Totals")
+            .map(|dn| (dn.local_sheet_id, dn.reference.as_str()))
+            .collect();
+        assert_eq!(
+            scopes,
+            vec![
+                (Some(0), "Data!$B$1:$B$3"),
+                (Some(2), "'Data (2)'!$B$1:$B$3")
+            ]
+        );
+
+        std::fs::remove_file(file_path).ok();
+    }
+
+    #[test]
+    fn test_reorder_sheets() {
+        let mut wb = Workbook::with_sheets(&["Data", "Notes", "Summary", "Raw"]);
+        wb.defined_names
+            .push(DefinedName::with_sheet_scope("Local", "Summary!$A$1", 2));
+        wb.set_active_sheet_by_name("Notes").unwrap();
+        let rel_id = wb.get_sheet("Summary").unwrap().info.rel_id.clone();
+
+        wb.reorder_sheets(&["Summary", "*", "Notes"]).unwrap();
+        assert_eq!(wb.sheet_names(), vec!["Summary", "Data", "Raw", "Notes"]);
+        assert_eq!(wb.get_sheet("Summary").unwrap().info.rel_id, rel_id);
+        assert_eq!(wb.defined_names()[0].local_sheet_id, Some(0));
+        assert_eq!(wb.active_sheet_index(), 3);
+
+        // Unlisted sheets follow the listed ones
+        wb.reorder_sheets(&["Raw"]).unwrap();
+        assert_eq!(wb.sheet_names(), vec!["Raw", "Summary", "Data", "Notes"]);
+        assert_eq!(wb.defined_names()[0].local_sheet_id, Some(1));
+
+        assert!(wb.reorder_sheets(&["Raw", "Raw"]).is_err());
+        assert!(wb.reorder_sheets(&["*", "*"]).is_err());
+        assert!(wb.reorder_sheets(&["Missing"]).is_err());
+    }
+
+    #[test]
+    fn test_find_and_remove_unused() {
+        use crate::style::{Font, Style};
+
+        let mut wb = Workbook::with_sheets(&["Report", "Lookup", "Scratch"]);
+        wb.set_cell(
+            "Report",
+            CellRef::new(1, 1),
+            CellValue::formula("VLOOKUP(A2,Lookup!A:B,2,FALSE)*TaxRate"),
+        )
+        .unwrap();
+        wb.set_defined_name(DefinedName::new("TaxRate", "Lookup!$D$1"));
+        wb.set_defined_name(DefinedName::new("OldRange", "Scratch!$A$1:$A$9"));
+        let mut print_area = DefinedName::new(DefinedName::PRINT_AREA, "Scratch!$A$1:$B$2");
+        print_area.local_sheet_id = Some(2);
+        wb.set_defined_name(print_area);
+        let bold = wb.style_registry_mut().add(Style {
+            font: Font {
+                bold: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let unused_style = wb.style_registry_mut().add(Style::default());
+        let italic = wb.style_registry_mut().add(Style {
+            font: Font {
+                italic: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        wb.get_sheet_mut("Report")
+            .unwrap()
+            .set_cell_style(&CellRef::new(1, 1), Some(italic));
+        wb.set_sheet_visibility("Lookup", SheetVisibility::Hidden)
+            .unwrap();
+        wb.set_sheet_visibility("Scratch", SheetVisibility::VeryHidden)
+            .unwrap();
+
+        let unused = wb.find_unused();
+        let names: Vec<&str> = unused
+            .defined_names
+            .iter()
+            .map(|d| d.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["OldRange"]);
+        assert_eq!(unused.styles, vec![unused_style]);
+        assert_eq!(bold, 0);
+        assert_eq!(unused.hidden_sheets, vec!["Scratch"]);
+
+        wb.remove_unused(&unused).unwrap();
+        assert_eq!(wb.sheet_names(), vec!["Report", "Lookup"]);
+        assert!(wb.get_defined_name("OldRange").is_none());
+        assert!(wb.get_defined_name(DefinedName::PRINT_AREA).is_none());
+        assert!(wb.get_defined_name("TaxRate").is_some());
+        let style = wb
+            .get_sheet("Report")
+            .unwrap()
+            .get_cell(&CellRef::new(1, 1))
+            .unwrap()
+            .style_id;
+        assert!(wb.style_registry().get(style.unwrap()).unwrap().font.italic);
+        assert!(wb.find_unused().is_empty());
+    }
+
+    #[test]
+    fn test_optimize_workbook() {
+        use crate::style::{Font, NumberFormat, Style};
+
+        let mut wb = Workbook::new();
+        let bold = Style {
+            font: Font {
+                bold: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let first = wb.style_registry_mut().add(bold.clone());
+        let second = wb.style_registry_mut().add(bold);
+        wb.style_registry_mut().add_number_format(164, "0.000");
+        wb.style_registry_mut().add(Style {
+            number_format: NumberFormat {
+                id: Some(164),
+                code: Some("0.000".to_string()),
+            },
+            ..Default::default()
+        });
+        wb.add_shared_string("deleted long ago");
+
+        let sheet = wb.get_sheet_mut("Sheet1").unwrap();
+        sheet.set_cell(CellRef::new(1, 1), CellValue::String("Total".to_string()));
+        sheet.set_cell_style(&CellRef::new(1, 1), Some(first));
+        sheet.set_cell(CellRef::new(2, 2), CellValue::Number(1.0));
+        sheet.set_cell_style(&CellRef::new(2, 2), Some(second));
+        sheet.set_cell(CellRef::new(26, 100), CellValue::Empty);
+        sheet.set_cell(CellRef::new(1, 2), CellValue::Empty);
+        sheet.set_row_height(500, 30.0);
+        sheet.set_row_height(2, 30.0);
+        sheet.set_column_width(30, 20.0);
+        sheet.set_row_hidden(600, true);
+        sheet.set_row_height(600, 30.0);
+
+        let report = wb.optimize();
+        assert_eq!((report.styles_before, report.styles_after), (3, 1));
+        assert_eq!(
+            (report.number_formats_before, report.number_formats_after),
+            (1, 0)
+        );
+        assert_eq!(
+            (report.shared_strings_before, report.shared_strings_after),
+            (1, 1)
+        );
+        assert_eq!(report.blank_cells_removed, 1);
+        assert_eq!(report.row_heights_removed, 1);
+        assert_eq!(report.column_widths_removed, 1);
+        assert_eq!(wb.shared_strings(), ["Total"]);
+
+        let sheet = wb.get_sheet("Sheet1").unwrap();
+        assert_eq!(sheet.cell_count(), 3);
+        assert_eq!(sheet.get_row_height(600), Some(30.0));
+        let a1 = sheet.get_cell(&CellRef::new(1, 1)).unwrap().style_id;
+        assert_eq!(a1, sheet.get_cell(&CellRef::new(2, 2)).unwrap().style_id);
+        assert!(wb.style_registry().get(a1.unwrap()).unwrap().font.bold);
+
+        let path = std::env::temp_dir().join("xlex_test_optimize.xlsx");
+        wb.save_as_compressed(&path, 9).unwrap();
+        let reopened = Workbook::open(&path).unwrap();
+        let a1 = reopened
+            .get_sheet("Sheet1")
+            .unwrap()
+            .get_cell(&CellRef::new(1, 1))
+            .unwrap()
+            .style_id;
+        assert!(reopened.style_registry().resolve(a1).font.bold);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_optimize_keeps_unlocked_cells() {
+        use crate::style::{Protection, Style};
+
+        let mut wb = Workbook::new();
+        let unlocked = wb.style_registry_mut().add(Style {
+            protection: Protection {
+                locked: false,
+                hidden: false,
+            },
+            ..Default::default()
+        });
+        let sheet = wb.get_sheet_mut("Sheet1").unwrap();
+        sheet.set_cell(CellRef::new(1, 1), CellValue::String("Name".to_string()));
+        // Input cells left empty for the user to fill in
+        for row in 2..=5 {
+            sheet.set_cell(CellRef::new(2, row), CellValue::Empty);
+            sheet.set_cell_style(&CellRef::new(2, row), Some(unlocked));
+        }
+
+        let report = wb.optimize();
+        assert_eq!(report.blank_cells_removed, 0);
+        let sheet = wb.get_sheet("Sheet1").unwrap();
+        assert_eq!(sheet.cell_count(), 5);
+        assert_eq!(
+            sheet.get_cell(&CellRef::new(2, 5)).unwrap().style_id,
+            Some(unlocked)
+        );
+    }
+
+    #[test]
+    fn test_find_hidden() {
+        use crate::range::Range;
+        use crate::style::{Color, Fill, FillPattern, Font, Style};
+
+        let mut wb = Workbook::with_sheets(&["Model", "Calc", "Keys"]);
+        wb.set_sheet_visibility("Calc", SheetVisibility::Hidden)
+            .unwrap();
+        wb.set_sheet_visibility("Keys", SheetVisibility::VeryHidden)
+            .unwrap();
+        let white = wb.style_registry_mut().add(Style {
+            font: Font {
+                color: Some(Color::Rgb(0xFFFFFF)),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let navy_on_navy = wb.style_registry_mut().add(Style {
+            font: Font {
+                color: Some(Color::Rgb(0x1F3864)),
+                ..Default::default()
+            },
+            fill: Fill {
+                pattern: FillPattern::Solid,
+                fg_color: Some(Color::Rgb(0x1F3864)),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let sheet = wb.get_sheet_mut("Model").unwrap();
+        sheet.set_row_hidden(4, true);
+        sheet.set_column_hidden(3, true);
+        sheet.set_column_width(5, 0.0);
+        sheet.set_cell(CellRef::new(1, 1), CellValue::string("Visible"));
+        sheet.set_cell(CellRef::new(2, 1), CellValue::string("Override"));
+        sheet.set_cell_style(&CellRef::new(2, 1), Some(white));
+        sheet.set_cell(CellRef::new(2, 2), CellValue::Number(1.0));
+        sheet.set_cell_style(&CellRef::new(2, 2), Some(navy_on_navy));
+        sheet.set_cell(CellRef::new(1, 6), CellValue::string("Title"));
+        sheet.set_cell(CellRef::new(3, 6), CellValue::Number(42.0));
+        sheet.add_merged_range(Range::parse("A6:D6").unwrap());
+
+        let hidden = wb.find_hidden();
+        assert_eq!(hidden.hidden_sheets, vec!["Calc"]);
+        assert_eq!(hidden.very_hidden_sheets, vec!["Keys"]);
+        assert_eq!(hidden.hidden_rows, vec![("Model".to_string(), 4)]);
+        assert_eq!(hidden.hidden_columns, vec![("Model".to_string(), 3)]);
+        assert_eq!(hidden.zero_width_columns, vec![("Model".to_string(), 5)]);
+        let cells = |list: &[(String, CellRef)]| -> Vec<String> {
+            list.iter().map(|(_, c)| c.to_a1()).collect()
+        };
+        assert_eq!(cells(&hidden.invisible_text), vec!["B1", "B2"]);
+        assert_eq!(cells(&hidden.covered_cells), vec!["C6"]);
+        assert!(Workbook::new().find_hidden().is_empty());
+    }
+
+    #[test]
+    fn test_sheet_background_roundtrip() {
+        use crate::sheet::SheetImage;
+
+        let temp_dir = std::env::temp_dir();
+        let file_path = temp_dir.join("test_sheet_background_roundtrip.xlsx");
+        let png = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 1, 2, 3];
+
+        {
+            let mut wb = Workbook::new();
+            wb.get_sheet_mut("Sheet1")
+                .unwrap()
+                .set_background(Some(SheetImage::new(png.clone(), "PNG").unwrap()));
+            wb.save_as(&file_path).unwrap();
+        }
+
+        // The picture survives a second save through the parser
+        Workbook::open(&file_path).unwrap().save().unwrap();
+        let wb = Workbook::open(&file_path).unwrap();
+        let image = wb.get_sheet("Sheet1").unwrap().background().unwrap();
+        assert_eq!(image.extension, "png");
+        assert_eq!(image.data, png);
+
+        std::fs::remove_file(file_path).ok();
+    }
+
+    #[test]
+    fn test_pattern_and_gradient_fill_roundtrip() {
+        use crate::style::{Color, FillPattern, GradientFill};
+
+        let temp_dir = std::env::temp_dir();
+        let file_path = temp_dir.join("test_fill_roundtrip.xlsx");
+
+        {
+            let mut wb = Workbook::new();
+            let mut pattern = Style::default();
+            pattern.fill.pattern = FillPattern::LightGrid;
+            pattern.fill.fg_color = Some(Color::rgb(0, 0, 0));
+            pattern.fill.bg_color = Some(Color::rgb(255, 255, 0));
+            let mut gradient = Style::default();
+            gradient.fill.gradient = Some(GradientFill::linear(
+                45.0,
+                vec![Color::rgb(255, 255, 255), Color::rgb(68, 114, 196)],
+            ));
+            let pattern_id = wb.style_registry_mut().add(pattern);
+            let gradient_id = wb.style_registry_mut().add(gradient);
+            let sheet = wb.get_sheet_mut("Sheet1").unwrap();
+            sheet.set_cell_style(&CellRef::new(1, 1), Some(pattern_id));
+            sheet.set_cell_style(&CellRef::new(2, 1), Some(gradient_id));
+            wb.save_as(&file_path).unwrap();
+        }
+
+        let wb = Workbook::open(&file_path).unwrap();
+        let pattern = wb.get_cell_style("Sheet1", &CellRef::new(1, 1)).unwrap();
+        assert_eq!(pattern.fill.pattern, FillPattern::LightGrid);
+        assert_eq!(
+            pattern.fill.bg_color.and_then(|c| c.to_hex()),
+            Some("FFFF00".to_string())
+        );
+        let gradient = wb.get_cell_style("Sheet1", &CellRef::new(2, 1)).unwrap();
+        let gradient = gradient.fill.gradient.unwrap();
+        assert_eq!(gradient.degree, 45.0);
+        assert_eq!(gradient.stops.len(), 2);
+
+        std::fs::remove_file(file_path).ok();
+    }
+
     #[test]
     fn test_save_with_multiple_sheets() {
         let temp_dir = std::env::temp_dir();
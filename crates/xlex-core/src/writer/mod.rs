@@ -1,5 +1,7 @@
 //! XLSX writing utilities.
 
+mod streaming;
 mod workbook;
 
+pub use streaming::StreamingSheetWriter;
 pub use workbook::WorkbookWriter;
@@ -0,0 +1,380 @@
+//! Row-by-row workbook writer.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use super::workbook::{escape_xml, WorkbookWriter};
+use crate::cell::{CellRef, CellValue};
+use crate::error::{XlexError, XlexResult};
+use crate::workbook::Workbook;
+
+const SHEET_END: &[u8] = b"    </sheetData>\n</worksheet>";
+
+/// Style index of the date format in the written styles part.
+const DATE_STYLE: u32 = 1;
+
+const STYLES_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+    <fonts count="1"><font><sz val="11"/><name val="Calibri"/></font></fonts>
+    <fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills>
+    <borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders>
+    <cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs>
+    <cellXfs count="2"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/><xf numFmtId="14" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/></cellXfs>
+    <cellStyles count="1"><cellStyle name="Normal" xfId="0" builtinId="0"/></cellStyles>
+</styleSheet>"#;
+
+/// Writes a workbook a row at a time, straight into the compressed file,
+/// so memory use doesn't grow with the number of rows.
+///
+/// Rows are numbered from 1 in the order they are written; further sheets
+/// can follow with [`next_sheet`](Self::next_sheet). Text is stored
+/// inline rather than in a shared string table, and dates get a date
+/// format. The file appears at its path only once [`finish`](Self::finish)
+/// succeeds; a writer dropped before that leaves nothing behind.
+///
+/// ```
+/// use xlex_core::writer::StreamingSheetWriter;
+/// use xlex_core::CellValue;
+///
+/// # let dir = tempfile::TempDir::new().unwrap();
+/// # let path = dir.path().join("out.xlsx");
+/// let mut writer = StreamingSheetWriter::create(&path, "Data").unwrap();
+/// writer.write_row(&[CellValue::string("id"), CellValue::string("total")]).unwrap();
+/// writer.write_row(&[CellValue::Number(1.0), CellValue::Number(9.5)]).unwrap();
+/// writer.finish().unwrap();
+/// ```
+pub struct StreamingSheetWriter {
+    zip: Option<ZipWriter<BufWriter<File>>>,
+    path: PathBuf,
+    temp_path: PathBuf,
+    sheet_names: Vec<String>,
+    rows: u32,
+    formatter: WorkbookWriter,
+}
+
+impl StreamingSheetWriter {
+    /// Starts writing a workbook at `path` with a single sheet.
+    pub fn create(path: impl AsRef<Path>, sheet_name: &str) -> XlexResult<Self> {
+        Workbook::validate_sheet_name(sheet_name)?;
+        let path = path.as_ref().to_path_buf();
+        let temp_path = path.with_extension("xlsx.tmp");
+        let file = File::create(&temp_path)?;
+
+        let mut writer = Self {
+            zip: Some(ZipWriter::new(BufWriter::new(file))),
+            path,
+            temp_path,
+            sheet_names: Vec::new(),
+            rows: 0,
+            formatter: WorkbookWriter::new(),
+        };
+        writer.start_sheet(sheet_name)?;
+        Ok(writer)
+    }
+
+    /// Ends the current sheet and starts another, whose rows are again
+    /// numbered from 1.
+    pub fn next_sheet(&mut self, sheet_name: &str) -> XlexResult<()> {
+        Workbook::validate_sheet_name(sheet_name)?;
+        if self
+            .sheet_names
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(sheet_name))
+        {
+            return Err(XlexError::SheetAlreadyExists {
+                name: sheet_name.to_string(),
+            });
+        }
+        if let Some(zip) = self.zip.as_mut() {
+            zip.write_all(SHEET_END)?;
+        }
+        self.start_sheet(sheet_name)
+    }
+
+    fn start_sheet(&mut self, sheet_name: &str) -> XlexResult<()> {
+        let Some(zip) = self.zip.as_mut() else {
+            return Ok(());
+        };
+        self.sheet_names.push(sheet_name.to_string());
+        self.rows = 0;
+        zip.start_file(
+            format!("xl/worksheets/sheet{}.xml", self.sheet_names.len()),
+            Self::options(),
+        )?;
+        zip.write_all(
+            br#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+    <sheetData>
+"#,
+        )?;
+        Ok(())
+    }
+
+    fn options() -> SimpleFileOptions {
+        SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated)
+    }
+
+    /// Returns the number of rows written to the current sheet so far.
+    pub fn rows_written(&self) -> u32 {
+        self.rows
+    }
+
+    /// Appends a row, with `values[0]` in column A. Empty values are
+    /// skipped.
+    pub fn write_row(&mut self, values: &[CellValue]) -> XlexResult<()> {
+        let Some(zip) = self.zip.as_mut() else {
+            return Ok(());
+        };
+        self.rows += 1;
+        let row = self.rows;
+        let mut xml = format!("<row r=\"{}\">", row);
+        for (index, value) in values.iter().enumerate() {
+            let cell_ref = CellRef::new(index as u32 + 1, row).to_a1();
+            match value {
+                CellValue::Empty => {}
+                CellValue::String(text) => xml.push_str(&format!(
+                    r#"<c r="{}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                    cell_ref,
+                    escape_xml(text)
+                )),
+                CellValue::Formula { formula, .. } => {
+                    let (cell_type, cached) = self.formatter.format_cell_value(value);
+                    xml.push_str(&format!(
+                        r#"<c r="{}"{}><f>{}</f>{}</c>"#,
+                        cell_ref,
+                        cell_type
+                            .map(|t| format!(r#" t="{}""#, t))
+                            .unwrap_or_default(),
+                        escape_xml(formula),
+                        cached.map(|v| format!("<v>{}</v>", v)).unwrap_or_default()
+                    ));
+                }
+                _ => {
+                    let (cell_type, text) = self.formatter.format_cell_value(value);
+                    let style = if matches!(value, CellValue::DateTime(_)) {
+                        format!(r#" s="{}""#, DATE_STYLE)
+                    } else {
+                        String::new()
+                    };
+                    xml.push_str(&format!(
+                        r#"<c r="{}"{}{}><v>{}</v></c>"#,
+                        cell_ref,
+                        cell_type
+                            .map(|t| format!(r#" t="{}""#, t))
+                            .unwrap_or_default(),
+                        style,
+                        text.unwrap_or_default()
+                    ));
+                }
+            }
+        }
+        xml.push_str("</row>\n");
+        zip.write_all(xml.as_bytes())?;
+        Ok(())
+    }
+
+    /// Writes the remaining parts of the workbook and moves it into place.
+    pub fn finish(mut self) -> XlexResult<()> {
+        let Some(mut zip) = self.zip.take() else {
+            return Ok(());
+        };
+        let result = (|| -> XlexResult<()> {
+            zip.write_all(SHEET_END)?;
+            let options = Self::options();
+            let (mut overrides, mut sheets, mut rels) =
+                (String::new(), String::new(), String::new());
+            for (index, name) in self.sheet_names.iter().enumerate() {
+                let n = index + 1;
+                overrides.push_str(&format!(
+                    r#"
+    <Override PartName="/xl/worksheets/sheet{}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+                    n
+                ));
+                sheets.push_str(&format!(
+                    r#"
+        <sheet name="{}" sheetId="{}" r:id="rId{}"/>"#,
+                    escape_xml(name),
+                    n,
+                    n
+                ));
+                rels.push_str(&format!(
+                    r#"
+    <Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet{}.xml"/>"#,
+                    n, n
+                ));
+            }
+            let styles_id = self.sheet_names.len() + 1;
+            let parts = [
+                (
+                    "[Content_Types].xml",
+                    format!(
+                        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+    <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+    <Default Extension="xml" ContentType="application/xml"/>
+    <Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>
+    <Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/>{}
+</Types>"#,
+                        overrides
+                    ),
+                ),
+                ("_rels/.rels", ROOT_RELS.to_string()),
+                (
+                    "xl/workbook.xml",
+                    format!(
+                        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+    <sheets>{}
+    </sheets>
+</workbook>"#,
+                        sheets
+                    ),
+                ),
+                (
+                    "xl/_rels/workbook.xml.rels",
+                    format!(
+                        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">{}
+    <Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/>
+</Relationships>"#,
+                        rels, styles_id
+                    ),
+                ),
+                ("xl/styles.xml", STYLES_XML.to_string()),
+            ];
+            for (name, content) in parts {
+                zip.start_file(name, options)?;
+                zip.write_all(content.as_bytes())?;
+            }
+            zip.finish()?.flush()?;
+            Ok(())
+        })();
+
+        match result {
+            Ok(()) => {
+                std::fs::rename(&self.temp_path, &self.path)?;
+                Ok(())
+            }
+            Err(e) => {
+                let _ = std::fs::remove_file(&self.temp_path);
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Drop for StreamingSheetWriter {
+    fn drop(&mut self) {
+        if self.zip.take().is_some() {
+            let _ = std::fs::remove_file(&self.temp_path);
+        }
+    }
+}
+
+const ROOT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+    <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/>
+</Relationships>"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::CellError;
+    use crate::lazy::LazyWorkbook;
+
+    #[test]
+    fn test_streaming_sheet_writer_round_trip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("stream.xlsx");
+        let rows = vec![
+            vec![CellValue::string("name"), CellValue::string("a & <b>")],
+            vec![
+                CellValue::Number(1.5),
+                CellValue::Empty,
+                CellValue::Boolean(true),
+                CellValue::DateTime(45292.0),
+                CellValue::Error(CellError::Na),
+                CellValue::Formula {
+                    formula: "A2*2".to_string(),
+                    cached_result: Some(Box::new(CellValue::Number(3.0))),
+                },
+            ],
+        ];
+        let mut writer = StreamingSheetWriter::create(&path, "R&D").unwrap();
+        for row in &rows {
+            writer.write_row(row).unwrap();
+        }
+        assert_eq!(writer.rows_written(), 2);
+        writer.finish().unwrap();
+        assert!(!path.with_extension("xlsx.tmp").exists());
+
+        let workbook = Workbook::open(&path).unwrap();
+        assert_eq!(workbook.sheet_names(), vec!["R&D"]);
+        let sheet = workbook.get_sheet("R&D").unwrap();
+        for (r, row) in rows.iter().enumerate() {
+            for (c, value) in row.iter().enumerate() {
+                let cell_ref = CellRef::new(c as u32 + 1, r as u32 + 1);
+                if let CellValue::DateTime(serial) = value {
+                    // Dates read back as numbers in a date format
+                    assert_eq!(sheet.get_value(&cell_ref), CellValue::Number(*serial));
+                    let style = workbook.get_cell_style("R&D", &cell_ref).unwrap();
+                    assert_eq!(style.number_format.id, Some(14));
+                } else {
+                    assert_eq!(&sheet.get_value(&cell_ref), value, "{}", cell_ref);
+                }
+            }
+        }
+
+        let lazy = LazyWorkbook::open(&path).unwrap();
+        let mut seen = 0;
+        lazy.for_each_row("R&D", |row| {
+            seen += 1;
+            assert_eq!(row.row_number, seen);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(seen, 2);
+    }
+
+    #[test]
+    fn test_streaming_sheet_writer_dropped() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("unfinished.xlsx");
+        let mut writer = StreamingSheetWriter::create(&path, "Data").unwrap();
+        writer.write_row(&[CellValue::Number(1.0)]).unwrap();
+        drop(writer);
+        assert!(!path.exists());
+        assert!(!path.with_extension("xlsx.tmp").exists());
+        assert!(StreamingSheetWriter::create(&path, "Bad/Name").is_err());
+    }
+
+    #[test]
+    fn test_streaming_sheet_writer_next_sheet() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("sheets.xlsx");
+        let mut writer = StreamingSheetWriter::create(&path, "One").unwrap();
+        writer.write_row(&[CellValue::Number(1.0)]).unwrap();
+        writer.next_sheet("Two").unwrap();
+        assert_eq!(writer.rows_written(), 0);
+        writer.write_row(&[CellValue::string("a")]).unwrap();
+        writer.write_row(&[CellValue::string("b")]).unwrap();
+        assert!(writer.next_sheet("one").is_err());
+        writer.finish().unwrap();
+
+        let workbook = Workbook::open(&path).unwrap();
+        assert_eq!(workbook.sheet_names(), vec!["One", "Two"]);
+        let two = workbook.get_sheet("Two").unwrap();
+        assert_eq!(two.get_value(&CellRef::new(1, 2)), CellValue::string("b"));
+        assert_eq!(
+            workbook
+                .get_sheet("One")
+                .unwrap()
+                .get_value(&CellRef::new(1, 1)),
+            CellValue::Number(1.0)
+        );
+    }
+}
@@ -7,21 +7,38 @@ use std::path::Path;
 use zip::write::SimpleFileOptions;
 use zip::ZipWriter;
 
-use crate::cell::CellValue;
-use crate::error::{XlexError, XlexResult};
-use crate::workbook::Workbook;
+use crate::cell::{CellRef, CellValue};
+use crate::error::{ErrorContext, XlexError, XlexResult};
+use crate::protection::{ProtectionPassword, SheetAction, SheetProtection};
+use crate::style::Color;
+use crate::workbook::{DefinedName, Workbook};
 
 /// Writer for xlsx workbooks.
-pub struct WorkbookWriter;
+pub struct WorkbookWriter {
+    /// Deflate level for every part (0-9)
+    compression_level: i64,
+}
 
 impl WorkbookWriter {
     /// Creates a new workbook writer.
     pub fn new() -> Self {
-        Self
+        Self {
+            compression_level: 6,
+        }
+    }
+
+    /// Creates a writer compressing parts at `level` (0 = fastest, 9 =
+    /// smallest).
+    pub fn with_compression_level(level: u32) -> Self {
+        Self {
+            compression_level: level.min(9) as i64,
+        }
     }
 
     /// Writes a workbook to a file.
     pub fn write(&self, workbook: &Workbook, path: &Path) -> XlexResult<()> {
+        let _span = tracing::info_span!("save", path = %path.display()).entered();
+
         // Create temp file
         let temp_path = path.with_extension("xlsx.tmp");
         let file = File::create(&temp_path)?;
@@ -31,6 +48,7 @@ impl WorkbookWriter {
 
         if result.is_ok() {
             // Atomic rename
+            let _span = tracing::info_span!("rename").entered();
             std::fs::rename(&temp_path, path)?;
         } else {
             // Cleanup temp file
@@ -49,7 +67,7 @@ impl WorkbookWriter {
         let mut zip = ZipWriter::new(writer);
         let options = SimpleFileOptions::default()
             .compression_method(zip::CompressionMethod::Deflated)
-            .compression_level(Some(6));
+            .compression_level(Some(self.compression_level));
 
         // Pre-scan sheets for comments and hyperlinks
         let mut sheets_with_comments: Vec<usize> = Vec::new();
@@ -63,6 +81,7 @@ impl WorkbookWriter {
         }
 
         // Build shared string table from all cells across all sheets
+        let ss_span = tracing::info_span!("build_shared_strings").entered();
         let mut ss_table: Vec<String> = Vec::new();
         let mut ss_map: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
 
@@ -81,6 +100,9 @@ impl WorkbookWriter {
         }
 
         let has_shared_strings = !ss_table.is_empty();
+        drop(ss_span);
+        let has_dynamic_arrays = has_dynamic_arrays(workbook);
+        let calc_chain = workbook.rebuilt_calc_chain();
 
         // Write [Content_Types].xml (with comment info)
         self.write_content_types(
@@ -88,11 +110,14 @@ impl WorkbookWriter {
             workbook,
             &sheets_with_comments,
             has_shared_strings,
+            has_dynamic_arrays,
+            !calc_chain.is_empty(),
             options,
         )?;
 
         // Write _rels/.rels
-        self.write_root_rels(&mut zip, options)?;
+        let has_custom_props = !workbook.properties().custom.is_empty();
+        self.write_root_rels(&mut zip, has_custom_props, options)?;
 
         // Write docProps/app.xml
         self.write_app_props(&mut zip, options)?;
@@ -100,18 +125,81 @@ impl WorkbookWriter {
         // Write docProps/core.xml
         self.write_core_props(&mut zip, workbook, options)?;
 
+        // Write docProps/custom.xml
+        if has_custom_props {
+            self.write_custom_props(&mut zip, workbook, options)?;
+        }
+
         // Write xl/_rels/workbook.xml.rels
-        self.write_workbook_rels(&mut zip, workbook, has_shared_strings, options)?;
+        self.write_workbook_rels(
+            &mut zip,
+            workbook,
+            has_shared_strings,
+            has_dynamic_arrays,
+            !calc_chain.is_empty(),
+            options,
+        )?;
 
         // Write xl/workbook.xml
         self.write_workbook_xml(&mut zip, workbook, options)?;
 
         // Write xl/styles.xml and get style ID mapping
-        let style_id_map = self.write_styles(&mut zip, workbook, options)?;
+        let style_id_map = {
+            let _span = tracing::info_span!("write_styles").entered();
+            self.write_styles(&mut zip, workbook, options)
+                .map_err(|e| e.with_context(ErrorContext::default().part("xl/styles.xml")))?
+        };
+
+        // Write xl/featurePropertyBag/featurePropertyBag.xml for checkbox cells
+        if has_checkboxes(workbook) {
+            zip.start_file("xl/featurePropertyBag/featurePropertyBag.xml", options)?;
+            zip.write_all(FEATURE_PROPERTY_BAGS.as_bytes())?;
+        }
+
+        // Write xl/metadata.xml declaring dynamic array formulas
+        if has_dynamic_arrays {
+            zip.start_file("xl/metadata.xml", options)?;
+            zip.write_all(DYNAMIC_ARRAY_METADATA.as_bytes())?;
+        }
+
+        // Write xl/calcChain.xml so Excel keeps its calculation order
+        if !calc_chain.is_empty() {
+            zip.start_file("xl/calcChain.xml", options)?;
+            zip.write_all(write_calc_chain(workbook, &calc_chain).as_bytes())?;
+        }
+
+        // Write xl/externalLinks/externalLink{n}.xml and their target rels
+        for (index, link) in workbook.external_links().iter().enumerate() {
+            zip.start_file(
+                format!("xl/externalLinks/externalLink{}.xml", index + 1),
+                options,
+            )?;
+            zip.write_all(link.part())?;
+            if let Some(rel_id) = link.target_rel_id() {
+                zip.start_file(
+                    format!("xl/externalLinks/_rels/externalLink{}.xml.rels", index + 1),
+                    options,
+                )?;
+                let rels = format!(
+                    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+    <Relationship Id="{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/externalLinkPath" Target="{}" TargetMode="External"/>
+</Relationships>"#,
+                    escape_xml(rel_id),
+                    escape_xml(&link.target)
+                );
+                zip.write_all(rels.as_bytes())?;
+            }
+        }
 
         // Write xl/sharedStrings.xml
         if has_shared_strings {
-            self.write_shared_strings_from_table(&mut zip, &ss_table, options)?;
+            let _span =
+                tracing::info_span!("write_shared_strings", count = ss_table.len()).entered();
+            self.write_shared_strings_from_table(&mut zip, &ss_table, options)
+                .map_err(|e| {
+                    e.with_context(ErrorContext::default().part("xl/sharedStrings.xml"))
+                })?;
         }
 
         // Write sheets
@@ -124,9 +212,17 @@ impl WorkbookWriter {
                 options,
                 &style_id_map,
                 &ss_map,
-            )?;
+            )
+            .map_err(|e| {
+                e.with_context(
+                    ErrorContext::default()
+                        .part(format!("xl/worksheets/sheet{}.xml", index + 1))
+                        .sheet(*sheet_name),
+                )
+            })?;
         }
 
+        let _span = tracing::info_span!("finish_zip").entered();
         zip.finish()?;
         Ok(())
     }
@@ -137,6 +233,8 @@ impl WorkbookWriter {
         workbook: &Workbook,
         sheets_with_comments: &[usize],
         has_shared_strings: bool,
+        has_dynamic_arrays: bool,
+        has_calc_chain: bool,
         options: SimpleFileOptions,
     ) -> XlexResult<()> {
         zip.start_file("[Content_Types].xml", options)?;
@@ -153,6 +251,11 @@ impl WorkbookWriter {
 "#,
         );
 
+        if !workbook.properties().custom.is_empty() {
+            content.push_str(r#"    <Override PartName="/docProps/custom.xml" ContentType="application/vnd.openxmlformats-officedocument.custom-properties+xml"/>
+"#);
+        }
+
         // Add shared strings if present
         if has_shared_strings {
             content.push_str(r#"    <Override PartName="/xl/sharedStrings.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sharedStrings+xml"/>
@@ -168,6 +271,47 @@ impl WorkbookWriter {
             ));
         }
 
+        if has_checkboxes(workbook) {
+            content.push_str(r#"    <Override PartName="/xl/featurePropertyBag/featurePropertyBag.xml" ContentType="application/vnd.ms-excel.featurepropertybag+xml"/>
+"#);
+        }
+
+        if has_dynamic_arrays {
+            content.push_str(r#"    <Override PartName="/xl/metadata.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheetMetadata+xml"/>
+"#);
+        }
+
+        if has_calc_chain {
+            content.push_str(r#"    <Override PartName="/xl/calcChain.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.calcChain+xml"/>
+"#);
+        }
+
+        for index in 1..=workbook.external_links().len() {
+            content.push_str(&format!(
+                r#"    <Override PartName="/xl/externalLinks/externalLink{}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.externalLink+xml"/>
+"#,
+                index
+            ));
+        }
+
+        // Add image content types for sheet backgrounds
+        let mut image_types: Vec<(&str, &str)> = Vec::new();
+        for sheet_name in workbook.sheet_names() {
+            if let Some(image) = workbook.get_sheet(sheet_name).and_then(|s| s.background()) {
+                let entry = (image.extension.as_str(), image.content_type());
+                if !image_types.contains(&entry) {
+                    image_types.push(entry);
+                }
+            }
+        }
+        for (extension, content_type) in image_types {
+            content.push_str(&format!(
+                r#"    <Default Extension="{}" ContentType="{}"/>
+"#,
+                extension, content_type
+            ));
+        }
+
         // Add comment content types
         if !sheets_with_comments.is_empty() {
             content.push_str(r#"    <Default Extension="vml" ContentType="application/vnd.openxmlformats-officedocument.vmlDrawing"/>
@@ -189,16 +333,24 @@ impl WorkbookWriter {
     fn write_root_rels<W: Write + std::io::Seek>(
         &self,
         zip: &mut ZipWriter<W>,
+        has_custom_props: bool,
         options: SimpleFileOptions,
     ) -> XlexResult<()> {
         zip.start_file("_rels/.rels", options)?;
 
-        let content = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+        let mut content = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
     <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/>
     <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/>
     <Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/extended-properties" Target="docProps/app.xml"/>
-</Relationships>"#;
+"#,
+        );
+        if has_custom_props {
+            content.push_str(r#"    <Relationship Id="rId4" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/custom-properties" Target="docProps/custom.xml"/>
+"#);
+        }
+        content.push_str("</Relationships>");
 
         zip.write_all(content.as_bytes())?;
         Ok(())
@@ -274,11 +426,47 @@ impl WorkbookWriter {
         Ok(())
     }
 
+    fn write_custom_props<W: Write + std::io::Seek>(
+        &self,
+        zip: &mut ZipWriter<W>,
+        workbook: &Workbook,
+        options: SimpleFileOptions,
+    ) -> XlexResult<()> {
+        zip.start_file("docProps/custom.xml", options)?;
+
+        let mut content = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Properties xmlns="http://schemas.openxmlformats.org/officeDocument/2006/custom-properties" xmlns:vt="http://schemas.openxmlformats.org/officeDocument/2006/docPropsVTypes">
+"#,
+        );
+        // Property IDs start at 2 and the format ID is fixed for user-defined properties
+        for (pid, property) in (2u32..).zip(&workbook.properties().custom) {
+            let value_type = if property.value_type.is_empty() {
+                "lpwstr"
+            } else {
+                property.value_type.as_str()
+            };
+            content.push_str(&format!(
+                "    <property fmtid=\"{{D5CDD505-2E9C-101B-9397-08002B2CF9AE}}\" pid=\"{}\" name=\"{}\"><vt:{}>{}</vt:{}></property>\n",
+                pid,
+                escape_xml(&property.name),
+                value_type,
+                escape_xml(&property.value),
+                value_type
+            ));
+        }
+        content.push_str("</Properties>");
+        zip.write_all(content.as_bytes())?;
+        Ok(())
+    }
+
     fn write_workbook_rels<W: Write + std::io::Seek>(
         &self,
         zip: &mut ZipWriter<W>,
         workbook: &Workbook,
         has_shared_strings: bool,
+        has_dynamic_arrays: bool,
+        has_calc_chain: bool,
         options: SimpleFileOptions,
     ) -> XlexResult<()> {
         zip.start_file("xl/_rels/workbook.xml.rels", options)?;
@@ -317,6 +505,33 @@ impl WorkbookWriter {
             ));
         }
 
+        // Feature property bags back checkbox cell formats
+        if has_checkboxes(workbook) {
+            content.push_str(r#"    <Relationship Id="rIdFpb" Type="http://schemas.microsoft.com/office/2022/11/relationships/FeaturePropertyBag" Target="featurePropertyBag/featurePropertyBag.xml"/>
+"#);
+        }
+
+        // Cell metadata backs dynamic array formulas
+        if has_dynamic_arrays {
+            content.push_str(r#"    <Relationship Id="rIdMeta" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/sheetMetadata" Target="metadata.xml"/>
+"#);
+        }
+
+        // Calculation chain ordering formula cells
+        if has_calc_chain {
+            content.push_str(r#"    <Relationship Id="rIdCalc" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/calcChain" Target="calcChain.xml"/>
+"#);
+        }
+
+        // External workbook links
+        for index in 1..=workbook.external_links().len() {
+            content.push_str(&format!(
+                r#"    <Relationship Id="rIdExt{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/externalLink" Target="externalLinks/externalLink{}.xml"/>
+"#,
+                index, index
+            ));
+        }
+
         content.push_str("</Relationships>");
         zip.write_all(content.as_bytes())?;
         Ok(())
@@ -356,11 +571,57 @@ impl WorkbookWriter {
 
         content.push_str("    </sheets>\n");
 
-        // Write defined names if any
-        let defined_names = workbook.defined_names();
+        if !workbook.external_links().is_empty() {
+            content.push_str("    <externalReferences>\n");
+            for index in 1..=workbook.external_links().len() {
+                content.push_str(&format!(
+                    "        <externalReference r:id=\"rIdExt{}\"/>\n",
+                    index
+                ));
+            }
+            content.push_str("    </externalReferences>\n");
+        }
+
+        // Write defined names if any, including the per-sheet print names
+        let mut defined_names: Vec<DefinedName> = Vec::new();
+        for (index, sheet_name) in workbook.sheet_names().iter().enumerate() {
+            if let Some(sheet) = workbook.get_sheet(sheet_name) {
+                if let Some(reference) = sheet.print_area_reference() {
+                    defined_names.push(DefinedName::with_sheet_scope(
+                        DefinedName::PRINT_AREA,
+                        reference,
+                        index,
+                    ));
+                }
+                if let Some(reference) = sheet.print_titles_reference() {
+                    defined_names.push(DefinedName::with_sheet_scope(
+                        DefinedName::PRINT_TITLES,
+                        reference,
+                        index,
+                    ));
+                }
+                if let Some(reference) = sheet.auto_filter_reference() {
+                    let mut name = DefinedName::with_sheet_scope(
+                        DefinedName::FILTER_DATABASE,
+                        reference,
+                        index,
+                    );
+                    name.hidden = true;
+                    defined_names.push(name);
+                }
+            }
+        }
+        for dn in workbook.defined_names() {
+            let shadowed = defined_names
+                .iter()
+                .any(|b| b.name == dn.name && b.local_sheet_id == dn.local_sheet_id);
+            if !shadowed {
+                defined_names.push(dn.clone());
+            }
+        }
         if !defined_names.is_empty() {
             content.push_str("    <definedNames>\n");
-            for dn in defined_names {
+            for dn in &defined_names {
                 let mut attrs = format!(r#"name="{}""#, escape_xml(&dn.name));
                 if let Some(sheet_id) = dn.local_sheet_id {
                     attrs.push_str(&format!(r#" localSheetId="{}""#, sheet_id));
@@ -393,7 +654,8 @@ impl WorkbookWriter {
         options: SimpleFileOptions,
     ) -> XlexResult<std::collections::HashMap<u32, u32>> {
         use crate::style::{
-            Border, BorderStyle, Fill, FillPattern, Font, HorizontalAlignment, VerticalAlignment,
+            Border, BorderStyle, Fill, FillPattern, Font, HorizontalAlignment, Protection,
+            VerticalAlignment,
         };
 
         zip.start_file("xl/styles.xml", options)?;
@@ -417,12 +679,15 @@ impl WorkbookWriter {
         let mut num_fmts: Vec<(u32, String)> = vec![];
 
         // CellXf entries: (fontId, fillId, borderId, numFmtId, style)
+        #[derive(PartialEq)]
         struct CellXf {
             font_id: usize,
             fill_id: usize,
             border_id: usize,
             num_fmt_id: u32,
             alignment: Option<(HorizontalAlignment, VerticalAlignment, bool)>, // (h_align, v_align, wrap)
+            checkbox: bool,
+            protection: Option<Protection>,
         }
 
         let mut cell_xfs: Vec<CellXf> = vec![CellXf {
@@ -431,14 +696,27 @@ impl WorkbookWriter {
             border_id: 0,
             num_fmt_id: 0,
             alignment: None,
+            checkbox: false,
+            protection: None,
         }];
 
         // Helper to find or add font
+        // Fonts are compared with the written defaults filled in, so a font
+        // read back from a saved file matches the one it was written from
         fn find_or_add_font(fonts: &mut Vec<Font>, font: &Font) -> usize {
-            fonts.iter().position(|f| f == font).unwrap_or_else(|| {
-                fonts.push(font.clone());
-                fonts.len() - 1
-            })
+            let normalized = |f: &Font| Font {
+                name: Some(f.name.clone().unwrap_or_else(|| "Calibri".to_string())),
+                size: Some(f.size.unwrap_or(11.0)),
+                ..f.clone()
+            };
+            let font = normalized(font);
+            fonts
+                .iter()
+                .position(|f| normalized(f) == font)
+                .unwrap_or_else(|| {
+                    fonts.push(font);
+                    fonts.len() - 1
+                })
         }
 
         // Helper to find or add fill
@@ -493,17 +771,24 @@ impl WorkbookWriter {
                 None
             };
 
-            // Map registry style ID to cellXfs index (current length before pushing)
-            let xf_index = cell_xfs.len() as u32;
-            style_id_map.insert(style_id, xf_index);
-
-            cell_xfs.push(CellXf {
+            // Map registry style ID to cellXfs index, reusing identical entries
+            let xf = CellXf {
                 font_id,
                 fill_id,
                 border_id,
                 num_fmt_id,
                 alignment,
-            });
+                checkbox: style.checkbox,
+                protection: (style.protection != Protection::default()).then_some(style.protection),
+            };
+            let xf_index = match cell_xfs.iter().position(|existing| *existing == xf) {
+                Some(index) => index as u32,
+                None => {
+                    cell_xfs.push(xf);
+                    cell_xfs.len() as u32 - 1
+                }
+            };
+            style_id_map.insert(style_id, xf_index);
         }
 
         // Generate XML
@@ -590,16 +875,15 @@ impl WorkbookWriter {
                 r#"
         <fill>"#,
             );
-            let pattern_type = match fill.pattern {
-                FillPattern::None => "none",
-                FillPattern::Solid => "solid",
-                FillPattern::Gray125 => "gray125",
-                FillPattern::MediumGray => "mediumGray",
-                FillPattern::DarkGray => "darkGray",
-                FillPattern::LightGray => "lightGray",
-                _ => "solid",
-            };
-            if fill.pattern == FillPattern::None || fill.pattern == FillPattern::Gray125 {
+            if let Some(ref gradient) = fill.gradient {
+                content.push_str(&write_gradient_fill(gradient));
+                content.push_str(r#"</fill>"#);
+                continue;
+            }
+            let pattern_type = fill.pattern.as_str();
+            if fill.pattern == FillPattern::None
+                || (fill.fg_color.is_none() && fill.bg_color.is_none())
+            {
                 content.push_str(&format!(r#"<patternFill patternType="{}"/>"#, pattern_type));
             } else {
                 content.push_str(&format!(r#"<patternFill patternType="{}">"#, pattern_type));
@@ -629,25 +913,26 @@ impl WorkbookWriter {
             borders.len()
         ));
         for border in &borders {
-            content.push_str(
+            content.push_str(&format!(
                 r#"
-        <border>"#,
-            );
+        <border{}{}>"#,
+                if border.diagonal_up {
+                    r#" diagonalUp="1""#
+                } else {
+                    ""
+                },
+                if border.diagonal_down {
+                    r#" diagonalDown="1""#
+                } else {
+                    ""
+                },
+            ));
 
             fn write_border_side(name: &str, side: &crate::style::BorderSide) -> String {
                 if side.style == BorderStyle::None {
                     return format!("<{}/>", name);
                 }
-                let style_str = match side.style {
-                    BorderStyle::Thin => "thin",
-                    BorderStyle::Medium => "medium",
-                    BorderStyle::Thick => "thick",
-                    BorderStyle::Dashed => "dashed",
-                    BorderStyle::Dotted => "dotted",
-                    BorderStyle::Double => "double",
-                    BorderStyle::Hair => "hair",
-                    _ => "thin",
-                };
+                let style_str = side.style.as_str();
                 if let Some(ref color) = side.color {
                     if let Some(argb) = color.to_argb_hex() {
                         return format!(
@@ -663,7 +948,7 @@ impl WorkbookWriter {
             content.push_str(&write_border_side("right", &border.right));
             content.push_str(&write_border_side("top", &border.top));
             content.push_str(&write_border_side("bottom", &border.bottom));
-            content.push_str(r#"<diagonal/>"#);
+            content.push_str(&write_border_side("diagonal", &border.diagonal));
             content.push_str(r#"</border>"#);
         }
         content.push_str(
@@ -707,6 +992,8 @@ impl WorkbookWriter {
                 ""
             };
 
+            let mut children = String::new();
+            let mut apply_alignment = "";
             if let Some((h_align, v_align, wrap)) = &xf.alignment {
                 let h_str = match h_align {
                     HorizontalAlignment::Left => "left",
@@ -722,27 +1009,47 @@ impl WorkbookWriter {
                     _ => "center",
                 };
                 let wrap_attr = if *wrap { r#" wrapText="1""# } else { "" };
-                content.push_str(&format!(
-                    r#"
-        <xf numFmtId="{}" fontId="{}" fillId="{}" borderId="{}" xfId="0"{}{}{}{} applyAlignment="1"><alignment horizontal="{}" vertical="{}"{}/></xf>"#,
-                    xf.num_fmt_id, xf.font_id, xf.fill_id, xf.border_id,
-                    apply_font, apply_fill, apply_border, apply_fmt,
+                apply_alignment = r#" applyAlignment="1""#;
+                children.push_str(&format!(
+                    r#"<alignment horizontal="{}" vertical="{}"{}/>"#,
                     h_str, v_str, wrap_attr
                 ));
-            } else {
-                content.push_str(&format!(
-                    r#"
-        <xf numFmtId="{}" fontId="{}" fillId="{}" borderId="{}" xfId="0"{}{}{}{}/>"#,
-                    xf.num_fmt_id,
-                    xf.font_id,
-                    xf.fill_id,
-                    xf.border_id,
-                    apply_font,
-                    apply_fill,
-                    apply_border,
-                    apply_fmt
+            }
+            let mut apply_protection = "";
+            if let Some(protection) = &xf.protection {
+                apply_protection = r#" applyProtection="1""#;
+                children.push_str(&format!(
+                    r#"<protection locked="{}" hidden="{}"/>"#,
+                    u8::from(protection.locked),
+                    u8::from(protection.hidden)
                 ));
             }
+            if xf.checkbox {
+                // Points at the XFComplements bag in featurePropertyBag.xml
+                children.push_str(
+                    r#"<extLst><ext uri="{C7286773-470A-42A8-94C5-96B5CB345126}" xmlns:xfpb="http://schemas.microsoft.com/office/spreadsheetml/2022/featurepropertybag"><xfpb:xfComplement i="0"/></ext></extLst>"#,
+                );
+            }
+
+            let open = format!(
+                r#"
+        <xf numFmtId="{}" fontId="{}" fillId="{}" borderId="{}" xfId="0"{}{}{}{}{}{}"#,
+                xf.num_fmt_id,
+                xf.font_id,
+                xf.fill_id,
+                xf.border_id,
+                apply_font,
+                apply_fill,
+                apply_border,
+                apply_fmt,
+                apply_alignment,
+                apply_protection
+            );
+            if children.is_empty() {
+                content.push_str(&format!("{}/>", open));
+            } else {
+                content.push_str(&format!("{}>{}</xf>", open, children));
+            }
         }
         content.push_str(
             r#"
@@ -793,6 +1100,7 @@ impl WorkbookWriter {
         style_id_map: &std::collections::HashMap<u32, u32>,
         ss_map: &std::collections::HashMap<String, usize>,
     ) -> XlexResult<()> {
+        let _span = tracing::info_span!("write_sheet", sheet = sheet_name).entered();
         let sheet = workbook
             .get_sheet(sheet_name)
             .ok_or_else(|| XlexError::SheetNotFound {
@@ -807,42 +1115,91 @@ impl WorkbookWriter {
 "#,
         );
 
-        // Write column widths and hidden columns
+        // Write frozen panes
+        if let Some((rows, cols)) = sheet.freeze_panes() {
+            let active_pane = match (rows > 0, cols > 0) {
+                (true, true) => "bottomRight",
+                (true, false) => "bottomLeft",
+                _ => "topRight",
+            };
+            let split = |attr: &str, count: u32| {
+                if count > 0 {
+                    format!(r#" {}="{}""#, attr, count)
+                } else {
+                    String::new()
+                }
+            };
+            content.push_str(&format!(
+                r#"    <sheetViews><sheetView workbookViewId="0"><pane{}{} topLeftCell="{}" activePane="{}" state="frozen"/></sheetView></sheetViews>"#,
+                split("xSplit", cols),
+                split("ySplit", rows),
+                CellRef::new(cols + 1, rows + 1).to_a1(),
+                active_pane
+            ));
+            content.push('\n');
+        }
+
+        // Write column widths, hidden columns and column styles
         let col_widths = sheet.column_widths();
         let hidden_cols = sheet.hidden_columns();
-        if !col_widths.is_empty() || !hidden_cols.is_empty() {
+        let col_styles = sheet.column_styles();
+        let style_attr = |style_id: Option<u32>| {
+            style_id
+                .and_then(|registry_id| style_id_map.get(&registry_id))
+                .map(|xf_index| format!(r#" style="{}""#, xf_index))
+                .unwrap_or_default()
+        };
+        let default_style = style_attr(sheet.default_style());
+        if !col_widths.is_empty()
+            || !hidden_cols.is_empty()
+            || !col_styles.is_empty()
+            || !default_style.is_empty()
+        {
             // Collect all columns that need a <col> element
             let mut all_cols: std::collections::BTreeSet<u32> = std::collections::BTreeSet::new();
-            for &c in col_widths.keys() {
-                all_cols.insert(c);
-            }
-            for &c in hidden_cols {
-                all_cols.insert(c);
-            }
+            all_cols.extend(col_widths.keys());
+            all_cols.extend(hidden_cols);
+            all_cols.extend(col_styles.keys());
+
+            // The sheet default style covers the gaps between those columns
+            let push_gap = |content: &mut String, start: u32, end: u32| {
+                if !default_style.is_empty() && start <= end {
+                    content.push_str(&format!(
+                        r#"        <col min="{}" max="{}"{}/>"#,
+                        start, end, default_style
+                    ));
+                    content.push('\n');
+                }
+            };
 
             content.push_str("    <cols>\n");
-            for col in &all_cols {
-                let width = col_widths.get(col).copied().unwrap_or(8.43); // Default Excel width
-                let hidden_attr = if hidden_cols.contains(col) {
+            let mut gap_start = 1;
+            for &col in &all_cols {
+                push_gap(&mut content, gap_start, col - 1);
+                gap_start = col + 1;
+                // Columns without a width of their own keep the sheet's default
+                let width = col_widths
+                    .get(&col)
+                    .map(|width| format!(r#" width="{}" customWidth="1""#, width))
+                    .unwrap_or_default();
+                let hidden_attr = if hidden_cols.contains(&col) {
                     r#" hidden="1""#
                 } else {
                     ""
                 };
-                let custom_width = if col_widths.contains_key(col) {
-                    r#" customWidth="1""#
-                } else {
-                    ""
-                };
+                let style = style_attr(col_styles.get(&col).copied().or(sheet.default_style()));
                 content.push_str(&format!(
-                    r#"        <col min="{}" max="{}" width="{}"{}{}/>"#,
-                    col, col, width, hidden_attr, custom_width
+                    r#"        <col min="{}" max="{}"{}{}{}/>"#,
+                    col, col, width, style, hidden_attr
                 ));
                 content.push('\n');
             }
+            push_gap(&mut content, gap_start, CellRef::MAX_COL);
             content.push_str("    </cols>\n");
         }
 
         content.push_str("    <sheetData>\n");
+        let shared_formulas = shared_formula_groups(sheet);
 
         // Collect cells by row
         let mut rows: std::collections::BTreeMap<u32, Vec<&crate::cell::Cell>> =
@@ -861,6 +1218,10 @@ impl WorkbookWriter {
         for &r in hidden_rows {
             rows.entry(r).or_default();
         }
+        let row_styles = sheet.row_styles();
+        for &r in row_styles.keys() {
+            rows.entry(r).or_default();
+        }
 
         // Write rows
         for (row_num, cells) in &rows {
@@ -876,6 +1237,14 @@ impl WorkbookWriter {
                 row_attrs.push_str(r#" hidden="1""#);
             }
 
+            // Add row style if set
+            if let Some(xf_index) = row_styles
+                .get(row_num)
+                .and_then(|registry_id| style_id_map.get(registry_id))
+            {
+                row_attrs.push_str(&format!(r#" s="{}" customFormat="1""#, xf_index));
+            }
+
             content.push_str(&row_attrs);
             content.push('>');
 
@@ -886,9 +1255,14 @@ impl WorkbookWriter {
             for cell in sorted_cells {
                 let cell_ref = cell.reference.to_a1();
 
-                // Map cell's style_id to cellXfs index using the mapping
+                // Map cell's style_id to cellXfs index using the mapping; a
+                // cell without its own style takes the one of its row, its
+                // column or the sheet, as Excel does when it is typed into
                 let style_attr = cell
                     .style_id
+                    .or_else(|| row_styles.get(row_num).copied())
+                    .or_else(|| col_styles.get(&cell.reference.col).copied())
+                    .or(sheet.default_style())
                     .and_then(|registry_id| style_id_map.get(&registry_id))
                     .map(|xf_index| format!(r#" s="{}""#, xf_index))
                     .unwrap_or_default();
@@ -896,22 +1270,50 @@ impl WorkbookWriter {
                 match &cell.value {
                     CellValue::Formula { formula, .. } => {
                         let (cell_type, cell_value) = self.format_cell_value(&cell.value);
-                        let type_attr = cell_type
+                        let mut type_attr = cell_type
                             .map(|t| format!(r#" t="{}""#, t))
                             .unwrap_or_default();
+                        let text = escape_xml(&crate::formula::add_function_prefixes(formula));
+                        let formula_xml = match (
+                            cell.array,
+                            shared_formulas.get(&(cell.reference.col, cell.reference.row)),
+                        ) {
+                            (Some(array), _) => {
+                                if array.dynamic {
+                                    // Cell metadata 1 is the dynamic array entry in xl/metadata.xml
+                                    type_attr.push_str(r#" cm="1""#);
+                                }
+                                format!(
+                                    r#"<f t="array" ref="{}">{}</f>"#,
+                                    array.range(&cell.reference).to_a1(),
+                                    text
+                                )
+                            }
+                            (None, Some(SharedFormula::First { index, range })) => format!(
+                                r#"<f t="shared" ref="{}" si="{}">{}</f>"#,
+                                range, index, text
+                            ),
+                            (None, Some(SharedFormula::Member(index))) => {
+                                format!(r#"<f t="shared" si="{}"/>"#, index)
+                            }
+                            (None, None) => format!("<f>{}</f>", text),
+                        };
                         content.push_str(&format!(
-                            r#"<c r="{}"{}{}><f>{}</f>{}</c>"#,
+                            r#"<c r="{}"{}{}>{}{}</c>"#,
                             cell_ref,
                             type_attr,
                             style_attr,
-                            escape_xml(formula),
+                            formula_xml,
                             cell_value
                                 .map(|v| format!("<v>{}</v>", v))
                                 .unwrap_or_default()
                         ));
                     }
                     CellValue::Empty => {
-                        // Skip empty cells
+                        // Empty cells are only written to keep their style
+                        if !style_attr.is_empty() {
+                            content.push_str(&format!(r#"<c r="{}"{}/>"#, cell_ref, style_attr));
+                        }
                     }
                     CellValue::String(s) => {
                         // Use shared string table reference
@@ -942,6 +1344,15 @@ impl WorkbookWriter {
 
         content.push_str("    </sheetData>\n");
 
+        if let Some(protection) = sheet.protection() {
+            content.push_str(&sheet_protection_xml(protection));
+        }
+
+        if let Some(range) = sheet.auto_filter() {
+            content.push_str(&format!(r#"    <autoFilter ref="{}"/>"#, range.to_a1()));
+            content.push('\n');
+        }
+
         // Write merged cells if any
         let merged_ranges = sheet.merged_ranges();
         if !merged_ranges.is_empty() {
@@ -955,15 +1366,37 @@ impl WorkbookWriter {
             content.push_str("</mergeCells>\n");
         }
 
-        // Collect hyperlinks from cells
+        let dropdowns = sheet.dropdowns();
+        if !dropdowns.is_empty() {
+            content.push_str(&format!(
+                "    <dataValidations count=\"{}\">\n",
+                dropdowns.len()
+            ));
+            for dropdown in dropdowns {
+                content.push_str(&format!(
+                    r#"        <dataValidation type="list" allowBlank="1" showInputMessage="1" showErrorMessage="1" sqref="{}"><formula1>{}</formula1></dataValidation>"#,
+                    dropdown.range.to_a1(),
+                    escape_xml(&dropdown.source)
+                ));
+                content.push('\n');
+            }
+            content.push_str("    </dataValidations>\n");
+        }
+
+        // Collect hyperlinks from cells; `#Sheet!A1` links point inside the
+        // workbook and need no relationship
         let mut hyperlinks: Vec<(&str, String)> = Vec::new();
+        let mut locations: Vec<(&str, String)> = Vec::new();
         for cell in sheet.cells() {
             if let Some(ref url) = cell.hyperlink {
-                hyperlinks.push((url.as_str(), cell.reference.to_a1()));
+                match url.strip_prefix('#') {
+                    Some(location) => locations.push((location, cell.reference.to_a1())),
+                    None => hyperlinks.push((url.as_str(), cell.reference.to_a1())),
+                }
             }
         }
 
-        if !hyperlinks.is_empty() {
+        if !hyperlinks.is_empty() || !locations.is_empty() {
             content.push_str("    <hyperlinks>\n");
             for (idx, (_url, cell_ref)) in hyperlinks.iter().enumerate() {
                 let rid = format!("rHl{}", idx + 1);
@@ -973,15 +1406,44 @@ impl WorkbookWriter {
                 ));
                 content.push('\n');
             }
+            for (location, cell_ref) in &locations {
+                content.push_str(&format!(
+                    r#"        <hyperlink ref="{}" location="{}"/>"#,
+                    cell_ref,
+                    escape_xml(location)
+                ));
+                content.push('\n');
+            }
             content.push_str("    </hyperlinks>\n");
         }
 
+        let background = sheet.background();
+        if background.is_some() {
+            content.push_str("    <picture r:id=\"rBg1\"/>\n");
+        }
+
+        if !sheet.sparkline_groups().is_empty() {
+            content.push_str(&write_sparkline_groups(sheet.sparkline_groups()));
+        }
+
         content.push_str("</worksheet>");
 
         zip.write_all(content.as_bytes())?;
 
-        // Write sheet relationship file for hyperlinks
-        if !hyperlinks.is_empty() {
+        // Write the background image into the media folder
+        let background_target = background.map(|image| {
+            (
+                format!("media/background{}.{}", sheet_number, image.extension),
+                image,
+            )
+        });
+        if let Some((ref target, image)) = background_target {
+            zip.start_file(format!("xl/{}", target), options)?;
+            zip.write_all(&image.data)?;
+        }
+
+        // Write sheet relationship file for hyperlinks and background
+        if !hyperlinks.is_empty() || background_target.is_some() {
             let rels_path = format!("xl/worksheets/_rels/sheet{}.xml.rels", sheet_number);
             zip.start_file(rels_path, options)?;
 
@@ -1001,6 +1463,14 @@ impl WorkbookWriter {
                 ));
             }
 
+            if let Some((ref target, _)) = background_target {
+                rels_content.push_str(&format!(
+                    r#"    <Relationship Id="rBg1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../{}"/>
+"#,
+                    target
+                ));
+            }
+
             rels_content.push_str("</Relationships>");
             zip.write_all(rels_content.as_bytes())?;
         }
@@ -1090,7 +1560,10 @@ impl WorkbookWriter {
 
     /// Formats a cell value for XML output.
     /// Returns (type_attribute, value_string).
-    fn format_cell_value(&self, value: &CellValue) -> (Option<&'static str>, Option<String>) {
+    pub(super) fn format_cell_value(
+        &self,
+        value: &CellValue,
+    ) -> (Option<&'static str>, Option<String>) {
         match value {
             CellValue::Empty => (None, None),
             CellValue::String(s) => (Some("inlineStr"), Some(escape_xml(s))),
@@ -1104,16 +1577,18 @@ impl WorkbookWriter {
             }
             CellValue::Boolean(b) => (Some("b"), Some(if *b { "1" } else { "0" }.to_string())),
             CellValue::Formula { cached_result, .. } => {
-                // Return cached result value if available
-                if let Some(cached) = cached_result {
-                    let (_, val) = self.format_cell_value(cached);
-                    (None, val)
-                } else {
-                    (None, None)
+                // Return cached result value if available, keeping the
+                // error type so cached errors read back as errors
+                match cached_result.as_deref() {
+                    Some(cached @ CellValue::Error(_)) => self.format_cell_value(cached),
+                    Some(cached) => (None, self.format_cell_value(cached).1),
+                    None => (None, None),
                 }
             }
             CellValue::Error(e) => (Some("e"), Some(e.to_string())),
             CellValue::DateTime(serial) => (None, Some(serial.to_string())),
+            // Every digit, for Excel to read as precisely as it can
+            CellValue::Decimal(d) => (None, Some(d.to_string())),
         }
     }
 }
@@ -1125,7 +1600,7 @@ impl Default for WorkbookWriter {
 }
 
 /// Escapes special XML characters.
-fn escape_xml(s: &str) -> String {
+pub(super) fn escape_xml(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
@@ -1133,6 +1608,240 @@ fn escape_xml(s: &str) -> String {
         .replace('\'', "&apos;")
 }
 
+/// Feature property bags declaring the checkbox cell control referenced by
+/// `xfpb:xfComplement i="0"` in styles.xml.
+const FEATURE_PROPERTY_BAGS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<FeaturePropertyBags xmlns="http://schemas.microsoft.com/office/spreadsheetml/2022/featurepropertybag"><bag type="Checkbox"/><bag type="XFControls"><bagId k="CellControl">0</bagId></bag><bag type="XFComplement"><bagId k="XFControls">1</bagId></bag><bag type="XFComplements" extRef="XFComplementsMapperExtRef"><a k="MappedFeaturePropertyBags"><bagId>2</bagId></a></bag></FeaturePropertyBags>"#;
+
+/// Builds the `<sheetProtection>` element, writing only the actions whose
+/// setting differs from the default.
+fn sheet_protection_xml(protection: &SheetProtection) -> String {
+    let mut xml = String::from("    <sheetProtection");
+    match &protection.password {
+        Some(ProtectionPassword::Legacy(hash)) => {
+            xml.push_str(&format!(r#" password="{:04X}""#, hash));
+        }
+        Some(ProtectionPassword::Hashed {
+            algorithm,
+            hash,
+            salt,
+            spin_count,
+        }) => {
+            xml.push_str(&format!(
+                r#" algorithmName="{}" hashValue="{}" saltValue="{}" spinCount="{}""#,
+                escape_xml(algorithm),
+                escape_xml(hash),
+                escape_xml(salt),
+                spin_count
+            ));
+        }
+        None => {}
+    }
+    xml.push_str(r#" sheet="1""#);
+    for action in SheetAction::ALL {
+        let allowed = protection.allows(action);
+        if allowed == action.blocked_by_default() {
+            xml.push_str(&format!(
+                r#" {}="{}""#,
+                action.attribute(),
+                u8::from(!allowed)
+            ));
+        }
+    }
+    xml.push_str("/>\n");
+    xml
+}
+
+/// Returns true if any registered style renders cells as checkboxes.
+fn has_checkboxes(workbook: &Workbook) -> bool {
+    workbook
+        .style_registry()
+        .iter()
+        .any(|(_, style)| style.checkbox)
+}
+
+/// Role of a formula cell in a shared formula group.
+enum SharedFormula {
+    /// First cell of group `index`, storing the formula for the whole range
+    First { index: u32, range: String },
+    /// Another cell of group `index`, whose formula is derived from the first
+    Member(u32),
+}
+
+/// Finds runs of vertically adjacent formulas that are the first formula
+/// filled down (each row the same formula shifted one more row) and groups
+/// them into shared formulas, so the formula text is stored once per run.
+fn shared_formula_groups(
+    sheet: &crate::sheet::Sheet,
+) -> std::collections::HashMap<(u32, u32), SharedFormula> {
+    let mut columns: std::collections::BTreeMap<u32, Vec<(u32, &str)>> =
+        std::collections::BTreeMap::new();
+    for cell in sheet.cells() {
+        if let (None, CellValue::Formula { formula, .. }) = (&cell.array, &cell.value) {
+            columns
+                .entry(cell.reference.col)
+                .or_default()
+                .push((cell.reference.row, formula));
+        }
+    }
+
+    let mut groups = std::collections::HashMap::new();
+    let mut next_index = 0;
+    for (col, mut formulas) in columns {
+        formulas.sort_unstable_by_key(|&(row, _)| row);
+        let mut start = 0;
+        while start < formulas.len() {
+            let (first_row, first) = formulas[start];
+            let mut end = start + 1;
+            while let Some(&(row, formula)) = formulas.get(end) {
+                let offset = row - first_row;
+                if offset as usize != end - start
+                    || formula != crate::formula::shift_refs(first, 0, offset as i64)
+                {
+                    break;
+                }
+                end += 1;
+            }
+            if end - start > 1 {
+                let last_row = formulas[end - 1].0;
+                let range = crate::range::Range::new(
+                    CellRef::new(col, first_row),
+                    CellRef::new(col, last_row),
+                );
+                groups.insert(
+                    (col, first_row),
+                    SharedFormula::First {
+                        index: next_index,
+                        range: range.to_a1(),
+                    },
+                );
+                for &(row, _) in &formulas[start + 1..end] {
+                    groups.insert((col, row), SharedFormula::Member(next_index));
+                }
+                next_index += 1;
+            }
+            start = end;
+        }
+    }
+    groups
+}
+
+/// Cell metadata declaring the dynamic array properties referenced by
+/// `cm="1"` on spilling formula cells.
+const DYNAMIC_ARRAY_METADATA: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<metadata xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:xda="http://schemas.microsoft.com/office/spreadsheetml/2017/dynamicarray"><metadataTypes count="1"><metadataType name="XLDAPR" minSupportedVersion="120000" copy="1" pasteAll="1" pasteValues="1" merge="1" splitFirst="1" rowColShift="1" clearFormats="1" clearComments="1" assign="1" coerce="1" cellMeta="1"/></metadataTypes><futureMetadata name="XLDAPR" count="1"><bk><extLst><ext uri="{bdbb8cdc-fa1e-496e-a857-3c3f30c029c3}"><xda:dynamicArrayProperties fDynamic="1" fCollapsed="0"/></ext></extLst></bk></futureMetadata><cellMetadata count="1"><bk><rc t="1" v="0"/></bk></cellMetadata></metadata>"#;
+
+/// Returns true if any sheet holds a dynamic array formula.
+fn has_dynamic_arrays(workbook: &Workbook) -> bool {
+    workbook.sheet_names().iter().any(|name| {
+        workbook.get_sheet(name).is_some_and(|sheet| {
+            sheet.cells().any(|cell| {
+                cell.array.is_some_and(|array| array.dynamic)
+                    && matches!(cell.value, CellValue::Formula { .. })
+            })
+        })
+    })
+}
+
+/// Serializes the calculation chain, naming each sheet by the `sheetId` it
+/// is written with and omitting `i` while it repeats.
+fn write_calc_chain(workbook: &Workbook, chain: &[(String, CellRef)]) -> String {
+    let sheet_ids: std::collections::HashMap<&str, usize> = workbook
+        .sheet_names()
+        .into_iter()
+        .enumerate()
+        .map(|(index, name)| (name, index + 1))
+        .collect();
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<calcChain xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
+    );
+    let mut previous = 0;
+    for (sheet, cell) in chain {
+        let sheet_id = sheet_ids[sheet.as_str()];
+        if sheet_id == previous {
+            xml.push_str(&format!(r#"<c r="{}"/>"#, cell.to_a1()));
+        } else {
+            xml.push_str(&format!(r#"<c r="{}" i="{}"/>"#, cell.to_a1(), sheet_id));
+            previous = sheet_id;
+        }
+    }
+    xml.push_str("</calcChain>");
+    xml
+}
+
+/// Serializes sparkline groups as the worksheet's x14 `<extLst>` element.
+fn write_sparkline_groups(groups: &[crate::sparkline::SparklineGroup]) -> String {
+    let mut xml = String::from(
+        r#"    <extLst><ext uri="{05C60535-1F16-4fd2-B633-F4F36F0B64E0}" xmlns:x14="http://schemas.microsoft.com/office/spreadsheetml/2009/9/main"><x14:sparklineGroups xmlns:xm="http://schemas.microsoft.com/office/excel/2006/main">"#,
+    );
+    for group in groups {
+        let mut attrs = String::new();
+        if group.sparkline_type != crate::sparkline::SparklineType::Line {
+            attrs.push_str(&format!(r#" type="{}""#, group.sparkline_type.as_str()));
+        }
+        if group.markers {
+            attrs.push_str(r#" markers="1""#);
+        }
+        if group.high_low {
+            attrs.push_str(r#" high="1" low="1""#);
+        }
+        xml.push_str(&format!(
+            r#"<x14:sparklineGroup displayEmptyCellsAs="gap"{}>"#,
+            attrs
+        ));
+        for (element, color) in [
+            ("colorSeries", &group.color),
+            ("colorNegative", &group.negative_color),
+            ("colorMarkers", &group.color),
+            ("colorHigh", &group.negative_color),
+            ("colorLow", &group.negative_color),
+        ] {
+            let attr = match color {
+                Color::Rgb(rgb) => format!(r#"rgb="FF{:06X}""#, rgb),
+                Color::Theme(theme) => format!(r#"theme="{}""#, theme),
+                Color::Indexed(indexed) => format!(r#"indexed="{}""#, indexed),
+                Color::Auto => r#"auto="1""#.to_string(),
+            };
+            xml.push_str(&format!("<x14:{} {}/>", element, attr));
+        }
+        xml.push_str("<x14:sparklines>");
+        for sparkline in &group.sparklines {
+            xml.push_str(&format!(
+                "<x14:sparkline><xm:f>{}</xm:f><xm:sqref>{}</xm:sqref></x14:sparkline>",
+                escape_xml(&sparkline.data),
+                sparkline.location.to_a1()
+            ));
+        }
+        xml.push_str("</x14:sparklines></x14:sparklineGroup>");
+    }
+    xml.push_str("</x14:sparklineGroups></ext></extLst>\n");
+    xml
+}
+
+/// Serializes a gradient fill as a `<gradientFill>` element.
+fn write_gradient_fill(gradient: &crate::style::GradientFill) -> String {
+    use crate::style::GradientType;
+
+    let mut xml = match gradient.gradient_type {
+        GradientType::Linear => format!(r#"<gradientFill degree="{}">"#, gradient.degree),
+        GradientType::Path => format!(
+            r#"<gradientFill type="path" left="{}" right="{}" top="{}" bottom="{}">"#,
+            gradient.left, gradient.right, gradient.top, gradient.bottom
+        ),
+    };
+    for stop in &gradient.stops {
+        if let Some(argb) = stop.color.to_argb_hex() {
+            xml.push_str(&format!(
+                r#"<stop position="{}"><color rgb="{}"/></stop>"#,
+                stop.position, argb
+            ));
+        }
+    }
+    xml.push_str("</gradientFill>");
+    xml
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1293,6 +2002,18 @@ mod tests {
         assert_eq!(v, Some("#DIV/0!".to_string()));
     }
 
+    #[test]
+    fn test_format_cell_value_formula_cached_error() {
+        let writer = WorkbookWriter::new();
+        let value = CellValue::Formula {
+            formula: "A1/0".to_string(),
+            cached_result: Some(Box::new(CellValue::Error(CellError::DivZero))),
+        };
+        let (t, v) = writer.format_cell_value(&value);
+        assert_eq!(t, Some("e"));
+        assert_eq!(v, Some("#DIV/0!".to_string()));
+    }
+
     #[test]
     fn test_format_cell_value_datetime() {
         let writer = WorkbookWriter::new();